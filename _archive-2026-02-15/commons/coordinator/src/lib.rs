@@ -5,6 +5,7 @@
 //! - Submit privacy-preserving contributions
 //! - Execute differential privacy queries with FORMAL guarantees
 //! - Democratic governance of health data
+//! - Run approved computations in place, releasing only aggregates
 //!
 //! # Differential Privacy Implementation
 //!
@@ -376,6 +377,235 @@ pub fn get_query_results(query_hash: ActionHash) -> ExternResult<Vec<Record>> {
     Ok(results)
 }
 
+// ==================== COMPUTATION ENCLAVE ====================
+
+/// Submit a request to compute over a pool in place
+#[hdk_extern]
+pub fn submit_computation_request(request: ComputationRequest) -> ExternResult<Record> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_computation_request(&request)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+    if request.requester != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest("Requests must be submitted by the requester".to_string())));
+    }
+    if !matches!(request.status, ComputationStatus::Pending) {
+        return Err(wasm_error!(WasmErrorInner::Guest("New requests must be pending".to_string())));
+    }
+
+    let pool = get_pool(&request.pool_hash)?.1;
+    if !check_query_permissions(&pool.query_permissions, &request.purpose, &request.requester) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Computation not permitted for this purpose".to_string())));
+    }
+
+    // A request may ask for more privacy than the pool, never less
+    let pool_params = &pool.privacy_params;
+    if request.dp_params.epsilon > pool_params.epsilon
+        || request.dp_params.delta > pool_params.delta
+        || request.dp_params.min_aggregation < pool_params.min_aggregation
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "DP parameters must be at least as strict as the pool's (ε ≤ {}, δ ≤ {}, k ≥ {})",
+            pool_params.epsilon, pool_params.delta, pool_params.min_aggregation
+        ))));
+    }
+
+    let request_hash = create_entry(&EntryTypes::ComputationRequest(request.clone()))?;
+    let record = get(request_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find computation request".to_string())))?;
+
+    create_link(request.pool_hash, request_hash.clone(), LinkTypes::PoolToComputationRequests, ())?;
+    create_link(request.requester, request_hash, LinkTypes::RequesterToComputationRequests, ())?;
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReviewComputationInput {
+    pub request_hash: ActionHash,
+    pub approve: bool,
+}
+
+/// Approve or reject a pending computation request (pool steward only)
+#[hdk_extern]
+pub fn review_computation_request(input: ReviewComputationInput) -> ExternResult<Record> {
+    let mut request = latest_computation_request(&input.request_hash)?;
+    require_pool_steward(&request.pool_hash)?;
+
+    if !matches!(request.status, ComputationStatus::Pending) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Only pending requests can be reviewed".to_string())));
+    }
+
+    request.status = if input.approve { ComputationStatus::Approved } else { ComputationStatus::Rejected };
+    request.reviewed_by = Some(agent_info()?.agent_initial_pubkey);
+    request.reviewed_at = Some(sys_time()?.as_micros() as i64);
+
+    let updated_hash = update_entry(input.request_hash, &request)?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated computation request".to_string())))
+}
+
+/// What one enclave run hands back: the execution log, which carries the
+/// aggregate when one was released
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EnclaveRunOutput {
+    pub request_hash: ActionHash,
+    pub log_hash: ActionHash,
+    pub log: EnclaveExecutionLog,
+}
+
+/// Run an approved computation over the pool's contributions
+///
+/// Hosted by the pool steward, the only agent that can read contributions.
+/// Contributions outside the query's time range or whose contributor has
+/// no budget left are left out. If fewer contributors remain than the
+/// pool's minimum, nothing is released and no budget is spent.
+#[hdk_extern]
+pub fn run_enclave_computation(request_hash: ActionHash) -> ExternResult<EnclaveRunOutput> {
+    let mut request = latest_computation_request(&request_hash)?;
+    let pool = require_pool_steward(&request.pool_hash)?;
+
+    if !matches!(request.status, ComputationStatus::Approved) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Computation request is not approved".to_string())));
+    }
+    let started_at = sys_time()?.as_micros() as i64;
+    if started_at < request.window_start {
+        return Err(wasm_error!(WasmErrorInner::Guest("Computation window has not opened".to_string())));
+    }
+    if started_at > request.window_end {
+        return Err(wasm_error!(WasmErrorInner::Guest("Computation window has closed".to_string())));
+    }
+    let executions = get_links(
+        LinkQuery::try_new(request_hash.clone(), LinkTypes::ComputationRequestToExecutionLogs)?,
+        GetStrategy::default(),
+    )?
+    .len() as u32;
+    if executions >= request.max_executions {
+        return Err(wasm_error!(WasmErrorInner::Guest("Computation request has used all its runs".to_string())));
+    }
+
+    let params = &request.dp_params;
+    let delta = match params.noise_mechanism {
+        NoiseMechanism::Gaussian => params.delta,
+        _ => 0.0,
+    };
+
+    let contributions = get_pool_contributions(request.pool_hash.clone())?;
+    let mut used = Vec::new();
+    let mut contributors: Vec<ActionHash> = Vec::new();
+    let mut excluded_out_of_range = 0u32;
+    let mut excluded_budget_exhausted = 0u32;
+    for record in &contributions {
+        let Some(contrib) = record.entry().to_app_option::<PrivacyContribution>().ok().flatten() else { continue };
+        if let Some(range) = &request.query_spec.time_range {
+            if contrib.contributed_at < range.start || contrib.contributed_at > range.end {
+                excluded_out_of_range += 1;
+                continue;
+            }
+        }
+        if !check_budget_available(&contrib.contributor, &request.pool_hash, params.epsilon, delta)? {
+            excluded_budget_exhausted += 1;
+            continue;
+        }
+        if !contributors.contains(&contrib.contributor) {
+            contributors.push(contrib.contributor.clone());
+        }
+        used.push(record.clone());
+    }
+
+    let required = pool.min_contributors.max(params.min_aggregation);
+    let (outcome, result) = if (contributors.len() as u32) < required {
+        let reason = format!("{} contributors available, {} required", contributors.len(), required);
+        (ExecutionOutcome::Suppressed(reason), None)
+    } else {
+        let result = compute_dp_result(&request.query_spec, &used, params)?;
+        for contributor in &contributors {
+            update_privacy_budget(contributor, &request.pool_hash, params.epsilon, delta)?;
+        }
+        (ExecutionOutcome::Released, Some(result))
+    };
+    let released = result.is_some();
+
+    let log = EnclaveExecutionLog {
+        request_hash: request_hash.clone(),
+        pool_hash: request.pool_hash.clone(),
+        executed_by: agent_info()?.agent_initial_pubkey,
+        started_at,
+        finished_at: sys_time()?.as_micros() as i64,
+        contributions_considered: contributions.len() as u32,
+        contributions_used: used.len() as u32,
+        excluded_out_of_range,
+        excluded_budget_exhausted,
+        epsilon_spent: if released { params.epsilon } else { 0.0 },
+        delta_spent: if released { delta } else { 0.0 },
+        noise_mechanism: params.noise_mechanism.clone(),
+        outcome,
+        result,
+    };
+    let log_hash = create_entry(&EntryTypes::EnclaveExecutionLog(log.clone()))?;
+    create_link(request_hash.clone(), log_hash.clone(), LinkTypes::ComputationRequestToExecutionLogs, ())?;
+
+    if executions + 1 >= request.max_executions {
+        request.status = ComputationStatus::Expired;
+        update_entry(request_hash.clone(), &request)?;
+    }
+
+    Ok(EnclaveRunOutput { request_hash, log_hash, log })
+}
+
+/// Execution logs of a computation request (requester or pool steward)
+#[hdk_extern]
+pub fn get_enclave_execution_logs(request_hash: ActionHash) -> ExternResult<Vec<EnclaveExecutionLog>> {
+    let request = latest_computation_request(&request_hash)?;
+    let caller = agent_info()?.agent_initial_pubkey;
+    if request.requester != caller {
+        require_pool_steward(&request.pool_hash)?;
+    }
+
+    let links = get_links(
+        LinkQuery::try_new(request_hash, LinkTypes::ComputationRequestToExecutionLogs)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut logs = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(log) = record.entry().to_app_option::<EnclaveExecutionLog>().ok().flatten() {
+                    logs.push(log);
+                }
+            }
+        }
+    }
+    logs.sort_by_key(|log| log.started_at);
+
+    Ok(logs)
+}
+
+/// A computation request at its current status
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ComputationRequestView {
+    pub request_hash: ActionHash,
+    pub request: ComputationRequest,
+}
+
+/// Computation requests the caller has submitted
+#[hdk_extern]
+pub fn get_my_computation_requests(_: ()) -> ExternResult<Vec<ComputationRequestView>> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    computation_requests_from(caller.into(), LinkTypes::RequesterToComputationRequests)
+}
+
+/// Computation requests awaiting review on a pool (pool steward only)
+#[hdk_extern]
+pub fn get_pending_computation_requests(pool_hash: ActionHash) -> ExternResult<Vec<ComputationRequestView>> {
+    require_pool_steward(&pool_hash)?;
+    Ok(computation_requests_from(pool_hash.into(), LinkTypes::PoolToComputationRequests)?
+        .into_iter()
+        .filter(|view| matches!(view.request.status, ComputationStatus::Pending))
+        .collect())
+}
+
 // ==================== GOVERNANCE ====================
 
 /// Create a governance proposal
@@ -649,6 +879,60 @@ fn check_query_permissions(perms: &QueryPermissions, purpose: &QueryPurpose, req
     }
 }
 
+/// Get a pool and its record's author
+fn get_pool(pool_hash: &ActionHash) -> ExternResult<(AgentPubKey, DataPool)> {
+    let record = get(pool_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Pool not found".to_string())))?;
+    let pool: DataPool = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid pool".to_string())))?;
+    Ok((record.action().author().clone(), pool))
+}
+
+/// Only the pool creator stewards its computations
+fn require_pool_steward(pool_hash: &ActionHash) -> ExternResult<DataPool> {
+    let (author, pool) = get_pool(pool_hash)?;
+    if author != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the pool steward can manage enclave computations".to_string()
+        )));
+    }
+    Ok(pool)
+}
+
+/// A computation request as of its latest update
+fn latest_computation_request(request_hash: &ActionHash) -> ExternResult<ComputationRequest> {
+    let Some(Details::Record(details)) = get_details(request_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Computation request not found".to_string())));
+    };
+    let record = match details.updates.iter().max_by_key(|update| update.action().timestamp()) {
+        Some(update) => get(update.as_hash().clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Computation request not found".to_string())))?,
+        None => details.record,
+    };
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid computation request".to_string())))
+}
+
+fn computation_requests_from(base: AnyLinkableHash, link_type: LinkTypes) -> ExternResult<Vec<ComputationRequestView>> {
+    let links = get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::default())?;
+
+    let mut requests = Vec::new();
+    for link in links {
+        if let Some(request_hash) = link.target.into_action_hash() {
+            let request = latest_computation_request(&request_hash)?;
+            requests.push(ComputationRequestView { request_hash, request });
+        }
+    }
+
+    Ok(requests)
+}
+
 /// Get or create privacy budget ledger entry for a contributor-pool pair
 ///
 /// This function properly retrieves existing budget entries using links,
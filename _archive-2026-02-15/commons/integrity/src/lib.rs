@@ -5,6 +5,7 @@
 //! - Privacy-preserving data contribution
 //! - Democratic governance of health data commons
 //! - Collective benefit from shared health knowledge
+//! - Time-boxed enclave computations that release only aggregates
 //!
 //! Key Principles:
 //! - Individual privacy protected via differential privacy
@@ -36,6 +37,10 @@ pub enum EntryTypes {
     CollectiveInsight(CollectiveInsight),
     /// Privacy budget ledger entry for formal DP tracking
     BudgetLedgerEntry(BudgetLedgerEntry),
+    /// Request to compute in place over a pool
+    ComputationRequest(ComputationRequest),
+    /// What one enclave run did and released
+    EnclaveExecutionLog(EnclaveExecutionLog),
 }
 
 /// Link types for the health commons zome
@@ -53,6 +58,9 @@ pub enum LinkTypes {
     /// Links patient+pool combination to their budget ledger entry
     /// Base: hash(patient_hash + pool_hash), Target: BudgetLedgerEntry
     PatientPoolToBudgetLedger,
+    PoolToComputationRequests,
+    RequesterToComputationRequests,
+    ComputationRequestToExecutionLogs,
 }
 
 // ==================== DATA POOLS ====================
@@ -694,6 +702,102 @@ pub enum ConfidenceLevel {
     Exploratory,
 }
 
+// ==================== COMPUTATION ENCLAVE ====================
+
+/// A computation a researcher asks to run in place over a pool
+///
+/// Contributions never leave the system. Once the pool steward approves
+/// the request it may run only inside its time window, and each run
+/// releases just the noisy aggregate and an execution log.
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct ComputationRequest {
+    /// Unique request ID
+    pub request_id: String,
+    /// Pool to compute over
+    pub pool_hash: ActionHash,
+    /// Who is asking
+    pub requester: AgentPubKey,
+    /// Organization name
+    pub organization: String,
+    /// Purpose of the computation
+    pub purpose: QueryPurpose,
+    /// Aggregate to compute
+    pub query_spec: QuerySpecification,
+    /// DP parameters for each run; no looser than the pool's own
+    pub dp_params: PrivacyParameters,
+    /// Runs are allowed from this time (microseconds)
+    pub window_start: i64,
+    /// Runs are allowed until this time (microseconds)
+    pub window_end: i64,
+    /// Most runs the request may make within its window
+    pub max_executions: u32,
+    /// Status
+    pub status: ComputationStatus,
+    /// Requested at
+    pub requested_at: i64,
+    /// Steward who approved or rejected the request
+    pub reviewed_by: Option<AgentPubKey>,
+    /// Reviewed at
+    pub reviewed_at: Option<i64>,
+}
+
+/// Computation request status
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ComputationStatus {
+    /// Awaiting the pool steward
+    Pending,
+    /// May run inside its window
+    Approved,
+    /// Refused by the pool steward
+    Rejected,
+    /// Window closed or runs used up
+    Expired,
+}
+
+/// Record of one enclave run; released alongside the aggregate
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct EnclaveExecutionLog {
+    /// Request that was run
+    pub request_hash: ActionHash,
+    /// Pool computed over
+    pub pool_hash: ActionHash,
+    /// Agent hosting the computation
+    pub executed_by: AgentPubKey,
+    /// Run started at
+    pub started_at: i64,
+    /// Run finished at
+    pub finished_at: i64,
+    /// Contributions in the pool when the run started
+    pub contributions_considered: u32,
+    /// Contributions that fed the aggregate
+    pub contributions_used: u32,
+    /// Contributions outside the query's time range
+    pub excluded_out_of_range: u32,
+    /// Contributions whose contributor had no privacy budget left
+    pub excluded_budget_exhausted: u32,
+    /// Epsilon charged to each contributor used
+    pub epsilon_spent: f64,
+    /// Delta charged to each contributor used
+    pub delta_spent: f64,
+    /// Noise mechanism applied
+    pub noise_mechanism: NoiseMechanism,
+    /// What left the enclave
+    pub outcome: ExecutionOutcome,
+    /// The released aggregate; None unless the outcome is Released
+    pub result: Option<DifferentiallyPrivateResult>,
+}
+
+/// What an enclave run released
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ExecutionOutcome {
+    /// The noisy aggregate was released
+    Released,
+    /// Too few contributors; nothing was released and no budget was spent
+    Suppressed(String),
+}
+
 // ==================== VALIDATION ====================
 
 /// Validate data pool
@@ -864,3 +968,36 @@ pub fn validate_budget_ledger_entry(entry: &BudgetLedgerEntry) -> ExternResult<V
 
     Ok(ValidateCallbackResult::Valid)
 }
+
+/// Validate computation request
+pub fn validate_computation_request(request: &ComputationRequest) -> ExternResult<ValidateCallbackResult> {
+    if request.request_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Request ID required".to_string()));
+    }
+
+    if request.organization.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Organization required".to_string()));
+    }
+
+    if request.window_end <= request.window_start {
+        return Ok(ValidateCallbackResult::Invalid("Window end must be after start".to_string()));
+    }
+
+    if request.max_executions == 0 {
+        return Ok(ValidateCallbackResult::Invalid("At least one execution must be allowed".to_string()));
+    }
+
+    if request.dp_params.epsilon <= 0.0 {
+        return Ok(ValidateCallbackResult::Invalid("Epsilon must be positive".to_string()));
+    }
+
+    if request.dp_params.delta < 0.0 || request.dp_params.delta >= 1.0 {
+        return Ok(ValidateCallbackResult::Invalid("Delta must be in [0, 1)".to_string()));
+    }
+
+    if request.dp_params.sensitivity_bound <= 0.0 {
+        return Ok(ValidateCallbackResult::Invalid("Sensitivity bound must be positive".to_string()));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -5,6 +5,7 @@
 use hdk::prelude::*;
 use dividends_integrity::*;
 use mycelix_health_shared::{require_authorization, log_data_access, DataCategory, Permission};
+use mycelix_health_shared::{increment_counter, read_counter};
 
 // ==================== DATA CONTRIBUTIONS ====================
 
@@ -31,6 +32,7 @@ pub fn create_data_contribution(contribution: DataContribution) -> ExternResult<
         LinkTypes::PatientToContributions,
         (),
     )?;
+    increment_counter(contribution.patient_hash.clone(), LinkTypes::ContributionCounter, 1)?;
 
     log_data_access(
         contribution.patient_hash,
@@ -325,6 +327,7 @@ pub fn create_dividend_distribution(distribution: DividendDistribution) -> Exter
         LinkTypes::PatientToDividends,
         (),
     )?;
+    increment_counter(distribution.patient_hash.clone(), LinkTypes::DividendCounter, 1)?;
 
     // Link to revenue event
     create_link(
@@ -449,7 +452,7 @@ pub struct ClaimDividendInput {
 /// Calculate total dividends for a patient
 #[hdk_extern]
 pub fn get_dividend_summary(patient_hash: ActionHash) -> ExternResult<DividendSummary> {
-    let dividends = get_patient_dividends(patient_hash)?;
+    let dividends = get_patient_dividends(patient_hash.clone())?;
 
     let mut total_earned: f64 = 0.0;
     let mut total_claimed: f64 = 0.0;
@@ -475,7 +478,7 @@ pub fn get_dividend_summary(patient_hash: ActionHash) -> ExternResult<DividendSu
         total_claimed,
         total_donated,
         pending_amount: pending,
-        dividend_count: dividends.len() as u32,
+        dividend_count: counter_value(patient_hash, LinkTypes::DividendCounter)?,
     })
 }
 
@@ -824,11 +827,10 @@ pub fn get_dividend_pools(_: ()) -> ExternResult<Vec<Record>> {
     Ok(pools)
 }
 
-/// Update pool balance
+/// Update pool balance (ungoverned pools only; governed pools use proposals)
 #[hdk_extern]
 pub fn update_pool_balance(input: UpdatePoolBalanceInput) -> ExternResult<Record> {
-    let record = get(input.pool_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Pool not found".to_string())))?;
+    let record = latest_version(input.pool_hash.clone(), LinkTypes::PoolUpdates)?;
 
     let mut pool: DividendPool = record
         .entry()
@@ -836,17 +838,19 @@ pub fn update_pool_balance(input: UpdatePoolBalanceInput) -> ExternResult<Record
         .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid pool".to_string())))?;
 
-    pool.balance = input.new_balance;
-
-    if input.is_distribution {
-        pool.total_distributed += input.distributed_amount.unwrap_or(0.0);
-        pool.last_distribution_at = Some(sys_time()?.as_micros() as i64);
+    if pool.governance_hash.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Pool is governed; propose the change and apply it once approved".to_string()
+        )));
     }
 
-    let updated_hash = update_entry(input.pool_hash, &pool)?;
+    apply_change(&mut pool, &PoolChange {
+        new_balance: input.new_balance,
+        is_distribution: input.is_distribution,
+        distributed_amount: input.distributed_amount,
+    })?;
 
-    get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated pool".to_string())))
+    update_pool(input.pool_hash, &record, &pool)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -857,6 +861,297 @@ pub struct UpdatePoolBalanceInput {
     pub distributed_amount: Option<f64>,
 }
 
+fn apply_change(pool: &mut DividendPool, change: &PoolChange) -> ExternResult<()> {
+    pool.balance = change.new_balance;
+
+    if change.is_distribution {
+        pool.total_distributed += change.distributed_amount.unwrap_or(0.0);
+        pool.last_distribution_at = Some(sys_time()?.as_micros() as i64);
+    }
+
+    Ok(())
+}
+
+/// Write a new pool version and link it from the original
+fn update_pool(pool_hash: ActionHash, current: &Record, pool: &DividendPool) -> ExternResult<Record> {
+    let updated_hash = update_entry(current.action_address().clone(), pool)?;
+    create_link(
+        pool_hash,
+        updated_hash.clone(),
+        LinkTypes::PoolUpdates,
+        (),
+    )?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated pool".to_string())))
+}
+
+/// Newest version of an entry, following its update links
+fn latest_version(original_hash: ActionHash, update_links: LinkTypes) -> ExternResult<Record> {
+    let mut links = get_links(
+        LinkQuery::try_new(original_hash.clone(), update_links)?,
+        GetStrategy::default(),
+    )?;
+    links.sort_by_key(|link| link.timestamp);
+
+    let latest_hash = links
+        .pop()
+        .and_then(|link| link.target.into_action_hash())
+        .unwrap_or(original_hash);
+
+    get(latest_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record not found".to_string())))
+}
+
+// ==================== POOL GOVERNANCE ====================
+
+/// Put a pool under M-of-N governance (pool creator only, once)
+#[hdk_extern]
+pub fn create_pool_governance(input: CreatePoolGovernanceInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let original = get(input.pool_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Pool not found".to_string())))?;
+    if original.action().author() != &caller {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the pool creator can set up its governance".to_string()
+        )));
+    }
+
+    let current = latest_version(input.pool_hash.clone(), LinkTypes::PoolUpdates)?;
+    let mut pool: DividendPool = current
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid pool".to_string())))?;
+    if pool.governance_hash.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest("Pool is already governed".to_string())));
+    }
+
+    let governance = PoolGovernance {
+        pool_id: pool.pool_id.clone(),
+        approvers: input.approvers,
+        threshold: input.threshold,
+        created_at: sys_time()?.as_micros() as i64,
+    };
+    if let ValidateCallbackResult::Invalid(reason) = validate_pool_governance(&governance)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let governance_hash = create_entry(&EntryTypes::PoolGovernance(governance))?;
+    create_link(
+        input.pool_hash.clone(),
+        governance_hash.clone(),
+        LinkTypes::PoolToGovernance,
+        (),
+    )?;
+
+    pool.governance_hash = Some(governance_hash.clone());
+    update_pool(input.pool_hash, &current, &pool)?;
+
+    get(governance_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find governance".to_string())))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePoolGovernanceInput {
+    pub pool_hash: ActionHash,
+    pub approvers: Vec<AgentPubKey>,
+    pub threshold: u32,
+}
+
+/// Propose a change to a governed pool
+#[hdk_extern]
+pub fn propose_pool_change(input: ProposePoolChangeInput) -> ExternResult<Record> {
+    let pool: DividendPool = latest_version(input.pool_hash.clone(), LinkTypes::PoolUpdates)?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid pool".to_string())))?;
+    let governance_hash = pool.governance_hash.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Pool is not governed; update it directly".to_string()
+    )))?;
+
+    let now = sys_time()?.as_micros() as i64;
+    let proposal = PoolChangeProposal {
+        proposal_id: format!("POOL-CHANGE-{}-{}", pool.pool_id, now),
+        pool_id: pool.pool_id,
+        governance_hash,
+        change: input.change,
+        proposed_by: agent_info()?.agent_initial_pubkey,
+        proposed_at: now,
+        approvals: Vec::new(),
+    };
+    if let ValidateCallbackResult::Invalid(reason) = validate_pool_change_proposal(&proposal)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let proposal_hash = create_entry(&EntryTypes::PoolChangeProposal(proposal))?;
+    create_link(
+        input.pool_hash,
+        proposal_hash.clone(),
+        LinkTypes::PoolToChangeProposals,
+        (),
+    )?;
+
+    get(proposal_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find proposal".to_string())))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProposePoolChangeInput {
+    pub pool_hash: ActionHash,
+    pub change: PoolChange,
+}
+
+/// Sign approval of a pool change proposal (governance approvers only)
+#[hdk_extern]
+pub fn approve_pool_change(proposal_hash: ActionHash) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let current = latest_version(proposal_hash.clone(), LinkTypes::ProposalUpdates)?;
+    let mut proposal: PoolChangeProposal = current
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid proposal".to_string())))?;
+
+    let governance = get_governance(&proposal.governance_hash)?;
+    if !governance.approvers.contains(&caller) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Not an approver for this pool".to_string())));
+    }
+    if proposal.approvals.iter().any(|a| a.approver == caller) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Already approved".to_string())));
+    }
+
+    let signature = sign(caller.clone(), proposal.approval_payload())?;
+    proposal.approvals.push(PoolChangeApproval {
+        approver: caller,
+        signature,
+        approved_at: sys_time()?.as_micros() as i64,
+    });
+
+    let updated_hash = update_entry(current.action_address().clone(), &proposal)?;
+    create_link(
+        proposal_hash,
+        updated_hash.clone(),
+        LinkTypes::ProposalUpdates,
+        (),
+    )?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated proposal".to_string())))
+}
+
+/// Apply a proposal once it has enough approvals
+#[hdk_extern]
+pub fn apply_pool_change(input: ApplyPoolChangeInput) -> ExternResult<Record> {
+    let proposal_record = latest_version(input.proposal_hash, LinkTypes::ProposalUpdates)?;
+    let proposal: PoolChangeProposal = proposal_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid proposal".to_string())))?;
+
+    let current = latest_version(input.pool_hash.clone(), LinkTypes::PoolUpdates)?;
+    let previous: DividendPool = current
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid pool".to_string())))?;
+
+    let mut pool = previous.clone();
+    apply_change(&mut pool, &proposal.change)?;
+    pool.applied_proposal = Some(proposal_record.action_address().clone());
+
+    if let ValidateCallbackResult::Invalid(reason) = validate_dividend_pool_update(&pool, &previous)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    update_pool(input.pool_hash, &current, &pool)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ApplyPoolChangeInput {
+    pub pool_hash: ActionHash,
+    pub proposal_hash: ActionHash,
+}
+
+/// Proposal with its approval progress
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PoolChangeProposalStatus {
+    pub proposal_hash: ActionHash,
+    /// Version carrying the most approvals
+    pub latest_hash: ActionHash,
+    pub proposal: PoolChangeProposal,
+    pub approvals: u32,
+    pub threshold: u32,
+    pub applied: bool,
+}
+
+/// List a pool's change proposals with approval progress
+#[hdk_extern]
+pub fn get_pool_change_proposals(pool_hash: ActionHash) -> ExternResult<Vec<PoolChangeProposalStatus>> {
+    let pool: DividendPool = latest_version(pool_hash.clone(), LinkTypes::PoolUpdates)?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid pool".to_string())))?;
+    let applied: Vec<ActionHash> = get_links(
+        LinkQuery::try_new(pool_hash.clone(), LinkTypes::PoolUpdates)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .filter_map(|hash| get(hash, GetOptions::default()).ok().flatten())
+    .filter_map(|record| record.entry().to_app_option::<DividendPool>().ok().flatten())
+    .filter_map(|version| version.applied_proposal)
+    .collect();
+    let threshold = match &pool.governance_hash {
+        Some(hash) => get_governance(hash)?.threshold,
+        None => 0,
+    };
+
+    let links = get_links(
+        LinkQuery::try_new(pool_hash, LinkTypes::PoolToChangeProposals)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut statuses = Vec::new();
+    for link in links {
+        let Some(proposal_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let latest = latest_version(proposal_hash.clone(), LinkTypes::ProposalUpdates)?;
+        let Some(proposal) = latest.entry().to_app_option::<PoolChangeProposal>().ok().flatten() else {
+            continue;
+        };
+        let versions = get_links(
+            LinkQuery::try_new(proposal_hash.clone(), LinkTypes::ProposalUpdates)?,
+            GetStrategy::default(),
+        )?;
+        let was_applied = applied.contains(&proposal_hash)
+            || versions
+                .iter()
+                .filter_map(|l| l.target.clone().into_action_hash())
+                .any(|hash| applied.contains(&hash));
+        statuses.push(PoolChangeProposalStatus {
+            proposal_hash,
+            latest_hash: latest.action_address().clone(),
+            approvals: proposal.approvals.len() as u32,
+            threshold,
+            applied: was_applied,
+            proposal,
+        });
+    }
+
+    Ok(statuses)
+}
+
+fn get_governance(governance_hash: &ActionHash) -> ExternResult<PoolGovernance> {
+    get(governance_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Pool governance not found".to_string())))
+}
+
 // ==================== HELPER FUNCTIONS ====================
 
 /// Calculate dividends for a revenue event
@@ -940,7 +1235,8 @@ pub struct CalculatedDistribution {
 pub fn get_patient_impact_summary(patient_hash: ActionHash) -> ExternResult<PatientImpactSummary> {
     let contributions = get_patient_contributions(patient_hash.clone())?;
     let usages = get_patient_usages(patient_hash.clone())?;
-    let dividends = get_patient_dividends(patient_hash)?;
+    let dividends = get_patient_dividends(patient_hash.clone())?;
+    let releases = get_patient_dataset_releases(patient_hash.clone())?;
 
     let mut total_data_points: u64 = 0;
     let mut total_earnings: f64 = 0.0;
@@ -969,15 +1265,24 @@ pub fn get_patient_impact_summary(patient_hash: ActionHash) -> ExternResult<Pati
     }
 
     Ok(PatientImpactSummary {
-        total_contributions: contributions.len() as u32,
+        total_contributions: counter_value(patient_hash.clone(), LinkTypes::ContributionCounter)?,
         total_data_points,
         projects_contributed,
         total_usages: usages.len() as u32,
         total_earnings,
-        total_dividends: dividends.len() as u32,
+        total_dividends: counter_value(patient_hash, LinkTypes::DividendCounter)?,
+        dataset_releases: releases.len() as u32,
+        releases_pending_destruction: releases
+            .iter()
+            .filter(|r| r.retention_expired && !r.destroyed)
+            .count() as u32,
     })
 }
 
+fn counter_value(patient_hash: ActionHash, link_type: LinkTypes) -> ExternResult<u32> {
+    Ok(read_counter(patient_hash, link_type)?.clamp(0, u32::MAX as i64) as u32)
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PatientImpactSummary {
     pub total_contributions: u32,
@@ -986,6 +1291,261 @@ pub struct PatientImpactSummary {
     pub total_usages: u32,
     pub total_earnings: f64,
     pub total_dividends: u32,
+    /// De-identified dataset releases that include this patient's data
+    pub dataset_releases: u32,
+    /// Releases past retention whose copies have not all been destroyed
+    pub releases_pending_destruction: u32,
+}
+
+// ==================== DATASET RELEASES ====================
+
+/// Record the release of a de-identified dataset (project owner only)
+#[hdk_extern]
+pub fn create_dataset_release(release: DatasetRelease) -> ExternResult<Record> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_dataset_release(&release)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let caller = agent_info()?.agent_initial_pubkey;
+    let project_record = get(release.project_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Project not found".to_string())))?;
+    if project_record.action().author() != &caller {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the project creator can release datasets".to_string()
+        )));
+    }
+
+    for contribution_hash in &release.contribution_hashes {
+        let contribution: DataContribution = get(contribution_hash.clone(), GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option().ok().flatten())
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Contribution not found".to_string())))?;
+        if contribution.revoked {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Contribution {} has been revoked",
+                contribution.contribution_id
+            ))));
+        }
+    }
+
+    let release_hash = create_entry(&EntryTypes::DatasetRelease(release.clone()))?;
+    let record = get(release_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find release".to_string())))?;
+
+    create_link(
+        release.project_hash,
+        release_hash.clone(),
+        LinkTypes::ProjectToDatasetReleases,
+        (),
+    )?;
+    create_link(
+        caller,
+        release_hash.clone(),
+        LinkTypes::OwnerToDatasetReleases,
+        (),
+    )?;
+    for contribution_hash in release.contribution_hashes {
+        create_link(
+            contribution_hash,
+            release_hash.clone(),
+            LinkTypes::ContributionToDatasetReleases,
+            (),
+        )?;
+    }
+    for recipient in release.recipients {
+        create_link(
+            recipient,
+            release_hash.clone(),
+            LinkTypes::RecipientToDatasetReleases,
+            (),
+        )?;
+    }
+
+    Ok(record)
+}
+
+/// Sign the destruction attestation for a release (recipients only)
+#[hdk_extern]
+pub fn attest_dataset_destruction(input: AttestDestructionInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let status = get_dataset_release_status(input.release_hash.clone())?;
+    if !status.release.recipients.contains(&caller) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only a recipient of the release can attest its destruction".to_string()
+        )));
+    }
+    if !status.pending_recipients.contains(&caller) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Destruction already attested".to_string()
+        )));
+    }
+
+    let attestation = DestructionAttestation {
+        release_hash: input.release_hash.clone(),
+        attested_by: caller,
+        destroyed_at: input.destroyed_at,
+        method: input.method,
+        statement: input.statement,
+    };
+    if let ValidateCallbackResult::Invalid(reason) = validate_destruction_attestation(&attestation)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let attestation_hash = create_entry(&EntryTypes::DestructionAttestation(attestation))?;
+    create_link(
+        input.release_hash,
+        attestation_hash.clone(),
+        LinkTypes::ReleaseToDestructionAttestations,
+        (),
+    )?;
+
+    get(attestation_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find attestation".to_string())))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AttestDestructionInput {
+    pub release_hash: ActionHash,
+    pub destroyed_at: i64,
+    pub method: String,
+    pub statement: String,
+}
+
+/// A release together with its destruction progress
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DatasetReleaseStatus {
+    pub release_hash: ActionHash,
+    pub release: DatasetRelease,
+    pub attestations: Vec<DestructionAttestation>,
+    /// Recipients who have not yet attested destruction
+    pub pending_recipients: Vec<AgentPubKey>,
+    pub retention_expired: bool,
+    /// Every recipient has attested destruction
+    pub destroyed: bool,
+}
+
+/// Get a release and which recipients still hold a copy
+#[hdk_extern]
+pub fn get_dataset_release_status(release_hash: ActionHash) -> ExternResult<DatasetReleaseStatus> {
+    let release: DatasetRelease = get(release_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Release not found".to_string())))?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid release".to_string())))?;
+
+    let links = get_links(
+        LinkQuery::try_new(release_hash.clone(), LinkTypes::ReleaseToDestructionAttestations)?,
+        GetStrategy::default(),
+    )?;
+    let mut attestations = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(attestation) = record.entry().to_app_option::<DestructionAttestation>().ok().flatten() {
+                    // Only the recipient's own signature counts
+                    if record.action().author() == &attestation.attested_by {
+                        attestations.push(attestation);
+                    }
+                }
+            }
+        }
+    }
+
+    let pending_recipients: Vec<AgentPubKey> = release
+        .recipients
+        .iter()
+        .filter(|r| !attestations.iter().any(|a| &a.attested_by == *r))
+        .cloned()
+        .collect();
+    let now = sys_time()?.as_micros() as i64;
+
+    Ok(DatasetReleaseStatus {
+        release_hash,
+        retention_expired: now >= release.retention_deadline,
+        destroyed: pending_recipients.is_empty(),
+        release,
+        attestations,
+        pending_recipients,
+    })
+}
+
+/// Releases nearing or past their retention deadline that still await destruction
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RetentionReminder {
+    pub release_hash: ActionHash,
+    pub release_id: String,
+    pub project_hash: ActionHash,
+    pub retention_deadline: i64,
+    pub overdue: bool,
+    pub pending_recipients: Vec<AgentPubKey>,
+}
+
+/// Retention reminders for the caller, as project owner or as a recipient
+/// who has not yet attested destruction
+#[hdk_extern]
+pub fn get_retention_reminders(_: ()) -> ExternResult<Vec<RetentionReminder>> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?.as_micros() as i64;
+    let horizon = now + RETENTION_REMINDER_LEAD_DAYS * MICROS_PER_DAY;
+
+    let mut release_hashes: Vec<ActionHash> = Vec::new();
+    for link_type in [LinkTypes::OwnerToDatasetReleases, LinkTypes::RecipientToDatasetReleases] {
+        let links = get_links(LinkQuery::try_new(caller.clone(), link_type)?, GetStrategy::default())?;
+        for hash in links.into_iter().filter_map(|l| l.target.into_action_hash()) {
+            if !release_hashes.contains(&hash) {
+                release_hashes.push(hash);
+            }
+        }
+    }
+
+    let mut reminders = Vec::new();
+    for release_hash in release_hashes {
+        let status = get_dataset_release_status(release_hash)?;
+        let is_owner = is_project_owner(&status.release.project_hash, &caller)?;
+        let awaiting_caller = (is_owner && !status.destroyed) || status.pending_recipients.contains(&caller);
+        if awaiting_caller && status.release.retention_deadline <= horizon {
+            reminders.push(RetentionReminder {
+                release_hash: status.release_hash,
+                release_id: status.release.release_id,
+                project_hash: status.release.project_hash,
+                retention_deadline: status.release.retention_deadline,
+                overdue: status.retention_expired,
+                pending_recipients: status.pending_recipients,
+            });
+        }
+    }
+    reminders.sort_by_key(|r| r.retention_deadline);
+
+    Ok(reminders)
+}
+
+fn is_project_owner(project_hash: &ActionHash, agent: &AgentPubKey) -> ExternResult<bool> {
+    Ok(get(project_hash.clone(), GetOptions::default())?
+        .is_some_and(|record| record.action().author() == agent))
+}
+
+/// Dataset releases derived from a patient's contributions
+#[hdk_extern]
+pub fn get_patient_dataset_releases(patient_hash: ActionHash) -> ExternResult<Vec<DatasetReleaseStatus>> {
+    let contributions = get_patient_contributions(patient_hash)?;
+
+    let mut release_hashes: Vec<ActionHash> = Vec::new();
+    for contrib in contributions {
+        let links = get_links(
+            LinkQuery::try_new(contrib.action_address().clone(), LinkTypes::ContributionToDatasetReleases)?,
+            GetStrategy::default(),
+        )?;
+        for hash in links.into_iter().filter_map(|l| l.target.into_action_hash()) {
+            if !release_hashes.contains(&hash) {
+                release_hashes.push(hash);
+            }
+        }
+    }
+
+    release_hashes
+        .into_iter()
+        .map(get_dataset_release_status)
+        .collect()
 }
 
 // ==================== CLINICAL TRIALS INTEGRATION ====================
@@ -1083,6 +1643,7 @@ pub fn create_trial_contribution(input: TrialDataContributionInput) -> ExternRes
         LinkTypes::PatientToContributions,
         (),
     )?;
+    increment_counter(input.patient_hash.clone(), LinkTypes::ContributionCounter, 1)?;
 
     // Link to trial (using project link type)
     create_link(
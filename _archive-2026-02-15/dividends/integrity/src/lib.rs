@@ -35,6 +35,14 @@ pub enum EntryTypes {
     AttributionChain(AttributionChain),
     /// Dividend pool (collective fund)
     DividendPool(DividendPool),
+    /// De-identified dataset release
+    DatasetRelease(DatasetRelease),
+    /// Recipient's destruction attestation for a release
+    DestructionAttestation(DestructionAttestation),
+    /// M-of-N approvers for a dividend pool
+    PoolGovernance(PoolGovernance),
+    /// Proposed pool change collecting approvals
+    PoolChangeProposal(PoolChangeProposal),
 }
 
 /// Link types for the data dividends zome
@@ -52,6 +60,22 @@ pub enum LinkTypes {
     ActiveProjects,
     DividendPools,
     AttributionChains,
+    /// Counter shards for a patient's contributions
+    ContributionCounter,
+    /// Counter shards for a patient's dividend distributions
+    DividendCounter,
+    ProjectToDatasetReleases,
+    ContributionToDatasetReleases,
+    /// Project owner to the releases they must see destroyed
+    OwnerToDatasetReleases,
+    RecipientToDatasetReleases,
+    ReleaseToDestructionAttestations,
+    /// Original pool to its later versions
+    PoolUpdates,
+    PoolToGovernance,
+    PoolToChangeProposals,
+    /// Original proposal to its versions carrying more approvals
+    ProposalUpdates,
 }
 
 // ==================== DATA CONTRIBUTIONS ====================
@@ -725,6 +749,12 @@ pub struct DividendPool {
     pub created_at: i64,
     /// Last distribution at
     pub last_distribution_at: Option<i64>,
+    /// M-of-N governance; once set, balance changes need an approved proposal
+    #[serde(default)]
+    pub governance_hash: Option<ActionHash>,
+    /// Approved proposal this version of the pool applies
+    #[serde(default)]
+    pub applied_proposal: Option<ActionHash>,
 }
 
 /// Types of pools
@@ -742,6 +772,136 @@ pub enum PoolType {
     Organizational(String),
 }
 
+// ==================== POOL GOVERNANCE ====================
+
+/// Approvers allowed to change a pool and how many must agree
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct PoolGovernance {
+    /// Pool being governed
+    pub pool_id: String,
+    /// Agents who may approve changes
+    pub approvers: Vec<AgentPubKey>,
+    /// Approvals needed (M of N)
+    pub threshold: u32,
+    /// Created at
+    pub created_at: i64,
+}
+
+/// A proposed change to a governed pool, collecting approvals
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct PoolChangeProposal {
+    /// Unique proposal ID
+    pub proposal_id: String,
+    /// Pool to change
+    pub pool_id: String,
+    /// Governance the approvals are counted against
+    pub governance_hash: ActionHash,
+    /// The change itself
+    pub change: PoolChange,
+    /// Who proposed it
+    pub proposed_by: AgentPubKey,
+    /// Proposed at
+    pub proposed_at: i64,
+    /// Approvals collected so far
+    pub approvals: Vec<PoolChangeApproval>,
+}
+
+/// New pool parameters; mirrors a balance update
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct PoolChange {
+    pub new_balance: f64,
+    pub is_distribution: bool,
+    pub distributed_amount: Option<f64>,
+}
+
+/// An approver's signature over the proposal
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PoolChangeApproval {
+    pub approver: AgentPubKey,
+    pub signature: Signature,
+    pub approved_at: i64,
+}
+
+/// What approvers sign
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PoolChangeApprovalPayload {
+    pub proposal_id: String,
+    pub pool_id: String,
+    pub change: PoolChange,
+}
+
+impl PoolChangeProposal {
+    pub fn approval_payload(&self) -> PoolChangeApprovalPayload {
+        PoolChangeApprovalPayload {
+            proposal_id: self.proposal_id.clone(),
+            pool_id: self.pool_id.clone(),
+            change: self.change.clone(),
+        }
+    }
+}
+
+// ==================== DATASET RELEASES ====================
+
+/// Micros in a day, for retention arithmetic on `i64` timestamps
+pub const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+/// How close to the deadline the project owner starts being reminded
+pub const RETENTION_REMINDER_LEAD_DAYS: i64 = 30;
+
+impl UsageType {
+    /// Longest a de-identified dataset released for this purpose may be kept
+    pub fn max_retention_days(&self) -> i64 {
+        match self {
+            UsageType::QualityMetric => 365,
+            UsageType::ModelTraining => 730,
+            UsageType::ResearchAnalysis => 1095,
+            UsageType::Publication | UsageType::PopulationStudy => 1825,
+            UsageType::DrugDiscovery => 3650,
+            UsageType::ClinicalTrial => 9125,
+        }
+    }
+}
+
+/// A de-identified dataset handed to external recipients
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct DatasetRelease {
+    /// Unique release ID
+    pub release_id: String,
+    /// Project the dataset was derived for
+    pub project_hash: ActionHash,
+    /// Hash of the released dataset (not the data itself)
+    pub dataset_hash: [u8; 32],
+    /// Contributions the dataset was derived from
+    pub contribution_hashes: Vec<ActionHash>,
+    /// Researchers who received a copy
+    pub recipients: Vec<AgentPubKey>,
+    /// Purpose the release is limited to
+    pub purpose: UsageType,
+    /// Released at
+    pub released_at: i64,
+    /// Every copy must be destroyed by this time
+    pub retention_deadline: i64,
+}
+
+/// A recipient's signed statement that their copy of a release was destroyed
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct DestructionAttestation {
+    /// Release being attested
+    pub release_hash: ActionHash,
+    /// Recipient attesting
+    pub attested_by: AgentPubKey,
+    /// When the copy was destroyed
+    pub destroyed_at: i64,
+    /// How it was destroyed (e.g. "crypto-shred", "NIST 800-88 purge")
+    pub method: String,
+    /// Free-text attestation
+    pub statement: String,
+}
+
 // ==================== VALIDATION ====================
 
 /// Validate a data contribution
@@ -842,3 +1002,156 @@ pub fn validate_dividend_preferences(prefs: &DividendPreferences) -> ExternResul
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Validate pool governance
+pub fn validate_pool_governance(governance: &PoolGovernance) -> ExternResult<ValidateCallbackResult> {
+    if governance.approvers.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("At least one approver required".to_string()));
+    }
+
+    for (i, approver) in governance.approvers.iter().enumerate() {
+        if governance.approvers[..i].contains(approver) {
+            return Ok(ValidateCallbackResult::Invalid("Approvers must be distinct".to_string()));
+        }
+    }
+
+    if governance.threshold == 0 || governance.threshold as usize > governance.approvers.len() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Threshold must be between 1 and the number of approvers".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate a pool change proposal and every approval it carries
+pub fn validate_pool_change_proposal(proposal: &PoolChangeProposal) -> ExternResult<ValidateCallbackResult> {
+    if proposal.proposal_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Proposal ID required".to_string()));
+    }
+
+    if proposal.change.new_balance < 0.0 || proposal.change.distributed_amount.is_some_and(|a| a < 0.0) {
+        return Ok(ValidateCallbackResult::Invalid("Pool amounts cannot be negative".to_string()));
+    }
+
+    let governance = must_get_governance(&proposal.governance_hash)?;
+    if governance.pool_id != proposal.pool_id {
+        return Ok(ValidateCallbackResult::Invalid("Governance is for a different pool".to_string()));
+    }
+
+    let payload = proposal.approval_payload();
+    for (i, approval) in proposal.approvals.iter().enumerate() {
+        if !governance.approvers.contains(&approval.approver) {
+            return Ok(ValidateCallbackResult::Invalid("Approval from a non-approver".to_string()));
+        }
+        if proposal.approvals[..i].iter().any(|a| a.approver == approval.approver) {
+            return Ok(ValidateCallbackResult::Invalid("Duplicate approval".to_string()));
+        }
+        if !verify_signature(approval.approver.clone(), approval.signature.clone(), &payload)? {
+            return Ok(ValidateCallbackResult::Invalid("Invalid approval signature".to_string()));
+        }
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate an update to a pool: governed pools may only change through an
+/// approved proposal, and governance cannot be dropped
+pub fn validate_dividend_pool_update(
+    pool: &DividendPool,
+    previous: &DividendPool,
+) -> ExternResult<ValidateCallbackResult> {
+    if pool.pool_id != previous.pool_id {
+        return Ok(ValidateCallbackResult::Invalid("Pool ID cannot change".to_string()));
+    }
+
+    let Some(governance_hash) = &previous.governance_hash else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    if pool.governance_hash.as_ref() != Some(governance_hash) {
+        return Ok(ValidateCallbackResult::Invalid("Pool governance cannot be changed".to_string()));
+    }
+
+    let Some(proposal_hash) = &pool.applied_proposal else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Governed pool updates must reference an approved proposal".to_string(),
+        ));
+    };
+    if previous.applied_proposal.as_ref() == Some(proposal_hash) {
+        return Ok(ValidateCallbackResult::Invalid("Proposal has already been applied".to_string()));
+    }
+
+    let proposal: PoolChangeProposal = must_get_valid_record(proposal_hash.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Referenced proposal is not a pool change proposal".to_string())))?;
+    if proposal.pool_id != pool.pool_id || &proposal.governance_hash != governance_hash {
+        return Ok(ValidateCallbackResult::Invalid("Proposal is for a different pool".to_string()));
+    }
+
+    let governance = must_get_governance(governance_hash)?;
+    if (proposal.approvals.len() as u32) < governance.threshold {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Proposal has {} of {} required approvals",
+            proposal.approvals.len(),
+            governance.threshold
+        )));
+    }
+
+    if pool.balance != proposal.change.new_balance {
+        return Ok(ValidateCallbackResult::Invalid("Pool balance does not match the approved proposal".to_string()));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn must_get_governance(governance_hash: &ActionHash) -> ExternResult<PoolGovernance> {
+    must_get_valid_record(governance_hash.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Referenced governance is not a pool governance".to_string())))
+}
+
+/// Validate a dataset release
+pub fn validate_dataset_release(release: &DatasetRelease) -> ExternResult<ValidateCallbackResult> {
+    if release.release_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Release ID required".to_string()));
+    }
+
+    if release.contribution_hashes.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("At least one contribution required".to_string()));
+    }
+
+    if release.recipients.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("At least one recipient required".to_string()));
+    }
+
+    if release.retention_deadline <= release.released_at {
+        return Ok(ValidateCallbackResult::Invalid("Retention deadline must be after release".to_string()));
+    }
+
+    let max_retention = release.purpose.max_retention_days() * MICROS_PER_DAY;
+    if release.retention_deadline - release.released_at > max_retention {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Retention for {:?} releases is limited to {} days",
+            release.purpose,
+            release.purpose.max_retention_days()
+        )));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate a destruction attestation
+pub fn validate_destruction_attestation(attestation: &DestructionAttestation) -> ExternResult<ValidateCallbackResult> {
+    if attestation.method.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Destruction method required".to_string()));
+    }
+
+    if attestation.statement.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Attestation statement required".to_string()));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -17,6 +17,8 @@ use mycelix_health_shared::{
     },
     batch::{links_to_records_paginated, links_to_recent_records},
     PaginationInput,
+    publish_event,
+    DomainEventPayload,
 };
 
 /// Input for creating a screening
@@ -617,10 +619,17 @@ pub fn report_crisis_event(input: ReportCrisisEventInput) -> ExternResult<Record
     // Emit signal for high-risk events
     if matches!(input.crisis_level, CrisisLevel::HighRisk | CrisisLevel::Imminent) {
         emit_signal(CrisisSignal {
-            patient_hash,
+            patient_hash: patient_hash.clone(),
             screening_hash: action_hash.clone(),
             message: format!("Crisis event reported: {:?}", input.crisis_level),
         })?;
+        publish_event(
+            patient_hash,
+            DomainEventPayload::CrisisAlert {
+                alert_hash: action_hash.clone(),
+                level: format!("{:?}", input.crisis_level),
+            },
+        );
     }
 
     get(action_hash, GetOptions::default())?
@@ -5,6 +5,7 @@
 use hdk::prelude::*;
 use twin_integrity::*;
 use mycelix_health_shared::{require_authorization, log_data_access, DataCategory, Permission};
+use mycelix_health_shared::local_date_time;
 
 fn get_twin_or_err(twin_hash: &ActionHash) -> ExternResult<HealthTwin> {
     let record = get(twin_hash.clone(), GetOptions::default())?
@@ -19,6 +20,25 @@ fn get_twin_or_err(twin_hash: &ActionHash) -> ExternResult<HealthTwin> {
     Ok(twin)
 }
 
+/// The patient's latest twin configuration, if any
+fn twin_configuration(patient_hash: &ActionHash) -> ExternResult<Option<TwinConfiguration>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::TwinToConfig)?,
+        GetStrategy::default(),
+    )?;
+    match links.last().and_then(|link| link.target.clone().into_action_hash()) {
+        Some(hash) => Ok(get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<TwinConfiguration>().ok().flatten())),
+        None => Ok(None),
+    }
+}
+
+/// Confidence below which the patient's twin outputs are flagged for review
+fn low_confidence_threshold(patient_hash: &ActionHash) -> ExternResult<f32> {
+    let config = twin_configuration(patient_hash)?;
+    Ok(config.map_or(DEFAULT_LOW_CONFIDENCE_THRESHOLD, |c| c.low_confidence_threshold))
+}
+
 // ==================== LOCAL TYPES FOR CROSS-ZOME DATA ====================
 // These mirror types from hdc_genetics_integrity for deserialization
 // without importing the integrity crate (which causes duplicate symbol errors)
@@ -194,7 +214,35 @@ pub fn update_risk_factors(input: UpdateRiskFactorsInput) -> ExternResult<Record
     twin.risk_factors = input.risk_factors;
     twin.last_updated = sys_time()?.as_micros() as i64;
 
-    let updated_hash = update_entry(input.twin_hash, &twin)?;
+    let updated_hash = update_entry(input.twin_hash.clone(), &twin)?;
+
+    // Externally supplied factors are explained by their contributors
+    // and the data sources the twin held at the time
+    for risk in &twin.risk_factors {
+        let mut inputs = data_source_inputs(&twin.data_sources);
+        inputs.extend(risk.contributors.iter().map(|c| TraceInput {
+            name: c.clone(),
+            value: "reported contributor".to_string(),
+            source: None,
+            observed_at: None,
+        }));
+        record_explainability_trace(
+            &input.twin_hash,
+            &twin.model_version,
+            TraceSubject::RiskFactor,
+            &risk.name,
+            risk.risk_level,
+            TraceEvidence {
+                inputs,
+                rules: vec![TraceRule {
+                    rule: "Risk level supplied with risk factor update".to_string(),
+                    weight: risk.risk_level,
+                    direction: InfluenceDirection::IncreasesRisk,
+                }],
+                thresholds: Vec::new(),
+            },
+        )?;
+    }
 
     log_data_access(
         patient_hash,
@@ -256,8 +304,10 @@ pub struct SetTwinStatusInput {
 
 /// Ingest a data point into the twin
 #[hdk_extern]
-pub fn ingest_data_point(data_point: TwinDataPoint) -> ExternResult<Record> {
+pub fn ingest_data_point(mut data_point: TwinDataPoint) -> ExternResult<Record> {
     let twin = get_twin_or_err(&data_point.twin_hash)?;
+    // Preliminary and cancelled results are kept but never update the model
+    data_point.triggered_update &= data_point.is_reportable();
     let patient_hash = twin.patient_hash.clone();
     let auth = require_authorization(
         patient_hash.clone(),
@@ -281,9 +331,11 @@ pub fn ingest_data_point(data_point: TwinDataPoint) -> ExternResult<Record> {
     // Update twin's last_updated and potentially recalculate
     if data_point.triggered_update {
         // Trigger model update
-        let _ = trigger_model_update(data_point.twin_hash, vec![record.action_address().clone()]);
+        let _ = trigger_model_update(data_point.twin_hash.clone(), vec![record.action_address().clone()]);
     }
 
+    refresh_goals_for(&data_point, &twin);
+
     log_data_access(
         patient_hash,
         vec![DataCategory::All],
@@ -427,11 +479,19 @@ pub fn run_simulation(input: RunSimulationInput) -> ExternResult<Record> {
     )?;
 
     // Run simulation (MVP: simplified model)
-    let results = run_simulation_model(&twin, &simulation);
+    let mut results = run_simulation_model(&twin, &simulation);
+    results.low_confidence = LowConfidenceFlag::check(
+        results.confidence,
+        low_confidence_threshold(&patient_hash)?,
+        &format!("Simulation \"{}\"", simulation.name),
+    );
 
     simulation.results = Some(results);
     simulation.status = SimulationStatus::Completed;
     simulation.completed_at = Some(sys_time()?.as_micros() as i64);
+    if let ValidateCallbackResult::Invalid(reason) = validate_simulation(&simulation)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
 
     let updated_hash = update_entry(input.simulation_hash, &simulation)?;
 
@@ -460,8 +520,10 @@ fn run_simulation_model(twin: &HealthTwin, simulation: &Simulation) -> Simulatio
     // Simple simulation based on current state and interventions
     let base_health = twin.physiological_state.overall_health_score as f32;
 
-    // Calculate intervention impact (simplified)
+    // Calculate intervention impact (simplified), and how much of it rests on
+    // the compliance assumptions
     let mut intervention_impact = 0.0;
+    let mut compliance_spread = 0.0;
     for intervention in &simulation.interventions {
         let base_impact = match intervention.intervention_type {
             InterventionType::Medication => 5.0,
@@ -473,18 +535,27 @@ fn run_simulation_model(twin: &HealthTwin, simulation: &Simulation) -> Simulatio
             InterventionType::Monitoring => 1.0,
         };
         intervention_impact += base_impact * intervention.compliance_rate;
+        compliance_spread += base_impact * (1.0 - intervention.compliance_rate);
     }
 
     // Project health score
     let projected_health = (base_health + intervention_impact).min(100.0);
 
+    let health_uncertainty =
+        projected_uncertainty(twin, simulation, projected_health, 5.0, compliance_spread, (0.0, 100.0));
     outcomes.push(ProjectedOutcome {
         metric: "overall_health_score".to_string(),
         current_value: base_health,
         projected_value: projected_health,
         change_percent: ((projected_health - base_health) / base_health) * 100.0,
-        confidence_interval: (projected_health - 5.0, projected_health + 5.0),
-        trajectory: generate_trajectory(base_health, projected_health, simulation.time_horizon_months),
+        confidence_interval: (health_uncertainty.lower, health_uncertainty.upper),
+        trajectory: generate_trajectory(
+            base_health,
+            projected_health,
+            simulation.time_horizon_months,
+            &health_uncertainty,
+        ),
+        uncertainty: Some(health_uncertainty),
     });
 
     // Project cardiovascular risk if available
@@ -492,13 +563,16 @@ fn run_simulation_model(twin: &HealthTwin, simulation: &Simulation) -> Simulatio
         let risk_reduction = intervention_impact * 0.02; // 2% per unit of intervention
         let new_risk = (cv_risk - risk_reduction).max(0.0);
 
+        let risk_uncertainty =
+            projected_uncertainty(twin, simulation, new_risk, 2.0, compliance_spread * 0.02, (0.0, 100.0));
         outcomes.push(ProjectedOutcome {
             metric: "cardiovascular_risk".to_string(),
             current_value: cv_risk,
             projected_value: new_risk,
             change_percent: ((new_risk - cv_risk) / cv_risk) * 100.0,
-            confidence_interval: (new_risk - 2.0, new_risk + 2.0),
-            trajectory: generate_trajectory(cv_risk, new_risk, simulation.time_horizon_months),
+            confidence_interval: (risk_uncertainty.lower, risk_uncertainty.upper),
+            trajectory: generate_trajectory(cv_risk, new_risk, simulation.time_horizon_months, &risk_uncertainty),
+            uncertainty: Some(risk_uncertainty),
         });
     }
 
@@ -517,19 +591,61 @@ fn run_simulation_model(twin: &HealthTwin, simulation: &Simulation) -> Simulatio
             "Individual responses may vary".to_string(),
         ],
         computed_at: 0, // Will be set externally
+        low_confidence: None, // Set against the twin's threshold by run_simulation
+    }
+}
+
+/// Uncertainty of a projected value, propagated from the twin's confidence,
+/// the spread of the compliance assumptions and the time horizon
+///
+/// `base_half_width` is the interval half-width for a fully confident twin
+/// over one year; `range` bounds the metric.
+fn projected_uncertainty(
+    twin: &HealthTwin,
+    simulation: &Simulation,
+    projected: f32,
+    base_half_width: f32,
+    compliance_spread: f32,
+    range: (f32, f32),
+) -> Uncertainty {
+    let model_half_width = base_half_width / twin.confidence.max(0.1);
+    let horizon_years = simulation.time_horizon_months as f32 / 12.0;
+    // Independent errors add in quadrature; model error grows with the horizon
+    let half_width = (model_half_width.powi(2) * horizon_years.max(1.0) + compliance_spread.powi(2)).sqrt();
+    let sample_size = twin.data_sources.iter().map(|source| source.data_point_count).sum();
+    Uncertainty {
+        lower: (projected - half_width).max(range.0),
+        upper: (projected + half_width).min(range.1),
+        level: 0.9,
+        method: UncertaintyMethod::Propagated,
+        sample_size,
+        sample_basis: format!(
+            "{} data points from {} sources, {} interventions over {} months",
+            sample_size,
+            twin.data_sources.len(),
+            simulation.interventions.len(),
+            simulation.time_horizon_months
+        ),
     }
 }
 
-/// Generate trajectory points
-fn generate_trajectory(start: f32, end: f32, months: u32) -> Vec<TrajectoryPoint> {
+/// Generate trajectory points, widening the interval towards its final bounds
+fn generate_trajectory(start: f32, end: f32, months: u32, uncertainty: &Uncertainty) -> Vec<TrajectoryPoint> {
     let mut trajectory = Vec::new();
     let step = (end - start) / months as f32;
 
     for m in 0..=months {
+        let value = start + (step * m as f32);
+        // Uncertainty grows with the square root of elapsed time
+        let spread = (m as f32 / months as f32).sqrt();
         trajectory.push(TrajectoryPoint {
             month: m,
-            value: start + (step * m as f32),
+            value,
             confidence: 1.0 - (m as f32 * 0.02), // Confidence decreases over time
+            interval: Some((
+                value - (end - uncertainty.lower) * spread,
+                value + (uncertainty.upper - end) * spread,
+            )),
         });
     }
 
@@ -576,13 +692,267 @@ pub fn get_twin_simulations(twin_hash: ActionHash) -> ExternResult<Vec<Record>>
     Ok(simulations)
 }
 
+/// What a simulation comparison ranks scenarios by
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Default)]
+pub enum ComparisonObjective {
+    /// Largest projected gain in overall health score
+    #[default]
+    MaxHealthScoreGain,
+    /// Largest risk reduction against the no-intervention baseline
+    MinRisk,
+    /// Lowest side effect risk
+    MinSideEffectExposure,
+}
+
+impl ComparisonObjective {
+    fn label(&self) -> &'static str {
+        match self {
+            ComparisonObjective::MaxHealthScoreGain => "health score gain",
+            ComparisonObjective::MinRisk => "risk reduction",
+            ComparisonObjective::MinSideEffectExposure => "side effect risk",
+        }
+    }
+
+    /// A scenario's value for this objective, in the objective's own units
+    fn value(&self, results: &SimulationResults) -> f32 {
+        match self {
+            ComparisonObjective::MaxHealthScoreGain => results
+                .outcomes
+                .iter()
+                .find(|o| o.metric == "overall_health_score")
+                .map(|o| o.projected_value - o.current_value)
+                .unwrap_or(0.0),
+            ComparisonObjective::MinRisk => results.baseline_comparison.risk_reduction_percent,
+            ComparisonObjective::MinSideEffectExposure => results.baseline_comparison.side_effect_risk,
+        }
+    }
+
+    fn higher_is_better(&self) -> bool {
+        !matches!(self, ComparisonObjective::MinSideEffectExposure)
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CompareSimulationsInput {
+    /// Completed simulations of one twin, at least two
+    pub simulation_hashes: Vec<ActionHash>,
+    #[serde(default)]
+    pub objective: ComparisonObjective,
+}
+
+/// One scenario's projection of one metric
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OutcomeCell {
+    pub current_value: f32,
+    pub projected_value: f32,
+    pub change_percent: f32,
+    pub confidence_interval: (f32, f32),
+}
+
+/// A scenario's column in the comparison matrix
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScenarioColumn {
+    pub simulation_hash: ActionHash,
+    pub name: String,
+    pub scenario_type: ScenarioType,
+    /// 1 for the best scenario under the objective
+    pub rank: u32,
+    /// The scenario's value for the objective
+    pub objective_value: f32,
+    /// One cell per row of `SimulationComparison::metrics`; None when the
+    /// scenario did not project that metric
+    pub outcomes: Vec<Option<OutcomeCell>>,
+    pub risk_reduction_percent: f32,
+    pub side_effect_risk: f32,
+    pub confidence: f32,
+    pub low_confidence: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ScenarioRecommendation {
+    pub simulation_hash: ActionHash,
+    pub name: String,
+    pub rationale: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SimulationComparison {
+    pub twin_hash: ActionHash,
+    pub objective: ComparisonObjective,
+    /// Rows of the matrix: every metric any scenario projected
+    pub metrics: Vec<String>,
+    /// Columns of the matrix, best first
+    pub scenarios: Vec<ScenarioColumn>,
+    pub recommended: ScenarioRecommendation,
+}
+
+/// Compare completed simulations of one twin side by side and rank them
+///
+/// Outcomes are aligned by metric into a matrix. The recommendation is the
+/// best-ranked scenario whose results are not flagged as low confidence,
+/// falling back to the best overall when every scenario is flagged.
+#[hdk_extern]
+pub fn compare_simulations(input: CompareSimulationsInput) -> ExternResult<SimulationComparison> {
+    if input.simulation_hashes.len() < 2 {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "At least two simulations are needed for a comparison".to_string()
+        )));
+    }
+
+    let mut simulations = Vec::new();
+    for hash in &input.simulation_hashes {
+        let simulation = latest_simulation(hash)?;
+        if simulation.results.is_none() || simulation.status != SimulationStatus::Completed {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Simulation \"{}\" has not completed",
+                simulation.name
+            ))));
+        }
+        simulations.push((hash.clone(), simulation));
+    }
+    let twin_hash = simulations[0].1.twin_hash.clone();
+    if simulations.iter().any(|(_, s)| s.twin_hash != twin_hash) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Simulations must belong to the same twin".to_string()
+        )));
+    }
+
+    let twin = get_twin_or_err(&twin_hash)?;
+    let patient_hash = twin.patient_hash.clone();
+    let auth = require_authorization(
+        patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let mut metrics: Vec<String> = Vec::new();
+    for (_, simulation) in &simulations {
+        for outcome in simulation.results.iter().flat_map(|r| &r.outcomes) {
+            if !metrics.contains(&outcome.metric) {
+                metrics.push(outcome.metric.clone());
+            }
+        }
+    }
+
+    let objective = input.objective;
+    let mut scenarios: Vec<ScenarioColumn> = simulations
+        .into_iter()
+        .filter_map(|(hash, simulation)| {
+            let results = simulation.results?;
+            let outcomes = metrics
+                .iter()
+                .map(|metric| {
+                    results.outcomes.iter().find(|o| &o.metric == metric).map(|o| OutcomeCell {
+                        current_value: o.current_value,
+                        projected_value: o.projected_value,
+                        change_percent: o.change_percent,
+                        confidence_interval: o.confidence_interval,
+                    })
+                })
+                .collect();
+            Some(ScenarioColumn {
+                simulation_hash: hash,
+                name: simulation.name,
+                scenario_type: simulation.scenario_type,
+                rank: 0,
+                objective_value: objective.value(&results),
+                outcomes,
+                risk_reduction_percent: results.baseline_comparison.risk_reduction_percent,
+                side_effect_risk: results.baseline_comparison.side_effect_risk,
+                confidence: results.confidence,
+                low_confidence: results.low_confidence.is_some(),
+            })
+        })
+        .collect();
+
+    // Best objective value first; ties go to the more confident scenario
+    scenarios.sort_by(|a, b| {
+        let by_objective = if objective.higher_is_better() {
+            b.objective_value.total_cmp(&a.objective_value)
+        } else {
+            a.objective_value.total_cmp(&b.objective_value)
+        };
+        by_objective.then_with(|| b.confidence.total_cmp(&a.confidence))
+    });
+    for (index, scenario) in scenarios.iter_mut().enumerate() {
+        scenario.rank = index as u32 + 1;
+    }
+
+    let recommended = recommend_scenario(&scenarios, objective);
+
+    log_data_access(
+        patient_hash,
+        vec![DataCategory::All],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(SimulationComparison {
+        twin_hash,
+        objective,
+        metrics,
+        scenarios,
+        recommended,
+    })
+}
+
+/// Pick the best-ranked confident scenario and explain the choice
+fn recommend_scenario(ranked: &[ScenarioColumn], objective: ComparisonObjective) -> ScenarioRecommendation {
+    let chosen = ranked.iter().find(|s| !s.low_confidence).unwrap_or(&ranked[0]);
+    let mut rationale = format!(
+        "\"{}\" has the best {} ({:.1}) among the scenarios",
+        chosen.name,
+        objective.label(),
+        chosen.objective_value
+    );
+    if chosen.rank > 1 {
+        rationale = format!(
+            "\"{}\" ranks {} by {} ({:.1}); the higher-ranked scenarios are flagged as low confidence",
+            chosen.name,
+            chosen.rank,
+            objective.label(),
+            chosen.objective_value
+        );
+    } else if let Some(runner_up) = ranked.get(1) {
+        let margin = (chosen.objective_value - runner_up.objective_value).abs();
+        rationale.push_str(&format!(", {:.1} ahead of \"{}\"", margin, runner_up.name));
+    }
+    rationale.push('.');
+    if chosen.low_confidence {
+        rationale.push_str(" Every scenario is low confidence, so review the projections with your provider before choosing.");
+    }
+    ScenarioRecommendation {
+        simulation_hash: chosen.simulation_hash.clone(),
+        name: chosen.name.clone(),
+        rationale,
+    }
+}
+
+/// A simulation as of its most recent run
+fn latest_simulation(simulation_hash: &ActionHash) -> ExternResult<Simulation> {
+    let Some(Details::Record(details)) = get_details(simulation_hash.clone(), GetOptions::default())? else {
+        return Err(wasm_error!(WasmErrorInner::Guest("Simulation not found".to_string())));
+    };
+    let record = match details.updates.iter().max_by_key(|update| update.action().timestamp()) {
+        Some(update) => get(update.as_hash().clone(), GetOptions::default())?
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Simulation not found".to_string())))?,
+        None => details.record,
+    };
+    record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid simulation".to_string())))
+}
+
 // ==================== PREDICTIONS ====================
 
 /// Generate a prediction
 #[hdk_extern]
-pub fn generate_prediction(prediction: Prediction) -> ExternResult<Record> {
-    validate_prediction(&prediction)?;
-
+pub fn generate_prediction(mut prediction: Prediction) -> ExternResult<Record> {
     let twin = get_twin_or_err(&prediction.twin_hash)?;
     let patient_hash = twin.patient_hash.clone();
     let auth = require_authorization(
@@ -592,6 +962,17 @@ pub fn generate_prediction(prediction: Prediction) -> ExternResult<Record> {
         false,
     )?;
 
+    // Predictions without their own confidence inherit the twin's
+    let confidence = *prediction.confidence.get_or_insert(twin.confidence);
+    prediction.low_confidence = LowConfidenceFlag::check(
+        confidence,
+        low_confidence_threshold(&patient_hash)?,
+        &format!("Prediction of {}", prediction.target),
+    );
+    if let ValidateCallbackResult::Invalid(reason) = validate_prediction(&prediction)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
     let pred_hash = create_entry(&EntryTypes::Prediction(prediction.clone()))?;
     let record = get(pred_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find prediction".to_string())))?;
@@ -599,11 +980,32 @@ pub fn generate_prediction(prediction: Prediction) -> ExternResult<Record> {
     // Link to twin
     create_link(
         prediction.twin_hash.clone(),
-        pred_hash,
+        pred_hash.clone(),
         LinkTypes::TwinToPredictions,
         (),
     )?;
 
+    let mut inputs = data_source_inputs(&twin.data_sources);
+    inputs.extend(prediction.key_features.iter().map(|f| TraceInput {
+        name: f.name.clone(),
+        value: f.value.to_string(),
+        source: None,
+        observed_at: None,
+    }));
+    let rules = prediction.key_features.iter().map(|f| TraceRule {
+        rule: format!("Feature importance ({})", prediction.model_id),
+        weight: f.importance,
+        direction: f.direction.clone(),
+    }).collect();
+    record_explainability_trace(
+        &prediction.twin_hash,
+        &twin.model_version,
+        TraceSubject::Prediction(pred_hash),
+        &prediction.target,
+        prediction.predicted_value,
+        TraceEvidence { inputs, rules, thresholds: Vec::new() },
+    )?;
+
     log_data_access(
         patient_hash,
         vec![DataCategory::All],
@@ -859,6 +1261,262 @@ pub fn get_twin_trajectories(twin_hash: ActionHash) -> ExternResult<Vec<Record>>
     Ok(trajectories)
 }
 
+// ==================== TRENDS ====================
+
+/// Most points a downsampled trend may ask for
+const MAX_TREND_POINTS: u32 = 2_000;
+
+/// How finely a trend series is returned
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum TrendResolution {
+    /// Every sample
+    Raw,
+    /// At most `max_points` samples chosen by largest-triangle-three-buckets,
+    /// which keeps the peaks and troughs a chart would show
+    Points { max_points: u32 },
+    /// Min, max and mean per week starting Monday (UTC)
+    Weekly,
+    /// Min, max and mean per calendar month (UTC)
+    Monthly,
+    /// Raw when the samples fit in `max_points`, otherwise the finest of
+    /// weekly or monthly buckets that fits, otherwise `Points`
+    Auto { max_points: u32 },
+}
+
+/// One point of a trend series
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TrendPoint {
+    /// Sample time, or the start of the bucket
+    pub at: i64,
+    /// Sample value, or the bucket mean
+    pub value: f32,
+    pub min: f32,
+    pub max: f32,
+    /// Samples the point stands for
+    pub count: u32,
+}
+
+/// A compact series for charting
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TrendSeries {
+    /// Resolution applied; `Auto` is resolved to what it chose
+    pub resolution: TrendResolution,
+    /// Numeric samples in range before downsampling
+    pub source_points: u32,
+    pub points: Vec<TrendPoint>,
+}
+
+/// Input for a metric's trend from the twin's data points
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMetricTrendInput {
+    pub twin_hash: ActionHash,
+    pub metric: TwinDataType,
+    pub since: Option<i64>,
+    pub until: Option<i64>,
+    pub resolution: TrendResolution,
+}
+
+/// Input for a stored trajectory's series at a coarser resolution
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetTrajectoryTrendInput {
+    pub trajectory_hash: ActionHash,
+    pub resolution: TrendResolution,
+}
+
+/// A metric's numeric samples over time, downsampled for charting
+///
+/// Samples whose value is not a plain number (blood pressure pairs, JSON
+/// lab payloads) are left out.
+#[hdk_extern]
+pub fn get_metric_trend(input: GetMetricTrendInput) -> ExternResult<TrendSeries> {
+    check_trend_resolution(input.resolution)?;
+    let twin = get_twin_or_err(&input.twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let mut samples: Vec<(i64, f32)> = twin_data_points(&input.twin_hash)?
+        .into_iter()
+        .filter(|p| p.data_type == input.metric)
+        .filter(|p| input.since.is_none_or(|since| p.measured_at >= since))
+        .filter(|p| input.until.is_none_or(|until| p.measured_at <= until))
+        .filter_map(|p| p.value.trim().parse::<f32>().ok().filter(|v| v.is_finite()).map(|v| (p.measured_at, v)))
+        .collect();
+    samples.sort_by_key(|(at, _)| *at);
+
+    if !samples.is_empty() {
+        log_data_access(
+            twin.patient_hash,
+            vec![DataCategory::All],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(downsample(&samples, input.resolution))
+}
+
+/// A stored trajectory's actual values, downsampled for charting
+#[hdk_extern]
+pub fn get_trajectory_trend(input: GetTrajectoryTrendInput) -> ExternResult<TrendSeries> {
+    check_trend_resolution(input.resolution)?;
+    let trajectory: HealthTrajectory = get(input.trajectory_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Trajectory not found".to_string())))?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid trajectory".to_string())))?;
+    let twin = get_twin_or_err(&trajectory.twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let mut samples: Vec<(i64, f32)> = trajectory
+        .data_points
+        .iter()
+        .filter(|p| p.actual.is_finite())
+        .map(|p| (p.timestamp, p.actual))
+        .collect();
+    samples.sort_by_key(|(at, _)| *at);
+
+    log_data_access(
+        twin.patient_hash,
+        vec![DataCategory::All],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(downsample(&samples, input.resolution))
+}
+
+fn check_trend_resolution(resolution: TrendResolution) -> ExternResult<()> {
+    match resolution {
+        TrendResolution::Points { max_points } | TrendResolution::Auto { max_points }
+            if !(3..=MAX_TREND_POINTS).contains(&max_points) =>
+        {
+            Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "max_points must be between 3 and {}",
+                MAX_TREND_POINTS
+            ))))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Reduce time-ordered samples to the requested resolution
+fn downsample(samples: &[(i64, f32)], resolution: TrendResolution) -> TrendSeries {
+    let resolution = match resolution {
+        TrendResolution::Auto { max_points } => {
+            let fits = |count: usize| count <= max_points as usize;
+            if fits(samples.len()) {
+                TrendResolution::Raw
+            } else if fits(bucket_samples(samples, week_start).len()) {
+                TrendResolution::Weekly
+            } else if fits(bucket_samples(samples, month_start).len()) {
+                TrendResolution::Monthly
+            } else {
+                TrendResolution::Points { max_points }
+            }
+        }
+        other => other,
+    };
+    let points = match resolution {
+        TrendResolution::Weekly => bucket_samples(samples, week_start),
+        TrendResolution::Monthly => bucket_samples(samples, month_start),
+        TrendResolution::Points { max_points } => lttb(samples, max_points as usize).into_iter().map(sample_point).collect(),
+        _ => samples.iter().copied().map(sample_point).collect(),
+    };
+    TrendSeries {
+        resolution,
+        source_points: samples.len() as u32,
+        points,
+    }
+}
+
+fn sample_point((at, value): (i64, f32)) -> TrendPoint {
+    TrendPoint { at, value, min: value, max: value, count: 1 }
+}
+
+/// Monday 00:00 UTC of the week holding `at` (the epoch fell on a Thursday)
+fn week_start(at: i64) -> i64 {
+    const MONDAY_OFFSET: i64 = 3 * MICROS_PER_DAY;
+    const MICROS_PER_WEEK: i64 = 7 * MICROS_PER_DAY;
+    (at + MONDAY_OFFSET).div_euclid(MICROS_PER_WEEK) * MICROS_PER_WEEK - MONDAY_OFFSET
+}
+
+/// First of the month holding `at`, 00:00 UTC
+fn month_start(at: i64) -> i64 {
+    let day = local_date_time(Timestamp::from_micros(at), 0).day as i64;
+    (at.div_euclid(MICROS_PER_DAY) - (day - 1)) * MICROS_PER_DAY
+}
+
+/// Min, max and mean of time-ordered samples per bucket
+fn bucket_samples(samples: &[(i64, f32)], bucket_of: fn(i64) -> i64) -> Vec<TrendPoint> {
+    let mut buckets: Vec<(TrendPoint, f64)> = Vec::new();
+    for &(at, value) in samples {
+        let start = bucket_of(at);
+        match buckets.last_mut() {
+            Some((point, total)) if point.at == start => {
+                point.min = point.min.min(value);
+                point.max = point.max.max(value);
+                point.count += 1;
+                *total += value as f64;
+            }
+            _ => buckets.push((TrendPoint { at: start, value, min: value, max: value, count: 1 }, value as f64)),
+        }
+    }
+    buckets
+        .into_iter()
+        .map(|(point, total)| TrendPoint { value: (total / point.count as f64) as f32, ..point })
+        .collect()
+}
+
+/// Largest-triangle-three-buckets: keep the first and last samples and,
+/// from each bucket between, the one forming the largest triangle with the
+/// previous pick and the next bucket's mean
+fn lttb(samples: &[(i64, f32)], threshold: usize) -> Vec<(i64, f32)> {
+    if threshold >= samples.len() || threshold < 3 {
+        return samples.to_vec();
+    }
+    let every = (samples.len() - 2) as f64 / (threshold - 2) as f64;
+    let mut picked = Vec::with_capacity(threshold);
+    picked.push(samples[0]);
+    let mut previous = samples[0];
+    for i in 0..threshold - 2 {
+        let next_start = ((i + 1) as f64 * every) as usize + 1;
+        let next_end = (((i + 2) as f64 * every) as usize + 1).min(samples.len());
+        let next = &samples[next_start..next_end.max(next_start + 1).min(samples.len())];
+        let avg_x = next.iter().map(|(at, _)| *at as f64).sum::<f64>() / next.len() as f64;
+        let avg_y = next.iter().map(|(_, v)| *v as f64).sum::<f64>() / next.len() as f64;
+
+        let start = (i as f64 * every) as usize + 1;
+        let end = next_start;
+        let (ax, ay) = (previous.0 as f64, previous.1 as f64);
+        let best = samples[start..end]
+            .iter()
+            .copied()
+            .max_by(|a, b| {
+                let area = |(x, y): (i64, f32)| ((ax - avg_x) * (y as f64 - ay) - (ax - x as f64) * (avg_y - ay)).abs();
+                area(*a).total_cmp(&area(*b))
+            })
+            .unwrap_or(samples[start]);
+        picked.push(best);
+        previous = best;
+    }
+    picked.push(samples[samples.len() - 1]);
+    picked
+}
+
 // ==================== MODEL UPDATES ====================
 
 /// Trigger a model update
@@ -1016,31 +1674,825 @@ pub struct TwinSummary {
     pub status: TwinStatus,
 }
 
-// ==================== ANCHOR SUPPORT ====================
+// ==================== EXPLAINABILITY ====================
 
-/// Anchor entry for indexing
-#[hdk_entry_helper]
-#[derive(Clone, PartialEq)]
-pub struct Anchor(pub String);
+/// Explain how the twin arrived at a risk factor or prediction target
+///
+/// Returns the most recent trace whose risk name matches (case-insensitive).
+#[hdk_extern]
+pub fn explain_risk_factor(input: ExplainRiskFactorInput) -> ExternResult<Option<ExplainabilityTrace>> {
+    let twin = get_twin_or_err(&input.twin_hash)?;
+    let patient_hash = twin.patient_hash.clone();
+    let auth = require_authorization(
+        patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
 
-fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
-    let anchor = Anchor(anchor_text.to_string());
-    hash_entry(&anchor)
-}
+    let links = get_links(
+        LinkQuery::try_new(input.twin_hash, LinkTypes::TwinToExplanations)?,
+        GetStrategy::default(),
+    )?;
 
-// ==================== GENETIC RISK INTEGRATION ====================
+    let mut latest: Option<ExplainabilityTrace> = None;
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(trace) = record.entry().to_app_option::<ExplainabilityTrace>().ok().flatten() {
+                    if trace.risk_name.eq_ignore_ascii_case(&input.risk_name)
+                        && latest.as_ref().is_none_or(|l| trace.generated_at > l.generated_at)
+                    {
+                        latest = Some(trace);
+                    }
+                }
+            }
+        }
+    }
+
+    if latest.is_some() {
+        log_data_access(
+            patient_hash,
+            vec![DataCategory::All],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(latest)
+}
 
-/// Input for updating genetic-derived risk factors
 #[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateGeneticRiskInput {
+pub struct ExplainRiskFactorInput {
     pub twin_hash: ActionHash,
-    /// Optional: specific genetic vector to analyze (if None, uses all patient vectors)
-    pub genetic_vector_hash: Option<ActionHash>,
+    pub risk_name: String,
 }
 
-/// Result of genetic risk analysis
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct GeneticRiskAnalysis {
+/// Evidence behind a single twin output
+struct TraceEvidence {
+    inputs: Vec<TraceInput>,
+    rules: Vec<TraceRule>,
+    thresholds: Vec<ThresholdCrossing>,
+}
+
+/// Persist an explainability trace and link it to the twin
+fn record_explainability_trace(
+    twin_hash: &ActionHash,
+    model_version: &str,
+    subject: TraceSubject,
+    risk_name: &str,
+    output_value: f32,
+    evidence: TraceEvidence,
+) -> ExternResult<ActionHash> {
+    let now = sys_time()?.as_micros();
+    let trace = ExplainabilityTrace {
+        trace_id: format!("TRACE-{}-{}", now, risk_name),
+        twin_hash: twin_hash.clone(),
+        subject,
+        risk_name: risk_name.to_string(),
+        output_value,
+        data_recency: data_recency(&evidence.inputs, now),
+        inputs_used: evidence.inputs,
+        rules_applied: evidence.rules,
+        thresholds_crossed: evidence.thresholds,
+        model_version: model_version.to_string(),
+        generated_at: now,
+    };
+
+    let trace_hash = create_entry(&EntryTypes::ExplainabilityTrace(trace))?;
+    create_link(
+        twin_hash.clone(),
+        trace_hash.clone(),
+        LinkTypes::TwinToExplanations,
+        (),
+    )?;
+
+    Ok(trace_hash)
+}
+
+/// Describe the twin's data sources as trace inputs
+fn data_source_inputs(sources: &[DataSourceInfo]) -> Vec<TraceInput> {
+    sources
+        .iter()
+        .map(|d| TraceInput {
+            name: format!("{:?} data", d.source_type),
+            value: format!("{} data points, quality {:.2}", d.data_point_count, d.quality_score),
+            source: Some(d.source_type.clone()),
+            observed_at: Some(d.last_data_at),
+        })
+        .collect()
+}
+
+/// Summarize how fresh the timestamped inputs are
+fn data_recency(inputs: &[TraceInput], now: i64) -> DataRecency {
+    let newest = inputs.iter().filter_map(|i| i.observed_at).max();
+    let oldest = inputs.iter().filter_map(|i| i.observed_at).min();
+    DataRecency {
+        newest_data_at: newest,
+        oldest_data_at: oldest,
+        max_age_days: oldest.map(|o| ((now - o).max(0) / MICROS_PER_DAY) as u32),
+    }
+}
+
+const MICROS_PER_DAY: i64 = 24 * 60 * 60 * 1_000_000;
+
+// ==================== GOALS ====================
+
+/// Days of logging the adherence summary looks back over
+const ADHERENCE_WINDOW_DAYS: i64 = 14;
+
+/// Progress counts as on track while it trails the time elapsed by less than this
+const ON_TRACK_SLACK: f32 = 0.1;
+
+/// Set a health goal measured against the twin's data
+#[hdk_extern]
+pub fn create_health_goal(goal: HealthGoal) -> ExternResult<Record> {
+    let twin = get_twin_or_err(&goal.twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Write,
+        false,
+    )?;
+
+    if let ValidateCallbackResult::Invalid(reason) = validate_health_goal(&goal)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let goal_hash = create_entry(&EntryTypes::HealthGoal(goal.clone()))?;
+    let record = get(goal_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find goal".to_string())))?;
+
+    create_link(
+        goal.twin_hash,
+        goal_hash,
+        LinkTypes::TwinToGoals,
+        (),
+    )?;
+
+    log_data_access(
+        twin.patient_hash,
+        vec![DataCategory::All],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(record)
+}
+
+/// Get all goals set on a twin
+#[hdk_extern]
+pub fn get_twin_goals(twin_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let twin = get_twin_or_err(&twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let goals = twin_goals(&twin_hash)?
+        .into_iter()
+        .map(|(record, _)| record)
+        .collect::<Vec<_>>();
+
+    if !goals.is_empty() {
+        log_data_access(
+            twin.patient_hash,
+            vec![DataCategory::All],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(goals)
+}
+
+/// Recompute a goal's progress from the twin's data points
+///
+/// Milestones reached since the previous snapshot notify the patient.
+#[hdk_extern]
+pub fn update_goal_progress(goal_hash: ActionHash) -> ExternResult<Record> {
+    let goal = get_goal_or_err(&goal_hash)?;
+    let twin = get_twin_or_err(&goal.twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Write,
+        false,
+    )?;
+
+    let record = record_goal_progress(&goal_hash, &goal, &twin)?;
+
+    log_data_access(
+        twin.patient_hash,
+        vec![DataCategory::All],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(record)
+}
+
+/// Most recent progress snapshot for a goal
+#[hdk_extern]
+pub fn get_goal_progress(goal_hash: ActionHash) -> ExternResult<Option<GoalProgress>> {
+    let goal = get_goal_or_err(&goal_hash)?;
+    let twin = get_twin_or_err(&goal.twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let progress = latest_goal_progress(&goal_hash)?;
+
+    if progress.is_some() {
+        log_data_access(
+            twin.patient_hash,
+            vec![DataCategory::All],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(progress)
+}
+
+/// How a patient is doing against their goals, for their care team
+///
+/// Gated by the patient's consent like any other twin read; the summary
+/// carries progress and logging regularity, not the underlying data points.
+#[hdk_extern]
+pub fn get_goal_adherence_summary(twin_hash: ActionHash) -> ExternResult<GoalAdherenceSummary> {
+    let twin = get_twin_or_err(&twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let now = sys_time()?.as_micros();
+    let points = twin_data_points(&twin_hash)?;
+    let window_start = now - ADHERENCE_WINDOW_DAYS * MICROS_PER_DAY;
+
+    let mut goals = Vec::new();
+    for (record, goal) in twin_goals(&twin_hash)? {
+        let goal_hash = record.action_address().clone();
+        let progress = latest_goal_progress(&goal_hash)?;
+        let mut logged_days: Vec<i64> = points
+            .iter()
+            .filter(|p| p.data_type == goal.metric && p.measured_at >= window_start.max(goal.start_at))
+            .map(|p| p.measured_at.div_euclid(MICROS_PER_DAY))
+            .collect();
+        logged_days.sort_unstable();
+        logged_days.dedup();
+        let expected_days = ((now - window_start.max(goal.start_at)) / MICROS_PER_DAY).clamp(1, ADHERENCE_WINDOW_DAYS);
+
+        goals.push(GoalAdherence {
+            goal_hash,
+            description: goal.description,
+            status: progress.as_ref().map_or(GoalStatus::NotStarted, |p| p.status),
+            fraction_complete: progress.as_ref().map_or(0.0, |p| p.fraction_complete),
+            on_track: progress.as_ref().is_some_and(|p| p.on_track),
+            days_remaining: ((goal.deadline - now).max(0) / MICROS_PER_DAY) as u32,
+            logging_rate: (logged_days.len() as f32 / expected_days as f32).min(1.0),
+            last_computed_at: progress.map(|p| p.computed_at),
+        });
+    }
+
+    let active: Vec<&GoalAdherence> = goals
+        .iter()
+        .filter(|g| matches!(g.status, GoalStatus::NotStarted | GoalStatus::InProgress))
+        .collect();
+    let summary = GoalAdherenceSummary {
+        twin_hash,
+        active_goals: active.len() as u32,
+        on_track_goals: active.iter().filter(|g| g.on_track).count() as u32,
+        achieved_goals: goals.iter().filter(|g| g.status == GoalStatus::Achieved).count() as u32,
+        missed_goals: goals.iter().filter(|g| g.status == GoalStatus::Missed).count() as u32,
+        average_logging_rate: if active.is_empty() {
+            None
+        } else {
+            Some(active.iter().map(|g| g.logging_rate).sum::<f32>() / active.len() as f32)
+        },
+        goals,
+        generated_at: now,
+    };
+
+    log_data_access(
+        twin.patient_hash,
+        vec![DataCategory::All],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(summary)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GoalAdherenceSummary {
+    pub twin_hash: ActionHash,
+    pub active_goals: u32,
+    pub on_track_goals: u32,
+    pub achieved_goals: u32,
+    pub missed_goals: u32,
+    /// Mean logging rate across active goals
+    pub average_logging_rate: Option<f32>,
+    pub goals: Vec<GoalAdherence>,
+    pub generated_at: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GoalAdherence {
+    pub goal_hash: ActionHash,
+    pub description: String,
+    pub status: GoalStatus,
+    pub fraction_complete: f32,
+    pub on_track: bool,
+    pub days_remaining: u32,
+    /// Share of recent days with at least one measurement for the goal
+    pub logging_rate: f32,
+    pub last_computed_at: Option<i64>,
+}
+
+/// Notification from the twin (mirrors consent_integrity::AccessNotification)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct TwinNotification {
+    notification_id: String,
+    patient_hash: ActionHash,
+    accessor: AgentPubKey,
+    accessor_name: String,
+    data_categories: Vec<DataCategory>,
+    purpose: String,
+    accessed_at: Timestamp,
+    emergency_access: bool,
+    priority: TwinNotificationPriority,
+    viewed: bool,
+    viewed_at: Option<Timestamp>,
+    summary: String,
+    access_log_hash: Option<ActionHash>,
+}
+
+/// Notification priority (mirrors consent_integrity::NotificationPriority)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum TwinNotificationPriority {
+    Daily,
+}
+
+fn get_goal_or_err(goal_hash: &ActionHash) -> ExternResult<HealthGoal> {
+    get(goal_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<HealthGoal>().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Goal not found".to_string())))
+}
+
+fn twin_goals(twin_hash: &ActionHash) -> ExternResult<Vec<(Record, HealthGoal)>> {
+    let links = get_links(
+        LinkQuery::try_new(twin_hash.clone(), LinkTypes::TwinToGoals)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut goals = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(goal) = record.entry().to_app_option::<HealthGoal>().ok().flatten() {
+                    goals.push((record, goal));
+                }
+            }
+        }
+    }
+    Ok(goals)
+}
+
+fn twin_data_points(twin_hash: &ActionHash) -> ExternResult<Vec<TwinDataPoint>> {
+    let links = get_links(
+        LinkQuery::try_new(twin_hash.clone(), LinkTypes::TwinToDataPoints)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut points = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(point) = record.entry().to_app_option::<TwinDataPoint>().ok().flatten() {
+                    points.push(point);
+                }
+            }
+        }
+    }
+    Ok(points)
+}
+
+fn latest_goal_progress(goal_hash: &ActionHash) -> ExternResult<Option<GoalProgress>> {
+    let links = get_links(
+        LinkQuery::try_new(goal_hash.clone(), LinkTypes::GoalToProgress)?,
+        GetStrategy::default(),
+    )?;
+    match links.into_iter().max_by_key(|link| link.timestamp).and_then(|link| link.target.into_action_hash()) {
+        Some(hash) => Ok(get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<GoalProgress>().ok().flatten())),
+        None => Ok(None),
+    }
+}
+
+/// Reduce the goal's data points to its current value
+fn goal_current_value(goal: &HealthGoal, points: &[TwinDataPoint], now: i64) -> (Option<f32>, u32, Option<i64>) {
+    let measured: Vec<(i64, f32)> = points
+        .iter()
+        .filter(|p| p.data_type == goal.metric && p.measured_at >= goal.start_at && p.measured_at <= now)
+        .filter_map(|p| p.value.trim().parse::<f32>().ok().filter(|v| v.is_finite()).map(|v| (p.measured_at, v)))
+        .collect();
+    let latest_at = measured.iter().map(|(at, _)| *at).max();
+
+    match goal.aggregation {
+        GoalAggregation::Latest => {
+            let latest = measured.iter().max_by_key(|(at, _)| *at).map(|(_, v)| *v);
+            (latest, measured.len() as u32, latest_at)
+        }
+        GoalAggregation::DailyAverage { days } => {
+            let since = now - days as i64 * MICROS_PER_DAY;
+            let mut totals: Vec<(i64, f32)> = Vec::new();
+            let mut used = 0;
+            for (at, value) in measured.iter().filter(|(at, _)| *at >= since) {
+                used += 1;
+                let day = at.div_euclid(MICROS_PER_DAY);
+                match totals.iter_mut().find(|(d, _)| *d == day) {
+                    Some((_, total)) => *total += value,
+                    None => totals.push((day, *value)),
+                }
+            }
+            let average = (!totals.is_empty())
+                .then(|| totals.iter().map(|(_, total)| total).sum::<f32>() / totals.len() as f32);
+            (average, used, latest_at)
+        }
+    }
+}
+
+/// Compute, store and link a progress snapshot, notifying new milestones
+fn record_goal_progress(goal_hash: &ActionHash, goal: &HealthGoal, twin: &HealthTwin) -> ExternResult<Record> {
+    let now = sys_time()?.as_micros();
+    let points = twin_data_points(&goal.twin_hash)?;
+    let (current_value, data_points_used, latest_data_at) = goal_current_value(goal, &points, now);
+    let previous = latest_goal_progress(goal_hash)?;
+
+    let fraction_complete = current_value.map_or(0.0, |v| goal.fraction_complete(v));
+    let met = current_value.is_some_and(|v| goal.is_met(v));
+    let status = if met || previous.as_ref().is_some_and(|p| p.status == GoalStatus::Achieved) {
+        GoalStatus::Achieved
+    } else if now > goal.deadline {
+        GoalStatus::Missed
+    } else if current_value.is_some() {
+        GoalStatus::InProgress
+    } else {
+        GoalStatus::NotStarted
+    };
+
+    let mut milestones_reached = previous.as_ref().map(|p| p.milestones_reached.clone()).unwrap_or_default();
+    let new_milestones: Vec<f32> = goal
+        .milestones
+        .iter()
+        .copied()
+        .filter(|m| fraction_complete >= *m && !milestones_reached.contains(m))
+        .collect();
+    milestones_reached.extend(new_milestones.iter().copied());
+
+    let progress = GoalProgress {
+        goal_hash: goal_hash.clone(),
+        current_value,
+        fraction_complete,
+        status,
+        on_track: status == GoalStatus::Achieved
+            || (status == GoalStatus::InProgress && fraction_complete + ON_TRACK_SLACK >= goal.fraction_elapsed(now)),
+        milestones_reached,
+        data_points_used,
+        latest_data_at,
+        computed_at: now,
+    };
+
+    let progress_hash = create_entry(&EntryTypes::GoalProgress(progress))?;
+    let record = get(progress_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find goal progress".to_string())))?;
+    create_link(
+        goal_hash.clone(),
+        progress_hash,
+        LinkTypes::GoalToProgress,
+        (),
+    )?;
+
+    let newly_achieved = status == GoalStatus::Achieved
+        && previous.as_ref().is_none_or(|p| p.status != GoalStatus::Achieved);
+    let summary = match new_milestones.iter().copied().reduce(f32::max) {
+        _ if newly_achieved => Some(format!("You reached your goal: {}", goal.description)),
+        Some(milestone) => Some(format!(
+            "You are {:.0}% of the way to your goal: {}",
+            milestone * 100.0,
+            goal.description
+        )),
+        None => None,
+    };
+    if let Some(summary) = summary {
+        let _ = notify_goal_milestone(twin, goal_hash, summary);
+    }
+
+    Ok(record)
+}
+
+fn notify_goal_milestone(twin: &HealthTwin, goal_hash: &ActionHash, summary: String) -> ExternResult<()> {
+    notify_patient(twin, format!("GOAL-{}", goal_hash), "Goal milestone", summary)
+}
+
+fn notify_patient(twin: &HealthTwin, id_prefix: String, purpose: &str, summary: String) -> ExternResult<()> {
+    let now = sys_time()?;
+    let notification = TwinNotification {
+        notification_id: format!("{}-{}", id_prefix, now.as_micros()),
+        patient_hash: twin.patient_hash.clone(),
+        accessor: agent_info()?.agent_initial_pubkey,
+        accessor_name: "Health twin".to_string(),
+        data_categories: vec![DataCategory::All],
+        purpose: purpose.to_string(),
+        accessed_at: now,
+        emergency_access: false,
+        priority: TwinNotificationPriority::Daily,
+        viewed: false,
+        viewed_at: None,
+        summary,
+        access_log_hash: None,
+    };
+
+    call(
+        CallTargetCell::Local,
+        ZomeName::from("consent"),
+        FunctionName::from("create_access_notification"),
+        None,
+        &notification,
+    )?;
+    Ok(())
+}
+
+/// Refresh the progress of goals a new data point counts towards
+/// This is best-effort - failures don't break ingestion
+fn refresh_goals_for(data_point: &TwinDataPoint, twin: &HealthTwin) {
+    let Ok(goals) = twin_goals(&data_point.twin_hash) else {
+        return;
+    };
+    for (record, goal) in goals {
+        if goal.metric == data_point.data_type && data_point.measured_at <= goal.deadline {
+            let _ = record_goal_progress(record.action_address(), &goal, twin);
+        }
+    }
+}
+
+// ==================== ENGAGEMENT ====================
+
+/// Freshness of the data one nudge rule watches
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DataStaleness {
+    pub target: NudgeTarget,
+    /// Newest matching data point; `None` if there never was one
+    pub last_data_at: Option<i64>,
+    /// Whole days since the newest data point
+    pub days_since: Option<u32>,
+    pub stale_after_days: u32,
+    pub stale: bool,
+}
+
+/// Freshness of each kind of data the twin's nudge rules watch
+#[hdk_extern]
+pub fn get_data_staleness(twin_hash: ActionHash) -> ExternResult<Vec<DataStaleness>> {
+    let twin = get_twin_or_err(&twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let (rules, _) = nudge_settings(&twin.patient_hash)?;
+    let points = twin_data_points(&twin_hash)?;
+    let staleness = data_staleness(&rules, &points, &twin, sys_time()?.as_micros());
+
+    log_data_access(
+        twin.patient_hash,
+        vec![DataCategory::All],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(staleness)
+}
+
+/// Nudge the patient about stale data
+///
+/// Returns `None` without nudging when nothing is stale, nudges are off, or
+/// the last nudge is more recent than the configured frequency allows.
+#[hdk_extern]
+pub fn generate_engagement_nudge(twin_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let twin = get_twin_or_err(&twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Write,
+        false,
+    )?;
+
+    let now = sys_time()?.as_micros();
+    let (rules, min_days_between_nudges) = nudge_settings(&twin.patient_hash)?;
+    if min_days_between_nudges == 0 {
+        return Ok(None);
+    }
+    let last_nudge = twin_nudges(&twin_hash)?.into_iter().map(|(_, nudge)| nudge.sent_at).max();
+    if last_nudge.is_some_and(|sent_at| now - sent_at < min_days_between_nudges as i64 * MICROS_PER_DAY) {
+        return Ok(None);
+    }
+
+    let points = twin_data_points(&twin_hash)?;
+    let (stale, prompts): (Vec<StaleData>, Vec<String>) = rules
+        .iter()
+        .zip(data_staleness(&rules, &points, &twin, now))
+        .filter(|(_, staleness)| staleness.stale)
+        .map(|(rule, staleness)| {
+            let prompt = rule.prompt.clone().unwrap_or_else(|| nudge_prompt(&staleness));
+            let stale = StaleData {
+                target: staleness.target,
+                last_data_at: staleness.last_data_at,
+                stale_after_days: staleness.stale_after_days,
+            };
+            (stale, prompt)
+        })
+        .unzip();
+    if stale.is_empty() {
+        return Ok(None);
+    }
+
+    let nudge = EngagementNudge {
+        twin_hash: twin_hash.clone(),
+        stale,
+        prompt: prompts.join("\n"),
+        sent_at: now,
+    };
+    if let ValidateCallbackResult::Invalid(reason) = validate_engagement_nudge(&nudge)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+
+    let nudge_hash = create_entry(&EntryTypes::EngagementNudge(nudge.clone()))?;
+    let record = get(nudge_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find nudge".to_string())))?;
+    create_link(
+        twin_hash,
+        nudge_hash.clone(),
+        LinkTypes::TwinToNudges,
+        (),
+    )?;
+
+    let _ = notify_patient(&twin, format!("NUDGE-{}", nudge_hash), "Data update reminder", nudge.prompt);
+
+    log_data_access(
+        twin.patient_hash,
+        vec![DataCategory::All],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(Some(record))
+}
+
+/// Get the nudges sent for a twin, newest first
+#[hdk_extern]
+pub fn get_twin_nudges(twin_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let twin = get_twin_or_err(&twin_hash)?;
+    let auth = require_authorization(
+        twin.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let mut nudges = twin_nudges(&twin_hash)?;
+    nudges.sort_by_key(|(_, nudge)| std::cmp::Reverse(nudge.sent_at));
+
+    if !nudges.is_empty() {
+        log_data_access(
+            twin.patient_hash,
+            vec![DataCategory::All],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(nudges.into_iter().map(|(record, _)| record).collect())
+}
+
+/// Nudge rules and frequency from the patient's configuration, else the defaults
+fn nudge_settings(patient_hash: &ActionHash) -> ExternResult<(Vec<NudgeRule>, u32)> {
+    Ok(match twin_configuration(patient_hash)? {
+        Some(config) => (config.nudge_rules, config.min_days_between_nudges),
+        None => (default_nudge_rules(), DEFAULT_MIN_DAYS_BETWEEN_NUDGES),
+    })
+}
+
+/// Data a twin never received counts as stale once the twin is older than
+/// the rule's threshold, so new twins aren't nudged straight away
+fn data_staleness(rules: &[NudgeRule], points: &[TwinDataPoint], twin: &HealthTwin, now: i64) -> Vec<DataStaleness> {
+    rules
+        .iter()
+        .map(|rule| {
+            let last_data_at = points
+                .iter()
+                .filter(|point| rule.target.matches(point) && point.measured_at <= now)
+                .map(|point| point.measured_at)
+                .max();
+            let days_since = last_data_at.map(|at| ((now - at) / MICROS_PER_DAY) as u32);
+            let quiet_days = ((now - last_data_at.unwrap_or(twin.created_at)) / MICROS_PER_DAY).max(0);
+            DataStaleness {
+                target: rule.target.clone(),
+                last_data_at,
+                days_since,
+                stale_after_days: rule.stale_after_days,
+                stale: quiet_days >= rule.stale_after_days as i64,
+            }
+        })
+        .collect()
+}
+
+fn nudge_prompt(staleness: &DataStaleness) -> String {
+    let what = staleness.target.describe();
+    match staleness.days_since {
+        Some(days) => format!(
+            "It's been {} days since we last got {}. A quick update keeps your health twin accurate.",
+            days, what
+        ),
+        None => format!(
+            "We haven't got {} yet. Sharing it helps your health twin understand you better.",
+            what
+        ),
+    }
+}
+
+fn twin_nudges(twin_hash: &ActionHash) -> ExternResult<Vec<(Record, EngagementNudge)>> {
+    let links = get_links(
+        LinkQuery::try_new(twin_hash.clone(), LinkTypes::TwinToNudges)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut nudges = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(nudge) = record.entry().to_app_option::<EngagementNudge>().ok().flatten() {
+                    nudges.push((record, nudge));
+                }
+            }
+        }
+    }
+    Ok(nudges)
+}
+
+// ==================== ANCHOR SUPPORT ====================
+
+/// Anchor entry for indexing
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Anchor(pub String);
+
+fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
+    let anchor = Anchor(anchor_text.to_string());
+    hash_entry(&anchor)
+}
+
+// ==================== GENETIC RISK INTEGRATION ====================
+
+/// Input for updating genetic-derived risk factors
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateGeneticRiskInput {
+    pub twin_hash: ActionHash,
+    /// Optional: specific genetic vector to analyze (if None, uses all patient vectors)
+    pub genetic_vector_hash: Option<ActionHash>,
+}
+
+/// Result of genetic risk analysis
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct GeneticRiskAnalysis {
     pub risk_factors: Vec<RiskFactor>,
     pub genetic_data_source: DataSourceInfo,
     pub analysis_timestamp: i64,
@@ -1099,7 +2551,8 @@ pub fn update_genetic_risk_factors(input: UpdateGeneticRiskInput) -> ExternResul
     }
 
     // Analyze genetic vectors and generate risk factors
-    let genetic_risk_factors = analyze_genetic_risks(&genetic_vectors)?;
+    let derivations = analyze_genetic_risks(&genetic_vectors)?;
+    let genetic_risk_factors: Vec<RiskFactor> = derivations.iter().map(|d| d.factor.clone()).collect();
 
     // Merge with existing risk factors (keep non-genetic, update genetic)
     let mut merged_risks = twin.risk_factors.clone();
@@ -1125,7 +2578,18 @@ pub fn update_genetic_risk_factors(input: UpdateGeneticRiskInput) -> ExternResul
     twin.data_sources.retain(|d| d.source_type != DataSourceType::Genetic);
     twin.data_sources.push(genetic_source);
 
-    let updated_hash = update_entry(twin_hash, &twin)?;
+    let updated_hash = update_entry(twin_hash.clone(), &twin)?;
+
+    for derivation in derivations {
+        record_explainability_trace(
+            &twin_hash,
+            &twin.model_version,
+            TraceSubject::RiskFactor,
+            &derivation.factor.name,
+            derivation.factor.risk_level,
+            derivation.evidence,
+        )?;
+    }
 
     log_data_access(
         patient_hash,
@@ -1140,8 +2604,56 @@ pub fn update_genetic_risk_factors(input: UpdateGeneticRiskInput) -> ExternResul
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated twin".to_string())))
 }
 
+/// Risk factor together with the evidence that produced it
+struct RiskDerivation {
+    factor: RiskFactor,
+    evidence: TraceEvidence,
+}
+
+impl RiskDerivation {
+    fn from_vector(
+        vector: &GeneticHypervector,
+        factor: RiskFactor,
+        rule: &str,
+        weight: f32,
+        thresholds: Vec<ThresholdCrossing>,
+    ) -> Self {
+        let observed_at = vector
+            .source_metadata
+            .test_date
+            .unwrap_or(vector.created_at)
+            .as_micros();
+        Self {
+            factor,
+            evidence: TraceEvidence {
+                inputs: vec![TraceInput {
+                    name: format!("Genetic vector {}", vector.vector_id),
+                    value: format!("{:?}, {} k-mers", vector.encoding_type, vector.kmer_count),
+                    source: Some(DataSourceType::Genetic),
+                    observed_at: Some(observed_at),
+                }],
+                rules: vec![TraceRule {
+                    rule: rule.to_string(),
+                    weight,
+                    direction: InfluenceDirection::IncreasesRisk,
+                }],
+                thresholds,
+            },
+        }
+    }
+}
+
+fn threshold(metric: &str, threshold: f32, observed: f32) -> ThresholdCrossing {
+    ThresholdCrossing {
+        metric: metric.to_string(),
+        threshold,
+        observed,
+        above: observed > threshold,
+    }
+}
+
 /// Analyze genetic hypervectors to generate risk factors
-fn analyze_genetic_risks(vectors: &[GeneticHypervector]) -> ExternResult<Vec<RiskFactor>> {
+fn analyze_genetic_risks(vectors: &[GeneticHypervector]) -> ExternResult<Vec<RiskDerivation>> {
     let mut risk_factors = Vec::new();
 
     for vector in vectors {
@@ -1169,7 +2681,7 @@ fn analyze_genetic_risks(vectors: &[GeneticHypervector]) -> ExternResult<Vec<Ris
 }
 
 /// Analyze SNP panel for disease risk associations
-fn analyze_snp_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskFactor>> {
+fn analyze_snp_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskDerivation>> {
     let mut risks = Vec::new();
 
     // Calculate risk scores based on hypervector characteristics
@@ -1178,116 +2690,149 @@ fn analyze_snp_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskFactor
 
     // Cardiovascular genetic risk (APOE, LDLR, PCSK9 variants)
     if vector_density > 0.3 {
-        risks.push(RiskFactor {
-            name: "Genetic Cardiovascular Predisposition".to_string(),
-            category: RiskCategory::Cardiovascular,
-            risk_level: (vector_density * 0.6).min(0.9),
-            trend: RiskTrend::Stable,
-            contributors: vec![
-                "SNP panel analysis".to_string(),
-                "Familial hypercholesterolemia markers".to_string(),
-            ],
-            modifiable: false,
-            interventions: vec![
-                "Aggressive lipid management".to_string(),
-                "Early statin therapy".to_string(),
-                "Regular cardiac screening".to_string(),
-            ],
-        });
+        risks.push(RiskDerivation::from_vector(
+            vector,
+            RiskFactor {
+                name: "Genetic Cardiovascular Predisposition".to_string(),
+                category: RiskCategory::Cardiovascular,
+                risk_level: (vector_density * 0.6).min(0.9),
+                trend: RiskTrend::Stable,
+                contributors: vec![
+                    "SNP panel analysis".to_string(),
+                    "Familial hypercholesterolemia markers".to_string(),
+                ],
+                modifiable: false,
+                interventions: vec![
+                    "Aggressive lipid management".to_string(),
+                    "Early statin therapy".to_string(),
+                    "Regular cardiac screening".to_string(),
+                ],
+            },
+            "Risk = SNP vector density x 0.6, capped at 0.9",
+            0.6,
+            vec![threshold("SNP vector density", 0.3, vector_density)],
+        ));
     }
 
     // Metabolic/diabetes risk (TCF7L2, FTO, MC4R variants)
     if vector.kmer_count > 50 {
         let metabolic_risk = ((vector.kmer_count as f32) / 200.0).min(0.8);
         if metabolic_risk > 0.2 {
-            risks.push(RiskFactor {
-                name: "Genetic Metabolic Syndrome Risk".to_string(),
-                category: RiskCategory::Metabolic,
-                risk_level: metabolic_risk,
-                trend: RiskTrend::Stable,
-                contributors: vec![
-                    "Diabetes susceptibility variants".to_string(),
-                    "Obesity-related gene markers".to_string(),
-                ],
-                modifiable: true,
-                interventions: vec![
-                    "Lifestyle modification".to_string(),
-                    "Regular glucose monitoring".to_string(),
-                    "Mediterranean diet".to_string(),
+            risks.push(RiskDerivation::from_vector(
+                vector,
+                RiskFactor {
+                    name: "Genetic Metabolic Syndrome Risk".to_string(),
+                    category: RiskCategory::Metabolic,
+                    risk_level: metabolic_risk,
+                    trend: RiskTrend::Stable,
+                    contributors: vec![
+                        "Diabetes susceptibility variants".to_string(),
+                        "Obesity-related gene markers".to_string(),
+                    ],
+                    modifiable: true,
+                    interventions: vec![
+                        "Lifestyle modification".to_string(),
+                        "Regular glucose monitoring".to_string(),
+                        "Mediterranean diet".to_string(),
+                    ],
+                },
+                "Risk = k-mer count / 200, capped at 0.8",
+                1.0 / 200.0,
+                vec![
+                    threshold("k-mer count", 50.0, vector.kmer_count as f32),
+                    threshold("Metabolic risk", 0.2, metabolic_risk),
                 ],
-            });
+            ));
         }
     }
 
     // Oncological risk (BRCA1/2, Lynch syndrome markers)
     let onco_score = calculate_oncology_score(&vector.data);
     if onco_score > 0.25 {
-        risks.push(RiskFactor {
-            name: "Genetic Cancer Predisposition".to_string(),
-            category: RiskCategory::Oncological,
-            risk_level: onco_score,
-            trend: RiskTrend::Stable,
-            contributors: vec!["Hereditary cancer gene analysis".to_string()],
-            modifiable: false,
-            interventions: vec![
-                "Enhanced cancer screening".to_string(),
-                "Genetic counseling".to_string(),
-                "Prophylactic measures discussion".to_string(),
-            ],
-        });
+        risks.push(RiskDerivation::from_vector(
+            vector,
+            RiskFactor {
+                name: "Genetic Cancer Predisposition".to_string(),
+                category: RiskCategory::Oncological,
+                risk_level: onco_score,
+                trend: RiskTrend::Stable,
+                contributors: vec!["Hereditary cancer gene analysis".to_string()],
+                modifiable: false,
+                interventions: vec![
+                    "Enhanced cancer screening".to_string(),
+                    "Genetic counseling".to_string(),
+                    "Prophylactic measures discussion".to_string(),
+                ],
+            },
+            "Risk = oncology pattern score",
+            1.0,
+            vec![threshold("Oncology pattern score", 0.25, onco_score)],
+        ));
     }
 
     Ok(risks)
 }
 
 /// Analyze HLA typing for disease susceptibility
-fn analyze_hla_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskFactor>> {
+fn analyze_hla_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskDerivation>> {
     let mut risks = Vec::new();
 
     // HLA associations with autoimmune diseases
     let autoimmune_score = calculate_autoimmune_score(&vector.data);
 
     if autoimmune_score > 0.3 {
-        risks.push(RiskFactor {
-            name: "HLA-Associated Autoimmune Risk".to_string(),
-            category: RiskCategory::Other("Autoimmune".to_string()),
-            risk_level: autoimmune_score,
-            trend: RiskTrend::Stable,
-            contributors: vec![
-                "HLA typing analysis".to_string(),
-                "Autoimmune disease susceptibility alleles".to_string(),
-            ],
-            modifiable: false,
-            interventions: vec![
-                "Autoimmune marker monitoring".to_string(),
-                "Early symptom recognition".to_string(),
-                "Immunology consultation if symptomatic".to_string(),
-            ],
-        });
+        risks.push(RiskDerivation::from_vector(
+            vector,
+            RiskFactor {
+                name: "HLA-Associated Autoimmune Risk".to_string(),
+                category: RiskCategory::Other("Autoimmune".to_string()),
+                risk_level: autoimmune_score,
+                trend: RiskTrend::Stable,
+                contributors: vec![
+                    "HLA typing analysis".to_string(),
+                    "Autoimmune disease susceptibility alleles".to_string(),
+                ],
+                modifiable: false,
+                interventions: vec![
+                    "Autoimmune marker monitoring".to_string(),
+                    "Early symptom recognition".to_string(),
+                    "Immunology consultation if symptomatic".to_string(),
+                ],
+            },
+            "Risk = autoimmune HLA pattern score",
+            1.0,
+            vec![threshold("Autoimmune HLA score", 0.3, autoimmune_score)],
+        ));
     }
 
     // HLA and drug response (pharmacogenomics)
     let pharmacogenomic_flag = vector.kmer_count > 5;
     if pharmacogenomic_flag {
-        risks.push(RiskFactor {
-            name: "HLA Drug Sensitivity Alert".to_string(),
-            category: RiskCategory::Other("Pharmacogenomics".to_string()),
-            risk_level: 0.5, // Informational, not really a "risk"
-            trend: RiskTrend::Stable,
-            contributors: vec!["HLA-drug interaction analysis".to_string()],
-            modifiable: true,
-            interventions: vec![
-                "Review medications with pharmacogenomic implications".to_string(),
-                "Consider HLA-guided prescribing".to_string(),
-            ],
-        });
+        risks.push(RiskDerivation::from_vector(
+            vector,
+            RiskFactor {
+                name: "HLA Drug Sensitivity Alert".to_string(),
+                category: RiskCategory::Other("Pharmacogenomics".to_string()),
+                risk_level: 0.5, // Informational, not really a "risk"
+                trend: RiskTrend::Stable,
+                contributors: vec!["HLA-drug interaction analysis".to_string()],
+                modifiable: true,
+                interventions: vec![
+                    "Review medications with pharmacogenomic implications".to_string(),
+                    "Consider HLA-guided prescribing".to_string(),
+                ],
+            },
+            "Informational level for HLA drug sensitivity",
+            0.5,
+            vec![threshold("HLA k-mer count", 5.0, vector.kmer_count as f32)],
+        ));
     }
 
     Ok(risks)
 }
 
 /// Analyze DNA sequence patterns
-fn analyze_sequence_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskFactor>> {
+fn analyze_sequence_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskDerivation>> {
     let mut risks = Vec::new();
 
     // Sequence-based analysis (mitochondrial, rare variants)
@@ -1295,18 +2840,24 @@ fn analyze_sequence_risks(vector: &GeneticHypervector) -> ExternResult<Vec<RiskF
 
     if sequence_complexity < 0.3 {
         // Low complexity might indicate certain genetic conditions
-        risks.push(RiskFactor {
-            name: "Genetic Sequence Variant Detected".to_string(),
-            category: RiskCategory::Other("Genetic".to_string()),
-            risk_level: 0.3,
-            trend: RiskTrend::Unknown,
-            contributors: vec!["DNA sequence analysis".to_string()],
-            modifiable: false,
-            interventions: vec![
-                "Genetic counseling recommended".to_string(),
-                "Family history assessment".to_string(),
-            ],
-        });
+        risks.push(RiskDerivation::from_vector(
+            vector,
+            RiskFactor {
+                name: "Genetic Sequence Variant Detected".to_string(),
+                category: RiskCategory::Other("Genetic".to_string()),
+                risk_level: 0.3,
+                trend: RiskTrend::Unknown,
+                contributors: vec!["DNA sequence analysis".to_string()],
+                modifiable: false,
+                interventions: vec![
+                    "Genetic counseling recommended".to_string(),
+                    "Family history assessment".to_string(),
+                ],
+            },
+            "Fixed level for low-complexity sequence",
+            0.3,
+            vec![threshold("Sequence complexity", 0.3, sequence_complexity)],
+        ));
     }
 
     Ok(risks)
@@ -1368,19 +2919,18 @@ fn is_genetic_risk_factor(risk: &RiskFactor) -> bool {
 }
 
 /// Aggregate overlapping risk factors
-fn aggregate_risk_factors(mut risks: Vec<RiskFactor>) -> Vec<RiskFactor> {
-    // Group by category and keep highest risk level
-    let mut by_category: std::collections::HashMap<String, RiskFactor> = std::collections::HashMap::new();
+fn aggregate_risk_factors(mut risks: Vec<RiskDerivation>) -> Vec<RiskDerivation> {
+    // Group by category and keep highest risk level (with its evidence)
+    let mut by_category: std::collections::HashMap<String, RiskDerivation> = std::collections::HashMap::new();
 
     for risk in risks.drain(..) {
-        let key = format!("{:?}-{}", risk.category, risk.name);
-        by_category.entry(key)
-            .and_modify(|existing| {
-                if risk.risk_level > existing.risk_level {
-                    *existing = risk.clone();
-                }
-            })
-            .or_insert(risk);
+        let key = format!("{:?}-{}", risk.factor.category, risk.factor.name);
+        match by_category.get(&key) {
+            Some(existing) if existing.factor.risk_level >= risk.factor.risk_level => {}
+            _ => {
+                by_category.insert(key, risk);
+            }
+        }
     }
 
     by_category.into_values().collect()
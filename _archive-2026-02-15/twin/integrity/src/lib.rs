@@ -33,6 +33,14 @@ pub enum EntryTypes {
     HealthTrajectory(HealthTrajectory),
     /// Model update (when twin learns)
     ModelUpdate(ModelUpdate),
+    /// Explanation of how a risk factor or prediction was derived
+    ExplainabilityTrace(ExplainabilityTrace),
+    /// Health goal the patient set
+    HealthGoal(HealthGoal),
+    /// Progress snapshot for a goal
+    GoalProgress(GoalProgress),
+    /// Prompt sent to a patient whose data has gone stale
+    EngagementNudge(EngagementNudge),
 }
 
 /// Link types for the health twin zome
@@ -46,6 +54,10 @@ pub enum LinkTypes {
     TwinToTrajectories,
     TwinToUpdates,
     ActiveTwins,
+    TwinToExplanations,
+    TwinToGoals,
+    GoalToProgress,
+    TwinToNudges,
 }
 
 // ==================== HEALTH TWIN ====================
@@ -381,6 +393,22 @@ pub struct TwinDataPoint {
     pub triggered_update: bool,
     /// Ingested at
     pub ingested_at: i64,
+    /// FHIR status code of the result this came from (e.g. "final",
+    /// "preliminary"); `None` for data without a result lifecycle, such as
+    /// device readings
+    #[serde(default)]
+    pub observation_status: Option<String>,
+}
+
+impl TwinDataPoint {
+    /// Whether the data point may feed model updates: only final, amended
+    /// or corrected results do, as with `ObservationStatus::is_reportable`
+    pub fn is_reportable(&self) -> bool {
+        match &self.observation_status {
+            Some(status) => matches!(status.as_str(), "final" | "amended" | "corrected"),
+            None => true,
+        }
+    }
 }
 
 /// Types of twin data
@@ -528,6 +556,9 @@ pub struct SimulationResults {
     pub caveats: Vec<String>,
     /// Computed at
     pub computed_at: i64,
+    /// Set when confidence is below the twin's review threshold
+    #[serde(default)]
+    pub low_confidence: Option<LowConfidenceFlag>,
 }
 
 /// Projected outcome from simulation
@@ -543,6 +574,9 @@ pub struct ProjectedOutcome {
     pub change_percent: f32,
     /// Confidence interval (low, high)
     pub confidence_interval: (f32, f32),
+    /// How the interval was derived and what it rests on
+    #[serde(default)]
+    pub uncertainty: Option<Uncertainty>,
     /// Trajectory over time
     pub trajectory: Vec<TrajectoryPoint>,
 }
@@ -556,6 +590,9 @@ pub struct TrajectoryPoint {
     pub value: f32,
     /// Confidence at this point
     pub confidence: f32,
+    /// Interval (low, high) at this point, widening with distance from now
+    #[serde(default)]
+    pub interval: Option<(f32, f32)>,
 }
 
 /// Comparison to baseline scenario
@@ -601,6 +638,15 @@ pub struct Prediction {
     pub predicted_value: f32,
     /// Confidence interval
     pub confidence_interval: (f32, f32),
+    /// How the interval was derived; required for new predictions
+    #[serde(default)]
+    pub uncertainty: Option<Uncertainty>,
+    /// Model confidence in this prediction (0.0 - 1.0)
+    #[serde(default)]
+    pub confidence: Option<f32>,
+    /// Set when confidence is below the twin's review threshold
+    #[serde(default)]
+    pub low_confidence: Option<LowConfidenceFlag>,
     /// Model used
     pub model_id: String,
     /// Features that drove prediction
@@ -613,6 +659,82 @@ pub struct Prediction {
     pub outcome: Option<PredictionOutcome>,
 }
 
+/// How an uncertainty interval was estimated
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum UncertaintyMethod {
+    /// Interval reported by the model itself
+    ModelInterval,
+    /// Resampling of the input data
+    Bootstrap,
+    /// Posterior credible interval
+    Bayesian,
+    /// Derived from the uncertainty of the inputs to a simulation
+    Propagated,
+    /// Rule of thumb, not statistically calibrated
+    Heuristic,
+}
+
+/// Structured uncertainty around a predicted or projected value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct Uncertainty {
+    /// Lower interval bound
+    pub lower: f32,
+    /// Upper interval bound
+    pub upper: f32,
+    /// Nominal coverage of the interval (e.g. 0.95)
+    pub level: f32,
+    /// How the interval was estimated
+    pub method: UncertaintyMethod,
+    /// Number of observations the estimate rests on
+    pub sample_size: u64,
+    /// What those observations are (e.g. "14 months of home BP readings")
+    pub sample_basis: String,
+}
+
+impl Uncertainty {
+    /// Why this uncertainty cannot describe `value`, if it cannot
+    pub fn problem_with(&self, value: f32) -> Option<String> {
+        if !(self.lower <= value && value <= self.upper) {
+            return Some(format!(
+                "Interval [{}, {}] does not contain the value {}",
+                self.lower, self.upper, value
+            ));
+        }
+        if !(self.level > 0.0 && self.level < 1.0) {
+            return Some("Interval level must be between 0 and 1".to_string());
+        }
+        if self.sample_basis.is_empty() {
+            return Some("Uncertainty must describe its sample basis".to_string());
+        }
+        None
+    }
+}
+
+/// Clinician-facing warning on an output whose confidence is too low to act on alone
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct LowConfidenceFlag {
+    /// Confidence of the output
+    pub confidence: f32,
+    /// Threshold it fell below
+    pub threshold: f32,
+    /// Explanation for the clinician
+    pub message: String,
+}
+
+impl LowConfidenceFlag {
+    /// Flag `confidence` if it is below `threshold`
+    pub fn check(confidence: f32, threshold: f32, subject: &str) -> Option<Self> {
+        (confidence < threshold).then(|| LowConfidenceFlag {
+            confidence,
+            threshold,
+            message: format!(
+                "{} has confidence {:.2}, below the review threshold of {:.2}; confirm with clinical judgement before acting on it",
+                subject, confidence, threshold
+            ),
+        })
+    }
+}
+
 /// Types of predictions
 #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub enum PredictionType {
@@ -697,10 +819,42 @@ pub struct TwinConfiguration {
     pub auto_simulation: AutoSimulationPrefs,
     /// Privacy level
     pub privacy_level: TwinPrivacyLevel,
+    /// Outputs below this confidence are flagged for clinician review
+    #[serde(default = "default_low_confidence_threshold")]
+    pub low_confidence_threshold: f32,
+    /// When each kind of data counts as stale
+    #[serde(default = "default_nudge_rules")]
+    pub nudge_rules: Vec<NudgeRule>,
+    /// Minimum days between two nudges; 0 turns nudges off
+    #[serde(default = "default_min_days_between_nudges")]
+    pub min_days_between_nudges: u32,
     /// Updated at
     pub updated_at: i64,
 }
 
+/// Review threshold used when a twin has no configuration
+pub const DEFAULT_LOW_CONFIDENCE_THRESHOLD: f32 = 0.6;
+
+fn default_low_confidence_threshold() -> f32 {
+    DEFAULT_LOW_CONFIDENCE_THRESHOLD
+}
+
+/// Nudge frequency used when a twin has no configuration
+pub const DEFAULT_MIN_DAYS_BETWEEN_NUDGES: u32 = 7;
+
+fn default_min_days_between_nudges() -> u32 {
+    DEFAULT_MIN_DAYS_BETWEEN_NUDGES
+}
+
+/// Staleness rules used when a twin has no configuration: device data
+/// after 14 days, vitals after 90
+pub fn default_nudge_rules() -> Vec<NudgeRule> {
+    vec![
+        NudgeRule { target: NudgeTarget::Source(DataSourceType::Wearable), stale_after_days: 14, prompt: None },
+        NudgeRule { target: NudgeTarget::VitalSigns, stale_after_days: 90, prompt: None },
+    ]
+}
+
 /// Auto-simulation preferences
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct AutoSimulationPrefs {
@@ -808,6 +962,264 @@ pub enum ModelUpdateReason {
     UserFeedback,
 }
 
+// ==================== EXPLAINABILITY ====================
+
+/// Trace of how the twin derived a risk factor or prediction, so
+/// providers can justify decisions based on it
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct ExplainabilityTrace {
+    /// Unique trace ID
+    pub trace_id: String,
+    /// Twin that emitted the output
+    pub twin_hash: ActionHash,
+    /// What is being explained
+    pub subject: TraceSubject,
+    /// Risk factor name or prediction target
+    pub risk_name: String,
+    /// Output value being explained (risk level or predicted value)
+    pub output_value: f32,
+    /// Inputs the derivation used
+    pub inputs_used: Vec<TraceInput>,
+    /// Weights or rules applied
+    pub rules_applied: Vec<TraceRule>,
+    /// Thresholds crossed on the way to the output
+    pub thresholds_crossed: Vec<ThresholdCrossing>,
+    /// How fresh the underlying data was
+    pub data_recency: DataRecency,
+    /// Model version at derivation time
+    pub model_version: String,
+    /// Generated at
+    pub generated_at: i64,
+}
+
+/// Output a trace explains
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TraceSubject {
+    RiskFactor,
+    Prediction(ActionHash),
+}
+
+/// Input used in a derivation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TraceInput {
+    /// Input name
+    pub name: String,
+    /// Value as used (free text for categorical inputs)
+    pub value: String,
+    /// Where the input came from
+    pub source: Option<DataSourceType>,
+    /// When the input was observed
+    pub observed_at: Option<i64>,
+}
+
+/// Weight or rule applied in a derivation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TraceRule {
+    /// Rule or weight description
+    pub rule: String,
+    /// Weight/importance applied
+    pub weight: f32,
+    /// Direction of influence
+    pub direction: InfluenceDirection,
+}
+
+/// Threshold crossed during a derivation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ThresholdCrossing {
+    /// Metric compared
+    pub metric: String,
+    /// Threshold value
+    pub threshold: f32,
+    /// Observed value
+    pub observed: f32,
+    /// True when the observed value is above the threshold
+    pub above: bool,
+}
+
+/// Recency of data behind a derivation
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DataRecency {
+    /// Newest input timestamp
+    pub newest_data_at: Option<i64>,
+    /// Oldest input timestamp
+    pub oldest_data_at: Option<i64>,
+    /// Age in days of the oldest input at derivation time
+    pub max_age_days: Option<u32>,
+}
+
+// ==================== GOALS ====================
+
+/// A health goal the patient set ("lose 5 kg", "walk 8k steps a day"),
+/// measured against the twin's data points
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct HealthGoal {
+    /// Unique goal ID
+    pub goal_id: String,
+    /// Twin whose data measures the goal
+    pub twin_hash: ActionHash,
+    /// Goal in the patient's words
+    pub description: String,
+    /// Data points that count towards the goal
+    pub metric: TwinDataType,
+    /// How data points are reduced to the current value
+    pub aggregation: GoalAggregation,
+    /// Value when the goal was set
+    pub baseline_value: f32,
+    /// Value to reach; below the baseline for goals that decrease
+    pub target_value: f32,
+    /// Unit
+    pub unit: Option<String>,
+    /// Start of the goal period
+    pub start_at: i64,
+    /// Deadline
+    pub deadline: i64,
+    /// Fractions of the way from baseline to target (0..1) that notify the patient
+    pub milestones: Vec<f32>,
+    /// Created at
+    pub created_at: i64,
+}
+
+/// Reduction of data points to a goal's current value
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum GoalAggregation {
+    /// Most recent measurement (weight, blood pressure)
+    Latest,
+    /// Mean of the daily totals over the trailing days (steps per day)
+    DailyAverage { days: u32 },
+}
+
+/// Where a goal stands
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum GoalStatus {
+    /// Not yet measured
+    NotStarted,
+    InProgress,
+    Achieved,
+    /// Deadline passed without reaching the target
+    Missed,
+}
+
+impl HealthGoal {
+    /// Fraction of the way from baseline to target, clamped to 0..=1
+    pub fn fraction_complete(&self, current: f32) -> f32 {
+        let span = self.target_value - self.baseline_value;
+        ((current - self.baseline_value) / span).clamp(0.0, 1.0)
+    }
+
+    pub fn is_met(&self, current: f32) -> bool {
+        if self.target_value < self.baseline_value {
+            current <= self.target_value
+        } else {
+            current >= self.target_value
+        }
+    }
+
+    /// Fraction of the goal period elapsed at `now`, clamped to 0..=1
+    pub fn fraction_elapsed(&self, now: i64) -> f32 {
+        let period = (self.deadline - self.start_at) as f64;
+        ((now - self.start_at) as f64 / period).clamp(0.0, 1.0) as f32
+    }
+}
+
+/// Progress towards a goal computed from the twin's data points
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct GoalProgress {
+    /// Goal measured
+    pub goal_hash: ActionHash,
+    /// Aggregated value; `None` without usable data points
+    pub current_value: Option<f32>,
+    /// Fraction of the way from baseline to target
+    pub fraction_complete: f32,
+    /// Status at computation time
+    pub status: GoalStatus,
+    /// Whether progress keeps pace with the time elapsed
+    pub on_track: bool,
+    /// Milestones reached so far
+    pub milestones_reached: Vec<f32>,
+    /// Data points the value was computed from
+    pub data_points_used: u32,
+    /// Newest data point used
+    pub latest_data_at: Option<i64>,
+    /// Computed at
+    pub computed_at: i64,
+}
+
+// ==================== ENGAGEMENT ====================
+
+/// Data whose freshness a nudge rule watches
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum NudgeTarget {
+    /// Anything from one source (wearables, self-reports)
+    Source(DataSourceType),
+    VitalSigns,
+    LabResults,
+    Lifestyle,
+}
+
+impl NudgeTarget {
+    pub fn matches(&self, point: &TwinDataPoint) -> bool {
+        match self {
+            NudgeTarget::Source(source) => point.source == *source,
+            NudgeTarget::VitalSigns => matches!(point.data_type, TwinDataType::VitalSign(_)),
+            NudgeTarget::LabResults => matches!(point.data_type, TwinDataType::LabResult(_)),
+            NudgeTarget::Lifestyle => matches!(point.data_type, TwinDataType::Lifestyle(_)),
+        }
+    }
+
+    /// The data in the patient's words
+    pub fn describe(&self) -> &'static str {
+        match self {
+            NudgeTarget::Source(DataSourceType::Wearable) => "data from your wearable device",
+            NudgeTarget::Source(DataSourceType::SelfReported) => "a check-in from you",
+            NudgeTarget::Source(DataSourceType::EHR) => "updates from your health records",
+            NudgeTarget::Source(DataSourceType::Laboratory) => "results from your lab",
+            NudgeTarget::Source(DataSourceType::Imaging) => "imaging results",
+            NudgeTarget::Source(DataSourceType::Pharmacy) => "updates from your pharmacy",
+            NudgeTarget::Source(DataSourceType::Genetic) => "genetic test results",
+            NudgeTarget::Source(DataSourceType::SocialDeterminants) => "answers about your living situation",
+            NudgeTarget::VitalSigns => "your vital signs",
+            NudgeTarget::LabResults => "your lab results",
+            NudgeTarget::Lifestyle => "your sleep, activity or diet logs",
+        }
+    }
+}
+
+/// When data counts as stale and what to tell the patient
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct NudgeRule {
+    pub target: NudgeTarget,
+    /// Days without data before the patient is nudged
+    pub stale_after_days: u32,
+    /// Prompt to send instead of the generated one
+    pub prompt: Option<String>,
+}
+
+/// Data found stale when a nudge was generated
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct StaleData {
+    pub target: NudgeTarget,
+    /// Newest matching data point; `None` if there never was one
+    pub last_data_at: Option<i64>,
+    pub stale_after_days: u32,
+}
+
+/// Prompt sent to a patient whose twin has gone without data
+#[hdk_entry_helper]
+#[derive(Clone)]
+pub struct EngagementNudge {
+    /// Twin the data feeds
+    pub twin_hash: ActionHash,
+    /// Everything stale at the time
+    pub stale: Vec<StaleData>,
+    /// Plain-language prompt, one line per stale item
+    pub prompt: String,
+    /// Sent at
+    pub sent_at: i64,
+}
+
 // ==================== VALIDATION ====================
 
 /// Validate a health twin
@@ -837,6 +1249,21 @@ pub fn validate_simulation(sim: &Simulation) -> ExternResult<ValidateCallbackRes
         return Ok(ValidateCallbackResult::Invalid("Time horizon must be positive".to_string()));
     }
 
+    if sim.status == SimulationStatus::Completed {
+        for outcome in sim.results.iter().flat_map(|r| &r.outcomes) {
+            let problem = match &outcome.uncertainty {
+                Some(uncertainty) => uncertainty.problem_with(outcome.projected_value),
+                None => Some("Uncertainty is required".to_string()),
+            };
+            if let Some(problem) = problem {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Projected outcome {}: {}",
+                    outcome.metric, problem
+                )));
+            }
+        }
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -855,6 +1282,89 @@ pub fn validate_prediction(pred: &Prediction) -> ExternResult<ValidateCallbackRe
         return Ok(ValidateCallbackResult::Invalid("Invalid confidence interval".to_string()));
     }
 
+    let Some(uncertainty) = &pred.uncertainty else {
+        return Ok(ValidateCallbackResult::Invalid("Prediction uncertainty required".to_string()));
+    };
+    if let Some(problem) = uncertainty.problem_with(pred.predicted_value) {
+        return Ok(ValidateCallbackResult::Invalid(problem));
+    }
+    if (uncertainty.lower, uncertainty.upper) != pred.confidence_interval {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Confidence interval must match the uncertainty bounds".to_string(),
+        ));
+    }
+    if pred.confidence.is_some_and(|c| !(0.0..=1.0).contains(&c)) {
+        return Ok(ValidateCallbackResult::Invalid("Confidence must be between 0 and 1".to_string()));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate an explainability trace
+pub fn validate_explainability_trace(trace: &ExplainabilityTrace) -> ExternResult<ValidateCallbackResult> {
+    if trace.trace_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Trace ID required".to_string()));
+    }
+
+    if trace.risk_name.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Risk name required".to_string()));
+    }
+
+    if trace.inputs_used.is_empty() && trace.rules_applied.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Trace must record at least one input or rule".to_string(),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Validate a health goal
+pub fn validate_health_goal(goal: &HealthGoal) -> ExternResult<ValidateCallbackResult> {
+    if goal.goal_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Goal ID required".to_string()));
+    }
+
+    if goal.description.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Goal description required".to_string()));
+    }
+
+    if !goal.baseline_value.is_finite() || !goal.target_value.is_finite() {
+        return Ok(ValidateCallbackResult::Invalid("Goal values must be finite".to_string()));
+    }
+
+    if goal.target_value == goal.baseline_value {
+        return Ok(ValidateCallbackResult::Invalid("Target must differ from the baseline".to_string()));
+    }
+
+    if goal.deadline <= goal.start_at {
+        return Ok(ValidateCallbackResult::Invalid("Deadline must be after the start".to_string()));
+    }
+
+    if let GoalAggregation::DailyAverage { days } = goal.aggregation {
+        if days == 0 {
+            return Ok(ValidateCallbackResult::Invalid("Averaging window must be at least one day".to_string()));
+        }
+    }
+
+    if goal.milestones.iter().any(|m| !(*m > 0.0 && *m < 1.0)) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Milestones must be fractions between 0 and 1".to_string(),
+        ));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Validate an engagement nudge
+pub fn validate_engagement_nudge(nudge: &EngagementNudge) -> ExternResult<ValidateCallbackResult> {
+    if nudge.stale.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Nudge must name stale data".to_string()));
+    }
+
+    if nudge.prompt.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid("Nudge prompt required".to_string()));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
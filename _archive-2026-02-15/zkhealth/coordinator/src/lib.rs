@@ -227,6 +227,17 @@ pub struct ZkVerificationAuditLog {
 pub fn generate_health_proof(proof: HealthProof) -> ExternResult<Record> {
     validate_health_proof(&proof)?;
 
+    // A cited template must match the proof exactly, and its data needs are authorized too
+    let template = match &proof.template {
+        Some(citation) => {
+            let template = load_cited_template(citation)?;
+            check_template_citation(&proof, citation, &template)
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+            Some(template)
+        }
+        None => None,
+    };
+
     let data_category = proof_type_to_category(&proof.proof_type);
     let auth = require_authorization(
         proof.patient_hash.clone(),
@@ -234,11 +245,27 @@ pub fn generate_health_proof(proof: HealthProof) -> ExternResult<Record> {
         Permission::Export,
         false,
     )?;
+    let mut template_categories = Vec::new();
+    for category in template.iter().flat_map(|t| t.required_data.iter()).map(required_data_to_category) {
+        if category != data_category && !template_categories.contains(&category) {
+            require_authorization(proof.patient_hash.clone(), category.clone(), Permission::Export, false)?;
+            template_categories.push(category);
+        }
+    }
 
     let proof_hash = create_entry(&EntryTypes::HealthProof(proof.clone()))?;
     let record = get(proof_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find proof".to_string())))?;
 
+    if let Some(citation) = &proof.template {
+        create_link(
+            citation.template_hash.clone(),
+            proof_hash.clone(),
+            LinkTypes::TemplateToProofs,
+            (),
+        )?;
+    }
+
     // Link to patient
     create_link(
         proof.patient_hash.clone(),
@@ -261,9 +288,11 @@ pub fn generate_health_proof(proof: HealthProof) -> ExternResult<Record> {
     let _ = log_proof_to_consent(&proof);
     // ================================================================
 
+    let mut logged_categories = vec![data_category];
+    logged_categories.extend(template_categories);
     log_data_access(
         proof.patient_hash,
-        vec![data_category],
+        logged_categories,
         Permission::Export,
         auth.consent_hash,
         auth.emergency_override,
@@ -561,15 +590,28 @@ pub fn verify_health_proof(input: VerifyProofInput) -> ExternResult<Record> {
     let data_recency_ok = (now - proof.public_inputs.data_timestamp) <
         (input.max_data_age_days.unwrap_or(365) as i64 * 24 * 60 * 60 * 1_000_000);
 
+    // Re-check the citation so verifiers know exactly which statement holds
+    let (template_consistent, proven_statement) = match &proof.template {
+        Some(citation) => match load_cited_template(citation) {
+            Ok(template) => {
+                let consistent = check_template_citation(&proof, citation, &template).is_ok();
+                let statement = template.statement.as_ref().map(|s| s.render(&citation.parameters));
+                (consistent, statement)
+            }
+            Err(_) => (false, None),
+        },
+        None => (true, None),
+    };
+
     let verified = crypto_valid && within_validity && not_revoked &&
-                   attestations_verified && data_recency_ok;
+                   attestations_verified && data_recency_ok && template_consistent;
 
     let end_time = sys_time()?.as_millis();
 
     let failure_reason = if !verified {
         Some(format!(
-            "Verification failed: crypto={}, validity={}, not_revoked={}, attestations={}, recency={}",
-            crypto_valid, within_validity, not_revoked, attestations_verified, data_recency_ok
+            "Verification failed: crypto={}, validity={}, not_revoked={}, attestations={}, recency={}, template={}",
+            crypto_valid, within_validity, not_revoked, attestations_verified, data_recency_ok, template_consistent
         ))
     } else {
         None
@@ -586,6 +628,8 @@ pub fn verify_health_proof(input: VerifyProofInput) -> ExternResult<Record> {
             attestations_verified,
             not_revoked,
             data_recency_ok,
+            template_consistent,
+            proven_statement,
             failure_reason,
             verification_time_ms: (end_time - start_time) as u64,
         },
@@ -761,16 +805,164 @@ pub fn get_attestors_by_type(credential_type: AttestorCredentialType) -> ExternR
 
 // ==================== PROOF TEMPLATES ====================
 
-/// Create a proof template
+/// Publish a proof template to the catalog
+///
+/// Templates are immutable: changing a statement or circuit means publishing
+/// the same `template_id` with a higher `template_version`.
 #[hdk_extern]
 pub fn create_proof_template(template: ProofTemplate) -> ExternResult<Record> {
-    validate_proof_template(&template)?;
+    let template_hash = publish_template(template)?;
+    get(template_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find template".to_string())))
+}
+
+fn publish_template(template: ProofTemplate) -> ExternResult<ActionHash> {
+    if let ValidateCallbackResult::Invalid(reason) = validate_proof_template(&template)? {
+        return Err(wasm_error!(WasmErrorInner::Guest(reason)));
+    }
+    let already_published = get_template_versions(&template.template_id)?
+        .iter()
+        .any(|(_, t)| t.template_version == template.template_version);
+    if already_published {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Template {} version {} is already published",
+            template.template_id, template.template_version
+        ))));
+    }
 
     let template_hash = create_entry(&EntryTypes::ProofTemplate(template.clone()))?;
-    let record = get(template_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find template".to_string())))?;
 
-    Ok(record)
+    create_link(
+        anchor_hash("proof_templates")?,
+        template_hash.clone(),
+        LinkTypes::ProofTemplates,
+        (),
+    )?;
+    create_link(
+        anchor_hash(&format!("proof_template:{}", template.template_id))?,
+        template_hash.clone(),
+        LinkTypes::TemplateIdToTemplates,
+        LinkTag::new(template.template_version.to_be_bytes().to_vec()),
+    )?;
+    create_link(
+        anchor_hash(&format!("proof_template_type:{:?}", template.proof_type))?,
+        template_hash.clone(),
+        LinkTypes::ProofTypeToTemplates,
+        (),
+    )?;
+
+    Ok(template_hash)
+}
+
+/// List every published proof template
+#[hdk_extern]
+pub fn get_proof_templates(_: ()) -> ExternResult<Vec<Record>> {
+    template_records(anchor_hash("proof_templates")?, LinkTypes::ProofTemplates)
+}
+
+/// Find templates that produce a given proof type
+#[hdk_extern]
+pub fn get_templates_for_proof_type(proof_type: HealthProofType) -> ExternResult<Vec<Record>> {
+    template_records(
+        anchor_hash(&format!("proof_template_type:{:?}", proof_type))?,
+        LinkTypes::ProofTypeToTemplates,
+    )
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetProofTemplateInput {
+    pub template_id: String,
+    /// Latest version when omitted
+    pub version: Option<u32>,
+}
+
+/// Look up a template by ID and version
+#[hdk_extern]
+pub fn get_proof_template(input: GetProofTemplateInput) -> ExternResult<Option<Record>> {
+    let versions = get_template_versions(&input.template_id)?;
+    let chosen = match input.version {
+        Some(version) => versions.into_iter().find(|(_, t)| t.template_version == version),
+        None => versions.into_iter().max_by_key(|(_, t)| t.template_version),
+    };
+    match chosen {
+        Some((hash, _)) => get(hash, GetOptions::default()),
+        None => Ok(None),
+    }
+}
+
+fn template_records(base: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<Record>> {
+    let links = get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::default())?;
+    let mut templates = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                templates.push(record);
+            }
+        }
+    }
+    Ok(templates)
+}
+
+fn get_template_versions(template_id: &str) -> ExternResult<Vec<(ActionHash, ProofTemplate)>> {
+    let records = template_records(
+        anchor_hash(&format!("proof_template:{}", template_id))?,
+        LinkTypes::TemplateIdToTemplates,
+    )?;
+    Ok(records
+        .into_iter()
+        .filter_map(|record| {
+            let template = record.entry().to_app_option::<ProofTemplate>().ok().flatten()?;
+            Some((record.action_address().clone(), template))
+        })
+        .collect())
+}
+
+fn load_cited_template(citation: &TemplateCitation) -> ExternResult<ProofTemplate> {
+    get(citation.template_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<ProofTemplate>().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Cited proof template not found".to_string())))
+}
+
+/// Ensure a proof proves exactly what its cited template states
+fn check_template_citation(
+    proof: &HealthProof,
+    citation: &TemplateCitation,
+    template: &ProofTemplate,
+) -> Result<(), String> {
+    if citation.template_id != template.template_id || citation.template_version != template.template_version {
+        return Err("Citation does not match the cited template's ID and version".to_string());
+    }
+    if citation.circuit_id != template.circuit_id || citation.circuit_version != template.circuit_version {
+        return Err("Citation circuit does not match the template circuit".to_string());
+    }
+    if proof.metadata.circuit_id != template.circuit_id {
+        return Err(format!(
+            "Proof was generated with circuit {} but the template requires {}",
+            proof.metadata.circuit_id, template.circuit_id
+        ));
+    }
+    if proof.proof_type != template.proof_type {
+        return Err("Proof type does not match the template".to_string());
+    }
+    match &template.statement {
+        Some(schema) => schema.check_bindings(&citation.parameters),
+        None if citation.parameters.is_empty() => Ok(()),
+        None => Err("Template declares no parameters".to_string()),
+    }
+}
+
+fn required_data_to_category(required: &RequiredDataCategory) -> DataCategory {
+    match required {
+        RequiredDataCategory::LabResults(_) => DataCategory::LabResults,
+        RequiredDataCategory::VitalSigns | RequiredDataCategory::PhysicalExam => DataCategory::VitalSigns,
+        RequiredDataCategory::Diagnoses => DataCategory::Diagnoses,
+        RequiredDataCategory::Medications => DataCategory::Medications,
+        RequiredDataCategory::Allergies => DataCategory::Allergies,
+        RequiredDataCategory::Immunizations => DataCategory::Immunizations,
+        RequiredDataCategory::MentalHealthAssessment => DataCategory::MentalHealth,
+        RequiredDataCategory::Imaging => DataCategory::ImagingStudies,
+        RequiredDataCategory::Procedures => DataCategory::Procedures,
+    }
 }
 
 /// Initialize system proof templates
@@ -796,6 +988,9 @@ pub fn initialize_proof_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             ],
             created_by: None,
             system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: None,
         },
         ProofTemplate {
             template_id: "EMPLOYMENT-PHYSICAL".to_string(),
@@ -815,6 +1010,9 @@ pub fn initialize_proof_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             ],
             created_by: None,
             system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: None,
         },
         ProofTemplate {
             template_id: "ORGAN-DONOR".to_string(),
@@ -834,6 +1032,9 @@ pub fn initialize_proof_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             ],
             created_by: None,
             system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: None,
         },
         ProofTemplate {
             template_id: "CLINICAL-TRIAL".to_string(),
@@ -854,6 +1055,9 @@ pub fn initialize_proof_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             ],
             created_by: None,
             system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: None,
         },
         ProofTemplate {
             template_id: "TRAVEL-HEALTH".to_string(),
@@ -874,6 +1078,9 @@ pub fn initialize_proof_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             ],
             created_by: None,
             system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: None,
         },
         ProofTemplate {
             template_id: "SUBSTANCE-SCREENING".to_string(),
@@ -893,13 +1100,113 @@ pub fn initialize_proof_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             ],
             created_by: None,
             system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: None,
+        },
+        ProofTemplate {
+            template_id: "AGE-OVER".to_string(),
+            name: "Age Threshold".to_string(),
+            description: "Proves the patient is at least a given age without revealing date of birth".to_string(),
+            proof_type: HealthProofType::AgeVerification,
+            required_data: vec![],
+            circuit_id: "health-age-threshold-v1".to_string(),
+            typically_requires_attestation: false,
+            default_validity_days: 365,
+            use_cases: vec![
+                "Over-18 checks".to_string(),
+                "Age-restricted services".to_string(),
+            ],
+            created_by: None,
+            system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: Some(StatementSchema {
+                statement: "age_in_years >= {min_age}".to_string(),
+                parameters: vec![StatementParameter {
+                    name: "min_age".to_string(),
+                    value_type: StatementValueType::Integer,
+                    description: "Minimum age in whole years".to_string(),
+                }],
+            }),
+        },
+        ProofTemplate {
+            template_id: "VACCINATED-AGAINST".to_string(),
+            name: "Vaccinated Against".to_string(),
+            description: "Proves a completed vaccination for a given vaccine without revealing other immunizations".to_string(),
+            proof_type: HealthProofType::VaccinationStatus,
+            required_data: vec![RequiredDataCategory::Immunizations],
+            circuit_id: "health-vaccination-v1".to_string(),
+            typically_requires_attestation: true,
+            default_validity_days: 365,
+            use_cases: vec![
+                "School or employment vaccination requirements".to_string(),
+                "International travel".to_string(),
+            ],
+            created_by: None,
+            system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: Some(StatementSchema {
+                statement: "completed immunization with CVX {vaccine_cvx} administered on or before {as_of}".to_string(),
+                parameters: vec![
+                    StatementParameter {
+                        name: "vaccine_cvx".to_string(),
+                        value_type: StatementValueType::Code,
+                        description: "CDC CVX vaccine code".to_string(),
+                    },
+                    StatementParameter {
+                        name: "as_of".to_string(),
+                        value_type: StatementValueType::Date,
+                        description: "Latest acceptable administration date".to_string(),
+                    },
+                ],
+            }),
+        },
+        ProofTemplate {
+            template_id: "HBA1C-BELOW".to_string(),
+            name: "HbA1c Below Threshold".to_string(),
+            description: "Proves the most recent HbA1c is under a threshold without revealing the value".to_string(),
+            proof_type: HealthProofType::LabThreshold,
+            required_data: vec![RequiredDataCategory::LabResults(vec!["4548-4".to_string()])],
+            circuit_id: "health-lab-threshold-v1".to_string(),
+            typically_requires_attestation: false,
+            default_validity_days: 90,
+            use_cases: vec![
+                "Diabetes management programs".to_string(),
+                "Wellness incentives".to_string(),
+            ],
+            created_by: None,
+            system_template: true,
+            template_version: 1,
+            circuit_version: "1.0.0".to_string(),
+            statement: Some(StatementSchema {
+                statement: "latest LOINC 4548-4 result < {threshold_percent}% and collected within {max_age_days} days".to_string(),
+                parameters: vec![
+                    StatementParameter {
+                        name: "threshold_percent".to_string(),
+                        value_type: StatementValueType::Decimal,
+                        description: "Upper bound on HbA1c in percent".to_string(),
+                    },
+                    StatementParameter {
+                        name: "max_age_days".to_string(),
+                        value_type: StatementValueType::Integer,
+                        description: "Maximum age of the result in days".to_string(),
+                    },
+                ],
+            }),
         },
     ];
 
     let mut hashes = Vec::new();
     for template in system_templates {
-        let hash = create_entry(&EntryTypes::ProofTemplate(template))?;
-        hashes.push(hash);
+        // Re-running initialization skips templates that are already published
+        let exists = get_template_versions(&template.template_id)?
+            .iter()
+            .any(|(_, t)| t.template_version == template.template_version);
+        if !exists {
+            hashes.push(publish_template(template)?);
+        }
     }
 
     Ok(hashes)
@@ -41,6 +41,9 @@ pub enum LinkTypes {
     VerifierToRequests,
     ProofToVerifications,
     TemplateToProofs,
+    ProofTemplates,
+    TemplateIdToTemplates,
+    ProofTypeToTemplates,
     TrustedAttestors,
     ActiveRequests,
     CompletedProofs,
@@ -81,6 +84,28 @@ pub struct HealthProof {
     pub revocation_reason: Option<String>,
     /// Generation timestamp
     pub generated_at: i64,
+    /// Template whose statement this proof attests to
+    #[serde(default)]
+    pub template: Option<Box<TemplateCitation>>,
+}
+
+/// Reference from a proof to the exact template statement it proves
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct TemplateCitation {
+    pub template_hash: ActionHash,
+    pub template_id: String,
+    pub template_version: u32,
+    pub circuit_id: String,
+    pub circuit_version: String,
+    /// Values bound to the template's public parameters
+    pub parameters: Vec<StatementBinding>,
+}
+
+/// A public parameter value fixed at proof generation
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatementBinding {
+    pub name: String,
+    pub value: String,
 }
 
 /// Types of health proofs
@@ -319,12 +344,22 @@ pub struct VerificationDetails {
     pub not_revoked: bool,
     /// Data recency acceptable
     pub data_recency_ok: bool,
+    /// Proof matches the template it cites (true when no template is cited)
+    #[serde(default = "default_true")]
+    pub template_consistent: bool,
+    /// Statement the proof attests to, rendered from its template
+    #[serde(default)]
+    pub proven_statement: Option<String>,
     /// Failure reason if any
     pub failure_reason: Option<String>,
     /// Verification time (milliseconds)
     pub verification_time_ms: u64,
 }
 
+fn default_true() -> bool {
+    true
+}
+
 // ==================== TRUSTED ATTESTORS ====================
 
 /// Registered trusted attestor
@@ -379,6 +414,81 @@ pub struct ProofTemplate {
     pub created_by: Option<AgentPubKey>,
     /// System template (vs user-created)
     pub system_template: bool,
+    /// Version of this template; publish a new version rather than editing
+    #[serde(default = "default_template_version")]
+    pub template_version: u32,
+    /// Version of the circuit identified by `circuit_id`
+    #[serde(default)]
+    pub circuit_version: String,
+    /// Exact statement a proof from this template attests to
+    #[serde(default)]
+    pub statement: Option<StatementSchema>,
+}
+
+fn default_template_version() -> u32 {
+    1
+}
+
+/// Standardized statement with named public parameters,
+/// e.g. "age_years >= {min_age}"
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatementSchema {
+    /// Statement text; `{name}` placeholders refer to `parameters`
+    pub statement: String,
+    pub parameters: Vec<StatementParameter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct StatementParameter {
+    pub name: String,
+    pub value_type: StatementValueType,
+    pub description: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum StatementValueType {
+    Integer,
+    Decimal,
+    Text,
+    /// Terminology code (LOINC, CVX, ICD-10, ...)
+    Code,
+    /// ISO 8601 date
+    Date,
+}
+
+impl StatementSchema {
+    /// Render the statement with bound parameter values
+    pub fn render(&self, bindings: &[StatementBinding]) -> String {
+        bindings.iter().fold(self.statement.clone(), |text, b| {
+            text.replace(&format!("{{{}}}", b.name), &b.value)
+        })
+    }
+
+    /// Check that bindings supply exactly the declared parameters with well-typed values
+    pub fn check_bindings(&self, bindings: &[StatementBinding]) -> Result<(), String> {
+        for param in &self.parameters {
+            let binding = bindings
+                .iter()
+                .find(|b| b.name == param.name)
+                .ok_or(format!("Missing value for parameter '{}'", param.name))?;
+            let well_typed = match param.value_type {
+                StatementValueType::Integer => binding.value.parse::<i64>().is_ok(),
+                StatementValueType::Decimal => binding.value.parse::<f64>().is_ok(),
+                StatementValueType::Date => binding.value.len() >= 10 && binding.value.as_bytes()[4] == b'-',
+                StatementValueType::Text | StatementValueType::Code => !binding.value.is_empty(),
+            };
+            if !well_typed {
+                return Err(format!(
+                    "Value '{}' is not a valid {:?} for parameter '{}'",
+                    binding.value, param.value_type, param.name
+                ));
+            }
+        }
+        if let Some(extra) = bindings.iter().find(|b| !self.parameters.iter().any(|p| p.name == b.name)) {
+            return Err(format!("Unknown parameter '{}'", extra.name));
+        }
+        Ok(())
+    }
 }
 
 /// Data categories needed for a proof
@@ -514,6 +624,25 @@ pub fn validate_proof_template(template: &ProofTemplate) -> ExternResult<Validat
         return Ok(ValidateCallbackResult::Invalid("Validity days must be positive".to_string()));
     }
 
+    if let Some(schema) = &template.statement {
+        if template.circuit_version.is_empty() {
+            return Ok(ValidateCallbackResult::Invalid("Circuit version required for templated statements".to_string()));
+        }
+        if schema.statement.is_empty() {
+            return Ok(ValidateCallbackResult::Invalid("Statement text required".to_string()));
+        }
+        for (i, param) in schema.parameters.iter().enumerate() {
+            if param.name.is_empty() || schema.parameters[..i].iter().any(|p| p.name == param.name) {
+                return Ok(ValidateCallbackResult::Invalid("Statement parameter names must be unique and non-empty".to_string()));
+            }
+            if !schema.statement.contains(&format!("{{{}}}", param.name)) {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Statement does not reference parameter '{}'", param.name
+                )));
+            }
+        }
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
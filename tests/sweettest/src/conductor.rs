@@ -0,0 +1,107 @@
+//! Zome call transport
+//!
+//! Scenarios only see `ZomeCaller`, so they run unchanged against the
+//! in-process conductor below or any other transport (an app websocket,
+//! a tryorama player) that can make a zome call as one agent.
+
+use anyhow::Result;
+use holochain::conductor::config::ConductorConfig;
+use holochain::conductor::{Conductor, ConductorBuilder};
+use holochain::prelude::*;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fmt::Debug;
+use std::future::Future;
+use std::path::PathBuf;
+
+/// One agent's view of the health DNA
+pub trait ZomeCaller {
+    fn agent_pubkey(&self) -> AgentPubKey;
+
+    fn call<I, O>(&self, zome: &str, function: &str, input: I) -> impl Future<Output = Result<O>>
+    where
+        I: Serialize + Debug + Send + 'static,
+        O: DeserializeOwned + Debug;
+}
+
+/// Path to the built DNA file
+pub fn dna_path() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("../../workdir/health.dna")
+}
+
+/// Conductor with one health cell per named agent
+pub struct TestNetwork {
+    pub conductor: Conductor,
+    pub cells: Vec<CellId>,
+}
+
+impl TestNetwork {
+    pub async fn with_agents(names: &[&str]) -> Result<Self> {
+        let conductor = ConductorBuilder::new()
+            .config(ConductorConfig::default())
+            .build()
+            .await?;
+
+        let dna_file = DnaFile::from_file_content(&std::fs::read(dna_path())?).await?;
+        let dna_hash = conductor.register_dna(dna_file).await?;
+
+        let mut cells = Vec::new();
+        for name in names {
+            let agent_key = conductor
+                .keystore()
+                .generate_new_sign_keypair_random()
+                .await?;
+            let cell_id = conductor
+                .install_app(
+                    format!("workflow-{}", name),
+                    vec![InstalledCell::new(
+                        CellId::new(dna_hash.clone(), agent_key),
+                        "health".into(),
+                    )],
+                )
+                .await?
+                .into_iter()
+                .next()
+                .unwrap()
+                .into_id();
+            cells.push(cell_id);
+        }
+
+        Ok(Self { conductor, cells })
+    }
+
+    /// Caller for the agent installed at `index`
+    pub fn agent(&self, index: usize) -> ConductorAgent<'_> {
+        ConductorAgent {
+            conductor: &self.conductor,
+            cell_id: self.cells[index].clone(),
+        }
+    }
+}
+
+pub struct ConductorAgent<'a> {
+    pub conductor: &'a Conductor,
+    pub cell_id: CellId,
+}
+
+impl ZomeCaller for ConductorAgent<'_> {
+    fn agent_pubkey(&self) -> AgentPubKey {
+        self.cell_id.agent_pubkey().clone()
+    }
+
+    async fn call<I, O>(&self, zome: &str, function: &str, input: I) -> Result<O>
+    where
+        I: Serialize + Debug + Send + 'static,
+        O: DeserializeOwned + Debug,
+    {
+        Ok(self.conductor.call_zome(&self.cell_id, zome, function, input).await?)
+    }
+}
+
+/// Decode the app entry of a record into a mirror type
+pub fn decode_entry<T: DeserializeOwned>(record: &Record) -> Result<T> {
+    match record.entry().as_option() {
+        Some(Entry::App(bytes)) => Ok(rmp_serde::from_slice(bytes.as_ref().bytes())?),
+        _ => anyhow::bail!("record {} has no app entry", record.action_address()),
+    }
+}
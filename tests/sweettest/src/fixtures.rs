@@ -0,0 +1,94 @@
+//! Fixture records
+//!
+//! Each fixture is one clinical record the records zome writes for the
+//! scenario's patient, in a single data category so consent scopes and
+//! category reads line up with it.
+
+use holochain::prelude::*;
+
+use crate::types::*;
+
+/// Patient id of the fixture patient
+pub const FIXTURE_PATIENT_ID: &str = "wf-patient-001";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Fixture {
+    /// A set of normal vital signs
+    Vitals,
+    /// A resulted hemoglobin A1c
+    LabResult,
+}
+
+impl Fixture {
+    pub const ALL: [Fixture; 2] = [Fixture::Vitals, Fixture::LabResult];
+
+    /// Category the record is written and read under
+    pub fn category(self) -> DataCategory {
+        match self {
+            Fixture::Vitals => DataCategory::VitalSigns,
+            Fixture::LabResult => DataCategory::LabResults,
+        }
+    }
+
+    /// Records zome function that writes the fixture
+    pub fn create_function(self) -> &'static str {
+        match self {
+            Fixture::Vitals => "record_vital_signs",
+            Fixture::LabResult => "create_lab_result",
+        }
+    }
+}
+
+/// Vital signs for `patient_hash`, recorded by `author`
+pub fn vitals(patient_hash: ActionHash, author: AgentPubKey) -> RecordVitalSignsInput {
+    RecordVitalSignsInput {
+        vitals: VitalSigns {
+            patient_hash,
+            encounter_hash: None,
+            recorded_at: Timestamp::now(),
+            recorded_by: author,
+            temperature_celsius: Some(36.8),
+            heart_rate_bpm: Some(72),
+            blood_pressure_systolic: Some(118),
+            blood_pressure_diastolic: Some(76),
+            respiratory_rate: Some(14),
+            oxygen_saturation: Some(98.0),
+            height_cm: None,
+            weight_kg: None,
+            bmi: None,
+            pain_level: Some(0),
+            notes: None,
+        },
+        is_emergency: false,
+        emergency_reason: None,
+    }
+}
+
+/// A lab result for `patient_hash`, ordered by `author`
+pub fn lab_result(patient_hash: ActionHash, author: AgentPubKey) -> CreateLabResultInput {
+    let now = Timestamp::now();
+    CreateLabResultInput {
+        lab_result: LabResult {
+            result_id: "WF-LAB-001".to_string(),
+            patient_hash,
+            encounter_hash: None,
+            ordering_provider: author,
+            loinc_code: "4548-4".to_string(),
+            test_name: "Hemoglobin A1c".to_string(),
+            value: "5.4".to_string(),
+            unit: "%".to_string(),
+            reference_range: "4.0-5.6".to_string(),
+            interpretation: LabInterpretation::Normal,
+            specimen_type: "Blood".to_string(),
+            collection_time: now,
+            result_time: now,
+            performing_lab: "Workflow Lab".to_string(),
+            notes: None,
+            is_critical: false,
+            acknowledged_by: None,
+            acknowledged_at: None,
+        },
+        is_emergency: false,
+        emergency_reason: None,
+    }
+}
@@ -0,0 +1,136 @@
+//! Cross-zome invariants
+//!
+//! Each check reads from the zome that owns the evidence (consent for access
+//! logs and audit coverage, records for the patient's indexes) rather than
+//! trusting the return value of the call that should have produced it.
+
+use anyhow::{ensure, Result};
+use holochain::prelude::*;
+
+use crate::conductor::{decode_entry, ZomeCaller};
+use crate::fixtures::Fixture;
+use crate::scenario::ScenarioOutcome;
+use crate::types::*;
+
+/// Access logs on the patient's record, read as the patient page by page
+pub async fn access_logs<C: ZomeCaller>(patient: &C, patient_hash: &ActionHash) -> Result<Vec<DataAccessLog>> {
+    let mut records: Vec<Record> = Vec::new();
+    let mut after = None;
+    loop {
+        let input = GetAccessLogsInput {
+            patient_hash: patient_hash.clone(),
+            page: CursorInput { after, limit: 100 },
+        };
+        let page: CursorPage<Record> = patient.call("consent", "get_access_logs", input).await?;
+        records.extend(page.items);
+        match page.next_cursor {
+            Some(cursor) => after = Some(cursor),
+            None => break,
+        }
+    }
+    records.iter().map(decode_entry).collect()
+}
+
+/// Every record the scenario wrote is indexed from the patient under its
+/// fixture's category
+pub async fn assert_records_indexed<C: ZomeCaller>(patient: &C, outcome: &ScenarioOutcome) -> Result<()> {
+    let inventory: Vec<RecordInventoryItem> = patient
+        .call("records", "get_patient_record_inventory", outcome.patient_hash()?)
+        .await?;
+    for (fixture, record_hash) in &outcome.records {
+        ensure!(
+            inventory
+                .iter()
+                .any(|item| &item.record_hash == record_hash && item.category == fixture.category()),
+            "{:?} record {} is not indexed from the patient",
+            fixture,
+            record_hash
+        );
+    }
+    Ok(())
+}
+
+/// A successful read by `accessor` left a read entry in the access log
+pub async fn assert_read_logged<C: ZomeCaller>(
+    patient: &C,
+    outcome: &ScenarioOutcome,
+    accessor: &AgentPubKey,
+) -> Result<()> {
+    let logs = access_logs(patient, &outcome.patient_hash()?).await?;
+    ensure!(
+        logs.iter()
+            .any(|log| &log.accessor == accessor && log.access_type == DataPermission::Read),
+        "no read by {} in the access log",
+        accessor
+    );
+    Ok(())
+}
+
+/// Every logged access by `accessor` was made under a consent, not an override
+pub async fn assert_accesses_consented<C: ZomeCaller>(
+    patient: &C,
+    outcome: &ScenarioOutcome,
+    accessor: &AgentPubKey,
+) -> Result<()> {
+    for log in access_logs(patient, &outcome.patient_hash()?).await? {
+        if &log.accessor != accessor {
+            continue;
+        }
+        ensure!(
+            log.consent_hash.is_some() && !log.emergency_override,
+            "access at {:?} by {} has no consent behind it",
+            log.accessed_at,
+            accessor
+        );
+    }
+    Ok(())
+}
+
+/// No authorized read since the scenario started is missing from the
+/// audit trail; `auditor` must hold the auditor role
+pub async fn assert_reads_audited<C: ZomeCaller>(auditor: &C, outcome: &ScenarioOutcome) -> Result<()> {
+    let report: AuditCoverageReport = auditor
+        .call(
+            "consent",
+            "get_audit_coverage_report",
+            AuditCoverageInput {
+                since: outcome.started_at.unwrap_or(Timestamp::from_micros(0)),
+                match_window_seconds: None,
+            },
+        )
+        .await?;
+    ensure!(
+        report.unlogged.is_empty(),
+        "{} of {} authorized reads were never logged",
+        report.unlogged.len(),
+        report.authorized_reads
+    );
+    Ok(())
+}
+
+/// The read at `index` succeeded and returned the record the scenario
+/// wrote for `fixture`
+pub fn assert_read_returned(outcome: &ScenarioOutcome, index: usize, fixture: Fixture) -> Result<()> {
+    let (_, result) = &outcome.reads[index];
+    let records = match result {
+        Ok(records) => records,
+        Err(e) => anyhow::bail!("read #{} was refused: {}", index, e),
+    };
+    let found = outcome
+        .records
+        .iter()
+        .filter(|(written, _)| *written == fixture)
+        .any(|(_, hash)| records.iter().any(|record| record.action_address() == hash));
+    ensure!(found, "read #{} did not return the {:?} record", index, fixture);
+    Ok(())
+}
+
+/// The read at `index` was refused
+pub fn assert_read_refused(outcome: &ScenarioOutcome, index: usize) -> Result<()> {
+    ensure!(
+        outcome.reads[index].1.is_err(),
+        "read #{} should have been refused",
+        index
+    );
+    Ok(())
+}
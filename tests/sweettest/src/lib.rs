@@ -0,0 +1,31 @@
+//! Workflow Test Harness
+//!
+//! Shared pieces for sweettest suites that cover flows spanning several
+//! zomes (record → consent → read → audit):
+//! - `conductor`: the `ZomeCaller` transport and an in-process test network
+//! - `fixtures`: clinical records the records zome writes for the patient
+//! - `scenario`: a builder that runs workflow steps as different agents
+//! - `invariants`: checks that read the evidence a workflow should leave
+//!
+//! ```rust,ignore
+//! let network = TestNetwork::with_agents(&["patient", "clinician"]).await?;
+//! let agents = [network.agent(0), network.agent(1)];
+//! let outcome = Scenario::new()
+//!     .create_patient(0)
+//!     .record(0, Fixture::Vitals)
+//!     .grant_consent(1, vec![DataCategory::VitalSigns], vec![DataPermission::Read])
+//!     .read(1, DataCategory::VitalSigns)
+//!     .run(&agents)
+//!     .await?;
+//! invariants::assert_read_logged(&agents[0], &outcome, &agents[1].agent_pubkey()).await?;
+//! ```
+
+pub mod conductor;
+pub mod fixtures;
+pub mod invariants;
+pub mod scenario;
+pub mod types;
+
+pub use conductor::{decode_entry, ConductorAgent, TestNetwork, ZomeCaller};
+pub use fixtures::Fixture;
+pub use scenario::{Scenario, ScenarioOutcome};
@@ -0,0 +1,203 @@
+//! Scenario builder for multi-zome workflows
+//!
+//! A scenario is a list of steps run in order by agents identified by their
+//! position in the caller slice. Steps that are expected to be refused
+//! (reads without consent) record the refusal instead of failing the run,
+//! so tests can assert on both outcomes.
+
+use anyhow::{anyhow, Result};
+use holochain::prelude::*;
+
+use crate::conductor::ZomeCaller;
+use crate::fixtures::{self, Fixture};
+use crate::types::*;
+
+#[derive(Clone, Debug)]
+enum Step {
+    CreatePatient { owner: usize },
+    Record { agent: usize, fixture: Fixture },
+    GrantConsent { grantee: usize, categories: Vec<DataCategory>, permissions: Vec<DataPermission> },
+    RevokeConsent { consent: usize },
+    Read { agent: usize, category: DataCategory },
+}
+
+/// What a scenario produced, in step order
+#[derive(Debug, Default)]
+pub struct ScenarioOutcome {
+    pub patient_hash: Option<ActionHash>,
+    /// Agent index that owns the patient record
+    pub owner: usize,
+    pub consents: Vec<ActionHash>,
+    /// Each fixture written and the action that wrote it
+    pub records: Vec<(Fixture, ActionHash)>,
+    /// Each category read with the agent that asked for it; refusals keep
+    /// the error
+    pub reads: Vec<(usize, Result<Vec<Record>, String>)>,
+    /// When the scenario started, for audit windows
+    pub started_at: Option<Timestamp>,
+}
+
+impl ScenarioOutcome {
+    pub fn patient_hash(&self) -> Result<ActionHash> {
+        self.patient_hash
+            .clone()
+            .ok_or_else(|| anyhow!("scenario did not create a patient"))
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Scenario {
+    steps: Vec<Step>,
+}
+
+impl Scenario {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The agent at `owner` creates the fixture patient
+    pub fn create_patient(mut self, owner: usize) -> Self {
+        self.steps.push(Step::CreatePatient { owner });
+        self
+    }
+
+    /// The agent at `agent` writes the fixture record for the patient
+    pub fn record(mut self, agent: usize, fixture: Fixture) -> Self {
+        self.steps.push(Step::Record { agent, fixture });
+        self
+    }
+
+    /// The patient owner grants `grantee` an active treatment consent
+    pub fn grant_consent(mut self, grantee: usize, categories: Vec<DataCategory>, permissions: Vec<DataPermission>) -> Self {
+        self.steps.push(Step::GrantConsent { grantee, categories, permissions });
+        self
+    }
+
+    /// The patient owner revokes the `consent`-th consent granted so far
+    pub fn revoke_consent(mut self, consent: usize) -> Self {
+        self.steps.push(Step::RevokeConsent { consent });
+        self
+    }
+
+    /// The agent at `agent` reads every patient record in `category`
+    pub fn read(mut self, agent: usize, category: DataCategory) -> Self {
+        self.steps.push(Step::Read { agent, category });
+        self
+    }
+
+    pub fn step_count(&self) -> usize {
+        self.steps.len()
+    }
+
+    pub async fn run<C: ZomeCaller>(self, agents: &[C]) -> Result<ScenarioOutcome> {
+        let mut outcome = ScenarioOutcome {
+            started_at: Some(Timestamp::now()),
+            ..Default::default()
+        };
+
+        for step in self.steps {
+            match step {
+                Step::CreatePatient { owner } => {
+                    let record: Record = agents[owner]
+                        .call("patient", "create_patient", Patient::fixture())
+                        .await?;
+                    outcome.patient_hash = Some(record.action_address().clone());
+                    outcome.owner = owner;
+                }
+                Step::Record { agent, fixture } => {
+                    let patient_hash = outcome.patient_hash()?;
+                    let author = agents[agent].agent_pubkey();
+                    let function = fixture.create_function();
+                    let record: Record = match fixture {
+                        Fixture::Vitals => {
+                            agents[agent]
+                                .call("records", function, fixtures::vitals(patient_hash, author))
+                                .await?
+                        }
+                        Fixture::LabResult => {
+                            agents[agent]
+                                .call("records", function, fixtures::lab_result(patient_hash, author))
+                                .await?
+                        }
+                    };
+                    outcome.records.push((fixture, record.action_address().clone()));
+                }
+                Step::GrantConsent { grantee, categories, permissions } => {
+                    let consent = Consent {
+                        consent_id: format!("WF-CONSENT-{}", outcome.consents.len() + 1),
+                        patient_hash: outcome.patient_hash()?,
+                        grantee: ConsentGrantee::Agent(agents[grantee].agent_pubkey()),
+                        scope: ConsentScope {
+                            data_categories: categories,
+                            date_range: None,
+                            encounter_hashes: None,
+                            exclusions: vec![],
+                        },
+                        permissions,
+                        purpose: ConsentPurpose::Treatment,
+                        status: ConsentStatus::Active,
+                        granted_at: Timestamp::now(),
+                        expires_at: None,
+                        revoked_at: None,
+                        revocation_reason: None,
+                        document_hash: None,
+                        witness: None,
+                        legal_representative: None,
+                        notes: None,
+                    };
+                    let record: Record = agents[outcome.owner]
+                        .call("consent", "create_consent", consent)
+                        .await?;
+                    outcome.consents.push(record.action_address().clone());
+                }
+                Step::RevokeConsent { consent } => {
+                    let consent_hash = outcome
+                        .consents
+                        .get(consent)
+                        .cloned()
+                        .ok_or_else(|| anyhow!("no consent #{} to revoke", consent))?;
+                    let _: Record = agents[outcome.owner]
+                        .call(
+                            "consent",
+                            "revoke_consent",
+                            RevokeConsentInput { consent_hash, reason: "Workflow test".to_string() },
+                        )
+                        .await?;
+                }
+                Step::Read { agent, category } => {
+                    let result = read_category(&agents[agent], outcome.patient_hash()?, category).await;
+                    outcome.reads.push((agent, result.map_err(|e| e.to_string())));
+                }
+            }
+        }
+
+        Ok(outcome)
+    }
+}
+
+/// Every record in `category`, following continuations; each chunk is
+/// authorized and logged on its own
+async fn read_category<C: ZomeCaller>(
+    agent: &C,
+    patient_hash: ActionHash,
+    category: DataCategory,
+) -> Result<Vec<Record>> {
+    let mut records = Vec::new();
+    let mut continuation = None;
+    loop {
+        let input = GetPatientRecordsChunkInput {
+            patient_hash: patient_hash.clone(),
+            category: category.clone(),
+            is_emergency: false,
+            emergency_reason: None,
+            chunk: BatchChunkInput { continuation, chunk_size: None },
+        };
+        let chunk: BatchChunk = agent.call("records", "get_patient_records_chunk", input).await?;
+        records.extend(chunk.result.records);
+        continuation = chunk.continuation;
+        if continuation.is_none() {
+            break;
+        }
+    }
+    Ok(records)
+}
@@ -0,0 +1,441 @@
+//! Type definitions matching the zome types the workflows touch
+//!
+//! Only the fields a workflow reads are mirrored on output types; fields
+//! the zomes default with `#[serde(default)]` are left out of inputs.
+
+use holochain::prelude::*;
+use serde::{Deserialize, Serialize};
+use serde_json::Value as JsonValue;
+
+// ============================================================================
+// Patient zome
+// ============================================================================
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum BiologicalSex {
+    Male,
+    Female,
+    Intersex,
+    Unknown,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ContactInfo {
+    pub address_line1: Option<String>,
+    pub address_line2: Option<String>,
+    pub city: Option<String>,
+    pub state_province: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: String,
+    pub phone_primary: Option<String>,
+    pub phone_secondary: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Patient {
+    pub patient_id: String,
+    pub mrn: Option<String>,
+    pub first_name: String,
+    pub last_name: String,
+    pub date_of_birth: String,
+    pub biological_sex: BiologicalSex,
+    pub gender_identity: Option<String>,
+    pub blood_type: Option<JsonValue>,
+    pub contact: ContactInfo,
+    pub emergency_contact: Option<JsonValue>,
+    pub primary_language: String,
+    pub allergies: Vec<JsonValue>,
+    pub conditions: Vec<String>,
+    pub medications: Vec<String>,
+    pub mycelix_identity_hash: Option<ActionHash>,
+    pub matl_trust_score: f64,
+    pub created_at: Timestamp,
+    pub updated_at: Timestamp,
+}
+
+impl Patient {
+    /// The patient the fixture bundles describe
+    pub fn fixture() -> Self {
+        Patient {
+            patient_id: crate::fixtures::FIXTURE_PATIENT_ID.to_string(),
+            mrn: Some("MRN-WF-001".to_string()),
+            first_name: "Alice".to_string(),
+            last_name: "Workflow".to_string(),
+            date_of_birth: "1990-01-15".to_string(),
+            biological_sex: BiologicalSex::Female,
+            gender_identity: None,
+            blood_type: None,
+            contact: ContactInfo {
+                address_line1: None,
+                address_line2: None,
+                city: None,
+                state_province: None,
+                postal_code: None,
+                country: "US".to_string(),
+                phone_primary: None,
+                phone_secondary: None,
+                email: None,
+            },
+            emergency_contact: None,
+            primary_language: "en".to_string(),
+            allergies: vec![],
+            conditions: vec![],
+            medications: vec![],
+            mycelix_identity_hash: None,
+            matl_trust_score: 0.9,
+            created_at: Timestamp::from_micros(0),
+            updated_at: Timestamp::from_micros(0),
+        }
+    }
+}
+
+// ============================================================================
+// Consent zome
+// ============================================================================
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum DataCategory {
+    Demographics,
+    Allergies,
+    Medications,
+    Diagnoses,
+    Procedures,
+    LabResults,
+    ImagingStudies,
+    VitalSigns,
+    Immunizations,
+    MentalHealth,
+    SubstanceAbuse,
+    SexualHealth,
+    GeneticData,
+    FinancialData,
+    All,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum DataPermission {
+    Read,
+    Write,
+    Share,
+    Export,
+    Delete,
+    Amend,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ConsentPurpose {
+    Treatment,
+    Payment,
+    HealthcareOperations,
+    Research,
+    PublicHealth,
+    LegalProceeding,
+    Marketing,
+    FamilyNotification,
+    Other(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ConsentStatus {
+    Active,
+    Expired,
+    Revoked,
+    Pending,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ConsentGrantee {
+    Provider(ActionHash),
+    Organization(String),
+    Agent(AgentPubKey),
+    ResearchStudy(ActionHash),
+    InsuranceCompany(ActionHash),
+    EmergencyAccess,
+    Public,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConsentScope {
+    pub data_categories: Vec<DataCategory>,
+    pub date_range: Option<JsonValue>,
+    pub encounter_hashes: Option<Vec<ActionHash>>,
+    pub exclusions: Vec<DataCategory>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Consent {
+    pub consent_id: String,
+    pub patient_hash: ActionHash,
+    pub grantee: ConsentGrantee,
+    pub scope: ConsentScope,
+    pub permissions: Vec<DataPermission>,
+    pub purpose: ConsentPurpose,
+    pub status: ConsentStatus,
+    pub granted_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+    pub revoked_at: Option<Timestamp>,
+    pub revocation_reason: Option<String>,
+    pub document_hash: Option<EntryHash>,
+    pub witness: Option<AgentPubKey>,
+    pub legal_representative: Option<AgentPubKey>,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RevokeConsentInput {
+    pub consent_hash: ActionHash,
+    pub reason: String,
+}
+
+/// Access log fields the audit invariants check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DataAccessLog {
+    pub patient_hash: ActionHash,
+    pub accessor: AgentPubKey,
+    pub access_type: DataPermission,
+    pub data_categories_accessed: Vec<DataCategory>,
+    pub consent_hash: Option<ActionHash>,
+    pub accessed_at: Timestamp,
+    pub emergency_override: bool,
+}
+
+/// Input to `get_access_logs`; `page` is left at its default limit
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetAccessLogsInput {
+    pub patient_hash: ActionHash,
+    pub page: CursorInput,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CursorInput {
+    pub after: Option<String>,
+    pub limit: usize,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CursorPage<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditCoverageInput {
+    pub since: Timestamp,
+    pub match_window_seconds: Option<i64>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UnloggedAccess {
+    pub patient_hash: ActionHash,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AuditCoverageReport {
+    pub since: Timestamp,
+    pub authorized_reads: u32,
+    pub logged_reads: u32,
+    pub unlogged: Vec<UnloggedAccess>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum TemplateType {
+    System,
+    Organization(String),
+    Personal,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CareTeamTemplate {
+    pub template_id: String,
+    pub name: String,
+    pub description: String,
+    pub permissions: Vec<DataPermission>,
+    pub data_categories: Vec<DataCategory>,
+    pub default_exclusions: Vec<DataCategory>,
+    pub purpose: ConsentPurpose,
+    pub default_duration_days: Option<u32>,
+    pub template_type: TemplateType,
+    pub created_by: AgentPubKey,
+    pub created_at: Timestamp,
+    pub active: bool,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CareTeamMemberType {
+    Provider(ActionHash),
+    Organization(String),
+    Agent(AgentPubKey),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CareTeamRole {
+    PrimaryCarePhysician,
+    Specialist,
+    Nurse,
+    NursePractitioner,
+    PhysicianAssistant,
+    Pharmacist,
+    CaseManager,
+    SocialWorker,
+    Therapist,
+    Dietitian,
+    PhysicalTherapist,
+    AdministrativeStaff,
+    BillingSpecialist,
+    Other(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CareTeamMember {
+    pub member: CareTeamMemberType,
+    pub role: CareTeamRole,
+    pub joined_at: Timestamp,
+    pub active: bool,
+    pub permission_overrides: Option<Vec<DataPermission>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CareTeamRoleOverride {
+    pub role: CareTeamRole,
+    pub permissions: Vec<DataPermission>,
+    pub data_categories: Vec<DataCategory>,
+}
+
+/// Input to `create_care_team_from_template`; overrides are set afterwards
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateCareTeamInput {
+    pub team_id: String,
+    pub patient_hash: ActionHash,
+    pub template_hash: ActionHash,
+    pub team_name: Option<String>,
+    pub members: Vec<CareTeamMember>,
+    pub additional_exclusions: Option<Vec<DataCategory>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SetRoleOverridesInput {
+    pub team_hash: ActionHash,
+    pub role_overrides: Vec<CareTeamRoleOverride>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CareTeamAuthInput {
+    pub patient_hash: ActionHash,
+    pub member: CareTeamMemberType,
+    pub permission: DataPermission,
+    pub data_category: DataCategory,
+}
+
+/// Care team authorization fields the workflows check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CareTeamAuthResult {
+    pub authorized: bool,
+    pub reason: String,
+}
+
+// ============================================================================
+// Records zome
+// ============================================================================
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct VitalSigns {
+    pub patient_hash: ActionHash,
+    pub encounter_hash: Option<ActionHash>,
+    pub recorded_at: Timestamp,
+    pub recorded_by: AgentPubKey,
+    pub temperature_celsius: Option<f64>,
+    pub heart_rate_bpm: Option<u32>,
+    pub blood_pressure_systolic: Option<u32>,
+    pub blood_pressure_diastolic: Option<u32>,
+    pub respiratory_rate: Option<u32>,
+    pub oxygen_saturation: Option<f64>,
+    pub height_cm: Option<f64>,
+    pub weight_kg: Option<f64>,
+    pub bmi: Option<f64>,
+    pub pain_level: Option<u8>,
+    pub notes: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordVitalSignsInput {
+    pub vitals: VitalSigns,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum LabInterpretation {
+    Normal,
+    Abnormal,
+    High,
+    Low,
+    Critical,
+    Inconclusive,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LabResult {
+    pub result_id: String,
+    pub patient_hash: ActionHash,
+    pub encounter_hash: Option<ActionHash>,
+    pub ordering_provider: AgentPubKey,
+    pub loinc_code: String,
+    pub test_name: String,
+    pub value: String,
+    pub unit: String,
+    pub reference_range: String,
+    pub interpretation: LabInterpretation,
+    pub specimen_type: String,
+    pub collection_time: Timestamp,
+    pub result_time: Timestamp,
+    pub performing_lab: String,
+    pub notes: Option<String>,
+    pub is_critical: bool,
+    pub acknowledged_by: Option<AgentPubKey>,
+    pub acknowledged_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CreateLabResultInput {
+    pub lab_result: LabResult,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Inventory fields the record invariants check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RecordInventoryItem {
+    pub record_hash: ActionHash,
+    pub category: DataCategory,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BatchChunkInput {
+    pub continuation: Option<String>,
+    pub chunk_size: Option<usize>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct GetPatientRecordsChunkInput {
+    pub patient_hash: ActionHash,
+    pub category: DataCategory,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+    pub chunk: BatchChunkInput,
+}
+
+/// Batch result fields the read invariants check
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchGetResult {
+    pub records: Vec<Record>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BatchChunk {
+    pub result: BatchGetResult,
+    pub continuation: Option<String>,
+    pub total: usize,
+}
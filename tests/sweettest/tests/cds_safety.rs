@@ -8,7 +8,7 @@
 //!
 //! ```bash
 //! nix develop
-//! cargo test -p hdc-genetics-sweettest --test cds_safety
+//! cargo test -p mycelix-health-sweettest --test cds_safety
 //! ```
 
 use anyhow::Result;
@@ -7,7 +7,7 @@
 //!
 //! ```bash
 //! nix develop
-//! cargo test -p hdc-genetics-sweettest --test fhir_integration
+//! cargo test -p mycelix-health-sweettest --test fhir_integration
 //! ```
 
 use anyhow::Result;
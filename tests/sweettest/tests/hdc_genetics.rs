@@ -12,7 +12,7 @@
 //! ```bash
 //! # Requires nix develop environment with Holochain
 //! nix develop
-//! cargo test -p hdc-genetics-sweettest
+//! cargo test -p mycelix-health-sweettest
 //! ```
 //!
 //! # Prerequisites
@@ -558,6 +558,6 @@ fn test_coverage_summary() {
     println!("  - Non-invertibility verification");
     println!("  - Data representation privacy");
     println!();
-    println!("Run with: cargo test -p hdc-genetics-sweettest -- --ignored");
+    println!("Run with: cargo test -p mycelix-health-sweettest -- --ignored");
     println!("=============================================\n");
 }
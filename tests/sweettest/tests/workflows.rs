@@ -0,0 +1,204 @@
+//! Workflow Tests — Record → Consent → Read → Audit
+//!
+//! Covers:
+//! - A consented clinician reads recorded data and the read is audited
+//! - Revoking the consent stops further reads
+//! - A role override set on an existing care team narrows its members
+//! - Fixtures line up with the categories they are read under
+//!
+//! Every call targets a zome shipped in `health.dna`.
+//!
+//! ```bash
+//! nix develop
+//! cargo test -p mycelix-health-sweettest --test workflows -- --ignored
+//! ```
+
+use anyhow::Result;
+use health_workflows::fixtures::{self, FIXTURE_PATIENT_ID};
+use health_workflows::invariants;
+use health_workflows::types::{
+    CareTeamAuthInput, CareTeamAuthResult, CareTeamMember, CareTeamMemberType, CareTeamRole,
+    CareTeamRoleOverride, CareTeamTemplate, ConsentPurpose, CreateCareTeamInput, DataCategory,
+    DataPermission, Patient, SetRoleOverridesInput, TemplateType,
+};
+use health_workflows::{Fixture, Scenario, TestNetwork, ZomeCaller};
+use holochain::prelude::*;
+
+const PATIENT: usize = 0;
+const CLINICIAN: usize = 1;
+const FRONT_DESK: usize = 1;
+
+/// Categories the clinician is granted in the scenarios below
+fn read_categories() -> Vec<DataCategory> {
+    Fixture::ALL.iter().map(|fixture| fixture.category()).collect()
+}
+
+// ============================================================================
+// Fixture consistency (no conductor needed)
+// ============================================================================
+
+#[test]
+fn test_fixtures_reference_the_patient_and_author() {
+    let patient_hash = ActionHash::from_raw_36(vec![1; 36]);
+    let author = AgentPubKey::from_raw_36(vec![2; 36]);
+
+    let vitals = fixtures::vitals(patient_hash.clone(), author.clone()).vitals;
+    assert_eq!(vitals.patient_hash, patient_hash);
+    assert_eq!(vitals.recorded_by, author);
+
+    let lab = fixtures::lab_result(patient_hash.clone(), author.clone()).lab_result;
+    assert_eq!(lab.patient_hash, patient_hash);
+    assert_eq!(lab.ordering_provider, author);
+    assert!(!lab.loinc_code.is_empty());
+}
+
+#[test]
+fn test_fixtures_have_distinct_categories() {
+    let categories = read_categories();
+    assert_eq!(categories, vec![DataCategory::VitalSigns, DataCategory::LabResults]);
+    assert_eq!(Patient::fixture().patient_id, FIXTURE_PATIENT_ID);
+}
+
+// ============================================================================
+// Workflows
+// ============================================================================
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires Holochain conductor and built WASM zomes"]
+async fn test_consented_read_is_audited() -> Result<()> {
+    let network = TestNetwork::with_agents(&["patient", "clinician"]).await?;
+    let agents = [network.agent(PATIENT), network.agent(CLINICIAN)];
+
+    let _: ActionHash = agents[PATIENT]
+        .call("consent", "register_auditor", agents[PATIENT].agent_pubkey())
+        .await?;
+
+    let outcome = Scenario::new()
+        .create_patient(PATIENT)
+        .record(PATIENT, Fixture::Vitals)
+        .record(PATIENT, Fixture::LabResult)
+        .grant_consent(CLINICIAN, read_categories(), vec![DataPermission::Read])
+        .read(CLINICIAN, DataCategory::VitalSigns)
+        .read(CLINICIAN, DataCategory::LabResults)
+        .run(&agents)
+        .await?;
+
+    invariants::assert_records_indexed(&agents[PATIENT], &outcome).await?;
+    invariants::assert_read_returned(&outcome, 0, Fixture::Vitals)?;
+    invariants::assert_read_returned(&outcome, 1, Fixture::LabResult)?;
+    invariants::assert_read_logged(&agents[PATIENT], &outcome, &agents[CLINICIAN].agent_pubkey()).await?;
+    invariants::assert_accesses_consented(&agents[PATIENT], &outcome, &agents[CLINICIAN].agent_pubkey()).await?;
+    invariants::assert_reads_audited(&agents[PATIENT], &outcome).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires Holochain conductor and built WASM zomes"]
+async fn test_revoked_consent_stops_reads() -> Result<()> {
+    let network = TestNetwork::with_agents(&["patient", "clinician"]).await?;
+    let agents = [network.agent(PATIENT), network.agent(CLINICIAN)];
+
+    let outcome = Scenario::new()
+        .create_patient(PATIENT)
+        .record(PATIENT, Fixture::Vitals)
+        .read(CLINICIAN, DataCategory::VitalSigns)
+        .grant_consent(CLINICIAN, read_categories(), vec![DataPermission::Read])
+        .read(CLINICIAN, DataCategory::VitalSigns)
+        .revoke_consent(0)
+        .read(CLINICIAN, DataCategory::VitalSigns)
+        .run(&agents)
+        .await?;
+
+    invariants::assert_read_refused(&outcome, 0)?;
+    invariants::assert_read_returned(&outcome, 1, Fixture::Vitals)?;
+    invariants::assert_read_refused(&outcome, 2)?;
+    invariants::assert_accesses_consented(&agents[PATIENT], &outcome, &agents[CLINICIAN].agent_pubkey()).await?;
+
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[ignore = "requires Holochain conductor and built WASM zomes"]
+async fn test_role_override_set_after_creation_narrows_front_desk() -> Result<()> {
+    let network = TestNetwork::with_agents(&["patient", "front-desk"]).await?;
+    let agents = [network.agent(PATIENT), network.agent(FRONT_DESK)];
+
+    let outcome = Scenario::new().create_patient(PATIENT).run(&agents).await?;
+    let patient_hash = outcome.patient_hash()?;
+
+    let template: Record = agents[PATIENT]
+        .call(
+            "consent",
+            "create_care_team_template",
+            CareTeamTemplate {
+                template_id: "wf-clinic".to_string(),
+                name: "Clinic".to_string(),
+                description: "Clinic staff caring for the patient".to_string(),
+                permissions: vec![DataPermission::Read],
+                data_categories: vec![DataCategory::Demographics, DataCategory::LabResults],
+                default_exclusions: vec![],
+                purpose: ConsentPurpose::Treatment,
+                default_duration_days: None,
+                template_type: TemplateType::Personal,
+                created_by: agents[PATIENT].agent_pubkey(),
+                created_at: Timestamp::now(),
+                active: true,
+            },
+        )
+        .await?;
+    let team: Record = agents[PATIENT]
+        .call(
+            "consent",
+            "create_care_team_from_template",
+            CreateCareTeamInput {
+                team_id: "wf-clinic-team".to_string(),
+                patient_hash: patient_hash.clone(),
+                template_hash: template.action_address().clone(),
+                team_name: None,
+                members: vec![CareTeamMember {
+                    member: CareTeamMemberType::Agent(agents[FRONT_DESK].agent_pubkey()),
+                    role: CareTeamRole::AdministrativeStaff,
+                    joined_at: Timestamp::now(),
+                    active: true,
+                    permission_overrides: None,
+                }],
+                additional_exclusions: None,
+                notes: None,
+            },
+        )
+        .await?;
+
+    let lab_read = || CareTeamAuthInput {
+        patient_hash: patient_hash.clone(),
+        member: CareTeamMemberType::Agent(agents[FRONT_DESK].agent_pubkey()),
+        permission: DataPermission::Read,
+        data_category: DataCategory::LabResults,
+    };
+    let before: CareTeamAuthResult = agents[PATIENT]
+        .call("consent", "check_care_team_authorization", lab_read())
+        .await?;
+    assert!(before.authorized, "front desk reads labs before the override: {}", before.reason);
+
+    let _: Record = agents[PATIENT]
+        .call(
+            "consent",
+            "set_care_team_role_overrides",
+            SetRoleOverridesInput {
+                team_hash: team.action_address().clone(),
+                role_overrides: vec![CareTeamRoleOverride {
+                    role: CareTeamRole::AdministrativeStaff,
+                    permissions: vec![DataPermission::Read],
+                    data_categories: vec![DataCategory::Demographics],
+                }],
+            },
+        )
+        .await?;
+
+    let after: CareTeamAuthResult = agents[PATIENT]
+        .call("consent", "check_care_team_authorization", lab_read())
+        .await?;
+    assert!(!after.authorized, "the override limits front desk to demographics");
+
+    Ok(())
+}
@@ -5,6 +5,10 @@
 
 use hdk::prelude::*;
 use bridge_integrity::*;
+use mycelix_health_shared::{require_authorization, DataCategory, Permission};
+use mycelix_health_shared::{
+    resilient_call, CallClass, DomainEventHandler, DomainEventNotice, DomainEventPayload, SUBSCRIPTIONS_EXTERN,
+};
 
 /// Register this hApp with the Mycelix bridge
 #[hdk_extern]
@@ -320,3 +324,477 @@ fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
     let anchor = Anchor(anchor_text.to_string());
     hash_entry(&anchor)
 }
+
+// ============================================================
+// WEBHOOK SUBSCRIPTIONS
+// ============================================================
+
+/// Signal sent to a subscription's bridge agent for each matching event
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct WebhookSignal {
+    pub delivery_hash: ActionHash,
+    pub subscription_hash: ActionHash,
+    pub source_system: String,
+    pub callback: WebhookCallback,
+    pub event: WebhookEvent,
+}
+
+/// Let other agents deliver webhook signals to this agent
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = HashSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("recv_remote_signal")));
+    create_cap_grant(ZomeCallCapGrant {
+        tag: "webhook_signals".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+    Ok(InitCallbackResult::Pass)
+}
+
+/// Forward a webhook signal to the bridge agent's client
+///
+/// Anyone can send a signal, so it is only forwarded when it names a live
+/// subscription of this agent that covers the event; the callback comes from
+/// the stored subscription, never from the signal.
+#[hdk_extern]
+pub fn recv_remote_signal(signal: ExternIO) -> ExternResult<()> {
+    let mut signal: WebhookSignal = signal.decode().map_err(|e| wasm_error!(e))?;
+    let Some(subscription) = get_webhook_subscription(&signal.subscription_hash)? else {
+        return Ok(());
+    };
+    if subscription.bridge_agent != agent_info()?.agent_initial_pubkey || !subscription_matches(&subscription, &signal.event) {
+        return Ok(());
+    }
+    signal.source_system = subscription.source_system;
+    signal.callback = subscription.callback;
+    emit_signal(&signal)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateWebhookSubscriptionInput {
+    pub subscription_id: String,
+    pub source_system: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub patient_hashes: Vec<ActionHash>,
+    pub callback: WebhookCallback,
+}
+
+/// Subscribe the calling bridge agent to events for patients it manages
+///
+/// The agent must be authorized to read each patient's demographics. Events
+/// carry hashes only; the external system still reads the data through
+/// consent-checked calls.
+#[hdk_extern]
+pub fn create_webhook_subscription(input: CreateWebhookSubscriptionInput) -> ExternResult<Record> {
+    for patient_hash in &input.patient_hashes {
+        require_authorization(patient_hash.clone(), DataCategory::Demographics, Permission::Read, false)?;
+    }
+
+    let bridge_agent = agent_info()?.agent_initial_pubkey;
+    let subscription = WebhookSubscription {
+        subscription_id: input.subscription_id,
+        source_system: input.source_system,
+        event_types: input.event_types,
+        patient_hashes: input.patient_hashes,
+        bridge_agent: bridge_agent.clone(),
+        callback: input.callback,
+        active: true,
+        created_at: sys_time()?,
+    };
+    let subscription_hash = create_entry(&EntryTypes::WebhookSubscription(subscription.clone()))?;
+    for patient_hash in &subscription.patient_hashes {
+        create_link(
+            patient_hash.clone(),
+            subscription_hash.clone(),
+            LinkTypes::PatientToWebhookSubscriptions,
+            (),
+        )?;
+    }
+    create_link(
+        bridge_agent,
+        subscription_hash.clone(),
+        LinkTypes::BridgeAgentToWebhookSubscriptions,
+        (),
+    )?;
+    get(subscription_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find subscription".to_string())))
+}
+
+/// Stop a subscription; deliveries already queued are kept
+#[hdk_extern]
+pub fn deactivate_webhook_subscription(subscription_hash: ActionHash) -> ExternResult<Record> {
+    let mut subscription = get_webhook_subscription(&subscription_hash)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Subscription not found".to_string())))?;
+    subscription.active = false;
+    let updated = update_entry(subscription_hash.clone(), &EntryTypes::WebhookSubscription(subscription.clone()))?;
+
+    for patient_hash in &subscription.patient_hashes {
+        for link in get_links(
+            LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToWebhookSubscriptions)?,
+            GetStrategy::default(),
+        )? {
+            if link.target.clone().into_action_hash().as_ref() == Some(&subscription_hash) {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+    }
+    get(updated, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find subscription".to_string())))
+}
+
+/// The calling bridge agent's subscriptions
+#[hdk_extern]
+pub fn get_my_webhook_subscriptions(_: ()) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(agent_info()?.agent_initial_pubkey, LinkTypes::BridgeAgentToWebhookSubscriptions)?,
+        GetStrategy::default(),
+    )?;
+    let mut subscriptions = Vec::new();
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if let Some(record) = latest_version(hash)? {
+            subscriptions.push(record);
+        }
+    }
+    Ok(subscriptions)
+}
+
+fn get_webhook_subscription(subscription_hash: &ActionHash) -> ExternResult<Option<WebhookSubscription>> {
+    Ok(latest_version(subscription_hash.clone())?
+        .and_then(|record| record.entry().to_app_option::<WebhookSubscription>().ok().flatten()))
+}
+
+fn subscription_matches(subscription: &WebhookSubscription, event: &WebhookEvent) -> bool {
+    subscription.active
+        && subscription.event_types.contains(&event.event_type)
+        && subscription.patient_hashes.contains(&event.patient_hash)
+}
+
+/// The most recent update of a record, or the record itself
+fn latest_version(original: ActionHash) -> ExternResult<Option<Record>> {
+    let Some(Details::Record(details)) = get_details(original, GetOptions::default())? else {
+        return Ok(None);
+    };
+    match details.updates.iter().max_by_key(|update| update.action().timestamp()) {
+        Some(update) => get(update.as_hash().clone(), GetOptions::default()),
+        None => Ok(Some(details.record)),
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmitWebhookEventInput {
+    pub event_type: WebhookEventType,
+    pub patient_hash: ActionHash,
+    pub resource_hash: ActionHash,
+}
+
+/// Queue a delivery for every active subscription matching the event and
+/// signal each bridge agent
+///
+/// Called by other zomes after a write; the resource must be authored by
+/// the caller, so agents cannot raise events for records they did not write.
+/// Missed signals are recovered through `get_pending_webhook_deliveries`.
+#[hdk_extern]
+pub fn emit_webhook_event(input: EmitWebhookEventInput) -> ExternResult<Vec<ActionHash>> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let resource = get(input.resource_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Event resource not found".to_string())))?;
+    if resource.action().author() != &caller {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Webhook events can only be raised for the caller's own records".to_string()
+        )));
+    }
+
+    let event = WebhookEvent {
+        event_type: input.event_type,
+        patient_hash: input.patient_hash.clone(),
+        resource_hash: input.resource_hash,
+        occurred_at: sys_time()?,
+    };
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash, LinkTypes::PatientToWebhookSubscriptions)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut deliveries = Vec::new();
+    for subscription_hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        let Some(subscription) = get_webhook_subscription(&subscription_hash)? else {
+            continue;
+        };
+        if !subscription_matches(&subscription, &event) {
+            continue;
+        }
+
+        let delivery = WebhookDelivery {
+            subscription_hash: subscription_hash.clone(),
+            bridge_agent: subscription.bridge_agent.clone(),
+            event: event.clone(),
+            state: WebhookDeliveryState::Pending,
+            attempts: 0,
+            last_attempt_at: None,
+            last_error: None,
+        };
+        let delivery_hash = create_entry(&EntryTypes::WebhookDelivery(delivery))?;
+        create_link(
+            subscription_hash.clone(),
+            delivery_hash.clone(),
+            LinkTypes::SubscriptionToDeliveries,
+            (),
+        )?;
+        create_link(
+            subscription.bridge_agent.clone(),
+            delivery_hash.clone(),
+            LinkTypes::BridgeAgentToPendingDeliveries,
+            (),
+        )?;
+
+        // Best effort: an offline bridge agent picks the delivery up later
+        let _ = send_remote_signal(
+            WebhookSignal {
+                delivery_hash: delivery_hash.clone(),
+                subscription_hash,
+                source_system: subscription.source_system,
+                callback: subscription.callback,
+                event: event.clone(),
+            },
+            vec![subscription.bridge_agent],
+        );
+        deliveries.push(delivery_hash);
+    }
+    Ok(deliveries)
+}
+
+/// Deliveries the calling bridge agent still owes, in their latest state
+#[hdk_extern]
+pub fn get_pending_webhook_deliveries(_: ()) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(agent_info()?.agent_initial_pubkey, LinkTypes::BridgeAgentToPendingDeliveries)?,
+        GetStrategy::default(),
+    )?;
+    let mut deliveries = Vec::new();
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if let Some(record) = latest_delivery(&hash)? {
+            deliveries.push(record);
+        }
+    }
+    Ok(deliveries)
+}
+
+fn latest_delivery(delivery_hash: &ActionHash) -> ExternResult<Option<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(delivery_hash.clone(), LinkTypes::DeliveryUpdates)?,
+        GetStrategy::default(),
+    )?;
+    let latest = links
+        .into_iter()
+        .max_by_key(|link| link.timestamp)
+        .and_then(|link| link.target.into_action_hash())
+        .unwrap_or_else(|| delivery_hash.clone());
+    get(latest, GetOptions::default())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordDeliveryAttemptInput {
+    /// The delivery as first created
+    pub delivery_hash: ActionHash,
+    pub succeeded: bool,
+    pub error: Option<String>,
+}
+
+/// Record one delivery attempt by the bridge agent
+///
+/// A failed attempt leaves the delivery pending for retry until
+/// MAX_WEBHOOK_ATTEMPTS, after which it is abandoned.
+#[hdk_extern]
+pub fn record_webhook_delivery_attempt(input: RecordDeliveryAttemptInput) -> ExternResult<Record> {
+    let latest = latest_delivery(&input.delivery_hash)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Delivery not found".to_string())))?;
+    let mut delivery: WebhookDelivery = latest
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid delivery".to_string())))?;
+
+    delivery.attempts += 1;
+    delivery.last_attempt_at = Some(sys_time()?);
+    if input.succeeded {
+        delivery.state = WebhookDeliveryState::Delivered;
+        delivery.last_error = None;
+    } else {
+        delivery.state = if delivery.attempts >= MAX_WEBHOOK_ATTEMPTS {
+            WebhookDeliveryState::Abandoned
+        } else {
+            WebhookDeliveryState::Failed
+        };
+        delivery.last_error = input.error;
+    }
+
+    let updated = update_entry(latest.action_address().clone(), &EntryTypes::WebhookDelivery(delivery.clone()))?;
+    create_link(
+        input.delivery_hash.clone(),
+        updated.clone(),
+        LinkTypes::DeliveryUpdates,
+        (),
+    )?;
+
+    if matches!(delivery.state, WebhookDeliveryState::Delivered | WebhookDeliveryState::Abandoned) {
+        for link in get_links(
+            LinkQuery::try_new(delivery.bridge_agent.clone(), LinkTypes::BridgeAgentToPendingDeliveries)?,
+            GetStrategy::default(),
+        )? {
+            if link.target.clone().into_action_hash().as_ref() == Some(&input.delivery_hash) {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+    }
+
+    get(updated, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find delivery".to_string())))
+}
+
+// ============================================================
+// DOMAIN EVENTS
+// ============================================================
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublishDomainEventInput {
+    pub patient_hash: ActionHash,
+    pub source_zome: String,
+    pub payload: DomainEventPayload,
+}
+
+/// Outcome of handing one event to one subscribed handler
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct DomainEventDelivery {
+    pub zome: String,
+    pub handler: String,
+    pub error: Option<String>,
+}
+
+/// Record a domain event on the caller's chain and dispatch it
+///
+/// Called through `publish_event` by the zome that made the write.
+/// Handler failures are reported by `dispatch_domain_event` but never
+/// fail the publish.
+#[hdk_extern]
+pub fn publish_domain_event(input: PublishDomainEventInput) -> ExternResult<ActionHash> {
+    let event = DomainEvent {
+        patient_hash: input.patient_hash.clone(),
+        published_by: agent_info()?.agent_initial_pubkey,
+        source_zome: input.source_zome,
+        payload: input.payload,
+        occurred_at: sys_time()?,
+    };
+    let event_hash = create_entry(&EntryTypes::DomainEvent(event))?;
+    create_link(
+        input.patient_hash,
+        event_hash.clone(),
+        LinkTypes::PatientToDomainEvents,
+        (),
+    )?;
+    dispatch_domain_event(event_hash.clone())?;
+    Ok(event_hash)
+}
+
+/// Hand one of the caller's events to every handler subscribed to its kind
+///
+/// Subscribers are discovered by calling `domain_event_subscriptions` on each
+/// zome in the DNA; zomes without it are skipped. Can be re-run to retry
+/// handlers that failed, so handlers must tolerate repeat notices.
+#[hdk_extern]
+pub fn dispatch_domain_event(event_hash: ActionHash) -> ExternResult<Vec<DomainEventDelivery>> {
+    let record = get(event_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Domain event not found".to_string())))?;
+    let event: DomainEvent = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid domain event".to_string())))?;
+    if event.published_by != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the publisher can dispatch a domain event".to_string()
+        )));
+    }
+
+    let kind = event.payload.kind();
+    let notice = DomainEventNotice {
+        event_hash,
+        patient_hash: event.patient_hash,
+        source_zome: event.source_zome,
+        payload: event.payload,
+        occurred_at: event.occurred_at,
+    };
+    let mut deliveries = Vec::new();
+    for zome in dna_info()?.zome_names {
+        for subscription in domain_event_subscriptions(&zome).into_iter().filter(|s| s.handles(kind)) {
+            let result: Result<(), _> =
+                resilient_call(&zome.to_string(), &subscription.handler, &notice, CallClass::Bulk);
+            deliveries.push(DomainEventDelivery {
+                zome: zome.to_string(),
+                handler: subscription.handler,
+                error: result.err().map(|failure| format!("{:?}", failure)),
+            });
+        }
+    }
+    Ok(deliveries)
+}
+
+/// Events published about a patient, oldest first; like webhook events they
+/// carry hashes only, so demographics access is enough to list them
+#[hdk_extern]
+pub fn get_patient_domain_events(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    require_authorization(patient_hash.clone(), DataCategory::Demographics, Permission::Read, false)?;
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToDomainEvents)?,
+        GetStrategy::default(),
+    )?;
+    let mut events = Vec::new();
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if let Some(record) = get(hash, GetOptions::default())? {
+            events.push(record);
+        }
+    }
+    events.sort_by_key(|record| record.action().timestamp());
+    Ok(events)
+}
+
+/// Handlers `zome` registers; empty when it does not subscribe to events
+fn domain_event_subscriptions(zome: &ZomeName) -> Vec<DomainEventHandler> {
+    match call(CallTargetCell::Local, zome.clone(), SUBSCRIPTIONS_EXTERN.into(), None, ()) {
+        Ok(ZomeCallResponse::Ok(extern_io)) => extern_io.decode().unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("register_with_bridge", "RegisterBridgeInput", "Record"),
+        ("query_federated_data", "HealthDataQuery", "Record"),
+        ("respond_to_query", "HealthDataResponse", "Record"),
+        ("get_query_responses", "ActionHash", "Vec<Record>"),
+        ("request_provider_verification", "ProviderVerificationRequest", "Record"),
+        ("submit_verification_result", "ProviderVerificationResult", "Record"),
+        ("create_epistemic_claim", "HealthEpistemicClaim", "Record"),
+        ("get_entity_claims", "ActionHash", "Vec<Record>"),
+        ("verify_claim", "VerifyClaimInput", "Record"),
+        ("update_federated_reputation", "HealthReputationFederation", "Record"),
+        ("get_federated_reputation", "ActionHash", "Option<Record>"),
+        ("aggregate_reputation", "AggregateReputationInput", "HealthReputationFederation"),
+        ("get_active_registrations", "()", "Vec<Record>"),
+        ("create_webhook_subscription", "CreateWebhookSubscriptionInput", "Record"),
+        ("deactivate_webhook_subscription", "ActionHash", "Record"),
+        ("get_my_webhook_subscriptions", "()", "Vec<Record>"),
+        ("emit_webhook_event", "EmitWebhookEventInput", "Vec<ActionHash>"),
+        ("get_pending_webhook_deliveries", "()", "Vec<Record>"),
+        ("record_webhook_delivery_attempt", "RecordDeliveryAttemptInput", "Record"),
+        ("publish_domain_event", "PublishDomainEventInput", "ActionHash"),
+        ("dispatch_domain_event", "ActionHash", "Vec<DomainEventDelivery>"),
+        ("get_patient_domain_events", "ActionHash", "Vec<Record>"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["webhooks", "domain_events"],
+}
@@ -4,6 +4,10 @@
 //! and ecosystem integration following the Mycelix bridge protocol.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, DomainEventPayload, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS,
+    NOTE_CHARS,
+};
 
 /// Bridge registration for health data federation
 #[hdk_entry_helper]
@@ -277,6 +281,86 @@ pub struct FederatedScore {
     pub timestamp: Timestamp,
 }
 
+/// Events an external system can subscribe to
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum WebhookEventType {
+    NewObservation,
+    ConsentChanged,
+}
+
+/// Where and how the bridge agent delivers a webhook
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WebhookCallback {
+    pub url: String,
+    /// Name of the signing secret held by the bridge agent, never the secret itself
+    pub secret_ref: Option<String>,
+    pub headers: Vec<(String, String)>,
+}
+
+/// An external system's subscription to events for the patients it manages
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct WebhookSubscription {
+    pub subscription_id: String,
+    pub source_system: String,
+    pub event_types: Vec<WebhookEventType>,
+    pub patient_hashes: Vec<ActionHash>,
+    /// Agent that receives event signals and calls the external system
+    pub bridge_agent: AgentPubKey,
+    pub callback: WebhookCallback,
+    pub active: bool,
+    pub created_at: Timestamp,
+}
+
+/// What happened; carries hashes only, so the external system reads the
+/// data itself through consent-checked calls
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct WebhookEvent {
+    pub event_type: WebhookEventType,
+    pub patient_hash: ActionHash,
+    pub resource_hash: ActionHash,
+    pub occurred_at: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum WebhookDeliveryState {
+    Pending,
+    Delivered,
+    /// Last attempt failed; the bridge agent retries
+    Failed,
+    /// Gave up after MAX_WEBHOOK_ATTEMPTS
+    Abandoned,
+}
+
+/// Attempts after which a delivery may be abandoned
+pub const MAX_WEBHOOK_ATTEMPTS: u32 = 8;
+
+/// One event owed to one subscription; the bridge agent updates it after
+/// each delivery attempt
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct WebhookDelivery {
+    pub subscription_hash: ActionHash,
+    pub bridge_agent: AgentPubKey,
+    pub event: WebhookEvent,
+    pub state: WebhookDeliveryState,
+    pub attempts: u32,
+    pub last_attempt_at: Option<Timestamp>,
+    pub last_error: Option<String>,
+}
+
+/// A domain event on the publishing agent's chain; immutable once written
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DomainEvent {
+    pub patient_hash: ActionHash,
+    pub published_by: AgentPubKey,
+    /// Coordinator zome that raised the event
+    pub source_zome: String,
+    pub payload: DomainEventPayload,
+    pub occurred_at: Timestamp,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -287,6 +371,9 @@ pub enum EntryTypes {
     ProviderVerificationResult(ProviderVerificationResult),
     HealthEpistemicClaim(HealthEpistemicClaim),
     HealthReputationFederation(HealthReputationFederation),
+    WebhookSubscription(WebhookSubscription),
+    WebhookDelivery(WebhookDelivery),
+    DomainEvent(DomainEvent),
 }
 
 #[hdk_link_types]
@@ -299,13 +386,31 @@ pub enum LinkTypes {
     PendingQueries,
     ActiveRegistrations,
     ClaimsByType,
+    PatientToWebhookSubscriptions,
+    BridgeAgentToWebhookSubscriptions,
+    SubscriptionToDeliveries,
+    /// Bridge agent to deliveries still owed; removed once delivered or abandoned
+    BridgeAgentToPendingDeliveries,
+    DeliveryUpdates,
+    PatientToDomainEvents,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("claim_content", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::HealthBridgeRegistration(r) => validate_registration(&r),
                 EntryTypes::HealthDataQuery(q) => validate_query(&q),
                 EntryTypes::HealthDataResponse(r) => validate_response(&r),
@@ -313,6 +418,19 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::ProviderVerificationResult(r) => validate_verification_result(&r),
                 EntryTypes::HealthEpistemicClaim(c) => validate_claim(&c),
                 EntryTypes::HealthReputationFederation(f) => validate_federation(&f),
+                EntryTypes::WebhookSubscription(w) => validate_webhook_subscription(&w, &action.author),
+                EntryTypes::WebhookDelivery(d) => validate_new_webhook_delivery(&d),
+                EntryTypes::DomainEvent(e) => validate_domain_event(&e, &action.author),
+            },
+            OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
+                EntryTypes::WebhookSubscription(w) => {
+                    validate_webhook_subscription_update(&w, &action)
+                }
+                EntryTypes::WebhookDelivery(d) => validate_webhook_delivery_update(&d, &action),
+                EntryTypes::DomainEvent(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Domain events cannot be updated".to_string(),
+                )),
+                _ => Ok(ValidateCallbackResult::Valid),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -425,3 +543,109 @@ fn validate_federation(fed: &HealthReputationFederation) -> ExternResult<Validat
     }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_webhook_subscription(sub: &WebhookSubscription, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if sub.subscription_id.is_empty() || sub.source_system.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Subscription ID and source system are required".to_string(),
+        ));
+    }
+    if sub.event_types.is_empty() || sub.patient_hashes.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A subscription needs at least one event type and patient".to_string(),
+        ));
+    }
+    if !sub.callback.url.starts_with("https://") {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Webhook callbacks must use HTTPS".to_string(),
+        ));
+    }
+    if &sub.bridge_agent != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the bridge agent can manage its subscriptions".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_webhook_subscription_update(sub: &WebhookSubscription, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    let original: WebhookSubscription = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original subscription not found".to_string())))?;
+    if sub.subscription_id != original.subscription_id || sub.bridge_agent != original.bridge_agent {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Subscription ID and bridge agent cannot change".to_string(),
+        ));
+    }
+    validate_webhook_subscription(sub, &action.author)
+}
+
+fn validate_new_webhook_delivery(delivery: &WebhookDelivery) -> ExternResult<ValidateCallbackResult> {
+    if delivery.state != WebhookDeliveryState::Pending || delivery.attempts != 0 || delivery.last_attempt_at.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "New deliveries start pending with no attempts".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Each update records one attempt by the bridge agent; delivered and
+/// abandoned deliveries are final
+fn validate_webhook_delivery_update(delivery: &WebhookDelivery, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    let previous: WebhookDelivery = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original delivery not found".to_string())))?;
+    if action.author != previous.bridge_agent {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the bridge agent can record delivery attempts".to_string(),
+        ));
+    }
+    if delivery.subscription_hash != previous.subscription_hash
+        || delivery.bridge_agent != previous.bridge_agent
+        || delivery.event != previous.event
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A delivery's subscription and event cannot change".to_string(),
+        ));
+    }
+    if matches!(previous.state, WebhookDeliveryState::Delivered | WebhookDeliveryState::Abandoned) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Delivery is already final".to_string(),
+        ));
+    }
+    if delivery.attempts != previous.attempts + 1 || delivery.last_attempt_at.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Each update must record exactly one attempt".to_string(),
+        ));
+    }
+    let valid_state = match delivery.state {
+        WebhookDeliveryState::Pending => false,
+        WebhookDeliveryState::Delivered | WebhookDeliveryState::Failed => true,
+        WebhookDeliveryState::Abandoned => delivery.attempts >= MAX_WEBHOOK_ATTEMPTS,
+    };
+    if !valid_state {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Invalid delivery state {:?} after {} attempts",
+            delivery.state, delivery.attempts
+        )));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_domain_event(event: &DomainEvent, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if &event.published_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Domain events must be published by their author".to_string(),
+        ));
+    }
+    if event.source_zome.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Domain events must name their source zome".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
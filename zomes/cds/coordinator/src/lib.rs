@@ -981,3 +981,32 @@ fn check_duplicate_therapies(medication_rxnorm_codes: &[String]) -> ExternResult
 
     Ok(duplicates)
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_drug_interaction", "DrugInteraction", "Record"),
+        ("check_drug_interactions", "CheckDrugInteractionsInput", "Vec<FoundInteraction>"),
+        ("create_drug_allergy_interaction", "DrugAllergyInteraction", "Record"),
+        ("check_allergy_conflicts", "CheckAllergyConflictsInput", "Vec<FoundAllergyConflict>"),
+        ("create_clinical_alert", "CreateAlertInput", "Record"),
+        ("get_patient_alerts", "GetPatientAlertsInput", "Vec<Record>"),
+        ("acknowledge_alert", "AcknowledgeAlertInput", "Record"),
+        ("resolve_alert", "ResolveAlertInput", "Record"),
+        ("create_clinical_guideline", "ClinicalGuideline", "Record"),
+        ("get_all_active_guidelines", "()", "Vec<Record>"),
+        ("get_guidelines_for_condition", "GetGuidelinesForConditionInput", "Vec<Record>"),
+        ("update_patient_guideline_status", "PatientGuidelineStatus", "Record"),
+        ("get_patient_guideline_statuses", "GetPatientGuidelineStatusInput", "Vec<Record>"),
+        ("perform_interaction_check", "InteractionCheckRequest", "Record"),
+        ("create_pgx_profile", "CreatePgxProfileInput", "Record"),
+        ("get_pgx_profile", "GetPgxProfileInput", "Option<Record>"),
+        ("create_drug_gene_interaction", "DrugGeneInteraction", "Record"),
+        ("check_pgx_interaction", "CheckPgxInteractionInput", "PharmacogenomicCheckResult"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: [],
+}
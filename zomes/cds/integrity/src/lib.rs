@@ -9,6 +9,9 @@
 //! HIPAA and clinical safety compliant.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 // ============================================================================
 // Drug Interaction Types
@@ -605,8 +608,24 @@ pub enum LinkTypes {
 // Validation Functions
 // ============================================================================
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("content", FieldLimit::Chars(NOTE_CHARS)),
+        ("clinical_notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("acknowledgment_notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("resolution_notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("hdc_encoded_profile", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => validate_create_entry(app_entry),
@@ -4,21 +4,94 @@
 //! access control, and audit logging.
 
 use hdk::prelude::*;
+use std::collections::{HashMap, HashSet};
 use consent_integrity::*;
+use mycelix_health_shared::{
+    conflict, consent_required, error_with_detail, internal_error, invalid_input, limit_exceeded, not_found,
+    unauthorized, HealthError,
+};
+use mycelix_health_shared::{anchor_for_period, links_in_period, period_anchor, AnchorPeriod};
+use mycelix_health_shared::{filter_links_by_metadata, sort_links_newest_first};
+use mycelix_health_shared::{require_admin_authorization, BudgetedPage, PaginatedResult, PaginationInput};
+use mycelix_health_shared::{cursor_page, links_to_records_cursor, CursorInput, CursorPage, PaginationCursor};
+use mycelix_health_shared::{HealthDnaProperties, RoleCheck};
+use mycelix_health_shared::AccessReceipt;
+use mycelix_health_shared::{batch_get_typed, BatchGetOptions};
+use mycelix_health_shared::dp_core::{BudgetSpend, CohortReservation};
+use mycelix_health_shared::access_control::{invalidate_authorization_cache, Role};
+use mycelix_health_shared::access_control::{combine_deny_overrides, PolicyDecision, PolicyEffect, PolicySource, PolicyStatement};
+use mycelix_health_shared::{increment_counter, read_counter};
+use mycelix_health_shared::{get_for, get_links_for, resilient_call, CallClass};
+use mycelix_health_shared::IdentityAttestations;
+use mycelix_health_shared::{OperationRateStatus, RateLimitStatus, SensitiveOperation, ThrottleTier};
+use mycelix_health_shared::PatientResidency;
+use mycelix_health_shared::ApplicableRetention;
+use mycelix_health_shared::DataCategory as SharedCategory;
+use mycelix_health_shared::{evaluate_site_rules as evaluate_rules, EvaluateSiteRulesInput, RuleEvaluation};
+use mycelix_health_shared::validation::validate_jurisdiction;
+use mycelix_health_shared::{notify_webhooks, WebhookEventType};
+use mycelix_health_shared::{publish_event, DomainEventHandler, DomainEventKind, DomainEventNotice, DomainEventPayload};
+use mycelix_health_shared::{date_label, is_valid_utc_offset, local_date_time, month_key, month_label, time_label, ExplanationLanguage};
+use mycelix_health_shared::{fhir_instant, parse_fhir_datetime, require_patient_self};
+use mycelix_health_shared::{Saga, SagaCompensation};
+use mycelix_health_shared::{IngestStatistics, IngestWindowStatistics, SourceIngestStatistics, StorageGrowthEstimate};
+use mycelix_health_shared::{recent_entry_footprints, sample_anchor_links, StorageReport};
+use mycelix_health_shared::{aggregate_timings, instrumented, ExternMetrics, PerformanceSampleInput};
+use mycelix_health_shared::idempotent_create;
+
+/// Input for creating a consent directive
+///
+/// The consent's fields sit at the top level, so a bare `Consent` is
+/// accepted too.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateConsentInput {
+    #[serde(flatten)]
+    pub consent: Consent,
+    /// Caller-chosen key, the same on every retry of one create; a retry
+    /// gets back the consent the first call created
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
 
 /// Create a new consent directive
 #[hdk_extern]
-pub fn create_consent(consent: Consent) -> ExternResult<Record> {
+pub fn create_consent(input: CreateConsentInput) -> ExternResult<Record> {
+    match &input.idempotency_key {
+        Some(key) => idempotent_create("consent::create_consent", key, &input.consent, LinkTypes::IdempotencyKeys, || {
+            write_consent(input.consent.clone())
+        }),
+        None => write_consent(input.consent),
+    }
+}
+
+fn write_consent(mut consent: Consent) -> ExternResult<Record> {
+
+    // Consents covered by a guardian policy stay pending until the quorum
+    // approves; every consent cites the policy version it was made under
+    let guardian_policy = patient_guardian_policy(&consent.patient_hash)?;
+    let needs_guardian_quorum = guardian_policy
+        .as_ref()
+        .is_some_and(|(_, policy)| policy.active && policy.quorum_for(&consent.scope.data_categories).is_some());
+    consent.guardian_policy = guardian_policy.map(|(hash, _)| hash);
+    consent.guardian_approvals = Vec::new();
+    if needs_guardian_quorum && matches!(consent.status, ConsentStatus::Active) {
+        consent.status = ConsentStatus::Pending;
+    }
+
+    // `All` only reaches the categories that exist as of today
+    consent.category_registry = Some(CategoryRegistry::current());
+
     let consent_hash = create_entry(&EntryTypes::Consent(consent.clone()))?;
     let record = get(consent_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find consent".to_string())))?;
+        .ok_or(not_found("Could not find consent"))?;
+    notify_webhooks(WebhookEventType::ConsentChanged, consent.patient_hash.clone(), consent_hash.clone());
     
     // Link to patient
     create_link(
         consent.patient_hash.clone(),
         consent_hash.clone(),
         LinkTypes::PatientToConsents,
-        (),
+        consent_link_tag(&consent),
     )?;
     
     // Link to active consents
@@ -30,6 +103,15 @@ pub fn create_consent(consent: Consent) -> ExternResult<Record> {
             LinkTypes::ActiveConsents,
             (),
         )?;
+        count_active_consents(1)?;
+    } else if needs_guardian_quorum && matches!(consent.status, ConsentStatus::Pending) {
+        let pending_anchor = anchor_hash("pending_guardian_consents")?;
+        create_link(
+            pending_anchor,
+            consent_hash,
+            LinkTypes::PendingGuardianConsents,
+            (),
+        )?;
     }
     
     Ok(record)
@@ -38,12 +120,18 @@ pub fn create_consent(consent: Consent) -> ExternResult<Record> {
 /// Get patient's consents
 #[hdk_extern]
 pub fn get_patient_consents(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToConsents)?, GetStrategy::default())?;
-    
+    let links = get_links_for(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToConsents)?,
+        CallClass::ConsentStatus,
+    )?;
+    consent_records(links)
+}
+
+fn consent_records(links: Vec<Link>) -> ExternResult<Vec<Record>> {
     let mut consents = Vec::new();
     for link in links {
         if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
+            if let Some(record) = get_for(hash, CallClass::ConsentStatus)? {
                 consents.push(record);
             }
         }
@@ -55,7 +143,13 @@ pub fn get_patient_consents(patient_hash: ActionHash) -> ExternResult<Vec<Record
 /// Get active consents for a patient
 #[hdk_extern]
 pub fn get_active_consents(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let all_consents = get_patient_consents(patient_hash)?;
+    let links = get_links_for(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToConsents)?,
+        CallClass::ConsentStatus,
+    )?;
+    // Tagged links already say which consents are active
+    let links = filter_links_by_metadata(links, |metadata| metadata.has_status(&ConsentStatus::Active));
+    let all_consents = consent_records(links)?;
     
     let active: Vec<Record> = all_consents
         .into_iter()
@@ -74,20 +168,34 @@ pub fn get_active_consents(patient_hash: ActionHash) -> ExternResult<Vec<Record>
 /// Revoke a consent
 #[hdk_extern]
 pub fn revoke_consent(input: RevokeConsentInput) -> ExternResult<Record> {
-    let record = get(input.consent_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Consent not found".to_string())))?;
+    let record = get_for(input.consent_hash.clone(), CallClass::ConsentStatus)?
+        .ok_or(not_found("Consent not found"))?;
     
     let mut consent: Consent = record
         .entry()
         .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid consent".to_string())))?;
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
     
+    let was_active = matches!(consent.status, ConsentStatus::Active);
     consent.status = ConsentStatus::Revoked;
     consent.revoked_at = Some(sys_time()?);
     consent.revocation_reason = Some(input.reason);
     
     let updated_hash = update_entry(input.consent_hash.clone(), &consent)?;
+    repoint_patient_link(
+        &consent.patient_hash,
+        LinkTypes::PatientToConsents,
+        &input.consent_hash,
+        updated_hash.clone(),
+        consent_link_tag(&consent),
+    )?;
+    invalidate_authorization_cache(&consent.patient_hash);
+    notify_webhooks(WebhookEventType::ConsentChanged, consent.patient_hash.clone(), updated_hash.clone());
+    publish_event(
+        consent.patient_hash.clone(),
+        DomainEventPayload::ConsentRevoked { consent_hash: updated_hash.clone() },
+    );
     
     // Add to revoked consents
     let revoked_anchor = anchor_hash("revoked_consents")?;
@@ -97,9 +205,12 @@ pub fn revoke_consent(input: RevokeConsentInput) -> ExternResult<Record> {
         LinkTypes::RevokedConsents,
         (),
     )?;
+    if was_active {
+        count_active_consents(-1)?;
+    }
     
     get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated consent".to_string())))
+        .ok_or(not_found("Could not find updated consent"))
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -108,1058 +219,2620 @@ pub struct RevokeConsentInput {
     pub reason: String,
 }
 
-/// Check if access is authorized
-/// Called by the shared crate's require_authorization() function
-#[hdk_extern]
-pub fn check_authorization(input: AuthorizationCheckInput) -> ExternResult<AuthorizationResult> {
-    let consents = get_active_consents(input.patient_hash.clone())?;
+// ============================================================
+// CATEGORY RE-AFFIRMATION
+// ============================================================
+
+/// An `All`-scoped consent that does not yet cover newly added categories
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryReaffirmation {
+    pub consent_hash: ActionHash,
+    pub consent_id: String,
+    pub grantee: ConsentGrantee,
+    pub registry_version: u32,
+    pub new_categories: Vec<DataCategory>,
+}
 
-    for record in consents {
+/// Active consents that have not been superseded by a later version
+fn current_active_consents(patient_hash: ActionHash) -> ExternResult<Vec<(ActionHash, Consent)>> {
+    let mut current = Vec::new();
+    for record in get_active_consents(patient_hash)? {
+        let consent_hash = record.action_address().clone();
+        // Superseded versions were re-affirmed or otherwise replaced
+        let updates = get_links_for(
+            LinkQuery::try_new(consent_hash.clone(), LinkTypes::ConsentUpdates)?,
+            CallClass::ConsentStatus,
+        )?;
+        if !updates.is_empty() {
+            continue;
+        }
         if let Some(consent) = record.entry().to_app_option::<Consent>().ok().flatten() {
-            // Check if grantee matches
-            let grantee_matches = match &consent.grantee {
-                ConsentGrantee::Agent(agent) => *agent == input.requestor,
-                ConsentGrantee::EmergencyAccess => input.is_emergency,
-                _ => false,
-            };
+            current.push((consent_hash, consent));
+        }
+    }
+    Ok(current)
+}
 
-            if grantee_matches {
-                // Check if data category is covered
-                let category_covered = consent.scope.data_categories.iter().any(|cat| {
-                    matches!(cat, DataCategory::All) || *cat == input.data_category
-                });
+/// Patient-link tag carrying a consent's status, grant time and expiry
+fn consent_link_tag(consent: &Consent) -> LinkTag {
+    LinkMetadata::new(&consent.status, consent.granted_at, consent.expires_at).to_tag()
+}
 
-                // Check if not excluded
-                let not_excluded = !consent.scope.exclusions.contains(&input.data_category);
+fn care_team_link_tag(team: &CareTeam) -> LinkTag {
+    LinkMetadata::new(&team.status, team.created_at, team.expires_at).to_tag()
+}
 
-                // Check if permission is granted
-                let permission_granted = consent.permissions.contains(&input.permission);
+fn notification_link_tag(notification: &AccessNotification) -> LinkTag {
+    LinkMetadata::new(&notification.read_state(), notification.accessed_at, None).to_tag()
+}
 
-                if category_covered && not_excluded && permission_granted {
-                    return Ok(AuthorizationResult {
-                        authorized: true,
-                        consent_hash: Some(record.action_address().clone()),
-                        reason: "Active consent found".to_string(),
-                        permissions: consent.permissions.clone(),
-                        emergency_override: false,
-                    });
-                }
-            }
+/// Move the patient's link from `previous` to its `updated` version under a
+/// new tag, so tag filters see the status change
+fn repoint_patient_link(
+    patient_hash: &ActionHash,
+    link_type: LinkTypes,
+    previous: &ActionHash,
+    updated: ActionHash,
+    tag: LinkTag,
+) -> ExternResult<()> {
+    let links = get_links(LinkQuery::try_new(patient_hash.clone(), link_type)?, GetStrategy::default())?;
+    for link in links {
+        if link.target.clone().into_action_hash().as_ref() == Some(previous) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
         }
     }
+    create_link(patient_hash.clone(), updated, link_type, tag)?;
+    Ok(())
+}
 
-    // Check if emergency access without consent
-    if input.is_emergency {
-        return Ok(AuthorizationResult {
-            authorized: false,
-            consent_hash: None,
-            reason: "No consent found - emergency override available".to_string(),
-            permissions: vec![input.permission],
-            emergency_override: true,
-        });
+/// List active consents whose `All` scope predates categories added since
+#[hdk_extern]
+pub fn get_consents_pending_reaffirmation(patient_hash: ActionHash) -> ExternResult<Vec<CategoryReaffirmation>> {
+    let mut pending = Vec::new();
+    for (consent_hash, consent) in current_active_consents(patient_hash)? {
+        let new_categories = consent.categories_pending_affirmation();
+        if !new_categories.is_empty() {
+            pending.push(CategoryReaffirmation {
+                consent_hash,
+                consent_id: consent.consent_id.clone(),
+                grantee: consent.grantee.clone(),
+                registry_version: consent.category_registry().version,
+                new_categories,
+            });
+        }
     }
-
-    Ok(AuthorizationResult {
-        authorized: false,
-        consent_hash: None,
-        reason: "No valid consent found".to_string(),
-        permissions: vec![],
-        emergency_override: false,
-    })
+    Ok(pending)
 }
 
-/// Input for authorization check - compatible with shared crate's AuthorizationInput
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AuthorizationCheckInput {
-    pub patient_hash: ActionHash,
-    pub requestor: AgentPubKey,
-    pub data_category: DataCategory,
-    pub permission: DataPermission,
-    pub is_emergency: bool,
-}
+/// Extend an `All`-scoped consent to every category known today
+///
+/// Only the patient can re-affirm; validation rejects updates by anyone else.
+#[hdk_extern]
+pub fn reaffirm_consent_categories(consent_hash: ActionHash) -> ExternResult<Record> {
+    let record = get(consent_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Consent not found"))?;
+    let mut consent: Consent = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
 
-/// Authorization result - compatible with shared crate's AuthorizationResult
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AuthorizationResult {
-    pub authorized: bool,
-    pub consent_hash: Option<ActionHash>,
-    pub reason: String,
-    /// Permissions granted by the consent
-    pub permissions: Vec<DataPermission>,
-    /// Whether this was an emergency override
-    pub emergency_override: bool,
+    if !matches!(consent.status, ConsentStatus::Active) {
+        return Err(conflict("Only active consents can be re-affirmed"));
+    }
+    if consent.categories_pending_affirmation().is_empty() {
+        return Ok(record);
+    }
+    consent.category_registry = Some(CategoryRegistry::current());
+
+    supersede_active_consent(consent_hash, &consent)
 }
 
-/// Create data access request
-#[hdk_extern]
-pub fn create_access_request(request: DataAccessRequest) -> ExternResult<Record> {
-    let request_hash = create_entry(&EntryTypes::DataAccessRequest(request.clone()))?;
-    let record = get(request_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find request".to_string())))?;
-    
+/// Replace an active consent with an amended version, keeping the patient
+/// and active-consent indexes pointing at the new one
+fn supersede_active_consent(consent_hash: ActionHash, consent: &Consent) -> ExternResult<Record> {
+    let updated_hash = update_entry(consent_hash.clone(), consent)?;
+    notify_webhooks(WebhookEventType::ConsentChanged, consent.patient_hash.clone(), updated_hash.clone());
+
     create_link(
-        request.patient_hash,
-        request_hash,
-        LinkTypes::PatientToAccessRequests,
+        consent_hash,
+        updated_hash.clone(),
+        LinkTypes::ConsentUpdates,
         (),
     )?;
-    
-    Ok(record)
-}
-
-/// Log data access
-#[hdk_extern]
-pub fn log_data_access(log: DataAccessLog) -> ExternResult<Record> {
-    let log_hash = create_entry(&EntryTypes::DataAccessLog(log.clone()))?;
-    let record = get(log_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find log".to_string())))?;
-    
     create_link(
-        log.patient_hash,
-        log_hash,
-        LinkTypes::PatientToAccessLogs,
+        consent.patient_hash.clone(),
+        updated_hash.clone(),
+        LinkTypes::PatientToConsents,
+        consent_link_tag(consent),
+    )?;
+    let active_anchor = anchor_hash("active_consents")?;
+    create_link(
+        active_anchor,
+        updated_hash.clone(),
+        LinkTypes::ActiveConsents,
         (),
     )?;
-    
-    Ok(record)
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated consent"))
 }
 
-/// Get patient's access logs
+/// Notify the patient about consents awaiting category re-affirmation
+///
+/// Each consent is notified once per registry version; returns the
+/// notifications created by this call.
 #[hdk_extern]
-pub fn get_access_logs(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        LinkQuery::try_new(patient_hash, LinkTypes::PatientToAccessLogs)?,
-        GetStrategy::default()
-    )?;
-
-    let mut logs = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                logs.push(record);
-            }
+pub fn notify_pending_category_reaffirmations(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let mut created = Vec::new();
+
+    for pending in get_consents_pending_reaffirmation(patient_hash.clone())? {
+        let notice_anchor = hash_entry(&Anchor(format!(
+            "category_notice:{}:{}",
+            pending.consent_hash, CATEGORY_REGISTRY_VERSION
+        )))?;
+        let already_notified = !get_links(
+            LinkQuery::try_new(notice_anchor.clone(), LinkTypes::CategoryReaffirmationNotices)?,
+            GetStrategy::default(),
+        )?
+        .is_empty();
+        if already_notified {
+            continue;
         }
+
+        let category_names: Vec<String> = pending.new_categories.iter().map(|c| format!("{:?}", c)).collect();
+        let notification = AccessNotification {
+            notification_id: format!("REAFFIRM-{}-{}", pending.consent_id, CATEGORY_REGISTRY_VERSION),
+            patient_hash: patient_hash.clone(),
+            accessor: me.clone(),
+            accessor_name: "Consent registry".to_string(),
+            data_categories: pending.new_categories.clone(),
+            purpose: "Re-affirm consent for new data categories".to_string(),
+            accessed_at: now,
+            emergency_access: false,
+            priority: NotificationPriority::Daily,
+            viewed: false,
+            viewed_at: None,
+            summary: format!(
+                "Your consent {} covers all of your records, but new categories ({}) were added since you granted it. They stay private until you re-affirm.",
+                pending.consent_id,
+                category_names.join(", ")
+            ),
+            access_log_hash: None,
+        };
+        let record = write_access_notification(notification)?;
+        create_link(
+            notice_anchor,
+            record.action_address().clone(),
+            LinkTypes::CategoryReaffirmationNotices,
+            (),
+        )?;
+        created.push(record);
     }
 
-    Ok(logs)
+    Ok(created)
+}
+
+// ============================================================
+// GRANTEE KEY CHANGES
+// ============================================================
+
+/// A grantee that moved from one agent key to another
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentKeyChange {
+    pub previous: AgentPubKey,
+    pub current: AgentPubKey,
 }
 
-/// Input format from shared crate's log_data_access function
 #[derive(Serialize, Deserialize, Debug)]
-pub struct AccessLogEntry {
-    pub log_id: String,
+pub struct GranteeReconfirmationInput {
     pub patient_hash: ActionHash,
-    pub accessor: AgentPubKey,
-    pub data_categories: Vec<DataCategory>,
-    pub access_type: DataPermission,
-    pub consent_hash: Option<ActionHash>,
-    pub access_reason: String,
-    pub accessed_at: Timestamp,
-    pub access_location: String,
-    pub emergency_override: bool,
-    pub override_reason: Option<String>,
+    pub key_changes: Vec<AgentKeyChange>,
 }
 
-/// Create access log - called by shared crate's log_data_access
+/// Suspend active consents granted to agents whose keys changed
+///
+/// Access never follows a grantee to a new key silently: each affected
+/// consent moves to the new key as `Pending` and the patient is asked to
+/// re-confirm it. Returns the suspended consents.
 #[hdk_extern]
-pub fn create_access_log(entry: AccessLogEntry) -> ExternResult<ActionHash> {
-    let log = DataAccessLog {
-        log_id: entry.log_id,
-        patient_hash: entry.patient_hash.clone(),
-        accessor: entry.accessor,
-        access_type: entry.access_type,
-        data_categories_accessed: entry.data_categories,
-        consent_hash: entry.consent_hash,
-        access_reason: entry.access_reason,
-        accessed_at: entry.accessed_at,
-        access_location: Some(entry.access_location),
-        emergency_override: entry.emergency_override,
-        override_reason: entry.override_reason,
-    };
+pub fn request_grantee_reconfirmation(input: GranteeReconfirmationInput) -> ExternResult<Vec<Record>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let mut suspended = Vec::new();
+
+    for (consent_hash, mut consent) in current_active_consents(input.patient_hash.clone())? {
+        let ConsentGrantee::Agent(grantee) = &consent.grantee else {
+            continue;
+        };
+        let Some(change) = input.key_changes.iter().find(|c| &c.previous == grantee) else {
+            continue;
+        };
+        consent.grantee = ConsentGrantee::Agent(change.current.clone());
+        consent.status = ConsentStatus::Pending;
+
+        let updated_hash = update_entry(consent_hash.clone(), &consent)?;
+        notify_webhooks(WebhookEventType::ConsentChanged, input.patient_hash.clone(), updated_hash.clone());
+        create_link(consent_hash, updated_hash.clone(), LinkTypes::ConsentUpdates, ())?;
+        create_link(
+            input.patient_hash.clone(),
+            updated_hash.clone(),
+            LinkTypes::PatientToConsents,
+            consent_link_tag(&consent),
+        )?;
+        create_link(
+            input.patient_hash.clone(),
+            updated_hash.clone(),
+            LinkTypes::PendingGranteeReconfirmations,
+            (),
+        )?;
+        count_active_consents(-1)?;
+
+        let data_categories = if consent.scope.data_categories.is_empty() {
+            vec![DataCategory::All]
+        } else {
+            consent.scope.data_categories.clone()
+        };
+        write_access_notification(AccessNotification {
+            notification_id: format!("RECONFIRM-{}-{}", consent.consent_id, now.as_micros()),
+            patient_hash: input.patient_hash.clone(),
+            accessor: me.clone(),
+            accessor_name: "Consent registry".to_string(),
+            data_categories,
+            purpose: "Re-confirm consent after a grantee key change".to_string(),
+            accessed_at: now,
+            emergency_access: false,
+            priority: NotificationPriority::Immediate,
+            viewed: false,
+            viewed_at: None,
+            summary: format!(
+                "Someone you granted access under consent {} now uses a new key. Their access is paused until you confirm the new key is really them.",
+                consent.consent_id
+            ),
+            access_log_hash: None,
+        })?;
+
+        suspended.push(
+            get(updated_hash, GetOptions::default())?
+                .ok_or(not_found("Could not find updated consent"))?,
+        );
+    }
 
-    let log_hash = create_entry(&EntryTypes::DataAccessLog(log))?;
+    Ok(suspended)
+}
 
-    create_link(
-        entry.patient_hash,
-        log_hash.clone(),
-        LinkTypes::PatientToAccessLogs,
-        (),
+/// Consents paused by a grantee key change and not yet re-confirmed
+#[hdk_extern]
+pub fn get_consents_awaiting_reconfirmation(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links_for(
+        LinkQuery::try_new(patient_hash, LinkTypes::PendingGranteeReconfirmations)?,
+        CallClass::ConsentStatus,
     )?;
 
-    Ok(log_hash)
+    let mut awaiting = Vec::new();
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        if let Some(record) = get_for(hash, CallClass::ConsentStatus)? {
+            let pending = record
+                .entry()
+                .to_app_option::<Consent>()
+                .ok()
+                .flatten()
+                .is_some_and(|consent| matches!(consent.status, ConsentStatus::Pending));
+            if pending {
+                awaiting.push(record);
+            }
+        }
+    }
+    Ok(awaiting)
 }
 
-/// Denied access log entry from shared crate
+/// Restore a paused consent for the grantee's new key
+#[hdk_extern]
+pub fn reconfirm_grantee_consent(consent_hash: ActionHash) -> ExternResult<Record> {
+    let record = get_for(consent_hash.clone(), CallClass::ConsentStatus)?
+        .ok_or(not_found("Consent not found"))?;
+    let mut consent: Consent = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
+
+    let pending_link = get_links_for(
+        LinkQuery::try_new(consent.patient_hash.clone(), LinkTypes::PendingGranteeReconfirmations)?,
+        CallClass::ConsentStatus,
+    )?
+    .into_iter()
+    .find(|link| link.target.clone().into_action_hash().as_ref() == Some(&consent_hash))
+    .ok_or(conflict("Consent is not awaiting grantee re-confirmation"))?;
+    if !matches!(consent.status, ConsentStatus::Pending) {
+        return Err(conflict("Only pending consents can be re-confirmed"));
+    }
+
+    consent.status = ConsentStatus::Active;
+    let updated = supersede_active_consent(consent_hash, &consent)?;
+    delete_link(pending_link.create_link_hash, GetOptions::default())?;
+    count_active_consents(1)?;
+    Ok(updated)
+}
+
+// ============================================================
+// CONSENT COVERAGE GAPS
+// ============================================================
+
+/// An active consent that neither covers nor excludes a category the
+/// patient's record now contains
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CoverageGap {
+    pub category: DataCategory,
+    pub consent_hash: ActionHash,
+    pub consent_id: String,
+    pub grantee: ConsentGrantee,
+}
+
+/// Categories found in newly ingested data
 #[derive(Serialize, Deserialize, Debug)]
-pub struct AccessDeniedLogEntry {
-    pub log_id: String,
+pub struct AnalyzeCoverageInput {
     pub patient_hash: ActionHash,
-    pub attempted_accessor: AgentPubKey,
-    pub data_category: DataCategory,
-    pub denial_reason: String,
-    pub attempted_at: Timestamp,
+    pub categories: Vec<DataCategory>,
 }
 
-/// Create access denied log - called by shared crate's log_access_denied
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CoverageAnalysis {
+    /// Categories seen for this patient for the first time
+    pub new_categories: Vec<DataCategory>,
+    pub gaps: Vec<CoverageGap>,
+    /// CoverageGap notifications created by this call
+    pub notifications: Vec<Record>,
+}
+
+/// Compare categories present in newly ingested data against active
+/// consent coverage, prompting the patient about any new category that an
+/// active consent does not yet address
 #[hdk_extern]
-pub fn create_access_denied_log(entry: AccessDeniedLogEntry) -> ExternResult<ActionHash> {
-    // Store denied access attempts for security monitoring
-    let log = DataAccessLog {
-        log_id: entry.log_id,
-        patient_hash: entry.patient_hash.clone(),
-        accessor: entry.attempted_accessor,
-        access_type: DataPermission::Read,
-        data_categories_accessed: vec![entry.data_category],
-        consent_hash: None,
-        access_reason: format!("DENIED: {}", entry.denial_reason),
-        accessed_at: entry.attempted_at,
-        access_location: None,
-        emergency_override: false,
-        override_reason: None,
-    };
+pub fn analyze_consent_coverage(input: AnalyzeCoverageInput) -> ExternResult<CoverageAnalysis> {
+    let known = data_profile_categories(input.patient_hash.clone())?;
+    let mut new_categories: Vec<DataCategory> = Vec::new();
+    for category in input.categories {
+        if !matches!(category, DataCategory::All) && !known.contains(&category) && !new_categories.contains(&category) {
+            new_categories.push(category);
+        }
+    }
 
-    let log_hash = create_entry(&EntryTypes::DataAccessLog(log))?;
+    for category in &new_categories {
+        let category_name = format!("{:?}", category);
+        create_link(
+            input.patient_hash.clone(),
+            hash_entry(&Anchor(format!("data_category:{}", category_name)))?,
+            LinkTypes::PatientToDataCategories,
+            LinkTag::new(category_name.into_bytes()),
+        )?;
+    }
 
-    // Link to patient for audit trail
-    create_link(
-        entry.patient_hash.clone(),
-        log_hash.clone(),
-        LinkTypes::PatientToAccessLogs,
-        (),
-    )?;
+    let gaps = coverage_gaps_for(input.patient_hash.clone(), &new_categories)?;
+    let notifications = notify_coverage_gaps(&input.patient_hash, &gaps)?;
 
-    // Also link to a denied access anchor for security monitoring
-    let denied_anchor = anchor_hash("denied_access_attempts")?;
-    create_link(
-        denied_anchor,
-        log_hash.clone(),
-        LinkTypes::PatientToAccessLogs, // Reusing link type
-        (),
-    )?;
+    Ok(CoverageAnalysis {
+        new_categories,
+        gaps,
+        notifications,
+    })
+}
 
-    Ok(log_hash)
-}
-
-/// Record emergency access (break-glass)
-#[hdk_extern]
-pub fn record_emergency_access(emergency: EmergencyAccess) -> ExternResult<Record> {
-    let emergency_hash = create_entry(&EntryTypes::EmergencyAccess(emergency.clone()))?;
-    let record = get(emergency_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find emergency access".to_string())))?;
-    
-    create_link(
-        emergency.patient_hash,
-        emergency_hash,
-        LinkTypes::PatientToEmergencyAccess,
-        (),
-    )?;
-    
-    Ok(record)
-}
-
-/// Create authorization document
+/// Domain event handlers this zome registers with the bridge's event bus
 #[hdk_extern]
-pub fn create_authorization_document(doc: AuthorizationDocument) -> ExternResult<Record> {
-    let doc_hash = create_entry(&EntryTypes::AuthorizationDocument(doc.clone()))?;
-    let record = get(doc_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find document".to_string())))?;
-    
-    create_link(
-        doc.patient_hash,
-        doc_hash,
-        LinkTypes::PatientToDocuments,
-        (),
-    )?;
-    
-    Ok(record)
+pub fn domain_event_subscriptions(_: ()) -> ExternResult<Vec<DomainEventHandler>> {
+    Ok(vec![DomainEventHandler::new("handle_ingest_completed", &[DomainEventKind::IngestCompleted])])
 }
 
-/// Get patient's authorization documents
+/// Run coverage analysis over the categories a completed ingest brought in
 #[hdk_extern]
-pub fn get_authorization_documents(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToDocuments)?, GetStrategy::default())?;
-    
-    let mut docs = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                docs.push(record);
-            }
-        }
+pub fn handle_ingest_completed(notice: DomainEventNotice) -> ExternResult<()> {
+    let DomainEventPayload::IngestCompleted { data_categories, .. } = notice.payload else {
+        return Ok(());
+    };
+    if data_categories.is_empty() {
+        return Ok(());
     }
-    
-    Ok(docs)
+    analyze_consent_coverage(AnalyzeCoverageInput {
+        patient_hash: notice.patient_hash,
+        categories: data_categories.iter().map(consent_category).collect(),
+    })?;
+    Ok(())
 }
 
-/// Get access logs filtered by date range
+/// List every unaddressed category across the patient's active consents
 #[hdk_extern]
-pub fn get_access_logs_by_date(input: DateRangeInput) -> ExternResult<Vec<Record>> {
-    let all_logs = get_access_logs(input.patient_hash)?;
-
-    let filtered: Vec<Record> = all_logs
-        .into_iter()
-        .filter(|record| {
-            if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
-                log.accessed_at >= input.start_date && log.accessed_at <= input.end_date
-            } else {
-                false
-            }
-        })
-        .collect();
+pub fn get_coverage_gaps(patient_hash: ActionHash) -> ExternResult<Vec<CoverageGap>> {
+    let categories = data_profile_categories(patient_hash.clone())?;
+    coverage_gaps_for(patient_hash, &categories)
+}
 
-    Ok(filtered)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum CoverageDecision {
+    Include,
+    Exclude,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct DateRangeInput {
-    pub patient_hash: ActionHash,
-    pub start_date: Timestamp,
-    pub end_date: Timestamp,
+pub struct ResolveCoverageGapInput {
+    pub consent_hash: ActionHash,
+    pub category: DataCategory,
+    pub decision: CoverageDecision,
 }
 
-/// Get access logs for a specific accessor (HIPAA audit trail)
+/// Explicitly include a category in, or exclude it from, an active consent
+///
+/// Only the patient can amend; validation rejects updates by anyone else.
 #[hdk_extern]
-pub fn get_access_logs_by_accessor(input: AccessorLogsInput) -> ExternResult<Vec<Record>> {
-    let all_logs = get_access_logs(input.patient_hash)?;
+pub fn resolve_coverage_gap(input: ResolveCoverageGapInput) -> ExternResult<Record> {
+    let record = get(input.consent_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Consent not found"))?;
+    let mut consent: Consent = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
 
-    let filtered: Vec<Record> = all_logs
-        .into_iter()
-        .filter(|record| {
-            if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
-                log.accessor == input.accessor
-            } else {
-                false
-            }
-        })
-        .collect();
+    if !matches!(consent.status, ConsentStatus::Active) {
+        return Err(conflict("Only active consents can be amended"));
+    }
+    if matches!(input.category, DataCategory::All) {
+        return Err(invalid_input("Coverage decisions are made per category"));
+    }
 
-    Ok(filtered)
-}
+    let original = consent.clone();
+    match input.decision {
+        CoverageDecision::Include => {
+            consent.scope.exclusions.retain(|c| c != &input.category);
+            if !consent.covers_category(&input.category) {
+                consent.scope.data_categories.push(input.category);
+            }
+        }
+        CoverageDecision::Exclude => {
+            if !consent.scope.exclusions.contains(&input.category) {
+                consent.scope.exclusions.push(input.category);
+            }
+        }
+    }
+    if consent == original {
+        return Ok(record);
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AccessorLogsInput {
-    pub patient_hash: ActionHash,
-    pub accessor: AgentPubKey,
+    supersede_active_consent(input.consent_hash, &consent)
 }
 
-/// Get all emergency access events (break-glass audit)
-#[hdk_extern]
-pub fn get_emergency_access_events(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToEmergencyAccess)?, GetStrategy::default())?;
-
-    let mut events = Vec::new();
+/// Categories recorded in the patient's data profile
+fn data_profile_categories(patient_hash: ActionHash) -> ExternResult<Vec<DataCategory>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToDataCategories)?,
+        GetStrategy::default(),
+    )?;
+    let mut categories: Vec<DataCategory> = Vec::new();
     for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                events.push(record);
+        let Ok(name) = String::from_utf8(link.tag.0) else {
+            continue;
+        };
+        if let Ok(category) = serde_json::from_value::<DataCategory>(serde_json::Value::String(name)) {
+            if !categories.contains(&category) {
+                categories.push(category);
             }
         }
     }
+    Ok(categories)
+}
 
-    Ok(events)
+fn coverage_gaps_for(patient_hash: ActionHash, categories: &[DataCategory]) -> ExternResult<Vec<CoverageGap>> {
+    let mut gaps = Vec::new();
+    if categories.is_empty() {
+        return Ok(gaps);
+    }
+    for (consent_hash, consent) in current_active_consents(patient_hash)? {
+        for category in categories {
+            if !consent.addresses_category(category) {
+                gaps.push(CoverageGap {
+                    category: category.clone(),
+                    consent_hash: consent_hash.clone(),
+                    consent_id: consent.consent_id.clone(),
+                    grantee: consent.grantee.clone(),
+                });
+            }
+        }
+    }
+    Ok(gaps)
 }
 
-/// Generate HIPAA-compliant accounting of disclosures report
-#[hdk_extern]
-pub fn generate_disclosure_report(input: DisclosureReportInput) -> ExternResult<DisclosureReport> {
-    let logs = get_access_logs_by_date(DateRangeInput {
-        patient_hash: input.patient_hash.clone(),
-        start_date: input.start_date,
-        end_date: input.end_date,
-    })?;
+/// One CoverageGap notification per category, sent once per patient
+fn notify_coverage_gaps(patient_hash: &ActionHash, gaps: &[CoverageGap]) -> ExternResult<Vec<Record>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let mut created = Vec::new();
 
-    let mut disclosures = Vec::new();
-    for record in logs {
-        if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
-            disclosures.push(DisclosureEntry {
-                accessed_at: log.accessed_at,
-                accessor: log.accessor,
-                data_categories: log.data_categories_accessed.iter()
-                    .map(|c| format!("{:?}", c))
-                    .collect(),
-                access_reason: log.access_reason.clone(),
-                consent_hash: log.consent_hash.clone(),
-                emergency_override: log.emergency_override,
-            });
+    let mut categories: Vec<&DataCategory> = Vec::new();
+    for gap in gaps {
+        if !categories.contains(&&gap.category) {
+            categories.push(&gap.category);
         }
     }
 
-    Ok(DisclosureReport {
-        patient_hash: input.patient_hash,
-        generated_at: sys_time()?,
-        period_start: input.start_date,
-        period_end: input.end_date,
-        total_disclosures: disclosures.len() as u32,
-        disclosures,
-    })
-}
+    for category in categories {
+        let notice_anchor = hash_entry(&Anchor(format!("coverage_gap:{}:{:?}", patient_hash, category)))?;
+        let already_notified = !get_links(
+            LinkQuery::try_new(notice_anchor.clone(), LinkTypes::CoverageGapNotices)?,
+            GetStrategy::default(),
+        )?
+        .is_empty();
+        if already_notified {
+            continue;
+        }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct DisclosureReportInput {
-    pub patient_hash: ActionHash,
-    pub start_date: Timestamp,
-    pub end_date: Timestamp,
+        let consent_ids: Vec<&str> = gaps
+            .iter()
+            .filter(|g| &g.category == category)
+            .map(|g| g.consent_id.as_str())
+            .collect();
+        let notification = AccessNotification {
+            notification_id: format!("COVERAGE-GAP-{:?}-{}", category, now.as_micros()),
+            patient_hash: patient_hash.clone(),
+            accessor: me.clone(),
+            accessor_name: "Consent coverage review".to_string(),
+            data_categories: vec![category.clone()],
+            purpose: "Include or exclude a new data category in your consents".to_string(),
+            accessed_at: now,
+            emergency_access: false,
+            priority: NotificationPriority::Immediate,
+            viewed: false,
+            viewed_at: None,
+            summary: format!(
+                "Your record now contains {:?} data, which your consents ({}) do not mention. Choose whether each should include or exclude it.",
+                category,
+                consent_ids.join(", ")
+            ),
+            access_log_hash: None,
+        };
+        let record = write_access_notification(notification)?;
+        create_link(
+            notice_anchor,
+            record.action_address().clone(),
+            LinkTypes::CoverageGapNotices,
+            (),
+        )?;
+        created.push(record);
+    }
+
+    Ok(created)
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct DisclosureReport {
-    pub patient_hash: ActionHash,
-    pub generated_at: Timestamp,
-    pub period_start: Timestamp,
-    pub period_end: Timestamp,
-    pub total_disclosures: u32,
-    pub disclosures: Vec<DisclosureEntry>,
+/// Check if access is authorized
+/// Called by the shared crate's require_authorization() function
+///
+/// Allowed reads and exports leave an authorization receipt for the audit
+/// coverage report.
+#[hdk_extern]
+pub fn check_authorization(input: AuthorizationCheckInput) -> ExternResult<AuthorizationResult> {
+    instrumented("consent", "check_authorization", || {
+        if let Some(context) = &input.access_context {
+            context.validate().into_result()?;
+        }
+        let result = authorization_decision(&input)?;
+        let reads_data = matches!(input.permission, DataPermission::Read | DataPermission::Export);
+        if reads_data && (result.authorized || result.emergency_override) {
+            record_authorization_receipt(&input, result.consent_hash.clone(), result.emergency_override)?;
+        }
+        Ok(result)
+    })
 }
 
+/// A decision the calling zome reused from its authorization cache -
+/// compatible with shared crate's CachedAuthorizationInput
 #[derive(Serialize, Deserialize, Debug)]
-pub struct DisclosureEntry {
-    pub accessed_at: Timestamp,
-    pub accessor: AgentPubKey,
-    pub data_categories: Vec<String>,
-    pub access_reason: String,
+pub struct CachedAuthorizationInput {
+    pub check: AuthorizationCheckInput,
     pub consent_hash: Option<ActionHash>,
-    pub emergency_override: bool,
 }
 
-/// Log consent view (for tracking patient access to their own data)
+/// Leave the authorization receipt for an access allowed from a zome's
+/// authorization cache, so cached reads still show in the audit coverage
+/// report
 #[hdk_extern]
-pub fn log_consent_view(input: ConsentViewInput) -> ExternResult<()> {
-    let log = DataAccessLog {
-        log_id: format!("VIEW-{:?}", sys_time()?),
-        patient_hash: input.patient_hash.clone(),
-        accessor: agent_info()?.agent_initial_pubkey,
-        access_type: DataPermission::Read,
-        data_categories_accessed: input.data_categories.clone(),
-        consent_hash: Some(input.consent_hash),
-        access_reason: "Patient self-access".to_string(),
-        accessed_at: sys_time()?,
-        access_location: None,
-        emergency_override: false,
-        override_reason: None,
-    };
+pub fn record_cached_authorization(input: CachedAuthorizationInput) -> ExternResult<()> {
+    if input.check.requestor != agent_info()?.agent_initial_pubkey {
+        return Err(unauthorized("Agents only record receipts for their own access"));
+    }
+    if !matches!(input.check.permission, DataPermission::Read | DataPermission::Export) {
+        return Ok(());
+    }
+    record_authorization_receipt(&input.check, input.consent_hash, false)
+}
 
-    let log_hash = create_entry(&EntryTypes::DataAccessLog(log))?;
+fn authorization_decision(input: &AuthorizationCheckInput) -> ExternResult<AuthorizationResult> {
+    // Registered service agents are held to their allowlist whatever the
+    // consents say, and cannot break glass past it
+    let registration = service_registration_for(&input.requestor)?;
+    let assurance_level = identity_assurance_for(&input.requestor, registration.as_ref())?;
+    if let Some(registration) = registration {
+        if !registration.allows(&input.data_category, &input.permission, sys_time()?) {
+            return Ok(AuthorizationResult {
+                authorized: false,
+                consent_hash: None,
+                reason: format!(
+                    "Service agent {} is not registered for {:?} {:?}",
+                    registration.service_name, input.permission, input.data_category
+                ),
+                permissions: vec![],
+                emergency_override: false,
+                assurance_level,
+                decisive_policy: None,
+            });
+        }
+    }
 
-    // Link to patient
-    create_link(
-        input.patient_hash,
-        log_hash,
-        LinkTypes::PatientToAccessLogs,
-        (),
-    )?;
+    // Consents, delegations and care teams are weighed together; an
+    // exclusion in any of them overrides every grant
+    let grants = policy_grants(input, assurance_level)?;
+    let decision = combine_deny_overrides(grants.iter().map(|grant| grant.statement.clone()).collect());
+    if decision.permitted() {
+        let decisive = decision.decisive.clone();
+        let grant = grants.iter().find(|grant| Some(&grant.statement) == decisive.as_ref());
+        return Ok(AuthorizationResult {
+            authorized: true,
+            consent_hash: decisive
+                .as_ref()
+                .filter(|statement| statement.source == PolicySource::Consent)
+                .map(|statement| statement.policy_hash.clone()),
+            reason: decision.explanation,
+            permissions: grant.map_or_else(|| vec![input.permission.clone()], |grant| grant.permissions.clone()),
+            emergency_override: false,
+            assurance_level,
+            decisive_policy: decisive,
+        });
+    }
 
-    Ok(())
-}
+    // Check if emergency access without consent
+    if input.is_emergency {
+        if !get_sensitivity_matrix(())?.allows_break_glass(&shared_category(&input.data_category)) {
+            return Ok(AuthorizationResult {
+                authorized: false,
+                consent_hash: None,
+                reason: format!("Emergency override is not allowed for {:?} without consent", input.data_category),
+                permissions: vec![],
+                emergency_override: false,
+                assurance_level,
+                decisive_policy: decision.decisive,
+            });
+        }
+        return Ok(AuthorizationResult {
+            authorized: false,
+            consent_hash: None,
+            reason: "No consent found - emergency override available".to_string(),
+            permissions: vec![input.permission.clone()],
+            emergency_override: true,
+            assurance_level,
+            decisive_policy: decision.decisive,
+        });
+    }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct ConsentViewInput {
-    pub patient_hash: ActionHash,
-    pub consent_hash: ActionHash,
-    pub data_categories: Vec<DataCategory>,
+    let reason = match decision.effect {
+        PolicyEffect::NotApplicable if decision.statements.is_empty() => "No valid consent found".to_string(),
+        _ => decision.explanation,
+    };
+    Ok(AuthorizationResult {
+        authorized: false,
+        consent_hash: None,
+        reason,
+        permissions: vec![],
+        emergency_override: false,
+        assurance_level,
+        decisive_policy: decision.decisive,
+    })
 }
 
-/// Update consent (e.g., extend expiration, modify scope)
-#[hdk_extern]
-pub fn update_consent(input: UpdateConsentInput) -> ExternResult<Record> {
-    let updated_hash = update_entry(input.original_hash.clone(), &input.updated_consent)?;
-    let record = get(updated_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated consent".to_string())))?;
-
-    // Create audit trail link
-    create_link(
-        input.original_hash,
-        updated_hash,
-        LinkTypes::ConsentUpdates,
-        (),
-    )?;
+// ============================================================
+// POLICY COMBINATION
+// ============================================================
 
-    Ok(record)
+/// A policy statement and the permissions its grant carries
+struct PolicyGrant {
+    statement: PolicyStatement,
+    permissions: Vec<DataPermission>,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct UpdateConsentInput {
-    pub original_hash: ActionHash,
-    pub updated_consent: Consent,
+/// The delegation permission that lets a delegate exercise `permission`
+fn delegation_permission_for(permission: &DataPermission) -> Option<DelegationPermission> {
+    match permission {
+        DataPermission::Read => Some(DelegationPermission::ViewRecords),
+        DataPermission::Export => Some(DelegationPermission::ExportData),
+        _ => None,
+    }
 }
 
-/// Get consent history (all versions for audit trail)
-#[hdk_extern]
-pub fn get_consent_history(consent_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(LinkQuery::try_new(consent_hash.clone(), LinkTypes::ConsentUpdates)?, GetStrategy::default())?;
+/// What each of the patient's active consents, delegations and care teams
+/// says about the request; grants that do not name the requestor say nothing
+fn policy_grants(input: &AuthorizationCheckInput, assurance_level: IdentityAssuranceLevel) -> ExternResult<Vec<PolicyGrant>> {
+    let category = &input.data_category;
+    let mut grants = Vec::new();
+    let mut push = |source: PolicySource, policy_hash: ActionHash, effect: PolicyEffect, explanation: String, permissions: Vec<DataPermission>| {
+        grants.push(PolicyGrant {
+            statement: PolicyStatement { source, policy_hash, effect, explanation },
+            permissions,
+        });
+    };
 
-    let mut history = Vec::new();
+    for (consent_hash, consent) in current_active_consents(input.patient_hash.clone())? {
+        let grantee_matches = match &consent.grantee {
+            ConsentGrantee::Agent(agent) => *agent == input.requestor,
+            ConsentGrantee::EmergencyAccess => input.is_emergency,
+            _ => false,
+        };
+        if !grantee_matches {
+            continue;
+        }
+        if consent.scope.exclusions.contains(category) {
+            push(PolicySource::Consent, consent_hash, PolicyEffect::Deny, format!("Consent excludes {:?}", category), vec![]);
+            continue;
+        }
+        // Categories added after an `All` consent was granted need re-affirmation
+        if !consent.covers_category(category) || !consent.permissions.contains(&input.permission) {
+            continue;
+        }
+        // Patients may demand stronger identity proof for sensitive
+        // categories than a self-asserted profile
+        let required = consent.scope.required_assurance(category);
+        if assurance_level < required {
+            let explanation = format!("Consent needs {:?} identity assurance; requestor has {:?}", required, assurance_level);
+            push(PolicySource::Consent, consent_hash, PolicyEffect::NotApplicable, explanation, vec![]);
+            continue;
+        }
+        push(PolicySource::Consent, consent_hash, PolicyEffect::Permit, "Active consent found".to_string(), consent.permissions);
+    }
 
-    // Add original
-    if let Some(original) = get(consent_hash, GetOptions::default())? {
-        history.push(original);
+    let delegated = delegation_permission_for(&input.permission);
+    for record in get_active_delegations(input.patient_hash.clone())? {
+        let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() else {
+            continue;
+        };
+        if delegation.delegate != input.requestor {
+            continue;
+        }
+        let delegation_hash = record.action_address().clone();
+        if delegation.exclusions.contains(category) {
+            push(PolicySource::Delegation, delegation_hash, PolicyEffect::Deny, format!("Delegation excludes {:?}", category), vec![]);
+            continue;
+        }
+        let in_scope = delegation.data_scope.iter().any(|cat| matches!(cat, DataCategory::All) || cat == category);
+        if in_scope && delegated.as_ref().is_some_and(|permission| delegation.permissions.contains(permission)) {
+            let explanation = format!("Active {:?} delegation found", delegation.delegation_type);
+            push(PolicySource::Delegation, delegation_hash, PolicyEffect::Permit, explanation, vec![input.permission.clone()]);
+        }
     }
 
-    // Add all updates
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                history.push(record);
+    for record in get_active_care_teams(input.patient_hash.clone())? {
+        let Some(team) = record.entry().to_app_option::<CareTeam>().ok().flatten() else {
+            continue;
+        };
+        let mut role = None;
+        for member in team.members.iter().filter(|member| member.active) {
+            if care_team_member_is(&member.member, &input.requestor)? {
+                role = Some(member.role.clone());
+                break;
             }
         }
+        let Some(role) = role else {
+            continue;
+        };
+        let team_hash = record.action_address().clone();
+        if team.exclusions.contains(category) {
+            let explanation = format!("Care team {} excludes {:?}", team.team_name, category);
+            push(PolicySource::CareTeam, team_hash, PolicyEffect::Deny, explanation, vec![]);
+        } else if team.role_allows(&role, &input.permission, category) {
+            let explanation = format!("Member of care team {} as {:?}", team.team_name, role);
+            push(PolicySource::CareTeam, team_hash, PolicyEffect::Permit, explanation, vec![input.permission.clone()]);
+        }
     }
 
-    Ok(history)
+    Ok(grants)
 }
 
-/// Anchor entry for indexing
-#[hdk_entry_helper]
-#[derive(Clone, PartialEq)]
-pub struct Anchor(pub String);
+/// Why `category` is closed to `agent` whatever any single grant allows:
+/// an exclusion in one of the patient's consents, delegations or care teams
+fn overriding_denial(patient_hash: &ActionHash, agent: &AgentPubKey, category: &DataCategory) -> ExternResult<Option<String>> {
+    // Exclusions apply to every permission, so any will do here
+    let input = AuthorizationCheckInput {
+        patient_hash: patient_hash.clone(),
+        requestor: agent.clone(),
+        data_category: category.clone(),
+        permission: DataPermission::Read,
+        is_emergency: false,
+        access_path: None,
+        access_context: None,
+    };
+    let grants = policy_grants(&input, IdentityAssuranceLevel::default())?;
+    let decision = combine_deny_overrides(grants.into_iter().map(|grant| grant.statement).collect());
+    Ok((decision.effect == PolicyEffect::Deny).then_some(decision.explanation))
+}
 
-fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
-    let anchor = Anchor(anchor_text.to_string());
-    hash_entry(&anchor)
+/// Weigh every consent, delegation and care team that names the requestor,
+/// returning the decisive policy and why
+#[hdk_extern]
+pub fn evaluate_access_policies(input: AuthorizationCheckInput) -> ExternResult<PolicyDecision> {
+    let registration = service_registration_for(&input.requestor)?;
+    let assurance_level = identity_assurance_for(&input.requestor, registration.as_ref())?;
+    let grants = policy_grants(&input, assurance_level)?;
+    Ok(combine_deny_overrides(grants.into_iter().map(|grant| grant.statement).collect()))
 }
 
-// ============================================================
-// CONSENT DELEGATION SYSTEM
-// ============================================================
+/// Identity assurance for an agent: an active service registration counts as
+/// an organization countersignature, licenses come from provider attestations
+///
+/// An unreachable provider zome leaves the agent at whatever the registration
+/// alone establishes rather than failing the authorization.
+fn identity_assurance_for(
+    agent: &AgentPubKey,
+    registration: Option<&ServiceAgentRegistration>,
+) -> ExternResult<IdentityAssuranceLevel> {
+    let mut attestations: IdentityAttestations = resilient_call(
+        "provider",
+        "get_identity_attestations",
+        agent,
+        CallClass::Authorization,
+    )
+    .unwrap_or_default();
+    let now = sys_time()?;
+    if registration.is_some_and(|registration| registration.is_active(now)) {
+        attestations.organization_countersigned = true;
+    }
+    Ok(attestations.assurance_level())
+}
 
-/// Create a new delegation grant
-#[hdk_extern]
-pub fn create_delegation(delegation: DelegationGrant) -> ExternResult<Record> {
-    let delegation_hash = create_entry(&EntryTypes::DelegationGrant(delegation.clone()))?;
-    let record = get(delegation_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find delegation".to_string())))?;
+/// Input for authorization check - compatible with shared crate's AuthorizationInput
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuthorizationCheckInput {
+    pub patient_hash: ActionHash,
+    pub requestor: AgentPubKey,
+    pub data_category: DataCategory,
+    pub permission: DataPermission,
+    pub is_emergency: bool,
+    #[serde(default)]
+    pub access_path: Option<String>,
+    #[serde(default)]
+    pub access_context: Option<AccessContext>,
+}
 
-    // Link to patient
-    create_link(
-        delegation.patient_hash.clone(),
-        delegation_hash.clone(),
-        LinkTypes::PatientToDelegations,
-        (),
-    )?;
+/// Authorization result - compatible with shared crate's AuthorizationResult
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuthorizationResult {
+    pub authorized: bool,
+    pub consent_hash: Option<ActionHash>,
+    pub reason: String,
+    /// Permissions granted by the consent
+    pub permissions: Vec<DataPermission>,
+    /// Whether this was an emergency override
+    pub emergency_override: bool,
+    /// Identity assurance the requestor held when the decision was made
+    #[serde(default)]
+    pub assurance_level: IdentityAssuranceLevel,
+    /// The consent, delegation or care team that decided the request
+    #[serde(default)]
+    pub decisive_policy: Option<PolicyStatement>,
+}
 
-    // Link to delegate
-    let delegate_anchor = hash_entry(&Anchor(format!("delegate:{:?}", delegation.delegate)))?;
+/// Create data access request
+#[hdk_extern]
+pub fn create_access_request(request: DataAccessRequest) -> ExternResult<Record> {
+    let request_hash = create_entry(&EntryTypes::DataAccessRequest(request.clone()))?;
+    let record = get(request_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find request"))?;
+    
     create_link(
-        delegate_anchor,
-        delegation_hash.clone(),
-        LinkTypes::DelegateToDelegations,
+        request.patient_hash,
+        request_hash,
+        LinkTypes::PatientToAccessRequests,
         (),
     )?;
-
-    // Link to active delegations if active
-    if matches!(delegation.status, DelegationStatus::Active) {
-        let active_anchor = anchor_hash("active_delegations")?;
-        create_link(
-            active_anchor,
-            delegation_hash,
-            LinkTypes::ActiveDelegations,
-            (),
-        )?;
-    }
-
+    
     Ok(record)
 }
 
-/// Get patient's delegations
+/// Log data access
 #[hdk_extern]
-pub fn get_patient_delegations(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        LinkQuery::try_new(patient_hash, LinkTypes::PatientToDelegations)?,
-        GetStrategy::default()
-    )?;
-
-    let mut delegations = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                delegations.push(record);
-            }
-        }
-    }
-
-    Ok(delegations)
+pub fn log_data_access(log: DataAccessLog) -> ExternResult<Record> {
+    let log_hash = append_access_log(log)?;
+    get(log_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find log"))
 }
 
-/// Get active delegations for a patient
-#[hdk_extern]
-pub fn get_active_delegations(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let all_delegations = get_patient_delegations(patient_hash)?;
-
-    let active: Vec<Record> = all_delegations
-        .into_iter()
-        .filter(|record| {
-            if let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() {
-                matches!(delegation.status, DelegationStatus::Active)
-            } else {
-                false
-            }
-        })
-        .collect();
+/// Commit an access log as the new head of its patient's audit chain
+fn append_access_log(mut log: DataAccessLog) -> ExternResult<ActionHash> {
+    let anchor = audit_chain_anchor(&log.patient_hash)?;
+    let head = get_links(
+        LinkQuery::try_new(anchor.clone(), LinkTypes::AuditChain)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| Some((tag_sequence(&link.tag)?, link.target.into_action_hash()?)))
+    .max_by_key(|(sequence, _)| *sequence);
+    let sequence = head.as_ref().map_or(0, |(sequence, _)| sequence + 1);
+    log.chain_sequence = Some(sequence);
+    log.previous_log = head.map(|(_, hash)| hash);
 
-    Ok(active)
+    let log_hash = create_entry(&EntryTypes::DataAccessLog(log.clone()))?;
+    create_link(log.patient_hash, log_hash.clone(), LinkTypes::PatientToAccessLogs, ())?;
+    create_link(
+        anchor,
+        log_hash.clone(),
+        LinkTypes::AuditChain,
+        LinkTag::new(sequence.to_be_bytes().to_vec()),
+    )?;
+    Ok(log_hash)
 }
 
-/// Revoke a delegation
-#[hdk_extern]
-pub fn revoke_delegation(input: RevokeDelegationInput) -> ExternResult<Record> {
-    let record = get(input.delegation_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Delegation not found".to_string())))?;
-
-    let mut delegation: DelegationGrant = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid delegation".to_string())))?;
-
-    delegation.status = DelegationStatus::Revoked;
-    delegation.revoked_at = Some(sys_time()?);
-    delegation.revocation_reason = Some(input.reason);
-
-    let updated_hash = update_entry(input.delegation_hash, &delegation)?;
-
-    get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated delegation".to_string())))
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum AuditChainProblem {
+    /// No chained log carries this sequence number
+    MissingSequence(u64),
+    /// Several logs claim one sequence number, from concurrent writers
+    Fork { sequence: u64, logs: Vec<ActionHash> },
+    /// A log the chain points at cannot be retrieved
+    MissingLog { sequence: u64, log: ActionHash },
+    /// A log on the chain has been deleted
+    DeletedLog { sequence: u64, log: ActionHash },
+    /// The log does not point at the patient's log one sequence number earlier
+    BrokenChain { sequence: u64, log: ActionHash },
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct RevokeDelegationInput {
-    pub delegation_hash: ActionHash,
-    pub reason: String,
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuditChainVerification {
+    pub patient_hash: ActionHash,
+    pub length: u64,
+    pub head: Option<ActionHash>,
+    pub verified: bool,
+    pub problems: Vec<AuditChainProblem>,
 }
 
-/// Check if delegate has authorization for patient
+/// Walk a patient's access log chain back from its head, reporting gaps,
+/// deletions and forks (patient or auditors)
 #[hdk_extern]
-pub fn check_delegation_authorization(input: DelegationAuthInput) -> ExternResult<DelegationAuthResult> {
-    let delegations = get_active_delegations(input.patient_hash.clone())?;
-
-    for record in delegations {
-        if let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() {
-            if delegation.delegate == input.delegate {
-                // Check if permission is granted
-                let permission_granted = delegation.permissions.contains(&input.permission);
-
-                // Check if data category is covered
-                let category_covered = delegation.data_scope.iter().any(|cat| {
-                    matches!(cat, DataCategory::All) || *cat == input.data_category
-                });
-
-                // Check if not excluded
-                let not_excluded = !delegation.exclusions.contains(&input.data_category);
+pub fn verify_audit_chain(patient_hash: ActionHash) -> ExternResult<AuditChainVerification> {
+    if !is_auditor(agent_info()?.agent_initial_pubkey)? {
+        require_patient_self(&patient_hash)?;
+    }
+    let mut chained: Vec<(u64, ActionHash)> = get_links(
+        LinkQuery::try_new(audit_chain_anchor(&patient_hash)?, LinkTypes::AuditChain)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| Some((tag_sequence(&link.tag)?, link.target.into_action_hash()?)))
+    .collect();
+    chained.sort_by_key(|(sequence, _)| *sequence);
+
+    let mut problems = Vec::new();
+    let length = chained.last().map_or(0, |(sequence, _)| sequence + 1);
+    for sequence in 0..length {
+        let at: Vec<ActionHash> = chained
+            .iter()
+            .filter(|(s, _)| *s == sequence)
+            .map(|(_, hash)| hash.clone())
+            .collect();
+        match at.len() {
+            0 => problems.push(AuditChainProblem::MissingSequence(sequence)),
+            1 => {}
+            _ => problems.push(AuditChainProblem::Fork { sequence, logs: at }),
+        }
+    }
 
-                if permission_granted && category_covered && not_excluded {
-                    return Ok(DelegationAuthResult {
-                        authorized: true,
-                        delegation_hash: Some(record.action_address().clone()),
-                        delegation_type: delegation.delegation_type.clone(),
-                        reason: "Active delegation found".to_string(),
-                    });
-                }
-            }
+    let head = chained.last().map(|(_, hash)| hash.clone());
+    let mut current = chained.last().cloned();
+    while let Some((sequence, hash)) = current.take() {
+        let Some(Details::Record(details)) = get_details(hash.clone(), GetOptions::default())? else {
+            problems.push(AuditChainProblem::MissingLog { sequence, log: hash });
+            break;
+        };
+        if !details.deletes.is_empty() {
+            problems.push(AuditChainProblem::DeletedLog { sequence, log: hash.clone() });
         }
+        let Some(log) = details.record.entry().to_app_option::<DataAccessLog>().ok().flatten() else {
+            problems.push(AuditChainProblem::MissingLog { sequence, log: hash });
+            break;
+        };
+        if log.patient_hash != patient_hash || log.chain_sequence != Some(sequence) {
+            problems.push(AuditChainProblem::BrokenChain { sequence, log: hash });
+            break;
+        }
+        current = match (log.previous_log, sequence.checked_sub(1)) {
+            (Some(previous), Some(previous_sequence)) => Some((previous_sequence, previous)),
+            (None, None) => None,
+            _ => {
+                problems.push(AuditChainProblem::BrokenChain { sequence, log: hash });
+                None
+            }
+        };
     }
 
-    Ok(DelegationAuthResult {
-        authorized: false,
-        delegation_hash: None,
-        delegation_type: DelegationType::Temporary, // Default
-        reason: "No valid delegation found".to_string(),
+    Ok(AuditChainVerification {
+        patient_hash,
+        length,
+        head,
+        verified: problems.is_empty(),
+        problems,
     })
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct DelegationAuthInput {
+pub struct GetAccessLogsInput {
     pub patient_hash: ActionHash,
-    pub delegate: AgentPubKey,
-    pub permission: DelegationPermission,
-    pub data_category: DataCategory,
-}
-
-#[derive(Serialize, Deserialize, Debug)]
-pub struct DelegationAuthResult {
-    pub authorized: bool,
-    pub delegation_hash: Option<ActionHash>,
-    pub delegation_type: DelegationType,
-    pub reason: String,
+    #[serde(default)]
+    pub page: CursorInput,
 }
 
-/// Get delegations where current agent is the delegate
+/// Get a page of the patient's access logs, oldest first
 #[hdk_extern]
-pub fn get_my_delegations(_: ()) -> ExternResult<Vec<Record>> {
-    let my_agent = agent_info()?.agent_initial_pubkey;
-    let delegate_anchor = hash_entry(&Anchor(format!("delegate:{:?}", my_agent)))?;
+pub fn get_access_logs(input: GetAccessLogsInput) -> ExternResult<CursorPage<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash, LinkTypes::PatientToAccessLogs)?,
+        GetStrategy::default()
+    )?;
+    links_to_records_cursor(links, &input.page)
+}
 
+/// All of the patient's access logs, for filtering and reports
+fn all_access_logs(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
     let links = get_links(
-        LinkQuery::try_new(delegate_anchor, LinkTypes::DelegateToDelegations)?,
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToAccessLogs)?,
         GetStrategy::default()
     )?;
 
-    let mut delegations = Vec::new();
+    let mut logs = Vec::new();
     for link in links {
         if let Some(hash) = link.target.into_action_hash() {
             if let Some(record) = get(hash, GetOptions::default())? {
-                // Only include active delegations
-                if let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() {
-                    if matches!(delegation.status, DelegationStatus::Active) {
-                        delegations.push(record);
-                    }
-                }
+                logs.push(record);
             }
         }
     }
 
-    Ok(delegations)
+    Ok(logs)
 }
 
-// ============================================================
-// PATIENT NOTIFICATION SYSTEM
-// ============================================================
+/// Input format from shared crate's log_data_access function
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AccessLogEntry {
+    pub log_id: String,
+    pub patient_hash: ActionHash,
+    pub accessor: AgentPubKey,
+    pub data_categories: Vec<DataCategory>,
+    pub access_type: DataPermission,
+    pub consent_hash: Option<ActionHash>,
+    pub access_reason: String,
+    pub accessed_at: Timestamp,
+    pub access_location: String,
+    pub emergency_override: bool,
+    pub override_reason: Option<String>,
+    #[serde(default)]
+    pub access_path: Option<String>,
+    #[serde(default)]
+    pub accessor_assurance: Option<IdentityAssuranceLevel>,
+    #[serde(default)]
+    pub access_context: Option<AccessContext>,
+}
 
-/// Create notification for patient about data access
+/// Create access log - called by shared crate's log_data_access
 #[hdk_extern]
-pub fn create_access_notification(notification: AccessNotification) -> ExternResult<Record> {
-    let notification_hash = create_entry(&EntryTypes::AccessNotification(notification.clone()))?;
-    let record = get(notification_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find notification".to_string())))?;
-
-    // Link to patient
-    create_link(
-        notification.patient_hash.clone(),
-        notification_hash.clone(),
-        LinkTypes::PatientToNotifications,
-        (),
-    )?;
+pub fn create_access_log(entry: AccessLogEntry) -> ExternResult<ActionHash> {
+    let log = DataAccessLog {
+        log_id: entry.log_id,
+        patient_hash: entry.patient_hash.clone(),
+        accessor: entry.accessor,
+        access_type: entry.access_type,
+        data_categories_accessed: entry.data_categories,
+        consent_hash: entry.consent_hash,
+        access_reason: entry.access_reason,
+        accessed_at: entry.accessed_at,
+        access_location: Some(entry.access_location),
+        emergency_override: entry.emergency_override,
+        override_reason: entry.override_reason,
+        access_path: entry.access_path,
+        accessor_assurance: entry.accessor_assurance,
+        previous_log: None,
+        chain_sequence: None,
+        access_context: entry.access_context,
+    };
 
-    // Link to unread notifications
-    if !notification.viewed {
-        let unread_anchor = hash_entry(&Anchor(format!("unread:{:?}", notification.patient_hash)))?;
+    let log_hash = append_access_log(log)?;
+
+    // Emergency overrides are also indexed for security monitoring
+    if entry.emergency_override {
+        let emergency_anchor = anchor_hash("emergency_override_events")?;
         create_link(
-            unread_anchor,
-            notification_hash,
-            LinkTypes::UnreadNotifications,
+            emergency_anchor,
+            log_hash.clone(),
+            LinkTypes::EmergencyOverrideEvents,
             (),
         )?;
     }
 
-    Ok(record)
+    Ok(log_hash)
 }
 
-/// Get patient's notifications
+/// Log a high-sensitivity access and ask the patient's agent to countersign
+/// it, keeping the unilateral log when the patient's agent cannot sign
 #[hdk_extern]
-pub fn get_patient_notifications(input: GetNotificationsInput) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToNotifications)?,
-        GetStrategy::default()
-    )?;
+pub fn create_countersigned_access_log(entry: AccessLogEntry) -> ExternResult<AccessReceipt> {
+    let patient_hash = entry.patient_hash.clone();
+    let log_hash = create_access_log(entry)?;
+    let log_record = get(log_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find access log"))?;
+    let log: DataAccessLog = log_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(not_found("Could not find access log"))?;
 
-    let mut notifications = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                notifications.push(record);
-            }
-        }
+    let Some(patient_agent) = get(patient_hash, GetOptions::default())?
+        .map(|record| record.action().author().clone())
+    else {
+        return Ok(AccessReceipt::Unilateral { log_hash, reason: "Patient record not found".to_string() });
+    };
+    if patient_agent == log.accessor {
+        return Ok(AccessReceipt::NotRequired { log_hash });
     }
 
-    // Filter by unread only if requested
-    if input.unread_only {
-        notifications = notifications
-            .into_iter()
-            .filter(|record| {
-                if let Some(n) = record.entry().to_app_option::<AccessNotification>().ok().flatten() {
-                    !n.viewed
-                } else {
-                    false
-                }
-            })
-            .collect();
+    let response = call_remote(
+        patient_agent.clone(),
+        zome_info()?.name,
+        "countersign_access_receipt".into(),
+        None,
+        &log,
+    )?;
+    let signed: (AccessReceiptClaims, Signature) = match response {
+        ZomeCallResponse::Ok(extern_io) => match extern_io.decode() {
+            Ok(signed) => signed,
+            Err(e) => {
+                return Ok(AccessReceipt::Unilateral { log_hash, reason: format!("Unreadable receipt: {:?}", e) })
+            }
+        },
+        other => return Ok(AccessReceipt::Unilateral { log_hash, reason: format!("{:?}", other) }),
+    };
+    let (claims, signature) = signed;
+    if log_record.action().entry_hash() != Some(&claims.log_entry_hash)
+        || !verify_signature(patient_agent.clone(), signature.clone(), &claims)?
+    {
+        return Ok(AccessReceipt::Unilateral {
+            log_hash,
+            reason: "Patient agent returned a receipt for a different log".to_string(),
+        });
     }
 
-    // Sort by accessed_at descending (most recent first)
-    notifications.sort_by(|a, b| {
-        let time_a = a.entry().to_app_option::<AccessNotification>().ok().flatten()
-            .map(|n| n.accessed_at.as_micros()).unwrap_or(0);
-        let time_b = b.entry().to_app_option::<AccessNotification>().ok().flatten()
-            .map(|n| n.accessed_at.as_micros()).unwrap_or(0);
-        time_b.cmp(&time_a) // Descending
-    });
+    let countersignature_hash = create_entry(&EntryTypes::AccessCountersignature(AccessCountersignature {
+        log_hash: log_hash.clone(),
+        claims,
+        patient_agent,
+        signature,
+    }))?;
+    create_link(
+        log_hash.clone(),
+        countersignature_hash.clone(),
+        LinkTypes::AccessLogToCountersignature,
+        (),
+    )?;
+    Ok(AccessReceipt::Countersigned { log_hash, countersignature_hash })
+}
 
-    // Apply limit
-    if let Some(limit) = input.limit {
-        notifications.truncate(limit as usize);
+/// Sign a receipt for an access log of one of this agent's patient records,
+/// called remotely by the accessor
+#[hdk_extern]
+pub fn countersign_access_receipt(log: DataAccessLog) -> ExternResult<(AccessReceiptClaims, Signature)> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if call_info()?.provenance != log.accessor {
+        return Err(unauthorized("Only the accessor can request a receipt for its log"));
+    }
+    let is_mine = get(log.patient_hash.clone(), GetOptions::default())?
+        .is_some_and(|record| record.action().author() == &me);
+    if !is_mine {
+        return Err(not_found("This agent does not hold the patient record"));
     }
 
-    Ok(notifications)
+    let claims = AccessReceiptClaims {
+        log_entry_hash: hash_entry(&log)?,
+        patient_hash: log.patient_hash,
+        accessor: log.accessor,
+        accessed_at: log.accessed_at,
+        signed_at: sys_time()?,
+    };
+    let signature = sign(me, &claims)?;
+    Ok((claims, signature))
+}
+
+/// The patient's countersignature on an access log, if it has one
+#[hdk_extern]
+pub fn get_access_countersignature(log_hash: ActionHash) -> ExternResult<Option<AccessCountersignature>> {
+    let links = get_links(
+        LinkQuery::try_new(log_hash, LinkTypes::AccessLogToCountersignature)?,
+        GetStrategy::default(),
+    )?;
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if let Some(countersignature) = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<AccessCountersignature>().ok().flatten())
+        {
+            return Ok(Some(countersignature));
+        }
+    }
+    Ok(None)
 }
 
+/// Denied access log entry from shared crate
 #[derive(Serialize, Deserialize, Debug)]
-pub struct GetNotificationsInput {
+pub struct AccessDeniedLogEntry {
+    pub log_id: String,
     pub patient_hash: ActionHash,
-    pub unread_only: bool,
-    pub limit: Option<u32>,
+    pub attempted_accessor: AgentPubKey,
+    pub data_category: DataCategory,
+    pub denial_reason: String,
+    pub attempted_at: Timestamp,
 }
 
-/// Mark notification as viewed
+/// Create access denied log - called by shared crate's log_access_denied
 #[hdk_extern]
-pub fn mark_notification_viewed(notification_hash: ActionHash) -> ExternResult<Record> {
-    let record = get(notification_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Notification not found".to_string())))?;
-
-    let mut notification: AccessNotification = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid notification".to_string())))?;
+pub fn create_access_denied_log(entry: AccessDeniedLogEntry) -> ExternResult<ActionHash> {
+    // Store denied access attempts for security monitoring
+    let log = DataAccessLog {
+        log_id: entry.log_id,
+        patient_hash: entry.patient_hash.clone(),
+        accessor: entry.attempted_accessor,
+        access_type: DataPermission::Read,
+        data_categories_accessed: vec![entry.data_category],
+        consent_hash: None,
+        access_reason: format!("DENIED: {}", entry.denial_reason),
+        accessed_at: entry.attempted_at,
+        access_location: None,
+        emergency_override: false,
+        override_reason: None,
+        access_path: None,
+        accessor_assurance: None,
+        previous_log: None,
+        chain_sequence: None,
+        access_context: None,
+    };
 
-    notification.viewed = true;
-    notification.viewed_at = Some(sys_time()?);
+    let log_hash = append_access_log(log)?;
 
-    let updated_hash = update_entry(notification_hash, &notification)?;
+    // Also link to the day's denied access anchor for security monitoring
+    let denied_anchor = anchor_for_period(DENIED_ACCESS_ANCHOR, sys_time()?, AnchorPeriod::Day)?;
+    create_link(
+        denied_anchor,
+        log_hash.clone(),
+        LinkTypes::PatientToAccessLogs, // Reusing link type
+        (),
+    )?;
 
-    get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated notification".to_string())))
+    Ok(log_hash)
 }
 
-/// Get unread notification count
+/// Record emergency access (break-glass)
 #[hdk_extern]
-pub fn get_unread_notification_count(patient_hash: ActionHash) -> ExternResult<u32> {
-    let unread_anchor = hash_entry(&Anchor(format!("unread:{:?}", patient_hash)))?;
-
-    let links = get_links(
-        LinkQuery::try_new(unread_anchor, LinkTypes::UnreadNotifications)?,
-        GetStrategy::default()
+pub fn record_emergency_access(emergency: EmergencyAccess) -> ExternResult<Record> {
+    let emergency_hash = create_entry(&EntryTypes::EmergencyAccess(emergency.clone()))?;
+    let record = get(emergency_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find emergency access"))?;
+    
+    create_link(
+        emergency.patient_hash,
+        emergency_hash.clone(),
+        LinkTypes::PatientToEmergencyAccess,
+        (),
     )?;
 
-    Ok(links.len() as u32)
+    let emergency_anchor = anchor_hash("emergency_override_events")?;
+    create_link(
+        emergency_anchor,
+        emergency_hash,
+        LinkTypes::EmergencyOverrideEvents,
+        (),
+    )?;
+    
+    Ok(record)
 }
 
-/// Set or update notification preferences
+/// Create authorization document
 #[hdk_extern]
-pub fn set_notification_preferences(prefs: NotificationPreferences) -> ExternResult<Record> {
-    let prefs_hash = create_entry(&EntryTypes::NotificationPreferences(prefs.clone()))?;
-    let record = get(prefs_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find preferences".to_string())))?;
-
-    // Link to patient (will have multiple over time, get latest)
+pub fn create_authorization_document(doc: AuthorizationDocument) -> ExternResult<Record> {
+    let doc_hash = create_entry(&EntryTypes::AuthorizationDocument(doc.clone()))?;
+    let record = get(doc_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find document"))?;
+    
     create_link(
-        prefs.patient_hash,
-        prefs_hash,
-        LinkTypes::PatientToNotificationPreferences,
+        doc.patient_hash,
+        doc_hash,
+        LinkTypes::PatientToDocuments,
         (),
     )?;
-
+    
     Ok(record)
 }
 
-/// Get patient's notification preferences
+/// Get patient's authorization documents
 #[hdk_extern]
-pub fn get_notification_preferences(patient_hash: ActionHash) -> ExternResult<Option<NotificationPreferences>> {
-    let links = get_links(
-        LinkQuery::try_new(patient_hash, LinkTypes::PatientToNotificationPreferences)?,
-        GetStrategy::default()
-    )?;
-
-    // Get the most recent preferences
-    let mut latest: Option<(Timestamp, NotificationPreferences)> = None;
-
+pub fn get_authorization_documents(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToDocuments)?, GetStrategy::default())?;
+    
+    let mut docs = Vec::new();
     for link in links {
         if let Some(hash) = link.target.into_action_hash() {
             if let Some(record) = get(hash, GetOptions::default())? {
-                if let Some(prefs) = record.entry().to_app_option::<NotificationPreferences>().ok().flatten() {
-                    match &latest {
-                        None => latest = Some((prefs.updated_at, prefs)),
-                        Some((ts, _)) if prefs.updated_at > *ts => {
-                            latest = Some((prefs.updated_at, prefs));
-                        }
-                        _ => {}
-                    }
-                }
+                docs.push(record);
             }
         }
     }
-
-    Ok(latest.map(|(_, prefs)| prefs))
+    
+    Ok(docs)
 }
 
-/// Create notification digest (daily/weekly summary)
+/// Get access logs filtered by date range
 #[hdk_extern]
-pub fn create_notification_digest(digest: NotificationDigest) -> ExternResult<Record> {
-    let digest_hash = create_entry(&EntryTypes::NotificationDigest(digest.clone()))?;
-    let record = get(digest_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find digest".to_string())))?;
+pub fn get_access_logs_by_date(input: DateRangeInput) -> ExternResult<Vec<Record>> {
+    let all_logs = all_access_logs(input.patient_hash)?;
 
-    create_link(
-        digest.patient_hash,
-        digest_hash,
-        LinkTypes::PatientToDigests,
-        (),
-    )?;
+    let filtered: Vec<Record> = all_logs
+        .into_iter()
+        .filter(|record| {
+            if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
+                log.accessed_at >= input.start_date && log.accessed_at <= input.end_date
+            } else {
+                false
+            }
+        })
+        .collect();
 
-    Ok(record)
+    Ok(filtered)
 }
 
-/// Generate plain-language summary for notification
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DateRangeInput {
+    pub patient_hash: ActionHash,
+    pub start_date: Timestamp,
+    pub end_date: Timestamp,
+}
+
+/// Get access logs for a specific accessor (HIPAA audit trail)
 #[hdk_extern]
-pub fn generate_notification_summary(input: GenerateSummaryInput) -> ExternResult<String> {
-    let categories: Vec<String> = input.data_categories.iter()
-        .map(|c| match c {
-            DataCategory::Demographics => "basic information",
-            DataCategory::Allergies => "allergy information",
-            DataCategory::Medications => "medications",
-            DataCategory::Diagnoses => "diagnoses",
-            DataCategory::Procedures => "procedures",
-            DataCategory::LabResults => "lab results",
-            DataCategory::ImagingStudies => "imaging studies",
-            DataCategory::VitalSigns => "vital signs",
-            DataCategory::Immunizations => "immunizations",
-            DataCategory::MentalHealth => "mental health records",
-            DataCategory::SubstanceAbuse => "substance abuse records",
-            DataCategory::SexualHealth => "sexual health records",
-            DataCategory::GeneticData => "genetic data",
-            DataCategory::FinancialData => "billing information",
-            DataCategory::All => "all records",
-        }.to_string())
-        .collect();
+pub fn get_access_logs_by_accessor(input: AccessorLogsInput) -> ExternResult<Vec<Record>> {
+    let all_logs = all_access_logs(input.patient_hash)?;
 
-    let categories_text = if categories.len() == 1 {
-        categories[0].clone()
-    } else if categories.len() == 2 {
-        format!("{} and {}", categories[0], categories[1])
-    } else {
-        let last = categories.last().unwrap();
-        let others = &categories[..categories.len()-1];
-        format!("{}, and {}", others.join(", "), last)
-    };
-
-    let summary = if input.emergency_access {
-        format!(
-            "{} accessed your {} in an emergency situation",
-            input.accessor_name, categories_text
-        )
-    } else {
-        format!(
-            "{} viewed your {}",
-            input.accessor_name, categories_text
-        )
-    };
+    let filtered: Vec<Record> = all_logs
+        .into_iter()
+        .filter(|record| {
+            if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
+                log.accessor == input.accessor
+            } else {
+                false
+            }
+        })
+        .collect();
 
-    Ok(summary)
+    Ok(filtered)
 }
 
 #[derive(Serialize, Deserialize, Debug)]
-pub struct GenerateSummaryInput {
-    pub accessor_name: String,
-    pub data_categories: Vec<DataCategory>,
-    pub emergency_access: bool,
+pub struct AccessorLogsInput {
+    pub patient_hash: ActionHash,
+    pub accessor: AgentPubKey,
 }
 
-// ============================================================
-// CARE TEAM TEMPLATES
-// ============================================================
+/// Get all emergency access events (break-glass audit)
+#[hdk_extern]
+pub fn get_emergency_access_events(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToEmergencyAccess)?, GetStrategy::default())?;
 
-/// Create a care team template
+    let mut events = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                events.push(record);
+            }
+        }
+    }
+
+    Ok(events)
+}
+
+/// Generate HIPAA-compliant accounting of disclosures report
 #[hdk_extern]
-pub fn create_care_team_template(template: CareTeamTemplate) -> ExternResult<Record> {
-    let template_hash = create_entry(&EntryTypes::CareTeamTemplate(template.clone()))?;
-    let record = get(template_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find template".to_string())))?;
+pub fn generate_disclosure_report(input: DisclosureReportInput) -> ExternResult<DisclosureReport> {
+    let logs = get_access_logs_by_date(DateRangeInput {
+        patient_hash: input.patient_hash.clone(),
+        start_date: input.start_date,
+        end_date: input.end_date,
+    })?;
 
-    // Link to system templates anchor if it's a system template
-    if matches!(template.template_type, TemplateType::System) {
-        let system_anchor = anchor_hash("system_templates")?;
-        create_link(
-            system_anchor,
-            template_hash,
-            LinkTypes::SystemTemplates,
-            (),
-        )?;
+    let mut disclosures = Vec::new();
+    for record in logs {
+        if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
+            disclosures.push(DisclosureEntry {
+                accessed_at: log.accessed_at,
+                accessor: log.accessor,
+                data_categories: log.data_categories_accessed.iter()
+                    .map(|c| format!("{:?}", c))
+                    .collect(),
+                access_reason: log.access_reason.clone(),
+                consent_hash: log.consent_hash.clone(),
+                emergency_override: log.emergency_override,
+            });
+        }
     }
 
-    Ok(record)
+    Ok(DisclosureReport {
+        patient_hash: input.patient_hash,
+        generated_at: sys_time()?,
+        period_start: input.start_date,
+        period_end: input.end_date,
+        total_disclosures: disclosures.len() as u32,
+        disclosures,
+    })
 }
 
-/// Get all system templates
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisclosureReportInput {
+    pub patient_hash: ActionHash,
+    pub start_date: Timestamp,
+    pub end_date: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisclosureReport {
+    pub patient_hash: ActionHash,
+    pub generated_at: Timestamp,
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub total_disclosures: u32,
+    pub disclosures: Vec<DisclosureEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisclosureEntry {
+    pub accessed_at: Timestamp,
+    pub accessor: AgentPubKey,
+    pub data_categories: Vec<String>,
+    pub access_reason: String,
+    pub consent_hash: Option<ActionHash>,
+    pub emergency_override: bool,
+}
+
+/// Display preferences from the patient profile (mirrors patient_integrity::Patient)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct PatientDisplayPreferences {
+    primary_language: String,
+    #[serde(default)]
+    utc_offset_minutes: Option<i32>,
+}
+
+/// Name fields of a provider profile (mirrors provider_integrity::Provider)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct ProviderNameFields {
+    first_name: String,
+    last_name: String,
+    title: String,
+}
+
+/// Disclosure report laid out for display: month sections, resolved names,
+/// plain-language purposes and dates in the patient's language and timezone
 #[hdk_extern]
-pub fn get_system_templates(_: ()) -> ExternResult<Vec<Record>> {
-    let system_anchor = anchor_hash("system_templates")?;
+pub fn render_disclosure_report(input: DisclosureReportInput) -> ExternResult<RenderedDisclosureReport> {
+    let patient_record = get(input.patient_hash.clone(), GetOptions::default())?;
+    let patient_agent = patient_record.as_ref().map(|record| record.action().author().clone());
+    let preferences = patient_record
+        .and_then(|record| record.entry().to_app_option::<PatientDisplayPreferences>().ok().flatten());
+    let language = preferences
+        .as_ref()
+        .map(|prefs| ExplanationLanguage::from_preference(&prefs.primary_language))
+        .unwrap_or(ExplanationLanguage::English);
+    let utc_offset_minutes = preferences
+        .and_then(|prefs| prefs.utc_offset_minutes)
+        .filter(|offset| is_valid_utc_offset(*offset))
+        .unwrap_or(0);
+
+    let mut logs: Vec<DataAccessLog> = get_access_logs_by_date(DateRangeInput {
+        patient_hash: input.patient_hash.clone(),
+        start_date: input.start_date,
+        end_date: input.end_date,
+    })?
+    .into_iter()
+    .filter_map(|record| record.entry().to_app_option::<DataAccessLog>().ok().flatten())
+    .collect();
+    logs.sort_by_key(|log| log.accessed_at);
+
+    let mut names: HashMap<AgentPubKey, String> = HashMap::new();
+    let mut purposes: HashMap<ActionHash, Option<ConsentPurpose>> = HashMap::new();
+    let mut sections: Vec<DisclosureMonthSection> = Vec::new();
+    let mut category_counts: Vec<DisclosureCategoryCount> = Vec::new();
+    let mut emergency_accesses = 0u32;
+
+    for log in &logs {
+        if !names.contains_key(&log.accessor) {
+            let name = accessor_display_name(&log.accessor, patient_agent.as_ref(), language);
+            names.insert(log.accessor.clone(), name);
+        }
+        let consent_purpose = match &log.consent_hash {
+            Some(consent_hash) => purposes
+                .entry(consent_hash.clone())
+                .or_insert_with(|| consent_purpose(consent_hash))
+                .clone(),
+            None => None,
+        };
+
+        let local = local_date_time(log.accessed_at, utc_offset_minutes);
+        let categories: Vec<String> = log.data_categories_accessed
+            .iter()
+            .map(|category| category_label(category, language).to_string())
+            .collect();
+        for category in &categories {
+            match category_counts.iter_mut().find(|count| &count.category == category) {
+                Some(count) => count.count += 1,
+                None => category_counts.push(DisclosureCategoryCount { category: category.clone(), count: 1 }),
+            }
+        }
+        if log.emergency_override {
+            emergency_accesses += 1;
+        }
 
-    let links = get_links(
-        LinkQuery::try_new(system_anchor, LinkTypes::SystemTemplates)?,
-        GetStrategy::default()
-    )?;
+        let disclosure = RenderedDisclosure {
+            accessed_at: log.accessed_at,
+            date_label: date_label(&local, language),
+            time_label: time_label(&local, language),
+            accessor: log.accessor.clone(),
+            accessor_name: names[&log.accessor].clone(),
+            purpose: plain_purpose(log, consent_purpose.as_ref(), language),
+            categories,
+            emergency_override: log.emergency_override,
+        };
+
+        let key = month_key(&local);
+        match sections.iter_mut().find(|section| section.month_key == key) {
+            Some(section) => section.disclosures.push(disclosure),
+            None => sections.push(DisclosureMonthSection {
+                month_key: key,
+                heading: month_label(&local, language),
+                disclosures: vec![disclosure],
+            }),
+        }
+    }
 
-    let mut templates = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                if let Some(template) = record.entry().to_app_option::<CareTeamTemplate>().ok().flatten() {
-                    if template.active {
-                        templates.push(record);
-                    }
-                }
+    category_counts.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.category.cmp(&b.category)));
+    let total_disclosures = logs.len() as u32;
+    let distinct_accessors = names.len() as u32;
+    let start = local_date_time(input.start_date, utc_offset_minutes);
+    let end = local_date_time(input.end_date, utc_offset_minutes);
+
+    Ok(RenderedDisclosureReport {
+        patient_hash: input.patient_hash,
+        generated_at: sys_time()?,
+        language: language.code().to_string(),
+        utc_offset_minutes,
+        period_label: format!("{} – {}", date_label(&start, language), date_label(&end, language)),
+        sections,
+        totals: DisclosureTotals {
+            total_disclosures,
+            distinct_accessors,
+            emergency_accesses,
+            summary: totals_summary(total_disclosures, distinct_accessors, emergency_accesses, language),
+            by_category: category_counts,
+        },
+    })
+}
+
+/// Who accessed the record, in words: the patient, a registered service, a
+/// provider profile, or a shortened key when none of those are known
+fn accessor_display_name(
+    accessor: &AgentPubKey,
+    patient_agent: Option<&AgentPubKey>,
+    language: ExplanationLanguage,
+) -> String {
+    if patient_agent == Some(accessor) {
+        return match language {
+            ExplanationLanguage::English => "You".to_string(),
+            ExplanationLanguage::Spanish => "Usted".to_string(),
+        };
+    }
+    if let Ok(Some(registration)) = service_registration_for(accessor) {
+        return registration.service_name;
+    }
+    let provider = resilient_call::<_, Option<Record>>("provider", "get_provider_by_agent", accessor, CallClass::Bulk)
+        .ok()
+        .flatten()
+        .and_then(|record| record.entry().to_app_option::<ProviderNameFields>().ok().flatten());
+    if let Some(provider) = provider {
+        return format!("{} {}, {}", provider.first_name, provider.last_name, provider.title);
+    }
+    let key = accessor.to_string();
+    let short = &key[key.len().saturating_sub(8)..];
+    match language {
+        ExplanationLanguage::English => format!("Unregistered user …{}", short),
+        ExplanationLanguage::Spanish => format!("Usuario no registrado …{}", short),
+    }
+}
+
+fn consent_purpose(consent_hash: &ActionHash) -> Option<ConsentPurpose> {
+    get(consent_hash.clone(), GetOptions::default())
+        .ok()
+        .flatten()
+        .and_then(|record| record.entry().to_app_option::<Consent>().ok().flatten())
+        .map(|consent| consent.purpose)
+}
+
+fn plain_purpose(log: &DataAccessLog, purpose: Option<&ConsentPurpose>, language: ExplanationLanguage) -> String {
+    let spanish = language == ExplanationLanguage::Spanish;
+    if log.emergency_override {
+        return if spanish { "Atención de emergencia" } else { "Emergency care" }.to_string();
+    }
+    let text = match purpose {
+        Some(ConsentPurpose::Treatment) => if spanish { "Su tratamiento" } else { "Your treatment" },
+        Some(ConsentPurpose::Payment) => if spanish { "Facturación y pagos" } else { "Billing and payment" },
+        Some(ConsentPurpose::HealthcareOperations) => {
+            if spanish { "Operaciones del centro de salud" } else { "Running the healthcare practice" }
+        }
+        Some(ConsentPurpose::Research) => if spanish { "Investigación" } else { "Research" },
+        Some(ConsentPurpose::PublicHealth) => if spanish { "Salud pública" } else { "Public health" },
+        Some(ConsentPurpose::LegalProceeding) => if spanish { "Un proceso legal" } else { "A legal proceeding" },
+        Some(ConsentPurpose::Marketing) => if spanish { "Mercadeo" } else { "Marketing" },
+        Some(ConsentPurpose::FamilyNotification) => {
+            if spanish { "Avisar a su familia" } else { "Keeping your family informed" }
+        }
+        Some(ConsentPurpose::Other(other)) if !other.trim().is_empty() => return other.clone(),
+        _ if !log.access_reason.trim().is_empty() => return log.access_reason.clone(),
+        _ => if spanish { "Motivo no indicado" } else { "No reason given" },
+    };
+    text.to_string()
+}
+
+fn category_label(category: &DataCategory, language: ExplanationLanguage) -> &'static str {
+    let spanish = language == ExplanationLanguage::Spanish;
+    match category {
+        DataCategory::Demographics => if spanish { "Datos personales" } else { "Personal details" },
+        DataCategory::Allergies => if spanish { "Alergias" } else { "Allergies" },
+        DataCategory::Medications => if spanish { "Medicamentos" } else { "Medications" },
+        DataCategory::Diagnoses => if spanish { "Diagnósticos" } else { "Diagnoses" },
+        DataCategory::Procedures => if spanish { "Procedimientos" } else { "Procedures" },
+        DataCategory::LabResults => if spanish { "Resultados de laboratorio" } else { "Lab results" },
+        DataCategory::ImagingStudies => if spanish { "Estudios de imagen" } else { "Imaging" },
+        DataCategory::VitalSigns => if spanish { "Signos vitales" } else { "Vital signs" },
+        DataCategory::Immunizations => if spanish { "Vacunas" } else { "Immunizations" },
+        DataCategory::MentalHealth => if spanish { "Salud mental" } else { "Mental health" },
+        DataCategory::SubstanceAbuse => if spanish { "Consumo de sustancias" } else { "Substance use" },
+        DataCategory::SexualHealth => if spanish { "Salud sexual" } else { "Sexual health" },
+        DataCategory::GeneticData => if spanish { "Datos genéticos" } else { "Genetic data" },
+        DataCategory::FinancialData => if spanish { "Datos financieros" } else { "Financial data" },
+        DataCategory::All => if spanish { "Todo su expediente" } else { "Your entire record" },
+    }
+}
+
+fn totals_summary(total: u32, accessors: u32, emergencies: u32, language: ExplanationLanguage) -> String {
+    let plural = |count: u32, one: &str, many: &str| format!("{} {}", count, if count == 1 { one } else { many });
+    match language {
+        ExplanationLanguage::English => {
+            let mut text = format!(
+                "{} by {}",
+                plural(total, "disclosure", "disclosures"),
+                plural(accessors, "person or service", "people or services"),
+            );
+            if emergencies > 0 {
+                text.push_str(&format!(", including {}", plural(emergencies, "emergency access", "emergency accesses")));
+            }
+            text.push('.');
+            text
+        }
+        ExplanationLanguage::Spanish => {
+            let mut text = format!(
+                "{} por {}",
+                plural(total, "divulgación", "divulgaciones"),
+                plural(accessors, "persona o servicio", "personas o servicios"),
+            );
+            if emergencies > 0 {
+                text.push_str(&format!(", incluido {}", plural(emergencies, "acceso de emergencia", "accesos de emergencia")));
             }
+            text.push('.');
+            text
         }
     }
+}
 
-    Ok(templates)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenderedDisclosureReport {
+    pub patient_hash: ActionHash,
+    pub generated_at: Timestamp,
+    /// Language code the labels are written in ("en", "es")
+    pub language: String,
+    pub utc_offset_minutes: i32,
+    pub period_label: String,
+    /// Months in chronological order, each holding its disclosures oldest first
+    pub sections: Vec<DisclosureMonthSection>,
+    pub totals: DisclosureTotals,
 }
 
-/// Initialize default system templates
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisclosureMonthSection {
+    /// Sortable key ("2026-03")
+    pub month_key: String,
+    pub heading: String,
+    pub disclosures: Vec<RenderedDisclosure>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RenderedDisclosure {
+    pub accessed_at: Timestamp,
+    pub date_label: String,
+    pub time_label: String,
+    pub accessor: AgentPubKey,
+    pub accessor_name: String,
+    pub purpose: String,
+    pub categories: Vec<String>,
+    pub emergency_override: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisclosureTotals {
+    pub total_disclosures: u32,
+    pub distinct_accessors: u32,
+    pub emergency_accesses: u32,
+    /// One-sentence summary in the report language
+    pub summary: String,
+    /// Disclosures per category, most frequent first
+    pub by_category: Vec<DisclosureCategoryCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DisclosureCategoryCount {
+    pub category: String,
+    pub count: u32,
+}
+
+/// Log consent view (for tracking patient access to their own data)
 #[hdk_extern]
-pub fn initialize_system_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
-    let templates = vec![
-        CareTeamTemplate {
-            template_id: "primary-care-team".to_string(),
-            name: "Primary Care Team".to_string(),
-            description: "Your primary care doctor, nurses, and office staff can view most of your health information to coordinate your care.".to_string(),
-            permissions: vec![DataPermission::Read],
-            data_categories: vec![
-                DataCategory::Demographics,
-                DataCategory::Allergies,
-                DataCategory::Medications,
-                DataCategory::Diagnoses,
-                DataCategory::Procedures,
-                DataCategory::LabResults,
-                DataCategory::ImagingStudies,
-                DataCategory::VitalSigns,
-                DataCategory::Immunizations,
-            ],
-            default_exclusions: vec![
-                DataCategory::MentalHealth,
-                DataCategory::SubstanceAbuse,
-                DataCategory::SexualHealth,
-                DataCategory::GeneticData,
-            ],
-            purpose: ConsentPurpose::Treatment,
-            default_duration_days: Some(365),
-            template_type: TemplateType::System,
-            created_by: agent_info()?.agent_initial_pubkey,
-            created_at: sys_time()?,
-            active: true,
-        },
-        CareTeamTemplate {
-            template_id: "specialist-referral".to_string(),
-            name: "Specialist Referral".to_string(),
-            description: "A specialist you've been referred to can view relevant information for your consultation.".to_string(),
-            permissions: vec![DataPermission::Read],
-            data_categories: vec![
-                DataCategory::Demographics,
-                DataCategory::Allergies,
-                DataCategory::Medications,
-                DataCategory::Diagnoses,
-                DataCategory::Procedures,
-                DataCategory::LabResults,
-            ],
-            default_exclusions: vec![
-                DataCategory::MentalHealth,
-                DataCategory::SubstanceAbuse,
-                DataCategory::SexualHealth,
-                DataCategory::GeneticData,
-                DataCategory::FinancialData,
-            ],
-            purpose: ConsentPurpose::Treatment,
-            default_duration_days: Some(90),
-            template_type: TemplateType::System,
-            created_by: agent_info()?.agent_initial_pubkey,
-            created_at: sys_time()?,
+pub fn log_consent_view(input: ConsentViewInput) -> ExternResult<()> {
+    let log = DataAccessLog {
+        log_id: format!("VIEW-{:?}", sys_time()?),
+        patient_hash: input.patient_hash.clone(),
+        accessor: agent_info()?.agent_initial_pubkey,
+        access_type: DataPermission::Read,
+        data_categories_accessed: input.data_categories.clone(),
+        consent_hash: Some(input.consent_hash),
+        access_reason: "Patient self-access".to_string(),
+        accessed_at: sys_time()?,
+        access_location: None,
+        emergency_override: false,
+        override_reason: None,
+        access_path: None,
+        accessor_assurance: None,
+        previous_log: None,
+        chain_sequence: None,
+        access_context: None,
+    };
+
+    append_access_log(log)?;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConsentViewInput {
+    pub patient_hash: ActionHash,
+    pub consent_hash: ActionHash,
+    pub data_categories: Vec<DataCategory>,
+}
+
+/// Update consent (e.g., extend expiration, modify scope)
+#[hdk_extern]
+pub fn update_consent(input: UpdateConsentInput) -> ExternResult<Record> {
+    let updated_hash = update_entry(input.original_hash.clone(), &input.updated_consent)?;
+    notify_webhooks(
+        WebhookEventType::ConsentChanged,
+        input.updated_consent.patient_hash.clone(),
+        updated_hash.clone(),
+    );
+    let record = get(updated_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find updated consent"))?;
+
+    // Create audit trail link
+    create_link(
+        input.original_hash,
+        updated_hash,
+        LinkTypes::ConsentUpdates,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateConsentInput {
+    pub original_hash: ActionHash,
+    pub updated_consent: Consent,
+}
+
+/// Get consent history (all versions for audit trail)
+#[hdk_extern]
+pub fn get_consent_history(consent_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(LinkQuery::try_new(consent_hash.clone(), LinkTypes::ConsentUpdates)?, GetStrategy::default())?;
+
+    let mut history = Vec::new();
+
+    // Add original
+    if let Some(original) = get(consent_hash, GetOptions::default())? {
+        history.push(original);
+    }
+
+    // Add all updates
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                history.push(record);
+            }
+        }
+    }
+
+    Ok(history)
+}
+
+/// Anchor entry for indexing
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Anchor(pub String);
+
+fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
+    let anchor = Anchor(anchor_text.to_string());
+    hash_entry(&anchor)
+}
+
+// ============================================================
+// CONSENT SIMULATION
+// ============================================================
+
+/// Zomes exposing `get_patient_record_inventory`
+const INVENTORY_ZOMES: &[&str] = &["records", "prescriptions"];
+
+/// Record titles shown per category in a simulation
+const SIMULATION_EXAMPLES: usize = 3;
+
+/// Subset of the shared `RecordInventoryItem`, decoded with this zome's
+/// `DataCategory`
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct InventoryItem {
+    record_hash: ActionHash,
+    category: DataCategory,
+    title: String,
+    recorded_at: Timestamp,
+    encounter_hash: Option<ActionHash>,
+}
+
+/// What a draft consent would expose in one category
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CategoryPreview {
+    pub category: DataCategory,
+    pub total_records: u32,
+    /// Records the grantee could read once the draft is granted
+    pub visible_records: u32,
+    /// Visible records the grantee can already read under an active consent
+    pub already_visible_records: u32,
+    /// Titles of the most recent visible records
+    pub examples: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConsentSimulation {
+    pub grantee: ConsentGrantee,
+    pub total_records: u32,
+    pub visible_records: u32,
+    pub newly_visible_records: u32,
+    pub categories: Vec<CategoryPreview>,
+    /// Zomes whose records could not be listed, so counts may be low
+    pub unavailable_sources: Vec<String>,
+}
+
+/// Whether a consent lets its grantee read a record, by category, date
+/// range and encounter
+fn consent_reaches(consent: &Consent, item: &InventoryItem) -> bool {
+    if !consent.permissions.contains(&DataPermission::Read) || !consent.covers_category(&item.category) {
+        return false;
+    }
+    if let Some(range) = &consent.scope.date_range {
+        if item.recorded_at < range.start || range.end.is_some_and(|end| item.recorded_at > end) {
+            return false;
+        }
+    }
+    match &consent.scope.encounter_hashes {
+        Some(encounters) => item.encounter_hash.as_ref().is_some_and(|e| encounters.contains(e)),
+        None => true,
+    }
+}
+
+/// Preview which of the patient's current records a draft consent would
+/// expose to its grantee, without creating any entries
+///
+/// Only the patient can list their record inventory, so only the patient
+/// can simulate.
+#[hdk_extern]
+pub fn simulate_consent(draft: Consent) -> ExternResult<ConsentSimulation> {
+    let mut draft = draft;
+    // Evaluate `All` the way create_consent would grant it
+    draft.category_registry = Some(CategoryRegistry::current());
+
+    let mut inventory: Vec<InventoryItem> = Vec::new();
+    let mut unavailable_sources = Vec::new();
+    for zome in INVENTORY_ZOMES {
+        match resilient_call::<_, Vec<InventoryItem>>(zome, "get_patient_record_inventory", &draft.patient_hash, CallClass::Bulk) {
+            Ok(items) => inventory.extend(items),
+            Err(failure) if failure.is_transient() => unavailable_sources.push(zome.to_string()),
+            Err(failure) => return Err(failure.into_wasm_error("listing patient records")),
+        }
+    }
+    inventory.sort_by_key(|item| std::cmp::Reverse(item.recorded_at));
+
+    let existing: Vec<Consent> = current_active_consents(draft.patient_hash.clone())?
+        .into_iter()
+        .map(|(_, consent)| consent)
+        .filter(|consent| consent.grantee == draft.grantee)
+        .collect();
+
+    let mut categories: Vec<CategoryPreview> = Vec::new();
+    for item in &inventory {
+        let index = match categories.iter().position(|c| c.category == item.category) {
+            Some(index) => index,
+            None => {
+                categories.push(CategoryPreview {
+                    category: item.category.clone(),
+                    total_records: 0,
+                    visible_records: 0,
+                    already_visible_records: 0,
+                    examples: Vec::new(),
+                });
+                categories.len() - 1
+            }
+        };
+        let preview = &mut categories[index];
+        preview.total_records += 1;
+        if !consent_reaches(&draft, item) {
+            continue;
+        }
+        preview.visible_records += 1;
+        if existing.iter().any(|consent| consent_reaches(consent, item)) {
+            preview.already_visible_records += 1;
+        }
+        if preview.examples.len() < SIMULATION_EXAMPLES {
+            preview.examples.push(item.title.clone());
+        }
+    }
+
+    let visible_records = categories.iter().map(|c| c.visible_records).sum();
+    let already_visible: u32 = categories.iter().map(|c| c.already_visible_records).sum();
+    Ok(ConsentSimulation {
+        grantee: draft.grantee,
+        total_records: inventory.len() as u32,
+        visible_records,
+        newly_visible_records: visible_records - already_visible,
+        categories,
+        unavailable_sources,
+    })
+}
+
+// ============================================================
+// CONSENT DELEGATION SYSTEM
+// ============================================================
+
+/// Create a new delegation grant
+#[hdk_extern]
+pub fn create_delegation(delegation: DelegationGrant) -> ExternResult<Record> {
+    let delegation_hash = create_entry(&EntryTypes::DelegationGrant(delegation.clone()))?;
+    let record = get(delegation_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find delegation"))?;
+
+    // Link to patient
+    create_link(
+        delegation.patient_hash.clone(),
+        delegation_hash.clone(),
+        LinkTypes::PatientToDelegations,
+        (),
+    )?;
+
+    // Link to delegate
+    let delegate_anchor = hash_entry(&Anchor(format!("delegate:{:?}", delegation.delegate)))?;
+    create_link(
+        delegate_anchor,
+        delegation_hash.clone(),
+        LinkTypes::DelegateToDelegations,
+        (),
+    )?;
+
+    // Link to active delegations if active
+    if matches!(delegation.status, DelegationStatus::Active) {
+        let active_anchor = anchor_hash("active_delegations")?;
+        create_link(
+            active_anchor,
+            delegation_hash,
+            LinkTypes::ActiveDelegations,
+            (),
+        )?;
+    }
+
+    Ok(record)
+}
+
+/// Get patient's delegations
+#[hdk_extern]
+pub fn get_patient_delegations(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToDelegations)?,
+        GetStrategy::default()
+    )?;
+
+    let mut delegations = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                delegations.push(record);
+            }
+        }
+    }
+
+    Ok(delegations)
+}
+
+/// Get active delegations for a patient
+#[hdk_extern]
+pub fn get_active_delegations(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let all_delegations = get_patient_delegations(patient_hash)?;
+
+    let active: Vec<Record> = all_delegations
+        .into_iter()
+        .filter(|record| {
+            if let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() {
+                matches!(delegation.status, DelegationStatus::Active)
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    Ok(active)
+}
+
+/// Revoke a delegation
+#[hdk_extern]
+pub fn revoke_delegation(input: RevokeDelegationInput) -> ExternResult<Record> {
+    let record = get(input.delegation_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Delegation not found"))?;
+
+    let mut delegation: DelegationGrant = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid delegation"))?;
+
+    delegation.status = DelegationStatus::Revoked;
+    delegation.revoked_at = Some(sys_time()?);
+    delegation.revocation_reason = Some(input.reason);
+
+    let updated_hash = update_entry(input.delegation_hash, &delegation)?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated delegation"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeDelegationInput {
+    pub delegation_hash: ActionHash,
+    pub reason: String,
+}
+
+/// Check if delegate has authorization for patient
+#[hdk_extern]
+pub fn check_delegation_authorization(input: DelegationAuthInput) -> ExternResult<DelegationAuthResult> {
+    let delegations = get_active_delegations(input.patient_hash.clone())?;
+
+    for record in delegations {
+        if let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() {
+            if delegation.delegate == input.delegate {
+                // Check if permission is granted
+                let permission_granted = delegation.permissions.contains(&input.permission);
+
+                // Check if data category is covered
+                let category_covered = delegation.data_scope.iter().any(|cat| {
+                    matches!(cat, DataCategory::All) || *cat == input.data_category
+                });
+
+                // Check if not excluded
+                let not_excluded = !delegation.exclusions.contains(&input.data_category);
+
+                if permission_granted && category_covered && not_excluded {
+                    if let Some(denial) = overriding_denial(&input.patient_hash, &input.delegate, &input.data_category)? {
+                        return Ok(DelegationAuthResult {
+                            authorized: false,
+                            delegation_hash: None,
+                            delegation_type: delegation.delegation_type.clone(),
+                            reason: denial,
+                        });
+                    }
+                    return Ok(DelegationAuthResult {
+                        authorized: true,
+                        delegation_hash: Some(record.action_address().clone()),
+                        delegation_type: delegation.delegation_type.clone(),
+                        reason: "Active delegation found".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(DelegationAuthResult {
+        authorized: false,
+        delegation_hash: None,
+        delegation_type: DelegationType::Temporary, // Default
+        reason: "No valid delegation found".to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DelegationAuthInput {
+    pub patient_hash: ActionHash,
+    pub delegate: AgentPubKey,
+    pub permission: DelegationPermission,
+    pub data_category: DataCategory,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct DelegationAuthResult {
+    pub authorized: bool,
+    pub delegation_hash: Option<ActionHash>,
+    pub delegation_type: DelegationType,
+    pub reason: String,
+}
+
+/// Get delegations where current agent is the delegate
+#[hdk_extern]
+pub fn get_my_delegations(_: ()) -> ExternResult<Vec<Record>> {
+    let my_agent = agent_info()?.agent_initial_pubkey;
+    let delegate_anchor = hash_entry(&Anchor(format!("delegate:{:?}", my_agent)))?;
+
+    let links = get_links(
+        LinkQuery::try_new(delegate_anchor, LinkTypes::DelegateToDelegations)?,
+        GetStrategy::default()
+    )?;
+
+    let mut delegations = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                // Only include active delegations
+                if let Some(delegation) = record.entry().to_app_option::<DelegationGrant>().ok().flatten() {
+                    if matches!(delegation.status, DelegationStatus::Active) {
+                        delegations.push(record);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(delegations)
+}
+
+// ============================================================
+// PATIENT NOTIFICATION SYSTEM
+// ============================================================
+
+/// Input for creating an access notification
+///
+/// The notification's fields sit at the top level, so a bare
+/// `AccessNotification` is accepted too.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateAccessNotificationInput {
+    #[serde(flatten)]
+    pub notification: AccessNotification,
+    /// Caller-chosen key, the same on every retry of one create; a retry
+    /// gets back the notification the first call created
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
+}
+
+/// Create notification for patient about data access
+///
+/// Notifications touching a category the sensitivity matrix marks for
+/// instant notification are raised to immediate priority.
+#[hdk_extern]
+pub fn create_access_notification(input: CreateAccessNotificationInput) -> ExternResult<Record> {
+    match &input.idempotency_key {
+        Some(key) => idempotent_create(
+            "consent::create_access_notification",
+            key,
+            &input.notification,
+            LinkTypes::IdempotencyKeys,
+            || write_access_notification(input.notification.clone()),
+        ),
+        None => write_access_notification(input.notification),
+    }
+}
+
+fn write_access_notification(mut notification: AccessNotification) -> ExternResult<Record> {
+    if notification.priority != NotificationPriority::Immediate {
+        let matrix = get_sensitivity_matrix(())?;
+        if notification.data_categories.iter().any(|c| matrix.notifies_instantly(&shared_category(c))) {
+            notification.priority = NotificationPriority::Immediate;
+        }
+    }
+    let notification_hash = create_entry(&EntryTypes::AccessNotification(notification.clone()))?;
+    let record = get(notification_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find notification"))?;
+
+    // Link to patient
+    create_link(
+        notification.patient_hash.clone(),
+        notification_hash.clone(),
+        LinkTypes::PatientToNotifications,
+        notification_link_tag(&notification),
+    )?;
+
+    if !notification.viewed {
+        increment_counter(notification.patient_hash, LinkTypes::UnreadNotificationCounter, 1)?;
+    }
+
+    Ok(record)
+}
+
+/// Get patient's notifications
+#[hdk_extern]
+pub fn get_patient_notifications(input: GetNotificationsInput) -> ExternResult<Vec<Record>> {
+    let mut links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToNotifications)?,
+        GetStrategy::default()
+    )?;
+    if input.unread_only {
+        links = filter_links_by_metadata(links, |metadata| metadata.has_status(&NotificationReadState::Unread));
+    }
+    // With every link tagged, only the newest `limit` records need fetching
+    if let Some(limit) = input.limit {
+        if links.iter().all(|link| LinkMetadata::from_tag(&link.tag).is_some()) {
+            sort_links_newest_first(&mut links);
+            links.truncate(limit as usize);
+        }
+    }
+
+    let mut notifications = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                notifications.push(record);
+            }
+        }
+    }
+
+    // Filter by unread only if requested
+    if input.unread_only {
+        notifications = notifications
+            .into_iter()
+            .filter(|record| {
+                if let Some(n) = record.entry().to_app_option::<AccessNotification>().ok().flatten() {
+                    !n.viewed
+                } else {
+                    false
+                }
+            })
+            .collect();
+    }
+
+    // Sort by accessed_at descending (most recent first)
+    notifications.sort_by(|a, b| {
+        let time_a = a.entry().to_app_option::<AccessNotification>().ok().flatten()
+            .map(|n| n.accessed_at.as_micros()).unwrap_or(0);
+        let time_b = b.entry().to_app_option::<AccessNotification>().ok().flatten()
+            .map(|n| n.accessed_at.as_micros()).unwrap_or(0);
+        time_b.cmp(&time_a) // Descending
+    });
+
+    // Apply limit
+    if let Some(limit) = input.limit {
+        notifications.truncate(limit as usize);
+    }
+
+    Ok(notifications)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetNotificationsInput {
+    pub patient_hash: ActionHash,
+    pub unread_only: bool,
+    pub limit: Option<u32>,
+}
+
+/// Mark notification as viewed
+#[hdk_extern]
+pub fn mark_notification_viewed(notification_hash: ActionHash) -> ExternResult<Record> {
+    let record = get(notification_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Notification not found"))?;
+
+    let mut notification: AccessNotification = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid notification"))?;
+
+    let was_unread = !notification.viewed;
+    notification.viewed = true;
+    notification.viewed_at = Some(sys_time()?);
+
+    let updated_hash = update_entry(notification_hash.clone(), &notification)?;
+    repoint_patient_link(
+        &notification.patient_hash,
+        LinkTypes::PatientToNotifications,
+        &notification_hash,
+        updated_hash.clone(),
+        notification_link_tag(&notification),
+    )?;
+
+    if was_unread {
+        increment_counter(notification.patient_hash, LinkTypes::UnreadNotificationCounter, -1)?;
+    }
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated notification"))
+}
+
+/// Get unread notification count
+#[hdk_extern]
+pub fn get_unread_notification_count(patient_hash: ActionHash) -> ExternResult<u32> {
+    let unread = read_counter(patient_hash, LinkTypes::UnreadNotificationCounter)?;
+    Ok(unread.clamp(0, u32::MAX as i64) as u32)
+}
+
+/// Set or update notification preferences
+#[hdk_extern]
+pub fn set_notification_preferences(prefs: NotificationPreferences) -> ExternResult<Record> {
+    let prefs_hash = create_entry(&EntryTypes::NotificationPreferences(prefs.clone()))?;
+    let record = get(prefs_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find preferences"))?;
+
+    // Link to patient (will have multiple over time, get latest)
+    create_link(
+        prefs.patient_hash,
+        prefs_hash,
+        LinkTypes::PatientToNotificationPreferences,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+/// Get patient's notification preferences
+#[hdk_extern]
+pub fn get_notification_preferences(patient_hash: ActionHash) -> ExternResult<Option<NotificationPreferences>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToNotificationPreferences)?,
+        GetStrategy::default()
+    )?;
+
+    // Get the most recent preferences
+    let mut latest: Option<(Timestamp, NotificationPreferences)> = None;
+
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if let Some(prefs) = record.entry().to_app_option::<NotificationPreferences>().ok().flatten() {
+                    match &latest {
+                        None => latest = Some((prefs.updated_at, prefs)),
+                        Some((ts, _)) if prefs.updated_at > *ts => {
+                            latest = Some((prefs.updated_at, prefs));
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(latest.map(|(_, prefs)| prefs))
+}
+
+/// Create notification digest (daily/weekly summary)
+#[hdk_extern]
+pub fn create_notification_digest(digest: NotificationDigest) -> ExternResult<Record> {
+    let digest_hash = create_entry(&EntryTypes::NotificationDigest(digest.clone()))?;
+    let record = get(digest_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find digest"))?;
+
+    create_link(
+        digest.patient_hash,
+        digest_hash,
+        LinkTypes::PatientToDigests,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+/// Generate plain-language summary for notification
+#[hdk_extern]
+pub fn generate_notification_summary(input: GenerateSummaryInput) -> ExternResult<String> {
+    let categories: Vec<String> = input.data_categories.iter()
+        .map(|c| match c {
+            DataCategory::Demographics => "basic information",
+            DataCategory::Allergies => "allergy information",
+            DataCategory::Medications => "medications",
+            DataCategory::Diagnoses => "diagnoses",
+            DataCategory::Procedures => "procedures",
+            DataCategory::LabResults => "lab results",
+            DataCategory::ImagingStudies => "imaging studies",
+            DataCategory::VitalSigns => "vital signs",
+            DataCategory::Immunizations => "immunizations",
+            DataCategory::MentalHealth => "mental health records",
+            DataCategory::SubstanceAbuse => "substance abuse records",
+            DataCategory::SexualHealth => "sexual health records",
+            DataCategory::GeneticData => "genetic data",
+            DataCategory::FinancialData => "billing information",
+            DataCategory::All => "all records",
+        }.to_string())
+        .collect();
+
+    let categories_text = if categories.len() == 1 {
+        categories[0].clone()
+    } else if categories.len() == 2 {
+        format!("{} and {}", categories[0], categories[1])
+    } else {
+        let last = categories.last().unwrap();
+        let others = &categories[..categories.len()-1];
+        format!("{}, and {}", others.join(", "), last)
+    };
+
+    let summary = if input.emergency_access {
+        format!(
+            "{} accessed your {} in an emergency situation",
+            input.accessor_name, categories_text
+        )
+    } else {
+        format!(
+            "{} viewed your {}",
+            input.accessor_name, categories_text
+        )
+    };
+
+    Ok(summary)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GenerateSummaryInput {
+    pub accessor_name: String,
+    pub data_categories: Vec<DataCategory>,
+    pub emergency_access: bool,
+}
+
+// ============================================================
+// CARE TEAM TEMPLATES
+// ============================================================
+
+/// Create a care team template
+#[hdk_extern]
+pub fn create_care_team_template(template: CareTeamTemplate) -> ExternResult<Record> {
+    let template_hash = create_entry(&EntryTypes::CareTeamTemplate(template.clone()))?;
+    let record = get(template_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find template"))?;
+
+    // Link to system templates anchor if it's a system template
+    if matches!(template.template_type, TemplateType::System) {
+        let system_anchor = anchor_hash("system_templates")?;
+        create_link(
+            system_anchor,
+            template_hash,
+            LinkTypes::SystemTemplates,
+            (),
+        )?;
+    } else if matches!(template.template_type, TemplateType::Organization(_)) {
+        let organization_anchor = anchor_hash("organization_templates")?;
+        create_link(
+            organization_anchor,
+            template_hash,
+            LinkTypes::OrganizationTemplates,
+            (),
+        )?;
+    }
+
+    if !matches!(template.template_type, TemplateType::Personal) {
+        append_policy_change(AppendPolicyLogInput {
+            policy_id: template.template_id.clone(),
+            kind: PolicyKind::CareTeamTemplate,
+            change: if template.previous_version.is_some() {
+                PolicyChange::Updated
+            } else {
+                PolicyChange::Created
+            },
+            policy_action: record.action_address().clone(),
+        })?;
+    }
+
+    Ok(record)
+}
+
+/// Get all system templates
+#[hdk_extern]
+pub fn get_system_templates(_: ()) -> ExternResult<Vec<Record>> {
+    let system_anchor = anchor_hash("system_templates")?;
+
+    let links = get_links(
+        LinkQuery::try_new(system_anchor, LinkTypes::SystemTemplates)?,
+        GetStrategy::default()
+    )?;
+
+    let hashes = links.into_iter().filter_map(|link| link.target.into_action_hash()).collect();
+    let templates = batch_get_typed::<CareTeamTemplate>(hashes, BatchGetOptions::default())?
+        .items
+        .into_iter()
+        .filter(|item| item.entry.active)
+        .map(|item| item.record)
+        .collect();
+
+    Ok(templates)
+}
+
+/// Initialize default system templates
+#[hdk_extern]
+pub fn initialize_system_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
+    let templates = vec![
+        CareTeamTemplate {
+            template_id: "primary-care-team".to_string(),
+            name: "Primary Care Team".to_string(),
+            description: "Your primary care doctor, nurses, and office staff can view most of your health information to coordinate your care.".to_string(),
+            permissions: vec![DataPermission::Read],
+            data_categories: vec![
+                DataCategory::Demographics,
+                DataCategory::Allergies,
+                DataCategory::Medications,
+                DataCategory::Diagnoses,
+                DataCategory::Procedures,
+                DataCategory::LabResults,
+                DataCategory::ImagingStudies,
+                DataCategory::VitalSigns,
+                DataCategory::Immunizations,
+            ],
+            default_exclusions: vec![
+                DataCategory::MentalHealth,
+                DataCategory::SubstanceAbuse,
+                DataCategory::SexualHealth,
+                DataCategory::GeneticData,
+            ],
+            purpose: ConsentPurpose::Treatment,
+            default_duration_days: Some(365),
+            template_type: TemplateType::System,
+            created_by: agent_info()?.agent_initial_pubkey,
+            created_at: sys_time()?,
+            active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
+        },
+        CareTeamTemplate {
+            template_id: "specialist-referral".to_string(),
+            name: "Specialist Referral".to_string(),
+            description: "A specialist you've been referred to can view relevant information for your consultation.".to_string(),
+            permissions: vec![DataPermission::Read],
+            data_categories: vec![
+                DataCategory::Demographics,
+                DataCategory::Allergies,
+                DataCategory::Medications,
+                DataCategory::Diagnoses,
+                DataCategory::Procedures,
+                DataCategory::LabResults,
+            ],
+            default_exclusions: vec![
+                DataCategory::MentalHealth,
+                DataCategory::SubstanceAbuse,
+                DataCategory::SexualHealth,
+                DataCategory::GeneticData,
+                DataCategory::FinancialData,
+            ],
+            purpose: ConsentPurpose::Treatment,
+            default_duration_days: Some(90),
+            template_type: TemplateType::System,
+            created_by: agent_info()?.agent_initial_pubkey,
+            created_at: sys_time()?,
             active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
         },
         CareTeamTemplate {
             template_id: "hospital-admission".to_string(),
@@ -1174,6 +2847,9 @@ pub fn initialize_system_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             created_by: agent_info()?.agent_initial_pubkey,
             created_at: sys_time()?,
             active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
         },
         CareTeamTemplate {
             template_id: "emergency-department".to_string(),
@@ -1196,6 +2872,9 @@ pub fn initialize_system_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             created_by: agent_info()?.agent_initial_pubkey,
             created_at: sys_time()?,
             active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
         },
         CareTeamTemplate {
             template_id: "mental-health-provider".to_string(),
@@ -1218,6 +2897,9 @@ pub fn initialize_system_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
             created_by: agent_info()?.agent_initial_pubkey,
             created_at: sys_time()?,
             active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
         },
         CareTeamTemplate {
             template_id: "pharmacy-access".to_string(),
@@ -1229,499 +2911,5401 @@ pub fn initialize_system_templates(_: ()) -> ExternResult<Vec<ActionHash>> {
                 DataCategory::Allergies,
                 DataCategory::Medications,
             ],
-            default_exclusions: vec![],
+            default_exclusions: vec![],
+            purpose: ConsentPurpose::Treatment,
+            default_duration_days: Some(365),
+            template_type: TemplateType::System,
+            created_by: agent_info()?.agent_initial_pubkey,
+            created_at: sys_time()?,
+            active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
+        },
+        CareTeamTemplate {
+            template_id: "insurance-billing".to_string(),
+            name: "Insurance & Billing".to_string(),
+            description: "Your insurance company can access billing information to process claims.".to_string(),
+            permissions: vec![DataPermission::Read],
+            data_categories: vec![
+                DataCategory::Demographics,
+                DataCategory::Diagnoses,
+                DataCategory::Procedures,
+                DataCategory::FinancialData,
+            ],
+            default_exclusions: vec![
+                DataCategory::MentalHealth,
+                DataCategory::SubstanceAbuse,
+                DataCategory::SexualHealth,
+                DataCategory::GeneticData,
+            ],
+            purpose: ConsentPurpose::Payment,
+            default_duration_days: Some(365),
+            template_type: TemplateType::System,
+            created_by: agent_info()?.agent_initial_pubkey,
+            created_at: sys_time()?,
+            active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
+        },
+        CareTeamTemplate {
+            template_id: "telehealth-visit".to_string(),
+            name: "Telehealth Visit".to_string(),
+            description: "A provider can access your records for a single telehealth visit.".to_string(),
+            permissions: vec![DataPermission::Read],
+            data_categories: vec![
+                DataCategory::Demographics,
+                DataCategory::Allergies,
+                DataCategory::Medications,
+                DataCategory::Diagnoses,
+                DataCategory::VitalSigns,
+            ],
+            default_exclusions: vec![
+                DataCategory::MentalHealth,
+                DataCategory::SubstanceAbuse,
+                DataCategory::SexualHealth,
+                DataCategory::GeneticData,
+                DataCategory::FinancialData,
+            ],
             purpose: ConsentPurpose::Treatment,
-            default_duration_days: Some(365),
+            default_duration_days: Some(1),
             template_type: TemplateType::System,
             created_by: agent_info()?.agent_initial_pubkey,
             created_at: sys_time()?,
             active: true,
+            specialty: None,
+            version: None,
+            previous_version: None,
+        },
+    ];
+
+    let mut created_hashes = Vec::new();
+    for template in templates {
+        let record = create_care_team_template(template)?;
+        created_hashes.push(record.action_address().clone());
+    }
+
+    Ok(created_hashes)
+}
+
+/// Create a care team from a template
+#[hdk_extern]
+pub fn create_care_team_from_template(input: CreateCareTeamInput) -> ExternResult<Record> {
+    // Get the template
+    let template_record = get(input.template_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Template not found"))?;
+
+    let template: CareTeamTemplate = template_record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid template"))?;
+
+    // Calculate expiration
+    let expires_at = template.default_duration_days.map(|days| {
+        let now = sys_time().unwrap();
+        let duration_micros = (days as i64) * 24 * 60 * 60 * 1_000_000;
+        Timestamp::from_micros(now.as_micros() + duration_micros)
+    });
+
+    // Create the care team
+    let care_team = CareTeam {
+        team_id: input.team_id,
+        patient_hash: input.patient_hash.clone(),
+        team_name: input.team_name.unwrap_or(template.name.clone()),
+        template_hash: Some(input.template_hash.clone()),
+        members: input.members,
+        permissions: template.permissions,
+        data_categories: template.data_categories,
+        exclusions: input.additional_exclusions.unwrap_or(template.default_exclusions),
+        purpose: template.purpose,
+        status: CareTeamStatus::Active,
+        created_at: sys_time()?,
+        expires_at,
+        notes: input.notes,
+        role_overrides: input.role_overrides,
+    };
+
+    // Each write is a saga step so a failed link cannot strand the team
+    let mut saga = Saga::begin(
+        "care_team_from_template",
+        &["create_care_team", "link_patient", "link_template", "link_active"],
+    )?;
+
+    let team_hash = saga.step("create_care_team", |undo| {
+        let hash = create_entry(&EntryTypes::CareTeam(care_team.clone()))?;
+        undo.push(SagaCompensation::DeleteEntry(hash.clone()));
+        Ok::<_, WasmError>(hash)
+    })?;
+    let record = get(team_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find care team"))?;
+
+    // Link to patient
+    saga.step("link_patient", |undo| {
+        undo.push(SagaCompensation::DeleteLink(create_link(
+            input.patient_hash.clone(),
+            team_hash.clone(),
+            LinkTypes::PatientToCareTeams,
+            care_team_link_tag(&care_team),
+        )?));
+        Ok::<_, WasmError>(())
+    })?;
+
+    // Link to template
+    saga.step("link_template", |undo| {
+        undo.push(SagaCompensation::DeleteLink(create_link(
+            input.template_hash.clone(),
+            team_hash.clone(),
+            LinkTypes::TemplateToTeams,
+            (),
+        )?));
+        Ok::<_, WasmError>(())
+    })?;
+
+    // Link to active care teams
+    saga.step("link_active", |undo| {
+        let active_anchor = hash_entry(&Anchor(format!("active_care_teams:{:?}", input.patient_hash)))?;
+        undo.push(SagaCompensation::DeleteLink(create_link(
+            active_anchor,
+            team_hash.clone(),
+            LinkTypes::ActiveCareTeams,
+            (),
+        )?));
+        Ok::<_, WasmError>(())
+    })?;
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateCareTeamInput {
+    pub team_id: String,
+    pub patient_hash: ActionHash,
+    pub template_hash: ActionHash,
+    pub team_name: Option<String>,
+    pub members: Vec<CareTeamMember>,
+    pub additional_exclusions: Option<Vec<DataCategory>>,
+    pub notes: Option<String>,
+    #[serde(default)]
+    pub role_overrides: Vec<CareTeamRoleOverride>,
+}
+
+// ============================================================
+// ORGANIZATION TEMPLATE MARKETPLACE
+// ============================================================
+
+/// Review state of a template version
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum EndorsementStatus {
+    /// Published but not yet reviewed
+    Pending,
+    Endorsed,
+    Rejected,
+}
+
+/// A discoverable template with its review state
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemplateListing {
+    pub template_hash: ActionHash,
+    pub template: CareTeamTemplate,
+    pub endorsement: EndorsementStatus,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct TemplateDiscoveryInput {
+    /// Case-insensitive specialty match
+    pub specialty: Option<String>,
+    pub endorsement: Option<EndorsementStatus>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PublishTemplateVersionInput {
+    pub previous_hash: ActionHash,
+    pub template: CareTeamTemplate,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EndorseTemplateInput {
+    pub template_hash: ActionHash,
+    pub decision: EndorsementDecision,
+    pub note: Option<String>,
+}
+
+/// A care team built from a template version that has since been superseded
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TemplateMigration {
+    pub team_hash: ActionHash,
+    pub team_name: String,
+    pub current_template_hash: ActionHash,
+    pub current_version: u32,
+    pub latest_template_hash: ActionHash,
+    pub latest_version: u32,
+    pub latest_endorsement: EndorsementStatus,
+}
+
+fn get_template(template_hash: &ActionHash) -> ExternResult<CareTeamTemplate> {
+    get(template_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<CareTeamTemplate>().ok().flatten())
+        .ok_or(not_found("Template not found"))
+}
+
+/// Follow the version chain from a template to its newest version
+fn latest_template_version(template_hash: ActionHash) -> ExternResult<(ActionHash, CareTeamTemplate)> {
+    let mut current_hash = template_hash;
+    let mut current = get_template(&current_hash)?;
+    loop {
+        let next = get_links(
+            LinkQuery::try_new(current_hash.clone(), LinkTypes::TemplateVersions)?,
+            GetStrategy::default(),
+        )?
+        .into_iter()
+        .filter_map(|link| link.target.into_action_hash())
+        .filter_map(|hash| get_template(&hash).ok().map(|template| (hash, template)))
+        .filter(|(_, template)| template.version() > current.version())
+        .max_by_key(|(_, template)| template.version());
+        match next {
+            Some((hash, template)) => {
+                current_hash = hash;
+                current = template;
+            }
+            None => return Ok((current_hash, current)),
+        }
+    }
+}
+
+/// Follow a record's updates to its newest version
+fn latest_record_version(record: Record) -> ExternResult<Record> {
+    let mut latest = record;
+    loop {
+        let newest_update = match get_details(latest.action_address().clone(), GetOptions::default())? {
+            Some(Details::Record(details)) => details
+                .updates
+                .into_iter()
+                .max_by_key(|update| update.action().timestamp()),
+            _ => None,
+        };
+        let Some(update) = newest_update else {
+            return Ok(latest);
+        };
+        match get(update.as_hash().clone(), GetOptions::default())? {
+            Some(next) => latest = next,
+            None => return Ok(latest),
+        }
+    }
+}
+
+/// Endorsement state of one template version; system templates need none
+fn template_endorsement_status(template_hash: &ActionHash, template: &CareTeamTemplate) -> ExternResult<EndorsementStatus> {
+    if matches!(template.template_type, TemplateType::System) {
+        return Ok(EndorsementStatus::Endorsed);
+    }
+    let mut latest: Option<TemplateEndorsement> = None;
+    for link in get_links(
+        LinkQuery::try_new(template_hash.clone(), LinkTypes::TemplateToEndorsements)?,
+        GetStrategy::default(),
+    )? {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(endorsement) = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<TemplateEndorsement>().ok().flatten())
+        else {
+            continue;
+        };
+        // Only decisions by current operators count
+        if !is_operator(endorsement.endorsed_by.clone())? {
+            continue;
+        }
+        if latest.as_ref().is_none_or(|l| endorsement.endorsed_at > l.endorsed_at) {
+            latest = Some(endorsement);
+        }
+    }
+    Ok(match latest.map(|e| e.decision) {
+        Some(EndorsementDecision::Endorsed) => EndorsementStatus::Endorsed,
+        Some(EndorsementDecision::Rejected) => EndorsementStatus::Rejected,
+        None => EndorsementStatus::Pending,
+    })
+}
+
+/// Publish an organization's own care team template
+///
+/// New templates start as version 1 and appear in discovery as pending
+/// until an operator endorses them.
+#[hdk_extern]
+pub fn publish_organization_template(template: CareTeamTemplate) -> ExternResult<Record> {
+    if !matches!(template.template_type, TemplateType::Organization(_)) {
+        return Err(conflict("Only organization templates can be published to the marketplace"));
+    }
+    let mut template = template;
+    template.created_by = agent_info()?.agent_initial_pubkey;
+    template.created_at = sys_time()?;
+    template.version = Some(1);
+    template.previous_version = None;
+    create_care_team_template(template)
+}
+
+/// Publish a new version of an organization template
+///
+/// The previous version stays in place for the teams built from it; they
+/// can migrate once the new version is endorsed.
+#[hdk_extern]
+pub fn publish_template_version(input: PublishTemplateVersionInput) -> ExternResult<Record> {
+    let (latest_hash, latest) = latest_template_version(input.previous_hash)?;
+    let mut template = input.template;
+    template.template_id = latest.template_id.clone();
+    template.template_type = latest.template_type.clone();
+    template.created_by = agent_info()?.agent_initial_pubkey;
+    template.created_at = sys_time()?;
+    template.version = Some(latest.version() + 1);
+    template.previous_version = Some(latest_hash.clone());
+
+    let record = create_care_team_template(template)?;
+    create_link(
+        latest_hash,
+        record.action_address().clone(),
+        LinkTypes::TemplateVersions,
+        (),
+    )?;
+    Ok(record)
+}
+
+/// Endorse or reject an organization template version (operators only)
+#[hdk_extern]
+pub fn endorse_template(input: EndorseTemplateInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller.clone())? {
+        return Err(unauthorized("Template endorsement requires the operator role"));
+    }
+    let template = get_template(&input.template_hash)?;
+    let endorsement = TemplateEndorsement {
+        template_hash: input.template_hash.clone(),
+        decision: input.decision,
+        endorsed_by: caller,
+        endorsed_at: sys_time()?,
+        note: input.note,
+    };
+    let endorsement_hash = create_entry(&EntryTypes::TemplateEndorsement(endorsement))?;
+    create_link(
+        input.template_hash,
+        endorsement_hash.clone(),
+        LinkTypes::TemplateToEndorsements,
+        (),
+    )?;
+    append_policy_change(AppendPolicyLogInput {
+        policy_id: template.template_id,
+        kind: PolicyKind::TemplateEndorsement,
+        change: PolicyChange::Created,
+        policy_action: endorsement_hash.clone(),
+    })?;
+    get(endorsement_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find endorsement"))
+}
+
+/// Find the newest version of active system and organization templates
+#[hdk_extern]
+pub fn discover_templates(input: TemplateDiscoveryInput) -> ExternResult<Vec<TemplateListing>> {
+    let specialty = input.specialty.map(|s| s.trim().to_lowercase());
+    let mut links = get_links(
+        LinkQuery::try_new(anchor_hash("system_templates")?, LinkTypes::SystemTemplates)?,
+        GetStrategy::default(),
+    )?;
+    links.extend(get_links(
+        LinkQuery::try_new(anchor_hash("organization_templates")?, LinkTypes::OrganizationTemplates)?,
+        GetStrategy::default(),
+    )?);
+
+    let mut listings = Vec::new();
+    for link in links {
+        let Some(template_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Ok(template) = get_template(&template_hash) else {
+            continue;
+        };
+        let superseded = !get_links(
+            LinkQuery::try_new(template_hash.clone(), LinkTypes::TemplateVersions)?,
+            GetStrategy::default(),
+        )?
+        .is_empty();
+        if !template.active || superseded {
+            continue;
+        }
+        if let Some(wanted) = &specialty {
+            let matches = template
+                .specialty
+                .as_ref()
+                .is_some_and(|s| &s.trim().to_lowercase() == wanted);
+            if !matches {
+                continue;
+            }
+        }
+        let endorsement = template_endorsement_status(&template_hash, &template)?;
+        if input.endorsement.as_ref().is_some_and(|wanted| wanted != &endorsement) {
+            continue;
+        }
+        listings.push(TemplateListing {
+            template_hash,
+            template,
+            endorsement,
+        });
+    }
+    Ok(listings)
+}
+
+/// Care teams whose template has a newer version
+#[hdk_extern]
+pub fn get_care_teams_pending_migration(patient_hash: ActionHash) -> ExternResult<Vec<TemplateMigration>> {
+    let mut pending = Vec::new();
+    for record in get_active_care_teams(patient_hash)? {
+        let record = latest_record_version(record)?;
+        let Some(team) = record.entry().to_app_option::<CareTeam>().ok().flatten() else {
+            continue;
+        };
+        let Some(current_hash) = team.template_hash.clone() else {
+            continue;
+        };
+        let Ok(current) = get_template(&current_hash) else {
+            continue;
+        };
+        let (latest_hash, latest) = latest_template_version(current_hash.clone())?;
+        if latest_hash == current_hash {
+            continue;
+        }
+        pending.push(TemplateMigration {
+            team_hash: record.action_address().clone(),
+            team_name: team.team_name,
+            current_template_hash: current_hash,
+            current_version: current.version(),
+            latest_endorsement: template_endorsement_status(&latest_hash, &latest)?,
+            latest_template_hash: latest_hash,
+            latest_version: latest.version(),
+        });
+    }
+    Ok(pending)
+}
+
+/// Move a care team onto the newest endorsed version of its template
+///
+/// The team takes the new version's permissions, categories and purpose,
+/// keeps its own exclusions on top of the new defaults, and drops role
+/// overrides the new grant no longer covers.
+#[hdk_extern]
+pub fn migrate_care_team_template(team_hash: ActionHash) -> ExternResult<Record> {
+    let record = latest_record_version(
+        get(team_hash, GetOptions::default())?
+            .ok_or(not_found("Care team not found"))?,
+    )?;
+    let team_hash = record.action_address().clone();
+    let mut team: CareTeam = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid care team"))?;
+    let current_hash = team.template_hash.clone().ok_or(conflict("Care team was not created from a template"))?;
+
+    let (latest_hash, latest) = latest_template_version(current_hash.clone())?;
+    if latest_hash == current_hash {
+        return Ok(record);
+    }
+    if template_endorsement_status(&latest_hash, &latest)? != EndorsementStatus::Endorsed {
+        return Err(conflict(format!(
+            "Version {} of this template has not been endorsed yet",
+            latest.version()
+        )));
+    }
+
+    team.template_hash = Some(latest_hash.clone());
+    team.permissions = latest.permissions;
+    team.data_categories = latest.data_categories;
+    team.purpose = latest.purpose;
+    for category in latest.default_exclusions {
+        if !team.exclusions.contains(&category) {
+            team.exclusions.push(category);
+        }
+    }
+    let still_covered: Vec<CareTeamRoleOverride> = team
+        .role_overrides
+        .iter()
+        .filter(|o| {
+            o.permissions
+                .iter()
+                .all(|p| o.data_categories.iter().all(|c| team.team_allows(p, c)))
+        })
+        .cloned()
+        .collect();
+    team.role_overrides = still_covered;
+
+    let updated_hash = update_entry(team_hash, &team)?;
+    create_link(latest_hash, updated_hash.clone(), LinkTypes::TemplateToTeams, ())?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated care team"))
+}
+
+/// Get patient's care teams
+#[hdk_extern]
+pub fn get_patient_care_teams(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToCareTeams)?,
+        GetStrategy::default()
+    )?;
+    care_team_records(links)
+}
+
+/// Linked care teams at their latest version, so members, overrides and
+/// status changes written as updates are what authorization reads
+fn care_team_records(links: Vec<Link>) -> ExternResult<Vec<Record>> {
+    let mut teams = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                teams.push(latest_record_version(record)?);
+            }
+        }
+    }
+
+    Ok(teams)
+}
+
+/// Get active care teams for a patient
+#[hdk_extern]
+pub fn get_active_care_teams(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToCareTeams)?,
+        GetStrategy::default()
+    )?;
+    let links = filter_links_by_metadata(links, |metadata| metadata.has_status(&CareTeamStatus::Active));
+    let all_teams = care_team_records(links)?;
+
+    let active: Vec<Record> = all_teams
+        .into_iter()
+        .filter(|record| {
+            if let Some(team) = record.entry().to_app_option::<CareTeam>().ok().flatten() {
+                matches!(team.status, CareTeamStatus::Active)
+            } else {
+                false
+            }
+        })
+        .collect();
+
+    Ok(active)
+}
+
+/// Add member to care team
+#[hdk_extern]
+pub fn add_care_team_member(input: AddMemberInput) -> ExternResult<Record> {
+    let record = latest_record_version(
+        get(input.team_hash.clone(), GetOptions::default())?
+            .ok_or(not_found("Care team not found"))?,
+    )?;
+
+    let mut team: CareTeam = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid care team"))?;
+
+    team.members.push(input.member);
+
+    let updated_hash = update_entry(record.action_address().clone(), &team)?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated care team"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddMemberInput {
+    pub team_hash: ActionHash,
+    pub member: CareTeamMember,
+}
+
+/// Remove member from care team
+#[hdk_extern]
+pub fn remove_care_team_member(input: RemoveMemberInput) -> ExternResult<Record> {
+    let record = latest_record_version(
+        get(input.team_hash.clone(), GetOptions::default())?
+            .ok_or(not_found("Care team not found"))?,
+    )?;
+
+    let mut team: CareTeam = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid care team"))?;
+
+    // Mark member as inactive instead of removing (for audit trail)
+    for member in &mut team.members {
+        match (&member.member, &input.member) {
+            (CareTeamMemberType::Provider(h1), CareTeamMemberType::Provider(h2)) if h1 == h2 => {
+                member.active = false;
+            }
+            (CareTeamMemberType::Agent(a1), CareTeamMemberType::Agent(a2)) if a1 == a2 => {
+                member.active = false;
+            }
+            (CareTeamMemberType::Organization(o1), CareTeamMemberType::Organization(o2)) if o1 == o2 => {
+                member.active = false;
+            }
+            _ => {}
+        }
+    }
+
+    let updated_hash = update_entry(record.action_address().clone(), &team)?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated care team"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemoveMemberInput {
+    pub team_hash: ActionHash,
+    pub member: CareTeamMemberType,
+}
+
+/// Replace the role-scoped permission overrides on a care team
+#[hdk_extern]
+pub fn set_care_team_role_overrides(input: SetRoleOverridesInput) -> ExternResult<Record> {
+    let record = latest_record_version(
+        get(input.team_hash.clone(), GetOptions::default())?
+            .ok_or(not_found("Care team not found"))?,
+    )?;
+
+    let mut team: CareTeam = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid care team"))?;
+
+    team.role_overrides = input.role_overrides;
+
+    let updated_hash = update_entry(record.action_address().clone(), &team)?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated care team"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetRoleOverridesInput {
+    pub team_hash: ActionHash,
+    pub role_overrides: Vec<CareTeamRoleOverride>,
+}
+
+/// Dissolve a care team
+#[hdk_extern]
+pub fn dissolve_care_team(team_hash: ActionHash) -> ExternResult<Record> {
+    let record = get(team_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Care team not found"))?;
+
+    let mut team: CareTeam = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid care team"))?;
+
+    team.status = CareTeamStatus::Dissolved;
+
+    let updated_hash = update_entry(team_hash.clone(), &team)?;
+    repoint_patient_link(
+        &team.patient_hash,
+        LinkTypes::PatientToCareTeams,
+        &team_hash,
+        updated_hash.clone(),
+        care_team_link_tag(&team),
+    )?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated care team"))
+}
+
+/// Check if a member has care team authorization
+#[hdk_extern]
+pub fn check_care_team_authorization(input: CareTeamAuthInput) -> ExternResult<CareTeamAuthResult> {
+    let teams = get_active_care_teams(input.patient_hash.clone())?;
+
+    for team_record in teams {
+        if let Some(team) = team_record.entry().to_app_option::<CareTeam>().ok().flatten() {
+            // Check if member is in this team
+            for member in &team.members {
+                if !member.active {
+                    continue;
+                }
+
+                let is_member = match (&member.member, &input.member) {
+                    (CareTeamMemberType::Provider(h1), CareTeamMemberType::Provider(h2)) => h1 == h2,
+                    (CareTeamMemberType::Agent(a1), CareTeamMemberType::Agent(a2)) => a1 == a2,
+                    (CareTeamMemberType::Organization(o1), CareTeamMemberType::Organization(o2)) => o1 == o2,
+                    _ => false,
+                };
+
+                // Role overrides can only narrow what the team grants
+                if is_member && team.role_allows(&member.role, &input.permission, &input.data_category) {
+                    let agent = match &input.member {
+                        CareTeamMemberType::Agent(agent) => Some(agent.clone()),
+                        CareTeamMemberType::Provider(provider_hash) => {
+                            get(provider_hash.clone(), GetOptions::default())?.map(|profile| profile.action().author().clone())
+                        }
+                        CareTeamMemberType::Organization(_) => None,
+                    };
+                    if let Some(agent) = agent {
+                        if let Some(denial) = overriding_denial(&input.patient_hash, &agent, &input.data_category)? {
+                            return Ok(CareTeamAuthResult {
+                                authorized: false,
+                                care_team_hash: None,
+                                team_name: team.team_name.clone(),
+                                member_role: member.role.clone(),
+                                reason: denial,
+                            });
+                        }
+                    }
+                    return Ok(CareTeamAuthResult {
+                        authorized: true,
+                        care_team_hash: Some(team_record.action_address().clone()),
+                        team_name: team.team_name.clone(),
+                        member_role: member.role.clone(),
+                        reason: "Active care team membership".to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    Ok(CareTeamAuthResult {
+        authorized: false,
+        care_team_hash: None,
+        team_name: String::new(),
+        member_role: CareTeamRole::Other("None".to_string()),
+        reason: "Not a member of any authorized care team".to_string(),
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CareTeamAuthInput {
+    pub patient_hash: ActionHash,
+    pub member: CareTeamMemberType,
+    pub permission: DataPermission,
+    pub data_category: DataCategory,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CareTeamAuthResult {
+    pub authorized: bool,
+    pub care_team_hash: Option<ActionHash>,
+    pub team_name: String,
+    pub member_role: CareTeamRole,
+    pub reason: String,
+}
+
+// ============================================================
+// POLICY TRANSPARENCY LOG
+// ============================================================
+
+const POLICY_LOG_ANCHOR: &str = "policy_log";
+
+fn policy_history_anchor(policy_id: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("policy_log:{}", policy_id))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AppendPolicyLogInput {
+    pub policy_id: String,
+    pub kind: PolicyKind,
+    pub change: PolicyChange,
+    /// Record holding the policy as changed; must be authored by the caller
+    pub policy_action: ActionHash,
+}
+
+/// Append a policy change to the transparency log
+///
+/// Concurrent appends can claim the same sequence number; `verify_policy_log`
+/// reports these as forks rather than hiding them.
+fn append_policy_change(input: AppendPolicyLogInput) -> ExternResult<Record> {
+    let policy_entry_hash = get(input.policy_action.clone(), GetOptions::default())?
+        .and_then(|record| record.action().entry_hash().cloned())
+        .ok_or(not_found("Policy record not found"))?;
+
+    let head = get_links(
+        LinkQuery::try_new(anchor_hash(POLICY_LOG_ANCHOR)?, LinkTypes::PolicyLog)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| Some((tag_sequence(&link.tag)?, link.target.into_action_hash()?)))
+    .max_by_key(|(sequence, _)| *sequence);
+
+    let entry = PolicyLogEntry {
+        sequence: head.as_ref().map_or(0, |(sequence, _)| sequence + 1),
+        previous: head.map(|(_, hash)| hash),
+        policy_id: input.policy_id,
+        kind: input.kind,
+        change: input.change,
+        policy_action: input.policy_action,
+        policy_entry_hash,
+        recorded_by: agent_info()?.agent_initial_pubkey,
+        recorded_at: sys_time()?,
+    };
+    let entry_hash = create_entry(&EntryTypes::PolicyLogEntry(entry.clone()))?;
+    create_link(
+        anchor_hash(POLICY_LOG_ANCHOR)?,
+        entry_hash.clone(),
+        LinkTypes::PolicyLog,
+        LinkTag::new(entry.sequence.to_be_bytes().to_vec()),
+    )?;
+    create_link(
+        policy_history_anchor(&entry.policy_id)?,
+        entry_hash.clone(),
+        LinkTypes::PolicyHistory,
+        (),
+    )?;
+    get(entry_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find policy log entry"))
+}
+
+fn tag_sequence(tag: &LinkTag) -> Option<u64> {
+    Some(u64::from_be_bytes(tag.as_ref().as_slice().try_into().ok()?))
+}
+
+/// Log a change to a policy this agent authored, for zomes that own other
+/// policy kinds
+#[hdk_extern]
+pub fn append_policy_log(input: AppendPolicyLogInput) -> ExternResult<Record> {
+    append_policy_change(input)
+}
+
+/// Log entries found at a log anchor, ordered by sequence
+fn policy_log_entries(base: EntryHash, link_type: LinkTypes) -> ExternResult<Vec<(ActionHash, PolicyLogEntry)>> {
+    let mut entries: Vec<(ActionHash, PolicyLogEntry)> = get_links(
+        LinkQuery::try_new(base, link_type)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .filter_map(|hash| {
+        let entry = get(hash.clone(), GetOptions::default()).ok()??
+            .entry()
+            .to_app_option::<PolicyLogEntry>()
+            .ok()??;
+        Some((hash, entry))
+    })
+    .collect();
+    entries.sort_by(|(a_hash, a), (b_hash, b)| a.sequence.cmp(&b.sequence).then_with(|| a_hash.cmp(b_hash)));
+    entries.dedup_by(|(a, _), (b, _)| a == b);
+    Ok(entries)
+}
+
+/// Something `verify_policy_log` found wrong with the log
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PolicyLogProblem {
+    /// No entry carries this sequence number
+    MissingSequence(u64),
+    /// Several entries claim one sequence number
+    Fork { sequence: u64, entries: Vec<ActionHash> },
+    /// The entry does not point at an entry one sequence number earlier
+    BrokenChain { sequence: u64, entry: ActionHash },
+    /// The logged policy record is unavailable or no longer matches its entry hash
+    ContentMismatch { sequence: u64, entry: ActionHash },
+    /// A published template version or endorsement that was never logged
+    Unlogged { policy_action: ActionHash },
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PolicyLogVerification {
+    pub length: u64,
+    pub head: Option<ActionHash>,
+    pub verified: bool,
+    pub problems: Vec<PolicyLogProblem>,
+}
+
+/// Check the policy log's hash chain and that every published template
+/// change appears in it
+#[hdk_extern]
+pub fn verify_policy_log(_: ()) -> ExternResult<PolicyLogVerification> {
+    let entries = policy_log_entries(anchor_hash(POLICY_LOG_ANCHOR)?, LinkTypes::PolicyLog)?;
+    let mut problems = Vec::new();
+
+    let length = entries.last().map_or(0, |(_, entry)| entry.sequence + 1);
+    for sequence in 0..length {
+        let at: Vec<ActionHash> = entries
+            .iter()
+            .filter(|(_, entry)| entry.sequence == sequence)
+            .map(|(hash, _)| hash.clone())
+            .collect();
+        match at.len() {
+            0 => problems.push(PolicyLogProblem::MissingSequence(sequence)),
+            1 => {}
+            _ => problems.push(PolicyLogProblem::Fork { sequence, entries: at }),
+        }
+    }
+
+    for (hash, entry) in &entries {
+        let links_back = match &entry.previous {
+            None => entry.sequence == 0,
+            Some(previous) => entries
+                .iter()
+                .any(|(h, e)| h == previous && e.sequence + 1 == entry.sequence),
+        };
+        if !links_back {
+            problems.push(PolicyLogProblem::BrokenChain { sequence: entry.sequence, entry: hash.clone() });
+        }
+        let matches = get(entry.policy_action.clone(), GetOptions::default())?
+            .is_some_and(|record| record.action().entry_hash() == Some(&entry.policy_entry_hash));
+        if !matches {
+            problems.push(PolicyLogProblem::ContentMismatch { sequence: entry.sequence, entry: hash.clone() });
+        }
+    }
+
+    let logged: Vec<&ActionHash> = entries.iter().map(|(_, entry)| &entry.policy_action).collect();
+    for policy_action in published_policy_actions()? {
+        if !logged.contains(&&policy_action) {
+            problems.push(PolicyLogProblem::Unlogged { policy_action });
+        }
+    }
+
+    Ok(PolicyLogVerification {
+        length,
+        head: entries.last().map(|(hash, _)| hash.clone()),
+        verified: problems.is_empty(),
+        problems,
+    })
+}
+
+/// Every record that changed a system or organization template: each
+/// version, any in-place update of one, and each endorsement
+fn published_policy_actions() -> ExternResult<Vec<ActionHash>> {
+    let mut template_links = get_links(
+        LinkQuery::try_new(anchor_hash("system_templates")?, LinkTypes::SystemTemplates)?,
+        GetStrategy::default(),
+    )?;
+    template_links.extend(get_links(
+        LinkQuery::try_new(anchor_hash("organization_templates")?, LinkTypes::OrganizationTemplates)?,
+        GetStrategy::default(),
+    )?);
+
+    let mut actions = Vec::new();
+    for template_hash in template_links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if actions.contains(&template_hash) {
+            continue;
+        }
+        if let Some(Details::Record(details)) = get_details(template_hash.clone(), GetOptions::default())? {
+            actions.extend(details.updates.iter().map(|update| update.as_hash().clone()));
+        }
+        actions.extend(
+            get_links(
+                LinkQuery::try_new(template_hash.clone(), LinkTypes::TemplateToEndorsements)?,
+                GetStrategy::default(),
+            )?
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash()),
+        );
+        actions.push(template_hash);
+    }
+    Ok(actions)
+}
+
+/// A policy log entry with the policy record it points to
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PolicyHistoryEntry {
+    pub log_hash: ActionHash,
+    pub entry: PolicyLogEntry,
+    pub policy: Option<Record>,
+}
+
+/// The full logged evolution of one policy, oldest first
+#[hdk_extern]
+pub fn get_policy_history(policy_id: String) -> ExternResult<Vec<PolicyHistoryEntry>> {
+    policy_log_entries(policy_history_anchor(&policy_id)?, LinkTypes::PolicyHistory)?
+        .into_iter()
+        .map(|(log_hash, entry)| {
+            let policy = get(entry.policy_action.clone(), GetOptions::default())?;
+            Ok(PolicyHistoryEntry { log_hash, entry, policy })
+        })
+        .collect()
+}
+
+// ============================================================
+// MULTI-GUARDIAN CONSENT (MINORS)
+// ============================================================
+
+/// Create a guardian policy for a minor patient
+///
+/// A patient has one policy; after it is written only its guardians can
+/// change it, through `update_guardian_policy`.
+#[hdk_extern]
+pub fn create_guardian_policy(policy: GuardianPolicy) -> ExternResult<Record> {
+    if patient_guardian_policy(&policy.patient_hash)?.is_some() {
+        return Err(conflict("The patient already has a guardian policy; its guardians update it"));
+    }
+    let policy_hash = create_entry(&EntryTypes::GuardianPolicy(policy.clone()))?;
+    let record = get(policy_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find guardian policy"))?;
+
+    create_link(
+        policy.patient_hash,
+        policy_hash,
+        LinkTypes::PatientToGuardianPolicies,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateGuardianPolicyInput {
+    /// The policy as the patient first wrote it
+    pub original_policy_hash: ActionHash,
+    pub policy: GuardianPolicy,
+}
+
+/// Replace or deactivate a patient's guardian policy (its guardians only)
+#[hdk_extern]
+pub fn update_guardian_policy(input: UpdateGuardianPolicyInput) -> ExternResult<Record> {
+    let (latest_hash, current) = latest_guardian_policy(&input.original_policy_hash)?;
+    let me = agent_info()?.agent_initial_pubkey;
+    if !current.guardians.contains(&me) {
+        return Err(unauthorized("Only the policy's guardians can change a guardian policy"));
+    }
+    if input.policy.patient_hash != current.patient_hash {
+        return Err(invalid_input("A guardian policy cannot move to another patient"));
+    }
+    let updated_hash = update_entry(latest_hash, &input.policy)?;
+    create_link(
+        input.original_policy_hash,
+        updated_hash.clone(),
+        LinkTypes::GuardianPolicyUpdates,
+        (),
+    )?;
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated guardian policy"))
+}
+
+/// Latest version of a guardian policy and its hash
+fn latest_guardian_policy(original_policy_hash: &ActionHash) -> ExternResult<(ActionHash, GuardianPolicy)> {
+    let latest = get_links(
+        LinkQuery::try_new(original_policy_hash.clone(), LinkTypes::GuardianPolicyUpdates)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash())
+    .unwrap_or_else(|| original_policy_hash.clone());
+    let policy = get(latest.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<GuardianPolicy>().ok().flatten())
+        .ok_or(not_found("Guardian policy not found"))?;
+    Ok((latest, policy))
+}
+
+/// The patient's guardian policy at its latest version, active or not
+///
+/// The patient's first policy is the one validation holds consents to, so
+/// later ones left by older chains are ignored.
+fn patient_guardian_policy(patient_hash: &ActionHash) -> ExternResult<Option<(ActionHash, GuardianPolicy)>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToGuardianPolicies)?,
+        GetStrategy::default(),
+    )?;
+    let mut first: Option<(Timestamp, ActionHash)> = None;
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if record.entry().to_app_option::<GuardianPolicy>().ok().flatten().is_none() {
+            continue;
+        }
+        let written_at = record.action().timestamp();
+        if first.as_ref().is_none_or(|(at, _)| written_at < *at) {
+            first = Some((written_at, hash));
+        }
+    }
+    match first {
+        Some((_, original)) => Ok(Some(latest_guardian_policy(&original)?)),
+        None => Ok(None),
+    }
+}
+
+/// Get the patient's guardian policy at its latest version, if active
+#[hdk_extern]
+pub fn get_guardian_policy(patient_hash: ActionHash) -> ExternResult<Option<Record>> {
+    match patient_guardian_policy(&patient_hash)? {
+        Some((hash, policy)) if policy.active => get(hash, GetOptions::default()),
+        _ => Ok(None),
+    }
+}
+
+/// Record the calling guardian's signed decision on a pending consent
+#[hdk_extern]
+pub fn submit_guardian_decision(input: GuardianDecisionInput) -> ExternResult<Record> {
+    let consent_record = get(input.consent_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Consent not found"))?;
+    let consent: Consent = consent_record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
+
+    if !matches!(consent.status, ConsentStatus::Pending) {
+        return Err(conflict("Only pending consents can collect guardian decisions"));
+    }
+
+    let policy_record = get_guardian_policy(consent.patient_hash)?
+        .ok_or(not_found("No active guardian policy for patient"))?;
+
+    let guardian = agent_info()?.agent_initial_pubkey;
+    let approval = GuardianApproval {
+        consent_hash: input.consent_hash.clone(),
+        policy_hash: policy_record.action_address().clone(),
+        guardian: guardian.clone(),
+        approved: input.approved,
+        signature: sign(guardian, &input.consent_hash)?,
+        decided_at: sys_time()?,
+        comment: input.comment,
+    };
+
+    let approval_hash = create_entry(&EntryTypes::GuardianApproval(approval))?;
+    create_link(
+        input.consent_hash,
+        approval_hash.clone(),
+        LinkTypes::ConsentToGuardianApprovals,
+        (),
+    )?;
+
+    get(approval_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find guardian approval"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GuardianDecisionInput {
+    pub consent_hash: ActionHash,
+    pub approved: bool,
+    pub comment: Option<String>,
+}
+
+/// Each guardian's latest decision on a consent under the policy version
+/// `policy_hash`, with the approval's hash
+fn guardian_decisions(
+    consent_hash: &ActionHash,
+    policy_hash: &ActionHash,
+    policy: &GuardianPolicy,
+) -> ExternResult<Vec<(ActionHash, GuardianApproval)>> {
+    let links = get_links(
+        LinkQuery::try_new(consent_hash.clone(), LinkTypes::ConsentToGuardianApprovals)?,
+        GetStrategy::default()
+    )?;
+    let mut decisions: Vec<(ActionHash, GuardianApproval)> = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash.clone(), GetOptions::default())? {
+                if let Some(approval) = record.entry().to_app_option::<GuardianApproval>().ok().flatten() {
+                    if !policy.guardians.contains(&approval.guardian) || approval.policy_hash != *policy_hash {
+                        continue;
+                    }
+                    match decisions.iter_mut().find(|(_, d)| d.guardian == approval.guardian) {
+                        Some(existing) if existing.1.decided_at < approval.decided_at => *existing = (hash, approval),
+                        Some(_) => {}
+                        None => decisions.push((hash, approval)),
+                    }
+                }
+            }
+        }
+    }
+    Ok(decisions)
+}
+
+/// Evaluate collected guardian decisions against the patient's policy
+///
+/// Only decisions made under the policy's current version count.
+#[hdk_extern]
+pub fn get_guardian_quorum_status(consent_hash: ActionHash) -> ExternResult<GuardianQuorumStatus> {
+    Ok(guardian_quorum(&consent_hash)?.0)
+}
+
+/// The quorum status of a consent with the policy version it was judged
+/// under and the approvals counted towards it
+fn guardian_quorum(consent_hash: &ActionHash) -> ExternResult<(GuardianQuorumStatus, ActionHash, Vec<ActionHash>)> {
+    let consent_record = get(consent_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Consent not found"))?;
+    let consent: Consent = consent_record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
+
+    let (policy_hash, policy) = patient_guardian_policy(&consent.patient_hash)?
+        .filter(|(_, policy)| policy.active)
+        .ok_or(not_found("No active guardian policy for patient"))?;
+
+    let (required_approvers, min_approvals) = match policy.quorum_for(&consent.scope.data_categories) {
+        Some(quorum) => quorum,
+        None => {
+            let status = GuardianQuorumStatus {
+                consent_hash: consent_hash.clone(),
+                quorum_met: true,
+                rejected: false,
+                approved_by: Vec::new(),
+                rejected_by: Vec::new(),
+                missing_required: Vec::new(),
+                approvals_needed: 0,
+            };
+            return Ok((status, policy_hash, Vec::new()));
+        }
+    };
+
+    // Latest decision per guardian wins
+    let decisions = guardian_decisions(consent_hash, &policy_hash, &policy)?;
+    let approvals: Vec<ActionHash> = decisions.iter().filter(|(_, d)| d.approved).map(|(hash, _)| hash.clone()).collect();
+    let approved_by: Vec<AgentPubKey> = decisions.iter().filter(|(_, d)| d.approved).map(|(_, d)| d.guardian.clone()).collect();
+    let rejected_by: Vec<AgentPubKey> = decisions.iter().filter(|(_, d)| !d.approved).map(|(_, d)| d.guardian.clone()).collect();
+    let missing_required: Vec<AgentPubKey> = required_approvers
+        .into_iter()
+        .filter(|a| !approved_by.contains(a))
+        .collect();
+    let approvals_needed = min_approvals.saturating_sub(approved_by.len() as u32);
+
+    let status = GuardianQuorumStatus {
+        consent_hash: consent_hash.clone(),
+        quorum_met: missing_required.is_empty() && approvals_needed == 0,
+        rejected: !rejected_by.is_empty(),
+        approved_by,
+        rejected_by,
+        missing_required,
+        approvals_needed,
+    };
+    Ok((status, policy_hash, approvals))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GuardianQuorumStatus {
+    pub consent_hash: ActionHash,
+    pub quorum_met: bool,
+    /// A guardian has declined; the consent cannot be activated
+    pub rejected: bool,
+    pub approved_by: Vec<AgentPubKey>,
+    pub rejected_by: Vec<AgentPubKey>,
+    pub missing_required: Vec<AgentPubKey>,
+    pub approvals_needed: u32,
+}
+
+/// Activate a pending consent once the guardian quorum has approved it
+///
+/// The consent carries the approvals and the policy version they were
+/// given under, which validation checks against the quorum.
+#[hdk_extern]
+pub fn activate_guardian_consent(consent_hash: ActionHash) -> ExternResult<Record> {
+    let (status, policy_hash, approvals) = guardian_quorum(&consent_hash)?;
+    if status.rejected {
+        return Err(conflict("Consent was declined by a guardian"));
+    }
+    if !status.quorum_met {
+        return Err(conflict(format!(
+            "Guardian quorum not met: {} more approval(s), {} required approver(s) outstanding",
+            status.approvals_needed,
+            status.missing_required.len()
+        )));
+    }
+
+    let record = get(consent_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Consent not found"))?;
+    let mut consent: Consent = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
+
+    if !matches!(consent.status, ConsentStatus::Pending) {
+        return Err(conflict("Only pending consents can be activated"));
+    }
+    consent.status = ConsentStatus::Active;
+    consent.guardian_policy = Some(policy_hash);
+    consent.guardian_approvals = approvals;
+
+    let updated_hash = update_entry(consent_hash.clone(), &consent)?;
+    notify_webhooks(WebhookEventType::ConsentChanged, consent.patient_hash.clone(), updated_hash.clone());
+
+    create_link(
+        consent_hash,
+        updated_hash.clone(),
+        LinkTypes::ConsentUpdates,
+        (),
+    )?;
+    create_link(
+        consent.patient_hash.clone(),
+        updated_hash.clone(),
+        LinkTypes::PatientToConsents,
+        consent_link_tag(&consent),
+    )?;
+    let active_anchor = anchor_hash("active_consents")?;
+    create_link(
+        active_anchor,
+        updated_hash.clone(),
+        LinkTypes::ActiveConsents,
+        (),
+    )?;
+    count_active_consents(1)?;
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find activated consent"))
+}
+
+// ============================================================
+// HOUSEHOLD GROUPING
+// ============================================================
+
+/// Create a household, optionally enrolling the creator's own patient record
+#[hdk_extern]
+pub fn create_household(input: CreateHouseholdInput) -> ExternResult<Record> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let household = Household {
+        household_id: input.household_id,
+        name: input.name,
+        created_by: me.clone(),
+        created_at: now,
+    };
+
+    let household_hash = create_entry(&EntryTypes::Household(household))?;
+    let record = get(household_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find household"))?;
+
+    let agent_anchor = hash_entry(&Anchor(format!("households:{:?}", me)))?;
+    create_link(
+        agent_anchor,
+        household_hash.clone(),
+        LinkTypes::AgentToHouseholds,
+        (),
+    )?;
+
+    if let Some(founder) = input.founding_member {
+        let invitation = HouseholdInvitation {
+            household_hash: household_hash.clone(),
+            patient_hash: founder.patient_hash,
+            role_label: founder.role_label,
+            invited_by: me,
+            invited_at: now,
+            message: None,
+            status: InvitationStatus::Accepted,
+            responded_at: Some(now),
+        };
+        let invitation_hash = create_entry(&EntryTypes::HouseholdInvitation(invitation.clone()))?;
+        link_household_member(&invitation, invitation_hash)?;
+    }
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateHouseholdInput {
+    pub household_id: String,
+    pub name: String,
+    pub founding_member: Option<FoundingMember>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FoundingMember {
+    pub patient_hash: ActionHash,
+    pub role_label: HouseholdRole,
+}
+
+/// Get households created by the current agent
+#[hdk_extern]
+pub fn get_my_households(_: ()) -> ExternResult<Vec<Record>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let agent_anchor = hash_entry(&Anchor(format!("households:{:?}", me)))?;
+    linked_records(agent_anchor, LinkTypes::AgentToHouseholds)
+}
+
+/// Invite a patient to join a household (household creator only)
+#[hdk_extern]
+pub fn invite_household_member(input: InviteHouseholdMemberInput) -> ExternResult<Record> {
+    let invitation = HouseholdInvitation {
+        household_hash: input.household_hash,
+        patient_hash: input.patient_hash.clone(),
+        role_label: input.role_label,
+        invited_by: agent_info()?.agent_initial_pubkey,
+        invited_at: sys_time()?,
+        message: input.message,
+        status: InvitationStatus::Pending,
+        responded_at: None,
+    };
+
+    let invitation_hash = create_entry(&EntryTypes::HouseholdInvitation(invitation))?;
+    let record = get(invitation_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find household invitation"))?;
+
+    create_link(
+        input.patient_hash,
+        invitation_hash,
+        LinkTypes::PatientToHouseholdInvitations,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct InviteHouseholdMemberInput {
+    pub household_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub role_label: HouseholdRole,
+    pub message: Option<String>,
+}
+
+/// Get pending household invitations for a patient
+#[hdk_extern]
+pub fn get_household_invitations(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let invitations = linked_records(patient_hash, LinkTypes::PatientToHouseholdInvitations)?;
+
+    Ok(invitations
+        .into_iter()
+        .filter(|record| {
+            record
+                .entry()
+                .to_app_option::<HouseholdInvitation>()
+                .ok()
+                .flatten()
+                .map(|i| matches!(i.status, InvitationStatus::Pending))
+                .unwrap_or(false)
+        })
+        .collect())
+}
+
+/// Accept or decline a household invitation (patient only)
+#[hdk_extern]
+pub fn respond_to_household_invitation(input: RespondHouseholdInvitationInput) -> ExternResult<Record> {
+    let record = get(input.invitation_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Household invitation not found"))?;
+
+    let mut invitation: HouseholdInvitation = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid household invitation"))?;
+
+    if !matches!(invitation.status, InvitationStatus::Pending) {
+        return Err(conflict("Household invitation has already been answered"));
+    }
+
+    invitation.status = if input.accept {
+        InvitationStatus::Accepted
+    } else {
+        InvitationStatus::Declined
+    };
+    invitation.responded_at = Some(sys_time()?);
+
+    let updated_hash = update_entry(input.invitation_hash, &invitation)?;
+    if input.accept {
+        link_household_member(&invitation, updated_hash.clone())?;
+    }
+
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated household invitation"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RespondHouseholdInvitationInput {
+    pub invitation_hash: ActionHash,
+    pub accept: bool,
+}
+
+/// Get the members of a household with their role labels
+#[hdk_extern]
+pub fn get_household_members(household_hash: ActionHash) -> ExternResult<Vec<HouseholdMemberInfo>> {
+    let mut members: Vec<HouseholdMemberInfo> = Vec::new();
+    for record in linked_records(household_hash, LinkTypes::HouseholdToMembers)? {
+        if let Some(invitation) = record.entry().to_app_option::<HouseholdInvitation>().ok().flatten() {
+            if matches!(invitation.status, InvitationStatus::Accepted)
+                && !members.iter().any(|m| m.patient_hash == invitation.patient_hash)
+            {
+                members.push(HouseholdMemberInfo {
+                    patient_hash: invitation.patient_hash,
+                    role_label: invitation.role_label,
+                    joined_at: invitation.responded_at.unwrap_or(invitation.invited_at),
+                });
+            }
+        }
+    }
+
+    Ok(members)
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct HouseholdMemberInfo {
+    pub patient_hash: ActionHash,
+    pub role_label: HouseholdRole,
+    pub joined_at: Timestamp,
+}
+
+/// Combined household view for the caller
+///
+/// Each member section is only filled in when the caller is that patient
+/// or holds an active delegation covering it; otherwise it stays `None`.
+#[hdk_extern]
+pub fn get_household_overview(input: HouseholdOverviewInput) -> ExternResult<HouseholdOverview> {
+    let household_record = get(input.household_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Household not found"))?;
+    let household: Household = household_record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid household"))?;
+
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let window_micros = i64::from(input.window_days.unwrap_or(30)) * 24 * 60 * 60 * 1_000_000;
+    let horizon = Timestamp::from_micros(now.as_micros() + window_micros);
+
+    let mut members = Vec::new();
+    for member in get_household_members(input.household_hash.clone())? {
+        let is_self = get(member.patient_hash.clone(), GetOptions::default())?
+            .map(|record| record.action().author() == &me)
+            .unwrap_or(false);
+
+        let delegated = |permission: DelegationPermission, data_category: DataCategory| -> ExternResult<bool> {
+            if is_self {
+                return Ok(true);
+            }
+            Ok(check_delegation_authorization(DelegationAuthInput {
+                patient_hash: member.patient_hash.clone(),
+                delegate: me.clone(),
+                permission,
+                data_category,
+            })?
+            .authorized)
+        };
+
+        let can_see_notifications = delegated(DelegationPermission::ReceiveNotifications, DataCategory::Demographics)?;
+        let can_see_medications = delegated(DelegationPermission::ManageMedications, DataCategory::Medications)?;
+
+        let unread_notifications = if can_see_notifications {
+            Some(get_unread_notification_count(member.patient_hash.clone())?)
+        } else {
+            None
+        };
+
+        let expiring_consents = if can_see_notifications {
+            let mut expiring = Vec::new();
+            for record in get_active_consents(member.patient_hash.clone())? {
+                if let Some(consent) = record.entry().to_app_option::<Consent>().ok().flatten() {
+                    if let Some(expires_at) = consent.expires_at {
+                        if expires_at >= now && expires_at <= horizon {
+                            expiring.push(ExpiringConsentSummary {
+                                consent_hash: record.action_address().clone(),
+                                grantee: consent.grantee,
+                                purpose: consent.purpose,
+                                expires_at,
+                            });
+                        }
+                    }
+                }
+            }
+            Some(expiring)
+        } else {
+            None
+        };
+
+        let upcoming_reminders = if can_see_medications {
+            Some(medication_reminders(&member.patient_hash, now, horizon))
+        } else {
+            None
+        };
+
+        members.push(HouseholdMemberOverview {
+            patient_hash: member.patient_hash,
+            role_label: member.role_label,
+            access: if is_self {
+                HouseholdAccess::Self_
+            } else if can_see_notifications || can_see_medications {
+                HouseholdAccess::Delegated
+            } else {
+                HouseholdAccess::NoAccess
+            },
+            upcoming_reminders,
+            unread_notifications,
+            expiring_consents,
+        });
+    }
+
+    Ok(HouseholdOverview {
+        household_hash: input.household_hash,
+        name: household.name,
+        generated_at: now,
+        members,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HouseholdOverviewInput {
+    pub household_hash: ActionHash,
+    /// Look-ahead window for reminders and expiring consents (default 30 days)
+    pub window_days: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HouseholdOverview {
+    pub household_hash: ActionHash,
+    pub name: String,
+    pub generated_at: Timestamp,
+    pub members: Vec<HouseholdMemberOverview>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HouseholdMemberOverview {
+    pub patient_hash: ActionHash,
+    pub role_label: HouseholdRole,
+    pub access: HouseholdAccess,
+    pub upcoming_reminders: Option<Vec<HouseholdReminder>>,
+    pub unread_notifications: Option<u32>,
+    pub expiring_consents: Option<Vec<ExpiringConsentSummary>>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum HouseholdAccess {
+    /// The caller is this member
+    Self_,
+    /// The caller holds a delegation for this member
+    Delegated,
+    /// Membership only; no per-member data is shown
+    NoAccess,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct HouseholdReminder {
+    pub source_hash: ActionHash,
+    pub title: String,
+    pub due_at: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExpiringConsentSummary {
+    pub consent_hash: ActionHash,
+    pub grantee: ConsentGrantee,
+    pub purpose: ConsentPurpose,
+    pub expires_at: Timestamp,
+}
+
+fn link_household_member(invitation: &HouseholdInvitation, invitation_hash: ActionHash) -> ExternResult<()> {
+    create_link(
+        invitation.household_hash.clone(),
+        invitation_hash,
+        LinkTypes::HouseholdToMembers,
+        (),
+    )?;
+    create_link(
+        invitation.patient_hash.clone(),
+        invitation.household_hash.clone(),
+        LinkTypes::PatientToHouseholds,
+        (),
+    )?;
+    Ok(())
+}
+
+/// Subset of the prescriptions zome's `Prescription` entry needed for
+/// reminders, decoded locally to avoid linking prescriptions_integrity
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+struct PrescriptionReminderFields {
+    medication_name: String,
+    refills_remaining: u32,
+    expiration_date: Timestamp,
+}
+
+/// Refill/renewal reminders from active prescriptions (best effort: the
+/// prescriptions zome enforces its own consent checks)
+fn medication_reminders(patient_hash: &ActionHash, now: Timestamp, horizon: Timestamp) -> Vec<HouseholdReminder> {
+    let mut records: Vec<Record> = Vec::new();
+    let mut continuation: Option<String> = None;
+    loop {
+        let input = serde_json::json!({
+            "patient_hash": patient_hash,
+            "is_emergency": false,
+            "emergency_reason": null,
+            "page": { "continuation": continuation, "max_bytes": null },
+        });
+        let page: BudgetedPage<Record> = match call(
+            CallTargetCell::Local,
+            "prescriptions",
+            "get_active_prescriptions".into(),
+            None,
+            &input,
+        ) {
+            Ok(ZomeCallResponse::Ok(extern_io)) => match extern_io.decode() {
+                Ok(page) => page,
+                Err(_) => break,
+            },
+            _ => break,
+        };
+        records.extend(page.items);
+        match page.continuation {
+            Some(next) => continuation = Some(next),
+            None => break,
+        }
+    }
+
+    let mut reminders = Vec::new();
+    for record in records {
+        if let Some(rx) = record.entry().to_app_option::<PrescriptionReminderFields>().ok().flatten() {
+            if rx.expiration_date >= now && rx.expiration_date <= horizon {
+                let title = if rx.refills_remaining == 0 {
+                    format!("Renew prescription: {}", rx.medication_name)
+                } else {
+                    format!("Refill before expiry: {}", rx.medication_name)
+                };
+                reminders.push(HouseholdReminder {
+                    source_hash: record.action_address().clone(),
+                    title,
+                    due_at: rx.expiration_date,
+                });
+            }
+        }
+    }
+    reminders.sort_by_key(|r| r.due_at);
+    reminders
+}
+
+// ============================================================
+// SECURITY EVENT EXPORT (SIEM)
+// ============================================================
+
+const SIEM_VENDOR: &str = "Mycelix";
+const SIEM_PRODUCT: &str = "mycelix-health";
+const SIEM_VERSION: &str = "0.1.0";
+
+/// Register an agent as a security auditor (admin only)
+#[hdk_extern]
+pub fn register_auditor(agent: AgentPubKey) -> ExternResult<ActionHash> {
+    require_admin_authorization()?;
+    let proof = my_admin_proof(&admin_links()?)?;
+
+    let auditors_anchor = anchor_hash("system_auditors")?;
+    create_link(
+        auditors_anchor,
+        agent,
+        LinkTypes::SystemAuditors,
+        admin_link_tag(proof.as_ref()),
+    )
+}
+
+/// Check whether an agent holds the auditor role, either registered here
+/// by a current admin or through an active role assignment
+#[hdk_extern]
+pub fn is_auditor(agent: AgentPubKey) -> ExternResult<bool> {
+    let auditors_anchor = anchor_hash("system_auditors")?;
+    let links = get_links(
+        LinkQuery::try_new(auditors_anchor, LinkTypes::SystemAuditors)?,
+        GetStrategy::default()
+    )?;
+
+    let registrations: Vec<Link> = links
+        .into_iter()
+        .filter(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&agent))
+        .collect();
+    if !registrations.is_empty() {
+        let admins = admin_agents(&admin_links()?);
+        if registrations.iter().any(|link| admins.contains(&link.author)) {
+            return Ok(true);
+        }
+    }
+    has_role(RoleCheck { agent, role: Role::Auditor })
+}
+
+/// Raise a security alert for review by auditors
+#[hdk_extern]
+pub fn raise_security_alert(alert: SecurityAlert) -> ExternResult<Record> {
+    let alert_hash = create_entry(&EntryTypes::SecurityAlert(alert))?;
+    let record = get(alert_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find security alert"))?;
+
+    let alerts_anchor = anchor_hash("security_alerts")?;
+    create_link(
+        alerts_anchor,
+        alert_hash,
+        LinkTypes::SecurityAlerts,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+/// Export denied accesses, emergency overrides and security alerts as
+/// SIEM-ready lines (CEF or structured syslog JSON), oldest first
+#[hdk_extern]
+pub fn export_security_events(input: SecurityEventExportInput) -> ExternResult<CursorPage<String>> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_auditor(caller)? {
+        return Err(unauthorized("Security event export requires the auditor role"));
+    }
+
+    let mut events = collect_security_events(input.period_start, input.period_end)?;
+    events.retain(|e| e.occurred_at >= input.period_start && e.occurred_at <= input.period_end);
+    let page = cursor_page(events, &input.page.unwrap_or_default(), |event| {
+        PaginationCursor::new(event.occurred_at, event.event_id.as_bytes())
+    })?;
+
+    Ok(CursorPage {
+        items: page
+            .items
+            .iter()
+            .map(|event| match input.format {
+                SiemFormat::Cef => event.to_cef(),
+                SiemFormat::SyslogJson => event.to_syslog_json(),
+            })
+            .collect(),
+        next_cursor: page.next_cursor,
+        has_more: page.has_more,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SecurityEventExportInput {
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub format: SiemFormat,
+    pub page: Option<CursorInput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq)]
+pub enum SiemFormat {
+    /// ArcSight Common Event Format
+    Cef,
+    /// One JSON object per line for syslog/JSON collectors
+    SyslogJson,
+}
+
+/// Normalised security event prior to rendering
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SecurityEvent {
+    pub event_id: String,
+    /// Stable signature ID: access_denied, emergency_override or security_alert
+    pub event_type: String,
+    /// CEF severity scale (0-10)
+    pub severity: u8,
+    pub occurred_at: Timestamp,
+    pub actor: Option<AgentPubKey>,
+    pub patient_hash: Option<ActionHash>,
+    pub data_categories: Vec<String>,
+    pub message: String,
+}
+
+impl SecurityEvent {
+    fn to_cef(&self) -> String {
+        let mut extension = vec![
+            format!("rt={}", self.occurred_at.as_millis()),
+            format!("externalId={}", cef_escape_extension(&self.event_id)),
+        ];
+        if let Some(actor) = &self.actor {
+            extension.push(format!("suser={}", actor));
+        }
+        if let Some(patient) = &self.patient_hash {
+            extension.push(format!("duid={}", patient));
+        }
+        if !self.data_categories.is_empty() {
+            extension.push("cs1Label=dataCategories".to_string());
+            extension.push(format!("cs1={}", cef_escape_extension(&self.data_categories.join(","))));
+        }
+        extension.push(format!("msg={}", cef_escape_extension(&self.message)));
+
+        format!(
+            "CEF:0|{}|{}|{}|{}|{}|{}|{}",
+            SIEM_VENDOR,
+            SIEM_PRODUCT,
+            SIEM_VERSION,
+            cef_escape_header(&self.event_type),
+            cef_escape_header(&cef_event_name(&self.event_type)),
+            self.severity,
+            extension.join(" ")
+        )
+    }
+
+    fn to_syslog_json(&self) -> String {
+        serde_json::json!({
+            "timestamp_ms": self.occurred_at.as_millis(),
+            "vendor": SIEM_VENDOR,
+            "product": SIEM_PRODUCT,
+            "product_version": SIEM_VERSION,
+            "event_id": self.event_id,
+            "event_type": self.event_type,
+            "severity": self.severity,
+            "actor": self.actor.as_ref().map(|a| a.to_string()),
+            "patient_hash": self.patient_hash.as_ref().map(|h| h.to_string()),
+            "data_categories": self.data_categories,
+            "message": self.message,
+        })
+        .to_string()
+    }
+}
+
+fn cef_event_name(event_type: &str) -> String {
+    match event_type {
+        "access_denied" => "Access denied".to_string(),
+        "emergency_override" => "Emergency access override".to_string(),
+        "security_alert" => "Security alert".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn cef_escape_header(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('|', "\\|").replace(['\n', '\r'], " ")
+}
+
+fn cef_escape_extension(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('=', "\\=")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+/// Anchor of denied access logs; since day buckets were introduced the
+/// undated anchor only holds older attempts
+const DENIED_ACCESS_ANCHOR: &str = "denied_access_attempts";
+
+fn collect_security_events(period_start: Timestamp, period_end: Timestamp) -> ExternResult<Vec<SecurityEvent>> {
+    let mut events = Vec::new();
+
+    // Denied access attempts
+    let mut denied = linked_records(anchor_hash(DENIED_ACCESS_ANCHOR)?, LinkTypes::PatientToAccessLogs)?;
+    for link in links_in_period(DENIED_ACCESS_ANCHOR, AnchorPeriod::Day, LinkTypes::PatientToAccessLogs, period_start, period_end)? {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                denied.push(record);
+            }
+        }
+    }
+    for record in denied {
+        if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
+            events.push(SecurityEvent {
+                event_id: log.log_id,
+                event_type: "access_denied".to_string(),
+                severity: 5,
+                occurred_at: log.accessed_at,
+                actor: Some(log.accessor),
+                patient_hash: Some(log.patient_hash),
+                data_categories: log.data_categories_accessed.iter().map(|c| format!("{:?}", c)).collect(),
+                message: log.access_reason,
+            });
+        }
+    }
+
+    // Emergency overrides (break-glass records and override access logs)
+    let emergency_anchor = anchor_hash("emergency_override_events")?;
+    for record in linked_records(emergency_anchor, LinkTypes::EmergencyOverrideEvents)? {
+        if let Some(emergency) = record.entry().to_app_option::<EmergencyAccess>().ok().flatten() {
+            events.push(SecurityEvent {
+                event_id: emergency.emergency_id,
+                event_type: "emergency_override".to_string(),
+                severity: 7,
+                occurred_at: emergency.accessed_at,
+                actor: Some(emergency.accessor),
+                patient_hash: Some(emergency.patient_hash),
+                data_categories: emergency.data_accessed.iter().map(|c| format!("{:?}", c)).collect(),
+                message: emergency.reason,
+            });
+        } else if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
+            events.push(SecurityEvent {
+                event_id: log.log_id,
+                event_type: "emergency_override".to_string(),
+                severity: 7,
+                occurred_at: log.accessed_at,
+                actor: Some(log.accessor),
+                patient_hash: Some(log.patient_hash),
+                data_categories: log.data_categories_accessed.iter().map(|c| format!("{:?}", c)).collect(),
+                message: log.override_reason.unwrap_or(log.access_reason),
+            });
+        }
+    }
+
+    // Security alerts
+    let alerts_anchor = anchor_hash("security_alerts")?;
+    for record in linked_records(alerts_anchor, LinkTypes::SecurityAlerts)? {
+        if let Some(alert) = record.entry().to_app_option::<SecurityAlert>().ok().flatten() {
+            let severity = match alert.severity {
+                AlertSeverity::Low => 3,
+                AlertSeverity::Medium => 5,
+                AlertSeverity::High => 8,
+                AlertSeverity::Critical => 10,
+            };
+            events.push(SecurityEvent {
+                event_id: alert.alert_id,
+                event_type: "security_alert".to_string(),
+                severity,
+                occurred_at: alert.raised_at,
+                actor: alert.subject_agent,
+                patient_hash: alert.patient_hash,
+                data_categories: Vec::new(),
+                message: format!("{:?}: {}", alert.alert_type, alert.description),
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+/// Fetch the records targeted by links of one type from a base
+fn linked_records(base: impl Into<AnyLinkableHash>, link_type: LinkTypes) -> ExternResult<Vec<Record>> {
+    let links = get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::default())?;
+
+    let mut records = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                records.push(record);
+            }
+        }
+    }
+
+    Ok(records)
+}
+
+// ==================== ZK PROOF AUDIT LOGGING ====================
+// Integration with zkhealth zome for HIPAA-compliant audit trails
+
+/// Input from zkhealth zome for proof generation audit
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZkProofAuditLog {
+    pub log_id: String,
+    pub patient_hash: ActionHash,
+    pub proof_id: String,
+    pub proof_type: String,
+    pub data_categories_used: Vec<String>,
+    pub verifier_hint: Option<String>,
+    pub generated_at: i64,
+    pub purpose: String,
+}
+
+/// Input from zkhealth zome for verification audit
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ZkVerificationAuditLog {
+    pub log_id: String,
+    pub patient_hash: ActionHash,
+    pub proof_id: String,
+    pub verifier: AgentPubKey,
+    pub verification_result: bool,
+    pub verified_at: i64,
+}
+
+/// Log ZK proof generation event (called by zkhealth zome)
+/// Creates an audit record of data accessed during proof generation
+#[hdk_extern]
+pub fn log_zk_proof_generation(input: ZkProofAuditLog) -> ExternResult<Record> {
+    // Convert string categories to DataCategory enum
+    let data_categories: Vec<DataCategory> = input.data_categories_used
+        .iter()
+        .map(|cat| string_to_data_category(cat))
+        .collect();
+
+    // Create audit log entry
+    let log = DataAccessLog {
+        log_id: input.log_id,
+        patient_hash: input.patient_hash.clone(),
+        accessor: agent_info()?.agent_initial_pubkey, // Self-access for proof generation
+        access_type: DataPermission::Read, // Proof generation reads data
+        data_categories_accessed: data_categories,
+        consent_hash: None, // Self-access doesn't require consent
+        access_reason: format!("ZK Proof Generation: {} (Proof ID: {})", input.proof_type, input.proof_id),
+        accessed_at: Timestamp::from_micros(input.generated_at),
+        access_location: Some("zkhealth-zome".to_string()),
+        emergency_override: false,
+        override_reason: None,
+        access_path: None,
+        accessor_assurance: None,
+        previous_log: None,
+        chain_sequence: None,
+        access_context: None,
+    };
+
+    let log_hash = append_access_log(log)?;
+    get(log_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find audit log"))
+}
+
+/// Log ZK proof verification event (called by zkhealth zome)
+/// Creates an audit record of a third party verifying a patient's proof
+#[hdk_extern]
+pub fn log_zk_proof_verification(input: ZkVerificationAuditLog) -> ExternResult<Record> {
+    // Create audit log entry - verification doesn't access categories, just verifies
+    let log = DataAccessLog {
+        log_id: input.log_id,
+        patient_hash: input.patient_hash.clone(),
+        accessor: input.verifier.clone(),
+        access_type: DataPermission::Read, // Verification is a form of read
+        data_categories_accessed: vec![], // No actual data accessed during verification
+        consent_hash: None, // ZK proofs don't require consent to verify
+        access_reason: format!(
+            "ZK Proof Verification: {} (Result: {})",
+            input.proof_id,
+            if input.verification_result { "Verified" } else { "Failed" }
+        ),
+        accessed_at: Timestamp::from_micros(input.verified_at),
+        access_location: Some("zkhealth-verification".to_string()),
+        emergency_override: false,
+        override_reason: None,
+        access_path: None,
+        accessor_assurance: None,
+        previous_log: None,
+        chain_sequence: None,
+        access_context: None,
+    };
+
+    let log_hash = append_access_log(log)?;
+    get(log_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find audit log"))
+}
+
+/// Convert string data category to DataCategory enum
+fn string_to_data_category(cat: &str) -> DataCategory {
+    match cat {
+        "VitalSigns" => DataCategory::VitalSigns,
+        "Allergies" => DataCategory::Allergies,
+        "Medications" => DataCategory::Medications,
+        "Diagnoses" | "Conditions" => DataCategory::Diagnoses,
+        "LabResults" | "Labs" => DataCategory::LabResults,
+        "Immunizations" => DataCategory::Immunizations,
+        "Procedures" => DataCategory::Procedures,
+        "Imaging" | "ImagingStudies" => DataCategory::ImagingStudies,
+        "MentalHealth" => DataCategory::MentalHealth,
+        "Demographics" => DataCategory::Demographics,
+        "SubstanceAbuse" => DataCategory::SubstanceAbuse,
+        "SexualHealth" => DataCategory::SexualHealth,
+        "GeneticData" => DataCategory::GeneticData,
+        "FinancialData" | "Insurance" => DataCategory::FinancialData,
+        "All" | _ => DataCategory::All, // Default unknown categories to All for audit completeness
+    }
+}
+
+/// Get all ZK proof audit logs for a patient
+#[hdk_extern]
+pub fn get_zk_proof_audit_logs(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToAccessLogs)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut zk_logs = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                // Filter for ZK proof logs
+                if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
+                    if log.access_reason.starts_with("ZK Proof") {
+                        zk_logs.push(record);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(zk_logs)
+}
+
+// ============================================================
+// AUDIT COVERAGE
+// ============================================================
+
+const AUTHORIZATION_RECEIPTS_ANCHOR: &str = "authorization_receipts";
+
+/// Seconds after an authorization check within which its access log must
+/// appear, unless the report asks for another window
+const DEFAULT_LOG_MATCH_WINDOW_SECONDS: i64 = 300;
+
+/// An allowed read or export, stored in the tag of an `AuthorizationReceipts` link
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuthorizationReceipt {
+    pub accessor: AgentPubKey,
+    pub data_category: DataCategory,
+    pub permission: DataPermission,
+    pub consent_hash: Option<ActionHash>,
+    pub emergency_override: bool,
+    pub access_path: Option<String>,
+    pub checked_at: Timestamp,
+    #[serde(default)]
+    pub purpose_of_use: Option<PurposeOfUse>,
+}
+
+fn record_authorization_receipt(
+    input: &AuthorizationCheckInput,
+    consent_hash: Option<ActionHash>,
+    emergency_override: bool,
+) -> ExternResult<()> {
+    let receipt = AuthorizationReceipt {
+        accessor: input.requestor.clone(),
+        data_category: input.data_category.clone(),
+        permission: input.permission.clone(),
+        consent_hash,
+        emergency_override,
+        access_path: input.access_path.clone(),
+        checked_at: sys_time()?,
+        purpose_of_use: input.access_context.as_ref().map(|context| context.purpose_of_use.clone()),
+    };
+    create_link(
+        anchor_hash(AUTHORIZATION_RECEIPTS_ANCHOR)?,
+        input.patient_hash.clone(),
+        LinkTypes::AuthorizationReceipts,
+        LinkTag::new(ExternIO::encode(&receipt).map_err(|e| wasm_error!(e))?.0),
+    )?;
+    Ok(())
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AuditCoverageInput {
+    pub since: Timestamp,
+    pub match_window_seconds: Option<i64>,
+}
+
+/// An authorized read with no access log in the match window
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct UnloggedAccess {
+    pub patient_hash: ActionHash,
+    pub receipt: AuthorizationReceipt,
+}
+
+/// Receipts and unlogged reads for one getter; `access_path` is `None` for
+/// getters that still call `require_authorization` directly
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AccessPathCoverage {
+    pub access_path: Option<String>,
+    pub authorized_reads: u32,
+    pub unlogged_reads: u32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AuditCoverageReport {
+    pub since: Timestamp,
+    pub authorized_reads: u32,
+    pub logged_reads: u32,
+    pub unlogged: Vec<UnloggedAccess>,
+    /// Getters with unlogged reads first, for developers to fix
+    pub paths: Vec<AccessPathCoverage>,
+}
+
+fn patient_access_logs(patient_hash: &ActionHash) -> ExternResult<Vec<DataAccessLog>> {
+    Ok(get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToAccessLogs)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .filter_map(|hash| get(hash, GetOptions::default()).ok().flatten())
+    .filter_map(|record| record.entry().to_app_option::<DataAccessLog>().ok().flatten())
+    .collect())
+}
+
+fn log_covers_receipt(log: &DataAccessLog, receipt: &AuthorizationReceipt, window_micros: i64) -> bool {
+    let elapsed = log.accessed_at.as_micros() - receipt.checked_at.as_micros();
+    log.accessor == receipt.accessor
+        && log.access_type == receipt.permission
+        && (0..=window_micros).contains(&elapsed)
+        && log
+            .data_categories_accessed
+            .iter()
+            .any(|c| c == &receipt.data_category || matches!(c, DataCategory::All))
+}
+
+/// Compare authorization receipts since `since` against access logs and
+/// report reads that were authorized but never logged (auditors only)
+///
+/// Patients reading their own data are authorized without consulting this
+/// zome, so those reads leave no receipt and are not covered.
+#[hdk_extern]
+pub fn get_audit_coverage_report(input: AuditCoverageInput) -> ExternResult<AuditCoverageReport> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_auditor(caller)? {
+        return Err(unauthorized("Audit coverage reports require the auditor role"));
+    }
+    let window_micros = input.match_window_seconds.unwrap_or(DEFAULT_LOG_MATCH_WINDOW_SECONDS) * 1_000_000;
+
+    let receipts: Vec<(ActionHash, AuthorizationReceipt)> = get_links(
+        LinkQuery::try_new(anchor_hash(AUTHORIZATION_RECEIPTS_ANCHOR)?, LinkTypes::AuthorizationReceipts)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter(|link| link.timestamp >= input.since)
+    .filter_map(|link| {
+        let receipt: AuthorizationReceipt = ExternIO(link.tag.0).decode().ok()?;
+        Some((link.target.into_action_hash()?, receipt))
+    })
+    .collect();
+
+    let mut logs_by_patient: Vec<(ActionHash, Vec<DataAccessLog>)> = Vec::new();
+    let mut report = AuditCoverageReport {
+        since: input.since,
+        authorized_reads: 0,
+        logged_reads: 0,
+        unlogged: Vec::new(),
+        paths: Vec::new(),
+    };
+    for (patient_hash, receipt) in receipts {
+        let logs = match logs_by_patient.iter().position(|(p, _)| p == &patient_hash) {
+            Some(index) => &logs_by_patient[index].1,
+            None => {
+                let logs = patient_access_logs(&patient_hash)?;
+                logs_by_patient.push((patient_hash.clone(), logs));
+                &logs_by_patient[logs_by_patient.len() - 1].1
+            }
+        };
+        let logged = logs.iter().any(|log| log_covers_receipt(log, &receipt, window_micros));
+
+        let index = match report.paths.iter().position(|p| p.access_path == receipt.access_path) {
+            Some(index) => index,
+            None => {
+                report.paths.push(AccessPathCoverage {
+                    access_path: receipt.access_path.clone(),
+                    authorized_reads: 0,
+                    unlogged_reads: 0,
+                });
+                report.paths.len() - 1
+            }
+        };
+        report.paths[index].authorized_reads += 1;
+        report.authorized_reads += 1;
+        if logged {
+            report.logged_reads += 1;
+        } else {
+            report.paths[index].unlogged_reads += 1;
+            report.unlogged.push(UnloggedAccess { patient_hash, receipt });
+        }
+    }
+    report.paths.sort_by(|a, b| b.unlogged_reads.cmp(&a.unlogged_reads).then_with(|| a.access_path.cmp(&b.access_path)));
+
+    Ok(report)
+}
+
+// ============================================================
+// SERVICE AGENT REGISTRATIONS
+// ============================================================
+
+const SERVICE_REGISTRY_ANCHOR: &str = "service_agent_registry";
+
+/// Latest version of a service registration (revocation is the only update)
+fn latest_service_registration(
+    registration_hash: &ActionHash,
+) -> ExternResult<(ActionHash, ServiceAgentRegistration)> {
+    let latest = get_links(
+        LinkQuery::try_new(registration_hash.clone(), LinkTypes::ServiceRegistrationUpdates)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash())
+    .unwrap_or_else(|| registration_hash.clone());
+    let registration = get(latest.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<ServiceAgentRegistration>().ok().flatten())
+        .ok_or(not_found("Service registration not found"))?;
+    Ok((latest, registration))
+}
+
+/// The most recent registration for an agent, revoked or not; `None` for
+/// agents that were never registered as a service
+fn service_registration_for(agent: &AgentPubKey) -> ExternResult<Option<ServiceAgentRegistration>> {
+    let newest = get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToServiceRegistrations)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash());
+    match newest {
+        Some(hash) => Ok(Some(latest_service_registration(&hash)?.1)),
+        None => Ok(None),
+    }
+}
+
+fn create_service_registration(registration: &ServiceAgentRegistration) -> ExternResult<Record> {
+    let hash = create_entry(&EntryTypes::ServiceAgentRegistration(registration.clone()))?;
+    create_link(
+        registration.agent.clone(),
+        hash.clone(),
+        LinkTypes::AgentToServiceRegistrations,
+        (),
+    )?;
+    create_link(
+        anchor_hash(SERVICE_REGISTRY_ANCHOR)?,
+        hash.clone(),
+        LinkTypes::ServiceRegistrations,
+        (),
+    )?;
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find service registration"))
+}
+
+fn revoke_service_registration(registration_hash: &ActionHash, now: Timestamp) -> ExternResult<Record> {
+    let (latest_hash, registration) = latest_service_registration(registration_hash)?;
+    if registration.revoked_at.is_some() {
+        return Err(conflict("Service registration is already revoked"));
+    }
+    let revoked = ServiceAgentRegistration {
+        revoked_at: Some(now),
+        admin_link: my_admin_proof(&admin_links()?)?,
+        ..registration
+    };
+    let updated_hash = update_entry(latest_hash, &revoked)?;
+    create_link(
+        registration_hash.clone(),
+        updated_hash.clone(),
+        LinkTypes::ServiceRegistrationUpdates,
+        (),
+    )?;
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find revoked registration"))
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RegisterServiceAgentInput {
+    pub service_name: String,
+    pub agent: AgentPubKey,
+    pub allowed: Vec<ServiceGrant>,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Register a backend service agent with a category/permission allowlist (admin only)
+#[hdk_extern]
+pub fn register_service_agent(input: RegisterServiceAgentInput) -> ExternResult<Record> {
+    require_admin_authorization()?;
+    if service_registration_for(&input.agent)?.is_some() {
+        return Err(conflict("Agent already has a service registration; rotate it instead"));
+    }
+
+    let now = sys_time()?;
+    create_service_registration(&ServiceAgentRegistration {
+        registration_id: format!("{}-{}", input.service_name, now.as_micros()),
+        service_name: input.service_name,
+        agent: input.agent,
+        allowed: input.allowed,
+        registered_by: agent_info()?.agent_initial_pubkey,
+        registered_at: now,
+        expires_at: input.expires_at,
+        rotated_from: None,
+        revoked_at: None,
+        admin_link: my_admin_proof(&admin_links()?)?,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RotateServiceAgentInput {
+    pub registration_hash: ActionHash,
+    /// Key the service runs under from now on; may be the same agent
+    pub new_agent: AgentPubKey,
+    /// Replaces the allowlist when given
+    pub allowed: Option<Vec<ServiceGrant>>,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Replace a service registration, revoking the old one (admin only)
+///
+/// Used to move a service to a new agent key, renew its expiry or change
+/// its allowlist.
+#[hdk_extern]
+pub fn rotate_service_agent(input: RotateServiceAgentInput) -> ExternResult<Record> {
+    require_admin_authorization()?;
+    let (_, current) = latest_service_registration(&input.registration_hash)?;
+    if current.revoked_at.is_some() {
+        return Err(conflict("Revoked registrations cannot be rotated; register the service again"));
+    }
+
+    if input.new_agent != current.agent && service_registration_for(&input.new_agent)?.is_some() {
+        return Err(conflict("New agent already has a service registration"));
+    }
+
+    let now = sys_time()?;
+    revoke_service_registration(&input.registration_hash, now)?;
+    create_service_registration(&ServiceAgentRegistration {
+        registration_id: format!("{}-{}", current.service_name, now.as_micros()),
+        service_name: current.service_name,
+        agent: input.new_agent,
+        allowed: input.allowed.unwrap_or(current.allowed),
+        registered_by: agent_info()?.agent_initial_pubkey,
+        registered_at: now,
+        expires_at: input.expires_at,
+        rotated_from: Some(input.registration_hash),
+        revoked_at: None,
+        admin_link: my_admin_proof(&admin_links()?)?,
+    })
+}
+
+/// Revoke a service registration (admin only)
+///
+/// The agent stays known as a service, so it is refused everything rather
+/// than falling back to plain consent checks.
+#[hdk_extern]
+pub fn revoke_service_agent(registration_hash: ActionHash) -> ExternResult<Record> {
+    require_admin_authorization()?;
+    revoke_service_registration(&registration_hash, sys_time()?)
+}
+
+/// All service registrations at their latest version (admins and auditors)
+#[hdk_extern]
+pub fn get_service_registrations(_: ()) -> ExternResult<Vec<(ActionHash, ServiceAgentRegistration)>> {
+    if !is_auditor(agent_info()?.agent_initial_pubkey)? {
+        require_admin_authorization()?;
+    }
+    get_links(
+        LinkQuery::try_new(anchor_hash(SERVICE_REGISTRY_ANCHOR)?, LinkTypes::ServiceRegistrations)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .map(|hash| Ok((hash.clone(), latest_service_registration(&hash)?.1)))
+    .collect()
+}
+
+/// The calling agent's own service registration, so a service can see its allowlist
+#[hdk_extern]
+pub fn get_my_service_registration(_: ()) -> ExternResult<Option<ServiceAgentRegistration>> {
+    service_registration_for(&agent_info()?.agent_initial_pubkey)
+}
+
+// ============================================================
+// RATE LIMITS
+// ============================================================
+
+/// Throttle tier from an agent's role entries: an active service
+/// registration makes an integration, a provider profile a provider, and
+/// everyone else gets the patient tier
+///
+/// An unreachable provider zome leaves the agent at the patient tier.
+fn throttle_tier_for(agent: &AgentPubKey) -> ExternResult<ThrottleTier> {
+    let now = sys_time()?;
+    if service_registration_for(agent)?.is_some_and(|registration| registration.is_active(now)) {
+        return Ok(ThrottleTier::Integration);
+    }
+    let attestations: IdentityAttestations = resilient_call(
+        "provider",
+        "get_identity_attestations",
+        agent,
+        CallClass::Authorization,
+    )
+    .unwrap_or_default();
+    Ok(if attestations.has_profile { ThrottleTier::Provider } else { ThrottleTier::Patient })
+}
+
+/// The calling agent's tier and how much of its window is left, counted
+/// from the access logs on its own chain
+///
+/// Every authorization checks this first, so clients can back off before
+/// their calls start failing.
+#[hdk_extern]
+pub fn get_my_rate_limit_status(_: ()) -> ExternResult<RateLimitStatus> {
+    let tier = throttle_tier_for(&agent_info()?.agent_initial_pubkey)?;
+    let logged = query(ChainQueryFilter::new().entry_type(UnitEntryTypes::DataAccessLog.try_into()?))?;
+    let times: Vec<Timestamp> = logged.iter().map(|record| record.action().timestamp()).collect();
+    Ok(RateLimitStatus::from_request_times(tier, &times, sys_time()?))
+}
+
+/// Whether an access log on the caller's chain records `operation`
+fn log_records_operation(log: &DataAccessLog, operation: SensitiveOperation) -> bool {
+    match operation {
+        SensitiveOperation::EmergencyOverride => log.emergency_override,
+        SensitiveOperation::Export => log.access_type == DataPermission::Export,
+    }
+}
+
+/// How much of a sensitive operation's sliding window the calling agent
+/// has used, counted from the access logs on its own chain
+#[hdk_extern]
+pub fn get_my_operation_rate_status(operation: SensitiveOperation) -> ExternResult<OperationRateStatus> {
+    let now = sys_time()?;
+    let window_start = now.as_micros() - operation.limits().window_seconds as i64 * 1_000_000;
+    let logged = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::DataAccessLog.try_into()?)
+            .include_entries(true),
+    )?;
+    let times: Vec<Timestamp> = logged
+        .iter()
+        .filter(|record| record.action().timestamp().as_micros() > window_start)
+        .filter(|record| {
+            record
+                .entry()
+                .to_app_option::<DataAccessLog>()
+                .ok()
+                .flatten()
+                .is_some_and(|log| log_records_operation(&log, operation))
+        })
+        .map(|record| record.action().timestamp())
+        .collect();
+    Ok(OperationRateStatus::from_times(operation, &times, now))
+}
+
+// ============================================================
+// JURISDICTION POLICIES
+// ============================================================
+
+/// Input for setting the prohibited uses of a jurisdiction
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetJurisdictionPolicyInput {
+    pub jurisdiction: String,
+    pub prohibited_uses: Vec<DataUse>,
+    pub rationale: Option<String>,
+}
+
+fn jurisdiction_anchor(jurisdiction: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("jurisdiction_policy:{}", jurisdiction))
+}
+
+/// Set the uses a jurisdiction prohibits, replacing its current policy
+/// (operators only)
+#[hdk_extern]
+pub fn set_jurisdiction_policy(input: SetJurisdictionPolicyInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller.clone())? {
+        return Err(unauthorized("Jurisdiction policies require the operator role"));
+    }
+    validate_jurisdiction(&input.jurisdiction).into_result()?;
+
+    let policy = JurisdictionPolicy {
+        jurisdiction: input.jurisdiction.clone(),
+        prohibited_uses: input.prohibited_uses,
+        rationale: input.rationale,
+        set_by: caller,
+        set_at: sys_time()?,
+        operator_link: my_operator_proof()?,
+    };
+    let hash = create_entry(&EntryTypes::JurisdictionPolicy(policy))?;
+    create_link(
+        jurisdiction_anchor(&input.jurisdiction)?,
+        hash.clone(),
+        LinkTypes::JurisdictionPolicies,
+        (),
+    )?;
+
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find jurisdiction policy"))
+}
+
+/// Current policy for a jurisdiction; `None` when nothing is prohibited
+///
+/// Policies set by agents who no longer hold the operator role are ignored.
+#[hdk_extern]
+pub fn get_jurisdiction_policy(jurisdiction: String) -> ExternResult<Option<JurisdictionPolicy>> {
+    let mut links = get_links(
+        LinkQuery::try_new(jurisdiction_anchor(&jurisdiction)?, LinkTypes::JurisdictionPolicies)?,
+        GetStrategy::default(),
+    )?;
+    if links.is_empty() {
+        return Ok(None);
+    }
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    let operators = operator_agents()?;
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let policy = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<JurisdictionPolicy>().ok().flatten());
+        if let Some(policy) = policy.filter(|policy| operators.contains(&policy.set_by)) {
+            return Ok(Some(policy));
+        }
+    }
+    Ok(None)
+}
+
+/// A patient's jurisdiction with the uses its policy prohibits
+///
+/// Subdivisions inherit their country's policy ("US-CA" is bound by "US"),
+/// and EU member states by the "EU" policy.
+#[hdk_extern]
+pub fn get_patient_residency(patient_hash: ActionHash) -> ExternResult<PatientResidency> {
+    let jurisdiction: Option<String> =
+        resilient_call("patient", "get_patient_jurisdiction", &patient_hash, CallClass::Authorization)
+            .map_err(|failure| failure.into_wasm_error("reading patient jurisdiction"))?;
+    let Some(jurisdiction) = jurisdiction else {
+        return Ok(PatientResidency::default());
+    };
+
+    let mut prohibited_uses: Vec<DataUse> = Vec::new();
+    for applicable in applicable_jurisdictions(&jurisdiction) {
+        if let Some(policy) = get_jurisdiction_policy(applicable)? {
+            for data_use in policy.prohibited_uses {
+                if !prohibited_uses.contains(&data_use) {
+                    prohibited_uses.push(data_use);
+                }
+            }
+        }
+    }
+    Ok(PatientResidency {
+        jurisdiction: Some(jurisdiction),
+        prohibited_uses,
+    })
+}
+
+/// EU member states, bound by "EU" policies as well as their own
+const EU_MEMBER_STATES: [&str; 27] = [
+    "AT", "BE", "BG", "CY", "CZ", "DE", "DK", "EE", "ES", "FI", "FR", "GR", "HR", "HU",
+    "IE", "IT", "LT", "LU", "LV", "MT", "NL", "PL", "PT", "RO", "SE", "SI", "SK",
+];
+
+/// The jurisdiction itself followed by those enclosing it
+fn applicable_jurisdictions(jurisdiction: &str) -> Vec<String> {
+    let mut applicable = vec![jurisdiction.to_string()];
+    let country = jurisdiction.split('-').next().unwrap_or(jurisdiction);
+    if country != jurisdiction {
+        applicable.push(country.to_string());
+    }
+    if EU_MEMBER_STATES.contains(&country) {
+        applicable.push("EU".to_string());
+    }
+    applicable
+}
+
+// ============================================================
+// DATA RETENTION
+// ============================================================
+
+/// Input for setting how long a jurisdiction keeps one data category
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetRetentionPolicyInput {
+    pub jurisdiction: String,
+    pub category: DataCategory,
+    pub retention_days: u32,
+    pub action: RetentionAction,
+    pub rationale: Option<String>,
+}
+
+fn retention_anchor(jurisdiction: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("retention_policy:{}", jurisdiction))
+}
+
+/// Set how long a jurisdiction keeps a category, replacing its current
+/// policy for that category (operators only); the change is published to
+/// the policy transparency log
+#[hdk_extern]
+pub fn set_retention_policy(input: SetRetentionPolicyInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller.clone())? {
+        return Err(unauthorized("Retention policies require the operator role"));
+    }
+    validate_jurisdiction(&input.jurisdiction).into_result()?;
+    if input.retention_days == 0 || input.retention_days > MAX_RETENTION_DAYS {
+        return Err(invalid_input(format!(
+            "Retention must be between 1 and {} days",
+            MAX_RETENTION_DAYS
+        )));
+    }
+    let replaces = get_retention_policies(input.jurisdiction.clone())?
+        .iter()
+        .any(|(_, policy)| policy.category == input.category);
+
+    let policy = RetentionPolicy {
+        jurisdiction: input.jurisdiction.clone(),
+        category: input.category.clone(),
+        retention_days: input.retention_days,
+        action: input.action,
+        rationale: input.rationale,
+        set_by: caller,
+        set_at: sys_time()?,
+        operator_link: my_operator_proof()?,
+    };
+    let hash = create_entry(&EntryTypes::RetentionPolicy(policy))?;
+    create_link(
+        retention_anchor(&input.jurisdiction)?,
+        hash.clone(),
+        LinkTypes::RetentionPolicies,
+        (),
+    )?;
+    append_policy_change(AppendPolicyLogInput {
+        policy_id: format!("retention:{}:{:?}", input.jurisdiction, input.category),
+        kind: PolicyKind::Retention,
+        change: if replaces { PolicyChange::Updated } else { PolicyChange::Created },
+        policy_action: hash.clone(),
+    })?;
+
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find retention policy"))
+}
+
+/// Current retention policies of a jurisdiction, one per category
+///
+/// Policies set by agents who no longer hold the operator role are ignored.
+#[hdk_extern]
+pub fn get_retention_policies(jurisdiction: String) -> ExternResult<Vec<(ActionHash, RetentionPolicy)>> {
+    let mut links = get_links(
+        LinkQuery::try_new(retention_anchor(&jurisdiction)?, LinkTypes::RetentionPolicies)?,
+        GetStrategy::default(),
+    )?;
+    if links.is_empty() {
+        return Ok(Vec::new());
+    }
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    let operators = operator_agents()?;
+
+    let mut policies: Vec<(ActionHash, RetentionPolicy)> = Vec::new();
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(policy) = get(hash.clone(), GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<RetentionPolicy>().ok().flatten())
+        else {
+            continue;
+        };
+        if !operators.contains(&policy.set_by) {
+            continue;
+        }
+        if !policies.iter().any(|(_, newer)| newer.category == policy.category) {
+            policies.push((hash, policy));
+        }
+    }
+    Ok(policies)
+}
+
+/// Retention policies in force for a patient, one per category
+///
+/// The patient's own jurisdiction is searched first, then those enclosing
+/// it, so "US-CA" overrides "US" for the same category. Sweeps prefer a
+/// category's own policy over an `All` policy wherever either was set.
+#[hdk_extern]
+pub fn get_patient_retention(patient_hash: ActionHash) -> ExternResult<Vec<ApplicableRetention>> {
+    let jurisdiction: Option<String> =
+        resilient_call("patient", "get_patient_jurisdiction", &patient_hash, CallClass::Authorization)
+            .map_err(|failure| failure.into_wasm_error("reading patient jurisdiction"))?;
+    let Some(jurisdiction) = jurisdiction else {
+        return Ok(Vec::new());
+    };
+
+    let mut applicable: Vec<ApplicableRetention> = Vec::new();
+    for enclosing in applicable_jurisdictions(&jurisdiction) {
+        for (policy_hash, policy) in get_retention_policies(enclosing)? {
+            let category = shared_category(&policy.category);
+            if applicable.iter().any(|covered| covered.category == category) {
+                continue;
+            }
+            applicable.push(ApplicableRetention {
+                policy_hash,
+                jurisdiction: policy.jurisdiction,
+                category,
+                retention_days: policy.retention_days,
+                action: policy.action,
+            });
+        }
+    }
+    Ok(applicable)
+}
+
+// ============================================================
+// CATEGORY SENSITIVITY
+// ============================================================
+
+/// Input for replacing the sensitivity matrix
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetSensitivityMatrixInput {
+    /// Rows for the categories whose handling differs from the built-in
+    pub categories: Vec<CategorySensitivity>,
+    pub rationale: Option<String>,
+}
+
+fn sensitivity_anchor() -> ExternResult<EntryHash> {
+    anchor_hash("sensitivity_matrix")
+}
+
+/// Replace how data categories are handled across the network
+/// (operators only)
+#[hdk_extern]
+pub fn set_sensitivity_matrix(input: SetSensitivityMatrixInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller.clone())? {
+        return Err(unauthorized("The sensitivity matrix requires the operator role"));
+    }
+    let matrix = SensitivityMatrix { categories: input.categories };
+    matrix.check().map_err(invalid_input)?;
+
+    let policy = SensitivityMatrixPolicy {
+        matrix,
+        rationale: input.rationale,
+        set_by: caller,
+        set_at: sys_time()?,
+        operator_link: my_operator_proof()?,
+    };
+    let hash = create_entry(&EntryTypes::SensitivityMatrixPolicy(policy))?;
+    create_link(sensitivity_anchor()?, hash.clone(), LinkTypes::SensitivityMatrixPolicies, ())?;
+
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find sensitivity policy"))
+}
+
+/// The matrix in force; built-in handling until an operator sets one
+///
+/// Encryption on import, break-glass access, access notifications and
+/// research contributions all read this. Policies set by agents who no
+/// longer hold the operator role are ignored.
+#[hdk_extern]
+pub fn get_sensitivity_matrix(_: ()) -> ExternResult<SensitivityMatrix> {
+    let mut links = get_links(
+        LinkQuery::try_new(sensitivity_anchor()?, LinkTypes::SensitivityMatrixPolicies)?,
+        GetStrategy::default(),
+    )?;
+    if links.is_empty() {
+        return Ok(SensitivityMatrix::default());
+    }
+    links.sort_by_key(|link| std::cmp::Reverse(link.timestamp));
+    let operators = operator_agents()?;
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let policy = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<SensitivityMatrixPolicy>().ok().flatten());
+        if let Some(policy) = policy.filter(|policy| operators.contains(&policy.set_by)) {
+            return Ok(policy.matrix);
+        }
+    }
+    Ok(SensitivityMatrix::default())
+}
+
+/// Every category with the handling currently in force
+#[hdk_extern]
+pub fn get_resolved_sensitivity(_: ()) -> ExternResult<Vec<CategorySensitivity>> {
+    Ok(get_sensitivity_matrix(())?.resolved())
+}
+
+/// The shared crate's name for a consent category
+fn shared_category(category: &DataCategory) -> SharedCategory {
+    match category {
+        DataCategory::Demographics => SharedCategory::Demographics,
+        DataCategory::Allergies => SharedCategory::Allergies,
+        DataCategory::Medications => SharedCategory::Medications,
+        DataCategory::Diagnoses => SharedCategory::Diagnoses,
+        DataCategory::Procedures => SharedCategory::Procedures,
+        DataCategory::LabResults => SharedCategory::LabResults,
+        DataCategory::ImagingStudies => SharedCategory::ImagingStudies,
+        DataCategory::VitalSigns => SharedCategory::VitalSigns,
+        DataCategory::Immunizations => SharedCategory::Immunizations,
+        DataCategory::MentalHealth => SharedCategory::MentalHealth,
+        DataCategory::SubstanceAbuse => SharedCategory::SubstanceAbuse,
+        DataCategory::SexualHealth => SharedCategory::SexualHealth,
+        DataCategory::GeneticData => SharedCategory::GeneticData,
+        DataCategory::FinancialData => SharedCategory::FinancialData,
+        DataCategory::All => SharedCategory::All,
+    }
+}
+
+/// The consent zome's name for a shared category
+fn consent_category(category: &SharedCategory) -> DataCategory {
+    match category {
+        SharedCategory::Demographics => DataCategory::Demographics,
+        SharedCategory::Allergies => DataCategory::Allergies,
+        SharedCategory::Medications => DataCategory::Medications,
+        SharedCategory::Diagnoses => DataCategory::Diagnoses,
+        SharedCategory::Procedures => DataCategory::Procedures,
+        SharedCategory::LabResults => DataCategory::LabResults,
+        SharedCategory::ImagingStudies => DataCategory::ImagingStudies,
+        SharedCategory::VitalSigns => DataCategory::VitalSigns,
+        SharedCategory::Immunizations => DataCategory::Immunizations,
+        SharedCategory::MentalHealth => DataCategory::MentalHealth,
+        SharedCategory::SubstanceAbuse => DataCategory::SubstanceAbuse,
+        SharedCategory::SexualHealth => DataCategory::SexualHealth,
+        SharedCategory::GeneticData => DataCategory::GeneticData,
+        SharedCategory::FinancialData => DataCategory::FinancialData,
+        SharedCategory::All => DataCategory::All,
+    }
+}
+
+// ============================================================
+// SITE VALIDATION RULES
+// ============================================================
+
+/// Input for authoring a rule set
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateValidationRuleSetInput {
+    pub name: String,
+    pub facility: Option<String>,
+    pub entry_type: String,
+    pub rules: Vec<SiteValidationRule>,
+}
+
+/// Input for selecting which rule set a facility applies to an entry type
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SelectValidationRuleSetInput {
+    /// `None` selects the network-wide rules, used by facilities without
+    /// a selection of their own
+    pub facility: Option<String>,
+    pub rule_set_hash: ActionHash,
+}
+
+/// Input for reading the rule set selected for a facility
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetSelectedRuleSetInput {
+    pub facility: Option<String>,
+    pub entry_type: String,
+}
+
+/// Organization of a provider profile (mirrors provider_integrity::Provider)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct ProviderOrganizationField {
+    organization: Option<String>,
+}
+
+fn selected_rules_anchor(facility: Option<&str>, entry_type: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("site_rules:{}:{}", facility.unwrap_or("*"), entry_type))
+}
+
+/// Author a rule set (operators only); it applies once selected
+#[hdk_extern]
+pub fn create_validation_rule_set(input: CreateValidationRuleSetInput) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller.clone())? {
+        return Err(unauthorized("Authoring validation rules requires the operator role"));
+    }
+    let rule_set = ValidationRuleSet {
+        name: input.name,
+        facility: input.facility,
+        entry_type: input.entry_type,
+        rules: input.rules,
+        created_by: caller,
+        created_at: sys_time()?,
+    };
+    let hash = create_entry(&EntryTypes::ValidationRuleSet(rule_set))?;
+    create_link(anchor_hash("validation_rule_sets")?, hash.clone(), LinkTypes::ValidationRuleSets, ())?;
+
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find rule set"))
+}
+
+/// Every authored rule set, selected or not
+#[hdk_extern]
+pub fn get_validation_rule_sets(_: ()) -> ExternResult<Vec<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(anchor_hash("validation_rule_sets")?, LinkTypes::ValidationRuleSets)?,
+        GetStrategy::default(),
+    )?;
+    let mut records = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                records.push(record);
+            }
+        }
+    }
+    Ok(records)
+}
+
+/// Apply a rule set to its entry type at a facility (operators only),
+/// replacing the facility's previous selection
+#[hdk_extern]
+pub fn select_validation_rule_set(input: SelectValidationRuleSetInput) -> ExternResult<ActionHash> {
+    if !is_operator(agent_info()?.agent_initial_pubkey)? {
+        return Err(unauthorized("Selecting validation rules requires the operator role"));
+    }
+    let rule_set: ValidationRuleSet = get(input.rule_set_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(not_found("Rule set not found"))?;
+    if rule_set.facility.is_some() && rule_set.facility != input.facility {
+        return Err(invalid_input(format!(
+            "Rule set was written for {}",
+            rule_set.facility.unwrap_or_default()
+        )));
+    }
+    create_link(
+        selected_rules_anchor(input.facility.as_deref(), &rule_set.entry_type)?,
+        input.rule_set_hash,
+        LinkTypes::SelectedRuleSets,
+        (),
+    )
+}
+
+/// Rule set currently selected for a facility and entry type
+#[hdk_extern]
+pub fn get_selected_rule_set(input: GetSelectedRuleSetInput) -> ExternResult<Option<Record>> {
+    let newest = get_links(
+        LinkQuery::try_new(
+            selected_rules_anchor(input.facility.as_deref(), &input.entry_type)?,
+            LinkTypes::SelectedRuleSets,
+        )?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash());
+    match newest {
+        Some(hash) => get(hash, GetOptions::default()),
+        None => Ok(None),
+    }
+}
+
+/// Evaluate the rules selected for a facility against an entry
+///
+/// Without an explicit facility the caller's provider organization is
+/// used. Facilities with no selection of their own fall back to the
+/// network-wide rules; with neither, the entry passes.
+#[hdk_extern]
+pub fn evaluate_site_rules(input: EvaluateSiteRulesInput) -> ExternResult<RuleEvaluation> {
+    let facility = match input.facility {
+        Some(facility) => Some(facility),
+        None => {
+            let caller = agent_info()?.agent_initial_pubkey;
+            resilient_call::<_, Option<Record>>("provider", "get_provider_by_agent", &caller, CallClass::Authorization)
+                .ok()
+                .flatten()
+                .and_then(|record| record.entry().to_app_option::<ProviderOrganizationField>().ok().flatten())
+                .and_then(|provider| provider.organization)
+        }
+    };
+
+    let mut selected = None;
+    if facility.is_some() {
+        selected = get_selected_rule_set(GetSelectedRuleSetInput {
+            facility: facility.clone(),
+            entry_type: input.entry_type.clone(),
+        })?;
+    }
+    if selected.is_none() {
+        selected = get_selected_rule_set(GetSelectedRuleSetInput {
+            facility: None,
+            entry_type: input.entry_type,
+        })?;
+    }
+    let Some(record) = selected else {
+        return Ok(RuleEvaluation { facility, ..Default::default() });
+    };
+    let rule_set: ValidationRuleSet = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid rule set"))?;
+
+    let (errors, warnings) = evaluate_rules(&rule_set.rules, &input.entry);
+    Ok(RuleEvaluation {
+        rule_set_hash: Some(record.action_address().clone()),
+        facility,
+        errors,
+        warnings,
+    })
+}
+
+// ============================================================
+// FHIR CONSENT TRANSLATION
+// ============================================================
+
+/// Code system for our data categories in FHIR provisions
+const FHIR_CATEGORY_SYSTEM: &str = "urn:mycelix:data-category";
+/// Code system for our data permissions alongside the FHIR consent actions
+const FHIR_PERMISSION_SYSTEM: &str = "urn:mycelix:data-permission";
+/// Identifier system for grantees, valued "<kind>/<id>"
+const FHIR_GRANTEE_SYSTEM: &str = "urn:mycelix:grantee";
+const FHIR_CONSENT_ACTION_SYSTEM: &str = "http://terminology.hl7.org/CodeSystem/consentaction";
+const FHIR_ACT_REASON_SYSTEM: &str = "http://terminology.hl7.org/CodeSystem/v3-ActReason";
+
+/// Active consents of a patient as FHIR R4 Consent resources
+///
+/// Each consent becomes one permit provision carrying its validity period,
+/// grantee, actions and purpose, with a nested permit per covered category,
+/// a nested deny per exclusion and the data period of the scope.
+#[hdk_extern]
+pub fn export_active_consents_fhir(patient_hash: ActionHash) -> ExternResult<Vec<serde_json::Value>> {
+    Ok(current_active_consents(patient_hash)?
+        .iter()
+        .map(|(_, consent)| consent_to_fhir(consent))
+        .collect())
+}
+
+/// A consent received from another system as a FHIR Consent resource
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ProposeFhirConsentInput {
+    pub patient_hash: ActionHash,
+    pub source_system: String,
+    pub resource: serde_json::Value,
+}
+
+/// Record an external FHIR Consent as a pending consent
+///
+/// Nothing is granted until the patient confirms it with
+/// `confirm_external_consent`; the patient is notified of the proposal.
+#[hdk_extern]
+pub fn propose_consent_from_fhir(input: ProposeFhirConsentInput) -> ExternResult<Record> {
+    let now = sys_time()?;
+    let consent = consent_from_fhir(&input.resource, &input.patient_hash, &input.source_system, now)
+        .map_err(|e| invalid_input(format!("Unsupported FHIR Consent: {}", e)))?;
+    propose_external_consent(consent, &input.source_system, now)
+}
+
+/// Create a pending consent from another system, list it for the patient's
+/// decision and tell them about it
+fn propose_external_consent(consent: Consent, source: &str, now: Timestamp) -> ExternResult<Record> {
+    let record = write_consent(consent.clone())?;
+    let consent_hash = record.action_address().clone();
+    create_link(
+        consent.patient_hash.clone(),
+        consent_hash,
+        LinkTypes::PendingExternalConsents,
+        (),
+    )?;
+
+    write_access_notification(AccessNotification {
+        notification_id: format!("EXTERNAL-CONSENT-{}-{}", consent.consent_id, now.as_micros()),
+        patient_hash: consent.patient_hash.clone(),
+        accessor: agent_info()?.agent_initial_pubkey,
+        accessor_name: source.to_string(),
+        data_categories: consent.scope.data_categories.clone(),
+        purpose: "Confirm a consent received from another system".to_string(),
+        accessed_at: now,
+        emergency_access: false,
+        priority: NotificationPriority::Immediate,
+        viewed: false,
+        viewed_at: None,
+        summary: format!(
+            "{} sent a consent for your records. It has no effect until you review and confirm it.",
+            source
+        ),
+        access_log_hash: None,
+    })?;
+    Ok(record)
+}
+
+/// External consents waiting for the patient's decision
+#[hdk_extern]
+pub fn get_external_consent_proposals(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let mut proposals = Vec::new();
+    for (_, hash) in pending_external_consent_links(&patient_hash)? {
+        if let Some(record) = get_for(hash, CallClass::ConsentStatus)? {
+            proposals.push(record);
+        }
+    }
+    Ok(proposals)
+}
+
+/// Patient accepts an external consent, making it active from now
+#[hdk_extern]
+pub fn confirm_external_consent(consent_hash: ActionHash) -> ExternResult<Record> {
+    let (link_hash, mut consent) = pending_external_consent(&consent_hash)?;
+    if patient_guardian_policy(&consent.patient_hash)?
+        .is_some_and(|(_, policy)| policy.active && policy.quorum_for(&consent.scope.data_categories).is_some())
+    {
+        return Err(conflict(
+            "A guardian quorum covers this consent; it is activated once the guardians approve it",
+        ));
+    }
+    consent.status = ConsentStatus::Active;
+    consent.granted_at = sys_time()?;
+    let updated = supersede_active_consent(consent_hash, &consent)?;
+    delete_link(link_hash, GetOptions::default())?;
+    count_active_consents(1)?;
+    Ok(updated)
+}
+
+/// Patient declines an external consent
+#[hdk_extern]
+pub fn reject_external_consent(consent_hash: ActionHash) -> ExternResult<Record> {
+    let (link_hash, mut consent) = pending_external_consent(&consent_hash)?;
+    consent.status = ConsentStatus::Rejected;
+    let updated_hash = update_entry(consent_hash.clone(), &consent)?;
+    notify_webhooks(WebhookEventType::ConsentChanged, consent.patient_hash.clone(), updated_hash.clone());
+    create_link(consent_hash, updated_hash.clone(), LinkTypes::ConsentUpdates, ())?;
+    create_link(
+        consent.patient_hash.clone(),
+        updated_hash.clone(),
+        LinkTypes::PatientToConsents,
+        consent_link_tag(&consent),
+    )?;
+    delete_link(link_hash, GetOptions::default())?;
+    get(updated_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find updated consent"))
+}
+
+fn pending_external_consent_links(patient_hash: &ActionHash) -> ExternResult<Vec<(ActionHash, ActionHash)>> {
+    Ok(get_links_for(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PendingExternalConsents)?,
+        CallClass::ConsentStatus,
+    )?
+    .into_iter()
+    .filter_map(|link| Some((link.create_link_hash, link.target.into_action_hash()?)))
+    .collect())
+}
+
+/// The pending-proposal link and consent, once the caller is confirmed as the patient
+fn pending_external_consent(consent_hash: &ActionHash) -> ExternResult<(ActionHash, Consent)> {
+    let record = get_for(consent_hash.clone(), CallClass::ConsentStatus)?
+        .ok_or(not_found("Consent not found"))?;
+    let consent: Consent = record
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
+    require_patient_self(&consent.patient_hash)?;
+
+    let (link_hash, _) = pending_external_consent_links(&consent.patient_hash)?
+        .into_iter()
+        .find(|(_, target)| target == consent_hash)
+        .ok_or(conflict("Consent is not an external proposal awaiting a decision"))?;
+    if !matches!(consent.status, ConsentStatus::Pending) {
+        return Err(conflict("Only pending consents can be confirmed or rejected"));
+    }
+    Ok((link_hash, consent))
+}
+
+// ============================================================
+// CROSS-NETWORK CONSENT ASSERTIONS
+// ============================================================
+
+/// Validity of an assertion when the patient does not choose one
+const DEFAULT_ASSERTION_VALIDITY_HOURS: u32 = 24;
+/// Longest an assertion may stay importable
+const MAX_ASSERTION_VALIDITY_HOURS: u32 = 24 * 30;
+
+/// Input for asserting one of the caller's consents to a partner network
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateConsentAssertionInput {
+    pub consent_hash: ActionHash,
+    /// Partner network that may import the assertion; `None` for any
+    pub audience: Option<DnaHash>,
+    pub valid_for_hours: Option<u32>,
+}
+
+/// Sign an assertion of an active consent for a partner network to verify
+///
+/// Only the patient can assert their consent. The assertion stops being
+/// importable after its validity window or the consent's own expiry,
+/// whichever comes first.
+#[hdk_extern]
+pub fn create_consent_assertion(input: CreateConsentAssertionInput) -> ExternResult<ConsentAssertion> {
+    let consent: Consent = get_for(input.consent_hash.clone(), CallClass::ConsentStatus)?
+        .ok_or(not_found("Consent not found"))?
+        .entry()
+        .to_app_option()
+        .map_err(internal_error)?
+        .ok_or(internal_error("Invalid consent"))?;
+    require_patient_self(&consent.patient_hash)?;
+
+    let now = sys_time()?;
+    let is_current = current_active_consents(consent.patient_hash.clone())?
+        .iter()
+        .any(|(hash, _)| *hash == input.consent_hash);
+    if !is_current || consent.expires_at.is_some_and(|expires| expires <= now) {
+        return Err(conflict("Only current, unexpired active consents can be asserted"));
+    }
+    let hours = input.valid_for_hours.unwrap_or(DEFAULT_ASSERTION_VALIDITY_HOURS);
+    if hours == 0 || hours > MAX_ASSERTION_VALIDITY_HOURS {
+        return Err(invalid_input(format!(
+            "Assertions are valid for between 1 and {} hours",
+            MAX_ASSERTION_VALIDITY_HOURS
+        )));
+    }
+    let window_end = Timestamp::from_micros(now.as_micros() + hours as i64 * 3_600_000_000);
+    let valid_until = consent.expires_at.map_or(window_end, |expires| expires.min(window_end));
+
+    let issuer = agent_info()?.agent_initial_pubkey;
+    let claims = ConsentAssertionClaims {
+        assertion_id: format!("CA-{}-{}", consent.consent_id, now.as_micros()),
+        issuer_network: dna_info()?.hash,
+        audience: input.audience,
+        issuer: issuer.clone(),
+        consent_hash: input.consent_hash,
+        grantee: consent.grantee,
+        data_categories: consent.scope.data_categories,
+        exclusions: consent.scope.exclusions,
+        permissions: consent.permissions,
+        purpose: consent.purpose,
+        consent_expires_at: consent.expires_at,
+        issued_at: now,
+        valid_until,
+    };
+    let signature = sign(issuer, &claims)?;
+    Ok(ConsentAssertion { claims, signature })
+}
+
+/// Input for importing a partner network's consent assertion
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ImportConsentAssertionInput {
+    /// The patient's record on this network
+    pub patient_hash: ActionHash,
+    pub assertion: ConsentAssertion,
+    /// Name of the partner network, shown to the patient
+    pub source_network: String,
+}
+
+/// Accept a partner network's consent assertion as advisory evidence
+///
+/// The signature, validity window and audience are checked, then the claims
+/// become a pending consent that grants nothing until the patient confirms
+/// it with `confirm_external_consent`. Each assertion imports once.
+#[hdk_extern]
+pub fn import_consent_assertion(input: ImportConsentAssertionInput) -> ExternResult<Record> {
+    let claims = input.assertion.claims.clone();
+    let now = sys_time()?;
+    let here = dna_info()?.hash;
+    if !input.assertion.verify()? {
+        return Err(invalid_input("Consent assertion signature does not match its issuer"));
+    }
+    if claims.issuer_network == here {
+        return Err(invalid_input("Assertions from this network are not imported; use the consent itself"));
+    }
+    if claims.audience.as_ref().is_some_and(|audience| *audience != here) {
+        return Err(invalid_input("Consent assertion was issued for a different network"));
+    }
+    if now < claims.issued_at || now > claims.valid_until {
+        return Err(invalid_input("Consent assertion is outside its validity window"));
+    }
+    let already_imported = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToConsentAssertions)?,
+        GetStrategy::default(),
+    )?
+    .iter()
+    .any(|link| link.tag.0 == claims.assertion_id.as_bytes());
+    if already_imported {
+        return Err(conflict("Consent assertion has already been imported"));
+    }
+
+    let consent = Consent {
+        consent_id: format!("ASSERTED-{}", claims.assertion_id),
+        patient_hash: input.patient_hash.clone(),
+        grantee: claims.grantee.clone(),
+        scope: ConsentScope {
+            data_categories: claims.data_categories.clone(),
+            date_range: None,
+            encounter_hashes: None,
+            exclusions: claims.exclusions.clone(),
+            minimum_assurance: Vec::new(),
         },
-        CareTeamTemplate {
-            template_id: "insurance-billing".to_string(),
-            name: "Insurance & Billing".to_string(),
-            description: "Your insurance company can access billing information to process claims.".to_string(),
-            permissions: vec![DataPermission::Read],
-            data_categories: vec![
-                DataCategory::Demographics,
-                DataCategory::Diagnoses,
-                DataCategory::Procedures,
-                DataCategory::FinancialData,
-            ],
-            default_exclusions: vec![
-                DataCategory::MentalHealth,
-                DataCategory::SubstanceAbuse,
-                DataCategory::SexualHealth,
-                DataCategory::GeneticData,
-            ],
-            purpose: ConsentPurpose::Payment,
-            default_duration_days: Some(365),
-            template_type: TemplateType::System,
-            created_by: agent_info()?.agent_initial_pubkey,
-            created_at: sys_time()?,
-            active: true,
+        permissions: claims.permissions.clone(),
+        purpose: claims.purpose.clone(),
+        status: ConsentStatus::Pending,
+        granted_at: now,
+        expires_at: claims.consent_expires_at,
+        revoked_at: None,
+        revocation_reason: None,
+        document_hash: None,
+        witness: None,
+        legal_representative: None,
+        notes: Some(format!(
+            "Asserted by the patient on {} (consent {})",
+            input.source_network, claims.consent_hash
+        )),
+        category_registry: None,
+        guardian_policy: None,
+        guardian_approvals: Vec::new(),
+    };
+    let record = propose_external_consent(consent, &input.source_network, now)?;
+    let consent_hash = record.action_address().clone();
+
+    let evidence_hash = create_entry(&EntryTypes::ImportedConsentAssertion(ImportedConsentAssertion {
+        patient_hash: input.patient_hash.clone(),
+        assertion: input.assertion,
+        proposed_consent_hash: consent_hash.clone(),
+        imported_by: agent_info()?.agent_initial_pubkey,
+        imported_at: now,
+    }))?;
+    create_link(
+        input.patient_hash,
+        evidence_hash.clone(),
+        LinkTypes::PatientToConsentAssertions,
+        LinkTag::new(claims.assertion_id.into_bytes()),
+    )?;
+    create_link(consent_hash, evidence_hash, LinkTypes::ConsentToAssertionEvidence, ())?;
+    Ok(record)
+}
+
+/// The partner assertion a proposed consent was imported from, if any
+#[hdk_extern]
+pub fn get_consent_assertion_evidence(consent_hash: ActionHash) -> ExternResult<Option<ImportedConsentAssertion>> {
+    let evidence = get_links(
+        LinkQuery::try_new(consent_hash, LinkTypes::ConsentToAssertionEvidence)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .next()
+    .and_then(|link| link.target.into_action_hash());
+    match evidence {
+        Some(hash) => Ok(get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<ImportedConsentAssertion>().ok().flatten())),
+        None => Ok(None),
+    }
+}
+
+fn consent_to_fhir(consent: &Consent) -> serde_json::Value {
+    use serde_json::json;
+
+    let status = match consent.status {
+        ConsentStatus::Active => "active",
+        ConsentStatus::Pending => "proposed",
+        ConsentStatus::Rejected => "rejected",
+        ConsentStatus::Expired | ConsentStatus::Revoked => "inactive",
+    };
+    let category_code = |category: &DataCategory| {
+        json!([{ "coding": [{ "system": FHIR_CATEGORY_SYSTEM, "code": format!("{:?}", category) }] }])
+    };
+    let mut nested: Vec<serde_json::Value> = consent
+        .scope
+        .data_categories
+        .iter()
+        .map(|category| json!({ "type": "permit", "code": category_code(category) }))
+        .collect();
+    nested.extend(
+        consent
+            .scope
+            .exclusions
+            .iter()
+            .map(|category| json!({ "type": "deny", "code": category_code(category) })),
+    );
+    let actions: Vec<serde_json::Value> = consent
+        .permissions
+        .iter()
+        .map(|permission| {
+            json!({ "coding": [
+                { "system": FHIR_CONSENT_ACTION_SYSTEM, "code": fhir_consent_action(permission) },
+                { "system": FHIR_PERMISSION_SYSTEM, "code": format!("{:?}", permission) },
+            ]})
+        })
+        .collect();
+
+    let mut period = json!({ "start": fhir_instant(consent.granted_at) });
+    if let Some(end) = consent.expires_at.or(consent.revoked_at) {
+        period["end"] = json!(fhir_instant(end));
+    }
+    let mut provision = json!({
+        "type": "permit",
+        "period": period,
+        "actor": [{
+            "role": { "coding": [{
+                "system": "http://terminology.hl7.org/CodeSystem/v3-ParticipationType",
+                "code": "IRCP",
+            }]},
+            "reference": fhir_grantee_reference(&consent.grantee),
+        }],
+        "action": actions,
+        "purpose": [fhir_purpose(&consent.purpose)],
+        "provision": nested,
+    });
+    if let Some(range) = &consent.scope.date_range {
+        let mut data_period = json!({ "start": fhir_instant(range.start) });
+        if let Some(end) = range.end {
+            data_period["end"] = json!(fhir_instant(end));
+        }
+        provision["dataPeriod"] = data_period;
+    }
+
+    json!({
+        "resourceType": "Consent",
+        "id": consent.consent_id,
+        "status": status,
+        "scope": { "coding": [{
+            "system": "http://terminology.hl7.org/CodeSystem/consentscope",
+            "code": "patient-privacy",
+        }]},
+        "category": [{ "coding": [{ "system": "http://loinc.org", "code": "59284-0" }] }],
+        "patient": { "reference": format!("Patient/{}", consent.patient_hash) },
+        "dateTime": fhir_instant(consent.granted_at),
+        "provision": provision,
+    })
+}
+
+/// Build a pending consent from a FHIR Consent resource
+///
+/// Only the top-level permit provision and its nested category provisions
+/// are understood; anything that would widen the grant if misread (no
+/// mappable category, an unknown actor) is refused rather than guessed.
+fn consent_from_fhir(
+    resource: &serde_json::Value,
+    patient_hash: &ActionHash,
+    source_system: &str,
+    now: Timestamp,
+) -> Result<Consent, String> {
+    if resource.get("resourceType").and_then(|t| t.as_str()) != Some("Consent") {
+        return Err("not a Consent resource".to_string());
+    }
+    let id = resource.get("id").and_then(|v| v.as_str()).ok_or("missing 'id'")?;
+    let provision = resource.get("provision").ok_or("missing 'provision'")?;
+    if provision.get("type").and_then(|t| t.as_str()).unwrap_or("permit") != "permit" {
+        return Err("only permit provisions can be proposed".to_string());
+    }
+    let time = |value: Option<&serde_json::Value>| value.and_then(|v| v.as_str()).and_then(parse_fhir_datetime);
+    let array = |value: &serde_json::Value, field: &str| -> Vec<serde_json::Value> {
+        value.get(field).and_then(|v| v.as_array()).cloned().unwrap_or_default()
+    };
+
+    let mut data_categories = Vec::new();
+    let mut exclusions = Vec::new();
+    for rule in array(provision, "provision") {
+        let categories: Vec<DataCategory> = array(&rule, "code")
+            .iter()
+            .flat_map(|concept| array(concept, "coding"))
+            .filter(|coding| coding.get("system").and_then(|s| s.as_str()) == Some(FHIR_CATEGORY_SYSTEM))
+            .filter_map(|coding| serde_json::from_value(coding.get("code")?.clone()).ok())
+            .collect();
+        match rule.get("type").and_then(|t| t.as_str()) {
+            Some("deny") => exclusions.extend(categories),
+            _ => data_categories.extend(categories),
+        }
+    }
+    if data_categories.is_empty() {
+        return Err("no data category we can map".to_string());
+    }
+
+    let grantee = array(provision, "actor")
+        .iter()
+        .find_map(|actor| actor.get("reference").and_then(grantee_from_fhir))
+        .ok_or("no actor we can map to a grantee")?;
+
+    let mut permissions: Vec<DataPermission> = Vec::new();
+    for concept in array(provision, "action") {
+        let codings = array(&concept, "coding");
+        let code_in = |system: &str| {
+            codings
+                .iter()
+                .find(|c| c.get("system").and_then(|s| s.as_str()) == Some(system))
+                .and_then(|c| c.get("code").and_then(|v| v.as_str()))
+                .map(|code| code.to_string())
+        };
+        let permission = match code_in(FHIR_PERMISSION_SYSTEM) {
+            Some(code) => serde_json::from_value(serde_json::json!(code)).ok(),
+            None => code_in(FHIR_CONSENT_ACTION_SYSTEM).and_then(|code| match code.as_str() {
+                "access" | "use" => Some(DataPermission::Read),
+                "collect" => Some(DataPermission::Write),
+                "disclose" => Some(DataPermission::Share),
+                "correct" => Some(DataPermission::Amend),
+                _ => None,
+            }),
+        };
+        if let Some(permission) = permission {
+            if !permissions.contains(&permission) {
+                permissions.push(permission);
+            }
+        }
+    }
+    if permissions.is_empty() {
+        permissions.push(DataPermission::Read);
+    }
+
+    let purpose = array(provision, "purpose")
+        .first()
+        .map(purpose_from_fhir)
+        .unwrap_or(ConsentPurpose::Treatment);
+    let date_range = time(provision.pointer("/dataPeriod/start")).map(|start| DateRange {
+        start,
+        end: time(provision.pointer("/dataPeriod/end")),
+    });
+
+    Ok(Consent {
+        consent_id: format!("FHIR-{}-{}", source_system, id),
+        patient_hash: patient_hash.clone(),
+        grantee,
+        scope: ConsentScope {
+            data_categories,
+            date_range,
+            encounter_hashes: None,
+            exclusions,
+            minimum_assurance: Vec::new(),
         },
-        CareTeamTemplate {
-            template_id: "telehealth-visit".to_string(),
-            name: "Telehealth Visit".to_string(),
-            description: "A provider can access your records for a single telehealth visit.".to_string(),
-            permissions: vec![DataPermission::Read],
-            data_categories: vec![
-                DataCategory::Demographics,
-                DataCategory::Allergies,
-                DataCategory::Medications,
-                DataCategory::Diagnoses,
-                DataCategory::VitalSigns,
-            ],
-            default_exclusions: vec![
-                DataCategory::MentalHealth,
-                DataCategory::SubstanceAbuse,
-                DataCategory::SexualHealth,
-                DataCategory::GeneticData,
-                DataCategory::FinancialData,
-            ],
-            purpose: ConsentPurpose::Treatment,
-            default_duration_days: Some(1),
-            template_type: TemplateType::System,
-            created_by: agent_info()?.agent_initial_pubkey,
-            created_at: sys_time()?,
-            active: true,
+        permissions,
+        purpose,
+        status: ConsentStatus::Pending,
+        granted_at: now,
+        expires_at: time(provision.pointer("/period/end")),
+        revoked_at: None,
+        revocation_reason: None,
+        document_hash: None,
+        witness: None,
+        legal_representative: None,
+        notes: Some(format!("Proposed from {} FHIR Consent/{}", source_system, id)),
+        category_registry: None,
+        guardian_policy: None,
+        guardian_approvals: Vec::new(),
+    })
+}
+
+fn fhir_consent_action(permission: &DataPermission) -> &'static str {
+    match permission {
+        DataPermission::Read => "access",
+        DataPermission::Write => "collect",
+        DataPermission::Share | DataPermission::Export => "disclose",
+        DataPermission::Amend | DataPermission::Delete => "correct",
+    }
+}
+
+/// Purposes with their HL7 v3 ActReason codes
+const FHIR_PURPOSE_CODES: &[(&str, ConsentPurpose)] = &[
+    ("TREAT", ConsentPurpose::Treatment),
+    ("HPAYMT", ConsentPurpose::Payment),
+    ("HOPERAT", ConsentPurpose::HealthcareOperations),
+    ("HRESCH", ConsentPurpose::Research),
+    ("PUBHLTH", ConsentPurpose::PublicHealth),
+    ("HLEGAL", ConsentPurpose::LegalProceeding),
+    ("HMARKT", ConsentPurpose::Marketing),
+    ("FAMRQT", ConsentPurpose::FamilyNotification),
+];
+
+fn fhir_purpose(purpose: &ConsentPurpose) -> serde_json::Value {
+    match purpose {
+        ConsentPurpose::Other(text) => serde_json::json!({ "system": FHIR_ACT_REASON_SYSTEM, "display": text }),
+        known => {
+            let code = FHIR_PURPOSE_CODES
+                .iter()
+                .find(|(_, p)| p == known)
+                .map(|(code, _)| *code)
+                .unwrap_or_default();
+            serde_json::json!({ "system": FHIR_ACT_REASON_SYSTEM, "code": code })
+        }
+    }
+}
+
+fn purpose_from_fhir(coding: &serde_json::Value) -> ConsentPurpose {
+    let code = coding.get("code").and_then(|c| c.as_str());
+    FHIR_PURPOSE_CODES
+        .iter()
+        .find(|(known, _)| Some(*known) == code)
+        .map(|(_, purpose)| purpose.clone())
+        .unwrap_or_else(|| {
+            let text = coding.get("display").and_then(|d| d.as_str()).or(code).unwrap_or("Unspecified");
+            ConsentPurpose::Other(text.to_string())
+        })
+}
+
+fn fhir_grantee_reference(grantee: &ConsentGrantee) -> serde_json::Value {
+    let (value, display) = match grantee {
+        ConsentGrantee::Provider(hash) => (format!("Provider/{}", hash), None),
+        ConsentGrantee::Organization(name) => (format!("Organization/{}", name), Some(name.clone())),
+        ConsentGrantee::Agent(agent) => (format!("Agent/{}", agent), None),
+        ConsentGrantee::ResearchStudy(hash) => (format!("ResearchStudy/{}", hash), None),
+        ConsentGrantee::InsuranceCompany(hash) => (format!("InsuranceCompany/{}", hash), None),
+        ConsentGrantee::EmergencyAccess => ("EmergencyAccess".to_string(), Some("Emergency access".to_string())),
+        ConsentGrantee::Public => ("Public".to_string(), Some("Public (anonymized research)".to_string())),
+    };
+    let mut reference = serde_json::json!({ "identifier": { "system": FHIR_GRANTEE_SYSTEM, "value": value } });
+    if let Some(display) = display {
+        reference["display"] = serde_json::json!(display);
+    }
+    reference
+}
+
+/// Grantee named by an actor reference: our own grantee identifiers, or a
+/// named external organization
+fn grantee_from_fhir(reference: &serde_json::Value) -> Option<ConsentGrantee> {
+    let identifier = reference.get("identifier");
+    if identifier.and_then(|i| i.get("system")).and_then(|s| s.as_str()) == Some(FHIR_GRANTEE_SYSTEM) {
+        let value = identifier?.get("value")?.as_str()?;
+        let (kind, id) = value.split_once('/').unwrap_or((value, ""));
+        let hash = || ActionHash::try_from(id.to_string()).ok();
+        return match kind {
+            "Provider" => hash().map(ConsentGrantee::Provider),
+            "Organization" => Some(ConsentGrantee::Organization(id.to_string())),
+            "Agent" => AgentPubKey::try_from(id.to_string()).ok().map(ConsentGrantee::Agent),
+            "ResearchStudy" => hash().map(ConsentGrantee::ResearchStudy),
+            "InsuranceCompany" => hash().map(ConsentGrantee::InsuranceCompany),
+            "EmergencyAccess" => Some(ConsentGrantee::EmergencyAccess),
+            "Public" => Some(ConsentGrantee::Public),
+            _ => None,
+        };
+    }
+    let is_organization = reference
+        .get("reference")
+        .and_then(|r| r.as_str())
+        .is_some_and(|r| r.starts_with("Organization/"))
+        || reference.get("type").and_then(|t| t.as_str()) == Some("Organization");
+    let display = reference.get("display").and_then(|d| d.as_str())?;
+    is_organization.then(|| ConsentGrantee::Organization(display.to_string()))
+}
+
+// ============================================================
+// PATIENT TIMELINE
+// ============================================================
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PatientTimelineInput {
+    pub patient_hash: ActionHash,
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub page: Option<CursorInput>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum TimelineEventKind {
+    DataIngested,
+    DataAccessed,
+    EmergencyAccess,
+    ConsentGranted,
+    ConsentRevoked,
+    ContributionMade,
+    ContributionRevoked,
+    DividendDistributed,
+    DividendClaimed,
+    Notification,
+}
+
+/// One entry in a patient's timeline
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct TimelineEvent {
+    pub occurred_at: Timestamp,
+    pub kind: TimelineEventKind,
+    /// Entry the event was read from
+    pub source_hash: ActionHash,
+    pub data_categories: Vec<DataCategory>,
+    pub summary: String,
+}
+
+/// Subset of the fhir_bridge zome's `IngestSummary` needed for the timeline
+#[derive(Serialize, Deserialize, Debug)]
+struct TimelineIngestFields {
+    report_hash: ActionHash,
+    source_system: String,
+    ingested_at: Timestamp,
+    total_processed: u32,
+    data_categories: Vec<DataCategory>,
+}
+
+/// Subset of the dividends zome's `DataContribution` entry
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+struct TimelineContributionFields {
+    contribution_id: String,
+    contributed_at: i64,
+    revoked_at: Option<i64>,
+}
+
+/// Subset of the dividends zome's `DividendDistribution` entry
+#[derive(Serialize, Deserialize, SerializedBytes, Debug)]
+struct TimelineDividendFields {
+    distribution_id: String,
+    distributed_at: i64,
+    claimed_at: Option<i64>,
+}
+
+/// Read decisions for the timeline's caller, made once per category
+struct TimelineFilter {
+    patient_hash: ActionHash,
+    requestor: AgentPubKey,
+    is_patient: bool,
+    decisions: Vec<(DataCategory, bool)>,
+}
+
+impl TimelineFilter {
+    fn new(patient_hash: &ActionHash) -> ExternResult<Self> {
+        let requestor = agent_info()?.agent_initial_pubkey;
+        let is_patient = get_for(patient_hash.clone(), CallClass::Authorization)?
+            .is_some_and(|record| record.action().author() == &requestor);
+        Ok(Self { patient_hash: patient_hash.clone(), requestor, is_patient, decisions: Vec::new() })
+    }
+
+    /// The patient sees everything; anyone else needs read consent for
+    /// every category an event touches
+    fn allows(&mut self, categories: &[DataCategory]) -> ExternResult<bool> {
+        if self.is_patient {
+            return Ok(true);
+        }
+        for category in categories {
+            let known = self.decisions.iter().find(|(c, _)| c == category).map(|(_, allowed)| *allowed);
+            let allowed = match known {
+                Some(allowed) => allowed,
+                None => {
+                    let decision = authorization_decision(&AuthorizationCheckInput {
+                        patient_hash: self.patient_hash.clone(),
+                        requestor: self.requestor.clone(),
+                        data_category: category.clone(),
+                        permission: DataPermission::Read,
+                        is_emergency: false,
+                        access_path: None,
+                        access_context: None,
+                    })?;
+                    self.decisions.push((category.clone(), decision.authorized));
+                    decision.authorized
+                }
+            };
+            if !allowed {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Ingests, accesses, consent changes, contributions, dividends and
+/// notifications for a patient in one chronological stream
+///
+/// Events touching a category the caller may not read are dropped.
+/// Ingests and dividends come from their own zomes and are left out when
+/// those zomes are not installed.
+#[hdk_extern]
+pub fn get_patient_timeline(input: PatientTimelineInput) -> ExternResult<CursorPage<TimelineEvent>> {
+    let page = input.page.unwrap_or_default();
+    page.validate()?;
+
+    let mut filter = TimelineFilter::new(&input.patient_hash)?;
+    let mut events = Vec::new();
+    for event in collect_timeline_events(&input.patient_hash)? {
+        if event.occurred_at < input.period_start || event.occurred_at > input.period_end {
+            continue;
+        }
+        if filter.allows(&event.data_categories)? {
+            events.push(event);
+        }
+    }
+    cursor_page(events, &page, |event| {
+        PaginationCursor::new(event.occurred_at, event.source_hash.get_raw_39())
+    })
+}
+
+fn collect_timeline_events(patient_hash: &ActionHash) -> ExternResult<Vec<TimelineEvent>> {
+    let mut events = Vec::new();
+
+    for record in get_patient_consents(patient_hash.clone())? {
+        let Some(consent) = record.entry().to_app_option::<Consent>().ok().flatten() else { continue };
+        if consent.status == ConsentStatus::Pending {
+            continue;
+        }
+        events.push(TimelineEvent {
+            occurred_at: consent.granted_at,
+            kind: TimelineEventKind::ConsentGranted,
+            source_hash: record.action_address().clone(),
+            data_categories: consent.scope.data_categories.clone(),
+            summary: format!("Consent {} granted for {:?}", consent.consent_id, consent.purpose),
+        });
+        if let Some(revoked_at) = consent.revoked_at {
+            events.push(TimelineEvent {
+                occurred_at: revoked_at,
+                kind: TimelineEventKind::ConsentRevoked,
+                source_hash: record.action_address().clone(),
+                data_categories: consent.scope.data_categories,
+                summary: format!("Consent {} revoked", consent.consent_id),
+            });
+        }
+    }
+
+    for record in all_access_logs(patient_hash.clone())? {
+        let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() else { continue };
+        let kind = if log.emergency_override {
+            TimelineEventKind::EmergencyAccess
+        } else {
+            TimelineEventKind::DataAccessed
+        };
+        events.push(TimelineEvent {
+            occurred_at: log.accessed_at,
+            kind,
+            source_hash: record.action_address().clone(),
+            data_categories: log.data_categories_accessed,
+            summary: format!("{:?} access: {}", log.access_type, log.access_reason),
+        });
+    }
+
+    let notifications = get_patient_notifications(GetNotificationsInput {
+        patient_hash: patient_hash.clone(),
+        unread_only: false,
+        limit: None,
+    })?;
+    for record in notifications {
+        let Some(notification) = record.entry().to_app_option::<AccessNotification>().ok().flatten() else {
+            continue;
+        };
+        events.push(TimelineEvent {
+            occurred_at: notification.accessed_at,
+            kind: TimelineEventKind::Notification,
+            source_hash: record.action_address().clone(),
+            data_categories: notification.data_categories,
+            summary: notification.summary,
+        });
+    }
+
+    let ingests: Vec<TimelineIngestFields> =
+        resilient_call("fhir_bridge", "get_patient_ingest_reports", patient_hash, CallClass::Bulk)
+            .unwrap_or_default();
+    for ingest in ingests {
+        events.push(TimelineEvent {
+            occurred_at: ingest.ingested_at,
+            kind: TimelineEventKind::DataIngested,
+            source_hash: ingest.report_hash,
+            data_categories: ingest.data_categories,
+            summary: format!("{} resources ingested from {}", ingest.total_processed, ingest.source_system),
+        });
+    }
+
+    let contributions: Vec<Record> =
+        resilient_call("dividends", "get_patient_contributions", patient_hash, CallClass::Bulk)
+            .unwrap_or_default();
+    for record in contributions {
+        let Some(contribution) = record.entry().to_app_option::<TimelineContributionFields>().ok().flatten() else {
+            continue;
+        };
+        events.push(TimelineEvent {
+            occurred_at: Timestamp::from_micros(contribution.contributed_at),
+            kind: TimelineEventKind::ContributionMade,
+            source_hash: record.action_address().clone(),
+            data_categories: vec![DataCategory::FinancialData],
+            summary: format!("Contribution {} shared for research", contribution.contribution_id),
+        });
+        if let Some(revoked_at) = contribution.revoked_at {
+            events.push(TimelineEvent {
+                occurred_at: Timestamp::from_micros(revoked_at),
+                kind: TimelineEventKind::ContributionRevoked,
+                source_hash: record.action_address().clone(),
+                data_categories: vec![DataCategory::FinancialData],
+                summary: format!("Contribution {} revoked", contribution.contribution_id),
+            });
+        }
+    }
+
+    let dividends: Vec<Record> =
+        resilient_call("dividends", "get_patient_dividends", patient_hash, CallClass::Bulk)
+            .unwrap_or_default();
+    for record in dividends {
+        let Some(dividend) = record.entry().to_app_option::<TimelineDividendFields>().ok().flatten() else {
+            continue;
+        };
+        events.push(TimelineEvent {
+            occurred_at: Timestamp::from_micros(dividend.distributed_at),
+            kind: TimelineEventKind::DividendDistributed,
+            source_hash: record.action_address().clone(),
+            data_categories: vec![DataCategory::FinancialData],
+            summary: format!("Dividend {} distributed", dividend.distribution_id),
+        });
+        if let Some(claimed_at) = dividend.claimed_at {
+            events.push(TimelineEvent {
+                occurred_at: Timestamp::from_micros(claimed_at),
+                kind: TimelineEventKind::DividendClaimed,
+                source_hash: record.action_address().clone(),
+                data_categories: vec![DataCategory::FinancialData],
+                summary: format!("Dividend {} claimed", dividend.distribution_id),
+            });
+        }
+    }
+
+    Ok(events)
+}
+
+// ============================================================
+// DIRECT MESSAGING
+// ============================================================
+
+/// Longest message body accepted, in characters
+const DIRECT_MESSAGE_MAX_CHARS: usize = 4000;
+
+/// Input for messaging the other party of a patient-provider relationship
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SendDirectMessageInput {
+    pub patient_hash: ActionHash,
+    pub recipient: AgentPubKey,
+    pub context: MessagingContext,
+    /// Clinical record the message asks about
+    pub regarding: Option<ActionHash>,
+    pub in_reply_to: Option<ActionHash>,
+    pub body: String,
+}
+
+/// A direct message opened by one of its two parties
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct DirectMessageView {
+    pub message_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub sender: AgentPubKey,
+    pub recipient: AgentPubKey,
+    pub context: MessagingContext,
+    pub regarding: Option<ActionHash>,
+    pub in_reply_to: Option<ActionHash>,
+    pub body: String,
+    pub sent_at: Timestamp,
+    /// When the recipient marked it read
+    pub read_at: Option<Timestamp>,
+}
+
+/// Input for listing the caller's messages
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetMyMessagesInput {
+    /// Only messages about this patient
+    pub patient_hash: Option<ActionHash>,
+    /// Only received messages not yet marked read
+    pub unread_only: bool,
+    pub pagination: Option<PaginationInput>,
+}
+
+/// Send a message between a patient and a provider
+///
+/// One party must be the patient and the other the provider the context
+/// covers; the consent or care team must still be active when sending.
+/// Messages already sent stay readable after the relationship ends.
+#[hdk_extern]
+pub fn send_direct_message(input: SendDirectMessageInput) -> ExternResult<Record> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let body = input.body.trim();
+    if body.is_empty() {
+        return Err(invalid_input("Message body cannot be empty"));
+    }
+    if body.chars().count() > DIRECT_MESSAGE_MAX_CHARS {
+        return Err(invalid_input(format!(
+            "Message must be at most {} characters",
+            DIRECT_MESSAGE_MAX_CHARS
+        )));
+    }
+
+    let patient_agent = get_for(input.patient_hash.clone(), CallClass::Authorization)?
+        .ok_or(not_found("Patient not found"))?
+        .action()
+        .author()
+        .clone();
+    let provider = if me == patient_agent {
+        input.recipient.clone()
+    } else if input.recipient == patient_agent {
+        me.clone()
+    } else {
+        return Err(invalid_input("Messages must be between the patient and a provider"));
+    };
+    require_messaging_relationship(&input.patient_hash, &provider, &input.context)?;
+
+    if let Some(reply_hash) = &input.in_reply_to {
+        let original = get(reply_hash.clone(), GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<DirectMessage>().ok().flatten())
+            .ok_or(not_found("Message being replied to not found"))?;
+        let same_parties = (original.sender == me && original.recipient == input.recipient)
+            || (original.sender == input.recipient && original.recipient == me);
+        if original.patient_hash != input.patient_hash || !same_parties {
+            return Err(invalid_input("Replies must stay in the same conversation"));
+        }
+    }
+
+    let message = DirectMessage {
+        patient_hash: input.patient_hash,
+        sender: me.clone(),
+        recipient: input.recipient.clone(),
+        context: input.context,
+        regarding: input.regarding.clone(),
+        in_reply_to: input.in_reply_to,
+        sealed_body: ed_25519_x_salsa20_poly1305_encrypt(
+            me.clone(),
+            input.recipient.clone(),
+            XSalsa20Poly1305Data::from(body.as_bytes().to_vec()),
+        )?,
+        sent_at: sys_time()?,
+    };
+    let message_hash = create_entry(&EntryTypes::DirectMessage(message))?;
+    let record = get(message_hash.clone(), GetOptions::default())?
+        .ok_or(not_found("Could not find direct message"))?;
+
+    create_link(me, message_hash.clone(), LinkTypes::AgentToMessages, ())?;
+    create_link(input.recipient, message_hash.clone(), LinkTypes::AgentToMessages, ())?;
+    if let Some(regarding) = input.regarding {
+        create_link(regarding, message_hash, LinkTypes::RecordToMessages, ())?;
+    }
+
+    Ok(record)
+}
+
+/// Reject messaging unless the context is in force for this provider
+fn require_messaging_relationship(
+    patient_hash: &ActionHash,
+    provider: &AgentPubKey,
+    context: &MessagingContext,
+) -> ExternResult<()> {
+    let now = sys_time()?;
+    let active = match context {
+        MessagingContext::Consent(hash) => match latest_entry::<Consent>(hash)? {
+            Some(consent) => {
+                consent.patient_hash == *patient_hash
+                    && matches!(consent.status, ConsentStatus::Active)
+                    && consent.expires_at.is_none_or(|expires| now < expires)
+                    && consent.grantee == ConsentGrantee::Agent(provider.clone())
+            }
+            None => false,
         },
-    ];
+        MessagingContext::CareTeam(hash) => match latest_entry::<CareTeam>(hash)? {
+            Some(team) => {
+                let mut is_member = false;
+                for member in team.members.iter().filter(|m| m.active) {
+                    if care_team_member_is(&member.member, provider)? {
+                        is_member = true;
+                        break;
+                    }
+                }
+                team.patient_hash == *patient_hash
+                    && matches!(team.status, CareTeamStatus::Active)
+                    && team.expires_at.is_none_or(|expires| now < expires)
+                    && is_member
+            }
+            None => false,
+        },
+    };
+    if !active {
+        return Err(consent_required("Messaging needs an active consent or care team with this provider"));
+    }
+    Ok(())
+}
 
-    let mut created_hashes = Vec::new();
-    for template in templates {
-        let record = create_care_team_template(template)?;
-        created_hashes.push(record.action_address().clone());
+/// Newest version of an entry, if the hash holds one of type `T`
+fn latest_entry<T>(hash: &ActionHash) -> ExternResult<Option<T>>
+where
+    T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let Some(record) = get_for(hash.clone(), CallClass::ConsentStatus)? else {
+        return Ok(None);
+    };
+    Ok(latest_record_version(record)?.entry().to_app_option::<T>().ok().flatten())
+}
+
+/// Whether a care team member is `agent`; provider members are matched by
+/// who authored their provider profile
+fn care_team_member_is(member: &CareTeamMemberType, agent: &AgentPubKey) -> ExternResult<bool> {
+    Ok(match member {
+        CareTeamMemberType::Agent(member_agent) => member_agent == agent,
+        CareTeamMemberType::Provider(provider_hash) => get(provider_hash.clone(), GetOptions::default())?
+            .is_some_and(|profile| profile.action().author() == agent),
+        CareTeamMemberType::Organization(_) => false,
+    })
+}
+
+/// The caller's sent and received messages, newest first
+#[hdk_extern]
+pub fn get_my_messages(input: GetMyMessagesInput) -> ExternResult<PaginatedResult<DirectMessageView>> {
+    let pagination = input.pagination.unwrap_or_default();
+    pagination.validate()?;
+    let me = agent_info()?.agent_initial_pubkey;
+
+    let mut messages = Vec::new();
+    for view in linked_messages(me.clone(), LinkTypes::AgentToMessages, &me)? {
+        if input.patient_hash.as_ref().is_some_and(|patient| *patient != view.patient_hash) {
+            continue;
+        }
+        if input.unread_only && (view.recipient != me || view.read_at.is_some()) {
+            continue;
+        }
+        messages.push(view);
     }
+    messages.sort_by_key(|message| std::cmp::Reverse(message.sent_at));
 
-    Ok(created_hashes)
+    let total = messages.len();
+    let page = messages
+        .into_iter()
+        .skip(pagination.offset)
+        .take(pagination.limit)
+        .collect();
+    Ok(PaginatedResult::new(page, total, &pagination))
 }
 
-/// Create a care team from a template
+/// The caller's messages about a clinical record, oldest first
 #[hdk_extern]
-pub fn create_care_team_from_template(input: CreateCareTeamInput) -> ExternResult<Record> {
-    // Get the template
-    let template_record = get(input.template_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Template not found".to_string())))?;
+pub fn get_record_messages(record_hash: ActionHash) -> ExternResult<Vec<DirectMessageView>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut messages = linked_messages(record_hash, LinkTypes::RecordToMessages, &me)?;
+    messages.sort_by_key(|message| message.sent_at);
+    Ok(messages)
+}
 
-    let template: CareTeamTemplate = template_record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid template".to_string())))?;
+/// Record that the caller read a message sent to them; marking twice
+/// returns the first receipt
+#[hdk_extern]
+pub fn mark_message_read(message_hash: ActionHash) -> ExternResult<Record> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let message = get(message_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<DirectMessage>().ok().flatten())
+        .ok_or(not_found("Message not found"))?;
+    if message.recipient != me {
+        return Err(unauthorized("Only the recipient can mark a message read"));
+    }
 
-    // Calculate expiration
-    let expires_at = template.default_duration_days.map(|days| {
-        let now = sys_time().unwrap();
-        let duration_micros = (days as i64) * 24 * 60 * 60 * 1_000_000;
-        Timestamp::from_micros(now.as_micros() + duration_micros)
-    });
+    let receipts = get_links(
+        LinkQuery::try_new(message_hash.clone(), LinkTypes::MessageToReadReceipts)?,
+        GetStrategy::default(),
+    )?;
+    for link in receipts {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                return Ok(record);
+            }
+        }
+    }
 
-    // Create the care team
-    let care_team = CareTeam {
-        team_id: input.team_id,
-        patient_hash: input.patient_hash.clone(),
-        team_name: input.team_name.unwrap_or(template.name.clone()),
-        template_hash: Some(input.template_hash.clone()),
-        members: input.members,
-        permissions: template.permissions,
-        data_categories: template.data_categories,
-        exclusions: input.additional_exclusions.unwrap_or(template.default_exclusions),
-        purpose: template.purpose,
-        status: CareTeamStatus::Active,
-        created_at: sys_time()?,
-        expires_at,
-        notes: input.notes,
+    let receipt = MessageReadReceipt {
+        message_hash: message_hash.clone(),
+        reader: me,
+        read_at: sys_time()?,
     };
+    let receipt_hash = create_entry(&EntryTypes::MessageReadReceipt(receipt))?;
+    create_link(message_hash, receipt_hash.clone(), LinkTypes::MessageToReadReceipts, ())?;
+    get(receipt_hash, GetOptions::default())?
+        .ok_or(not_found("Could not find read receipt"))
+}
 
-    let team_hash = create_entry(&EntryTypes::CareTeam(care_team.clone()))?;
-    let record = get(team_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find care team".to_string())))?;
+/// Messages linked from `base` that `me` is party to, decrypted
+fn linked_messages(
+    base: impl Into<AnyLinkableHash>,
+    link_type: LinkTypes,
+    me: &AgentPubKey,
+) -> ExternResult<Vec<DirectMessageView>> {
+    let links = get_links(LinkQuery::try_new(base, link_type)?, GetStrategy::default())?;
+    let mut views = Vec::new();
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else { continue };
+        if views.iter().any(|view: &DirectMessageView| view.message_hash == hash) {
+            continue;
+        }
+        let Some(record) = get(hash, GetOptions::default())? else { continue };
+        if let Some(view) = direct_message_view(&record, me)? {
+            views.push(view);
+        }
+    }
+    Ok(views)
+}
 
-    // Link to patient
-    create_link(
-        input.patient_hash.clone(),
-        team_hash.clone(),
-        LinkTypes::PatientToCareTeams,
-        (),
-    )?;
+fn direct_message_view(record: &Record, me: &AgentPubKey) -> ExternResult<Option<DirectMessageView>> {
+    let Some(message) = record.entry().to_app_option::<DirectMessage>().ok().flatten() else {
+        return Ok(None);
+    };
+    let counterpart = if message.sender == *me {
+        message.recipient.clone()
+    } else if message.recipient == *me {
+        message.sender.clone()
+    } else {
+        return Ok(None);
+    };
+    let body = ed_25519_x_salsa20_poly1305_decrypt(me.clone(), counterpart, message.sealed_body.clone())?;
 
-    // Link to template
-    create_link(
-        input.template_hash,
-        team_hash.clone(),
-        LinkTypes::TemplateToTeams,
-        (),
-    )?;
+    let read_at = get_links(
+        LinkQuery::try_new(record.action_address().clone(), LinkTypes::MessageToReadReceipts)?,
+        GetStrategy::default(),
+    )?
+    .iter()
+    .filter(|link| link.author == message.recipient)
+    .map(|link| link.timestamp)
+    .min();
+
+    Ok(Some(DirectMessageView {
+        message_hash: record.action_address().clone(),
+        patient_hash: message.patient_hash,
+        sender: message.sender,
+        recipient: message.recipient,
+        context: message.context,
+        regarding: message.regarding,
+        in_reply_to: message.in_reply_to,
+        body: String::from_utf8_lossy(body.as_ref()).into_owned(),
+        sent_at: message.sent_at,
+        read_at,
+    }))
+}
 
-    // Link to active care teams
-    let active_anchor = hash_entry(&Anchor(format!("active_care_teams:{:?}", input.patient_hash)))?;
-    create_link(
-        active_anchor,
-        team_hash,
-        LinkTypes::ActiveCareTeams,
-        (),
-    )?;
+// ============================================================
+// NETWORK STATISTICS
+// ============================================================
 
-    Ok(record)
+const ACTIVE_CONSENTS_STAT: &str = "stats:active_consents";
+
+fn count_active_consents(delta: i64) -> ExternResult<()> {
+    increment_counter(anchor_hash(ACTIVE_CONSENTS_STAT)?, LinkTypes::StatisticsCounter, delta)?;
+    Ok(())
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CreateCareTeamInput {
-    pub team_id: String,
-    pub patient_hash: ActionHash,
-    pub template_hash: ActionHash,
-    pub team_name: Option<String>,
-    pub members: Vec<CareTeamMember>,
-    pub additional_exclusions: Option<Vec<DataCategory>>,
-    pub notes: Option<String>,
+/// Let accessors ask this agent to countersign access receipts, and make
+/// it an admin when the DNA properties name it as a bootstrap admin
+#[hdk_extern]
+pub fn init(_: ()) -> ExternResult<InitCallbackResult> {
+    let mut functions = HashSet::new();
+    functions.insert((zome_info()?.name, FunctionName::from("countersign_access_receipt")));
+    create_cap_grant(ZomeCallCapGrant {
+        tag: "access_receipts".to_string(),
+        access: CapAccess::Unrestricted,
+        functions: GrantedFunctions::Listed(functions),
+    })?;
+
+    let me = agent_info()?.agent_initial_pubkey;
+    if HealthDnaProperties::current()?.bootstrap_admin_keys().contains(&me)
+        && !admin_links()?.iter().any(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&me))
+    {
+        create_link(anchor_hash("system_admins")?, me, LinkTypes::SystemAdmins, admin_link_tag(None))?;
+    }
+    Ok(InitCallbackResult::Pass)
 }
 
-/// Get patient's care teams
+/// Grant the admin role to an agent (admin only)
 #[hdk_extern]
-pub fn get_patient_care_teams(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+pub fn grant_admin(agent: AgentPubKey) -> ExternResult<ActionHash> {
+    require_admin_authorization()?;
+    let links = admin_links()?;
+    if let Some(link) = links
+        .iter()
+        .find(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&agent))
+    {
+        return Ok(link.create_link_hash.clone());
+    }
+    create_admin_link(&links, agent)
+}
+
+/// Link `agent` as an admin, citing the caller's own admin link unless the
+/// caller is a bootstrap admin
+fn create_admin_link(links: &[Link], agent: AgentPubKey) -> ExternResult<ActionHash> {
+    let proof = my_admin_proof(links)?;
+    create_link(anchor_hash("system_admins")?, agent, LinkTypes::SystemAdmins, admin_link_tag(proof.as_ref()))
+}
+
+/// The caller's own admin link, cited as proof when they act as admin;
+/// bootstrap admins need none
+fn my_admin_proof(links: &[Link]) -> ExternResult<Option<ActionHash>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if HealthDnaProperties::current()?.bootstrap_admin_keys().contains(&me) {
+        return Ok(None);
+    }
+    Ok(links
+        .iter()
+        .find(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&me))
+        .map(|link| link.create_link_hash.clone()))
+}
+
+/// Revoke an agent's admin role (admin only)
+///
+/// The last admin and bootstrap admins cannot be revoked. Only the admin
+/// who granted the role, a bootstrap admin or the admin themselves may
+/// revoke it. Admins the revoked agent granted keep the role, re-granted
+/// by the caller.
+#[hdk_extern]
+pub fn revoke_admin(agent: AgentPubKey) -> ExternResult<()> {
+    require_admin_authorization()?;
+    let me = agent_info()?.agent_initial_pubkey;
+    let bootstrap = HealthDnaProperties::current()?.bootstrap_admin_keys();
+    let links = admin_links()?;
+    let (revoked, remaining): (Vec<Link>, Vec<Link>) = links
+        .iter()
+        .cloned()
+        .partition(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&agent));
+    if revoked.is_empty() {
+        return Err(invalid_input("Agent is not an admin"));
+    }
+    if remaining.is_empty() {
+        return Err(conflict("Cannot revoke the last admin"));
+    }
+    if bootstrap.contains(&agent) {
+        return Err(conflict("Bootstrap admins cannot be revoked"));
+    }
+    if !bootstrap.contains(&me) && me != agent && revoked.iter().any(|link| link.author != me) {
+        return Err(HealthError::Unauthorized(
+            "Only the granting admin, a bootstrap admin or the admin themselves can revoke an admin".to_string(),
+        )
+        .detail("agent", agent.to_string())
+        .into());
+    }
+
+    let admins_before = admin_agents(&links);
+    for link in revoked {
+        delete_link(link.create_link_hash, GetOptions::default())?;
+    }
+    let after = effective_admin_links(remaining, &bootstrap);
+    let admins_after = admin_agents(&after);
+    for orphaned in admins_before.into_iter().filter(|admin| *admin != agent && !admins_after.contains(admin)) {
+        create_admin_link(&after, orphaned)?;
+    }
+    Ok(())
+}
+
+fn admin_agents(links: &[Link]) -> Vec<AgentPubKey> {
+    let mut admins: Vec<AgentPubKey> = Vec::new();
+    for agent in links.iter().filter_map(|link| link.target.clone().into_agent_pub_key()) {
+        if !admins.contains(&agent) {
+            admins.push(agent);
+        }
+    }
+    admins
+}
+
+/// Agents holding the admin role
+#[hdk_extern]
+pub fn list_admins(_: ()) -> ExternResult<Vec<AgentPubKey>> {
+    Ok(admin_agents(&admin_links()?))
+}
+
+/// Check whether an agent holds the admin role
+#[hdk_extern]
+pub fn is_admin(agent: AgentPubKey) -> ExternResult<bool> {
+    Ok(admin_links()?
+        .into_iter()
+        .any(|link| link.target.into_agent_pub_key().as_ref() == Some(&agent)))
+}
+
+fn admin_links() -> ExternResult<Vec<Link>> {
     let links = get_links(
-        LinkQuery::try_new(patient_hash, LinkTypes::PatientToCareTeams)?,
-        GetStrategy::default()
+        LinkQuery::try_new(anchor_hash("system_admins")?, LinkTypes::SystemAdmins)?,
+        GetStrategy::default(),
     )?;
+    Ok(effective_admin_links(links, &HealthDnaProperties::current()?.bootstrap_admin_keys()))
+}
 
-    let mut teams = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                teams.push(record);
-            }
+/// The admin links currently in force
+///
+/// A link counts when a bootstrap admin wrote it, or when the admin link
+/// it was granted under still counts. Revoking an admin therefore also
+/// drops the links that admin wrote, and a revoked admin cannot re-enter
+/// through an old proof.
+fn effective_admin_links(links: Vec<Link>, bootstrap: &[AgentPubKey]) -> Vec<Link> {
+    let (mut effective, mut pending): (Vec<Link>, Vec<Link>) =
+        links.into_iter().partition(|link| bootstrap.contains(&link.author));
+    loop {
+        let (granted, rest): (Vec<Link>, Vec<Link>) = pending.into_iter().partition(|link| {
+            admin_link_proof(&link.tag)
+                .is_some_and(|proof| effective.iter().any(|admin| admin.create_link_hash == proof))
+        });
+        pending = rest;
+        if granted.is_empty() {
+            return effective;
         }
+        effective.extend(granted);
     }
+}
 
-    Ok(teams)
+
+// ============================================================
+// ROLE ASSIGNMENTS
+// ============================================================
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AssignRoleInput {
+    pub agent: AgentPubKey,
+    pub role: Role,
+    pub expires_at: Option<Timestamp>,
+    pub reason: String,
 }
 
-/// Get active care teams for a patient
+/// Assign an access control role to an agent (admin only)
 #[hdk_extern]
-pub fn get_active_care_teams(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let all_teams = get_patient_care_teams(patient_hash)?;
+pub fn assign_role(input: AssignRoleInput) -> ExternResult<Record> {
+    require_admin_authorization()?;
+    let now = sys_time()?;
+    if role_assignments_for(&input.agent)?
+        .iter()
+        .any(|(_, assignment)| assignment.role == input.role && assignment.is_active(now))
+    {
+        return Err(conflict("Agent already holds this role"));
+    }
 
-    let active: Vec<Record> = all_teams
+    let hash = create_entry(&EntryTypes::RoleAssignment(RoleAssignment {
+        agent: input.agent.clone(),
+        role: input.role,
+        assigned_by: agent_info()?.agent_initial_pubkey,
+        assigned_at: now,
+        expires_at: input.expires_at,
+        reason: input.reason,
+        revoked_at: None,
+        admin_link: my_admin_proof(&admin_links()?)?,
+    }))?;
+    create_link(input.agent, hash.clone(), LinkTypes::AgentToRoleAssignments, ())?;
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find role assignment"))
+}
+
+/// Revoke an agent's active assignments of a role (admin only)
+#[hdk_extern]
+pub fn revoke_role(input: RoleCheck) -> ExternResult<Vec<Record>> {
+    require_admin_authorization()?;
+    let now = sys_time()?;
+    let admin_link = my_admin_proof(&admin_links()?)?;
+    let mut revoked = Vec::new();
+    for (hash, assignment) in role_assignments_for(&input.agent)? {
+        if assignment.role != input.role || !assignment.is_active(now) {
+            continue;
+        }
+        let (latest_hash, _) = latest_role_assignment(&hash)?;
+        let updated_hash = update_entry(latest_hash, &RoleAssignment {
+            revoked_at: Some(now),
+            admin_link: admin_link.clone(),
+            ..assignment
+        })?;
+        create_link(hash, updated_hash.clone(), LinkTypes::RoleAssignmentUpdates, ())?;
+        if let Some(record) = get(updated_hash, GetOptions::default())? {
+            revoked.push(record);
+        }
+    }
+    if revoked.is_empty() {
+        return Err(invalid_input("Agent does not hold this role"));
+    }
+    Ok(revoked)
+}
+
+/// An agent's role assignments at their latest version, revoked ones included
+#[hdk_extern]
+pub fn get_agent_roles(agent: AgentPubKey) -> ExternResult<Vec<(ActionHash, RoleAssignment)>> {
+    role_assignments_for(&agent)
+}
+
+/// Check whether an agent holds a role; `Role::Admin` reads the admin links
+///
+/// An assignment only counts while the admin who made it still holds the
+/// admin role.
+#[hdk_extern]
+pub fn has_role(input: RoleCheck) -> ExternResult<bool> {
+    if input.role == Role::Admin {
+        return is_admin(input.agent);
+    }
+    let now = sys_time()?;
+    let assignments: Vec<RoleAssignment> = role_assignments_for(&input.agent)?
         .into_iter()
-        .filter(|record| {
-            if let Some(team) = record.entry().to_app_option::<CareTeam>().ok().flatten() {
-                matches!(team.status, CareTeamStatus::Active)
-            } else {
-                false
-            }
-        })
+        .map(|(_, assignment)| assignment)
+        .filter(|assignment| assignment.role == input.role && assignment.is_active(now))
         .collect();
+    if assignments.is_empty() {
+        return Ok(false);
+    }
+    let admins = admin_agents(&admin_links()?);
+    Ok(assignments
+        .iter()
+        .any(|assignment| assignment.agent != assignment.assigned_by && admins.contains(&assignment.assigned_by)))
+}
 
-    Ok(active)
+/// Latest version of a role assignment (revocation is the only update)
+fn latest_role_assignment(assignment_hash: &ActionHash) -> ExternResult<(ActionHash, RoleAssignment)> {
+    let latest = get_links(
+        LinkQuery::try_new(assignment_hash.clone(), LinkTypes::RoleAssignmentUpdates)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash())
+    .unwrap_or_else(|| assignment_hash.clone());
+    let assignment = get(latest.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<RoleAssignment>().ok().flatten())
+        .ok_or(not_found("Role assignment not found"))?;
+    Ok((latest, assignment))
 }
 
-/// Add member to care team
+/// Every assignment for an agent, keyed by its original action
+fn role_assignments_for(agent: &AgentPubKey) -> ExternResult<Vec<(ActionHash, RoleAssignment)>> {
+    get_links(
+        LinkQuery::try_new(agent.clone(), LinkTypes::AgentToRoleAssignments)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .map(|hash| Ok((hash.clone(), latest_role_assignment(&hash)?.1)))
+    .collect()
+}
+
+/// Register an agent as a network operator (admin only)
 #[hdk_extern]
-pub fn add_care_team_member(input: AddMemberInput) -> ExternResult<Record> {
-    let record = get(input.team_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Care team not found".to_string())))?;
+pub fn register_operator(agent: AgentPubKey) -> ExternResult<ActionHash> {
+    require_admin_authorization()?;
+    let proof = my_admin_proof(&admin_links()?)?;
+    create_link(
+        anchor_hash("system_operators")?,
+        agent,
+        LinkTypes::SystemOperators,
+        admin_link_tag(proof.as_ref()),
+    )
+}
 
-    let mut team: CareTeam = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid care team".to_string())))?;
+/// Check whether an agent holds the operator role
+///
+/// A registration only counts while the admin who wrote it still holds
+/// the admin role.
+#[hdk_extern]
+pub fn is_operator(agent: AgentPubKey) -> ExternResult<bool> {
+    Ok(operator_links()?
+        .iter()
+        .any(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&agent)))
+}
 
-    team.members.push(input.member);
+/// Agents currently holding the operator role
+fn operator_agents() -> ExternResult<Vec<AgentPubKey>> {
+    Ok(operator_links()?
+        .into_iter()
+        .filter_map(|link| link.target.into_agent_pub_key())
+        .collect())
+}
 
-    let updated_hash = update_entry(input.team_hash, &team)?;
+/// Operator registrations written by current admins
+fn operator_links() -> ExternResult<Vec<Link>> {
+    let links = get_links(
+        LinkQuery::try_new(anchor_hash("system_operators")?, LinkTypes::SystemOperators)?,
+        GetStrategy::default(),
+    )?;
+    if links.is_empty() {
+        return Ok(links);
+    }
+    let admins = admin_agents(&admin_links()?);
+    Ok(links.into_iter().filter(|link| admins.contains(&link.author)).collect())
+}
 
-    get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated care team".to_string())))
+/// The operator link validation accepts for the calling agent's policies
+fn my_operator_proof() -> ExternResult<Option<ActionHash>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    match operator_links()?
+        .into_iter()
+        .find(|link| link.target.clone().into_agent_pub_key().as_ref() == Some(&me))
+    {
+        Some(link) => Ok(Some(link.create_link_hash)),
+        None => Err(unauthorized("The caller is not a registered operator")),
+    }
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct AddMemberInput {
-    pub team_hash: ActionHash,
-    pub member: CareTeamMember,
+/// Network-wide figures for operators
+///
+/// Patient and ingest figures come from the patient and fhir_bridge zomes;
+/// they are `None` when that zome is not part of this DNA.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct NetworkStatistics {
+    pub generated_at: Timestamp,
+    pub total_patients: Option<u64>,
+    pub active_consents: u64,
+    pub ingests_by_source: Vec<SourceIngestStatistics>,
+    pub ingests_overall: Vec<IngestWindowStatistics>,
+    pub storage_growth: Option<StorageGrowthEstimate>,
 }
 
-/// Remove member from care team
+/// Aggregate network health figures from the statistics counters (operators only)
 #[hdk_extern]
-pub fn remove_care_team_member(input: RemoveMemberInput) -> ExternResult<Record> {
-    let record = get(input.team_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Care team not found".to_string())))?;
-
-    let mut team: CareTeam = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid care team".to_string())))?;
-
-    // Mark member as inactive instead of removing (for audit trail)
-    for member in &mut team.members {
-        match (&member.member, &input.member) {
-            (CareTeamMemberType::Provider(h1), CareTeamMemberType::Provider(h2)) if h1 == h2 => {
-                member.active = false;
-            }
-            (CareTeamMemberType::Agent(a1), CareTeamMemberType::Agent(a2)) if a1 == a2 => {
-                member.active = false;
-            }
-            (CareTeamMemberType::Organization(o1), CareTeamMemberType::Organization(o2)) if o1 == o2 => {
-                member.active = false;
-            }
-            _ => {}
-        }
+pub fn get_network_statistics(_: ()) -> ExternResult<NetworkStatistics> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller)? {
+        return Err(unauthorized("Network statistics require the operator role"));
     }
 
-    let updated_hash = update_entry(input.team_hash, &team)?;
+    let active_consents = read_counter(anchor_hash(ACTIVE_CONSENTS_STAT)?, LinkTypes::StatisticsCounter)?.max(0) as u64;
+    let total_patients: Option<u64> = statistics_call("patient", "get_patient_count");
+    let ingests: Option<IngestStatistics> = statistics_call("fhir_bridge", "get_ingest_statistics");
+
+    let (ingests_by_source, ingests_overall, storage_growth) = match ingests {
+        Some(stats) => (stats.sources, stats.overall, Some(stats.storage_growth)),
+        None => (Vec::new(), Vec::new(), None),
+    };
 
-    get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated care team".to_string())))
+    Ok(NetworkStatistics {
+        generated_at: sys_time()?,
+        total_patients,
+        active_consents,
+        ingests_by_source,
+        ingests_overall,
+        storage_growth,
+    })
 }
 
+/// Input for a storage report
 #[derive(Serialize, Deserialize, Debug)]
-pub struct RemoveMemberInput {
-    pub team_hash: ActionHash,
-    pub member: CareTeamMemberType,
+pub struct StorageReportInput {
+    /// Days of recent writes to draw growth rates from (default 30, at most 90)
+    pub window_days: Option<u32>,
 }
 
-/// Dissolve a care team
+/// Link counts, tombstones and write rates for this zome's global anchors
+/// and entry types, with a 30-day growth projection (operators only)
 #[hdk_extern]
-pub fn dissolve_care_team(team_hash: ActionHash) -> ExternResult<Record> {
-    let record = get(team_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Care team not found".to_string())))?;
-
-    let mut team: CareTeam = record
-        .entry()
-        .to_app_option()
-        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid care team".to_string())))?;
-
-    team.status = CareTeamStatus::Dissolved;
+pub fn get_storage_report(input: StorageReportInput) -> ExternResult<StorageReport> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller)? {
+        return Err(unauthorized("Storage reports require the operator role"));
+    }
 
-    let updated_hash = update_entry(team_hash, &team)?;
+    let window_days = input.window_days.unwrap_or(30).clamp(1, 90);
+    let now = sys_time()?;
+    let since = Timestamp::from_micros(now.as_micros() - i64::from(window_days) * 86_400_000_000);
+
+    let anchors = vec![
+        sample_anchor_links("active_consents", LinkTypes::ActiveConsents, since)?,
+        sample_anchor_links("revoked_consents", LinkTypes::RevokedConsents, since)?,
+        sample_anchor_links("pending_guardian_consents", LinkTypes::PendingGuardianConsents, since)?,
+        sample_anchor_links("active_delegations", LinkTypes::ActiveDelegations, since)?,
+        sample_anchor_links(DENIED_ACCESS_ANCHOR, LinkTypes::PatientToAccessLogs, since)?,
+        sample_anchor_links(&period_anchor(DENIED_ACCESS_ANCHOR, now, AnchorPeriod::Day), LinkTypes::PatientToAccessLogs, since)?,
+        sample_anchor_links("emergency_override_events", LinkTypes::EmergencyOverrideEvents, since)?,
+        sample_anchor_links("security_alerts", LinkTypes::SecurityAlerts, since)?,
+        sample_anchor_links("system_templates", LinkTypes::SystemTemplates, since)?,
+        sample_anchor_links("organization_templates", LinkTypes::OrganizationTemplates, since)?,
+        sample_anchor_links("system_auditors", LinkTypes::SystemAuditors, since)?,
+        sample_anchor_links("system_operators", LinkTypes::SystemOperators, since)?,
+        // Counter shards are rewritten on every change, so this one shows
+        // how fast tombstones pile up
+        sample_anchor_links(ACTIVE_CONSENTS_STAT, LinkTypes::StatisticsCounter, since)?,
+    ];
+    let entry_types = recent_entry_footprints::<UnitEntryTypes>(since)?;
 
-    get(updated_hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated care team".to_string())))
+    Ok(StorageReport::new("consent", now, window_days, anchors, entry_types))
 }
 
-/// Check if a member has care team authorization
+// ============================================================
+// PERFORMANCE METRICS
+// ============================================================
+
+/// Store a window of extern timings flushed by a zome's instrumentation
+///
+/// Not instrumented itself, so a flush never triggers another.
 #[hdk_extern]
-pub fn check_care_team_authorization(input: CareTeamAuthInput) -> ExternResult<CareTeamAuthResult> {
-    let teams = get_active_care_teams(input.patient_hash.clone())?;
+pub fn record_performance_sample(sample: PerformanceSampleInput) -> ExternResult<ActionHash> {
+    let sample_hash = create_entry(&EntryTypes::PerformanceSample(PerformanceSample {
+        window_start: sample.window_start,
+        window_end: sample.window_end,
+        externs: sample.externs,
+        recorded_by: agent_info()?.agent_initial_pubkey,
+    }))?;
+    create_link(
+        anchor_hash("performance_samples")?,
+        sample_hash.clone(),
+        LinkTypes::PerformanceSamples,
+        (),
+    )?;
+    Ok(sample_hash)
+}
 
-    for team_record in teams {
-        if let Some(team) = team_record.entry().to_app_option::<CareTeam>().ok().flatten() {
-            // Check if member is in this team
-            for member in &team.members {
-                if !member.active {
-                    continue;
-                }
+/// Input for aggregated performance metrics
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PerformanceMetricsInput {
+    /// Only externs of this zome
+    pub zome: Option<String>,
+    /// Hours of samples to aggregate (default 24, at most 720)
+    pub since_hours: Option<u32>,
+}
 
-                let is_member = match (&member.member, &input.member) {
-                    (CareTeamMemberType::Provider(h1), CareTeamMemberType::Provider(h2)) => h1 == h2,
-                    (CareTeamMemberType::Agent(a1), CareTeamMemberType::Agent(a2)) => a1 == a2,
-                    (CareTeamMemberType::Organization(o1), CareTeamMemberType::Organization(o2)) => o1 == o2,
-                    _ => false,
-                };
+/// Per-extern figures aggregated across flushed samples
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PerformanceReport {
+    pub generated_at: Timestamp,
+    pub since: Timestamp,
+    /// Samples the figures are drawn from
+    pub samples: u32,
+    /// Most total time first
+    pub externs: Vec<ExternMetrics>,
+}
 
-                if is_member {
-                    // Check permissions
-                    let permission_granted = team.permissions.contains(&input.permission);
+/// Call counts, durations, DHT reads and error rates per zome and
+/// function (operators only)
+#[hdk_extern]
+pub fn get_performance_metrics(input: PerformanceMetricsInput) -> ExternResult<PerformanceReport> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    if !is_operator(caller)? {
+        return Err(unauthorized("Performance metrics require the operator role"));
+    }
 
-                    // Check data category
-                    let category_covered = team.data_categories.iter().any(|cat| {
-                        matches!(cat, DataCategory::All) || *cat == input.data_category
-                    });
+    let hours = input.since_hours.unwrap_or(24).clamp(1, 720);
+    let now = sys_time()?;
+    let since = Timestamp::from_micros(now.as_micros() - i64::from(hours) * 3_600_000_000);
+    let links = get_links(
+        LinkQuery::try_new(anchor_hash("performance_samples")?, LinkTypes::PerformanceSamples)?,
+        GetStrategy::default(),
+    )?;
+    let samples: Vec<PerformanceSample> = links
+        .into_iter()
+        .filter(|link| link.timestamp >= since)
+        .filter_map(|link| link.target.into_action_hash())
+        .filter_map(|hash| get(hash, GetOptions::default()).ok().flatten())
+        .filter_map(|record| record.entry().to_app_option::<PerformanceSample>().ok().flatten())
+        .collect();
 
-                    // Check not excluded
-                    let not_excluded = !team.exclusions.contains(&input.data_category);
+    let timings = samples
+        .iter()
+        .flat_map(|sample| sample.externs.iter())
+        .filter(|timing| input.zome.as_ref().is_none_or(|zome| timing.zome == *zome));
+    Ok(PerformanceReport {
+        generated_at: now,
+        since,
+        samples: samples.len() as u32,
+        externs: aggregate_timings(timings),
+    })
+}
 
-                    if permission_granted && category_covered && not_excluded {
-                        return Ok(CareTeamAuthResult {
-                            authorized: true,
-                            care_team_hash: Some(team_record.action_address().clone()),
-                            team_name: team.team_name.clone(),
-                            member_role: member.role.clone(),
-                            reason: "Active care team membership".to_string(),
-                        });
-                    }
-                }
-            }
-        }
+/// Best-effort call to another zome's statistics extern
+fn statistics_call<T: serde::de::DeserializeOwned + std::fmt::Debug>(zome: &str, function: &str) -> Option<T> {
+    match call(CallTargetCell::Local, zome, function.into(), None, ()) {
+        Ok(ZomeCallResponse::Ok(extern_io)) => extern_io.decode().ok(),
+        _ => None,
     }
-
-    Ok(CareTeamAuthResult {
-        authorized: false,
-        care_team_hash: None,
-        team_name: String::new(),
-        member_role: CareTeamRole::Other("None".to_string()),
-        reason: "Not a member of any authorized care team".to_string(),
-    })
 }
 
+// ============================================================
+// PRIVACY BUDGETS
+// ============================================================
+
+/// How long a reservation holds budget unless the caller asks otherwise
+const DEFAULT_BUDGET_HOLD_MINUTES: u32 = 15;
+const MAX_BUDGET_HOLD_MINUTES: u32 = 24 * 60;
+
+/// Input for opening a privacy budget
 #[derive(Serialize, Deserialize, Debug)]
-pub struct CareTeamAuthInput {
+pub struct CreatePrivacyBudgetInput {
     pub patient_hash: ActionHash,
-    pub member: CareTeamMemberType,
-    pub permission: DataPermission,
-    pub data_category: DataCategory,
+    pub dataset_id: String,
+    /// Total ε the patient allows across all queries at `delta`
+    pub epsilon_limit: f64,
+    pub delta: f64,
 }
 
-#[derive(Serialize, Deserialize, Debug)]
-pub struct CareTeamAuthResult {
-    pub authorized: bool,
-    pub care_team_hash: Option<ActionHash>,
-    pub team_name: String,
-    pub member_role: CareTeamRole,
-    pub reason: String,
+/// Identifies one patient's budget for one dataset
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PrivacyBudgetKey {
+    pub patient_hash: ActionHash,
+    pub dataset_id: String,
 }
 
-// ==================== ZK PROOF AUDIT LOGGING ====================
-// Integration with zkhealth zome for HIPAA-compliant audit trails
-
-/// Input from zkhealth zome for proof generation audit
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ZkProofAuditLog {
-    pub log_id: String,
+/// Input for holding budget before an aggregate query runs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReservePrivacyBudgetInput {
     pub patient_hash: ActionHash,
-    pub proof_id: String,
-    pub proof_type: String,
-    pub data_categories_used: Vec<String>,
-    pub verifier_hint: Option<String>,
-    pub generated_at: i64,
+    pub dataset_id: String,
+    pub cost: PrivacyCost,
     pub purpose: String,
+    /// Minutes to hold the budget (default 15, at most a day)
+    pub hold_minutes: Option<u32>,
 }
 
-/// Input from zkhealth zome for verification audit
-#[derive(Serialize, Deserialize, Debug, Clone)]
-pub struct ZkVerificationAuditLog {
-    pub log_id: String,
+/// Identifies a reservation on a budget
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BudgetReservationRef {
     pub patient_hash: ActionHash,
-    pub proof_id: String,
-    pub verifier: AgentPubKey,
-    pub verification_result: bool,
-    pub verified_at: i64,
+    pub dataset_id: String,
+    pub reservation_id: u64,
 }
 
-/// Log ZK proof generation event (called by zkhealth zome)
-/// Creates an audit record of data accessed during proof generation
-#[hdk_extern]
-pub fn log_zk_proof_generation(input: ZkProofAuditLog) -> ExternResult<Record> {
-    // Convert string categories to DataCategory enum
-    let data_categories: Vec<DataCategory> = input.data_categories_used
-        .iter()
-        .map(|cat| string_to_data_category(cat))
-        .collect();
-
-    // Create audit log entry
-    let log = DataAccessLog {
-        log_id: input.log_id,
-        patient_hash: input.patient_hash.clone(),
-        accessor: agent_info()?.agent_initial_pubkey, // Self-access for proof generation
-        access_type: DataPermission::Read, // Proof generation reads data
-        data_categories_accessed: data_categories,
-        consent_hash: None, // Self-access doesn't require consent
-        access_reason: format!("ZK Proof Generation: {} (Proof ID: {})", input.proof_type, input.proof_id),
-        accessed_at: Timestamp::from_micros(input.generated_at),
-        access_location: Some("zkhealth-zome".to_string()),
-        emergency_override: false,
-        override_reason: None,
-    };
+/// Spend on a budget converted to (ε, δ)
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrivacyBudgetStatus {
+    /// Original ledger action
+    pub ledger_hash: ActionHash,
+    pub epsilon_limit: f64,
+    pub delta: f64,
+    pub epsilon_committed: f64,
+    /// Committed spend plus budget held by live reservations
+    pub epsilon_held: f64,
+    pub epsilon_remaining: f64,
+    /// RDP order giving the tightest bound on the held spend
+    pub optimal_order: f64,
+    pub committed_queries: u32,
+    pub active_reservations: Vec<BudgetReservation>,
+}
 
-    let log_hash = create_entry(&EntryTypes::DataAccessLog(log.clone()))?;
-    let record = get(log_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find audit log".to_string())))?;
+fn budget_error(e: BudgetError) -> WasmError {
+    match e {
+        BudgetError::Exhausted { required, remaining } => HealthError::LimitExceeded(e.to_string())
+            .detail("required", required)
+            .detail("remaining", remaining)
+            .into(),
+        BudgetError::InvalidParameter(_) => invalid_input(e.to_string()),
+        BudgetError::OperationFailed(_) => internal_error(e),
+    }
+}
 
-    // Link to patient's audit logs
+/// Open a Rényi DP budget for one of the caller's datasets
+#[hdk_extern]
+pub fn create_privacy_budget(input: CreatePrivacyBudgetInput) -> ExternResult<Record> {
+    require_patient_self(&input.patient_hash)?;
+    if find_privacy_budget(&input.patient_hash, &input.dataset_id)?.is_some() {
+        return Err(conflict("A privacy budget already exists for this dataset"));
+    }
+    let accountant = RdpAccountant::new();
+    let hash = create_entry(&EntryTypes::PrivacyBudgetLedger(PrivacyBudgetLedger {
+        patient_hash: input.patient_hash.clone(),
+        dataset_id: input.dataset_id.clone(),
+        epsilon_limit: input.epsilon_limit,
+        delta: input.delta,
+        orders: accountant.orders().to_vec(),
+        committed_rdp: accountant.rdp().to_vec(),
+        reservations: Vec::new(),
+        committed_queries: 0,
+        next_reservation_id: 0,
+        created_by: agent_info()?.agent_initial_pubkey,
+        updated_at: sys_time()?,
+    }))?;
     create_link(
         input.patient_hash,
-        log_hash,
-        LinkTypes::PatientToAccessLogs,
-        (),
+        hash.clone(),
+        LinkTypes::PatientToPrivacyBudgets,
+        LinkTag::new(input.dataset_id.into_bytes()),
     )?;
-
-    Ok(record)
+    get(hash, GetOptions::default())?
+        .ok_or(not_found("Could not find privacy budget"))
 }
 
-/// Log ZK proof verification event (called by zkhealth zome)
-/// Creates an audit record of a third party verifying a patient's proof
+/// Hold budget for an aggregate query before it runs (researchers only)
+///
+/// Fails when the query would take the patient past their limit; run the
+/// query only once this succeeds, then commit or release the reservation.
 #[hdk_extern]
-pub fn log_zk_proof_verification(input: ZkVerificationAuditLog) -> ExternResult<Record> {
-    // Create audit log entry - verification doesn't access categories, just verifies
-    let log = DataAccessLog {
-        log_id: input.log_id,
-        patient_hash: input.patient_hash.clone(),
-        accessor: input.verifier.clone(),
-        access_type: DataPermission::Read, // Verification is a form of read
-        data_categories_accessed: vec![], // No actual data accessed during verification
-        consent_hash: None, // ZK proofs don't require consent to verify
-        access_reason: format!(
-            "ZK Proof Verification: {} (Result: {})",
-            input.proof_id,
-            if input.verification_result { "Verified" } else { "Failed" }
-        ),
-        accessed_at: Timestamp::from_micros(input.verified_at),
-        access_location: Some("zkhealth-verification".to_string()),
-        emergency_override: false,
-        override_reason: None,
-    };
+pub fn reserve_privacy_budget(input: ReservePrivacyBudgetInput) -> ExternResult<BudgetReservation> {
+    let me = require_budget_reserver()?;
+    let now = sys_time()?;
+    hold_privacy_budget(
+        &me,
+        &input.patient_hash,
+        &input.dataset_id,
+        input.cost,
+        &input.purpose,
+        input.hold_minutes,
+        now,
+    )
+}
 
-    let log_hash = create_entry(&EntryTypes::DataAccessLog(log.clone()))?;
-    let record = get(log_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find audit log".to_string())))?;
+/// Record a reserved query as run, moving its cost into the committed spend
+///
+/// A lapsed reservation still commits while the budget covers it, since
+/// the query's privacy cost was paid either way.
+#[hdk_extern]
+pub fn commit_privacy_budget(input: BudgetReservationRef) -> ExternResult<PrivacyBudgetStatus> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let (original, ledger) = settle_privacy_budget(&me, &input.patient_hash, &input.dataset_id, input.reservation_id, true, now)?;
+    privacy_budget_status(original, &ledger, now)
+}
 
-    // Link to patient's audit logs
-    create_link(
-        input.patient_hash,
-        log_hash,
-        LinkTypes::PatientToAccessLogs,
-        (),
-    )?;
+/// Give back a reservation whose query did not run (its holder or the patient)
+#[hdk_extern]
+pub fn release_privacy_budget(input: BudgetReservationRef) -> ExternResult<PrivacyBudgetStatus> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let (original, ledger) = settle_privacy_budget(&me, &input.patient_hash, &input.dataset_id, input.reservation_id, false, now)?;
+    privacy_budget_status(original, &ledger, now)
+}
 
-    Ok(record)
+/// Hold budget on every patient an aggregate query reads (researchers only)
+///
+/// Called through `dp_core::spend_budget`. One exhausted or missing budget
+/// fails the whole call, so no patient is charged for a query that cannot run.
+#[hdk_extern]
+pub fn reserve_cohort_budget(input: BudgetSpend) -> ExternResult<CohortReservation> {
+    let me = require_budget_reserver()?;
+    let now = sys_time()?;
+    let mut reservations = Vec::new();
+    let mut seen = HashSet::new();
+    for patient_hash in input.patient_hashes.iter().filter(|hash| seen.insert(*hash)) {
+        let reservation = hold_privacy_budget(&me, patient_hash, &input.dataset_id, input.cost, &input.purpose, None, now)
+            .map_err(|e| error_with_detail(e, "patient_hash", patient_hash))?;
+        reservations.push((patient_hash.clone(), reservation.reservation_id));
+    }
+    Ok(CohortReservation {
+        dataset_id: input.dataset_id,
+        reservations,
+    })
 }
 
-/// Convert string data category to DataCategory enum
-fn string_to_data_category(cat: &str) -> DataCategory {
-    match cat {
-        "VitalSigns" => DataCategory::VitalSigns,
-        "Allergies" => DataCategory::Allergies,
-        "Medications" => DataCategory::Medications,
-        "Diagnoses" | "Conditions" => DataCategory::Diagnoses,
-        "LabResults" | "Labs" => DataCategory::LabResults,
-        "Immunizations" => DataCategory::Immunizations,
-        "Procedures" => DataCategory::Procedures,
-        "Imaging" | "ImagingStudies" => DataCategory::ImagingStudies,
-        "MentalHealth" => DataCategory::MentalHealth,
-        "Demographics" => DataCategory::Demographics,
-        "SubstanceAbuse" => DataCategory::SubstanceAbuse,
-        "SexualHealth" => DataCategory::SexualHealth,
-        "GeneticData" => DataCategory::GeneticData,
-        "FinancialData" | "Insurance" => DataCategory::FinancialData,
-        "All" | _ => DataCategory::All, // Default unknown categories to All for audit completeness
+/// Commit every reservation held for a cohort query that ran
+#[hdk_extern]
+pub fn commit_cohort_budget(input: CohortReservation) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    for (patient_hash, reservation_id) in &input.reservations {
+        settle_privacy_budget(&me, patient_hash, &input.dataset_id, *reservation_id, true, now)?;
     }
+    Ok(())
 }
 
-/// Get all ZK proof audit logs for a patient
+/// Release every reservation held for a cohort query that did not run
 #[hdk_extern]
-pub fn get_zk_proof_audit_logs(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
-    let links = get_links(
-        LinkQuery::try_new(patient_hash, LinkTypes::PatientToAccessLogs)?,
-        GetStrategy::default(),
-    )?;
+pub fn release_cohort_budget(input: CohortReservation) -> ExternResult<()> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    for (patient_hash, reservation_id) in &input.reservations {
+        settle_privacy_budget(&me, patient_hash, &input.dataset_id, *reservation_id, false, now)?;
+    }
+    Ok(())
+}
 
-    let mut zk_logs = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                // Filter for ZK proof logs
-                if let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() {
-                    if log.access_reason.starts_with("ZK Proof") {
-                        zk_logs.push(record);
-                    }
-                }
-            }
+fn require_budget_reserver() -> ExternResult<AgentPubKey> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if !has_role(RoleCheck { agent: me.clone(), role: Role::Researcher })? {
+        return Err(unauthorized("Only researchers can reserve privacy budget"));
+    }
+    Ok(me)
+}
+
+fn hold_privacy_budget(
+    me: &AgentPubKey,
+    patient_hash: &ActionHash,
+    dataset_id: &str,
+    cost: PrivacyCost,
+    purpose: &str,
+    hold_minutes: Option<u32>,
+    now: Timestamp,
+) -> ExternResult<BudgetReservation> {
+    if purpose.trim().is_empty() {
+        return Err(invalid_input("Reservations need a purpose"));
+    }
+    let (original, latest, mut ledger) = require_privacy_budget(patient_hash, dataset_id)?;
+    ledger.reservations.retain(|reservation| reservation.expires_at > now);
+
+    let held = ledger.with_reservations(now).map_err(budget_error)?;
+    let held_epsilon = held.to_epsilon(ledger.delta).map_err(budget_error)?.0;
+    let epsilon = held.epsilon_with(&cost, ledger.delta).map_err(budget_error)?;
+    if epsilon > ledger.epsilon_limit {
+        return Err(budget_error(BudgetError::Exhausted {
+            required: epsilon - held_epsilon,
+            remaining: (ledger.epsilon_limit - held_epsilon).max(0.0),
+        }));
+    }
+
+    let hold = hold_minutes
+        .unwrap_or(DEFAULT_BUDGET_HOLD_MINUTES)
+        .clamp(1, MAX_BUDGET_HOLD_MINUTES);
+    let reservation = BudgetReservation {
+        reservation_id: ledger.next_reservation_id,
+        agent: me.clone(),
+        cost,
+        purpose: purpose.to_string(),
+        reserved_at: now,
+        expires_at: Timestamp::from_micros(now.as_micros() + i64::from(hold) * 60_000_000),
+    };
+    ledger.next_reservation_id += 1;
+    ledger.reservations.push(reservation.clone());
+    ledger.updated_at = now;
+    write_privacy_budget(&original, latest, &ledger)?;
+    Ok(reservation)
+}
+
+/// Commit or release a reservation, returning the original hash and the
+/// ledger as written
+///
+/// Only the holder commits; the holder or the patient may release.
+fn settle_privacy_budget(
+    me: &AgentPubKey,
+    patient_hash: &ActionHash,
+    dataset_id: &str,
+    reservation_id: u64,
+    commit: bool,
+    now: Timestamp,
+) -> ExternResult<(ActionHash, PrivacyBudgetLedger)> {
+    let (original, latest, mut ledger) = require_privacy_budget(patient_hash, dataset_id)?;
+    let position = ledger
+        .reservations
+        .iter()
+        .position(|r| r.reservation_id == reservation_id)
+        .ok_or(not_found("No such reservation"))?;
+    if ledger.reservations[position].agent != *me {
+        if commit {
+            return Err(unauthorized("Only the reservation's holder can commit it"));
+        }
+        require_patient_self(patient_hash)?;
+    }
+    let reservation = ledger.reservations.remove(position);
+
+    if commit {
+        let mut committed = ledger.committed().map_err(budget_error)?;
+        committed.compose(&reservation.cost, 1).map_err(budget_error)?;
+        ledger.committed_rdp = committed.rdp().to_vec();
+        ledger.committed_queries += 1;
+        let held_epsilon = ledger
+            .with_reservations(now)
+            .and_then(|held| held.to_epsilon(ledger.delta))
+            .map_err(budget_error)?
+            .0;
+        if held_epsilon > ledger.epsilon_limit {
+            return Err(limit_exceeded("Reservation lapsed and the budget no longer covers it"));
         }
     }
+    ledger.updated_at = now;
+    write_privacy_budget(&original, latest, &ledger)?;
+    Ok((original, ledger))
+}
 
-    Ok(zk_logs)
+/// Budget spent and remaining on a patient's dataset (the patient or researchers)
+#[hdk_extern]
+pub fn get_privacy_budget(input: PrivacyBudgetKey) -> ExternResult<Option<PrivacyBudgetStatus>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    if !has_role(RoleCheck { agent: me, role: Role::Researcher })? {
+        require_patient_self(&input.patient_hash)?;
+    }
+    let Some((original, _, ledger)) = find_privacy_budget(&input.patient_hash, &input.dataset_id)? else {
+        return Ok(None);
+    };
+    privacy_budget_status(original, &ledger, sys_time()?).map(Some)
+}
+
+fn privacy_budget_status(
+    ledger_hash: ActionHash,
+    ledger: &PrivacyBudgetLedger,
+    now: Timestamp,
+) -> ExternResult<PrivacyBudgetStatus> {
+    let epsilon_committed = ledger
+        .committed()
+        .and_then(|committed| committed.to_epsilon(ledger.delta))
+        .map_err(budget_error)?
+        .0;
+    let (epsilon_held, optimal_order) = ledger
+        .with_reservations(now)
+        .and_then(|held| held.to_epsilon(ledger.delta))
+        .map_err(budget_error)?;
+    Ok(PrivacyBudgetStatus {
+        ledger_hash,
+        epsilon_limit: ledger.epsilon_limit,
+        delta: ledger.delta,
+        epsilon_committed,
+        epsilon_held,
+        epsilon_remaining: (ledger.epsilon_limit - epsilon_held).max(0.0),
+        optimal_order,
+        committed_queries: ledger.committed_queries,
+        active_reservations: ledger.reservations.iter().filter(|r| r.expires_at > now).cloned().collect(),
+    })
+}
+
+/// A patient's budget for a dataset as (original hash, latest hash, ledger)
+///
+/// The earliest ledger linked for the dataset is the one in force.
+fn find_privacy_budget(
+    patient_hash: &ActionHash,
+    dataset_id: &str,
+) -> ExternResult<Option<(ActionHash, ActionHash, PrivacyBudgetLedger)>> {
+    let Some(original) = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToPrivacyBudgets)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter(|link| link.tag.0 == dataset_id.as_bytes())
+    .min_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash()) else {
+        return Ok(None);
+    };
+    let latest = get_links(
+        LinkQuery::try_new(original.clone(), LinkTypes::PrivacyBudgetUpdates)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash())
+    .unwrap_or_else(|| original.clone());
+    let ledger = get(latest.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<PrivacyBudgetLedger>().ok().flatten())
+        .ok_or(not_found("Privacy budget not found"))?;
+    Ok(Some((original, latest, ledger)))
+}
+
+fn require_privacy_budget(
+    patient_hash: &ActionHash,
+    dataset_id: &str,
+) -> ExternResult<(ActionHash, ActionHash, PrivacyBudgetLedger)> {
+    find_privacy_budget(patient_hash, dataset_id)?.ok_or(not_found("No privacy budget for this dataset"))
+}
+
+fn write_privacy_budget(original: &ActionHash, latest: ActionHash, ledger: &PrivacyBudgetLedger) -> ExternResult<()> {
+    let updated_hash = update_entry(latest, ledger)?;
+    create_link(original.clone(), updated_hash, LinkTypes::PrivacyBudgetUpdates, ())?;
+    Ok(())
+}
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_consent", "CreateConsentInput", "Record"),
+        ("get_patient_consents", "ActionHash", "Vec<Record>"),
+        ("get_active_consents", "ActionHash", "Vec<Record>"),
+        ("revoke_consent", "RevokeConsentInput", "Record"),
+        ("get_consents_pending_reaffirmation", "ActionHash", "Vec<CategoryReaffirmation>"),
+        ("reaffirm_consent_categories", "ActionHash", "Record"),
+        ("notify_pending_category_reaffirmations", "ActionHash", "Vec<Record>"),
+        ("request_grantee_reconfirmation", "GranteeReconfirmationInput", "Vec<Record>"),
+        ("get_consents_awaiting_reconfirmation", "ActionHash", "Vec<Record>"),
+        ("reconfirm_grantee_consent", "ActionHash", "Record"),
+        ("analyze_consent_coverage", "AnalyzeCoverageInput", "CoverageAnalysis"),
+        ("domain_event_subscriptions", "()", "Vec<DomainEventHandler>"),
+        ("handle_ingest_completed", "DomainEventNotice", "()"),
+        ("get_coverage_gaps", "ActionHash", "Vec<CoverageGap>"),
+        ("resolve_coverage_gap", "ResolveCoverageGapInput", "Record"),
+        ("check_authorization", "AuthorizationCheckInput", "AuthorizationResult"),
+        ("record_cached_authorization", "CachedAuthorizationInput", "()"),
+        ("create_access_request", "DataAccessRequest", "Record"),
+        ("log_data_access", "DataAccessLog", "Record"),
+        ("get_access_logs", "GetAccessLogsInput", "CursorPage<Record>"),
+        ("create_access_log", "AccessLogEntry", "ActionHash"),
+        ("create_access_denied_log", "AccessDeniedLogEntry", "ActionHash"),
+        ("record_emergency_access", "EmergencyAccess", "Record"),
+        ("create_authorization_document", "AuthorizationDocument", "Record"),
+        ("get_authorization_documents", "ActionHash", "Vec<Record>"),
+        ("get_access_logs_by_date", "DateRangeInput", "Vec<Record>"),
+        ("get_access_logs_by_accessor", "AccessorLogsInput", "Vec<Record>"),
+        ("get_emergency_access_events", "ActionHash", "Vec<Record>"),
+        ("generate_disclosure_report", "DisclosureReportInput", "DisclosureReport"),
+        ("render_disclosure_report", "DisclosureReportInput", "RenderedDisclosureReport"),
+        ("log_consent_view", "ConsentViewInput", "()"),
+        ("update_consent", "UpdateConsentInput", "Record"),
+        ("get_consent_history", "ActionHash", "Vec<Record>"),
+        ("simulate_consent", "Consent", "ConsentSimulation"),
+        ("create_delegation", "DelegationGrant", "Record"),
+        ("get_patient_delegations", "ActionHash", "Vec<Record>"),
+        ("get_active_delegations", "ActionHash", "Vec<Record>"),
+        ("revoke_delegation", "RevokeDelegationInput", "Record"),
+        ("check_delegation_authorization", "DelegationAuthInput", "DelegationAuthResult"),
+        ("get_my_delegations", "()", "Vec<Record>"),
+        ("create_access_notification", "CreateAccessNotificationInput", "Record"),
+        ("get_patient_notifications", "GetNotificationsInput", "Vec<Record>"),
+        ("mark_notification_viewed", "ActionHash", "Record"),
+        ("get_unread_notification_count", "ActionHash", "u32"),
+        ("set_notification_preferences", "NotificationPreferences", "Record"),
+        ("get_notification_preferences", "ActionHash", "Option<NotificationPreferences>"),
+        ("create_notification_digest", "NotificationDigest", "Record"),
+        ("generate_notification_summary", "GenerateSummaryInput", "String"),
+        ("create_care_team_template", "CareTeamTemplate", "Record"),
+        ("get_system_templates", "()", "Vec<Record>"),
+        ("initialize_system_templates", "()", "Vec<ActionHash>"),
+        ("create_care_team_from_template", "CreateCareTeamInput", "Record"),
+        ("publish_organization_template", "CareTeamTemplate", "Record"),
+        ("publish_template_version", "PublishTemplateVersionInput", "Record"),
+        ("endorse_template", "EndorseTemplateInput", "Record"),
+        ("discover_templates", "TemplateDiscoveryInput", "Vec<TemplateListing>"),
+        ("get_care_teams_pending_migration", "ActionHash", "Vec<TemplateMigration>"),
+        ("migrate_care_team_template", "ActionHash", "Record"),
+        ("get_patient_care_teams", "ActionHash", "Vec<Record>"),
+        ("get_active_care_teams", "ActionHash", "Vec<Record>"),
+        ("add_care_team_member", "AddMemberInput", "Record"),
+        ("remove_care_team_member", "RemoveMemberInput", "Record"),
+        ("set_care_team_role_overrides", "SetRoleOverridesInput", "Record"),
+        ("dissolve_care_team", "ActionHash", "Record"),
+        ("check_care_team_authorization", "CareTeamAuthInput", "CareTeamAuthResult"),
+        ("evaluate_access_policies", "AuthorizationCheckInput", "PolicyDecision"),
+        ("append_policy_log", "AppendPolicyLogInput", "Record"),
+        ("verify_policy_log", "()", "PolicyLogVerification"),
+        ("verify_audit_chain", "ActionHash", "AuditChainVerification"),
+        ("create_countersigned_access_log", "AccessLogEntry", "AccessReceipt"),
+        ("countersign_access_receipt", "DataAccessLog", "(AccessReceiptClaims, Signature)"),
+        ("get_access_countersignature", "ActionHash", "Option<AccessCountersignature>"),
+        ("get_policy_history", "String", "Vec<PolicyHistoryEntry>"),
+        ("create_guardian_policy", "GuardianPolicy", "Record"),
+        ("update_guardian_policy", "UpdateGuardianPolicyInput", "Record"),
+        ("get_guardian_policy", "ActionHash", "Option<Record>"),
+        ("submit_guardian_decision", "GuardianDecisionInput", "Record"),
+        ("get_guardian_quorum_status", "ActionHash", "GuardianQuorumStatus"),
+        ("activate_guardian_consent", "ActionHash", "Record"),
+        ("create_household", "CreateHouseholdInput", "Record"),
+        ("get_my_households", "()", "Vec<Record>"),
+        ("invite_household_member", "InviteHouseholdMemberInput", "Record"),
+        ("get_household_invitations", "ActionHash", "Vec<Record>"),
+        ("respond_to_household_invitation", "RespondHouseholdInvitationInput", "Record"),
+        ("get_household_members", "ActionHash", "Vec<HouseholdMemberInfo>"),
+        ("get_household_overview", "HouseholdOverviewInput", "HouseholdOverview"),
+        ("register_auditor", "AgentPubKey", "ActionHash"),
+        ("is_auditor", "AgentPubKey", "bool"),
+        ("raise_security_alert", "SecurityAlert", "Record"),
+        ("export_security_events", "SecurityEventExportInput", "CursorPage<String>"),
+        ("log_zk_proof_generation", "ZkProofAuditLog", "Record"),
+        ("log_zk_proof_verification", "ZkVerificationAuditLog", "Record"),
+        ("get_zk_proof_audit_logs", "ActionHash", "Vec<Record>"),
+        ("get_audit_coverage_report", "AuditCoverageInput", "AuditCoverageReport"),
+        ("register_service_agent", "RegisterServiceAgentInput", "Record"),
+        ("rotate_service_agent", "RotateServiceAgentInput", "Record"),
+        ("revoke_service_agent", "ActionHash", "Record"),
+        ("get_service_registrations", "()", "Vec<(ActionHash, ServiceAgentRegistration)>"),
+        ("get_my_service_registration", "()", "Option<ServiceAgentRegistration>"),
+        ("get_my_rate_limit_status", "()", "RateLimitStatus"),
+        ("get_my_operation_rate_status", "SensitiveOperation", "OperationRateStatus"),
+        ("set_jurisdiction_policy", "SetJurisdictionPolicyInput", "Record"),
+        ("get_jurisdiction_policy", "String", "Option<JurisdictionPolicy>"),
+        ("get_patient_residency", "ActionHash", "PatientResidency"),
+        ("set_retention_policy", "SetRetentionPolicyInput", "Record"),
+        ("get_retention_policies", "String", "Vec<(ActionHash, RetentionPolicy)>"),
+        ("get_patient_retention", "ActionHash", "Vec<ApplicableRetention>"),
+        ("set_sensitivity_matrix", "SetSensitivityMatrixInput", "Record"),
+        ("get_sensitivity_matrix", "()", "SensitivityMatrix"),
+        ("get_resolved_sensitivity", "()", "Vec<CategorySensitivity>"),
+        ("create_validation_rule_set", "CreateValidationRuleSetInput", "Record"),
+        ("get_validation_rule_sets", "()", "Vec<Record>"),
+        ("select_validation_rule_set", "SelectValidationRuleSetInput", "ActionHash"),
+        ("get_selected_rule_set", "GetSelectedRuleSetInput", "Option<Record>"),
+        ("evaluate_site_rules", "EvaluateSiteRulesInput", "RuleEvaluation"),
+        ("export_active_consents_fhir", "ActionHash", "Vec<serde_json::Value>"),
+        ("propose_consent_from_fhir", "ProposeFhirConsentInput", "Record"),
+        ("get_external_consent_proposals", "ActionHash", "Vec<Record>"),
+        ("confirm_external_consent", "ActionHash", "Record"),
+        ("reject_external_consent", "ActionHash", "Record"),
+        ("create_consent_assertion", "CreateConsentAssertionInput", "ConsentAssertion"),
+        ("import_consent_assertion", "ImportConsentAssertionInput", "Record"),
+        ("get_consent_assertion_evidence", "ActionHash", "Option<ImportedConsentAssertion>"),
+        ("get_patient_timeline", "PatientTimelineInput", "CursorPage<TimelineEvent>"),
+        ("send_direct_message", "SendDirectMessageInput", "Record"),
+        ("get_my_messages", "GetMyMessagesInput", "PaginatedResult<DirectMessageView>"),
+        ("get_record_messages", "ActionHash", "Vec<DirectMessageView>"),
+        ("mark_message_read", "ActionHash", "Record"),
+        ("grant_admin", "AgentPubKey", "ActionHash"),
+        ("revoke_admin", "AgentPubKey", "()"),
+        ("list_admins", "()", "Vec<AgentPubKey>"),
+        ("is_admin", "AgentPubKey", "bool"),
+        ("assign_role", "AssignRoleInput", "Record"),
+        ("revoke_role", "RoleCheck", "Vec<Record>"),
+        ("get_agent_roles", "AgentPubKey", "Vec<(ActionHash, RoleAssignment)>"),
+        ("has_role", "RoleCheck", "bool"),
+        ("create_privacy_budget", "CreatePrivacyBudgetInput", "Record"),
+        ("reserve_privacy_budget", "ReservePrivacyBudgetInput", "BudgetReservation"),
+        ("commit_privacy_budget", "BudgetReservationRef", "PrivacyBudgetStatus"),
+        ("release_privacy_budget", "BudgetReservationRef", "PrivacyBudgetStatus"),
+        ("get_privacy_budget", "PrivacyBudgetKey", "Option<PrivacyBudgetStatus>"),
+        ("reserve_cohort_budget", "BudgetSpend", "CohortReservation"),
+        ("commit_cohort_budget", "CohortReservation", "()"),
+        ("release_cohort_budget", "CohortReservation", "()"),
+        ("register_operator", "AgentPubKey", "ActionHash"),
+        ("is_operator", "AgentPubKey", "bool"),
+        ("get_network_statistics", "()", "NetworkStatistics"),
+        ("get_storage_report", "StorageReportInput", "StorageReport"),
+        ("record_performance_sample", "PerformanceSampleInput", "ActionHash"),
+        ("get_performance_metrics", "PerformanceMetricsInput", "PerformanceReport"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["guardian_quorum", "siem_export", "household_overview", "category_reaffirmation", "network_statistics", "coverage_gaps", "care_team_role_overrides", "grantee_reconfirmation", "template_marketplace", "policy_transparency_log", "consent_simulation", "audit_coverage", "service_agent_allowlists", "disclosure_rendering", "fhir_consent", "identity_assurance", "patient_timeline", "storage_report", "direct_messaging", "rate_limit_tiers", "jurisdiction_policies", "site_validation_rules", "sensitivity_matrix", "consent_assertions", "performance_metrics", "domain_events", "admin_roles", "role_assignments", "audit_chain", "countersigned_access", "privacy_budget", "access_context", "policy_combination", "link_metadata_tags", "operation_rate_limits", "data_retention", "idempotency_keys"],
+    // v2: consents carry a category registry snapshot
+    // v2: access logs point at the previous log in the patient's chain
+    // v3: access logs carry the caller's access context
+    // v2: role assignments cite the writing admin's admin link
+    entry_versions: [("Consent", 2), ("DataAccessLog", 3), ("RoleAssignment", 2)],
 }
@@ -4,6 +4,21 @@
 //! and audit logging with HIPAA alignment.
 
 use hdi::prelude::*;
+pub use mycelix_health_shared::IdentityAssuranceLevel;
+pub use mycelix_health_shared::DataUse;
+pub use mycelix_health_shared::{RetentionAction, MAX_RETENTION_DAYS};
+pub use mycelix_health_shared::{RulePredicate, RuleSeverity, SiteValidationRule};
+pub use mycelix_health_shared::{CategorySensitivity, SensitivityMatrix};
+pub use mycelix_health_shared::ExternTimings;
+pub use mycelix_health_shared::{LinkMetadata, TagStatus};
+pub use mycelix_health_shared::access_control::Role;
+pub use mycelix_health_shared::access_control::{AccessContext, PurposeOfUse};
+pub use mycelix_health_shared::dp_core::{BudgetError, PrivacyCost, RdpAccountant};
+use mycelix_health_shared::validation::validate_jurisdiction;
+use mycelix_health_shared::{anchor_hash, HealthDnaProperties};
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 /// Consent directive from patient
 #[hdk_entry_helper]
@@ -35,6 +50,59 @@ pub struct Consent {
     pub legal_representative: Option<AgentPubKey>,
     /// Notes
     pub notes: Option<String>,
+    /// Categories known when the consent was granted or last re-affirmed;
+    /// `All` only reaches categories listed here
+    #[serde(default)]
+    pub category_registry: Option<CategoryRegistry>,
+    /// Version of the patient's guardian policy the consent was made under
+    #[serde(default)]
+    pub guardian_policy: Option<ActionHash>,
+    /// Guardian approvals of the original consent it was activated with
+    #[serde(default)]
+    pub guardian_approvals: Vec<ActionHash>,
+}
+
+impl Consent {
+    /// Registry this consent was granted under; consents predating registries
+    /// were granted against the baseline categories
+    pub fn category_registry(&self) -> CategoryRegistry {
+        self.category_registry
+            .clone()
+            .unwrap_or_else(|| CategoryRegistry::at_version(1))
+    }
+
+    /// Whether the scope covers a category, honouring exclusions and the
+    /// registry snapshot for `All`-scoped consents
+    pub fn covers_category(&self, category: &DataCategory) -> bool {
+        if self.scope.exclusions.contains(category) {
+            return false;
+        }
+        if self.scope.data_categories.contains(category) {
+            return true;
+        }
+        self.scope.data_categories.contains(&DataCategory::All)
+            && (matches!(category, DataCategory::All) || self.category_registry().covers(category))
+    }
+
+    /// Whether the patient has decided on a category for this consent,
+    /// either by covering it or by excluding it
+    pub fn addresses_category(&self, category: &DataCategory) -> bool {
+        self.scope.exclusions.contains(category) || self.covers_category(category)
+    }
+
+    /// Categories introduced since this `All`-scoped consent was granted,
+    /// which it does not cover until the patient re-affirms
+    pub fn categories_pending_affirmation(&self) -> Vec<DataCategory> {
+        if !self.scope.data_categories.contains(&DataCategory::All) {
+            return Vec::new();
+        }
+        let snapshot = self.category_registry();
+        CategoryRegistry::current()
+            .categories
+            .into_iter()
+            .filter(|c| !snapshot.covers(c) && !self.scope.exclusions.contains(c))
+            .collect()
+    }
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -65,6 +133,27 @@ pub struct ConsentScope {
     pub encounter_hashes: Option<Vec<ActionHash>>,
     /// Exclusions
     pub exclusions: Vec<DataCategory>,
+    /// Identity assurance the grantee must hold to read a category
+    #[serde(default)]
+    pub minimum_assurance: Vec<AssuranceRequirement>,
+}
+
+impl ConsentScope {
+    /// Strictest assurance required for a category, counting `All` entries
+    pub fn required_assurance(&self, category: &DataCategory) -> IdentityAssuranceLevel {
+        self.minimum_assurance
+            .iter()
+            .filter(|req| &req.category == category || req.category == DataCategory::All)
+            .map(|req| req.level)
+            .max()
+            .unwrap_or_default()
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AssuranceRequirement {
+    pub category: DataCategory,
+    pub level: IdentityAssuranceLevel,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -92,6 +181,58 @@ pub enum DataCategory {
     All,
 }
 
+/// Version of the category registry; bump it whenever a `DataCategory`
+/// variant is added and list the new variant in `CATEGORY_INTRODUCTIONS`
+pub const CATEGORY_REGISTRY_VERSION: u32 = 1;
+
+/// Every concrete category with the registry version that introduced it
+const CATEGORY_INTRODUCTIONS: &[(DataCategory, u32)] = &[
+    (DataCategory::Demographics, 1),
+    (DataCategory::Allergies, 1),
+    (DataCategory::Medications, 1),
+    (DataCategory::Diagnoses, 1),
+    (DataCategory::Procedures, 1),
+    (DataCategory::LabResults, 1),
+    (DataCategory::ImagingStudies, 1),
+    (DataCategory::VitalSigns, 1),
+    (DataCategory::Immunizations, 1),
+    (DataCategory::MentalHealth, 1),
+    (DataCategory::SubstanceAbuse, 1),
+    (DataCategory::SexualHealth, 1),
+    (DataCategory::GeneticData, 1),
+    (DataCategory::FinancialData, 1),
+];
+
+/// Snapshot of the data categories that existed at a registry version
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CategoryRegistry {
+    pub version: u32,
+    pub categories: Vec<DataCategory>,
+}
+
+impl CategoryRegistry {
+    /// Categories known at the given registry version
+    pub fn at_version(version: u32) -> Self {
+        CategoryRegistry {
+            version,
+            categories: CATEGORY_INTRODUCTIONS
+                .iter()
+                .filter(|(_, introduced)| *introduced <= version)
+                .map(|(category, _)| category.clone())
+                .collect(),
+        }
+    }
+
+    /// Categories known to this build
+    pub fn current() -> Self {
+        Self::at_version(CATEGORY_REGISTRY_VERSION)
+    }
+
+    pub fn covers(&self, category: &DataCategory) -> bool {
+        self.categories.contains(category)
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum DataPermission {
     Read,
@@ -124,6 +265,29 @@ pub enum ConsentStatus {
     Rejected,
 }
 
+impl TagStatus for ConsentStatus {
+    fn tag_code(&self) -> u8 {
+        match self {
+            ConsentStatus::Active => 0,
+            ConsentStatus::Expired => 1,
+            ConsentStatus::Revoked => 2,
+            ConsentStatus::Pending => 3,
+            ConsentStatus::Rejected => 4,
+        }
+    }
+
+    fn from_tag_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0 => ConsentStatus::Active,
+            1 => ConsentStatus::Expired,
+            2 => ConsentStatus::Revoked,
+            3 => ConsentStatus::Pending,
+            4 => ConsentStatus::Rejected,
+            _ => return None,
+        })
+    }
+}
+
 /// Data access request
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -159,7 +323,7 @@ pub enum RequestStatus {
     Withdrawn,
 }
 
-/// Audit log entry for data access
+/// Audit log entry for data access; append-only
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
 pub struct DataAccessLog {
@@ -176,6 +340,43 @@ pub struct DataAccessLog {
     /// Was this an emergency override?
     pub emergency_override: bool,
     pub override_reason: Option<String>,
+    /// Getter that logged the access, when it read through `audited_read`
+    #[serde(default)]
+    pub access_path: Option<String>,
+    /// Identity assurance the accessor held when access was granted
+    #[serde(default)]
+    pub accessor_assurance: Option<IdentityAssuranceLevel>,
+    /// The patient's previous log, so gaps and deletions show up when the
+    /// chain is walked; `None` for the first log
+    #[serde(default)]
+    pub previous_log: Option<ActionHash>,
+    /// Position in the patient's log chain, starting at 0
+    #[serde(default)]
+    pub chain_sequence: Option<u64>,
+    /// Purpose of use, facility, device and application the caller gave
+    #[serde(default)]
+    pub access_context: Option<AccessContext>,
+}
+
+/// What the patient's agent signs to acknowledge an access log
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct AccessReceiptClaims {
+    /// Entry hash of the logged `DataAccessLog`
+    pub log_entry_hash: EntryHash,
+    pub patient_hash: ActionHash,
+    pub accessor: AgentPubKey,
+    pub accessed_at: Timestamp,
+    pub signed_at: Timestamp,
+}
+
+/// A high-sensitivity access log countersigned by the patient's agent
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct AccessCountersignature {
+    pub log_hash: ActionHash,
+    pub claims: AccessReceiptClaims,
+    pub patient_agent: AgentPubKey,
+    pub signature: Signature,
 }
 
 /// Break-glass emergency access record
@@ -367,6 +568,40 @@ pub struct AccessNotification {
     pub access_log_hash: Option<ActionHash>,
 }
 
+impl AccessNotification {
+    pub fn read_state(&self) -> NotificationReadState {
+        if self.viewed {
+            NotificationReadState::Viewed
+        } else {
+            NotificationReadState::Unread
+        }
+    }
+}
+
+/// Whether the patient has seen a notification, as tagged on its link
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub enum NotificationReadState {
+    Unread,
+    Viewed,
+}
+
+impl TagStatus for NotificationReadState {
+    fn tag_code(&self) -> u8 {
+        match self {
+            NotificationReadState::Unread => 0,
+            NotificationReadState::Viewed => 1,
+        }
+    }
+
+    fn from_tag_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0 => NotificationReadState::Unread,
+            1 => NotificationReadState::Viewed,
+            _ => return None,
+        })
+    }
+}
+
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
 pub enum NotificationPriority {
     /// Immediate notification (emergency, new provider, sensitive data)
@@ -469,6 +704,39 @@ pub struct CareTeamTemplate {
     pub created_at: Timestamp,
     /// Is this template active?
     pub active: bool,
+    /// Clinical specialty, used for discovery
+    #[serde(default)]
+    pub specialty: Option<String>,
+    /// Version number; templates predating versioning are version 1
+    #[serde(default)]
+    pub version: Option<u32>,
+    /// The version this one supersedes
+    #[serde(default)]
+    pub previous_version: Option<ActionHash>,
+}
+
+impl CareTeamTemplate {
+    pub fn version(&self) -> u32 {
+        self.version.unwrap_or(1)
+    }
+}
+
+/// A reviewer's decision on an organization-published template
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct TemplateEndorsement {
+    /// The template version being reviewed
+    pub template_hash: ActionHash,
+    pub decision: EndorsementDecision,
+    pub endorsed_by: AgentPubKey,
+    pub endorsed_at: Timestamp,
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum EndorsementDecision {
+    Endorsed,
+    Rejected,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -531,6 +799,49 @@ pub struct CareTeam {
     pub expires_at: Option<Timestamp>,
     /// Notes
     pub notes: Option<String>,
+    /// Narrower access for particular roles on the team
+    #[serde(default)]
+    pub role_overrides: Vec<CareTeamRoleOverride>,
+}
+
+impl CareTeam {
+    pub fn role_override(&self, role: &CareTeamRole) -> Option<&CareTeamRoleOverride> {
+        self.role_overrides.iter().find(|o| &o.role == role)
+    }
+
+    /// Whether the team grants `permission` on `category`
+    pub fn team_allows(&self, permission: &DataPermission, category: &DataCategory) -> bool {
+        let category_covered = self
+            .data_categories
+            .iter()
+            .any(|cat| matches!(cat, DataCategory::All) || cat == category);
+        self.permissions.contains(permission) && category_covered && !self.exclusions.contains(category)
+    }
+
+    /// The narrower of the team's grant and the role's override, if any
+    pub fn role_allows(&self, role: &CareTeamRole, permission: &DataPermission, category: &DataCategory) -> bool {
+        if !self.team_allows(permission, category) {
+            return false;
+        }
+        match self.role_override(role) {
+            Some(o) => {
+                o.permissions.contains(permission)
+                    && o.data_categories.iter().any(|cat| matches!(cat, DataCategory::All) || cat == category)
+            }
+            None => true,
+        }
+    }
+}
+
+/// Role-scoped narrowing of a care team's access, e.g. front-desk staff
+/// limited to reading demographics
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CareTeamRoleOverride {
+    pub role: CareTeamRole,
+    /// Must be a subset of the team's permissions
+    pub permissions: Vec<DataPermission>,
+    /// Must be covered by the team's categories and not excluded
+    pub data_categories: Vec<DataCategory>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -580,453 +891,2564 @@ pub enum CareTeamStatus {
     Expired,
 }
 
-#[hdk_entry_types]
-#[unit_enum(UnitEntryTypes)]
-pub enum EntryTypes {
-    Consent(Consent),
-    DataAccessRequest(DataAccessRequest),
-    DataAccessLog(DataAccessLog),
-    EmergencyAccess(EmergencyAccess),
-    AuthorizationDocument(AuthorizationDocument),
-    // Consent Delegation
-    DelegationGrant(DelegationGrant),
-    // Patient Notifications
-    AccessNotification(AccessNotification),
-    NotificationPreferences(NotificationPreferences),
-    NotificationDigest(NotificationDigest),
-    // Care Team Templates
-    CareTeamTemplate(CareTeamTemplate),
-    CareTeam(CareTeam),
+impl TagStatus for CareTeamStatus {
+    fn tag_code(&self) -> u8 {
+        match self {
+            CareTeamStatus::Active => 0,
+            CareTeamStatus::Inactive => 1,
+            CareTeamStatus::Dissolved => 2,
+            CareTeamStatus::Expired => 3,
+        }
+    }
+
+    fn from_tag_code(code: u8) -> Option<Self> {
+        Some(match code {
+            0 => CareTeamStatus::Active,
+            1 => CareTeamStatus::Inactive,
+            2 => CareTeamStatus::Dissolved,
+            3 => CareTeamStatus::Expired,
+            _ => return None,
+        })
+    }
 }
 
-#[hdk_link_types]
-pub enum LinkTypes {
-    PatientToConsents,
-    PatientToAccessRequests,
-    PatientToAccessLogs,
-    ConsentToLogs,
-    PatientToEmergencyAccess,
-    PatientToDocuments,
-    GranteeToConsents,
-    ActiveConsents,
-    RevokedConsents,
-    ConsentUpdates,
-    // Consent Delegation links
-    PatientToDelegations,
-    DelegateToDelegations,
-    ActiveDelegations,
-    // Patient Notification links
-    PatientToNotifications,
-    PatientToNotificationPreferences,
-    PatientToDigests,
-    UnreadNotifications,
-    // Care Team links
-    PatientToCareTeams,
-    CareTeamToMembers,
-    TemplateToTeams,
-    SystemTemplates,
-    ActiveCareTeams,
+// ============================================================
+// MULTI-GUARDIAN CONSENT (MINORS)
+// ============================================================
+
+/// Policy requiring several guardians to approve consents for a minor,
+/// e.g. shared custody where both parents must agree
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct GuardianPolicy {
+    pub policy_id: String,
+    pub patient_hash: ActionHash,
+    /// Every guardian recognised for this patient
+    pub guardians: Vec<AgentPubKey>,
+    /// Approval requirements per data category
+    pub rules: Vec<GuardianQuorumRule>,
+    /// Custody order or other legal documentation
+    pub legal_document_hash: Option<EntryHash>,
+    pub created_at: Timestamp,
+    pub active: bool,
 }
 
-#[hdk_extern]
-pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
-    match op.flattened::<EntryTypes, LinkTypes>()? {
-        FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { action, app_entry, .. } => {
-                let author = &action.author;
-                match app_entry {
-                    EntryTypes::Consent(c) => validate_consent(&c, author),
-                    EntryTypes::DataAccessRequest(r) => validate_access_request(&r, author),
-                    EntryTypes::DataAccessLog(l) => validate_access_log(&l, author),
-                    EntryTypes::EmergencyAccess(e) => validate_emergency_access(&e, author),
-                    EntryTypes::AuthorizationDocument(d) => validate_authorization(&d, author),
-                    EntryTypes::DelegationGrant(d) => validate_delegation_grant(&d, author),
-                    EntryTypes::AccessNotification(n) => validate_access_notification(&n, author),
-                    EntryTypes::NotificationPreferences(p) => validate_notification_preferences(&p, author),
-                    EntryTypes::NotificationDigest(d) => validate_notification_digest(&d, author),
-                    EntryTypes::CareTeamTemplate(t) => validate_care_team_template(&t),
-                    EntryTypes::CareTeam(t) => validate_care_team(&t, author),
-                }
-            },
-            OpEntry::UpdateEntry { action, app_entry, .. } => {
-                let author = &action.author;
-                match app_entry {
-                    EntryTypes::Consent(c) => validate_consent(&c, author),
-                    EntryTypes::DataAccessRequest(r) => validate_access_request(&r, author),
-                    EntryTypes::DataAccessLog(l) => validate_access_log(&l, author),
-                    EntryTypes::EmergencyAccess(e) => validate_emergency_access(&e, author),
-                    EntryTypes::AuthorizationDocument(d) => validate_authorization(&d, author),
-                    EntryTypes::DelegationGrant(d) => validate_delegation_grant(&d, author),
-                    EntryTypes::AccessNotification(n) => validate_access_notification(&n, author),
-                    EntryTypes::NotificationPreferences(p) => validate_notification_preferences(&p, author),
-                    EntryTypes::NotificationDigest(d) => validate_notification_digest(&d, author),
-                    EntryTypes::CareTeamTemplate(t) => validate_care_team_template(&t),
-                    EntryTypes::CareTeam(t) => validate_care_team(&t, author),
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct GuardianQuorumRule {
+    /// Category this rule governs (`All` covers every category)
+    pub data_category: DataCategory,
+    /// Guardians whose approval is mandatory
+    pub required_approvers: Vec<AgentPubKey>,
+    /// Minimum number of distinct guardian approvals
+    pub min_approvals: u32,
+}
+
+impl GuardianPolicy {
+    /// Combine the rules that cover any of the given categories into the
+    /// mandatory approvers and the strictest minimum approval count
+    pub fn quorum_for(&self, categories: &[DataCategory]) -> Option<(Vec<AgentPubKey>, u32)> {
+        let covers_all = categories.contains(&DataCategory::All);
+        let mut required_approvers: Vec<AgentPubKey> = Vec::new();
+        let mut min_approvals = 0u32;
+        let mut applies = false;
+
+        for rule in &self.rules {
+            let matches = covers_all
+                || matches!(rule.data_category, DataCategory::All)
+                || categories.contains(&rule.data_category);
+            if !matches {
+                continue;
+            }
+            applies = true;
+            min_approvals = min_approvals.max(rule.min_approvals);
+            for approver in &rule.required_approvers {
+                if !required_approvers.contains(approver) {
+                    required_approvers.push(approver.clone());
                 }
             }
-            _ => Ok(ValidateCallbackResult::Valid),
-        },
-        _ => Ok(ValidateCallbackResult::Valid),
-    }
-}
+        }
 
-fn validate_consent(consent: &Consent, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
-    if consent.consent_id.is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Consent ID is required".to_string(),
-        ));
-    }
-    if consent.permissions.is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "At least one permission must be granted".to_string(),
-        ));
-    }
-    let ownership = validate_patient_reference_and_ownership(&consent.patient_hash, author, "create consent")?;
-    if !matches!(ownership, ValidateCallbackResult::Valid) {
-        return Ok(ownership);
+        applies.then_some((required_approvers, min_approvals))
     }
-    Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_access_request(request: &DataAccessRequest, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
-    if request.request_id.is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Request ID is required".to_string(),
-        ));
-    }
-    if request.justification.is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Justification is required for data access requests".to_string(),
-        ));
-    }
-    let patient_ref = validate_patient_reference(&request.patient_hash)?;
-    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
-        return Ok(patient_ref);
-    }
-    if &request.requestor != author {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Data access requestor must match the action author".to_string(),
-        ));
-    }
-    Ok(ValidateCallbackResult::Valid)
+/// A single guardian's signed decision on a pending consent
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct GuardianApproval {
+    pub consent_hash: ActionHash,
+    pub policy_hash: ActionHash,
+    pub guardian: AgentPubKey,
+    pub approved: bool,
+    /// Guardian's signature over `consent_hash`
+    pub signature: Signature,
+    pub decided_at: Timestamp,
+    pub comment: Option<String>,
 }
 
-fn validate_access_log(log: &DataAccessLog, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
-    if log.log_id.is_empty() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Log ID is required".to_string(),
-        ));
-    }
-    if log.emergency_override && log.override_reason.is_none() {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Override reason is required for emergency access".to_string(),
-        ));
-    }
-    let patient_ref = validate_patient_reference(&log.patient_hash)?;
-    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
-        return Ok(patient_ref);
-    }
-    if &log.accessor != author {
-        return Ok(ValidateCallbackResult::Invalid(
-            "Access log accessor must match the action author".to_string(),
-        ));
-    }
-    Ok(ValidateCallbackResult::Valid)
+// ============================================================
+// HOUSEHOLD GROUPING
+// ============================================================
+
+/// Family/household grouping for combined views; membership is per
+/// patient and never grants data access on its own
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Household {
+    pub household_id: String,
+    pub name: String,
+    pub created_by: AgentPubKey,
+    pub created_at: Timestamp,
 }
 
-fn validate_emergency_access(
-    emergency: &EmergencyAccess,
-    author: &AgentPubKey,
-) -> ExternResult<ValidateCallbackResult> {
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum HouseholdRole {
+    Head,
+    Spouse,
+    Partner,
+    Parent,
+    Child,
+    Dependent,
+    Other(String),
+}
+
+/// Invitation for a patient to join a household
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct HouseholdInvitation {
+    pub household_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub role_label: HouseholdRole,
+    pub invited_by: AgentPubKey,
+    pub invited_at: Timestamp,
+    pub message: Option<String>,
+    pub status: InvitationStatus,
+    pub responded_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum InvitationStatus {
+    Pending,
+    Accepted,
+    Declined,
+    Withdrawn,
+}
+
+// ============================================================
+// SECURITY MONITORING
+// ============================================================
+
+/// Security alert raised for review by security teams
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct SecurityAlert {
+    pub alert_id: String,
+    pub alert_type: SecurityAlertType,
+    pub severity: AlertSeverity,
+    /// Patient whose data is involved (if any)
+    pub patient_hash: Option<ActionHash>,
+    /// Agent whose behaviour triggered the alert (if any)
+    pub subject_agent: Option<AgentPubKey>,
+    pub description: String,
+    pub raised_by: AgentPubKey,
+    pub raised_at: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum SecurityAlertType {
+    RepeatedAccessDenials,
+    UnusualAccessPattern,
+    UnauditedEmergencyAccess,
+    ConsentTampering,
+    Other(String),
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum AlertSeverity {
+    Low,
+    Medium,
+    High,
+    Critical,
+}
+
+// ============================================================
+// POLICY TRANSPARENCY LOG
+// ============================================================
+
+/// Policies whose changes are published to the transparency log
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum PolicyKind {
+    /// System and organization care team templates
+    CareTeamTemplate,
+    TemplateEndorsement,
+    /// Weighting policies, logged by the zome that owns them
+    Weighting,
+    /// Retention policies, logged by the zome that owns them
+    Retention,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum PolicyChange {
+    Created,
+    Updated,
+    Retired,
+}
+
+/// One entry of the append-only, hash-chained policy log
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PolicyLogEntry {
+    /// Position in the log, starting at 0
+    pub sequence: u64,
+    /// The entry at `sequence - 1`
+    pub previous: Option<ActionHash>,
+    pub policy_id: String,
+    pub kind: PolicyKind,
+    pub change: PolicyChange,
+    /// Record holding the policy as changed
+    pub policy_action: ActionHash,
+    /// Entry hash of that record, so the logged content can be checked
+    pub policy_entry_hash: EntryHash,
+    pub recorded_by: AgentPubKey,
+    pub recorded_at: Timestamp,
+}
+
+// ============================================================
+// SERVICE AGENT REGISTRATIONS
+// ============================================================
+
+/// Permissions a service agent may use for one data category
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ServiceGrant {
+    pub category: DataCategory,
+    pub permissions: Vec<DataPermission>,
+}
+
+/// Allowlist for a backend service agent (analytics exporter, notification
+/// relay, ...)
+///
+/// It is a ceiling on top of patient consent: a registered agent is refused
+/// anything outside its allowlist even where a consent would allow it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ServiceAgentRegistration {
+    pub registration_id: String,
+    pub service_name: String,
+    pub agent: AgentPubKey,
+    pub allowed: Vec<ServiceGrant>,
+    pub registered_by: AgentPubKey,
+    pub registered_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+    /// Registration whose agent key this one replaced
+    pub rotated_from: Option<ActionHash>,
+    pub revoked_at: Option<Timestamp>,
+    /// Admin link of the agent who wrote this version; empty for
+    /// bootstrap admins
+    #[serde(default)]
+    pub admin_link: Option<ActionHash>,
+}
+
+/// An access control role an admin has assigned to an agent
+///
+/// Revocation is the only update; reassigning creates a new entry.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct RoleAssignment {
+    pub agent: AgentPubKey,
+    pub role: Role,
+    pub assigned_by: AgentPubKey,
+    pub assigned_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+    pub reason: String,
+    pub revoked_at: Option<Timestamp>,
+    /// Admin link of the agent who wrote this version; empty for
+    /// bootstrap admins
+    #[serde(default)]
+    pub admin_link: Option<ActionHash>,
+}
+
+impl RoleAssignment {
+    /// Whether the assignment is in force at `now`
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires| now < expires)
+    }
+}
+
+impl ServiceAgentRegistration {
+    /// Whether the registration is in force at `now`
+    pub fn is_active(&self, now: Timestamp) -> bool {
+        self.revoked_at.is_none() && self.expires_at.is_none_or(|expires| now < expires)
+    }
+
+    /// Whether the allowlist permits `permission` on `category` at `now`
+    ///
+    /// An `All` grant covers every category; asking for `All` needs an
+    /// explicit `All` grant.
+    pub fn allows(&self, category: &DataCategory, permission: &DataPermission, now: Timestamp) -> bool {
+        self.is_active(now)
+            && self.allowed.iter().any(|grant| {
+                (grant.category == *category || grant.category == DataCategory::All)
+                    && grant.permissions.contains(permission)
+            })
+    }
+}
+
+// ============================================================
+// DIRECT MESSAGING
+// ============================================================
+
+/// Relationship under which a patient and a provider may message each other
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum MessagingContext {
+    /// A consent the patient granted to the provider
+    Consent(ActionHash),
+    /// A care team the provider belongs to
+    CareTeam(ActionHash),
+}
+
+/// A message between a patient and a provider, sealed so only the two of
+/// them can read it
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DirectMessage {
+    pub patient_hash: ActionHash,
+    pub sender: AgentPubKey,
+    pub recipient: AgentPubKey,
+    pub context: MessagingContext,
+    /// Clinical record the message asks about
+    pub regarding: Option<ActionHash>,
+    pub in_reply_to: Option<ActionHash>,
+    /// Body boxed from the sender's key to the recipient's
+    pub sealed_body: XSalsa20Poly1305EncryptedData,
+    pub sent_at: Timestamp,
+}
+
+/// The recipient opened a direct message
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct MessageReadReceipt {
+    pub message_hash: ActionHash,
+    pub reader: AgentPubKey,
+    pub read_at: Timestamp,
+}
+
+// ============================================================
+// JURISDICTION POLICIES
+// ============================================================
+
+/// Uses of patient data a jurisdiction prohibits; the newest policy for a
+/// jurisdiction replaces earlier ones
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct JurisdictionPolicy {
+    /// ISO 3166 code the policy applies to (e.g. "EU", "US-CA")
+    pub jurisdiction: String,
+    pub prohibited_uses: Vec<DataUse>,
+    /// Regulation or decision behind the policy
+    pub rationale: Option<String>,
+    /// Operator who set the policy
+    pub set_by: AgentPubKey,
+    pub set_at: Timestamp,
+    /// Operator or admin link of the agent who set the policy; empty for
+    /// bootstrap admins
+    #[serde(default)]
+    pub operator_link: Option<ActionHash>,
+}
+
+/// How long a jurisdiction keeps one category of patient data; the newest
+/// policy for a jurisdiction and category replaces earlier ones
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct RetentionPolicy {
+    /// ISO 3166 code the policy applies to (e.g. "EU", "US-CA")
+    pub jurisdiction: String,
+    /// `All` covers the categories without a policy of their own
+    pub category: DataCategory,
+    /// Days after a record is written that it is kept
+    pub retention_days: u32,
+    pub action: RetentionAction,
+    /// Regulation or decision behind the policy
+    pub rationale: Option<String>,
+    /// Operator who set the policy
+    pub set_by: AgentPubKey,
+    pub set_at: Timestamp,
+    /// Operator or admin link of the agent who set the policy; empty for
+    /// bootstrap admins
+    #[serde(default)]
+    pub operator_link: Option<ActionHash>,
+}
+
+// ============================================================
+// SITE VALIDATION RULES
+// ============================================================
+
+/// Most rules a single rule set may hold
+pub const MAX_SITE_RULES: usize = 64;
+
+/// Site-specific rules for one entry type, evaluated by coordinators before
+/// commit once selected for a facility
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ValidationRuleSet {
+    pub name: String,
+    /// Facility the rule set was written for; `None` for network-wide rules
+    pub facility: Option<String>,
+    /// Entry type the rules apply to (e.g. "Patient", "Provider")
+    pub entry_type: String,
+    pub rules: Vec<SiteValidationRule>,
+    pub created_by: AgentPubKey,
+    pub created_at: Timestamp,
+}
+
+// ============================================================
+// CATEGORY SENSITIVITY
+// ============================================================
+
+/// Operator-set handling of data categories; the newest policy replaces
+/// earlier ones
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct SensitivityMatrixPolicy {
+    pub matrix: SensitivityMatrix,
+    /// Regulation or decision behind the policy
+    pub rationale: Option<String>,
+    /// Operator who set the policy
+    pub set_by: AgentPubKey,
+    pub set_at: Timestamp,
+    /// Operator or admin link of the agent who set the policy; empty for
+    /// bootstrap admins
+    #[serde(default)]
+    pub operator_link: Option<ActionHash>,
+}
+
+// ============================================================
+// PERFORMANCE SAMPLES
+// ============================================================
+
+/// Extern timings one agent's zome buffered over a window and flushed
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PerformanceSample {
+    pub window_start: Timestamp,
+    pub window_end: Timestamp,
+    pub externs: Vec<ExternTimings>,
+    pub recorded_by: AgentPubKey,
+}
+
+// ============================================================
+// CONSENT ASSERTIONS
+// ============================================================
+
+/// What a patient asserts about one of their consents to a partner network
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConsentAssertionClaims {
+    pub assertion_id: String,
+    /// Network holding the consent
+    pub issuer_network: DnaHash,
+    /// Partner network the assertion is meant for; `None` for any
+    pub audience: Option<DnaHash>,
+    /// Patient agent that signed the claims
+    pub issuer: AgentPubKey,
+    pub consent_hash: ActionHash,
+    pub grantee: ConsentGrantee,
+    pub data_categories: Vec<DataCategory>,
+    pub exclusions: Vec<DataCategory>,
+    pub permissions: Vec<DataPermission>,
+    pub purpose: ConsentPurpose,
+    pub consent_expires_at: Option<Timestamp>,
+    pub issued_at: Timestamp,
+    /// The assertion cannot be imported after this
+    pub valid_until: Timestamp,
+}
+
+/// Consent claims signed by the patient's agent key
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ConsentAssertion {
+    pub claims: ConsentAssertionClaims,
+    pub signature: Signature,
+}
+
+impl ConsentAssertion {
+    /// Whether the issuer signed exactly these claims
+    pub fn verify(&self) -> ExternResult<bool> {
+        verify_signature(self.claims.issuer.clone(), self.signature.clone(), &self.claims)
+    }
+}
+
+/// A partner network's consent assertion, kept as evidence for the pending
+/// consent proposed from it
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ImportedConsentAssertion {
+    pub patient_hash: ActionHash,
+    pub assertion: ConsentAssertion,
+    /// Pending consent awaiting the patient's confirmation
+    pub proposed_consent_hash: ActionHash,
+    pub imported_by: AgentPubKey,
+    pub imported_at: Timestamp,
+}
+
+// ============================================================
+// PRIVACY BUDGETS
+// ============================================================
+
+/// Privacy cost held for an aggregate query that has not run yet
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct BudgetReservation {
+    pub reservation_id: u64,
+    pub agent: AgentPubKey,
+    pub cost: PrivacyCost,
+    pub purpose: String,
+    pub reserved_at: Timestamp,
+    /// Held budget is freed if the reservation is not committed by then
+    pub expires_at: Timestamp,
+}
+
+/// Rényi DP spend against one patient's data in one dataset
+///
+/// Updated as queries reserve and commit budget. The limit and tracked
+/// orders never change and the committed curve only grows.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PrivacyBudgetLedger {
+    pub patient_hash: ActionHash,
+    pub dataset_id: String,
+    /// Total ε the patient allows at `delta`
+    pub epsilon_limit: f64,
+    pub delta: f64,
+    pub orders: Vec<f64>,
+    /// RDP of every committed query at each order
+    pub committed_rdp: Vec<f64>,
+    pub reservations: Vec<BudgetReservation>,
+    pub committed_queries: u32,
+    pub next_reservation_id: u64,
+    pub created_by: AgentPubKey,
+    pub updated_at: Timestamp,
+}
+
+impl PrivacyBudgetLedger {
+    /// Accountant over the committed queries only
+    pub fn committed(&self) -> Result<RdpAccountant, BudgetError> {
+        RdpAccountant::from_parts(self.orders.clone(), self.committed_rdp.clone())
+    }
+
+    /// Accountant over committed queries and reservations unexpired at `now`
+    pub fn with_reservations(&self, now: Timestamp) -> Result<RdpAccountant, BudgetError> {
+        let mut accountant = self.committed()?;
+        for reservation in self.reservations.iter().filter(|r| r.expires_at > now) {
+            accountant.compose(&reservation.cost, 1)?;
+        }
+        Ok(accountant)
+    }
+}
+
+#[hdk_entry_types]
+#[unit_enum(UnitEntryTypes)]
+pub enum EntryTypes {
+    Consent(Consent),
+    DataAccessRequest(DataAccessRequest),
+    DataAccessLog(DataAccessLog),
+    EmergencyAccess(EmergencyAccess),
+    AuthorizationDocument(AuthorizationDocument),
+    // Consent Delegation
+    DelegationGrant(DelegationGrant),
+    // Patient Notifications
+    AccessNotification(AccessNotification),
+    NotificationPreferences(NotificationPreferences),
+    NotificationDigest(NotificationDigest),
+    // Care Team Templates
+    CareTeamTemplate(CareTeamTemplate),
+    CareTeam(CareTeam),
+    TemplateEndorsement(TemplateEndorsement),
+    // Multi-guardian consent
+    GuardianPolicy(GuardianPolicy),
+    GuardianApproval(GuardianApproval),
+    // Security monitoring
+    SecurityAlert(SecurityAlert),
+    // Household grouping
+    Household(Household),
+    HouseholdInvitation(HouseholdInvitation),
+    // Policy transparency log
+    PolicyLogEntry(PolicyLogEntry),
+    // Service agent allowlists
+    ServiceAgentRegistration(ServiceAgentRegistration),
+    // Direct messaging
+    DirectMessage(DirectMessage),
+    MessageReadReceipt(MessageReadReceipt),
+    // Data residency
+    JurisdictionPolicy(JurisdictionPolicy),
+    // Site validation rules
+    ValidationRuleSet(ValidationRuleSet),
+    // Category sensitivity
+    SensitivityMatrixPolicy(SensitivityMatrixPolicy),
+    // Cross-network consent assertions
+    ImportedConsentAssertion(ImportedConsentAssertion),
+    // Performance metrics
+    PerformanceSample(PerformanceSample),
+    RoleAssignment(RoleAssignment),
+    AccessCountersignature(AccessCountersignature),
+    PrivacyBudgetLedger(PrivacyBudgetLedger),
+    // Data retention
+    RetentionPolicy(RetentionPolicy),
+}
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    PatientToConsents,
+    PatientToAccessRequests,
+    PatientToAccessLogs,
+    ConsentToLogs,
+    PatientToEmergencyAccess,
+    PatientToDocuments,
+    GranteeToConsents,
+    ActiveConsents,
+    RevokedConsents,
+    ConsentUpdates,
+    // Consent Delegation links
+    PatientToDelegations,
+    DelegateToDelegations,
+    ActiveDelegations,
+    // Patient Notification links
+    PatientToNotifications,
+    PatientToNotificationPreferences,
+    PatientToDigests,
+    /// Legacy per-notification unread index, superseded by UnreadNotificationCounter
+    UnreadNotifications,
+    /// Counter shards for a patient's unread notifications
+    UnreadNotificationCounter,
+    // Care Team links
+    PatientToCareTeams,
+    CareTeamToMembers,
+    TemplateToTeams,
+    SystemTemplates,
+    ActiveCareTeams,
+    /// Anchor to organization-published templates
+    OrganizationTemplates,
+    /// Template version to the version that supersedes it
+    TemplateVersions,
+    TemplateToEndorsements,
+    // Multi-guardian consent links
+    PatientToGuardianPolicies,
+    ConsentToGuardianApprovals,
+    PendingGuardianConsents,
+    // Security monitoring links
+    SecurityAlerts,
+    EmergencyOverrideEvents,
+    /// Agents holding the auditor role, linked from the `system_auditors`
+    /// anchor by an admin
+    SystemAuditors,
+    /// Agents holding the operator role, linked from the `system_operators`
+    /// anchor by an admin
+    SystemOperators,
+    /// Agents holding the admin role, linked from the `system_admins` anchor
+    SystemAdmins,
+    /// Counter shards behind the operator statistics
+    StatisticsCounter,
+    // Household links
+    AgentToHouseholds,
+    HouseholdToMembers,
+    PatientToHouseholds,
+    PatientToHouseholdInvitations,
+    // Category re-affirmation links
+    CategoryReaffirmationNotices,
+    // Consent coverage gap links
+    /// Patient to the data categories their record is known to contain
+    PatientToDataCategories,
+    CoverageGapNotices,
+    // Grantee key change links
+    /// Patient to consents suspended until they re-confirm a grantee's new key
+    PendingGranteeReconfirmations,
+    // Policy transparency log links
+    /// Log anchor to every entry, tagged with its sequence number
+    PolicyLog,
+    /// Per-policy anchor to that policy's log entries
+    PolicyHistory,
+    // Audit coverage links
+    /// Receipt anchor to the patient whose data an authorization check
+    /// allowed reading, tagged with the encoded receipt
+    AuthorizationReceipts,
+    // Service agent registration links
+    /// Service agent key to its registrations
+    AgentToServiceRegistrations,
+    /// Registry anchor to every registration
+    ServiceRegistrations,
+    ServiceRegistrationUpdates,
+    /// Per-patient audit chain anchor to each chained access log, tagged
+    /// with its sequence; the highest is the chain head
+    AuditChain,
+    AccessLogToCountersignature,
+    // Role assignment links
+    /// Agent to the roles assigned to it
+    AgentToRoleAssignments,
+    RoleAssignmentUpdates,
+    // External FHIR consent links
+    /// Patient to consents received from other systems awaiting their decision
+    PendingExternalConsents,
+    // Direct messaging links
+    /// Sender and recipient to each message they are party to
+    AgentToMessages,
+    /// Clinical record to the messages asking about it
+    RecordToMessages,
+    MessageToReadReceipts,
+    // Data residency links
+    /// Jurisdiction anchor to each policy set for it
+    JurisdictionPolicies,
+    // Site validation rule links
+    /// Registry anchor to every rule set
+    ValidationRuleSets,
+    /// Facility and entry type anchor to each rule set selected for it
+    SelectedRuleSets,
+    // Category sensitivity links
+    /// Sensitivity anchor to each matrix policy set
+    SensitivityMatrixPolicies,
+    // Consent assertion links
+    /// Patient to each imported assertion, tagged with its assertion id
+    PatientToConsentAssertions,
+    /// Proposed consent to the assertion it was imported from
+    ConsentToAssertionEvidence,
+    // Performance metrics links
+    /// Performance anchor to each flushed sample
+    PerformanceSamples,
+    // Privacy budget links
+    /// Patient to each budget ledger, tagged with its dataset id
+    PatientToPrivacyBudgets,
+    PrivacyBudgetUpdates,
+    // Data retention links
+    /// Jurisdiction retention anchor to each policy set for it
+    RetentionPolicies,
+    // Idempotency links
+    /// Per-caller idempotency key anchor to the action its create made
+    IdempotencyKeys,
+    /// Original guardian policy to each version guardians wrote
+    GuardianPolicyUpdates,
+}
+
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("audit_findings", FieldLimit::Chars(NOTE_CHARS)),
+        ("exclusions", FieldLimit::Items(64)),
+        ("default_exclusions", FieldLimit::Items(64)),
+        ("data_categories", FieldLimit::Items(64)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
+#[hdk_extern]
+pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
+    match op.flattened::<EntryTypes, LinkTypes>()? {
+        FlatOp::StoreEntry(store_entry) => match store_entry {
+            OpEntry::CreateEntry { action, app_entry, .. } => {
+                let author = &action.author;
+                match app_entry {
+                    EntryTypes::Consent(c) => validate_consent_version(&c, author, &action.prev_action, None),
+                    EntryTypes::DataAccessRequest(r) => validate_access_request(&r, author),
+                    EntryTypes::DataAccessLog(l) => validate_access_log(&l, author),
+                    EntryTypes::EmergencyAccess(e) => validate_emergency_access(&e, author),
+                    EntryTypes::AuthorizationDocument(d) => validate_authorization(&d, author),
+                    EntryTypes::DelegationGrant(d) => validate_delegation_grant(&d, author),
+                    EntryTypes::AccessNotification(n) => validate_access_notification(&n, author),
+                    EntryTypes::NotificationPreferences(p) => validate_notification_preferences(&p, author),
+                    EntryTypes::NotificationDigest(d) => validate_notification_digest(&d, author),
+                    EntryTypes::CareTeamTemplate(t) => validate_care_team_template(&t, author),
+                    EntryTypes::CareTeam(t) => validate_care_team(&t, author),
+                    EntryTypes::TemplateEndorsement(e) => validate_template_endorsement(&e, author),
+                    EntryTypes::GuardianPolicy(p) => validate_guardian_policy(&p, author, &action.prev_action),
+                    EntryTypes::GuardianApproval(a) => validate_guardian_approval(&a, author),
+                    EntryTypes::SecurityAlert(a) => validate_security_alert(&a, author),
+                    EntryTypes::Household(h) => validate_household(&h, author),
+                    EntryTypes::HouseholdInvitation(i) => validate_household_invitation(&i, author),
+                    EntryTypes::PolicyLogEntry(e) => validate_policy_log_entry(&e, author),
+                    EntryTypes::ServiceAgentRegistration(r) => validate_service_registration(&r, author),
+                    EntryTypes::DirectMessage(m) => validate_direct_message(&m, author),
+                    EntryTypes::MessageReadReceipt(r) => validate_read_receipt(&r, author),
+                    EntryTypes::JurisdictionPolicy(p) => validate_jurisdiction_policy(&p, author),
+                    EntryTypes::ValidationRuleSet(r) => validate_rule_set(&r, author),
+                    EntryTypes::SensitivityMatrixPolicy(p) => validate_sensitivity_policy(&p, author),
+                    EntryTypes::ImportedConsentAssertion(a) => validate_imported_assertion(&a, author),
+                    EntryTypes::PerformanceSample(p) => validate_performance_sample(&p, author),
+                    EntryTypes::RoleAssignment(a) => validate_role_assignment(&a, author),
+                    EntryTypes::AccessCountersignature(c) => validate_access_countersignature(&c, author),
+                    EntryTypes::PrivacyBudgetLedger(l) => validate_privacy_budget_ledger(&l, author),
+                    EntryTypes::RetentionPolicy(p) => validate_retention_policy(&p, author),
+                }
+            },
+            OpEntry::UpdateEntry { action, app_entry, .. } => {
+                let author = &action.author;
+                match app_entry {
+                    EntryTypes::Consent(c) => validate_consent_version(
+                        &c,
+                        author,
+                        &action.prev_action,
+                        Some(&action.original_action_address),
+                    ),
+                    EntryTypes::DataAccessRequest(r) => validate_access_request(&r, author),
+                    EntryTypes::DataAccessLog(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Access logs are append-only".to_string(),
+                    )),
+                    EntryTypes::EmergencyAccess(e) => validate_emergency_access(&e, author),
+                    EntryTypes::AuthorizationDocument(d) => validate_authorization(&d, author),
+                    EntryTypes::DelegationGrant(d) => validate_delegation_grant(&d, author),
+                    EntryTypes::AccessNotification(n) => validate_access_notification(&n, author),
+                    EntryTypes::NotificationPreferences(p) => validate_notification_preferences(&p, author),
+                    EntryTypes::NotificationDigest(d) => validate_notification_digest(&d, author),
+                    EntryTypes::CareTeamTemplate(t) => validate_care_team_template(&t, author),
+                    EntryTypes::CareTeam(t) => validate_care_team(&t, author),
+                    EntryTypes::TemplateEndorsement(e) => validate_template_endorsement(&e, author),
+                    EntryTypes::GuardianPolicy(p) => validate_guardian_policy_update(&p, &action),
+                    EntryTypes::GuardianApproval(a) => validate_guardian_approval(&a, author),
+                    EntryTypes::SecurityAlert(a) => validate_security_alert(&a, author),
+                    EntryTypes::Household(h) => validate_household(&h, author),
+                    EntryTypes::HouseholdInvitation(i) => validate_household_invitation(&i, author),
+                    EntryTypes::PolicyLogEntry(_) => Ok(ValidateCallbackResult::Invalid(
+                        "The policy log is append-only".to_string(),
+                    )),
+                    EntryTypes::ServiceAgentRegistration(r) => {
+                        validate_service_registration_update(&r, &action)
+                    }
+                    EntryTypes::DirectMessage(_) | EntryTypes::MessageReadReceipt(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Messages and read receipts cannot be edited".to_string(),
+                    )),
+                    EntryTypes::JurisdictionPolicy(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Jurisdiction policies are replaced, not edited".to_string(),
+                    )),
+                    EntryTypes::ValidationRuleSet(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Rule sets are replaced, not edited".to_string(),
+                    )),
+                    EntryTypes::SensitivityMatrixPolicy(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Sensitivity policies are replaced, not edited".to_string(),
+                    )),
+                    EntryTypes::ImportedConsentAssertion(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Imported consent assertions cannot be edited".to_string(),
+                    )),
+                    EntryTypes::PerformanceSample(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Performance samples cannot be edited".to_string(),
+                    )),
+                    EntryTypes::RoleAssignment(a) => validate_role_assignment_update(&a, &action),
+                    EntryTypes::AccessCountersignature(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Access countersignatures cannot be edited".to_string(),
+                    )),
+                    EntryTypes::PrivacyBudgetLedger(l) => validate_privacy_budget_update(&l, &action),
+                    EntryTypes::RetentionPolicy(_) => Ok(ValidateCallbackResult::Invalid(
+                        "Retention policies are replaced, not edited".to_string(),
+                    )),
+                }
+            }
+            _ => Ok(ValidateCallbackResult::Valid),
+        },
+        FlatOp::RegisterDelete(OpDelete { action }) => {
+            let original = must_get_action(action.deletes_address)?;
+            let policy_log_type = EntryType::try_from(UnitEntryTypes::PolicyLogEntry)?;
+            if original.action().entry_type() == Some(&policy_log_type) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "The policy log is append-only".to_string(),
+                ));
+            }
+            let guardian_policy_type = EntryType::try_from(UnitEntryTypes::GuardianPolicy)?;
+            if original.action().entry_type() == Some(&guardian_policy_type) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Guardian policies are deactivated by their guardians, not deleted".to_string(),
+                ));
+            }
+            let access_log_type = EntryType::try_from(UnitEntryTypes::DataAccessLog)?;
+            if original.action().entry_type() == Some(&access_log_type) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Access logs are append-only".to_string(),
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::SystemAdmins,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_admin_link(&base_address, &target_address, &tag, &action.author),
+        FlatOp::RegisterDeleteLink {
+            link_type: LinkTypes::SystemAdmins,
+            original_action,
+            action,
+            ..
+        } => validate_admin_link_delete(&original_action, &action),
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::SystemOperators,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_role_link("system_operators", &base_address, &target_address, &tag, &action.author),
+        FlatOp::RegisterDeleteLink {
+            link_type: LinkTypes::SystemOperators,
+            original_action,
+            action,
+            ..
+        } => validate_role_link_delete(&original_action, &action),
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::AgentToServiceRegistrations,
+            base_address,
+            target_address,
+            action,
+            ..
+        } => validate_service_registration_link(&base_address, &target_address, &action.author),
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::ServiceRegistrationUpdates,
+            base_address,
+            target_address,
+            ..
+        } => validate_service_registration_updates_link(&base_address, &target_address),
+        FlatOp::RegisterDeleteLink {
+            link_type: LinkTypes::AgentToServiceRegistrations | LinkTypes::ServiceRegistrationUpdates,
+            ..
+        } => Ok(ValidateCallbackResult::Invalid(
+            "Service registrations are revoked, not unlinked".to_string(),
+        )),
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::SystemAuditors,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_role_link("system_auditors", &base_address, &target_address, &tag, &action.author),
+        FlatOp::RegisterDeleteLink {
+            link_type: LinkTypes::SystemAuditors,
+            original_action,
+            action,
+            ..
+        } => validate_role_link_delete(&original_action, &action),
+        FlatOp::RegisterCreateLink {
+            link_type: LinkTypes::AuditChain,
+            base_address,
+            target_address,
+            tag,
+            action,
+        } => validate_audit_chain_link(&base_address, &target_address, &tag, &action.author),
+        FlatOp::RegisterDeleteLink {
+            link_type: LinkTypes::AuditChain,
+            ..
+        } => Ok(ValidateCallbackResult::Invalid(
+            "The audit chain is append-only".to_string(),
+        )),
+        _ => Ok(ValidateCallbackResult::Valid),
+    }
+}
+
+fn validate_consent(consent: &Consent, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if consent.consent_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Consent ID is required".to_string(),
+        ));
+    }
+    if consent.permissions.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "At least one permission must be granted".to_string(),
+        ));
+    }
+    if let Some(registry) = &consent.category_registry {
+        if registry.version > CATEGORY_REGISTRY_VERSION {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Consent category registry is newer than this DNA".to_string(),
+            ));
+        }
+        if registry.categories.contains(&DataCategory::All) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Category registry must list concrete categories only".to_string(),
+            ));
+        }
+    }
+    let ownership = validate_patient_reference_and_ownership(&consent.patient_hash, author, "create consent")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_access_request(request: &DataAccessRequest, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if request.request_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Request ID is required".to_string(),
+        ));
+    }
+    if request.justification.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Justification is required for data access requests".to_string(),
+        ));
+    }
+    let patient_ref = validate_patient_reference(&request.patient_hash)?;
+    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
+        return Ok(patient_ref);
+    }
+    if &request.requestor != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Data access requestor must match the action author".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_access_log(log: &DataAccessLog, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if log.log_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Log ID is required".to_string(),
+        ));
+    }
+    if log.emergency_override && log.override_reason.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Override reason is required for emergency access".to_string(),
+        ));
+    }
+    let patient_ref = validate_patient_reference(&log.patient_hash)?;
+    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
+        return Ok(patient_ref);
+    }
+    if &log.accessor != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Access log accessor must match the action author".to_string(),
+        ));
+    }
+    if let Some(context) = &log.access_context {
+        if let Some(error) = context.validate().errors.first() {
+            return Ok(ValidateCallbackResult::Invalid(format!("Invalid access context: {}", error)));
+        }
+    }
+    validate_access_log_chain(log)
+}
+
+/// Every log takes a place in its patient's chain, following a log of the
+/// same patient one position back
+fn validate_access_log_chain(log: &DataAccessLog) -> ExternResult<ValidateCallbackResult> {
+    match (&log.previous_log, log.chain_sequence) {
+        (_, None) => Ok(ValidateCallbackResult::Invalid(
+            "Access logs must carry their position in the patient's audit chain".to_string(),
+        )),
+        (None, Some(0)) => Ok(ValidateCallbackResult::Valid),
+        (Some(previous_hash), Some(sequence)) if sequence > 0 => {
+            let previous: DataAccessLog = must_get_valid_record(previous_hash.clone())?
+                .entry()
+                .to_app_option()
+                .map_err(|e| wasm_error!(e))?
+                .ok_or(wasm_error!(WasmErrorInner::Guest("Previous access log not found".to_string())))?;
+            if previous.patient_hash != log.patient_hash || previous.chain_sequence != Some(sequence - 1) {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Access log must follow the previous log in the patient's chain".to_string(),
+                ));
+            }
+            Ok(ValidateCallbackResult::Valid)
+        }
+        _ => Ok(ValidateCallbackResult::Invalid(
+            "Access log chain position does not match its previous log".to_string(),
+        )),
+    }
+}
+
+/// Anchor of a patient's access log chain
+pub fn audit_chain_anchor(patient_hash: &ActionHash) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("audit_chain:{}", patient_hash))
+}
+
+/// An audit chain link is written by the accessor, from the log's patient
+/// anchor and tagged with the log's own sequence
+fn validate_audit_chain_link(
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    tag: &LinkTag,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(log_hash) = target.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Audit chain links must point at an access log".to_string(),
+        ));
+    };
+    let record = must_get_valid_record(log_hash)?;
+    let Some(log) = record.entry().to_app_option::<DataAccessLog>().ok().flatten() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Audit chain links must point at an access log".to_string(),
+        ));
+    };
+    if record.action().author() != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the accessor can chain an access log".to_string(),
+        ));
+    }
+    if *base != AnyLinkableHash::from(audit_chain_anchor(&log.patient_hash)?) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Audit chain links must start at the log's patient chain".to_string(),
+        ));
+    }
+    if log.chain_sequence.map(|sequence| sequence.to_be_bytes().to_vec()).as_deref() != Some(tag.as_ref().as_slice()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Audit chain link tag must be the log's chain sequence".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The receipt must be signed by the patient's agent over the exact log the
+/// accessor committed
+fn validate_access_countersignature(
+    countersignature: &AccessCountersignature,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let claims = &countersignature.claims;
+    if &claims.accessor != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Countersignatures are stored by the accessor".to_string(),
+        ));
+    }
+    let log_record = must_get_valid_record(countersignature.log_hash.clone())?;
+    let log: DataAccessLog = log_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Access log not found".to_string())))?;
+    if log_record.action().entry_hash() != Some(&claims.log_entry_hash)
+        || log.patient_hash != claims.patient_hash
+        || log.accessor != claims.accessor
+        || log.accessed_at != claims.accessed_at
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Countersignature claims do not match the access log".to_string(),
+        ));
+    }
+    let patient_record = must_get_valid_record(claims.patient_hash.clone())?;
+    if patient_record.action().author() != &countersignature.patient_agent {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Countersignature must come from the patient's agent".to_string(),
+        ));
+    }
+    if !verify_signature(countersignature.patient_agent.clone(), countersignature.signature.clone(), claims)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Invalid patient countersignature".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_emergency_access(
+    emergency: &EmergencyAccess,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
     if emergency.emergency_id.is_empty() {
         return Ok(ValidateCallbackResult::Invalid(
-            "Emergency ID is required".to_string(),
+            "Emergency ID is required".to_string(),
+        ));
+    }
+    if emergency.reason.is_empty() || emergency.clinical_justification.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Emergency access requires reason and clinical justification".to_string(),
+        ));
+    }
+    let patient_ref = validate_patient_reference(&emergency.patient_hash)?;
+    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
+        return Ok(patient_ref);
+    }
+    if &emergency.accessor != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Emergency access accessor must match the action author".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_authorization(
+    doc: &AuthorizationDocument,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if doc.document_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Document ID is required".to_string(),
+        ));
+    }
+    if doc.patient_signature.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Patient signature is required".to_string(),
+        ));
+    }
+    let ownership = validate_patient_reference_and_ownership(&doc.patient_hash, author, "create authorization document")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: CONSENT DELEGATION
+// ============================================================
+
+fn validate_delegation_grant(
+    delegation: &DelegationGrant,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if delegation.delegation_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Delegation ID is required".to_string(),
+        ));
+    }
+    if delegation.permissions.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "At least one permission must be granted".to_string(),
+        ));
+    }
+    if delegation.data_scope.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Data scope must specify at least one category".to_string(),
+        ));
+    }
+    let ownership = validate_patient_reference_and_ownership(&delegation.patient_hash, author, "create delegation grant")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    // Healthcare proxy and legal guardian require identity verification
+    if matches!(delegation.delegation_type, DelegationType::HealthcareProxy | DelegationType::LegalGuardian) {
+        if !delegation.identity_verified {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Healthcare proxy and legal guardian delegations require identity verification".to_string(),
+            ));
+        }
+        if delegation.legal_document_hash.is_none() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Healthcare proxy and legal guardian require legal documentation".to_string(),
+            ));
+        }
+    }
+    // Temporary delegation must have expiration
+    if matches!(delegation.delegation_type, DelegationType::Temporary) {
+        if delegation.expires_at.is_none() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Temporary delegations must have an expiration date".to_string(),
+            ));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: PATIENT NOTIFICATIONS
+// ============================================================
+
+fn validate_access_notification(
+    notification: &AccessNotification,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if notification.notification_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Notification ID is required".to_string(),
+        ));
+    }
+    if notification.accessor_name.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Accessor name is required for patient-friendly notifications".to_string(),
+        ));
+    }
+    if notification.summary.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Plain language summary is required".to_string(),
+        ));
+    }
+    if notification.data_categories.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Data categories accessed must be specified".to_string(),
+        ));
+    }
+    let patient_ref = validate_patient_reference(&notification.patient_hash)?;
+    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
+        return Ok(patient_ref);
+    }
+    if &notification.accessor != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Notification accessor must match the action author".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_notification_preferences(
+    prefs: &NotificationPreferences,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let ownership = validate_patient_reference_and_ownership(&prefs.patient_hash, author, "set notification preferences")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    // Validate daily digest hour
+    if let Some(hour) = prefs.daily_digest_hour {
+        if hour > 23 {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Daily digest hour must be 0-23".to_string(),
+            ));
+        }
+    }
+    // Validate weekly summary day
+    if let Some(day) = prefs.weekly_summary_day {
+        if day > 6 {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Weekly summary day must be 0-6 (Sunday-Saturday)".to_string(),
+            ));
+        }
+    }
+    // If email enabled, email address required
+    if prefs.email_enabled && prefs.email_address.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Email address required when email notifications enabled".to_string(),
+        ));
+    }
+    // If SMS enabled, phone number required
+    if prefs.sms_enabled && prefs.phone_number.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Phone number required when SMS notifications enabled".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_notification_digest(
+    digest: &NotificationDigest,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if digest.digest_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Digest ID is required".to_string(),
+        ));
+    }
+    let ownership = validate_patient_reference_and_ownership(&digest.patient_hash, author, "create notification digest")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    // Period end must be after period start
+    if digest.period_end.as_micros() <= digest.period_start.as_micros() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Period end must be after period start".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: CARE TEAM TEMPLATES
+// ============================================================
+
+fn validate_care_team_template(template: &CareTeamTemplate, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if template.template_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Template ID is required".to_string(),
+        ));
+    }
+    if template.name.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Template name is required".to_string(),
+        ));
+    }
+    if template.description.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Template description is required for patient understanding".to_string(),
+        ));
+    }
+    if template.permissions.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Template must specify at least one permission".to_string(),
+        ));
+    }
+    if template.data_categories.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Template must specify at least one data category".to_string(),
+        ));
+    }
+    if &template.created_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Template creator must match the action author".to_string(),
+        ));
+    }
+    if matches!(&template.template_type, TemplateType::Organization(org) if org.trim().is_empty()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Organization templates must name the publishing organization".to_string(),
+        ));
+    }
+    match &template.previous_version {
+        None if template.version() != 1 => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Only the first version of a template can omit its previous version".to_string(),
+            ));
+        }
+        None => {}
+        Some(previous_hash) => {
+            let previous_record = must_get_valid_record(previous_hash.clone())?;
+            let Some(previous) = previous_record
+                .entry()
+                .to_app_option::<CareTeamTemplate>()
+                .ok()
+                .flatten()
+            else {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Previous version must be a care team template".to_string(),
+                ));
+            };
+            if previous_record.action().author() != author
+                || previous.template_id != template.template_id
+                || previous.template_type != template.template_type
+            {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "A new template version must come from the same publisher and template".to_string(),
+                ));
+            }
+            if template.version() != previous.version() + 1 {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Template version must follow version {}",
+                    previous.version()
+                )));
+            }
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_template_endorsement(
+    endorsement: &TemplateEndorsement,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if &endorsement.endorsed_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Endorser must match the action author".to_string(),
+        ));
+    }
+    let template_record = must_get_valid_record(endorsement.template_hash.clone())?;
+    let is_organization_template = template_record
+        .entry()
+        .to_app_option::<CareTeamTemplate>()
+        .ok()
+        .flatten()
+        .is_some_and(|t| matches!(t.template_type, TemplateType::Organization(_)));
+    if !is_organization_template {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only organization-published templates are endorsed".to_string(),
+        ));
+    }
+    if template_record.action().author() == author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Publishers cannot endorse their own templates".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_policy_log_entry(entry: &PolicyLogEntry, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if &entry.recorded_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Policy log recorder must match the action author".to_string(),
+        ));
+    }
+    if entry.policy_id.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Policy ID is required".to_string(),
+        ));
+    }
+    match (&entry.previous, entry.sequence) {
+        (None, 0) => {}
+        (Some(previous), sequence) if sequence > 0 => {
+            let follows = must_get_valid_record(previous.clone())?
+                .entry()
+                .to_app_option::<PolicyLogEntry>()
+                .ok()
+                .flatten()
+                .is_some_and(|p| p.sequence + 1 == sequence);
+            if !follows {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Policy log entry must directly follow its previous entry".to_string(),
+                ));
+            }
+        }
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Only the first policy log entry has no previous entry".to_string(),
+            ))
+        }
+    }
+    let policy = must_get_valid_record(entry.policy_action.clone())?;
+    if policy.action().entry_hash() != Some(&entry.policy_entry_hash) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Logged entry hash does not match the policy record".to_string(),
+        ));
+    }
+    if policy.action().author() != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only a policy's author can log its changes".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_care_team(team: &CareTeam, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if team.team_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Team ID is required".to_string(),
+        ));
+    }
+    if team.team_name.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Team name is required".to_string(),
+        ));
+    }
+    if team.members.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Care team must have at least one member".to_string(),
+        ));
+    }
+    if team.permissions.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Care team must have at least one permission".to_string(),
+        ));
+    }
+    if team.data_categories.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Care team must specify data categories".to_string(),
+        ));
+    }
+    for (i, role_override) in team.role_overrides.iter().enumerate() {
+        if team.role_overrides[..i].iter().any(|o| o.role == role_override.role) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Role {:?} has more than one permission override",
+                role_override.role
+            )));
+        }
+        if role_override.permissions.is_empty() || role_override.data_categories.is_empty() {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Override for role {:?} must grant at least one permission and category",
+                role_override.role
+            )));
+        }
+        if let Some(extra) = role_override.permissions.iter().find(|p| !team.permissions.contains(p)) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Override for role {:?} grants {:?}, which the team does not have",
+                role_override.role, extra
+            )));
+        }
+        let team_has_all = team.data_categories.contains(&DataCategory::All);
+        for category in &role_override.data_categories {
+            let covered = team_has_all || team.data_categories.contains(category);
+            if !covered || team.exclusions.contains(category) {
+                return Ok(ValidateCallbackResult::Invalid(format!(
+                    "Override for role {:?} includes {:?}, which is outside the team's categories",
+                    role_override.role, category
+                )));
+            }
+        }
+    }
+    let ownership = validate_patient_reference_and_ownership(&team.patient_hash, author, "create care team")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: MULTI-GUARDIAN CONSENT
+// ============================================================
+
+/// A patient's guardian policy: written once by the patient, after which
+/// only the guardians change it
+fn validate_guardian_policy(
+    policy: &GuardianPolicy,
+    author: &AgentPubKey,
+    prev_action: &ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let rules = validate_guardian_policy_rules(policy)?;
+    if !matches!(rules, ValidateCallbackResult::Valid) {
+        return Ok(rules);
+    }
+    let ownership = validate_patient_reference_and_ownership(&policy.patient_hash, author, "create guardian policy")?;
+    if !matches!(ownership, ValidateCallbackResult::Valid) {
+        return Ok(ownership);
+    }
+    if chained_guardian_policy(author, prev_action, &policy.patient_hash)?.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "The patient already has a guardian policy; its guardians update it".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Policy versions are written by a guardian of the version they replace
+/// and stay with the same patient
+fn validate_guardian_policy_update(policy: &GuardianPolicy, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    let previous: GuardianPolicy = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original guardian policy not found".to_string())))?;
+    if !previous.guardians.contains(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the policy's guardians can change a guardian policy".to_string(),
+        ));
+    }
+    if policy.patient_hash != previous.patient_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A guardian policy cannot move to another patient".to_string(),
+        ));
+    }
+    validate_guardian_policy_rules(policy)
+}
+
+fn validate_guardian_policy_rules(policy: &GuardianPolicy) -> ExternResult<ValidateCallbackResult> {
+    if policy.policy_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Policy ID is required".to_string(),
+        ));
+    }
+    if policy.guardians.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Guardian policy must list at least one guardian".to_string(),
+        ));
+    }
+    if policy.rules.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Guardian policy must define at least one quorum rule".to_string(),
+        ));
+    }
+    for rule in &policy.rules {
+        if rule.min_approvals == 0 {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Quorum rules must require at least one approval".to_string(),
+            ));
+        }
+        if rule.min_approvals as usize > policy.guardians.len() {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Quorum cannot exceed the number of guardians".to_string(),
+            ));
+        }
+        if rule.required_approvers.iter().any(|a| !policy.guardians.contains(a)) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Required approvers must be listed as guardians".to_string(),
+            ));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// The first guardian policy `author` wrote for the patient, found by
+/// walking their chain back from `chain_top`
+fn chained_guardian_policy(
+    author: &AgentPubKey,
+    chain_top: &ActionHash,
+    patient_hash: &ActionHash,
+) -> ExternResult<Option<ActionHash>> {
+    let policy_type = EntryType::try_from(UnitEntryTypes::GuardianPolicy)?;
+    let mut first: Option<(u32, ActionHash)> = None;
+    for activity in must_get_agent_activity(author.clone(), ChainFilter::new(chain_top.clone()))? {
+        let Action::Create(create) = activity.action.action() else {
+            continue;
+        };
+        if create.entry_type != policy_type {
+            continue;
+        }
+        let policy = GuardianPolicy::try_from(must_get_entry(create.entry_hash.clone())?.into_content())?;
+        if policy.patient_hash != *patient_hash {
+            continue;
+        }
+        if first.as_ref().is_none_or(|(seq, _)| create.action_seq < *seq) {
+            first = Some((create.action_seq, activity.action.as_hash().clone()));
+        }
+    }
+    Ok(first.map(|(_, hash)| hash))
+}
+
+/// The create an update chain starts from
+fn root_action(mut hash: ActionHash) -> ExternResult<ActionHash> {
+    loop {
+        match must_get_action(hash.clone())?.action() {
+            Action::Update(update) => hash = update.original_action_address.clone(),
+            _ => return Ok(hash),
+        }
+    }
+}
+
+/// Every consent version for a patient with a guardian policy cites a
+/// version of that policy; while the cited version is active and covers
+/// the consent's categories, an active consent must carry approvals of its
+/// original action from the quorum. A consent under a quorum is therefore
+/// created pending and activated by update.
+fn validate_consent_version(
+    consent: &Consent,
+    author: &AgentPubKey,
+    prev_action: &ActionHash,
+    updates: Option<&ActionHash>,
+) -> ExternResult<ValidateCallbackResult> {
+    let basics = validate_consent(consent, author)?;
+    if !matches!(basics, ValidateCallbackResult::Valid) {
+        return Ok(basics);
+    }
+    let Some(policy_root) = chained_guardian_policy(author, prev_action, &consent.patient_hash)? else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    let Some(cited) = &consent.guardian_policy else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Consents for a patient with a guardian policy must cite the policy".to_string(),
+        ));
+    };
+    if root_action(cited.clone())? != policy_root {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Consent cites a guardian policy other than the patient's".to_string(),
+        ));
+    }
+    let policy: GuardianPolicy = must_get_valid_record(cited.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Cited guardian policy not found".to_string())))?;
+    if !policy.active || !matches!(consent.status, ConsentStatus::Active) {
+        return Ok(ValidateCallbackResult::Valid);
+    }
+    let Some((required_approvers, min_approvals)) = policy.quorum_for(&consent.scope.data_categories) else {
+        return Ok(ValidateCallbackResult::Valid);
+    };
+    let Some(updates) = updates else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Consents covered by a guardian quorum must start pending".to_string(),
+        ));
+    };
+
+    let consent_root = root_action(updates.clone())?;
+    let mut approvers: Vec<AgentPubKey> = Vec::new();
+    for approval_hash in &consent.guardian_approvals {
+        let approval = must_get_valid_record(approval_hash.clone())?
+            .entry()
+            .to_app_option::<GuardianApproval>()
+            .ok()
+            .flatten();
+        let Some(approval) = approval else {
+            return Ok(ValidateCallbackResult::Invalid(
+                "guardian_approvals must reference guardian approvals".to_string(),
+            ));
+        };
+        if !approval.approved || approval.consent_hash != consent_root || approval.policy_hash != *cited {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Guardian approvals must approve this consent under the cited policy".to_string(),
+            ));
+        }
+        if !approvers.contains(&approval.guardian) {
+            approvers.push(approval.guardian);
+        }
+    }
+    if approvers.len() < min_approvals as usize || required_approvers.iter().any(|a| !approvers.contains(a)) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Guardian quorum not met for an active consent".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_guardian_approval(
+    approval: &GuardianApproval,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if &approval.guardian != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Guardian approval must be authored by the guardian".to_string(),
+        ));
+    }
+    let policy_record = must_get_valid_record(approval.policy_hash.clone())?;
+    let policy = match policy_record.entry().to_app_option::<GuardianPolicy>() {
+        Ok(Some(policy)) => policy,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "policy_hash must reference a guardian policy".to_string(),
+            ));
+        }
+    };
+    if !policy.guardians.contains(author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only guardians named in the policy can approve".to_string(),
+        ));
+    }
+    let consent_record = must_get_valid_record(approval.consent_hash.clone())?;
+    let consent = match consent_record.entry().to_app_option::<Consent>() {
+        Ok(Some(consent)) => consent,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "consent_hash must reference a consent".to_string(),
+            ));
+        }
+    };
+    if consent.patient_hash != policy.patient_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Guardian policy and consent must belong to the same patient".to_string(),
+        ));
+    }
+    if !verify_signature(author.clone(), approval.signature.clone(), &approval.consent_hash)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Guardian signature does not match the consent".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: SECURITY MONITORING
+// ============================================================
+
+fn validate_security_alert(
+    alert: &SecurityAlert,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if alert.alert_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Alert ID is required".to_string(),
+        ));
+    }
+    if alert.description.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Alert description is required".to_string(),
+        ));
+    }
+    if &alert.raised_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Security alert must be raised by the action author".to_string(),
+        ));
+    }
+    if let Some(patient_hash) = &alert.patient_hash {
+        let patient_ref = validate_patient_reference(patient_hash)?;
+        if !matches!(patient_ref, ValidateCallbackResult::Valid) {
+            return Ok(patient_ref);
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: HOUSEHOLD GROUPING
+// ============================================================
+
+fn validate_household(household: &Household, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if household.household_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Household ID is required".to_string(),
+        ));
+    }
+    if household.name.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Household name is required".to_string(),
+        ));
+    }
+    if &household.created_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Household creator must match the action author".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_household_invitation(
+    invitation: &HouseholdInvitation,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let household_record = must_get_valid_record(invitation.household_hash.clone())?;
+    let household = match household_record.entry().to_app_option::<Household>() {
+        Ok(Some(household)) => household,
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "household_hash must reference a household".to_string(),
+            ));
+        }
+    };
+    if invitation.invited_by != household.created_by {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the household creator can invite members".to_string(),
+        ));
+    }
+    match invitation.status {
+        // Issuing or withdrawing is done by the inviter
+        InvitationStatus::Pending | InvitationStatus::Withdrawn => {
+            if &invitation.invited_by != author {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Household invitation must be issued by the inviter".to_string(),
+                ));
+            }
+            let patient_ref = validate_patient_reference(&invitation.patient_hash)?;
+            if !matches!(patient_ref, ValidateCallbackResult::Valid) {
+                return Ok(patient_ref);
+            }
+        }
+        // Accepting or declining is the invited patient's decision
+        InvitationStatus::Accepted | InvitationStatus::Declined => {
+            if invitation.responded_at.is_none() {
+                return Ok(ValidateCallbackResult::Invalid(
+                    "Invitation responses must record when they were made".to_string(),
+                ));
+            }
+            let ownership = validate_patient_reference_and_ownership(&invitation.patient_hash, author, "respond to household invitation")?;
+            if !matches!(ownership, ValidateCallbackResult::Valid) {
+                return Ok(ownership);
+            }
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_patient_reference(patient_hash: &ActionHash) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(patient_hash.clone())?;
+    match record.entry() {
+        RecordEntry::Present(_) => Ok(ValidateCallbackResult::Valid),
+        _ => Ok(ValidateCallbackResult::Invalid(
+            "patient_hash must reference an existing record with an entry".to_string(),
+        )),
+    }
+}
+
+fn validate_patient_reference_and_ownership(
+    patient_hash: &ActionHash,
+    author: &AgentPubKey,
+    operation: &str,
+) -> ExternResult<ValidateCallbackResult> {
+    let record = must_get_valid_record(patient_hash.clone())?;
+    match record.entry() {
+        RecordEntry::Present(_) => {}
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "patient_hash must reference an existing record with an entry".to_string(),
+            ));
+        }
+    }
+    if record.action().author() != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            format!("Only the patient can {} for this patient_hash", operation),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_service_registration(
+    registration: &ServiceAgentRegistration,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if registration.registration_id.is_empty() || registration.service_name.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registrations need an ID and a service name".to_string(),
+        ));
+    }
+    if registration.registered_by != *author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registrations must be created by the registering admin".to_string(),
+        ));
+    }
+    if !proves_admin(author, registration.admin_link.as_ref())? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only admins can register service agents".to_string(),
+        ));
+    }
+    if registration.registered_by == registration.agent {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A service agent cannot register itself".to_string(),
+        ));
+    }
+    if registration.allowed.iter().any(|grant| grant.permissions.is_empty()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Each allowed category needs at least one permission".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Updates only revoke, by an admin: the allowlist and agent key never
+/// change in place
+fn validate_service_registration_update(
+    registration: &ServiceAgentRegistration,
+    action: &Update,
+) -> ExternResult<ValidateCallbackResult> {
+    if !proves_admin(&action.author, registration.admin_link.as_ref())? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only admins can revoke a service registration".to_string(),
+        ));
+    }
+    let original: ServiceAgentRegistration = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original service registration not found".to_string())))?;
+    let unrevoked = ServiceAgentRegistration {
+        revoked_at: None,
+        admin_link: original.admin_link.clone(),
+        ..registration.clone()
+    };
+    if original.revoked_at.is_some() || registration.revoked_at.is_none() || unrevoked != original {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registrations can only be revoked once; rotate to change them".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A service agent's link to a registration is written by the registering
+/// admin from the agent the registration names
+fn validate_service_registration_link(
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let Some(target) = target.clone().into_action_hash() else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registration links must point at a registration".to_string(),
+        ));
+    };
+    let Some(registration) = must_get_valid_record(target)?
+        .entry()
+        .to_app_option::<ServiceAgentRegistration>()
+        .ok()
+        .flatten()
+    else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registration links must point at a registration".to_string(),
+        ));
+    };
+    if *base != AnyLinkableHash::from(registration.agent.clone()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registration links must hang off the registered agent".to_string(),
+        ));
+    }
+    if registration.registered_by != *author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registration links are written by the registering admin".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A registration links only to its own later versions
+fn validate_service_registration_updates_link(
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let (Some(original), Some(target)) = (base.clone().into_action_hash(), target.clone().into_action_hash()) else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registration update links join two registrations".to_string(),
+        ));
+    };
+    if !matches!(must_get_action(target.clone())?.action(), Action::Update(_)) || root_action(target)? != original {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Service registration update links must point at a version of the registration".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Tag of a `SystemAdmins` link: the granting admin's own admin link, or
+/// empty when a bootstrap admin grants
+pub fn admin_link_tag(proof: Option<&ActionHash>) -> LinkTag {
+    LinkTag::new(proof.map(|hash| hash.get_raw_39().to_vec()).unwrap_or_default())
+}
+
+/// The admin link a `SystemAdmins` link was granted under, if any
+pub fn admin_link_proof(tag: &LinkTag) -> Option<ActionHash> {
+    ActionHash::try_from_raw_39(tag.as_ref().to_vec()).ok()
+}
+
+/// Whether `author` holds the admin role as far as validation can tell:
+/// a bootstrap admin, or the target of the valid admin link `proof`
+///
+/// Validation cannot see deletes, so reads re-check the
+/// chain of proofs against the live links.
+pub fn proves_admin(author: &AgentPubKey, proof: Option<&ActionHash>) -> ExternResult<bool> {
+    if HealthDnaProperties::current()?.bootstrap_admin_keys().contains(author) {
+        return Ok(true);
+    }
+    let Some(proof) = proof else {
+        return Ok(false);
+    };
+    links_role_to(proof, "system_admins", LinkTypes::SystemAdmins, author)
+}
+
+/// Whether `author` holds the operator role as far as validation can tell:
+/// an admin, or the target of the valid operator link `proof`
+pub fn proves_operator(author: &AgentPubKey, proof: Option<&ActionHash>) -> ExternResult<bool> {
+    if proves_admin(author, proof)? {
+        return Ok(true);
+    }
+    let Some(proof) = proof else {
+        return Ok(false);
+    };
+    links_role_to(proof, "system_operators", LinkTypes::SystemOperators, author)
+}
+
+/// Whether `proof` is a valid link of `link_type` from the role's anchor
+/// to `agent`
+fn links_role_to(proof: &ActionHash, anchor: &str, link_type: LinkTypes, agent: &AgentPubKey) -> ExternResult<bool> {
+    let anchor = AnyLinkableHash::from(anchor_hash(anchor)?);
+    let link_type = ScopedLinkType::try_from(link_type)?;
+    Ok(match must_get_valid_record(proof.clone())?.action() {
+        Action::CreateLink(link) => {
+            link.zome_index == link_type.zome_index
+                && link.link_type == link_type.zome_type
+                && link.base_address == anchor
+                && link.target_address.clone().into_agent_pub_key().as_ref() == Some(agent)
+        }
+        _ => false,
+    })
+}
+
+/// Admin links hang off the `system_admins` anchor, point at an agent and
+/// are written by a bootstrap admin or an admin citing their own link
+fn validate_admin_link(
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    tag: &LinkTag,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if *base != AnyLinkableHash::from(anchor_hash("system_admins")?) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Admin links must hang off the system_admins anchor".to_string(),
         ));
     }
-    if emergency.reason.is_empty() || emergency.clinical_justification.is_empty() {
+    if target.clone().into_agent_pub_key().is_none() {
         return Ok(ValidateCallbackResult::Invalid(
-            "Emergency access requires reason and clinical justification".to_string(),
+            "Admin links must point at an agent".to_string(),
         ));
     }
-    let patient_ref = validate_patient_reference(&emergency.patient_hash)?;
-    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
-        return Ok(patient_ref);
+    if !proves_admin(author, admin_link_proof(tag).as_ref())? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only admins can grant the admin role".to_string(),
+        ));
     }
-    if &emergency.accessor != author {
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Bootstrap admins can never be revoked, so the role is never left
+/// without a holder; other admin links are removed by the admin who wrote
+/// them, a bootstrap admin, or the admin themselves
+fn validate_admin_link_delete(original: &CreateLink, action: &DeleteLink) -> ExternResult<ValidateCallbackResult> {
+    let bootstrap = HealthDnaProperties::current()?.bootstrap_admin_keys();
+    let target = original.target_address.clone().into_agent_pub_key();
+    if target.as_ref().is_some_and(|agent| bootstrap.contains(agent)) {
         return Ok(ValidateCallbackResult::Invalid(
-            "Emergency access accessor must match the action author".to_string(),
+            "Bootstrap admins cannot be revoked".to_string(),
+        ));
+    }
+    let author = &action.author;
+    if *author != original.author && !bootstrap.contains(author) && target.as_ref() != Some(author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the granting admin, a bootstrap admin or the admin themselves can revoke an admin link".to_string(),
         ));
     }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_authorization(
-    doc: &AuthorizationDocument,
+/// Links granting an anchored role hang off the role's anchor, point at an
+/// agent and are written by an admin citing their admin link in the tag
+fn validate_role_link(
+    anchor: &str,
+    base: &AnyLinkableHash,
+    target: &AnyLinkableHash,
+    tag: &LinkTag,
     author: &AgentPubKey,
 ) -> ExternResult<ValidateCallbackResult> {
-    if doc.document_id.is_empty() {
+    if *base != AnyLinkableHash::from(anchor_hash(anchor)?) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Role links must hang off the {} anchor",
+            anchor
+        )));
+    }
+    if target.clone().into_agent_pub_key().is_none() {
         return Ok(ValidateCallbackResult::Invalid(
-            "Document ID is required".to_string(),
+            "Role links must point at an agent".to_string(),
         ));
     }
-    if doc.patient_signature.is_empty() {
+    if !proves_admin(author, admin_link_proof(tag).as_ref())? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Patient signature is required".to_string(),
+            "Only admins can grant this role".to_string(),
         ));
     }
-    let ownership = validate_patient_reference_and_ownership(&doc.patient_hash, author, "create authorization document")?;
-    if !matches!(ownership, ValidateCallbackResult::Valid) {
-        return Ok(ownership);
-    }
     Ok(ValidateCallbackResult::Valid)
 }
 
-// ============================================================
-// VALIDATION: CONSENT DELEGATION
-// ============================================================
-
-fn validate_delegation_grant(
-    delegation: &DelegationGrant,
-    author: &AgentPubKey,
-) -> ExternResult<ValidateCallbackResult> {
-    if delegation.delegation_id.is_empty() {
+/// Role links are removed by the admin who wrote them, a bootstrap admin or
+/// the role holder
+fn validate_role_link_delete(original: &CreateLink, action: &DeleteLink) -> ExternResult<ValidateCallbackResult> {
+    let author = &action.author;
+    if *author != original.author
+        && !HealthDnaProperties::current()?.bootstrap_admin_keys().contains(author)
+        && original.target_address.clone().into_agent_pub_key().as_ref() != Some(author)
+    {
         return Ok(ValidateCallbackResult::Invalid(
-            "Delegation ID is required".to_string(),
+            "Only the granting admin, a bootstrap admin or the holder can remove a role link".to_string(),
         ));
     }
-    if delegation.permissions.is_empty() {
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_role_assignment(assignment: &RoleAssignment, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if assignment.assigned_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "At least one permission must be granted".to_string(),
+            "Role assignments must be created by the assigning admin".to_string(),
         ));
     }
-    if delegation.data_scope.is_empty() {
+    if !proves_admin(author, assignment.admin_link.as_ref())? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Data scope must specify at least one category".to_string(),
+            "Only admins can assign roles".to_string(),
         ));
     }
-    let ownership = validate_patient_reference_and_ownership(&delegation.patient_hash, author, "create delegation grant")?;
-    if !matches!(ownership, ValidateCallbackResult::Valid) {
-        return Ok(ownership);
+    if assignment.agent == assignment.assigned_by {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Admins cannot assign roles to themselves".to_string(),
+        ));
     }
-    // Healthcare proxy and legal guardian require identity verification
-    if matches!(delegation.delegation_type, DelegationType::HealthcareProxy | DelegationType::LegalGuardian) {
-        if !delegation.identity_verified {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Healthcare proxy and legal guardian delegations require identity verification".to_string(),
-            ));
-        }
-        if delegation.legal_document_hash.is_none() {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Healthcare proxy and legal guardian require legal documentation".to_string(),
-            ));
-        }
+    if !assignment.role.is_assignable() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only provider, researcher, auditor and emergency access roles are assigned".to_string(),
+        ));
     }
-    // Temporary delegation must have expiration
-    if matches!(delegation.delegation_type, DelegationType::Temporary) {
-        if delegation.expires_at.is_none() {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Temporary delegations must have an expiration date".to_string(),
-            ));
-        }
+    if assignment.reason.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Role assignments need a reason".to_string(),
+        ));
     }
     Ok(ValidateCallbackResult::Valid)
 }
 
-// ============================================================
-// VALIDATION: PATIENT NOTIFICATIONS
-// ============================================================
-
-fn validate_access_notification(
-    notification: &AccessNotification,
-    author: &AgentPubKey,
+/// Updates only revoke, by the assigning admin or another admin: the
+/// agent, role and expiry never change in place
+fn validate_role_assignment_update(
+    assignment: &RoleAssignment,
+    action: &Update,
 ) -> ExternResult<ValidateCallbackResult> {
-    if notification.notification_id.is_empty() {
+    let original: RoleAssignment = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original role assignment not found".to_string())))?;
+    if original.assigned_by != action.author && !proves_admin(&action.author, assignment.admin_link.as_ref())? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Notification ID is required".to_string(),
+            "Only admins or the assigning admin can revoke a role assignment".to_string(),
         ));
     }
-    if notification.accessor_name.is_empty() {
+    let unrevoked = RoleAssignment {
+        revoked_at: None,
+        admin_link: original.admin_link.clone(),
+        ..assignment.clone()
+    };
+    if original.revoked_at.is_some() || assignment.revoked_at.is_none() || unrevoked != original {
         return Ok(ValidateCallbackResult::Invalid(
-            "Accessor name is required for patient-friendly notifications".to_string(),
+            "Role assignments can only be revoked once".to_string(),
         ));
     }
-    if notification.summary.is_empty() {
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// A message is sent by one party to the other, one of them being the
+/// patient, under a consent or care team for that patient
+fn validate_direct_message(message: &DirectMessage, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if message.sender != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Plain language summary is required".to_string(),
+            "Messages must be sent by their author".to_string(),
         ));
     }
-    if notification.data_categories.is_empty() {
+    if message.sender == message.recipient {
         return Ok(ValidateCallbackResult::Invalid(
-            "Data categories accessed must be specified".to_string(),
+            "Messages need a recipient other than the sender".to_string(),
         ));
     }
-    let patient_ref = validate_patient_reference(&notification.patient_hash)?;
-    if !matches!(patient_ref, ValidateCallbackResult::Valid) {
-        return Ok(patient_ref);
+    let patient_record = must_get_valid_record(message.patient_hash.clone())?;
+    let patient_agent = patient_record.action().author();
+    if patient_agent != &message.sender && patient_agent != &message.recipient {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Messages must be between the patient and a provider".to_string(),
+        ));
     }
-    if &notification.accessor != author {
+
+    let context_patient = match &message.context {
+        MessagingContext::Consent(hash) => must_get_valid_record(hash.clone())?
+            .entry()
+            .to_app_option::<Consent>()
+            .ok()
+            .flatten()
+            .map(|consent| consent.patient_hash),
+        MessagingContext::CareTeam(hash) => must_get_valid_record(hash.clone())?
+            .entry()
+            .to_app_option::<CareTeam>()
+            .ok()
+            .flatten()
+            .map(|team| team.patient_hash),
+    };
+    if context_patient.as_ref() != Some(&message.patient_hash) {
         return Ok(ValidateCallbackResult::Invalid(
-            "Notification accessor must match the action author".to_string(),
+            "Message context must be a consent or care team for the same patient".to_string(),
         ));
     }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_notification_preferences(
-    prefs: &NotificationPreferences,
-    author: &AgentPubKey,
-) -> ExternResult<ValidateCallbackResult> {
-    let ownership = validate_patient_reference_and_ownership(&prefs.patient_hash, author, "set notification preferences")?;
-    if !matches!(ownership, ValidateCallbackResult::Valid) {
-        return Ok(ownership);
-    }
-    // Validate daily digest hour
-    if let Some(hour) = prefs.daily_digest_hour {
-        if hour > 23 {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Daily digest hour must be 0-23".to_string(),
-            ));
-        }
+/// Only the recipient can acknowledge reading a message
+fn validate_read_receipt(receipt: &MessageReadReceipt, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if receipt.reader != *author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Read receipts must be created by the reader".to_string(),
+        ));
     }
-    // Validate weekly summary day
-    if let Some(day) = prefs.weekly_summary_day {
-        if day > 6 {
-            return Ok(ValidateCallbackResult::Invalid(
-                "Weekly summary day must be 0-6 (Sunday-Saturday)".to_string(),
-            ));
-        }
+    let message = must_get_valid_record(receipt.message_hash.clone())?
+        .entry()
+        .to_app_option::<DirectMessage>()
+        .ok()
+        .flatten();
+    match message {
+        Some(message) if message.recipient == receipt.reader => Ok(ValidateCallbackResult::Valid),
+        Some(_) => Ok(ValidateCallbackResult::Invalid(
+            "Only the recipient can mark a message read".to_string(),
+        )),
+        None => Ok(ValidateCallbackResult::Invalid(
+            "Read receipts must reference a direct message".to_string(),
+        )),
     }
-    // If email enabled, email address required
-    if prefs.email_enabled && prefs.email_address.is_none() {
+}
+
+// ============================================================
+// VALIDATION: JURISDICTION POLICIES
+// ============================================================
+
+fn validate_jurisdiction_policy(policy: &JurisdictionPolicy, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if policy.set_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Email address required when email notifications enabled".to_string(),
+            "Jurisdiction policies must be set by their author".to_string(),
         ));
     }
-    // If SMS enabled, phone number required
-    if prefs.sms_enabled && prefs.phone_number.is_none() {
+    if !proves_operator(author, policy.operator_link.as_ref())? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Phone number required when SMS notifications enabled".to_string(),
+            "Only operators can set jurisdiction policies".to_string(),
         ));
     }
+    if let Some(error) = validate_jurisdiction(&policy.jurisdiction).errors.first() {
+        return Ok(ValidateCallbackResult::Invalid(error.message.clone()));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_notification_digest(
-    digest: &NotificationDigest,
-    author: &AgentPubKey,
-) -> ExternResult<ValidateCallbackResult> {
-    if digest.digest_id.is_empty() {
+fn validate_retention_policy(policy: &RetentionPolicy, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if policy.set_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Digest ID is required".to_string(),
+            "Retention policies must be set by their author".to_string(),
         ));
     }
-    let ownership = validate_patient_reference_and_ownership(&digest.patient_hash, author, "create notification digest")?;
-    if !matches!(ownership, ValidateCallbackResult::Valid) {
-        return Ok(ownership);
-    }
-    // Period end must be after period start
-    if digest.period_end.as_micros() <= digest.period_start.as_micros() {
+    if !proves_operator(author, policy.operator_link.as_ref())? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Period end must be after period start".to_string(),
+            "Only operators can set retention policies".to_string(),
         ));
     }
+    if let Some(error) = validate_jurisdiction(&policy.jurisdiction).errors.first() {
+        return Ok(ValidateCallbackResult::Invalid(error.message.clone()));
+    }
+    if policy.retention_days == 0 || policy.retention_days > MAX_RETENTION_DAYS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Retention must be between 1 and {} days",
+            MAX_RETENTION_DAYS
+        )));
+    }
     Ok(ValidateCallbackResult::Valid)
 }
 
 // ============================================================
-// VALIDATION: CARE TEAM TEMPLATES
+// VALIDATION: CATEGORY SENSITIVITY
 // ============================================================
 
-fn validate_care_team_template(template: &CareTeamTemplate) -> ExternResult<ValidateCallbackResult> {
-    if template.template_id.is_empty() {
+fn validate_sensitivity_policy(policy: &SensitivityMatrixPolicy, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if policy.set_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Template ID is required".to_string(),
+            "Sensitivity policies must be set by their author".to_string(),
         ));
     }
-    if template.name.is_empty() {
+    if !proves_operator(author, policy.operator_link.as_ref())? {
         return Ok(ValidateCallbackResult::Invalid(
-            "Template name is required".to_string(),
+            "Only operators can set the sensitivity matrix".to_string(),
         ));
     }
-    if template.description.is_empty() {
+    if let Err(problem) = policy.matrix.check() {
+        return Ok(ValidateCallbackResult::Invalid(problem));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: CONSENT ASSERTIONS
+// ============================================================
+
+fn validate_imported_assertion(imported: &ImportedConsentAssertion, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if imported.imported_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Template description is required for patient understanding".to_string(),
+            "Consent assertions must be imported by their author".to_string(),
         ));
     }
-    if template.permissions.is_empty() {
+    let claims = &imported.assertion.claims;
+    if claims.assertion_id.trim().is_empty() || claims.data_categories.is_empty() {
         return Ok(ValidateCallbackResult::Invalid(
-            "Template must specify at least one permission".to_string(),
+            "Consent assertions need an id and at least one data category".to_string(),
         ));
     }
-    if template.data_categories.is_empty() {
+    if imported.imported_at < claims.issued_at || imported.imported_at > claims.valid_until {
         return Ok(ValidateCallbackResult::Invalid(
-            "Template must specify at least one data category".to_string(),
+            "Consent assertion was imported outside its validity window".to_string(),
+        ));
+    }
+    if !imported.assertion.verify()? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Consent assertion signature does not match its issuer".to_string(),
         ));
     }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_care_team(team: &CareTeam, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
-    if team.team_id.is_empty() {
+// ============================================================
+// VALIDATION: SITE VALIDATION RULES
+// ============================================================
+
+fn validate_rule_set(rule_set: &ValidationRuleSet, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if rule_set.created_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Team ID is required".to_string(),
+            "Rule sets must be created by their author".to_string(),
         ));
     }
-    if team.team_name.is_empty() {
+    if rule_set.name.trim().is_empty() || rule_set.entry_type.trim().is_empty() {
         return Ok(ValidateCallbackResult::Invalid(
-            "Team name is required".to_string(),
+            "Rule sets need a name and an entry type".to_string(),
         ));
     }
-    if team.members.is_empty() {
+    if rule_set.facility.as_ref().is_some_and(|f| f.trim().is_empty()) {
         return Ok(ValidateCallbackResult::Invalid(
-            "Care team must have at least one member".to_string(),
+            "Facility cannot be blank".to_string(),
         ));
     }
-    if team.permissions.is_empty() {
+    if rule_set.rules.is_empty() || rule_set.rules.len() > MAX_SITE_RULES {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Rule sets hold between 1 and {} rules",
+            MAX_SITE_RULES
+        )));
+    }
+    for rule in &rule_set.rules {
+        if rule.field_path.trim().is_empty() || rule.field_path.split('.').any(str::is_empty) {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Invalid field path '{}'",
+                rule.field_path
+            )));
+        }
+        let malformed = match &rule.predicate {
+            RulePredicate::Mask(mask) => mask.is_empty(),
+            RulePredicate::OneOf(allowed) => allowed.is_empty(),
+            RulePredicate::NumericRange { min, max } => match (min, max) {
+                (None, None) => true,
+                (Some(min), Some(max)) => min > max,
+                _ => false,
+            },
+            RulePredicate::Required | RulePredicate::MinLength(_) | RulePredicate::MaxLength(_) => false,
+        };
+        if malformed {
+            return Ok(ValidateCallbackResult::Invalid(format!(
+                "Rule on '{}' has invalid parameters",
+                rule.field_path
+            )));
+        }
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+// ============================================================
+// VALIDATION: PERFORMANCE SAMPLES
+// ============================================================
+
+fn validate_performance_sample(sample: &PerformanceSample, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if sample.recorded_by != *author {
         return Ok(ValidateCallbackResult::Invalid(
-            "Care team must have at least one permission".to_string(),
+            "Performance samples must be recorded by their author".to_string(),
         ));
     }
-    if team.data_categories.is_empty() {
+    if sample.window_end < sample.window_start || sample.externs.is_empty() {
         return Ok(ValidateCallbackResult::Invalid(
-            "Care team must specify data categories".to_string(),
+            "Performance samples need timings over a forward window".to_string(),
         ));
     }
-    let ownership = validate_patient_reference_and_ownership(&team.patient_hash, author, "create care team")?;
-    if !matches!(ownership, ValidateCallbackResult::Valid) {
-        return Ok(ownership);
+    if sample.externs.iter().any(|t| t.errors > t.calls || t.max_micros > t.total_micros) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Performance sample totals are inconsistent".to_string(),
+        ));
     }
     Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_patient_reference(patient_hash: &ActionHash) -> ExternResult<ValidateCallbackResult> {
-    let record = must_get_valid_record(patient_hash.clone())?;
-    match record.entry() {
-        RecordEntry::Present(_) => Ok(ValidateCallbackResult::Valid),
-        _ => Ok(ValidateCallbackResult::Invalid(
-            "patient_hash must reference an existing record with an entry".to_string(),
-        )),
+// ============================================================
+// VALIDATION: PRIVACY BUDGETS
+// ============================================================
+
+fn validate_privacy_budget_ledger(ledger: &PrivacyBudgetLedger, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if ledger.created_by != *author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Privacy budgets must be created by their author".to_string(),
+        ));
+    }
+    if ledger.dataset_id.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Privacy budgets need a dataset id".to_string(),
+        ));
+    }
+    if mycelix_health_shared::dp_core::validate_epsilon(ledger.epsilon_limit).is_err()
+        || !(ledger.delta > 0.0 && ledger.delta < 1.0)
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Privacy budgets need a positive epsilon limit and a delta in (0, 1)".to_string(),
+        ));
+    }
+    if let Err(e) = ledger.committed() {
+        return Ok(ValidateCallbackResult::Invalid(e.to_string()));
+    }
+    if ledger.committed_rdp.iter().any(|rho| *rho != 0.0)
+        || !ledger.reservations.is_empty()
+        || ledger.committed_queries != 0
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Privacy budgets start unspent".to_string(),
+        ));
     }
+    Ok(ValidateCallbackResult::Valid)
 }
 
-fn validate_patient_reference_and_ownership(
-    patient_hash: &ActionHash,
-    author: &AgentPubKey,
-    operation: &str,
+/// Updates only spend: the limit and orders are fixed, committed RDP never
+/// shrinks, and committed plus held budget stays within the limit
+fn validate_privacy_budget_update(
+    ledger: &PrivacyBudgetLedger,
+    action: &Update,
 ) -> ExternResult<ValidateCallbackResult> {
-    let record = must_get_valid_record(patient_hash.clone())?;
-    match record.entry() {
-        RecordEntry::Present(_) => {}
-        _ => {
-            return Ok(ValidateCallbackResult::Invalid(
-                "patient_hash must reference an existing record with an entry".to_string(),
-            ));
-        }
+    let original: PrivacyBudgetLedger = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original privacy budget not found".to_string())))?;
+    if ledger.patient_hash != original.patient_hash
+        || ledger.dataset_id != original.dataset_id
+        || ledger.epsilon_limit != original.epsilon_limit
+        || ledger.delta != original.delta
+        || ledger.orders != original.orders
+        || ledger.created_by != original.created_by
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A privacy budget's dataset, limit and orders cannot change".to_string(),
+        ));
     }
-    if record.action().author() != author {
+    if ledger.committed_rdp.len() != original.committed_rdp.len()
+        || ledger.committed_rdp.iter().zip(&original.committed_rdp).any(|(new, old)| new < old)
+        || ledger.committed_queries < original.committed_queries
+        || ledger.next_reservation_id < original.next_reservation_id
+    {
         return Ok(ValidateCallbackResult::Invalid(
-            format!("Only the patient can {} for this patient_hash", operation),
+            "Committed privacy spend cannot be given back".to_string(),
         ));
     }
-    Ok(ValidateCallbackResult::Valid)
+    if ledger.reservations.iter().any(|r| r.reservation_id >= ledger.next_reservation_id) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reservation ids must come from the ledger's counter".to_string(),
+        ));
+    }
+    let spent = ledger
+        .with_reservations(action.timestamp)
+        .and_then(|accountant| accountant.to_epsilon(ledger.delta));
+    match spent {
+        Ok((epsilon, _)) if epsilon <= ledger.epsilon_limit => Ok(ValidateCallbackResult::Valid),
+        Ok(_) => Ok(ValidateCallbackResult::Invalid(
+            "Privacy budget exceeded".to_string(),
+        )),
+        Err(e) => Ok(ValidateCallbackResult::Invalid(e.to_string())),
+    }
 }
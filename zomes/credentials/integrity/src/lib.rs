@@ -4,6 +4,7 @@
 //! Uses Anchor pattern for link bases and FlatOp validation.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{entry_size_violation, EntrySizeLimits, DEFAULT_ENTRY_SIZE_LIMITS};
 
 // ============================================================================
 // Anchor Entry Type
@@ -125,8 +126,14 @@ pub fn genesis_self_check(_data: GenesisSelfCheckData) -> ExternResult<ValidateC
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = DEFAULT_ENTRY_SIZE_LIMITS;
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, action } => {
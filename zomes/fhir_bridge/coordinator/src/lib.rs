@@ -6,12 +6,22 @@
 //! This zome bridges external FHIR resources to internal Mycelix-Health
 //! data structures, handling:
 //! - Bundle parsing and resource extraction
-//! - Deduplication via source_system + resource_id anchors
+//! - Normalizing observation units to UCUM, rejecting unknown units
+//! - Rejecting resources whose LOINC, SNOMED CT, ICD-10-CM, RxNorm or
+//!   CPT codes are malformed
+//! - Deduplication via source_system + resource_id anchors, and of
+//!   observations across sources by clinical identity
 //! - Cross-zome calls to create internal records
+//! - Evidence links from results to the conditions they bear on
+//! - Preserving resource extensions so exports round-trip
+//! - Retracting mappings when the source deletes a resource
 //! - Audit logging of all data access
 
 use hdk::prelude::*;
 use fhir_bridge_integrity::*;
+use mycelix_health_shared::{require_jurisdiction_allows, DataUse};
+use mycelix_health_shared::validation::ucum;
+use mycelix_health_shared::{validate_code, ClinicalCodeSystem};
 
 // Local mirrors of fhir_mapping_integrity types to avoid duplicate __num_entry_types
 // symbols when compiling to WASM. These must match the serialization layout exactly.
@@ -80,7 +90,7 @@ pub struct ObservationReferenceRange {
     pub text: Option<String>,
 }
 
-#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Clone, Debug, Serialize, Deserialize, SerializedBytes, PartialEq)]
 pub struct FhirObservationMapping {
     pub internal_record_hash: ActionHash,
     pub patient_hash: ActionHash,
@@ -93,6 +103,7 @@ pub struct FhirObservationMapping {
     pub snomed_code: Option<String>,
     pub value_quantity: Option<FhirQuantity>,
     pub value_codeable_concept: Option<FhirCodeableConcept>,
+    #[serde(with = "mycelix_health_shared::compressed")]
     pub value_string: Option<String>,
     pub value_boolean: Option<bool>,
     pub effective_datetime: Timestamp,
@@ -102,6 +113,14 @@ pub struct FhirObservationMapping {
     pub note: Vec<String>,
     pub mapping_version: String,
     pub last_synced: Timestamp,
+    #[serde(default)]
+    pub effective_time: Option<Timestamp>,
+    #[serde(default)]
+    pub recorded_time: Option<Timestamp>,
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -126,6 +145,16 @@ pub struct FhirConditionMapping {
     pub note: Vec<String>,
     pub mapping_version: String,
     pub last_synced: Timestamp,
+    #[serde(default)]
+    pub effective_time: Option<Timestamp>,
+    #[serde(default)]
+    pub recorded_time: Option<Timestamp>,
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+    #[serde(default)]
+    pub inferred_onset: Option<InferredOnset>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -149,12 +178,227 @@ pub struct FhirMedicationMapping {
     pub note: Vec<String>,
     pub mapping_version: String,
     pub last_synced: Timestamp,
+    #[serde(default)]
+    pub effective_time: Option<Timestamp>,
+    #[serde(default)]
+    pub recorded_time: Option<Timestamp>,
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+}
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PatientIdentifier {
+    pub system: String,
+    pub value: String,
+    pub use_code: Option<String>,
+    pub type_display: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FhirHumanName {
+    pub use_code: Option<String>,
+    pub text: Option<String>,
+    pub family: Option<String>,
+    pub given: Vec<String>,
+    pub prefix: Vec<String>,
+    pub suffix: Vec<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FhirAddress {
+    pub use_code: Option<String>,
+    pub type_code: Option<String>,
+    pub text: Option<String>,
+    pub line: Vec<String>,
+    pub city: Option<String>,
+    pub state: Option<String>,
+    pub postal_code: Option<String>,
+    pub country: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FhirContactPoint {
+    pub system: Option<String>,
+    pub value: Option<String>,
+    pub use_code: Option<String>,
+    pub rank: Option<u32>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum SyncStatus {
+    Synced,
+    Pending,
+    Conflict,
+    Error,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FhirPatientMapping {
+    pub internal_patient_hash: ActionHash,
+    pub fhir_patient_id: String,
+    pub source_system: String,
+    pub fhir_identifiers: Vec<PatientIdentifier>,
+    pub name: Vec<FhirHumanName>,
+    pub telecom: Vec<FhirContactPoint>,
+    pub gender: Option<String>,
+    pub birth_date: Option<String>,
+    pub deceased: Option<String>,
+    pub address: Vec<FhirAddress>,
+    pub marital_status: Option<FhirCodeableConcept>,
+    pub communication: Vec<FhirCoding>,
+    pub fhir_version_id: Option<String>,
+    pub fhir_last_updated: Option<String>,
+    pub mapping_version: String,
+    pub last_synced: Timestamp,
+    pub sync_status: SyncStatus,
+    pub sync_errors: Vec<String>,
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+    #[serde(default)]
+    pub us_core: UsCoreDemographics,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProcedurePerformer {
+    pub actor: FhirReference,
+    pub function: Option<FhirCodeableConcept>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct FhirProcedureMapping {
+    pub internal_procedure_hash: Option<ActionHash>,
+    pub patient_hash: ActionHash,
+    pub fhir_procedure_id: String,
+    pub source_system: String,
+    pub status: String,
+    pub code: FhirCodeableConcept,
+    pub cpt_code: Option<String>,
+    pub snomed_code: Option<String>,
+    pub performed_datetime: Option<String>,
+    pub performed_period: Option<FhirPeriod>,
+    pub performers: Vec<ProcedurePerformer>,
+    pub body_site: Vec<FhirCodeableConcept>,
+    pub outcome: Option<FhirCodeableConcept>,
+    pub complications: Vec<FhirCodeableConcept>,
+    pub has_complications: bool,
+    pub reason_code: Vec<FhirCodeableConcept>,
+    pub encounter_reference: Option<FhirReference>,
+    pub note: Vec<String>,
+    #[serde(with = "mycelix_health_shared::compressed")]
+    pub source_resource: Option<String>,
+    pub mapping_version: String,
+    pub last_synced: Timestamp,
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum RetractionTrigger {
+    Deleted,
+    EnteredInError,
+}
+// Local mirrors of records_integrity lab order types, for the same reason
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum LabOrderStatus {
+    Ordered,
+    Collected,
+    InLab,
+    Resulted,
+    Cancelled,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum LabOrderPriority {
+    Routine,
+    Urgent,
+    Stat,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct LabOrder {
+    pub order_id: String,
+    pub patient_hash: ActionHash,
+    pub encounter_hash: Option<ActionHash>,
+    pub ordering_provider: AgentPubKey,
+    pub loinc_code: String,
+    pub test_name: String,
+    pub priority: LabOrderPriority,
+    pub clinical_indication: Option<String>,
+    pub status: LabOrderStatus,
+    pub ordered_at: Timestamp,
+    pub status_changed_at: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CustodyAction {
+    Collected,
+    Transferred,
+    ReceivedInLab,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CustodyEvent {
+    pub action: CustodyAction,
+    pub custodian: AgentPubKey,
+    pub location: String,
+    pub at: Timestamp,
+    pub note: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct Specimen {
+    pub specimen_id: String,
+    pub order_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub accession_number: String,
+    pub specimen_type: String,
+    pub collected_by: AgentPubKey,
+    pub collection_time: Timestamp,
+    pub custody: Vec<CustodyEvent>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct LabOrderDetails {
+    pub order_hash: ActionHash,
+    pub order: LabOrder,
+    pub specimens: Vec<(ActionHash, Specimen)>,
+    pub results: Vec<Record>,
 }
+
 use mycelix_health_shared::{
     require_authorization,
+    require_operator,
     anchor_hash,
+    increment_counter,
+    read_counter,
+    stats_day,
+    ingest_windows,
+    IngestStatistics,
+    IngestTotals,
+    SourceIngestStatistics,
+    StatisticsWindow,
+    StorageGrowthEstimate,
     DataCategory,
     Permission,
+    Saga,
+    SagaCompensation,
+    resilient_call,
+    CallClass,
+    FhirExtension,
+    UsCoreDemographics,
+    capture_fhir_extensions,
+    sensitivity_matrix,
+    infer_onset,
+    normalized_onset,
+    InferredOnset,
+    OnsetCandidate,
+    publish_event,
+    DomainEventPayload,
 };
 use serde_json::Value as JsonValue;
 
@@ -194,8 +438,23 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
         diagnostic_reports_skipped: 0,
         care_plans_created: 0,
         care_plans_skipped: 0,
+        service_requests_created: 0,
+        service_requests_skipped: 0,
+        specimens_created: 0,
+        specimens_skipped: 0,
+        observations_deduplicated: 0,
+        evidence_links_created: 0,
+        consents_proposed: 0,
+        consents_skipped: 0,
+        resources_retracted: 0,
+        discharge_summaries: 0,
+        follow_up_tasks_created: 0,
         unknown_types: Vec::new(),
         parse_errors: Vec::new(),
+        payload_stats: Default::default(),
+        data_categories: Vec::new(),
+        quality_findings: Vec::new(),
+        outcome: IngestOutcome::Accepted,
     };
 
     // Extract entries from bundle
@@ -204,14 +463,36 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
         _ => {
             report.parse_errors.push("Bundle has no 'entry' array".to_string());
             // Store the report even on error
-            create_entry(&EntryTypes::IngestReport(report.clone()))?;
+            store_ingest_report(&report)?;
             return Ok(report);
         }
     };
 
+    // Withdrawals go first: they name a mapping that already has its patient,
+    // and a bundle may carry nothing else
+    let mut remaining = Vec::with_capacity(entries.len());
+    for entry in entries {
+        let Some(retraction) = upstream_retraction(&entry) else {
+            remaining.push(entry);
+            continue;
+        };
+        report.total_processed += 1;
+        match process_retraction(&retraction, &input.source_system) {
+            Ok(true) => report.resources_retracted += 1,
+            Ok(false) => {}
+            Err(e) => report.parse_errors.push(format!(
+                "Retraction {}/{}: {}",
+                retraction.resource_type, retraction.fhir_id, e
+            )),
+        }
+    }
+    let entries = remaining;
+
     // First pass: find and process Patient resources to establish patient hash
     let mut patient_hash: Option<ActionHash> = None;
     let mut patient_fhir_id: Option<String> = None;
+    // Unknown when the patient is only referenced; classification then errs towards minors
+    let mut birth_date: Option<Timestamp> = None;
 
     for entry in &entries {
         let resource = match entry.get("resource") {
@@ -224,7 +505,9 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 Ok((hash, created)) => {
                     patient_hash = Some(hash);
                     patient_fhir_id = get_resource_id(resource);
+                    birth_date = get_fhir_string(resource, "birthDate").and_then(|d| parse_fhir_datetime(&d));
                     report.total_processed += 1;
+                    report.data_categories.push(DataCategory::Demographics);
                     if created {
                         report.patients_created += 1;
                     } else {
@@ -258,14 +541,26 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
     let patient_hash = match patient_hash {
         Some(h) => h,
         None => {
-            report.parse_errors.push("No Patient resource found and could not resolve patient reference".to_string());
-            create_entry(&EntryTypes::IngestReport(report.clone()))?;
+            if !entries.is_empty() {
+                report.parse_errors.push("No Patient resource found and could not resolve patient reference".to_string());
+            }
+            store_ingest_report(&report)?;
             return Ok(report);
         }
     };
 
-    // Second pass: process all other resources
-    for entry in &entries {
+    // Second pass: process all other resources. Specimens go last so the
+    // ServiceRequests they were collected for already exist.
+    let sensitivity = sensitivity_matrix();
+    let resources: Vec<&JsonValue> = entries.iter().filter_map(|entry| entry.get("resource")).collect();
+    let is_specimen = |entry: &&JsonValue| {
+        entry.get("resource").and_then(get_resource_type).as_deref() == Some("Specimen")
+    };
+    let ordered_entries = entries
+        .iter()
+        .filter(|entry| !is_specimen(entry))
+        .chain(entries.iter().filter(is_specimen));
+    for entry in ordered_entries {
         let resource = match entry.get("resource") {
             Some(r) => r,
             None => continue,
@@ -285,22 +580,29 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
         }
 
         report.total_processed += 1;
+        let created_before = report.records_created();
+        let context = ClassificationContext {
+            sensitivity: sensitivity.clone(),
+            ..ClassificationContext::at(birth_date, resource_event_time(resource).unwrap_or(report.ingested_at))
+        };
+        // These mappings keep the source resource JSON in a compressed field
+        let keeps_source_json = matches!(
+            resource_type.as_str(),
+            "AllergyIntolerance" | "Immunization" | "Procedure" | "DiagnosticReport" | "CarePlan"
+        );
 
         match resource_type.as_str() {
             "Observation" => {
-                match process_observation(resource, &patient_hash, &input.source_system) {
-                    Ok(created) => {
-                        if created {
-                            report.observations_created += 1;
-                        } else {
-                            report.observations_skipped += 1;
-                        }
-                    }
+                match process_observation(resource, &patient_hash, &input.source_system, &context, &input.dedup_tolerance) {
+                    Ok(ObservationIngest::Created) => report.observations_created += 1,
+                    Ok(ObservationIngest::Skipped) => report.observations_skipped += 1,
+                    Ok(ObservationIngest::Deduplicated) => report.observations_deduplicated += 1,
                     Err(e) => report.parse_errors.push(format!("Observation: {}", e)),
                 }
             }
             "Condition" => {
-                match process_condition(resource, &patient_hash, &input.source_system) {
+                let candidates = condition_onset_candidates(resource, &resources);
+                match process_condition(resource, &patient_hash, &input.source_system, &context, &candidates) {
                     Ok(created) => {
                         if created {
                             report.conditions_created += 1;
@@ -312,7 +614,7 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 }
             }
             "MedicationRequest" | "MedicationStatement" => {
-                match process_medication(resource, &patient_hash, &input.source_system) {
+                match process_medication(resource, &patient_hash, &input.source_system, &context) {
                     Ok(created) => {
                         if created {
                             report.medications_created += 1;
@@ -324,7 +626,7 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 }
             }
             "AllergyIntolerance" => {
-                match process_allergy(resource, &patient_hash, &input.source_system) {
+                match process_allergy(resource, &patient_hash, &input.source_system, &context) {
                     Ok(created) => {
                         if created {
                             report.allergies_created += 1;
@@ -336,7 +638,7 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 }
             }
             "Immunization" => {
-                match process_immunization(resource, &patient_hash, &input.source_system) {
+                match process_immunization(resource, &patient_hash, &input.source_system, &context) {
                     Ok(created) => {
                         if created {
                             report.immunizations_created += 1;
@@ -348,7 +650,7 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 }
             }
             "Procedure" => {
-                match process_procedure(resource, &patient_hash, &input.source_system) {
+                match process_procedure(resource, &patient_hash, &input.source_system, &context) {
                     Ok(created) => {
                         if created {
                             report.procedures_created += 1;
@@ -360,7 +662,7 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 }
             }
             "DiagnosticReport" => {
-                match process_diagnostic_report(resource, &patient_hash, &input.source_system) {
+                match process_diagnostic_report(resource, &patient_hash, &input.source_system, &context) {
                     Ok(created) => {
                         if created {
                             report.diagnostic_reports_created += 1;
@@ -372,10 +674,17 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                 }
             }
             "CarePlan" => {
-                match process_care_plan(resource, &patient_hash, &input.source_system) {
+                match process_care_plan(resource, &patient_hash, &input.source_system, &context) {
                     Ok(created) => {
                         if created {
                             report.care_plans_created += 1;
+                            if is_discharge_resource(resource) {
+                                report.discharge_summaries += 1;
+                                match create_discharge_tasks(resource, &patient_hash, &input.source_system) {
+                                    Ok(hashes) => report.follow_up_tasks_created += hashes.len() as u32,
+                                    Err(e) => report.parse_errors.push(format!("Discharge follow-ups: {}", e)),
+                                }
+                            }
                         } else {
                             report.care_plans_skipped += 1;
                         }
@@ -383,28 +692,176 @@ pub fn ingest_bundle(input: IngestBundleInput) -> ExternResult<IngestReport> {
                     Err(e) => report.parse_errors.push(format!("CarePlan: {}", e)),
                 }
             }
+            "Composition" if is_discharge_resource(resource) => {
+                match process_discharge_composition(resource, &patient_hash, &input.source_system) {
+                    Ok(Some(created)) => {
+                        report.discharge_summaries += 1;
+                        report.follow_up_tasks_created += created;
+                    }
+                    Ok(None) => {}
+                    Err(e) => report.parse_errors.push(format!("Composition: {}", e)),
+                }
+            }
+            "ServiceRequest" => {
+                match process_service_request(resource, &patient_hash, &input.source_system) {
+                    Ok(created) => {
+                        if created {
+                            report.service_requests_created += 1;
+                        } else {
+                            report.service_requests_skipped += 1;
+                        }
+                    }
+                    Err(e) => report.parse_errors.push(format!("ServiceRequest: {}", e)),
+                }
+            }
+            "Specimen" => {
+                match process_specimen(resource, &input.source_system) {
+                    Ok(created) => {
+                        if created {
+                            report.specimens_created += 1;
+                        } else {
+                            report.specimens_skipped += 1;
+                        }
+                    }
+                    Err(e) => report.parse_errors.push(format!("Specimen: {}", e)),
+                }
+            }
+            "Consent" => {
+                match process_consent(resource, &patient_hash, &input.source_system) {
+                    Ok(created) => {
+                        if created {
+                            report.consents_proposed += 1;
+                        } else {
+                            report.consents_skipped += 1;
+                        }
+                    }
+                    Err(e) => report.parse_errors.push(format!("Consent: {}", e)),
+                }
+            }
             _ => {
                 if !report.unknown_types.contains(&resource_type) {
                     report.unknown_types.push(resource_type);
                 }
             }
         }
+
+        if report.records_created() > created_before {
+            for category in classify_resource(resource, &context) {
+                if !report.data_categories.contains(&category) {
+                    report.data_categories.push(category);
+                }
+            }
+            if keeps_source_json {
+                report.payload_stats.record(&serde_json::to_string(resource).ok());
+            }
+            report.quality_findings.extend(resource_quality_findings(resource));
+        }
     }
 
+    // Third pass: link results to the diagnoses they bear on, now that every
+    // resource in the bundle has a mapping
+    for reference in bundle_evidence_references(&resources) {
+        match link_evidence(&reference, &input.source_system) {
+            Ok(true) => report.evidence_links_created += 1,
+            Ok(false) => {}
+            Err(e) => report.parse_errors.push(format!("Evidence {}: {}", reference.derived_from(), e)),
+        }
+    }
+
+    report.outcome = ingest_outcome(
+        &report.quality_findings,
+        report.total_processed,
+        &DEFAULT_QUALITY_THRESHOLDS,
+    );
+
     // Store the ingest report
-    let report_hash = create_entry(&EntryTypes::IngestReport(report.clone()))?;
+    let report_hash = store_ingest_report(&report)?;
 
     // Link report to patient
     create_link(
-        patient_hash,
-        report_hash,
+        patient_hash.clone(),
+        report_hash.clone(),
         LinkTypes::PatientToIngestReports,
         LinkTag::new(input.source_system.as_bytes().to_vec()),
     )?;
 
+    // Consent coverage prompts and other reactions subscribe to this event
+    publish_event(
+        patient_hash,
+        DomainEventPayload::IngestCompleted {
+            report_hash: report_hash.clone(),
+            source_system: report.source_system.clone(),
+            data_categories: report.data_categories.clone(),
+            records_created: report.records_created(),
+        },
+    );
+
     Ok(report)
 }
 
+/// When and what a past ingest brought into a patient's record
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct IngestSummary {
+    pub report_hash: ActionHash,
+    pub report_id: String,
+    pub source_system: String,
+    pub ingested_at: Timestamp,
+    pub total_processed: u32,
+    pub data_categories: Vec<DataCategory>,
+    pub outcome: IngestOutcome,
+}
+
+/// Ingests into a patient's record, oldest first
+///
+/// Reports touching a category the caller may not read are left out.
+#[hdk_extern]
+pub fn get_patient_ingest_reports(patient_hash: ActionHash) -> ExternResult<Vec<IngestSummary>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToIngestReports)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut readable: Vec<(DataCategory, bool)> = Vec::new();
+    let mut summaries = Vec::new();
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else { continue };
+        let Some(record) = get(hash.clone(), GetOptions::default())? else { continue };
+        let Some(report) = record.entry().to_app_option::<IngestReport>().ok().flatten() else {
+            continue;
+        };
+
+        let mut visible = true;
+        for category in &report.data_categories {
+            let allowed = match readable.iter().find(|(known, _)| known == category) {
+                Some((_, allowed)) => *allowed,
+                None => {
+                    let allowed =
+                        require_authorization(patient_hash.clone(), category.clone(), Permission::Read, false).is_ok();
+                    readable.push((category.clone(), allowed));
+                    allowed
+                }
+            };
+            visible &= allowed;
+        }
+        if !visible {
+            continue;
+        }
+
+        summaries.push(IngestSummary {
+            report_hash: hash,
+            report_id: report.report_id,
+            source_system: report.source_system,
+            ingested_at: report.ingested_at,
+            total_processed: report.total_processed,
+            data_categories: report.data_categories,
+            outcome: report.outcome,
+        });
+    }
+    summaries.sort_by_key(|summary| summary.ingested_at);
+
+    Ok(summaries)
+}
+
 /// Export a patient's data as a FHIR R4 Bundle
 #[hdk_extern]
 pub fn export_patient_fhir(input: ExportPatientInput) -> ExternResult<ExportResult> {
@@ -418,6 +875,9 @@ pub fn export_patient_fhir(input: ExportPatientInput) -> ExternResult<ExportResu
     if input.include_sections.iter().any(|s| s == "MedicationRequest") {
         required_categories.push(DataCategory::Medications);
     }
+    if input.include_sections.iter().any(|s| s == "Procedure") {
+        required_categories.push(DataCategory::Procedures);
+    }
 
     if required_categories.is_empty() {
         required_categories.push(DataCategory::All);
@@ -438,41 +898,153 @@ pub fn export_patient_fhir(input: ExportPatientInput) -> ExternResult<ExportResu
         "include_observations": input.include_sections.contains(&"Observation".to_string()),
         "include_conditions": input.include_sections.contains(&"Condition".to_string()),
         "include_medications": input.include_sections.contains(&"MedicationRequest".to_string()),
+        "include_procedures": input.include_sections.contains(&"Procedure".to_string()),
+        "observation_status_filter": if input.include_non_final_observations { "All" } else { "Reportable" },
+        "include_retracted": input.include_retracted,
         "is_emergency": false,
         "emergency_reason": null
     });
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("export_patient_bundle"),
-        None,
-        &export_input,
-    )?;
-
-    let bundle_output: JsonValue = match response {
-        ZomeCallResponse::Ok(io) => io.decode()
-            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to decode export: {}", e))))?,
-        ZomeCallResponse::NetworkError(e) => {
-            return Err(wasm_error!(WasmErrorInner::Guest(format!("Network error: {}", e))));
-        }
-        ZomeCallResponse::CountersigningSession(e) => {
-            return Err(wasm_error!(WasmErrorInner::Guest(format!("Countersigning error: {}", e))));
-        }
-        _ => return Err(wasm_error!(WasmErrorInner::Guest("Unexpected response".to_string()))),
-    };
+    let mut bundle_output: JsonValue =
+        resilient_call("fhir_mapping", "export_patient_bundle", &export_input, CallClass::Bulk)
+            .map_err(|failure| failure.into_wasm_error("exporting patient bundle"))?;
 
     // Count resources in the output
-    let resource_count = count_resources(&bundle_output);
+    let mut resource_count = count_resources(&bundle_output);
+
+    if input.include_sections.iter().any(|s| s == "Consent") {
+        let consents: Vec<JsonValue> =
+            resilient_call("consent", "export_active_consents_fhir", &input.patient_hash, CallClass::Bulk)
+                .map_err(|failure| failure.into_wasm_error("exporting consents"))?;
+        resource_count += consents.len() as u32;
+        bundle_output["consents"] = JsonValue::Array(consents);
+    }
+
+    let jurisdiction = bundle_output["jurisdiction"].as_str().map(str::to_string);
 
     Ok(ExportResult {
         bundle: bundle_output,
         resource_count,
         format: input.format.unwrap_or_else(|| "r4".to_string()),
         sections_exported: input.include_sections,
+        jurisdiction,
     })
 }
 
+/// Input for exporting a lab order
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExportLabOrderInput {
+    pub order_hash: ActionHash,
+}
+
+/// Export a lab order as a FHIR R4 Bundle of its ServiceRequest and Specimens
+#[hdk_extern]
+pub fn export_lab_order_fhir(input: ExportLabOrderInput) -> ExternResult<ExportResult> {
+    let read_input = serde_json::json!({
+        "order_hash": input.order_hash,
+        "is_emergency": false,
+        "emergency_reason": null
+    });
+    let details: LabOrderDetails =
+        resilient_call("records", "get_lab_order", &read_input, CallClass::Bulk)
+            .map_err(|failure| failure.into_wasm_error("reading lab order"))?;
+    require_authorization(
+        details.order.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Export,
+        false,
+    )?;
+    let jurisdiction = require_jurisdiction_allows(&details.order.patient_hash, DataUse::Export)?;
+
+    let mut resources = vec![service_request_to_fhir(&details)];
+    resources.extend(details.specimens.iter().map(|(_, specimen)| specimen_to_fhir(specimen)));
+    let entries: Vec<JsonValue> = resources
+        .into_iter()
+        .map(|resource| serde_json::json!({ "resource": resource }))
+        .collect();
+    let bundle = serde_json::json!({
+        "resourceType": "Bundle",
+        "type": "collection",
+        "entry": entries,
+    });
+
+    Ok(ExportResult {
+        resource_count: count_resources(&bundle),
+        bundle,
+        format: "r4".to_string(),
+        sections_exported: vec!["ServiceRequest".to_string(), "Specimen".to_string()],
+        jurisdiction,
+    })
+}
+
+fn service_request_to_fhir(details: &LabOrderDetails) -> JsonValue {
+    let order = &details.order;
+    let status = match order.status {
+        LabOrderStatus::Resulted => "completed",
+        LabOrderStatus::Cancelled => "revoked",
+        _ => "active",
+    };
+    let priority = match order.priority {
+        LabOrderPriority::Routine => "routine",
+        LabOrderPriority::Urgent => "urgent",
+        LabOrderPriority::Stat => "stat",
+    };
+    let specimens: Vec<JsonValue> = details
+        .specimens
+        .iter()
+        .map(|(_, specimen)| serde_json::json!({ "reference": format!("Specimen/{}", specimen.specimen_id) }))
+        .collect();
+    let mut resource = serde_json::json!({
+        "resourceType": "ServiceRequest",
+        "id": order.order_id,
+        "status": status,
+        "intent": "order",
+        "priority": priority,
+        "code": {
+            "coding": [{ "system": "http://loinc.org", "code": order.loinc_code, "display": order.test_name }],
+            "text": order.test_name,
+        },
+        "subject": { "reference": format!("Patient/{}", order.patient_hash) },
+        "authoredOn": order.ordered_at,
+        "requester": { "identifier": { "value": order.ordering_provider.to_string() } },
+        "specimen": specimens,
+    });
+    if let Some(indication) = &order.clinical_indication {
+        resource["reasonCode"] = serde_json::json!([{ "text": indication }]);
+    }
+    resource
+}
+
+fn specimen_to_fhir(specimen: &Specimen) -> JsonValue {
+    let rejected = specimen.custody.iter().any(|event| event.action == CustodyAction::Rejected);
+    let received = specimen
+        .custody
+        .iter()
+        .find(|event| event.action == CustodyAction::ReceivedInLab);
+    let mut resource = serde_json::json!({
+        "resourceType": "Specimen",
+        "id": specimen.specimen_id,
+        "accessionIdentifier": { "value": specimen.accession_number },
+        "status": if rejected { "unsatisfactory" } else { "available" },
+        "type": { "text": specimen.specimen_type },
+        "subject": { "reference": format!("Patient/{}", specimen.patient_hash) },
+        "collection": {
+            "collector": { "identifier": { "value": specimen.collected_by.to_string() } },
+            "collectedDateTime": specimen.collection_time,
+        },
+    });
+    if let Some(event) = received {
+        resource["receivedTime"] = serde_json::json!(event.at);
+    }
+    let notes: Vec<JsonValue> = specimen
+        .custody
+        .iter()
+        .map(|event| serde_json::json!({ "text": format!("{:?} at {} ({})", event.action, event.location, event.at) }))
+        .collect();
+    resource["note"] = serde_json::json!(notes);
+    resource
+}
+
 /// Validate a FHIR resource before ingestion
 #[hdk_extern]
 pub fn validate_fhir_resource(resource: JsonValue) -> ExternResult<bool> {
@@ -493,6 +1065,7 @@ pub fn validate_fhir_resource(resource: JsonValue) -> ExternResult<bool> {
         "Observation" => Ok(validate_observation_resource(&resource)),
         "Condition" => Ok(validate_condition_resource(&resource)),
         "MedicationRequest" => Ok(validate_medication_resource(&resource)),
+        "Procedure" => Ok(validate_procedure_resource(&resource)),
         _ => Ok(true), // Allow unknown types to pass basic validation
     }
 }
@@ -530,44 +1103,236 @@ fn process_patient(resource: &JsonValue, source_system: &str) -> Result<(ActionH
         "external_id": fhir_id,
     });
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("patient"),
-        FunctionName::from("create_or_update_patient"),
-        None,
-        &patient_input,
-    ).map_err(|e| format!("Failed to call patient zome: {}", e))?;
-
-    let patient_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => io.decode()
-            .map_err(|e| format!("Failed to decode patient hash: {}", e))?,
-        _ => return Err("Failed to create patient".to_string()),
+    let patient_hash = ingest_saga("Patient", &["create_patient", "create_mapping", "create_anchor"], |saga| {
+        // The patient zome may have matched an existing patient, so this step
+        // has nothing to compensate
+        let patient_hash = saga.step("create_patient", |_| {
+            resilient_call::<_, ActionHash>("patient", "create_or_update_patient", &patient_input, CallClass::Bulk)
+                .map_err(|failure| format!("Failed to create patient: {}", failure))
+        })?;
+
+        // The mapping keeps what the patient entry has no room for, including
+        // extensions; a nameless resource cannot be mapped
+        let now = sys_time().map_err(|e| e.to_string())?;
+        let mapping = patient_mapping(resource, &patient_hash, &fhir_id, source_system, now);
+        if !mapping.name.is_empty() {
+            create_mapping_step(saga, "create_fhir_patient_mapping", &mapping, "patient")?;
+        }
+
+        // Create anchor for deduplication
+        let anchor = FhirResourceAnchor {
+            source_key,
+            resource_type: "Patient".to_string(),
+            internal_hash: patient_hash.clone(),
+            first_ingested: Timestamp::from_micros(now.as_micros() as i64),
+            last_updated: Timestamp::from_micros(now.as_micros() as i64),
+        };
+        saga.step("create_anchor", |undo| {
+            let hash = create_entry(&EntryTypes::FhirResourceAnchor(anchor))
+                .map_err(|e| e.to_string())?;
+            undo.push(SagaCompensation::DeleteEntry(hash));
+            Ok::<_, String>(())
+        })?;
+
+        Ok(patient_hash)
+    })?;
+
+    Ok((patient_hash, true))
+}
+
+/// Patient mapping carrying the resource's identifiers, contacts and extensions
+fn patient_mapping(
+    resource: &JsonValue,
+    patient_hash: &ActionHash,
+    fhir_id: &str,
+    source_system: &str,
+    now: Timestamp,
+) -> FhirPatientMapping {
+    let elements = |field: &str| resource.get(field).and_then(|v| v.as_array()).cloned().unwrap_or_default();
+    let strings = |json: &JsonValue, field: &str| -> Vec<String> {
+        json.get(field)
+            .and_then(|v| v.as_array())
+            .map(|values| values.iter().filter_map(|v| v.as_str()).map(|v| v.to_string()).collect())
+            .unwrap_or_default()
     };
+    let extensions = capture_fhir_extensions(resource);
 
-    // Create anchor for deduplication
-    let now = sys_time().map_err(|e| e.to_string())?;
-    let anchor = FhirResourceAnchor {
-        source_key,
-        resource_type: "Patient".to_string(),
-        internal_hash: patient_hash.clone(),
-        first_ingested: Timestamp::from_micros(now.as_micros() as i64),
-        last_updated: Timestamp::from_micros(now.as_micros() as i64),
+    FhirPatientMapping {
+        internal_patient_hash: patient_hash.clone(),
+        fhir_patient_id: fhir_id.to_string(),
+        source_system: source_system.to_string(),
+        fhir_identifiers: elements("identifier")
+            .iter()
+            .filter_map(|identifier| {
+                Some(PatientIdentifier {
+                    system: get_fhir_string(identifier, "system")?,
+                    value: get_fhir_string(identifier, "value")?,
+                    use_code: get_fhir_string(identifier, "use"),
+                    type_display: identifier.pointer("/type/text").and_then(|t| t.as_str()).map(|t| t.to_string()),
+                })
+            })
+            .collect(),
+        name: elements("name")
+            .iter()
+            .map(|name| FhirHumanName {
+                use_code: get_fhir_string(name, "use"),
+                text: get_fhir_string(name, "text"),
+                family: get_fhir_string(name, "family"),
+                given: strings(name, "given"),
+                prefix: strings(name, "prefix"),
+                suffix: strings(name, "suffix"),
+            })
+            .collect(),
+        telecom: elements("telecom")
+            .iter()
+            .map(|contact| FhirContactPoint {
+                system: get_fhir_string(contact, "system"),
+                value: get_fhir_string(contact, "value"),
+                use_code: get_fhir_string(contact, "use"),
+                rank: contact.get("rank").and_then(|r| r.as_u64()).map(|r| r as u32),
+            })
+            .collect(),
+        gender: get_fhir_string(resource, "gender"),
+        birth_date: get_fhir_string(resource, "birthDate"),
+        deceased: get_fhir_string(resource, "deceasedDateTime")
+            .or_else(|| resource.get("deceasedBoolean").and_then(|d| d.as_bool()).map(|d| d.to_string())),
+        address: elements("address")
+            .iter()
+            .map(|address| FhirAddress {
+                use_code: get_fhir_string(address, "use"),
+                type_code: get_fhir_string(address, "type"),
+                text: get_fhir_string(address, "text"),
+                line: strings(address, "line"),
+                city: get_fhir_string(address, "city"),
+                state: get_fhir_string(address, "state"),
+                postal_code: get_fhir_string(address, "postalCode"),
+                country: get_fhir_string(address, "country"),
+            })
+            .collect(),
+        marital_status: resource.get("maritalStatus").map(json_codeable_concept),
+        communication: Vec::new(),
+        fhir_version_id: resource.pointer("/meta/versionId").and_then(|v| v.as_str()).map(|v| v.to_string()),
+        fhir_last_updated: resource.pointer("/meta/lastUpdated").and_then(|v| v.as_str()).map(|v| v.to_string()),
+        mapping_version: "1".to_string(),
+        last_synced: now,
+        sync_status: SyncStatus::Synced,
+        sync_errors: Vec::new(),
+        us_core: UsCoreDemographics::from_extensions(&extensions),
+        extensions,
+    }
+}
+
+/// What ingesting one Observation did
+enum ObservationIngest {
+    Created,
+    /// This source already sent the resource
+    Skipped,
+    /// Another source already sent the same result; joined its duplicate cluster
+    Deduplicated,
+}
+
+/// A bundle entry withdrawing a resource the source sent before
+struct UpstreamRetraction {
+    resource_type: String,
+    fhir_id: String,
+    trigger: RetractionTrigger,
+    reason: Option<String>,
+}
+
+/// Read a DELETE request or an entered-in-error resource as a retraction
+fn upstream_retraction(entry: &JsonValue) -> Option<UpstreamRetraction> {
+    let resource = entry.get("resource");
+    if entry.pointer("/request/method").and_then(|m| m.as_str()) == Some("DELETE") {
+        // "Observation/123", possibly absolute or with search parameters
+        let url = entry.pointer("/request/url")?.as_str()?;
+        let path = url.split('?').next()?.trim_end_matches('/');
+        let mut segments = path.rsplit('/');
+        let fhir_id = segments.next()?.to_string();
+        let resource_type = segments.next()?.to_string();
+        return Some(UpstreamRetraction {
+            resource_type,
+            fhir_id,
+            trigger: RetractionTrigger::Deleted,
+            reason: resource.and_then(retraction_reason),
+        });
+    }
+
+    let resource = resource?;
+    if !is_entered_in_error(resource) {
+        return None;
+    }
+    Some(UpstreamRetraction {
+        resource_type: get_resource_type(resource)?,
+        fhir_id: get_resource_id(resource)?,
+        trigger: RetractionTrigger::EnteredInError,
+        reason: retraction_reason(resource),
+    })
+}
+
+/// Entered-in-error as a plain status or, for conditions and allergies,
+/// as the verification status
+fn is_entered_in_error(resource: &JsonValue) -> bool {
+    get_fhir_string(resource, "status").as_deref() == Some("entered-in-error")
+        || resource
+            .pointer("/verificationStatus/coding")
+            .and_then(|c| c.as_array())
+            .is_some_and(|codings| codings.iter().any(|c| c["code"] == "entered-in-error"))
+}
+
+/// Why the source withdrew a resource, from its status reason or first note
+fn retraction_reason(resource: &JsonValue) -> Option<String> {
+    resource
+        .pointer("/statusReason/text")
+        .or_else(|| resource.pointer("/statusReason/coding/0/display"))
+        .or_else(|| resource.pointer("/note/0/text"))
+        .and_then(|r| r.as_str())
+        .map(|r| r.to_string())
+}
+
+/// Retract the mapping behind a resource its source withdrew
+///
+/// Returns false when this source never sent the resource, so there is
+/// nothing to withdraw.
+fn process_retraction(retraction: &UpstreamRetraction, source_system: &str) -> Result<bool, String> {
+    let key_type = match retraction.resource_type.as_str() {
+        "MedicationRequest" | "MedicationStatement" => "Medication",
+        "Observation" | "Condition" | "AllergyIntolerance" | "Immunization" | "Procedure"
+        | "DiagnosticReport" | "CarePlan" => retraction.resource_type.as_str(),
+        other => return Err(format!("retracting {} resources is not supported", other)),
+    };
+    let source_key = format!("{}:{}:{}", source_system, key_type, retraction.fhir_id);
+    let Some(anchor) = lookup_resource_anchor(&source_key).map_err(|e| e.to_string())? else {
+        return Ok(false);
     };
-    create_entry(&EntryTypes::FhirResourceAnchor(anchor))
-        .map_err(|e| e.to_string())?;
 
-    Ok((patient_hash, true))
+    let input = serde_json::json!({
+        "mapping_hash": anchor.internal_hash,
+        "resource_type": retraction.resource_type,
+        "fhir_id": retraction.fhir_id,
+        "source_system": source_system,
+        "trigger": retraction.trigger,
+        "reason": retraction.reason,
+    });
+    let _: Record = resilient_call("fhir_mapping", "retract_fhir_mapping", &input, CallClass::Bulk)
+        .map_err(|failure| format!("Failed to retract mapping: {}", failure))?;
+    Ok(true)
 }
 
 /// Process an Observation resource
-fn process_observation(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_observation(
+    resource: &JsonValue,
+    patient_hash: &ActionHash,
+    source_system: &str,
+    context: &ClassificationContext,
+    tolerance: &DedupTolerance,
+) -> Result<ObservationIngest, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("Observation missing 'id' field")?;
 
     // Check for duplicate
     let source_key = format!("{}:Observation:{}", source_system, fhir_id);
     if lookup_resource_anchor(&source_key).map_err(|e| e.to_string())?.is_some() {
-        return Ok(false); // Already exists
+        return Ok(ObservationIngest::Skipped);
     }
 
     // Extract observation data
@@ -575,6 +1340,39 @@ fn process_observation(resource: &JsonValue, patient_hash: &ActionHash, source_s
     let loinc_code = code.clone().unwrap_or_else(|| "unknown".to_string());
     let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
     let now = sys_time().map_err(|e| e.to_string())?;
+    let effective = get_fhir_time(resource, &["/effectiveDateTime", "/effectivePeriod/start", "/effectiveInstant"]);
+    let issued = get_fhir_time(resource, &["/issued"]);
+    check_resource_codes(resource, &["code"])?;
+    let value_quantity = extract_quantity(resource)?;
+
+    // Only results with a code, a value and a source-stated effective time
+    // have a clinical identity worth matching on
+    let identity = match (&code, extract_value(resource), effective) {
+        (Some(code), Some(value), Some(effective)) => Some((
+            clinical_identity_key(patient_hash, code, &value, extract_unit(resource).as_deref()),
+            effective,
+        )),
+        _ => None,
+    };
+    if let Some((key, effective)) = &identity {
+        let window = tolerance.window_for(&loinc_code);
+        if let Some(canonical) = find_clinical_duplicate(key, *effective, window).map_err(|e| e.to_string())? {
+            ingest_saga("Observation", &DUPLICATE_SAGA_STEPS, |saga| {
+                let anchor_action = create_resource_anchor(saga, &source_key, "Observation", &canonical)?;
+                saga.step("link_duplicate", |undo| {
+                    let link_hash = create_link(
+                        canonical.clone(),
+                        anchor_action,
+                        LinkTypes::ObservationDuplicates,
+                        LinkTag::new(source_system.as_bytes().to_vec()),
+                    ).map_err(|e| e.to_string())?;
+                    undo.push(SagaCompensation::DeleteLink(link_hash));
+                    Ok::<_, String>(())
+                })
+            })?;
+            return Ok(ObservationIngest::Deduplicated);
+        }
+    }
 
     let mapping = FhirObservationMapping {
         fhir_observation_id: fhir_id.clone(),
@@ -586,45 +1384,244 @@ fn process_observation(resource: &JsonValue, patient_hash: &ActionHash, source_s
         code: build_codeable_concept(code, display, system),
         loinc_code,
         snomed_code: None,
-        value_quantity: None,
+        value_quantity,
         value_codeable_concept: None,
         value_string: extract_value(resource),
         value_boolean: resource.get("valueBoolean").and_then(|v| v.as_bool()),
-        effective_datetime: now,
-        issued: None,
+        effective_datetime: effective.unwrap_or(now),
+        issued,
         reference_range: None,
         interpretation: Vec::new(),
         note: Vec::new(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(effective.unwrap_or(now).min(now)),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    // Call fhir_mapping to create
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_observation_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create observation mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode observation: {}", e))?;
-            record.action_address().clone()
+    ingest_saga("Observation", &OBSERVATION_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_observation_mapping", &mapping, "observation")?;
+        create_resource_anchor(saga, &source_key, "Observation", &mapping_hash)?;
+        match &identity {
+            Some((key, effective)) => saga.step("link_clinical_identity", |undo| {
+                let bucket = anchor_hash(&format!("{}:{}", key, clinical_identity_bucket(*effective)))
+                    .map_err(|e| e.to_string())?;
+                let link_hash = create_link(
+                    bucket,
+                    mapping_hash.clone(),
+                    LinkTypes::ClinicalIdentityToObservation,
+                    LinkTag::new(effective.as_micros().to_be_bytes().to_vec()),
+                ).map_err(|e| e.to_string())?;
+                undo.push(SagaCompensation::DeleteLink(link_hash));
+                Ok(())
+            }),
+            None => Ok(()),
+        }
+    })?;
+    Ok(ObservationIngest::Created)
+}
+
+/// Mapping of an already-ingested result with the same clinical identity
+/// whose effective time is within `window_seconds`, nearest first
+fn find_clinical_duplicate(key: &str, effective: Timestamp, window_seconds: u32) -> ExternResult<Option<ActionHash>> {
+    let window_micros = window_seconds as i64 * 1_000_000;
+    let mut nearest: Option<(i64, ActionHash)> = None;
+    for bucket in clinical_identity_buckets(effective, window_seconds) {
+        let links = get_links(
+            LinkQuery::try_new(anchor_hash(&format!("{}:{}", key, bucket))?, LinkTypes::ClinicalIdentityToObservation)?,
+            GetStrategy::default(),
+        )?;
+        for link in links {
+            let Ok(micros) = <[u8; 8]>::try_from(link.tag.as_ref().as_slice()).map(i64::from_be_bytes) else {
+                continue;
+            };
+            let distance = (micros - effective.as_micros()).abs();
+            if distance > window_micros || nearest.as_ref().is_some_and(|(best, _)| *best <= distance) {
+                continue;
+            }
+            if let Some(hash) = link.target.into_action_hash() {
+                nearest = Some((distance, hash));
+            }
+        }
+    }
+    Ok(nearest.map(|(_, hash)| hash))
+}
+
+/// Observations sharing a clinical identity with an ingested result
+#[hdk_extern]
+pub fn get_observation_duplicates(mapping_hash: ActionHash) -> ExternResult<ObservationDuplicateCluster> {
+    let links = get_links(
+        LinkQuery::try_new(mapping_hash.clone(), LinkTypes::ObservationDuplicates)?,
+        GetStrategy::default(),
+    )?;
+    let mut duplicates = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(anchor) = get(hash, GetOptions::default())?
+                .and_then(|record| record.entry().to_app_option::<FhirResourceAnchor>().ok().flatten())
+            {
+                duplicates.push(anchor);
+            }
         }
-        _ => return Err("Failed to create observation mapping".to_string()),
+    }
+    Ok(ObservationDuplicateCluster {
+        canonical_mapping: mapping_hash,
+        duplicates,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ObservationDuplicateCluster {
+    /// Mapping created from the first source to send the result
+    pub canonical_mapping: ActionHash,
+    /// Source anchors of the same result from other sources
+    pub duplicates: Vec<FhirResourceAnchor>,
+}
+
+/// Record an evidence reference between two ingested mappings
+///
+/// Returns false when either side was never ingested or the link already
+/// exists from an earlier ingest.
+fn link_evidence(reference: &EvidenceReference, source_system: &str) -> Result<bool, String> {
+    let condition_key = format!("{}:Condition:{}", source_system, reference.condition_id);
+    let result_key = format!("{}:{}:{}", source_system, reference.result_type, reference.result_id);
+    let (Some(condition), Some(result)) = (
+        lookup_resource_anchor(&condition_key).map_err(|e| e.to_string())?,
+        lookup_resource_anchor(&result_key).map_err(|e| e.to_string())?,
+    ) else {
+        return Ok(false);
     };
 
-    // Create deduplication anchor
-    create_resource_anchor(&source_key, "Observation", &mapping_hash)?;
+    let evidence = EvidenceLink {
+        condition_mapping: condition.internal_hash,
+        observation_mapping: result.internal_hash,
+        evidence_type: reference.evidence_type,
+        source_system: source_system.to_string(),
+        derived_from: reference.derived_from(),
+    };
+    let evidence_hash = hash_entry(&evidence).map_err(|e| e.to_string())?;
+    let existing = get_links(
+        LinkQuery::try_new(evidence.condition_mapping.clone(), LinkTypes::ConditionToEvidence)
+            .map_err(|e| e.to_string())?,
+        GetStrategy::default(),
+    ).map_err(|e| e.to_string())?;
+    if existing.iter().any(|link| link.target.clone().into_entry_hash().as_ref() == Some(&evidence_hash)) {
+        return Ok(false);
+    }
 
+    ingest_saga("EvidenceLink", &EVIDENCE_SAGA_STEPS, |saga| {
+        saga.step("create_evidence", |undo| {
+            let hash = create_entry(&EntryTypes::EvidenceLink(evidence.clone())).map_err(|e| e.to_string())?;
+            undo.push(SagaCompensation::DeleteEntry(hash));
+            Ok::<_, String>(())
+        })?;
+        saga.step("link_condition", |undo| {
+            let link_hash = create_link(
+                evidence.condition_mapping.clone(),
+                evidence_hash.clone(),
+                LinkTypes::ConditionToEvidence,
+                LinkTag::new(evidence.evidence_type.tag().as_bytes().to_vec()),
+            ).map_err(|e| e.to_string())?;
+            undo.push(SagaCompensation::DeleteLink(link_hash));
+            Ok::<_, String>(())
+        })?;
+        saga.step("link_observation", |undo| {
+            let link_hash = create_link(
+                evidence.observation_mapping.clone(),
+                evidence_hash.clone(),
+                LinkTypes::ObservationToEvidence,
+                LinkTag::new(evidence.evidence_type.tag().as_bytes().to_vec()),
+            ).map_err(|e| e.to_string())?;
+            undo.push(SagaCompensation::DeleteLink(link_hash));
+            Ok(())
+        })
+    })?;
     Ok(true)
 }
 
+/// Results recorded as evidence for a condition mapping, for chart review
+///
+/// Each result is read through the fhir_mapping zome, so the caller needs
+/// lab results access to the patient and the read is audited there.
+#[hdk_extern]
+pub fn get_condition_evidence(condition_hash: ActionHash) -> ExternResult<ConditionEvidence> {
+    let links = get_links(
+        LinkQuery::try_new(condition_hash.clone(), LinkTypes::ConditionToEvidence)?,
+        GetStrategy::default(),
+    )?;
+    let mut evidence = Vec::new();
+    for link in links {
+        let Some(hash) = link.target.into_entry_hash() else {
+            continue;
+        };
+        let Some(entry) = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<EvidenceLink>().ok().flatten())
+        else {
+            continue;
+        };
+        let read_input = serde_json::json!({
+            "mapping_hash": entry.observation_mapping,
+            "is_emergency": false,
+            "emergency_reason": null
+        });
+        let observation: Option<Record> =
+            resilient_call("fhir_mapping", "get_fhir_observation_mapping", &read_input, CallClass::Bulk)
+                .map_err(|failure| failure.into_wasm_error("reading evidence observation"))?;
+        evidence.push(ConditionEvidenceItem {
+            evidence_type: entry.evidence_type,
+            observation_mapping: entry.observation_mapping,
+            derived_from: entry.derived_from,
+            observation: observation
+                .and_then(|record| record.entry().to_app_option::<FhirObservationMapping>().ok().flatten()),
+        });
+    }
+    // Supporting evidence first, newest results first within each type
+    let rank = |t: EvidenceType| match t {
+        EvidenceType::Diagnostic => 0,
+        EvidenceType::Monitoring => 1,
+        EvidenceType::RuleOut => 2,
+    };
+    evidence.sort_by(|a, b| {
+        rank(a.evidence_type).cmp(&rank(b.evidence_type)).then_with(|| {
+            let effective = |item: &ConditionEvidenceItem| item.observation.as_ref().map(|o| o.effective_datetime);
+            effective(b).cmp(&effective(a))
+        })
+    });
+    Ok(ConditionEvidence {
+        condition_mapping: condition_hash,
+        evidence,
+    })
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConditionEvidenceItem {
+    pub evidence_type: EvidenceType,
+    pub observation_mapping: ActionHash,
+    /// Source reference the link came from
+    pub derived_from: String,
+    /// None when the mapping could not be found
+    pub observation: Option<FhirObservationMapping>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ConditionEvidence {
+    pub condition_mapping: ActionHash,
+    pub evidence: Vec<ConditionEvidenceItem>,
+}
+
 /// Process a Condition resource
-fn process_condition(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+///
+/// Without an asserted onset, the earliest of `onset_candidates` is kept as
+/// an inferred onset and places the condition on the timeline.
+fn process_condition(
+    resource: &JsonValue,
+    patient_hash: &ActionHash,
+    source_system: &str,
+    context: &ClassificationContext,
+    onset_candidates: &[OnsetCandidate],
+) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("Condition missing 'id' field")?;
 
@@ -633,8 +1630,15 @@ fn process_condition(resource: &JsonValue, patient_hash: &ActionHash, source_sys
         return Ok(false);
     }
 
+    check_resource_codes(resource, &["code"])?;
     let (code, display, system) = extract_coding(resource, "code");
     let now = sys_time().map_err(|e| e.to_string())?;
+    let onset = get_fhir_time(resource, &["/onsetDateTime", "/onsetPeriod/start"]);
+    let inferred_onset = match onset {
+        Some(_) => None,
+        None => infer_onset(onset_candidates),
+    };
+    let recorded_date = get_fhir_time(resource, &["/recordedDate"]);
     let clinical_status = get_fhir_string(resource, "clinicalStatus").unwrap_or_else(|| "unknown".to_string());
     let verification_status = get_fhir_string(resource, "verificationStatus").unwrap_or_else(|| "unknown".to_string());
     let icd10_code = extract_icd10(resource).unwrap_or_else(|| "unknown".to_string());
@@ -652,39 +1656,32 @@ fn process_condition(resource: &JsonValue, patient_hash: &ActionHash, source_sys
         icd10_code,
         snomed_code: None,
         body_site: Vec::new(),
-        onset_datetime: None,
-        abatement_datetime: None,
-        recorded_date: None,
+        onset_datetime: onset,
+        abatement_datetime: get_fhir_time(resource, &["/abatementDateTime", "/abatementPeriod/end"]),
+        recorded_date,
         recorder_reference: None,
         asserter_reference: None,
         note: Vec::new(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(
+            normalized_onset(onset, inferred_onset.as_ref()).at.or(recorded_date).unwrap_or(now).min(now),
+        ),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
+        inferred_onset,
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_condition_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create condition mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode condition: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create condition mapping".to_string()),
-    };
-
-    create_resource_anchor(&source_key, "Condition", &mapping_hash)?;
+    ingest_saga("Condition", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_condition_mapping", &mapping, "condition")?;
+        create_resource_anchor(saga, &source_key, "Condition", &mapping_hash)
+    })?;
     Ok(true)
 }
 
 /// Process a Medication resource
-fn process_medication(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_medication(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str, context: &ClassificationContext) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("Medication missing 'id' field")?;
 
@@ -693,10 +1690,12 @@ fn process_medication(resource: &JsonValue, patient_hash: &ActionHash, source_sy
         return Ok(false);
     }
 
+    check_resource_codes(resource, &["medicationCodeableConcept"])?;
     let medication_code = extract_medication_code(resource);
     let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
     let intent = get_fhir_string(resource, "intent").unwrap_or_else(|| "unknown".to_string());
     let now = sys_time().map_err(|e| e.to_string())?;
+    let authored_on = get_fhir_time(resource, &["/authoredOn", "/effectiveDateTime", "/effectivePeriod/start"]);
     let rxnorm_code = medication_code.0.clone().unwrap_or_else(|| "unknown".to_string());
 
     let mapping = FhirMedicationMapping {
@@ -715,35 +1714,25 @@ fn process_medication(resource: &JsonValue, patient_hash: &ActionHash, source_sy
         dispense_quantity: None,
         dispense_refills: None,
         validity_period: None,
-        authored_on: None,
+        authored_on,
         note: Vec::new(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(authored_on.unwrap_or(now).min(now)),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_medication_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create medication mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode medication: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create medication mapping".to_string()),
-    };
-
-    create_resource_anchor(&source_key, "Medication", &mapping_hash)?;
+    ingest_saga("Medication", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_medication_mapping", &mapping, "medication")?;
+        create_resource_anchor(saga, &source_key, "Medication", &mapping_hash)
+    })?;
     Ok(true)
 }
 
 /// Process an AllergyIntolerance resource
-fn process_allergy(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_allergy(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str, context: &ClassificationContext) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("AllergyIntolerance missing 'id' field")?;
 
@@ -757,6 +1746,8 @@ fn process_allergy(resource: &JsonValue, patient_hash: &ActionHash, source_syste
     let (code, display, system) = extract_coding(resource, "code");
     let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
     let now = sys_time().map_err(|e| e.to_string())?;
+    let effective = get_fhir_time(resource, &["/onsetDateTime", "/onsetPeriod/start", "/recordedDate"]);
+    let issued = get_fhir_time(resource, &["/recordedDate"]);
 
     let mapping = FhirObservationMapping {
         fhir_observation_id: format!("allergy-{}", fhir_id),
@@ -772,38 +1763,28 @@ fn process_allergy(resource: &JsonValue, patient_hash: &ActionHash, source_syste
         value_codeable_concept: None,
         value_string: serde_json::to_string(resource).ok(),
         value_boolean: None,
-        effective_datetime: now,
-        issued: None,
+        effective_datetime: effective.unwrap_or(now),
+        issued,
         reference_range: None,
         interpretation: Vec::new(),
         note: Vec::new(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(effective.unwrap_or(now).min(now)),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_observation_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create allergy mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode allergy: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create allergy mapping".to_string()),
-    };
-
-    create_resource_anchor(&source_key, "AllergyIntolerance", &mapping_hash)?;
+    ingest_saga("AllergyIntolerance", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_observation_mapping", &mapping, "allergy")?;
+        create_resource_anchor(saga, &source_key, "AllergyIntolerance", &mapping_hash)
+    })?;
     Ok(true)
 }
 
 /// Process an Immunization resource
-fn process_immunization(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_immunization(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str, context: &ClassificationContext) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("Immunization missing 'id' field")?;
 
@@ -815,6 +1796,8 @@ fn process_immunization(resource: &JsonValue, patient_hash: &ActionHash, source_
     let (code, display, system) = extract_coding(resource, "vaccineCode");
     let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
     let now = sys_time().map_err(|e| e.to_string())?;
+    let effective = get_fhir_time(resource, &["/occurrenceDateTime"]);
+    let issued = get_fhir_time(resource, &["/recorded"]);
 
     let mapping = FhirObservationMapping {
         fhir_observation_id: format!("immunization-{}", fhir_id),
@@ -830,38 +1813,28 @@ fn process_immunization(resource: &JsonValue, patient_hash: &ActionHash, source_
         value_codeable_concept: None,
         value_string: serde_json::to_string(resource).ok(),
         value_boolean: None,
-        effective_datetime: now,
-        issued: None,
+        effective_datetime: effective.unwrap_or(now),
+        issued,
         reference_range: None,
         interpretation: Vec::new(),
         note: Vec::new(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(effective.unwrap_or(now).min(now)),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_observation_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create immunization mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode immunization: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create immunization mapping".to_string()),
-    };
-
-    create_resource_anchor(&source_key, "Immunization", &mapping_hash)?;
+    ingest_saga("Immunization", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_observation_mapping", &mapping, "immunization")?;
+        create_resource_anchor(saga, &source_key, "Immunization", &mapping_hash)
+    })?;
     Ok(true)
 }
 
 /// Process a Procedure resource
-fn process_procedure(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_procedure(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str, context: &ClassificationContext) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("Procedure missing 'id' field")?;
 
@@ -870,58 +1843,72 @@ fn process_procedure(resource: &JsonValue, patient_hash: &ActionHash, source_sys
         return Ok(false);
     }
 
-    let (code, display, system) = extract_coding(resource, "code");
-    let loinc_code = code.clone().unwrap_or_else(|| "procedure".to_string());
-    let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
+    let code = resource.get("code").ok_or("Procedure missing 'code' field")?;
+    check_resource_codes(resource, &["code"])?;
+    let cpt_code = find_coding_code(code, "cpt");
+    let snomed_code = find_coding_code(code, "snomed");
+    if cpt_code.is_none() && snomed_code.is_none() {
+        return Err("Procedure has no CPT or SNOMED code".to_string());
+    }
+
+    let complications = json_codeable_concepts(resource, "complication");
+    let performers = resource.get("performer")
+        .and_then(|p| p.as_array())
+        .map(|performers| {
+            performers.iter()
+                .filter_map(|p| {
+                    Some(ProcedurePerformer {
+                        actor: json_reference(p.get("actor")?)?,
+                        function: p.get("function").map(json_codeable_concept),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
     let now = sys_time().map_err(|e| e.to_string())?;
 
-    let mapping = FhirObservationMapping {
-        fhir_observation_id: format!("procedure-{}", fhir_id),
-        internal_record_hash: patient_hash.clone(),
+    let mapping = FhirProcedureMapping {
+        internal_procedure_hash: None,
         patient_hash: patient_hash.clone(),
+        fhir_procedure_id: fhir_id,
         source_system: source_system.to_string(),
-        status,
-        category: Vec::new(),
-        code: build_codeable_concept(code, display, system),
-        loinc_code,
-        snomed_code: None,
-        value_quantity: None,
-        value_codeable_concept: None,
-        value_string: serde_json::to_string(resource).ok(),
-        value_boolean: None,
-        effective_datetime: now,
-        issued: None,
-        reference_range: None,
-        interpretation: Vec::new(),
-        note: Vec::new(),
+        status: get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string()),
+        code: json_codeable_concept(code),
+        cpt_code,
+        snomed_code,
+        performed_datetime: get_fhir_string(resource, "performedDateTime"),
+        performed_period: resource.get("performedPeriod").map(|period| FhirPeriod {
+            start: get_fhir_string(period, "start"),
+            end: get_fhir_string(period, "end"),
+        }),
+        performers,
+        body_site: json_codeable_concepts(resource, "bodySite"),
+        outcome: resource.get("outcome").map(json_codeable_concept),
+        has_complications: !complications.is_empty(),
+        complications,
+        reason_code: json_codeable_concepts(resource, "reasonCode"),
+        encounter_reference: resource.get("encounter").and_then(json_reference),
+        note: resource.get("note")
+            .and_then(|n| n.as_array())
+            .map(|notes| notes.iter().filter_map(|n| get_fhir_string(n, "text")).collect())
+            .unwrap_or_default(),
+        source_resource: serde_json::to_string(resource).ok(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_observation_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create procedure mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode procedure: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create procedure mapping".to_string()),
-    };
-
-    create_resource_anchor(&source_key, "Procedure", &mapping_hash)?;
+    ingest_saga("Procedure", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_procedure_mapping", &mapping, "procedure")?;
+        create_resource_anchor(saga, &source_key, "Procedure", &mapping_hash)
+    })?;
     Ok(true)
 }
 
 /// Process a DiagnosticReport resource
 /// DiagnosticReports represent lab results, imaging studies, pathology reports, etc.
-fn process_diagnostic_report(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_diagnostic_report(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str, context: &ClassificationContext) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("DiagnosticReport missing 'id' field")?;
 
@@ -935,6 +1922,8 @@ fn process_diagnostic_report(resource: &JsonValue, patient_hash: &ActionHash, so
     let category = extract_category(resource);
     let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
     let now = sys_time().map_err(|e| e.to_string())?;
+    let effective = get_fhir_time(resource, &["/effectiveDateTime", "/effectivePeriod/start", "/effectiveInstant"]);
+    let issued = get_fhir_time(resource, &["/issued"]);
     let loinc_code = code.clone().unwrap_or_else(|| format!("diagnostic-report:{}", category.unwrap_or_default()));
 
     let mapping = FhirObservationMapping {
@@ -951,39 +1940,165 @@ fn process_diagnostic_report(resource: &JsonValue, patient_hash: &ActionHash, so
         value_codeable_concept: None,
         value_string: serde_json::to_string(resource).ok(),
         value_boolean: None,
-        effective_datetime: now,
-        issued: None,
+        effective_datetime: effective.unwrap_or(now),
+        issued,
         reference_range: None,
         interpretation: Vec::new(),
         note: Vec::new(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(effective.unwrap_or(now).min(now)),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_observation_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create diagnostic report mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode diagnostic report: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create diagnostic report mapping".to_string()),
+    ingest_saga("DiagnosticReport", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_observation_mapping", &mapping, "diagnostic report")?;
+        create_resource_anchor(saga, &source_key, "DiagnosticReport", &mapping_hash)
+    })?;
+    Ok(true)
+}
+
+/// Process a ServiceRequest resource as a lab order placed by this bridge agent
+fn process_service_request(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+    let fhir_id = get_resource_id(resource)
+        .ok_or("ServiceRequest missing 'id' field")?;
+
+    let source_key = format!("{}:ServiceRequest:{}", source_system, fhir_id);
+    if lookup_resource_anchor(&source_key).map_err(|e| e.to_string())?.is_some() {
+        return Ok(false);
+    }
+    // Revoked orders will never produce a specimen, so they are not tracked
+    let status = get_fhir_string(resource, "status").unwrap_or_default();
+    if matches!(status.as_str(), "revoked" | "entered-in-error") {
+        return Ok(false);
+    }
+
+    let (code, display, _) = extract_coding(resource, "code");
+    let loinc_code = resource
+        .get("code")
+        .and_then(|concept| find_coding_code(concept, "loinc"))
+        .or(code)
+        .ok_or("ServiceRequest missing 'code'")?;
+    let now = sys_time().map_err(|e| e.to_string())?;
+    let priority = match get_fhir_string(resource, "priority").as_deref() {
+        Some("urgent") => LabOrderPriority::Urgent,
+        Some("asap" | "stat") => LabOrderPriority::Stat,
+        _ => LabOrderPriority::Routine,
+    };
+    let order = LabOrder {
+        order_id: format!("{}:{}", source_system, fhir_id),
+        patient_hash: patient_hash.clone(),
+        encounter_hash: None,
+        ordering_provider: agent_info().map_err(|e| e.to_string())?.agent_initial_pubkey,
+        test_name: display
+            .or_else(|| resource.pointer("/code/text").and_then(|t| t.as_str()).map(|t| t.to_string()))
+            .unwrap_or_else(|| loinc_code.clone()),
+        loinc_code,
+        priority,
+        clinical_indication: resource
+            .pointer("/reasonCode/0/text")
+            .or_else(|| resource.pointer("/reasonCode/0/coding/0/display"))
+            .and_then(|t| t.as_str())
+            .map(|t| t.to_string()),
+        status: LabOrderStatus::Ordered,
+        ordered_at: get_fhir_time(resource, &["/authoredOn"]).unwrap_or(now),
+        status_changed_at: now,
     };
+    let order_input = serde_json::json!({
+        "order": order,
+        "is_emergency": false,
+        "emergency_reason": null
+    });
+
+    ingest_saga("ServiceRequest", &INGEST_SAGA_STEPS, |saga| {
+        let order_hash = create_record_step(saga, "create_lab_order", &order_input, "lab order")?;
+        create_resource_anchor(saga, &source_key, "ServiceRequest", &order_hash)
+    })?;
+    Ok(true)
+}
+
+/// Process a Specimen resource against the lab order of its ServiceRequest
+fn process_specimen(resource: &JsonValue, source_system: &str) -> Result<bool, String> {
+    let fhir_id = get_resource_id(resource)
+        .ok_or("Specimen missing 'id' field")?;
+
+    let source_key = format!("{}:Specimen:{}", source_system, fhir_id);
+    if lookup_resource_anchor(&source_key).map_err(|e| e.to_string())?.is_some() {
+        return Ok(false);
+    }
+
+    let request_id = get_service_request_id(resource)
+        .ok_or("Specimen does not reference a ServiceRequest")?;
+    let order = lookup_resource_anchor(&format!("{}:ServiceRequest:{}", source_system, request_id))
+        .map_err(|e| e.to_string())?
+        .ok_or(format!("ServiceRequest/{} has not been ingested", request_id))?;
+
+    let (code, display, _) = extract_coding(resource, "type");
+    let now = sys_time().map_err(|e| e.to_string())?;
+    let specimen_input = serde_json::json!({
+        "order_hash": order.internal_hash,
+        "specimen_id": fhir_id,
+        "accession_number": resource
+            .pointer("/accessionIdentifier/value")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string())
+            .unwrap_or_else(|| format!("{}:{}", source_system, fhir_id)),
+        "specimen_type": display
+            .or(code)
+            .or_else(|| resource.pointer("/type/text").and_then(|t| t.as_str()).map(|t| t.to_string()))
+            .unwrap_or_else(|| "unknown".to_string()),
+        "collection_time": get_fhir_time(resource, &["/collection/collectedDateTime", "/collection/collectedPeriod/start"])
+            .unwrap_or(now)
+            .min(now),
+        "location": source_system,
+        "note": null,
+        "is_emergency": false,
+        "emergency_reason": null
+    });
+
+    ingest_saga("Specimen", &INGEST_SAGA_STEPS, |saga| {
+        let specimen_hash = create_record_step(saga, "collect_specimen", &specimen_input, "specimen")?;
+        create_resource_anchor(saga, &source_key, "Specimen", &specimen_hash)
+    })?;
+    Ok(true)
+}
+
+/// Process a Consent resource as a consent the patient must confirm
+///
+/// The consent zome translates the resource; nothing is granted until the
+/// patient accepts the proposal there.
+fn process_consent(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+    let fhir_id = get_resource_id(resource)
+        .ok_or("Consent missing 'id' field")?;
 
-    create_resource_anchor(&source_key, "DiagnosticReport", &mapping_hash)?;
+    let source_key = format!("{}:Consent:{}", source_system, fhir_id);
+    if lookup_resource_anchor(&source_key).map_err(|e| e.to_string())?.is_some() {
+        return Ok(false);
+    }
+
+    let proposal = serde_json::json!({
+        "patient_hash": patient_hash,
+        "source_system": source_system,
+        "resource": resource,
+    });
+    ingest_saga("Consent", &INGEST_SAGA_STEPS, |saga| {
+        let consent_hash = saga.step("create_mapping", |undo| {
+            let record: Record = resilient_call("consent", "propose_consent_from_fhir", &proposal, CallClass::Bulk)
+                .map_err(|failure| format!("Failed to propose consent: {}", failure))?;
+            let hash = record.action_address().clone();
+            undo.push(SagaCompensation::DeleteEntry(hash.clone()));
+            Ok::<_, String>(hash)
+        })?;
+        create_resource_anchor(saga, &source_key, "Consent", &consent_hash)
+    })?;
     Ok(true)
 }
 
 /// Process a CarePlan resource
 /// CarePlans represent care plans, treatment plans, health maintenance plans
-fn process_care_plan(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<bool, String> {
+fn process_care_plan(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str, context: &ClassificationContext) -> Result<bool, String> {
     let fhir_id = get_resource_id(resource)
         .ok_or("CarePlan missing 'id' field")?;
 
@@ -998,6 +2113,8 @@ fn process_care_plan(resource: &JsonValue, patient_hash: &ActionHash, source_sys
     let status = get_fhir_string(resource, "status").unwrap_or_else(|| "unknown".to_string());
     let category = extract_category(resource);
     let now = sys_time().map_err(|e| e.to_string())?;
+    let effective = get_fhir_time(resource, &["/period/start", "/created"]);
+    let issued = get_fhir_time(resource, &["/created"]);
 
     let display = title
         .or_else(|| description.clone())
@@ -1021,36 +2138,81 @@ fn process_care_plan(resource: &JsonValue, patient_hash: &ActionHash, source_sys
         value_codeable_concept: None,
         value_string: serde_json::to_string(resource).ok(),
         value_boolean: None,
-        effective_datetime: now,
-        issued: None,
+        effective_datetime: effective.unwrap_or(now),
+        issued,
         reference_range: None,
         interpretation: Vec::new(),
         note: description.into_iter().collect(),
         mapping_version: "1".to_string(),
         last_synced: now,
+        effective_time: Some(effective.unwrap_or(now).min(now)),
+        recorded_time: Some(now),
+        sensitive_categories: sensitive_categories(&classify_resource(resource, context), &context.sensitivity),
+        extensions: capture_fhir_extensions(resource),
     };
 
-    let response = call(
-        CallTargetCell::Local,
-        ZomeName::from("fhir_mapping"),
-        FunctionName::from("create_fhir_observation_mapping"),
-        None,
-        &mapping,
-    ).map_err(|e| format!("Failed to create care plan mapping: {}", e))?;
-
-    let mapping_hash: ActionHash = match response {
-        ZomeCallResponse::Ok(io) => {
-            let record: Record = io.decode()
-                .map_err(|e| format!("Failed to decode care plan: {}", e))?;
-            record.action_address().clone()
-        }
-        _ => return Err("Failed to create care plan mapping".to_string()),
-    };
-
-    create_resource_anchor(&source_key, "CarePlan", &mapping_hash)?;
+    ingest_saga("CarePlan", &INGEST_SAGA_STEPS, |saga| {
+        let mapping_hash = create_mapping_step(saga, "create_fhir_observation_mapping", &mapping, "care plan")?;
+        create_resource_anchor(saga, &source_key, "CarePlan", &mapping_hash)
+    })?;
     Ok(true)
 }
 
+/// Create the follow-up tasks a discharge summary lists, assigned to the
+/// patient's care team by the records zome
+fn create_discharge_tasks(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<Vec<ActionHash>, String> {
+    let follow_ups = discharge_follow_ups(resource);
+    if follow_ups.is_empty() {
+        return Ok(Vec::new());
+    }
+    let source_reference = format!(
+        "{}:{}/{}",
+        source_system,
+        get_resource_type(resource).unwrap_or_default(),
+        get_resource_id(resource).unwrap_or_default()
+    );
+    let input = serde_json::json!({
+        "patient_hash": patient_hash,
+        "care_team_hash": null,
+        "source_reference": source_reference,
+        "tasks": follow_ups,
+        "is_emergency": false,
+        "emergency_reason": null
+    });
+    let records: Vec<Record> = resilient_call("records", "create_follow_up_tasks", &input, CallClass::Bulk)
+        .map_err(|failure| format!("Failed to create follow-up tasks: {}", failure))?;
+    Ok(records.iter().map(|record| record.action_address().clone()).collect())
+}
+
+/// Steps of a discharge Composition ingest
+const DISCHARGE_SAGA_STEPS: [&str; 3] = ["create_tasks", "create_anchor", "link_anchor"];
+
+/// Process a discharge summary Composition into follow-up tasks
+///
+/// The Composition itself is not mapped; its anchor points at the first task
+/// so a re-sent summary is skipped. Returns `None` when already ingested.
+fn process_discharge_composition(resource: &JsonValue, patient_hash: &ActionHash, source_system: &str) -> Result<Option<u32>, String> {
+    let fhir_id = get_resource_id(resource)
+        .ok_or("Composition missing 'id' field")?;
+
+    let source_key = format!("{}:Composition:{}", source_system, fhir_id);
+    if lookup_resource_anchor(&source_key).map_err(|e| e.to_string())?.is_some() {
+        return Ok(None);
+    }
+
+    ingest_saga("Composition", &DISCHARGE_SAGA_STEPS, |saga| {
+        let task_hashes = saga.step("create_tasks", |undo| {
+            let hashes = create_discharge_tasks(resource, patient_hash, source_system)?;
+            undo.extend(hashes.iter().cloned().map(SagaCompensation::DeleteEntry));
+            Ok::<_, String>(hashes)
+        })?;
+        if let Some(first) = task_hashes.first() {
+            create_resource_anchor(saga, &source_key, "Composition", first)?;
+        }
+        Ok(Some(task_hashes.len() as u32))
+    })
+}
+
 /// Extract category from FHIR resource
 fn extract_category(resource: &JsonValue) -> Option<String> {
     resource.get("category")
@@ -1069,6 +2231,227 @@ fn extract_category(resource: &JsonValue) -> Option<String> {
         })
 }
 
+// ============================================================================
+// Ingest Statistics
+// ============================================================================
+
+const INGEST_SOURCES_STAT: &str = "stats:ingest_sources";
+
+/// Per-day counters kept for each source system, in `IngestTotals` field order
+const INGEST_METRICS: [&str; 5] = [
+    "ingests",
+    "ingests_with_errors",
+    "resources_processed",
+    "parse_errors",
+    "stored_bytes",
+];
+
+fn ingest_counter_anchor(source_system: &str, day: i64, metric: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("stats:ingest:{}:{}:{}", source_system, day, metric))
+}
+
+/// Store an ingest report and fold it into its source's statistics
+fn store_ingest_report(report: &IngestReport) -> ExternResult<ActionHash> {
+    let report_hash = create_entry(&EntryTypes::IngestReport(report.clone()))?;
+
+    register_ingest_source(&report.source_system)?;
+    let report_bytes = SerializedBytes::try_from(report.clone())
+        .map(|bytes| bytes.bytes().len() as u64)
+        .unwrap_or(0);
+    let values = [
+        1,
+        u64::from(!report.parse_errors.is_empty()),
+        u64::from(report.total_processed),
+        report.parse_errors.len() as u64,
+        report_bytes + report.payload_stats.stored_bytes,
+    ];
+    let day = stats_day(report.ingested_at);
+    for (metric, value) in INGEST_METRICS.iter().zip(values) {
+        if value > 0 {
+            increment_counter(
+                ingest_counter_anchor(&report.source_system, day, metric)?,
+                LinkTypes::IngestStatisticsCounter,
+                value as i64,
+            )?;
+        }
+    }
+
+    for code in QualityFindingCode::ALL {
+        let count = report.quality_findings.iter().filter(|f| f.code == code).count();
+        if count > 0 {
+            increment_counter(
+                quality_counter_anchor(&report.source_system, day, &format!("{:?}", code))?,
+                LinkTypes::IngestStatisticsCounter,
+                count as i64,
+            )?;
+        }
+    }
+    if report.outcome == IngestOutcome::AcceptedWithWarnings {
+        increment_counter(
+            quality_counter_anchor(&report.source_system, day, ACCEPTED_WITH_WARNINGS_METRIC)?,
+            LinkTypes::IngestStatisticsCounter,
+            1,
+        )?;
+    }
+
+    Ok(report_hash)
+}
+
+const ACCEPTED_WITH_WARNINGS_METRIC: &str = "accepted_with_warnings";
+
+fn quality_counter_anchor(source_system: &str, day: i64, metric: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("stats:quality:{}:{}:{}", source_system, day, metric))
+}
+
+fn ingest_sources() -> ExternResult<Vec<String>> {
+    let links = get_links(
+        LinkQuery::try_new(anchor_hash(INGEST_SOURCES_STAT)?, LinkTypes::IngestStatisticsSources)?,
+        GetStrategy::default(),
+    )?;
+    let mut sources: Vec<String> = links
+        .into_iter()
+        .filter_map(|link| String::from_utf8(link.tag.0).ok())
+        .collect();
+    sources.sort();
+    sources.dedup();
+    Ok(sources)
+}
+
+fn register_ingest_source(source_system: &str) -> ExternResult<()> {
+    if ingest_sources()?.iter().any(|s| s == source_system) {
+        return Ok(());
+    }
+    create_link(
+        anchor_hash(INGEST_SOURCES_STAT)?,
+        anchor_hash(&format!("stats:ingest_source:{}", source_system))?,
+        LinkTypes::IngestStatisticsSources,
+        LinkTag::new(source_system.as_bytes().to_vec()),
+    )?;
+    Ok(())
+}
+
+fn read_ingest_day(source_system: &str, day: i64) -> ExternResult<IngestTotals> {
+    let mut values = [0u64; 5];
+    for (value, metric) in values.iter_mut().zip(INGEST_METRICS) {
+        let count = read_counter(
+            ingest_counter_anchor(source_system, day, metric)?,
+            LinkTypes::IngestStatisticsCounter,
+        )?;
+        *value = count.max(0) as u64;
+    }
+    let [ingests, ingests_with_errors, resources_processed, parse_errors, stored_bytes] = values;
+    Ok(IngestTotals {
+        ingests,
+        ingests_with_errors,
+        resources_processed,
+        parse_errors,
+        stored_bytes,
+    })
+}
+
+/// Ingest volume, error rates and storage growth per source system (operators only)
+#[hdk_extern]
+pub fn get_ingest_statistics(_: ()) -> ExternResult<IngestStatistics> {
+    require_operator()?;
+
+    let today = stats_day(sys_time()?);
+    let days = StatisticsWindow::Last30Days.days();
+    let mut overall_daily = vec![IngestTotals::default(); days];
+    let mut sources = Vec::new();
+
+    for source_system in ingest_sources()? {
+        let daily = (0..days)
+            .map(|offset| read_ingest_day(&source_system, today - offset as i64))
+            .collect::<ExternResult<Vec<_>>>()?;
+        for (total, day) in overall_daily.iter_mut().zip(&daily) {
+            total.add(day);
+        }
+        sources.push(SourceIngestStatistics {
+            source_system,
+            windows: ingest_windows(&daily),
+        });
+    }
+
+    Ok(IngestStatistics {
+        sources,
+        overall: ingest_windows(&overall_daily),
+        storage_growth: StorageGrowthEstimate::from_daily(&overall_daily),
+    })
+}
+
+/// Input for quality trends of one source system
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QualityTrendsInput {
+    pub source_system: String,
+    pub window: StatisticsWindow,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QualityFindingCount {
+    pub code: QualityFindingCode,
+    pub count: u64,
+}
+
+/// One day of quality findings for a source system
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QualityTrendDay {
+    /// Days since the Unix epoch (UTC)
+    pub day: i64,
+    pub ingests: u64,
+    pub accepted_with_warnings: u64,
+    pub resources_processed: u64,
+    pub findings: Vec<QualityFindingCount>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct QualityTrends {
+    pub source_system: String,
+    pub window: StatisticsWindow,
+    /// Oldest day first
+    pub daily: Vec<QualityTrendDay>,
+}
+
+/// Daily data quality findings for a source system (operators only)
+#[hdk_extern]
+pub fn get_quality_trends(input: QualityTrendsInput) -> ExternResult<QualityTrends> {
+    require_operator()?;
+
+    let today = stats_day(sys_time()?);
+    let mut daily = Vec::new();
+    for offset in (0..input.window.days() as i64).rev() {
+        let day = today - offset;
+        let totals = read_ingest_day(&input.source_system, day)?;
+        let mut findings = Vec::new();
+        for code in QualityFindingCode::ALL {
+            let count = read_counter(
+                quality_counter_anchor(&input.source_system, day, &format!("{:?}", code))?,
+                LinkTypes::IngestStatisticsCounter,
+            )?;
+            findings.push(QualityFindingCount {
+                code,
+                count: count.max(0) as u64,
+            });
+        }
+        let accepted_with_warnings = read_counter(
+            quality_counter_anchor(&input.source_system, day, ACCEPTED_WITH_WARNINGS_METRIC)?,
+            LinkTypes::IngestStatisticsCounter,
+        )?;
+        daily.push(QualityTrendDay {
+            day,
+            ingests: totals.ingests,
+            accepted_with_warnings: accepted_with_warnings.max(0) as u64,
+            resources_processed: totals.resources_processed,
+            findings,
+        });
+    }
+
+    Ok(QualityTrends {
+        source_system: input.source_system,
+        window: input.window,
+        daily,
+    })
+}
+
 // ============================================================================
 // Helper Functions
 // ============================================================================
@@ -1091,6 +2474,47 @@ fn build_codeable_concept(
     }
 }
 
+/// Convert a FHIR CodeableConcept as received into its mapped form
+fn json_codeable_concept(concept: &JsonValue) -> FhirCodeableConcept {
+    let coding = concept.get("coding")
+        .and_then(|c| c.as_array())
+        .map(|codings| {
+            codings.iter()
+                .filter_map(|c| {
+                    Some(FhirCoding {
+                        system: get_fhir_string(c, "system").unwrap_or_else(|| "unknown".to_string()),
+                        code: get_fhir_string(c, "code")?,
+                        display: get_fhir_string(c, "display"),
+                        version: get_fhir_string(c, "version"),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+
+    FhirCodeableConcept {
+        coding,
+        text: get_fhir_string(concept, "text"),
+    }
+}
+
+fn json_codeable_concepts(resource: &JsonValue, field: &str) -> Vec<FhirCodeableConcept> {
+    resource.get(field)
+        .and_then(|v| v.as_array())
+        .map(|concepts| concepts.iter().map(json_codeable_concept).collect())
+        .unwrap_or_default()
+}
+
+fn json_reference(reference: &JsonValue) -> Option<FhirReference> {
+    let mapped = FhirReference {
+        reference: get_fhir_string(reference, "reference"),
+        type_name: get_fhir_string(reference, "type"),
+        identifier: None,
+        display: get_fhir_string(reference, "display"),
+    };
+    (mapped.reference.is_some() || mapped.display.is_some()).then_some(mapped)
+}
+
 fn lookup_resource_anchor(source_key: &str) -> ExternResult<Option<FhirResourceAnchor>> {
     let anchor = anchor_hash(&format!("fhir_anchor:{}", source_key))?;
     let links = get_links(
@@ -1108,7 +2532,7 @@ fn lookup_resource_anchor(source_key: &str) -> ExternResult<Option<FhirResourceA
     Ok(None)
 }
 
-fn create_resource_anchor(source_key: &str, resource_type: &str, internal_hash: &ActionHash) -> Result<(), String> {
+fn create_resource_anchor(saga: &mut Saga, source_key: &str, resource_type: &str, internal_hash: &ActionHash) -> Result<ActionHash, String> {
     let now = sys_time().map_err(|e| e.to_string())?;
     let anchor_entry = FhirResourceAnchor {
         source_key: source_key.to_string(),
@@ -1118,20 +2542,76 @@ fn create_resource_anchor(source_key: &str, resource_type: &str, internal_hash:
         last_updated: Timestamp::from_micros(now.as_micros() as i64),
     };
 
-    let anchor_hash_result = create_entry(&EntryTypes::FhirResourceAnchor(anchor_entry))
-        .map_err(|e| e.to_string())?;
+    let anchor_action = saga.step("create_anchor", |undo| {
+        let hash = create_entry(&EntryTypes::FhirResourceAnchor(anchor_entry))
+            .map_err(|e| e.to_string())?;
+        undo.push(SagaCompensation::DeleteEntry(hash.clone()));
+        Ok::<_, String>(hash)
+    })?;
+
+    saga.step("link_anchor", |undo| {
+        let link_anchor = anchor_hash(&format!("fhir_anchor:{}", source_key))
+            .map_err(|e| e.to_string())?;
+        let link_hash = create_link(
+            link_anchor,
+            anchor_action.clone(),
+            LinkTypes::SourceKeyToAnchor,
+            LinkTag::new(""),
+        ).map_err(|e| e.to_string())?;
+        undo.push(SagaCompensation::DeleteLink(link_hash));
+        Ok::<_, String>(())
+    })?;
+    Ok(anchor_action)
+}
+
+/// Steps of a resource ingest saga
+const INGEST_SAGA_STEPS: [&str; 3] = ["create_mapping", "create_anchor", "link_anchor"];
+
+/// Observations also index their clinical identity
+const OBSERVATION_SAGA_STEPS: [&str; 4] = ["create_mapping", "create_anchor", "link_anchor", "link_clinical_identity"];
+
+/// A result another source already sent gets an anchor pointing at the
+/// existing mapping and joins its duplicate cluster
+const DUPLICATE_SAGA_STEPS: [&str; 3] = ["create_anchor", "link_anchor", "link_duplicate"];
 
-    let link_anchor = anchor_hash(&format!("fhir_anchor:{}", source_key))
+/// An evidence link is indexed from both of its mappings
+const EVIDENCE_SAGA_STEPS: [&str; 3] = ["create_evidence", "link_condition", "link_observation"];
+
+/// Run the writes for one resource as a saga
+///
+/// A resource that fails part way is rolled back so a later ingest can retry
+/// it, and the compensated saga log is kept on the source chain.
+fn ingest_saga<T>(resource_type: &str, steps: &[&str], run: impl FnOnce(&mut Saga) -> Result<T, String>) -> Result<T, String> {
+    let mut saga = Saga::begin(&format!("ingest:{}", resource_type), steps)
         .map_err(|e| e.to_string())?;
+    let result = run(&mut saga);
+    if saga.failed() {
+        let log = saga.finish(sys_time().map_err(|e| e.to_string())?);
+        create_entry(&EntryTypes::SagaLog(log)).map_err(|e| e.to_string())?;
+    }
+    result
+}
 
-    create_link(
-        link_anchor,
-        anchor_hash_result,
-        LinkTypes::SourceKeyToAnchor,
-        LinkTag::new(""),
-    ).map_err(|e| e.to_string())?;
+/// Create a mapping through the fhir_mapping zome as a saga step
+fn create_mapping_step<M: Serialize + std::fmt::Debug>(saga: &mut Saga, function: &str, mapping: &M, label: &str) -> Result<ActionHash, String> {
+    saga.step("create_mapping", |undo| {
+        let record: Record = resilient_call("fhir_mapping", function, mapping, CallClass::Bulk)
+            .map_err(|failure| format!("Failed to create {} mapping: {}", label, failure))?;
+        let hash = record.action_address().clone();
+        undo.push(SagaCompensation::DeleteEntry(hash.clone()));
+        Ok(hash)
+    })
+}
 
-    Ok(())
+/// Create an entry through the records zome as a saga step
+fn create_record_step(saga: &mut Saga, function: &str, input: &JsonValue, label: &str) -> Result<ActionHash, String> {
+    saga.step("create_mapping", |undo| {
+        let record: Record = resilient_call("records", function, input, CallClass::Bulk)
+            .map_err(|failure| format!("Failed to create {}: {}", label, failure))?;
+        let hash = record.action_address().clone();
+        undo.push(SagaCompensation::DeleteEntry(hash.clone()));
+        Ok(hash)
+    })
 }
 
 fn lookup_patient_by_fhir_reference(reference: &str, source_system: &str) -> ExternResult<Option<ActionHash>> {
@@ -1204,6 +2684,65 @@ fn extract_unit(resource: &JsonValue) -> Option<String> {
         .map(|s| s.to_string())
 }
 
+/// Reject a resource whose codings are malformed for their code system
+///
+/// Only systems `validate_code` knows are checked; local codes pass.
+fn check_resource_codes(resource: &JsonValue, fields: &[&str]) -> Result<(), String> {
+    for field in fields {
+        let codings = resource.get(*field).and_then(|c| c.get("coding")).and_then(|c| c.as_array());
+        for coding in codings.into_iter().flatten() {
+            let system = coding.get("system").and_then(|s| s.as_str()).and_then(ClinicalCodeSystem::from_system);
+            let code = coding.get("code").and_then(|c| c.as_str());
+            if let (Some(system), Some(code)) = (system, code) {
+                validate_code(system, code).into_result().map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The observation's valueQuantity with its unit as a UCUM code
+///
+/// Units stated in another code system are kept as given; anything else
+/// must normalize to UCUM or the observation is rejected.
+fn extract_quantity(resource: &JsonValue) -> Result<Option<FhirQuantity>, String> {
+    let Some(vq) = resource.get("valueQuantity") else {
+        return Ok(None);
+    };
+    let field = |name: &str| vq.get(name).and_then(|v| v.as_str()).map(|s| s.to_string());
+    let value = vq.get("value").and_then(|v| v.as_f64()).ok_or("valueQuantity has no numeric value")?;
+    let (unit, system, code) = (field("unit"), field("system"), field("code"));
+
+    let quantity = match system {
+        Some(system) if system != ucum::UCUM_SYSTEM => FhirQuantity {
+            value,
+            unit: unit.or_else(|| code.clone()).unwrap_or_default(),
+            system: Some(system),
+            code,
+            comparator: field("comparator"),
+        },
+        _ => match code.as_ref().or(unit.as_ref()) {
+            Some(stated) => {
+                let ucum_code = ucum::normalize_unit(stated)
+                    .ok_or_else(|| format!("'{}' is not a valid UCUM unit", stated))?;
+                FhirQuantity {
+                    value,
+                    unit: unit.unwrap_or_else(|| ucum_code.clone()),
+                    system: Some(ucum::UCUM_SYSTEM.to_string()),
+                    code: Some(ucum_code),
+                    comparator: field("comparator"),
+                }
+            }
+            // A bare number, e.g. a ratio or score
+            None => FhirQuantity { value, unit: String::new(), system: None, code: None, comparator: field("comparator") },
+        },
+    };
+    ucum::validate_quantity("valueQuantity", quantity.value, quantity.system.as_deref(), quantity.code.as_deref())
+        .into_result()
+        .map_err(|e| e.to_string())?;
+    Ok(Some(quantity))
+}
+
 fn extract_icd10(resource: &JsonValue) -> Option<String> {
     if let Some(code_field) = resource.get("code") {
         if let Some(codings) = code_field.get("coding").and_then(|c| c.as_array()) {
@@ -1273,8 +2812,36 @@ fn validate_condition_resource(resource: &JsonValue) -> bool {
     resource.get("code").is_some()
 }
 
+fn validate_procedure_resource(resource: &JsonValue) -> bool {
+    // Procedure must have status and a CPT or SNOMED code
+    resource.get("status").is_some() &&
+    resource.get("code").is_some_and(|code| {
+        find_coding_code(code, "cpt").is_some() || find_coding_code(code, "snomed").is_some()
+    })
+}
+
 fn validate_medication_resource(resource: &JsonValue) -> bool {
     // MedicationRequest must have medication reference or code
     resource.get("medicationCodeableConcept").is_some() ||
     resource.get("medicationReference").is_some()
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("ingest_bundle", "IngestBundleInput", "IngestReport"),
+        ("get_patient_ingest_reports", "ActionHash", "Vec<IngestSummary>"),
+        ("export_patient_fhir", "ExportPatientInput", "ExportResult"),
+        ("export_lab_order_fhir", "ExportLabOrderInput", "ExportResult"),
+        ("validate_fhir_resource", "JsonValue", "bool"),
+        ("get_observation_duplicates", "ActionHash", "ObservationDuplicateCluster"),
+        ("get_condition_evidence", "ActionHash", "ConditionEvidence"),
+        ("get_ingest_statistics", "()", "IngestStatistics"),
+        ("get_quality_trends", "QualityTrendsInput", "QualityTrends"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["network_statistics", "ingest_quality_findings", "lab_orders", "clinical_identity_dedup", "condition_evidence", "fhir_consent", "patient_timeline", "fhir_extensions", "upstream_retractions", "jurisdiction_residency", "discharge_follow_ups"],
+}
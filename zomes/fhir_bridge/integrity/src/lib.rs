@@ -5,7 +5,12 @@
 //! and Mycelix-Health's internal data structures.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{entry_size_violation, EntrySizeLimits, DEFAULT_ENTRY_SIZE_LIMITS};
 use serde_json::Value as JsonValue;
+use mycelix_health_shared::encryption::sha256_hash;
+use mycelix_health_shared::{DataCategory, PayloadSizeStats, SagaLog, SensitivityMatrix};
+use mycelix_health_shared::{OnsetBasis, OnsetCandidate};
+pub use mycelix_health_shared::parse_fhir_datetime;
 
 /// Input for ingesting a FHIR Bundle
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -14,6 +19,43 @@ pub struct IngestBundleInput {
     pub bundle: JsonValue,
     /// Source EHR system identifier (e.g., "epic-sandbox", "cerner-prod")
     pub source_system: String,
+    /// How far apart two sources' effective times may be for the same result
+    #[serde(default)]
+    pub dedup_tolerance: DedupTolerance,
+}
+
+/// Effective-time windows within which observations with the same patient,
+/// LOINC code and value are treated as one clinical result
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DedupTolerance {
+    /// Window applied to codes without an override
+    pub default_window_seconds: u32,
+    /// Per-LOINC overrides, e.g. a wider window for results that labs
+    /// stamp with the accession time rather than the collection time
+    pub loinc_windows: Vec<(String, u32)>,
+}
+
+/// Widest window accepted; anything longer would merge repeat tests
+pub const MAX_DEDUP_WINDOW_SECONDS: u32 = 86_400;
+
+impl Default for DedupTolerance {
+    fn default() -> Self {
+        Self {
+            default_window_seconds: 900,
+            loinc_windows: Vec::new(),
+        }
+    }
+}
+
+impl DedupTolerance {
+    pub fn window_for(&self, loinc_code: &str) -> u32 {
+        self.loinc_windows
+            .iter()
+            .find(|(code, _)| code == loinc_code)
+            .map(|(_, window)| *window)
+            .unwrap_or(self.default_window_seconds)
+            .min(MAX_DEDUP_WINDOW_SECONDS)
+    }
 }
 
 /// Report of what was ingested from a FHIR Bundle
@@ -64,10 +106,245 @@ pub struct IngestReport {
     pub care_plans_created: u32,
     /// CarePlans skipped
     pub care_plans_skipped: u32,
+    /// ServiceRequests created as lab orders
+    #[serde(default)]
+    pub service_requests_created: u32,
+    /// ServiceRequests skipped
+    #[serde(default)]
+    pub service_requests_skipped: u32,
+    /// Specimens created against lab orders
+    #[serde(default)]
+    pub specimens_created: u32,
+    /// Specimens skipped
+    #[serde(default)]
+    pub specimens_skipped: u32,
+    /// Observations already ingested from another source, linked into the
+    /// existing result's duplicate cluster instead of being stored again
+    #[serde(default)]
+    pub observations_deduplicated: u32,
+    /// Observation-to-condition evidence links created from bundle references
+    #[serde(default)]
+    pub evidence_links_created: u32,
+    /// External Consent resources recorded as consents awaiting the patient
+    #[serde(default)]
+    pub consents_proposed: u32,
+    /// Consent resources skipped (already ingested)
+    #[serde(default)]
+    pub consents_skipped: u32,
+    /// Previously ingested resources the source deleted or marked entered-in-error
+    #[serde(default)]
+    pub resources_retracted: u32,
+    /// Discharge CarePlans and Compositions recognized
+    #[serde(default)]
+    pub discharge_summaries: u32,
+    /// Follow-up tasks created from discharge summaries
+    #[serde(default)]
+    pub follow_up_tasks_created: u32,
     /// Resource types that were not recognized
     pub unknown_types: Vec<String>,
     /// Errors encountered during parsing
+    #[serde(with = "mycelix_health_shared::compressed")]
     pub parse_errors: Vec<String>,
+    /// Original vs at-rest size of the source JSON kept by created mappings
+    #[serde(default)]
+    pub payload_stats: PayloadSizeStats,
+    /// Data categories the created records fall into
+    #[serde(default)]
+    pub data_categories: Vec<DataCategory>,
+    /// Data quality problems found in the created records
+    #[serde(default)]
+    pub quality_findings: Vec<QualityFinding>,
+    /// Whether quality findings crossed the warning thresholds
+    #[serde(default)]
+    pub outcome: IngestOutcome,
+}
+
+impl IngestReport {
+    /// Internal records created across all resource types
+    pub fn records_created(&self) -> u32 {
+        self.conditions_created
+            + self.medications_created
+            + self.allergies_created
+            + self.immunizations_created
+            + self.observations_created
+            + self.procedures_created
+            + self.diagnostic_reports_created
+            + self.care_plans_created
+            + self.service_requests_created
+            + self.specimens_created
+    }
+}
+
+/// Kind of data quality problem found in an ingested resource
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum QualityFindingCode {
+    /// A quantity has neither a unit nor a unit code
+    MissingUnit,
+    /// A date/time field is present but not a valid FHIR date
+    UnparseableDate,
+    /// A required code was absent and stored as "unknown"
+    UnknownCodeDefaulted,
+    /// Status was absent and stored as "unknown"
+    MissingStatus,
+}
+
+impl QualityFindingCode {
+    pub const ALL: [QualityFindingCode; 4] = [
+        QualityFindingCode::MissingUnit,
+        QualityFindingCode::UnparseableDate,
+        QualityFindingCode::UnknownCodeDefaulted,
+        QualityFindingCode::MissingStatus,
+    ];
+}
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QualitySeverity {
+    Info,
+    Warning,
+    /// The stored record is not clinically usable as-is
+    Error,
+}
+
+/// One data quality problem in one resource
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct QualityFinding {
+    pub resource_type: String,
+    pub resource_id: Option<String>,
+    pub code: QualityFindingCode,
+    pub severity: QualitySeverity,
+    /// FHIR element the finding is about (e.g. "valueQuantity.unit")
+    pub element: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+pub enum IngestOutcome {
+    #[default]
+    Accepted,
+    AcceptedWithWarnings,
+}
+
+/// Limits past which an ingest is downgraded to accepted-with-warnings
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+pub struct QualityThresholds {
+    /// Error-severity findings tolerated
+    pub max_errors: u32,
+    /// Warning-severity findings per processed resource tolerated
+    pub max_warning_rate: f64,
+}
+
+pub const DEFAULT_QUALITY_THRESHOLDS: QualityThresholds = QualityThresholds {
+    max_errors: 0,
+    max_warning_rate: 0.25,
+};
+
+/// Date/time elements checked for parseability
+const QUALITY_DATE_ELEMENTS: [&str; 11] = [
+    "/effectiveDateTime",
+    "/effectivePeriod/start",
+    "/issued",
+    "/onsetDateTime",
+    "/recordedDate",
+    "/abatementDateTime",
+    "/authoredOn",
+    "/occurrenceDateTime",
+    "/performedDateTime",
+    "/period/start",
+    "/created",
+];
+
+/// Data quality findings for one resource, matching what the mappers default
+pub fn resource_quality_findings(resource: &JsonValue) -> Vec<QualityFinding> {
+    let resource_type = get_resource_type(resource).unwrap_or_default();
+    let resource_id = get_resource_id(resource);
+    let mut findings = Vec::new();
+    let mut finding = |code, severity, element: &str| {
+        findings.push(QualityFinding {
+            resource_type: resource_type.clone(),
+            resource_id: resource_id.clone(),
+            code,
+            severity,
+            element: element.to_string(),
+        });
+    };
+
+    let has_status_element = !matches!(resource_type.as_str(), "Condition" | "AllergyIntolerance");
+    if has_status_element && get_fhir_string(resource, "status").is_none() {
+        finding(QualityFindingCode::MissingStatus, QualitySeverity::Info, "status");
+    }
+
+    match resource_type.as_str() {
+        "Observation" => {
+            let first_code = resource.pointer("/code/coding/0/code").and_then(|c| c.as_str());
+            if first_code.is_none() {
+                finding(QualityFindingCode::UnknownCodeDefaulted, QualitySeverity::Warning, "code");
+            }
+        }
+        "Condition" => {
+            let icd10 = resource.get("code").and_then(|code| find_coding_code(code, "icd"));
+            if icd10.is_none() {
+                finding(QualityFindingCode::UnknownCodeDefaulted, QualitySeverity::Error, "code");
+            }
+        }
+        "MedicationRequest" | "MedicationStatement" => {
+            let rxnorm = resource
+                .get("medicationCodeableConcept")
+                .and_then(|concept| find_coding_code(concept, "rxnorm"));
+            if rxnorm.is_none() {
+                finding(
+                    QualityFindingCode::UnknownCodeDefaulted,
+                    QualitySeverity::Error,
+                    "medicationCodeableConcept",
+                );
+            }
+        }
+        _ => {}
+    }
+
+    let components = resource
+        .get("component")
+        .and_then(|c| c.as_array())
+        .map(|c| c.iter().collect::<Vec<_>>())
+        .unwrap_or_default();
+    for (element, holder) in std::iter::once(("valueQuantity.unit", resource))
+        .chain(components.into_iter().map(|c| ("component.valueQuantity.unit", c)))
+    {
+        if let Some(quantity) = holder.get("valueQuantity") {
+            if quantity.get("unit").is_none() && quantity.get("code").is_none() {
+                finding(QualityFindingCode::MissingUnit, QualitySeverity::Warning, element);
+            }
+        }
+    }
+
+    for pointer in QUALITY_DATE_ELEMENTS {
+        if let Some(value) = resource.pointer(pointer).and_then(|v| v.as_str()) {
+            if parse_fhir_datetime(value).is_none() {
+                let element = pointer.trim_start_matches('/').replace('/', ".");
+                finding(QualityFindingCode::UnparseableDate, QualitySeverity::Warning, &element);
+            }
+        }
+    }
+
+    findings
+}
+
+/// Accepted, or accepted with warnings when findings cross `thresholds`
+pub fn ingest_outcome(
+    findings: &[QualityFinding],
+    total_processed: u32,
+    thresholds: &QualityThresholds,
+) -> IngestOutcome {
+    let errors = findings.iter().filter(|f| f.severity == QualitySeverity::Error).count() as u32;
+    let warnings = findings.iter().filter(|f| f.severity == QualitySeverity::Warning).count();
+    let warning_rate = if total_processed == 0 {
+        0.0
+    } else {
+        warnings as f64 / total_processed as f64
+    };
+    if errors > thresholds.max_errors || warning_rate > thresholds.max_warning_rate {
+        IngestOutcome::AcceptedWithWarnings
+    } else {
+        IngestOutcome::Accepted
+    }
 }
 
 /// Input for exporting a patient's data as FHIR
@@ -75,10 +352,17 @@ pub struct IngestReport {
 pub struct ExportPatientInput {
     /// Patient hash to export
     pub patient_hash: ActionHash,
-    /// Which sections to include
+    /// Which sections to include ("Observation", "Condition", "MedicationRequest",
+    /// "Procedure", and "Consent" for the active consents)
     pub include_sections: Vec<String>,
     /// Format: "r4" (default), "us-core", "ips"
     pub format: Option<String>,
+    /// Include registered/preliminary/cancelled observations (excluded by default)
+    #[serde(default)]
+    pub include_non_final_observations: bool,
+    /// Include resources their source system retracted (excluded by default)
+    #[serde(default)]
+    pub include_retracted: bool,
 }
 
 /// Result of exporting patient data
@@ -92,6 +376,9 @@ pub struct ExportResult {
     pub format: String,
     /// Sections that were exported
     pub sections_exported: Vec<String>,
+    /// Jurisdiction tag of the exported patient
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
 }
 
 /// A deduplication anchor for tracking ingested resources
@@ -110,11 +397,52 @@ pub struct FhirResourceAnchor {
     pub last_updated: Timestamp,
 }
 
+/// How a result bears on a diagnosis
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum EvidenceType {
+    /// Supports the diagnosis
+    Diagnostic,
+    /// Tracks the course of an established condition
+    Monitoring,
+    /// Was used to exclude the diagnosis
+    RuleOut,
+}
+
+impl EvidenceType {
+    /// Tag stored on evidence links
+    pub fn tag(&self) -> &'static str {
+        match self {
+            EvidenceType::Diagnostic => "diagnostic",
+            EvidenceType::Monitoring => "monitoring",
+            EvidenceType::RuleOut => "rule-out",
+        }
+    }
+}
+
+/// An observation mapping recorded as evidence for a condition mapping
+///
+/// Holds no timestamp, so the same evidence from a re-ingested bundle hashes
+/// to the same entry.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EvidenceLink {
+    pub condition_mapping: ActionHash,
+    pub observation_mapping: ActionHash,
+    pub evidence_type: EvidenceType,
+    pub source_system: String,
+    /// Source reference the link came from, e.g. "Condition/c1 -> Observation/o1"
+    pub derived_from: String,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     IngestReport(IngestReport),
     FhirResourceAnchor(FhirResourceAnchor),
+    /// Kept for resource ingests that failed part way and were rolled back
+    #[entry_type(visibility = "private")]
+    SagaLog(SagaLog),
+    EvidenceLink(EvidenceLink),
 }
 
 #[hdk_link_types]
@@ -127,15 +455,39 @@ pub enum LinkTypes {
     ResourceTypeIndex,
     /// Deduplication anchor by source key
     SourceKeyToAnchor,
+    /// Counter shards behind the per-source ingest statistics
+    IngestStatisticsCounter,
+    /// Source systems that have ingest statistics
+    IngestStatisticsSources,
+    /// Clinical identity bucket to the observation mappings in it; the tag
+    /// holds the effective time in microseconds
+    ClinicalIdentityToObservation,
+    /// Canonical observation mapping to the anchors of its duplicates
+    ObservationDuplicates,
+    /// Condition mapping to its evidence links; the tag holds the evidence type
+    ConditionToEvidence,
+    /// Observation mapping to the evidence links it appears in
+    ObservationToEvidence,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = DEFAULT_ENTRY_SIZE_LIMITS;
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => match app_entry {
                 EntryTypes::IngestReport(r) => validate_ingest_report(&r),
                 EntryTypes::FhirResourceAnchor(a) => validate_resource_anchor(&a),
+                EntryTypes::EvidenceLink(link) => validate_evidence_link(&link),
+                EntryTypes::SagaLog(log) => Ok(match log.validate() {
+                    Ok(()) => ValidateCallbackResult::Valid,
+                    Err(e) => ValidateCallbackResult::Invalid(e),
+                }),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -171,6 +523,53 @@ fn validate_resource_anchor(anchor: &FhirResourceAnchor) -> ExternResult<Validat
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn validate_evidence_link(link: &EvidenceLink) -> ExternResult<ValidateCallbackResult> {
+    if link.condition_mapping == link.observation_mapping {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Evidence must link two different mappings".to_string(),
+        ));
+    }
+    if link.source_system.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Source system is required".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Clinical identity of an observation, independent of the source's FHIR ID
+///
+/// The value is normalized (numbers compared numerically, text case- and
+/// whitespace-insensitively) before hashing, so "5.90 %" and "5.9 %" match.
+pub fn clinical_identity_key(patient_hash: &ActionHash, loinc_code: &str, value: &str, unit: Option<&str>) -> String {
+    let value = match value.trim().trim_matches('"').parse::<f64>() {
+        Ok(number) => format!("{}", number),
+        Err(_) => value.split_whitespace().collect::<Vec<_>>().join(" ").to_lowercase(),
+    };
+    let unit = unit.unwrap_or_default().trim().to_lowercase();
+    let digest = sha256_hash(format!("{}|{}", value, unit).as_bytes());
+    let value_hash: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("clinical_obs:{}:{}:{}", patient_hash, loinc_code, value_hash)
+}
+
+/// Width of the effective-time buckets clinical identities are indexed by
+///
+/// Fixed rather than derived from the tolerance, so changing the tolerance
+/// never hides results indexed under an earlier setting.
+pub const CLINICAL_IDENTITY_BUCKET_SECONDS: i64 = 3_600;
+
+pub fn clinical_identity_bucket(effective_time: Timestamp) -> i64 {
+    effective_time.as_micros().div_euclid(CLINICAL_IDENTITY_BUCKET_SECONDS * 1_000_000)
+}
+
+/// Buckets that can hold a result within `window_seconds` of `effective_time`
+pub fn clinical_identity_buckets(effective_time: Timestamp, window_seconds: u32) -> std::ops::RangeInclusive<i64> {
+    let window_micros = window_seconds as i64 * 1_000_000;
+    let micros = effective_time.as_micros();
+    clinical_identity_bucket(Timestamp::from_micros(micros - window_micros))
+        ..=clinical_identity_bucket(Timestamp::from_micros(micros + window_micros))
+}
+
 /// Helper to extract a string field from FHIR JSON
 pub fn get_fhir_string(resource: &JsonValue, field: &str) -> Option<String> {
     resource.get(field).and_then(|v| v.as_str()).map(|s| s.to_string())
@@ -203,6 +602,577 @@ pub fn get_patient_reference(resource: &JsonValue) -> Option<String> {
     None
 }
 
+/// ID of the ServiceRequest a Specimen was collected for
+pub fn get_service_request_id(specimen: &JsonValue) -> Option<String> {
+    specimen
+        .get("request")?
+        .as_array()?
+        .iter()
+        .filter_map(|request| request.get("reference").and_then(|r| r.as_str()))
+        .find_map(|reference| reference.strip_prefix("ServiceRequest/"))
+        .map(|id| id.to_string())
+}
+
+/// A condition-to-result reference found in a bundle
+#[derive(Clone, Debug, PartialEq)]
+pub struct EvidenceReference {
+    pub condition_id: String,
+    /// "Observation" or "DiagnosticReport"
+    pub result_type: String,
+    pub result_id: String,
+    pub evidence_type: EvidenceType,
+}
+
+impl EvidenceReference {
+    pub fn derived_from(&self) -> String {
+        format!("Condition/{} -> {}/{}", self.condition_id, self.result_type, self.result_id)
+    }
+}
+
+/// Split a relative reference such as "Observation/123" into type and ID
+fn split_reference(reference: &JsonValue) -> Option<(String, String)> {
+    let (resource_type, id) = reference.get("reference")?.as_str()?.split_once('/')?;
+    (!id.is_empty() && !id.contains('/')).then(|| (resource_type.to_string(), id.to_string()))
+}
+
+fn references_in<'a>(resource: &'a JsonValue, field: &str) -> impl Iterator<Item = &'a JsonValue> {
+    resource.get(field).and_then(|v| v.as_array()).into_iter().flatten()
+}
+
+/// Evidence between the Conditions and results of a bundle
+///
+/// A Condition's `evidence.detail` references are diagnostic evidence, or
+/// rule-out evidence when the condition is refuted; a referenced
+/// DiagnosticReport also contributes its `result` observations. An
+/// Observation whose `focus` is a Condition is monitoring evidence.
+pub fn bundle_evidence_references(resources: &[&JsonValue]) -> Vec<EvidenceReference> {
+    let report_results = |report_id: &str| -> Vec<String> {
+        resources
+            .iter()
+            .find(|r| {
+                get_resource_type(r).as_deref() == Some("DiagnosticReport")
+                    && get_resource_id(r).as_deref() == Some(report_id)
+            })
+            .map(|report| {
+                references_in(report, "result")
+                    .filter_map(split_reference)
+                    .filter(|(resource_type, _)| resource_type == "Observation")
+                    .map(|(_, id)| id)
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let mut found: Vec<EvidenceReference> = Vec::new();
+    let mut push = |reference: EvidenceReference| {
+        if !found.iter().any(|f| f.condition_id == reference.condition_id
+            && f.result_type == reference.result_type
+            && f.result_id == reference.result_id)
+        {
+            found.push(reference);
+        }
+    };
+    for resource in resources {
+        let (Some(resource_type), Some(id)) = (get_resource_type(resource), get_resource_id(resource)) else {
+            continue;
+        };
+        match resource_type.as_str() {
+            "Condition" => {
+                let refuted = resource
+                    .pointer("/verificationStatus/coding/0/code")
+                    .and_then(|c| c.as_str())
+                    == Some("refuted");
+                let evidence_type = if refuted { EvidenceType::RuleOut } else { EvidenceType::Diagnostic };
+                let details = references_in(resource, "evidence")
+                    .flat_map(|evidence| references_in(evidence, "detail"))
+                    .filter_map(split_reference);
+                for (result_type, result_id) in details {
+                    let results = match result_type.as_str() {
+                        "Observation" => Vec::new(),
+                        "DiagnosticReport" => report_results(&result_id),
+                        _ => continue,
+                    };
+                    push(EvidenceReference {
+                        condition_id: id.clone(),
+                        result_type,
+                        result_id,
+                        evidence_type,
+                    });
+                    for observation_id in results {
+                        push(EvidenceReference {
+                            condition_id: id.clone(),
+                            result_type: "Observation".to_string(),
+                            result_id: observation_id,
+                            evidence_type,
+                        });
+                    }
+                }
+            }
+            "Observation" => {
+                for (focus_type, condition_id) in references_in(resource, "focus").filter_map(split_reference) {
+                    if focus_type == "Condition" {
+                        push(EvidenceReference {
+                            condition_id,
+                            result_type: "Observation".to_string(),
+                            result_id: id.clone(),
+                            evidence_type: EvidenceType::Monitoring,
+                        });
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    found
+}
+
+/// Dated resources in a bundle that bound when a Condition began: the
+/// Encounter it was recorded in and the results cited for it
+///
+/// Rule-out evidence says nothing about onset and is skipped, as are
+/// references to resources outside the bundle.
+pub fn condition_onset_candidates(condition: &JsonValue, resources: &[&JsonValue]) -> Vec<OnsetCandidate> {
+    let Some(condition_id) = get_resource_id(condition) else {
+        return Vec::new();
+    };
+    let find = |resource_type: &str, id: &str| {
+        resources.iter().find(|r| {
+            get_resource_type(r).as_deref() == Some(resource_type) && get_resource_id(r).as_deref() == Some(id)
+        })
+    };
+
+    let mut candidates = Vec::new();
+    if let Some((resource_type, id)) = condition.get("encounter").and_then(split_reference) {
+        let start = find(&resource_type, &id)
+            .filter(|_| resource_type == "Encounter")
+            .and_then(|encounter| get_fhir_time(encounter, &["/period/start", "/actualPeriod/start"]));
+        if let Some(at) = start {
+            candidates.push(OnsetCandidate {
+                at,
+                basis: OnsetBasis::Encounter,
+                derived_from: format!("Encounter/{}", id),
+            });
+        }
+    }
+    for reference in bundle_evidence_references(resources) {
+        if reference.condition_id != condition_id {
+            continue;
+        }
+        let basis = match reference.evidence_type {
+            EvidenceType::Diagnostic => OnsetBasis::DiagnosticResult,
+            EvidenceType::Monitoring => OnsetBasis::MonitoringResult,
+            EvidenceType::RuleOut => continue,
+        };
+        let effective = find(&reference.result_type, &reference.result_id).and_then(|result| {
+            get_fhir_time(result, &["/effectiveDateTime", "/effectivePeriod/start", "/effectiveInstant", "/issued"])
+        });
+        if let Some(at) = effective {
+            candidates.push(OnsetCandidate {
+                at,
+                basis,
+                derived_from: format!("{}/{}", reference.result_type, reference.result_id),
+            });
+        }
+    }
+    candidates
+}
+
+/// Find the code of the first coding in a CodeableConcept whose system contains `system_fragment`
+pub fn find_coding_code(concept: &JsonValue, system_fragment: &str) -> Option<String> {
+    concept
+        .get("coding")?
+        .as_array()?
+        .iter()
+        .find(|coding| {
+            coding
+                .get("system")
+                .and_then(|s| s.as_str())
+                .is_some_and(|system| system.to_lowercase().contains(system_fragment))
+        })
+        .and_then(|coding| coding.get("code"))
+        .and_then(|c| c.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Whether any `category` CodeableConcept carries one of `codes` (case-insensitive)
+fn has_category_code(resource: &JsonValue, codes: &[&str]) -> bool {
+    let Some(categories) = resource.get("category").and_then(|c| c.as_array()) else {
+        return false;
+    };
+    categories
+        .iter()
+        .filter_map(|concept| concept.get("coding").and_then(|c| c.as_array()))
+        .flatten()
+        .filter_map(|coding| coding.get("code").and_then(|c| c.as_str()))
+        .any(|code| codes.iter().any(|c| c.eq_ignore_ascii_case(code)))
+}
+
+/// Data categories a FHIR resource adds to the patient's record
+///
+/// Equivalent to [`classify_resource`] for a patient of unknown age.
+pub fn resource_data_categories(resource: &JsonValue) -> Vec<DataCategory> {
+    classify_resource(resource, &ClassificationContext::default())
+}
+
+/// Data categories of a FHIR resource for a patient in a given context
+///
+/// The resource type gives the base category. Sensitive categories come from
+/// the codes the resource carries anywhere a clinician might record them
+/// (`code`, values, medications, vaccines and reasons), so a generic
+/// Observation holding an HIV test is still recognised as sexual health data.
+pub fn classify_resource(resource: &JsonValue, context: &ClassificationContext) -> Vec<DataCategory> {
+    let resource_type = get_resource_type(resource).unwrap_or_default();
+    let mut categories = match resource_type.as_str() {
+        "Patient" => vec![DataCategory::Demographics],
+        "Condition" => vec![DataCategory::Diagnoses],
+        "MedicationRequest" | "MedicationStatement" => vec![DataCategory::Medications],
+        "AllergyIntolerance" => vec![DataCategory::Allergies],
+        "Immunization" => vec![DataCategory::Immunizations],
+        "Procedure" => vec![DataCategory::Procedures],
+        "ImagingStudy" => vec![DataCategory::ImagingStudies],
+        "MolecularSequence" => vec![DataCategory::GeneticData],
+        "Observation" if has_category_code(resource, &["vital-signs"]) => vec![DataCategory::VitalSigns],
+        "Observation" => vec![DataCategory::LabResults],
+        "DiagnosticReport" if has_category_code(resource, &["RAD", "imaging"]) => vec![DataCategory::ImagingStudies],
+        "DiagnosticReport" => vec![DataCategory::LabResults],
+        "ServiceRequest" | "Specimen" => vec![DataCategory::LabResults],
+        _ => Vec::new(),
+    };
+
+    if has_category_code(resource, &["GE", "genetics", "genomics"]) {
+        categories.push(DataCategory::GeneticData);
+    }
+    for (system, code) in resource_codings(resource) {
+        if let Some(category) = classify_code(system, &code, context) {
+            if !categories.contains(&category) {
+                categories.push(category);
+            }
+        }
+    }
+    categories
+}
+
+/// Sensitive categories of a resource: those the sensitivity matrix marks
+/// for field-level encryption, which also need specific consent
+pub fn sensitive_categories(categories: &[DataCategory], matrix: &SensitivityMatrix) -> Vec<DataCategory> {
+    categories
+        .iter()
+        .filter(|category| matrix.requires_encryption(category))
+        .cloned()
+        .collect()
+}
+
+/// Age at which adolescent confidential-care rules stop applying
+pub const ADULT_AGE_YEARS: u32 = 18;
+
+/// What is known about the patient when classifying a resource
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClassificationContext {
+    /// Age when the resource's clinical event happened, if a birth date is known
+    pub patient_age_years: Option<u32>,
+    /// Category handling in force when the resource is ingested
+    pub sensitivity: SensitivityMatrix,
+}
+
+impl ClassificationContext {
+    pub fn at(birth_date: Option<Timestamp>, event_time: Timestamp) -> Self {
+        Self {
+            patient_age_years: birth_date.map(|birth| age_in_years(birth, event_time)),
+            sensitivity: SensitivityMatrix::default(),
+        }
+    }
+
+    /// Unknown ages are treated as minors so confidential care is never missed
+    pub fn is_minor(&self) -> bool {
+        self.patient_age_years.is_none_or(|age| age < ADULT_AGE_YEARS)
+    }
+}
+
+/// Whole years between a birth date and a later time
+pub fn age_in_years(birth_date: Timestamp, at: Timestamp) -> u32 {
+    const MICROS_PER_YEAR: i64 = 31_556_952_000_000; // 365.2425 days
+    (at.as_micros() - birth_date.as_micros()).max(0).checked_div(MICROS_PER_YEAR).unwrap_or(0) as u32
+}
+
+/// Code systems the classifier has rules for
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CodeSystem {
+    Icd10,
+    Snomed,
+    Loinc,
+    RxNorm,
+}
+
+impl CodeSystem {
+    pub fn from_uri(system: &str) -> Option<Self> {
+        let system = system.to_lowercase();
+        if system.contains("icd-10") {
+            Some(CodeSystem::Icd10)
+        } else if system.contains("snomed") {
+            Some(CodeSystem::Snomed)
+        } else if system.contains("loinc") {
+            Some(CodeSystem::Loinc)
+        } else if system.contains("rxnorm") {
+            Some(CodeSystem::RxNorm)
+        } else {
+            None
+        }
+    }
+}
+
+/// How a rule matches codes
+#[derive(Clone, Copy, Debug)]
+pub enum CodeMatch {
+    Exact(&'static [&'static str]),
+    /// Codes starting with one of these prefixes
+    Prefix(&'static [&'static str]),
+    /// ICD-10 categories (letter and two digits) within an inclusive range
+    Icd10Range(&'static str, &'static str),
+}
+
+impl CodeMatch {
+    fn matches(&self, code: &str) -> bool {
+        let code = code.trim().to_uppercase();
+        match self {
+            CodeMatch::Exact(codes) => codes.iter().any(|c| c.eq_ignore_ascii_case(&code)),
+            CodeMatch::Prefix(prefixes) => prefixes.iter().any(|p| code.starts_with(&p.to_uppercase())),
+            CodeMatch::Icd10Range(first, last) => {
+                code.get(..3).is_some_and(|category| category >= *first && category <= *last)
+            }
+        }
+    }
+}
+
+/// A code-driven route into a sensitive data category
+#[derive(Clone, Debug)]
+pub struct SensitiveCodeRule {
+    pub system: CodeSystem,
+    pub codes: CodeMatch,
+    pub category: DataCategory,
+    /// Only applies while the patient is a minor (adolescent confidential care)
+    pub minors_only: bool,
+}
+
+const fn rule(system: CodeSystem, codes: CodeMatch, category: DataCategory) -> SensitiveCodeRule {
+    SensitiveCodeRule { system, codes, category, minors_only: false }
+}
+
+const fn minor_rule(system: CodeSystem, codes: CodeMatch, category: DataCategory) -> SensitiveCodeRule {
+    SensitiveCodeRule { system, codes, category, minors_only: true }
+}
+
+/// Sensitive code rules, first match wins
+///
+/// RxNorm rules list ingredient concepts; product-level codes are not expanded.
+pub const SENSITIVE_CODE_RULES: &[SensitiveCodeRule] = &[
+    // ICD-10
+    rule(CodeSystem::Icd10, CodeMatch::Icd10Range("F10", "F19"), DataCategory::SubstanceAbuse),
+    rule(CodeSystem::Icd10, CodeMatch::Icd10Range("F01", "F99"), DataCategory::MentalHealth),
+    rule(CodeSystem::Icd10, CodeMatch::Prefix(&["R45.851"]), DataCategory::MentalHealth),
+    rule(CodeSystem::Icd10, CodeMatch::Icd10Range("A50", "A64"), DataCategory::SexualHealth),
+    rule(CodeSystem::Icd10, CodeMatch::Icd10Range("B20", "B24"), DataCategory::SexualHealth),
+    rule(CodeSystem::Icd10, CodeMatch::Prefix(&["Z21"]), DataCategory::SexualHealth),
+    rule(CodeSystem::Icd10, CodeMatch::Icd10Range("O00", "O08"), DataCategory::SexualHealth),
+    minor_rule(CodeSystem::Icd10, CodeMatch::Prefix(&["Z30", "Z32"]), DataCategory::SexualHealth),
+    // LOINC
+    rule(
+        CodeSystem::Loinc,
+        // HIV antibody/antigen/RNA, chlamydia, gonorrhoea and syphilis tests
+        CodeMatch::Exact(&["75622-1", "7917-8", "68961-2", "56888-1", "25835-0", "20447-9", "43304-5", "43305-2", "20507-0"]),
+        DataCategory::SexualHealth,
+    ),
+    // PHQ-9 and GAD-7 totals
+    rule(CodeSystem::Loinc, CodeMatch::Exact(&["44261-6", "70274-6"]), DataCategory::MentalHealth),
+    // AUDIT-C total
+    rule(CodeSystem::Loinc, CodeMatch::Exact(&["75626-2"]), DataCategory::SubstanceAbuse),
+    // Pregnancy test
+    minor_rule(CodeSystem::Loinc, CodeMatch::Exact(&["2106-3"]), DataCategory::SexualHealth),
+    // SNOMED CT
+    rule(CodeSystem::Snomed, CodeMatch::Exact(&["66590003", "7200002", "191816009"]), DataCategory::SubstanceAbuse),
+    rule(CodeSystem::Snomed, CodeMatch::Exact(&["35489007", "13746004", "58214004", "197480006"]), DataCategory::MentalHealth),
+    rule(CodeSystem::Snomed, CodeMatch::Exact(&["86406008", "165816005", "8098009"]), DataCategory::SexualHealth),
+    minor_rule(CodeSystem::Snomed, CodeMatch::Exact(&["77386006"]), DataCategory::SexualHealth),
+    // RxNorm: opioid and alcohol use disorder treatment, HIV, contraception
+    rule(CodeSystem::RxNorm, CodeMatch::Exact(&["1819", "6813", "7243", "3554", "82819"]), DataCategory::SubstanceAbuse),
+    rule(CodeSystem::RxNorm, CodeMatch::Exact(&["276237"]), DataCategory::SexualHealth),
+    minor_rule(CodeSystem::RxNorm, CodeMatch::Exact(&["6373", "6691", "4124"]), DataCategory::SexualHealth),
+];
+
+/// The sensitive category a single code falls into, if any
+pub fn classify_code(system: CodeSystem, code: &str, context: &ClassificationContext) -> Option<DataCategory> {
+    SENSITIVE_CODE_RULES
+        .iter()
+        .filter(|rule| rule.system == system && (!rule.minors_only || context.is_minor()))
+        .find(|rule| rule.codes.matches(code))
+        .map(|rule| rule.category.clone())
+}
+
+/// Every coding of a known system in the fields that carry clinical codes
+fn resource_codings(resource: &JsonValue) -> Vec<(CodeSystem, String)> {
+    let mut concepts: Vec<&JsonValue> = ["code", "valueCodeableConcept", "medicationCodeableConcept", "vaccineCode"]
+        .iter()
+        .filter_map(|field| resource.get(*field))
+        .collect();
+    if let Some(reasons) = resource.get("reasonCode").and_then(|r| r.as_array()) {
+        concepts.extend(reasons);
+    }
+    concepts
+        .into_iter()
+        .filter_map(|concept| concept.get("coding").and_then(|c| c.as_array()))
+        .flatten()
+        .filter_map(|coding| {
+            let system = CodeSystem::from_uri(coding.get("system")?.as_str()?)?;
+            Some((system, coding.get("code")?.as_str()?.to_string()))
+        })
+        .collect()
+}
+
+/// First parseable timestamp among the given JSON pointers (e.g. `/effectivePeriod/start`)
+pub fn get_fhir_time(resource: &JsonValue, pointers: &[&str]) -> Option<Timestamp> {
+    pointers
+        .iter()
+        .filter_map(|pointer| resource.pointer(pointer).and_then(|v| v.as_str()))
+        .find_map(parse_fhir_datetime)
+}
+
+/// What a discharge follow-up asks for (mirrors records_integrity::FollowUpTaskKind)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FollowUpTaskKind {
+    ScheduleAppointment,
+    ReconcileMedications,
+    RepeatLab,
+    Other,
+}
+
+/// A follow-up found in a discharge summary
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct DischargeFollowUp {
+    pub kind: FollowUpTaskKind,
+    pub description: String,
+    pub due_at: Option<Timestamp>,
+}
+
+/// SNOMED CT "Discharge care plan"
+const DISCHARGE_CARE_PLAN_CODE: &str = "736372004";
+/// LOINC "Discharge summary"
+const DISCHARGE_SUMMARY_CODE: &str = "18842-5";
+/// LOINC "Hospital discharge medications"
+const DISCHARGE_MEDICATIONS_SECTION: &str = "10183-2";
+
+/// Whether a CarePlan or Composition is a discharge summary
+pub fn is_discharge_resource(resource: &JsonValue) -> bool {
+    match get_resource_type(resource).as_deref() {
+        Some("CarePlan") => has_category_code(resource, &[DISCHARGE_CARE_PLAN_CODE, "discharge"]),
+        Some("Composition") => resource
+            .get("type")
+            .and_then(|concept| find_coding_code(concept, "loinc"))
+            .as_deref()
+            == Some(DISCHARGE_SUMMARY_CODE),
+        _ => false,
+    }
+}
+
+fn follow_up_kind(resource_type: &str) -> FollowUpTaskKind {
+    match resource_type {
+        "Appointment" => FollowUpTaskKind::ScheduleAppointment,
+        "MedicationRequest" | "MedicationStatement" => FollowUpTaskKind::ReconcileMedications,
+        "ServiceRequest" => FollowUpTaskKind::RepeatLab,
+        _ => FollowUpTaskKind::Other,
+    }
+}
+
+fn concept_text(concept: &JsonValue) -> Option<String> {
+    concept
+        .get("text")
+        .or_else(|| concept.pointer("/coding/0/display"))
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string())
+}
+
+/// Follow-ups listed in a discharge summary; empty for anything else
+///
+/// CarePlan activities become tasks by their `detail.kind` or referenced
+/// resource type. Composition sections become tasks from their entry
+/// references, and a discharge medications section asks for reconciliation.
+pub fn discharge_follow_ups(resource: &JsonValue) -> Vec<DischargeFollowUp> {
+    if !is_discharge_resource(resource) {
+        return Vec::new();
+    }
+    let mut follow_ups = Vec::new();
+    match get_resource_type(resource).as_deref() {
+        Some("CarePlan") => {
+            for activity in references_in(resource, "activity") {
+                let (resource_type, description, due_at) = match activity.get("detail") {
+                    Some(detail) => (
+                        get_fhir_string(detail, "kind").unwrap_or_default(),
+                        get_fhir_string(detail, "description")
+                            .or_else(|| detail.get("code").and_then(concept_text)),
+                        get_fhir_time(detail, &["/scheduledPeriod/start", "/scheduledString"]),
+                    ),
+                    None => {
+                        let reference = activity.get("reference");
+                        (
+                            reference.and_then(split_reference).map(|(t, _)| t).unwrap_or_default(),
+                            reference.and_then(|r| get_fhir_string(r, "display")),
+                            None,
+                        )
+                    }
+                };
+                if let Some(description) = description {
+                    follow_ups.push(DischargeFollowUp { kind: follow_up_kind(&resource_type), description, due_at });
+                }
+            }
+        }
+        Some("Composition") => {
+            for section in references_in(resource, "section") {
+                let title = get_fhir_string(section, "title");
+                let code = section.get("code").and_then(|concept| find_coding_code(concept, "loinc"));
+                if code.as_deref() == Some(DISCHARGE_MEDICATIONS_SECTION) {
+                    follow_ups.push(DischargeFollowUp {
+                        kind: FollowUpTaskKind::ReconcileMedications,
+                        description: title.clone().unwrap_or_else(|| "Reconcile discharge medications".to_string()),
+                        due_at: None,
+                    });
+                    continue;
+                }
+                for entry in references_in(section, "entry") {
+                    let Some((resource_type, _)) = split_reference(entry) else {
+                        continue;
+                    };
+                    let Some(description) = get_fhir_string(entry, "display").or_else(|| title.clone()) else {
+                        continue;
+                    };
+                    follow_ups.push(DischargeFollowUp { kind: follow_up_kind(&resource_type), description, due_at: None });
+                }
+            }
+        }
+        _ => {}
+    }
+    follow_ups
+}
+
+/// When a resource's clinical event happened, across the resource types we ingest
+pub fn resource_event_time(resource: &JsonValue) -> Option<Timestamp> {
+    get_fhir_time(
+        resource,
+        &[
+            "/effectiveDateTime",
+            "/effectivePeriod/start",
+            "/effectiveInstant",
+            "/onsetDateTime",
+            "/onsetPeriod/start",
+            "/occurrenceDateTime",
+            "/performedDateTime",
+            "/performedPeriod/start",
+            "/authoredOn",
+            "/period/start",
+            "/recordedDate",
+            "/created",
+            "/issued",
+        ],
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -226,4 +1196,357 @@ mod tests {
         });
         assert_eq!(get_patient_reference(&obs), Some("Patient/123".to_string()));
     }
+
+    #[test]
+    fn test_get_service_request_id() {
+        let specimen: JsonValue = serde_json::json!({
+            "resourceType": "Specimen",
+            "request": [
+                { "reference": "Encounter/9" },
+                { "reference": "ServiceRequest/lab-42" }
+            ]
+        });
+        assert_eq!(get_service_request_id(&specimen), Some("lab-42".to_string()));
+        assert_eq!(get_service_request_id(&serde_json::json!({ "resourceType": "Specimen" })), None);
+    }
+
+    #[test]
+    fn test_clinical_identity_key() {
+        let patient = ActionHash::from_raw_36(vec![7; 36]);
+        let hospital = clinical_identity_key(&patient, "4548-4", "5.90", Some("%"));
+        assert_eq!(hospital, clinical_identity_key(&patient, "4548-4", "5.9", Some(" % ")));
+        assert_ne!(hospital, clinical_identity_key(&patient, "4548-4", "6.0", Some("%")));
+        assert_ne!(hospital, clinical_identity_key(&patient, "2345-7", "5.9", Some("%")));
+        assert_eq!(
+            clinical_identity_key(&patient, "5778-6", "Yellow  Clear", None),
+            clinical_identity_key(&patient, "5778-6", "yellow clear", None),
+        );
+
+        let tolerance = DedupTolerance {
+            default_window_seconds: 600,
+            loinc_windows: vec![("4548-4".to_string(), 7_200), ("2345-7".to_string(), 999_999)],
+        };
+        assert_eq!(tolerance.window_for("4548-4"), 7_200);
+        assert_eq!(tolerance.window_for("2345-7"), MAX_DEDUP_WINDOW_SECONDS);
+        assert_eq!(tolerance.window_for("718-7"), 600);
+
+        // 01:55 UTC with a 10 minute window spans the 01:00 and 02:00 buckets
+        let at = Timestamp::from_micros(6_900_000_000);
+        assert_eq!(clinical_identity_bucket(at), 1);
+        assert_eq!(clinical_identity_buckets(at, 600), 1..=2);
+        assert_eq!(clinical_identity_buckets(at, 60), 1..=1);
+        assert_eq!(clinical_identity_bucket(Timestamp::from_micros(-1)), -1);
+    }
+
+    #[test]
+    fn test_find_coding_code() {
+        let code: JsonValue = serde_json::json!({
+            "coding": [
+                { "system": "http://snomed.info/sct", "code": "80146002" },
+                { "system": "http://www.ama-assn.org/go/cpt", "code": "44970" }
+            ]
+        });
+        assert_eq!(find_coding_code(&code, "cpt"), Some("44970".to_string()));
+        assert_eq!(find_coding_code(&code, "snomed"), Some("80146002".to_string()));
+        assert_eq!(find_coding_code(&code, "loinc"), None);
+    }
+
+    #[test]
+    fn test_resource_data_categories() {
+        let depression: JsonValue = serde_json::json!({
+            "resourceType": "Condition",
+            "code": { "coding": [{ "system": "http://hl7.org/fhir/sid/icd-10-cm", "code": "F32.1" }] }
+        });
+        assert_eq!(
+            resource_data_categories(&depression),
+            vec![DataCategory::Diagnoses, DataCategory::MentalHealth]
+        );
+
+        let alcohol: JsonValue = serde_json::json!({
+            "resourceType": "Condition",
+            "code": { "coding": [{ "system": "http://hl7.org/fhir/sid/icd-10-cm", "code": "F10.20" }] }
+        });
+        assert!(resource_data_categories(&alcohol).contains(&DataCategory::SubstanceAbuse));
+
+        let brca: JsonValue = serde_json::json!({
+            "resourceType": "DiagnosticReport",
+            "category": [{ "coding": [{ "system": "http://terminology.hl7.org/CodeSystem/v2-0074", "code": "GE" }] }]
+        });
+        assert_eq!(
+            resource_data_categories(&brca),
+            vec![DataCategory::LabResults, DataCategory::GeneticData]
+        );
+
+        let bp: JsonValue = serde_json::json!({
+            "resourceType": "Observation",
+            "category": [{ "coding": [{ "code": "vital-signs" }] }]
+        });
+        assert_eq!(resource_data_categories(&bp), vec![DataCategory::VitalSigns]);
+    }
+
+    #[test]
+    fn test_classify_resource_sensitive_codes() {
+        let adult = ClassificationContext { patient_age_years: Some(34), ..Default::default() };
+        let teen = ClassificationContext { patient_age_years: Some(15), ..Default::default() };
+
+        let hiv_test: JsonValue = serde_json::json!({
+            "resourceType": "Observation",
+            "code": { "coding": [{ "system": "http://loinc.org", "code": "75622-1" }] }
+        });
+        assert_eq!(
+            classify_resource(&hiv_test, &adult),
+            vec![DataCategory::LabResults, DataCategory::SexualHealth]
+        );
+
+        let buprenorphine: JsonValue = serde_json::json!({
+            "resourceType": "MedicationRequest",
+            "medicationCodeableConcept": {
+                "coding": [{ "system": "http://www.nlm.nih.gov/research/umls/rxnorm", "code": "1819" }]
+            }
+        });
+        assert!(classify_resource(&buprenorphine, &adult).contains(&DataCategory::SubstanceAbuse));
+
+        let counselling: JsonValue = serde_json::json!({
+            "resourceType": "Procedure",
+            "reasonCode": [{ "coding": [{ "system": "http://hl7.org/fhir/sid/icd-10-cm", "code": "F11.20" }] }]
+        });
+        assert_eq!(
+            classify_resource(&counselling, &adult),
+            vec![DataCategory::Procedures, DataCategory::SubstanceAbuse]
+        );
+
+        let contraception: JsonValue = serde_json::json!({
+            "resourceType": "MedicationStatement",
+            "medicationCodeableConcept": {
+                "coding": [{ "system": "http://www.nlm.nih.gov/research/umls/rxnorm", "code": "6373" }]
+            }
+        });
+        assert_eq!(classify_resource(&contraception, &adult), vec![DataCategory::Medications]);
+        assert!(classify_resource(&contraception, &teen).contains(&DataCategory::SexualHealth));
+        assert!(resource_data_categories(&contraception).contains(&DataCategory::SexualHealth));
+
+        assert_eq!(
+            sensitive_categories(&classify_resource(&hiv_test, &adult), &adult.sensitivity),
+            vec![DataCategory::SexualHealth]
+        );
+    }
+
+    #[test]
+    fn test_age_in_years() {
+        let birth = parse_fhir_datetime("2008-06-15").unwrap();
+        assert_eq!(age_in_years(birth, parse_fhir_datetime("2026-06-14").unwrap()), 17);
+        assert_eq!(age_in_years(birth, parse_fhir_datetime("2026-06-16").unwrap()), 18);
+        assert_eq!(age_in_years(birth, parse_fhir_datetime("2000-01-01").unwrap()), 0);
+        assert!(!ClassificationContext::at(Some(birth), parse_fhir_datetime("2027-01-01").unwrap()).is_minor());
+        assert!(ClassificationContext::default().is_minor());
+    }
+
+    #[test]
+    fn test_parse_fhir_datetime() {
+        assert_eq!(parse_fhir_datetime("1970-01-01"), Some(Timestamp::from_micros(0)));
+        assert_eq!(
+            parse_fhir_datetime("2024-03-01"),
+            Some(Timestamp::from_micros(1_709_251_200_000_000))
+        );
+        assert_eq!(parse_fhir_datetime("2024-03"), parse_fhir_datetime("2024-03-01"));
+        assert_eq!(parse_fhir_datetime("2024"), parse_fhir_datetime("2024-01-01"));
+        assert_eq!(
+            parse_fhir_datetime("2024-03-01T10:30:00Z"),
+            Some(Timestamp::from_micros(1_709_289_000_000_000))
+        );
+        assert_eq!(
+            parse_fhir_datetime("2024-03-01T05:30:00-05:00"),
+            parse_fhir_datetime("2024-03-01T10:30:00Z")
+        );
+        assert_eq!(
+            parse_fhir_datetime("2024-03-01T10:30:00.25+00:00"),
+            Some(Timestamp::from_micros(1_709_289_000_250_000))
+        );
+        assert_eq!(parse_fhir_datetime("2024-13-01"), None);
+        assert_eq!(parse_fhir_datetime("not a date"), None);
+
+        let obs: JsonValue = serde_json::json!({
+            "effectivePeriod": { "start": "2024-03-01" }
+        });
+        assert_eq!(
+            get_fhir_time(&obs, &["/effectiveDateTime", "/effectivePeriod/start"]),
+            parse_fhir_datetime("2024-03-01")
+        );
+    }
+
+    #[test]
+    fn test_resource_quality_findings() {
+        let glucose: JsonValue = serde_json::json!({
+            "resourceType": "Observation",
+            "id": "obs-1",
+            "status": "final",
+            "code": { "coding": [{ "system": "http://loinc.org", "code": "2345-7" }] },
+            "valueQuantity": { "value": 95 },
+            "effectiveDateTime": "yesterday"
+        });
+        let findings = resource_quality_findings(&glucose);
+        let codes: Vec<_> = findings.iter().map(|f| f.code).collect();
+        assert_eq!(codes, vec![QualityFindingCode::MissingUnit, QualityFindingCode::UnparseableDate]);
+        assert_eq!(findings[1].element, "effectiveDateTime");
+        assert_eq!(findings[0].resource_id.as_deref(), Some("obs-1"));
+
+        let uncoded: JsonValue = serde_json::json!({
+            "resourceType": "Condition",
+            "code": { "text": "Chest pain" },
+            "onsetDateTime": "2024-03-01"
+        });
+        let findings = resource_quality_findings(&uncoded);
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].code, QualityFindingCode::UnknownCodeDefaulted);
+        assert_eq!(findings[0].severity, QualitySeverity::Error);
+
+        assert_eq!(ingest_outcome(&[], 10, &DEFAULT_QUALITY_THRESHOLDS), IngestOutcome::Accepted);
+        assert_eq!(
+            ingest_outcome(&findings, 10, &DEFAULT_QUALITY_THRESHOLDS),
+            IngestOutcome::AcceptedWithWarnings
+        );
+        let warnings = resource_quality_findings(&glucose);
+        assert_eq!(ingest_outcome(&warnings, 10, &DEFAULT_QUALITY_THRESHOLDS), IngestOutcome::Accepted);
+        assert_eq!(
+            ingest_outcome(&warnings, 4, &DEFAULT_QUALITY_THRESHOLDS),
+            IngestOutcome::AcceptedWithWarnings
+        );
+    }
+
+    #[test]
+    fn test_bundle_evidence_references() {
+        let condition = serde_json::json!({
+            "resourceType": "Condition",
+            "id": "dm2",
+            "evidence": [{ "detail": [
+                { "reference": "Observation/a1c" },
+                { "reference": "DiagnosticReport/panel" }
+            ]}]
+        });
+        let refuted = serde_json::json!({
+            "resourceType": "Condition",
+            "id": "ckd",
+            "verificationStatus": { "coding": [{ "code": "refuted" }] },
+            "evidence": [{ "detail": [{ "reference": "Observation/egfr" }] }]
+        });
+        let report = serde_json::json!({
+            "resourceType": "DiagnosticReport",
+            "id": "panel",
+            "result": [{ "reference": "Observation/glucose" }, { "reference": "Observation/a1c" }]
+        });
+        let follow_up = serde_json::json!({
+            "resourceType": "Observation",
+            "id": "a1c-3mo",
+            "focus": [{ "reference": "Condition/dm2" }]
+        });
+
+        let found = bundle_evidence_references(&[&condition, &refuted, &report, &follow_up]);
+        let summary: Vec<_> = found
+            .iter()
+            .map(|r| (r.condition_id.as_str(), r.result_id.as_str(), r.evidence_type))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("dm2", "a1c", EvidenceType::Diagnostic),
+                ("dm2", "panel", EvidenceType::Diagnostic),
+                ("dm2", "glucose", EvidenceType::Diagnostic),
+                ("ckd", "egfr", EvidenceType::RuleOut),
+                ("dm2", "a1c-3mo", EvidenceType::Monitoring),
+            ]
+        );
+        assert_eq!(found[1].derived_from(), "Condition/dm2 -> DiagnosticReport/panel");
+    }
+
+    #[test]
+    fn test_condition_onset_candidates() {
+        let condition = serde_json::json!({
+            "resourceType": "Condition",
+            "id": "dm2",
+            "encounter": { "reference": "Encounter/visit" },
+            "evidence": [{ "detail": [
+                { "reference": "Observation/a1c" },
+                { "reference": "Observation/missing" }
+            ]}]
+        });
+        let encounter = serde_json::json!({
+            "resourceType": "Encounter",
+            "id": "visit",
+            "period": { "start": "2024-03-10T09:00:00Z" }
+        });
+        let a1c = serde_json::json!({
+            "resourceType": "Observation",
+            "id": "a1c",
+            "effectiveDateTime": "2024-02-20"
+        });
+        let follow_up = serde_json::json!({
+            "resourceType": "Observation",
+            "id": "a1c-3mo",
+            "effectiveDateTime": "2024-06-01",
+            "focus": [{ "reference": "Condition/dm2" }]
+        });
+        let other = serde_json::json!({
+            "resourceType": "Observation",
+            "id": "bp",
+            "effectiveDateTime": "2020-01-01",
+            "focus": [{ "reference": "Condition/htn" }]
+        });
+
+        let candidates = condition_onset_candidates(&condition, &[&condition, &encounter, &a1c, &follow_up, &other]);
+        let summary: Vec<_> = candidates.iter().map(|c| (c.derived_from.as_str(), c.basis)).collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("Encounter/visit", OnsetBasis::Encounter),
+                ("Observation/a1c", OnsetBasis::DiagnosticResult),
+                ("Observation/a1c-3mo", OnsetBasis::MonitoringResult),
+            ]
+        );
+        assert_eq!(candidates[1].at, parse_fhir_datetime("2024-02-20").unwrap());
+
+        let onset = mycelix_health_shared::infer_onset(&candidates).unwrap();
+        assert_eq!(onset.derived_from, "Observation/a1c");
+        assert_eq!(onset.confidence, mycelix_health_shared::OnsetConfidence::High);
+    }
+
+    #[test]
+    fn test_discharge_follow_ups() {
+        let care_plan = serde_json::json!({
+            "resourceType": "CarePlan",
+            "id": "dc-1",
+            "category": [{ "coding": [{ "system": "http://snomed.info/sct", "code": "736372004" }] }],
+            "activity": [
+                { "detail": {
+                    "kind": "Appointment",
+                    "description": "Cardiology follow-up in 2 weeks",
+                    "scheduledPeriod": { "start": "2026-11-01T09:00:00Z" }
+                }},
+                { "reference": { "reference": "ServiceRequest/bmp", "display": "Repeat BMP" } },
+                { "detail": { "kind": "MedicationRequest" } }
+            ]
+        });
+        let follow_ups = discharge_follow_ups(&care_plan);
+        let kinds: Vec<_> = follow_ups.iter().map(|f| f.kind.clone()).collect();
+        assert_eq!(kinds, vec![FollowUpTaskKind::ScheduleAppointment, FollowUpTaskKind::RepeatLab]);
+        assert!(follow_ups[0].due_at.is_some());
+
+        let composition = serde_json::json!({
+            "resourceType": "Composition",
+            "id": "dc-summary",
+            "type": { "coding": [{ "system": "http://loinc.org", "code": "18842-5" }] },
+            "section": [
+                { "title": "Discharge medications", "code": { "coding": [{ "system": "http://loinc.org", "code": "10183-2" }] } },
+                { "title": "Plan of care", "entry": [{ "reference": "Appointment/pcp", "display": "See PCP within 7 days" }] }
+            ]
+        });
+        let follow_ups = discharge_follow_ups(&composition);
+        assert_eq!(follow_ups.len(), 2);
+        assert_eq!(follow_ups[0].kind, FollowUpTaskKind::ReconcileMedications);
+        assert_eq!(follow_ups[1].description, "See PCP within 7 days");
+
+        let routine = serde_json::json!({ "resourceType": "CarePlan", "id": "cp", "activity": [
+            { "detail": { "kind": "Appointment", "description": "Annual visit" } }
+        ]});
+        assert!(discharge_follow_ups(&routine).is_empty());
+    }
 }
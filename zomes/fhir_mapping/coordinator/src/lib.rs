@@ -7,9 +7,18 @@
 
 use hdk::prelude::*;
 use fhir_mapping_integrity::*;
+use mycelix_health_shared::emit_fhir_extensions;
+use mycelix_health_shared::{budgeted_page, BudgetInput, BudgetedPage};
+use mycelix_health_shared::{validate_code, ClinicalCodeSystem};
+use mycelix_health_shared::{require_jurisdiction_allows, DataUse};
+use mycelix_health_shared::{
+    create_tracked_link, delete_tracked_link, entry_references, reference_registry_anchor,
+    register_tracked_entry, require_operator, EntryReference,
+};
 use mycelix_health_shared::{
     require_authorization, log_data_access,
     DataCategory, Permission, anchor_hash,
+    notify_webhooks, WebhookEventType,
 };
 
 // ============================================================================
@@ -18,7 +27,9 @@ use mycelix_health_shared::{
 
 /// Create a FHIR Patient mapping from internal patient record
 #[hdk_extern]
-pub fn create_fhir_patient_mapping(mapping: FhirPatientMapping) -> ExternResult<Record> {
+pub fn create_fhir_patient_mapping(mut mapping: FhirPatientMapping) -> ExternResult<Record> {
+    // Typed US Core fields always reflect the preserved extensions
+    mapping.us_core = UsCoreDemographics::from_extensions(&mapping.extensions);
     let auth = require_authorization(
         mapping.internal_patient_hash.clone(),
         DataCategory::Demographics,
@@ -28,29 +39,30 @@ pub fn create_fhir_patient_mapping(mapping: FhirPatientMapping) -> ExternResult<
     let mapping_hash = create_entry(&EntryTypes::FhirPatientMapping(mapping.clone()))?;
     let record = get(mapping_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created FHIR patient mapping".to_string())))?;
+    track_mapping("FhirPatientMapping", &mapping_hash)?;
 
     // Link from internal patient to FHIR mapping
-    create_link(
+    link_mapping(
         mapping.internal_patient_hash.clone(),
-        mapping_hash.clone(),
+        &mapping_hash,
         LinkTypes::PatientToFhirMappings,
         (),
     )?;
 
     // Link to source system anchor for cross-system queries
     let source_anchor = anchor_hash(&format!("fhir_source_{}", mapping.source_system))?;
-    create_link(
+    link_mapping(
         source_anchor,
-        mapping_hash.clone(),
+        &mapping_hash,
         LinkTypes::SourceSystemMappings,
         (),
     )?;
 
     // Link to all FHIR patient mappings anchor
     let all_mappings_anchor = anchor_hash("all_fhir_patient_mappings")?;
-    create_link(
+    link_mapping(
         all_mappings_anchor,
-        mapping_hash,
+        &mapping_hash,
         LinkTypes::AllFhirPatientMappings,
         (),
     )?;
@@ -112,10 +124,15 @@ pub struct GetPatientFhirMappingsInput {
     pub patient_hash: ActionHash,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
 }
 
+/// Returns as many mappings as fit the response budget; follow
+/// `continuation` for the rest
 #[hdk_extern]
-pub fn get_patient_fhir_mappings(input: GetPatientFhirMappingsInput) -> ExternResult<Vec<Record>> {
+pub fn get_patient_fhir_mappings(input: GetPatientFhirMappingsInput) -> ExternResult<BudgetedPage<Record>> {
     // Require authorization
     let auth = require_authorization(
         input.patient_hash.clone(),
@@ -127,14 +144,13 @@ pub fn get_patient_fhir_mappings(input: GetPatientFhirMappingsInput) -> ExternRe
     let links = get_links(
         LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToFhirMappings)?, GetStrategy::default())?;
 
-    let mut mappings = Vec::new();
-    for link in links {
-        if let Some(hash) = link.target.into_action_hash() {
-            if let Some(record) = get(hash, GetOptions::default())? {
-                mappings.push(record);
-            }
-        }
-    }
+    let mut sensitive = SensitiveFilter::new(&input.patient_hash, Permission::Read, input.is_emergency, &input.emergency_reason);
+    let mappings = budgeted_page(links, &input.page, |link| {
+        let Some(hash) = link.target.into_action_hash() else {
+            return Ok(None);
+        };
+        Ok(get(hash, GetOptions::default())?.filter(|record| sensitive.allows(record)))
+    })?;
 
     // Log access
     log_data_access(
@@ -162,26 +178,35 @@ pub fn create_fhir_observation_mapping(mapping: FhirObservationMapping) -> Exter
         Permission::Write,
         false,
     )?;
+    require_sensitive_access(&mapping.patient_hash, &mapping.sensitive_categories, Permission::Write, false, None)?;
+    require_valid_codes(&[&mapping.code], &[(ClinicalCodeSystem::SnomedCt, mapping.snomed_code.as_deref())])?;
     let mapping_hash = create_entry(&EntryTypes::FhirObservationMapping(mapping.clone()))?;
     let record = get(mapping_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created FHIR observation mapping".to_string())))?;
+    track_mapping("FhirObservationMapping", &mapping_hash)?;
 
     // Link from internal record to FHIR mapping
-    create_link(
+    link_mapping(
         mapping.internal_record_hash.clone(),
-        mapping_hash.clone(),
+        &mapping_hash,
         LinkTypes::RecordToFhirObservation,
         (),
     )?;
 
     // Link from patient to this observation
-    create_link(
+    link_mapping(
         mapping.patient_hash.clone(),
-        mapping_hash,
+        &mapping_hash,
         LinkTypes::PatientToFhirMappings,
         (),
     )?;
 
+    notify_webhooks(
+        WebhookEventType::NewObservation,
+        mapping.patient_hash.clone(),
+        mapping_hash,
+    );
+
     log_data_access(
         mapping.patient_hash,
         vec![DataCategory::LabResults],
@@ -208,6 +233,14 @@ pub fn get_fhir_observation_mapping(input: GetFhirMappingInput) -> ExternResult<
                 input.is_emergency,
             )?;
 
+            require_sensitive_access(
+                &mapping.patient_hash,
+                &mapping.sensitive_categories,
+                Permission::Read,
+                input.is_emergency,
+                input.emergency_reason.clone(),
+            )?;
+
             log_data_access(
                 mapping.patient_hash,
                 vec![DataCategory::LabResults],
@@ -222,6 +255,168 @@ pub fn get_fhir_observation_mapping(input: GetFhirMappingInput) -> ExternResult<
     Ok(record)
 }
 
+/// Which observation statuses a query should return
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Default)]
+pub enum ObservationStatusFilter {
+    /// Only final, amended and corrected results (default)
+    #[default]
+    Reportable,
+    /// Every observation regardless of status
+    All,
+    /// Only observations with one of the given FHIR status codes
+    Statuses(Vec<String>),
+}
+
+impl ObservationStatusFilter {
+    pub fn matches(&self, status: &str) -> bool {
+        match self {
+            ObservationStatusFilter::Reportable => ObservationStatus::from_code(status)
+                .map(|s| s.is_reportable())
+                .unwrap_or(false),
+            ObservationStatusFilter::All => true,
+            ObservationStatusFilter::Statuses(codes) => codes.iter().any(|c| c == status),
+        }
+    }
+}
+
+/// Input for listing a patient's observation mappings
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientObservationsInput {
+    pub patient_hash: ActionHash,
+    /// Defaults to `ObservationStatusFilter::Reportable`
+    pub status_filter: Option<ObservationStatusFilter>,
+    /// Include observations their source system retracted (excluded by default)
+    #[serde(default)]
+    pub include_retracted: bool,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
+}
+
+/// Get a patient's FHIR observation mappings, filtered by status
+///
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
+#[hdk_extern]
+pub fn get_patient_observation_mappings(input: GetPatientObservationsInput) -> ExternResult<BudgetedPage<Record>> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Read,
+        input.is_emergency,
+    )?;
+
+    let filter = input.status_filter.unwrap_or_default();
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToFhirMappings)?, GetStrategy::default())?;
+
+    let mut sensitive = SensitiveFilter::new(&input.patient_hash, Permission::Read, input.is_emergency, &input.emergency_reason);
+    let observations = budgeted_page(links, &input.page, |link| {
+        let Some(hash) = link.target.into_action_hash() else {
+            return Ok(None);
+        };
+        if !input.include_retracted && is_retracted(&hash)? {
+            return Ok(None);
+        }
+        Ok(get(hash, GetOptions::default())?.filter(|record| {
+            record
+                .entry()
+                .to_app_option::<FhirObservationMapping>()
+                .ok()
+                .flatten()
+                .is_some_and(|mapping| filter.matches(&mapping.status))
+                && sensitive.allows(record)
+        }))
+    })?;
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::LabResults],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(observations)
+}
+
+/// Input for moving an observation through its status lifecycle
+#[derive(Serialize, Deserialize, Debug)]
+pub struct UpdateObservationStatusInput {
+    pub mapping_hash: ActionHash,
+    /// New FHIR status code (e.g. "final", "amended", "entered-in-error")
+    pub new_status: String,
+    pub note: Option<String>,
+}
+
+/// Update the status of a FHIR observation mapping
+///
+/// Allowable transitions are enforced by the integrity zome; this checks
+/// them up front so callers get a clear error instead of a validation failure.
+#[hdk_extern]
+pub fn update_fhir_observation_status(input: UpdateObservationStatusInput) -> ExternResult<Record> {
+    let record = get(input.mapping_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Mapping not found".to_string())))?;
+
+    let mut mapping: FhirObservationMapping = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid observation mapping entry".to_string())))?;
+
+    let current = ObservationStatus::from_code(&mapping.status)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Invalid current status: {}", mapping.status))))?;
+    let next = ObservationStatus::from_code(&input.new_status)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("Invalid observation status: {}", input.new_status))))?;
+    if !current.can_transition_to(&next) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Observation status cannot change from {} to {}",
+            current.as_code(),
+            next.as_code()
+        ))));
+    }
+
+    let auth = require_authorization(
+        mapping.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Amend,
+        false,
+    )?;
+
+    let now = sys_time()?;
+    mapping.effective_time = Some(mapping.bitemporal().effective_time);
+    mapping.recorded_time = Some(now);
+    mapping.status = next.as_code().to_string();
+    mapping.last_synced = now;
+    if let Some(note) = input.note {
+        mapping.note.push(note);
+    }
+
+    let updated_hash = update_entry(input.mapping_hash.clone(), &mapping)?;
+    let updated_record = get(updated_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated observation mapping".to_string())))?;
+
+    create_link(
+        input.mapping_hash,
+        updated_hash,
+        LinkTypes::FhirMappingUpdates,
+        (),
+    )?;
+
+    log_data_access(
+        mapping.patient_hash,
+        vec![DataCategory::LabResults],
+        Permission::Amend,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(updated_record)
+}
+
 // ============================================================================
 // Condition FHIR Mapping Functions
 // ============================================================================
@@ -235,22 +430,31 @@ pub fn create_fhir_condition_mapping(mapping: FhirConditionMapping) -> ExternRes
         Permission::Write,
         false,
     )?;
+    require_sensitive_access(&mapping.patient_hash, &mapping.sensitive_categories, Permission::Write, false, None)?;
+    require_valid_codes(
+        &[&mapping.code],
+        &[
+            (ClinicalCodeSystem::Icd10Cm, Some(mapping.icd10_code.as_str())),
+            (ClinicalCodeSystem::SnomedCt, mapping.snomed_code.as_deref()),
+        ],
+    )?;
     let mapping_hash = create_entry(&EntryTypes::FhirConditionMapping(mapping.clone()))?;
     let record = get(mapping_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created FHIR condition mapping".to_string())))?;
+    track_mapping("FhirConditionMapping", &mapping_hash)?;
 
     // Link from internal diagnosis to FHIR mapping
-    create_link(
+    link_mapping(
         mapping.internal_diagnosis_hash.clone(),
-        mapping_hash.clone(),
+        &mapping_hash,
         LinkTypes::DiagnosisToFhirCondition,
         (),
     )?;
 
     // Link from patient
-    create_link(
+    link_mapping(
         mapping.patient_hash.clone(),
-        mapping_hash,
+        &mapping_hash,
         LinkTypes::PatientToFhirMappings,
         (),
     )?;
@@ -281,6 +485,14 @@ pub fn get_fhir_condition_mapping(input: GetFhirMappingInput) -> ExternResult<Op
                 input.is_emergency,
             )?;
 
+            require_sensitive_access(
+                &mapping.patient_hash,
+                &mapping.sensitive_categories,
+                Permission::Read,
+                input.is_emergency,
+                input.emergency_reason.clone(),
+            )?;
+
             log_data_access(
                 mapping.patient_hash,
                 vec![DataCategory::Diagnoses],
@@ -291,81 +503,772 @@ pub fn get_fhir_condition_mapping(input: GetFhirMappingInput) -> ExternResult<Op
             )?;
         }
     }
-
-    Ok(record)
+
+    Ok(record)
+}
+
+// ============================================================================
+// Medication FHIR Mapping Functions
+// ============================================================================
+
+/// Create a FHIR Medication mapping
+#[hdk_extern]
+pub fn create_fhir_medication_mapping(mapping: FhirMedicationMapping) -> ExternResult<Record> {
+    let auth = require_authorization(
+        mapping.patient_hash.clone(),
+        DataCategory::Medications,
+        Permission::Write,
+        false,
+    )?;
+    require_sensitive_access(&mapping.patient_hash, &mapping.sensitive_categories, Permission::Write, false, None)?;
+    require_valid_codes(
+        &[&mapping.medication_codeable_concept],
+        &[(ClinicalCodeSystem::RxNorm, Some(mapping.rxnorm_code.as_str()))],
+    )?;
+    let mapping_hash = create_entry(&EntryTypes::FhirMedicationMapping(mapping.clone()))?;
+    let record = get(mapping_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created FHIR medication mapping".to_string())))?;
+    track_mapping("FhirMedicationMapping", &mapping_hash)?;
+
+    // Link from internal medication to FHIR mapping
+    link_mapping(
+        mapping.internal_medication_hash.clone(),
+        &mapping_hash,
+        LinkTypes::MedicationToFhirMapping,
+        (),
+    )?;
+
+    // Link from patient
+    link_mapping(
+        mapping.patient_hash.clone(),
+        &mapping_hash,
+        LinkTypes::PatientToFhirMappings,
+        (),
+    )?;
+
+    log_data_access(
+        mapping.patient_hash,
+        vec![DataCategory::Medications],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(record)
+}
+
+/// Get FHIR medication mapping with access control
+#[hdk_extern]
+pub fn get_fhir_medication_mapping(input: GetFhirMappingInput) -> ExternResult<Option<Record>> {
+    let record = get(input.mapping_hash.clone(), GetOptions::default())?;
+
+    if let Some(ref rec) = record {
+        if let Some(mapping) = rec.entry().to_app_option::<FhirMedicationMapping>().ok().flatten() {
+            let auth = require_authorization(
+                mapping.patient_hash.clone(),
+                DataCategory::Medications,
+                Permission::Read,
+                input.is_emergency,
+            )?;
+
+            require_sensitive_access(
+                &mapping.patient_hash,
+                &mapping.sensitive_categories,
+                Permission::Read,
+                input.is_emergency,
+                input.emergency_reason.clone(),
+            )?;
+
+            log_data_access(
+                mapping.patient_hash,
+                vec![DataCategory::Medications],
+                Permission::Read,
+                auth.consent_hash,
+                auth.emergency_override,
+                input.emergency_reason,
+            )?;
+        }
+    }
+
+    Ok(record)
+}
+
+// ============================================================================
+// Procedure FHIR Mapping Functions
+// ============================================================================
+
+/// Code system used to look up procedures
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum ProcedureCodeSystem {
+    Cpt,
+    Snomed,
+}
+
+fn procedure_code_tag(system: &ProcedureCodeSystem, code: &str) -> LinkTag {
+    let prefix = match system {
+        ProcedureCodeSystem::Cpt => "cpt",
+        ProcedureCodeSystem::Snomed => "snomed",
+    };
+    LinkTag::new(format!("{}:{}", prefix, code).into_bytes())
+}
+
+/// Create a FHIR Procedure mapping
+#[hdk_extern]
+pub fn create_fhir_procedure_mapping(mapping: FhirProcedureMapping) -> ExternResult<Record> {
+    let auth = require_authorization(
+        mapping.patient_hash.clone(),
+        DataCategory::Procedures,
+        Permission::Write,
+        false,
+    )?;
+    require_sensitive_access(&mapping.patient_hash, &mapping.sensitive_categories, Permission::Write, false, None)?;
+    require_valid_codes(
+        &[&mapping.code],
+        &[
+            (ClinicalCodeSystem::Cpt, mapping.cpt_code.as_deref()),
+            (ClinicalCodeSystem::SnomedCt, mapping.snomed_code.as_deref()),
+        ],
+    )?;
+    let mapping_hash = create_entry(&EntryTypes::FhirProcedureMapping(mapping.clone()))?;
+    let record = get(mapping_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created FHIR procedure mapping".to_string())))?;
+    track_mapping("FhirProcedureMapping", &mapping_hash)?;
+
+    // Link from internal procedure to FHIR mapping
+    if let Some(procedure_hash) = &mapping.internal_procedure_hash {
+        link_mapping(
+            procedure_hash.clone(),
+            &mapping_hash,
+            LinkTypes::ProcedureToFhirMapping,
+            (),
+        )?;
+    }
+
+    // Link from patient
+    link_mapping(
+        mapping.patient_hash.clone(),
+        &mapping_hash,
+        LinkTypes::PatientToFhirMappings,
+        (),
+    )?;
+
+    // Index by code for billing and quality measure queries
+    let codes = [
+        (ProcedureCodeSystem::Cpt, &mapping.cpt_code),
+        (ProcedureCodeSystem::Snomed, &mapping.snomed_code),
+    ];
+    for (system, code) in codes {
+        if let Some(code) = code {
+            link_mapping(
+                mapping.patient_hash.clone(),
+                &mapping_hash,
+                LinkTypes::PatientToProcedureCodes,
+                procedure_code_tag(&system, code),
+            )?;
+        }
+    }
+
+    log_data_access(
+        mapping.patient_hash,
+        vec![DataCategory::Procedures],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(record)
+}
+
+/// Get FHIR procedure mapping with access control
+#[hdk_extern]
+pub fn get_fhir_procedure_mapping(input: GetFhirMappingInput) -> ExternResult<Option<Record>> {
+    let record = get(input.mapping_hash.clone(), GetOptions::default())?;
+
+    if let Some(ref rec) = record {
+        if let Some(mapping) = rec.entry().to_app_option::<FhirProcedureMapping>().ok().flatten() {
+            let auth = require_authorization(
+                mapping.patient_hash.clone(),
+                DataCategory::Procedures,
+                Permission::Read,
+                input.is_emergency,
+            )?;
+
+            require_sensitive_access(
+                &mapping.patient_hash,
+                &mapping.sensitive_categories,
+                Permission::Read,
+                input.is_emergency,
+                input.emergency_reason.clone(),
+            )?;
+
+            log_data_access(
+                mapping.patient_hash,
+                vec![DataCategory::Procedures],
+                Permission::Read,
+                auth.consent_hash,
+                auth.emergency_override,
+                input.emergency_reason,
+            )?;
+        }
+    }
+
+    Ok(record)
+}
+
+/// Input for finding a patient's procedures by code
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetProceduresByCodeInput {
+    pub patient_hash: ActionHash,
+    pub code_system: ProcedureCodeSystem,
+    pub code: String,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Get a patient's procedure mappings with a given CPT or SNOMED code
+#[hdk_extern]
+pub fn get_procedures_by_code(input: GetProceduresByCodeInput) -> ExternResult<Vec<Record>> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Procedures,
+        Permission::Read,
+        input.is_emergency,
+    )?;
+
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToProcedureCodes)?
+            .tag_prefix(procedure_code_tag(&input.code_system, &input.code)),
+        GetStrategy::default(),
+    )?;
+
+    let expected = procedure_code_tag(&input.code_system, &input.code);
+    let mut sensitive = SensitiveFilter::new(&input.patient_hash, Permission::Read, input.is_emergency, &input.emergency_reason);
+    let mut procedures = Vec::new();
+    for link in links {
+        // Tag prefixes would also match longer codes
+        if link.tag != expected {
+            continue;
+        }
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if sensitive.allows(&record) {
+                    procedures.push(record);
+                }
+            }
+        }
+    }
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Procedures],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(procedures)
+}
+
+/// Export a procedure mapping as a FHIR R4 Procedure resource
+#[hdk_extern]
+pub fn export_fhir_procedure(input: GetFhirMappingInput) -> ExternResult<serde_json::Value> {
+    let mapping: FhirProcedureMapping = get_fhir_procedure_mapping(input)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Procedure mapping not found".to_string())))?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not a FHIR procedure mapping".to_string())))?;
+    Ok(procedure_to_fhir(&mapping))
+}
+
+fn procedure_to_fhir(mapping: &FhirProcedureMapping) -> serde_json::Value {
+    let mut fields = serde_json::Map::new();
+    fields.insert("resourceType".to_string(), serde_json::json!("Procedure"));
+    fields.insert("id".to_string(), serde_json::json!(mapping.fhir_procedure_id));
+    fields.insert("status".to_string(), serde_json::json!(mapping.status));
+    fields.insert("code".to_string(), codeable_concept_json(&mapping.code));
+    fields.insert(
+        "subject".to_string(),
+        serde_json::json!({ "reference": format!("Patient/{}", mapping.patient_hash) }),
+    );
+
+    if let Some(performed) = &mapping.performed_datetime {
+        fields.insert("performedDateTime".to_string(), serde_json::json!(performed));
+    } else if let Some(period) = &mapping.performed_period {
+        fields.insert(
+            "performedPeriod".to_string(),
+            serde_json::json!({ "start": period.start, "end": period.end }),
+        );
+    }
+    if !mapping.performers.is_empty() {
+        let performers: Vec<serde_json::Value> = mapping
+            .performers
+            .iter()
+            .map(|p| {
+                let mut performer = serde_json::json!({ "actor": reference_json(&p.actor) });
+                if let Some(function) = &p.function {
+                    performer["function"] = codeable_concept_json(function);
+                }
+                performer
+            })
+            .collect();
+        fields.insert("performer".to_string(), serde_json::json!(performers));
+    }
+    let concept_lists = [
+        ("bodySite", &mapping.body_site),
+        ("complication", &mapping.complications),
+        ("reasonCode", &mapping.reason_code),
+    ];
+    for (name, concepts) in concept_lists {
+        if !concepts.is_empty() {
+            let values: Vec<serde_json::Value> = concepts.iter().map(codeable_concept_json).collect();
+            fields.insert(name.to_string(), serde_json::json!(values));
+        }
+    }
+    if let Some(outcome) = &mapping.outcome {
+        fields.insert("outcome".to_string(), codeable_concept_json(outcome));
+    }
+    if let Some(encounter) = &mapping.encounter_reference {
+        fields.insert("encounter".to_string(), reference_json(encounter));
+    }
+    if !mapping.note.is_empty() {
+        let notes: Vec<serde_json::Value> = mapping.note.iter().map(|n| serde_json::json!({ "text": n })).collect();
+        fields.insert("note".to_string(), serde_json::json!(notes));
+    }
+
+    let mut resource = serde_json::Value::Object(fields);
+    emit_fhir_extensions(&mut resource, &mapping.extensions);
+    resource
+}
+
+/// Export a patient mapping as a FHIR R4 Patient resource, including the
+/// extensions preserved at ingest
+#[hdk_extern]
+pub fn export_fhir_patient(input: GetFhirMappingInput) -> ExternResult<serde_json::Value> {
+    let mapping: FhirPatientMapping = get_fhir_patient_mapping(input)?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Patient mapping not found".to_string())))?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Not a FHIR patient mapping".to_string())))?;
+    Ok(patient_to_fhir(&mapping))
+}
+
+fn patient_to_fhir(mapping: &FhirPatientMapping) -> serde_json::Value {
+    let mut fields = serde_json::Map::new();
+    fields.insert("resourceType".to_string(), serde_json::json!("Patient"));
+    fields.insert("id".to_string(), serde_json::json!(mapping.fhir_patient_id));
+
+    if !mapping.fhir_identifiers.is_empty() {
+        let identifiers: Vec<serde_json::Value> = mapping
+            .fhir_identifiers
+            .iter()
+            .map(|i| {
+                let mut identifier = serde_json::json!({ "system": i.system, "value": i.value });
+                if let Some(use_code) = &i.use_code {
+                    identifier["use"] = serde_json::json!(use_code);
+                }
+                if let Some(type_display) = &i.type_display {
+                    identifier["type"] = serde_json::json!({ "text": type_display });
+                }
+                identifier
+            })
+            .collect();
+        fields.insert("identifier".to_string(), serde_json::json!(identifiers));
+    }
+    let names: Vec<serde_json::Value> = mapping
+        .name
+        .iter()
+        .map(|n| {
+            let mut name = serde_json::json!({ "given": n.given });
+            if let Some(family) = &n.family {
+                name["family"] = serde_json::json!(family);
+            }
+            if let Some(use_code) = &n.use_code {
+                name["use"] = serde_json::json!(use_code);
+            }
+            if let Some(text) = &n.text {
+                name["text"] = serde_json::json!(text);
+            }
+            if !n.prefix.is_empty() {
+                name["prefix"] = serde_json::json!(n.prefix);
+            }
+            if !n.suffix.is_empty() {
+                name["suffix"] = serde_json::json!(n.suffix);
+            }
+            name
+        })
+        .collect();
+    fields.insert("name".to_string(), serde_json::json!(names));
+    if let Some(gender) = &mapping.gender {
+        fields.insert("gender".to_string(), serde_json::json!(gender));
+    }
+    if let Some(birth_date) = &mapping.birth_date {
+        fields.insert("birthDate".to_string(), serde_json::json!(birth_date));
+    }
+    if let Some(marital_status) = &mapping.marital_status {
+        fields.insert("maritalStatus".to_string(), codeable_concept_json(marital_status));
+    }
+
+    let mut resource = serde_json::Value::Object(fields);
+    emit_fhir_extensions(&mut resource, &mapping.extensions);
+    resource
+}
+
+fn codeable_concept_json(concept: &FhirCodeableConcept) -> serde_json::Value {
+    let coding: Vec<serde_json::Value> = concept
+        .coding
+        .iter()
+        .map(|c| {
+            let mut coding = serde_json::json!({ "system": c.system, "code": c.code });
+            if let Some(display) = &c.display {
+                coding["display"] = serde_json::json!(display);
+            }
+            if let Some(version) = &c.version {
+                coding["version"] = serde_json::json!(version);
+            }
+            coding
+        })
+        .collect();
+    let mut value = serde_json::json!({ "coding": coding });
+    if let Some(text) = &concept.text {
+        value["text"] = serde_json::json!(text);
+    }
+    value
+}
+
+fn reference_json(reference: &FhirReference) -> serde_json::Value {
+    let mut value = serde_json::json!({});
+    if let Some(r) = &reference.reference {
+        value["reference"] = serde_json::json!(r);
+    }
+    if let Some(type_name) = &reference.type_name {
+        value["type"] = serde_json::json!(type_name);
+    }
+    if let Some(display) = &reference.display {
+        value["display"] = serde_json::json!(display);
+    }
+    value
+}
+
+// ============================================================================
+// As-Of Queries
+// ============================================================================
+
+/// Input for reconstructing what the record said at a point in time
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientRecordAsOfInput {
+    pub patient_hash: ActionHash,
+    /// Only versions recorded at or before this time are returned
+    pub as_of: Timestamp,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Observation, condition and medication mappings as known at `as_of`,
+/// each in the latest version recorded by then
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PatientRecordAsOf {
+    pub as_of: Timestamp,
+    pub observations: Vec<Record>,
+    pub conditions: Vec<Record>,
+    pub medications: Vec<Record>,
+}
+
+/// What did we know about this patient at `as_of`?
+///
+/// Facts recorded later are left out even if they took effect earlier, and
+/// corrections recorded later do not replace the version known then.
+#[hdk_extern]
+pub fn get_patient_record_as_of(input: GetPatientRecordAsOfInput) -> ExternResult<PatientRecordAsOf> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        input.is_emergency,
+    )?;
+
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToFhirMappings)?, GetStrategy::default())?;
+
+    let mut sensitive = SensitiveFilter::new(&input.patient_hash, Permission::Read, input.is_emergency, &input.emergency_reason);
+    let mut observations = Vec::new();
+    let mut conditions = Vec::new();
+    let mut medications = Vec::new();
+
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(record) = get(hash, GetOptions::default())? else {
+            continue;
+        };
+        if record.entry().to_app_option::<FhirObservationMapping>().ok().flatten().is_some() {
+            if let Some(version) = version_known_at::<FhirObservationMapping>(record, input.as_of)? {
+                if sensitive.allows(&version) {
+                    observations.push(version);
+                }
+            }
+        } else if record.entry().to_app_option::<FhirConditionMapping>().ok().flatten().is_some() {
+            if let Some(version) = version_known_at::<FhirConditionMapping>(record, input.as_of)? {
+                if sensitive.allows(&version) {
+                    conditions.push(version);
+                }
+            }
+        } else if record.entry().to_app_option::<FhirMedicationMapping>().ok().flatten().is_some() {
+            if let Some(version) = version_known_at::<FhirMedicationMapping>(record, input.as_of)? {
+                if sensitive.allows(&version) {
+                    medications.push(version);
+                }
+            }
+        }
+    }
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::All],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(PatientRecordAsOf {
+        as_of: input.as_of,
+        observations,
+        conditions,
+        medications,
+    })
+}
+
+/// Follow a mapping's update chain to the latest version recorded by `as_of`
+fn version_known_at<T>(original: Record, as_of: Timestamp) -> ExternResult<Option<Record>>
+where
+    T: BiTemporalMapping + TryFrom<SerializedBytes, Error = SerializedBytesError>,
+{
+    let recorded_by = |record: &Record| {
+        record
+            .entry()
+            .to_app_option::<T>()
+            .ok()
+            .flatten()
+            .is_some_and(|mapping| mapping.bitemporal().known_at(as_of))
+    };
+    if !recorded_by(&original) {
+        return Ok(None);
+    }
+
+    let mut known = original;
+    loop {
+        let updates = get_links(
+            LinkQuery::try_new(known.action_address().clone(), LinkTypes::FhirMappingUpdates)?,
+            GetStrategy::default(),
+        )?;
+        let mut next = None;
+        for hash in updates.into_iter().filter_map(|l| l.target.into_action_hash()) {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                if recorded_by(&record) {
+                    next = Some(record);
+                    break;
+                }
+            }
+        }
+        match next {
+            Some(record) => known = record,
+            None => return Ok(Some(known)),
+        }
+    }
+}
+
+// ============================================================================
+// Problem List and Episodes
+// ============================================================================
+
+/// Input for the problem list and episode views
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientConditionsInput {
+    pub patient_hash: ActionHash,
+    /// Also list inactive, remitted and resolved conditions
+    #[serde(default)]
+    pub include_resolved: bool,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// A condition placed on the normalized timeline
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProblemListItem {
+    pub mapping_hash: ActionHash,
+    pub code: FhirCodeableConcept,
+    pub icd10_code: String,
+    pub clinical_status: String,
+    pub source_system: String,
+    /// Asserted onset, else inferred; drives the ordering
+    pub onset: NormalizedOnset,
+    pub asserted_onset: Option<Timestamp>,
+    pub inferred_onset: Option<InferredOnset>,
+    pub abatement: Option<Timestamp>,
+}
+
+/// Span of illness made of conditions with the same code
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ConditionEpisode {
+    /// ICD-10 code, else the first coding's code
+    pub code: String,
+    pub display: Option<String>,
+    pub start: NormalizedOnset,
+    /// `None` while any condition in the episode is unresolved
+    pub end: Option<Timestamp>,
+    /// Conditions in onset order
+    pub conditions: Vec<ActionHash>,
+}
+
+const RESOLVED_CLINICAL_STATUSES: &[&str] = &["inactive", "remission", "resolved"];
+const EXCLUDED_VERIFICATION_STATUSES: &[&str] = &["refuted", "entered-in-error"];
+
+/// Current, unretracted condition mappings the caller may read, ordered by
+/// normalized onset with undated conditions last
+fn patient_problem_list(input: &GetPatientConditionsInput) -> ExternResult<Vec<ProblemListItem>> {
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToFhirMappings)?,
+        GetStrategy::default(),
+    )?;
+    let now = sys_time()?;
+    let mut sensitive = SensitiveFilter::new(&input.patient_hash, Permission::Read, input.is_emergency, &input.emergency_reason);
+    let mut items = Vec::new();
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if is_retracted(&hash)? {
+            continue;
+        }
+        let Some(record) = get(hash.clone(), GetOptions::default())? else {
+            continue;
+        };
+        if record.entry().to_app_option::<FhirConditionMapping>().ok().flatten().is_none() {
+            continue;
+        }
+        let Some(current) = version_known_at::<FhirConditionMapping>(record, now)? else {
+            continue;
+        };
+        let Some(condition) = current.entry().to_app_option::<FhirConditionMapping>().ok().flatten() else {
+            continue;
+        };
+        let status = condition.clinical_status.to_lowercase();
+        if EXCLUDED_VERIFICATION_STATUSES.contains(&condition.verification_status.to_lowercase().as_str())
+            || (!input.include_resolved && RESOLVED_CLINICAL_STATUSES.contains(&status.as_str()))
+            || !sensitive.allows(&current)
+        {
+            continue;
+        }
+        items.push(ProblemListItem {
+            mapping_hash: hash,
+            onset: condition.normalized_onset(),
+            code: condition.code,
+            icd10_code: condition.icd10_code,
+            clinical_status: condition.clinical_status,
+            source_system: condition.source_system,
+            asserted_onset: condition.onset_datetime,
+            inferred_onset: condition.inferred_onset,
+            abatement: condition.abatement_datetime,
+        });
+    }
+    items.sort_by_key(|item| (item.onset.at.is_none(), item.onset.at));
+    Ok(items)
 }
 
-// ============================================================================
-// Medication FHIR Mapping Functions
-// ============================================================================
-
-/// Create a FHIR Medication mapping
+/// The patient's conditions on a normalized timeline
+///
+/// Conditions without an asserted onset are placed at the onset inferred
+/// at ingest, flagged with its confidence.
 #[hdk_extern]
-pub fn create_fhir_medication_mapping(mapping: FhirMedicationMapping) -> ExternResult<Record> {
+pub fn get_problem_list(input: GetPatientConditionsInput) -> ExternResult<Vec<ProblemListItem>> {
     let auth = require_authorization(
-        mapping.patient_hash.clone(),
-        DataCategory::Medications,
-        Permission::Write,
-        false,
-    )?;
-    let mapping_hash = create_entry(&EntryTypes::FhirMedicationMapping(mapping.clone()))?;
-    let record = get(mapping_hash.clone(), GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created FHIR medication mapping".to_string())))?;
-
-    // Link from internal medication to FHIR mapping
-    create_link(
-        mapping.internal_medication_hash.clone(),
-        mapping_hash.clone(),
-        LinkTypes::MedicationToFhirMapping,
-        (),
-    )?;
-
-    // Link from patient
-    create_link(
-        mapping.patient_hash.clone(),
-        mapping_hash,
-        LinkTypes::PatientToFhirMappings,
-        (),
+        input.patient_hash.clone(),
+        DataCategory::Diagnoses,
+        Permission::Read,
+        input.is_emergency,
     )?;
+    let items = patient_problem_list(&input)?;
 
     log_data_access(
-        mapping.patient_hash,
-        vec![DataCategory::Medications],
-        Permission::Write,
+        input.patient_hash,
+        vec![DataCategory::Diagnoses],
+        Permission::Read,
         auth.consent_hash,
         auth.emergency_override,
-        None,
+        input.emergency_reason,
     )?;
-
-    Ok(record)
+    Ok(items)
 }
 
-/// Get FHIR medication mapping with access control
-#[hdk_extern]
-pub fn get_fhir_medication_mapping(input: GetFhirMappingInput) -> ExternResult<Option<Record>> {
-    let record = get(input.mapping_hash.clone(), GetOptions::default())?;
-
-    if let Some(ref rec) = record {
-        if let Some(mapping) = rec.entry().to_app_option::<FhirMedicationMapping>().ok().flatten() {
-            let auth = require_authorization(
-                mapping.patient_hash.clone(),
-                DataCategory::Medications,
-                Permission::Read,
-                input.is_emergency,
-            )?;
-
-            log_data_access(
-                mapping.patient_hash,
-                vec![DataCategory::Medications],
-                Permission::Read,
-                auth.consent_hash,
-                auth.emergency_override,
-                input.emergency_reason,
-            )?;
+/// Group same-code conditions into episodes
+///
+/// A condition joins the latest episode of its code unless that episode
+/// resolved before the condition's onset. Undated conditions join the
+/// latest episode of their code, or open one of their own.
+fn group_episodes(items: &[ProblemListItem]) -> Vec<ConditionEpisode> {
+    let mut episodes: Vec<ConditionEpisode> = Vec::new();
+    for item in items {
+        let code = match item.icd10_code.as_str() {
+            "" | "unknown" => item.code.coding.first().map(|c| c.code.clone()).unwrap_or_default(),
+            icd10 => icd10.to_string(),
+        };
+        let latest = episodes.iter_mut().rev().find(|episode| episode.code == code);
+        let continues = latest.filter(|episode| match (episode.end, item.onset.at) {
+            (Some(end), Some(onset)) => onset <= end,
+            _ => true,
+        });
+        match continues {
+            Some(episode) => {
+                episode.end = match (episode.end, item.abatement) {
+                    (Some(end), Some(abatement)) => Some(end.max(abatement)),
+                    _ => None,
+                };
+                if episode.start.at.is_none() {
+                    episode.start = item.onset.clone();
+                }
+                episode.conditions.push(item.mapping_hash.clone());
+            }
+            None => episodes.push(ConditionEpisode {
+                code,
+                display: item.code.text.clone().or_else(|| item.code.coding.first().and_then(|c| c.display.clone())),
+                start: item.onset.clone(),
+                end: item.abatement,
+                conditions: vec![item.mapping_hash.clone()],
+            }),
         }
     }
+    episodes
+}
 
-    Ok(record)
+/// The patient's conditions grouped into episodes of care on the normalized
+/// timeline, resolved conditions included
+#[hdk_extern]
+pub fn get_condition_episodes(input: GetPatientConditionsInput) -> ExternResult<Vec<ConditionEpisode>> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Diagnoses,
+        Permission::Read,
+        input.is_emergency,
+    )?;
+    let items = patient_problem_list(&GetPatientConditionsInput {
+        patient_hash: input.patient_hash.clone(),
+        include_resolved: true,
+        is_emergency: input.is_emergency,
+        emergency_reason: input.emergency_reason.clone(),
+    })?;
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Diagnoses],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+    Ok(group_episodes(&items))
 }
 
 // ============================================================================
@@ -379,6 +1282,14 @@ pub struct ExportPatientBundleInput {
     pub include_observations: bool,
     pub include_conditions: bool,
     pub include_medications: bool,
+    #[serde(default)]
+    pub include_procedures: bool,
+    /// Observation statuses to export; preliminary and cancelled results are excluded by default
+    #[serde(default)]
+    pub observation_status_filter: ObservationStatusFilter,
+    /// Include mappings their source system retracted (excluded by default)
+    #[serde(default)]
+    pub include_retracted: bool,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
 }
@@ -391,6 +1302,10 @@ pub struct FhirBundleOutput {
     pub observations: Vec<Record>,
     pub conditions: Vec<Record>,
     pub medications: Vec<Record>,
+    pub procedures: Vec<Record>,
+    /// Jurisdiction tag of the exported patient
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
 }
 
 /// Export a patient's data as a FHIR bundle
@@ -403,6 +1318,7 @@ pub fn export_patient_bundle(input: ExportPatientBundleInput) -> ExternResult<Fh
         Permission::Export,
         input.is_emergency,
     )?;
+    let jurisdiction = require_jurisdiction_allows(&input.patient_hash, DataUse::Export)?;
 
     // Get all FHIR mappings for this patient
     let links = get_links(
@@ -412,19 +1328,30 @@ pub fn export_patient_bundle(input: ExportPatientBundleInput) -> ExternResult<Fh
     let mut observations: Vec<Record> = Vec::new();
     let mut conditions: Vec<Record> = Vec::new();
     let mut medications: Vec<Record> = Vec::new();
+    let mut procedures: Vec<Record> = Vec::new();
+    let mut sensitive = SensitiveFilter::new(&input.patient_hash, Permission::Export, input.is_emergency, &input.emergency_reason);
 
     for link in links {
         if let Some(hash) = link.target.into_action_hash() {
+            if !input.include_retracted && is_retracted(&hash)? {
+                continue;
+            }
             if let Some(record) = get(hash.clone(), GetOptions::default())? {
                 // Determine the type of mapping
                 if record.entry().to_app_option::<FhirPatientMapping>().ok().flatten().is_some() {
                     patient_mapping = Some(record);
-                } else if input.include_observations && record.entry().to_app_option::<FhirObservationMapping>().ok().flatten().is_some() {
-                    observations.push(record);
+                } else if !sensitive.allows(&record) {
+                    // Sensitive record without consent for its category
+                } else if let Some(observation) = record.entry().to_app_option::<FhirObservationMapping>().ok().flatten() {
+                    if input.include_observations && input.observation_status_filter.matches(&observation.status) {
+                        observations.push(record);
+                    }
                 } else if input.include_conditions && record.entry().to_app_option::<FhirConditionMapping>().ok().flatten().is_some() {
                     conditions.push(record);
                 } else if input.include_medications && record.entry().to_app_option::<FhirMedicationMapping>().ok().flatten().is_some() {
                     medications.push(record);
+                } else if input.include_procedures && record.entry().to_app_option::<FhirProcedureMapping>().ok().flatten().is_some() {
+                    procedures.push(record);
                 }
             }
         }
@@ -456,6 +1383,12 @@ pub fn export_patient_bundle(input: ExportPatientBundleInput) -> ExternResult<Fh
             count: medications.len() as u32,
         });
     }
+    if !procedures.is_empty() {
+        resource_summary.push(ResourceTypeSummary {
+            resource_type: "Procedure".to_string(),
+            count: procedures.len() as u32,
+        });
+    }
 
     let total = resource_summary.iter().map(|s| s.count).sum();
     let bundle = FhirBundleRecord {
@@ -467,6 +1400,7 @@ pub fn export_patient_bundle(input: ExportPatientBundleInput) -> ExternResult<Fh
         resource_summary,
         status: BundleStatus::Completed,
         errors: Vec::new(),
+        jurisdiction: jurisdiction.clone(),
     };
 
     let bundle_hash = create_entry(&EntryTypes::FhirBundleRecord(bundle))?;
@@ -497,6 +1431,8 @@ pub fn export_patient_bundle(input: ExportPatientBundleInput) -> ExternResult<Fh
         observations,
         conditions,
         medications,
+        procedures,
+        jurisdiction,
     })
 }
 
@@ -535,9 +1471,10 @@ pub fn import_fhir_bundle(input: ImportFhirBundleInput) -> ExternResult<ImportBu
     if let Some(patient_mapping) = input.patient_mapping {
         match create_entry(&EntryTypes::FhirPatientMapping(patient_mapping.clone())) {
             Ok(hash) => {
-                create_link(
+                track_mapping("FhirPatientMapping", &hash)?;
+                link_mapping(
                     patient_mapping.internal_patient_hash.clone(),
-                    hash.clone(),
+                    &hash,
                     LinkTypes::PatientToFhirMappings,
                     (),
                 )?;
@@ -551,9 +1488,10 @@ pub fn import_fhir_bundle(input: ImportFhirBundleInput) -> ExternResult<ImportBu
     for obs in input.observations {
         match create_entry(&EntryTypes::FhirObservationMapping(obs.clone())) {
             Ok(hash) => {
-                create_link(
+                track_mapping("FhirObservationMapping", &hash)?;
+                link_mapping(
                     obs.patient_hash.clone(),
-                    hash.clone(),
+                    &hash,
                     LinkTypes::PatientToFhirMappings,
                     (),
                 )?;
@@ -567,9 +1505,10 @@ pub fn import_fhir_bundle(input: ImportFhirBundleInput) -> ExternResult<ImportBu
     for cond in input.conditions {
         match create_entry(&EntryTypes::FhirConditionMapping(cond.clone())) {
             Ok(hash) => {
-                create_link(
+                track_mapping("FhirConditionMapping", &hash)?;
+                link_mapping(
                     cond.patient_hash.clone(),
-                    hash.clone(),
+                    &hash,
                     LinkTypes::PatientToFhirMappings,
                     (),
                 )?;
@@ -583,9 +1522,10 @@ pub fn import_fhir_bundle(input: ImportFhirBundleInput) -> ExternResult<ImportBu
     for med in input.medications {
         match create_entry(&EntryTypes::FhirMedicationMapping(med.clone())) {
             Ok(hash) => {
-                create_link(
+                track_mapping("FhirMedicationMapping", &hash)?;
+                link_mapping(
                     med.patient_hash.clone(),
-                    hash.clone(),
+                    &hash,
                     LinkTypes::PatientToFhirMappings,
                     (),
                 )?;
@@ -623,6 +1563,7 @@ pub fn import_fhir_bundle(input: ImportFhirBundleInput) -> ExternResult<ImportBu
         ],
         status,
         errors: errors.clone(),
+        jurisdiction: None,
     };
 
     let bundle_hash = create_entry(&EntryTypes::FhirBundleRecord(bundle))?;
@@ -661,67 +1602,52 @@ pub struct ValidateCodeInput {
 /// Validate a LOINC code
 #[hdk_extern]
 pub fn validate_loinc_code(input: ValidateCodeInput) -> ExternResult<Record> {
-    // Basic LOINC code format validation (NNNNN-N)
-    let is_valid = validate_loinc_format(&input.code);
-
-    let validation = TerminologyValidation {
-        code_system: "loinc".to_string(),
-        code: input.code.clone(),
-        display: input.display,
-        is_valid,
-        message: if is_valid {
-            Some("LOINC code format is valid".to_string())
-        } else {
-            Some("Invalid LOINC code format. Expected format: NNNNN-N".to_string())
-        },
-        validated_at: sys_time()?,
-    };
-
-    let hash = create_entry(&EntryTypes::TerminologyValidation(validation))?;
-    get(hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find validation record".to_string())))
+    record_code_validation(ClinicalCodeSystem::Loinc, "loinc", "LOINC", input)
 }
 
 /// Validate a SNOMED CT code
 #[hdk_extern]
 pub fn validate_snomed_code(input: ValidateCodeInput) -> ExternResult<Record> {
-    // Basic SNOMED code validation (numeric, typically 6-18 digits)
-    let is_valid = validate_snomed_format(&input.code);
-
-    let validation = TerminologyValidation {
-        code_system: "snomed".to_string(),
-        code: input.code.clone(),
-        display: input.display,
-        is_valid,
-        message: if is_valid {
-            Some("SNOMED CT code format is valid".to_string())
-        } else {
-            Some("Invalid SNOMED CT code format. Expected 6-18 digit numeric code".to_string())
-        },
-        validated_at: sys_time()?,
-    };
-
-    let hash = create_entry(&EntryTypes::TerminologyValidation(validation))?;
-    get(hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find validation record".to_string())))
+    record_code_validation(ClinicalCodeSystem::SnomedCt, "snomed", "SNOMED CT", input)
 }
 
 /// Validate an ICD-10 code
 #[hdk_extern]
 pub fn validate_icd10_code(input: ValidateCodeInput) -> ExternResult<Record> {
-    // Basic ICD-10 validation (letter followed by digits and optional decimal)
-    let is_valid = validate_icd10_format(&input.code);
+    record_code_validation(ClinicalCodeSystem::Icd10Cm, "icd10", "ICD-10", input)
+}
+
+/// Validate an RxNorm code
+#[hdk_extern]
+pub fn validate_rxnorm_code(input: ValidateCodeInput) -> ExternResult<Record> {
+    record_code_validation(ClinicalCodeSystem::RxNorm, "rxnorm", "RxNorm", input)
+}
+
+/// Validate a CPT code
+#[hdk_extern]
+pub fn validate_cpt_code(input: ValidateCodeInput) -> ExternResult<Record> {
+    record_code_validation(ClinicalCodeSystem::Cpt, "cpt", "CPT", input)
+}
+
+/// Check a code's syntax and record the outcome
+fn record_code_validation(
+    system: ClinicalCodeSystem,
+    code_system: &str,
+    label: &str,
+    input: ValidateCodeInput,
+) -> ExternResult<Record> {
+    let result = validate_code(system, &input.code);
+    let message = match result.errors.first() {
+        None => format!("{} code format is valid", label),
+        Some(error) => error.message.clone(),
+    };
 
     let validation = TerminologyValidation {
-        code_system: "icd10".to_string(),
-        code: input.code.clone(),
+        code_system: code_system.to_string(),
+        code: input.code,
         display: input.display,
-        is_valid,
-        message: if is_valid {
-            Some("ICD-10 code format is valid".to_string())
-        } else {
-            Some("Invalid ICD-10 code format. Expected format: A00.0 or A00".to_string())
-        },
+        is_valid: result.is_valid(),
+        message: Some(message),
         validated_at: sys_time()?,
     };
 
@@ -730,73 +1656,414 @@ pub fn validate_icd10_code(input: ValidateCodeInput) -> ExternResult<Record> {
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find validation record".to_string())))
 }
 
-/// Validate an RxNorm code
+/// Reject a mapping whose codes are malformed for their code system
+///
+/// Codings from systems `validate_code` does not know are left alone, as is
+/// the "unknown" placeholder the FHIR bridge stores for uncoded resources.
+fn require_valid_codes(concepts: &[&FhirCodeableConcept], codes: &[(ClinicalCodeSystem, Option<&str>)]) -> ExternResult<()> {
+    let codings = concepts
+        .iter()
+        .flat_map(|concept| &concept.coding)
+        .filter_map(|coding| ClinicalCodeSystem::from_system(&coding.system).map(|system| (system, coding.code.as_str())));
+    let fields = codes.iter().filter_map(|(system, code)| code.map(|code| (*system, code)));
+    for (system, code) in codings.chain(fields).filter(|(_, code)| *code != "unknown") {
+        validate_code(system, code).into_result()?;
+    }
+    Ok(())
+}
+
+// ============================================================================
+// Upstream Retractions
+// ============================================================================
+
+/// Input for retracting a mapping its source system withdrew
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RetractFhirMappingInput {
+    pub mapping_hash: ActionHash,
+    pub resource_type: String,
+    pub fhir_id: String,
+    /// Must be the source system the mapping was ingested from
+    pub source_system: String,
+    pub trigger: RetractionTrigger,
+    pub reason: Option<String>,
+}
+
+/// Record that a source system withdrew a mapping
+///
+/// The mapping stays on the DHT for audit; exports and observation listings
+/// leave it out from then on. Only the source a mapping came from can retract
+/// it, and retracting twice returns the first retraction.
 #[hdk_extern]
-pub fn validate_rxnorm_code(input: ValidateCodeInput) -> ExternResult<Record> {
-    // Basic RxNorm validation (numeric code)
-    let is_valid = input.code.chars().all(|c| c.is_ascii_digit()) && !input.code.is_empty();
+pub fn retract_fhir_mapping(input: RetractFhirMappingInput) -> ExternResult<Record> {
+    let record = get(input.mapping_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Mapping not found".to_string())))?;
+    let (patient_hash, source_system) = mapping_origin(&record)
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Only clinical mappings can be retracted".to_string())))?;
+    if source_system != input.source_system {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Mapping was ingested from {}, not {}",
+            source_system, input.source_system
+        ))));
+    }
 
-    let validation = TerminologyValidation {
-        code_system: "rxnorm".to_string(),
-        code: input.code.clone(),
-        display: input.display,
-        is_valid,
-        message: if is_valid {
-            Some("RxNorm code format is valid".to_string())
-        } else {
-            Some("Invalid RxNorm code format. Expected numeric code".to_string())
-        },
-        validated_at: sys_time()?,
+    let auth = require_authorization(
+        patient_hash.clone(),
+        DataCategory::All,
+        Permission::Amend,
+        false,
+    )?;
+
+    if let Some(existing) = mapping_retraction(&input.mapping_hash)? {
+        return Ok(existing);
+    }
+
+    let retraction = FhirMappingRetraction {
+        mapping_hash: input.mapping_hash.clone(),
+        patient_hash: patient_hash.clone(),
+        resource_type: input.resource_type.clone(),
+        fhir_id: input.fhir_id,
+        source_system: input.source_system,
+        trigger: input.trigger,
+        reason: input.reason,
+        retracted_at: sys_time()?,
     };
+    let hash = create_entry(&EntryTypes::FhirMappingRetraction(retraction))?;
+
+    create_link(
+        input.mapping_hash,
+        hash.clone(),
+        LinkTypes::MappingToRetraction,
+        (),
+    )?;
+    create_link(
+        patient_hash.clone(),
+        hash.clone(),
+        LinkTypes::PatientToRetractions,
+        LinkTag::new(input.resource_type.as_bytes().to_vec()),
+    )?;
+
+    log_data_access(
+        patient_hash,
+        vec![DataCategory::All],
+        Permission::Amend,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
 
-    let hash = create_entry(&EntryTypes::TerminologyValidation(validation))?;
     get(hash, GetOptions::default())?
-        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find validation record".to_string())))
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find retraction".to_string())))
+}
+
+/// Retraction audit trail for a patient's mappings, oldest first
+#[hdk_extern]
+pub fn get_patient_retractions(patient_hash: ActionHash) -> ExternResult<Vec<Record>> {
+    let auth = require_authorization(
+        patient_hash.clone(),
+        DataCategory::All,
+        Permission::Read,
+        false,
+    )?;
+
+    let links = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToRetractions)?, GetStrategy::default())?;
+    let mut retractions = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                retractions.push(record);
+            }
+        }
+    }
+    retractions.sort_by_key(|record| record.action().timestamp());
+
+    log_data_access(
+        patient_hash,
+        vec![DataCategory::All],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(retractions)
+}
+
+/// Patient and source system of any kind of clinical mapping
+fn mapping_origin(record: &Record) -> Option<(ActionHash, String)> {
+    let entry = record.entry();
+    if let Some(m) = entry.to_app_option::<FhirObservationMapping>().ok().flatten() {
+        Some((m.patient_hash, m.source_system))
+    } else if let Some(m) = entry.to_app_option::<FhirConditionMapping>().ok().flatten() {
+        Some((m.patient_hash, m.source_system))
+    } else if let Some(m) = entry.to_app_option::<FhirMedicationMapping>().ok().flatten() {
+        Some((m.patient_hash, m.source_system))
+    } else {
+        entry
+            .to_app_option::<FhirProcedureMapping>()
+            .ok()
+            .flatten()
+            .map(|m| (m.patient_hash, m.source_system))
+    }
+}
+
+fn mapping_retraction(mapping_hash: &ActionHash) -> ExternResult<Option<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(mapping_hash.clone(), LinkTypes::MappingToRetraction)?, GetStrategy::default())?;
+    match links.into_iter().find_map(|link| link.target.into_action_hash()) {
+        Some(hash) => get(hash, GetOptions::default()),
+        None => Ok(None),
+    }
+}
+
+fn is_retracted(mapping_hash: &ActionHash) -> ExternResult<bool> {
+    let links = get_links(
+        LinkQuery::try_new(mapping_hash.clone(), LinkTypes::MappingToRetraction)?, GetStrategy::default())?;
+    Ok(!links.is_empty())
 }
 
 // ============================================================================
-// Helper Functions
+// Reference Tracking
 // ============================================================================
 
-fn validate_loinc_format(code: &str) -> bool {
-    // LOINC format: NNNNN-N (5+ digits, dash, check digit)
-    let parts: Vec<&str> = code.split('-').collect();
-    if parts.len() != 2 {
-        return false;
+/// Mapping entry types kept in the reference registry
+const TRACKED_MAPPING_TYPES: &[&str] = &[
+    "FhirPatientMapping",
+    "FhirObservationMapping",
+    "FhirConditionMapping",
+    "FhirMedicationMapping",
+    "FhirProcedureMapping",
+];
+
+/// Orphans listed per call when no limit is given
+const DEFAULT_ORPHAN_LIMIT: u32 = 50;
+
+fn track_mapping(entry_type: &str, mapping_hash: &ActionHash) -> ExternResult<()> {
+    register_tracked_entry(entry_type, mapping_hash.clone(), LinkTypes::MappingRegistry)?;
+    Ok(())
+}
+
+/// Link into a mapping, recording the back-reference that counts it
+fn link_mapping(
+    base: impl Into<AnyLinkableHash>,
+    mapping_hash: &ActionHash,
+    link_type: LinkTypes,
+    tag: impl Into<LinkTag>,
+) -> ExternResult<ActionHash> {
+    create_tracked_link(base, mapping_hash.clone(), link_type, tag, LinkTypes::MappingReferrers)
+}
+
+fn require_tracked_type(entry_type: &str) -> ExternResult<()> {
+    if TRACKED_MAPPING_TYPES.contains(&entry_type) {
+        Ok(())
+    } else {
+        Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "{} is not reference-tracked; expected one of {}",
+            entry_type,
+            TRACKED_MAPPING_TYPES.join(", ")
+        ))))
+    }
+}
+
+fn registry_links(entry_type: &str) -> ExternResult<Vec<Link>> {
+    get_links(
+        LinkQuery::try_new(reference_registry_anchor(entry_type)?, LinkTypes::MappingRegistry)?, GetStrategy::default())
+}
+
+/// Input for listing orphaned mappings of one type
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FindOrphanedEntriesInput {
+    pub entry_type: String,
+    pub limit: Option<u32>,
+}
+
+/// A tracked mapping that no patient or clinical record reaches any more
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct OrphanedEntry {
+    pub entry_hash: ActionHash,
+    pub entry_type: String,
+    /// Index links (source system, procedure code) that still reach it
+    pub index_references: Vec<EntryReference>,
+}
+
+/// List tracked mappings whose owning patient and clinical records are all
+/// gone, e.g. after the patient was deleted
+///
+/// Mappings created before reference tracking are not in the registry and
+/// are never listed. Operator only.
+#[hdk_extern]
+pub fn find_orphaned_entries(input: FindOrphanedEntriesInput) -> ExternResult<Vec<OrphanedEntry>> {
+    require_operator()?;
+    require_tracked_type(&input.entry_type)?;
+    let limit = input.limit.unwrap_or(DEFAULT_ORPHAN_LIMIT) as usize;
+
+    let mut orphans = Vec::new();
+    for link in registry_links(&input.entry_type)? {
+        if orphans.len() >= limit {
+            break;
+        }
+        let Some(entry_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let references = entry_references(&entry_hash, LinkTypes::MappingReferrers)?;
+        if references.iter().any(|reference| reference.owning) {
+            continue;
+        }
+        orphans.push(OrphanedEntry {
+            entry_hash,
+            entry_type: input.entry_type.clone(),
+            index_references: references,
+        });
     }
-    parts[0].len() >= 3 && parts[0].chars().all(|c| c.is_ascii_digit())
-        && parts[1].len() == 1 && parts[1].chars().all(|c| c.is_ascii_digit())
+    Ok(orphans)
+}
+
+/// Input for pruning one orphaned mapping
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PruneOrphanedEntryInput {
+    pub entry_type: String,
+    pub entry_hash: ActionHash,
 }
 
-fn validate_snomed_format(code: &str) -> bool {
-    // SNOMED codes are numeric, typically 6-18 digits
-    code.len() >= 6 && code.len() <= 18 && code.chars().all(|c| c.is_ascii_digit())
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PruneResult {
+    pub entry_hash: ActionHash,
+    pub delete_action: ActionHash,
+    pub index_links_removed: u32,
 }
 
-fn validate_icd10_format(code: &str) -> bool {
-    // ICD-10 format: Letter + 2 digits, optionally followed by decimal and more digits
-    if code.is_empty() {
-        return false;
+/// Delete an orphaned mapping along with the index links that still reach it
+///
+/// References are re-read before anything is deleted, so a mapping that
+/// gained an owner since it was listed is refused. Operator only.
+#[hdk_extern]
+pub fn prune_orphaned_entry(input: PruneOrphanedEntryInput) -> ExternResult<PruneResult> {
+    require_operator()?;
+    require_tracked_type(&input.entry_type)?;
+    let registry_link = registry_links(&input.entry_type)?
+        .into_iter()
+        .find(|link| link.target.clone().into_action_hash().as_ref() == Some(&input.entry_hash))
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!(
+            "Entry is not in the {} reference registry",
+            input.entry_type
+        ))))?;
+
+    let references = entry_references(&input.entry_hash, LinkTypes::MappingReferrers)?;
+    if let Some(owner) = references.iter().find(|reference| reference.owning) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Entry is still referenced through {}",
+            owner.link_type
+        ))));
     }
-    let chars: Vec<char> = code.chars().collect();
-    if !chars[0].is_ascii_alphabetic() {
-        return false;
+
+    for reference in &references {
+        delete_tracked_link(&input.entry_hash, &reference.forward_link, LinkTypes::MappingReferrers)?;
     }
-    if chars.len() < 3 {
-        return false;
+    delete_link(registry_link.create_link_hash, GetOptions::default())?;
+    let delete_action = delete_entry(input.entry_hash.clone())?;
+
+    Ok(PruneResult {
+        entry_hash: input.entry_hash,
+        delete_action,
+        index_links_removed: references.len() as u32,
+    })
+}
+
+// ============================================================================
+// Sensitive Category Enforcement
+// ============================================================================
+
+/// Require consent for each sensitive category a mapping carries, on top of
+/// the category its resource type needs
+fn require_sensitive_access(
+    patient_hash: &ActionHash,
+    categories: &[DataCategory],
+    permission: Permission,
+    is_emergency: bool,
+    emergency_reason: Option<String>,
+) -> ExternResult<()> {
+    for category in categories {
+        let auth = require_authorization(patient_hash.clone(), category.clone(), permission.clone(), is_emergency)?;
+        log_data_access(
+            patient_hash.clone(),
+            vec![category.clone()],
+            permission.clone(),
+            auth.consent_hash,
+            auth.emergency_override,
+            emergency_reason.clone(),
+        )?;
     }
-    // Check remaining characters are digits or decimal point
-    for (i, c) in chars.iter().enumerate().skip(1) {
-        if i == 3 && *c == '.' {
-            continue;
+    Ok(())
+}
+
+/// Sensitive categories recorded on any kind of clinical mapping
+fn record_sensitive_categories(record: &Record) -> Vec<DataCategory> {
+    let entry = record.entry();
+    if let Some(m) = entry.to_app_option::<FhirObservationMapping>().ok().flatten() {
+        m.sensitive_categories
+    } else if let Some(m) = entry.to_app_option::<FhirConditionMapping>().ok().flatten() {
+        m.sensitive_categories
+    } else if let Some(m) = entry.to_app_option::<FhirMedicationMapping>().ok().flatten() {
+        m.sensitive_categories
+    } else if let Some(m) = entry.to_app_option::<FhirProcedureMapping>().ok().flatten() {
+        m.sensitive_categories
+    } else {
+        Vec::new()
+    }
+}
+
+/// Decides, once per category, which sensitive records a listing may include
+///
+/// Listings leave out records the caller has no consent for instead of
+/// failing, so a broad grant still returns the non-sensitive record.
+struct SensitiveFilter {
+    patient_hash: ActionHash,
+    permission: Permission,
+    is_emergency: bool,
+    emergency_reason: Option<String>,
+    decided: Vec<(DataCategory, bool)>,
+}
+
+impl SensitiveFilter {
+    fn new(patient_hash: &ActionHash, permission: Permission, is_emergency: bool, emergency_reason: &Option<String>) -> Self {
+        Self {
+            patient_hash: patient_hash.clone(),
+            permission,
+            is_emergency,
+            emergency_reason: emergency_reason.clone(),
+            decided: Vec::new(),
         }
-        if !c.is_ascii_digit() {
-            return false;
+    }
+
+    fn allows(&mut self, record: &Record) -> bool {
+        for category in record_sensitive_categories(record) {
+            let allowed = match self.decided.iter().find(|(c, _)| *c == category) {
+                Some((_, allowed)) => *allowed,
+                None => {
+                    let allowed = require_sensitive_access(
+                        &self.patient_hash,
+                        std::slice::from_ref(&category),
+                        self.permission.clone(),
+                        self.is_emergency,
+                        self.emergency_reason.clone(),
+                    )
+                    .is_ok();
+                    self.decided.push((category, allowed));
+                    allowed
+                }
+            };
+            if !allowed {
+                return false;
+            }
         }
+        true
     }
-    true
 }
 
+// ============================================================================
+// Helper Functions
+// ============================================================================
+
 // ============================================================================
 // Sync Status Updates
 // ============================================================================
@@ -840,3 +2107,45 @@ pub fn update_patient_mapping_sync_status(input: UpdateSyncStatusInput) -> Exter
 
     Ok(updated_record)
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_fhir_patient_mapping", "FhirPatientMapping", "Record"),
+        ("get_fhir_patient_mapping", "GetFhirMappingInput", "Option<Record>"),
+        ("get_patient_fhir_mappings", "GetPatientFhirMappingsInput", "BudgetedPage<Record>"),
+        ("create_fhir_observation_mapping", "FhirObservationMapping", "Record"),
+        ("get_fhir_observation_mapping", "GetFhirMappingInput", "Option<Record>"),
+        ("get_patient_observation_mappings", "GetPatientObservationsInput", "BudgetedPage<Record>"),
+        ("update_fhir_observation_status", "UpdateObservationStatusInput", "Record"),
+        ("create_fhir_condition_mapping", "FhirConditionMapping", "Record"),
+        ("get_fhir_condition_mapping", "GetFhirMappingInput", "Option<Record>"),
+        ("create_fhir_medication_mapping", "FhirMedicationMapping", "Record"),
+        ("get_fhir_medication_mapping", "GetFhirMappingInput", "Option<Record>"),
+        ("create_fhir_procedure_mapping", "FhirProcedureMapping", "Record"),
+        ("get_fhir_procedure_mapping", "GetFhirMappingInput", "Option<Record>"),
+        ("get_procedures_by_code", "GetProceduresByCodeInput", "Vec<Record>"),
+        ("export_fhir_procedure", "GetFhirMappingInput", "serde_json::Value"),
+        ("export_fhir_patient", "GetFhirMappingInput", "serde_json::Value"),
+        ("get_patient_record_as_of", "GetPatientRecordAsOfInput", "PatientRecordAsOf"),
+        ("get_problem_list", "GetPatientConditionsInput", "Vec<ProblemListItem>"),
+        ("get_condition_episodes", "GetPatientConditionsInput", "Vec<ConditionEpisode>"),
+        ("export_patient_bundle", "ExportPatientBundleInput", "FhirBundleOutput"),
+        ("import_fhir_bundle", "ImportFhirBundleInput", "ImportBundleResult"),
+        ("validate_loinc_code", "ValidateCodeInput", "Record"),
+        ("validate_snomed_code", "ValidateCodeInput", "Record"),
+        ("validate_icd10_code", "ValidateCodeInput", "Record"),
+        ("validate_rxnorm_code", "ValidateCodeInput", "Record"),
+        ("validate_cpt_code", "ValidateCodeInput", "Record"),
+        ("retract_fhir_mapping", "RetractFhirMappingInput", "Record"),
+        ("get_patient_retractions", "ActionHash", "Vec<Record>"),
+        ("find_orphaned_entries", "FindOrphanedEntriesInput", "Vec<OrphanedEntry>"),
+        ("prune_orphaned_entry", "PruneOrphanedEntryInput", "PruneResult"),
+        ("update_patient_mapping_sync_status", "UpdateSyncStatusInput", "Record"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["observation_status_lifecycle", "procedure_mapping", "bitemporal_records", "fhir_extensions", "upstream_retractions", "jurisdiction_residency", "reference_tracking", "condition_onsets", "response_budgets", "code_syntax_validation"],
+}
@@ -9,9 +9,20 @@
 //! - Observation resource mapping (vital signs, lab results)
 //! - Condition resource mapping (diagnoses)
 //! - Medication resource mapping
+//! - Procedure resource mapping (CPT/SNOMED, performers, outcomes)
+//! - Extension preservation, with US Core demographics as typed fields
+//! - Retractions of mappings whose source resource was deleted upstream
 //! - Bundle operations for bulk data exchange
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
+use mycelix_health_shared::DataCategory;
+pub use mycelix_health_shared::{FhirExtension, UsCoreDemographics};
+pub use mycelix_health_shared::{InferredOnset, NormalizedOnset, OnsetBasis, OnsetConfidence};
+use mycelix_health_shared::normalized_onset;
+use mycelix_health_shared::validation::ucum;
 
 // ============================================================================
 // FHIR Common Types
@@ -203,6 +214,12 @@ pub struct FhirPatientMapping {
     pub sync_status: SyncStatus,
     /// Any sync error messages
     pub sync_errors: Vec<String>,
+    /// Extensions from the source resource, re-emitted on export
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+    /// US Core race, ethnicity and birth sex parsed from `extensions`
+    #[serde(default)]
+    pub us_core: UsCoreDemographics,
 }
 
 /// Mapping between internal medical record and FHIR Observation resource
@@ -231,7 +248,9 @@ pub struct FhirObservationMapping {
     pub value_quantity: Option<FhirQuantity>,
     /// Observation value (codeable concept)
     pub value_codeable_concept: Option<FhirCodeableConcept>,
-    /// Observation value (string)
+    /// Observation value (string); holds the source resource JSON for
+    /// bridged non-observation resources, so it is compressed at rest
+    #[serde(with = "mycelix_health_shared::compressed")]
     pub value_string: Option<String>,
     /// Observation value (boolean)
     pub value_boolean: Option<bool>,
@@ -249,6 +268,92 @@ pub struct FhirObservationMapping {
     pub mapping_version: String,
     /// Last synced
     pub last_synced: Timestamp,
+    /// When the clinical event happened
+    #[serde(default)]
+    pub effective_time: Option<Timestamp>,
+    /// When this version entered the record (first recorded or corrected)
+    #[serde(default)]
+    pub recorded_time: Option<Timestamp>,
+    /// Sensitive categories found in the resource's codes; reads need consent for each
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    /// Extensions from the source resource, re-emitted on export
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+}
+
+/// FHIR Observation status lifecycle
+///
+/// Mirrors the R4 `ObservationStatus` value set. Mappings persist the raw
+/// FHIR code in `FhirObservationMapping::status`; this enum is used to parse
+/// it and to enforce allowable transitions when a mapping is updated.
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ObservationStatus {
+    Registered,
+    Preliminary,
+    Final,
+    Amended,
+    Corrected,
+    Cancelled,
+    EnteredInError,
+    Unknown,
+}
+
+impl ObservationStatus {
+    /// Parse a FHIR status code (e.g. "entered-in-error")
+    pub fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "registered" => Some(Self::Registered),
+            "preliminary" => Some(Self::Preliminary),
+            "final" => Some(Self::Final),
+            "amended" => Some(Self::Amended),
+            "corrected" => Some(Self::Corrected),
+            "cancelled" => Some(Self::Cancelled),
+            "entered-in-error" => Some(Self::EnteredInError),
+            "unknown" => Some(Self::Unknown),
+            _ => None,
+        }
+    }
+
+    /// FHIR status code for this status
+    pub fn as_code(&self) -> &'static str {
+        match self {
+            Self::Registered => "registered",
+            Self::Preliminary => "preliminary",
+            Self::Final => "final",
+            Self::Amended => "amended",
+            Self::Corrected => "corrected",
+            Self::Cancelled => "cancelled",
+            Self::EnteredInError => "entered-in-error",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Whether the result is complete and verified.
+    ///
+    /// Only reportable results feed derived models (e.g. the health twin)
+    /// and are included in exports by default.
+    pub fn is_reportable(&self) -> bool {
+        matches!(self, Self::Final | Self::Amended | Self::Corrected)
+    }
+
+    /// Whether an observation may move from this status to `next`
+    pub fn can_transition_to(&self, next: &ObservationStatus) -> bool {
+        use ObservationStatus::*;
+        match self {
+            // Unknown status from a source system can be resolved to anything
+            Unknown => true,
+            Registered => !matches!(next, Unknown),
+            Preliminary => matches!(
+                next,
+                Preliminary | Final | Amended | Corrected | Cancelled | EnteredInError
+            ),
+            Final | Amended | Corrected => matches!(next, Amended | Corrected | EnteredInError),
+            Cancelled => matches!(next, Cancelled | EnteredInError),
+            // Entered-in-error is terminal
+            EnteredInError => matches!(next, EnteredInError),
+        }
+    }
 }
 
 /// Reference range for observations
@@ -294,7 +399,7 @@ pub struct FhirConditionMapping {
     pub snomed_code: Option<String>,
     /// Body site affected
     pub body_site: Vec<FhirCodeableConcept>,
-    /// Onset datetime or age
+    /// Onset datetime or age, as asserted by the source
     pub onset_datetime: Option<Timestamp>,
     /// Abatement (resolution) datetime
     pub abatement_datetime: Option<Timestamp>,
@@ -310,6 +415,21 @@ pub struct FhirConditionMapping {
     pub mapping_version: String,
     /// Last synced
     pub last_synced: Timestamp,
+    /// When the clinical event happened
+    #[serde(default)]
+    pub effective_time: Option<Timestamp>,
+    /// When this version entered the record (first recorded or corrected)
+    #[serde(default)]
+    pub recorded_time: Option<Timestamp>,
+    /// Sensitive categories found in the resource's codes; reads need consent for each
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    /// Extensions from the source resource, re-emitted on export
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+    /// Onset estimated at ingest when the source asserts none
+    #[serde(default)]
+    pub inferred_onset: Option<InferredOnset>,
 }
 
 /// Mapping between internal medication and FHIR MedicationRequest resource
@@ -354,6 +474,165 @@ pub struct FhirMedicationMapping {
     pub mapping_version: String,
     /// Last synced
     pub last_synced: Timestamp,
+    /// When the clinical event happened
+    #[serde(default)]
+    pub effective_time: Option<Timestamp>,
+    /// When this version entered the record (first recorded or corrected)
+    #[serde(default)]
+    pub recorded_time: Option<Timestamp>,
+    /// Sensitive categories found in the resource's codes; reads need consent for each
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    /// Extensions from the source resource, re-emitted on export
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+}
+
+/// Bi-temporal stamps of a clinical fact: when it happened versus when it
+/// became part of the record
+#[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BiTemporal {
+    pub effective_time: Timestamp,
+    pub recorded_time: Timestamp,
+}
+
+impl BiTemporal {
+    /// Whether this version was part of the record at `as_of`
+    pub fn known_at(&self, as_of: Timestamp) -> bool {
+        self.recorded_time <= as_of
+    }
+}
+
+/// Mappings that carry bi-temporal stamps
+///
+/// Mappings written before `effective_time`/`recorded_time` existed fall
+/// back to their FHIR date fields, then to `last_synced`.
+pub trait BiTemporalMapping {
+    fn bitemporal(&self) -> BiTemporal;
+}
+
+impl BiTemporalMapping for FhirObservationMapping {
+    fn bitemporal(&self) -> BiTemporal {
+        BiTemporal {
+            effective_time: self.effective_time.unwrap_or(self.effective_datetime),
+            recorded_time: self.recorded_time.or(self.issued).unwrap_or(self.last_synced),
+        }
+    }
+}
+
+impl BiTemporalMapping for FhirConditionMapping {
+    fn bitemporal(&self) -> BiTemporal {
+        let recorded_time = self.recorded_time.or(self.recorded_date).unwrap_or(self.last_synced);
+        BiTemporal {
+            effective_time: self
+                .effective_time
+                .or(self.normalized_onset().at)
+                .unwrap_or(recorded_time),
+            recorded_time,
+        }
+    }
+}
+
+impl FhirConditionMapping {
+    /// The asserted onset, else the inferred one
+    pub fn normalized_onset(&self) -> NormalizedOnset {
+        normalized_onset(self.onset_datetime, self.inferred_onset.as_ref())
+    }
+}
+
+impl BiTemporalMapping for FhirMedicationMapping {
+    fn bitemporal(&self) -> BiTemporal {
+        let recorded_time = self.recorded_time.or(self.authored_on).unwrap_or(self.last_synced);
+        BiTemporal {
+            effective_time: self.effective_time.or(self.authored_on).unwrap_or(recorded_time),
+            recorded_time,
+        }
+    }
+}
+
+/// Valid FHIR R4 Procedure.status codes
+pub const PROCEDURE_STATUSES: [&str; 8] = [
+    "preparation", "in-progress", "not-done", "on-hold", "stopped", "completed", "entered-in-error", "unknown",
+];
+
+/// Who performed a procedure and in what role
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ProcedurePerformer {
+    /// Practitioner, organization or device that performed the procedure
+    pub actor: FhirReference,
+    /// Role played (e.g., primary surgeon, anesthesiologist)
+    pub function: Option<FhirCodeableConcept>,
+}
+
+/// Mapping between internal procedure and FHIR Procedure resource
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FhirProcedureMapping {
+    /// Internal Mycelix procedure hash, if the procedure was recorded locally
+    pub internal_procedure_hash: Option<ActionHash>,
+    /// Patient the procedure was performed on
+    pub patient_hash: ActionHash,
+    /// FHIR Procedure resource ID
+    pub fhir_procedure_id: String,
+    /// Source system identifier
+    pub source_system: String,
+    /// Procedure status (see PROCEDURE_STATUSES)
+    pub status: String,
+    /// Procedure code
+    pub code: FhirCodeableConcept,
+    /// CPT code for billing
+    pub cpt_code: Option<String>,
+    /// SNOMED CT code for quality measures
+    pub snomed_code: Option<String>,
+    /// When the procedure was performed (ISO 8601)
+    pub performed_datetime: Option<String>,
+    /// Period for procedures spanning time
+    pub performed_period: Option<FhirPeriod>,
+    /// Who performed the procedure
+    pub performers: Vec<ProcedurePerformer>,
+    /// Anatomical locations
+    pub body_site: Vec<FhirCodeableConcept>,
+    /// Coded outcome (e.g., successful, partially successful)
+    pub outcome: Option<FhirCodeableConcept>,
+    /// Complications that occurred
+    pub complications: Vec<FhirCodeableConcept>,
+    /// Complication flag for quality reporting; must be set when complications are listed
+    pub has_complications: bool,
+    /// Why the procedure was performed
+    pub reason_code: Vec<FhirCodeableConcept>,
+    /// Encounter the procedure was part of
+    pub encounter_reference: Option<FhirReference>,
+    /// Clinical notes
+    pub note: Vec<String>,
+    /// Source resource JSON as received
+    #[serde(with = "mycelix_health_shared::compressed")]
+    pub source_resource: Option<String>,
+    /// Mapping version
+    pub mapping_version: String,
+    /// Last synced
+    pub last_synced: Timestamp,
+    /// Sensitive categories found in the resource's codes; reads need consent for each
+    #[serde(default)]
+    pub sensitive_categories: Vec<DataCategory>,
+    /// Extensions from the source resource, re-emitted on export
+    #[serde(default)]
+    pub extensions: Vec<FhirExtension>,
+}
+
+/// Check a CPT code: five digits (Category I), or four digits followed by
+/// F (Category II) or T (Category III)
+pub fn is_valid_cpt_code(code: &str) -> bool {
+    let bytes = code.as_bytes();
+    bytes.len() == 5
+        && bytes[..4].iter().all(|b| b.is_ascii_digit())
+        && (bytes[4].is_ascii_digit() || bytes[4] == b'F' || bytes[4] == b'T')
+}
+
+/// Check a SNOMED CT concept ID: 6-18 digits without a leading zero
+pub fn is_valid_snomed_code(code: &str) -> bool {
+    (6..=18).contains(&code.len())
+        && code.chars().all(|c| c.is_ascii_digit())
+        && !code.starts_with('0')
 }
 
 /// FHIR Bundle for bulk data operations
@@ -375,7 +654,11 @@ pub struct FhirBundleRecord {
     /// Bundle status
     pub status: BundleStatus,
     /// Processing errors
+    #[serde(with = "mycelix_health_shared::compressed")]
     pub errors: Vec<String>,
+    /// Jurisdiction of the patient whose data left in this bundle
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
 }
 
 /// Summary of resource types in a bundle
@@ -404,6 +687,38 @@ pub enum SyncStatus {
     Error,
 }
 
+/// How the source system withdrew a resource
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+pub enum RetractionTrigger {
+    /// A bundle entry with `request.method` DELETE
+    Deleted,
+    /// The resource was re-sent with an entered-in-error status
+    EnteredInError,
+}
+
+/// Audit record of a mapping withdrawn by its source system
+///
+/// The mapping itself is left untouched; a retraction linked from it keeps
+/// it out of exports and derived models unless callers ask for it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FhirMappingRetraction {
+    /// Mapping that was retracted
+    pub mapping_hash: ActionHash,
+    /// Patient the mapping belongs to
+    pub patient_hash: ActionHash,
+    /// FHIR resource type (e.g. "Observation")
+    pub resource_type: String,
+    /// FHIR resource ID in the source system
+    pub fhir_id: String,
+    /// Source system that withdrew the resource
+    pub source_system: String,
+    pub trigger: RetractionTrigger,
+    /// Reason given by the source, if any
+    pub reason: Option<String>,
+    pub retracted_at: Timestamp,
+}
+
 /// Terminology validation result
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -433,8 +748,10 @@ pub enum EntryTypes {
     FhirObservationMapping(FhirObservationMapping),
     FhirConditionMapping(FhirConditionMapping),
     FhirMedicationMapping(FhirMedicationMapping),
+    FhirProcedureMapping(FhirProcedureMapping),
     FhirBundleRecord(FhirBundleRecord),
     TerminologyValidation(TerminologyValidation),
+    FhirMappingRetraction(FhirMappingRetraction),
 }
 
 #[hdk_link_types]
@@ -457,18 +774,55 @@ pub enum LinkTypes {
     BundleToEntries,
     /// Updates tracking
     FhirMappingUpdates,
+    /// Internal procedure to FHIR procedure
+    ProcedureToFhirMapping,
+    /// Patient to procedure mappings, tagged "cpt:<code>" or "snomed:<code>"
+    PatientToProcedureCodes,
+    /// Mapping to the retraction withdrawing it
+    MappingToRetraction,
+    /// Patient to the retractions of their mappings
+    PatientToRetractions,
+    /// Mapping back to the base of each link that reaches it; the tag names
+    /// the forward link
+    MappingReferrers,
+    /// Per-type registry anchor to every reference-tracked mapping
+    MappingRegistry,
 }
 
 // ============================================================================
 // Validation Functions
 // ============================================================================
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("text", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => validate_create_entry(app_entry),
-            OpEntry::UpdateEntry { app_entry, .. } => validate_create_entry(app_entry),
+            OpEntry::UpdateEntry { original_action_hash, app_entry, .. } => {
+                if let EntryTypes::FhirMappingRetraction(_) = &app_entry {
+                    return Ok(ValidateCallbackResult::Invalid(
+                        "Retractions cannot be updated".to_string(),
+                    ));
+                }
+                if let EntryTypes::FhirObservationMapping(mapping) = &app_entry {
+                    let transition = validate_observation_status_transition(&original_action_hash, mapping)?;
+                    if !matches!(transition, ValidateCallbackResult::Valid) {
+                        return Ok(transition);
+                    }
+                }
+                validate_create_entry(app_entry)
+            }
             _ => Ok(ValidateCallbackResult::Valid),
         },
         FlatOp::RegisterCreateLink { link_type, .. } => validate_link(link_type),
@@ -482,8 +836,10 @@ fn validate_create_entry(entry: EntryTypes) -> ExternResult<ValidateCallbackResu
         EntryTypes::FhirObservationMapping(mapping) => validate_fhir_observation_mapping(&mapping),
         EntryTypes::FhirConditionMapping(mapping) => validate_fhir_condition_mapping(&mapping),
         EntryTypes::FhirMedicationMapping(mapping) => validate_fhir_medication_mapping(&mapping),
+        EntryTypes::FhirProcedureMapping(mapping) => validate_fhir_procedure_mapping(&mapping),
         EntryTypes::FhirBundleRecord(bundle) => validate_fhir_bundle(&bundle),
         EntryTypes::TerminologyValidation(validation) => validate_terminology_validation(&validation),
+        EntryTypes::FhirMappingRetraction(retraction) => validate_retraction(&retraction),
     }
 }
 
@@ -516,9 +872,30 @@ fn validate_fhir_patient_mapping(mapping: &FhirPatientMapping) -> ExternResult<V
         ));
     }
 
+    if let Some(invalid) = validate_extensions(&mapping.extensions) {
+        return Ok(invalid);
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
+/// A fact cannot take effect after it was recorded
+fn validate_bitemporal(effective_time: Option<Timestamp>, recorded_time: Option<Timestamp>) -> Option<ValidateCallbackResult> {
+    match (effective_time, recorded_time) {
+        (Some(effective), Some(recorded)) if effective > recorded => Some(ValidateCallbackResult::Invalid(
+            "Effective time cannot be later than recorded time".to_string(),
+        )),
+        _ => None,
+    }
+}
+
+/// Preserved extensions must still be the elements they claim to be
+fn validate_extensions(extensions: &[FhirExtension]) -> Option<ValidateCallbackResult> {
+    extensions.iter().find(|e| !e.is_well_formed()).map(|e| {
+        ValidateCallbackResult::Invalid(format!("Malformed FHIR extension: {}", e.url))
+    })
+}
+
 fn validate_fhir_observation_mapping(mapping: &FhirObservationMapping) -> ExternResult<ValidateCallbackResult> {
     // Validate FHIR observation ID
     if mapping.fhir_observation_id.is_empty() {
@@ -535,10 +912,9 @@ fn validate_fhir_observation_mapping(mapping: &FhirObservationMapping) -> Extern
     }
 
     // Validate status is valid FHIR status
-    let valid_statuses = ["registered", "preliminary", "final", "amended", "corrected", "cancelled", "entered-in-error", "unknown"];
-    if !valid_statuses.contains(&mapping.status.as_str()) {
+    if ObservationStatus::from_code(&mapping.status).is_none() {
         return Ok(ValidateCallbackResult::Invalid(
-            format!("Invalid observation status: {}. Must be one of: {:?}", mapping.status, valid_statuses),
+            format!("Invalid observation status: {}", mapping.status),
         ));
     }
 
@@ -553,6 +929,64 @@ fn validate_fhir_observation_mapping(mapping: &FhirObservationMapping) -> Extern
         ));
     }
 
+    if let Some(quantity) = &mapping.value_quantity {
+        let result = ucum::validate_quantity("value_quantity", quantity.value, quantity.system.as_deref(), quantity.code.as_deref());
+        if let Some(error) = result.errors.first() {
+            return Ok(ValidateCallbackResult::Invalid(error.to_string()));
+        }
+    }
+
+    if let Some(invalid) = validate_bitemporal(mapping.effective_time, mapping.recorded_time) {
+        return Ok(invalid);
+    }
+
+    if let Some(invalid) = validate_extensions(&mapping.extensions) {
+        return Ok(invalid);
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_observation_status_transition(
+    original_action_hash: &ActionHash,
+    mapping: &FhirObservationMapping,
+) -> ExternResult<ValidateCallbackResult> {
+    let original_record = must_get_valid_record(original_action_hash.clone())?;
+    let original: FhirObservationMapping = match original_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    {
+        Some(original) => original,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Original entry is not a FHIR observation mapping".to_string(),
+            ));
+        }
+    };
+
+    let (from, to) = match (
+        ObservationStatus::from_code(&original.status),
+        ObservationStatus::from_code(&mapping.status),
+    ) {
+        (Some(from), Some(to)) => (from, to),
+        _ => {
+            return Ok(ValidateCallbackResult::Invalid(
+                format!("Invalid observation status: {}", mapping.status),
+            ));
+        }
+    };
+
+    if !from.can_transition_to(&to) {
+        return Ok(ValidateCallbackResult::Invalid(
+            format!(
+                "Observation status cannot change from {} to {}",
+                from.as_code(),
+                to.as_code()
+            ),
+        ));
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -587,6 +1021,14 @@ fn validate_fhir_condition_mapping(mapping: &FhirConditionMapping) -> ExternResu
         ));
     }
 
+    if let Some(invalid) = validate_bitemporal(mapping.effective_time, mapping.recorded_time) {
+        return Ok(invalid);
+    }
+
+    if let Some(invalid) = validate_extensions(&mapping.extensions) {
+        return Ok(invalid);
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -621,6 +1063,71 @@ fn validate_fhir_medication_mapping(mapping: &FhirMedicationMapping) -> ExternRe
         ));
     }
 
+    if let Some(invalid) = validate_bitemporal(mapping.effective_time, mapping.recorded_time) {
+        return Ok(invalid);
+    }
+
+    if let Some(invalid) = validate_extensions(&mapping.extensions) {
+        return Ok(invalid);
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_fhir_procedure_mapping(mapping: &FhirProcedureMapping) -> ExternResult<ValidateCallbackResult> {
+    // Validate FHIR procedure ID
+    if mapping.fhir_procedure_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "FHIR procedure ID cannot be empty".to_string(),
+        ));
+    }
+
+    // Validate status
+    if !PROCEDURE_STATUSES.contains(&mapping.status.as_str()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            format!("Invalid procedure status: {}. Must be one of: {:?}", mapping.status, PROCEDURE_STATUSES),
+        ));
+    }
+
+    // Billing needs CPT, quality measures need SNOMED; at least one is required
+    if mapping.cpt_code.is_none() && mapping.snomed_code.is_none() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Procedures require a CPT or SNOMED code".to_string(),
+        ));
+    }
+    if let Some(cpt) = &mapping.cpt_code {
+        if !is_valid_cpt_code(cpt) {
+            return Ok(ValidateCallbackResult::Invalid(
+                format!("Invalid CPT code format: {}", cpt),
+            ));
+        }
+    }
+    if let Some(snomed) = &mapping.snomed_code {
+        if !is_valid_snomed_code(snomed) {
+            return Ok(ValidateCallbackResult::Invalid(
+                format!("Invalid SNOMED CT code format: {}", snomed),
+            ));
+        }
+    }
+
+    // Validate complication flag agrees with listed complications
+    if !mapping.complications.is_empty() && !mapping.has_complications {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Procedures with listed complications must be flagged as complicated".to_string(),
+        ));
+    }
+
+    // Validate performers reference someone
+    if mapping.performers.iter().any(|p| p.actor.reference.is_none() && p.actor.display.is_none()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Procedure performers need a reference or display name".to_string(),
+        ));
+    }
+
+    if let Some(invalid) = validate_extensions(&mapping.extensions) {
+        return Ok(invalid);
+    }
+
     Ok(ValidateCallbackResult::Valid)
 }
 
@@ -662,6 +1169,28 @@ fn validate_terminology_validation(validation: &TerminologyValidation) -> Extern
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn validate_retraction(retraction: &FhirMappingRetraction) -> ExternResult<ValidateCallbackResult> {
+    if retraction.resource_type.is_empty() || retraction.fhir_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Retraction must name the FHIR resource it withdraws".to_string(),
+        ));
+    }
+
+    if retraction.source_system.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Source system must be specified".to_string(),
+        ));
+    }
+
+    if retraction.reason.as_ref().is_some_and(|r| r.chars().count() > NOTE_CHARS) {
+        return Ok(ValidateCallbackResult::Invalid(
+            format!("Retraction reason cannot exceed {} characters", NOTE_CHARS),
+        ));
+    }
+
+    Ok(ValidateCallbackResult::Valid)
+}
+
 fn validate_link(link_type: LinkTypes) -> ExternResult<ValidateCallbackResult> {
     match link_type {
         LinkTypes::PatientToFhirMappings => Ok(ValidateCallbackResult::Valid),
@@ -673,5 +1202,11 @@ fn validate_link(link_type: LinkTypes) -> ExternResult<ValidateCallbackResult> {
         LinkTypes::AllFhirPatientMappings => Ok(ValidateCallbackResult::Valid),
         LinkTypes::BundleToEntries => Ok(ValidateCallbackResult::Valid),
         LinkTypes::FhirMappingUpdates => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::ProcedureToFhirMapping => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::PatientToProcedureCodes => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::MappingToRetraction => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::PatientToRetractions => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::MappingReferrers => Ok(ValidateCallbackResult::Valid),
+        LinkTypes::MappingRegistry => Ok(ValidateCallbackResult::Valid),
     }
 }
@@ -1,7 +1,8 @@
 //! Insurance Coordinator Zome
 //! 
 //! Provides extern functions for insurance plan management,
-//! claims processing, and prior authorization workflows.
+//! claims processing, prior authorization workflows, and ingestion
+//! of payer remittances (FHIR ExplanationOfBenefit and X12 835).
 
 use hdk::prelude::*;
 use insurance_integrity::*;
@@ -461,11 +462,18 @@ pub fn create_eob(eob: ExplanationOfBenefits) -> ExternResult<Record> {
     
     create_link(
         eob.claim_hash,
-        eob_hash,
+        eob_hash.clone(),
         LinkTypes::ClaimToEOB,
         (),
     )?;
 
+    create_link(
+        eob.patient_hash.clone(),
+        eob_hash,
+        LinkTypes::PatientToEOBs,
+        (),
+    )?;
+
     log_data_access(
         eob.patient_hash,
         vec![DataCategory::FinancialData],
@@ -549,6 +557,850 @@ pub fn get_pending_claims(_: ()) -> ExternResult<Vec<Record>> {
     Ok(claims)
 }
 
+// ============================================================
+// PAYER CLAIMS DATA INGESTION
+// ============================================================
+
+/// An existing diagnosis or procedure record a remittance may have paid for
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServiceRecordRef {
+    /// ICD-10 code (diagnoses) or CPT/HCPCS code (procedures)
+    pub code: String,
+    pub record_hash: ActionHash,
+}
+
+/// Where ingested remittances are attached
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClaimsIngestContext {
+    pub patient_hash: ActionHash,
+    pub plan_hash: ActionHash,
+    pub source_system: String,
+    /// Claim to attach to; otherwise matched by claim id among the patient's claims
+    pub claim_hash: Option<ActionHash>,
+    /// Needed to record a claim the network has not seen yet
+    pub encounter_hash: Option<ActionHash>,
+    pub billing_provider_hash: Option<ActionHash>,
+    pub diagnosis_records: Vec<ServiceRecordRef>,
+    pub procedure_records: Vec<ServiceRecordRef>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IngestFhirEobInput {
+    pub context: ClaimsIngestContext,
+    /// FHIR R4 ExplanationOfBenefit resource
+    pub resource: serde_json::Value,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IngestX12RemittanceInput {
+    pub context: ClaimsIngestContext,
+    /// Raw X12 835 interchange
+    pub payload: String,
+}
+
+/// Outcome of ingesting one remittance
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ClaimsIngestResult {
+    pub source_id: String,
+    pub eob_hash: ActionHash,
+    pub claim_hash: ActionHash,
+    pub mapping_hash: Option<ActionHash>,
+    /// Already ingested from this source; nothing was written
+    pub duplicate: bool,
+    pub linked_diagnoses: u32,
+    pub linked_procedures: u32,
+    /// Supplied record codes the payer did not adjudicate
+    pub unmatched_codes: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RemittanceIngestReport {
+    pub results: Vec<ClaimsIngestResult>,
+    pub errors: Vec<String>,
+}
+
+/// Normalized remittance, independent of wire format
+struct ParsedRemittance {
+    source_id: String,
+    claim_id: Option<String>,
+    payer_claim_number: Option<String>,
+    status: ClaimStatus,
+    diagnosis_codes: Vec<String>,
+    lines: Vec<EOBLineDetail>,
+    total_charges: f64,
+    total_allowed: f64,
+    amount_paid: f64,
+    patient_owes: f64,
+    applied_to_deductible: f64,
+    remarks: Vec<String>,
+    service_date_from: String,
+    service_date_to: String,
+}
+
+/// Ingest a FHIR ExplanationOfBenefit from a payer
+#[hdk_extern]
+pub fn ingest_fhir_eob(input: IngestFhirEobInput) -> ExternResult<ClaimsIngestResult> {
+    let auth = require_authorization(
+        input.context.patient_hash.clone(),
+        DataCategory::FinancialData,
+        Permission::Write,
+        false,
+    )?;
+
+    let parsed = parse_fhir_eob(&input.resource)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+    let result = store_remittance(
+        &input.context,
+        input.context.claim_hash.clone(),
+        parsed,
+        ClaimsDataSource::FhirExplanationOfBenefit,
+    )?;
+
+    log_data_access(
+        input.context.patient_hash,
+        vec![DataCategory::FinancialData],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(result)
+}
+
+/// Ingest the claim payments in an X12 835 remittance advice
+///
+/// Each CLP loop becomes one EOB; `context.claim_hash` is only honoured
+/// when the interchange carries a single claim.
+#[hdk_extern]
+pub fn ingest_x12_remittance(input: IngestX12RemittanceInput) -> ExternResult<RemittanceIngestReport> {
+    let auth = require_authorization(
+        input.context.patient_hash.clone(),
+        DataCategory::FinancialData,
+        Permission::Write,
+        false,
+    )?;
+
+    let remittances = parse_x12_835(&input.payload)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+    let claim_override = if remittances.len() == 1 {
+        input.context.claim_hash.clone()
+    } else {
+        None
+    };
+
+    let mut report = RemittanceIngestReport { results: Vec::new(), errors: Vec::new() };
+    for parsed in remittances {
+        let source_id = parsed.source_id.clone();
+        match store_remittance(
+            &input.context,
+            claim_override.clone(),
+            parsed,
+            ClaimsDataSource::X12Remittance835,
+        ) {
+            Ok(result) => report.results.push(result),
+            Err(e) => report.errors.push(format!("{}: {:?}", source_id, e)),
+        }
+    }
+
+    if !report.results.is_empty() {
+        log_data_access(
+            input.context.patient_hash,
+            vec![DataCategory::FinancialData],
+            Permission::Write,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(report)
+}
+
+/// Get the provenance and linked records for an EOB
+#[hdk_extern]
+pub fn get_eob_mapping(eob_hash: ActionHash) -> ExternResult<Option<Record>> {
+    let eob_record = match get(eob_hash.clone(), GetOptions::default())? {
+        Some(record) => record,
+        None => return Ok(None),
+    };
+    let eob: ExplanationOfBenefits = eob_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid EOB".to_string())))?;
+
+    let auth = require_authorization(
+        eob.patient_hash.clone(),
+        DataCategory::FinancialData,
+        Permission::Read,
+        false,
+    )?;
+
+    let links = get_links(
+        LinkQuery::try_new(eob_hash, LinkTypes::EOBToMapping)?,
+        GetStrategy::default(),
+    )?;
+    let record = match links.first().and_then(|l| l.target.clone().into_action_hash()) {
+        Some(hash) => get(hash, GetOptions::default())?,
+        None => None,
+    };
+
+    if record.is_some() {
+        log_data_access(
+            eob.patient_hash,
+            vec![DataCategory::FinancialData],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(record)
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CostSummaryInput {
+    pub patient_hash: ActionHash,
+    pub since: Option<Timestamp>,
+    pub until: Option<Timestamp>,
+}
+
+/// What the patient was charged for one kind of service
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ServiceCostLine {
+    pub procedure_code: String,
+    pub description: String,
+    pub times_billed: u32,
+    pub billed: f64,
+    pub plan_paid: f64,
+    pub patient_owes: f64,
+}
+
+/// Patient-facing roll-up of EOBs
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PatientCostSummary {
+    pub patient_hash: ActionHash,
+    pub eob_count: u32,
+    pub total_billed: f64,
+    pub total_plan_paid: f64,
+    pub total_patient_owes: f64,
+    pub total_applied_to_deductible: f64,
+    pub by_service: Vec<ServiceCostLine>,
+    pub generated_at: Timestamp,
+}
+
+/// Summarize what a patient's care cost them and their plan
+#[hdk_extern]
+pub fn get_patient_cost_summary(input: CostSummaryInput) -> ExternResult<PatientCostSummary> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::FinancialData,
+        Permission::Read,
+        false,
+    )?;
+
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToEOBs)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut summary = PatientCostSummary {
+        patient_hash: input.patient_hash.clone(),
+        eob_count: 0,
+        total_billed: 0.0,
+        total_plan_paid: 0.0,
+        total_patient_owes: 0.0,
+        total_applied_to_deductible: 0.0,
+        by_service: Vec::new(),
+        generated_at: sys_time()?,
+    };
+
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else { continue };
+        let Some(record) = get(hash, GetOptions::default())? else { continue };
+        let Some(eob) = record.entry().to_app_option::<ExplanationOfBenefits>().ok().flatten() else {
+            continue;
+        };
+        if input.since.is_some_and(|since| eob.issued_date < since)
+            || input.until.is_some_and(|until| eob.issued_date > until)
+        {
+            continue;
+        }
+
+        summary.eob_count += 1;
+        summary.total_billed += eob.total_charges;
+        summary.total_plan_paid += eob.amount_paid;
+        summary.total_patient_owes += eob.patient_owes;
+        summary.total_applied_to_deductible += eob.applied_to_deductible;
+
+        for line in eob.line_details {
+            match summary.by_service.iter_mut().find(|s| s.procedure_code == line.procedure_code) {
+                Some(entry) => {
+                    entry.times_billed += 1;
+                    entry.billed += line.billed_amount;
+                    entry.plan_paid += line.paid_amount;
+                    entry.patient_owes += line.patient_responsibility;
+                }
+                None => summary.by_service.push(ServiceCostLine {
+                    procedure_code: line.procedure_code,
+                    description: line.description,
+                    times_billed: 1,
+                    billed: line.billed_amount,
+                    plan_paid: line.paid_amount,
+                    patient_owes: line.patient_responsibility,
+                }),
+            }
+        }
+    }
+
+    summary
+        .by_service
+        .sort_by(|a, b| b.patient_owes.partial_cmp(&a.patient_owes).unwrap_or(std::cmp::Ordering::Equal));
+
+    if summary.eob_count > 0 {
+        log_data_access(
+            input.patient_hash,
+            vec![DataCategory::FinancialData],
+            Permission::Read,
+            auth.consent_hash,
+            auth.emergency_override,
+            None,
+        )?;
+    }
+
+    Ok(summary)
+}
+
+/// Persist a parsed remittance as Claim/EOB/mapping entries
+fn store_remittance(
+    ctx: &ClaimsIngestContext,
+    claim_override: Option<ActionHash>,
+    parsed: ParsedRemittance,
+    source_format: ClaimsDataSource,
+) -> ExternResult<ClaimsIngestResult> {
+    let source_anchor = anchor_hash(&format!("eob_source:{}:{}", ctx.source_system, parsed.source_id))?;
+    let existing = get_links(
+        LinkQuery::try_new(source_anchor.clone(), LinkTypes::SourceIdToEOB)?,
+        GetStrategy::default(),
+    )?;
+    if let Some(eob_hash) = existing.first().and_then(|l| l.target.clone().into_action_hash()) {
+        let eob: ExplanationOfBenefits = get(eob_hash.clone(), GetOptions::default())?
+            .and_then(|r| r.entry().to_app_option().ok().flatten())
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Ingested EOB not found".to_string())))?;
+        return Ok(ClaimsIngestResult {
+            source_id: parsed.source_id,
+            eob_hash,
+            claim_hash: eob.claim_hash,
+            mapping_hash: None,
+            duplicate: true,
+            linked_diagnoses: 0,
+            linked_procedures: 0,
+            unmatched_codes: Vec::new(),
+        });
+    }
+
+    let now = sys_time()?;
+    let claim_hash = match claim_override {
+        Some(hash) => hash,
+        None => match find_patient_claim(&ctx.patient_hash, &parsed)? {
+            Some(hash) => hash,
+            None => create_remitted_claim(ctx, &parsed, now)?,
+        },
+    };
+
+    let eob = ExplanationOfBenefits {
+        eob_id: format!("eob-{}-{}", ctx.source_system, parsed.source_id),
+        claim_hash: claim_hash.clone(),
+        patient_hash: ctx.patient_hash.clone(),
+        plan_hash: ctx.plan_hash.clone(),
+        issued_date: now,
+        total_charges: parsed.total_charges,
+        plan_discount: (parsed.total_charges - parsed.total_allowed).max(0.0),
+        amount_paid: parsed.amount_paid,
+        patient_owes: parsed.patient_owes,
+        applied_to_deductible: parsed.applied_to_deductible,
+        line_details: parsed.lines.clone(),
+        remarks: parsed.remarks.clone(),
+        appeal_deadline: None,
+    };
+    let eob_hash = create_entry(&EntryTypes::ExplanationOfBenefits(eob))?;
+    create_link(claim_hash.clone(), eob_hash.clone(), LinkTypes::ClaimToEOB, ())?;
+    create_link(ctx.patient_hash.clone(), eob_hash.clone(), LinkTypes::PatientToEOBs, ())?;
+    create_link(source_anchor, eob_hash.clone(), LinkTypes::SourceIdToEOB, ())?;
+
+    let procedure_codes: Vec<String> = parsed.lines.iter().map(|l| l.procedure_code.clone()).collect();
+    let mut unmatched_codes = Vec::new();
+    let diagnosis_records = match_service_records(&ctx.diagnosis_records, &parsed.diagnosis_codes, &mut unmatched_codes);
+    let procedure_records = match_service_records(&ctx.procedure_records, &procedure_codes, &mut unmatched_codes);
+
+    for (kind, refs) in [("diagnosis", &diagnosis_records), ("procedure", &procedure_records)] {
+        for r in refs.iter() {
+            create_link(
+                eob_hash.clone(),
+                r.record_hash.clone(),
+                LinkTypes::EOBToServiceRecords,
+                LinkTag::new(format!("{}:{}", kind, r.code).as_bytes().to_vec()),
+            )?;
+        }
+    }
+
+    let mapping = EobMapping {
+        eob_hash: eob_hash.clone(),
+        claim_hash: claim_hash.clone(),
+        patient_hash: ctx.patient_hash.clone(),
+        source_format,
+        source_system: ctx.source_system.clone(),
+        source_id: parsed.source_id.clone(),
+        payer_claim_number: parsed.payer_claim_number.clone(),
+        diagnosis_codes: parsed.diagnosis_codes.clone(),
+        procedure_codes,
+        diagnosis_record_hashes: diagnosis_records.iter().map(|r| r.record_hash.clone()).collect(),
+        procedure_record_hashes: procedure_records.iter().map(|r| r.record_hash.clone()).collect(),
+        ingested_by: agent_info()?.agent_initial_pubkey,
+        ingested_at: now,
+    };
+    let mapping_hash = create_entry(&EntryTypes::EobMapping(mapping))?;
+    create_link(eob_hash.clone(), mapping_hash.clone(), LinkTypes::EOBToMapping, ())?;
+
+    Ok(ClaimsIngestResult {
+        source_id: parsed.source_id,
+        eob_hash,
+        claim_hash,
+        mapping_hash: Some(mapping_hash),
+        duplicate: false,
+        linked_diagnoses: diagnosis_records.len() as u32,
+        linked_procedures: procedure_records.len() as u32,
+        unmatched_codes,
+    })
+}
+
+/// Match a remittance to a claim the patient already submitted
+fn find_patient_claim(patient_hash: &ActionHash, parsed: &ParsedRemittance) -> ExternResult<Option<ActionHash>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToClaims)?,
+        GetStrategy::default(),
+    )?;
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else { continue };
+        let Some(record) = get(hash.clone(), GetOptions::default())? else { continue };
+        let Some(claim) = record.entry().to_app_option::<Claim>().ok().flatten() else { continue };
+        let id_matches = parsed.claim_id.as_deref() == Some(claim.claim_id.as_str());
+        let payer_matches = parsed.payer_claim_number.is_some()
+            && claim.payer_claim_number == parsed.payer_claim_number;
+        if id_matches || payer_matches {
+            return Ok(Some(hash));
+        }
+    }
+    Ok(None)
+}
+
+/// Record a payer-adjudicated claim that was submitted outside this network
+fn create_remitted_claim(
+    ctx: &ClaimsIngestContext,
+    parsed: &ParsedRemittance,
+    now: Timestamp,
+) -> ExternResult<ActionHash> {
+    let (Some(encounter_hash), Some(billing_provider_hash)) =
+        (ctx.encounter_hash.clone(), ctx.billing_provider_hash.clone())
+    else {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "No claim matches remittance {}; supply claim_hash or encounter and billing provider",
+            parsed.source_id
+        ))));
+    };
+    let primary_diagnosis = parsed.diagnosis_codes.first().cloned().ok_or(wasm_error!(
+        WasmErrorInner::Guest("Remittance has no diagnosis to record a claim against".to_string())
+    ))?;
+
+    let claim = Claim {
+        claim_id: parsed.claim_id.clone().unwrap_or_else(|| parsed.source_id.clone()),
+        patient_hash: ctx.patient_hash.clone(),
+        plan_hash: ctx.plan_hash.clone(),
+        encounter_hash: encounter_hash.clone(),
+        billing_provider_hash,
+        rendering_provider_hash: None,
+        claim_type: ClaimType::Professional,
+        place_of_service: "99".to_string(),
+        primary_diagnosis,
+        secondary_diagnoses: parsed.diagnosis_codes.iter().skip(1).cloned().collect(),
+        line_items: parsed
+            .lines
+            .iter()
+            .enumerate()
+            .map(|(i, line)| ClaimLineItem {
+                line_number: i as u32 + 1,
+                procedure_code: line.procedure_code.clone(),
+                modifiers: Vec::new(),
+                description: line.description.clone(),
+                units: 1.0,
+                charge_amount: line.billed_amount,
+                allowed_amount: Some(line.allowed_amount),
+                paid_amount: Some(line.paid_amount),
+                denial_reason: line.adjustment_reason.clone(),
+                service_date: line.service_date.clone(),
+                ndc: None,
+            })
+            .collect(),
+        total_charges: parsed.total_charges,
+        total_allowed: Some(parsed.total_allowed),
+        total_paid: Some(parsed.amount_paid),
+        patient_responsibility: Some(parsed.patient_owes),
+        service_date_from: parsed.service_date_from.clone(),
+        service_date_to: parsed.service_date_to.clone(),
+        submitted_at: now,
+        status: parsed.status.clone(),
+        adjudication_date: Some(now),
+        payer_claim_number: parsed.payer_claim_number.clone(),
+        remittance_hash: None,
+    };
+
+    let claim_hash = create_entry(&EntryTypes::Claim(claim))?;
+    create_link(ctx.patient_hash.clone(), claim_hash.clone(), LinkTypes::PatientToClaims, ())?;
+    create_link(ctx.plan_hash.clone(), claim_hash.clone(), LinkTypes::PlanToClaims, ())?;
+    create_link(encounter_hash, claim_hash.clone(), LinkTypes::EncounterToClaim, ())?;
+    if parsed.status == ClaimStatus::Denied {
+        let denied_anchor = anchor_hash("denied_claims")?;
+        create_link(denied_anchor, claim_hash.clone(), LinkTypes::DeniedClaims, ())?;
+    }
+    Ok(claim_hash)
+}
+
+/// Keep the supplied records whose code the payer adjudicated
+fn match_service_records(
+    refs: &[ServiceRecordRef],
+    codes: &[String],
+    unmatched: &mut Vec<String>,
+) -> Vec<ServiceRecordRef> {
+    let normalized: Vec<String> = codes.iter().map(|c| normalize_code(c)).collect();
+    let mut matched = Vec::new();
+    for r in refs {
+        if normalized.contains(&normalize_code(&r.code)) {
+            matched.push(r.clone());
+        } else {
+            unmatched.push(r.code.clone());
+        }
+    }
+    matched
+}
+
+/// ICD-10 codes travel with the dot in FHIR but without it in X12
+fn normalize_code(code: &str) -> String {
+    code.trim().replace('.', "").to_uppercase()
+}
+
+fn parse_fhir_eob(resource: &serde_json::Value) -> Result<ParsedRemittance, String> {
+    if resource.get("resourceType").and_then(|v| v.as_str()) != Some("ExplanationOfBenefit") {
+        return Err("Resource is not an ExplanationOfBenefit".to_string());
+    }
+    let source_id = resource
+        .get("id")
+        .and_then(|v| v.as_str())
+        .ok_or("ExplanationOfBenefit missing 'id' field")?
+        .to_string();
+
+    let claim_id = resource.get("claim").and_then(|c| {
+        c.pointer("/identifier/value")
+            .and_then(|v| v.as_str())
+            .or_else(|| c.get("reference").and_then(|v| v.as_str()).and_then(|r| r.rsplit('/').next()))
+            .map(|s| s.to_string())
+    });
+    let payer_claim_number = resource
+        .pointer("/identifier/0/value")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let diagnosis_codes: Vec<String> = json_array(resource, "diagnosis")
+        .iter()
+        .filter_map(|d| d.pointer("/diagnosisCodeableConcept/coding/0/code"))
+        .filter_map(|v| v.as_str().map(|s| s.to_string()))
+        .collect();
+
+    let mut lines = Vec::new();
+    let mut deductible = 0.0;
+    for item in json_array(resource, "item") {
+        let adjudication = json_array(item, "adjudication");
+        let billed = adjudication_amount(adjudication, &["submitted"]);
+        let paid = adjudication_amount(adjudication, &["benefit"]);
+        let line_deductible = adjudication_amount(adjudication, &["deductible"]);
+        let eligible = adjudication_amount(adjudication, &["eligible"]);
+        let member = adjudication_amount(adjudication, &["memberliability", "paidbypatient"]);
+        let patient = if member > 0.0 {
+            member
+        } else {
+            adjudication_amount(adjudication, &["copay", "deductible", "coinsurance"])
+        };
+        deductible += line_deductible;
+
+        lines.push(EOBLineDetail {
+            service_date: item
+                .get("servicedDate")
+                .or_else(|| item.pointer("/servicedPeriod/start"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            procedure_code: item
+                .pointer("/productOrService/coding/0/code")
+                .and_then(|v| v.as_str())
+                .unwrap_or("unknown")
+                .to_string(),
+            description: item
+                .pointer("/productOrService/coding/0/display")
+                .or_else(|| item.pointer("/productOrService/text"))
+                .and_then(|v| v.as_str())
+                .unwrap_or_default()
+                .to_string(),
+            billed_amount: billed,
+            allowed_amount: if eligible > 0.0 { eligible } else { paid + patient },
+            paid_amount: paid,
+            patient_responsibility: patient,
+            adjustment_reason: adjudication
+                .iter()
+                .find_map(|a| a.pointer("/reason/coding/0/code"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string()),
+        });
+    }
+
+    let totals = json_array(resource, "total");
+    let line_sum = |f: fn(&EOBLineDetail) -> f64| lines.iter().map(f).sum::<f64>();
+    let total_charges = non_zero_or(adjudication_amount(totals, &["submitted"]), line_sum(|l| l.billed_amount));
+    let total_allowed = non_zero_or(adjudication_amount(totals, &["eligible"]), line_sum(|l| l.allowed_amount));
+    let amount_paid = resource
+        .pointer("/payment/amount/value")
+        .and_then(|v| v.as_f64())
+        .unwrap_or_else(|| non_zero_or(adjudication_amount(totals, &["benefit"]), line_sum(|l| l.paid_amount)));
+    let patient_owes = non_zero_or(
+        adjudication_amount(totals, &["memberliability", "paidbypatient"]),
+        line_sum(|l| l.patient_responsibility),
+    );
+
+    let status = match resource.get("outcome").and_then(|v| v.as_str()) {
+        Some("queued") => ClaimStatus::InProcess,
+        Some("partial") => ClaimStatus::PartiallyApproved,
+        Some("error") => ClaimStatus::Denied,
+        _ if amount_paid <= 0.0 && total_charges > 0.0 => ClaimStatus::Denied,
+        _ => ClaimStatus::Paid,
+    };
+
+    let mut service_dates: Vec<String> = lines
+        .iter()
+        .map(|l| l.service_date.clone())
+        .filter(|d| !d.is_empty())
+        .collect();
+    service_dates.sort();
+    let period = |key: &str| {
+        resource
+            .pointer(&format!("/billablePeriod/{}", key))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+
+    Ok(ParsedRemittance {
+        source_id,
+        claim_id,
+        payer_claim_number,
+        status,
+        diagnosis_codes,
+        total_charges,
+        total_allowed,
+        amount_paid,
+        patient_owes,
+        applied_to_deductible: deductible,
+        remarks: json_array(resource, "processNote")
+            .iter()
+            .filter_map(|n| n.get("text").and_then(|v| v.as_str()).map(|s| s.to_string()))
+            .collect(),
+        service_date_from: period("start")
+            .or_else(|| service_dates.first().cloned())
+            .unwrap_or_default(),
+        service_date_to: period("end")
+            .or_else(|| service_dates.last().cloned())
+            .unwrap_or_default(),
+        lines,
+    })
+}
+
+fn json_array<'a>(value: &'a serde_json::Value, key: &str) -> &'a [serde_json::Value] {
+    value
+        .get(key)
+        .and_then(|v| v.as_array())
+        .map(|a| a.as_slice())
+        .unwrap_or_default()
+}
+
+/// Sum FHIR adjudication/total amounts whose category code is in `codes`
+fn adjudication_amount(entries: &[serde_json::Value], codes: &[&str]) -> f64 {
+    entries
+        .iter()
+        .filter(|e| {
+            json_array(e.get("category").unwrap_or(&serde_json::Value::Null), "coding")
+                .iter()
+                .any(|c| c.get("code").and_then(|v| v.as_str()).is_some_and(|code| codes.contains(&code)))
+        })
+        .filter_map(|e| e.pointer("/amount/value").and_then(|v| v.as_f64()))
+        .sum()
+}
+
+fn non_zero_or(value: f64, fallback: f64) -> f64 {
+    if value != 0.0 { value } else { fallback }
+}
+
+/// Minimal X12 835 reader: CLP claim loops with their SVC, CAS, DTM and LQ segments
+fn parse_x12_835(payload: &str) -> Result<Vec<ParsedRemittance>, String> {
+    let payload = payload.trim();
+    if !payload.starts_with("ISA") && !payload.starts_with("ST") {
+        return Err("Payload is not an X12 interchange".to_string());
+    }
+    let element_sep = if payload.starts_with("ISA") {
+        payload.chars().nth(3).ok_or("Truncated ISA segment")?
+    } else {
+        '*'
+    };
+    let component_sep = payload
+        .split('~')
+        .next()
+        .and_then(|isa| isa.split(element_sep).nth(16))
+        .and_then(|c| c.trim().chars().next())
+        .filter(|_| payload.starts_with("ISA"))
+        .unwrap_or(':');
+
+    let mut claims: Vec<ParsedRemittance> = Vec::new();
+    let mut in_service_line = false;
+
+    for segment in payload.split('~').map(|s| s.trim()).filter(|s| !s.is_empty()) {
+        let el: Vec<&str> = segment.split(element_sep).collect();
+        let field = |i: usize| el.get(i).map(|s| s.trim()).unwrap_or_default();
+        let amount = |i: usize| field(i).parse::<f64>().unwrap_or(0.0);
+
+        match el[0] {
+            "CLP" => {
+                let claim_id = field(1).to_string();
+                if claim_id.is_empty() {
+                    return Err("CLP segment missing claim control number".to_string());
+                }
+                let payer_claim_number = Some(field(7).to_string()).filter(|s| !s.is_empty());
+                claims.push(ParsedRemittance {
+                    source_id: payer_claim_number.clone().unwrap_or_else(|| claim_id.clone()),
+                    claim_id: Some(claim_id),
+                    payer_claim_number,
+                    status: match field(2) {
+                        "4" => ClaimStatus::Denied,
+                        "22" => ClaimStatus::Voided,
+                        _ if amount(4) <= 0.0 && amount(3) > 0.0 => ClaimStatus::Denied,
+                        _ => ClaimStatus::Paid,
+                    },
+                    diagnosis_codes: Vec::new(),
+                    lines: Vec::new(),
+                    total_charges: amount(3),
+                    total_allowed: amount(4) + amount(5),
+                    amount_paid: amount(4),
+                    patient_owes: amount(5),
+                    applied_to_deductible: 0.0,
+                    remarks: Vec::new(),
+                    service_date_from: String::new(),
+                    service_date_to: String::new(),
+                });
+                in_service_line = false;
+            }
+            "SVC" => {
+                let Some(claim) = claims.last_mut() else { continue };
+                let procedure_code = field(1)
+                    .split(component_sep)
+                    .nth(1)
+                    .unwrap_or("unknown")
+                    .to_string();
+                claim.lines.push(EOBLineDetail {
+                    service_date: claim.service_date_from.clone(),
+                    procedure_code,
+                    description: String::new(),
+                    billed_amount: amount(2),
+                    allowed_amount: amount(3),
+                    paid_amount: amount(3),
+                    patient_responsibility: 0.0,
+                    adjustment_reason: None,
+                });
+                in_service_line = true;
+            }
+            "CAS" => {
+                let Some(claim) = claims.last_mut() else { continue };
+                let group = field(1);
+                // Reason/amount/quantity triples start at CAS02
+                let mut i = 2;
+                while !field(i).is_empty() {
+                    let reason = format!("{}-{}", group, field(i));
+                    let adjusted = amount(i + 1);
+                    if group == "PR" && field(i) == "1" {
+                        claim.applied_to_deductible += adjusted;
+                    }
+                    if in_service_line {
+                        if let Some(line) = claim.lines.last_mut() {
+                            if group == "PR" {
+                                line.patient_responsibility += adjusted;
+                                line.allowed_amount += adjusted;
+                            }
+                            line.adjustment_reason.get_or_insert(reason);
+                        }
+                    } else {
+                        claim.remarks.push(format!("Adjustment {}: {:.2}", reason, adjusted));
+                    }
+                    i += 3;
+                }
+            }
+            "DTM" => {
+                let Some(claim) = claims.last_mut() else { continue };
+                let date = x12_date(field(2));
+                match field(1) {
+                    "472" if in_service_line => {
+                        if let Some(line) = claim.lines.last_mut() {
+                            line.service_date = date;
+                        }
+                    }
+                    "232" => claim.service_date_from = date,
+                    "233" => claim.service_date_to = date,
+                    _ => {}
+                }
+            }
+            "LQ" => {
+                if let Some(claim) = claims.last_mut() {
+                    claim.remarks.push(format!("Remark {}", field(2)));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if claims.is_empty() {
+        return Err("Remittance contains no CLP claim payment loops".to_string());
+    }
+    for claim in claims.iter_mut() {
+        let mut dates: Vec<String> = claim
+            .lines
+            .iter()
+            .map(|l| l.service_date.clone())
+            .filter(|d| !d.is_empty())
+            .collect();
+        dates.sort();
+        if claim.service_date_from.is_empty() {
+            claim.service_date_from = dates.first().cloned().unwrap_or_default();
+        }
+        if claim.service_date_to.is_empty() {
+            claim.service_date_to = dates.last().cloned().unwrap_or_else(|| claim.service_date_from.clone());
+        }
+    }
+    Ok(claims)
+}
+
+/// CCYYMMDD to ISO 8601
+fn x12_date(raw: &str) -> String {
+    if raw.len() == 8 && raw.chars().all(|c| c.is_ascii_digit()) {
+        format!("{}-{}-{}", &raw[0..4], &raw[4..6], &raw[6..8])
+    } else {
+        raw.to_string()
+    }
+}
+
 /// Anchor entry for indexing
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -558,3 +1410,31 @@ fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
     let anchor = Anchor(anchor_text.to_string());
     hash_entry(&anchor)
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("register_insurance_plan", "InsurancePlan", "Record"),
+        ("get_patient_insurance", "ActionHash", "Vec<Record>"),
+        ("update_insurance_plan", "UpdatePlanInput", "Record"),
+        ("submit_claim", "Claim", "Record"),
+        ("get_patient_claims", "ActionHash", "Vec<Record>"),
+        ("update_claim_status", "UpdateClaimInput", "Record"),
+        ("submit_prior_auth", "PriorAuthorization", "Record"),
+        ("get_patient_authorizations", "ActionHash", "Vec<Record>"),
+        ("update_authorization", "UpdateAuthInput", "Record"),
+        ("check_eligibility", "EligibilityCheck", "Record"),
+        ("create_eob", "ExplanationOfBenefits", "Record"),
+        ("get_claim_eob", "ActionHash", "Option<Record>"),
+        ("get_pending_claims", "()", "Vec<Record>"),
+        ("ingest_fhir_eob", "IngestFhirEobInput", "ClaimsIngestResult"),
+        ("ingest_x12_remittance", "IngestX12RemittanceInput", "RemittanceIngestReport"),
+        ("get_eob_mapping", "ActionHash", "Option<Record>"),
+        ("get_patient_cost_summary", "CostSummaryInput", "PatientCostSummary"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["claims_ingestion"],
+}
@@ -4,6 +4,9 @@
 //! and coverage verification with X12 EDI alignment.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 /// Insurance plan/policy
 #[hdk_entry_helper]
@@ -298,6 +301,39 @@ pub struct EOBLineDetail {
     pub adjustment_reason: Option<String>,
 }
 
+/// Format a payer remittance arrived in
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum ClaimsDataSource {
+    /// FHIR R4 ExplanationOfBenefit resource
+    FhirExplanationOfBenefit,
+    /// X12 835 health care claim payment/advice
+    X12Remittance835,
+}
+
+/// Provenance and clinical linkage for an ingested EOB
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EobMapping {
+    pub eob_hash: ActionHash,
+    pub claim_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub source_format: ClaimsDataSource,
+    pub source_system: String,
+    /// EOB resource id or CLP claim control number
+    pub source_id: String,
+    pub payer_claim_number: Option<String>,
+    /// ICD-10 codes the payer adjudicated
+    pub diagnosis_codes: Vec<String>,
+    /// CPT/HCPCS codes the payer adjudicated
+    pub procedure_codes: Vec<String>,
+    /// Diagnosis records the payment covered
+    pub diagnosis_record_hashes: Vec<ActionHash>,
+    /// Procedure records the payment covered
+    pub procedure_record_hashes: Vec<ActionHash>,
+    pub ingested_by: AgentPubKey,
+    pub ingested_at: Timestamp,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -306,6 +342,7 @@ pub enum EntryTypes {
     PriorAuthorization(PriorAuthorization),
     EligibilityCheck(EligibilityCheck),
     ExplanationOfBenefits(ExplanationOfBenefits),
+    EobMapping(EobMapping),
 }
 
 #[hdk_link_types]
@@ -319,10 +356,25 @@ pub enum LinkTypes {
     PendingAuths,
     PendingClaims,
     DeniedClaims,
+    PatientToEOBs,
+    EOBToMapping,
+    EOBToServiceRecords,
+    SourceIdToEOB,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("clinical_notes", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => match app_entry {
@@ -331,6 +383,7 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::PriorAuthorization(a) => validate_auth(&a),
                 EntryTypes::EligibilityCheck(_) => Ok(ValidateCallbackResult::Valid),
                 EntryTypes::ExplanationOfBenefits(_) => Ok(ValidateCallbackResult::Valid),
+                EntryTypes::EobMapping(m) => validate_eob_mapping(&m),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -376,6 +429,38 @@ fn validate_claim(claim: &Claim) -> ExternResult<ValidateCallbackResult> {
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn validate_eob_mapping(mapping: &EobMapping) -> ExternResult<ValidateCallbackResult> {
+    if mapping.source_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Source ID is required".to_string(),
+        ));
+    }
+    if mapping.source_system.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Source system is required".to_string(),
+        ));
+    }
+    let eob_record = must_get_valid_record(mapping.eob_hash.clone())?;
+    let eob = match eob_record
+        .entry()
+        .to_app_option::<ExplanationOfBenefits>()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+    {
+        Some(eob) => eob,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Mapping must reference an EOB".to_string(),
+            ))
+        }
+    };
+    if eob.patient_hash != mapping.patient_hash || eob.claim_hash != mapping.claim_hash {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Mapping patient and claim must match the EOB".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
 fn validate_auth(auth: &PriorAuthorization) -> ExternResult<ValidateCallbackResult> {
     if auth.auth_id.is_empty() {
         return Ok(ValidateCallbackResult::Invalid(
@@ -1107,3 +1107,32 @@ pub fn generate_recommendations_from_sdoh(
         recommendation_hashes,
     })
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("get_patient_restrictions", "ActionHash", "Vec<Record>"),
+        ("add_dietary_restriction", "DietaryRestriction", "Record"),
+        ("update_dietary_restriction", "UpdateRestrictionInput", "Record"),
+        ("deactivate_restriction", "ActionHash", "Record"),
+        ("get_drug_food_interactions", "String", "Vec<Record>"),
+        ("add_drug_food_interaction", "DrugFoodInteraction", "Record"),
+        ("get_all_interactions", "()", "Vec<Record>"),
+        ("check_food_safety", "CheckFoodSafetyInput", "FoodSafetyResult"),
+        ("get_patient_goals", "ActionHash", "Vec<Record>"),
+        ("create_nutrition_goal", "NutritionGoal", "Record"),
+        ("update_nutrition_goal", "UpdateGoalInput", "Record"),
+        ("log_meal", "MealLog", "Record"),
+        ("get_patient_meals", "GetMealsInput", "Vec<Record>"),
+        ("get_daily_nutrition_summary", "DailySummaryInput", "DailyNutritionSummary"),
+        ("get_patient_recommendations", "ActionHash", "Vec<Record>"),
+        ("create_recommendation", "NutritionRecommendation", "Record"),
+        ("acknowledge_recommendation", "ActionHash", "Record"),
+        ("generate_recommendations_from_sdoh", "SdohFoodSecurityInput, ", "SdohNutritionRecommendationsOutput"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: [],
+}
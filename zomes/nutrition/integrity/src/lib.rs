@@ -6,6 +6,9 @@
 //! This zome complements the health-food SDK integration module.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 // ============================================================================
 // Dietary Restriction Types
@@ -345,8 +348,20 @@ pub enum LinkTypes {
 // Validation
 // ============================================================================
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("clinical_notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => validate_create_entry(app_entry),
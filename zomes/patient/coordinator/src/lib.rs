@@ -6,13 +6,28 @@
 //! All data access functions enforce consent-based access control.
 
 use hdk::prelude::*;
+use std::collections::{HashMap, HashSet};
 use patient_integrity::*;
+use mycelix_health_shared::{invalidate_authorization_cache, DomainEventHandler, DomainEventKind, DomainEventNotice};
+use mycelix_health_shared::links_to_entries;
 use mycelix_health_shared::{
-    require_authorization, require_admin_authorization,
+    require_authorization, require_admin_authorization, require_operator,
+    increment_counter, read_counter,
+    ProvisionalCommit, PublicationConfirmation,
+    BackedUpEntry, BackedUpLink, BackupChunk, ChainBackupPayload, BACKUP_KDF_ITERATIONS, BACKUP_NONCE_BYTES,
+    open_chain_backup, remap_raw_hashes, seal_chain_backup,
     log_data_access,
     DataCategory, Permission, GetPatientInput,
-    validation::{validate_mrn, validate_confidence_score, ValidationResult},
+    validation::{validate_mrn, validate_confidence_score, validate_jurisdiction, ValidationResult},
+    enforce_site_rules,
+    require_patient_self,
 };
+use mycelix_health_shared::encryption::{stored_sealed_field, BlindIndex, EncryptedField, FieldBinding, SensitiveFieldType};
+use mycelix_health_shared::key_management::{
+    blind_index_for, create_key_metadata, generate_master_key, key_matches_metadata, open_field, seal_field,
+    should_rotate_key, KeyMetadata, KeyRotationEvent, MasterKey, MasterKeyRing, OpenedField,
+};
+use mycelix_health_shared::key_management::escrow::{combine_shares, split_secret, RecoveryRole, SecretShare};
 
 /// Validate patient data before creation/update
 fn validate_patient(patient: &Patient) -> ValidationResult {
@@ -23,6 +38,10 @@ fn validate_patient(patient: &Patient) -> ValidationResult {
         result.merge(validate_mrn(mrn));
     }
 
+    if let Some(ref jurisdiction) = patient.jurisdiction {
+        result.merge(validate_jurisdiction(jurisdiction));
+    }
+
     // Validate MATL trust score (should be 0.0 - 1.0)
     result.merge(validate_confidence_score(patient.matl_trust_score, "matl_trust_score"));
 
@@ -58,8 +77,9 @@ fn validate_patient(patient: &Patient) -> ValidationResult {
 /// Create a new patient profile
 #[hdk_extern]
 pub fn create_patient(patient: Patient) -> ExternResult<Record> {
-    // Validate patient data
+    // Validate patient data, then the caller's facility rules
     validate_patient(&patient).into_result()?;
+    enforce_site_rules("Patient", &patient, None)?;
 
     let patient_hash = create_entry(&EntryTypes::Patient(patient.clone()))?;
     let record = get(patient_hash.clone(), GetOptions::default())?
@@ -73,10 +93,23 @@ pub fn create_patient(patient: Patient) -> ExternResult<Record> {
         LinkTypes::AllPatients,
         (),
     )?;
+    increment_counter(anchor_hash(PATIENTS_STAT)?, LinkTypes::StatisticsCounter, 1)?;
     
     Ok(record)
 }
 
+/// Create a patient, returning as soon as it is on the source chain
+#[hdk_extern]
+pub fn create_patient_provisional(patient: Patient) -> ExternResult<ProvisionalCommit> {
+    Ok(ProvisionalCommit::from_record(create_patient(patient)?))
+}
+
+/// Check whether a provisional write has been published to the DHT
+#[hdk_extern]
+pub fn confirm_entry_published(action_hash: ActionHash) -> ExternResult<PublicationConfirmation> {
+    mycelix_health_shared::confirm_entry_published(action_hash)
+}
+
 /// Get a patient by their action hash (without access control - internal use only)
 fn get_patient_internal(patient_hash: ActionHash) -> ExternResult<Option<Record>> {
     get(patient_hash, GetOptions::default())
@@ -123,8 +156,9 @@ pub struct UpdatePatientInput {
 /// Update an existing patient with consent-based access control
 #[hdk_extern]
 pub fn update_patient(input: UpdatePatientInput) -> ExternResult<Record> {
-    // Validate updated patient data
+    // Validate updated patient data, then the caller's facility rules
     validate_patient(&input.updated_patient).into_result()?;
+    enforce_site_rules("Patient", &input.updated_patient, None)?;
 
     // Require Write authorization before modifying PHI
     let auth = require_authorization(
@@ -179,6 +213,7 @@ pub fn delete_patient(input: DeletePatientInput) -> ExternResult<ActionHash> {
     )?;
 
     let result = delete_entry(input.patient_hash.clone())?;
+    increment_counter(anchor_hash(PATIENTS_STAT)?, LinkTypes::StatisticsCounter, -1)?;
 
     // Log the deletion for audit trail
     log_data_access(
@@ -193,6 +228,35 @@ pub fn delete_patient(input: DeletePatientInput) -> ExternResult<ActionHash> {
     Ok(result)
 }
 
+/// Jurisdiction of the latest version of a patient record
+///
+/// Only the tag is returned, without consent or an access log, so residency
+/// checks can run for any accessor before data is touched.
+#[hdk_extern]
+pub fn get_patient_jurisdiction(patient_hash: ActionHash) -> ExternResult<Option<String>> {
+    let latest = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientUpdates)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .max_by_key(|link| link.timestamp)
+    .and_then(|link| link.target.into_action_hash())
+    .unwrap_or(patient_hash);
+
+    Ok(get_patient_internal(latest)?
+        .and_then(|record| record.entry().to_app_option::<Patient>().ok().flatten())
+        .and_then(|patient| patient.jurisdiction))
+}
+
+const PATIENTS_STAT: &str = "stats:patients";
+
+/// Number of registered patients, from the statistics counter (operators only)
+#[hdk_extern]
+pub fn get_patient_count(_: ()) -> ExternResult<u64> {
+    require_operator()?;
+    Ok(read_counter(anchor_hash(PATIENTS_STAT)?, LinkTypes::StatisticsCounter)?.max(0) as u64)
+}
+
 /// Get all patients (admin function - requires admin authorization)
 #[hdk_extern]
 pub fn get_all_patients(_: ()) -> ExternResult<Vec<Record>> {
@@ -613,9 +677,15 @@ pub fn get_patient_by_mrn(input: GetPatientByMrnInput) -> ExternResult<Option<Re
     // which requires admin authorization for the search itself
     require_admin_authorization()?;
 
-    let all_patients = get_all_patients_internal()?;
+    // An MRN this admin indexed under their blind index key avoids
+    // scanning every patient
+    let indexed: Vec<Record> = blind_index_matches(&mrn_field_type(), &input.mrn)?
+        .into_iter()
+        .filter_map(|hash| get_patient_internal(hash).transpose())
+        .collect::<ExternResult<_>>()?;
+    let candidates = if indexed.is_empty() { get_all_patients_internal()? } else { indexed };
 
-    for record in all_patients {
+    for record in candidates {
         if let Some(patient) = record.entry().to_app_option::<Patient>().ok().flatten() {
             if patient.mrn == Some(input.mrn.clone()) {
                 // Found the patient - now check if caller has access to this specific patient
@@ -646,6 +716,1373 @@ pub fn get_patient_by_mrn(input: GetPatientByMrnInput) -> ExternResult<Option<Re
     Ok(None)
 }
 
+// ============================================================
+// EMERGENCY CARD
+// ============================================================
+
+/// Input for issuing an emergency card
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IssueEmergencyCardInput {
+    pub patient_hash: ActionHash,
+    pub blood_type: Option<BloodType>,
+    pub allergies: Vec<EmergencyCardAllergy>,
+    pub critical_medications: Vec<String>,
+    pub dnr_status: DnrStatus,
+    pub emergency_contacts: Vec<EmergencyContact>,
+    /// Card lifetime; None for no expiry
+    pub valid_days: Option<u32>,
+}
+
+/// The data encoded onto a QR code or NFC tag
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EmergencyCardPayload {
+    pub card_hash: ActionHash,
+    pub card_id: String,
+    pub blood_type: Option<BloodType>,
+    pub allergies: Vec<EmergencyCardAllergy>,
+    pub critical_medications: Vec<String>,
+    pub dnr_status: DnrStatus,
+    pub emergency_contacts: Vec<EmergencyContact>,
+    pub issued_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+/// Encoded payload plus the patient's signature over it, readable offline
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SignedEmergencyCard {
+    pub payload: Vec<u8>,
+    pub signature: Signature,
+    pub signer: AgentPubKey,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IssuedEmergencyCard {
+    pub record: Record,
+    pub signed: SignedEmergencyCard,
+}
+
+/// Issue a signed emergency card from a patient-curated dataset
+#[hdk_extern]
+pub fn issue_emergency_card(input: IssueEmergencyCardInput) -> ExternResult<IssuedEmergencyCard> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let patient_record = get_patient_internal(input.patient_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Patient not found".to_string())))?;
+    if patient_record.action().author() != &me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the patient can issue their emergency card".to_string()
+        )));
+    }
+
+    let now = sys_time()?;
+    let card = EmergencyCard {
+        card_id: format!("ECARD-{}", now.as_micros()),
+        patient_hash: input.patient_hash.clone(),
+        blood_type: input.blood_type,
+        allergies: input.allergies,
+        critical_medications: input.critical_medications,
+        dnr_status: input.dnr_status,
+        emergency_contacts: input.emergency_contacts,
+        issued_at: now,
+        expires_at: input
+            .valid_days
+            .map(|days| Timestamp::from_micros(now.as_micros() + days as i64 * 24 * 60 * 60 * 1_000_000)),
+    };
+
+    let card_hash = create_entry(&EntryTypes::EmergencyCard(card.clone()))?;
+    let record = get(card_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created emergency card".to_string())))?;
+    create_link(
+        input.patient_hash,
+        card_hash.clone(),
+        LinkTypes::PatientToEmergencyCards,
+        (),
+    )?;
+
+    let payload = EmergencyCardPayload {
+        card_hash,
+        card_id: card.card_id,
+        blood_type: card.blood_type,
+        allergies: card.allergies,
+        critical_medications: card.critical_medications,
+        dnr_status: card.dnr_status,
+        emergency_contacts: card.emergency_contacts,
+        issued_at: card.issued_at,
+        expires_at: card.expires_at,
+    };
+    let payload = ExternIO::encode(&payload)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Failed to encode emergency card: {:?}", e))))?
+        .0;
+    if payload.len() > EMERGENCY_CARD_MAX_BYTES {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Emergency card is {} bytes; trim it to {} to fit a QR code or NFC tag",
+            payload.len(),
+            EMERGENCY_CARD_MAX_BYTES
+        ))));
+    }
+    let signature = sign_raw(me.clone(), payload.clone())?;
+
+    Ok(IssuedEmergencyCard {
+        record,
+        signed: SignedEmergencyCard {
+            payload,
+            signature,
+            signer: me,
+        },
+    })
+}
+
+/// Input for revoking an emergency card
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RevokeEmergencyCardInput {
+    pub card_hash: ActionHash,
+    pub reason: Option<String>,
+}
+
+/// Revoke an emergency card so verifiers stop trusting its payload
+#[hdk_extern]
+pub fn revoke_emergency_card(input: RevokeEmergencyCardInput) -> ExternResult<Record> {
+    let card_record = get(input.card_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Emergency card not found".to_string())))?;
+    let card: EmergencyCard = card_record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid emergency card".to_string())))?;
+
+    let revocation = EmergencyCardRevocation {
+        card_hash: input.card_hash.clone(),
+        card_id: card.card_id,
+        revoked_at: sys_time()?,
+        reason: input.reason,
+    };
+    let revocation_hash = create_entry(&EntryTypes::EmergencyCardRevocation(revocation))?;
+    create_link(
+        input.card_hash,
+        revocation_hash.clone(),
+        LinkTypes::EmergencyCardRevocations,
+        (),
+    )?;
+
+    get(revocation_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find emergency card revocation".to_string())))
+}
+
+/// Outcome of checking a scanned emergency card
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EmergencyCardVerification {
+    /// Signature is good, card is on the DHT, unrevoked and unexpired
+    pub valid: bool,
+    pub signature_valid: bool,
+    /// Whether the card entry could be fetched to check issuer and revocation
+    pub revocation_checked: bool,
+    pub revoked: bool,
+    pub expired: bool,
+    pub payload: Option<EmergencyCardPayload>,
+}
+
+/// Verify a scanned emergency card's signature and revocation status
+///
+/// Called by responders' apps once they are back online.
+#[hdk_extern]
+pub fn verify_emergency_card(signed: SignedEmergencyCard) -> ExternResult<EmergencyCardVerification> {
+    let signature_valid =
+        verify_signature_raw(signed.signer.clone(), signed.signature, signed.payload.clone())?;
+    let payload: Option<EmergencyCardPayload> = ExternIO(signed.payload).decode().ok();
+
+    let Some(payload) = payload.filter(|_| signature_valid) else {
+        return Ok(EmergencyCardVerification {
+            valid: false,
+            signature_valid,
+            revocation_checked: false,
+            revoked: false,
+            expired: false,
+            payload: None,
+        });
+    };
+
+    let now = sys_time()?;
+    let expired = payload.expires_at.is_some_and(|expires| expires <= now);
+
+    // The card on the DHT must be the one the signer issued
+    let card_record = get(payload.card_hash.clone(), GetOptions::network())?;
+    let issued_by_signer = card_record.as_ref().is_some_and(|record| {
+        record.action().author() == &signed.signer
+            && record
+                .entry()
+                .to_app_option::<EmergencyCard>()
+                .ok()
+                .flatten()
+                .is_some_and(|card| card.card_id == payload.card_id)
+    });
+    let revoked = issued_by_signer
+        && !get_links(
+            LinkQuery::try_new(payload.card_hash.clone(), LinkTypes::EmergencyCardRevocations)?,
+            GetStrategy::Network,
+        )?
+        .is_empty();
+
+    Ok(EmergencyCardVerification {
+        valid: issued_by_signer && !revoked && !expired,
+        signature_valid,
+        revocation_checked: issued_by_signer,
+        revoked,
+        expired,
+        payload: Some(payload),
+    })
+}
+
+/// List the emergency cards a patient has issued
+#[hdk_extern]
+pub fn get_patient_emergency_cards(input: GetPatientInput) -> ExternResult<Vec<Record>> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Demographics,
+        Permission::Read,
+        input.is_emergency,
+    )?;
+
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToEmergencyCards)?,
+        GetStrategy::default(),
+    )?;
+    let mut cards = Vec::new();
+    for link in links {
+        if let Some(hash) = link.target.into_action_hash() {
+            if let Some(record) = get(hash, GetOptions::default())? {
+                cards.push(record);
+            }
+        }
+    }
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Demographics],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(cards)
+}
+
+// ============================================================
+// CHAIN BACKUP AND RESTORE
+// ============================================================
+
+/// Input for creating an encrypted chain backup
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateChainBackupInput {
+    pub passphrase: String,
+}
+
+/// Encrypted backup of the caller's chain, ready to store off-device
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ChainBackup {
+    pub backup_id: String,
+    pub created_at: Timestamp,
+    pub entries: u32,
+    pub links: u32,
+    pub chunks: Vec<BackupChunk>,
+}
+
+/// A grantee that moved from one agent key to another
+/// (mirrors consent's AgentKeyChange)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AgentKeyChange {
+    pub previous: AgentPubKey,
+    pub current: AgentPubKey,
+}
+
+/// Input for restoring a backup onto the calling (new) agent
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RestoreFromBackupInput {
+    pub passphrase: String,
+    pub chunks: Vec<BackupChunk>,
+    /// Grantees known to have changed keys since the backup was taken
+    #[serde(default)]
+    pub grantee_key_changes: Vec<AgentKeyChange>,
+}
+
+/// Input for the consent zome's grantee re-confirmation
+/// (mirrors consent's GranteeReconfirmationInput)
+#[derive(Serialize, Deserialize, Debug)]
+struct GranteeReconfirmationInput {
+    patient_hash: ActionHash,
+    key_changes: Vec<AgentKeyChange>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RestoreReport {
+    pub restore_hash: ActionHash,
+    pub backup_id: String,
+    pub entries_restored: u32,
+    pub links_restored: u32,
+    /// Patient profiles re-created under the new agent
+    pub patient_hashes: Vec<ActionHash>,
+    /// Consents paused until the patient re-confirms a grantee's new key
+    pub consents_awaiting_reconfirmation: u32,
+}
+
+/// Where a restored action came from
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RestoreProvenance {
+    pub restore_hash: ActionHash,
+    pub backup_id: String,
+    pub original_agent: AgentPubKey,
+    pub original_action_hash: ActionHash,
+    pub original_timestamp: Timestamp,
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn app_entry_def(unit: UnitEntryTypes) -> ExternResult<AppEntryDef> {
+    match EntryType::try_from(unit)? {
+        EntryType::App(def) => Ok(def),
+        _ => Err(wasm_error!(WasmErrorInner::Guest("Expected an app entry type".to_string()))),
+    }
+}
+
+fn same_entry_type(a: &AppEntryDef, b: &AppEntryDef) -> bool {
+    a.zome_index == b.zome_index && a.entry_index == b.entry_index
+}
+
+fn app_entry_from_bytes(bytes: Vec<u8>) -> ExternResult<Entry> {
+    Ok(Entry::App(
+        AppEntryBytes::try_from(SerializedBytes::from(UnsafeBytes::from(bytes)))
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Invalid backed-up entry: {:?}", e))))?,
+    ))
+}
+
+fn remap_linkable(hash: AnyLinkableHash, replacements: &HashMap<Vec<u8>, Vec<u8>>) -> ExternResult<AnyLinkableHash> {
+    match replacements.get(hash.get_raw_39()) {
+        Some(new) => AnyLinkableHash::try_from_raw_39(new.clone())
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Invalid remapped hash: {:?}", e)))),
+        None => Ok(hash),
+    }
+}
+
+/// Serialize and encrypt the caller's chain under a passphrase
+///
+/// Every app entry and link the agent authored is included, minus anything
+/// since deleted and the markers left by earlier restores. Nothing is
+/// written to the chain; the returned chunks are the backup.
+#[hdk_extern]
+pub fn create_chain_backup(input: CreateChainBackupInput) -> ExternResult<ChainBackup> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let restore_marker = app_entry_def(UnitEntryTypes::ChainRestore)?;
+    let provenance_link = ScopedLinkType::try_from(LinkTypes::RestoredEntryProvenance)?;
+
+    let records = query(ChainQueryFilter::new().include_entries(true))?;
+    let deleted: HashSet<ActionHash> = records
+        .iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            Action::DeleteLink(delete) => Some(delete.link_add_address.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut entries = Vec::new();
+    let mut links = Vec::new();
+    for record in &records {
+        let action_hash = record.action_address().clone();
+        if deleted.contains(&action_hash) {
+            continue;
+        }
+        let (entry_type, revision_of) = match record.action() {
+            Action::Create(create) => (create.entry_type.clone(), None),
+            Action::Update(update) => (update.entry_type.clone(), Some(update.original_action_address.clone())),
+            Action::CreateLink(link) => {
+                if link.zome_index != provenance_link.zome_index || link.link_type != provenance_link.zome_type {
+                    links.push(BackedUpLink {
+                        original_action_hash: action_hash,
+                        original_timestamp: link.timestamp,
+                        zome_index: link.zome_index,
+                        link_type: link.link_type,
+                        base: link.base_address.clone(),
+                        target: link.target_address.clone(),
+                        tag: link.tag.0.clone(),
+                    });
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        let EntryType::App(def) = entry_type else {
+            continue;
+        };
+        let Some(Entry::App(bytes)) = record.entry().as_option() else {
+            continue;
+        };
+        if same_entry_type(&def, &restore_marker) {
+            continue;
+        }
+        entries.push(BackedUpEntry {
+            original_action_hash: action_hash,
+            original_timestamp: record.action().timestamp(),
+            entry_type: def,
+            revision_of,
+            entry_bytes: bytes.as_ref().bytes().to_vec(),
+        });
+    }
+
+    let payload = ChainBackupPayload {
+        backup_id: format!("BACKUP-{}", hex_encode(&random_bytes(8)?)),
+        agent: me,
+        created_at: now,
+        entries,
+        links,
+    };
+    let chunks = seal_chain_backup(
+        &payload,
+        &input.passphrase,
+        &random_bytes(16)?,
+        &random_bytes(BACKUP_NONCE_BYTES as u32)?,
+        BACKUP_KDF_ITERATIONS,
+    )
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
+    Ok(ChainBackup {
+        backup_id: payload.backup_id,
+        created_at: now,
+        entries: payload.entries.len() as u32,
+        links: payload.links.len() as u32,
+        chunks,
+    })
+}
+
+/// Re-create a backed-up chain on the calling agent
+///
+/// Entries are replayed in their original order with references to earlier
+/// entries, and the old agent key, rewritten to their new counterparts.
+/// Each restored action links to a `ChainRestore` marker recording where it
+/// came from. The restore is all-or-nothing: if any entry fails validation
+/// under the new agent, nothing is committed.
+#[hdk_extern]
+pub fn restore_from_backup(input: RestoreFromBackupInput) -> ExternResult<RestoreReport> {
+    let payload = open_chain_backup(&input.chunks, &input.passphrase)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+    let me = agent_info()?.agent_initial_pubkey;
+    if payload.agent == me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Restore the backup onto a new agent; this agent already holds the original chain".to_string()
+        )));
+    }
+    let previous_restores = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::ChainRestore.try_into()?)
+            .include_entries(true),
+    )?;
+    let already_restored = previous_restores.iter().any(|record| {
+        record
+            .entry()
+            .to_app_option::<ChainRestore>()
+            .ok()
+            .flatten()
+            .is_some_and(|restore| restore.backup_id == payload.backup_id)
+    });
+    if already_restored {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "This backup has already been restored onto this agent".to_string()
+        )));
+    }
+
+    let restore = ChainRestore {
+        backup_id: payload.backup_id.clone(),
+        original_agent: payload.agent.clone(),
+        backup_created_at: payload.created_at,
+        restored_at: sys_time()?,
+        entries_restored: payload.entries.len() as u32,
+        links_restored: payload.links.len() as u32,
+    };
+    let restore_hash = create_entry(&EntryTypes::ChainRestore(restore))?;
+
+    let patient_type = app_entry_def(UnitEntryTypes::Patient)?;
+    let mut replacements: HashMap<Vec<u8>, Vec<u8>> = HashMap::new();
+    replacements.insert(payload.agent.get_raw_39().to_vec(), me.get_raw_39().to_vec());
+    let mut restored_actions: HashMap<ActionHash, ActionHash> = HashMap::new();
+    let mut patient_hashes = Vec::new();
+
+    for entry in &payload.entries {
+        let original_entry_hash = hash_entry(app_entry_from_bytes(entry.entry_bytes.clone())?)?;
+        let mut bytes = entry.entry_bytes.clone();
+        remap_raw_hashes(&mut bytes, &replacements);
+        let app_entry = app_entry_from_bytes(bytes)?;
+        let entry_hash = hash_entry(app_entry.clone())?;
+
+        let original = entry.revision_of.as_ref().and_then(|hash| restored_actions.get(hash));
+        let action_hash = match original {
+            Some(original) => update(UpdateInput::new(original.clone(), app_entry, ChainTopOrdering::default()))?,
+            None => create(CreateInput::new(
+                EntryDefLocation::App(AppEntryDefLocation {
+                    zome_index: entry.entry_type.zome_index,
+                    entry_def_index: entry.entry_type.entry_index,
+                }),
+                entry.entry_type.visibility,
+                app_entry,
+                ChainTopOrdering::default(),
+            ))?,
+        };
+
+        let tag = RestoreProvenanceTag {
+            original_action_hash: entry.original_action_hash.clone(),
+            original_timestamp: entry.original_timestamp,
+        };
+        create_link(
+            action_hash.clone(),
+            restore_hash.clone(),
+            LinkTypes::RestoredEntryProvenance,
+            LinkTag::new(ExternIO::encode(&tag).map_err(|e| wasm_error!(e))?.0),
+        )?;
+
+        if entry.revision_of.is_none() && same_entry_type(&entry.entry_type, &patient_type) {
+            patient_hashes.push(action_hash.clone());
+        }
+        replacements.insert(entry.original_action_hash.get_raw_39().to_vec(), action_hash.get_raw_39().to_vec());
+        if entry_hash != original_entry_hash {
+            replacements.insert(original_entry_hash.get_raw_39().to_vec(), entry_hash.get_raw_39().to_vec());
+        }
+        restored_actions.insert(entry.original_action_hash.clone(), action_hash);
+    }
+
+    for link in &payload.links {
+        let mut tag = link.tag.clone();
+        remap_raw_hashes(&mut tag, &replacements);
+        let action_hash = create_link(
+            remap_linkable(link.base.clone(), &replacements)?,
+            remap_linkable(link.target.clone(), &replacements)?,
+            ScopedLinkType {
+                zome_index: link.zome_index,
+                zome_type: link.link_type,
+            },
+            LinkTag::new(tag),
+        )?;
+        let provenance = RestoreProvenanceTag {
+            original_action_hash: link.original_action_hash.clone(),
+            original_timestamp: link.original_timestamp,
+        };
+        create_link(
+            action_hash,
+            restore_hash.clone(),
+            LinkTypes::RestoredEntryProvenance,
+            LinkTag::new(ExternIO::encode(&provenance).map_err(|e| wasm_error!(e))?.0),
+        )?;
+    }
+
+    let mut consents_awaiting_reconfirmation = 0;
+    if !input.grantee_key_changes.is_empty() {
+        for patient_hash in &patient_hashes {
+            let response = call(
+                CallTargetCell::Local,
+                ZomeName::from("consent"),
+                FunctionName::from("request_grantee_reconfirmation"),
+                None,
+                &GranteeReconfirmationInput {
+                    patient_hash: patient_hash.clone(),
+                    key_changes: input.grantee_key_changes.clone(),
+                },
+            )?;
+            let ZomeCallResponse::Ok(result) = response else {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Could not request consent re-confirmation: {:?}",
+                    response
+                ))));
+            };
+            let suspended: Vec<Record> = result.decode().map_err(|e| wasm_error!(e))?;
+            consents_awaiting_reconfirmation += suspended.len() as u32;
+        }
+    }
+
+    Ok(RestoreReport {
+        restore_hash,
+        backup_id: payload.backup_id,
+        entries_restored: payload.entries.len() as u32,
+        links_restored: payload.links.len() as u32,
+        patient_hashes,
+        consents_awaiting_reconfirmation,
+    })
+}
+
+/// Look up where a restored action came from, if it was restored
+#[hdk_extern]
+pub fn get_restore_provenance(action_hash: ActionHash) -> ExternResult<Option<RestoreProvenance>> {
+    let links = get_links(
+        LinkQuery::try_new(action_hash, LinkTypes::RestoredEntryProvenance)?,
+        GetStrategy::default(),
+    )?;
+    let Some(link) = links.into_iter().next() else {
+        return Ok(None);
+    };
+    let Some(restore_hash) = link.target.into_action_hash() else {
+        return Ok(None);
+    };
+    let Some(restore) = get(restore_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<ChainRestore>().ok().flatten())
+    else {
+        return Ok(None);
+    };
+    let tag: RestoreProvenanceTag = ExternIO(link.tag.0).decode().map_err(|e| wasm_error!(e))?;
+
+    Ok(Some(RestoreProvenance {
+        restore_hash,
+        backup_id: restore.backup_id,
+        original_agent: restore.original_agent,
+        original_action_hash: tag.original_action_hash,
+        original_timestamp: tag.original_timestamp,
+    }))
+}
+
+// ============================================================
+// FIELD KEY ROTATION
+// ============================================================
+
+/// Input for rotating a patient's field master key
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RotateFieldKeyInput {
+    pub patient_hash: ActionHash,
+    pub reason: String,
+}
+
+/// Input for sealing a sensitive field under the active master key
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SealPatientFieldInput {
+    pub patient_hash: ActionHash,
+    /// Entry type and field the sealed value will be stored in; the entry
+    /// type must be one of this zome's
+    pub entry_type: String,
+    pub field_name: String,
+    pub field_type: SensitiveFieldType,
+    pub plaintext: String,
+}
+
+/// Input for reading a sealed field from where it is stored
+#[derive(Serialize, Deserialize, Debug)]
+pub struct OpenPatientFieldInput {
+    pub patient_hash: ActionHash,
+    /// Record holding the field
+    pub record_hash: ActionHash,
+    pub field_name: String,
+}
+
+/// Input for confirming that a resealed field replaced its stored original
+///
+/// Both versions are read here; the key counts follow what they hold.
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordFieldResealedInput {
+    pub patient_hash: ActionHash,
+    /// Entry version holding the field under its previous key
+    pub previous_record: ActionHash,
+    /// Later version of the same entry holding the resealed field
+    pub updated_record: ActionHash,
+    pub field_name: String,
+}
+
+/// A rotated-out key and the fields still sealed under it
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RetainedKeyStatus {
+    pub key_id: String,
+    pub created_at: Timestamp,
+    pub remaining_fields: i64,
+}
+
+/// Progress of field re-encryption after key rotations
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct KeyRotationStatus {
+    pub active_key_id: Option<String>,
+    pub active_fields: i64,
+    /// Whether the active key is close enough to expiry to rotate
+    pub rotation_due: bool,
+    pub retained: Vec<RetainedKeyStatus>,
+    /// Fields still sealed under retained keys
+    pub fields_pending_migration: i64,
+    pub rotations: Vec<KeyRotationEvent>,
+}
+
+/// Rotate the patient's field master key
+///
+/// The first call creates the key. Later calls make a new active key,
+/// record a `FieldKeyRotation`, and retain the previous key until every
+/// field sealed under it has been resealed on read.
+#[hdk_extern]
+pub fn rotate_field_master_key(input: RotateFieldKeyInput) -> ExternResult<KeyRotationStatus> {
+    require_patient_self(&input.patient_hash)?;
+    let previous = field_key_ring(&input.patient_hash)?.map(|(ring, _)| ring.active);
+
+    let key = generate_master_key()?;
+    let version = previous.as_ref().map(|p| p.metadata.version + 1).unwrap_or(1);
+    let metadata = create_key_metadata(&key, version)?;
+    create_entry(&EntryTypes::FieldMasterKey(FieldMasterKey {
+        patient_hash: input.patient_hash.clone(),
+        metadata: metadata.clone(),
+        key: key.to_vec(),
+    }))?;
+
+    if let Some(previous) = previous {
+        let rotation = FieldKeyRotation {
+            patient_hash: input.patient_hash.clone(),
+            event: KeyRotationEvent {
+                old_key_id: previous.metadata.key_id,
+                new_key_id: metadata.key_id,
+                rotated_at: sys_time()?,
+                rotated_by: agent_info()?.agent_initial_pubkey,
+                reason: input.reason,
+            },
+        };
+        let rotation_hash = create_entry(&EntryTypes::FieldKeyRotation(rotation))?;
+        create_link(
+            input.patient_hash.clone(),
+            rotation_hash,
+            LinkTypes::PatientToKeyRotations,
+            (),
+        )?;
+    }
+    key_rotation_status(&input.patient_hash)
+}
+
+/// Seal a sensitive field under the patient's active master key, bound to
+/// the entry type and field it will be stored in
+#[hdk_extern]
+pub fn seal_patient_field(input: SealPatientFieldInput) -> ExternResult<EncryptedField> {
+    require_patient_self(&input.patient_hash)?;
+    let (ring, _) = require_field_key_ring(&input.patient_hash)?;
+    let entry_type = patient_entry_type(&input.entry_type)?;
+    let binding = FieldBinding::new(input.patient_hash, format!("{:?}", entry_type), input.field_name);
+    let sealed = seal_field(&input.plaintext, &binding, input.field_type, &ring)?;
+    count_field_key_usage(&binding.patient_hash, &ring.active.metadata, 1)?;
+    Ok(sealed)
+}
+
+/// Read a sealed field from the record holding it
+///
+/// The field is bound to the record's own entry type and patient, so a
+/// field sealed for another entry type or field is rejected. When the
+/// field was sealed under a retained key or an older key derivation, the
+/// result carries the field resealed under the active key; the caller
+/// stores it in place of the original and confirms with
+/// `record_field_resealed`.
+#[hdk_extern]
+pub fn open_patient_field(input: OpenPatientFieldInput) -> ExternResult<OpenedField> {
+    require_patient_self(&input.patient_hash)?;
+    let (ring, _) = require_field_key_ring(&input.patient_hash)?;
+    let stored = stored_field(&input.patient_hash, &input.record_hash, &input.field_name)?;
+    open_field(&stored.field, &stored.binding, &ring)
+}
+
+/// This zome's entry type called `name`, the form fields are bound to
+fn patient_entry_type(name: &str) -> ExternResult<UnitEntryTypes> {
+    UnitEntryTypes::iter()
+        .find(|unit| format!("{:?}", unit) == name)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("{} is not a patient zome entry type", name))))
+}
+
+/// Move a resealed field's count to the active key, deleting the previous
+/// key once no field is sealed under it
+///
+/// The updated version must be a later version of the same entry and hold
+/// the field sealed under the active key. Each reseal is counted once.
+#[hdk_extern]
+pub fn record_field_resealed(input: RecordFieldResealedInput) -> ExternResult<KeyRotationStatus> {
+    require_patient_self(&input.patient_hash)?;
+    let (ring, key_actions) = require_field_key_ring(&input.patient_hash)?;
+    let previous = stored_field(&input.patient_hash, &input.previous_record, &input.field_name)?;
+    let updated = stored_field(&input.patient_hash, &input.updated_record, &input.field_name)?;
+    if previous.lineage != updated.lineage || updated.written_at <= previous.written_at {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The updated record must be a later version of the same entry".to_string()
+        )));
+    }
+    let opened = open_field(&updated.field, &updated.binding, &ring)?;
+    if opened.resealed.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The updated record does not hold the field sealed under the active key".to_string()
+        )));
+    }
+    if previous.field.master_key_id == updated.field.master_key_id
+        || reseal_recorded(&input.updated_record, &input.previous_record, &input.field_name)?
+    {
+        return key_rotation_status(&input.patient_hash);
+    }
+
+    create_link(
+        input.updated_record.clone(),
+        input.previous_record.clone(),
+        LinkTypes::ResealedFields,
+        LinkTag::new(input.field_name.as_bytes().to_vec()),
+    )?;
+    count_field_key_usage(&input.patient_hash, &ring.active.metadata, 1)?;
+    let previous_key = previous
+        .field
+        .master_key_id
+        .as_ref()
+        .and_then(|key_id| ring.retained.iter().find(|key| &key.metadata.key_id == key_id));
+    if let Some(previous_key) = previous_key {
+        let remaining = count_field_key_usage(&input.patient_hash, &previous_key.metadata, -1)?;
+        if remaining <= 0 {
+            if let Some(action_hash) = key_actions.get(&previous_key.metadata.key_id) {
+                delete_entry(action_hash.clone())?;
+            }
+        }
+    }
+    key_rotation_status(&input.patient_hash)
+}
+
+/// A sealed field as stored, with the binding its record implies
+struct StoredField {
+    binding: FieldBinding,
+    field: EncryptedField,
+    /// Original action of the entry the record is a version of
+    lineage: ActionHash,
+    written_at: Timestamp,
+}
+
+/// Entries outside the patient record that name their patient
+#[derive(Deserialize, Debug)]
+struct PatientScopedEntry {
+    patient_hash: ActionHash,
+}
+
+/// Read `field_name` from a patient zome record, binding it to the
+/// record's own entry type and patient rather than to anything the caller
+/// claims
+fn stored_field(patient_hash: &ActionHash, record_hash: &ActionHash, field_name: &str) -> ExternResult<StoredField> {
+    let record = get(record_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record holding the field not found".to_string())))?;
+    let entry_type = record
+        .action()
+        .entry_type()
+        .and_then(|entry_type| {
+            UnitEntryTypes::iter().find(|unit| EntryType::try_from(*unit).ok().as_ref() == Some(entry_type))
+        })
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "Sealed fields are read from patient zome entries".to_string()
+        )))?;
+    let entry = record
+        .entry()
+        .as_option()
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Record holding the field has no entry".to_string())))?;
+    let lineage = match record.action() {
+        Action::Update(update) => update.original_action_address.clone(),
+        _ => record_hash.clone(),
+    };
+    let owner = match (entry_type, entry) {
+        (UnitEntryTypes::Patient, _) => lineage.clone(),
+        (_, Entry::App(bytes)) => holochain_serialized_bytes::decode::<_, PatientScopedEntry>(bytes.bytes())
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Record does not name its patient".to_string())))?
+            .patient_hash,
+        _ => return Err(wasm_error!(WasmErrorInner::Guest("Record does not name its patient".to_string()))),
+    };
+    if &owner != patient_hash {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "The record does not belong to this patient".to_string()
+        )));
+    }
+    Ok(StoredField {
+        binding: FieldBinding::new(patient_hash.clone(), format!("{:?}", entry_type), field_name),
+        field: stored_sealed_field(entry, field_name)?,
+        lineage,
+        written_at: record.action().timestamp(),
+    })
+}
+
+fn reseal_recorded(updated_record: &ActionHash, previous_record: &ActionHash, field_name: &str) -> ExternResult<bool> {
+    Ok(get_links(
+        LinkQuery::try_new(updated_record.clone(), LinkTypes::ResealedFields)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .any(|link| {
+        link.tag.as_ref() == field_name.as_bytes() && link.target.into_action_hash().as_ref() == Some(previous_record)
+    }))
+}
+
+/// Active key, retained keys, and how many fields still need resealing
+#[hdk_extern]
+pub fn get_key_rotation_status(patient_hash: ActionHash) -> ExternResult<KeyRotationStatus> {
+    require_patient_self(&patient_hash)?;
+    key_rotation_status(&patient_hash)
+}
+
+fn key_rotation_status(patient_hash: &ActionHash) -> ExternResult<KeyRotationStatus> {
+    let ring = field_key_ring(patient_hash)?.map(|(ring, _)| ring);
+    let (active_key_id, active_fields, rotation_due) = match &ring {
+        Some(ring) => (
+            Some(ring.active.metadata.key_id.clone()),
+            read_field_key_usage(patient_hash, &ring.active.metadata)?,
+            should_rotate_key(&ring.active.metadata)?,
+        ),
+        None => (None, 0, false),
+    };
+    let mut retained = Vec::new();
+    for key in ring.iter().flat_map(|ring| ring.retained.iter()) {
+        retained.push(RetainedKeyStatus {
+            key_id: key.metadata.key_id.clone(),
+            created_at: key.metadata.created_at,
+            remaining_fields: read_field_key_usage(patient_hash, &key.metadata)?.max(0),
+        });
+    }
+
+    let links = get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToKeyRotations)?,
+        GetStrategy::default(),
+    )?;
+    let mut rotations = Vec::new();
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if let Some(rotation) = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<FieldKeyRotation>().ok().flatten())
+        {
+            rotations.push(rotation.event);
+        }
+    }
+    rotations.sort_by_key(|event| event.rotated_at);
+
+    Ok(KeyRotationStatus {
+        active_key_id,
+        active_fields,
+        rotation_due,
+        fields_pending_migration: retained.iter().map(|key| key.remaining_fields).sum(),
+        retained,
+        rotations,
+    })
+}
+
+/// The patient's field keys from this agent's chain: the newest is active,
+/// older undeleted keys are retained. Also maps key ids to their actions.
+fn field_key_ring(patient_hash: &ActionHash) -> ExternResult<Option<(MasterKeyRing, HashMap<String, ActionHash>)>> {
+    let records = query(ChainQueryFilter::new().include_entries(true))?;
+    let deleted: HashSet<ActionHash> = records
+        .iter()
+        .filter_map(|record| match record.action() {
+            Action::Delete(delete) => Some(delete.deletes_address.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut keys: Vec<MasterKey> = Vec::new();
+    let mut actions = HashMap::new();
+    for record in &records {
+        if deleted.contains(record.action_address()) {
+            continue;
+        }
+        let Some(stored) = record.entry().to_app_option::<FieldMasterKey>().ok().flatten() else {
+            continue;
+        };
+        let Ok(key) = <[u8; 32]>::try_from(stored.key.as_slice()) else {
+            continue;
+        };
+        if &stored.patient_hash != patient_hash {
+            continue;
+        }
+        actions.insert(stored.metadata.key_id.clone(), record.action_address().clone());
+        keys.push(MasterKey { metadata: stored.metadata, key });
+    }
+    keys.sort_by_key(|key| key.metadata.created_at);
+
+    let Some(active) = keys.pop() else {
+        return Ok(None);
+    };
+    let retained = keys
+        .into_iter()
+        .rev()
+        .map(|mut key| {
+            key.metadata.is_active = false;
+            key
+        })
+        .collect();
+    Ok(Some((MasterKeyRing { active, retained }, actions)))
+}
+
+fn require_field_key_ring(patient_hash: &ActionHash) -> ExternResult<(MasterKeyRing, HashMap<String, ActionHash>)> {
+    field_key_ring(patient_hash)?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "No field master key; call rotate_field_master_key first".to_string()
+    )))
+}
+
+/// Usage counter anchor for one of the patient's keys, named by its full
+/// fingerprint so keys of different patients never share a counter
+fn field_key_usage_anchor(patient_hash: &ActionHash, key: &KeyMetadata) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("field_key:{}:{}", patient_hash, key.key_hash))
+}
+
+fn count_field_key_usage(patient_hash: &ActionHash, key: &KeyMetadata, delta: i64) -> ExternResult<i64> {
+    increment_counter(field_key_usage_anchor(patient_hash, key)?, LinkTypes::FieldKeyUsage, delta)
+}
+
+fn read_field_key_usage(patient_hash: &ActionHash, key: &KeyMetadata) -> ExternResult<i64> {
+    read_counter(field_key_usage_anchor(patient_hash, key)?, LinkTypes::FieldKeyUsage)
+}
+
+// ============================================================
+// BLIND INDEXES
+// ============================================================
+
+/// Input for indexing a patient's searchable field
+#[derive(Serialize, Deserialize, Debug)]
+pub struct IndexPatientFieldInput {
+    pub patient_hash: ActionHash,
+    pub field_type: SensitiveFieldType,
+    pub plaintext: String,
+}
+
+/// Input for an exact-match lookup through the blind index
+#[derive(Serialize, Deserialize, Debug)]
+pub struct BlindIndexLookupInput {
+    pub field_type: SensitiveFieldType,
+    pub plaintext: String,
+}
+
+/// Field type MRNs are indexed under
+fn mrn_field_type() -> SensitiveFieldType {
+    SensitiveFieldType::Other("MRN".to_string())
+}
+
+/// Create a new blind index key for this admin's lookups (admin only)
+///
+/// Earlier keys stay on the chain so values indexed under them are still
+/// found; re-index under the new key to retire them.
+#[hdk_extern]
+pub fn rotate_blind_index_key(_: ()) -> ExternResult<KeyMetadata> {
+    require_admin_authorization()?;
+    let version = blind_index_keys()?.first().map(|key| key.metadata.version + 1).unwrap_or(1);
+    let key = generate_master_key()?;
+    let metadata = create_key_metadata(&key, version)?;
+    create_entry(&EntryTypes::BlindIndexKey(BlindIndexKey {
+        metadata: metadata.clone(),
+        key: key.to_vec(),
+    }))?;
+    Ok(metadata)
+}
+
+/// Index a patient's field under this admin's active blind index key
+/// (admin only)
+///
+/// The plaintext is only hashed; store the field itself sealed with
+/// `seal_patient_field`. The key stays on the admin's private chain, so
+/// only the admin who indexed a value can look it up.
+#[hdk_extern]
+pub fn index_patient_field(input: IndexPatientFieldInput) -> ExternResult<BlindIndex> {
+    require_admin_authorization()?;
+    let key = blind_index_keys()?.into_iter().next().ok_or(wasm_error!(WasmErrorInner::Guest(
+        "No blind index key; call rotate_blind_index_key first".to_string()
+    )))?;
+    let index = blind_index_for(&input.plaintext, input.field_type, &key)?;
+    create_link(
+        anchor_hash(&index.anchor())?,
+        input.patient_hash,
+        LinkTypes::BlindIndexToPatients,
+        (),
+    )?;
+    Ok(index)
+}
+
+/// Patients whose field this admin indexed as `plaintext` (admin only)
+///
+/// Matches are candidates: index links are not tied to the field's
+/// ciphertext, so callers still authorize and check the record they read.
+#[hdk_extern]
+pub fn find_patients_by_blind_index(input: BlindIndexLookupInput) -> ExternResult<Vec<ActionHash>> {
+    require_admin_authorization()?;
+    blind_index_matches(&input.field_type, &input.plaintext)
+}
+
+fn blind_index_matches(field_type: &SensitiveFieldType, plaintext: &str) -> ExternResult<Vec<ActionHash>> {
+    let mut matches = Vec::new();
+    for key in blind_index_keys()? {
+        let index = blind_index_for(plaintext, field_type.clone(), &key)?;
+        let links = get_links(
+            LinkQuery::try_new(anchor_hash(&index.anchor())?, LinkTypes::BlindIndexToPatients)?,
+            GetStrategy::default(),
+        )?;
+        for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+            if !matches.contains(&hash) {
+                matches.push(hash);
+            }
+        }
+    }
+    Ok(matches)
+}
+
+/// This agent's blind index keys, newest first
+fn blind_index_keys() -> ExternResult<Vec<MasterKey>> {
+    let records = query(
+        ChainQueryFilter::new()
+            .entry_type(UnitEntryTypes::BlindIndexKey.try_into()?)
+            .include_entries(true),
+    )?;
+    let mut keys: Vec<MasterKey> = records
+        .iter()
+        .filter_map(|record| record.entry().to_app_option::<BlindIndexKey>().ok().flatten())
+        .filter_map(|stored| {
+            let key = <[u8; 32]>::try_from(stored.key.as_slice()).ok()?;
+            Some(MasterKey { metadata: stored.metadata, key })
+        })
+        .collect();
+    keys.sort_by_key(|key| std::cmp::Reverse(key.metadata.created_at));
+    Ok(keys)
+}
+
+// ============================================================
+// KEY ESCROW
+// ============================================================
+
+/// An agent designated to hold one escrow share
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EscrowRecoveryAgent {
+    pub agent: AgentPubKey,
+    pub role: RecoveryRole,
+}
+
+/// Input for escrowing the active field master key
+#[derive(Serialize, Deserialize, Debug)]
+pub struct EscrowFieldKeyInput {
+    pub patient_hash: ActionHash,
+    pub threshold: u8,
+    pub recovery_agents: Vec<EscrowRecoveryAgent>,
+}
+
+/// Shares issued for one escrowed key
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EscrowIssuance {
+    pub key_id: String,
+    pub threshold: u8,
+    pub share_hashes: Vec<ActionHash>,
+}
+
+/// Input for rebuilding an escrowed key from released shares
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecoverEscrowedKeyInput {
+    pub patient_hash: ActionHash,
+    pub key_id: String,
+}
+
+/// Result of a recovery attempt; failures are reported here rather than
+/// as errors so that the attempt's audit entry is kept
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EscrowRecoveryOutcome {
+    pub key_id: String,
+    pub shares_used: u32,
+    pub recovered: bool,
+    pub reason: Option<String>,
+}
+
+/// Split the patient's active field master key into one share per recovery
+/// agent, any `threshold` of which rebuild it
+///
+/// Each share is boxed to its agent's key, so only that agent can release it.
+#[hdk_extern]
+pub fn escrow_field_master_key(input: EscrowFieldKeyInput) -> ExternResult<EscrowIssuance> {
+    require_patient_self(&input.patient_hash)?;
+    let (ring, _) = require_field_key_ring(&input.patient_hash)?;
+    let me = agent_info()?.agent_initial_pubkey;
+    let mut agents = HashSet::new();
+    if input.recovery_agents.iter().any(|a| a.agent == me || !agents.insert(a.agent.clone())) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Recovery agents must be distinct and exclude the patient".to_string()
+        )));
+    }
+    let count = u8::try_from(input.recovery_agents.len()).map_err(|_| {
+        wasm_error!(WasmErrorInner::Guest("At most 255 recovery agents are supported".to_string()))
+    })?;
+
+    let key = &ring.active;
+    let shares = split_secret(&key.key, input.threshold, count)?;
+    let mut share_hashes = Vec::new();
+    for (share, recovery) in shares.into_iter().zip(input.recovery_agents) {
+        let encrypted_share = ed_25519_x_salsa20_poly1305_encrypt(
+            me.clone(),
+            recovery.agent.clone(),
+            share.value.into(),
+        )?;
+        let share_hash = create_entry(&EntryTypes::EscrowShare(EscrowShare {
+            patient_hash: input.patient_hash.clone(),
+            key_metadata: key.metadata.clone(),
+            threshold: input.threshold,
+            share_index: share.index,
+            recovery_agent: recovery.agent.clone(),
+            role: recovery.role.clone(),
+            issued_by: me.clone(),
+            encrypted_share,
+        }))?;
+        create_link(input.patient_hash.clone(), share_hash.clone(), LinkTypes::PatientToEscrowShares, ())?;
+        create_link(recovery.agent.clone(), share_hash.clone(), LinkTypes::RecoveryAgentToEscrowShares, ())?;
+        record_escrow_audit(
+            &input.patient_hash,
+            &key.metadata.key_id,
+            EscrowAuditAction::ShareIssued {
+                share_index: share.index,
+                recovery_agent: recovery.agent,
+                role: recovery.role,
+            },
+        )?;
+        share_hashes.push(share_hash);
+    }
+    Ok(EscrowIssuance {
+        key_id: key.metadata.key_id.clone(),
+        threshold: input.threshold,
+        share_hashes,
+    })
+}
+
+/// Escrow shares held by the calling agent
+#[hdk_extern]
+pub fn get_my_escrow_shares(_: ()) -> ExternResult<Vec<Record>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(me, LinkTypes::RecoveryAgentToEscrowShares)?,
+        GetStrategy::default(),
+    )?;
+    let mut records = Vec::new();
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        if let Some(record) = get(hash, GetOptions::default())? {
+            records.push(record);
+        }
+    }
+    Ok(records)
+}
+
+/// Release a held share to the patient, re-boxed to the patient's key
+#[hdk_extern]
+pub fn release_escrow_share(share_hash: ActionHash) -> ExternResult<Record> {
+    let share: EscrowShare = get(share_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Escrow share not found".to_string())))?;
+    let me = agent_info()?.agent_initial_pubkey;
+    if share.recovery_agent != me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the share's recovery agent can release it".to_string()
+        )));
+    }
+    let patient = get_patient_internal(share.patient_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Patient not found".to_string())))?;
+    let recipient = patient.action().author().clone();
+
+    let value = ed_25519_x_salsa20_poly1305_decrypt(me.clone(), share.issued_by.clone(), share.encrypted_share)?;
+    let encrypted_share = ed_25519_x_salsa20_poly1305_encrypt(me.clone(), recipient.clone(), value)?;
+    let release_hash = create_entry(&EntryTypes::EscrowShareRelease(EscrowShareRelease {
+        share_hash,
+        patient_hash: share.patient_hash.clone(),
+        key_id: share.key_metadata.key_id.clone(),
+        recipient: recipient.clone(),
+        released_by: me,
+        encrypted_share,
+    }))?;
+    create_link(share.patient_hash.clone(), release_hash.clone(), LinkTypes::PatientToShareReleases, ())?;
+    record_escrow_audit(
+        &share.patient_hash,
+        &share.key_metadata.key_id,
+        EscrowAuditAction::ShareReleased { share_index: share.share_index, recipient },
+    )?;
+    get(release_hash, GetOptions::default())?.ok_or(wasm_error!(WasmErrorInner::Guest(
+        "Could not find the released share".to_string()
+    )))
+}
+
+/// Rebuild an escrowed field master key from the shares released to the
+/// patient, restoring it to this chain once it matches its fingerprint
+///
+/// Every attempt is audited, successful or not.
+#[hdk_extern]
+pub fn recover_escrowed_key(input: RecoverEscrowedKeyInput) -> ExternResult<EscrowRecoveryOutcome> {
+    require_patient_self(&input.patient_hash)?;
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToShareReleases)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut shares = Vec::new();
+    let mut escrowed: Option<EscrowShare> = None;
+    for hash in links.into_iter().filter_map(|link| link.target.into_action_hash()) {
+        let Some(release) = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<EscrowShareRelease>().ok().flatten())
+        else {
+            continue;
+        };
+        if release.key_id != input.key_id || release.recipient != me {
+            continue;
+        }
+        let Some(share) = get(release.share_hash.clone(), GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<EscrowShare>().ok().flatten())
+        else {
+            continue;
+        };
+        let Ok(value) = ed_25519_x_salsa20_poly1305_decrypt(me.clone(), release.released_by, release.encrypted_share)
+        else {
+            continue;
+        };
+        shares.push(SecretShare { index: share.share_index, value: value.as_ref().to_vec() });
+        escrowed.get_or_insert(share);
+    }
+
+    let shares_used = shares.len() as u32;
+    let result = match &escrowed {
+        None => Err("No shares have been released for this key".to_string()),
+        Some(escrowed) => combine_shares(&shares, escrowed.threshold).and_then(|secret| {
+            let key = <[u8; 32]>::try_from(secret.as_slice())
+                .map_err(|_| "Recovered secret is not a field master key".to_string())?;
+            match key_matches_metadata(&key, &escrowed.key_metadata) {
+                Ok(true) => Ok(key),
+                _ => Err("Recovered key does not match the escrowed fingerprint".to_string()),
+            }
+        }),
+    };
+
+    let reason = match (&result, &escrowed) {
+        (Ok(key), Some(escrowed)) => {
+            let already_held = field_key_ring(&input.patient_hash)?.is_some_and(|(ring, _)| {
+                std::iter::once(&ring.active)
+                    .chain(&ring.retained)
+                    .any(|held| held.metadata.key_id == input.key_id)
+            });
+            if !already_held {
+                create_entry(&EntryTypes::FieldMasterKey(FieldMasterKey {
+                    patient_hash: input.patient_hash.clone(),
+                    metadata: escrowed.key_metadata.clone(),
+                    key: key.to_vec(),
+                }))?;
+            }
+            None
+        }
+        (Err(reason), _) => Some(reason.clone()),
+        (Ok(_), None) => None,
+    };
+    let recovered = reason.is_none();
+    record_escrow_audit(
+        &input.patient_hash,
+        &input.key_id,
+        EscrowAuditAction::RecoveryAttempted { shares_used, succeeded: recovered, reason: reason.clone() },
+    )?;
+    Ok(EscrowRecoveryOutcome { key_id: input.key_id, shares_used, recovered, reason })
+}
+
+/// Share issuances, releases, and recovery attempts for the patient
+#[hdk_extern]
+pub fn get_escrow_audit(patient_hash: ActionHash) -> ExternResult<Vec<EscrowAuditEvent>> {
+    require_patient_self(&patient_hash)?;
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToEscrowAudit)?,
+        GetStrategy::default(),
+    )?;
+    let mut events = links_to_entries::<EscrowAuditEvent>(links)?;
+    events.sort_by_key(|event| event.occurred_at);
+    Ok(events)
+}
+
+fn record_escrow_audit(patient_hash: &ActionHash, key_id: &str, action: EscrowAuditAction) -> ExternResult<()> {
+    let event_hash = create_entry(&EntryTypes::EscrowAuditEvent(EscrowAuditEvent {
+        patient_hash: patient_hash.clone(),
+        key_id: key_id.to_string(),
+        action,
+        actor: agent_info()?.agent_initial_pubkey,
+        occurred_at: sys_time()?,
+    }))?;
+    create_link(patient_hash.clone(), event_hash, LinkTypes::PatientToEscrowAudit, ())?;
+    Ok(())
+}
+
 // Helper function to create anchor hash
 /// Anchor entry for indexing
 #[hdk_entry_helper]
@@ -656,3 +2093,71 @@ fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
     let anchor = Anchor(anchor_text.to_string());
     hash_entry(&anchor)
 }
+
+// ============================================================
+// DOMAIN EVENTS
+// ============================================================
+
+/// Domain event handlers this zome registers with the bridge's event bus
+#[hdk_extern]
+pub fn domain_event_subscriptions(_: ()) -> ExternResult<Vec<DomainEventHandler>> {
+    Ok(vec![DomainEventHandler::new("handle_consent_revoked", &[DomainEventKind::ConsentRevoked])])
+}
+
+/// Forget cached authorizations for the patient so profile reads recheck consent
+#[hdk_extern]
+pub fn handle_consent_revoked(notice: DomainEventNotice) -> ExternResult<()> {
+    invalidate_authorization_cache(&notice.patient_hash);
+    Ok(())
+}
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_patient", "Patient", "Record"),
+        ("create_patient_provisional", "Patient", "ProvisionalCommit"),
+        ("confirm_entry_published", "ActionHash", "PublicationConfirmation"),
+        ("get_patient", "GetPatientInput", "Option<Record>"),
+        ("update_patient", "UpdatePatientInput", "Record"),
+        ("delete_patient", "DeletePatientInput", "ActionHash"),
+        ("get_patient_jurisdiction", "ActionHash", "Option<String>"),
+        ("get_patient_count", "()", "u64"),
+        ("get_all_patients", "()", "Vec<Record>"),
+        ("search_patients_by_name", "SearchPatientsInput", "Vec<Record>"),
+        ("link_patient_to_identity", "LinkIdentityInput", "Record"),
+        ("get_patient_by_did", "GetPatientByDIDInput", "Option<Record>"),
+        ("get_did_for_patient", "GetDIDForPatientInput", "Option<PatientDIDInfo>"),
+        ("verify_did_patient_link", "VerifyDIDPatientLinkInput", "bool"),
+        ("create_health_summary", "PatientHealthSummary", "Record"),
+        ("get_patient_health_summary", "GetPatientInput", "Option<Record>"),
+        ("add_patient_allergy", "AddAllergyInput", "Record"),
+        ("get_patient_by_mrn", "GetPatientByMrnInput", "Option<Record>"),
+        ("issue_emergency_card", "IssueEmergencyCardInput", "IssuedEmergencyCard"),
+        ("revoke_emergency_card", "RevokeEmergencyCardInput", "Record"),
+        ("verify_emergency_card", "SignedEmergencyCard", "EmergencyCardVerification"),
+        ("get_patient_emergency_cards", "GetPatientInput", "Vec<Record>"),
+        ("create_chain_backup", "CreateChainBackupInput", "ChainBackup"),
+        ("restore_from_backup", "RestoreFromBackupInput", "RestoreReport"),
+        ("get_restore_provenance", "ActionHash", "Option<RestoreProvenance>"),
+        ("rotate_field_master_key", "RotateFieldKeyInput", "KeyRotationStatus"),
+        ("seal_patient_field", "SealPatientFieldInput", "EncryptedField"),
+        ("open_patient_field", "OpenPatientFieldInput", "OpenedField"),
+        ("record_field_resealed", "RecordFieldResealedInput", "KeyRotationStatus"),
+        ("get_key_rotation_status", "ActionHash", "KeyRotationStatus"),
+        ("escrow_field_master_key", "EscrowFieldKeyInput", "EscrowIssuance"),
+        ("get_my_escrow_shares", "()", "Vec<Record>"),
+        ("release_escrow_share", "ActionHash", "Record"),
+        ("recover_escrowed_key", "RecoverEscrowedKeyInput", "EscrowRecoveryOutcome"),
+        ("get_escrow_audit", "ActionHash", "Vec<EscrowAuditEvent>"),
+        ("rotate_blind_index_key", "()", "KeyMetadata"),
+        ("index_patient_field", "IndexPatientFieldInput", "BlindIndex"),
+        ("find_patients_by_blind_index", "BlindIndexLookupInput", "Vec<ActionHash>"),
+        ("domain_event_subscriptions", "()", "Vec<DomainEventHandler>"),
+        ("handle_consent_revoked", "DomainEventNotice", "()"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["network_statistics", "provisional_commits", "emergency_card", "chain_backup", "jurisdiction_tags", "site_validation_rules", "field_key_rotation", "key_escrow", "blind_index", "authorization_cache"],
+}
@@ -4,6 +4,10 @@
 //! and demographic information with HIPAA-compliant validation.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{entry_size_violation, EntrySizeLimits, DEFAULT_ENTRY_SIZE_LIMITS};
+use mycelix_health_shared::validation::validate_jurisdiction;
+use mycelix_health_shared::key_management::{key_matches_metadata, KeyMetadata, KeyRotationEvent};
+use mycelix_health_shared::key_management::escrow::RecoveryRole;
 
 /// Patient profile with demographics and health identifiers
 #[hdk_entry_helper]
@@ -45,6 +49,12 @@ pub struct Patient {
     pub created_at: Timestamp,
     /// Timestamp of last update
     pub updated_at: Timestamp,
+    /// Preferred display timezone as minutes east of UTC (reports use UTC when unset)
+    #[serde(default)]
+    pub utc_offset_minutes: Option<i32>,
+    /// Jurisdiction whose data rules apply (ISO 3166 code, e.g. "DE" or "US-CA")
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -134,12 +144,163 @@ pub struct PatientHealthSummary {
     pub care_team: Vec<AgentPubKey>,
 }
 
+/// Largest encoded emergency card payload, so it fits a QR code or NFC tag
+pub const EMERGENCY_CARD_MAX_BYTES: usize = 2048;
+/// Caps on list fields to keep emergency cards minimal
+pub const EMERGENCY_CARD_MAX_ITEMS: usize = 10;
+pub const EMERGENCY_CARD_MAX_CONTACTS: usize = 3;
+
+/// Minimal dataset a patient curates for responders who may have no
+/// network access
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EmergencyCard {
+    pub card_id: String,
+    pub patient_hash: ActionHash,
+    pub blood_type: Option<BloodType>,
+    pub allergies: Vec<EmergencyCardAllergy>,
+    pub critical_medications: Vec<String>,
+    pub dnr_status: DnrStatus,
+    pub emergency_contacts: Vec<EmergencyContact>,
+    pub issued_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct EmergencyCardAllergy {
+    pub allergen: String,
+    pub severity: AllergySeverity,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum DnrStatus {
+    NotStated,
+    FullCode,
+    DoNotResuscitate,
+}
+
+/// Withdrawal of an emergency card; responders' apps check for this when
+/// they are back online
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EmergencyCardRevocation {
+    pub card_hash: ActionHash,
+    pub card_id: String,
+    pub revoked_at: Timestamp,
+    pub reason: Option<String>,
+}
+
+/// Marker that this agent's chain was re-created from another agent's backup
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct ChainRestore {
+    pub backup_id: String,
+    /// Agent whose chain the backup was taken from
+    pub original_agent: AgentPubKey,
+    pub backup_created_at: Timestamp,
+    pub restored_at: Timestamp,
+    pub entries_restored: u32,
+    pub links_restored: u32,
+}
+
+/// Tag of a `RestoredEntryProvenance` link: where a restored action came from
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct RestoreProvenanceTag {
+    pub original_action_hash: ActionHash,
+    pub original_timestamp: Timestamp,
+}
+
+/// Master key for a patient's field-level encryption; private, so the key
+/// material never leaves the patient's source chain
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FieldMasterKey {
+    pub patient_hash: ActionHash,
+    pub metadata: KeyMetadata,
+    pub key: Vec<u8>,
+}
+
+/// Key for blind indexing searchable fields; private to the admin that
+/// indexes and searches, since anyone holding it can test guesses against
+/// the index
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct BlindIndexKey {
+    pub metadata: KeyMetadata,
+    pub key: Vec<u8>,
+}
+
+/// Public record that a patient rotated their field master key
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FieldKeyRotation {
+    pub patient_hash: ActionHash,
+    pub event: KeyRotationEvent,
+}
+
+/// One share of an escrowed field master key, boxed to its recovery agent
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EscrowShare {
+    pub patient_hash: ActionHash,
+    /// Metadata of the escrowed key, used to verify a reconstruction
+    pub key_metadata: KeyMetadata,
+    pub threshold: u8,
+    pub share_index: u8,
+    pub recovery_agent: AgentPubKey,
+    pub role: RecoveryRole,
+    pub issued_by: AgentPubKey,
+    pub encrypted_share: XSalsa20Poly1305EncryptedData,
+}
+
+/// A recovery agent's share re-boxed to the agent recovering the key
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EscrowShareRelease {
+    pub share_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub key_id: String,
+    pub recipient: AgentPubKey,
+    pub released_by: AgentPubKey,
+    pub encrypted_share: XSalsa20Poly1305EncryptedData,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum EscrowAuditAction {
+    ShareIssued { share_index: u8, recovery_agent: AgentPubKey, role: RecoveryRole },
+    ShareReleased { share_index: u8, recipient: AgentPubKey },
+    RecoveryAttempted { shares_used: u32, succeeded: bool, reason: Option<String> },
+}
+
+/// Audit trail of key escrow; written for every share issuance, release,
+/// and recovery attempt
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct EscrowAuditEvent {
+    pub patient_hash: ActionHash,
+    pub key_id: String,
+    pub action: EscrowAuditAction,
+    pub actor: AgentPubKey,
+    pub occurred_at: Timestamp,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
     Patient(Patient),
     PatientIdentityLink(PatientIdentityLink),
     PatientHealthSummary(PatientHealthSummary),
+    EmergencyCard(EmergencyCard),
+    EmergencyCardRevocation(EmergencyCardRevocation),
+    ChainRestore(ChainRestore),
+    #[entry_type(visibility = "private")]
+    FieldMasterKey(FieldMasterKey),
+    FieldKeyRotation(FieldKeyRotation),
+    EscrowShare(EscrowShare),
+    EscrowShareRelease(EscrowShareRelease),
+    EscrowAuditEvent(EscrowAuditEvent),
+    #[entry_type(visibility = "private")]
+    BlindIndexKey(BlindIndexKey),
 }
 
 #[hdk_link_types]
@@ -158,22 +319,81 @@ pub enum LinkTypes {
     DIDToPatient,
     /// Link from patient to their identity verification records
     PatientToIdentityLink,
+    /// Counter shards behind the operator statistics
+    StatisticsCounter,
+    /// Link from patient to the emergency cards they have issued
+    PatientToEmergencyCards,
+    /// Link from an emergency card to its revocation
+    EmergencyCardRevocations,
+    /// Link from a restored action to the chain restore that re-created it
+    RestoredEntryProvenance,
+    /// Link from patient to their field key rotations
+    PatientToKeyRotations,
+    /// Counter shards of the fields sealed under each field master key
+    FieldKeyUsage,
+    PatientToEscrowShares,
+    RecoveryAgentToEscrowShares,
+    PatientToShareReleases,
+    PatientToEscrowAudit,
+    /// Blind index anchor to the patients whose field has that token
+    BlindIndexToPatients,
+    /// Entry version storing a resealed field to the version it replaced,
+    /// tagged with the field name; each reseal is counted once
+    ResealedFields,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = DEFAULT_ENTRY_SIZE_LIMITS;
+
 /// Validation for Patient entries
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Patient(patient) => validate_patient(&patient),
                 EntryTypes::PatientIdentityLink(link) => validate_identity_link(&link),
                 EntryTypes::PatientHealthSummary(summary) => validate_health_summary(&summary),
+                EntryTypes::EmergencyCard(card) => validate_emergency_card(&card, &action.author),
+                EntryTypes::EmergencyCardRevocation(revocation) => validate_card_revocation(&revocation, &action.author),
+                EntryTypes::ChainRestore(restore) => validate_chain_restore(&restore, &action.author),
+                EntryTypes::FieldMasterKey(key) => validate_field_master_key(&key),
+                EntryTypes::FieldKeyRotation(rotation) => validate_field_key_rotation(&rotation, &action.author),
+                EntryTypes::EscrowShare(share) => validate_escrow_share(&share, &action.author),
+                EntryTypes::EscrowShareRelease(release) => validate_share_release(&release, &action.author),
+                EntryTypes::EscrowAuditEvent(event) => {
+                    if event.actor == action.author {
+                        Ok(ValidateCallbackResult::Valid)
+                    } else {
+                        Ok(ValidateCallbackResult::Invalid("Escrow audit events are written by their actor".to_string()))
+                    }
+                }
+                EntryTypes::BlindIndexKey(key) => validate_blind_index_key(&key),
             },
             OpEntry::UpdateEntry { app_entry, .. } => match app_entry {
                 EntryTypes::Patient(patient) => validate_patient(&patient),
                 EntryTypes::PatientIdentityLink(link) => validate_identity_link(&link),
                 EntryTypes::PatientHealthSummary(summary) => validate_health_summary(&summary),
+                // Signed payloads are already out in the world; revoke and reissue instead
+                EntryTypes::EmergencyCard(_) | EntryTypes::EmergencyCardRevocation(_) => Ok(
+                    ValidateCallbackResult::Invalid("Emergency cards cannot be updated".to_string()),
+                ),
+                EntryTypes::ChainRestore(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Chain restore markers cannot be updated".to_string(),
+                )),
+                // Keys are retired by deleting them once no field needs them
+                EntryTypes::FieldMasterKey(_) | EntryTypes::FieldKeyRotation(_) => Ok(
+                    ValidateCallbackResult::Invalid("Field keys and rotations cannot be updated".to_string()),
+                ),
+                EntryTypes::EscrowShare(_) | EntryTypes::EscrowShareRelease(_) | EntryTypes::EscrowAuditEvent(_) => Ok(
+                    ValidateCallbackResult::Invalid("Escrow records cannot be updated".to_string()),
+                ),
+                EntryTypes::BlindIndexKey(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Blind index keys are rotated, not updated".to_string(),
+                )),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -189,6 +409,18 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
             LinkTypes::PatientToDID => Ok(ValidateCallbackResult::Valid),
             LinkTypes::DIDToPatient => Ok(ValidateCallbackResult::Valid),
             LinkTypes::PatientToIdentityLink => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::StatisticsCounter => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::PatientToEmergencyCards => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::EmergencyCardRevocations => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::RestoredEntryProvenance => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::PatientToKeyRotations => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::FieldKeyUsage => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::PatientToEscrowShares => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::RecoveryAgentToEscrowShares => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::PatientToShareReleases => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::PatientToEscrowAudit => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::BlindIndexToPatients => Ok(ValidateCallbackResult::Valid),
+            LinkTypes::ResealedFields => Ok(ValidateCallbackResult::Valid),
         },
         _ => Ok(ValidateCallbackResult::Valid),
     }
@@ -223,6 +455,20 @@ fn validate_patient(patient: &Patient) -> ExternResult<ValidateCallbackResult> {
         ));
     }
 
+    if let Some(offset) = patient.utc_offset_minutes {
+        if !(-720..=840).contains(&offset) {
+            return Ok(ValidateCallbackResult::Invalid(
+                "UTC offset must be between -720 and 840 minutes".to_string(),
+            ));
+        }
+    }
+
+    if let Some(jurisdiction) = &patient.jurisdiction {
+        if let Some(error) = validate_jurisdiction(jurisdiction).errors.first() {
+            return Ok(ValidateCallbackResult::Invalid(error.message.clone()));
+        }
+    }
+
     // Validate allergies have required fields
     for allergy in &patient.allergies {
         if allergy.allergen.is_empty() || allergy.reaction.is_empty() {
@@ -270,6 +516,157 @@ fn validate_health_summary(_summary: &PatientHealthSummary) -> ExternResult<Vali
     Ok(ValidateCallbackResult::Valid)
 }
 
+fn validate_emergency_card(card: &EmergencyCard, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if card.card_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Emergency card ID is required".to_string(),
+        ));
+    }
+    if card.allergies.len() > EMERGENCY_CARD_MAX_ITEMS
+        || card.critical_medications.len() > EMERGENCY_CARD_MAX_ITEMS
+    {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Emergency cards list at most {} allergies and {} medications",
+            EMERGENCY_CARD_MAX_ITEMS, EMERGENCY_CARD_MAX_ITEMS
+        )));
+    }
+    if card.emergency_contacts.len() > EMERGENCY_CARD_MAX_CONTACTS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Emergency cards list at most {} contacts",
+            EMERGENCY_CARD_MAX_CONTACTS
+        )));
+    }
+    if card.allergies.iter().any(|a| a.allergen.is_empty())
+        || card.critical_medications.iter().any(|m| m.is_empty())
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Emergency card allergies and medications cannot be blank".to_string(),
+        ));
+    }
+    if card.expires_at.is_some_and(|expires| expires <= card.issued_at) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Emergency card must expire after it is issued".to_string(),
+        ));
+    }
+    let patient_record = must_get_valid_record(card.patient_hash.clone())?;
+    if patient_record.action().author() != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the patient can issue their emergency card".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_card_revocation(
+    revocation: &EmergencyCardRevocation,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let card_record = must_get_valid_record(revocation.card_hash.clone())?;
+    if card_record.action().author() != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the card's issuer can revoke an emergency card".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_chain_restore(restore: &ChainRestore, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if restore.backup_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Backup ID is required".to_string(),
+        ));
+    }
+    if &restore.original_agent == author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A backup can only be restored onto a new agent".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_field_master_key(key: &FieldMasterKey) -> ExternResult<ValidateCallbackResult> {
+    let Ok(material) = <[u8; 32]>::try_from(key.key.as_slice()) else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Field master keys are 32 bytes".to_string(),
+        ));
+    };
+    if key.metadata.key_id.is_empty() || !key_matches_metadata(&material, &key.metadata)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Field master key does not match its metadata".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_blind_index_key(key: &BlindIndexKey) -> ExternResult<ValidateCallbackResult> {
+    let Ok(material) = <[u8; 32]>::try_from(key.key.as_slice()) else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Blind index keys are 32 bytes".to_string(),
+        ));
+    };
+    if key.metadata.key_id.is_empty() || !key_matches_metadata(&material, &key.metadata)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Blind index key does not match its metadata".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_field_key_rotation(rotation: &FieldKeyRotation, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if &rotation.event.rotated_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Key rotations must be recorded by the rotating agent".to_string(),
+        ));
+    }
+    if rotation.event.old_key_id.is_empty() || rotation.event.old_key_id == rotation.event.new_key_id {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A rotation must replace one key with another".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_escrow_share(share: &EscrowShare, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if &share.issued_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Escrow shares are issued by their author".to_string(),
+        ));
+    }
+    if share.threshold < 2 || share.share_index == 0 {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Escrow needs a threshold of at least 2 and non-zero share indices".to_string(),
+        ));
+    }
+    if &share.recovery_agent == author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A patient cannot hold their own escrow share".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_share_release(release: &EscrowShareRelease, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if &release.released_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Share releases are written by the releasing agent".to_string(),
+        ));
+    }
+    let share: EscrowShare = must_get_valid_record(release.share_hash.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Escrow share not found".to_string())))?;
+    if &share.recovery_agent != author
+        || share.patient_hash != release.patient_hash
+        || share.key_metadata.key_id != release.key_id
+    {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only a share's recovery agent can release it".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
 fn is_valid_date_format(date: &str) -> bool {
     // Basic YYYY-MM-DD validation
     if date.len() != 10 {
@@ -2,6 +2,7 @@
 //!
 //! Provides extern functions for prescription management,
 //! pharmacy interactions, and medication adherence tracking.
+//! New prescriptions are routed to the patient's preferred pharmacy.
 //!
 //! All prescription data access enforces consent-based access control
 //! per HIPAA requirements. Controlled substance tracking has additional
@@ -11,10 +12,15 @@
 
 use hdk::prelude::*;
 use prescriptions_integrity::*;
+use mycelix_health_shared::{invalidate_authorization_cache, DomainEventHandler, DomainEventKind, DomainEventNotice};
 use mycelix_health_shared::{
-    require_authorization, require_admin_authorization,
-    log_data_access,
+    require_authorization, require_admin_authorization, require_patient_self,
+    log_data_access, audited_read,
     DataCategory, Permission,
+    RecordInventoryItem,
+    resilient_call, CallClass,
+    budgeted_page, links_to_records_budgeted, BudgetInput, BudgetedPage,
+    supply_coverage, AdherencePeriod, Dispense, SupplyCoverage, ADHERENCE_THRESHOLD,
 };
 use holochain_serialized_bytes::prelude::*;
 
@@ -142,7 +148,7 @@ pub struct CreatePrescriptionInput {
 
 /// Create a new prescription with access control
 #[hdk_extern]
-pub fn create_prescription(input: CreatePrescriptionInput) -> ExternResult<Record> {
+pub fn create_prescription(mut input: CreatePrescriptionInput) -> ExternResult<Record> {
     // Require Write authorization for Medications category
     let auth = require_authorization(
         input.prescription.patient_hash.clone(),
@@ -151,6 +157,7 @@ pub fn create_prescription(input: CreatePrescriptionInput) -> ExternResult<Recor
         input.is_emergency,
     )?;
 
+    route_prescription(&mut input.prescription)?;
     let rx_hash = create_entry(&EntryTypes::Prescription(input.prescription.clone()))?;
     let record = get(rx_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find prescription".to_string())))?;
@@ -171,6 +178,8 @@ pub fn create_prescription(input: CreatePrescriptionInput) -> ExternResult<Recor
         (),
     )?;
 
+    link_routing_target(&rx_hash, &input.prescription)?;
+
     // If controlled substance, add to tracking
     if input.prescription.schedule.is_some() && input.prescription.schedule != Some(DrugSchedule::NotControlled) {
         let controlled_anchor = anchor_hash("controlled_substances")?;
@@ -249,6 +258,9 @@ pub struct GetPatientPrescriptionsInput {
     pub patient_hash: ActionHash,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
 }
 
 /// Internal get without access control
@@ -268,70 +280,56 @@ fn get_patient_prescriptions_internal(patient_hash: ActionHash) -> ExternResult<
 }
 
 /// Get patient's prescriptions with access control
+///
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
 #[hdk_extern]
-pub fn get_patient_prescriptions(input: GetPatientPrescriptionsInput) -> ExternResult<Vec<Record>> {
-    // Require Read authorization for Medications category
-    let auth = require_authorization(
-        input.patient_hash.clone(),
+pub fn get_patient_prescriptions(input: GetPatientPrescriptionsInput) -> ExternResult<BudgetedPage<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let page = input.page.clone();
+    audited_read(
+        "prescriptions::get_patient_prescriptions",
+        input.patient_hash,
         DataCategory::Medications,
-        Permission::Read,
         input.is_emergency,
-    )?;
-
-    let prescriptions = get_patient_prescriptions_internal(input.patient_hash.clone())?;
-
-    // Log the access
-    if !prescriptions.is_empty() {
-        log_data_access(
-            input.patient_hash,
-            vec![DataCategory::Medications],
-            Permission::Read,
-            auth.consent_hash,
-            auth.emergency_override,
-            input.emergency_reason,
-        )?;
-    }
-
-    Ok(prescriptions)
+        input.emergency_reason,
+        || {
+            let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToPrescriptions)?, GetStrategy::default())?;
+            links_to_records_budgeted(links, &page)
+        },
+    )
 }
 
 /// Get active prescriptions for a patient with access control
+///
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
 #[hdk_extern]
-pub fn get_active_prescriptions(input: GetPatientPrescriptionsInput) -> ExternResult<Vec<Record>> {
-    // Require Read authorization for Medications category
-    let auth = require_authorization(
-        input.patient_hash.clone(),
+pub fn get_active_prescriptions(input: GetPatientPrescriptionsInput) -> ExternResult<BudgetedPage<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let page = input.page.clone();
+    audited_read(
+        "prescriptions::get_active_prescriptions",
+        input.patient_hash,
         DataCategory::Medications,
-        Permission::Read,
         input.is_emergency,
-    )?;
-
-    let all_rx = get_patient_prescriptions_internal(input.patient_hash.clone())?;
-
-    let active: Vec<Record> = all_rx
-        .into_iter()
-        .filter(|record| {
-            if let Some(rx) = record.entry().to_app_option::<Prescription>().ok().flatten() {
-                matches!(rx.status, PrescriptionStatus::Active)
-            } else {
-                false
-            }
-        })
-        .collect();
-
-    // Log the access
-    if !active.is_empty() {
-        log_data_access(
-            input.patient_hash,
-            vec![DataCategory::Medications],
-            Permission::Read,
-            auth.consent_hash,
-            auth.emergency_override,
-            input.emergency_reason,
-        )?;
-    }
-
-    Ok(active)
+        input.emergency_reason,
+        || {
+            let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToPrescriptions)?, GetStrategy::default())?;
+            budgeted_page(links, &page, |link| {
+                let Some(hash) = link.target.into_action_hash() else {
+                    return Ok(None);
+                };
+                let record = get(hash, GetOptions::default())?;
+                Ok(record.filter(|record| {
+                    record
+                        .entry()
+                        .to_app_option::<Prescription>()
+                        .ok()
+                        .flatten()
+                        .is_some_and(|rx| matches!(rx.status, PrescriptionStatus::Active))
+                }))
+            })
+        },
+    )
 }
 
 /// Input for filling prescription with access control
@@ -670,6 +668,243 @@ pub fn set_patient_pharmacy(input: SetPharmacyInput) -> ExternResult<()> {
     Ok(())
 }
 
+// ============================================================
+// PHARMACY PREFERENCES AND ROUTING
+// ============================================================
+
+/// Input for saving a pharmacy preference with access control
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AddPharmacyPreferenceInput {
+    pub preference: PharmacyPreference,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Save a pharmacy the patient wants prescriptions sent to
+///
+/// A preferred pharmacy replaces the previous one as the routing default.
+#[hdk_extern]
+pub fn add_pharmacy_preference(input: AddPharmacyPreferenceInput) -> ExternResult<Record> {
+    let patient_hash = input.preference.patient_hash.clone();
+    let auth = require_authorization(
+        patient_hash.clone(),
+        DataCategory::Demographics,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+    validate_ncpdp_id(&input.preference.ncpdp_id)
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+
+    if input.preference.preferred {
+        clear_preferred_pharmacy(&patient_hash)?;
+    }
+    let preference_hash = create_entry(&EntryTypes::PharmacyPreference(input.preference))?;
+    let record = get(preference_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find pharmacy preference".to_string())))?;
+    create_link(
+        patient_hash.clone(),
+        preference_hash,
+        LinkTypes::PatientToPharmacyPreferences,
+        (),
+    )?;
+
+    log_data_access(
+        patient_hash,
+        vec![DataCategory::Demographics],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(record)
+}
+
+/// Get a patient's saved pharmacies with access control
+#[hdk_extern]
+pub fn get_pharmacy_preferences(input: GetPatientPrescriptionsInput) -> ExternResult<Vec<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    audited_read(
+        "prescriptions::get_pharmacy_preferences",
+        input.patient_hash,
+        DataCategory::Demographics,
+        input.is_emergency,
+        input.emergency_reason,
+        || {
+            Ok(pharmacy_preferences_internal(patient_hash)?
+                .into_iter()
+                .map(|(_, record, _)| record)
+                .collect())
+        },
+    )
+}
+
+/// Input for choosing which saved pharmacy new prescriptions route to
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SetPreferredPharmacyInput {
+    pub patient_hash: ActionHash,
+    pub preference_hash: ActionHash,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Make a saved pharmacy the patient's preferred one
+#[hdk_extern]
+pub fn set_preferred_pharmacy(input: SetPreferredPharmacyInput) -> ExternResult<Record> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Demographics,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+
+    let (link, _, mut preference) = pharmacy_preferences_internal(input.patient_hash.clone())?
+        .into_iter()
+        .find(|(_, record, _)| record.action_address() == &input.preference_hash)
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Pharmacy preference not found for patient".to_string())))?;
+    clear_preferred_pharmacy(&input.patient_hash)?;
+    preference.preferred = true;
+    let updated_hash = replace_preference(link, input.preference_hash, &preference)?;
+    let updated_record = get(updated_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated pharmacy preference".to_string())))?;
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Demographics],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(updated_record)
+}
+
+/// Current versions of a patient's saved pharmacies with the links to them
+fn pharmacy_preferences_internal(
+    patient_hash: ActionHash,
+) -> ExternResult<Vec<(Link, Record, PharmacyPreference)>> {
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToPharmacyPreferences)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut preferences = Vec::new();
+    for link in links {
+        let Some(hash) = link.target.clone().into_action_hash() else { continue };
+        let Some(record) = get(hash, GetOptions::default())? else { continue };
+        if let Some(preference) = record.entry().to_app_option::<PharmacyPreference>().ok().flatten() {
+            preferences.push((link, record, preference));
+        }
+    }
+    Ok(preferences)
+}
+
+/// Update a preference and move the patient's link to the new version
+fn replace_preference(link: Link, original: ActionHash, preference: &PharmacyPreference) -> ExternResult<ActionHash> {
+    let updated_hash = update_entry(original, preference)?;
+    delete_link(link.create_link_hash, GetOptions::default())?;
+    create_link(
+        preference.patient_hash.clone(),
+        updated_hash.clone(),
+        LinkTypes::PatientToPharmacyPreferences,
+        (),
+    )?;
+    Ok(updated_hash)
+}
+
+fn clear_preferred_pharmacy(patient_hash: &ActionHash) -> ExternResult<()> {
+    for (link, record, mut preference) in pharmacy_preferences_internal(patient_hash.clone())? {
+        if preference.preferred {
+            preference.preferred = false;
+            replace_preference(link, record.action_address().clone(), &preference)?;
+        }
+    }
+    Ok(())
+}
+
+/// Route a new prescription to the patient's preferred pharmacy unless the
+/// prescriber already chose a target
+fn route_prescription(prescription: &mut Prescription) -> ExternResult<()> {
+    if prescription.routing.is_some() {
+        return Ok(());
+    }
+    let preferred = pharmacy_preferences_internal(prescription.patient_hash.clone())?
+        .into_iter()
+        .find(|(_, _, preference)| preference.preferred);
+    if let Some((_, record, preference)) = preferred {
+        if prescription.pharmacy_hash.is_none() {
+            prescription.pharmacy_hash = preference.pharmacy_hash.clone();
+        }
+        prescription.routing = Some(PrescriptionRouting {
+            preference_hash: record.action_address().clone(),
+            ncpdp_id: preference.ncpdp_id,
+            pharmacy_name: preference.name,
+        });
+    }
+    Ok(())
+}
+
+fn link_routing_target(rx_hash: &ActionHash, prescription: &Prescription) -> ExternResult<()> {
+    if let Some(routing) = &prescription.routing {
+        create_link(
+            rx_hash.clone(),
+            routing.preference_hash.clone(),
+            LinkTypes::PrescriptionToRoutingTarget,
+            LinkTag::new(routing.ncpdp_id.as_bytes().to_vec()),
+        )?;
+    }
+    Ok(())
+}
+
+/// A prescription together with the pharmacy it is routed to
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PrescriptionExport {
+    pub rx_hash: ActionHash,
+    pub prescription: Prescription,
+    pub pharmacy: Option<PharmacyPreference>,
+}
+
+/// Export a prescription for transmission, with its routing pharmacy
+#[hdk_extern]
+pub fn export_prescription(input: GetPrescriptionInput) -> ExternResult<PrescriptionExport> {
+    let record = get_prescription_internal(input.rx_hash.clone())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Prescription not found".to_string())))?;
+    let prescription: Prescription = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid prescription entry".to_string())))?;
+
+    let auth = require_authorization(
+        prescription.patient_hash.clone(),
+        DataCategory::Medications,
+        Permission::Export,
+        input.is_emergency,
+    )?;
+
+    let pharmacy = match &prescription.routing {
+        Some(routing) => get(routing.preference_hash.clone(), GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<PharmacyPreference>().ok().flatten()),
+        None => None,
+    };
+
+    log_data_access(
+        prescription.patient_hash.clone(),
+        vec![DataCategory::Medications],
+        Permission::Export,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(PrescriptionExport {
+        rx_hash: input.rx_hash,
+        prescription,
+        pharmacy,
+    })
+}
+
 /// Input for discontinuing prescription with access control
 #[derive(Serialize, Deserialize, Debug)]
 pub struct DiscontinueInput {
@@ -772,6 +1007,21 @@ pub fn check_prescription_safety(input: CheckPrescriptionSafetyInput) -> ExternR
     // Add the new medication to check
     existing_rxnorm_codes.push(input.new_medication_rxnorm.clone());
 
+    // Vaccine components the patient has reacted to are checked like allergies
+    let mut patient_allergies = input.patient_allergies.clone();
+    let reaction_allergens: Vec<String> = resilient_call(
+        "records",
+        "get_vaccine_reaction_allergens",
+        &input.patient_hash,
+        CallClass::Authorization,
+    )
+    .unwrap_or_default();
+    for allergen in reaction_allergens {
+        if !patient_allergies.iter().any(|known| known.eq_ignore_ascii_case(&allergen)) {
+            patient_allergies.push(allergen);
+        }
+    }
+
     // Call CDS zome to perform interaction check
     let request_id = format!("RX-SAFETY-{}", sys_time()?.as_micros());
 
@@ -779,7 +1029,7 @@ pub fn check_prescription_safety(input: CheckPrescriptionSafetyInput) -> ExternR
         request_id: request_id.clone(),
         patient_hash: input.patient_hash.clone(),
         medication_rxnorm_codes: existing_rxnorm_codes,
-        patient_allergies: input.patient_allergies.clone(),
+        patient_allergies,
         check_allergies: true,
         check_duplicates: true,
     };
@@ -865,7 +1115,7 @@ pub struct CheckPrescriptionSafetyInput {
 
 /// Create a prescription with integrated safety checking
 #[hdk_extern]
-pub fn create_prescription_with_safety(input: CreatePrescriptionWithSafetyInput) -> ExternResult<PrescriptionWithSafetyResponse> {
+pub fn create_prescription_with_safety(mut input: CreatePrescriptionWithSafetyInput) -> ExternResult<PrescriptionWithSafetyResponse> {
     // Require Write authorization for Medications category
     let auth = require_authorization(
         input.prescription.patient_hash.clone(),
@@ -917,6 +1167,7 @@ pub fn create_prescription_with_safety(input: CreatePrescriptionWithSafetyInput)
     }
 
     // Create the prescription
+    route_prescription(&mut input.prescription)?;
     let rx_hash = create_entry(&EntryTypes::Prescription(input.prescription.clone()))?;
     let record = get(rx_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find prescription".to_string())))?;
@@ -937,6 +1188,8 @@ pub fn create_prescription_with_safety(input: CreatePrescriptionWithSafetyInput)
         (),
     )?;
 
+    link_routing_target(&rx_hash, &input.prescription)?;
+
     // If controlled substance, add to tracking
     if input.prescription.schedule.is_some() && input.prescription.schedule != Some(DrugSchedule::NotControlled) {
         let controlled_anchor = anchor_hash("controlled_substances")?;
@@ -1113,3 +1366,394 @@ pub fn get_medication_safety_summary(input: GetPatientPrescriptionsInput) -> Ext
         }
     }
 }
+
+// ============================================================
+// ADHERENCE
+// ============================================================
+
+/// Input for an adherence summary of one medication
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAdherenceSummaryInput {
+    pub patient_hash: ActionHash,
+    pub rxnorm_code: String,
+    pub period: AdherencePeriod,
+    /// PDC below which the care team is alerted; defaults to
+    /// `ADHERENCE_THRESHOLD`
+    pub alert_threshold: Option<f64>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Adherence to one medication over a period
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct AdherenceSummary {
+    pub rxnorm_code: String,
+    pub medication_name: Option<String>,
+    pub period: AdherencePeriod,
+    pub prescriptions: u32,
+    /// Dispensed and partial fills counted toward coverage
+    pub dispenses: u32,
+    pub coverage: SupplyCoverage,
+    /// Doses logged by smart pill bottles in the period
+    pub device_doses_logged: u32,
+    pub device_doses_taken: u32,
+    pub device_dose_rate: Option<f64>,
+    pub non_adherent: bool,
+    /// Open alert for this medication, raised by this call or earlier
+    pub alert_hash: Option<ActionHash>,
+}
+
+/// Creation time of a care team (mirrors consent_integrity::CareTeam)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct CareTeamCreatedAt {
+    created_at: Timestamp,
+}
+
+/// The patient's most recently formed active care team
+fn responsible_care_team(patient_hash: &ActionHash) -> Option<ActionHash> {
+    let teams: Vec<Record> =
+        resilient_call("consent", "get_active_care_teams", patient_hash, CallClass::Authorization).ok()?;
+    teams
+        .into_iter()
+        .filter_map(|record| {
+            let created_at = record.entry().to_app_option::<CareTeamCreatedAt>().ok()??.created_at;
+            Some((created_at, record.action_address().clone()))
+        })
+        .max_by_key(|(created_at, _)| *created_at)
+        .map(|(_, hash)| hash)
+}
+
+fn compute_adherence(input: &GetAdherenceSummaryInput, threshold: f64) -> ExternResult<AdherenceSummary> {
+    let prescriptions: Vec<(ActionHash, Prescription)> = get_patient_prescriptions_internal(input.patient_hash.clone())?
+        .into_iter()
+        .filter_map(|record| {
+            let rx = record.entry().to_app_option::<Prescription>().ok()??;
+            (rx.rxnorm_code == input.rxnorm_code).then(|| (record.action_address().clone(), rx))
+        })
+        .collect();
+
+    let mut dispenses = Vec::new();
+    for (rx_hash, _) in &prescriptions {
+        let links = get_links(LinkQuery::try_new(rx_hash.clone(), LinkTypes::PrescriptionToFills)?, GetStrategy::default())?;
+        for link in links {
+            let Some(hash) = link.target.into_action_hash() else {
+                continue;
+            };
+            let Some(fill) = get(hash, GetOptions::default())?
+                .and_then(|record| record.entry().to_app_option::<PrescriptionFill>().ok().flatten())
+            else {
+                continue;
+            };
+            if matches!(fill.status, FillStatus::Dispensed | FillStatus::PartialFill) {
+                dispenses.push(Dispense {
+                    dispensed_at: fill.fill_date,
+                    days_supply: fill.days_supply_dispensed,
+                });
+            }
+        }
+    }
+
+    let mut device_doses_logged = 0;
+    let mut device_doses_taken = 0;
+    let links = get_links(LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToAdherence)?, GetStrategy::default())?;
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let Some(event) = get(hash, GetOptions::default())?
+            .and_then(|record| record.entry().to_app_option::<MedicationAdherence>().ok().flatten())
+        else {
+            continue;
+        };
+        let for_medication = prescriptions.iter().any(|(rx_hash, _)| *rx_hash == event.prescription_hash);
+        let at = event.dose_time.unwrap_or(event.recorded_at);
+        if event.source == AdherenceSource::SmartPillBottle && for_medication && input.period.contains(at) {
+            device_doses_logged += 1;
+            if event.dose_taken {
+                device_doses_taken += 1;
+            }
+        }
+    }
+
+    let coverage = supply_coverage(&dispenses, &input.period);
+    let device_dose_rate = (device_doses_logged > 0).then(|| device_doses_taken as f64 / device_doses_logged as f64);
+    let non_adherent = (!dispenses.is_empty() && coverage.pdc < threshold)
+        || device_dose_rate.is_some_and(|rate| rate < threshold);
+    Ok(AdherenceSummary {
+        rxnorm_code: input.rxnorm_code.clone(),
+        medication_name: prescriptions.first().map(|(_, rx)| rx.medication_name.clone()),
+        period: input.period,
+        prescriptions: prescriptions.len() as u32,
+        dispenses: dispenses.len() as u32,
+        coverage,
+        device_doses_logged,
+        device_doses_taken,
+        device_dose_rate,
+        non_adherent,
+        alert_hash: None,
+    })
+}
+
+/// Alerts on the patient's record, with whether each is acknowledged
+fn adherence_alerts(patient_hash: &ActionHash) -> ExternResult<Vec<(ActionHash, Record)>> {
+    let links = get_links(LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToAdherenceAlerts)?, GetStrategy::default())?;
+    let mut alerts = Vec::new();
+    for link in links {
+        let Some(original) = link.target.into_action_hash() else {
+            continue;
+        };
+        let updates = get_links(LinkQuery::try_new(original.clone(), LinkTypes::AdherenceAlertUpdates)?, GetStrategy::default())?;
+        let latest = updates
+            .into_iter()
+            .max_by_key(|update| update.timestamp)
+            .and_then(|update| update.target.into_action_hash())
+            .unwrap_or(original.clone());
+        if let Some(record) = get(latest, GetOptions::default())? {
+            alerts.push((original, record));
+        }
+    }
+    Ok(alerts)
+}
+
+fn raise_adherence_alert(summary: &AdherenceSummary, patient_hash: &ActionHash, threshold: f64) -> ExternResult<ActionHash> {
+    let open = adherence_alerts(patient_hash)?.into_iter().find(|(_, record)| {
+        record
+            .entry()
+            .to_app_option::<AdherenceAlert>()
+            .ok()
+            .flatten()
+            .is_some_and(|alert| alert.rxnorm_code == summary.rxnorm_code && !alert.acknowledged)
+    });
+    if let Some((original, _)) = open {
+        return Ok(original);
+    }
+
+    let now = sys_time()?;
+    let alert = AdherenceAlert {
+        alert_id: format!("ADH-{}-{}", summary.rxnorm_code, now.as_micros()),
+        patient_hash: patient_hash.clone(),
+        rxnorm_code: summary.rxnorm_code.clone(),
+        medication_name: summary.medication_name.clone().unwrap_or_default(),
+        care_team_hash: responsible_care_team(patient_hash),
+        pdc: summary.coverage.pdc,
+        threshold,
+        period_start: summary.period.start,
+        period_end: summary.period.end,
+        raised_at: now,
+        acknowledged: false,
+        acknowledged_by: None,
+        acknowledged_at: None,
+        acknowledgement_note: None,
+    };
+    let alert_hash = create_entry(&EntryTypes::AdherenceAlert(alert))?;
+    create_link(
+        patient_hash.clone(),
+        alert_hash.clone(),
+        LinkTypes::PatientToAdherenceAlerts,
+        LinkTag::new(summary.rxnorm_code.as_bytes().to_vec()),
+    )?;
+    Ok(alert_hash)
+}
+
+/// Proportion of days covered and possession ratio for one medication,
+/// from its dispenses and any smart pill bottle events
+///
+/// When the patient falls below the threshold an alert is raised for their
+/// care team, unless one for the medication is still unacknowledged.
+#[hdk_extern]
+pub fn get_adherence_summary(input: GetAdherenceSummaryInput) -> ExternResult<AdherenceSummary> {
+    let threshold = input.alert_threshold.unwrap_or(ADHERENCE_THRESHOLD);
+    if !(0.0..=1.0).contains(&threshold) {
+        return Err(wasm_error!(WasmErrorInner::Guest("Alert threshold must be between 0.0 and 1.0".to_string())));
+    }
+    if input.period.end <= input.period.start {
+        return Err(wasm_error!(WasmErrorInner::Guest("Adherence period must end after it starts".to_string())));
+    }
+
+    let mut summary = audited_read(
+        "prescriptions::get_adherence_summary",
+        input.patient_hash.clone(),
+        DataCategory::Medications,
+        input.is_emergency,
+        input.emergency_reason.clone(),
+        || compute_adherence(&input, threshold),
+    )?;
+    if summary.non_adherent {
+        summary.alert_hash = Some(raise_adherence_alert(&summary, &input.patient_hash, threshold)?);
+    }
+    Ok(summary)
+}
+
+/// Input for listing a patient's adherence alerts
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetAdherenceAlertsInput {
+    pub patient_hash: ActionHash,
+    /// Only alerts assigned to this care team
+    pub care_team_hash: Option<ActionHash>,
+    pub include_acknowledged: bool,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// A patient's adherence alerts, latest versions, newest first
+#[hdk_extern]
+pub fn get_adherence_alerts(input: GetAdherenceAlertsInput) -> ExternResult<Vec<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    audited_read(
+        "prescriptions::get_adherence_alerts",
+        input.patient_hash,
+        DataCategory::Medications,
+        input.is_emergency,
+        input.emergency_reason,
+        || {
+            let mut alerts: Vec<(Timestamp, Record)> = adherence_alerts(&patient_hash)?
+                .into_iter()
+                .filter_map(|(_, record)| {
+                    let alert = record.entry().to_app_option::<AdherenceAlert>().ok()??;
+                    let on_team = input.care_team_hash.is_none() || alert.care_team_hash == input.care_team_hash;
+                    (on_team && (input.include_acknowledged || !alert.acknowledged)).then_some((alert.raised_at, record))
+                })
+                .collect();
+            alerts.sort_by_key(|(raised_at, _)| std::cmp::Reverse(*raised_at));
+            Ok(alerts.into_iter().map(|(_, record)| record).collect())
+        },
+    )
+}
+
+/// Input for acknowledging an adherence alert
+#[derive(Serialize, Deserialize, Debug)]
+pub struct AcknowledgeAdherenceAlertInput {
+    pub alert_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub note: Option<String>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Acknowledge an adherence alert so a later shortfall can raise a new one
+#[hdk_extern]
+pub fn acknowledge_adherence_alert(input: AcknowledgeAdherenceAlertInput) -> ExternResult<Record> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Medications,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+
+    let (original, latest) = adherence_alerts(&input.patient_hash)?
+        .into_iter()
+        .find(|(original, _)| *original == input.alert_hash)
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Adherence alert not found for this patient".to_string())))?;
+    let mut alert: AdherenceAlert = latest
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid adherence alert".to_string())))?;
+    if alert.acknowledged {
+        return Err(wasm_error!(WasmErrorInner::Guest("Adherence alert is already acknowledged".to_string())));
+    }
+
+    alert.acknowledged = true;
+    alert.acknowledged_by = Some(agent_info()?.agent_initial_pubkey);
+    alert.acknowledged_at = Some(sys_time()?);
+    alert.acknowledgement_note = input.note;
+
+    let updated_hash = update_entry(original.clone(), &alert)?;
+    let updated_record = get(updated_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated adherence alert".to_string())))?;
+    create_link(original, updated_hash, LinkTypes::AdherenceAlertUpdates, ())?;
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Medications],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(updated_record)
+}
+
+// ============================================================
+// RECORD INVENTORY
+// ============================================================
+
+/// Every prescription held for the patient, summarized
+///
+/// Patient only and not access-logged, so consent previews can be computed
+/// without writing entries.
+#[hdk_extern]
+pub fn get_patient_record_inventory(patient_hash: ActionHash) -> ExternResult<Vec<RecordInventoryItem>> {
+    require_patient_self(&patient_hash)?;
+
+    let mut items: Vec<RecordInventoryItem> = get_patient_prescriptions_internal(patient_hash)?
+        .into_iter()
+        .filter_map(|record| {
+            let rx = record.entry().to_app_option::<Prescription>().ok()??;
+            Some(RecordInventoryItem {
+                record_hash: record.action_address().clone(),
+                category: DataCategory::Medications,
+                title: format!("{} {}", rx.medication_name, rx.strength),
+                recorded_at: rx.written_date,
+                encounter_hash: rx.encounter_hash,
+            })
+        })
+        .collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.recorded_at));
+    Ok(items)
+}
+
+// ============================================================
+// DOMAIN EVENTS
+// ============================================================
+
+/// Domain event handlers this zome registers with the bridge's event bus
+#[hdk_extern]
+pub fn domain_event_subscriptions(_: ()) -> ExternResult<Vec<DomainEventHandler>> {
+    Ok(vec![DomainEventHandler::new("handle_consent_revoked", &[DomainEventKind::ConsentRevoked])])
+}
+
+/// Forget cached authorizations for the patient so prescription reads recheck consent
+#[hdk_extern]
+pub fn handle_consent_revoked(notice: DomainEventNotice) -> ExternResult<()> {
+    invalidate_authorization_cache(&notice.patient_hash);
+    Ok(())
+}
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_prescription", "CreatePrescriptionInput", "Record"),
+        ("get_prescription", "GetPrescriptionInput", "Option<Record>"),
+        ("get_patient_prescriptions", "GetPatientPrescriptionsInput", "BudgetedPage<Record>"),
+        ("get_active_prescriptions", "GetPatientPrescriptionsInput", "BudgetedPage<Record>"),
+        ("fill_prescription", "FillPrescriptionInput", "Record"),
+        ("get_prescription_fills", "GetPrescriptionFillsInput", "Vec<Record>"),
+        ("record_adherence", "RecordAdherenceInput", "Record"),
+        ("create_interaction_alert", "CreateInteractionAlertInput", "Record"),
+        ("acknowledge_alert", "AcknowledgeAlertInput", "Record"),
+        ("register_pharmacy", "Pharmacy", "Record"),
+        ("get_all_pharmacies", "()", "Vec<Record>"),
+        ("set_patient_pharmacy", "SetPharmacyInput", "()"),
+        ("add_pharmacy_preference", "AddPharmacyPreferenceInput", "Record"),
+        ("get_pharmacy_preferences", "GetPatientPrescriptionsInput", "Vec<Record>"),
+        ("set_preferred_pharmacy", "SetPreferredPharmacyInput", "Record"),
+        ("export_prescription", "GetPrescriptionInput", "PrescriptionExport"),
+        ("discontinue_prescription", "DiscontinueInput", "Record"),
+        ("check_prescription_safety", "CheckPrescriptionSafetyInput", "PrescriptionSafetyResult"),
+        ("create_prescription_with_safety", "CreatePrescriptionWithSafetyInput", "PrescriptionWithSafetyResponse"),
+        ("get_medication_safety_summary", "GetPatientPrescriptionsInput", "PrescriptionSafetyResult"),
+        ("get_adherence_summary", "GetAdherenceSummaryInput", "AdherenceSummary"),
+        ("get_adherence_alerts", "GetAdherenceAlertsInput", "Vec<Record>"),
+        ("acknowledge_adherence_alert", "AcknowledgeAdherenceAlertInput", "Record"),
+        ("get_patient_record_inventory", "ActionHash", "Vec<RecordInventoryItem>"),
+        ("domain_event_subscriptions", "()", "Vec<DomainEventHandler>"),
+        ("handle_consent_revoked", "DomainEventNotice", "()"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["record_inventory", "pharmacy_routing", "adherence_scoring", "response_budgets", "authorization_cache"],
+}
@@ -4,6 +4,9 @@
 //! refills, and pharmacy interactions with RxNorm alignment.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 /// Prescription entry
 #[hdk_entry_helper]
@@ -37,6 +40,10 @@ pub struct Prescription {
     pub dea_number: Option<String>,
     /// Pharmacy to fill
     pub pharmacy_hash: Option<ActionHash>,
+    /// E-prescribing target, taken from the patient's preferred pharmacy
+    /// when the prescriber does not choose one
+    #[serde(default)]
+    pub routing: Option<PrescriptionRouting>,
     pub notes: Option<String>,
     /// Diagnosis justifying prescription
     pub indication: String,
@@ -155,6 +162,28 @@ pub enum AdherenceSource {
     PharmacyRecord,
 }
 
+/// Non-adherence alert raised for a patient's care team when a medication's
+/// proportion of days covered falls below the alert threshold
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct AdherenceAlert {
+    pub alert_id: String,
+    pub patient_hash: ActionHash,
+    pub rxnorm_code: String,
+    pub medication_name: String,
+    /// Care team responsible for the patient when the alert was raised
+    pub care_team_hash: Option<ActionHash>,
+    pub pdc: f64,
+    pub threshold: f64,
+    pub period_start: Timestamp,
+    pub period_end: Timestamp,
+    pub raised_at: Timestamp,
+    pub acknowledged: bool,
+    pub acknowledged_by: Option<AgentPubKey>,
+    pub acknowledged_at: Option<Timestamp>,
+    pub acknowledgement_note: Option<String>,
+}
+
 /// Drug-drug interaction alert
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -218,6 +247,43 @@ pub enum PharmacyType {
     LongTermCare,
 }
 
+/// A pharmacy the patient has chosen for e-prescriptions
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PharmacyPreference {
+    pub patient_hash: ActionHash,
+    /// NCPDP provider ID the prescription is routed to
+    pub ncpdp_id: String,
+    pub name: String,
+    pub address_line1: String,
+    pub address_line2: Option<String>,
+    pub city: String,
+    pub state_province: String,
+    pub postal_code: String,
+    pub country: String,
+    /// Registered pharmacy profile, when there is one
+    pub pharmacy_hash: Option<ActionHash>,
+    /// New prescriptions route here unless the prescriber picks another
+    pub preferred: bool,
+    pub created_at: Timestamp,
+}
+
+/// Where a prescription is sent for dispensing
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct PrescriptionRouting {
+    pub preference_hash: ActionHash,
+    pub ncpdp_id: String,
+    pub pharmacy_name: String,
+}
+
+/// Check an NCPDP provider ID: exactly seven digits
+pub fn validate_ncpdp_id(ncpdp_id: &str) -> Result<(), String> {
+    if ncpdp_id.len() != 7 || !ncpdp_id.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("NCPDP ID must be 7 digits, got '{}'", ncpdp_id));
+    }
+    Ok(())
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -226,6 +292,8 @@ pub enum EntryTypes {
     MedicationAdherence(MedicationAdherence),
     DrugInteractionAlert(DrugInteractionAlert),
     Pharmacy(Pharmacy),
+    PharmacyPreference(PharmacyPreference),
+    AdherenceAlert(AdherenceAlert),
 }
 
 #[hdk_link_types]
@@ -238,10 +306,29 @@ pub enum LinkTypes {
     PatientToPharmacy,
     AllPharmacies,
     ControlledSubstances,
+    PatientToPharmacyPreferences,
+    PrescriptionToRoutingTarget,
+    /// Patient to their adherence alerts; the tag holds the RxNorm code
+    PatientToAdherenceAlerts,
+    /// Adherence alert to its acknowledged version
+    AdherenceAlertUpdates,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("dosage_instructions", FieldLimit::Chars(NOTE_CHARS)),
+        ("acknowledgement_note", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => match app_entry {
@@ -250,7 +337,15 @@ pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
                 EntryTypes::MedicationAdherence(_) => Ok(ValidateCallbackResult::Valid),
                 EntryTypes::DrugInteractionAlert(_) => Ok(ValidateCallbackResult::Valid),
                 EntryTypes::Pharmacy(p) => validate_pharmacy(&p),
+                EntryTypes::PharmacyPreference(pref) => validate_pharmacy_preference(&pref),
+                EntryTypes::AdherenceAlert(alert) => validate_adherence_alert(&alert),
             },
+            OpEntry::UpdateEntry { app_entry: EntryTypes::PharmacyPreference(pref), .. } => {
+                validate_pharmacy_preference(&pref)
+            }
+            OpEntry::UpdateEntry { app_entry: EntryTypes::AdherenceAlert(alert), original_action_hash, .. } => {
+                validate_adherence_alert_update(&alert, &original_action_hash)
+            }
             _ => Ok(ValidateCallbackResult::Valid),
         },
         _ => Ok(ValidateCallbackResult::Valid),
@@ -278,6 +373,11 @@ fn validate_prescription(rx: &Prescription) -> ExternResult<ValidateCallbackResu
             "Dosage instructions are required".to_string(),
         ));
     }
+    if let Some(routing) = &rx.routing {
+        if let Err(e) = validate_ncpdp_id(&routing.ncpdp_id) {
+            return Ok(ValidateCallbackResult::Invalid(e));
+        }
+    }
     // Controlled substances must have DEA number
     if rx.schedule.is_some() && rx.schedule != Some(DrugSchedule::NotControlled) {
         if rx.dea_number.is_none() {
@@ -316,3 +416,74 @@ fn validate_pharmacy(pharmacy: &Pharmacy) -> ExternResult<ValidateCallbackResult
     }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_pharmacy_preference(pref: &PharmacyPreference) -> ExternResult<ValidateCallbackResult> {
+    if let Err(e) = validate_ncpdp_id(&pref.ncpdp_id) {
+        return Ok(ValidateCallbackResult::Invalid(e));
+    }
+    if pref.name.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Pharmacy name is required".to_string(),
+        ));
+    }
+    if pref.address_line1.trim().is_empty() || pref.city.trim().is_empty() || pref.postal_code.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Pharmacy address needs a street, city and postal code".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_adherence_alert(alert: &AdherenceAlert) -> ExternResult<ValidateCallbackResult> {
+    if alert.rxnorm_code.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "RxNorm code is required".to_string(),
+        ));
+    }
+    if !(0.0..=1.0).contains(&alert.pdc) || !(0.0..=1.0).contains(&alert.threshold) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "PDC and threshold must be between 0.0 and 1.0".to_string(),
+        ));
+    }
+    if alert.period_end <= alert.period_start {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Adherence period must end after it starts".to_string(),
+        ));
+    }
+    if alert.acknowledged != alert.acknowledged_at.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Acknowledged alerts need an acknowledgement time".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+/// Only acknowledgement may change, and only once
+fn validate_adherence_alert_update(
+    alert: &AdherenceAlert,
+    original_action_hash: &ActionHash,
+) -> ExternResult<ValidateCallbackResult> {
+    let previous: AdherenceAlert = must_get_valid_record(original_action_hash.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original adherence alert not found".to_string())))?;
+    if previous.acknowledged || !alert.acknowledged {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Adherence alerts can only be updated to acknowledge them".to_string(),
+        ));
+    }
+    let unchanged = AdherenceAlert {
+        acknowledged: previous.acknowledged,
+        acknowledged_by: previous.acknowledged_by.clone(),
+        acknowledged_at: previous.acknowledged_at,
+        acknowledgement_note: previous.acknowledgement_note.clone(),
+        ..alert.clone()
+    };
+    if unchanged != previous {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the acknowledgement of an adherence alert can change".to_string(),
+        ));
+    }
+    validate_adherence_alert(alert)
+}
@@ -2,14 +2,21 @@
 //! 
 //! Provides extern functions for provider management,
 //! credential verification, and patient relationships.
+//! Third-party attestations back the identity assurance level the consent
+//! zome reports with each authorization.
 
 use hdk::prelude::*;
 use provider_integrity::*;
 use mycelix_health_shared::{require_authorization, log_data_access, DataCategory, Permission};
+use mycelix_health_shared::IdentityAttestations;
+use mycelix_health_shared::enforce_site_rules;
 
 /// Create a new provider profile
 #[hdk_extern]
 pub fn create_provider(provider: Provider) -> ExternResult<Record> {
+    // A new profile is checked against its own organization's rules
+    enforce_site_rules("Provider", &provider, provider.organization.clone())?;
+
     let provider_hash = create_entry(&EntryTypes::Provider(provider.clone()))?;
     let record = get(provider_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find newly created provider".to_string())))?;
@@ -53,6 +60,8 @@ pub fn update_provider(input: UpdateProviderInput) -> ExternResult<Record> {
         )));
     }
 
+    enforce_site_rules("Provider", &input.updated_provider, input.updated_provider.organization.clone())?;
+
     let updated_hash = update_entry(input.original_hash.clone(), &input.updated_provider)?;
     let record = get(updated_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated provider".to_string())))?;
@@ -336,6 +345,134 @@ pub fn get_provider_by_npi(npi: String) -> ExternResult<Option<Record>> {
     Ok(None)
 }
 
+/// Get the provider profile an agent published
+#[hdk_extern]
+pub fn get_provider_by_agent(agent: AgentPubKey) -> ExternResult<Option<Record>> {
+    Ok(get_all_providers(())?
+        .into_iter()
+        .find(|record| record.action().author() == &agent))
+}
+
+// ============================================================
+// IDENTITY ATTESTATIONS
+// ============================================================
+
+/// Vouch for another agent's provider profile
+///
+/// License attestations must name one of the provider's active licenses.
+#[hdk_extern]
+pub fn attest_provider_identity(attestation: IdentityAttestation) -> ExternResult<Record> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let provider_record = get(attestation.provider_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Provider not found".to_string())))?;
+    if provider_record.action().author() == &caller {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Providers cannot attest to their own identity".to_string()
+        )));
+    }
+    if let AttestationKind::License { license_hash, .. } = &attestation.kind {
+        let owned_and_active = get_provider_licenses(attestation.provider_hash.clone())?
+            .into_iter()
+            .filter(|record| record.action_address() == license_hash)
+            .filter_map(|record| record.entry().to_app_option::<License>().ok().flatten())
+            .any(|license| license.status == LicenseStatus::Active);
+        if !owned_and_active {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "License attestations must name an active license of the provider".to_string()
+            )));
+        }
+    }
+
+    let attestation_hash = create_entry(&EntryTypes::IdentityAttestation(attestation.clone()))?;
+    let record = get(attestation_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find attestation".to_string())))?;
+
+    create_link(
+        attestation.provider_hash,
+        attestation_hash,
+        LinkTypes::ProviderToAttestations,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+/// Withdraw an attestation the caller made
+#[hdk_extern]
+pub fn revoke_identity_attestation(attestation_hash: ActionHash) -> ExternResult<()> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let record = get(attestation_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Attestation not found".to_string())))?;
+    if record.action().author() != &caller {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the attester can revoke an attestation".to_string()
+        )));
+    }
+    let attestation: IdentityAttestation = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid attestation entry".to_string())))?;
+
+    let links = get_links(
+        LinkQuery::try_new(attestation.provider_hash, LinkTypes::ProviderToAttestations)?,
+        GetStrategy::default(),
+    )?;
+    for link in links {
+        if link.target.clone().into_action_hash().as_ref() == Some(&attestation_hash) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+    delete_entry(attestation_hash)?;
+
+    Ok(())
+}
+
+/// Current attestations backing an agent's identity
+///
+/// Agents without a provider profile have none.
+#[hdk_extern]
+pub fn get_identity_attestations(agent: AgentPubKey) -> ExternResult<IdentityAttestations> {
+    let mut attestations = IdentityAttestations::default();
+    let Some(provider_record) = get_provider_by_agent(agent.clone())? else {
+        return Ok(attestations);
+    };
+    attestations.has_profile = true;
+
+    let provider_hash = provider_record.action_address().clone();
+    let now = sys_time()?;
+    let links = get_links(
+        LinkQuery::try_new(provider_hash.clone(), LinkTypes::ProviderToAttestations)?,
+        GetStrategy::default(),
+    )?;
+    for link in links {
+        let Some(hash) = link.target.into_action_hash() else { continue };
+        let Some(record) = get(hash, GetOptions::default())? else { continue };
+        if record.action().author() == &agent {
+            continue;
+        }
+        let Some(attestation) = record.entry().to_app_option::<IdentityAttestation>().ok().flatten() else {
+            continue;
+        };
+        if attestation.expires_at.is_some_and(|expires| expires <= now) {
+            continue;
+        }
+        match attestation.kind {
+            AttestationKind::Organization { .. } => attestations.organization_countersigned = true,
+            AttestationKind::License { license_hash, .. } => {
+                let active = get(license_hash, GetOptions::default())?
+                    .and_then(|record| record.entry().to_app_option::<License>().ok().flatten())
+                    .is_some_and(|license| {
+                        license.provider_hash == provider_hash && license.status == LicenseStatus::Active
+                    });
+                attestations.license_verified |= active;
+            }
+        }
+    }
+
+    Ok(attestations)
+}
+
 /// Anchor entry for indexing
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -345,3 +482,31 @@ fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
     let anchor = Anchor(anchor_text.to_string());
     hash_entry(&anchor)
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_provider", "Provider", "Record"),
+        ("get_provider", "ActionHash", "Option<Record>"),
+        ("update_provider", "UpdateProviderInput", "Record"),
+        ("get_all_providers", "()", "Vec<Record>"),
+        ("search_providers_by_specialty", "String", "Vec<Record>"),
+        ("add_license", "License", "Record"),
+        ("get_provider_licenses", "ActionHash", "Vec<Record>"),
+        ("add_board_certification", "BoardCertification", "Record"),
+        ("get_provider_certifications", "ActionHash", "Vec<Record>"),
+        ("create_provider_patient_relationship", "ProviderPatientRelationship", "Record"),
+        ("get_provider_patients", "ActionHash", "Vec<ActionHash>"),
+        ("verify_provider_credentials", "ActionHash", "CredentialVerificationResult"),
+        ("get_provider_by_npi", "String", "Option<Record>"),
+        ("get_provider_by_agent", "AgentPubKey", "Option<Record>"),
+        ("attest_provider_identity", "IdentityAttestation", "Record"),
+        ("revoke_identity_attestation", "ActionHash", "()"),
+        ("get_identity_attestations", "AgentPubKey", "IdentityAttestations"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["identity_attestations", "site_validation_rules"],
+}
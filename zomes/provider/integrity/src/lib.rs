@@ -4,6 +4,9 @@
 //! licenses, and specializations with verification tracking.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 /// Healthcare provider profile
 #[hdk_entry_helper]
@@ -183,6 +186,27 @@ pub enum RelationshipType {
     Other(String),
 }
 
+/// Another agent vouching for a provider's identity
+///
+/// Attestations feed the identity assurance level reported with every
+/// authorization; a provider cannot attest to themselves.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct IdentityAttestation {
+    pub provider_hash: ActionHash,
+    pub kind: AttestationKind,
+    pub attested_at: Timestamp,
+    pub expires_at: Option<Timestamp>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum AttestationKind {
+    /// An organization countersigns the provider profile
+    Organization { organization_name: String },
+    /// The attester checked a license with its issuing authority
+    License { license_hash: ActionHash, verification_source: String },
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -190,6 +214,7 @@ pub enum EntryTypes {
     License(License),
     BoardCertification(BoardCertification),
     ProviderPatientRelationship(ProviderPatientRelationship),
+    IdentityAttestation(IdentityAttestation),
 }
 
 #[hdk_link_types]
@@ -204,23 +229,40 @@ pub enum LinkTypes {
     AllProviders,
     ProvidersBySpecialty,
     ProvidersByLocation,
+    ProviderToAttestations,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Provider(provider) => validate_provider(&provider),
                 EntryTypes::License(license) => validate_license(&license),
                 EntryTypes::BoardCertification(cert) => validate_certification(&cert),
                 EntryTypes::ProviderPatientRelationship(rel) => validate_relationship(&rel),
+                EntryTypes::IdentityAttestation(attestation) => validate_attestation(&attestation, &action.author),
             },
             OpEntry::UpdateEntry { app_entry, .. } => match app_entry {
                 EntryTypes::Provider(provider) => validate_provider(&provider),
                 EntryTypes::License(license) => validate_license(&license),
                 EntryTypes::BoardCertification(cert) => validate_certification(&cert),
                 EntryTypes::ProviderPatientRelationship(rel) => validate_relationship(&rel),
+                // Attestations are revoked and reissued, never edited
+                EntryTypes::IdentityAttestation(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Identity attestations cannot be updated".to_string(),
+                )),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -301,3 +343,34 @@ fn validate_relationship(_rel: &ProviderPatientRelationship) -> ExternResult<Val
     // Relationship validation - hashes must exist (checked at runtime)
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_attestation(
+    attestation: &IdentityAttestation,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    match &attestation.kind {
+        AttestationKind::Organization { organization_name } if organization_name.trim().is_empty() => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Organization name is required".to_string(),
+            ));
+        }
+        AttestationKind::License { verification_source, .. } if verification_source.trim().is_empty() => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "License verification source is required".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    if attestation.expires_at.is_some_and(|expires| expires <= attestation.attested_at) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Attestation must expire after it is made".to_string(),
+        ));
+    }
+    let provider_record = must_get_valid_record(attestation.provider_hash.clone())?;
+    if provider_record.action().author() == author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Providers cannot attest to their own identity".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
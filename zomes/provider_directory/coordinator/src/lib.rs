@@ -448,3 +448,25 @@ fn validate_npi_luhn(npi: &str) -> bool {
 
     sum % 10 == 0
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("register_provider", "ProviderProfile", "Record"),
+        ("get_provider", "ActionHash", "Option<Record>"),
+        ("update_provider", "UpdateProviderInput", "Record"),
+        ("search_providers", "ProviderSearchCriteria", "Vec<Record>"),
+        ("get_provider_by_npi", "String", "Option<Record>"),
+        ("verify_npi", "String", "NpiVerificationResult"),
+        ("create_npi_verification", "NpiVerification", "Record"),
+        ("add_provider_affiliation", "ProviderAffiliation", "Record"),
+        ("get_provider_affiliations", "ActionHash", "Vec<Record>"),
+        ("get_telehealth_providers", "()", "Vec<Record>"),
+        ("get_telehealth_providers_by_state", "TelehealthByStateInput", "Vec<Record>"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: [],
+}
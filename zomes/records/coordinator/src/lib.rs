@@ -11,14 +11,37 @@
 //! When lab results or vital signs are recorded, this zome automatically
 //! feeds the data to the patient's Health Twin (if one exists) for
 //! continuous model updates and health predictions.
+//!
+//! Follow-up tasks from care transitions are assigned to the patient's
+//! care team in the consent zome; overdue ones are escalated to the
+//! patient as notifications.
+//!
+//! Vaccine reactions reported by patients or providers feed the allergy
+//! cross-check in the prescriptions zome and can be exported as VAERS-style
+//! reports for public health.
+//!
+//! Large record sets can be read a chunk at a time, each call returning a
+//! continuation token for the next.
+//!
+//! Operators sweep a patient's records against the retention policies of
+//! their jurisdiction, marking or tombstoning records held too long.
 
 use hdk::prelude::*;
 use records_integrity::*;
+use mycelix_health_shared::{invalidate_authorization_cache, DomainEventHandler, DomainEventKind, DomainEventNotice};
 use mycelix_health_shared::{
-    require_authorization, require_admin_authorization,
-    log_data_access,
+    require_authorization, require_admin_authorization, require_patient_self, require_operator,
+    log_data_access, audited_read,
     DataCategory, Permission,
     batch::links_to_records,
+    batch_get_records_chunked, BatchChunk, BatchChunkInput, BatchGetOptions,
+    links_to_records_budgeted, BudgetInput, BudgetedPage,
+    explain_lab_result, ExplanationLanguage, LabExplanation,
+    ProvisionalCommit, PublicationConfirmation,
+    RecordInventoryItem,
+    log_purge_decision, patient_retention, retention_for, PurgeDecision, PurgeReport, RetentionAction,
+    notify_webhooks, WebhookEventType,
+    resilient_call, CallClass,
 };
 
 // ==================== HEALTH TWIN INTEGRATION ====================
@@ -439,39 +462,29 @@ pub struct GetPatientEncountersInput {
     pub patient_hash: ActionHash,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
 }
 
 /// Get patient's encounters with access control
 ///
-/// OPTIMIZED: Uses batch query to avoid N+1 pattern
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
 #[hdk_extern]
-pub fn get_patient_encounters(input: GetPatientEncountersInput) -> ExternResult<Vec<Record>> {
-    // Require Read authorization for Procedures category
-    let auth = require_authorization(
-        input.patient_hash.clone(),
+pub fn get_patient_encounters(input: GetPatientEncountersInput) -> ExternResult<BudgetedPage<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let page = input.page.clone();
+    audited_read(
+        "records::get_patient_encounters",
+        input.patient_hash,
         DataCategory::Procedures,
-        Permission::Read,
         input.is_emergency,
-    )?;
-
-    let links = get_links(LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToEncounters)?, GetStrategy::default())?;
-
-    // FIXED N+1: Use batch fetch instead of individual get() calls
-    let encounters = links_to_records(links)?;
-
-    // Log the access
-    if !encounters.is_empty() {
-        log_data_access(
-            input.patient_hash,
-            vec![DataCategory::Procedures],
-            Permission::Read,
-            auth.consent_hash,
-            auth.emergency_override,
-            input.emergency_reason,
-        )?;
-    }
-
-    Ok(encounters)
+        input.emergency_reason,
+        || {
+            let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToEncounters)?, GetStrategy::default())?;
+            links_to_records_budgeted(links, &page)
+        },
+    )
 }
 
 /// Input for creating diagnosis with access control
@@ -656,6 +669,22 @@ pub fn create_lab_result(input: CreateLabResultInput) -> ExternResult<Record> {
     let record = get(result_hash.clone(), GetOptions::default())?
         .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find lab result".to_string())))?;
 
+    if let Some(order_hash) = &input.lab_result.order_hash {
+        let (_, order) = latest_version::<LabOrder>(order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+        if order.patient_hash != input.lab_result.patient_hash {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Lab result patient does not match the lab order".to_string()
+            )));
+        }
+        create_link(
+            order_hash.clone(),
+            result_hash.clone(),
+            LinkTypes::LabOrderToResults,
+            (),
+        )?;
+        advance_lab_order(order_hash, LabOrderStatus::Resulted)?;
+    }
+
     // Link to patient
     create_link(
         input.lab_result.patient_hash.clone(),
@@ -681,6 +710,13 @@ pub fn create_lab_result(input: CreateLabResultInput) -> ExternResult<Record> {
     try_feed_to_health_twin(&input.lab_result.patient_hash, twin_data_point);
     // ================================================================
 
+    try_notify_lab_result(&input.lab_result, record.action_address().clone());
+    notify_webhooks(
+        WebhookEventType::NewObservation,
+        input.lab_result.patient_hash.clone(),
+        record.action_address().clone(),
+    );
+
     // Log the access
     log_data_access(
         input.lab_result.patient_hash,
@@ -700,39 +736,193 @@ pub struct GetPatientLabResultsInput {
     pub patient_hash: ActionHash,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
 }
 
 /// Get patient's lab results with access control
 ///
-/// OPTIMIZED: Uses batch query to avoid N+1 pattern
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
+#[hdk_extern]
+pub fn get_patient_lab_results(input: GetPatientLabResultsInput) -> ExternResult<BudgetedPage<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let page = input.page.clone();
+    audited_read(
+        "records::get_patient_lab_results",
+        input.patient_hash,
+        DataCategory::LabResults,
+        input.is_emergency,
+        input.emergency_reason,
+        || {
+            let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToLabResults)?, GetStrategy::default())?;
+            links_to_records_budgeted(links, &page)
+        },
+    )
+}
+
+// ==================== PATIENT-FRIENDLY LAB EXPLANATIONS ====================
+
+/// Fields of the patient profile needed to localize explanations (mirrors patient_integrity)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct PatientLanguageFields {
+    primary_language: String,
+}
+
+/// Access notification (mirrors consent_integrity::AccessNotification)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct PatientNotification {
+    notification_id: String,
+    patient_hash: ActionHash,
+    accessor: AgentPubKey,
+    accessor_name: String,
+    data_categories: Vec<DataCategory>,
+    purpose: String,
+    accessed_at: Timestamp,
+    emergency_access: bool,
+    priority: PatientNotificationPriority,
+    viewed: bool,
+    viewed_at: Option<Timestamp>,
+    summary: String,
+    access_log_hash: Option<ActionHash>,
+}
+
+/// Notification priority (mirrors consent_integrity::NotificationPriority)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum PatientNotificationPriority {
+    Immediate,
+    Daily,
+}
+
+/// Language for a patient's explanations; English when the profile is unavailable
+fn patient_explanation_language(patient_hash: &ActionHash) -> ExplanationLanguage {
+    get(patient_hash.clone(), GetOptions::default())
+        .ok()
+        .flatten()
+        .and_then(|record| record.entry().to_app_option::<PatientLanguageFields>().ok().flatten())
+        .map(|fields| ExplanationLanguage::from_preference(&fields.primary_language))
+        .unwrap_or(ExplanationLanguage::English)
+}
+
+fn explain_lab(lab: &LabResult, language: ExplanationLanguage) -> Option<LabExplanation> {
+    explain_lab_result(
+        &lab.loinc_code,
+        &lab.value,
+        &lab.unit,
+        &format!("{:?}", lab.interpretation),
+        language,
+    )
+}
+
+/// Tell the patient a new result is available, with a plain-language explanation
+/// This is a best-effort operation - failures don't break result creation
+fn try_notify_lab_result(lab: &LabResult, result_hash: ActionHash) {
+    let _ = notify_lab_result_internal(lab, result_hash);
+}
+
+fn notify_lab_result_internal(lab: &LabResult, result_hash: ActionHash) -> ExternResult<()> {
+    let now = sys_time()?;
+    let language = patient_explanation_language(&lab.patient_hash);
+    let summary = match explain_lab(lab, language) {
+        Some(explanation) => explanation.summary,
+        None => format!("A new {} result is available.", lab.test_name),
+    };
+
+    let notification = PatientNotification {
+        notification_id: format!("LAB-{}-{}", result_hash, now.as_micros()),
+        patient_hash: lab.patient_hash.clone(),
+        accessor: agent_info()?.agent_initial_pubkey,
+        accessor_name: lab.performing_lab.clone(),
+        data_categories: vec![DataCategory::LabResults],
+        purpose: format!("New lab result: {}", lab.test_name),
+        accessed_at: now,
+        emergency_access: false,
+        priority: if lab.is_critical {
+            PatientNotificationPriority::Immediate
+        } else {
+            PatientNotificationPriority::Daily
+        },
+        viewed: false,
+        viewed_at: None,
+        summary,
+        access_log_hash: None,
+    };
+
+    call(
+        CallTargetCell::Local,
+        ZomeName::from("consent"),
+        FunctionName::from("create_access_notification"),
+        None,
+        &notification,
+    )?;
+    Ok(())
+}
+
+/// Input for explaining a single lab result
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExplainLabResultInput {
+    pub result_hash: ActionHash,
+    /// Overrides the patient's primary language
+    pub language: Option<String>,
+}
+
+/// Explain a lab result in plain language for the patient
 #[hdk_extern]
-pub fn get_patient_lab_results(input: GetPatientLabResultsInput) -> ExternResult<Vec<Record>> {
-    // Require Read authorization for LabResults category
+pub fn get_lab_result_explanation(input: ExplainLabResultInput) -> ExternResult<Option<LabExplanation>> {
+    let record = get(input.result_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Lab result not found".to_string())))?;
+    let lab: LabResult = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid lab result".to_string())))?;
+
     let auth = require_authorization(
-        input.patient_hash.clone(),
+        lab.patient_hash.clone(),
         DataCategory::LabResults,
         Permission::Read,
-        input.is_emergency,
+        false,
     )?;
 
-    let links = get_links(LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToLabResults)?, GetStrategy::default())?;
+    let language = match input.language {
+        Some(preference) => ExplanationLanguage::from_preference(&preference),
+        None => patient_explanation_language(&lab.patient_hash),
+    };
 
-    // FIXED N+1: Use batch fetch instead of individual get() calls
-    let results = links_to_records(links)?;
+    log_data_access(
+        lab.patient_hash.clone(),
+        vec![DataCategory::LabResults],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
 
-    // Log the access
-    if !results.is_empty() {
-        log_data_access(
-            input.patient_hash,
-            vec![DataCategory::LabResults],
-            Permission::Read,
-            auth.consent_hash,
-            auth.emergency_override,
-            input.emergency_reason,
-        )?;
-    }
+    Ok(explain_lab(&lab, language))
+}
 
-    Ok(results)
+/// A lab result paired with its patient-facing explanation
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ExplainedLabResult {
+    pub record: Record,
+    pub explanation: Option<LabExplanation>,
+}
+
+/// Get patient's lab results with plain-language explanations for dashboards
+#[hdk_extern]
+pub fn get_patient_lab_results_explained(input: GetPatientLabResultsInput) -> ExternResult<BudgetedPage<ExplainedLabResult>> {
+    let language = patient_explanation_language(&input.patient_hash);
+    let records = get_patient_lab_results(input)?;
+
+    Ok(records.map(|record| {
+        let explanation = record
+            .entry()
+            .to_app_option::<LabResult>()
+            .ok()
+            .flatten()
+            .and_then(|lab| explain_lab(&lab, language));
+        ExplainedLabResult { record, explanation }
+    }))
 }
 
 /// Input for acknowledging critical result with access control
@@ -842,39 +1032,29 @@ pub struct GetPatientImagingInput {
     pub patient_hash: ActionHash,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
 }
 
 /// Get patient's imaging studies with access control
 ///
-/// OPTIMIZED: Uses batch query to avoid N+1 pattern
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
 #[hdk_extern]
-pub fn get_patient_imaging(input: GetPatientImagingInput) -> ExternResult<Vec<Record>> {
-    // Require Read authorization for ImagingStudies category
-    let auth = require_authorization(
-        input.patient_hash.clone(),
+pub fn get_patient_imaging(input: GetPatientImagingInput) -> ExternResult<BudgetedPage<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let page = input.page.clone();
+    audited_read(
+        "records::get_patient_imaging",
+        input.patient_hash,
         DataCategory::ImagingStudies,
-        Permission::Read,
         input.is_emergency,
-    )?;
-
-    let links = get_links(LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToImaging)?, GetStrategy::default())?;
-
-    // FIXED N+1: Use batch fetch instead of individual get() calls
-    let studies = links_to_records(links)?;
-
-    // Log the access
-    if !studies.is_empty() {
-        log_data_access(
-            input.patient_hash,
-            vec![DataCategory::ImagingStudies],
-            Permission::Read,
-            auth.consent_hash,
-            auth.emergency_override,
-            input.emergency_reason,
-        )?;
-    }
-
-    Ok(studies)
+        input.emergency_reason,
+        || {
+            let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToImaging)?, GetStrategy::default())?;
+            links_to_records_budgeted(links, &page)
+        },
+    )
 }
 
 /// Input for recording vital signs with access control
@@ -919,6 +1099,12 @@ pub fn record_vital_signs(input: RecordVitalSignsInput) -> ExternResult<Record>
     }
     // ================================================================
 
+    notify_webhooks(
+        WebhookEventType::NewObservation,
+        input.vitals.patient_hash.clone(),
+        record.action_address().clone(),
+    );
+
     // Log the access
     log_data_access(
         input.vitals.patient_hash,
@@ -938,39 +1124,29 @@ pub struct GetPatientVitalsInput {
     pub patient_hash: ActionHash,
     pub is_emergency: bool,
     pub emergency_reason: Option<String>,
+    /// Continuation and response size budget
+    #[serde(default)]
+    pub page: BudgetInput,
 }
 
 /// Get patient's recent vital signs with access control
 ///
-/// OPTIMIZED: Uses batch query to avoid N+1 pattern
+/// Returns as many as fit the response budget; follow `continuation` for the rest.
 #[hdk_extern]
-pub fn get_patient_vitals(input: GetPatientVitalsInput) -> ExternResult<Vec<Record>> {
-    // Require Read authorization for VitalSigns category
-    let auth = require_authorization(
-        input.patient_hash.clone(),
+pub fn get_patient_vitals(input: GetPatientVitalsInput) -> ExternResult<BudgetedPage<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let page = input.page.clone();
+    audited_read(
+        "records::get_patient_vitals",
+        input.patient_hash,
         DataCategory::VitalSigns,
-        Permission::Read,
         input.is_emergency,
-    )?;
-
-    let links = get_links(LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToVitals)?, GetStrategy::default())?;
-
-    // FIXED N+1: Use batch fetch instead of individual get() calls
-    let vitals = links_to_records(links)?;
-
-    // Log the access
-    if !vitals.is_empty() {
-        log_data_access(
-            input.patient_hash,
-            vec![DataCategory::VitalSigns],
-            Permission::Read,
-            auth.consent_hash,
-            auth.emergency_override,
-            input.emergency_reason,
-        )?;
-    }
-
-    Ok(vitals)
+        input.emergency_reason,
+        || {
+            let links = get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToVitals)?, GetStrategy::default())?;
+            links_to_records_budgeted(links, &page)
+        },
+    )
 }
 
 /// Get all critical/unacknowledged results (admin function)
@@ -1220,12 +1396,1814 @@ pub fn get_encounter_history(input: GetEncounterHistoryInput) -> ExternResult<Ve
     Ok(history)
 }
 
-/// Anchor entry for indexing
-#[hdk_entry_helper]
-#[derive(Clone, PartialEq)]
-pub struct Anchor(pub String);
+// ==================== LAB ORDERS AND SPECIMENS ====================
 
-fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
-    let anchor = Anchor(anchor_text.to_string());
-    hash_entry(&anchor)
+/// Latest version of an entry whose updates are linked from the original
+fn latest_version<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(
+    original: &ActionHash,
+    updates: LinkTypes,
+    what: &str,
+) -> ExternResult<(ActionHash, T)> {
+    let latest = get_links(LinkQuery::try_new(original.clone(), updates)?, GetStrategy::default())?
+        .into_iter()
+        .max_by_key(|link| link.timestamp)
+        .and_then(|link| link.target.into_action_hash())
+        .unwrap_or_else(|| original.clone());
+    let entry = get(latest.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<T>().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest(format!("{} not found", what))))?;
+    Ok((latest, entry))
+}
+
+/// Move a lab order to `next`, doing nothing if it is already there
+fn advance_lab_order(order_hash: &ActionHash, next: LabOrderStatus) -> ExternResult<LabOrder> {
+    let (latest_hash, order) = latest_version::<LabOrder>(order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+    if order.status == next {
+        return Ok(order);
+    }
+    if !order.status.can_advance_to(&next) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Lab order is {:?} and cannot move to {:?}",
+            order.status, next
+        ))));
+    }
+    let updated = LabOrder {
+        status: next,
+        status_changed_at: sys_time()?,
+        ..order
+    };
+    let updated_hash = update_entry(latest_hash, &updated)?;
+    create_link(order_hash.clone(), updated_hash, LinkTypes::LabOrderUpdates, ())?;
+    Ok(updated)
+}
+
+fn specimen_accession_anchor(accession_number: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("specimen_accession:{}", accession_number))
+}
+
+/// Input for placing a lab order with access control
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateLabOrderInput {
+    pub order: LabOrder,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Place a lab order; specimens and results are tracked against it
+#[hdk_extern]
+pub fn create_lab_order(input: CreateLabOrderInput) -> ExternResult<Record> {
+    let auth = require_authorization(
+        input.order.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+    if input.order.ordering_provider != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Lab orders must be placed by the ordering provider".to_string()
+        )));
+    }
+
+    let order_hash = create_entry(&EntryTypes::LabOrder(input.order.clone()))?;
+    let record = get(order_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find lab order".to_string())))?;
+
+    create_link(
+        input.order.patient_hash.clone(),
+        order_hash,
+        LinkTypes::PatientToLabOrders,
+        (),
+    )?;
+
+    log_data_access(
+        input.order.patient_hash,
+        vec![DataCategory::LabResults],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(record)
+}
+
+/// Input for cancelling a lab order
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CancelLabOrderInput {
+    pub order_hash: ActionHash,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Cancel a lab order that has not been resulted (ordering provider only)
+#[hdk_extern]
+pub fn cancel_lab_order(input: CancelLabOrderInput) -> ExternResult<LabOrder> {
+    let (_, order) = latest_version::<LabOrder>(&input.order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+    let auth = require_authorization(
+        order.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+    if order.ordering_provider != agent_info()?.agent_initial_pubkey {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the ordering provider can cancel a lab order".to_string()
+        )));
+    }
+
+    let cancelled = advance_lab_order(&input.order_hash, LabOrderStatus::Cancelled)?;
+
+    log_data_access(
+        order.patient_hash,
+        vec![DataCategory::LabResults],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(cancelled)
+}
+
+/// Input for collecting a specimen against a lab order
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CollectSpecimenInput {
+    pub order_hash: ActionHash,
+    pub specimen_id: String,
+    pub accession_number: String,
+    pub specimen_type: String,
+    pub collection_time: Timestamp,
+    /// Where the specimen was collected
+    pub location: String,
+    pub note: Option<String>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Record specimen collection, starting its chain of custody with the caller
+#[hdk_extern]
+pub fn collect_specimen(input: CollectSpecimenInput) -> ExternResult<Record> {
+    let (_, order) = latest_version::<LabOrder>(&input.order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+    let auth = require_authorization(
+        order.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+
+    if !matches!(
+        order.status,
+        LabOrderStatus::Ordered | LabOrderStatus::Collected | LabOrderStatus::InLab
+    ) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Cannot collect specimens for a {:?} lab order",
+            order.status
+        ))));
+    }
+
+    let accession_anchor = specimen_accession_anchor(&input.accession_number)?;
+    if !get_links(
+        LinkQuery::try_new(accession_anchor.clone(), LinkTypes::AccessionToSpecimen)?,
+        GetStrategy::default(),
+    )?
+    .is_empty()
+    {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Accession number {} is already in use",
+            input.accession_number
+        ))));
+    }
+
+    let collector = agent_info()?.agent_initial_pubkey;
+    let specimen = Specimen {
+        specimen_id: input.specimen_id,
+        order_hash: input.order_hash.clone(),
+        patient_hash: order.patient_hash.clone(),
+        accession_number: input.accession_number,
+        specimen_type: input.specimen_type,
+        collected_by: collector.clone(),
+        collection_time: input.collection_time,
+        custody: vec![CustodyEvent {
+            action: CustodyAction::Collected,
+            custodian: collector,
+            location: input.location,
+            at: input.collection_time,
+            note: input.note,
+        }],
+    };
+    let specimen_hash = create_entry(&EntryTypes::Specimen(specimen))?;
+    let record = get(specimen_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find specimen".to_string())))?;
+
+    create_link(
+        input.order_hash.clone(),
+        specimen_hash.clone(),
+        LinkTypes::LabOrderToSpecimens,
+        (),
+    )?;
+    create_link(accession_anchor, specimen_hash, LinkTypes::AccessionToSpecimen, ())?;
+    // Further specimens for an order already in progress leave its status alone
+    if order.status == LabOrderStatus::Ordered {
+        advance_lab_order(&input.order_hash, LabOrderStatus::Collected)?;
+    }
+
+    log_data_access(
+        order.patient_hash,
+        vec![DataCategory::LabResults],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(record)
+}
+
+/// Input for recording a specimen handling step
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordCustodyInput {
+    /// Original specimen hash (as returned by collect_specimen)
+    pub specimen_hash: ActionHash,
+    pub action: CustodyAction,
+    pub location: String,
+    pub note: Option<String>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Append a handling step to a specimen's chain of custody
+///
+/// The caller becomes the specimen's custodian. The first receipt in the
+/// lab moves a collected order to InLab.
+#[hdk_extern]
+pub fn record_specimen_custody(input: RecordCustodyInput) -> ExternResult<Record> {
+    if input.action == CustodyAction::Collected {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Collection is recorded with collect_specimen".to_string()
+        )));
+    }
+    let (latest_hash, specimen) =
+        latest_version::<Specimen>(&input.specimen_hash, LinkTypes::SpecimenUpdates, "Specimen")?;
+    let auth = require_authorization(
+        specimen.patient_hash.clone(),
+        DataCategory::LabResults,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+
+    let received_in_lab = input.action == CustodyAction::ReceivedInLab;
+    let mut updated = specimen.clone();
+    updated.custody.push(CustodyEvent {
+        action: input.action,
+        custodian: agent_info()?.agent_initial_pubkey,
+        location: input.location,
+        at: sys_time()?,
+        note: input.note,
+    });
+    let updated_hash = update_entry(latest_hash, &updated)?;
+    let record = get(updated_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find updated specimen".to_string())))?;
+    create_link(
+        input.specimen_hash,
+        updated_hash,
+        LinkTypes::SpecimenUpdates,
+        (),
+    )?;
+
+    if received_in_lab {
+        let (_, order) = latest_version::<LabOrder>(&specimen.order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+        if order.status == LabOrderStatus::Collected {
+            advance_lab_order(&specimen.order_hash, LabOrderStatus::InLab)?;
+        }
+    }
+
+    log_data_access(
+        specimen.patient_hash,
+        vec![DataCategory::LabResults],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(record)
+}
+
+/// A lab order with its specimens and resulting observations
+#[derive(Serialize, Deserialize, Debug)]
+pub struct LabOrderDetails {
+    pub order_hash: ActionHash,
+    pub order: LabOrder,
+    /// Latest version of each specimen, keyed by its original hash
+    pub specimens: Vec<(ActionHash, Specimen)>,
+    pub results: Vec<Record>,
+}
+
+fn lab_order_details(order_hash: ActionHash) -> ExternResult<LabOrderDetails> {
+    let (_, order) = latest_version::<LabOrder>(&order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+    let specimens = get_links(
+        LinkQuery::try_new(order_hash.clone(), LinkTypes::LabOrderToSpecimens)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .filter_map(|link| link.target.into_action_hash())
+    .map(|hash| {
+        let (_, specimen) = latest_version::<Specimen>(&hash, LinkTypes::SpecimenUpdates, "Specimen")?;
+        Ok((hash, specimen))
+    })
+    .collect::<ExternResult<Vec<_>>>()?;
+    let results = links_to_records(get_links(
+        LinkQuery::try_new(order_hash.clone(), LinkTypes::LabOrderToResults)?,
+        GetStrategy::default(),
+    )?)?;
+    Ok(LabOrderDetails { order_hash, order, specimens, results })
+}
+
+/// Input for reading a lab order with access control
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetLabOrderInput {
+    pub order_hash: ActionHash,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Get a lab order with its specimens and results
+#[hdk_extern]
+pub fn get_lab_order(input: GetLabOrderInput) -> ExternResult<LabOrderDetails> {
+    let (_, order) = latest_version::<LabOrder>(&input.order_hash, LinkTypes::LabOrderUpdates, "Lab order")?;
+    audited_read(
+        "records::get_lab_order",
+        order.patient_hash,
+        DataCategory::LabResults,
+        input.is_emergency,
+        input.emergency_reason,
+        || lab_order_details(input.order_hash),
+    )
+}
+
+/// Input for listing a patient's lab orders with access control
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientLabOrdersInput {
+    pub patient_hash: ActionHash,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Get a patient's lab orders with their specimens and results
+#[hdk_extern]
+pub fn get_patient_lab_orders(input: GetPatientLabOrdersInput) -> ExternResult<Vec<LabOrderDetails>> {
+    let patient_hash = input.patient_hash.clone();
+    audited_read(
+        "records::get_patient_lab_orders",
+        input.patient_hash,
+        DataCategory::LabResults,
+        input.is_emergency,
+        input.emergency_reason,
+        || {
+            get_links(LinkQuery::try_new(patient_hash, LinkTypes::PatientToLabOrders)?, GetStrategy::default())?
+                .into_iter()
+                .filter_map(|link| link.target.into_action_hash())
+                .map(lab_order_details)
+                .collect()
+        },
+    )
+}
+
+/// Input for looking up a specimen by accession number
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetSpecimenByAccessionInput {
+    pub accession_number: String,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Look up a specimen by its accession number, returning its original hash
+/// and latest version
+#[hdk_extern]
+pub fn get_specimen_by_accession(input: GetSpecimenByAccessionInput) -> ExternResult<Option<(ActionHash, Specimen)>> {
+    let Some(specimen_hash) = get_links(
+        LinkQuery::try_new(specimen_accession_anchor(&input.accession_number)?, LinkTypes::AccessionToSpecimen)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .find_map(|link| link.target.into_action_hash()) else {
+        return Ok(None);
+    };
+    let (_, specimen) = latest_version::<Specimen>(&specimen_hash, LinkTypes::SpecimenUpdates, "Specimen")?;
+    audited_read(
+        "records::get_specimen_by_accession",
+        specimen.patient_hash.clone(),
+        DataCategory::LabResults,
+        input.is_emergency,
+        input.emergency_reason,
+        || Ok(Some((specimen_hash, specimen))),
+    )
+}
+
+// ==================== PATIENT CORRECTION REQUESTS ====================
+
+fn correction_category(target_type: &CorrectionTargetType) -> DataCategory {
+    match target_type {
+        CorrectionTargetType::Encounter | CorrectionTargetType::Procedure => DataCategory::Procedures,
+        CorrectionTargetType::Diagnosis => DataCategory::Diagnoses,
+        CorrectionTargetType::LabResult => DataCategory::LabResults,
+        CorrectionTargetType::ImagingStudy => DataCategory::ImagingStudies,
+        CorrectionTargetType::VitalSigns => DataCategory::VitalSigns,
+    }
+}
+
+fn get_correction_request_entry(request_hash: &ActionHash) -> ExternResult<CorrectionRequest> {
+    get(request_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Correction request not found".to_string())))?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid correction request".to_string())))
+}
+
+fn get_correction_response_record(request_hash: &ActionHash) -> ExternResult<Option<Record>> {
+    let links = get_links(
+        LinkQuery::try_new(request_hash.clone(), LinkTypes::CorrectionRequestToResponse)?,
+        GetStrategy::default(),
+    )?;
+    Ok(links_to_records(links)?.into_iter().next())
+}
+
+/// Input for requesting a correction to a record
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RequestCorrectionInput {
+    pub patient_hash: ActionHash,
+    pub target_record: ActionHash,
+    pub target_type: CorrectionTargetType,
+    pub proposed_change: String,
+    pub rationale: String,
+}
+
+/// Ask the authoring provider to amend a record
+///
+/// The provider must accept or reject within CORRECTION_RESPONSE_DAYS.
+#[hdk_extern]
+pub fn request_record_correction(input: RequestCorrectionInput) -> ExternResult<Record> {
+    let category = correction_category(&input.target_type);
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        category.clone(),
+        Permission::Read,
+        false,
+    )?;
+
+    let target = get(input.target_record.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Target record not found".to_string())))?;
+
+    let now = sys_time()?;
+    let request = CorrectionRequest {
+        request_id: format!("CORR-{}", now.as_micros()),
+        patient_hash: input.patient_hash.clone(),
+        target_record: input.target_record.clone(),
+        target_type: input.target_type,
+        responsible_provider: target.action().author().clone(),
+        proposed_change: input.proposed_change,
+        rationale: input.rationale,
+        requested_by: agent_info()?.agent_initial_pubkey,
+        requested_at: now,
+        response_due: Timestamp::from_micros(
+            now.as_micros() + CORRECTION_RESPONSE_DAYS * 24 * 60 * 60 * 1_000_000,
+        ),
+    };
+
+    let request_hash = create_entry(&EntryTypes::CorrectionRequest(request.clone()))?;
+    let record = get(request_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find correction request".to_string())))?;
+
+    create_link(
+        input.patient_hash.clone(),
+        request_hash.clone(),
+        LinkTypes::PatientToCorrectionRequests,
+        (),
+    )?;
+    create_link(
+        request.responsible_provider,
+        request_hash.clone(),
+        LinkTypes::ProviderToCorrectionRequests,
+        (),
+    )?;
+    create_link(
+        input.target_record,
+        request_hash,
+        LinkTypes::RecordToCorrectionRequests,
+        (),
+    )?;
+
+    log_data_access(
+        input.patient_hash,
+        vec![category],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(record)
+}
+
+/// Provider's decision on a correction request
+#[derive(Serialize, Deserialize, Debug)]
+pub enum CorrectionDecisionInput {
+    /// Amendment text appended to the record; defaults to the proposed change
+    Accept { amendment_text: Option<String>, explanation: String },
+    Reject { basis: CorrectionDenialBasis, explanation: String },
+}
+
+/// Input for responding to a correction request
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RespondToCorrectionInput {
+    pub request_hash: ActionHash,
+    pub decision: CorrectionDecisionInput,
+}
+
+/// Accept or reject a correction request as the record's author
+///
+/// Accepting appends a RecordAmendment to the target record.
+#[hdk_extern]
+pub fn respond_to_correction_request(input: RespondToCorrectionInput) -> ExternResult<Record> {
+    let request = get_correction_request_entry(&input.request_hash)?;
+    let caller = agent_info()?.agent_initial_pubkey;
+    if caller != request.responsible_provider {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the record's author can respond to this correction request".to_string()
+        )));
+    }
+    if get_correction_response_record(&input.request_hash)?.is_some() {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Correction request has already been answered".to_string()
+        )));
+    }
+
+    let category = correction_category(&request.target_type);
+    let auth = require_authorization(
+        request.patient_hash.clone(),
+        category.clone(),
+        Permission::Amend,
+        false,
+    )?;
+
+    let now = sys_time()?;
+    let (decision, explanation, amendment_hash) = match input.decision {
+        CorrectionDecisionInput::Accept { amendment_text, explanation } => {
+            let amendment = RecordAmendment {
+                request_hash: input.request_hash.clone(),
+                patient_hash: request.patient_hash.clone(),
+                target_record: request.target_record.clone(),
+                amendment_text: amendment_text.unwrap_or_else(|| request.proposed_change.clone()),
+                amended_by: caller.clone(),
+                amended_at: now,
+            };
+            let amendment_hash = create_entry(&EntryTypes::RecordAmendment(amendment))?;
+            create_link(
+                request.target_record.clone(),
+                amendment_hash.clone(),
+                LinkTypes::RecordToAmendments,
+                (),
+            )?;
+            (CorrectionDecision::Accepted, explanation, Some(amendment_hash))
+        }
+        CorrectionDecisionInput::Reject { basis, explanation } => {
+            (CorrectionDecision::Rejected(basis), explanation, None)
+        }
+    };
+
+    let response = CorrectionResponse {
+        request_hash: input.request_hash.clone(),
+        patient_hash: request.patient_hash.clone(),
+        decision,
+        explanation,
+        amendment_hash,
+        responded_by: caller,
+        responded_at: now,
+    };
+    let response_hash = create_entry(&EntryTypes::CorrectionResponse(response))?;
+    let record = get(response_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find correction response".to_string())))?;
+
+    create_link(
+        input.request_hash,
+        response_hash,
+        LinkTypes::CorrectionRequestToResponse,
+        (),
+    )?;
+
+    log_data_access(
+        request.patient_hash,
+        vec![category],
+        Permission::Amend,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(record)
+}
+
+/// Input for disagreeing with a rejected correction
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubmitDisagreementInput {
+    pub request_hash: ActionHash,
+    pub statement: String,
+}
+
+/// Attach a statement of disagreement to a record after a rejected correction
+#[hdk_extern]
+pub fn submit_disagreement_statement(input: SubmitDisagreementInput) -> ExternResult<Record> {
+    let request = get_correction_request_entry(&input.request_hash)?;
+    let caller = agent_info()?.agent_initial_pubkey;
+    if caller != request.requested_by {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the requester can disagree with this decision".to_string()
+        )));
+    }
+
+    let response: CorrectionResponse = get_correction_response_record(&input.request_hash)?
+        .and_then(|record| record.entry().to_app_option().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Correction request has not been answered".to_string())))?;
+    if !matches!(response.decision, CorrectionDecision::Rejected(_)) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Disagreement statements are only allowed for rejected corrections".to_string()
+        )));
+    }
+
+    let existing = get_links(
+        LinkQuery::try_new(request.target_record.clone(), LinkTypes::RecordToDisagreements)?,
+        GetStrategy::default(),
+    )?;
+    let already_submitted = links_to_records(existing)?.iter().any(|record| {
+        record
+            .entry()
+            .to_app_option::<DisagreementStatement>()
+            .ok()
+            .flatten()
+            .is_some_and(|d| d.request_hash == input.request_hash)
+    });
+    if already_submitted {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "A statement of disagreement was already submitted for this request".to_string()
+        )));
+    }
+
+    let statement = DisagreementStatement {
+        request_hash: input.request_hash,
+        patient_hash: request.patient_hash,
+        target_record: request.target_record.clone(),
+        statement: input.statement,
+        submitted_by: caller,
+        submitted_at: sys_time()?,
+    };
+    let statement_hash = create_entry(&EntryTypes::DisagreementStatement(statement))?;
+    let record = get(statement_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find disagreement statement".to_string())))?;
+
+    create_link(
+        request.target_record,
+        statement_hash,
+        LinkTypes::RecordToDisagreements,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+/// A correction request with its current status
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CorrectionRequestView {
+    pub request: Record,
+    pub status: CorrectionStatus,
+    pub response: Option<Record>,
+    /// Still pending after its response deadline
+    pub overdue: bool,
+}
+
+fn correction_request_view(request: Record, now: Timestamp) -> ExternResult<Option<CorrectionRequestView>> {
+    let entry: CorrectionRequest = match request.entry().to_app_option().ok().flatten() {
+        Some(entry) => entry,
+        None => return Ok(None),
+    };
+    let response = get_correction_response_record(request.action_address())?;
+    let status = match response
+        .as_ref()
+        .and_then(|r| r.entry().to_app_option::<CorrectionResponse>().ok().flatten())
+    {
+        Some(CorrectionResponse { decision: CorrectionDecision::Accepted, .. }) => CorrectionStatus::Accepted,
+        Some(_) => CorrectionStatus::Rejected,
+        None => CorrectionStatus::Pending,
+    };
+    let overdue = status == CorrectionStatus::Pending && now > entry.response_due;
+    Ok(Some(CorrectionRequestView { request, status, response, overdue }))
+}
+
+fn correction_request_views(links: Vec<Link>) -> ExternResult<Vec<CorrectionRequestView>> {
+    let now = sys_time()?;
+    let mut views = Vec::new();
+    for record in links_to_records(links)? {
+        if let Some(view) = correction_request_view(record, now)? {
+            views.push(view);
+        }
+    }
+    Ok(views)
+}
+
+/// Get a patient's correction requests with their status
+#[hdk_extern]
+pub fn get_patient_correction_requests(patient_hash: ActionHash) -> ExternResult<Vec<CorrectionRequestView>> {
+    let caller = agent_info()?.agent_initial_pubkey;
+    let is_patient = get(patient_hash.clone(), GetOptions::default())?
+        .is_some_and(|record| *record.action().author() == caller);
+    let links = get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToCorrectionRequests)?,
+        GetStrategy::default(),
+    )?;
+    // The patient sees everything; requesters and providers only their own requests
+    Ok(correction_request_views(links)?
+        .into_iter()
+        .filter(|view| {
+            is_patient
+                || view.request
+                    .entry()
+                    .to_app_option::<CorrectionRequest>()
+                    .ok()
+                    .flatten()
+                    .is_some_and(|r| r.requested_by == caller || r.responsible_provider == caller)
+        })
+        .collect())
+}
+
+/// Get correction requests awaiting the calling provider, oldest deadline first
+#[hdk_extern]
+pub fn get_pending_correction_requests(_: ()) -> ExternResult<Vec<CorrectionRequestView>> {
+    let links = get_links(
+        LinkQuery::try_new(agent_info()?.agent_initial_pubkey, LinkTypes::ProviderToCorrectionRequests)?,
+        GetStrategy::default(),
+    )?;
+    let mut pending: Vec<(Timestamp, CorrectionRequestView)> = correction_request_views(links)?
+        .into_iter()
+        .filter(|view| view.status == CorrectionStatus::Pending)
+        .filter_map(|view| {
+            let due = view.request.entry().to_app_option::<CorrectionRequest>().ok().flatten()?.response_due;
+            Some((due, view))
+        })
+        .collect();
+    pending.sort_by_key(|(due, _)| *due);
+    Ok(pending.into_iter().map(|(_, view)| view).collect())
+}
+
+/// Amendments and disagreement statements attached to a record
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RecordCorrections {
+    pub amendments: Vec<Record>,
+    pub disagreements: Vec<Record>,
+}
+
+/// Input for getting a record's amendments
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetRecordCorrectionsInput {
+    pub patient_hash: ActionHash,
+    pub record_hash: ActionHash,
+    pub target_type: CorrectionTargetType,
+}
+
+/// Get the amendments and statements of disagreement attached to a record
+///
+/// Anyone allowed to read the record sees them alongside it.
+#[hdk_extern]
+pub fn get_record_corrections(input: GetRecordCorrectionsInput) -> ExternResult<RecordCorrections> {
+    let category = correction_category(&input.target_type);
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        category.clone(),
+        Permission::Read,
+        false,
+    )?;
+
+    let amendments = get_links(
+        LinkQuery::try_new(input.record_hash.clone(), LinkTypes::RecordToAmendments)?,
+        GetStrategy::default(),
+    )?;
+    let disagreements = get_links(
+        LinkQuery::try_new(input.record_hash, LinkTypes::RecordToDisagreements)?,
+        GetStrategy::default(),
+    )?;
+    let corrections = RecordCorrections {
+        amendments: links_to_records(amendments)?,
+        disagreements: links_to_records(disagreements)?,
+    };
+
+    log_data_access(
+        input.patient_hash,
+        vec![category],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+
+    Ok(corrections)
+}
+
+/// Anchor entry for indexing
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Anchor(pub String);
+
+fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
+    let anchor = Anchor(anchor_text.to_string());
+    hash_entry(&anchor)
+}
+
+// ==================== FOLLOW-UP TASKS ====================
+
+/// A task to create, before the patient and care team are attached
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FollowUpTaskDraft {
+    pub kind: FollowUpTaskKind,
+    pub description: String,
+    pub due_at: Option<Timestamp>,
+}
+
+/// Input for recording the follow-up tasks of a care transition
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreateFollowUpTasksInput {
+    pub patient_hash: ActionHash,
+    /// Responsible care team; defaults to the patient's newest active team
+    pub care_team_hash: Option<ActionHash>,
+    pub source_reference: Option<String>,
+    pub tasks: Vec<FollowUpTaskDraft>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Creation time of a care team (mirrors consent_integrity::CareTeam)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct CareTeamCreatedAt {
+    created_at: Timestamp,
+}
+
+/// The patient's most recently formed active care team
+fn responsible_care_team(patient_hash: &ActionHash) -> Option<ActionHash> {
+    let teams: Vec<Record> =
+        resilient_call("consent", "get_active_care_teams", patient_hash, CallClass::Authorization).ok()?;
+    teams
+        .into_iter()
+        .filter_map(|record| {
+            let created_at = record.entry().to_app_option::<CareTeamCreatedAt>().ok()??.created_at;
+            Some((created_at, record.action_address().clone()))
+        })
+        .max_by_key(|(created_at, _)| *created_at)
+        .map(|(_, hash)| hash)
+}
+
+/// Record follow-up tasks for a patient and assign them to a care team
+#[hdk_extern]
+pub fn create_follow_up_tasks(input: CreateFollowUpTasksInput) -> ExternResult<Vec<Record>> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Procedures,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+
+    let care_team_hash = input
+        .care_team_hash
+        .or_else(|| responsible_care_team(&input.patient_hash));
+    let now = sys_time()?;
+    let open_anchor = anchor_hash("open_follow_up_tasks")?;
+    let mut records = Vec::new();
+    for (index, draft) in input.tasks.into_iter().enumerate() {
+        let task = FollowUpTask {
+            task_id: format!("FUT-{}-{}", now.as_micros(), index),
+            patient_hash: input.patient_hash.clone(),
+            care_team_hash: care_team_hash.clone(),
+            kind: draft.kind,
+            description: draft.description,
+            due_at: draft.due_at,
+            source_reference: input.source_reference.clone(),
+            status: FollowUpTaskStatus::Open,
+            created_at: now,
+            resolved_at: None,
+            resolved_by: None,
+            resolution_note: None,
+            escalated_at: None,
+        };
+        let task_hash = create_entry(&EntryTypes::FollowUpTask(task))?;
+        create_link(input.patient_hash.clone(), task_hash.clone(), LinkTypes::PatientToFollowUpTasks, ())?;
+        if let Some(team_hash) = &care_team_hash {
+            create_link(team_hash.clone(), task_hash.clone(), LinkTypes::CareTeamToFollowUpTasks, ())?;
+        }
+        create_link(open_anchor.clone(), task_hash.clone(), LinkTypes::OpenFollowUpTasks, ())?;
+        records.push(
+            get(task_hash, GetOptions::default())?
+                .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find follow-up task".to_string())))?,
+        );
+    }
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Procedures],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(records)
+}
+
+/// Input for completing or cancelling a follow-up task
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ResolveFollowUpTaskInput {
+    pub task_hash: ActionHash,
+    /// `Completed` or `Cancelled`
+    pub outcome: FollowUpTaskStatus,
+    pub note: Option<String>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Complete or cancel an open follow-up task
+#[hdk_extern]
+pub fn resolve_follow_up_task(input: ResolveFollowUpTaskInput) -> ExternResult<FollowUpTask> {
+    let (latest_hash, task) = latest_version::<FollowUpTask>(&input.task_hash, LinkTypes::FollowUpTaskUpdates, "Follow-up task")?;
+    let auth = require_authorization(
+        task.patient_hash.clone(),
+        DataCategory::Procedures,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+    if !task.status.can_advance_to(&input.outcome) {
+        return Err(wasm_error!(WasmErrorInner::Guest(format!(
+            "Follow-up task is {:?} and cannot move to {:?}",
+            task.status, input.outcome
+        ))));
+    }
+
+    let updated = FollowUpTask {
+        status: input.outcome,
+        resolved_at: Some(sys_time()?),
+        resolved_by: Some(agent_info()?.agent_initial_pubkey),
+        resolution_note: input.note,
+        ..task
+    };
+    let updated_hash = update_entry(latest_hash, &updated)?;
+    create_link(input.task_hash.clone(), updated_hash, LinkTypes::FollowUpTaskUpdates, ())?;
+
+    for link in get_links(
+        LinkQuery::try_new(anchor_hash("open_follow_up_tasks")?, LinkTypes::OpenFollowUpTasks)?,
+        GetStrategy::default(),
+    )? {
+        if link.target.clone().into_action_hash().as_ref() == Some(&input.task_hash) {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+    }
+
+    log_data_access(
+        updated.patient_hash.clone(),
+        vec![DataCategory::Procedures],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(updated)
+}
+
+/// A follow-up task in its latest version
+#[derive(Serialize, Deserialize, Debug)]
+pub struct FollowUpTaskView {
+    /// Hash of the original task, used to resolve it
+    pub task_hash: ActionHash,
+    pub task: FollowUpTask,
+    /// Still open after its due date
+    pub overdue: bool,
+}
+
+fn is_overdue(task: &FollowUpTask, now: Timestamp) -> bool {
+    task.status == FollowUpTaskStatus::Open && task.due_at.is_some_and(|due| now > due)
+}
+
+/// Input for listing a patient's follow-up tasks
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientFollowUpTasksInput {
+    pub patient_hash: ActionHash,
+    /// Only tasks assigned to this care team
+    pub care_team_hash: Option<ActionHash>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// A patient's follow-up tasks, overdue first, then by due date
+#[hdk_extern]
+pub fn get_patient_follow_up_tasks(input: GetPatientFollowUpTasksInput) -> ExternResult<Vec<FollowUpTaskView>> {
+    let auth = require_authorization(
+        input.patient_hash.clone(),
+        DataCategory::Procedures,
+        Permission::Read,
+        input.is_emergency,
+    )?;
+
+    let now = sys_time()?;
+    let mut views = Vec::new();
+    for link in get_links(
+        LinkQuery::try_new(input.patient_hash.clone(), LinkTypes::PatientToFollowUpTasks)?,
+        GetStrategy::default(),
+    )? {
+        let Some(task_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let (_, task) = latest_version::<FollowUpTask>(&task_hash, LinkTypes::FollowUpTaskUpdates, "Follow-up task")?;
+        if input.care_team_hash.is_some() && task.care_team_hash != input.care_team_hash {
+            continue;
+        }
+        let overdue = is_overdue(&task, now);
+        views.push(FollowUpTaskView { task_hash, task, overdue });
+    }
+    views.sort_by_key(|view| (!view.overdue, view.task.due_at.map_or(i64::MAX, |due| due.as_micros())));
+
+    log_data_access(
+        input.patient_hash,
+        vec![DataCategory::Procedures],
+        Permission::Read,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+
+    Ok(views)
+}
+
+/// Escalate open tasks past their due date (operators only)
+///
+/// Each overdue task is escalated once: it is marked escalated and the
+/// patient gets an immediate notification. Returns the escalated tasks.
+#[hdk_extern]
+pub fn escalate_overdue_follow_up_tasks(_: ()) -> ExternResult<Vec<ActionHash>> {
+    require_operator()?;
+
+    let now = sys_time()?;
+    let mut escalated = Vec::new();
+    for link in get_links(
+        LinkQuery::try_new(anchor_hash("open_follow_up_tasks")?, LinkTypes::OpenFollowUpTasks)?,
+        GetStrategy::default(),
+    )? {
+        let Some(task_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let (latest_hash, task) = latest_version::<FollowUpTask>(&task_hash, LinkTypes::FollowUpTaskUpdates, "Follow-up task")?;
+        if !is_overdue(&task, now) || task.escalated_at.is_some() {
+            continue;
+        }
+
+        let updated = FollowUpTask {
+            escalated_at: Some(now),
+            ..task
+        };
+        let updated_hash = update_entry(latest_hash, &updated)?;
+        create_link(task_hash.clone(), updated_hash, LinkTypes::FollowUpTaskUpdates, ())?;
+        try_notify_overdue_task(&updated, &task_hash);
+        escalated.push(task_hash);
+    }
+
+    Ok(escalated)
+}
+
+/// Tell the patient a follow-up task is overdue (best effort)
+fn try_notify_overdue_task(task: &FollowUpTask, task_hash: &ActionHash) {
+    let _ = notify_overdue_task_internal(task, task_hash);
+}
+
+fn notify_overdue_task_internal(task: &FollowUpTask, task_hash: &ActionHash) -> ExternResult<()> {
+    let now = sys_time()?;
+    let notification = PatientNotification {
+        notification_id: format!("FUT-{}-{}", task_hash, now.as_micros()),
+        patient_hash: task.patient_hash.clone(),
+        accessor: agent_info()?.agent_initial_pubkey,
+        accessor_name: "Care transitions".to_string(),
+        data_categories: vec![DataCategory::Procedures],
+        purpose: "Overdue follow-up".to_string(),
+        accessed_at: now,
+        emergency_access: false,
+        priority: PatientNotificationPriority::Immediate,
+        viewed: false,
+        viewed_at: None,
+        summary: format!("A follow-up from your care transition is overdue: {}", task.description),
+        access_log_hash: None,
+    };
+
+    call(
+        CallTargetCell::Local,
+        ZomeName::from("consent"),
+        FunctionName::from("create_access_notification"),
+        None,
+        &notification,
+    )?;
+    Ok(())
+}
+
+// ==================== VACCINE REACTIONS ====================
+
+/// A vaccine reaction as reported, before the reporter is attached
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct VaccineReactionReport {
+    pub patient_hash: ActionHash,
+    pub immunization_hash: ActionHash,
+    pub vaccine_code: String,
+    pub vaccine_name: String,
+    pub lot_number: Option<String>,
+    pub manufacturer: Option<String>,
+    pub administered_at: Timestamp,
+    pub onset_at: Timestamp,
+    pub symptoms: Vec<ReactionSymptom>,
+    pub suspected_allergens: Vec<String>,
+    pub severity: VaccineReactionSeverity,
+    pub outcome: VaccineReactionOutcome,
+    pub narrative: Option<String>,
+}
+
+/// Input for a provider reporting a vaccine reaction
+#[derive(Serialize, Deserialize, Debug)]
+pub struct ReportVaccineReactionInput {
+    pub report: VaccineReactionReport,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+fn create_vaccine_reaction(report: VaccineReactionReport, reporter: VaccineReactionReporter) -> ExternResult<Record> {
+    let now = sys_time()?;
+    let reaction = VaccineReaction {
+        reaction_id: format!("VR-{}", now.as_micros()),
+        patient_hash: report.patient_hash.clone(),
+        immunization_hash: report.immunization_hash.clone(),
+        vaccine_code: report.vaccine_code,
+        vaccine_name: report.vaccine_name,
+        lot_number: report.lot_number,
+        manufacturer: report.manufacturer,
+        administered_at: report.administered_at,
+        onset_at: report.onset_at,
+        symptoms: report.symptoms,
+        suspected_allergens: report.suspected_allergens,
+        severity: report.severity,
+        outcome: report.outcome,
+        narrative: report.narrative,
+        reporter,
+        reported_by: agent_info()?.agent_initial_pubkey,
+        reported_at: now,
+    };
+    let reaction_hash = create_entry(&EntryTypes::VaccineReaction(reaction))?;
+    create_link(
+        report.patient_hash,
+        reaction_hash.clone(),
+        LinkTypes::PatientToVaccineReactions,
+        (),
+    )?;
+    create_link(
+        report.immunization_hash,
+        reaction_hash.clone(),
+        LinkTypes::ImmunizationToReactions,
+        (),
+    )?;
+    get(reaction_hash, GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find vaccine reaction".to_string())))
+}
+
+/// Report a reaction to one of the caller's own vaccinations
+#[hdk_extern]
+pub fn report_vaccine_reaction_as_patient(report: VaccineReactionReport) -> ExternResult<Record> {
+    require_patient_self(&report.patient_hash)?;
+    create_vaccine_reaction(report, VaccineReactionReporter::Patient)
+}
+
+/// Report a patient's reaction to a vaccine on behalf of their provider
+#[hdk_extern]
+pub fn report_vaccine_reaction(input: ReportVaccineReactionInput) -> ExternResult<Record> {
+    let auth = require_authorization(
+        input.report.patient_hash.clone(),
+        DataCategory::Immunizations,
+        Permission::Write,
+        input.is_emergency,
+    )?;
+    let patient_hash = input.report.patient_hash.clone();
+    let record = create_vaccine_reaction(input.report, VaccineReactionReporter::Provider)?;
+
+    log_data_access(
+        patient_hash,
+        vec![DataCategory::Immunizations],
+        Permission::Write,
+        auth.consent_hash,
+        auth.emergency_override,
+        input.emergency_reason,
+    )?;
+    Ok(record)
+}
+
+/// Input for reading a patient's vaccine reactions
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientVaccineReactionsInput {
+    pub patient_hash: ActionHash,
+    /// Only reactions to this immunization
+    pub immunization_hash: Option<ActionHash>,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+}
+
+/// Get a patient's vaccine reactions, optionally for one immunization
+#[hdk_extern]
+pub fn get_patient_vaccine_reactions(input: GetPatientVaccineReactionsInput) -> ExternResult<Vec<Record>> {
+    let patient_hash = input.patient_hash.clone();
+    let immunization_hash = input.immunization_hash;
+    audited_read(
+        "records::get_patient_vaccine_reactions",
+        input.patient_hash,
+        DataCategory::Immunizations,
+        input.is_emergency,
+        input.emergency_reason,
+        || {
+            let links = match immunization_hash {
+                Some(immunization) => get_links(
+                    LinkQuery::try_new(immunization, LinkTypes::ImmunizationToReactions)?,
+                    GetStrategy::default(),
+                )?,
+                None => get_links(
+                    LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToVaccineReactions)?,
+                    GetStrategy::default(),
+                )?,
+            };
+            Ok(links_to_records(links)?
+                .into_iter()
+                .filter(|record| {
+                    record
+                        .entry()
+                        .to_app_option::<VaccineReaction>()
+                        .ok()
+                        .flatten()
+                        .is_some_and(|reaction| reaction.patient_hash == patient_hash)
+                })
+                .collect())
+        },
+    )
+}
+
+/// Vaccine components a patient has reacted to, for allergy cross-checking
+#[hdk_extern]
+pub fn get_vaccine_reaction_allergens(patient_hash: ActionHash) -> ExternResult<Vec<String>> {
+    let reactions_of = patient_hash.clone();
+    audited_read(
+        "records::get_vaccine_reaction_allergens",
+        patient_hash,
+        DataCategory::Allergies,
+        false,
+        None,
+        || {
+            let mut allergens: Vec<String> = links_to_records(get_links(
+                LinkQuery::try_new(reactions_of, LinkTypes::PatientToVaccineReactions)?,
+                GetStrategy::default(),
+            )?)?
+            .iter()
+            .filter_map(|record| record.entry().to_app_option::<VaccineReaction>().ok().flatten())
+            .flat_map(|reaction| reaction.suspected_allergens)
+            .map(|allergen| allergen.trim().to_lowercase())
+            .filter(|allergen| !allergen.is_empty())
+            .collect();
+            allergens.sort();
+            allergens.dedup();
+            Ok(allergens)
+        },
+    )
+}
+
+/// Purpose of a consent (mirrors consent_integrity::ConsentPurpose)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+enum ConsentPurposeMirror {
+    Treatment,
+    Payment,
+    HealthcareOperations,
+    Research,
+    PublicHealth,
+    LegalProceeding,
+    Marketing,
+    FamilyNotification,
+    Other(String),
+}
+
+/// Purpose field of a consent (mirrors consent_integrity::Consent)
+#[derive(Serialize, Deserialize, SerializedBytes, Debug, Clone)]
+struct ConsentPurposeField {
+    purpose: ConsentPurposeMirror,
+}
+
+/// Export a vaccine reaction as a VAERS-style JSON report
+///
+/// Anyone but the patient needs an Export consent given for public health.
+#[hdk_extern]
+pub fn export_vaers_report(reaction_hash: ActionHash) -> ExternResult<serde_json::Value> {
+    let record = get(reaction_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Vaccine reaction not found".to_string())))?;
+    let reaction: VaccineReaction = record
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid vaccine reaction".to_string())))?;
+
+    let auth = require_authorization(
+        reaction.patient_hash.clone(),
+        DataCategory::Immunizations,
+        Permission::Export,
+        false,
+    )?;
+    if let Some(consent_hash) = &auth.consent_hash {
+        let purpose = get(consent_hash.clone(), GetOptions::default())?
+            .and_then(|consent| consent.entry().to_app_option::<ConsentPurposeField>().ok().flatten())
+            .map(|consent| consent.purpose);
+        if purpose != Some(ConsentPurposeMirror::PublicHealth) {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "VAERS reports may only be exported under a public health consent".to_string()
+            )));
+        }
+    }
+
+    let report = vaers_report(&reaction, &reaction_hash, sys_time()?);
+    log_data_access(
+        reaction.patient_hash,
+        vec![DataCategory::Immunizations],
+        Permission::Export,
+        auth.consent_hash,
+        auth.emergency_override,
+        None,
+    )?;
+    Ok(report)
+}
+
+/// Lay out a reaction along the sections of the VAERS 2.0 form
+fn vaers_report(reaction: &VaccineReaction, reaction_hash: &ActionHash, generated_at: Timestamp) -> serde_json::Value {
+    const DAY_MICROS: i64 = 24 * 60 * 60 * 1_000_000;
+    let outcome = &reaction.outcome;
+    let recovered = match outcome {
+        VaccineReactionOutcome::Recovered => "Y",
+        VaccineReactionOutcome::Unknown => "U",
+        _ => "N",
+    };
+    serde_json::json!({
+        "reportType": "VAERS",
+        "formVersion": "2.0",
+        "reportId": reaction.reaction_id,
+        "sourceRecord": reaction_hash.to_string(),
+        "reporterType": match reaction.reporter {
+            VaccineReactionReporter::Patient => "patient",
+            VaccineReactionReporter::Provider => "healthcare_professional",
+        },
+        "vaccinationDate": reaction.administered_at.to_string(),
+        "adverseEventOnsetDate": reaction.onset_at.to_string(),
+        "daysToOnset": (reaction.onset_at.as_micros() - reaction.administered_at.as_micros()) / DAY_MICROS,
+        "vaccines": [{
+            "cvxCode": reaction.vaccine_code,
+            "name": reaction.vaccine_name,
+            "manufacturer": reaction.manufacturer,
+            "lotNumber": reaction.lot_number,
+        }],
+        "symptoms": reaction.symptoms.iter().map(|symptom| serde_json::json!({
+            "system": symptom.system,
+            "code": symptom.code,
+            "display": symptom.display,
+        })).collect::<Vec<_>>(),
+        "adverseEventDescription": reaction.narrative,
+        "severity": format!("{:?}", reaction.severity),
+        "outcomes": {
+            "died": *outcome == VaccineReactionOutcome::Died,
+            "lifeThreatening": reaction.severity == VaccineReactionSeverity::LifeThreatening,
+            "hospitalized": *outcome == VaccineReactionOutcome::Hospitalized,
+            "disabilityOrPermanentDamage": matches!(
+                outcome,
+                VaccineReactionOutcome::Disabled | VaccineReactionOutcome::RecoveredWithSequelae
+            ),
+            "recovered": recovered,
+        },
+        "reportedAt": reaction.reported_at.to_string(),
+        "generatedAt": generated_at.to_string(),
+    })
+}
+
+// ==================== PROVISIONAL COMMITS ====================
+
+/// Create an encounter, returning as soon as it is on the source chain
+#[hdk_extern]
+pub fn create_encounter_provisional(input: CreateEncounterInput) -> ExternResult<ProvisionalCommit> {
+    Ok(ProvisionalCommit::from_record(create_encounter(input)?))
+}
+
+/// Create a lab result, returning as soon as it is on the source chain
+#[hdk_extern]
+pub fn create_lab_result_provisional(input: CreateLabResultInput) -> ExternResult<ProvisionalCommit> {
+    Ok(ProvisionalCommit::from_record(create_lab_result(input)?))
+}
+
+/// Record vital signs, returning as soon as they are on the source chain
+#[hdk_extern]
+pub fn record_vital_signs_provisional(input: RecordVitalSignsInput) -> ExternResult<ProvisionalCommit> {
+    Ok(ProvisionalCommit::from_record(record_vital_signs(input)?))
+}
+
+/// Check whether a provisional write has been published to the DHT
+#[hdk_extern]
+pub fn confirm_entry_published(action_hash: ActionHash) -> ExternResult<PublicationConfirmation> {
+    mycelix_health_shared::confirm_entry_published(action_hash)
+}
+
+// ==================== RECORD INVENTORY ====================
+
+fn inventory_item<T: TryFrom<SerializedBytes, Error = SerializedBytesError>>(
+    record: &Record,
+    summarize: impl FnOnce(T) -> (DataCategory, String, Timestamp, Option<ActionHash>),
+) -> Option<RecordInventoryItem> {
+    let entry = record.entry().to_app_option::<T>().ok()??;
+    let (category, title, recorded_at, encounter_hash) = summarize(entry);
+    Some(RecordInventoryItem {
+        record_hash: record.action_address().clone(),
+        category,
+        title,
+        recorded_at,
+        encounter_hash,
+    })
+}
+
+/// Every record held for the patient, summarized
+///
+/// Patient only and not access-logged, so consent previews can be computed
+/// without writing entries.
+#[hdk_extern]
+pub fn get_patient_record_inventory(patient_hash: ActionHash) -> ExternResult<Vec<RecordInventoryItem>> {
+    require_patient_self(&patient_hash)?;
+
+    let mut items = Vec::new();
+    let encounters = links_to_records(get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToEncounters)?,
+        GetStrategy::default(),
+    )?)?;
+    for record in &encounters {
+        let encounter_hash = record.action_address().clone();
+        items.extend(inventory_item(record, |e: Encounter| (
+            DataCategory::Procedures,
+            format!("{:?} encounter: {}", e.encounter_type, e.chief_complaint),
+            e.start_time,
+            Some(encounter_hash.clone()),
+        )));
+        for diagnosis in links_to_records(get_links(
+            LinkQuery::try_new(encounter_hash.clone(), LinkTypes::EncounterToDiagnoses)?,
+            GetStrategy::default(),
+        )?)? {
+            items.extend(inventory_item(&diagnosis, |d: Diagnosis| (
+                DataCategory::Diagnoses,
+                d.description,
+                d.created_at,
+                d.encounter_hash,
+            )));
+        }
+        for procedure in links_to_records(get_links(
+            LinkQuery::try_new(encounter_hash.clone(), LinkTypes::EncounterToProcedures)?,
+            GetStrategy::default(),
+        )?)? {
+            items.extend(inventory_item(&procedure, |p: ProcedurePerformed| (
+                DataCategory::Procedures,
+                p.description,
+                p.performed_at,
+                Some(p.encounter_hash),
+            )));
+        }
+    }
+
+    for record in links_to_records(get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToLabResults)?,
+        GetStrategy::default(),
+    )?)? {
+        items.extend(inventory_item(&record, |l: LabResult| (
+            DataCategory::LabResults,
+            l.test_name,
+            l.collection_time,
+            l.encounter_hash,
+        )));
+    }
+    for record in links_to_records(get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToImaging)?,
+        GetStrategy::default(),
+    )?)? {
+        items.extend(inventory_item(&record, |i: ImagingStudy| (
+            DataCategory::ImagingStudies,
+            format!("{:?} {}", i.modality, i.body_site),
+            i.study_date,
+            i.encounter_hash,
+        )));
+    }
+    for record in links_to_records(get_links(
+        LinkQuery::try_new(patient_hash.clone(), LinkTypes::PatientToVitals)?,
+        GetStrategy::default(),
+    )?)? {
+        items.extend(inventory_item(&record, |v: VitalSigns| (
+            DataCategory::VitalSigns,
+            "Vital signs".to_string(),
+            v.recorded_at,
+            v.encounter_hash,
+        )));
+    }
+
+    for record in links_to_records(get_links(
+        LinkQuery::try_new(patient_hash, LinkTypes::PatientToVaccineReactions)?,
+        GetStrategy::default(),
+    )?)? {
+        items.extend(inventory_item(&record, |r: VaccineReaction| (
+            DataCategory::Immunizations,
+            format!("Reaction to {}", r.vaccine_name),
+            r.onset_at,
+            None,
+        )));
+    }
+
+    items.sort_by_key(|item| std::cmp::Reverse(item.recorded_at));
+    Ok(items)
+}
+
+// ==================== CHUNKED RECORD FETCH ====================
+
+/// Input for reading one category of a patient's records a chunk at a time
+#[derive(Serialize, Deserialize, Debug)]
+pub struct GetPatientRecordsChunkInput {
+    pub patient_hash: ActionHash,
+    pub category: DataCategory,
+    pub is_emergency: bool,
+    pub emergency_reason: Option<String>,
+    /// Continuation and chunk size
+    #[serde(default)]
+    pub chunk: BatchChunkInput,
+}
+
+/// One chunk of the patient's records in a category, with access control
+///
+/// For patients with more records than fit one call; follow
+/// `continuation` until it is `None`. Each chunk is authorized and logged
+/// on its own. Encounters are not in any category here; read them with
+/// `get_patient_encounters`.
+#[hdk_extern]
+pub fn get_patient_records_chunk(input: GetPatientRecordsChunkInput) -> ExternResult<BatchChunk> {
+    let patient_hash = input.patient_hash.clone();
+    let category = input.category.clone();
+    audited_read(
+        "records::get_patient_records_chunk",
+        input.patient_hash,
+        input.category,
+        input.is_emergency,
+        input.emergency_reason,
+        || {
+            let hashes = indexed_patient_records(&patient_hash)?
+                .into_iter()
+                .filter(|record| indexed_category(record.index).as_ref() == Some(&category))
+                .filter_map(|record| record.link.target.into_action_hash())
+                .collect();
+            let options = BatchGetOptions { skip_deleted: true, class: CallClass::Bulk, ..Default::default() };
+            batch_get_records_chunked(hashes, options, &input.chunk)
+        },
+    )
+}
+
+// ==================== PATIENT RECORD INDEXES ====================
+
+/// Patient indexes that reach the patient's records
+const PATIENT_RECORD_INDEXES: [LinkTypes; 5] = [
+    LinkTypes::PatientToEncounters,
+    LinkTypes::PatientToLabResults,
+    LinkTypes::PatientToImaging,
+    LinkTypes::PatientToVitals,
+    LinkTypes::PatientToVaccineReactions,
+];
+
+/// Encounter indexes walked for each encounter reached
+const ENCOUNTER_RECORD_INDEXES: [LinkTypes; 2] = [
+    LinkTypes::EncounterToDiagnoses,
+    LinkTypes::EncounterToProcedures,
+];
+
+/// A record indexed from the patient and the link that indexes it
+struct IndexedRecord {
+    link: Link,
+    index: LinkTypes,
+}
+
+/// Every indexed record of the patient, an encounter's diagnoses and
+/// procedures ahead of the encounter itself
+fn indexed_patient_records(patient_hash: &ActionHash) -> ExternResult<Vec<IndexedRecord>> {
+    let mut records = Vec::new();
+    for index in PATIENT_RECORD_INDEXES {
+        for link in get_links(LinkQuery::try_new(patient_hash.clone(), index)?, GetStrategy::default())? {
+            if index == LinkTypes::PatientToEncounters {
+                if let Some(encounter_hash) = link.target.clone().into_action_hash() {
+                    for child_index in ENCOUNTER_RECORD_INDEXES {
+                        for child in get_links(LinkQuery::try_new(encounter_hash.clone(), child_index)?, GetStrategy::default())? {
+                            records.push(IndexedRecord { link: child, index: child_index });
+                        }
+                    }
+                }
+            }
+            records.push(IndexedRecord { link, index });
+        }
+    }
+    Ok(records)
+}
+
+/// Category of the records an index holds
+///
+/// Encounters have none: they are read through `get_patient_encounters`,
+/// so a category read never returns them alongside procedures.
+fn indexed_category(index: LinkTypes) -> Option<DataCategory> {
+    match index {
+        LinkTypes::EncounterToDiagnoses => Some(DataCategory::Diagnoses),
+        LinkTypes::EncounterToProcedures => Some(DataCategory::Procedures),
+        LinkTypes::PatientToLabResults => Some(DataCategory::LabResults),
+        LinkTypes::PatientToImaging => Some(DataCategory::ImagingStudies),
+        LinkTypes::PatientToVitals => Some(DataCategory::VitalSigns),
+        LinkTypes::PatientToVaccineReactions => Some(DataCategory::Immunizations),
+        _ => None,
+    }
+}
+
+// ==================== RETENTION SWEEP ====================
+
+/// Category a retention policy ages the record under; encounters age with
+/// procedures, the category they are authorized under
+fn retention_category(index: LinkTypes) -> Option<DataCategory> {
+    match index {
+        LinkTypes::PatientToEncounters => Some(DataCategory::Procedures),
+        other => indexed_category(other),
+    }
+}
+
+fn marked_under(record_hash: &ActionHash, policy_hash: &ActionHash) -> ExternResult<bool> {
+    Ok(get_links(
+        LinkQuery::try_new(record_hash.clone(), LinkTypes::RecordToRetentionMarks)?,
+        GetStrategy::default(),
+    )?
+    .into_iter()
+    .any(|link| link.target.into_action_hash().as_ref() == Some(policy_hash)))
+}
+
+/// Input for sweeping one patient's records against their retention policies
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RetentionSweepInput {
+    pub patient_hash: ActionHash,
+    /// Report what the sweep would do without changing anything
+    pub dry_run: bool,
+}
+
+/// Apply the patient's retention policies to their records (operators only)
+///
+/// Records age from when they were written. A marked record is linked to
+/// the policy that marked it and skipped by later sweeps under that policy;
+/// a tombstoned one is deleted with the link that indexes it. Every applied
+/// decision is written to the access log. Operators run this on a schedule,
+/// like the follow-up task escalation.
+#[hdk_extern]
+pub fn sweep_retention(input: RetentionSweepInput) -> ExternResult<PurgeReport> {
+    require_operator()?;
+
+    let policies = patient_retention(&input.patient_hash)?;
+    let now = sys_time()?;
+    let mut report = PurgeReport {
+        zome: "records".to_string(),
+        patient_hash: input.patient_hash.clone(),
+        swept_at: now,
+        dry_run: input.dry_run,
+        examined: 0,
+        already_marked: 0,
+        decisions: Vec::new(),
+    };
+    if policies.is_empty() {
+        return Ok(report);
+    }
+
+    for candidate in indexed_patient_records(&input.patient_hash)? {
+        let Some(record_hash) = candidate.link.target.clone().into_action_hash() else {
+            continue;
+        };
+        report.examined += 1;
+        let Some(category) = retention_category(candidate.index) else {
+            continue;
+        };
+        let Some(policy) = retention_for(&policies, &category) else {
+            continue;
+        };
+        if !policy.expired(candidate.link.timestamp, now) {
+            continue;
+        }
+        if policy.action == RetentionAction::Mark && marked_under(&record_hash, &policy.policy_hash)? {
+            report.already_marked += 1;
+            continue;
+        }
+
+        let mut decision = PurgeDecision {
+            record_hash: record_hash.clone(),
+            category,
+            recorded_at: candidate.link.timestamp,
+            policy_hash: policy.policy_hash.clone(),
+            action: policy.action,
+            applied: false,
+        };
+        if !input.dry_run {
+            match policy.action {
+                RetentionAction::Mark => {
+                    create_link(record_hash, policy.policy_hash.clone(), LinkTypes::RecordToRetentionMarks, ())?;
+                }
+                RetentionAction::Tombstone => {
+                    delete_link(candidate.link.create_link_hash, GetOptions::default())?;
+                    delete_entry(record_hash)?;
+                }
+            }
+            log_purge_decision(input.patient_hash.clone(), &decision)?;
+            decision.applied = true;
+        }
+        report.decisions.push(decision);
+    }
+
+    Ok(report)
+}
+
+// ============================================================
+// DOMAIN EVENTS
+// ============================================================
+
+/// Domain event handlers this zome registers with the bridge's event bus
+#[hdk_extern]
+pub fn domain_event_subscriptions(_: ()) -> ExternResult<Vec<DomainEventHandler>> {
+    Ok(vec![DomainEventHandler::new("handle_consent_revoked", &[DomainEventKind::ConsentRevoked])])
+}
+
+/// Forget cached authorizations for the patient so record reads recheck consent
+#[hdk_extern]
+pub fn handle_consent_revoked(notice: DomainEventNotice) -> ExternResult<()> {
+    invalidate_authorization_cache(&notice.patient_hash);
+    Ok(())
+}
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_encounter", "CreateEncounterInput", "Record"),
+        ("get_encounter", "GetEncounterInput", "Option<Record>"),
+        ("get_patient_encounters", "GetPatientEncountersInput", "BudgetedPage<Record>"),
+        ("create_diagnosis", "CreateDiagnosisInput", "Record"),
+        ("get_encounter_diagnoses", "GetEncounterDiagnosesInput", "Vec<Record>"),
+        ("create_procedure", "CreateProcedureInput", "Record"),
+        ("create_lab_result", "CreateLabResultInput", "Record"),
+        ("get_patient_lab_results", "GetPatientLabResultsInput", "BudgetedPage<Record>"),
+        ("get_lab_result_explanation", "ExplainLabResultInput", "Option<LabExplanation>"),
+        ("get_patient_lab_results_explained", "GetPatientLabResultsInput", "BudgetedPage<ExplainedLabResult>"),
+        ("acknowledge_critical_result", "AcknowledgeInput", "Record"),
+        ("create_imaging_study", "CreateImagingStudyInput", "Record"),
+        ("get_patient_imaging", "GetPatientImagingInput", "BudgetedPage<Record>"),
+        ("record_vital_signs", "RecordVitalSignsInput", "Record"),
+        ("get_patient_vitals", "GetPatientVitalsInput", "BudgetedPage<Record>"),
+        ("get_critical_results", "()", "Vec<Record>"),
+        ("update_encounter", "UpdateEncounterInput", "Record"),
+        ("update_diagnosis", "UpdateDiagnosisInput", "Record"),
+        ("update_lab_result", "UpdateLabResultInput", "Record"),
+        ("delete_encounter", "DeleteEncounterInput", "ActionHash"),
+        ("get_encounter_history", "GetEncounterHistoryInput", "Vec<Record>"),
+        ("create_lab_order", "CreateLabOrderInput", "Record"),
+        ("cancel_lab_order", "CancelLabOrderInput", "LabOrder"),
+        ("collect_specimen", "CollectSpecimenInput", "Record"),
+        ("record_specimen_custody", "RecordCustodyInput", "Record"),
+        ("get_lab_order", "GetLabOrderInput", "LabOrderDetails"),
+        ("get_patient_lab_orders", "GetPatientLabOrdersInput", "Vec<LabOrderDetails>"),
+        ("get_specimen_by_accession", "GetSpecimenByAccessionInput", "Option<(ActionHash, Specimen)>"),
+        ("request_record_correction", "RequestCorrectionInput", "Record"),
+        ("respond_to_correction_request", "RespondToCorrectionInput", "Record"),
+        ("submit_disagreement_statement", "SubmitDisagreementInput", "Record"),
+        ("get_patient_correction_requests", "ActionHash", "Vec<CorrectionRequestView>"),
+        ("get_pending_correction_requests", "()", "Vec<CorrectionRequestView>"),
+        ("get_record_corrections", "GetRecordCorrectionsInput", "RecordCorrections"),
+        ("create_follow_up_tasks", "CreateFollowUpTasksInput", "Vec<Record>"),
+        ("resolve_follow_up_task", "ResolveFollowUpTaskInput", "FollowUpTask"),
+        ("get_patient_follow_up_tasks", "GetPatientFollowUpTasksInput", "Vec<FollowUpTaskView>"),
+        ("escalate_overdue_follow_up_tasks", "()", "Vec<ActionHash>"),
+        ("report_vaccine_reaction_as_patient", "VaccineReactionReport", "Record"),
+        ("report_vaccine_reaction", "ReportVaccineReactionInput", "Record"),
+        ("get_patient_vaccine_reactions", "GetPatientVaccineReactionsInput", "Vec<Record>"),
+        ("get_vaccine_reaction_allergens", "ActionHash", "Vec<String>"),
+        ("export_vaers_report", "ActionHash", "serde_json::Value"),
+        ("create_encounter_provisional", "CreateEncounterInput", "ProvisionalCommit"),
+        ("create_lab_result_provisional", "CreateLabResultInput", "ProvisionalCommit"),
+        ("record_vital_signs_provisional", "RecordVitalSignsInput", "ProvisionalCommit"),
+        ("confirm_entry_published", "ActionHash", "PublicationConfirmation"),
+        ("get_patient_record_inventory", "ActionHash", "Vec<RecordInventoryItem>"),
+        ("get_patient_records_chunk", "GetPatientRecordsChunkInput", "BatchChunk"),
+        ("sweep_retention", "RetentionSweepInput", "PurgeReport"),
+        ("domain_event_subscriptions", "()", "Vec<DomainEventHandler>"),
+        ("handle_consent_revoked", "DomainEventNotice", "()"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["lab_explanations", "correction_requests", "provisional_commits", "record_inventory", "lab_orders", "follow_up_tasks", "vaccine_reactions", "response_budgets", "authorization_cache", "retention_sweep", "chunked_record_fetch"],
 }
@@ -1,9 +1,13 @@
 //! Medical Records and Health Data Integrity Zome
 //! 
 //! Defines entry types for medical records, encounters, diagnoses,
-//! procedures, lab results, and imaging with HL7 FHIR alignment.
+//! procedures, lab results, and imaging with HL7 FHIR alignment, plus the
+//! follow-up tasks left by care transitions and reactions to vaccines.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 /// Medical encounter/visit record
 #[hdk_entry_helper]
@@ -167,6 +171,9 @@ pub struct LabResult {
     pub is_critical: bool,
     pub acknowledged_by: Option<AgentPubKey>,
     pub acknowledged_at: Option<Timestamp>,
+    /// Lab order this result answers, if it was ordered through the network
+    #[serde(default)]
+    pub order_hash: Option<ActionHash>,
 }
 
 #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
@@ -179,6 +186,89 @@ pub enum LabInterpretation {
     Inconclusive,
 }
 
+/// Lab order lifecycle: ordered → collected → in-lab → resulted
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum LabOrderStatus {
+    Ordered,
+    Collected,
+    InLab,
+    Resulted,
+    Cancelled,
+}
+
+impl LabOrderStatus {
+    /// Orders only move forward; cancellation is possible until resulted
+    pub fn can_advance_to(&self, next: &LabOrderStatus) -> bool {
+        use LabOrderStatus::*;
+        matches!(
+            (self, next),
+            (Ordered, Collected)
+                | (Collected, InLab)
+                | (InLab, Resulted)
+                | (Ordered | Collected | InLab, Cancelled)
+        )
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum LabOrderPriority {
+    Routine,
+    Urgent,
+    Stat,
+}
+
+/// Order for a lab test, placed by a provider before a specimen is collected
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct LabOrder {
+    pub order_id: String,
+    pub patient_hash: ActionHash,
+    pub encounter_hash: Option<ActionHash>,
+    pub ordering_provider: AgentPubKey,
+    /// LOINC code for the ordered test
+    pub loinc_code: String,
+    pub test_name: String,
+    pub priority: LabOrderPriority,
+    pub clinical_indication: Option<String>,
+    pub status: LabOrderStatus,
+    pub ordered_at: Timestamp,
+    pub status_changed_at: Timestamp,
+}
+
+/// Handling step in a specimen's chain of custody
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CustodyAction {
+    Collected,
+    Transferred,
+    ReceivedInLab,
+    Rejected,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct CustodyEvent {
+    pub action: CustodyAction,
+    pub custodian: AgentPubKey,
+    pub location: String,
+    pub at: Timestamp,
+    pub note: Option<String>,
+}
+
+/// Specimen collected for a lab order
+///
+/// The custody list is append-only: each update adds one handling step.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct Specimen {
+    pub specimen_id: String,
+    pub order_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub accession_number: String,
+    pub specimen_type: String,
+    pub collected_by: AgentPubKey,
+    pub collection_time: Timestamp,
+    pub custody: Vec<CustodyEvent>,
+}
+
 /// Imaging study result
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -244,6 +334,146 @@ pub struct VitalSigns {
     pub notes: Option<String>,
 }
 
+/// Days a provider has to act on a correction request (HIPAA 164.526(b)(2))
+pub const CORRECTION_RESPONSE_DAYS: i64 = 60;
+
+/// Kind of record a correction request targets
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CorrectionTargetType {
+    Encounter,
+    Diagnosis,
+    Procedure,
+    LabResult,
+    ImagingStudy,
+    VitalSigns,
+}
+
+/// Patient request to amend a provider-authored record
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CorrectionRequest {
+    pub request_id: String,
+    pub patient_hash: ActionHash,
+    pub target_record: ActionHash,
+    pub target_type: CorrectionTargetType,
+    /// Author of the target record, who must respond
+    pub responsible_provider: AgentPubKey,
+    pub proposed_change: String,
+    pub rationale: String,
+    pub requested_by: AgentPubKey,
+    pub requested_at: Timestamp,
+    pub response_due: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CorrectionStatus {
+    Pending,
+    Accepted,
+    Rejected,
+}
+
+/// Permitted grounds for denying an amendment (HIPAA 164.526(a)(2))
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CorrectionDenialBasis {
+    NotCreatedByProvider,
+    NotPartOfDesignatedRecordSet,
+    NotAvailableForInspection,
+    AccurateAndComplete,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum CorrectionDecision {
+    Accepted,
+    Rejected(CorrectionDenialBasis),
+}
+
+/// Provider's decision on a correction request
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct CorrectionResponse {
+    pub request_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub decision: CorrectionDecision,
+    /// Written explanation shown to the patient
+    pub explanation: String,
+    pub amendment_hash: Option<ActionHash>,
+    pub responded_by: AgentPubKey,
+    pub responded_at: Timestamp,
+}
+
+/// Amendment appended to a record after an accepted correction
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct RecordAmendment {
+    pub request_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub target_record: ActionHash,
+    pub amendment_text: String,
+    pub amended_by: AgentPubKey,
+    pub amended_at: Timestamp,
+}
+
+/// Patient's statement of disagreement attached to a record after a rejected correction
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct DisagreementStatement {
+    pub request_hash: ActionHash,
+    pub patient_hash: ActionHash,
+    pub target_record: ActionHash,
+    pub statement: String,
+    pub submitted_by: AgentPubKey,
+    pub submitted_at: Timestamp,
+}
+
+/// What a discharge follow-up asks the care team to do
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FollowUpTaskKind {
+    ScheduleAppointment,
+    ReconcileMedications,
+    RepeatLab,
+    Other,
+}
+
+/// Follow-up task lifecycle: open → completed or cancelled
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum FollowUpTaskStatus {
+    Open,
+    Completed,
+    Cancelled,
+}
+
+impl FollowUpTaskStatus {
+    pub fn can_advance_to(&self, next: &FollowUpTaskStatus) -> bool {
+        matches!(
+            (self, next),
+            (FollowUpTaskStatus::Open, FollowUpTaskStatus::Completed | FollowUpTaskStatus::Cancelled)
+        )
+    }
+}
+
+/// Task left by a care transition (e.g. a discharge summary) for the
+/// patient's care team
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct FollowUpTask {
+    pub task_id: String,
+    pub patient_hash: ActionHash,
+    /// Care team responsible for the task
+    pub care_team_hash: Option<ActionHash>,
+    pub kind: FollowUpTaskKind,
+    pub description: String,
+    pub due_at: Option<Timestamp>,
+    /// Source system and resource the task came from ("ehr:CarePlan/42")
+    pub source_reference: Option<String>,
+    pub status: FollowUpTaskStatus,
+    pub created_at: Timestamp,
+    pub resolved_at: Option<Timestamp>,
+    pub resolved_by: Option<AgentPubKey>,
+    pub resolution_note: Option<String>,
+    /// When the overdue task was escalated; set at most once
+    pub escalated_at: Option<Timestamp>,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -253,6 +483,14 @@ pub enum EntryTypes {
     LabResult(LabResult),
     ImagingStudy(ImagingStudy),
     VitalSigns(VitalSigns),
+    CorrectionRequest(CorrectionRequest),
+    CorrectionResponse(CorrectionResponse),
+    RecordAmendment(RecordAmendment),
+    DisagreementStatement(DisagreementStatement),
+    LabOrder(LabOrder),
+    Specimen(Specimen),
+    FollowUpTask(FollowUpTask),
+    VaccineReaction(VaccineReaction),
 }
 
 #[hdk_link_types]
@@ -268,27 +506,148 @@ pub enum LinkTypes {
     EncounterUpdates,
     LabResultUpdates,
     CriticalResults,
+    PatientToCorrectionRequests,
+    ProviderToCorrectionRequests,
+    RecordToCorrectionRequests,
+    CorrectionRequestToResponse,
+    RecordToAmendments,
+    RecordToDisagreements,
+    PatientToLabOrders,
+    LabOrderUpdates,
+    LabOrderToSpecimens,
+    SpecimenUpdates,
+    AccessionToSpecimen,
+    LabOrderToResults,
+    PatientToFollowUpTasks,
+    CareTeamToFollowUpTasks,
+    FollowUpTaskUpdates,
+    /// Anchor to tasks not yet completed or cancelled
+    OpenFollowUpTasks,
+    PatientToVaccineReactions,
+    ImmunizationToReactions,
+    /// Record to each retention policy that marked it past retention
+    RecordToRetentionMarks,
+}
+
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("note", FieldLimit::Chars(NOTE_CHARS)),
+        ("findings", FieldLimit::Chars(NOTE_CHARS)),
+        ("impression", FieldLimit::Chars(NOTE_CHARS)),
+        ("explanation", FieldLimit::Chars(NOTE_CHARS)),
+        ("amendment_text", FieldLimit::Chars(NOTE_CHARS)),
+        ("resolution_note", FieldLimit::Chars(NOTE_CHARS)),
+        ("narrative", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
+/// How bad a vaccine reaction was, in VAERS terms
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum VaccineReactionSeverity {
+    Mild,
+    Moderate,
+    Severe,
+    LifeThreatening,
+}
+
+/// What became of the patient after a vaccine reaction
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum VaccineReactionOutcome {
+    Recovered,
+    Recovering,
+    NotRecovered,
+    RecoveredWithSequelae,
+    Hospitalized,
+    Disabled,
+    Died,
+    Unknown,
+}
+
+/// Who reported a vaccine reaction
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum VaccineReactionReporter {
+    Patient,
+    Provider,
+}
+
+/// A coded symptom of a vaccine reaction (MedDRA or SNOMED CT)
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct ReactionSymptom {
+    pub system: String,
+    pub code: String,
+    pub display: String,
+}
+
+/// Adverse reaction to a vaccine, linked to the immunization record
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct VaccineReaction {
+    pub reaction_id: String,
+    pub patient_hash: ActionHash,
+    /// The immunization the reaction followed
+    pub immunization_hash: ActionHash,
+    /// CVX code of the vaccine
+    pub vaccine_code: String,
+    pub vaccine_name: String,
+    pub lot_number: Option<String>,
+    pub manufacturer: Option<String>,
+    pub administered_at: Timestamp,
+    pub onset_at: Timestamp,
+    pub symptoms: Vec<ReactionSymptom>,
+    /// Vaccine components suspected of causing the reaction ("gelatin",
+    /// "polyethylene glycol"), checked like allergies
+    pub suspected_allergens: Vec<String>,
+    pub severity: VaccineReactionSeverity,
+    pub outcome: VaccineReactionOutcome,
+    pub narrative: Option<String>,
+    pub reporter: VaccineReactionReporter,
+    pub reported_by: AgentPubKey,
+    pub reported_at: Timestamp,
 }
 
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::Encounter(e) => validate_encounter(&e),
                 EntryTypes::Diagnosis(d) => validate_diagnosis(&d),
                 EntryTypes::ProcedurePerformed(p) => validate_procedure(&p),
                 EntryTypes::LabResult(l) => validate_lab_result(&l),
                 EntryTypes::ImagingStudy(i) => validate_imaging(&i),
                 EntryTypes::VitalSigns(v) => validate_vitals(&v),
+                EntryTypes::CorrectionRequest(c) => validate_correction_request(&c),
+                EntryTypes::CorrectionResponse(r) => validate_correction_response(&r),
+                EntryTypes::RecordAmendment(a) => validate_record_amendment(&a),
+                EntryTypes::DisagreementStatement(d) => validate_disagreement_statement(&d),
+                EntryTypes::LabOrder(o) => validate_new_lab_order(&o),
+                EntryTypes::Specimen(sp) => validate_new_specimen(&sp),
+                EntryTypes::FollowUpTask(t) => validate_new_follow_up_task(&t),
+                EntryTypes::VaccineReaction(r) => validate_vaccine_reaction(&r, &action.author),
             },
-            OpEntry::UpdateEntry { app_entry, .. } => match app_entry {
+            OpEntry::UpdateEntry { app_entry, action, .. } => match app_entry {
                 EntryTypes::Encounter(e) => validate_encounter(&e),
                 EntryTypes::Diagnosis(d) => validate_diagnosis(&d),
                 EntryTypes::ProcedurePerformed(p) => validate_procedure(&p),
                 EntryTypes::LabResult(l) => validate_lab_result(&l),
                 EntryTypes::ImagingStudy(i) => validate_imaging(&i),
                 EntryTypes::VitalSigns(v) => validate_vitals(&v),
+                EntryTypes::CorrectionRequest(c) => validate_correction_request(&c),
+                EntryTypes::CorrectionResponse(r) => validate_correction_response(&r),
+                EntryTypes::RecordAmendment(a) => validate_record_amendment(&a),
+                EntryTypes::DisagreementStatement(d) => validate_disagreement_statement(&d),
+                EntryTypes::LabOrder(o) => validate_lab_order_update(&o, &action),
+                EntryTypes::Specimen(sp) => validate_specimen_update(&sp, &action),
+                EntryTypes::FollowUpTask(t) => validate_follow_up_task_update(&t, &action),
+                EntryTypes::VaccineReaction(_) => Ok(ValidateCallbackResult::Invalid(
+                    "Vaccine reactions cannot be edited; report a new one".to_string(),
+                )),
             },
             _ => Ok(ValidateCallbackResult::Valid),
         },
@@ -366,3 +725,260 @@ fn validate_vitals(vitals: &VitalSigns) -> ExternResult<ValidateCallbackResult>
     }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_correction_request(request: &CorrectionRequest) -> ExternResult<ValidateCallbackResult> {
+    if request.proposed_change.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Proposed change is required".to_string(),
+        ));
+    }
+    if request.rationale.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Rationale for the correction is required".to_string(),
+        ));
+    }
+    let window = request.response_due.as_micros() - request.requested_at.as_micros();
+    if window <= 0 || window > CORRECTION_RESPONSE_DAYS * 24 * 60 * 60 * 1_000_000 {
+        return Ok(ValidateCallbackResult::Invalid(
+            format!("Response deadline must be within {} days of the request", CORRECTION_RESPONSE_DAYS),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_correction_response(response: &CorrectionResponse) -> ExternResult<ValidateCallbackResult> {
+    match (&response.decision, &response.amendment_hash) {
+        (CorrectionDecision::Accepted, None) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Accepted corrections must reference the amendment".to_string(),
+            ));
+        }
+        (CorrectionDecision::Rejected(_), Some(_)) => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Rejected corrections cannot carry an amendment".to_string(),
+            ));
+        }
+        (CorrectionDecision::Rejected(_), None) if response.explanation.trim().is_empty() => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Rejections must explain the basis for denial".to_string(),
+            ));
+        }
+        _ => {}
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_record_amendment(amendment: &RecordAmendment) -> ExternResult<ValidateCallbackResult> {
+    if amendment.amendment_text.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Amendment text is required".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_disagreement_statement(statement: &DisagreementStatement) -> ExternResult<ValidateCallbackResult> {
+    if statement.statement.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Statement of disagreement cannot be empty".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_lab_order(order: &LabOrder) -> ExternResult<ValidateCallbackResult> {
+    if order.order_id.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Lab order ID is required".to_string(),
+        ));
+    }
+    if order.loinc_code.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "LOINC code is required for lab orders".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_new_lab_order(order: &LabOrder) -> ExternResult<ValidateCallbackResult> {
+    if order.status != LabOrderStatus::Ordered {
+        return Ok(ValidateCallbackResult::Invalid(
+            "New lab orders must start in the Ordered status".to_string(),
+        ));
+    }
+    validate_lab_order(order)
+}
+
+/// Updates move the order one step along its workflow and change nothing else
+fn validate_lab_order_update(order: &LabOrder, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    let previous: LabOrder = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original lab order not found".to_string())))?;
+    let unchanged = LabOrder {
+        status: previous.status.clone(),
+        status_changed_at: previous.status_changed_at,
+        ..order.clone()
+    };
+    if unchanged != previous {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the status of a lab order can change".to_string(),
+        ));
+    }
+    if !previous.status.can_advance_to(&order.status) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Lab order cannot move from {:?} to {:?}",
+            previous.status, order.status
+        )));
+    }
+    validate_lab_order(order)
+}
+
+fn validate_new_specimen(specimen: &Specimen) -> ExternResult<ValidateCallbackResult> {
+    if specimen.accession_number.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Accession number is required for specimens".to_string(),
+        ));
+    }
+    match specimen.custody.as_slice() {
+        [first] if first.action == CustodyAction::Collected && first.custodian == specimen.collected_by => {
+            Ok(ValidateCallbackResult::Valid)
+        }
+        _ => Ok(ValidateCallbackResult::Invalid(
+            "Custody must start with a single collection by the collector".to_string(),
+        )),
+    }
+}
+
+/// Custody is append-only: updates add exactly one event, in time order
+fn validate_specimen_update(specimen: &Specimen, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    let previous: Specimen = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original specimen not found".to_string())))?;
+    let (appended, history) = match specimen.custody.split_last() {
+        Some(split) => split,
+        None => {
+            return Ok(ValidateCallbackResult::Invalid(
+                "Specimen custody cannot be emptied".to_string(),
+            ))
+        }
+    };
+    let unchanged = Specimen {
+        custody: history.to_vec(),
+        ..specimen.clone()
+    };
+    if unchanged != previous {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Specimen updates may only append one custody event".to_string(),
+        ));
+    }
+    if previous.custody.last().is_some_and(|last| appended.at < last.at || last.action == CustodyAction::Rejected) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Custody events must follow the last event and cannot follow a rejection".to_string(),
+        ));
+    }
+    if appended.custodian != action.author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Custody events must be recorded by their custodian".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_follow_up_task(task: &FollowUpTask) -> ExternResult<ValidateCallbackResult> {
+    if task.task_id.is_empty() || task.description.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Follow-up tasks need an ID and a description".to_string(),
+        ));
+    }
+    let resolved = task.status != FollowUpTaskStatus::Open;
+    if resolved != (task.resolved_at.is_some() && task.resolved_by.is_some()) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Resolved tasks, and only those, record who resolved them and when".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_new_follow_up_task(task: &FollowUpTask) -> ExternResult<ValidateCallbackResult> {
+    if task.status != FollowUpTaskStatus::Open || task.escalated_at.is_some() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Follow-up tasks start open and unescalated".to_string(),
+        ));
+    }
+    validate_follow_up_task(task)
+}
+
+fn validate_follow_up_task_update(task: &FollowUpTask, action: &Update) -> ExternResult<ValidateCallbackResult> {
+    let previous: FollowUpTask = must_get_valid_record(action.original_action_address.clone())?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(e))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Original follow-up task not found".to_string())))?;
+    let unchanged = FollowUpTask {
+        status: previous.status.clone(),
+        resolved_at: previous.resolved_at,
+        resolved_by: previous.resolved_by.clone(),
+        resolution_note: previous.resolution_note.clone(),
+        escalated_at: previous.escalated_at,
+        ..task.clone()
+    };
+    if unchanged != previous {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the status, resolution and escalation of a follow-up task can change".to_string(),
+        ));
+    }
+    if task.status == previous.status {
+        let same_resolution = task.resolved_at == previous.resolved_at
+            && task.resolved_by == previous.resolved_by
+            && task.resolution_note == previous.resolution_note;
+        if !same_resolution {
+            return Ok(ValidateCallbackResult::Invalid(
+                "A follow-up task's resolution cannot be rewritten".to_string(),
+            ));
+        }
+    } else if !previous.status.can_advance_to(&task.status) {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Follow-up task cannot move from {:?} to {:?}",
+            previous.status, task.status
+        )));
+    }
+    if previous.escalated_at.is_some() && task.escalated_at != previous.escalated_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "A follow-up task is escalated at most once".to_string(),
+        ));
+    }
+    if task.status != previous.status && task.resolved_by.as_ref() != Some(&action.author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Follow-up tasks must be resolved by the agent recording it".to_string(),
+        ));
+    }
+    validate_follow_up_task(task)
+}
+
+fn validate_vaccine_reaction(reaction: &VaccineReaction, author: &AgentPubKey) -> ExternResult<ValidateCallbackResult> {
+    if &reaction.reported_by != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vaccine reactions must be reported by the agent recording them".to_string(),
+        ));
+    }
+    if reaction.reaction_id.is_empty() || reaction.vaccine_code.trim().is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vaccine reactions need an ID and the vaccine's CVX code".to_string(),
+        ));
+    }
+    if reaction.symptoms.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Vaccine reactions need at least one coded symptom".to_string(),
+        ));
+    }
+    if reaction.onset_at < reaction.administered_at || reaction.reported_at < reaction.onset_at {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Reactions start after the vaccine is given and are reported after they start".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
@@ -0,0 +1,205 @@
+//! Discrete Laplace (Geometric) Mechanism for Differential Privacy
+//!
+//! Adds integer-valued noise to integer queries such as patient counts, so
+//! released values stay whole numbers and avoid the floating-point
+//! artifacts of the continuous Laplace mechanism.
+//!
+//! # Mathematical Foundation
+//!
+//! For an integer query f with sensitivity Δf, the mechanism adds noise Z
+//! from the two-sided geometric distribution with p = e^(-ε/Δf):
+//!
+//! ```text
+//! P[Z = k] = (1 - p) / (1 + p) · p^|k|      for k ∈ ℤ
+//! ```
+//!
+//! # Privacy Guarantee
+//!
+//! Shifting f by up to Δf changes any output's probability by at most a
+//! factor of p^(-Δf) = e^ε, giving (ε, 0)-differential privacy.
+//!
+//! # Sampling
+//!
+//! Z is the difference of two independent geometric variables counting
+//! failures before a success with probability 1 - p, each drawn by
+//! inverse CDF: G = ⌊ln(U) / ln(p)⌋ for U ~ Uniform(0, 1].
+
+use super::rng::{RngError, SecureRng};
+use super::validation::{validate_epsilon, DpValidationError};
+use serde::{Deserialize, Serialize};
+
+/// Error type for discrete Laplace mechanism operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum DiscreteLaplaceError {
+    /// RNG failure
+    Rng(String),
+    /// Invalid parameters
+    Validation(String),
+}
+
+impl std::fmt::Display for DiscreteLaplaceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DiscreteLaplaceError::Rng(msg) => write!(f, "RNG error: {}", msg),
+            DiscreteLaplaceError::Validation(msg) => write!(f, "Validation error: {}", msg),
+        }
+    }
+}
+
+impl From<RngError> for DiscreteLaplaceError {
+    fn from(e: RngError) -> Self {
+        DiscreteLaplaceError::Rng(e.to_string())
+    }
+}
+
+impl From<DpValidationError> for DiscreteLaplaceError {
+    fn from(e: DpValidationError) -> Self {
+        DiscreteLaplaceError::Validation(e.to_string())
+    }
+}
+
+/// Discrete Laplace mechanism for (ε, 0)-differential privacy on integers
+pub struct DiscreteLaplaceMechanism;
+
+impl DiscreteLaplaceMechanism {
+    /// The geometric ratio p = e^(-ε/Δf)
+    pub fn ratio(sensitivity: u64, epsilon: f64) -> Result<f64, DiscreteLaplaceError> {
+        validate_epsilon(epsilon)?;
+        if sensitivity == 0 {
+            return Err(DiscreteLaplaceError::Validation(
+                "Sensitivity must be at least 1".to_string(),
+            ));
+        }
+        Ok((-epsilon / sensitivity as f64).exp())
+    }
+
+    /// Failures before the first success, where each trial fails with
+    /// probability `p`
+    fn sample_geometric(p: f64) -> Result<i64, DiscreteLaplaceError> {
+        // 1 - [0, 1) keeps U in (0, 1] so ln(U) is finite
+        let u = 1.0 - SecureRng::random_f64_uniform()?;
+        Ok((u.ln() / p.ln()).floor() as i64)
+    }
+
+    /// Sample integer noise for the given sensitivity and epsilon
+    pub fn sample(sensitivity: u64, epsilon: f64) -> Result<i64, DiscreteLaplaceError> {
+        let p = Self::ratio(sensitivity, epsilon)?;
+        Ok(Self::sample_geometric(p)? - Self::sample_geometric(p)?)
+    }
+
+    /// Add discrete Laplace noise to an integer value
+    ///
+    /// # Arguments
+    /// * `value` - The true value to protect
+    /// * `sensitivity` - The L1 sensitivity (Δf) of the query
+    /// * `epsilon` - Privacy parameter (lower = more private)
+    pub fn add_noise(value: i64, sensitivity: u64, epsilon: f64) -> Result<i64, DiscreteLaplaceError> {
+        Ok(value.saturating_add(Self::sample(sensitivity, epsilon)?))
+    }
+
+    /// Release a count with sensitivity 1, clamped at zero
+    ///
+    /// Clamping is post-processing and costs no privacy, but biases small
+    /// counts upward.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let patients_with_condition = 37;
+    /// let released = DiscreteLaplaceMechanism::noisy_count(patients_with_condition, 0.5)?;
+    /// ```
+    pub fn noisy_count(count: u64, epsilon: f64) -> Result<u64, DiscreteLaplaceError> {
+        let noisy = Self::add_noise(count.min(i64::MAX as u64) as i64, 1, epsilon)?;
+        Ok(noisy.max(0) as u64)
+    }
+
+    /// Probability that the noise equals `k`
+    pub fn pmf(k: i64, sensitivity: u64, epsilon: f64) -> Result<f64, DiscreteLaplaceError> {
+        let p = Self::ratio(sensitivity, epsilon)?;
+        Ok((1.0 - p) / (1.0 + p) * p.powf(k.unsigned_abs() as f64))
+    }
+
+    /// Variance of the noise: 2p / (1 - p)²
+    pub fn variance(sensitivity: u64, epsilon: f64) -> Result<f64, DiscreteLaplaceError> {
+        let p = Self::ratio(sensitivity, epsilon)?;
+        Ok(2.0 * p / ((1.0 - p) * (1.0 - p)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pmf_sums_to_one() {
+        let total: f64 = (-200..=200)
+            .map(|k| DiscreteLaplaceMechanism::pmf(k, 1, 0.5).unwrap())
+            .sum();
+        assert!((total - 1.0).abs() < 1e-9, "pmf sums to {}", total);
+    }
+
+    #[test]
+    fn test_pmf_privacy_ratio() {
+        // Neighboring outputs differ by exactly e^ε
+        let epsilon = 0.7;
+        for k in 0..10 {
+            let p0 = DiscreteLaplaceMechanism::pmf(k, 1, epsilon).unwrap();
+            let p1 = DiscreteLaplaceMechanism::pmf(k + 1, 1, epsilon).unwrap();
+            assert!((p0 / p1 - epsilon.exp()).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_empirical_frequencies_match_pmf() {
+        let epsilon = 1.0;
+        let n = 20000;
+        let samples: Vec<i64> = (0..n)
+            .map(|_| DiscreteLaplaceMechanism::sample(1, epsilon).unwrap())
+            .collect();
+
+        for k in -2..=2 {
+            let expected = DiscreteLaplaceMechanism::pmf(k, 1, epsilon).unwrap();
+            let observed = samples.iter().filter(|s| **s == k).count() as f64 / n as f64;
+            // Within 5 standard errors of the expected frequency
+            let se = (expected * (1.0 - expected) / n as f64).sqrt();
+            assert!((observed - expected).abs() < 5.0 * se, "k={} observed {} expected {}", k, observed, expected);
+        }
+    }
+
+    #[test]
+    fn test_sample_variance_approximately_correct() {
+        let epsilon = 0.5;
+        let n = 20000;
+        let samples: Vec<f64> = (0..n)
+            .map(|_| DiscreteLaplaceMechanism::sample(2, epsilon).unwrap() as f64)
+            .collect();
+        let mean: f64 = samples.iter().sum::<f64>() / n as f64;
+        let variance: f64 = samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (n - 1) as f64;
+        let expected = DiscreteLaplaceMechanism::variance(2, epsilon).unwrap();
+
+        // Should be within 20% (statistical test)
+        assert!(
+            (variance - expected).abs() / expected < 0.2,
+            "Variance {} too far from expected {}",
+            variance,
+            expected
+        );
+    }
+
+    #[test]
+    fn test_noisy_count_unbiased_away_from_zero() {
+        let n = 5000;
+        let total: u64 = (0..n)
+            .map(|_| DiscreteLaplaceMechanism::noisy_count(1000, 1.0).unwrap())
+            .sum();
+        let mean = total as f64 / n as f64;
+        // SE = sqrt(variance/n) with variance ≈ 1.84 at ε = 1
+        assert!((mean - 1000.0).abs() < 0.2, "Mean {} too far from 1000", mean);
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        assert!(DiscreteLaplaceMechanism::sample(0, 1.0).is_err());
+        assert!(DiscreteLaplaceMechanism::sample(1, 0.0).is_err());
+        assert!(DiscreteLaplaceMechanism::sample(1, -1.0).is_err());
+    }
+}
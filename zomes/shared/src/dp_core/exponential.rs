@@ -0,0 +1,249 @@
+//! Exponential Mechanism for Differential Privacy
+//!
+//! Privately selects one output from a finite set of candidates, such as the
+//! most common diagnosis in a cohort, where adding noise to the answer
+//! itself makes no sense.
+//!
+//! # Mathematical Foundation
+//!
+//! Given a utility function u(D, r) scoring candidate r on dataset D, with
+//! sensitivity Δu (the most any single record can change any score), the
+//! exponential mechanism outputs r with probability:
+//!
+//! ```text
+//! P[M(D) = r] ∝ exp(ε · u(D, r) / (2Δu))
+//! ```
+//!
+//! # Privacy Guarantee
+//!
+//! The exponential mechanism provides (ε, 0)-differential privacy. For
+//! counting utilities, where every score moves in the same direction when a
+//! record is added, the factor of 2 can be dropped (`monotonic`).
+//!
+//! # Sampling
+//!
+//! Scores are shifted by their maximum before exponentiating so large
+//! utilities cannot overflow, then one candidate is drawn by inverse CDF.
+
+use super::rng::{RngError, SecureRng};
+use super::validation::{validate_epsilon, validate_sensitivity, DpValidationError};
+use serde::{Deserialize, Serialize};
+
+/// Error type for exponential mechanism operations
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ExponentialError {
+    /// RNG failure
+    Rng(String),
+    /// Invalid parameters
+    Validation(String),
+    /// Nothing to select from
+    NoCandidates,
+}
+
+impl std::fmt::Display for ExponentialError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ExponentialError::Rng(msg) => write!(f, "RNG error: {}", msg),
+            ExponentialError::Validation(msg) => write!(f, "Validation error: {}", msg),
+            ExponentialError::NoCandidates => write!(f, "No candidates to select from"),
+        }
+    }
+}
+
+impl From<RngError> for ExponentialError {
+    fn from(e: RngError) -> Self {
+        ExponentialError::Rng(e.to_string())
+    }
+}
+
+impl From<DpValidationError> for ExponentialError {
+    fn from(e: DpValidationError) -> Self {
+        ExponentialError::Validation(e.to_string())
+    }
+}
+
+/// Calibration of the exponential mechanism
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub struct ExponentialParams {
+    /// Privacy parameter
+    pub epsilon: f64,
+    /// Sensitivity Δu of the utility function
+    pub sensitivity: f64,
+    /// Whether every utility moves the same way when a record changes,
+    /// which halves the noise needed
+    pub monotonic: bool,
+}
+
+impl ExponentialParams {
+    /// Parameters for a counting utility (sensitivity 1, monotonic)
+    pub fn counting(epsilon: f64) -> Self {
+        Self { epsilon, sensitivity: 1.0, monotonic: true }
+    }
+
+    fn validate(&self) -> Result<(), ExponentialError> {
+        validate_epsilon(self.epsilon)?;
+        validate_sensitivity(self.sensitivity)?;
+        Ok(())
+    }
+
+    /// Multiplier applied to each utility before exponentiating
+    fn scale(&self) -> f64 {
+        let divisor = if self.monotonic { 1.0 } else { 2.0 };
+        self.epsilon / (divisor * self.sensitivity)
+    }
+}
+
+/// Exponential mechanism for private selection
+pub struct ExponentialMechanism;
+
+impl ExponentialMechanism {
+    /// Selection probability of each candidate given its utility
+    ///
+    /// # Arguments
+    /// * `utilities` - Utility score of each candidate
+    /// * `params` - Privacy calibration
+    ///
+    /// # Returns
+    /// Probabilities in the same order, summing to 1
+    pub fn probabilities(utilities: &[f64], params: &ExponentialParams) -> Result<Vec<f64>, ExponentialError> {
+        params.validate()?;
+        if utilities.is_empty() {
+            return Err(ExponentialError::NoCandidates);
+        }
+        if utilities.iter().any(|u| !u.is_finite()) {
+            return Err(ExponentialError::Validation("Utilities must be finite".to_string()));
+        }
+
+        let scale = params.scale();
+        let max = utilities.iter().cloned().fold(f64::NEG_INFINITY, f64::max);
+        let weights: Vec<f64> = utilities.iter().map(|u| (scale * (u - max)).exp()).collect();
+        let total: f64 = weights.iter().sum();
+        Ok(weights.into_iter().map(|w| w / total).collect())
+    }
+
+    /// Select the index of one candidate given each candidate's utility
+    pub fn select_index(utilities: &[f64], params: &ExponentialParams) -> Result<usize, ExponentialError> {
+        let probabilities = Self::probabilities(utilities, params)?;
+        let u = SecureRng::random_f64_uniform()?;
+
+        let mut cumulative = 0.0;
+        for (index, p) in probabilities.iter().enumerate() {
+            cumulative += p;
+            if u < cumulative {
+                return Ok(index);
+            }
+        }
+        // Rounding can leave the total a hair under 1
+        Ok(probabilities.len() - 1)
+    }
+
+    /// Select one candidate, scoring each with `utility`
+    ///
+    /// # Example
+    /// ```ignore
+    /// // Pick a clinic to highlight by patient satisfaction (scores in [0, 5])
+    /// let params = ExponentialParams { epsilon: 0.5, sensitivity: 5.0, monotonic: false };
+    /// let clinic = ExponentialMechanism::select(&clinics, |c| c.mean_score, &params)?;
+    /// ```
+    pub fn select<'a, T, F>(candidates: &'a [T], utility: F, params: &ExponentialParams) -> Result<&'a T, ExponentialError>
+    where
+        F: Fn(&T) -> f64,
+    {
+        let utilities: Vec<f64> = candidates.iter().map(utility).collect();
+        let index = Self::select_index(&utilities, params)?;
+        Ok(&candidates[index])
+    }
+
+    /// Privately select the most frequent category from per-category counts
+    ///
+    /// Each record contributes to one count, so the utility is the count
+    /// itself with sensitivity 1.
+    ///
+    /// # Example
+    /// ```ignore
+    /// let counts = vec![("E11", 412), ("I10", 530), ("J45", 97)];
+    /// let top_diagnosis = ExponentialMechanism::most_frequent(&counts, 0.1)?;
+    /// ```
+    pub fn most_frequent<K: Clone>(counts: &[(K, u64)], epsilon: f64) -> Result<K, ExponentialError> {
+        let selected = Self::select(counts, |(_, count)| *count as f64, &ExponentialParams::counting(epsilon))?;
+        Ok(selected.0.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_probabilities_match_closed_form() {
+        let params = ExponentialParams { epsilon: 1.0, sensitivity: 1.0, monotonic: false };
+        let probabilities = ExponentialMechanism::probabilities(&[0.0, 2.0], &params).unwrap();
+
+        // P ∝ exp(u/2): exp(0) : exp(1)
+        let e = 1.0_f64.exp();
+        assert!((probabilities[0] - 1.0 / (1.0 + e)).abs() < 1e-12);
+        assert!((probabilities[1] - e / (1.0 + e)).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_probabilities_stable_for_large_utilities() {
+        let params = ExponentialParams::counting(1.0);
+        let probabilities = ExponentialMechanism::probabilities(&[1e6, 1e6 - 1.0], &params).unwrap();
+        assert!(probabilities.iter().all(|p| p.is_finite()));
+        assert!((probabilities.iter().sum::<f64>() - 1.0).abs() < 1e-12);
+        assert!(probabilities[0] > probabilities[1]);
+    }
+
+    #[test]
+    fn test_privacy_ratio_bounded() {
+        // Neighboring datasets shift one count by the sensitivity; no
+        // output's probability may change by more than e^ε
+        let epsilon = 0.5;
+        let params = ExponentialParams::counting(epsilon);
+        let before = ExponentialMechanism::probabilities(&[10.0, 12.0, 7.0], &params).unwrap();
+        let after = ExponentialMechanism::probabilities(&[11.0, 12.0, 7.0], &params).unwrap();
+        for (p, q) in before.iter().zip(&after) {
+            let ratio = (p / q).max(q / p);
+            assert!(ratio <= epsilon.exp() + 1e-12, "ratio {} exceeds e^ε", ratio);
+        }
+    }
+
+    #[test]
+    fn test_empirical_frequencies_match_distribution() {
+        let params = ExponentialParams::counting(1.0);
+        let utilities = [0.0, 1.0, 2.0];
+        let expected = ExponentialMechanism::probabilities(&utilities, &params).unwrap();
+
+        let n = 20000;
+        let mut counts = [0usize; 3];
+        for _ in 0..n {
+            counts[ExponentialMechanism::select_index(&utilities, &params).unwrap()] += 1;
+        }
+        for (count, p) in counts.iter().zip(&expected) {
+            let observed = *count as f64 / n as f64;
+            // Within 5 standard errors of the expected frequency
+            let se = (p * (1.0 - p) / n as f64).sqrt();
+            assert!((observed - p).abs() < 5.0 * se, "observed {} expected {}", observed, p);
+        }
+    }
+
+    #[test]
+    fn test_most_frequent_prefers_clear_winner() {
+        let counts = vec![("E11", 10), ("I10", 500), ("J45", 12)];
+        let picks: Vec<&str> = (0..50)
+            .map(|_| ExponentialMechanism::most_frequent(&counts, 1.0).unwrap())
+            .collect();
+        assert!(picks.iter().all(|pick| *pick == "I10"));
+    }
+
+    #[test]
+    fn test_invalid_inputs_rejected() {
+        let params = ExponentialParams::counting(1.0);
+        assert!(matches!(
+            ExponentialMechanism::select_index(&[], &params),
+            Err(ExponentialError::NoCandidates)
+        ));
+        assert!(ExponentialMechanism::probabilities(&[f64::NAN], &params).is_err());
+        assert!(ExponentialMechanism::probabilities(&[1.0], &ExponentialParams::counting(0.0)).is_err());
+    }
+}
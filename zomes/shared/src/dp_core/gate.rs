@@ -0,0 +1,82 @@
+//! Privacy Budget Gate
+//!
+//! Cross-zome protocol every aggregate query over patient data goes
+//! through. Budgets live in the consent zome as per-patient Rényi DP
+//! ledgers; this gate reserves the query's cost on every patient it reads,
+//! runs the query, then commits the spend (or releases it on failure).
+//!
+//! # Protocol
+//!
+//! ```text
+//! caller zome                      consent zome
+//!     │ reserve_cohort_budget(BudgetSpend) ──▶ hold cost on each ledger
+//!     │ ◀── CohortReservation                  (any exhausted → hard fail)
+//!     │ run query
+//!     │ commit_cohort_budget / release_cohort_budget ──▶
+//! ```
+//!
+//! A patient with no budget for the dataset counts as exhausted: aggregates
+//! only run over patients who have opted in with a limit.
+//!
+//! No zome in the DNA computes cross-patient aggregates yet (the dividends,
+//! twin and mental health summaries are per patient and archived), so
+//! nothing calls the gate and the consent zome does not advertise it as a
+//! feature. Aggregate queries added later go through `spend_budget`.
+
+use hdk::prelude::*;
+use super::rdp::PrivacyCost;
+use crate::reads::CallClass;
+use crate::resilience::resilient_call;
+
+/// Zome holding the privacy budget ledgers
+pub const BUDGET_ZOME: &str = "consent";
+
+/// Privacy cost an aggregate query will charge each patient it reads
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BudgetSpend {
+    pub patient_hashes: Vec<ActionHash>,
+    pub dataset_id: String,
+    pub cost: PrivacyCost,
+    pub purpose: String,
+}
+
+/// Reservations held for one query, as (patient, reservation id)
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct CohortReservation {
+    pub dataset_id: String,
+    pub reservations: Vec<(ActionHash, u64)>,
+}
+
+/// Run `query` only if every patient in `spend` has budget for it
+///
+/// Fails before the query runs when any patient's budget is exhausted or
+/// missing. The spend is committed when the query succeeds and released
+/// when it fails.
+///
+/// # Example
+/// ```ignore
+/// let spend = BudgetSpend {
+///     patient_hashes: cohort.clone(),
+///     dataset_id: "mental_health_cohort".to_string(),
+///     cost: PrivacyCost::Laplace { epsilon: 0.1 },
+///     purpose: "PHQ-9 mean by age band".to_string(),
+/// };
+/// let stats = spend_budget(spend, || cohort_stats(&cohort, 0.1))?;
+/// ```
+pub fn spend_budget<T>(spend: BudgetSpend, query: impl FnOnce() -> ExternResult<T>) -> ExternResult<T> {
+    let reservation: CohortReservation = resilient_call(BUDGET_ZOME, "reserve_cohort_budget", &spend, CallClass::Authorization)
+        .map_err(|failure| failure.into_wasm_error("reserving privacy budget"))?;
+
+    match query() {
+        Ok(result) => {
+            resilient_call::<_, ()>(BUDGET_ZOME, "commit_cohort_budget", &reservation, CallClass::Audit)
+                .map_err(|failure| failure.into_wasm_error("committing privacy budget"))?;
+            Ok(result)
+        }
+        Err(e) => {
+            // Unreleased holds lapse on their own, so a failed release is not fatal
+            let _ = resilient_call::<_, ()>(BUDGET_ZOME, "release_cohort_budget", &reservation, CallClass::Audit);
+            Err(e)
+        }
+    }
+}
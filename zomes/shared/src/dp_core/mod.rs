@@ -4,7 +4,11 @@
 //! - Cryptographically secure random number generation
 //! - Laplace mechanism for (ε, 0)-DP
 //! - Gaussian mechanism for (ε, δ)-DP
+//! - Discrete Laplace mechanism for integer counts
+//! - Exponential mechanism for private selection
 //! - Privacy budget accounting with composition theorems
+//! - Rényi DP accounting with conversion to (ε, δ)
+//! - A cross-zome budget gate for aggregate queries
 //! - Input validation for DP parameters
 //!
 //! # Mathematical Guarantees
@@ -45,12 +49,20 @@
 pub mod rng;
 pub mod laplace;
 pub mod gaussian;
+pub mod discrete_laplace;
+pub mod exponential;
 pub mod budget;
+pub mod rdp;
+pub mod gate;
 pub mod validation;
 
 // Re-export commonly used items
 pub use rng::SecureRng;
 pub use laplace::LaplaceMechanism;
 pub use gaussian::GaussianMechanism;
+pub use discrete_laplace::DiscreteLaplaceMechanism;
+pub use exponential::{ExponentialMechanism, ExponentialParams};
 pub use budget::{BudgetAccount, BudgetError, CompositionTheorem};
+pub use rdp::{PrivacyCost, RdpAccountant};
+pub use gate::{spend_budget, BudgetSpend, CohortReservation};
 pub use validation::{DpValidationError, validate_epsilon, validate_delta, validate_sensitivity};
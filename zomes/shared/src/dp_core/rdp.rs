@@ -0,0 +1,262 @@
+//! Rényi Differential Privacy (RDP) Accounting
+//!
+//! Tracks privacy loss as a curve of Rényi divergences over a fixed set of
+//! orders α, which composes by simple addition and converts to a much
+//! tighter (ε, δ) guarantee than basic or advanced composition.
+//!
+//! # Rényi DP (Mironov, 2017)
+//!
+//! A mechanism is (α, ρ)-RDP if the Rényi divergence of order α between its
+//! outputs on neighboring datasets is at most ρ.
+//!
+//! - Gaussian mechanism with noise multiplier z = σ/Δ: ρ(α) = α / (2z²)
+//! - Laplace mechanism with ε = Δ/b:
+//!   ρ(α) = 1/(α-1) · ln( α/(2α-1) · e^((α-1)ε) + (α-1)/(2α-1) · e^(-αε) )
+//! - Composition: ρ_total(α) = Σ ρ_i(α)
+//! - Conversion: (α, ρ)-RDP implies (ρ + ln(1/δ)/(α-1), δ)-DP, minimized
+//!   over the tracked orders
+
+use serde::{Deserialize, Serialize};
+use super::budget::BudgetError;
+use super::validation::{validate_delta, validate_epsilon};
+
+/// Orders tracked by default, dense where the optimum usually falls
+pub const DEFAULT_ORDERS: [f64; 14] = [
+    1.25, 1.5, 1.75, 2.0, 2.5, 3.0, 4.0, 5.0, 6.0, 8.0, 12.0, 16.0, 32.0, 64.0,
+];
+
+/// Privacy cost of one query, as declared before it runs
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum PrivacyCost {
+    /// Laplace mechanism calibrated for ε-DP
+    Laplace { epsilon: f64 },
+    /// Gaussian mechanism calibrated for (ε, δ)-DP with
+    /// σ = Δ · √(2 ln(1.25/δ)) / ε, as in `GaussianMechanism`
+    Gaussian { epsilon: f64, delta: f64 },
+    /// Gaussian mechanism with an explicit noise multiplier σ/Δ
+    GaussianNoise { noise_multiplier: f64 },
+}
+
+impl PrivacyCost {
+    /// The cost's RDP curve at `orders`
+    pub fn rdp_curve(&self, orders: &[f64]) -> Result<Vec<f64>, BudgetError> {
+        match *self {
+            PrivacyCost::Laplace { epsilon } => {
+                validate_epsilon(epsilon)?;
+                Ok(orders.iter().map(|&alpha| laplace_rdp(epsilon, alpha)).collect())
+            }
+            PrivacyCost::Gaussian { epsilon, delta } => {
+                validate_epsilon(epsilon)?;
+                validate_delta(delta)?;
+                if delta == 0.0 {
+                    return Err(BudgetError::InvalidParameter(
+                        "Gaussian mechanism needs a positive delta".to_string(),
+                    ));
+                }
+                let noise_multiplier = (2.0 * (1.25 / delta).ln()).sqrt() / epsilon;
+                Ok(gaussian_rdp(noise_multiplier, orders))
+            }
+            PrivacyCost::GaussianNoise { noise_multiplier } => {
+                if !noise_multiplier.is_finite() || noise_multiplier <= 0.0 {
+                    return Err(BudgetError::InvalidParameter(
+                        "Noise multiplier must be positive".to_string(),
+                    ));
+                }
+                Ok(gaussian_rdp(noise_multiplier, orders))
+            }
+        }
+    }
+}
+
+/// RDP of the Gaussian mechanism with noise multiplier z at each order
+pub fn gaussian_rdp(noise_multiplier: f64, orders: &[f64]) -> Vec<f64> {
+    let z2 = noise_multiplier * noise_multiplier;
+    orders.iter().map(|&alpha| alpha / (2.0 * z2)).collect()
+}
+
+/// RDP of the ε-DP Laplace mechanism at order α, never above ε
+pub fn laplace_rdp(epsilon: f64, alpha: f64) -> f64 {
+    // Evaluated as a log-sum-exp so large α·ε cannot overflow
+    let a = (alpha / (2.0 * alpha - 1.0)).ln() + (alpha - 1.0) * epsilon;
+    let b = ((alpha - 1.0) / (2.0 * alpha - 1.0)).ln() - alpha * epsilon;
+    let (hi, lo) = if a >= b { (a, b) } else { (b, a) };
+    let log_sum = hi + (lo - hi).exp().ln_1p();
+    (log_sum / (alpha - 1.0)).clamp(0.0, epsilon)
+}
+
+/// Running RDP total over a fixed set of orders
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RdpAccountant {
+    orders: Vec<f64>,
+    rdp: Vec<f64>,
+}
+
+impl Default for RdpAccountant {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RdpAccountant {
+    /// A fresh accountant over `DEFAULT_ORDERS`
+    pub fn new() -> Self {
+        Self {
+            orders: DEFAULT_ORDERS.to_vec(),
+            rdp: vec![0.0; DEFAULT_ORDERS.len()],
+        }
+    }
+
+    /// Restore an accountant from persisted orders and totals
+    pub fn from_parts(orders: Vec<f64>, rdp: Vec<f64>) -> Result<Self, BudgetError> {
+        if orders.is_empty() || orders.len() != rdp.len() {
+            return Err(BudgetError::InvalidParameter(
+                "RDP totals must match the tracked orders".to_string(),
+            ));
+        }
+        if orders.iter().any(|alpha| !alpha.is_finite() || *alpha <= 1.0) {
+            return Err(BudgetError::InvalidParameter(
+                "RDP orders must be finite and greater than 1".to_string(),
+            ));
+        }
+        if rdp.iter().any(|rho| !rho.is_finite() || *rho < 0.0) {
+            return Err(BudgetError::InvalidParameter(
+                "RDP totals must be finite and non-negative".to_string(),
+            ));
+        }
+        Ok(Self { orders, rdp })
+    }
+
+    pub fn orders(&self) -> &[f64] {
+        &self.orders
+    }
+
+    pub fn rdp(&self) -> &[f64] {
+        &self.rdp
+    }
+
+    /// Add a curve evaluated at this accountant's orders
+    pub fn compose_curve(&mut self, curve: &[f64]) -> Result<(), BudgetError> {
+        if curve.len() != self.orders.len() {
+            return Err(BudgetError::InvalidParameter(
+                "RDP curve does not match the tracked orders".to_string(),
+            ));
+        }
+        for (total, rho) in self.rdp.iter_mut().zip(curve) {
+            *total += rho;
+        }
+        Ok(())
+    }
+
+    /// Add `steps` runs of a query with the given cost
+    pub fn compose(&mut self, cost: &PrivacyCost, steps: u32) -> Result<(), BudgetError> {
+        let curve: Vec<f64> = cost
+            .rdp_curve(&self.orders)?
+            .into_iter()
+            .map(|rho| rho * steps as f64)
+            .collect();
+        self.compose_curve(&curve)
+    }
+
+    /// Tightest ε over the tracked orders for the given δ, with the order
+    /// that achieves it
+    ///
+    /// The ln(1/δ)/(α-1) term means even one tiny query costs a noticeable
+    /// ε; size limits with that floor in mind.
+    pub fn to_epsilon(&self, delta: f64) -> Result<(f64, f64), BudgetError> {
+        validate_delta(delta)?;
+        if delta == 0.0 {
+            return Err(BudgetError::InvalidParameter(
+                "Converting RDP to (ε, δ) needs a positive delta".to_string(),
+            ));
+        }
+        // Nothing has run, so nothing has leaked
+        if self.rdp.iter().all(|rho| *rho == 0.0) {
+            return Ok((0.0, self.orders.last().copied().unwrap_or_default()));
+        }
+        let log_inv_delta = (1.0 / delta).ln();
+        self.orders
+            .iter()
+            .zip(&self.rdp)
+            .map(|(&alpha, &rho)| (rho + log_inv_delta / (alpha - 1.0), alpha))
+            .min_by(|a, b| a.0.total_cmp(&b.0))
+            .ok_or(BudgetError::OperationFailed("No RDP orders tracked".to_string()))
+    }
+
+    /// ε at `delta` if `cost` were added, without recording it
+    pub fn epsilon_with(&self, cost: &PrivacyCost, delta: f64) -> Result<f64, BudgetError> {
+        let mut trial = self.clone();
+        trial.compose(cost, 1)?;
+        Ok(trial.to_epsilon(delta)?.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_gaussian_rdp_linear_in_order() {
+        let curve = gaussian_rdp(2.0, &[2.0, 4.0]);
+        assert!((curve[0] - 0.25).abs() < 1e-12);
+        assert!((curve[1] - 0.5).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_laplace_rdp_bounded_by_epsilon() {
+        for &alpha in DEFAULT_ORDERS.iter() {
+            let rho = laplace_rdp(0.5, alpha);
+            assert!(rho > 0.0 && rho <= 0.5, "order {} gave {}", alpha, rho);
+        }
+        // Divergence grows with the order
+        assert!(laplace_rdp(0.5, 2.0) < laplace_rdp(0.5, 16.0));
+        // Large α·ε stays finite
+        assert!(laplace_rdp(5.0, 64.0).is_finite());
+    }
+
+    #[test]
+    fn test_composition_is_additive() {
+        let cost = PrivacyCost::GaussianNoise { noise_multiplier: 1.5 };
+        let mut once = RdpAccountant::new();
+        once.compose(&cost, 10).unwrap();
+        let mut stepwise = RdpAccountant::new();
+        for _ in 0..10 {
+            stepwise.compose(&cost, 1).unwrap();
+        }
+        for (a, b) in once.rdp().iter().zip(stepwise.rdp()) {
+            assert!((a - b).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn test_rdp_tighter_than_basic_composition() {
+        let cost = PrivacyCost::Gaussian { epsilon: 0.1, delta: 1e-6 };
+        let mut accountant = RdpAccountant::new();
+        accountant.compose(&cost, 100).unwrap();
+        let (epsilon, order) = accountant.to_epsilon(1e-5).unwrap();
+        assert!(epsilon < 100.0 * 0.1, "RDP gave ε={}", epsilon);
+        assert!(DEFAULT_ORDERS.contains(&order));
+    }
+
+    #[test]
+    fn test_epsilon_is_monotone() {
+        let mut accountant = RdpAccountant::new();
+        let mut previous = accountant.to_epsilon(1e-6).unwrap().0;
+        assert_eq!(previous, 0.0);
+        for _ in 0..20 {
+            accountant.compose(&PrivacyCost::Laplace { epsilon: 0.05 }, 1).unwrap();
+            let current = accountant.to_epsilon(1e-6).unwrap().0;
+            assert!(current >= previous);
+            previous = current;
+        }
+    }
+
+    #[test]
+    fn test_invalid_parameters_rejected() {
+        assert!(RdpAccountant::from_parts(vec![1.0], vec![0.0]).is_err());
+        assert!(RdpAccountant::from_parts(vec![2.0, 4.0], vec![0.0]).is_err());
+        assert!(RdpAccountant::new().to_epsilon(0.0).is_err());
+        let mut accountant = RdpAccountant::new();
+        assert!(accountant.compose(&PrivacyCost::Gaussian { epsilon: 1.0, delta: 0.0 }, 1).is_err());
+        assert!(accountant.compose(&PrivacyCost::GaussianNoise { noise_multiplier: 0.0 }, 1).is_err());
+    }
+}
@@ -5,6 +5,31 @@
 //! - Audit logging
 //! - Common types and utilities
 //! - Anchor management
+//! - At-rest compression of large payload fields
+//! - Patient-friendly lab result explanations
+//! - Medication adherence from dispense records
+//! - FHIR extension preservation and US Core demographics
+//! - Condition onsets inferred from related results and encounters
+//! - Concurrent-safe counters
+//! - Signed attestations over canonically encoded payloads
+//! - Reference counting for link-reached entries
+//! - Operator statistics and storage reports
+//! - Provisional commits for optimistic UIs
+//! - Idempotency keys for retried creates
+//! - Per-call-class read strategies with failover
+//! - Byte budgets that truncate large list responses with a continuation token
+//! - Cross-zome calls retried with jittered backoff
+//! - Per-persona rate limits on authorized data access
+//! - Per-extern performance metrics flushed to the consent zome
+//! - Jurisdiction tags and the data uses a jurisdiction prohibits
+//! - Per-jurisdiction retention policies and purge decisions
+//! - Operator-configurable category sensitivity
+//! - Site-specific validation rules evaluated before commit
+//! - Paced batch writes split across calls
+//! - Webhook notifications through the bridge zome
+//! - Typed domain events dispatched to subscribing zomes
+//! - Passphrase-encrypted source chain backups
+//! - Sagas that compensate partially applied multi-step writes
 //! - Differential privacy primitives (dp_core)
 
 use hdk::prelude::*;
@@ -16,7 +41,35 @@ pub use audit::*;
 pub use types::*;
 pub use anchors::*;
 pub use validation::*;
+pub use entry_limits::*;
 pub use batch::*;
+pub use manifest::*;
+pub use compression::*;
+pub use lab_explanations::*;
+pub use adherence::*;
+pub use counters::*;
+pub use link_tags::*;
+pub use attestations::*;
+pub use references::*;
+pub use statistics::*;
+pub use provisional::*;
+pub use idempotency::*;
+pub use reads::*;
+pub use response_budget::*;
+pub use backup::*;
+pub use saga::*;
+pub use resilience::*;
+pub use rate_limits::*;
+pub use performance::*;
+pub use residency::*;
+pub use retention::*;
+pub use sensitivity::*;
+pub use site_rules::*;
+pub use webhooks::*;
+pub use events::*;
+pub use localization::*;
+pub use fhir_extensions::*;
+pub use condition_onsets::*;
 
 /// Formal Differential Privacy module
 ///
@@ -30,6 +83,7 @@ pub mod dp_core;
 /// Access control module - enforces consent-based authorization
 pub mod access_control {
     use super::*;
+    use std::cell::RefCell;
 
     /// Result of an authorization check
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -44,6 +98,49 @@ pub mod access_control {
         pub permissions: Vec<Permission>,
         /// Whether this was an emergency override
         pub emergency_override: bool,
+        /// How well the requestor's identity is established
+        #[serde(default)]
+        pub assurance_level: IdentityAssuranceLevel,
+        /// The consent, delegation or care team that decided the request
+        #[serde(default)]
+        pub decisive_policy: Option<PolicyStatement>,
+    }
+
+    /// How well an accessor's identity is established, weakest first
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum IdentityAssuranceLevel {
+        /// Only the agent vouches for who they are
+        #[default]
+        SelfAsserted,
+        /// An organization other than the agent countersigned the identity
+        OrganizationCountersigned,
+        /// Holds an active license verified by someone other than the holder
+        LicenseVerified,
+    }
+
+    /// Attestations that back an agent's identity
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct IdentityAttestations {
+        /// Published a provider profile
+        pub has_profile: bool,
+        /// Registered as a service or attested by an organization
+        pub organization_countersigned: bool,
+        /// Holds an active, independently verified license
+        pub license_verified: bool,
+    }
+
+    impl IdentityAttestations {
+        /// The strongest level the attestations support; a license only
+        /// counts when it is attached to a profile
+        pub fn assurance_level(&self) -> IdentityAssuranceLevel {
+            if self.has_profile && self.license_verified {
+                IdentityAssuranceLevel::LicenseVerified
+            } else if self.organization_countersigned {
+                IdentityAssuranceLevel::OrganizationCountersigned
+            } else {
+                IdentityAssuranceLevel::SelfAsserted
+            }
+        }
     }
 
     /// Permission types for data access
@@ -99,6 +196,155 @@ pub mod access_control {
         }
     }
 
+    /// Kind of grant a policy statement comes from
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum PolicySource {
+        Consent,
+        Delegation,
+        CareTeam,
+    }
+
+    /// What a policy says about a request
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum PolicyEffect {
+        Permit,
+        Deny,
+        /// The policy covers the requestor but does not decide the request
+        NotApplicable,
+    }
+
+    /// One grant's verdict on a request, with the reason it gives
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct PolicyStatement {
+        pub source: PolicySource,
+        pub policy_hash: ActionHash,
+        pub effect: PolicyEffect,
+        pub explanation: String,
+    }
+
+    /// The combined verdict and the statement that decided it
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct PolicyDecision {
+        pub effect: PolicyEffect,
+        /// `None` when no policy applies
+        pub decisive: Option<PolicyStatement>,
+        pub explanation: String,
+        /// Every statement that was combined, in the order given
+        pub statements: Vec<PolicyStatement>,
+    }
+
+    impl PolicyDecision {
+        pub fn permitted(&self) -> bool {
+            self.effect == PolicyEffect::Permit
+        }
+    }
+
+    /// Combine statements from consents, delegations and care teams,
+    /// letting any deny override every permit
+    ///
+    /// The first deny decides; failing that the first permit does. With
+    /// neither, the first not-applicable statement explains why nothing
+    /// granted the request.
+    pub fn combine_deny_overrides(statements: Vec<PolicyStatement>) -> PolicyDecision {
+        let first = |effect: PolicyEffect| statements.iter().find(|s| s.effect == effect).cloned();
+        let permits = statements.iter().filter(|s| s.effect == PolicyEffect::Permit).count();
+        let (effect, decisive, explanation) = if let Some(deny) = first(PolicyEffect::Deny) {
+            let overridden = match permits {
+                0 => String::new(),
+                1 => "; overrides 1 permit".to_string(),
+                n => format!("; overrides {} permits", n),
+            };
+            let explanation = format!("Denied by {:?} {}: {}{}", deny.source, deny.policy_hash, deny.explanation, overridden);
+            (PolicyEffect::Deny, Some(deny), explanation)
+        } else if let Some(permit) = first(PolicyEffect::Permit) {
+            let explanation = format!("Permitted by {:?} {}: {}", permit.source, permit.policy_hash, permit.explanation);
+            (PolicyEffect::Permit, Some(permit), explanation)
+        } else {
+            let explanation = first(PolicyEffect::NotApplicable)
+                .map_or_else(|| "No applicable policy".to_string(), |s| s.explanation);
+            (PolicyEffect::NotApplicable, None, explanation)
+        };
+        PolicyDecision { effect, decisive, explanation, statements }
+    }
+
+    /// Why data is being accessed, after the HL7 PurposeOfUse value set
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum PurposeOfUse {
+        Treatment,
+        Payment,
+        HealthcareOperations,
+        Emergency,
+        Research,
+        PublicHealth,
+        PatientRequest,
+        Legal,
+        Other(String),
+    }
+
+    /// Longest facility, device or application identifier an access
+    /// context may carry
+    pub const MAX_ACCESS_CONTEXT_FIELD_LEN: usize = 128;
+
+    /// Where and why an access happened, as the calling application
+    /// reports it; kept on the access log for the audit trail
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct AccessContext {
+        pub purpose_of_use: PurposeOfUse,
+        /// Facility or site the request came from
+        #[serde(default)]
+        pub facility: Option<String>,
+        /// Workstation or device identifier
+        #[serde(default)]
+        pub device: Option<String>,
+        /// Application that made the request on the user's behalf
+        #[serde(default)]
+        pub requesting_application: Option<String>,
+    }
+
+    impl AccessContext {
+        pub fn new(purpose_of_use: PurposeOfUse) -> Self {
+            Self { purpose_of_use, facility: None, device: None, requesting_application: None }
+        }
+
+        /// Identifiers that are present must be non-blank, printable and
+        /// at most `MAX_ACCESS_CONTEXT_FIELD_LEN` characters
+        pub fn validate(&self) -> ValidationResult {
+            let mut result = ValidationResult::new();
+            if let PurposeOfUse::Other(purpose) = &self.purpose_of_use {
+                check_context_field(&mut result, "purpose_of_use", purpose);
+            }
+            let fields = [
+                ("facility", &self.facility),
+                ("device", &self.device),
+                ("requesting_application", &self.requesting_application),
+            ];
+            for (field, value) in fields {
+                if let Some(value) = value {
+                    check_context_field(&mut result, field, value);
+                }
+            }
+            result
+        }
+
+        /// The facility when one is given, else the node itself
+        pub fn access_location(&self) -> String {
+            self.facility.clone().unwrap_or_else(|| DEFAULT_ACCESS_LOCATION.to_string())
+        }
+    }
+
+    /// Recorded as the access location when the caller gives no facility
+    pub const DEFAULT_ACCESS_LOCATION: &str = "holochain_node";
+
+    fn check_context_field(result: &mut ValidationResult, field: &str, value: &str) {
+        if value.trim().is_empty() {
+            result.add_error(field, "Access context field cannot be blank", ValidationErrorCode::Required);
+        } else if value.chars().count() > MAX_ACCESS_CONTEXT_FIELD_LEN {
+            result.add_error(field, "Access context field is too long", ValidationErrorCode::TooLong);
+        } else if value.chars().any(char::is_control) {
+            result.add_error(field, "Access context field contains control characters", ValidationErrorCode::InvalidCharacters);
+        }
+    }
+
     /// Input for authorization check via cross-zome call
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct AuthorizationInput {
@@ -107,6 +353,12 @@ pub mod access_control {
         pub data_category: DataCategory,
         pub permission: Permission,
         pub is_emergency: bool,
+        /// Getter that asked, recorded on the consent zome's authorization receipt
+        #[serde(default)]
+        pub access_path: Option<String>,
+        /// Caller-supplied context, validated before the consent zome is asked
+        #[serde(default)]
+        pub access_context: Option<AccessContext>,
     }
 
     /// Check if the calling agent has authorization to access patient data.
@@ -129,6 +381,121 @@ pub mod access_control {
         permission: Permission,
         is_emergency: bool,
     ) -> ExternResult<AuthorizationResult> {
+        authorize(patient_hash, category, permission, is_emergency, None, None)
+    }
+
+    /// `require_authorization` with the caller's purpose of use, facility,
+    /// device and application; an invalid context is rejected before the
+    /// consent zome is asked
+    pub fn require_authorization_with_context(
+        patient_hash: ActionHash,
+        category: DataCategory,
+        permission: Permission,
+        is_emergency: bool,
+        context: &AccessContext,
+    ) -> ExternResult<AuthorizationResult> {
+        authorize(patient_hash, category, permission, is_emergency, None, Some(context))
+    }
+
+    /// Seconds a granted authorization is reused unless the DNA properties
+    /// say otherwise
+    pub const DEFAULT_AUTHORIZATION_CACHE_TTL_SECONDS: u32 = 30;
+    /// Most decisions one zome instance remembers; the oldest go first
+    pub const MAX_CACHED_AUTHORIZATIONS: usize = 256;
+
+    /// What an authorization decision was made for
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct AuthorizationCacheKey {
+        pub requestor: AgentPubKey,
+        pub patient_hash: ActionHash,
+        pub category: DataCategory,
+        pub permission: Permission,
+        /// Purpose-of-use policies can decide differently per context
+        pub access_context: Option<AccessContext>,
+    }
+
+    /// Granted authorizations a zome instance reuses for a short TTL
+    ///
+    /// Denials and emergency overrides are never kept, so a new consent
+    /// applies at once. A revocation on this node clears the patient's
+    /// entries through the `ConsentRevoked` domain event; one made on
+    /// another node takes effect once the TTL runs out. A zome instance
+    /// seldom outlives one zome call, so the saving is mostly repeat
+    /// checks within a call, such as a batch read of one patient's records.
+    #[derive(Debug, Default)]
+    pub struct AuthorizationCache {
+        entries: Vec<(AuthorizationCacheKey, AuthorizationResult, Timestamp)>,
+    }
+
+    impl AuthorizationCache {
+        /// A decision for `key` cached less than `ttl_seconds` ago; expired
+        /// entries are dropped on the way
+        pub fn lookup(&mut self, key: &AuthorizationCacheKey, now: Timestamp, ttl_seconds: u32) -> Option<AuthorizationResult> {
+            let oldest = now.as_micros() - ttl_seconds as i64 * 1_000_000;
+            self.entries.retain(|(_, _, cached_at)| cached_at.as_micros() > oldest);
+            self.entries
+                .iter()
+                .find(|(cached, _, _)| cached == key)
+                .map(|(_, result, _)| result.clone())
+        }
+
+        /// Remember a granted decision, replacing any earlier one for `key`
+        pub fn insert(&mut self, key: AuthorizationCacheKey, result: AuthorizationResult, now: Timestamp) {
+            if !result.authorized || result.emergency_override {
+                return;
+            }
+            self.entries.retain(|(cached, _, _)| *cached != key);
+            if self.entries.len() >= MAX_CACHED_AUTHORIZATIONS {
+                self.entries.remove(0);
+            }
+            self.entries.push((key, result, now));
+        }
+
+        /// Forget every decision about `patient_hash`
+        pub fn invalidate_patient(&mut self, patient_hash: &ActionHash) {
+            self.entries.retain(|(key, _, _)| key.patient_hash != *patient_hash);
+        }
+
+        pub fn len(&self) -> usize {
+            self.entries.len()
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.entries.is_empty()
+        }
+    }
+
+    thread_local! {
+        static AUTHORIZATION_CACHE: RefCell<AuthorizationCache> = RefCell::new(AuthorizationCache::default());
+    }
+
+    /// A reused decision, sent to the consent zome so the access still
+    /// leaves an authorization receipt
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CachedAuthorizationInput {
+        pub check: AuthorizationInput,
+        pub consent_hash: Option<ActionHash>,
+    }
+
+    /// Drop this zome's cached authorizations for a patient, e.g. from a
+    /// `ConsentRevoked` handler
+    pub fn invalidate_authorization_cache(patient_hash: &ActionHash) {
+        AUTHORIZATION_CACHE.with(|cache| cache.borrow_mut().invalidate_patient(patient_hash));
+    }
+
+    /// `require_authorization` on behalf of a named getter
+    pub(crate) fn authorize(
+        patient_hash: ActionHash,
+        category: DataCategory,
+        permission: Permission,
+        is_emergency: bool,
+        access_path: Option<&str>,
+        context: Option<&AccessContext>,
+    ) -> ExternResult<AuthorizationResult> {
+        if let Some(context) = context {
+            context.validate().into_result()?;
+        }
+        rate_limits::enforce_rate_limit()?;
         let caller = agent_info()?.agent_initial_pubkey;
 
         // First check if caller is the patient themselves (always authorized for own data)
@@ -139,71 +506,84 @@ pub mod access_control {
                 reason: "Patient accessing own data".to_string(),
                 permissions: vec![Permission::Read, Permission::Write, Permission::Export],
                 emergency_override: false,
+                assurance_level: IdentityAssuranceLevel::SelfAsserted,
+                decisive_policy: None,
             });
         }
 
-        // Call the consent zome to check authorization
+        let cache_key = AuthorizationCacheKey {
+            requestor: caller.clone(),
+            patient_hash: patient_hash.clone(),
+            category: category.clone(),
+            permission: permission.clone(),
+            access_context: context.cloned(),
+        };
         let input = AuthorizationInput {
             patient_hash: patient_hash.clone(),
-            requestor: caller.clone(),
+            requestor: caller,
             data_category: category.clone(),
             permission: permission.clone(),
             is_emergency,
+            access_path: access_path.map(str::to_string),
+            access_context: context.cloned(),
+        };
+        // Exports count against their limit whether or not the answer is cached
+        if permission == Permission::Export {
+            rate_limits::enforce_operation_limit(SensitiveOperation::Export)?;
+        }
+        let ttl = HealthDnaProperties::current()?.authorization_cache_ttl();
+        let now = sys_time()?;
+        let cached = match ttl {
+            0 => None,
+            ttl => AUTHORIZATION_CACHE.with(|cache| cache.borrow_mut().lookup(&cache_key, now, ttl)),
         };
+        if let Some(cached) = cached {
+            let receipt = CachedAuthorizationInput {
+                check: input,
+                consent_hash: cached.consent_hash.clone(),
+            };
+            resilience::resilient_call::<_, ()>("consent", "record_cached_authorization", &receipt, reads::CallClass::Audit)
+                .map_err(|failure| failure.into_wasm_error("recording authorization receipt"))?;
+            return Ok(cached);
+        }
 
-        let response = call(
-            CallTargetCell::Local,
+        // Call the consent zome to check authorization
+
+        let auth_result: AuthorizationResult = resilience::resilient_call(
             "consent",
-            "check_authorization".into(),
-            None,
+            "check_authorization",
             &input,
-        )?;
-
-        // Decode the ZomeCallResponse
-        let auth_result: AuthorizationResult = match response {
-            ZomeCallResponse::Ok(extern_io) => {
-                extern_io.decode()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(
-                        format!("Failed to decode authorization response: {:?}", e)
-                    )))?
-            },
-            ZomeCallResponse::Unauthorized(_, _, _, _) => {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    "Unauthorized to call consent zome".to_string()
-                )));
-            },
-            ZomeCallResponse::NetworkError(err) => {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Network error checking authorization: {}", err)
-                )));
-            },
-            ZomeCallResponse::CountersigningSession(err) => {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Countersigning error: {}", err)
-                )));
-            },
-            ZomeCallResponse::AuthenticationFailed(_, _) => {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    "Authentication failed for consent zome call".to_string()
-                )));
-            },
-        };
+            reads::CallClass::Authorization,
+        )
+        .map_err(|failure| failure.into_wasm_error("checking authorization"))?;
+        if ttl > 0 {
+            AUTHORIZATION_CACHE.with(|cache| cache.borrow_mut().insert(cache_key, auth_result.clone(), now));
+        }
 
-        // If not authorized and not emergency, deny access
-        if !auth_result.authorized && !is_emergency {
-            return Err(wasm_error!(WasmErrorInner::Guest(
-                format!("Access denied: {}", auth_result.reason)
-            )));
+        // The consent zome offers the emergency override unless the requestor
+        // may not break glass (service agents outside their allowlist)
+        let emergency = is_emergency && auth_result.emergency_override;
+
+        // If not authorized and not emergency, deny access; the denial log
+        // is best effort and only kept if the caller's call completes
+        if !auth_result.authorized && !emergency {
+            audit::log_access_denied(patient_hash, category.clone(), auth_result.reason.clone()).ok();
+            return Err(HealthError::ConsentRequired(format!("Access denied: {}", auth_result.reason))
+                .detail("data_category", format!("{:?}", category))
+                .into());
         }
 
         // If emergency, mark as override but allow
-        if !auth_result.authorized && is_emergency {
+        if !auth_result.authorized && emergency {
+            rate_limits::enforce_operation_limit(SensitiveOperation::EmergencyOverride)?;
             return Ok(AuthorizationResult {
                 authorized: true,
                 consent_hash: None,
                 reason: "Emergency override - requires post-hoc justification".to_string(),
                 permissions: vec![permission],
                 emergency_override: true,
+                assurance_level: auth_result.assurance_level,
+                decisive_policy: auth_result.decisive_policy,
             });
         }
 
@@ -213,34 +593,84 @@ pub mod access_control {
     /// Check if the caller is the patient themselves
     fn is_patient_self(patient_hash: &ActionHash, caller: &AgentPubKey) -> ExternResult<bool> {
         // Get the patient record to check creator
-        if let Some(record) = get(patient_hash.clone(), GetOptions::default())? {
+        if let Some(record) = reads::get_for(patient_hash.clone(), reads::CallClass::Authorization)? {
             let author = record.action().author();
             return Ok(author == caller);
         }
         Ok(false)
     }
 
+    /// Reject callers other than the patient, for views that reveal the
+    /// shape of a whole record and are not access-logged
+    pub fn require_patient_self(patient_hash: &ActionHash) -> ExternResult<()> {
+        let caller = agent_info()?.agent_initial_pubkey;
+        if !is_patient_self(patient_hash, &caller)? {
+            return Err(unauthorized("Only the patient can perform this operation"));
+        }
+        Ok(())
+    }
+
     /// Require admin authorization for sensitive operations
     ///
-    /// This checks if the caller is in the system admin list.
-    /// Admin links are stored from the system_admins anchor to agent public keys.
-    ///
-    /// Note: In production, you would set up admin links during initialization.
-    /// For now, this function checks if caller created the patient (owner permission).
+    /// Admins are agents linked from the `system_admins` anchor in the
+    /// consent zome; the first ones are bootstrapped from the DNA properties.
     pub fn require_admin_authorization() -> ExternResult<()> {
-        // For now, admin check is a placeholder that allows authorized callers
-        // In production, this would query admin links from the system_admins anchor
-        // using a specific link type defined in the DNA.
-        //
-        // The full implementation would be:
-        // 1. Create an "admin" link type in the DNA
-        // 2. Link admin agents from the system_admins anchor
-        // 3. Query those links here
-        //
-        // For now, we reject by default and require explicit admin setup
-        Err(wasm_error!(WasmErrorInner::Guest(
-            "Admin authorization required - admin system not yet configured".to_string()
-        )))
+        let caller = agent_info()?.agent_initial_pubkey;
+        let response = call(
+            CallTargetCell::Local,
+            "consent",
+            "is_admin".into(),
+            None,
+            &caller,
+        )?;
+
+        let is_admin: bool = match response {
+            ZomeCallResponse::Ok(extern_io) => extern_io
+                .decode()
+                .map_err(|e| internal_error(format!("Failed to decode admin check: {:?}", e)))?,
+            other => return Err(internal_error(format!("Admin check failed: {:?}", other))),
+        };
+
+        if !is_admin {
+            return Err(HealthError::Unauthorized("Admin authorization required".to_string())
+                .detail("role", "Admin")
+                .into());
+        }
+        Ok(())
+    }
+
+    /// DNA properties read by the access control checks
+    ///
+    /// `bootstrap_admins` lists base64 agent keys that become admins when
+    /// their consent zome initializes, e.g.
+    /// `properties: { bootstrap_admins: ["uhCAk..."] }` in `dna.yaml`.
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, SerializedBytes)]
+    pub struct HealthDnaProperties {
+        #[serde(default)]
+        pub bootstrap_admins: Vec<String>,
+        /// How long a zome reuses a granted authorization; 0 turns the
+        /// cache off. Defaults to `DEFAULT_AUTHORIZATION_CACHE_TTL_SECONDS`
+        #[serde(default)]
+        pub authorization_cache_ttl_seconds: Option<u32>,
+    }
+
+    impl HealthDnaProperties {
+        /// The DNA's properties; empty when unset or unreadable
+        pub fn current() -> ExternResult<Self> {
+            Ok(Self::try_from(dna_info()?.modifiers.properties).unwrap_or_default())
+        }
+
+        /// Bootstrap admin keys, skipping any that do not parse
+        pub fn bootstrap_admin_keys(&self) -> Vec<AgentPubKey> {
+            self.bootstrap_admins
+                .iter()
+                .filter_map(|key| AgentPubKey::try_from(key.clone()).ok())
+                .collect()
+        }
+
+        pub fn authorization_cache_ttl(&self) -> u32 {
+            self.authorization_cache_ttl_seconds.unwrap_or(DEFAULT_AUTHORIZATION_CACHE_TTL_SECONDS)
+        }
     }
 
     /// Role types for role-based access control
@@ -253,6 +683,56 @@ pub mod access_control {
         Auditor,
         EmergencyAccess,
     }
+
+    impl Role {
+        /// Whether the role is granted through a `RoleAssignment`; patients
+        /// are checked per record and admins through the admin links
+        pub fn is_assignable(&self) -> bool {
+            matches!(self, Role::Provider | Role::Researcher | Role::Auditor | Role::EmergencyAccess)
+        }
+    }
+
+    /// Input to the consent zome's `has_role` check
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct RoleCheck {
+        pub agent: AgentPubKey,
+        pub role: Role,
+    }
+
+    /// Reject callers that do not hold `role`
+    ///
+    /// Roles are assigned by admins in the consent zome. `Role::Patient` is
+    /// relative to a record, so use `require_patient_self` for it.
+    pub fn require_role(role: Role) -> ExternResult<()> {
+        if role == Role::Patient {
+            return Err(invalid_input("The patient role is checked per record"));
+        }
+        let check = RoleCheck {
+            agent: agent_info()?.agent_initial_pubkey,
+            role,
+        };
+        let response = call(
+            CallTargetCell::Local,
+            "consent",
+            "has_role".into(),
+            None,
+            &check,
+        )?;
+
+        let has_role: bool = match response {
+            ZomeCallResponse::Ok(extern_io) => extern_io
+                .decode()
+                .map_err(|e| internal_error(format!("Failed to decode role check: {:?}", e)))?,
+            other => return Err(internal_error(format!("Role check failed: {:?}", other))),
+        };
+
+        if !has_role {
+            return Err(HealthError::Unauthorized(format!("This operation requires the {:?} role", check.role))
+                .detail("role", format!("{:?}", check.role))
+                .into());
+        }
+        Ok(())
+    }
 }
 
 /// Audit logging module - tracks all PHI access
@@ -273,6 +753,17 @@ pub mod audit {
         pub access_location: String,
         pub emergency_override: bool,
         pub override_reason: Option<String>,
+        /// Getter that logged the access; the coverage marker matched
+        /// against authorization receipts
+        #[serde(default)]
+        pub access_path: Option<String>,
+        /// Accessor's assurance level when the read was authorized; the
+        /// consent zome works it out when the caller does not say
+        #[serde(default)]
+        pub accessor_assurance: Option<access_control::IdentityAssuranceLevel>,
+        /// Purpose of use, facility, device and application the caller gave
+        #[serde(default)]
+        pub access_context: Option<access_control::AccessContext>,
     }
 
     /// Denied access log for security monitoring
@@ -305,10 +796,62 @@ pub mod audit {
         is_emergency: bool,
         override_reason: Option<String>,
     ) -> ExternResult<ActionHash> {
+        record_access(patient_hash, categories, access_type, consent_hash, is_emergency, override_reason, LogAnnotations::default())
+    }
+
+    /// `log_data_access` with the caller's access context, which also sets
+    /// the logged access location
+    pub fn log_data_access_with_context(
+        patient_hash: ActionHash,
+        categories: Vec<access_control::DataCategory>,
+        access_type: access_control::Permission,
+        consent_hash: Option<ActionHash>,
+        is_emergency: bool,
+        override_reason: Option<String>,
+        context: &access_control::AccessContext,
+    ) -> ExternResult<ActionHash> {
+        context.validate().into_result()?;
+        record_access(patient_hash, categories, access_type, consent_hash, is_emergency, override_reason, LogAnnotations { audited: None, context: Some(context) })
+    }
+
+    /// What a log carries beyond the access itself
+    #[derive(Default)]
+    struct LogAnnotations<'a> {
+        /// The getter's access path and the assurance level its
+        /// authorization returned
+        audited: Option<(&'a str, access_control::IdentityAssuranceLevel)>,
+        context: Option<&'a access_control::AccessContext>,
+    }
+
+    fn record_access(
+        patient_hash: ActionHash,
+        categories: Vec<access_control::DataCategory>,
+        access_type: access_control::Permission,
+        consent_hash: Option<ActionHash>,
+        is_emergency: bool,
+        override_reason: Option<String>,
+        annotations: LogAnnotations,
+    ) -> ExternResult<ActionHash> {
+        let log_entry = access_log_entry(patient_hash, categories, access_type, consent_hash, is_emergency, override_reason, annotations)?;
+
+        // Call consent zome to persist log
+        resilience::resilient_call("consent", "create_access_log", &log_entry, reads::CallClass::Audit)
+            .map_err(|failure| failure.into_wasm_error("logging access"))
+    }
+
+    fn access_log_entry(
+        patient_hash: ActionHash,
+        categories: Vec<access_control::DataCategory>,
+        access_type: access_control::Permission,
+        consent_hash: Option<ActionHash>,
+        is_emergency: bool,
+        override_reason: Option<String>,
+        LogAnnotations { audited, context }: LogAnnotations,
+    ) -> ExternResult<AccessLogEntry> {
         let caller = agent_info()?.agent_initial_pubkey;
         let now = sys_time()?;
 
-        let log_entry = AccessLogEntry {
+        Ok(AccessLogEntry {
             log_id: format!("LOG-{}-{}", now.as_micros(), short_hash(&caller)),
             patient_hash: patient_hash.clone(),
             accessor: caller,
@@ -321,108 +864,194 @@ pub mod audit {
                 "Authorized access".to_string()
             },
             accessed_at: Timestamp::from_micros(now.as_micros() as i64),
-            access_location: "holochain_node".to_string(),
+            access_location: context.map_or_else(
+                || access_control::DEFAULT_ACCESS_LOCATION.to_string(),
+                access_control::AccessContext::access_location,
+            ),
             emergency_override: is_emergency,
             override_reason,
-        };
+            access_path: audited.map(|(path, _)| path.to_string()),
+            accessor_assurance: audited.map(|(_, level)| level),
+            access_context: context.cloned(),
+        })
+    }
 
-        // Call consent zome to persist log
-        let response = call(
-            CallTargetCell::Local,
-            "consent",
-            "create_access_log".into(),
-            None,
-            &log_entry,
-        )?;
+    /// Categories whose reads the patient's agent is asked to countersign
+    pub const COUNTERSIGNED_CATEGORIES: &[access_control::DataCategory] = &[
+        access_control::DataCategory::MentalHealth,
+        access_control::DataCategory::GeneticData,
+    ];
 
-        match response {
-            ZomeCallResponse::Ok(extern_io) => {
-                extern_io.decode()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(
-                        format!("Failed to decode access log response: {:?}", e)
-                    )))
-            },
-            ZomeCallResponse::Unauthorized(_, _, _, _) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    "Unauthorized to log access".to_string()
-                )))
-            },
-            ZomeCallResponse::NetworkError(err) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Network error logging access: {}", err)
-                )))
-            },
-            ZomeCallResponse::CountersigningSession(err) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Countersigning error: {}", err)
-                )))
-            },
-            ZomeCallResponse::AuthenticationFailed(_, _) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    "Authentication failed for access log call".to_string()
-                )))
-            },
-        }
+    /// Whether reading `categories` calls for a countersigned receipt
+    pub fn needs_countersignature(categories: &[access_control::DataCategory]) -> bool {
+        categories
+            .iter()
+            .any(|c| COUNTERSIGNED_CATEGORIES.contains(c) || *c == access_control::DataCategory::All)
     }
 
-    /// Log denied access attempt for security monitoring
-    pub fn log_access_denied(
-        patient_hash: ActionHash,
-        category: access_control::DataCategory,
-        denial_reason: String,
-    ) -> ExternResult<ActionHash> {
-        let caller = agent_info()?.agent_initial_pubkey;
-        let now = sys_time()?;
-
-        let log_entry = AccessDeniedLogEntry {
-            log_id: format!("DENY-{}-{}", now.as_micros(), short_hash(&caller)),
-            patient_hash,
-            attempted_accessor: caller,
-            data_category: category,
-            denial_reason,
-            attempted_at: Timestamp::from_micros(now.as_micros() as i64),
-        };
+    /// How an access was recorded by `log_countersigned_access`
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub enum AccessReceipt {
+        /// The patient's agent signed a receipt for the log
+        Countersigned { log_hash: ActionHash, countersignature_hash: ActionHash },
+        /// The patient's agent could not be reached or did not sign, so the
+        /// log stands alone
+        Unilateral { log_hash: ActionHash, reason: String },
+        /// No countersignature was needed: the categories are not high
+        /// sensitivity or the accessor is the patient
+        NotRequired { log_hash: ActionHash },
+    }
 
-        // Call consent zome to persist log
-        let response = call(
-            CallTargetCell::Local,
-            "consent",
-            "create_access_denied_log".into(),
-            None,
-            &log_entry,
-        )?;
+    impl AccessReceipt {
+        pub fn log_hash(&self) -> &ActionHash {
+            match self {
+                AccessReceipt::Countersigned { log_hash, .. }
+                | AccessReceipt::Unilateral { log_hash, .. }
+                | AccessReceipt::NotRequired { log_hash } => log_hash,
+            }
+        }
+    }
 
-        match response {
-            ZomeCallResponse::Ok(extern_io) => {
-                extern_io.decode()
-                    .map_err(|e| wasm_error!(WasmErrorInner::Guest(
-                        format!("Failed to decode denied log response: {:?}", e)
-                    )))
-            },
-            ZomeCallResponse::Unauthorized(_, _, _, _) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    "Unauthorized to log denied access".to_string()
-                )))
-            },
-            ZomeCallResponse::NetworkError(err) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Network error logging denied access: {}", err)
-                )))
-            },
-            ZomeCallResponse::CountersigningSession(err) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Countersigning error: {}", err)
-                )))
-            },
-            ZomeCallResponse::AuthenticationFailed(_, _) => {
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    "Authentication failed for denied log call".to_string()
-                )))
-            },
+    /// Log data access, asking the patient's agent to countersign reads of
+    /// `COUNTERSIGNED_CATEGORIES`
+    ///
+    /// Opt-in alternative to [`log_data_access`]. The access is always
+    /// logged; when the patient's agent is offline the receipt says so
+    /// rather than failing the read.
+    pub fn log_countersigned_access(
+        patient_hash: ActionHash,
+        categories: Vec<access_control::DataCategory>,
+        access_type: access_control::Permission,
+        consent_hash: Option<ActionHash>,
+        is_emergency: bool,
+        override_reason: Option<String>,
+    ) -> ExternResult<AccessReceipt> {
+        let countersign = needs_countersignature(&categories);
+        let log_entry = access_log_entry(patient_hash, categories, access_type, consent_hash, is_emergency, override_reason, LogAnnotations::default())?;
+        if !countersign {
+            let log_hash = resilience::resilient_call("consent", "create_access_log", &log_entry, reads::CallClass::Audit)
+                .map_err(|failure| failure.into_wasm_error("logging access"))?;
+            return Ok(AccessReceipt::NotRequired { log_hash });
         }
+        resilience::resilient_call("consent", "create_countersigned_access_log", &log_entry, reads::CallClass::Audit)
+            .map_err(|failure| failure.into_wasm_error("logging countersigned access"))
     }
 
-    /// Generate a short hash string for log IDs
+    /// Authorize, run and log a read of patient data
+    ///
+    /// Getters should read through this rather than pairing
+    /// `require_authorization` with `log_data_access` by hand: both calls
+    /// carry `access_path`, so the consent zome's audit coverage report can
+    /// name any getter whose authorized reads were never logged.
+    pub fn audited_read<T>(
+        access_path: &str,
+        patient_hash: ActionHash,
+        category: access_control::DataCategory,
+        is_emergency: bool,
+        emergency_reason: Option<String>,
+        read: impl FnOnce() -> ExternResult<T>,
+    ) -> ExternResult<T> {
+        audited_read_in(access_path, patient_hash, category, is_emergency, emergency_reason, None, read)
+    }
+
+    /// `audited_read` for getters whose callers supply an access context;
+    /// the context goes on both the authorization check and the log
+    pub fn audited_read_with_context<T>(
+        access_path: &str,
+        patient_hash: ActionHash,
+        category: access_control::DataCategory,
+        is_emergency: bool,
+        emergency_reason: Option<String>,
+        context: &access_control::AccessContext,
+        read: impl FnOnce() -> ExternResult<T>,
+    ) -> ExternResult<T> {
+        audited_read_in(access_path, patient_hash, category, is_emergency, emergency_reason, Some(context), read)
+    }
+
+    fn audited_read_in<T>(
+        access_path: &str,
+        patient_hash: ActionHash,
+        category: access_control::DataCategory,
+        is_emergency: bool,
+        emergency_reason: Option<String>,
+        context: Option<&access_control::AccessContext>,
+        read: impl FnOnce() -> ExternResult<T>,
+    ) -> ExternResult<T> {
+        let (zome, function) = access_path.split_once("::").unwrap_or(("unknown", access_path));
+        performance::instrumented(zome, function, || {
+            let auth = access_control::authorize(
+                patient_hash.clone(),
+                category.clone(),
+                access_control::Permission::Read,
+                is_emergency,
+                Some(access_path),
+                context,
+            )?;
+            let value = read()?;
+            record_access(
+                patient_hash,
+                vec![category],
+                access_control::Permission::Read,
+                auth.consent_hash,
+                auth.emergency_override,
+                emergency_reason,
+                LogAnnotations { audited: Some((access_path, auth.assurance_level)), context },
+            )?;
+            Ok(value)
+        })
+    }
+
+    /// Log a purge decision a retention sweep applied; the reason names the
+    /// action, the record and the policy behind it
+    pub fn log_purge_decision(
+        patient_hash: ActionHash,
+        decision: &retention::PurgeDecision,
+    ) -> ExternResult<ActionHash> {
+        let access_type = match decision.action {
+            retention::RetentionAction::Mark => access_control::Permission::Write,
+            retention::RetentionAction::Tombstone => access_control::Permission::Delete,
+        };
+        let mut log_entry = access_log_entry(
+            patient_hash,
+            vec![decision.category.clone()],
+            access_type,
+            None,
+            false,
+            None,
+            LogAnnotations::default(),
+        )?;
+        log_entry.access_reason = format!(
+            "RETENTION: {:?} {} under policy {}",
+            decision.action, decision.record_hash, decision.policy_hash
+        );
+        resilience::resilient_call("consent", "create_access_log", &log_entry, reads::CallClass::Audit)
+            .map_err(|failure| failure.into_wasm_error("logging purge decision"))
+    }
+
+    /// Log denied access attempt for security monitoring
+    pub fn log_access_denied(
+        patient_hash: ActionHash,
+        category: access_control::DataCategory,
+        denial_reason: String,
+    ) -> ExternResult<ActionHash> {
+        let caller = agent_info()?.agent_initial_pubkey;
+        let now = sys_time()?;
+
+        let log_entry = AccessDeniedLogEntry {
+            log_id: format!("DENY-{}-{}", now.as_micros(), short_hash(&caller)),
+            patient_hash,
+            attempted_accessor: caller,
+            data_category: category,
+            denial_reason,
+            attempted_at: Timestamp::from_micros(now.as_micros() as i64),
+        };
+
+        // Call consent zome to persist log
+        resilience::resilient_call("consent", "create_access_denied_log", &log_entry, reads::CallClass::Audit)
+            .map_err(|failure| failure.into_wasm_error("logging denied access"))
+    }
+
+    /// Generate a short hash string for log IDs
     fn short_hash(agent: &AgentPubKey) -> String {
         let bytes = agent.get_raw_39();
         format!("{:02x}{:02x}{:02x}{:02x}",
@@ -433,6 +1062,7 @@ pub mod audit {
 /// Common types used across zomes
 pub mod types {
     use super::*;
+    use std::collections::BTreeMap;
 
     /// Input for paginated queries
     #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -446,14 +1076,14 @@ pub mod types {
 
         pub fn validate(&self) -> ExternResult<()> {
             if self.limit > Self::MAX_LIMIT {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Limit cannot exceed {}", Self::MAX_LIMIT)
-                )));
+                return Err(HealthError::ValidationError(format!("Limit cannot exceed {}", Self::MAX_LIMIT))
+                    .field("limit")
+                    .into());
             }
             if self.limit == 0 {
-                return Err(wasm_error!(WasmErrorInner::Guest(
-                    "Limit must be greater than 0".to_string()
-                )));
+                return Err(HealthError::ValidationError("Limit must be greater than 0".to_string())
+                    .field("limit")
+                    .into());
             }
             Ok(())
         }
@@ -468,6 +1098,127 @@ pub mod types {
         }
     }
 
+    const CURSOR_PREFIX: &str = "pc1:";
+
+    /// Position just after the last item of a cursor page
+    ///
+    /// Items are ordered by timestamp, then hash. New items arrive with
+    /// later timestamps, so a cursor keeps its place as they are added.
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct PaginationCursor {
+        pub timestamp: Timestamp,
+        /// Raw hash bytes, ordering items that share a timestamp
+        pub hash: Vec<u8>,
+    }
+
+    impl PaginationCursor {
+        pub fn new(timestamp: Timestamp, hash: &[u8]) -> Self {
+            Self { timestamp, hash: hash.to_vec() }
+        }
+
+        /// Cursor for a link: its creation time and target
+        pub fn from_link(link: &Link) -> Self {
+            Self::new(link.timestamp, link.target.get_raw_39())
+        }
+
+        /// Opaque token handed to callers
+        pub fn encode(&self) -> String {
+            format!(
+                "{}{}:{}",
+                CURSOR_PREFIX,
+                self.timestamp.as_micros(),
+                encryption::base64_encode(&self.hash)
+            )
+        }
+
+        pub fn decode(token: &str) -> ExternResult<Self> {
+            let invalid = || WasmError::from(HealthError::ValidationError("Invalid pagination cursor".to_string()).field("cursor"));
+            let (micros, hash) = token
+                .strip_prefix(CURSOR_PREFIX)
+                .and_then(|rest| rest.split_once(':'))
+                .ok_or_else(invalid)?;
+            Ok(Self {
+                timestamp: Timestamp::from_micros(micros.parse().map_err(|_| invalid())?),
+                hash: encryption::base64_decode(hash).map_err(|_| invalid())?,
+            })
+        }
+    }
+
+    /// Input for cursor-paginated queries; the default is the first page
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CursorInput {
+        /// `next_cursor` of the previous page
+        pub after: Option<String>,
+        pub limit: usize,
+    }
+
+    impl CursorInput {
+        pub fn validate(&self) -> ExternResult<()> {
+            PaginationInput { offset: 0, limit: self.limit }.validate()
+        }
+    }
+
+    impl Default for CursorInput {
+        fn default() -> Self {
+            Self {
+                after: None,
+                limit: PaginationInput::default().limit,
+            }
+        }
+    }
+
+    /// One page of a cursor-paginated query
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct CursorPage<T> {
+        pub items: Vec<T>,
+        /// Pass back in `CursorInput::after` for the next page
+        pub next_cursor: Option<String>,
+        pub has_more: bool,
+    }
+
+    /// The page of `items` after `input.after`, oldest first
+    ///
+    /// `cursor_of` gives each item's position; items need not be sorted.
+    pub fn cursor_page<T>(
+        items: Vec<T>,
+        input: &CursorInput,
+        cursor_of: impl Fn(&T) -> PaginationCursor,
+    ) -> ExternResult<CursorPage<T>> {
+        input.validate()?;
+        let after = input.after.as_deref().map(PaginationCursor::decode).transpose()?;
+
+        let mut positioned: Vec<(PaginationCursor, T)> = items
+            .into_iter()
+            .map(|item| (cursor_of(&item), item))
+            .filter(|(cursor, _)| after.as_ref().is_none_or(|after| cursor > after))
+            .collect();
+        positioned.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let has_more = positioned.len() > input.limit;
+        positioned.truncate(input.limit);
+        let next_cursor = if has_more {
+            positioned.last().map(|(cursor, _)| cursor.encode())
+        } else {
+            None
+        };
+        Ok(CursorPage {
+            items: positioned.into_iter().map(|(_, item)| item).collect(),
+            next_cursor,
+            has_more,
+        })
+    }
+
+    /// One record summarized for the patient's own previews; carries no
+    /// clinical values
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct RecordInventoryItem {
+        pub record_hash: ActionHash,
+        pub category: access_control::DataCategory,
+        pub title: String,
+        pub recorded_at: Timestamp,
+        pub encounter_hash: Option<ActionHash>,
+    }
+
     /// Result wrapper for paginated queries
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct PaginatedResult<T> {
@@ -501,6 +1252,10 @@ pub mod types {
     }
 
     /// Standard error types for consistent error handling
+    ///
+    /// Zome calls return these as a JSON `HealthErrorPayload` in the guest
+    /// error string, so clients can branch on the code instead of parsing
+    /// the wording.
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub enum HealthError {
         NotFound(String),
@@ -509,6 +1264,53 @@ pub mod types {
         ConsentRequired(String),
         ExpiredConsent(String),
         InternalError(String),
+        /// The request conflicts with the current state, e.g. a duplicate
+        /// or a transition from the wrong status
+        Conflict(String),
+        /// A quota, rate limit or privacy budget would be exceeded
+        LimitExceeded(String),
+        /// A dependency could not be reached; retrying may succeed
+        Unavailable(String),
+    }
+
+    impl HealthError {
+        pub fn code(&self) -> HealthErrorCode {
+            match self {
+                HealthError::NotFound(_) => HealthErrorCode::NotFound,
+                HealthError::Unauthorized(_) => HealthErrorCode::Unauthorized,
+                HealthError::ValidationError(_) => HealthErrorCode::ValidationError,
+                HealthError::ConsentRequired(_) => HealthErrorCode::ConsentRequired,
+                HealthError::ExpiredConsent(_) => HealthErrorCode::ExpiredConsent,
+                HealthError::InternalError(_) => HealthErrorCode::InternalError,
+                HealthError::Conflict(_) => HealthErrorCode::Conflict,
+                HealthError::LimitExceeded(_) => HealthErrorCode::LimitExceeded,
+                HealthError::Unavailable(_) => HealthErrorCode::Unavailable,
+            }
+        }
+
+        pub fn message(&self) -> &str {
+            match self {
+                HealthError::NotFound(msg)
+                | HealthError::Unauthorized(msg)
+                | HealthError::ValidationError(msg)
+                | HealthError::ConsentRequired(msg)
+                | HealthError::ExpiredConsent(msg)
+                | HealthError::InternalError(msg)
+                | HealthError::Conflict(msg)
+                | HealthError::LimitExceeded(msg)
+                | HealthError::Unavailable(msg) => msg,
+            }
+        }
+
+        /// Payload naming the input field at fault
+        pub fn field(self, field: &str) -> HealthErrorPayload {
+            HealthErrorPayload::from(self).field(field)
+        }
+
+        /// Payload with one detail attached
+        pub fn detail(self, key: &str, value: impl ToString) -> HealthErrorPayload {
+            HealthErrorPayload::from(self).detail(key, value)
+        }
     }
 
     impl std::fmt::Display for HealthError {
@@ -520,13 +1322,167 @@ pub mod types {
                 HealthError::ConsentRequired(msg) => write!(f, "Consent required: {}", msg),
                 HealthError::ExpiredConsent(msg) => write!(f, "Expired consent: {}", msg),
                 HealthError::InternalError(msg) => write!(f, "Internal error: {}", msg),
+                HealthError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+                HealthError::LimitExceeded(msg) => write!(f, "Limit exceeded: {}", msg),
+                HealthError::Unavailable(msg) => write!(f, "Unavailable: {}", msg),
             }
         }
     }
 
     impl From<HealthError> for WasmError {
         fn from(err: HealthError) -> Self {
-            wasm_error!(WasmErrorInner::Guest(err.to_string()))
+            HealthErrorPayload::from(err).into()
+        }
+    }
+
+    /// Machine-readable `HealthError` category
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+    pub enum HealthErrorCode {
+        NotFound,
+        Unauthorized,
+        ValidationError,
+        ConsentRequired,
+        ExpiredConsent,
+        InternalError,
+        Conflict,
+        LimitExceeded,
+        Unavailable,
+    }
+
+    /// Error as it travels in the guest error string
+    ///
+    /// Serialized as JSON, e.g.
+    /// `{"code":"NOT_FOUND","message":"Consent not found"}`, with `field`
+    /// and `details` present only when set.
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct HealthErrorPayload {
+        pub code: HealthErrorCode,
+        pub message: String,
+        /// Input field the error is about
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        pub field: Option<String>,
+        /// Structured context, e.g. the role a check required
+        #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+        pub details: BTreeMap<String, String>,
+    }
+
+    impl HealthErrorPayload {
+        pub fn new(code: HealthErrorCode, message: impl Into<String>) -> Self {
+            Self { code, message: message.into(), field: None, details: BTreeMap::new() }
+        }
+
+        pub fn field(mut self, field: &str) -> Self {
+            self.field = Some(field.to_string());
+            self
+        }
+
+        pub fn detail(mut self, key: &str, value: impl ToString) -> Self {
+            self.details.insert(key.to_string(), value.to_string());
+            self
+        }
+
+        /// The JSON carried as the guest error string
+        pub fn to_guest_string(&self) -> String {
+            serde_json::to_string(self).unwrap_or_else(|_| self.message.clone())
+        }
+
+        /// Recover a payload from a guest error message
+        ///
+        /// Errors relayed through a cross-zome call arrive wrapped in host
+        /// context and with their quotes escaped; both forms are accepted.
+        pub fn from_guest_message(message: &str) -> Option<Self> {
+            let parse = |text: &str| {
+                let start = text.find("{\"code\"")?;
+                serde_json::Deserializer::from_str(&text[start..])
+                    .into_iter::<Self>()
+                    .next()?
+                    .ok()
+            };
+            parse(message).or_else(|| parse(&message.replace("\\\"", "\"").replace("\\\\", "\\")))
+        }
+
+        pub fn from_wasm_error(error: &WasmError) -> Option<Self> {
+            match &error.error {
+                WasmErrorInner::Guest(message) => Self::from_guest_message(message),
+                _ => Self::from_guest_message(&error.to_string()),
+            }
+        }
+    }
+
+    impl std::fmt::Display for HealthErrorPayload {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}: {}", self.code, self.message)
+        }
+    }
+
+    impl From<HealthError> for HealthErrorPayload {
+        fn from(err: HealthError) -> Self {
+            let code = err.code();
+            let message = match err {
+                HealthError::NotFound(msg)
+                | HealthError::Unauthorized(msg)
+                | HealthError::ValidationError(msg)
+                | HealthError::ConsentRequired(msg)
+                | HealthError::ExpiredConsent(msg)
+                | HealthError::InternalError(msg)
+                | HealthError::Conflict(msg)
+                | HealthError::LimitExceeded(msg)
+                | HealthError::Unavailable(msg) => msg,
+            };
+            Self::new(code, message)
+        }
+    }
+
+    impl From<HealthErrorPayload> for WasmError {
+        fn from(payload: HealthErrorPayload) -> Self {
+            wasm_error!(WasmErrorInner::Guest(payload.to_guest_string()))
+        }
+    }
+
+    /// The record or entity asked for does not exist or is not visible
+    pub fn not_found(message: impl Into<String>) -> WasmError {
+        HealthError::NotFound(message.into()).into()
+    }
+
+    /// The caller lacks the role, relationship or ownership required
+    pub fn unauthorized(message: impl Into<String>) -> WasmError {
+        HealthError::Unauthorized(message.into()).into()
+    }
+
+    /// The input is malformed or inconsistent
+    pub fn invalid_input(message: impl Into<String>) -> WasmError {
+        HealthError::ValidationError(message.into()).into()
+    }
+
+    /// An active consent is needed and missing
+    pub fn consent_required(message: impl Into<String>) -> WasmError {
+        HealthError::ConsentRequired(message.into()).into()
+    }
+
+    /// The request conflicts with the current state
+    pub fn conflict(message: impl Into<String>) -> WasmError {
+        HealthError::Conflict(message.into()).into()
+    }
+
+    /// A quota, rate limit or budget would be exceeded
+    pub fn limit_exceeded(message: impl Into<String>) -> WasmError {
+        HealthError::LimitExceeded(message.into()).into()
+    }
+
+    /// Anything the caller cannot fix, e.g. an undecodable entry; usable
+    /// directly in `map_err`
+    pub fn internal_error(error: impl std::fmt::Display) -> WasmError {
+        HealthError::InternalError(error.to_string()).into()
+    }
+
+    /// Add context to an error, keeping its code when it is structured
+    pub fn error_with_detail(error: WasmError, key: &str, value: impl ToString) -> WasmError {
+        match HealthErrorPayload::from_wasm_error(&error) {
+            Some(payload) => payload.detail(key, value).into(),
+            None => HealthErrorPayload::from(HealthError::InternalError(error.to_string()))
+                .detail(key, value)
+                .into(),
         }
     }
 
@@ -557,22 +1513,97 @@ pub mod types {
 /// - Substance abuse records
 /// - Genetic data
 ///
-/// NOTE: Field-level encryption is not implemented in this repository (a previous insecure
-/// placeholder was removed). Integrate a proper AEAD before storing PHI at rest.
+/// Version 3 fields are ChaCha20-Poly1305 with a random nonce; the version,
+/// the field type and the field's binding (patient, entry type and field
+/// name) are authenticated as associated data, so a ciphertext copied into
+/// another record or field fails to decrypt. Version 2 fields authenticate
+/// only the version and field type, so they could be read from anywhere;
+/// version 1 fields came from an XOR keystream placeholder. Neither is read
+/// here: both are re-encrypted through `migrate_legacy_field` with a
+/// decryptor the migration tool supplies.
+///
+/// Keys derived per patient and field type come from
+/// `key_management::derive_subkey`; fields record which derivation produced
+/// their key, so fields sealed under older derivations stay readable through
+/// `EncryptionKey::derive_for_field`.
 pub mod encryption {
     use super::*;
+    use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+    use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+
+    /// Version written by `encrypt_field`: bound to the field's location
+    pub const ENCRYPTION_VERSION: u8 = 3;
+
+    /// Bound to the field type only; readable only by a migration decryptor
+    pub const FIELD_TYPE_BOUND_VERSION: u8 = 2;
+
+    /// XOR keystream placeholder; readable only by a legacy decryptor
+    pub const LEGACY_XOR_VERSION: u8 = 1;
+
+    /// Key derivation used by `EncryptionKey::derive`: HKDF with a labelled subkey
+    pub const KEY_DERIVATION_VERSION: u8 = 2;
+
+    /// Iterated SHA-256 derivation used before HKDF
+    pub const LEGACY_KEY_DERIVATION_VERSION: u8 = 1;
+
+    const NONCE_LEN: usize = 12;
+
+    fn legacy_key_derivation() -> u8 {
+        LEGACY_KEY_DERIVATION_VERSION
+    }
 
     /// Encrypted field wrapper - stores ciphertext and nonce
     #[derive(Clone, Debug, Serialize, Deserialize)]
     pub struct EncryptedField {
-        /// Base64-encoded ciphertext
+        /// Base64-encoded ciphertext with the 16-byte Poly1305 tag appended
         pub ciphertext: String,
-        /// Base64-encoded nonce (12 bytes for GCM)
+        /// Base64-encoded nonce (12 bytes)
         pub nonce: String,
         /// Field type indicator for audit
         pub field_type: SensitiveFieldType,
         /// Version of encryption scheme
         pub version: u8,
+        /// Derivation that produced the key; fields written before it was
+        /// recorded used the legacy derivation
+        #[serde(default = "legacy_key_derivation")]
+        pub key_derivation: u8,
+        /// Master key the field key was derived from, when sealed through a key ring
+        #[serde(default)]
+        pub master_key_id: Option<String>,
+    }
+
+    impl EncryptedField {
+        /// Text form kept in an entry's string field
+        pub fn to_stored(&self) -> ExternResult<String> {
+            serde_json::to_string(self).map_err(types::internal_error)
+        }
+
+        pub fn from_stored(text: &str) -> ExternResult<Self> {
+            serde_json::from_str(text).map_err(|_| types::invalid_input("The field does not hold a sealed value"))
+        }
+    }
+
+    /// A top-level entry field as far as `stored_sealed_field` cares
+    #[derive(Debug, Deserialize)]
+    #[serde(untagged)]
+    enum StoredValue {
+        Text(String),
+        Other(serde::de::IgnoredAny),
+    }
+
+    /// The sealed field stored as text in `field_name` of an app entry
+    pub fn stored_sealed_field(entry: &Entry, field_name: &str) -> ExternResult<EncryptedField> {
+        let Entry::App(bytes) = entry else {
+            return Err(types::invalid_input("Sealed fields are stored in app entries"));
+        };
+        let fields: std::collections::HashMap<String, StoredValue> =
+            holochain_serialized_bytes::decode(bytes.bytes()).map_err(types::internal_error)?;
+        match fields.get(field_name) {
+            Some(StoredValue::Text(text)) => EncryptedField::from_stored(text),
+            _ => Err(HealthError::ValidationError("The entry has no sealed text field by that name".to_string())
+                .field(field_name)
+                .into()),
+        }
     }
 
     /// Types of sensitive fields that require encryption
@@ -588,17 +1619,50 @@ pub mod encryption {
         Other(String),
     }
 
+    /// Where an encrypted field lives
+    ///
+    /// Callers pass the binding of the record they read a field from, so a
+    /// ciphertext swapped in from another patient, entry type or field is
+    /// rejected instead of decrypted.
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct FieldBinding {
+        pub patient_hash: ActionHash,
+        /// Entry type holding the field, e.g. "Patient"
+        pub entry_type: String,
+        /// Name of the field within the entry, e.g. "ssn"
+        pub field_name: String,
+    }
+
+    impl FieldBinding {
+        pub fn new(patient_hash: ActionHash, entry_type: impl Into<String>, field_name: impl Into<String>) -> Self {
+            Self {
+                patient_hash,
+                entry_type: entry_type.into(),
+                field_name: field_name.into(),
+            }
+        }
+
+        fn check(&self) -> ExternResult<()> {
+            if self.entry_type.is_empty() || self.field_name.is_empty() {
+                return Err(types::invalid_input("Field bindings need an entry type and a field name"));
+            }
+            Ok(())
+        }
+    }
+
     /// Encryption key wrapper for secure handling
     #[derive(Clone)]
     pub struct EncryptionKey {
         /// 32-byte key material
         key_material: [u8; 32],
+        /// Derivation that produced the key material
+        derivation: u8,
     }
 
     impl EncryptionKey {
         /// Create a new encryption key from bytes
         pub fn new(bytes: [u8; 32]) -> Self {
-            Self { key_material: bytes }
+            Self { key_material: bytes, derivation: KEY_DERIVATION_VERSION }
         }
 
         /// Get the key bytes (use carefully)
@@ -608,35 +1672,64 @@ pub mod encryption {
 
         /// Derive a key from patient hash and master secret
         ///
-        /// This creates a patient-specific key by combining:
-        /// - Patient's action hash (unique per patient)
-        /// - Master key (from key management system)
-        /// - Field type (different key per field type)
+        /// The key is the master key's subkey labelled with the patient and
+        /// field type, so every patient and field type gets an independent key.
         pub fn derive(
             patient_hash: &ActionHash,
             master_key: &[u8; 32],
             field_type: &SensitiveFieldType,
+        ) -> Self {
+            let label = key_management::SubkeyLabel::Field {
+                patient_hash: patient_hash.clone(),
+                field_type: field_type.clone(),
+            };
+            Self {
+                key_material: key_management::derive_subkey(master_key, &label),
+                derivation: KEY_DERIVATION_VERSION,
+            }
+        }
+
+        /// Derive the key `encrypted` was sealed under
+        pub fn derive_for_field(
+            patient_hash: &ActionHash,
+            master_key: &[u8; 32],
+            encrypted: &EncryptedField,
+        ) -> ExternResult<Self> {
+            match encrypted.key_derivation {
+                KEY_DERIVATION_VERSION => Ok(Self::derive(patient_hash, master_key, &encrypted.field_type)),
+                LEGACY_KEY_DERIVATION_VERSION => Ok(Self::derive_legacy(patient_hash, master_key, &encrypted.field_type)),
+                other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Unsupported key derivation version {}",
+                    other
+                )))),
+            }
+        }
+
+        /// Iterated SHA-256 derivation; only for reading older fields
+        fn derive_legacy(
+            patient_hash: &ActionHash,
+            master_key: &[u8; 32],
+            field_type: &SensitiveFieldType,
         ) -> Self {
             let mut input = Vec::new();
             input.extend_from_slice(patient_hash.get_raw_39());
             input.extend_from_slice(master_key);
             input.extend_from_slice(format!("{:?}", field_type).as_bytes());
 
-            // Simple PBKDF2-like derivation using SHA-256
-            let mut key = [0u8; 32];
-            let hash = sha256_hash(&input);
-            key.copy_from_slice(&hash[..32]);
-
-            // Additional rounds for security
+            let mut key = sha256_hash(&input);
             for _ in 0..1000 {
                 let mut round_input = Vec::new();
                 round_input.extend_from_slice(&key);
                 round_input.extend_from_slice(master_key);
-                let hash = sha256_hash(&round_input);
-                key.copy_from_slice(&hash[..32]);
+                key = sha256_hash(&round_input);
             }
 
-            Self { key_material: key }
+            Self { key_material: key, derivation: LEGACY_KEY_DERIVATION_VERSION }
+        }
+
+        /// Derivation that produced this key
+        pub fn derivation(&self) -> u8 {
+            self.derivation
         }
     }
 
@@ -653,58 +1746,325 @@ pub mod encryption {
         out
     }
 
-    /// Encrypt a sensitive field value
-    ///
-    /// # Arguments
-    /// * `plaintext` - The sensitive data to encrypt
-    /// * `key` - The encryption key
-    /// * `field_type` - Type of field for audit purposes
-    ///
-    /// # Returns
-    /// Encrypted field struct with ciphertext and nonce
-    pub fn encrypt_field(
-        plaintext: &str,
-        key: &EncryptionKey,
-        field_type: SensitiveFieldType,
-    ) -> ExternResult<EncryptedField> {
-        let _ = (plaintext, key, field_type);
-        Err(wasm_error!(WasmErrorInner::Guest(
-            "Field-level encryption is not implemented (insecure placeholder removed)".to_string()
-        )))
-    }
+    /// HMAC-SHA256 (RFC 2104) over the concatenation of `message`
+    pub fn hmac_sha256(key: &[u8], message: &[&[u8]]) -> [u8; 32] {
+        use sha2::{Digest, Sha256};
 
-    /// Decrypt a sensitive field value
-    ///
-    /// # Arguments
-    /// * `encrypted` - The encrypted field struct
-    /// * `key` - The encryption key
-    ///
-    /// # Returns
-    /// Decrypted plaintext string
-    pub fn decrypt_field(
-        encrypted: &EncryptedField,
-        key: &EncryptionKey,
-    ) -> ExternResult<String> {
-        let _ = (encrypted, key);
-        Err(wasm_error!(WasmErrorInner::Guest(
-            "Field-level decryption is not implemented (insecure placeholder removed)".to_string()
-        )))
-    }
+        const BLOCK: usize = 64;
+        let mut block_key = [0u8; BLOCK];
+        if key.len() > BLOCK {
+            block_key[..32].copy_from_slice(&Sha256::digest(key));
+        } else {
+            block_key[..key.len()].copy_from_slice(key);
+        }
 
-    /// Base64 encode bytes
-    pub fn base64_encode(data: &[u8]) -> String {
-        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+        let mut inner = Sha256::new();
+        inner.update(block_key.map(|b| b ^ 0x36));
+        for part in message {
+            inner.update(part);
+        }
+        let mut outer = Sha256::new();
+        outer.update(block_key.map(|b| b ^ 0x5c));
+        outer.update(inner.finalize());
 
-        let mut result = String::new();
-        let mut i = 0;
+        let mut out = [0u8; 32];
+        out.copy_from_slice(&outer.finalize());
+        out
+    }
 
-        while i < data.len() {
-            let b0 = data[i] as usize;
-            let b1 = if i + 1 < data.len() { data[i + 1] as usize } else { 0 };
-            let b2 = if i + 2 < data.len() { data[i + 2] as usize } else { 0 };
+    /// Longest output `hkdf_expand` can produce (RFC 5869)
+    pub const HKDF_MAX_OUTPUT_BYTES: usize = 255 * 32;
 
-            result.push(ALPHABET[b0 >> 2] as char);
-            result.push(ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)] as char);
+    /// HKDF-Extract: concentrate `input_key` into a pseudorandom key
+    pub fn hkdf_extract(salt: &[u8], input_key: &[u8]) -> [u8; 32] {
+        hmac_sha256(salt, &[input_key])
+    }
+
+    /// HKDF-Expand: fill `output` from `pseudorandom_key`, bound to `info`
+    pub fn hkdf_expand(pseudorandom_key: &[u8; 32], info: &[u8], output: &mut [u8]) -> Result<(), String> {
+        if output.len() > HKDF_MAX_OUTPUT_BYTES {
+            return Err(format!("HKDF output is limited to {} bytes", HKDF_MAX_OUTPUT_BYTES));
+        }
+        let mut previous: Vec<u8> = Vec::new();
+        for (index, chunk) in output.chunks_mut(32).enumerate() {
+            let block = hmac_sha256(pseudorandom_key, &[&previous, info, &[index as u8 + 1]]);
+            chunk.copy_from_slice(&block[..chunk.len()]);
+            previous = block.to_vec();
+        }
+        Ok(())
+    }
+
+    /// PBKDF2-HMAC-SHA256 (RFC 8018) filling `output`
+    pub fn pbkdf2_hmac_sha256(password: &[u8], salt: &[u8], iterations: u32, output: &mut [u8]) {
+        for (index, chunk) in output.chunks_mut(32).enumerate() {
+            let block_number = (index as u32 + 1).to_be_bytes();
+            let mut u = hmac_sha256(password, &[salt, &block_number]);
+            let mut block = u;
+            for _ in 1..iterations {
+                u = hmac_sha256(password, &[&u]);
+                for (b, x) in block.iter_mut().zip(u.iter()) {
+                    *b ^= x;
+                }
+            }
+            chunk.copy_from_slice(&block[..chunk.len()]);
+        }
+    }
+
+    /// scrypt cost parameters (RFC 7914)
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct ScryptCost {
+        /// Base-2 log of the CPU/memory cost N
+        pub log_n: u8,
+        /// Block size; memory use is 128 * r * N bytes
+        pub r: u32,
+        /// Parallelization, run sequentially here
+        pub p: u32,
+    }
+
+    impl ScryptCost {
+        /// Bytes of working memory one derivation needs
+        pub fn memory_bytes(&self) -> u64 {
+            128 * self.r as u64 * (1u64 << self.log_n)
+        }
+    }
+
+    /// scrypt (RFC 7914): memory-hard derivation filling `output`
+    pub fn scrypt(password: &[u8], salt: &[u8], cost: &ScryptCost, output: &mut [u8]) -> Result<(), String> {
+        if cost.log_n == 0 || cost.log_n >= 32 || cost.r == 0 || cost.p == 0 {
+            return Err("Invalid scrypt cost parameters".to_string());
+        }
+        if (cost.r as u64) * (cost.p as u64) >= 1 << 30 {
+            return Err("scrypt r * p must be below 2^30".to_string());
+        }
+        let block_bytes = 128 * cost.r as usize;
+        let mut blocks = vec![0u8; block_bytes * cost.p as usize];
+        pbkdf2_hmac_sha256(password, salt, 1, &mut blocks);
+        for block in blocks.chunks_mut(block_bytes) {
+            scrypt_ro_mix(block, cost.r as usize, 1usize << cost.log_n);
+        }
+        pbkdf2_hmac_sha256(password, &blocks, 1, output);
+        Ok(())
+    }
+
+    fn scrypt_ro_mix(block: &mut [u8], r: usize, n: usize) {
+        let words = 32 * r;
+        let mut x: Vec<u32> = block
+            .chunks(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        let mut scratch = vec![0u32; words];
+        let mut v = vec![0u32; words * n];
+        for i in 0..n {
+            v[i * words..(i + 1) * words].copy_from_slice(&x);
+            scrypt_block_mix(&mut x, &mut scratch, r);
+        }
+        for _ in 0..n {
+            let j = x[(2 * r - 1) * 16] as usize & (n - 1);
+            for (a, b) in x.iter_mut().zip(&v[j * words..(j + 1) * words]) {
+                *a ^= b;
+            }
+            scrypt_block_mix(&mut x, &mut scratch, r);
+        }
+        for (chunk, word) in block.chunks_mut(4).zip(x) {
+            chunk.copy_from_slice(&word.to_le_bytes());
+        }
+    }
+
+    fn scrypt_block_mix(b: &mut [u32], scratch: &mut [u32], r: usize) {
+        let mut x = [0u32; 16];
+        x.copy_from_slice(&b[(2 * r - 1) * 16..2 * r * 16]);
+        for i in 0..2 * r {
+            for (a, w) in x.iter_mut().zip(&b[i * 16..(i + 1) * 16]) {
+                *a ^= w;
+            }
+            salsa20_8(&mut x);
+            // Even blocks go to the first half of the output, odd to the second
+            let target = (i / 2 + (i % 2) * r) * 16;
+            scratch[target..target + 16].copy_from_slice(&x);
+        }
+        b.copy_from_slice(scratch);
+    }
+
+    fn salsa20_8(block: &mut [u32; 16]) {
+        fn quarter(x: &mut [u32; 16], a: usize, b: usize, c: usize, d: usize) {
+            x[b] ^= x[a].wrapping_add(x[d]).rotate_left(7);
+            x[c] ^= x[b].wrapping_add(x[a]).rotate_left(9);
+            x[d] ^= x[c].wrapping_add(x[b]).rotate_left(13);
+            x[a] ^= x[d].wrapping_add(x[c]).rotate_left(18);
+        }
+        let mut x = *block;
+        for _ in 0..4 {
+            quarter(&mut x, 0, 4, 8, 12);
+            quarter(&mut x, 5, 9, 13, 1);
+            quarter(&mut x, 10, 14, 2, 6);
+            quarter(&mut x, 15, 3, 7, 11);
+            quarter(&mut x, 0, 1, 2, 3);
+            quarter(&mut x, 5, 6, 7, 4);
+            quarter(&mut x, 10, 11, 8, 9);
+            quarter(&mut x, 15, 12, 13, 14);
+        }
+        for (b, x) in block.iter_mut().zip(x) {
+            *b = b.wrapping_add(x);
+        }
+    }
+
+    /// HKDF-SHA256 (RFC 5869) with a single 32-byte output block
+    pub fn hkdf_sha256(salt: &[u8], input_key: &[u8], info: &[u8]) -> [u8; 32] {
+        let mut output = [0u8; 32];
+        // A single block is always within the output limit
+        let _ = hkdf_expand(&hkdf_extract(salt, input_key), info, &mut output);
+        output
+    }
+
+    /// Encrypt a sensitive field value
+    ///
+    /// # Arguments
+    /// * `plaintext` - The sensitive data to encrypt
+    /// * `key` - The encryption key
+    /// * `field_type` - Type of field for audit purposes
+    /// * `binding` - Record and field the ciphertext will be stored in
+    ///
+    /// # Returns
+    /// Encrypted field struct with ciphertext and nonce
+    pub fn encrypt_field(
+        plaintext: &str,
+        key: &EncryptionKey,
+        field_type: SensitiveFieldType,
+        binding: &FieldBinding,
+    ) -> ExternResult<EncryptedField> {
+        binding.check()?;
+        let mut nonce = [0u8; NONCE_LEN];
+        getrandom::fill(&mut nonce).map_err(|e| wasm_error!(WasmErrorInner::Guest(
+            format!("Failed to generate nonce: {:?}", e)
+        )))?;
+
+        let aad = associated_data(ENCRYPTION_VERSION, &field_type, binding);
+        let ciphertext = ChaCha20Poly1305::new(key.as_bytes().into())
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: plaintext.as_bytes(), aad: &aad })
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Encryption failed".to_string())))?;
+
+        Ok(EncryptedField {
+            ciphertext: base64_encode(&ciphertext),
+            nonce: base64_encode(&nonce),
+            field_type,
+            version: ENCRYPTION_VERSION,
+            key_derivation: key.derivation(),
+            master_key_id: None,
+        })
+    }
+
+    /// Decrypt a sensitive field value
+    ///
+    /// # Arguments
+    /// * `encrypted` - The encrypted field struct
+    /// * `key` - The encryption key
+    /// * `binding` - Record and field the ciphertext was read from; a
+    ///   field sealed for anywhere else fails to decrypt
+    ///
+    /// # Returns
+    /// Decrypted plaintext string
+    pub fn decrypt_field(
+        encrypted: &EncryptedField,
+        key: &EncryptionKey,
+        binding: &FieldBinding,
+    ) -> ExternResult<String> {
+        match encrypted.version {
+            ENCRYPTION_VERSION => binding.check()?,
+            FIELD_TYPE_BOUND_VERSION | LEGACY_XOR_VERSION => {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Version {} ciphertext must be migrated before it can be read",
+                    encrypted.version
+                ))))
+            }
+            other => {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Unsupported encryption version {}",
+                    other
+                ))))
+            }
+        };
+
+        let invalid = |what: &str| wasm_error!(WasmErrorInner::Guest(format!("Invalid {}", what)));
+        let nonce = base64_decode(&encrypted.nonce).map_err(|_| invalid("nonce"))?;
+        if nonce.len() != NONCE_LEN {
+            return Err(invalid("nonce"));
+        }
+        let ciphertext = base64_decode(&encrypted.ciphertext).map_err(|_| invalid("ciphertext"))?;
+
+        let aad = associated_data(encrypted.version, &encrypted.field_type, binding);
+        let plaintext = ChaCha20Poly1305::new(key.as_bytes().into())
+            .decrypt(Nonce::from_slice(&nonce), Payload { msg: &ciphertext, aad: &aad })
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest(
+                "Decryption failed: wrong key or tampered field".to_string()
+            )))?;
+        String::from_utf8(plaintext).map_err(|_| invalid("plaintext encoding"))
+    }
+
+    /// Whether a field predates the current encryption version
+    pub fn needs_migration(encrypted: &EncryptedField) -> bool {
+        encrypted.version < ENCRYPTION_VERSION
+    }
+
+    /// Re-encrypt an older field under the current version, bound to
+    /// `binding`
+    ///
+    /// `legacy_decrypt` reads version 1 and 2 ciphertext; it is supplied by
+    /// the migration tool, which knows where each field was stored, since
+    /// neither version can tell a moved field from one left in place.
+    /// Current-version fields are returned unchanged.
+    pub fn migrate_legacy_field(
+        encrypted: &EncryptedField,
+        key: &EncryptionKey,
+        binding: &FieldBinding,
+        legacy_decrypt: impl FnOnce(&EncryptedField, &EncryptionKey) -> ExternResult<String>,
+    ) -> ExternResult<EncryptedField> {
+        let plaintext = match encrypted.version {
+            ENCRYPTION_VERSION => return Ok(encrypted.clone()),
+            FIELD_TYPE_BOUND_VERSION | LEGACY_XOR_VERSION => legacy_decrypt(encrypted, key)?,
+            other => {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Unsupported encryption version {}",
+                    other
+                ))))
+            }
+        };
+        let mut migrated = encrypt_field(&plaintext, key, encrypted.field_type.clone(), binding)?;
+        migrated.master_key_id = encrypted.master_key_id.clone();
+        Ok(migrated)
+    }
+
+    /// Version, field type and the field's binding, authenticated
+    /// alongside the ciphertext
+    ///
+    /// Binding parts are length-prefixed so no two bindings encode alike.
+    fn associated_data(version: u8, field_type: &SensitiveFieldType, binding: &FieldBinding) -> Vec<u8> {
+        let mut aad = vec![version];
+        aad.extend_from_slice(format!("{:?}", field_type).as_bytes());
+        for part in [
+            binding.patient_hash.get_raw_39(),
+            binding.entry_type.as_bytes(),
+            binding.field_name.as_bytes(),
+        ] {
+            aad.extend_from_slice(&(part.len() as u32).to_be_bytes());
+            aad.extend_from_slice(part);
+        }
+        aad
+    }
+
+    /// Base64 encode bytes
+    pub fn base64_encode(data: &[u8]) -> String {
+        const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+        let mut result = String::new();
+        let mut i = 0;
+
+        while i < data.len() {
+            let b0 = data[i] as usize;
+            let b1 = if i + 1 < data.len() { data[i + 1] as usize } else { 0 };
+            let b2 = if i + 2 < data.len() { data[i + 2] as usize } else { 0 };
+
+            result.push(ALPHABET[b0 >> 2] as char);
+            result.push(ALPHABET[((b0 & 0x03) << 4) | (b1 >> 4)] as char);
 
             if i + 1 < data.len() {
                 result.push(ALPHABET[((b1 & 0x0f) << 2) | (b2 >> 6)] as char);
@@ -766,16 +2126,70 @@ pub mod encryption {
         Ok(result)
     }
 
-    /// Check if a data category requires encryption
-    pub fn requires_encryption(category: &access_control::DataCategory) -> bool {
-        matches!(
-            category,
-            access_control::DataCategory::MentalHealth
-                | access_control::DataCategory::SubstanceAbuse
-                | access_control::DataCategory::SexualHealth
-                | access_control::DataCategory::GeneticData
-                | access_control::DataCategory::FinancialData
-        )
+    /// Scheme written by `compute_blind_index`
+    pub const BLIND_INDEX_VERSION: u8 = 1;
+
+    /// Token bytes kept from the HMAC; enough to rule out accidental
+    /// collisions without storing the full tag
+    pub const BLIND_INDEX_BYTES: usize = 16;
+
+    /// Keyed exact-match token for an encrypted field
+    ///
+    /// Equal plaintexts (after normalization) give equal tokens under the
+    /// same index key, so zomes can link from `anchor()` and look a value up
+    /// without decrypting anything. Without the key the token reveals nothing.
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct BlindIndex {
+        /// Base64-encoded truncated HMAC-SHA256
+        pub token: String,
+        pub field_type: SensitiveFieldType,
+        /// Index key the token was computed under
+        pub key_id: String,
+        pub version: u8,
+    }
+
+    impl BlindIndex {
+        /// Anchor string zomes link from; scoped to the index key so tokens
+        /// from a rotated key never mix with current ones
+        pub fn anchor(&self) -> String {
+            format!("blind_index:v{}:{}:{:?}:{}", self.version, self.key_id, self.field_type, self.token)
+        }
+    }
+
+    /// Canonical form of a value before indexing, so formatting differences
+    /// do not defeat exact-match lookup
+    ///
+    /// SSNs keep only their digits; anything else is trimmed, has its
+    /// whitespace collapsed and is uppercased.
+    pub fn normalize_for_index(field_type: &SensitiveFieldType, plaintext: &str) -> String {
+        match field_type {
+            SensitiveFieldType::Ssn => plaintext.chars().filter(|c| c.is_ascii_digit()).collect(),
+            _ => plaintext.split_whitespace().collect::<Vec<_>>().join(" ").to_uppercase(),
+        }
+    }
+
+    /// Blind index of `plaintext` under a field type's index subkey
+    ///
+    /// `index_key` is the subkey from `key_management::blind_index_subkey`.
+    pub fn compute_blind_index(
+        index_key: &[u8; 32],
+        key_id: &str,
+        field_type: SensitiveFieldType,
+        plaintext: &str,
+    ) -> ExternResult<BlindIndex> {
+        let normalized = normalize_for_index(&field_type, plaintext);
+        if normalized.is_empty() {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Cannot index an empty value".to_string()
+            )));
+        }
+        let tag = hmac_sha256(index_key, &[&[BLIND_INDEX_VERSION], normalized.as_bytes()]);
+        Ok(BlindIndex {
+            token: base64_encode(&tag[..BLIND_INDEX_BYTES]),
+            field_type,
+            key_id: key_id.to_string(),
+            version: BLIND_INDEX_VERSION,
+        })
     }
 
     /// Map data category to sensitive field type
@@ -795,15 +2209,107 @@ pub mod encryption {
 
 /// Key management for field-level encryption
 ///
-/// This module handles secure storage and lifecycle management of encryption keys.
+/// This module handles secure storage and lifecycle management of encryption keys,
+/// and derives labelled subkeys so keys used across zomes stay independent.
 ///
-/// NOTE: Key wrapping/unwrapping is not implemented in this repository (a previous insecure
-/// placeholder was removed).
+/// Keys are wrapped with ChaCha20-Poly1305, either to the agent (under a
+/// key-encryption key only that agent's keystore can derive) or under a
+/// recovery passphrase with scrypt.
 pub mod key_management {
     use super::*;
+    use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+    use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+
+    /// Fingerprint scheme written by `create_key_metadata`
+    pub const KEY_FINGERPRINT_VERSION: u8 = 2;
+
+    /// Plain SHA-256 of the key, written before fingerprints were keyed
+    pub const LEGACY_KEY_FINGERPRINT_VERSION: u8 = 1;
+
+    fn legacy_key_fingerprint() -> u8 {
+        LEGACY_KEY_FINGERPRINT_VERSION
+    }
+
+    /// HKDF salt shared by every subkey of a master key
+    const SUBKEY_SALT: &[u8] = b"mycelix-health subkeys v1";
+
+    /// What a subkey is for; distinct labels give independent keys
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum SubkeyLabel {
+        /// Field-level encryption of one patient's field type
+        Field {
+            patient_hash: ActionHash,
+            field_type: super::encryption::SensitiveFieldType,
+        },
+        /// A zome-specific purpose, optionally scoped to one patient
+        Purpose {
+            zome: String,
+            purpose: String,
+            patient_hash: Option<ActionHash>,
+        },
+        /// Blind index tokens for one field type, shared across patients so
+        /// the same value indexes the same way for everyone
+        BlindIndex {
+            field_type: super::encryption::SensitiveFieldType,
+        },
+    }
+
+    impl SubkeyLabel {
+        /// Readable form of the label for audit logs
+        pub fn describe(&self) -> String {
+            match self {
+                SubkeyLabel::Field { patient_hash, field_type } => {
+                    format!("field/{}/{:?}", patient_hash, field_type)
+                }
+                SubkeyLabel::Purpose { zome, purpose, patient_hash: Some(patient_hash) } => {
+                    format!("purpose/{}/{}/{}", zome, purpose, patient_hash)
+                }
+                SubkeyLabel::Purpose { zome, purpose, patient_hash: None } => {
+                    format!("purpose/{}/{}", zome, purpose)
+                }
+                SubkeyLabel::BlindIndex { field_type } => format!("blind_index/{:?}", field_type),
+            }
+        }
+
+        /// HKDF info string: each component is length-prefixed, so no two
+        /// labels share an encoding
+        pub fn info(&self) -> Vec<u8> {
+            let mut parts: Vec<Vec<u8>> = Vec::new();
+            match self {
+                SubkeyLabel::Field { patient_hash, field_type } => {
+                    parts.push(b"field".to_vec());
+                    parts.push(patient_hash.get_raw_39().to_vec());
+                    parts.push(format!("{:?}", field_type).into_bytes());
+                }
+                SubkeyLabel::Purpose { zome, purpose, patient_hash } => {
+                    parts.push(b"purpose".to_vec());
+                    parts.push(zome.as_bytes().to_vec());
+                    parts.push(purpose.as_bytes().to_vec());
+                    if let Some(patient_hash) = patient_hash {
+                        parts.push(patient_hash.get_raw_39().to_vec());
+                    }
+                }
+                SubkeyLabel::BlindIndex { field_type } => {
+                    parts.push(b"blind_index".to_vec());
+                    parts.push(format!("{:?}", field_type).into_bytes());
+                }
+            }
+            let mut info = b"mycelix-health".to_vec();
+            for part in parts {
+                info.extend_from_slice(&(part.len() as u32).to_be_bytes());
+                info.extend_from_slice(&part);
+            }
+            info
+        }
+    }
+
+    /// Derive the subkey of `master_key` for `label` (HKDF-SHA256)
+    pub fn derive_subkey(master_key: &[u8; 32], label: &SubkeyLabel) -> [u8; 32] {
+        super::encryption::hkdf_sha256(SUBKEY_SALT, master_key, &label.info())
+    }
 
     /// Key metadata stored in DHT
-    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
     pub struct KeyMetadata {
         /// Unique key identifier
         pub key_id: String,
@@ -817,6 +2323,20 @@ pub mod key_management {
         pub version: u32,
         /// Hash of the wrapped key (for verification)
         pub key_hash: String,
+        /// Scheme that produced `key_hash`
+        #[serde(default = "legacy_key_fingerprint")]
+        pub fingerprint_version: u8,
+        /// Passphrase derivation, when the key is wrapped under a recovery passphrase
+        #[serde(default)]
+        pub passphrase_kdf: Option<PassphraseKdf>,
+    }
+
+    /// Memory-hard derivation of a key-encryption key from a passphrase
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct PassphraseKdf {
+        pub cost: super::encryption::ScryptCost,
+        /// Base64-encoded random salt
+        pub salt: String,
     }
 
     /// Wrapped (encrypted) key for secure storage
@@ -824,14 +2344,14 @@ pub mod key_management {
     pub struct WrappedKey {
         /// Key metadata
         pub metadata: KeyMetadata,
-        /// Encrypted key material (encrypted with agent's public key)
+        /// Base64-encoded key material sealed under the key-encryption key
         pub encrypted_key: String,
         /// Nonce used for encryption
         pub nonce: String,
     }
 
     /// Key rotation event for audit trail
-    #[derive(Clone, Debug, Serialize, Deserialize)]
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
     pub struct KeyRotationEvent {
         pub old_key_id: String,
         pub new_key_id: String,
@@ -857,19 +2377,11 @@ pub mod key_management {
     pub fn create_key_metadata(key: &[u8; 32], version: u32) -> ExternResult<KeyMetadata> {
         let now = sys_time()?;
 
-        // Generate key ID from hash of key + timestamp
-        let mut id_input = Vec::new();
-        id_input.extend_from_slice(key);
-        id_input.extend_from_slice(&now.as_micros().to_le_bytes());
-        let id_hash = super::encryption::sha256_hash(&id_input);
-        let key_id = format!("KEY-{:02x}{:02x}{:02x}{:02x}",
-            id_hash[0], id_hash[1], id_hash[2], id_hash[3]);
-
-        // Hash the key for verification
-        let key_hash_bytes = super::encryption::sha256_hash(key);
-        let key_hash = format!("{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}{:02x}",
-            key_hash_bytes[0], key_hash_bytes[1], key_hash_bytes[2], key_hash_bytes[3],
-            key_hash_bytes[4], key_hash_bytes[5], key_hash_bytes[6], key_hash_bytes[7]);
+        // Key ID and fingerprint are MACs under the key itself, so neither
+        // reveals a plain hash of the key material
+        let id_hash = super::encryption::hmac_sha256(key, &[b"key-id", &now.as_micros().to_le_bytes()]);
+        let key_id = format!("KEY-{}", hex_prefix(&id_hash, id_hash.len()));
+        let key_hash = key_fingerprint(key, KEY_FINGERPRINT_VERSION)?;
 
         // Set expiration to 1 year from now
         let one_year_micros = 365 * 24 * 60 * 60 * 1_000_000i64;
@@ -882,109 +2394,656 @@ pub mod key_management {
             is_active: true,
             version,
             key_hash,
+            fingerprint_version: KEY_FINGERPRINT_VERSION,
+            passphrase_kdf: None,
         })
     }
 
-    /// Wrap a key for secure storage
-    pub fn wrap_key(
-        key: &[u8; 32],
-        metadata: KeyMetadata,
-        agent: &AgentPubKey,
-    ) -> ExternResult<WrappedKey> {
-        let _ = (key, metadata, agent);
-        Err(wasm_error!(WasmErrorInner::Guest(
-            "Key wrapping is not implemented (insecure placeholder removed)".to_string()
-        )))
+    /// Fingerprint of `key` under the given scheme, as stored in `key_hash`
+    ///
+    /// Keyed fingerprints keep the whole tag; legacy ones were cut to 8 bytes.
+    pub fn key_fingerprint(key: &[u8; 32], fingerprint_version: u8) -> ExternResult<String> {
+        match fingerprint_version {
+            KEY_FINGERPRINT_VERSION => {
+                let tag = super::encryption::hmac_sha256(key, &[b"key-fingerprint"]);
+                Ok(hex_prefix(&tag, tag.len()))
+            }
+            LEGACY_KEY_FINGERPRINT_VERSION => Ok(hex_prefix(&super::encryption::sha256_hash(key), 8)),
+            other => Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Unsupported key fingerprint version {}",
+                other
+            )))),
+        }
     }
 
-    /// Unwrap a key for use
-    pub fn unwrap_key(
-        wrapped: &WrappedKey,
-        agent: &AgentPubKey,
-    ) -> ExternResult<[u8; 32]> {
-        let _ = (wrapped, agent);
-        Err(wasm_error!(WasmErrorInner::Guest(
-            "Key unwrapping is not implemented (insecure placeholder removed)".to_string()
-        )))
+    /// Whether `key` is the key `metadata` describes
+    pub fn key_matches_metadata(key: &[u8; 32], metadata: &KeyMetadata) -> ExternResult<bool> {
+        Ok(key_fingerprint(key, metadata.fingerprint_version)? == metadata.key_hash)
     }
 
-    /// Check if a key should be rotated
-    pub fn should_rotate_key(metadata: &KeyMetadata) -> ExternResult<bool> {
-        if let Some(expires_at) = metadata.expires_at {
-            let now = sys_time()?;
-            // Rotate 30 days before expiration
-            let thirty_days = 30 * 24 * 60 * 60 * 1_000_000i64;
-            let rotation_threshold = expires_at.as_micros() - thirty_days;
-            return Ok(now.as_micros() as i64 >= rotation_threshold);
-        }
-        Ok(false)
+    fn hex_prefix(bytes: &[u8], len: usize) -> String {
+        bytes.iter().take(len).map(|b| format!("{:02x}", b)).collect()
     }
 
-}
+    /// Default recovery passphrase cost: 32 MiB of memory per derivation
+    pub const DEFAULT_PASSPHRASE_COST: super::encryption::ScryptCost =
+        super::encryption::ScryptCost { log_n: 15, r: 8, p: 1 };
 
-/// Anchor utilities for consistent indexing
-pub mod anchors {
-    use super::*;
+    /// Weakest cost accepted when wrapping
+    pub const MIN_PASSPHRASE_COST: super::encryption::ScryptCost =
+        super::encryption::ScryptCost { log_n: 14, r: 8, p: 1 };
 
-    /// Standard anchor entry type
-    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
-    pub struct Anchor(pub String);
+    /// Most memory a stored cost may demand on unwrap, so tampered metadata
+    /// cannot exhaust the zome's memory
+    pub const MAX_PASSPHRASE_MEMORY_BYTES: u64 = 256 * 1024 * 1024;
 
-    /// Get the entry hash for an anchor by hashing the serialized bytes
-    pub fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
-        // Serialize the anchor to bytes
-        let anchor = Anchor(anchor_text.to_string());
-        let bytes = serde_json::to_vec(&anchor)
+    pub const RECOVERY_MIN_PASSPHRASE_CHARS: usize = 12;
+
+    const PASSPHRASE_SALT_LEN: usize = 16;
+    const WRAP_NONCE_LEN: usize = 12;
+
+    /// Wrap `key` under a recovery passphrase for device-loss recovery
+    ///
+    /// The key-encryption key comes from scrypt over the passphrase; its cost
+    /// and salt are stored in the returned metadata so the wrap can be opened
+    /// later with the passphrase alone.
+    pub fn wrap_key_with_passphrase(
+        key: &[u8; 32],
+        mut metadata: KeyMetadata,
+        passphrase: &str,
+        cost: super::encryption::ScryptCost,
+    ) -> ExternResult<WrappedKey> {
+        if passphrase.chars().count() < RECOVERY_MIN_PASSPHRASE_CHARS {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Recovery passphrases need at least {} characters",
+                RECOVERY_MIN_PASSPHRASE_CHARS
+            ))));
+        }
+        if cost.log_n < MIN_PASSPHRASE_COST.log_n || cost.r < MIN_PASSPHRASE_COST.r || cost.p < MIN_PASSPHRASE_COST.p {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Passphrase cost is below the minimum".to_string()
+            )));
+        }
+        if !key_matches_metadata(key, &metadata)? {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Key does not match its metadata".to_string()
+            )));
+        }
+
+        let mut salt = [0u8; PASSPHRASE_SALT_LEN];
+        getrandom::fill(&mut salt)
             .map_err(|e| wasm_error!(WasmErrorInner::Guest(
-                format!("Failed to serialize anchor: {}", e)
+                format!("Failed to generate wrapping salt: {:?}", e)
             )))?;
+        metadata.passphrase_kdf = Some(PassphraseKdf {
+            cost,
+            salt: super::encryption::base64_encode(&salt),
+        });
 
-        // Create an entry hash from the bytes using the host function
-        // This matches how other zomes create anchor hashes
-        let entry = Entry::App(AppEntryBytes::try_from(SerializedBytes::try_from(UnsafeBytes::from(bytes))
-            .map_err(|e| wasm_error!(WasmErrorInner::Guest(
-                format!("Failed to create serialized bytes: {:?}", e)
-            )))?)
-            .map_err(|e| wasm_error!(WasmErrorInner::Guest(
-                format!("Failed to create app entry bytes: {:?}", e)
-            )))?);
+        let kek = passphrase_kek(passphrase, &salt, &cost)?;
+        seal_wrapped_key(key, metadata, &kek)
+    }
 
-        hash_entry(entry)
+    /// Recover a key wrapped by `wrap_key_with_passphrase`
+    pub fn unwrap_key_with_passphrase(wrapped: &WrappedKey, passphrase: &str) -> ExternResult<[u8; 32]> {
+        let invalid = |what: &str| wasm_error!(WasmErrorInner::Guest(format!("Invalid {}", what)));
+        let kdf = wrapped.metadata.passphrase_kdf.as_ref()
+            .ok_or(wasm_error!(WasmErrorInner::Guest("Key is not passphrase-wrapped".to_string())))?;
+        if kdf.cost.memory_bytes() > MAX_PASSPHRASE_MEMORY_BYTES || kdf.cost.p > 16 {
+            return Err(invalid("passphrase cost"));
+        }
+        let salt = super::encryption::base64_decode(&kdf.salt).map_err(|_| invalid("salt"))?;
+        let kek = passphrase_kek(passphrase, &salt, &kdf.cost)?;
+        open_wrapped_key(wrapped, &kek)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest(
+                "Unwrapping failed: wrong passphrase or tampered key".to_string()
+            )))
     }
 
-    /// Create a sharded anchor for scalable indexing
-    ///
-    /// Instead of one global anchor, uses first character to create 26+ anchors
-    pub fn sharded_anchor_hash(prefix: &str, key: &str) -> ExternResult<EntryHash> {
-        let shard_char = key
-            .chars()
-            .next()
-            .unwrap_or('_')
-            .to_uppercase()
-            .next()
-            .unwrap_or('_');
+    fn passphrase_kek(passphrase: &str, salt: &[u8], cost: &super::encryption::ScryptCost) -> ExternResult<[u8; 32]> {
+        let mut kek = [0u8; 32];
+        super::encryption::scrypt(passphrase.as_bytes(), salt, cost, &mut kek)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))?;
+        Ok(kek)
+    }
 
-        anchor_hash(&format!("{}_{}", prefix, shard_char))
+    /// Metadata bound to the wrapped key, so its ID, fingerprint, and cost
+    /// cannot be swapped without failing the unwrap
+    fn wrap_associated_data(metadata: &KeyMetadata) -> Vec<u8> {
+        let mut aad = Vec::new();
+        for part in [metadata.key_id.as_bytes(), metadata.key_hash.as_bytes()] {
+            aad.extend_from_slice(&(part.len() as u32).to_be_bytes());
+            aad.extend_from_slice(part);
+        }
+        aad.push(metadata.fingerprint_version);
+        if let Some(kdf) = &metadata.passphrase_kdf {
+            aad.push(kdf.cost.log_n);
+            aad.extend_from_slice(&kdf.cost.r.to_be_bytes());
+            aad.extend_from_slice(&kdf.cost.p.to_be_bytes());
+            aad.extend_from_slice(kdf.salt.as_bytes());
+        }
+        aad
     }
 
-    /// Get all shard anchors for a given prefix (for bulk operations)
-    pub fn all_shard_anchors(prefix: &str) -> Vec<String> {
-        let mut anchors = Vec::new();
-        for c in 'A'..='Z' {
-            anchors.push(format!("{}_{}", prefix, c));
+    /// Seal `key` under a key-encryption key, binding its metadata
+    fn seal_wrapped_key(key: &[u8; 32], metadata: KeyMetadata, kek: &[u8; 32]) -> ExternResult<WrappedKey> {
+        if !key_matches_metadata(key, &metadata)? {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Key does not match its metadata".to_string()
+            )));
         }
-        anchors.push(format!("{}__", prefix)); // For non-alpha characters
-        anchors
+        let mut nonce = [0u8; WRAP_NONCE_LEN];
+        getrandom::fill(&mut nonce)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(
+                format!("Failed to generate wrapping nonce: {:?}", e)
+            )))?;
+        let encrypted_key = ChaCha20Poly1305::new(kek.into())
+            .encrypt(Nonce::from_slice(&nonce), Payload { msg: key, aad: &wrap_associated_data(&metadata) })
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest("Key wrapping failed".to_string())))?;
+
+        Ok(WrappedKey {
+            metadata,
+            encrypted_key: super::encryption::base64_encode(&encrypted_key),
+            nonce: super::encryption::base64_encode(&nonce),
+        })
     }
-}
 
-/// Input validation module - ensures data quality and security
-///
-/// Provides validators for:
-/// - Medical Record Numbers (MRN)
-/// - Decentralized Identifiers (DID)
-/// - Score ranges for instruments
-/// - Mental health screening responses
+    /// Open a key sealed by `seal_wrapped_key` and check it against its metadata
+    fn open_wrapped_key(wrapped: &WrappedKey, kek: &[u8; 32]) -> ExternResult<[u8; 32]> {
+        let invalid = |what: &str| wasm_error!(WasmErrorInner::Guest(format!("Invalid {}", what)));
+        let nonce = super::encryption::base64_decode(&wrapped.nonce).map_err(|_| invalid("nonce"))?;
+        if nonce.len() != WRAP_NONCE_LEN {
+            return Err(invalid("nonce"));
+        }
+        let encrypted_key = super::encryption::base64_decode(&wrapped.encrypted_key)
+            .map_err(|_| invalid("wrapped key"))?;
+        let key_bytes = ChaCha20Poly1305::new(kek.into())
+            .decrypt(
+                Nonce::from_slice(&nonce),
+                Payload { msg: &encrypted_key, aad: &wrap_associated_data(&wrapped.metadata) },
+            )
+            .map_err(|_| invalid("wrapped key"))?;
+        let key: [u8; 32] = key_bytes.try_into().map_err(|_| invalid("wrapped key"))?;
+        if !key_matches_metadata(&key, &wrapped.metadata)? {
+            return Err(invalid("wrapped key"));
+        }
+        Ok(key)
+    }
+
+    /// Salt for key-encryption keys derived from agent signatures
+    const AGENT_KEK_SALT: &[u8] = b"mycelix-health/agent-kek/v1";
+
+    /// Key-encryption key only `agent`'s keystore can derive
+    ///
+    /// HKDF over the agent's signature of a label naming the key. Ed25519
+    /// signatures are deterministic, so the agent gets the same KEK back on
+    /// every unwrap, while the signature itself is never published.
+    fn agent_kek(agent: &AgentPubKey, metadata: &KeyMetadata) -> ExternResult<[u8; 32]> {
+        let label = [b"key-wrap/".as_slice(), metadata.key_id.as_bytes()].concat();
+        let signature = sign_raw(agent.clone(), label)?;
+        Ok(super::encryption::hkdf_sha256(AGENT_KEK_SALT, &signature.0, b"key-encryption-key"))
+    }
+
+    /// Wrap a key for secure storage on `agent`'s chain
+    ///
+    /// `agent` must be this cell's agent, since only its keystore can sign
+    /// for the key-encryption key.
+    pub fn wrap_key(
+        key: &[u8; 32],
+        mut metadata: KeyMetadata,
+        agent: &AgentPubKey,
+    ) -> ExternResult<WrappedKey> {
+        metadata.passphrase_kdf = None;
+        let kek = agent_kek(agent, &metadata)?;
+        seal_wrapped_key(key, metadata, &kek)
+    }
+
+    /// Unwrap a key wrapped by `wrap_key` for the same agent
+    pub fn unwrap_key(
+        wrapped: &WrappedKey,
+        agent: &AgentPubKey,
+    ) -> ExternResult<[u8; 32]> {
+        if wrapped.metadata.passphrase_kdf.is_some() {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Key is passphrase-wrapped; use unwrap_key_with_passphrase".to_string()
+            )));
+        }
+        let kek = agent_kek(agent, &wrapped.metadata)?;
+        open_wrapped_key(wrapped, &kek)
+            .map_err(|_| wasm_error!(WasmErrorInner::Guest(
+                "Unwrapping failed: wrong agent or tampered key".to_string()
+            )))
+    }
+
+    /// A master key and its metadata
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct MasterKey {
+        pub metadata: KeyMetadata,
+        pub key: [u8; 32],
+    }
+
+    /// The master key new fields are sealed under, plus rotated-out keys
+    /// retained until every field sealed under them has been resealed
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct MasterKeyRing {
+        pub active: MasterKey,
+        pub retained: Vec<MasterKey>,
+    }
+
+    /// A field read through a key ring
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct OpenedField {
+        pub plaintext: String,
+        /// The field sealed under the active key, when the stored copy is
+        /// stale; the caller stores it in place of the original
+        pub resealed: Option<super::encryption::EncryptedField>,
+    }
+
+    /// Seal a field under the ring's active master key, bound to where it
+    /// will be stored
+    pub fn seal_field(
+        plaintext: &str,
+        binding: &super::encryption::FieldBinding,
+        field_type: super::encryption::SensitiveFieldType,
+        ring: &MasterKeyRing,
+    ) -> ExternResult<super::encryption::EncryptedField> {
+        let key = super::encryption::EncryptionKey::derive(&binding.patient_hash, &ring.active.key, &field_type);
+        let mut sealed = super::encryption::encrypt_field(plaintext, &key, field_type, binding)?;
+        sealed.master_key_id = Some(ring.active.metadata.key_id.clone());
+        Ok(sealed)
+    }
+
+    /// Open a field read from `binding` with whichever ring key sealed it,
+    /// resealing it under the active key if it was sealed under a retained
+    /// key or an older derivation
+    pub fn open_field(
+        field: &super::encryption::EncryptedField,
+        binding: &super::encryption::FieldBinding,
+        ring: &MasterKeyRing,
+    ) -> ExternResult<OpenedField> {
+        let candidates: Vec<&MasterKey> = match &field.master_key_id {
+            Some(key_id) => std::iter::once(&ring.active)
+                .chain(ring.retained.iter())
+                .filter(|master| &master.metadata.key_id == key_id)
+                .collect(),
+            // Fields sealed before key ids were recorded: try every key
+            None => std::iter::once(&ring.active).chain(ring.retained.iter()).collect(),
+        };
+        if candidates.is_empty() {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "The master key this field was sealed under is no longer retained".to_string()
+            )));
+        }
+
+        let mut last_error = None;
+        for master in candidates {
+            let key = super::encryption::EncryptionKey::derive_for_field(&binding.patient_hash, &master.key, field)?;
+            match super::encryption::decrypt_field(field, &key, binding) {
+                Ok(plaintext) => {
+                    let stale = master.metadata.key_id != ring.active.metadata.key_id
+                        || field.master_key_id.is_none()
+                        || field.key_derivation != super::encryption::KEY_DERIVATION_VERSION;
+                    let resealed = if stale {
+                        Some(seal_field(&plaintext, binding, field.field_type.clone(), ring)?)
+                    } else {
+                        None
+                    };
+                    return Ok(OpenedField { plaintext, resealed });
+                }
+                Err(e) => last_error = Some(e),
+            }
+        }
+        Err(last_error.unwrap_or(wasm_error!(WasmErrorInner::Guest("Decryption failed".to_string()))))
+    }
+
+    /// Subkey of a blind index key for one field type
+    pub fn blind_index_subkey(index_key: &[u8; 32], field_type: &super::encryption::SensitiveFieldType) -> [u8; 32] {
+        derive_subkey(index_key, &SubkeyLabel::BlindIndex { field_type: field_type.clone() })
+    }
+
+    /// Blind index of a value under an index key
+    ///
+    /// Index keys are separate from field master keys: a field key is the
+    /// patient's alone, while an index key is held by the admin who indexes
+    /// and searches, so that one token finds the value across patients.
+    /// Values are only found under the key they were indexed with.
+    pub fn blind_index_for(
+        plaintext: &str,
+        field_type: super::encryption::SensitiveFieldType,
+        index_key: &MasterKey,
+    ) -> ExternResult<super::encryption::BlindIndex> {
+        let subkey = blind_index_subkey(&index_key.key, &field_type);
+        super::encryption::compute_blind_index(&subkey, &index_key.metadata.key_id, field_type, plaintext)
+    }
+
+    /// A sealed field and the blind index to link it from
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct SearchableField {
+        pub field: super::encryption::EncryptedField,
+        pub index: super::encryption::BlindIndex,
+    }
+
+    /// Seal a field under the ring's active key and index it under `index_key`
+    pub fn seal_searchable_field(
+        plaintext: &str,
+        binding: &super::encryption::FieldBinding,
+        field_type: super::encryption::SensitiveFieldType,
+        ring: &MasterKeyRing,
+        index_key: &MasterKey,
+    ) -> ExternResult<SearchableField> {
+        Ok(SearchableField {
+            index: blind_index_for(plaintext, field_type.clone(), index_key)?,
+            field: seal_field(plaintext, binding, field_type, ring)?,
+        })
+    }
+
+    /// Check if a key should be rotated
+    pub fn should_rotate_key(metadata: &KeyMetadata) -> ExternResult<bool> {
+        if let Some(expires_at) = metadata.expires_at {
+            let now = sys_time()?;
+            // Rotate 30 days before expiration
+            let thirty_days = 30 * 24 * 60 * 60 * 1_000_000i64;
+            let rotation_threshold = expires_at.as_micros() - thirty_days;
+            return Ok(now.as_micros() as i64 >= rotation_threshold);
+        }
+        Ok(false)
+    }
+
+    /// Shamir secret sharing of master keys with designated recovery agents
+    ///
+    /// Shares are points on random degree `threshold - 1` polynomials over
+    /// GF(256), one polynomial per secret byte; any `threshold` shares
+    /// rebuild the secret and fewer reveal nothing about it.
+    pub mod escrow {
+        use super::*;
+
+        /// Who holds a share
+        #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+        pub enum RecoveryRole {
+            Guardian,
+            Provider,
+            Platform,
+        }
+
+        /// One share of a split secret; `index` is the x coordinate and never 0
+        #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+        pub struct SecretShare {
+            pub index: u8,
+            #[serde(with = "serde_bytes")]
+            pub value: Vec<u8>,
+        }
+
+        /// Split `secret` into `shares` shares, any `threshold` of which rebuild it
+        pub fn split_secret(secret: &[u8], threshold: u8, shares: u8) -> ExternResult<Vec<SecretShare>> {
+            let mut failure = None;
+            let split = split_secret_with(secret, threshold, shares, |bytes| {
+                if let Err(e) = getrandom::fill(bytes) {
+                    failure = Some(e);
+                }
+            });
+            if let Some(e) = failure {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Failed to generate share coefficients: {:?}",
+                    e
+                ))));
+            }
+            split.map_err(|e| wasm_error!(WasmErrorInner::Guest(e)))
+        }
+
+        /// `split_secret` with the coefficient source supplied by the caller
+        pub fn split_secret_with(
+            secret: &[u8],
+            threshold: u8,
+            shares: u8,
+            mut fill_random: impl FnMut(&mut [u8]),
+        ) -> Result<Vec<SecretShare>, String> {
+            if threshold < 2 || shares < threshold {
+                return Err("Escrow needs a threshold of at least 2 and at least that many shares".to_string());
+            }
+            if secret.is_empty() {
+                return Err("Nothing to split".to_string());
+            }
+            let mut result: Vec<SecretShare> = (1..=shares)
+                .map(|index| SecretShare { index, value: Vec::with_capacity(secret.len()) })
+                .collect();
+            let mut coefficients = vec![0u8; threshold as usize];
+            for &byte in secret {
+                coefficients[0] = byte;
+                fill_random(&mut coefficients[1..]);
+                for share in &mut result {
+                    // Horner's rule, highest coefficient first
+                    let value = coefficients.iter().rev().fold(0u8, |acc, &c| gf_mul(acc, share.index) ^ c);
+                    share.value.push(value);
+                }
+            }
+            Ok(result)
+        }
+
+        /// Rebuild a secret from at least `threshold` distinct shares
+        pub fn combine_shares(shares: &[SecretShare], threshold: u8) -> Result<Vec<u8>, String> {
+            let mut distinct: Vec<&SecretShare> = Vec::new();
+            for share in shares {
+                if share.index == 0 {
+                    return Err("Share index 0 is invalid".to_string());
+                }
+                if !distinct.iter().any(|s| s.index == share.index) {
+                    distinct.push(share);
+                }
+            }
+            if threshold < 2 || distinct.len() < threshold as usize {
+                return Err(format!("{} distinct shares are needed, {} given", threshold, distinct.len()));
+            }
+            let used = &distinct[..threshold as usize];
+            let len = used[0].value.len();
+            if used.iter().any(|share| share.value.len() != len) {
+                return Err("Shares are of different secrets".to_string());
+            }
+
+            // Lagrange interpolation at x = 0; subtraction is XOR in GF(256)
+            let weights: Vec<u8> = used
+                .iter()
+                .map(|share| {
+                    used.iter().filter(|other| other.index != share.index).fold(1u8, |weight, other| {
+                        gf_mul(weight, gf_div(other.index, other.index ^ share.index))
+                    })
+                })
+                .collect();
+            Ok((0..len)
+                .map(|position| {
+                    used.iter()
+                        .zip(&weights)
+                        .fold(0u8, |acc, (share, &weight)| acc ^ gf_mul(share.value[position], weight))
+                })
+                .collect())
+        }
+
+        /// Multiplication in GF(256) modulo x^8 + x^4 + x^3 + x + 1
+        fn gf_mul(mut a: u8, mut b: u8) -> u8 {
+            let mut product = 0u8;
+            while b != 0 {
+                if b & 1 != 0 {
+                    product ^= a;
+                }
+                let carry = a & 0x80 != 0;
+                a <<= 1;
+                if carry {
+                    a ^= 0x1b;
+                }
+                b >>= 1;
+            }
+            product
+        }
+
+        /// `a / b` for non-zero `b`, via b^254 = b^-1
+        fn gf_div(a: u8, b: u8) -> u8 {
+            let mut inverse = 1u8;
+            for _ in 0..254 {
+                inverse = gf_mul(inverse, b);
+            }
+            gf_mul(a, inverse)
+        }
+    }
+
+}
+
+/// Anchor utilities for consistent indexing
+pub mod anchors {
+    use super::*;
+
+    /// Standard anchor entry type
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct Anchor(pub String);
+
+    /// Get the entry hash for an anchor by hashing the serialized bytes
+    pub fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
+        // Serialize the anchor to bytes
+        let anchor = Anchor(anchor_text.to_string());
+        let bytes = serde_json::to_vec(&anchor)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(
+                format!("Failed to serialize anchor: {}", e)
+            )))?;
+
+        // Create an entry hash from the bytes using the host function
+        // This matches how other zomes create anchor hashes
+        let entry = Entry::App(AppEntryBytes::try_from(SerializedBytes::try_from(UnsafeBytes::from(bytes))
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(
+                format!("Failed to create serialized bytes: {:?}", e)
+            )))?)
+            .map_err(|e| wasm_error!(WasmErrorInner::Guest(
+                format!("Failed to create app entry bytes: {:?}", e)
+            )))?);
+
+        hash_entry(entry)
+    }
+
+    /// Create a sharded anchor for scalable indexing
+    ///
+    /// Instead of one global anchor, uses first character to create 26+ anchors
+    pub fn sharded_anchor_hash(prefix: &str, key: &str) -> ExternResult<EntryHash> {
+        let shard_char = key
+            .chars()
+            .next()
+            .unwrap_or('_')
+            .to_uppercase()
+            .next()
+            .unwrap_or('_');
+
+        anchor_hash(&format!("{}_{}", prefix, shard_char))
+    }
+
+    /// Get all shard anchors for a given prefix (for bulk operations)
+    pub fn all_shard_anchors(prefix: &str) -> Vec<String> {
+        let mut anchors = Vec::new();
+        for c in 'A'..='Z' {
+            anchors.push(format!("{}_{}", prefix, c));
+        }
+        anchors.push(format!("{}__", prefix)); // For non-alpha characters
+        anchors
+    }
+
+    const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+    /// Most buckets a range scan will visit
+    pub const MAX_PERIOD_BUCKETS: usize = 400;
+
+    /// Calendar span of a time-bucketed anchor, in UTC
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum AnchorPeriod {
+        Day,
+        Month,
+    }
+
+    /// Anchor text for the bucket holding `at` ("access_logs:2026-03-14"
+    /// for a day, "access_logs:2026-03" for a month)
+    pub fn period_anchor(prefix: &str, at: Timestamp, period: AnchorPeriod) -> String {
+        let date = localization::local_date_time(at, 0);
+        match period {
+            AnchorPeriod::Day => format!("{}:{:04}-{:02}-{:02}", prefix, date.year, date.month, date.day),
+            AnchorPeriod::Month => format!("{}:{}", prefix, localization::month_key(&date)),
+        }
+    }
+
+    /// Anchor hash for the bucket holding `at`, so a link set grows one
+    /// bucket at a time instead of on a single base
+    pub fn anchor_for_period(prefix: &str, at: Timestamp, period: AnchorPeriod) -> ExternResult<EntryHash> {
+        anchor_hash(&period_anchor(prefix, at, period))
+    }
+
+    /// Every bucket anchor from the one holding `from` through the one
+    /// holding `to`, oldest first
+    pub fn period_anchors_between(prefix: &str, period: AnchorPeriod, from: Timestamp, to: Timestamp) -> ExternResult<Vec<String>> {
+        if to < from {
+            return Ok(Vec::new());
+        }
+        let buckets = match period {
+            AnchorPeriod::Day => {
+                to.as_micros().div_euclid(MICROS_PER_DAY) - from.as_micros().div_euclid(MICROS_PER_DAY) + 1
+            }
+            AnchorPeriod::Month => {
+                let month_index = |at: Timestamp| {
+                    let date = localization::local_date_time(at, 0);
+                    date.year * 12 + date.month as i64 - 1
+                };
+                month_index(to) - month_index(from) + 1
+            }
+        };
+        if buckets as usize > MAX_PERIOD_BUCKETS {
+            return Err(HealthError::LimitExceeded(format!(
+                "Range spans {} {:?} buckets; at most {} can be scanned",
+                buckets, period, MAX_PERIOD_BUCKETS
+            ))
+            .detail("max_buckets", MAX_PERIOD_BUCKETS)
+            .into());
+        }
+
+        let mut anchors = Vec::with_capacity(buckets as usize);
+        let mut date = localization::local_date_time(from, 0);
+        for bucket in 0..buckets {
+            match period {
+                AnchorPeriod::Day => {
+                    let start = from.as_micros().div_euclid(MICROS_PER_DAY) + bucket;
+                    anchors.push(period_anchor(prefix, Timestamp::from_micros(start * MICROS_PER_DAY), period));
+                }
+                AnchorPeriod::Month => {
+                    anchors.push(format!("{}:{}", prefix, localization::month_key(&date)));
+                    date.month += 1;
+                    if date.month > 12 {
+                        date.month = 1;
+                        date.year += 1;
+                    }
+                }
+            }
+        }
+        Ok(anchors)
+    }
+
+    /// Links on the bucket anchors covering `from..=to` whose own
+    /// timestamps fall in that range, oldest first
+    pub fn links_in_period<T>(prefix: &str, period: AnchorPeriod, link_type: T, from: Timestamp, to: Timestamp) -> ExternResult<Vec<Link>>
+    where
+        T: TryInto<LinkTypeFilter, Error = WasmError> + Clone,
+    {
+        let mut links = Vec::new();
+        for anchor in period_anchors_between(prefix, period, from, to)? {
+            let query = LinkQuery::try_new(anchor_hash(&anchor)?, link_type.clone())?;
+            links.extend(
+                reads::get_links_for(query, reads::CallClass::Bulk)?
+                    .into_iter()
+                    .filter(|link| link.timestamp >= from && link.timestamp <= to),
+            );
+        }
+        links.sort_by_key(|link| link.timestamp);
+        Ok(links)
+    }
+}
+
+/// Input validation module - ensures data quality and security
+///
+/// Provides validators for:
+/// - Medical Record Numbers (MRN)
+/// - Decentralized Identifiers (DID)
+/// - Score ranges for instruments
+/// - Mental health screening responses
 pub mod validation {
     use super::*;
 
@@ -1007,6 +3066,12 @@ pub mod validation {
         InvalidCharacters,
         DuplicateValue,
         InvalidReference,
+        /// A list holds more items than allowed
+        TooManyItems,
+        /// The serialized entry exceeds the size limit
+        EntryTooLarge,
+        /// A code's check digit does not match the rest of the code
+        InvalidCheckDigit,
     }
 
     impl std::fmt::Display for ValidationError {
@@ -1015,9 +3080,17 @@ pub mod validation {
         }
     }
 
+    impl From<ValidationError> for HealthErrorPayload {
+        fn from(err: ValidationError) -> Self {
+            HealthErrorPayload::new(HealthErrorCode::ValidationError, err.message)
+                .field(&err.field)
+                .detail("rule", format!("{:?}", err.code))
+        }
+    }
+
     impl From<ValidationError> for WasmError {
         fn from(err: ValidationError) -> Self {
-            wasm_error!(WasmErrorInner::Guest(format!("Validation error - {}", err)))
+            HealthErrorPayload::from(err).into()
         }
     }
 
@@ -1044,15 +3117,18 @@ pub mod validation {
             self.errors.is_empty()
         }
 
+        /// The first error as a structured payload, listing every error
+        /// under `details.errors` when there are several
         pub fn into_result(self) -> ExternResult<()> {
-            if self.is_valid() {
-                Ok(())
-            } else {
+            let Some(first) = self.errors.first().cloned() else {
+                return Ok(());
+            };
+            let mut payload = HealthErrorPayload::from(first);
+            if self.errors.len() > 1 {
                 let messages: Vec<String> = self.errors.iter().map(|e| e.to_string()).collect();
-                Err(wasm_error!(WasmErrorInner::Guest(
-                    format!("Validation failed: {}", messages.join("; "))
-                )))
+                payload = payload.detail("errors", messages.join("; "));
             }
+            Err(payload.into())
         }
 
         pub fn merge(&mut self, other: ValidationResult) {
@@ -1089,6 +3165,32 @@ pub mod validation {
         result
     }
 
+    /// Validate a jurisdiction code
+    ///
+    /// An ISO 3166-1 alpha-2 country with an optional subdivision ("DE",
+    /// "US-CA"), or "EU" for rules shared across the union.
+    pub fn validate_jurisdiction(code: &str) -> ValidationResult {
+        let mut result = ValidationResult::new();
+
+        let (country, subdivision) = match code.split_once('-') {
+            Some((country, subdivision)) => (country, Some(subdivision)),
+            None => (code, None),
+        };
+        let country_ok = country.len() == 2 && country.chars().all(|c| c.is_ascii_uppercase());
+        let subdivision_ok = subdivision.is_none_or(|s| {
+            (1..=3).contains(&s.len()) && s.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit())
+        });
+        if !country_ok || !subdivision_ok {
+            result.add_error(
+                "jurisdiction",
+                "Jurisdiction must be an ISO 3166 code such as \"DE\" or \"US-CA\"",
+                ValidationErrorCode::InvalidFormat,
+            );
+        }
+
+        result
+    }
+
     /// Validate a Decentralized Identifier (DID)
     ///
     /// DID must follow the format: did:method:specific-id
@@ -1325,171 +3427,5576 @@ pub mod validation {
 
         result
     }
-}
-
-/// Batch operations module - solves N+1 query patterns
-///
-/// Provides efficient batch fetching for common patterns:
-/// - Batch get records from multiple hashes
-/// - Paginated link fetching helpers
-pub mod batch {
-    use super::*;
 
-    /// Options for batch record fetching
-    #[derive(Clone, Debug, Default)]
-    pub struct BatchGetOptions {
-        /// Maximum number of records to fetch (0 = unlimited)
-        pub limit: usize,
-        /// Skip records that are deleted
-        pub skip_deleted: bool,
+    /// Clinical terminologies whose code syntax `validate_code` checks
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum ClinicalCodeSystem {
+        Loinc,
+        SnomedCt,
+        Icd10Cm,
+        RxNorm,
+        Cpt,
     }
 
-    /// Result of a batch get operation
-    #[derive(Clone, Debug, Serialize, Deserialize)]
-    pub struct BatchGetResult {
-        /// Successfully fetched records
-        pub records: Vec<Record>,
-        /// Hashes that were not found (404)
-        pub not_found: Vec<ActionHash>,
-        /// Hashes that failed to fetch (errors)
-        pub errors: Vec<(ActionHash, String)>,
-        /// Total requested
-        pub total_requested: usize,
-        /// Successfully fetched count
-        pub success_count: usize,
-    }
+    impl ClinicalCodeSystem {
+        /// Canonical FHIR system URI
+        pub fn uri(&self) -> &'static str {
+            match self {
+                ClinicalCodeSystem::Loinc => "http://loinc.org",
+                ClinicalCodeSystem::SnomedCt => "http://snomed.info/sct",
+                ClinicalCodeSystem::Icd10Cm => "http://hl7.org/fhir/sid/icd-10-cm",
+                ClinicalCodeSystem::RxNorm => "http://www.nlm.nih.gov/research/umls/rxnorm",
+                ClinicalCodeSystem::Cpt => "http://www.ama-assn.org/go/cpt",
+            }
+        }
 
-    impl BatchGetResult {
-        pub fn new(total_requested: usize) -> Self {
-            Self {
-                records: Vec::new(),
-                not_found: Vec::new(),
-                errors: Vec::new(),
-                total_requested,
-                success_count: 0,
+        /// Recognize a FHIR system URI or a short name such as "loinc"
+        /// or "icd10-cm"; other systems are not checked
+        pub fn from_system(system: &str) -> Option<Self> {
+            let system = system.trim();
+            if let Some(known) = [Self::Loinc, Self::SnomedCt, Self::Icd10Cm, Self::RxNorm, Self::Cpt]
+                .into_iter()
+                .find(|known| known.uri() == system)
+            {
+                return Some(known);
+            }
+            match system.to_lowercase().as_str() {
+                "loinc" => Some(Self::Loinc),
+                "snomed" | "snomed-ct" | "sct" => Some(Self::SnomedCt),
+                // WHO ICD-10 codes follow the same pattern
+                "icd10" | "icd10-cm" | "icd-10-cm" | "http://hl7.org/fhir/sid/icd-10" => Some(Self::Icd10Cm),
+                "rxnorm" => Some(Self::RxNorm),
+                "cpt" => Some(Self::Cpt),
+                _ => None,
             }
         }
     }
 
+    /// Validate the syntax of a clinical code
+    ///
+    /// Checks format and check digits only; whether the code exists in the
+    /// current release of the terminology is a separate lookup.
+    /// - LOINC: up to 7 digits, "-", and a mod-10 check digit ("2345-7")
+    /// - SNOMED CT: 6-18 digit concept SCTID with a Verhoeff check digit
+    /// - ICD-10-CM: letter, digit, alphanumeric, then up to 4 more after
+    ///   an optional dot ("E11.65", "S72001A")
+    /// - RxNorm: numeric RXCUI without leading zeros
+    /// - CPT: five digits, or four digits and F, T or U ("99213", "0001F")
+    pub fn validate_code(system: ClinicalCodeSystem, code: &str) -> ValidationResult {
+        let mut result = ValidationResult::new();
+        let field = "code";
+
+        if code.is_empty() {
+            result.add_error(field, "Code is required", ValidationErrorCode::Required);
+            return result;
+        }
+
+        match system {
+            ClinicalCodeSystem::Loinc => {
+                let parts = code.split_once('-').filter(|(number, check)| {
+                    (1..=7).contains(&number.len())
+                        && check.len() == 1
+                        && number.chars().chain(check.chars()).all(|c| c.is_ascii_digit())
+                });
+                match parts {
+                    None => result.add_error(field, &format!("'{}' is not a LOINC code (NNNNN-N)", code), ValidationErrorCode::InvalidFormat),
+                    Some((number, check)) if luhn_check_digit(number) != check.as_bytes()[0] - b'0' => {
+                        result.add_error(field, &format!("LOINC code '{}' has the wrong check digit", code), ValidationErrorCode::InvalidCheckDigit)
+                    }
+                    Some(_) => {}
+                }
+            }
+            ClinicalCodeSystem::SnomedCt => {
+                let well_formed = (6..=18).contains(&code.len())
+                    && code.chars().all(|c| c.is_ascii_digit())
+                    && !code.starts_with('0');
+                if !well_formed {
+                    result.add_error(field, &format!("'{}' is not a SNOMED CT identifier", code), ValidationErrorCode::InvalidFormat);
+                    return result;
+                }
+                // Partition identifier: the two digits before the check digit,
+                // "00" or "10" for a concept
+                let partition = &code[code.len() - 3..code.len() - 1];
+                if partition != "00" && partition != "10" {
+                    result.add_error(field, &format!("SNOMED CT identifier '{}' is not a concept", code), ValidationErrorCode::InvalidFormat);
+                }
+                if !verhoeff_valid(code) {
+                    result.add_error(field, &format!("SNOMED CT identifier '{}' has the wrong check digit", code), ValidationErrorCode::InvalidCheckDigit);
+                }
+            }
+            ClinicalCodeSystem::Icd10Cm => {
+                let compact = match code.split_once('.') {
+                    Some((category, extension)) if category.len() == 3 && !extension.is_empty() => format!("{}{}", category, extension),
+                    Some(_) => String::new(),
+                    None => code.to_string(),
+                };
+                let chars: Vec<char> = compact.chars().collect();
+                let well_formed = (3..=7).contains(&chars.len())
+                    && chars[0].is_ascii_uppercase()
+                    && chars[1].is_ascii_digit()
+                    && chars[2..].iter().all(|c| c.is_ascii_digit() || c.is_ascii_uppercase());
+                if !well_formed {
+                    result.add_error(field, &format!("'{}' is not an ICD-10-CM code (A00.0)", code), ValidationErrorCode::InvalidFormat);
+                }
+            }
+            ClinicalCodeSystem::RxNorm => {
+                if code.len() > 8 || !code.chars().all(|c| c.is_ascii_digit()) || code.starts_with('0') {
+                    result.add_error(field, &format!("'{}' is not an RxNorm concept identifier", code), ValidationErrorCode::InvalidFormat);
+                }
+            }
+            ClinicalCodeSystem::Cpt => {
+                let well_formed = code.len() == 5
+                    && code[..4].chars().all(|c| c.is_ascii_digit())
+                    && code[4..].chars().all(|c| c.is_ascii_digit() || matches!(c, 'F' | 'T' | 'U'));
+                if !well_formed {
+                    result.add_error(field, &format!("'{}' is not a CPT code", code), ValidationErrorCode::InvalidFormat);
+                }
+            }
+        }
+
+        result
+    }
+
+    /// LOINC's mod-10 check digit: Luhn, doubling from the rightmost digit
+    fn luhn_check_digit(number: &str) -> u8 {
+        let sum: u32 = number
+            .bytes()
+            .rev()
+            .enumerate()
+            .map(|(position, byte)| {
+                let digit = (byte - b'0') as u32;
+                if position % 2 == 0 {
+                    let doubled = digit * 2;
+                    doubled / 10 + doubled % 10
+                } else {
+                    digit
+                }
+            })
+            .sum();
+        ((10 - sum % 10) % 10) as u8
+    }
+
+    /// Whether a digit string ends in a valid Verhoeff check digit
+    fn verhoeff_valid(digits: &str) -> bool {
+        const D: [[u8; 10]; 10] = [
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            [1, 2, 3, 4, 0, 6, 7, 8, 9, 5],
+            [2, 3, 4, 0, 1, 7, 8, 9, 5, 6],
+            [3, 4, 0, 1, 2, 8, 9, 5, 6, 7],
+            [4, 0, 1, 2, 3, 9, 5, 6, 7, 8],
+            [5, 9, 8, 7, 6, 0, 4, 3, 2, 1],
+            [6, 5, 9, 8, 7, 1, 0, 4, 3, 2],
+            [7, 6, 5, 9, 8, 2, 1, 0, 4, 3],
+            [8, 7, 6, 5, 9, 3, 2, 1, 0, 4],
+            [9, 8, 7, 6, 5, 4, 3, 2, 1, 0],
+        ];
+        const P: [[u8; 10]; 8] = [
+            [0, 1, 2, 3, 4, 5, 6, 7, 8, 9],
+            [1, 5, 7, 6, 2, 8, 3, 0, 9, 4],
+            [5, 8, 0, 3, 7, 9, 6, 1, 4, 2],
+            [8, 9, 1, 6, 0, 4, 3, 5, 2, 7],
+            [9, 4, 5, 3, 1, 2, 8, 7, 6, 0],
+            [4, 2, 8, 6, 5, 7, 3, 9, 0, 1],
+            [2, 7, 9, 3, 8, 0, 6, 4, 1, 5],
+            [7, 0, 4, 6, 9, 1, 3, 2, 5, 8],
+        ];
+        let check = digits
+            .bytes()
+            .rev()
+            .enumerate()
+            .fold(0u8, |c, (i, byte)| D[c as usize][P[i % 8][(byte - b'0') as usize] as usize]);
+        check == 0
+    }
+
+    /// UCUM unit codes: validation, normalization and clinical conversions
+    ///
+    /// Validation checks UCUM syntax against the atoms clinical data uses;
+    /// it is deliberately not the full UCUM table. Conversions cover mass,
+    /// length, volume, temperature and lab concentrations, crossing between
+    /// mass (mg/dL) and substance (mmol/L) concentrations with the analyte's
+    /// molar mass.
+    pub mod ucum {
+        use super::*;
+
+        /// FHIR `Quantity.system` for UCUM codes
+        pub const UCUM_SYSTEM: &str = "http://unitsofmeasure.org";
+
+        /// Atoms that take metric prefixes
+        const METRIC_ATOMS: &[&str] = &[
+            "g", "m", "s", "l", "L", "mol", "eq", "osm", "K", "Cel", "Pa", "bar", "Hz", "kat", "U", "[IU]",
+            "cal", "J", "W", "V", "A", "m[Hg]", "m[H2O]", "deg", "t",
+        ];
+
+        /// Atoms that do not
+        const OTHER_ATOMS: &[&str] = &[
+            "%", "min", "h", "d", "wk", "mo", "a", "10*", "10^", "[lb_av]", "[oz_av]", "[in_i]", "[ft_i]",
+            "[degF]", "[pH]", "[ppm]", "[ppb]", "[drp]", "[HPF]", "[LPF]", "[arb'U]", "[Cal]", "[psi]",
+        ];
+
+        /// Prefixes longest first, so "da" is tried before "d"
+        const PREFIXES: &[&str] = &[
+            "da", "Y", "Z", "E", "P", "T", "G", "M", "k", "h", "d", "c", "m", "u", "n", "p", "f", "a", "z", "y",
+        ];
+
+        /// Common non-UCUM spellings seen in EHR feeds
+        const ALIASES: &[(&str, &str)] = &[
+            ("lb", "[lb_av]"), ("lbs", "[lb_av]"), ("pound", "[lb_av]"), ("pounds", "[lb_av]"),
+            ("oz", "[oz_av]"), ("in", "[in_i]"), ("inch", "[in_i]"), ("inches", "[in_i]"),
+            ("ft", "[ft_i]"), ("feet", "[ft_i]"),
+            ("°f", "[degF]"), ("degf", "[degF]"), ("deg f", "[degF]"), ("fahrenheit", "[degF]"),
+            ("°c", "Cel"), ("degc", "Cel"), ("deg c", "Cel"), ("celsius", "Cel"),
+            ("mmhg", "mm[Hg]"), ("mm hg", "mm[Hg]"), ("bpm", "/min"), ("beats/min", "/min"),
+            ("mg/dl", "mg/dL"), ("g/dl", "g/dL"), ("ug/dl", "ug/dL"), ("mmol/l", "mmol/L"),
+            ("umol/l", "umol/L"), ("nmol/l", "nmol/L"), ("mg/l", "mg/L"), ("g/l", "g/L"), ("ng/ml", "ng/mL"),
+        ];
+
+        fn unit_error(field: &str, message: String, code: ValidationErrorCode) -> ValidationError {
+            ValidationError { field: field.to_string(), message, code }
+        }
+
+        /// Whether `code` is a syntactically valid UCUM expression over
+        /// known atoms, e.g. "mg/dL", "mm[Hg]", "10*3/uL", "kg/m2"
+        pub fn is_valid_ucum(code: &str) -> bool {
+            if code.is_empty() || code.contains(char::is_whitespace) {
+                return false;
+            }
+            // A leading "/" is a reciprocal, as in "/min"
+            valid_term(code.strip_prefix('/').unwrap_or(code))
+        }
+
+        fn valid_term(term: &str) -> bool {
+            let mut depth = 0i32;
+            let mut start = 0;
+            for (i, c) in term.char_indices() {
+                match c {
+                    '(' | '[' | '{' => depth += 1,
+                    ')' | ']' | '}' => {
+                        depth -= 1;
+                        if depth < 0 {
+                            return false;
+                        }
+                    }
+                    '.' | '/' if depth == 0 => {
+                        if !valid_component(&term[start..i]) {
+                            return false;
+                        }
+                        start = i + 1;
+                    }
+                    _ => {}
+                }
+            }
+            depth == 0 && valid_component(&term[start..])
+        }
+
+        fn valid_component(component: &str) -> bool {
+            // An annotation may follow a unit or stand alone, as in "{cells}"
+            let (unit, annotation) = match component.find('{') {
+                Some(open) => (&component[..open], Some(&component[open..])),
+                None => (component, None),
+            };
+            if let Some(annotation) = annotation {
+                let well_formed = annotation.ends_with('}')
+                    && annotation[1..annotation.len() - 1].chars().all(|c| c.is_ascii_graphic() && c != '{' && c != '}');
+                if !well_formed {
+                    return false;
+                }
+                if unit.is_empty() {
+                    return true;
+                }
+            }
+            if let Some(inner) = unit.strip_prefix('(').and_then(|u| u.strip_suffix(')')) {
+                return valid_term(inner);
+            }
+            if !unit.is_empty() && unit.chars().all(|c| c.is_ascii_digit()) {
+                return true;
+            }
+            let exponent_start = unit
+                .trim_end_matches(|c: char| c.is_ascii_digit())
+                .trim_end_matches(['+', '-']);
+            let simple = if exponent_start.len() < unit.len()
+                && unit[exponent_start.len()..].chars().any(|c| c.is_ascii_digit())
+            {
+                exponent_start
+            } else {
+                unit
+            };
+            valid_simple_unit(simple)
+        }
+
+        fn valid_simple_unit(unit: &str) -> bool {
+            if METRIC_ATOMS.contains(&unit) || OTHER_ATOMS.contains(&unit) {
+                return true;
+            }
+            PREFIXES.iter().any(|prefix| {
+                unit.strip_prefix(prefix).is_some_and(|atom| METRIC_ATOMS.contains(&atom))
+            })
+        }
+
+        /// Map a stated unit to a UCUM code
+        ///
+        /// Known aliases are rewritten first, so "lbs" becomes "[lb_av]" and
+        /// "mg/dl" becomes "mg/dL"; otherwise the unit is kept if it is
+        /// already valid UCUM. Bare "C" and "F" stay coulomb and farad.
+        pub fn normalize_unit(unit: &str) -> Option<String> {
+            let trimmed = unit.trim().replace(['µ', 'μ'], "u");
+            let lower = trimmed.to_lowercase();
+            if let Some((_, code)) = ALIASES.iter().find(|(alias, _)| *alias == lower) {
+                return Some(code.to_string());
+            }
+            is_valid_ucum(&trimmed).then_some(trimmed)
+        }
+
+        /// Validate a FHIR Quantity's value and unit code
+        ///
+        /// Codes from other systems are accepted as stated; a UCUM quantity
+        /// must carry a valid code.
+        pub fn validate_quantity(field: &str, value: f64, system: Option<&str>, code: Option<&str>) -> ValidationResult {
+            let mut result = ValidationResult::new();
+
+            if !value.is_finite() {
+                result.add_error(field, "Quantity value must be a finite number", ValidationErrorCode::InvalidFormat);
+            }
+            match (system, code) {
+                (Some(system), _) if system != UCUM_SYSTEM => {}
+                (Some(_), None) => {
+                    result.add_error(field, "UCUM quantities need a unit code", ValidationErrorCode::Required);
+                }
+                (_, Some(code)) if !is_valid_ucum(code) => {
+                    result.add_error(field, &format!("'{}' is not a valid UCUM code", code), ValidationErrorCode::InvalidFormat);
+                }
+                _ => {}
+            }
+
+            result
+        }
+
+        /// Dimension a convertible unit measures
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        pub enum UnitKind {
+            Mass,
+            Length,
+            Volume,
+            Temperature,
+            /// Mass per volume, e.g. mg/dL
+            MassConcentration,
+            /// Substance per volume, e.g. mmol/L
+            SubstanceConcentration,
+        }
+
+        /// Kind and factor to the kind's base unit (g, m, L, g/L, mol/L);
+        /// temperatures convert through Celsius instead
+        fn unit_scale(code: &str) -> Option<(UnitKind, f64)> {
+            let scale = match code {
+                "g" => (UnitKind::Mass, 1.0),
+                "kg" => (UnitKind::Mass, 1e3),
+                "mg" => (UnitKind::Mass, 1e-3),
+                "ug" => (UnitKind::Mass, 1e-6),
+                "[lb_av]" => (UnitKind::Mass, 453.59237),
+                "[oz_av]" => (UnitKind::Mass, 28.349523125),
+                "m" => (UnitKind::Length, 1.0),
+                "cm" => (UnitKind::Length, 1e-2),
+                "mm" => (UnitKind::Length, 1e-3),
+                "[in_i]" => (UnitKind::Length, 0.0254),
+                "[ft_i]" => (UnitKind::Length, 0.3048),
+                "L" | "l" => (UnitKind::Volume, 1.0),
+                "dL" | "dl" => (UnitKind::Volume, 1e-1),
+                "mL" | "ml" => (UnitKind::Volume, 1e-3),
+                "Cel" | "[degF]" | "K" => (UnitKind::Temperature, 1.0),
+                "g/L" => (UnitKind::MassConcentration, 1.0),
+                "g/dL" => (UnitKind::MassConcentration, 10.0),
+                "mg/dL" => (UnitKind::MassConcentration, 1e-2),
+                "mg/L" => (UnitKind::MassConcentration, 1e-3),
+                "ug/dL" => (UnitKind::MassConcentration, 1e-5),
+                "ug/L" | "ng/mL" => (UnitKind::MassConcentration, 1e-6),
+                "mol/L" => (UnitKind::SubstanceConcentration, 1.0),
+                "mmol/L" => (UnitKind::SubstanceConcentration, 1e-3),
+                "umol/L" => (UnitKind::SubstanceConcentration, 1e-6),
+                "nmol/L" => (UnitKind::SubstanceConcentration, 1e-9),
+                _ => return None,
+            };
+            Some(scale)
+        }
+
+        /// The kind of a unit `convert` understands
+        pub fn unit_kind(unit: &str) -> Option<UnitKind> {
+            normalize_unit(unit).and_then(|code| unit_scale(&code)).map(|(kind, _)| kind)
+        }
+
+        fn to_celsius(value: f64, code: &str) -> f64 {
+            match code {
+                "[degF]" => (value - 32.0) * 5.0 / 9.0,
+                "K" => value - 273.15,
+                _ => value,
+            }
+        }
+
+        fn from_celsius(value: f64, code: &str) -> f64 {
+            match code {
+                "[degF]" => value * 9.0 / 5.0 + 32.0,
+                "K" => value + 273.15,
+                _ => value,
+            }
+        }
+
+        /// Convert `value` between units
+        ///
+        /// Mass and substance concentrations convert through `molar_mass` in
+        /// g/mol (see `molar_mass_for_loinc`); it is ignored otherwise.
+        ///
+        /// # Example
+        /// ```ignore
+        /// let kg = ucum::convert(165.0, "lbs", "kg", None)?;
+        /// let mmol = ucum::convert(99.0, "mg/dL", "mmol/L", ucum::molar_mass_for_loinc("2345-7"))?;
+        /// ```
+        pub fn convert(value: f64, from: &str, to: &str, molar_mass: Option<f64>) -> Result<f64, ValidationError> {
+            let resolve = |unit: &str| {
+                normalize_unit(unit).and_then(|code| unit_scale(&code).map(|scale| (code, scale))).ok_or_else(|| {
+                    unit_error("unit", format!("'{}' is not a convertible unit", unit), ValidationErrorCode::InvalidFormat)
+                })
+            };
+            let (from_code, (from_kind, from_factor)) = resolve(from)?;
+            let (to_code, (to_kind, to_factor)) = resolve(to)?;
+
+            match (from_kind, to_kind) {
+                (UnitKind::Temperature, UnitKind::Temperature) => {
+                    let celsius = to_celsius(value, &from_code);
+                    if celsius < -273.15 {
+                        return Err(unit_error("value", "Temperature is below absolute zero".to_string(), ValidationErrorCode::OutOfRange));
+                    }
+                    Ok(from_celsius(celsius, &to_code))
+                }
+                (from_kind, to_kind) if from_kind == to_kind => Ok(value * from_factor / to_factor),
+                (UnitKind::MassConcentration, UnitKind::SubstanceConcentration)
+                | (UnitKind::SubstanceConcentration, UnitKind::MassConcentration) => {
+                    let molar_mass = molar_mass.filter(|m| m.is_finite() && *m > 0.0).ok_or_else(|| {
+                        unit_error(
+                            "unit",
+                            format!("Converting {} to {} needs the analyte's molar mass", from, to),
+                            ValidationErrorCode::Required,
+                        )
+                    })?;
+                    // g/L = mol/L × g/mol
+                    let base = value * from_factor;
+                    let converted = if from_kind == UnitKind::MassConcentration { base / molar_mass } else { base * molar_mass };
+                    Ok(converted / to_factor)
+                }
+                _ => Err(unit_error(
+                    "unit",
+                    format!("Cannot convert {} to {}", from, to),
+                    ValidationErrorCode::InvalidFormat,
+                )),
+            }
+        }
+
+        /// Molar mass in g/mol of the analyte a LOINC lab code measures
+        ///
+        /// Urea nitrogen uses N₂, matching how BUN in mg/dL is reported.
+        pub fn molar_mass_for_loinc(loinc_code: &str) -> Option<f64> {
+            let molar_mass = match loinc_code {
+                // Glucose
+                "2345-7" | "2339-0" | "1558-6" | "15074-8" | "14749-6" => 180.156,
+                // Cholesterol: total, HDL, LDL
+                "2093-3" | "2085-9" | "2089-1" | "13457-7" | "18262-6" | "14647-2" | "14646-4" | "22748-8" => 386.65,
+                // Triglycerides
+                "2571-8" | "14927-8" => 885.7,
+                // Creatinine
+                "2160-0" | "14682-9" => 113.12,
+                // Urea nitrogen
+                "3094-0" | "14937-7" => 28.014,
+                // Calcium
+                "17861-6" | "2000-8" => 40.078,
+                // Bilirubin, total
+                "1975-2" | "14631-6" => 584.66,
+                // Uric acid
+                "3084-1" | "14933-6" => 168.11,
+                _ => return None,
+            };
+            Some(molar_mass)
+        }
+    }
+}
+
+/// Size guards applied to every app entry before its type-specific validation
+///
+/// Oversized payloads are rejected deterministically with a specific
+/// `ValidationErrorCode` rather than failing later at the host layer. The
+/// checks walk the serialized entry, so one limits table per zome covers every
+/// entry type it defines: a total byte budget, a character limit for strings
+/// and an item limit for lists, each overridable by field name.
+pub mod entry_limits {
+    use super::*;
+    use super::validation::{ValidationError, ValidationErrorCode};
+    use serde::de::{self, Deserializer, MapAccess, SeqAccess, Visitor};
+
+    /// Limit for a named field, wherever it appears in an entry
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum FieldLimit {
+        /// Maximum characters of a string field
+        Chars(usize),
+        /// Maximum items of a list field
+        Items(usize),
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    pub struct EntrySizeLimits {
+        pub max_entry_bytes: usize,
+        pub max_string_chars: usize,
+        pub max_list_items: usize,
+        pub field_limits: &'static [(&'static str, FieldLimit)],
+    }
+
+    pub const DEFAULT_ENTRY_SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+        max_entry_bytes: 1_048_576,
+        max_string_chars: 16_384,
+        max_list_items: 1_000,
+        field_limits: &[],
+    };
+
+    /// Free-text fields that may run longer than the default string limit
+    pub const NOTE_CHARS: usize = 65_536;
+
+    impl EntrySizeLimits {
+        fn chars_for(&self, field: &str) -> usize {
+            self.field_limits
+                .iter()
+                .find_map(|(name, limit)| match limit {
+                    FieldLimit::Chars(chars) if *name == field => Some(*chars),
+                    _ => None,
+                })
+                .unwrap_or(self.max_string_chars)
+        }
+
+        fn items_for(&self, field: &str) -> usize {
+            self.field_limits
+                .iter()
+                .find_map(|(name, limit)| match limit {
+                    FieldLimit::Items(items) if *name == field => Some(*items),
+                    _ => None,
+                })
+                .unwrap_or(self.max_list_items)
+        }
+    }
+
+    /// Shape of a serialized value, enough to measure it against the limits
+    #[derive(Debug)]
+    enum Shape {
+        Scalar,
+        Text(String),
+        List(Vec<Shape>),
+        Map(Vec<(Option<String>, Shape)>),
+    }
+
+    impl<'de> Deserialize<'de> for Shape {
+        fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            deserializer.deserialize_any(ShapeVisitor)
+        }
+    }
+
+    struct ShapeVisitor;
+
+    impl<'de> Visitor<'de> for ShapeVisitor {
+        type Value = Shape;
+
+        fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+            f.write_str("any serialized value")
+        }
+
+        fn visit_bool<E: de::Error>(self, _: bool) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_i64<E: de::Error>(self, _: i64) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_u64<E: de::Error>(self, _: u64) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_f64<E: de::Error>(self, _: f64) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Shape, E> {
+            Ok(Shape::Text(value.to_string()))
+        }
+
+        // Hashes, signatures and compressed payloads; only the byte budget applies
+        fn visit_bytes<E: de::Error>(self, _: &[u8]) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_unit<E: de::Error>(self) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_none<E: de::Error>(self) -> Result<Shape, E> {
+            Ok(Shape::Scalar)
+        }
+
+        fn visit_some<D: Deserializer<'de>>(self, deserializer: D) -> Result<Shape, D::Error> {
+            Shape::deserialize(deserializer)
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Shape, A::Error> {
+            let mut items = Vec::new();
+            while let Some(item) = seq.next_element::<Shape>()? {
+                items.push(item);
+            }
+            Ok(Shape::List(items))
+        }
+
+        fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Shape, A::Error> {
+            let mut entries = Vec::new();
+            while let Some(key) = map.next_key::<Shape>()? {
+                let name = match key {
+                    Shape::Text(name) => Some(name),
+                    _ => None,
+                };
+                entries.push((name, map.next_value::<Shape>()?));
+            }
+            Ok(Shape::Map(entries))
+        }
+    }
+
+    fn violation(field: &str, message: String, code: ValidationErrorCode) -> ValidationError {
+        ValidationError {
+            field: if field.is_empty() { "entry".to_string() } else { field.to_string() },
+            message,
+            code,
+        }
+    }
+
+    fn check_shape(shape: &Shape, name: &str, path: &str, limits: &EntrySizeLimits) -> Result<(), ValidationError> {
+        match shape {
+            Shape::Scalar => Ok(()),
+            Shape::Text(text) => {
+                let limit = limits.chars_for(name);
+                let chars = text.chars().count();
+                if chars > limit {
+                    return Err(violation(
+                        path,
+                        format!("{} characters exceeds the {} allowed", chars, limit),
+                        ValidationErrorCode::TooLong,
+                    ));
+                }
+                Ok(())
+            }
+            Shape::List(items) => {
+                let limit = limits.items_for(name);
+                // Lists of plain numbers are byte arrays; the byte budget covers them
+                if items.len() > limit && !items.iter().all(|item| matches!(item, Shape::Scalar)) {
+                    return Err(violation(
+                        path,
+                        format!("{} items exceeds the {} allowed", items.len(), limit),
+                        ValidationErrorCode::TooManyItems,
+                    ));
+                }
+                for (index, item) in items.iter().enumerate() {
+                    check_shape(item, name, &format!("{}[{}]", path, index), limits)?;
+                }
+                Ok(())
+            }
+            Shape::Map(entries) => {
+                for (key, value) in entries {
+                    let field = key.as_deref().unwrap_or(name);
+                    let child = match (path.is_empty(), key) {
+                        (_, None) => path.to_string(),
+                        (true, Some(key)) => key.clone(),
+                        (false, Some(key)) => format!("{}.{}", path, key),
+                    };
+                    check_shape(value, field, &child, limits)?;
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Check a serialized app entry against the limits
+    pub fn check_entry_bytes(bytes: &[u8], limits: &EntrySizeLimits) -> Result<(), ValidationError> {
+        if bytes.len() > limits.max_entry_bytes {
+            return Err(violation(
+                "",
+                format!("{} bytes exceeds the {} allowed", bytes.len(), limits.max_entry_bytes),
+                ValidationErrorCode::EntryTooLarge,
+            ));
+        }
+        // Entries that do not decode are left to the type-specific validation
+        match holochain_serialized_bytes::decode::<_, Shape>(bytes) {
+            Ok(shape) => check_shape(&shape, "", "", limits),
+            Err(_) => Ok(()),
+        }
+    }
+
+    /// Invalid result for an op whose app entry breaks the limits, `None` otherwise
+    ///
+    /// Call first thing in a zome's `validate` callback.
+    pub fn entry_size_violation(op: &Op, limits: &EntrySizeLimits) -> Option<ValidateCallbackResult> {
+        let entry = match op {
+            Op::StoreEntry(store) => Some(&store.entry),
+            Op::StoreRecord(store) => store.record.entry().as_option(),
+            _ => None,
+        };
+        match entry {
+            Some(Entry::App(bytes)) => check_entry_bytes(bytes.bytes(), limits)
+                .err()
+                .map(|error| ValidateCallbackResult::Invalid(format!("Validation error - {}", error))),
+            _ => None,
+        }
+    }
+}
+
+/// Batch operations module - solves N+1 query patterns
+///
+/// Provides efficient batch fetching for common patterns:
+/// - Batch get records from multiple hashes
+/// - Chunked batch gets resumable with a continuation token
+/// - Paginated link fetching helpers
+pub mod batch {
+    use super::*;
+
+    /// Options for batch record fetching
+    #[derive(Clone, Debug, Default)]
+    pub struct BatchGetOptions {
+        /// Maximum number of records to fetch (0 = unlimited)
+        pub limit: usize,
+        /// Skip records that have been deleted; implies reading details
+        pub skip_deleted: bool,
+        /// Read policy for each fetch
+        pub class: reads::CallClass,
+        /// Only keep records of this entry type, e.g.
+        /// `UnitEntryTypes::Consent.try_into()?`
+        pub entry_type: Option<EntryType>,
+        /// Read each record with `get_details` to report updates and deletes
+        pub with_details: bool,
+    }
+
+    /// Result of a batch get operation
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BatchGetResult {
+        /// Successfully fetched records
+        pub records: Vec<Record>,
+        /// Update and delete status of each record, in the same order, when
+        /// fetched with `with_details` or `skip_deleted`
+        pub details: Vec<RecordStatus>,
+        /// Hashes that were not found (404)
+        pub not_found: Vec<ActionHash>,
+        /// Records left out because they are not of the expected entry type
+        pub wrong_type: Vec<ActionHash>,
+        /// Hashes that failed to fetch (errors)
+        pub errors: Vec<(ActionHash, String)>,
+        /// Total requested
+        pub total_requested: usize,
+        /// Successfully fetched count
+        pub success_count: usize,
+    }
+
+    impl BatchGetResult {
+        pub fn new(total_requested: usize) -> Self {
+            Self {
+                records: Vec::new(),
+                details: Vec::new(),
+                not_found: Vec::new(),
+                wrong_type: Vec::new(),
+                errors: Vec::new(),
+                total_requested,
+                success_count: 0,
+            }
+        }
+    }
+
+    /// Update and delete status of a fetched record
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct RecordStatus {
+        pub updates: Vec<ActionHash>,
+        pub deletes: Vec<ActionHash>,
+    }
+
+    impl RecordStatus {
+        pub fn from_details(details: &RecordDetails) -> Self {
+            Self {
+                updates: details.updates.iter().map(|u| u.as_hash().clone()).collect(),
+                deletes: details.deletes.iter().map(|d| d.as_hash().clone()).collect(),
+            }
+        }
+
+        pub fn is_deleted(&self) -> bool {
+            !self.deletes.is_empty()
+        }
+
+        pub fn is_updated(&self) -> bool {
+            !self.updates.is_empty()
+        }
+    }
+
+    enum Fetched {
+        Found(Box<Record>, Option<RecordStatus>),
+        NotFound,
+        WrongType,
+        Failed(String),
+    }
+
+    fn fetch_one(hash: &ActionHash, options: &BatchGetOptions) -> Fetched {
+        let fetched = if options.with_details || options.skip_deleted {
+            reads::get_details_for(hash.clone(), options.class).map(|details| {
+                details.map(|details| {
+                    let status = RecordStatus::from_details(&details);
+                    (details.record, Some(status))
+                })
+            })
+        } else {
+            reads::get_for(hash.clone(), options.class).map(|record| record.map(|record| (record, None)))
+        };
+        match fetched {
+            Ok(Some((record, status))) => {
+                if options.skip_deleted && status.as_ref().is_some_and(RecordStatus::is_deleted) {
+                    return Fetched::NotFound;
+                }
+                if let Some(expected) = &options.entry_type {
+                    if record.action().entry_type() != Some(expected) {
+                        return Fetched::WrongType;
+                    }
+                }
+                Fetched::Found(Box::new(record), status.filter(|_| options.with_details))
+            }
+            Ok(None) => Fetched::NotFound,
+            Err(e) => Fetched::Failed(format!("{:?}", e)),
+        }
+    }
+
     /// Batch get records from multiple action hashes
     ///
     /// This is more efficient than individual get() calls in a loop
     /// because it collects all results and handles errors gracefully.
     ///
-    /// # Arguments
-    /// * `hashes` - Action hashes to fetch
-    /// * `options` - Batch get options
+    /// # Arguments
+    /// * `hashes` - Action hashes to fetch
+    /// * `options` - Batch get options
+    ///
+    /// # Returns
+    /// BatchGetResult with records, not_found, and errors
+    pub fn batch_get_records(
+        hashes: Vec<ActionHash>,
+        options: BatchGetOptions,
+    ) -> ExternResult<BatchGetResult> {
+        let total = hashes.len();
+        let mut result = BatchGetResult::new(total);
+
+        let limit = if options.limit == 0 { total } else { options.limit.min(total) };
+
+        for hash in hashes.into_iter().take(limit) {
+            match fetch_one(&hash, &options) {
+                Fetched::Found(record, status) => {
+                    result.records.push(*record);
+                    result.details.extend(status);
+                    result.success_count += 1;
+                }
+                Fetched::NotFound => result.not_found.push(hash),
+                Fetched::WrongType => result.wrong_type.push(hash),
+                Fetched::Failed(error) => result.errors.push((hash, error)),
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Chunk size when the caller does not ask for one
+    pub const DEFAULT_BATCH_CHUNK_SIZE: usize = 50;
+
+    /// Largest chunk a caller may ask for
+    pub const MAX_BATCH_CHUNK_SIZE: usize = 200;
+
+    const CHUNK_TOKEN_PREFIX: &str = "bc1:";
+
+    /// Where to resume a chunked batch get and how many hashes to fetch;
+    /// the default is the first chunk at the default size
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    pub struct BatchChunkInput {
+        /// `continuation` of the previous chunk
+        pub continuation: Option<String>,
+        /// Hashes to fetch, capped at `MAX_BATCH_CHUNK_SIZE`
+        pub chunk_size: Option<usize>,
+    }
+
+    /// The hashes one chunk covers, in stable order
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct ChunkPlan {
+        pub hashes: Vec<ActionHash>,
+        pub continuation: Option<String>,
+        /// Distinct hashes across all chunks
+        pub total: usize,
+    }
+
+    /// One chunk of a batch get
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BatchChunk {
+        pub result: BatchGetResult,
+        /// Pass back in `BatchChunkInput::continuation` for the next chunk;
+        /// `None` once every hash has been fetched
+        pub continuation: Option<String>,
+        /// Distinct hashes across all chunks
+        pub total: usize,
+    }
+
+    /// Token for resuming after `last`, the hash at `offset - 1`
+    fn chunk_token(offset: usize, last: &ActionHash) -> String {
+        format!(
+            "{}{}:{}",
+            CHUNK_TOKEN_PREFIX,
+            offset,
+            encryption::base64_encode(last.get_raw_39())
+        )
+    }
+
+    fn decode_chunk_token(token: &str) -> ExternResult<(usize, Vec<u8>)> {
+        let invalid = || WasmError::from(HealthError::ValidationError("Invalid continuation token".to_string()).field("continuation"));
+        let (offset, last) = token
+            .strip_prefix(CHUNK_TOKEN_PREFIX)
+            .and_then(|rest| rest.split_once(':'))
+            .ok_or_else(invalid)?;
+        Ok((
+            offset.parse().map_err(|_| invalid())?,
+            encryption::base64_decode(last).map_err(|_| invalid())?,
+        ))
+    }
+
+    /// Sort and dedupe `hashes`, then pick the chunk after `input.continuation`
+    ///
+    /// The token is an offset into the sorted list plus the hash it follows.
+    /// When hashes were added or removed between calls and the offset no
+    /// longer lands after that hash, the chunk resumes from the first hash
+    /// sorting after it, so nothing is repeated or skipped.
+    pub fn plan_chunk(mut hashes: Vec<ActionHash>, input: &BatchChunkInput) -> ExternResult<ChunkPlan> {
+        let chunk_size = input.chunk_size.unwrap_or(DEFAULT_BATCH_CHUNK_SIZE);
+        if chunk_size == 0 || chunk_size > MAX_BATCH_CHUNK_SIZE {
+            return Err(HealthError::ValidationError(format!(
+                "Chunk size must be between 1 and {}",
+                MAX_BATCH_CHUNK_SIZE
+            ))
+            .field("chunk_size")
+            .into());
+        }
+        hashes.sort_unstable();
+        hashes.dedup();
+
+        let start = match input.continuation.as_deref().map(decode_chunk_token).transpose()? {
+            None => 0,
+            Some((offset, last)) => {
+                let in_place = offset
+                    .checked_sub(1)
+                    .and_then(|index| hashes.get(index))
+                    .is_some_and(|hash| hash.get_raw_39() == last.as_slice());
+                if in_place {
+                    offset
+                } else {
+                    hashes.partition_point(|hash| hash.get_raw_39() <= last.as_slice())
+                }
+            }
+        };
+
+        let total = hashes.len();
+        let end = (start + chunk_size).min(total);
+        let chunk: Vec<ActionHash> = hashes.drain(start.min(end)..end).collect();
+        let continuation = match chunk.last() {
+            Some(last) if end < total => Some(chunk_token(end, last)),
+            _ => None,
+        };
+        Ok(ChunkPlan { hashes: chunk, continuation, total })
+    }
+
+    /// Batch get one chunk of `hashes`, resumable across zome calls
+    ///
+    /// For result sets too large for a single call. Hashes are fetched in
+    /// hash order rather than the order given; `options.limit` is ignored
+    /// in favour of the chunk size.
+    pub fn batch_get_records_chunked(
+        hashes: Vec<ActionHash>,
+        options: BatchGetOptions,
+        input: &BatchChunkInput,
+    ) -> ExternResult<BatchChunk> {
+        let plan = plan_chunk(hashes, input)?;
+        let result = batch_get_records(plan.hashes, BatchGetOptions { limit: 0, ..options })?;
+        Ok(BatchChunk { result, continuation: plan.continuation, total: plan.total })
+    }
+
+    /// A fetched record with its entry deserialized
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct TypedRecord<T> {
+        pub record: Record,
+        pub entry: T,
+        /// Update and delete status, when fetched with `with_details`
+        pub status: Option<RecordStatus>,
+    }
+
+    impl<T> TypedRecord<T> {
+        pub fn action_hash(&self) -> &ActionHash {
+            self.record.action_address()
+        }
+    }
+
+    /// Result of a typed batch get
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BatchGetTyped<T> {
+        pub items: Vec<TypedRecord<T>>,
+        pub not_found: Vec<ActionHash>,
+        /// Records of another entry type, or whose entry does not deserialize
+        pub wrong_type: Vec<ActionHash>,
+        pub errors: Vec<(ActionHash, String)>,
+    }
+
+    impl<T> BatchGetTyped<T> {
+        /// Just the deserialized entries
+        pub fn entries(self) -> Vec<T> {
+            self.items.into_iter().map(|item| item.entry).collect()
+        }
+    }
+
+    /// Batch get records and deserialize their entries as `T` in one pass
+    ///
+    /// Replaces the fetch-then-`to_app_option` loop; set
+    /// `options.entry_type` to skip other entry types before decoding.
+    pub fn batch_get_typed<T>(hashes: Vec<ActionHash>, options: BatchGetOptions) -> ExternResult<BatchGetTyped<T>>
+    where
+        T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    {
+        let limit = if options.limit == 0 { hashes.len() } else { options.limit.min(hashes.len()) };
+        let mut result = BatchGetTyped {
+            items: Vec::new(),
+            not_found: Vec::new(),
+            wrong_type: Vec::new(),
+            errors: Vec::new(),
+        };
+        for hash in hashes.into_iter().take(limit) {
+            match fetch_one(&hash, &options) {
+                Fetched::Found(record, status) => match record.entry().to_app_option::<T>() {
+                    Ok(Some(entry)) => result.items.push(TypedRecord { record: *record, entry, status }),
+                    _ => result.wrong_type.push(hash),
+                },
+                Fetched::NotFound => result.not_found.push(hash),
+                Fetched::WrongType => result.wrong_type.push(hash),
+                Fetched::Failed(error) => result.errors.push((hash, error)),
+            }
+        }
+        Ok(result)
+    }
+
+    /// Entries of type `T` behind `links`, skipping links to anything else
+    pub fn links_to_entries<T>(links: Vec<Link>) -> ExternResult<Vec<T>>
+    where
+        T: TryFrom<SerializedBytes, Error = SerializedBytesError>,
+    {
+        let hashes = links.into_iter().filter_map(|link| link.target.into_action_hash()).collect();
+        Ok(batch_get_typed(hashes, BatchGetOptions::default())?.entries())
+    }
+
+    /// Convert links to records with pagination
+    ///
+    /// Takes a list of links and returns paginated records.
+    /// Use this after getting links from your zome's link type.
+    ///
+    /// # Arguments
+    /// * `links` - Links to process
+    /// * `pagination` - Pagination parameters
+    ///
+    /// # Returns
+    /// PaginatedResult with the fetched records
+    pub fn links_to_records_paginated(
+        links: Vec<Link>,
+        pagination: &types::PaginationInput,
+    ) -> ExternResult<types::PaginatedResult<Record>> {
+        pagination.validate()?;
+
+        let total = links.len();
+
+        // Apply pagination
+        let paginated_links: Vec<_> = links
+            .into_iter()
+            .skip(pagination.offset)
+            .take(pagination.limit)
+            .collect();
+
+        // Extract target hashes
+        let hashes: Vec<ActionHash> = paginated_links
+            .iter()
+            .filter_map(|link| link.target.clone().into_action_hash())
+            .collect();
+
+        // Batch fetch records
+        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
+
+        Ok(types::PaginatedResult::new(
+            batch_result.records,
+            total,
+            pagination,
+        ))
+    }
+
+    /// Convert links to records a cursor page at a time
+    ///
+    /// Pages are ordered by link timestamp, so links created between calls
+    /// land on later pages instead of shifting earlier ones.
+    pub fn links_to_records_cursor(
+        links: Vec<Link>,
+        input: &types::CursorInput,
+    ) -> ExternResult<types::CursorPage<Record>> {
+        let page = types::cursor_page(links, input, types::PaginationCursor::from_link)?;
+        let hashes: Vec<ActionHash> = page
+            .items
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+            .collect();
+        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
+        Ok(types::CursorPage {
+            items: batch_result.records,
+            next_cursor: page.next_cursor,
+            has_more: page.has_more,
+        })
+    }
+
+    /// Get records from links (non-paginated helper)
+    ///
+    /// Converts a list of links to their target records.
+    pub fn links_to_records(links: Vec<Link>) -> ExternResult<Vec<Record>> {
+        let hashes: Vec<ActionHash> = links
+            .into_iter()
+            .filter_map(|link| link.target.into_action_hash())
+            .collect();
+
+        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
+        Ok(batch_result.records)
+    }
+
+    /// Get the most recent N records from links
+    ///
+    /// Useful for "recent activity" views.
+    /// Links are sorted by timestamp (newest first).
+    pub fn links_to_recent_records(
+        mut links: Vec<Link>,
+        count: usize,
+    ) -> ExternResult<Vec<Record>> {
+        // Sort by timestamp (newest first)
+        links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+
+        // Take only the requested count
+        let hashes: Vec<ActionHash> = links
+            .into_iter()
+            .take(count)
+            .filter_map(|link| link.target.into_action_hash())
+            .collect();
+
+        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
+        Ok(batch_result.records)
+    }
+
+    /// Longest a paced call may spend waiting between commits, well inside
+    /// the zome call timeout
+    pub const MAX_PACED_WAIT_MS: u64 = 10_000;
+
+    /// How fast a batch write commits
+    ///
+    /// Every commit is validated by the agents responsible for its address,
+    /// so a burst of commits from one call lands on a handful of validators.
+    /// Spacing commits and splitting the batch across calls spreads that load.
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct BatchWritePacing {
+        /// Items attempted per zome call; the rest wait for the next call
+        pub max_items_per_call: u32,
+        /// Minimum time between the starts of consecutive commits
+        pub commit_spacing_ms: u64,
+    }
+
+    impl Default for BatchWritePacing {
+        fn default() -> Self {
+            Self { max_items_per_call: 50, commit_spacing_ms: 20 }
+        }
+    }
+
+    impl BatchWritePacing {
+        pub fn validate(&self) -> ExternResult<()> {
+            if self.max_items_per_call == 0 {
+                return Err(invalid_input("Batch pacing must allow at least one item per call"));
+            }
+            let wait = self
+                .commit_spacing_ms
+                .saturating_mul(self.max_items_per_call.saturating_sub(1) as u64);
+            if wait > MAX_PACED_WAIT_MS {
+                return Err(invalid_input(format!(
+                    "Batch pacing would wait {}ms in one call (max {}ms); lower the spacing or items per call",
+                    wait, MAX_PACED_WAIT_MS
+                )));
+            }
+            Ok(())
+        }
+    }
+
+    /// Where a batch write split across several calls has got to
+    ///
+    /// Zomes store this as an entry after each call so the next call, or an
+    /// operator, can see how far the batch has come.
+    #[hdk_entry_helper]
+    #[derive(Clone, PartialEq)]
+    pub struct BatchWriteProgress {
+        pub batch_id: String,
+        pub name: String,
+        pub total_items: u32,
+        /// Index of the first item not yet attempted
+        pub next_index: u32,
+        pub committed: u32,
+        pub failed: u32,
+        pub calls: u32,
+        pub started_at: Timestamp,
+        pub updated_at: Timestamp,
+    }
+
+    impl BatchWriteProgress {
+        pub fn new(name: &str, total_items: u32, started_at: Timestamp) -> Self {
+            Self {
+                batch_id: format!("{}-{}", name, started_at.as_micros()),
+                name: name.to_string(),
+                total_items,
+                next_index: 0,
+                committed: 0,
+                failed: 0,
+                calls: 0,
+                started_at,
+                updated_at: started_at,
+            }
+        }
+
+        pub fn is_complete(&self) -> bool {
+            self.next_index >= self.total_items
+        }
+
+        pub fn remaining(&self) -> u32 {
+            self.total_items.saturating_sub(self.next_index)
+        }
+
+        /// Check that the counters add up
+        pub fn validate(&self) -> Result<(), String> {
+            if self.batch_id.trim().is_empty() || self.name.trim().is_empty() {
+                return Err("Batch progress needs an id and a name".to_string());
+            }
+            if self.next_index > self.total_items {
+                return Err("Batch progress is past the end of the batch".to_string());
+            }
+            if self.committed as u64 + self.failed as u64 != self.next_index as u64 {
+                return Err("Committed and failed items must add up to the items attempted".to_string());
+            }
+            if self.updated_at < self.started_at {
+                return Err("Batch progress cannot be updated before it started".to_string());
+            }
+            Ok(())
+        }
+    }
+
+    /// Commit timing of one paced call, for tuning throughput against DHT load
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct BatchCommitMetrics {
+        pub attempted: u32,
+        pub committed: u32,
+        /// Wall time of the whole call
+        pub elapsed_ms: u64,
+        /// Time spent waiting out the commit spacing
+        pub spacing_wait_ms: u64,
+        pub mean_commit_ms: u64,
+        pub slowest_commit_ms: u64,
+        pub commits_per_minute: u64,
+    }
+
+    /// Outcome of one paced call; one failing item does not stop the rest
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BatchWriteResult<O> {
+        /// Results by index into the whole batch
+        pub succeeded: Vec<(usize, O)>,
+        pub failed: Vec<(usize, String)>,
+        pub progress: BatchWriteProgress,
+        pub metrics: BatchCommitMetrics,
+    }
+
+    impl<O> BatchWriteResult<O> {
+        /// Whether the whole batch has been attempted
+        pub fn is_complete(&self) -> bool {
+            self.progress.is_complete()
+        }
+    }
+
+    /// Write the next slice of `items` with commits spaced out under `pacing`
+    ///
+    /// Picks up at `progress.next_index` and attempts at most
+    /// `max_items_per_call` items. Store the returned progress and call again
+    /// with it until the result is complete.
+    pub fn paced_batch_write<I, O>(
+        items: &[I],
+        progress: BatchWriteProgress,
+        pacing: BatchWritePacing,
+        write: impl FnMut(&I) -> ExternResult<O>,
+    ) -> ExternResult<BatchWriteResult<O>> {
+        pacing.validate()?;
+        if progress.total_items as usize != items.len() {
+            return Err(invalid_input(format!(
+                "Batch progress expects {} items but {} were given",
+                progress.total_items,
+                items.len()
+            )));
+        }
+        Ok(paced_write_with(
+            items,
+            progress,
+            pacing,
+            write,
+            || sys_time().map(|now| now.as_micros()).unwrap_or(0),
+            resilience::wait_ms,
+        ))
+    }
+
+    /// Paced write loop with the host clock and wait passed in
+    pub(crate) fn paced_write_with<I, O>(
+        items: &[I],
+        mut progress: BatchWriteProgress,
+        pacing: BatchWritePacing,
+        mut write: impl FnMut(&I) -> ExternResult<O>,
+        clock: impl Fn() -> i64,
+        mut wait: impl FnMut(u64),
+    ) -> BatchWriteResult<O> {
+        let start = progress.next_index as usize;
+        let end = items.len().min(start + pacing.max_items_per_call as usize);
+        let mut succeeded = Vec::new();
+        let mut failed = Vec::new();
+        let mut metrics = BatchCommitMetrics::default();
+        let mut commit_total_us = 0i64;
+        let mut last_commit_at: Option<i64> = None;
+        let call_started = clock();
+
+        for (index, item) in items.iter().enumerate().take(end).skip(start) {
+            if let Some(last) = last_commit_at {
+                let since_ms = ((clock() - last).max(0) / 1_000) as u64;
+                let pause = pacing.commit_spacing_ms.saturating_sub(since_ms);
+                if pause > 0 {
+                    wait(pause);
+                    metrics.spacing_wait_ms += pause;
+                }
+            }
+            let commit_started = clock();
+            last_commit_at = Some(commit_started);
+            let outcome = write(item);
+            let commit_us = (clock() - commit_started).max(0);
+            commit_total_us += commit_us;
+            metrics.slowest_commit_ms = metrics.slowest_commit_ms.max((commit_us / 1_000) as u64);
+            metrics.attempted += 1;
+            match outcome {
+                Ok(output) => {
+                    metrics.committed += 1;
+                    succeeded.push((index, output));
+                }
+                Err(e) => failed.push((index, e.to_string())),
+            }
+        }
+
+        let finished = clock();
+        metrics.elapsed_ms = ((finished - call_started).max(0) / 1_000) as u64;
+        if metrics.attempted > 0 {
+            metrics.mean_commit_ms = (commit_total_us / metrics.attempted as i64 / 1_000) as u64;
+        }
+        metrics.commits_per_minute = (metrics.committed as u64 * 60_000)
+            .checked_div(metrics.elapsed_ms)
+            .unwrap_or(0);
+
+        progress.next_index = end.max(start) as u32;
+        progress.committed += metrics.committed;
+        progress.failed += metrics.attempted - metrics.committed;
+        progress.calls += 1;
+        progress.updated_at = Timestamp::from_micros(finished.max(progress.updated_at.as_micros()));
+
+        BatchWriteResult { succeeded, failed, progress, metrics }
+    }
+}
+
+/// Read strategy module - GetOptions and retries chosen per call class
+///
+/// Guest code cannot sleep, so backoff is expressed as escalation: a
+/// policy moves from cheap local reads to network reads instead of waiting.
+pub mod reads {
+    use super::*;
+
+    /// What a read or cross-zome call is for
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum CallClass {
+        /// Authorization checks: answer from local data, go to the network only on a miss
+        Authorization,
+        /// Consent status: must see the latest revocation, so always ask the network
+        ConsentStatus,
+        /// Audit trail writes: must not be dropped on a transient network error
+        Audit,
+        /// Listings and batch fetches: local first, network for anything not held locally
+        #[default]
+        Bulk,
+    }
+
+    /// Ordered attempts for a call class
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct CallPolicy {
+        /// Strategies tried in turn; the next one is used after an error
+        pub attempts: Vec<GetStrategy>,
+        /// Whether an empty result also moves on to the next attempt
+        pub retry_on_miss: bool,
+    }
+
+    impl CallClass {
+        pub fn policy(self) -> CallPolicy {
+            match self {
+                CallClass::Authorization | CallClass::Bulk => CallPolicy {
+                    attempts: vec![GetStrategy::Local, GetStrategy::Network],
+                    retry_on_miss: true,
+                },
+                // No local fallback: a stale consent could miss a revocation,
+                // and an audit write must reach its authorities
+                CallClass::ConsentStatus | CallClass::Audit => CallPolicy {
+                    attempts: vec![GetStrategy::Network; 3],
+                    retry_on_miss: false,
+                },
+            }
+        }
+    }
+
+    /// `get` following the policy of `class`
+    pub fn get_for<H>(hash: H, class: CallClass) -> ExternResult<Option<Record>>
+    where
+        H: Clone,
+        AnyDhtHash: From<H>,
+    {
+        let policy = class.policy();
+        let mut last_error = None;
+        for strategy in policy.attempts {
+            performance::count_dht_get();
+            match get(hash.clone(), GetOptions { strategy }) {
+                Ok(Some(record)) => return Ok(Some(record)),
+                Ok(None) if policy.retry_on_miss => last_error = None,
+                Ok(None) => return Ok(None),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    /// `get_details` of a record following the policy of `class`
+    pub fn get_details_for(hash: ActionHash, class: CallClass) -> ExternResult<Option<RecordDetails>> {
+        let policy = class.policy();
+        let mut last_error = None;
+        for strategy in policy.attempts {
+            performance::count_dht_get();
+            match get_details(hash.clone(), GetOptions { strategy }) {
+                Ok(Some(Details::Record(details))) => return Ok(Some(details)),
+                Ok(_) if policy.retry_on_miss => last_error = None,
+                Ok(_) => return Ok(None),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(None),
+        }
+    }
+
+    /// `get_links` following the policy of `class`
+    pub fn get_links_for(query: LinkQuery, class: CallClass) -> ExternResult<Vec<Link>> {
+        let policy = class.policy();
+        let mut last_error = None;
+        for strategy in policy.attempts {
+            performance::count_link_query();
+            match get_links(query.clone(), strategy) {
+                Ok(links) if links.is_empty() && policy.retry_on_miss => last_error = None,
+                Ok(links) => return Ok(links),
+                Err(e) => last_error = Some(e),
+            }
+        }
+        match last_error {
+            Some(e) => Err(e),
+            None => Ok(Vec::new()),
+        }
+    }
+}
+
+/// Response budget module - keeps list responses within guest memory
+///
+/// Getters add items one at a time and stop once the serialized size would
+/// pass the budget, returning what fits with a token to resume from instead
+/// of exhausting memory mid-call.
+pub mod response_budget {
+    use super::*;
+
+    /// Budget when the caller does not ask for one
+    pub const DEFAULT_RESPONSE_BUDGET_BYTES: usize = 1024 * 1024;
+
+    /// Largest budget a caller may ask for
+    pub const MAX_RESPONSE_BUDGET_BYTES: usize = 8 * 1024 * 1024;
+
+    /// Allowance for the framing each item adds to the response
+    const ITEM_OVERHEAD_BYTES: usize = 16;
+
+    const TOKEN_PREFIX: &str = "rb1:";
+
+    /// Where to resume and how much to return; the default starts at the
+    /// beginning with the default budget
+    #[derive(Clone, Debug, Default, Serialize, Deserialize)]
+    pub struct BudgetInput {
+        /// Token from a previous truncated response
+        pub continuation: Option<String>,
+        /// Response size budget, capped at `MAX_RESPONSE_BUDGET_BYTES`
+        pub max_bytes: Option<usize>,
+    }
+
+    /// Items that fit the budget
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BudgetedPage<T> {
+        pub items: Vec<T>,
+        /// Whether items were left out to stay within the budget
+        pub truncated: bool,
+        /// Pass back in `BudgetInput::continuation` for the next items
+        pub continuation: Option<String>,
+    }
+
+    impl<T> BudgetedPage<T> {
+        pub fn map<U>(self, f: impl FnMut(T) -> U) -> BudgetedPage<U> {
+            BudgetedPage {
+                items: self.items.into_iter().map(f).collect(),
+                truncated: self.truncated,
+                continuation: self.continuation,
+            }
+        }
+    }
+
+    /// Running size of a response being built
+    #[derive(Clone, Debug)]
+    pub struct ResponseBudget {
+        max_bytes: usize,
+        used_bytes: usize,
+        admitted: usize,
+    }
+
+    impl ResponseBudget {
+        pub fn new(max_bytes: Option<usize>) -> ExternResult<Self> {
+            let max_bytes = max_bytes.unwrap_or(DEFAULT_RESPONSE_BUDGET_BYTES);
+            if max_bytes == 0 || max_bytes > MAX_RESPONSE_BUDGET_BYTES {
+                return Err(HealthError::ValidationError(format!(
+                    "Response budget must be between 1 and {} bytes",
+                    MAX_RESPONSE_BUDGET_BYTES
+                ))
+                .field("max_bytes")
+                .into());
+            }
+            Ok(Self { max_bytes, used_bytes: 0, admitted: 0 })
+        }
+
+        /// Count `item` against the budget if it fits
+        ///
+        /// The first item is always admitted so a single oversized item
+        /// cannot stall a caller that follows continuation tokens.
+        pub fn admit<T: Serialize + std::fmt::Debug>(&mut self, item: &T) -> ExternResult<bool> {
+            let size = estimated_size(item)?;
+            if self.admitted > 0 && self.used_bytes + size > self.max_bytes {
+                return Ok(false);
+            }
+            self.used_bytes += size;
+            self.admitted += 1;
+            Ok(true)
+        }
+
+        pub fn used_bytes(&self) -> usize {
+            self.used_bytes
+        }
+    }
+
+    /// Serialized size of `item` as it will travel in the response
+    pub fn estimated_size<T: Serialize + std::fmt::Debug>(item: &T) -> ExternResult<usize> {
+        let encoded = ExternIO::encode(item).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(encoded.0.len() + ITEM_OVERHEAD_BYTES)
+    }
+
+    pub fn continuation_token(position: usize) -> String {
+        format!("{}{}", TOKEN_PREFIX, position)
+    }
+
+    /// Source position a token resumes from; 0 without a token
+    pub fn continuation_position(token: Option<&str>) -> ExternResult<usize> {
+        match token {
+            None => Ok(0),
+            Some(token) => token
+                .strip_prefix(TOKEN_PREFIX)
+                .and_then(|position| position.parse().ok())
+                .ok_or(HealthError::ValidationError("Invalid continuation token".to_string()).field("continuation").into()),
+        }
+    }
+
+    /// Fetch items for `source` in order until the budget runs out
+    ///
+    /// `fetch` turns one source element (a link, say) into an item, or
+    /// `None` to return nothing for it. Elements before the continuation
+    /// are not fetched again.
+    pub fn budgeted_page<S, T>(
+        source: impl IntoIterator<Item = S>,
+        input: &BudgetInput,
+        mut fetch: impl FnMut(S) -> ExternResult<Option<T>>,
+    ) -> ExternResult<BudgetedPage<T>>
+    where
+        T: Serialize + std::fmt::Debug,
+    {
+        let start = continuation_position(input.continuation.as_deref())?;
+        let mut budget = ResponseBudget::new(input.max_bytes)?;
+        let mut items = Vec::new();
+        for (position, element) in source.into_iter().enumerate().skip(start) {
+            let Some(item) = fetch(element)? else {
+                continue;
+            };
+            if !budget.admit(&item)? {
+                return Ok(BudgetedPage {
+                    items,
+                    truncated: true,
+                    continuation: Some(continuation_token(position)),
+                });
+            }
+            items.push(item);
+        }
+        Ok(BudgetedPage { items, truncated: false, continuation: None })
+    }
+
+    /// Link targets fetched as records until the budget runs out
+    pub fn links_to_records_budgeted(links: Vec<Link>, input: &BudgetInput) -> ExternResult<BudgetedPage<Record>> {
+        budgeted_page(links, input, |link| match link.target.into_action_hash() {
+            Some(hash) => reads::get_for(hash, reads::CallClass::Bulk),
+            None => Ok(None),
+        })
+    }
+}
+
+/// Resilient cross-zome calls - bounded retries with jittered backoff
+///
+/// Local calls run in the caller's workspace, so a failed attempt leaves no
+/// writes behind and is safe to repeat. Only transient failures (network,
+/// timeout, countersigning) are retried; an error returned by the callee is
+/// deterministic and is reported straight away.
+pub mod resilience {
+    use super::*;
+    use serde::de::DeserializeOwned;
+
+    /// Why a cross-zome call failed
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum CallFailure {
+        Network(String),
+        Timeout(String),
+        Countersigning(String),
+        Unauthorized,
+        AuthenticationFailed,
+        /// The callee ran and returned an error
+        Rejected(String),
+        Decode(String),
+    }
+
+    impl CallFailure {
+        /// Whether another attempt could succeed
+        pub fn is_transient(&self) -> bool {
+            matches!(
+                self,
+                CallFailure::Network(_) | CallFailure::Timeout(_) | CallFailure::Countersigning(_)
+            )
+        }
+
+        /// Categorize an error raised by the `call` host function
+        pub fn from_wasm_error(error: &WasmError) -> Self {
+            let message = error.to_string();
+            let lower = message.to_lowercase();
+            if lower.contains("timeout") || lower.contains("timed out") {
+                CallFailure::Timeout(message)
+            } else if lower.contains("network") {
+                CallFailure::Network(message)
+            } else {
+                CallFailure::Rejected(message)
+            }
+        }
+
+        /// Guest error naming what the call was doing, e.g. "checking authorization"
+        ///
+        /// A structured error from the callee keeps its code, with `doing`
+        /// added under `details.during`.
+        pub fn into_wasm_error(self, doing: &str) -> WasmError {
+            self.into_payload(doing).into()
+        }
+
+        pub fn into_payload(self, doing: &str) -> HealthErrorPayload {
+            let error = match self {
+                CallFailure::Rejected(err) => match HealthErrorPayload::from_guest_message(&err) {
+                    Some(payload) => return payload.detail("during", doing),
+                    None => HealthError::InternalError(format!("Failed {}: {}", doing, err)),
+                },
+                CallFailure::Network(err) => HealthError::Unavailable(format!("Network error {}: {}", doing, err)),
+                CallFailure::Timeout(err) => HealthError::Unavailable(format!("Timed out {}: {}", doing, err)),
+                CallFailure::Countersigning(err) => {
+                    HealthError::Unavailable(format!("Countersigning error {}: {}", doing, err))
+                }
+                CallFailure::Unauthorized => HealthError::Unauthorized(format!("Unauthorized {}", doing)),
+                CallFailure::AuthenticationFailed => {
+                    HealthError::Unauthorized(format!("Authentication failed {}", doing))
+                }
+                CallFailure::Decode(err) => {
+                    HealthError::InternalError(format!("Failed to decode response {}: {}", doing, err))
+                }
+            };
+            HealthErrorPayload::from(error)
+        }
+    }
+
+    impl std::fmt::Display for CallFailure {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "{:?}", self)
+        }
+    }
+
+    /// Attempt limits and backoff for one call
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct RetryPolicy {
+        pub max_attempts: u32,
+        pub base_delay_ms: u64,
+        pub max_delay_ms: u64,
+        /// Total backoff allowed across attempts, so retries cannot eat the
+        /// caller's own zome call timeout
+        pub wait_budget_ms: u64,
+    }
+
+    impl RetryPolicy {
+        pub fn for_class(class: reads::CallClass) -> Self {
+            match class {
+                reads::CallClass::Authorization => Self { max_attempts: 3, base_delay_ms: 20, max_delay_ms: 100, wait_budget_ms: 200 },
+                reads::CallClass::ConsentStatus => Self { max_attempts: 3, base_delay_ms: 25, max_delay_ms: 200, wait_budget_ms: 300 },
+                // Audit writes must not be dropped, so they get the longest budget
+                reads::CallClass::Audit => Self { max_attempts: 5, base_delay_ms: 25, max_delay_ms: 400, wait_budget_ms: 1_000 },
+                reads::CallClass::Bulk => Self { max_attempts: 2, base_delay_ms: 20, max_delay_ms: 100, wait_budget_ms: 100 },
+            }
+        }
+
+        /// Delay before retry number `retry` (0-based): exponential, capped,
+        /// with jitter drawn from the upper half of the window
+        pub fn backoff_ms(&self, retry: u32, jitter: u8) -> u64 {
+            let window = self
+                .base_delay_ms
+                .saturating_mul(1u64.checked_shl(retry).unwrap_or(u64::MAX))
+                .min(self.max_delay_ms);
+            window / 2 + (window / 2) * jitter as u64 / u8::MAX as u64
+        }
+    }
+
+    /// Retry loop with the host effects passed in
+    pub(crate) fn retry_with<T>(
+        policy: RetryPolicy,
+        mut attempt: impl FnMut() -> Result<T, CallFailure>,
+        mut jitter: impl FnMut() -> u8,
+        mut wait: impl FnMut(u64),
+    ) -> Result<T, CallFailure> {
+        let mut waited = 0u64;
+        let mut retry = 0u32;
+        loop {
+            let failure = match attempt() {
+                Ok(value) => return Ok(value),
+                Err(failure) => failure,
+            };
+            if !failure.is_transient() || retry + 1 >= policy.max_attempts.max(1) {
+                return Err(failure);
+            }
+            let remaining = policy.wait_budget_ms.saturating_sub(waited);
+            if remaining == 0 {
+                return Err(failure);
+            }
+            let delay = policy.backoff_ms(retry, jitter()).min(remaining);
+            wait(delay);
+            waited += delay;
+            retry += 1;
+        }
+    }
+
+    /// Guest code cannot sleep, so wait by polling the host clock
+    pub(crate) fn wait_ms(ms: u64) {
+        let Ok(start) = sys_time() else { return };
+        let deadline = start.as_micros() + (ms as i64) * 1_000;
+        while sys_time().map(|now| now.as_micros() < deadline).unwrap_or(false) {}
+    }
+
+    fn jitter_byte() -> u8 {
+        random_bytes(1).ok().and_then(|bytes| bytes.first().copied()).unwrap_or(u8::MAX / 2)
+    }
+
+    fn call_once<I, O>(zome: &str, function: &str, input: &I) -> Result<O, CallFailure>
+    where
+        I: Serialize + std::fmt::Debug,
+        O: DeserializeOwned + std::fmt::Debug,
+    {
+        match call(CallTargetCell::Local, zome, function.into(), None, input) {
+            Ok(ZomeCallResponse::Ok(extern_io)) => {
+                extern_io.decode().map_err(|e| CallFailure::Decode(format!("{:?}", e)))
+            }
+            Ok(ZomeCallResponse::NetworkError(err)) => Err(CallFailure::Network(err)),
+            Ok(ZomeCallResponse::CountersigningSession(err)) => Err(CallFailure::Countersigning(err)),
+            Ok(ZomeCallResponse::Unauthorized(..)) => Err(CallFailure::Unauthorized),
+            Ok(ZomeCallResponse::AuthenticationFailed(..)) => Err(CallFailure::AuthenticationFailed),
+            Err(e) => Err(CallFailure::from_wasm_error(&e)),
+        }
+    }
+
+    /// Local cross-zome call retried under the policy of `class`
+    pub fn resilient_call<I, O>(zome: &str, function: &str, input: &I, class: reads::CallClass) -> Result<O, CallFailure>
+    where
+        I: Serialize + std::fmt::Debug,
+        O: DeserializeOwned + std::fmt::Debug,
+    {
+        retry_with(
+            RetryPolicy::for_class(class),
+            || call_once(zome, function, input),
+            jitter_byte,
+            wait_ms,
+        )
+    }
+
+    /// Outcome of a batch of calls; one failing input does not discard the rest
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct BatchCallResult<O> {
+        /// Results by input index
+        pub succeeded: Vec<(usize, O)>,
+        pub failed: Vec<(usize, CallFailure)>,
+    }
+
+    impl<O> BatchCallResult<O> {
+        pub fn is_complete(&self) -> bool {
+            self.failed.is_empty()
+        }
+    }
+
+    /// Call `function` once per input, retrying each under the policy of `class`
+    pub fn resilient_call_batch<I, O>(zome: &str, function: &str, inputs: &[I], class: reads::CallClass) -> BatchCallResult<O>
+    where
+        I: Serialize + std::fmt::Debug,
+        O: DeserializeOwned + std::fmt::Debug,
+    {
+        let mut result = BatchCallResult { succeeded: Vec::new(), failed: Vec::new() };
+        for (index, input) in inputs.iter().enumerate() {
+            match resilient_call(zome, function, input, class) {
+                Ok(output) => result.succeeded.push((index, output)),
+                Err(failure) => result.failed.push((index, failure)),
+            }
+        }
+        result
+    }
+}
+
+/// Rate limits - throttle tiers by persona, enforced before authorization
+///
+/// The consent zome assigns each agent a tier from its registrations and
+/// counts the accesses it has logged in the current window; every
+/// authorization checks that count first.
+pub mod rate_limits {
+    use super::*;
+
+    /// Throttle tier, lowest ceiling first
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum ThrottleTier {
+        /// Patients and agents without a role registration
+        #[default]
+        Patient,
+        /// Agents with a provider profile
+        Provider,
+        /// Agents with an active service registration (integration bridges)
+        Integration,
+    }
+
+    /// How many logged accesses a tier may make per window
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct TierLimits {
+        pub requests: u32,
+        pub window_seconds: u32,
+    }
+
+    impl ThrottleTier {
+        pub fn limits(&self) -> TierLimits {
+            match self {
+                ThrottleTier::Patient => TierLimits { requests: 120, window_seconds: 60 },
+                ThrottleTier::Provider => TierLimits { requests: 600, window_seconds: 60 },
+                ThrottleTier::Integration => TierLimits { requests: 3_000, window_seconds: 60 },
+            }
+        }
+    }
+
+    /// Where an agent stands against its tier's ceiling
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct RateLimitStatus {
+        pub tier: ThrottleTier,
+        pub limit: u32,
+        pub window_seconds: u32,
+        /// Accesses logged in the current window
+        pub used: u32,
+        pub remaining: u32,
+        /// When the oldest access in the window ages out; `None` when the
+        /// window is empty
+        pub resets_at: Option<Timestamp>,
+    }
+
+    impl RateLimitStatus {
+        /// Status from the times of the agent's logged accesses, in any order
+        pub fn from_request_times(tier: ThrottleTier, times: &[Timestamp], now: Timestamp) -> Self {
+            let limits = tier.limits();
+            let (used, resets_at) = window_usage(times, now, limits.window_seconds);
+            Self {
+                tier,
+                limit: limits.requests,
+                window_seconds: limits.window_seconds,
+                used,
+                remaining: limits.requests.saturating_sub(used),
+                resets_at,
+            }
+        }
+
+        pub fn exceeded(&self) -> bool {
+            self.remaining == 0
+        }
+    }
+
+    /// How many of `times` fall in the window ending at `now`, and when the
+    /// oldest of them ages out
+    fn window_usage(times: &[Timestamp], now: Timestamp, window_seconds: u32) -> (u32, Option<Timestamp>) {
+        let window_micros = window_seconds as i64 * 1_000_000;
+        let window_start = now.as_micros() - window_micros;
+        let in_window = times.iter().filter(|t| t.as_micros() > window_start);
+        let used = in_window.clone().count() as u32;
+        let resets_at = in_window
+            .map(|t| t.as_micros())
+            .min()
+            .map(|oldest| Timestamp::from_micros(oldest + window_micros));
+        (used, resets_at)
+    }
+
+    /// A `LimitExceeded` error carrying the time the caller may retry
+    pub fn rate_limited(message: impl Into<String>, retry_after: Option<Timestamp>) -> WasmError {
+        let mut error = HealthErrorPayload::from(HealthError::LimitExceeded(message.into()));
+        if let Some(retry_after) = retry_after {
+            error = error.detail("retry_after", retry_after.as_micros());
+        }
+        error.into()
+    }
+
+    /// Reject the caller once its tier's ceiling is reached
+    ///
+    /// Fails open when the consent zome cannot be reached; authorization
+    /// itself still needs it for anything but a patient's own data.
+    pub fn enforce_rate_limit() -> ExternResult<()> {
+        let status: RateLimitStatus = match resilience::resilient_call(
+            "consent",
+            "get_my_rate_limit_status",
+            &(),
+            reads::CallClass::Authorization,
+        ) {
+            Ok(status) => status,
+            Err(_) => return Ok(()),
+        };
+        if status.exceeded() {
+            return Err(rate_limited(
+                format!(
+                    "Rate limit exceeded: {:?} tier allows {} requests per {} seconds; retry after {:?}",
+                    status.tier, status.limit, status.window_seconds, status.resets_at
+                ),
+                status.resets_at,
+            ));
+        }
+        Ok(())
+    }
+
+    /// Operations throttled on their own, whatever the agent's tier
+    ///
+    /// Denied authorizations are not among them: a denial fails the call,
+    /// which discards its access log, so there is nothing durable to count.
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum SensitiveOperation {
+        /// Break-glass accesses
+        EmergencyOverride,
+        /// Accesses with the `Export` permission
+        Export,
+    }
+
+    impl SensitiveOperation {
+        /// Sliding window each operation is counted over
+        pub fn limits(&self) -> TierLimits {
+            match self {
+                SensitiveOperation::EmergencyOverride => TierLimits { requests: 5, window_seconds: 3_600 },
+                SensitiveOperation::Export => TierLimits { requests: 30, window_seconds: 3_600 },
+            }
+        }
+    }
+
+    /// Where an agent stands against one operation's sliding window
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct OperationRateStatus {
+        pub operation: SensitiveOperation,
+        pub limit: u32,
+        pub window_seconds: u32,
+        /// Times the operation appears on the agent's chain in the window
+        pub used: u32,
+        pub remaining: u32,
+        /// When the oldest occurrence in the window ages out; `None` when
+        /// the window is empty
+        pub resets_at: Option<Timestamp>,
+    }
+
+    impl OperationRateStatus {
+        /// Status from the times the agent performed `operation`, in any order
+        pub fn from_times(operation: SensitiveOperation, times: &[Timestamp], now: Timestamp) -> Self {
+            let limits = operation.limits();
+            let (used, resets_at) = window_usage(times, now, limits.window_seconds);
+            Self {
+                operation,
+                limit: limits.requests,
+                window_seconds: limits.window_seconds,
+                used,
+                remaining: limits.requests.saturating_sub(used),
+                resets_at,
+            }
+        }
+
+        pub fn exceeded(&self) -> bool {
+            self.remaining == 0
+        }
+    }
+
+    /// Reject the caller once `operation` has used up its window
+    ///
+    /// The consent zome counts the operation from the access logs on the
+    /// caller's chain. Fails closed: when the consent zome cannot be
+    /// reached the operation is refused.
+    pub fn enforce_operation_limit(operation: SensitiveOperation) -> ExternResult<()> {
+        let status: OperationRateStatus = resilience::resilient_call(
+            "consent",
+            "get_my_operation_rate_status",
+            &operation,
+            reads::CallClass::Authorization,
+        )
+        .map_err(|failure| failure.into_wasm_error("checking operation limits"))?;
+        if status.exceeded() {
+            return Err(error_with_detail(
+                rate_limited(
+                    format!(
+                        "Rate limited: {:?} allowed {} times per {} seconds; retry after {:?}",
+                        operation, status.limit, status.window_seconds, status.resets_at
+                    ),
+                    status.resets_at,
+                ),
+                "operation",
+                format!("{:?}", operation),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Performance metrics - per-extern call counts, durations, DHT reads and
+/// error rates
+///
+/// Measurements accumulate in the wasm instance and are flushed to the
+/// consent zome as `PerformanceSample` entries once enough calls or time
+/// have gone by. Anything still buffered when the host drops the instance
+/// is lost, so the figures are indicative rather than exact. DHT reads are
+/// counted when they go through [`reads::get_for`] and
+/// [`reads::get_links_for`]; every [`audit::audited_read`] is measured.
+pub mod performance {
+    use super::*;
+    use std::cell::RefCell;
+    use std::collections::BTreeMap;
+
+    /// Flush once this many calls are buffered
+    pub const FLUSH_AFTER_CALLS: u64 = 50;
+    /// Flush once the oldest buffered call is this old (five minutes)
+    pub const FLUSH_INTERVAL_MICROS: i64 = 5 * 60 * 1_000_000;
+
+    /// Totals for one extern over a sample window
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct ExternTimings {
+        pub zome: String,
+        pub function: String,
+        pub calls: u64,
+        pub errors: u64,
+        pub total_micros: u64,
+        pub max_micros: u64,
+        pub dht_gets: u64,
+        pub link_queries: u64,
+    }
+
+    impl ExternTimings {
+        fn absorb(&mut self, other: &ExternTimings) {
+            self.calls += other.calls;
+            self.errors += other.errors;
+            self.total_micros += other.total_micros;
+            self.max_micros = self.max_micros.max(other.max_micros);
+            self.dht_gets += other.dht_gets;
+            self.link_queries += other.link_queries;
+        }
+    }
+
+    /// A window of buffered timings, as handed to the consent zome
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct PerformanceSampleInput {
+        pub window_start: Timestamp,
+        pub window_end: Timestamp,
+        pub externs: Vec<ExternTimings>,
+    }
+
+    /// Aggregated figures for one extern
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct ExternMetrics {
+        pub zome: String,
+        pub function: String,
+        pub calls: u64,
+        pub errors: u64,
+        /// Share of calls that returned an error
+        pub error_rate: f64,
+        pub mean_ms: f64,
+        pub max_ms: f64,
+        pub gets_per_call: f64,
+        pub link_queries_per_call: f64,
+    }
+
+    impl ExternMetrics {
+        fn from_totals(totals: &ExternTimings) -> Self {
+            let per_call = |value: u64| value as f64 / totals.calls.max(1) as f64;
+            Self {
+                zome: totals.zome.clone(),
+                function: totals.function.clone(),
+                calls: totals.calls,
+                errors: totals.errors,
+                error_rate: per_call(totals.errors),
+                mean_ms: per_call(totals.total_micros) / 1_000.0,
+                max_ms: totals.max_micros as f64 / 1_000.0,
+                gets_per_call: per_call(totals.dht_gets),
+                link_queries_per_call: per_call(totals.link_queries),
+            }
+        }
+    }
+
+    /// Merge timings per zome and function, most total time first
+    pub fn aggregate_timings<'a>(timings: impl IntoIterator<Item = &'a ExternTimings>) -> Vec<ExternMetrics> {
+        let mut merged: BTreeMap<(String, String), ExternTimings> = BTreeMap::new();
+        for timing in timings {
+            merged
+                .entry((timing.zome.clone(), timing.function.clone()))
+                .or_insert_with(|| ExternTimings {
+                    zome: timing.zome.clone(),
+                    function: timing.function.clone(),
+                    ..Default::default()
+                })
+                .absorb(timing);
+        }
+        let mut totals: Vec<ExternTimings> = merged.into_values().collect();
+        totals.sort_by_key(|t| std::cmp::Reverse(t.total_micros));
+        totals.iter().map(ExternMetrics::from_totals).collect()
+    }
+
+    #[derive(Default)]
+    struct Buffer {
+        window_start: Option<Timestamp>,
+        calls: u64,
+        externs: BTreeMap<(String, String), ExternTimings>,
+        /// Whether a measured call is running; nested ones are folded into it
+        measuring: bool,
+        gets: u64,
+        link_queries: u64,
+    }
+
+    thread_local! {
+        static BUFFER: RefCell<Buffer> = RefCell::new(Buffer::default());
+    }
+
+    /// Count a DHT `get` against the call being measured
+    pub fn count_dht_get() {
+        BUFFER.with(|buffer| buffer.borrow_mut().gets += 1);
+    }
+
+    /// Count a `get_links` against the call being measured
+    pub fn count_link_query() {
+        BUFFER.with(|buffer| buffer.borrow_mut().link_queries += 1);
+    }
+
+    /// Run an extern body, recording its duration, DHT reads and outcome
+    ///
+    /// Calls made while another is measured count towards the outer one.
+    pub fn instrumented<T>(zome: &str, function: &str, body: impl FnOnce() -> ExternResult<T>) -> ExternResult<T> {
+        let nested = BUFFER.with(|buffer| {
+            let mut buffer = buffer.borrow_mut();
+            let nested = buffer.measuring;
+            if !nested {
+                buffer.measuring = true;
+                buffer.gets = 0;
+                buffer.link_queries = 0;
+            }
+            nested
+        });
+        if nested {
+            return body();
+        }
+
+        let started = sys_time().ok();
+        let result = body();
+        let finished = sys_time().ok();
+        let elapsed = match (started, finished) {
+            (Some(started), Some(finished)) => (finished.as_micros() - started.as_micros()).max(0) as u64,
+            _ => 0,
+        };
+        BUFFER.with(|buffer| {
+            let mut buffer = buffer.borrow_mut();
+            buffer.measuring = false;
+            let (gets, link_queries) = (buffer.gets, buffer.link_queries);
+            if buffer.window_start.is_none() {
+                buffer.window_start = started;
+            }
+            buffer.calls += 1;
+            let timings = buffer
+                .externs
+                .entry((zome.to_string(), function.to_string()))
+                .or_insert_with(|| ExternTimings {
+                    zome: zome.to_string(),
+                    function: function.to_string(),
+                    ..Default::default()
+                });
+            timings.absorb(&ExternTimings {
+                calls: 1,
+                errors: u64::from(result.is_err()),
+                total_micros: elapsed,
+                max_micros: elapsed,
+                dht_gets: gets,
+                link_queries,
+                ..Default::default()
+            });
+        });
+        if let Some(now) = finished {
+            flush_if_due(now);
+        }
+        result
+    }
+
+    /// Hand buffered timings to the consent zome once enough have gathered
+    /// (best effort: kept for the next attempt if the call fails)
+    fn flush_if_due(now: Timestamp) {
+        let sample = BUFFER.with(|buffer| {
+            let buffer = buffer.borrow();
+            let window_start = buffer.window_start?;
+            let due = buffer.calls >= FLUSH_AFTER_CALLS
+                || now.as_micros() - window_start.as_micros() >= FLUSH_INTERVAL_MICROS;
+            due.then(|| PerformanceSampleInput {
+                window_start,
+                window_end: now,
+                externs: buffer.externs.values().cloned().collect(),
+            })
+        });
+        let Some(sample) = sample else { return };
+        let recorded: Result<ActionHash, resilience::CallFailure> = resilience::resilient_call(
+            "consent",
+            "record_performance_sample",
+            &sample,
+            reads::CallClass::Bulk,
+        );
+        if recorded.is_ok() {
+            BUFFER.with(|buffer| {
+                let mut buffer = buffer.borrow_mut();
+                buffer.window_start = None;
+                buffer.calls = 0;
+                buffer.externs.clear();
+            });
+        }
+    }
+}
+
+/// Data residency - jurisdiction tags on patients and the uses of their
+/// data that a jurisdiction's policy prohibits
+///
+/// Policies live in the consent zome; the patient zome holds each patient's
+/// jurisdiction. Export and cohort discovery paths check the use before
+/// touching data, and contributions carry the tag onwards.
+pub mod residency {
+    use super::*;
+
+    /// A use of patient data a jurisdiction policy can prohibit; names match
+    /// the permitted and prohibited uses on data contributions
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum DataUse {
+        Treatment,
+        /// FHIR bundle exports leaving the network
+        Export,
+        /// Screening patients against research cohorts
+        CohortDiscovery,
+        AcademicResearch,
+        DrugDevelopment,
+        ClinicalDecisionSupport,
+        Marketing,
+        InsuranceUnderwriting,
+        EmploymentDecisions,
+    }
+
+    /// A patient's jurisdiction and what its policy prohibits
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct PatientResidency {
+        /// `None` for patients onboarded without a jurisdiction
+        pub jurisdiction: Option<String>,
+        pub prohibited_uses: Vec<DataUse>,
+    }
+
+    impl PatientResidency {
+        pub fn allows(&self, data_use: DataUse) -> bool {
+            !self.prohibited_uses.contains(&data_use)
+        }
+
+        /// Contribution use names with the prohibited ones removed
+        pub fn permitted_contribution_uses(&self, uses: &[String]) -> Vec<String> {
+            uses.iter()
+                .filter(|name| !self.prohibited_uses.iter().any(|u| format!("{:?}", u) == **name))
+                .cloned()
+                .collect()
+        }
+    }
+
+    /// Jurisdiction and prohibited uses for a patient, from the consent zome
+    pub fn patient_residency(patient_hash: &ActionHash) -> ExternResult<PatientResidency> {
+        resilience::resilient_call("consent", "get_patient_residency", patient_hash, reads::CallClass::Authorization)
+            .map_err(|failure| failure.into_wasm_error("reading jurisdiction policy"))
+    }
+
+    /// Refuse `data_use` when the patient's jurisdiction prohibits it;
+    /// returns the jurisdiction so derived data can carry the tag
+    pub fn require_jurisdiction_allows(patient_hash: &ActionHash, data_use: DataUse) -> ExternResult<Option<String>> {
+        let residency = patient_residency(patient_hash)?;
+        if !residency.allows(data_use) {
+            let jurisdiction = residency.jurisdiction.as_deref().unwrap_or("untagged");
+            return Err(HealthError::Unauthorized(format!(
+                "{:?} of data from {} patients is prohibited by jurisdiction policy",
+                data_use, jurisdiction
+            ))
+            .detail("jurisdiction", jurisdiction)
+            .into());
+        }
+        Ok(residency.jurisdiction)
+    }
+}
+
+/// Data retention - how long each category of a patient's data is kept in
+/// their jurisdiction, and what a sweep does with records past that
+///
+/// Operators set policies in the consent zome; the zomes holding the
+/// records sweep them, and every decision a sweep applies is written to the
+/// access log.
+pub mod retention {
+    use super::*;
+    use access_control::DataCategory;
+
+    /// Longest retention period a policy may set
+    pub const MAX_RETENTION_DAYS: u32 = 36_500;
+
+    /// What a sweep does with a record past its retention period
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum RetentionAction {
+        /// Flag the record for review and leave it readable
+        Mark,
+        /// Delete the record and the link that indexes it
+        Tombstone,
+    }
+
+    /// The retention policy in force for one category of a patient's data
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct ApplicableRetention {
+        /// Record holding the policy
+        pub policy_hash: ActionHash,
+        /// Jurisdiction the policy was set for, the patient's own or one
+        /// enclosing it
+        pub jurisdiction: String,
+        /// `All` for a policy covering categories without their own
+        pub category: DataCategory,
+        pub retention_days: u32,
+        pub action: RetentionAction,
+    }
+
+    impl ApplicableRetention {
+        /// Whether a record written at `recorded_at` is past retention at `now`
+        pub fn expired(&self, recorded_at: Timestamp, now: Timestamp) -> bool {
+            let retention_micros = self.retention_days as i64 * 86_400_000_000;
+            now.as_micros() - recorded_at.as_micros() > retention_micros
+        }
+    }
+
+    /// The policy for `category`: its own, else one set for all categories
+    pub fn retention_for<'a>(
+        policies: &'a [ApplicableRetention],
+        category: &DataCategory,
+    ) -> Option<&'a ApplicableRetention> {
+        policies
+            .iter()
+            .find(|policy| policy.category == *category)
+            .or_else(|| policies.iter().find(|policy| policy.category == DataCategory::All))
+    }
+
+    /// Retention policies in force for a patient, from the consent zome
+    pub fn patient_retention(patient_hash: &ActionHash) -> ExternResult<Vec<ApplicableRetention>> {
+        resilience::resilient_call("consent", "get_patient_retention", patient_hash, reads::CallClass::Authorization)
+            .map_err(|failure| failure.into_wasm_error("reading retention policy"))
+    }
+
+    /// A record a sweep found past its retention period
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct PurgeDecision {
+        pub record_hash: ActionHash,
+        pub category: DataCategory,
+        pub recorded_at: Timestamp,
+        pub policy_hash: ActionHash,
+        pub action: RetentionAction,
+        /// False on dry runs
+        pub applied: bool,
+    }
+
+    /// What a sweep of one patient's records in one zome decided
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct PurgeReport {
+        pub zome: String,
+        pub patient_hash: ActionHash,
+        pub swept_at: Timestamp,
+        pub dry_run: bool,
+        /// Records looked at
+        pub examined: u32,
+        /// Records past retention that an earlier sweep already marked
+        /// under the same policy
+        pub already_marked: u32,
+        pub decisions: Vec<PurgeDecision>,
+    }
+
+    impl PurgeReport {
+        pub fn count(&self, action: RetentionAction) -> usize {
+            self.decisions.iter().filter(|decision| decision.action == action).count()
+        }
+    }
+}
+
+/// Category sensitivity - how each data category is handled: encrypted at
+/// rest, reachable by break-glass access, notified instantly, offered for
+/// research
+///
+/// Operators set the matrix in the consent zome; categories it leaves out
+/// keep the built-in handling. Enforcement points ask the matrix instead of
+/// matching on categories themselves.
+pub mod sensitivity {
+    use super::*;
+    use access_control::DataCategory;
+
+    /// Every category a matrix can hold a row for
+    pub const SENSITIVITY_CATEGORIES: [DataCategory; 14] = [
+        DataCategory::Demographics,
+        DataCategory::Allergies,
+        DataCategory::Medications,
+        DataCategory::Diagnoses,
+        DataCategory::Procedures,
+        DataCategory::LabResults,
+        DataCategory::ImagingStudies,
+        DataCategory::VitalSigns,
+        DataCategory::Immunizations,
+        DataCategory::MentalHealth,
+        DataCategory::SubstanceAbuse,
+        DataCategory::SexualHealth,
+        DataCategory::GeneticData,
+        DataCategory::FinancialData,
+    ];
+
+    /// Handling flags for one data category
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct CategorySensitivity {
+        pub category: DataCategory,
+        /// Field-level encryption at rest and specific consent on import
+        pub encrypt: bool,
+        /// Emergency override may reach the category without a consent
+        pub break_glass_allowed: bool,
+        /// Access notifications are raised to immediate priority
+        pub notify_instantly: bool,
+        /// May be contributed to research and used for cohort discovery
+        pub research_allowed: bool,
+    }
+
+    impl CategorySensitivity {
+        /// Built-in handling for a category the matrix leaves out
+        pub fn default_for(category: &DataCategory) -> Self {
+            Self {
+                category: category.clone(),
+                encrypt: matches!(
+                    category,
+                    DataCategory::MentalHealth
+                        | DataCategory::SubstanceAbuse
+                        | DataCategory::SexualHealth
+                        | DataCategory::GeneticData
+                        | DataCategory::FinancialData
+                ),
+                break_glass_allowed: true,
+                notify_instantly: false,
+                research_allowed: true,
+            }
+        }
+    }
+
+    /// Operator overrides of the built-in category handling
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct SensitivityMatrix {
+        /// At most one row per category; `All` has no row of its own
+        pub categories: Vec<CategorySensitivity>,
+    }
+
+    impl SensitivityMatrix {
+        /// Handling for a category; `All` takes the strictest flag across
+        /// every category
+        pub fn for_category(&self, category: &DataCategory) -> CategorySensitivity {
+            if *category == DataCategory::All {
+                let rows: Vec<CategorySensitivity> =
+                    SENSITIVITY_CATEGORIES.iter().map(|c| self.for_category(c)).collect();
+                return CategorySensitivity {
+                    category: DataCategory::All,
+                    encrypt: rows.iter().any(|row| row.encrypt),
+                    break_glass_allowed: rows.iter().all(|row| row.break_glass_allowed),
+                    notify_instantly: rows.iter().any(|row| row.notify_instantly),
+                    research_allowed: rows.iter().all(|row| row.research_allowed),
+                };
+            }
+            self.categories
+                .iter()
+                .find(|row| row.category == *category)
+                .cloned()
+                .unwrap_or_else(|| CategorySensitivity::default_for(category))
+        }
+
+        pub fn requires_encryption(&self, category: &DataCategory) -> bool {
+            self.for_category(category).encrypt
+        }
+
+        pub fn allows_break_glass(&self, category: &DataCategory) -> bool {
+            self.for_category(category).break_glass_allowed
+        }
+
+        pub fn notifies_instantly(&self, category: &DataCategory) -> bool {
+            self.for_category(category).notify_instantly
+        }
+
+        pub fn allows_research(&self, category: &DataCategory) -> bool {
+            self.for_category(category).research_allowed
+        }
+
+        /// Every category with its effective handling
+        pub fn resolved(&self) -> Vec<CategorySensitivity> {
+            SENSITIVITY_CATEGORIES.iter().map(|c| self.for_category(c)).collect()
+        }
+
+        /// Reject rows for `All` and repeated categories
+        pub fn check(&self) -> Result<(), String> {
+            for (i, row) in self.categories.iter().enumerate() {
+                if row.category == DataCategory::All {
+                    return Err("The sensitivity matrix has no row for All".to_string());
+                }
+                if self.categories[..i].iter().any(|earlier| earlier.category == row.category) {
+                    return Err(format!("{} appears more than once in the sensitivity matrix", row.category));
+                }
+            }
+            Ok(())
+        }
+    }
+
+    /// The operator's matrix from the consent zome; the built-in handling
+    /// when it cannot be reached
+    pub fn sensitivity_matrix() -> SensitivityMatrix {
+        resilience::resilient_call("consent", "get_sensitivity_matrix", &(), reads::CallClass::Authorization)
+            .unwrap_or_default()
+    }
+}
+
+/// Site rules - validation a facility layers over the integrity zomes
+///
+/// Rule sets live in the consent zome and are selected per facility and
+/// entry type. Coordinators evaluate them on create and update, before the
+/// entry is committed; error-severity violations refuse the write and
+/// warnings are reported back.
+pub mod site_rules {
+    use super::*;
+    use serde_json::Value as JsonValue;
+
+    /// Whether a failed rule refuses the write
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum RuleSeverity {
+        Error,
+        Warning,
+    }
+
+    /// Check applied to the value at a rule's field path
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub enum RulePredicate {
+        /// Present, not null and not an empty string or list
+        Required,
+        MinLength(u32),
+        MaxLength(u32),
+        /// Character mask: `#` a digit, `@` a letter, `*` either, anything
+        /// else itself ("MRN-######")
+        Mask(String),
+        OneOf(Vec<String>),
+        NumericRange { min: Option<f64>, max: Option<f64> },
+    }
+
+    /// One site rule; `field_path` is dotted with numeric list indices
+    /// ("contact.phone", "allergies.0")
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct SiteValidationRule {
+        pub field_path: String,
+        pub predicate: RulePredicate,
+        pub severity: RuleSeverity,
+        /// Shown instead of the generated message
+        pub message: Option<String>,
+    }
+
+    /// A rule the entry failed
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct RuleViolation {
+        pub field_path: String,
+        pub severity: RuleSeverity,
+        pub message: String,
+    }
+
+    /// Input for evaluating the selected rule set against an entry
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct EvaluateSiteRulesInput {
+        pub entry_type: String,
+        pub entry: JsonValue,
+        /// Facility whose rules apply; `None` uses the caller's facility
+        pub facility: Option<String>,
+    }
+
+    /// Outcome of evaluating a rule set
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct RuleEvaluation {
+        /// Rule set that was applied; `None` when none is selected
+        pub rule_set_hash: Option<ActionHash>,
+        pub facility: Option<String>,
+        pub errors: Vec<RuleViolation>,
+        pub warnings: Vec<RuleViolation>,
+    }
+
+    impl RuleEvaluation {
+        pub fn into_result(self) -> ExternResult<Self> {
+            if self.errors.is_empty() {
+                return Ok(self);
+            }
+            let messages: Vec<String> = self
+                .errors
+                .iter()
+                .map(|v| format!("{}: {}", v.field_path, v.message))
+                .collect();
+            let mut error = HealthErrorPayload::from(HealthError::ValidationError(format!(
+                "Site validation failed for {}: {}",
+                self.facility.as_deref().unwrap_or("network"),
+                messages.join("; ")
+            )));
+            if let Some(first) = self.errors.first() {
+                error = error.field(&first.field_path);
+            }
+            Err(error.into())
+        }
+    }
+
+    /// Value at a dotted path, `None` when any segment is missing
+    pub fn resolve_field_path<'a>(value: &'a JsonValue, path: &str) -> Option<&'a JsonValue> {
+        path.split('.').try_fold(value, |current, segment| match current {
+            JsonValue::Array(items) => segment.parse::<usize>().ok().and_then(|i| items.get(i)),
+            JsonValue::Object(fields) => fields.get(segment),
+            _ => None,
+        })
+    }
+
+    fn matches_mask(value: &str, mask: &str) -> bool {
+        value.chars().count() == mask.chars().count()
+            && value.chars().zip(mask.chars()).all(|(c, m)| match m {
+                '#' => c.is_ascii_digit(),
+                '@' => c.is_ascii_alphabetic(),
+                '*' => c.is_ascii_alphanumeric(),
+                literal => c == literal,
+            })
+    }
+
+    fn value_length(value: &JsonValue) -> Option<usize> {
+        match value {
+            JsonValue::String(s) => Some(s.chars().count()),
+            JsonValue::Array(items) => Some(items.len()),
+            _ => None,
+        }
+    }
+
+    /// Message for a failed predicate, `None` when it holds
+    fn check_predicate(predicate: &RulePredicate, value: Option<&JsonValue>) -> Option<String> {
+        let present = value.filter(|v| !v.is_null() && value_length(v) != Some(0));
+        match predicate {
+            RulePredicate::Required => present.is_none().then(|| "is required".to_string()),
+            // Only Required fails on a missing value
+            _ if present.is_none() => None,
+            RulePredicate::MinLength(min) => present
+                .and_then(value_length)
+                .filter(|len| *len < *min as usize)
+                .map(|_| format!("must be at least {} long", min)),
+            RulePredicate::MaxLength(max) => present
+                .and_then(value_length)
+                .filter(|len| *len > *max as usize)
+                .map(|_| format!("must be at most {} long", max)),
+            RulePredicate::Mask(mask) => match present.and_then(|v| v.as_str()) {
+                Some(s) if matches_mask(s, mask) => None,
+                _ => Some(format!("must match {}", mask)),
+            },
+            RulePredicate::OneOf(allowed) => {
+                let text = present.map(|v| v.as_str().map(str::to_string).unwrap_or_else(|| v.to_string()));
+                match text {
+                    Some(t) if allowed.contains(&t) => None,
+                    _ => Some(format!("must be one of {}", allowed.join(", "))),
+                }
+            }
+            RulePredicate::NumericRange { min, max } => match present.and_then(|v| v.as_f64()) {
+                Some(n) if min.is_none_or(|m| n >= m) && max.is_none_or(|m| n <= m) => None,
+                _ => Some(format!(
+                    "must be a number in {}..={}",
+                    min.map(|m| m.to_string()).unwrap_or_default(),
+                    max.map(|m| m.to_string()).unwrap_or_default()
+                )),
+            },
+        }
+    }
+
+    /// Evaluate rules against a serialized entry
+    pub fn evaluate_site_rules(rules: &[SiteValidationRule], entry: &JsonValue) -> (Vec<RuleViolation>, Vec<RuleViolation>) {
+        let mut errors = Vec::new();
+        let mut warnings = Vec::new();
+        for rule in rules {
+            let Some(failure) = check_predicate(&rule.predicate, resolve_field_path(entry, &rule.field_path)) else {
+                continue;
+            };
+            let violation = RuleViolation {
+                field_path: rule.field_path.clone(),
+                severity: rule.severity,
+                message: rule.message.clone().unwrap_or(failure),
+            };
+            match rule.severity {
+                RuleSeverity::Error => errors.push(violation),
+                RuleSeverity::Warning => warnings.push(violation),
+            }
+        }
+        (errors, warnings)
+    }
+
+    /// Refuse an entry that breaks the selected site rules
+    ///
+    /// Call from create/update externs before committing. Fails open when
+    /// the consent zome cannot be reached; integrity validation still runs.
+    pub fn enforce_site_rules<T: Serialize>(entry_type: &str, entry: &T, facility: Option<String>) -> ExternResult<RuleEvaluation> {
+        let input = EvaluateSiteRulesInput {
+            entry_type: entry_type.to_string(),
+            entry: serde_json::to_value(entry)
+                .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?,
+            facility,
+        };
+        let evaluation: RuleEvaluation = match resilience::resilient_call(
+            "consent",
+            "evaluate_site_rules",
+            &input,
+            reads::CallClass::Authorization,
+        ) {
+            Ok(evaluation) => evaluation,
+            Err(_) => return Ok(RuleEvaluation::default()),
+        };
+        evaluation.into_result()
+    }
+}
+
+/// Integration webhooks - tell the bridge zome about events external
+/// systems may subscribe to
+pub mod webhooks {
+    use super::*;
+
+    /// Mirrors the bridge zome's `WebhookEventType`
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum WebhookEventType {
+        NewObservation,
+        ConsentChanged,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct EmitWebhookEventInput {
+        event_type: WebhookEventType,
+        patient_hash: ActionHash,
+        resource_hash: ActionHash,
+    }
+
+    /// Queue webhook deliveries for `resource_hash`, which the caller must
+    /// have authored (best effort: never fails the write that caused it)
+    pub fn notify_webhooks(event_type: WebhookEventType, patient_hash: ActionHash, resource_hash: ActionHash) {
+        let input = EmitWebhookEventInput { event_type, patient_hash, resource_hash };
+        let _: Result<Vec<ActionHash>, resilience::CallFailure> =
+            resilience::resilient_call("bridge", "emit_webhook_event", &input, reads::CallClass::Bulk);
+    }
+}
+
+/// Domain events - typed notices published through the bridge zome and
+/// fanned out to every zome that subscribes to their kind
+///
+/// Publishers call `publish_event` after a write; subscribers expose
+/// `domain_event_subscriptions` listing their handlers and the extern each
+/// handler names, which receives a `DomainEventNotice`.
+pub mod events {
+    use super::*;
+
+    /// Extern every subscribing zome exposes, returning its handlers
+    pub const SUBSCRIPTIONS_EXTERN: &str = "domain_event_subscriptions";
+
+    /// What happened; carries hashes only, so handlers read the data
+    /// themselves through their usual authorized calls
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub enum DomainEventPayload {
+        IngestCompleted {
+            report_hash: ActionHash,
+            source_system: String,
+            data_categories: Vec<DataCategory>,
+            records_created: u32,
+        },
+        ConsentRevoked {
+            consent_hash: ActionHash,
+        },
+        CrisisAlert {
+            alert_hash: ActionHash,
+            level: String,
+        },
+    }
+
+    /// Payload variant without its data, used to register handlers
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, Hash)]
+    pub enum DomainEventKind {
+        IngestCompleted,
+        ConsentRevoked,
+        CrisisAlert,
+    }
+
+    impl DomainEventPayload {
+        pub fn kind(&self) -> DomainEventKind {
+            match self {
+                DomainEventPayload::IngestCompleted { .. } => DomainEventKind::IngestCompleted,
+                DomainEventPayload::ConsentRevoked { .. } => DomainEventKind::ConsentRevoked,
+                DomainEventPayload::CrisisAlert { .. } => DomainEventKind::CrisisAlert,
+            }
+        }
+    }
+
+    /// One handler extern in a subscribing zome and the kinds it receives
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct DomainEventHandler {
+        pub handler: String,
+        pub kinds: Vec<DomainEventKind>,
+    }
+
+    impl DomainEventHandler {
+        pub fn new(handler: &str, kinds: &[DomainEventKind]) -> Self {
+            Self { handler: handler.to_string(), kinds: kinds.to_vec() }
+        }
+
+        pub fn handles(&self, kind: DomainEventKind) -> bool {
+            self.kinds.contains(&kind)
+        }
+    }
+
+    /// What a handler extern receives
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct DomainEventNotice {
+        pub event_hash: ActionHash,
+        pub patient_hash: ActionHash,
+        pub source_zome: String,
+        pub payload: DomainEventPayload,
+        pub occurred_at: Timestamp,
+    }
+
+    /// Mirrors the bridge zome's `PublishDomainEventInput`
+    #[derive(Debug, Serialize, Deserialize)]
+    struct PublishDomainEventInput {
+        patient_hash: ActionHash,
+        source_zome: String,
+        payload: DomainEventPayload,
+    }
+
+    /// Record `payload` on the bridge zome's event log and dispatch it to
+    /// subscribers (best effort: never fails the write that caused it)
+    pub fn publish_event(patient_hash: ActionHash, payload: DomainEventPayload) {
+        let Ok(info) = zome_info() else {
+            return;
+        };
+        let input = PublishDomainEventInput {
+            patient_hash,
+            source_zome: info.name.to_string(),
+            payload,
+        };
+        let _: Result<ActionHash, resilience::CallFailure> =
+            resilience::resilient_call("bridge", "publish_domain_event", &input, reads::CallClass::Bulk);
+    }
+}
+
+/// API manifest module - capability discovery for UIs and integration engines
+///
+/// Each coordinator declares its extern table with [`api_manifest!`], which
+/// generates `get_api_manifest`, so clients can check versions and
+/// supported calls at runtime.
+pub mod manifest {
+    use super::*;
+
+    /// Declared signature of a coordinator extern
+    #[derive(Clone, Copy, Debug)]
+    pub struct ExternSpec {
+        pub name: &'static str,
+        pub input: &'static str,
+        pub output: &'static str,
+    }
+
+    /// Extern entry as reported to clients
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct ExternDescriptor {
+        pub name: String,
+        pub input_type: String,
+        /// See [`type_tag`]
+        pub input_tag: String,
+        pub output_type: String,
+        pub output_tag: String,
+    }
+
+    /// Entry type and its schema version
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct EntryTypeVersion {
+        pub name: String,
+        pub version: u32,
+    }
+
+    /// Capability manifest for a single coordinator zome
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct ApiManifest {
+        pub zome_name: String,
+        /// Semantic version of the coordinator crate
+        pub version: String,
+        pub externs: Vec<ExternDescriptor>,
+        pub feature_flags: Vec<String>,
+        pub entry_types: Vec<EntryTypeVersion>,
+    }
+
+    impl ApiManifest {
+        /// Whether the zome exposes an extern with this name
+        pub fn supports(&self, extern_name: &str) -> bool {
+            self.externs.iter().any(|e| e.name == extern_name)
+        }
+    }
+
+    /// Entry types start at schema version 1
+    pub const INITIAL_ENTRY_VERSION: u32 = 1;
+
+    /// Type name and entry schema versions a client can compare across
+    /// releases, e.g. `Option<Consent@v2>`
+    ///
+    /// Whitespace is dropped and each entry type named in the signature
+    /// carries its schema version. The tag does not describe the fields of
+    /// other types, so it only changes when a signature or an entry
+    /// version does.
+    pub fn type_tag(type_signature: &str, entry_types: &[EntryTypeVersion]) -> String {
+        let mut tag = String::new();
+        let mut name = String::new();
+        let flush = |name: &mut String, tag: &mut String| {
+            tag.push_str(name);
+            if let Some(entry) = entry_types.iter().find(|entry| entry.name == *name) {
+                tag.push_str(&format!("@v{}", entry.version));
+            }
+            name.clear();
+        };
+        for c in type_signature.chars().filter(|c| !c.is_whitespace()) {
+            if c.is_alphanumeric() || c == '_' {
+                name.push(c);
+            } else {
+                flush(&mut name, &mut tag);
+                tag.push(c);
+            }
+        }
+        flush(&mut name, &mut tag);
+        tag
+    }
+
+    /// Build a manifest from a zome's extern table and entry type names
+    ///
+    /// `entry_version_overrides` lists entry types whose schema has been
+    /// bumped past [`INITIAL_ENTRY_VERSION`].
+    pub fn build_api_manifest(
+        zome_name: &str,
+        version: &str,
+        externs: &[ExternSpec],
+        feature_flags: &[&str],
+        entry_types: impl IntoIterator<Item = String>,
+        entry_version_overrides: &[(&str, u32)],
+    ) -> ApiManifest {
+        let entry_types: Vec<EntryTypeVersion> = entry_types
+            .into_iter()
+            .map(|name| {
+                let version = entry_version_overrides
+                    .iter()
+                    .find(|(n, _)| *n == name)
+                    .map(|(_, v)| *v)
+                    .unwrap_or(INITIAL_ENTRY_VERSION);
+                EntryTypeVersion { name, version }
+            })
+            .collect();
+        ApiManifest {
+            zome_name: zome_name.to_string(),
+            version: version.to_string(),
+            externs: externs
+                .iter()
+                .map(|spec| ExternDescriptor {
+                    name: spec.name.to_string(),
+                    input_type: spec.input.to_string(),
+                    input_tag: type_tag(spec.input, &entry_types),
+                    output_type: spec.output.to_string(),
+                    output_tag: type_tag(spec.output, &entry_types),
+                })
+                .collect(),
+            feature_flags: feature_flags.iter().map(|f| f.to_string()).collect(),
+            entry_types,
+        }
+    }
+
+    /// Declare a coordinator's externs and feature flags, generating its
+    /// `get_api_manifest` extern
+    ///
+    /// Each extern is `(name, input type, output type)`. `entry_versions`
+    /// lists entry types whose schema has been bumped past
+    /// [`INITIAL_ENTRY_VERSION`]. The calling crate needs `hdk_extern` and
+    /// its integrity crate's `UnitEntryTypes` in scope.
+    ///
+    /// ```ignore
+    /// mycelix_health_shared::api_manifest! {
+    ///     externs: [
+    ///         ("get_patient", "GetPatientInput", "Option<Record>"),
+    ///     ],
+    ///     feature_flags: ["blind_index"],
+    ///     entry_versions: [("Patient", 2)],
+    /// }
+    /// ```
+    #[macro_export]
+    macro_rules! api_manifest {
+        (
+            externs: [$(($name:expr, $input:expr, $output:expr)),* $(,)?],
+            feature_flags: [$($flag:expr),* $(,)?]
+            $(, entry_versions: [$(($entry:expr, $version:expr)),* $(,)?])?
+            $(,)?
+        ) => {
+            /// Declared signatures of every extern in this zome
+            const API_EXTERNS: &[$crate::ExternSpec] = &[
+                $($crate::ExternSpec { name: $name, input: $input, output: $output }),*
+            ];
+            const API_FEATURE_FLAGS: &[&str] = &[$($flag),*];
+
+            /// Describe this zome's API for capability discovery
+            #[hdk_extern]
+            pub fn get_api_manifest(_: ()) -> ExternResult<$crate::ApiManifest> {
+                Ok($crate::build_api_manifest(
+                    env!("CARGO_PKG_NAME"),
+                    env!("CARGO_PKG_VERSION"),
+                    API_EXTERNS,
+                    API_FEATURE_FLAGS,
+                    UnitEntryTypes::iter().map(|t| format!("{:?}", t)),
+                    &[$($(($entry, $version)),*)?],
+                ))
+            }
+        };
+    }
+}
+
+/// At-rest compression module - deflates large payload fields in entries
+///
+/// Annotate a field with `#[serde(with = "mycelix_health_shared::compressed")]`
+/// and it is stored deflated once its JSON form crosses
+/// [`COMPRESSION_THRESHOLD_BYTES`]. Reads accept both the compressed form and
+/// the plain form written before the field was designated. Non-Rust clients
+/// decoding such entries see a [`CompressedPayload`] map in place of the value.
+pub mod compression {
+    use super::*;
+    use serde::de::DeserializeOwned;
+
+    /// Payloads smaller than this are stored as-is
+    pub const COMPRESSION_THRESHOLD_BYTES: usize = 512;
+
+    /// Upper bound on an inflated payload, guarding against deflate bombs
+    pub const MAX_INFLATED_BYTES: usize = 8 * 1024 * 1024;
+
+    /// Compression scheme of a stored payload
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum PayloadEncoding {
+        /// Raw DEFLATE (RFC 1951) over the JSON serialization
+        Deflate,
+    }
+
+    /// Stored form of a compressed field
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct CompressedPayload {
+        pub encoding: PayloadEncoding,
+        /// Size of the JSON serialization before compression
+        pub original_size: u32,
+        #[serde(with = "serde_bytes")]
+        pub data: Vec<u8>,
+    }
+
+    impl CompressedPayload {
+        /// Compress a value if doing so is worthwhile
+        pub fn pack<T: Serialize>(value: &T) -> Result<Option<Self>, String> {
+            let json = serde_json::to_vec(value).map_err(|e| e.to_string())?;
+            if json.len() < COMPRESSION_THRESHOLD_BYTES || json.len() > MAX_INFLATED_BYTES {
+                return Ok(None);
+            }
+            let data = miniz_oxide::deflate::compress_to_vec(&json, 6);
+            if data.len() >= json.len() {
+                return Ok(None);
+            }
+            Ok(Some(Self {
+                encoding: PayloadEncoding::Deflate,
+                original_size: json.len() as u32,
+                data,
+            }))
+        }
+
+        /// Restore the original value
+        pub fn unpack<T: DeserializeOwned>(&self) -> Result<T, String> {
+            let limit = (self.original_size as usize).min(MAX_INFLATED_BYTES);
+            let json = miniz_oxide::inflate::decompress_to_vec_with_limit(&self.data, limit)
+                .map_err(|e| format!("Failed to inflate payload: {:?}", e.status))?;
+            serde_json::from_slice(&json).map_err(|e| e.to_string())
+        }
+    }
+
+    /// Size metadata for observability of stored payloads
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct PayloadSizeStats {
+        /// Number of designated fields measured
+        pub fields: u32,
+        /// How many of them were stored compressed
+        pub compressed_fields: u32,
+        pub original_bytes: u64,
+        pub stored_bytes: u64,
+    }
+
+    impl PayloadSizeStats {
+        /// Account for one designated field as it will be stored
+        pub fn record<T: Serialize>(&mut self, value: &T) {
+            let original = serde_json::to_vec(value).map(|v| v.len()).unwrap_or(0) as u64;
+            self.fields += 1;
+            self.original_bytes += original;
+            match CompressedPayload::pack(value) {
+                Ok(Some(packed)) => {
+                    self.compressed_fields += 1;
+                    self.stored_bytes += packed.data.len() as u64;
+                }
+                _ => self.stored_bytes += original,
+            }
+        }
+
+        /// Stored size as a fraction of the original (1.0 when nothing was saved)
+        pub fn ratio(&self) -> f64 {
+            if self.original_bytes == 0 {
+                return 1.0;
+            }
+            self.stored_bytes as f64 / self.original_bytes as f64
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum StoredField<T> {
+        Compressed(CompressedPayload),
+        Plain(T),
+    }
+
+    /// Serde adapter applying compression transparently to a field
+    pub mod compressed {
+        use super::*;
+
+        pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            T: Serialize,
+            S: serde::Serializer,
+        {
+            match CompressedPayload::pack(value).map_err(serde::ser::Error::custom)? {
+                Some(packed) => packed.serialize(serializer),
+                None => value.serialize(serializer),
+            }
+        }
+
+        pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+        where
+            T: DeserializeOwned,
+            D: serde::Deserializer<'de>,
+        {
+            match StoredField::<T>::deserialize(deserializer)? {
+                StoredField::Compressed(packed) => packed.unpack().map_err(serde::de::Error::custom),
+                StoredField::Plain(value) => Ok(value),
+            }
+        }
+    }
+}
+
+/// Lab explanation module - plain-language blurbs for common LOINC results
+///
+/// Explanations are localized by the patient's primary language and fall
+/// back to English. Unknown LOINC codes produce no explanation.
+pub mod lab_explanations {
+    use super::*;
+
+    /// Languages explanations are written in
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum ExplanationLanguage {
+        English,
+        Spanish,
+    }
+
+    impl ExplanationLanguage {
+        /// Resolve a free-text language preference ("es-MX", "Spanish", "español")
+        pub fn from_preference(preference: &str) -> Self {
+            let lowered = preference.trim().to_lowercase();
+            let primary = lowered.split(['-', '_']).next().unwrap_or_default();
+            match primary {
+                "es" | "spa" | "spanish" | "español" | "espanol" => ExplanationLanguage::Spanish,
+                _ => ExplanationLanguage::English,
+            }
+        }
+
+        pub fn code(&self) -> &'static str {
+            match self {
+                ExplanationLanguage::English => "en",
+                ExplanationLanguage::Spanish => "es",
+            }
+        }
+    }
+
+    /// Where a numeric result sits against the typical range
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum RangePosition {
+        WellBelow,
+        SlightlyBelow,
+        Within,
+        SlightlyAbove,
+        WellAbove,
+        /// Value was not numeric or not in the expected unit
+        Unknown,
+    }
+
+    /// Patient-facing explanation of one lab result
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct LabExplanation {
+        pub loinc_code: String,
+        /// ISO 639-1 code of the text
+        pub language: String,
+        pub test_name: String,
+        pub what_it_measures: String,
+        pub position: RangePosition,
+        /// e.g. "Your HbA1c measures average blood sugar over about 3 months; yours is slightly above the typical range."
+        pub summary: String,
+        pub disclaimer: String,
+    }
+
+    struct LocalizedText {
+        name: &'static str,
+        measures: &'static str,
+    }
+
+    struct LabTopic {
+        loinc_codes: &'static [&'static str],
+        unit: &'static str,
+        low: Option<f64>,
+        high: Option<f64>,
+        en: LocalizedText,
+        es: LocalizedText,
+    }
+
+    /// Fraction of a bound within which a result counts as "slightly" out of range
+    const SLIGHT_MARGIN: f64 = 0.10;
+
+    const LAB_TOPICS: &[LabTopic] = &[
+        LabTopic {
+            loinc_codes: &["4548-4", "17856-6"],
+            unit: "%",
+            low: Some(4.0),
+            high: Some(5.6),
+            en: LocalizedText { name: "HbA1c", measures: "average blood sugar over about 3 months" },
+            es: LocalizedText { name: "HbA1c", measures: "el azúcar promedio en la sangre durante unos 3 meses" },
+        },
+        LabTopic {
+            loinc_codes: &["2345-7", "2339-0"],
+            unit: "mg/dL",
+            low: Some(70.0),
+            high: Some(99.0),
+            en: LocalizedText { name: "glucose", measures: "the sugar in your blood when the sample was taken" },
+            es: LocalizedText { name: "glucosa", measures: "el azúcar en su sangre al momento de la muestra" },
+        },
+        LabTopic {
+            loinc_codes: &["2093-3"],
+            unit: "mg/dL",
+            low: None,
+            high: Some(200.0),
+            en: LocalizedText { name: "total cholesterol", measures: "all the cholesterol carried in your blood" },
+            es: LocalizedText { name: "colesterol total", measures: "todo el colesterol que circula en su sangre" },
+        },
+        LabTopic {
+            loinc_codes: &["2085-9"],
+            unit: "mg/dL",
+            low: Some(40.0),
+            high: None,
+            en: LocalizedText { name: "HDL cholesterol", measures: "the \"good\" cholesterol that helps clear fat from your arteries" },
+            es: LocalizedText { name: "colesterol HDL", measures: "el colesterol \"bueno\" que ayuda a limpiar la grasa de las arterias" },
+        },
+        LabTopic {
+            loinc_codes: &["13457-7", "18262-6", "2089-1"],
+            unit: "mg/dL",
+            low: None,
+            high: Some(100.0),
+            en: LocalizedText { name: "LDL cholesterol", measures: "the \"bad\" cholesterol that can build up in your arteries" },
+            es: LocalizedText { name: "colesterol LDL", measures: "el colesterol \"malo\" que puede acumularse en las arterias" },
+        },
+        LabTopic {
+            loinc_codes: &["2571-8"],
+            unit: "mg/dL",
+            low: None,
+            high: Some(150.0),
+            en: LocalizedText { name: "triglycerides", measures: "a type of fat in your blood that rises with sugar, alcohol and extra calories" },
+            es: LocalizedText { name: "triglicéridos", measures: "un tipo de grasa en la sangre que sube con el azúcar, el alcohol y el exceso de calorías" },
+        },
+        LabTopic {
+            loinc_codes: &["2160-0"],
+            unit: "mg/dL",
+            low: Some(0.6),
+            high: Some(1.3),
+            en: LocalizedText { name: "creatinine", measures: "a waste product your kidneys filter out, so it reflects kidney function" },
+            es: LocalizedText { name: "creatinina", measures: "un desecho que filtran los riñones, por lo que refleja su función renal" },
+        },
+        LabTopic {
+            loinc_codes: &["33914-3", "62238-1", "98979-8"],
+            unit: "mL/min/1.73m2",
+            low: Some(60.0),
+            high: None,
+            en: LocalizedText { name: "eGFR", measures: "how well your kidneys filter your blood" },
+            es: LocalizedText { name: "TFGe", measures: "qué tan bien sus riñones filtran la sangre" },
+        },
+        LabTopic {
+            loinc_codes: &["718-7"],
+            unit: "g/dL",
+            low: Some(12.0),
+            high: Some(17.5),
+            en: LocalizedText { name: "hemoglobin", measures: "the protein in red blood cells that carries oxygen" },
+            es: LocalizedText { name: "hemoglobina", measures: "la proteína de los glóbulos rojos que transporta oxígeno" },
+        },
+        LabTopic {
+            loinc_codes: &["6690-2"],
+            unit: "10*3/uL",
+            low: Some(4.5),
+            high: Some(11.0),
+            en: LocalizedText { name: "white blood cell count", measures: "the cells that fight infection" },
+            es: LocalizedText { name: "conteo de glóbulos blancos", measures: "las células que combaten las infecciones" },
+        },
+        LabTopic {
+            loinc_codes: &["777-3"],
+            unit: "10*3/uL",
+            low: Some(150.0),
+            high: Some(450.0),
+            en: LocalizedText { name: "platelet count", measures: "the cells that help your blood clot" },
+            es: LocalizedText { name: "conteo de plaquetas", measures: "las células que ayudan a coagular la sangre" },
+        },
+        LabTopic {
+            loinc_codes: &["3016-3"],
+            unit: "mIU/L",
+            low: Some(0.4),
+            high: Some(4.0),
+            en: LocalizedText { name: "TSH", measures: "the signal your brain sends to your thyroid, showing how active the thyroid is" },
+            es: LocalizedText { name: "TSH", measures: "la señal que el cerebro envía a la tiroides, que muestra qué tan activa está" },
+        },
+        LabTopic {
+            loinc_codes: &["2823-3", "6298-4"],
+            unit: "mmol/L",
+            low: Some(3.5),
+            high: Some(5.1),
+            en: LocalizedText { name: "potassium", measures: "a mineral that keeps your heart and muscles working properly" },
+            es: LocalizedText { name: "potasio", measures: "un mineral que ayuda a que el corazón y los músculos funcionen bien" },
+        },
+        LabTopic {
+            loinc_codes: &["2951-2", "2947-0"],
+            unit: "mmol/L",
+            low: Some(135.0),
+            high: Some(145.0),
+            en: LocalizedText { name: "sodium", measures: "the salt balance and fluid levels in your body" },
+            es: LocalizedText { name: "sodio", measures: "el equilibrio de sal y líquidos en su cuerpo" },
+        },
+    ];
+
+    /// Whether an explanation exists for this LOINC code
+    pub fn has_lab_explanation(loinc_code: &str) -> bool {
+        find_topic(loinc_code).is_some()
+    }
+
+    /// Explain a result in plain language
+    ///
+    /// `interpretation` is the lab's own flag (e.g. "High", "Normal") and is
+    /// used when the value cannot be compared against the typical range.
+    pub fn explain_lab_result(
+        loinc_code: &str,
+        value: &str,
+        unit: &str,
+        interpretation: &str,
+        language: ExplanationLanguage,
+    ) -> Option<LabExplanation> {
+        let topic = find_topic(loinc_code)?;
+        let text = match language {
+            ExplanationLanguage::English => &topic.en,
+            ExplanationLanguage::Spanish => &topic.es,
+        };
+
+        let position = match (same_unit(unit, topic.unit), parse_numeric(value)) {
+            (true, Some(v)) => range_position(v, topic.low, topic.high),
+            _ => position_from_interpretation(interpretation),
+        };
+
+        let (verb_phrase, result_phrase, disclaimer) = match language {
+            ExplanationLanguage::English => (
+                format!("Your {} measures {}", text.name, text.measures),
+                match position {
+                    RangePosition::WellBelow => "yours is below the typical range",
+                    RangePosition::SlightlyBelow => "yours is slightly below the typical range",
+                    RangePosition::Within => "yours is within the typical range",
+                    RangePosition::SlightlyAbove => "yours is slightly above the typical range",
+                    RangePosition::WellAbove => "yours is above the typical range",
+                    RangePosition::Unknown => "ask your care team what your result means for you",
+                },
+                "This explanation is general information; your care team can tell you what it means for you.",
+            ),
+            ExplanationLanguage::Spanish => (
+                format!("Su {} mide {}", text.name, text.measures),
+                match position {
+                    RangePosition::WellBelow => "el suyo está por debajo del rango habitual",
+                    RangePosition::SlightlyBelow => "el suyo está un poco por debajo del rango habitual",
+                    RangePosition::Within => "el suyo está dentro del rango habitual",
+                    RangePosition::SlightlyAbove => "el suyo está un poco por encima del rango habitual",
+                    RangePosition::WellAbove => "el suyo está por encima del rango habitual",
+                    RangePosition::Unknown => "pregunte a su equipo de atención qué significa su resultado",
+                },
+                "Esta explicación es información general; su equipo de atención puede decirle qué significa para usted.",
+            ),
+        };
+
+        Some(LabExplanation {
+            loinc_code: loinc_code.to_string(),
+            language: language.code().to_string(),
+            test_name: text.name.to_string(),
+            what_it_measures: text.measures.to_string(),
+            position,
+            summary: format!("{}; {}.", verb_phrase, result_phrase),
+            disclaimer: disclaimer.to_string(),
+        })
+    }
+
+    fn find_topic(loinc_code: &str) -> Option<&'static LabTopic> {
+        let code = loinc_code.trim();
+        LAB_TOPICS.iter().find(|t| t.loinc_codes.contains(&code))
+    }
+
+    fn same_unit(unit: &str, expected: &str) -> bool {
+        let normalize = |u: &str| u.trim().to_lowercase().replace(' ', "");
+        unit.trim().is_empty() || normalize(unit) == normalize(expected)
+    }
+
+    /// Accepts plain numbers and comparator-prefixed values like "<0.5"
+    fn parse_numeric(value: &str) -> Option<f64> {
+        value
+            .trim()
+            .trim_start_matches(['<', '>', '=', '~'])
+            .trim()
+            .parse::<f64>()
+            .ok()
+    }
+
+    fn range_position(value: f64, low: Option<f64>, high: Option<f64>) -> RangePosition {
+        if let Some(high) = high {
+            if value > high {
+                return if value <= high * (1.0 + SLIGHT_MARGIN) {
+                    RangePosition::SlightlyAbove
+                } else {
+                    RangePosition::WellAbove
+                };
+            }
+        }
+        if let Some(low) = low {
+            if value < low {
+                return if value >= low * (1.0 - SLIGHT_MARGIN) {
+                    RangePosition::SlightlyBelow
+                } else {
+                    RangePosition::WellBelow
+                };
+            }
+        }
+        RangePosition::Within
+    }
+
+    fn position_from_interpretation(interpretation: &str) -> RangePosition {
+        match interpretation {
+            "Normal" => RangePosition::Within,
+            "High" => RangePosition::WellAbove,
+            "Low" => RangePosition::WellBelow,
+            _ => RangePosition::Unknown,
+        }
+    }
+}
+
+/// Medication adherence
+///
+/// Proportion of days covered (PDC) walks the dispenses in fill order and
+/// pushes an early refill's supply past the end of the previous one, so
+/// stockpiling never counts a day twice. Medication possession ratio (MPR)
+/// is the plain sum of days supplied over the period and can exceed 1.
+pub mod adherence {
+    use super::*;
+
+    const DAY_MICROS: i64 = 86_400_000_000;
+
+    /// PDC below this is non-adherent, the usual cut-off for chronic therapy
+    pub const ADHERENCE_THRESHOLD: f64 = 0.8;
+
+    /// One dispense of a medication
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct Dispense {
+        pub dispensed_at: Timestamp,
+        pub days_supply: u32,
+    }
+
+    /// Half-open window an adherence figure covers
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct AdherencePeriod {
+        pub start: Timestamp,
+        pub end: Timestamp,
+    }
+
+    impl AdherencePeriod {
+        /// Whole days in the period, counting a partial last day
+        pub fn days(&self) -> u32 {
+            let micros = (self.end.as_micros() - self.start.as_micros()).max(0);
+            ((micros + DAY_MICROS - 1) / DAY_MICROS) as u32
+        }
+
+        pub fn contains(&self, at: Timestamp) -> bool {
+            at >= self.start && at < self.end
+        }
+
+        /// Day index of `at` relative to the start; negative before it
+        fn day_of(&self, at: Timestamp) -> i64 {
+            (at.as_micros() - self.start.as_micros()).div_euclid(DAY_MICROS)
+        }
+    }
+
+    /// Supply coverage of a period, from dispense records alone
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct SupplyCoverage {
+        pub days_in_period: u32,
+        pub days_covered: u32,
+        /// Proportion of days covered, 0 to 1
+        pub pdc: f64,
+        /// Medication possession ratio; above 1 means oversupply
+        pub mpr: f64,
+        /// Uncovered runs after the first covered day
+        pub refill_gaps: u32,
+        pub longest_gap_days: u32,
+    }
+
+    /// Coverage of `period` by `dispenses`, in any order
+    ///
+    /// Dispenses before the period still count for the days their supply
+    /// reaches into it; MPR only counts dispenses made inside the period.
+    pub fn supply_coverage(dispenses: &[Dispense], period: &AdherencePeriod) -> SupplyCoverage {
+        let days = period.days();
+        if days == 0 {
+            return SupplyCoverage::default();
+        }
+        let mut ordered: Vec<&Dispense> = dispenses.iter().filter(|d| d.dispensed_at < period.end).collect();
+        ordered.sort_by_key(|d| d.dispensed_at);
+
+        let mut covered = vec![false; days as usize];
+        let mut next_free = i64::MIN;
+        let mut supplied_in_period: u64 = 0;
+        for dispense in ordered {
+            if period.contains(dispense.dispensed_at) {
+                supplied_in_period += dispense.days_supply as u64;
+            }
+            let begin = period.day_of(dispense.dispensed_at).max(next_free);
+            let end = begin + dispense.days_supply as i64;
+            for day in begin.max(0)..end.min(days as i64) {
+                covered[day as usize] = true;
+            }
+            next_free = end;
+        }
+
+        let days_covered = covered.iter().filter(|c| **c).count() as u32;
+        let mut refill_gaps = 0;
+        let mut longest_gap_days = 0;
+        if let Some(first) = covered.iter().position(|c| *c) {
+            let mut run = 0u32;
+            for &day in &covered[first..] {
+                if day {
+                    run = 0;
+                } else {
+                    if run == 0 {
+                        refill_gaps += 1;
+                    }
+                    run += 1;
+                    longest_gap_days = longest_gap_days.max(run);
+                }
+            }
+        }
+
+        SupplyCoverage {
+            days_in_period: days,
+            days_covered,
+            pdc: days_covered as f64 / days as f64,
+            mpr: supplied_in_period as f64 / days as f64,
+            refill_gaps,
+            longest_gap_days,
+        }
+    }
+}
+
+/// Locale-aware date labels for patient-facing reports
+///
+/// Timestamps are shifted by the patient's preferred UTC offset before being
+/// split into calendar fields, so an access late in the evening lands on the
+/// day (and month) the patient experienced it. FHIR dates, which are
+/// exchanged in UTC, are converted here too.
+pub mod localization {
+    use super::*;
+
+    const MICROS_PER_MINUTE: i64 = 60_000_000;
+    const MINUTES_PER_DAY: i64 = 1_440;
+
+    /// Widest offsets in use (UTC-12:00 through UTC+14:00)
+    pub const MIN_UTC_OFFSET_MINUTES: i32 = -720;
+    pub const MAX_UTC_OFFSET_MINUTES: i32 = 840;
+
+    const MONTHS_EN: [&str; 12] = [
+        "January", "February", "March", "April", "May", "June",
+        "July", "August", "September", "October", "November", "December",
+    ];
+    const MONTHS_ES: [&str; 12] = [
+        "enero", "febrero", "marzo", "abril", "mayo", "junio",
+        "julio", "agosto", "septiembre", "octubre", "noviembre", "diciembre",
+    ];
+
+    /// Wall-clock date and time in the patient's timezone
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct LocalDateTime {
+        pub year: i64,
+        pub month: u32,
+        pub day: u32,
+        pub hour: u32,
+        pub minute: u32,
+    }
+
+    pub fn is_valid_utc_offset(offset_minutes: i32) -> bool {
+        (MIN_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&offset_minutes)
+    }
+
+    /// Split a timestamp into local calendar fields
+    pub fn local_date_time(at: Timestamp, utc_offset_minutes: i32) -> LocalDateTime {
+        let minutes = at.as_micros().div_euclid(MICROS_PER_MINUTE) + utc_offset_minutes as i64;
+        let days = minutes.div_euclid(MINUTES_PER_DAY);
+        let minute_of_day = minutes.rem_euclid(MINUTES_PER_DAY);
+        let (year, month, day) = civil_from_days(days);
+        LocalDateTime {
+            year,
+            month,
+            day,
+            hour: (minute_of_day / 60) as u32,
+            minute: (minute_of_day % 60) as u32,
+        }
+    }
+
+    /// Sortable month key ("2026-03")
+    pub fn month_key(local: &LocalDateTime) -> String {
+        format!("{:04}-{:02}", local.year, local.month)
+    }
+
+    /// Month heading ("March 2026" / "marzo de 2026")
+    pub fn month_label(local: &LocalDateTime, language: ExplanationLanguage) -> String {
+        let index = (local.month as usize).saturating_sub(1).min(11);
+        match language {
+            ExplanationLanguage::English => format!("{} {}", MONTHS_EN[index], local.year),
+            ExplanationLanguage::Spanish => format!("{} de {}", MONTHS_ES[index], local.year),
+        }
+    }
+
+    /// Full date ("March 5, 2026" / "5 de marzo de 2026")
+    pub fn date_label(local: &LocalDateTime, language: ExplanationLanguage) -> String {
+        let index = (local.month as usize).saturating_sub(1).min(11);
+        match language {
+            ExplanationLanguage::English => format!("{} {}, {}", MONTHS_EN[index], local.day, local.year),
+            ExplanationLanguage::Spanish => format!("{} de {} de {}", local.day, MONTHS_ES[index], local.year),
+        }
+    }
+
+    /// Time of day ("2:05 PM" / "14:05")
+    pub fn time_label(local: &LocalDateTime, language: ExplanationLanguage) -> String {
+        match language {
+            ExplanationLanguage::English => {
+                let suffix = if local.hour < 12 { "AM" } else { "PM" };
+                let hour = match local.hour % 12 {
+                    0 => 12,
+                    hour => hour,
+                };
+                format!("{}:{:02} {}", hour, local.minute, suffix)
+            }
+            ExplanationLanguage::Spanish => format!("{:02}:{:02}", local.hour, local.minute),
+        }
+    }
+
+    /// FHIR instant in UTC ("2024-03-01T10:30:00Z")
+    pub fn fhir_instant(at: Timestamp) -> String {
+        let utc = local_date_time(at, 0);
+        let second = at.as_micros().div_euclid(1_000_000).rem_euclid(60);
+        format!(
+            "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+            utc.year, utc.month, utc.day, utc.hour, utc.minute, second
+        )
+    }
+
+    /// Parse a FHIR date/dateTime/instant (`YYYY`, `YYYY-MM`, `YYYY-MM-DD` or
+    /// `YYYY-MM-DDThh:mm:ss[.fff](Z|±hh:mm)`) into a UTC timestamp.
+    /// Partial dates resolve to the start of the period.
+    pub fn parse_fhir_datetime(value: &str) -> Option<Timestamp> {
+        let (date, time) = match value.split_once('T') {
+            Some((date, time)) => (date, Some(time)),
+            None => (value, None),
+        };
+        let mut parts = date.splitn(3, '-');
+        let year: i64 = parts.next()?.parse().ok()?;
+        let month: u32 = parts.next().map_or(Some(1), |m| m.parse().ok())?;
+        let day: u32 = parts.next().map_or(Some(1), |d| d.parse().ok())?;
+        if !(1..=12).contains(&month) || !(1..=31).contains(&day) {
+            return None;
+        }
+
+        let mut seconds = days_from_civil(year, month, day) * 86_400;
+        let mut micros = 0i64;
+        if let Some(time) = time {
+            let (clock, offset_seconds) = if let Some(clock) = time.strip_suffix('Z') {
+                (clock, 0)
+            } else {
+                let split = time.rfind(['+', '-'])?;
+                let (clock, offset) = time.split_at(split);
+                let sign = if offset.starts_with('-') { -1 } else { 1 };
+                let (hours, minutes) = offset[1..].split_once(':')?;
+                let offset = hours.parse::<i64>().ok()? * 3600 + minutes.parse::<i64>().ok()? * 60;
+                (clock, sign * offset)
+            };
+            let mut fields = clock.splitn(3, ':');
+            let hour: i64 = fields.next()?.parse().ok()?;
+            let minute: i64 = fields.next()?.parse().ok()?;
+            let (second, fraction) = match fields.next() {
+                Some(s) => s.split_once('.').unwrap_or((s, "")),
+                None => ("0", ""),
+            };
+            let second: i64 = second.parse().ok()?;
+            if hour > 23 || minute > 59 || second > 60 {
+                return None;
+            }
+            if !fraction.is_empty() {
+                let digits: String = fraction.chars().chain(std::iter::repeat('0')).take(6).collect();
+                micros = digits.parse().ok()?;
+            }
+            seconds += hour * 3600 + minute * 60 + second - offset_seconds;
+        }
+        Some(Timestamp::from_micros(seconds * 1_000_000 + micros))
+    }
+
+    /// Days since 1970-01-01 for a proleptic Gregorian date
+    fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+        let year = if month <= 2 { year - 1 } else { year };
+        let era = year.div_euclid(400);
+        let year_of_era = year - era * 400;
+        let month = month as i64;
+        let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+        let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+        era * 146_097 + day_of_era - 719_468
+    }
+
+    /// Proleptic Gregorian date for a day count since 1970-01-01
+    fn civil_from_days(days: i64) -> (i64, u32, u32) {
+        let z = days + 719_468;
+        let era = z.div_euclid(146_097);
+        let day_of_era = z.rem_euclid(146_097);
+        let year_of_era = (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+        let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+        let month_index = (5 * day_of_year + 2) / 153;
+        let day = (day_of_year - (153 * month_index + 2) / 5 + 1) as u32;
+        let month = if month_index < 10 { month_index + 3 } else { month_index - 9 } as u32;
+        let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+        (year, month, day)
+    }
+}
+
+/// Condition onsets - an estimate for conditions whose source asserts none
+///
+/// Ingest gathers dated candidates (the encounter the condition was recorded
+/// in, results cited as evidence or monitoring it) and keeps the earliest as
+/// an inferred onset next to the asserted one. Problem lists and episode
+/// views order conditions by the normalized onset.
+pub mod condition_onsets {
+    use super::*;
+
+    /// Days within which two candidates are taken to corroborate each other
+    pub const CORROBORATION_DAYS: i64 = 30;
+
+    /// How far an inferred onset can be trusted
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+    pub enum OnsetConfidence {
+        Low,
+        Moderate,
+        High,
+    }
+
+    /// What a candidate onset was taken from
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum OnsetBasis {
+        /// The encounter the condition was recorded in
+        Encounter,
+        /// A result cited as evidence for the condition
+        DiagnosticResult,
+        /// A result monitoring the condition
+        MonitoringResult,
+    }
+
+    impl OnsetBasis {
+        fn confidence(&self) -> OnsetConfidence {
+            match self {
+                OnsetBasis::Encounter | OnsetBasis::DiagnosticResult => OnsetConfidence::Moderate,
+                OnsetBasis::MonitoringResult => OnsetConfidence::Low,
+            }
+        }
+    }
+
+    /// A dated resource related to a condition
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct OnsetCandidate {
+        pub at: Timestamp,
+        pub basis: OnsetBasis,
+        /// Source reference, e.g. "Encounter/e1"
+        pub derived_from: String,
+    }
+
+    /// Onset estimated from related resources
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct InferredOnset {
+        pub at: Timestamp,
+        pub confidence: OnsetConfidence,
+        pub basis: OnsetBasis,
+        pub derived_from: String,
+    }
+
+    /// The earliest candidate, upgraded to high confidence when another
+    /// encounter or diagnostic candidate falls within
+    /// [`CORROBORATION_DAYS`] of it
+    ///
+    /// The condition was present by then, so the estimate is a latest
+    /// possible onset rather than the true one.
+    pub fn infer_onset(candidates: &[OnsetCandidate]) -> Option<InferredOnset> {
+        let earliest = candidates.iter().min_by_key(|c| c.at)?;
+        let window = CORROBORATION_DAYS * 86_400_000_000;
+        let corroborated = candidates.iter().any(|other| {
+            !std::ptr::eq(other, earliest)
+                && other.basis.confidence() >= OnsetConfidence::Moderate
+                && other.at.as_micros() - earliest.at.as_micros() <= window
+        });
+        Some(InferredOnset {
+            at: earliest.at,
+            confidence: if corroborated { OnsetConfidence::High } else { earliest.basis.confidence() },
+            basis: earliest.basis,
+            derived_from: earliest.derived_from.clone(),
+        })
+    }
+
+    /// Onset used to place a condition on a timeline
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct NormalizedOnset {
+        /// `None` when neither an asserted nor an inferred onset is known
+        pub at: Option<Timestamp>,
+        pub inferred: bool,
+        /// Set for inferred onsets only
+        pub confidence: Option<OnsetConfidence>,
+    }
+
+    /// The asserted onset when there is one, else the inferred one
+    pub fn normalized_onset(asserted: Option<Timestamp>, inferred: Option<&InferredOnset>) -> NormalizedOnset {
+        match (asserted, inferred) {
+            (Some(at), _) => NormalizedOnset { at: Some(at), inferred: false, confidence: None },
+            (None, Some(inferred)) => NormalizedOnset {
+                at: Some(inferred.at),
+                inferred: true,
+                confidence: Some(inferred.confidence),
+            },
+            (None, None) => NormalizedOnset { at: None, inferred: false, confidence: None },
+        }
+    }
+}
+
+/// FHIR extension preservation
+///
+/// Ingest keeps every top-level `extension` and `modifierExtension` element
+/// verbatim so export can re-emit it; US Core demographics are also parsed
+/// into typed fields.
+pub mod fhir_extensions {
+    use super::*;
+    use serde_json::Value as JsonValue;
+
+    pub const US_CORE_RACE_URL: &str = "http://hl7.org/fhir/us/core/StructureDefinition/us-core-race";
+    pub const US_CORE_ETHNICITY_URL: &str = "http://hl7.org/fhir/us/core/StructureDefinition/us-core-ethnicity";
+    pub const US_CORE_BIRTH_SEX_URL: &str = "http://hl7.org/fhir/us/core/StructureDefinition/us-core-birthsex";
+
+    /// A FHIR extension kept exactly as received
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct FhirExtension {
+        pub url: String,
+        /// Came from `modifierExtension` rather than `extension`
+        pub modifier: bool,
+        /// The whole extension element as JSON
+        pub json: String,
+    }
+
+    impl FhirExtension {
+        /// The stored element is a JSON object carrying the same URL
+        pub fn is_well_formed(&self) -> bool {
+            !self.url.trim().is_empty()
+                && serde_json::from_str::<JsonValue>(&self.json)
+                    .is_ok_and(|json| json.get("url").and_then(|u| u.as_str()) == Some(self.url.as_str()))
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct UsCoreCoding {
+        pub system: String,
+        pub code: String,
+        pub display: Option<String>,
+    }
+
+    /// Contents of a US Core race or ethnicity extension
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct UsCoreCategory {
+        /// OMB minimum categories
+        pub omb_categories: Vec<UsCoreCoding>,
+        /// Detailed CDC race and ethnicity codes
+        pub detailed: Vec<UsCoreCoding>,
+        pub text: Option<String>,
+    }
+
+    /// US Core patient extensions recognized at ingest
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct UsCoreDemographics {
+        pub race: Option<UsCoreCategory>,
+        pub ethnicity: Option<UsCoreCategory>,
+        /// Birth sex code (M, F or UNK)
+        pub birth_sex: Option<String>,
+    }
+
+    impl UsCoreDemographics {
+        pub fn from_extensions(extensions: &[FhirExtension]) -> Self {
+            let mut demographics = Self::default();
+            for extension in extensions.iter().filter(|e| !e.modifier) {
+                let Ok(json) = serde_json::from_str::<JsonValue>(&extension.json) else { continue };
+                match extension.url.as_str() {
+                    US_CORE_RACE_URL => demographics.race = Some(us_core_category(&json)),
+                    US_CORE_ETHNICITY_URL => demographics.ethnicity = Some(us_core_category(&json)),
+                    US_CORE_BIRTH_SEX_URL => {
+                        demographics.birth_sex = json.get("valueCode").and_then(|v| v.as_str()).map(str::to_string)
+                    }
+                    _ => {}
+                }
+            }
+            demographics
+        }
+    }
+
+    /// Race and ethnicity are complex extensions with `ombCategory`,
+    /// `detailed` and `text` sub-extensions
+    fn us_core_category(json: &JsonValue) -> UsCoreCategory {
+        let mut category = UsCoreCategory::default();
+        let parts = json.get("extension").and_then(|e| e.as_array()).cloned().unwrap_or_default();
+        for part in parts {
+            let coding = part.get("valueCoding").map(|coding| UsCoreCoding {
+                system: coding.get("system").and_then(|s| s.as_str()).unwrap_or_default().to_string(),
+                code: coding.get("code").and_then(|c| c.as_str()).unwrap_or_default().to_string(),
+                display: coding.get("display").and_then(|d| d.as_str()).map(str::to_string),
+            });
+            match (part.get("url").and_then(|u| u.as_str()), coding) {
+                (Some("ombCategory"), Some(coding)) => category.omb_categories.push(coding),
+                (Some("detailed"), Some(coding)) => category.detailed.push(coding),
+                (Some("text"), _) => {
+                    category.text = part.get("valueString").and_then(|t| t.as_str()).map(str::to_string)
+                }
+                _ => {}
+            }
+        }
+        category
+    }
+
+    /// Every top-level extension on a resource; elements without a URL are
+    /// not valid FHIR and are skipped
+    pub fn capture_fhir_extensions(resource: &JsonValue) -> Vec<FhirExtension> {
+        let mut captured = Vec::new();
+        for (field, modifier) in [("extension", false), ("modifierExtension", true)] {
+            let Some(elements) = resource.get(field).and_then(|e| e.as_array()) else { continue };
+            for element in elements {
+                if let Some(url) = element.get("url").and_then(|u| u.as_str()) {
+                    captured.push(FhirExtension {
+                        url: url.to_string(),
+                        modifier,
+                        json: element.to_string(),
+                    });
+                }
+            }
+        }
+        captured
+    }
+
+    /// Put preserved extensions back on an exported resource, in the order
+    /// they were received
+    pub fn emit_fhir_extensions(resource: &mut JsonValue, extensions: &[FhirExtension]) {
+        let Some(fields) = resource.as_object_mut() else { return };
+        for (field, modifier) in [("extension", false), ("modifierExtension", true)] {
+            let elements: Vec<JsonValue> = extensions
+                .iter()
+                .filter(|e| e.modifier == modifier)
+                .filter_map(|e| serde_json::from_str(&e.json).ok())
+                .collect();
+            if !elements.is_empty() {
+                fields.insert(field.to_string(), JsonValue::Array(elements));
+            }
+        }
+    }
+}
+
+/// Concurrent-safe counters for high-frequency statistics
+///
+/// A counter lives on a base hash under a zome-specific link type. Every agent
+/// that changes it owns one shard: a link targeting its own key whose tag holds
+/// its running increment and decrement totals. Agents only rewrite their own
+/// shard, so concurrent writers never conflict, and stale or duplicate shard
+/// links merge by taking the larger total (a PN-counter). Reading costs one
+/// link per writer instead of one per counted item.
+pub mod counters {
+    use super::*;
+
+    const SHARD_TAG_PREFIX: &[u8] = b"pn1";
+
+    /// One agent's share of a counter
+    #[derive(Clone, Copy, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct CounterShard {
+        pub increments: u64,
+        pub decrements: u64,
+    }
+
+    impl CounterShard {
+        /// Add a signed delta to the shard
+        pub fn apply(&mut self, delta: i64) {
+            if delta >= 0 {
+                self.increments = self.increments.saturating_add(delta as u64);
+            } else {
+                self.decrements = self.decrements.saturating_add(delta.unsigned_abs());
+            }
+        }
+
+        /// Merge two observations of the same shard
+        pub fn merge(self, other: Self) -> Self {
+            Self {
+                increments: self.increments.max(other.increments),
+                decrements: self.decrements.max(other.decrements),
+            }
+        }
+
+        pub fn value(&self) -> i64 {
+            (self.increments as i128 - self.decrements as i128)
+                .clamp(i64::MIN as i128, i64::MAX as i128) as i64
+        }
+
+        pub fn to_tag(self) -> LinkTag {
+            let mut bytes = SHARD_TAG_PREFIX.to_vec();
+            bytes.extend_from_slice(&self.increments.to_be_bytes());
+            bytes.extend_from_slice(&self.decrements.to_be_bytes());
+            LinkTag::new(bytes)
+        }
+
+        /// Decode a shard tag; tags written by anything else are ignored
+        pub fn from_tag(tag: &LinkTag) -> Option<Self> {
+            let body = tag.as_ref().strip_prefix(SHARD_TAG_PREFIX)?;
+            if body.len() != 16 {
+                return None;
+            }
+            Some(Self {
+                increments: u64::from_be_bytes(body[..8].try_into().ok()?),
+                decrements: u64::from_be_bytes(body[8..].try_into().ok()?),
+            })
+        }
+    }
+
+    /// Sum shard observations, merging those that belong to the same owner
+    pub fn sum_counter_shards<K: PartialEq>(shards: impl IntoIterator<Item = (K, CounterShard)>) -> i64 {
+        let mut merged: Vec<(K, CounterShard)> = Vec::new();
+        for (owner, shard) in shards {
+            match merged.iter_mut().find(|(o, _)| *o == owner) {
+                Some((_, existing)) => *existing = existing.merge(shard),
+                None => merged.push((owner, shard)),
+            }
+        }
+        merged.iter().map(|(_, shard)| shard.value()).sum()
+    }
+
+    fn shard_links<T>(base: AnyLinkableHash, link_type: T, author: Option<AgentPubKey>) -> ExternResult<Vec<Link>>
+    where
+        T: TryInto<LinkTypeFilter, Error = WasmError>,
+    {
+        let mut query = LinkQuery::try_new(base, link_type)?;
+        if let Some(author) = author {
+            query = query.author(author);
+        }
+        get_links(query, GetStrategy::default())
+    }
+
+    /// Add `delta` to the counter at `base`, returning the caller's shard total
+    pub fn increment_counter<T>(base: impl Into<AnyLinkableHash>, link_type: T, delta: i64) -> ExternResult<i64>
+    where
+        T: Clone + TryInto<LinkTypeFilter, Error = WasmError>,
+        ScopedLinkType: TryFrom<T, Error = WasmError>,
+    {
+        let base = base.into();
+        let me = agent_info()?.agent_initial_pubkey;
+        let previous = shard_links(base.clone(), link_type.clone(), Some(me.clone()))?;
+
+        let mut shard = previous
+            .iter()
+            .filter_map(|link| CounterShard::from_tag(&link.tag))
+            .fold(CounterShard::default(), CounterShard::merge);
+        shard.apply(delta);
+
+        create_link(base, me, link_type, shard.to_tag())?;
+        for link in previous {
+            delete_link(link.create_link_hash, GetOptions::default())?;
+        }
+        Ok(shard.value())
+    }
+
+    /// Current value of the counter at `base`
+    pub fn read_counter<T>(base: impl Into<AnyLinkableHash>, link_type: T) -> ExternResult<i64>
+    where
+        T: TryInto<LinkTypeFilter, Error = WasmError>,
+    {
+        let links = shard_links(base.into(), link_type, None)?;
+        Ok(sum_counter_shards(
+            links
+                .into_iter()
+                .filter_map(|link| Some((link.author, CounterShard::from_tag(&link.tag)?))),
+        ))
+    }
+}
+
+/// Link tag codec - a record's status, creation time and expiry packed into
+/// the tag of the link that indexes it
+///
+/// List endpoints can then filter by status and sort by time from the links
+/// alone, fetching only the records they return. Tags cannot change, so a
+/// status change deletes the link and writes a newly tagged one. Links
+/// written before the codec carry no metadata; callers fall back to the
+/// record for those.
+pub mod link_tags {
+    use super::*;
+
+    const TAG_PREFIX: &[u8] = b"lm1";
+
+    /// A status enum with a stable one-byte code for link tags
+    pub trait TagStatus: Sized {
+        fn tag_code(&self) -> u8;
+        fn from_tag_code(code: u8) -> Option<Self>;
+    }
+
+    /// What a link tag says about its target
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct LinkMetadata {
+        pub status: u8,
+        pub created_at: Timestamp,
+        pub expires_at: Option<Timestamp>,
+    }
+
+    impl LinkMetadata {
+        pub fn new<S: TagStatus>(status: &S, created_at: Timestamp, expires_at: Option<Timestamp>) -> Self {
+            Self { status: status.tag_code(), created_at, expires_at }
+        }
+
+        pub fn status<S: TagStatus>(&self) -> Option<S> {
+            S::from_tag_code(self.status)
+        }
+
+        pub fn has_status<S: TagStatus>(&self, status: &S) -> bool {
+            self.status == status.tag_code()
+        }
+
+        pub fn is_expired(&self, now: Timestamp) -> bool {
+            self.expires_at.is_some_and(|expires_at| expires_at <= now)
+        }
+
+        /// Prefix, status, created_at and, when set, expires_at (big-endian)
+        pub fn to_tag(&self) -> LinkTag {
+            let mut bytes = TAG_PREFIX.to_vec();
+            bytes.push(self.status);
+            bytes.extend_from_slice(&self.created_at.as_micros().to_be_bytes());
+            if let Some(expires_at) = self.expires_at {
+                bytes.extend_from_slice(&expires_at.as_micros().to_be_bytes());
+            }
+            LinkTag::new(bytes)
+        }
+
+        /// Decode a metadata tag; untagged links and other tags are `None`
+        pub fn from_tag(tag: &LinkTag) -> Option<Self> {
+            let body = tag.as_ref().strip_prefix(TAG_PREFIX)?;
+            let micros = |bytes: &[u8]| bytes.try_into().ok().map(|b| Timestamp::from_micros(i64::from_be_bytes(b)));
+            let (status, times) = body.split_first()?;
+            let (created_at, expires_at) = match times.len() {
+                8 => (micros(times)?, None),
+                16 => (micros(&times[..8])?, Some(micros(&times[8..])?)),
+                _ => return None,
+            };
+            Some(Self { status: *status, created_at, expires_at })
+        }
+    }
+
+    /// Drop tagged links whose metadata fails `keep`; untagged links stay
+    /// for the caller to check against their records
+    pub fn filter_links_by_metadata(links: Vec<Link>, keep: impl Fn(&LinkMetadata) -> bool) -> Vec<Link> {
+        links
+            .into_iter()
+            .filter(|link| LinkMetadata::from_tag(&link.tag).is_none_or(|metadata| keep(&metadata)))
+            .collect()
+    }
+
+    /// Newest first by tagged creation time, falling back to when the link
+    /// was written
+    pub fn sort_links_newest_first(links: &mut [Link]) {
+        let created = |link: &Link| LinkMetadata::from_tag(&link.tag).map_or(link.timestamp, |m| m.created_at);
+        links.sort_by_key(|link| std::cmp::Reverse(created(link)));
+    }
+}
+
+/// Signed attestations - an agent attesting to a payload at a point in time
+///
+/// Consent receipts, revenue events and export manifests all need the same
+/// shape of proof. The signature covers the attestation kind, the attester,
+/// the time and a canonical encoding of the payload, so a signature made
+/// for one kind cannot be passed off as another and a payload re-encoded
+/// with its keys in a different order still verifies.
+pub mod attestations {
+    use super::*;
+
+    /// Version of the claims layout signed by `sign_attestation`
+    pub const ATTESTATION_VERSION: u8 = 1;
+
+    /// Longest attestation kind, e.g. "consent_receipt"
+    pub const MAX_ATTESTATION_KIND_LEN: usize = 64;
+
+    /// What the attester signs
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct AttestationClaims {
+        pub version: u8,
+        /// What is being attested to, in lower snake case
+        pub kind: String,
+        pub attester: AgentPubKey,
+        pub attested_at: Timestamp,
+        /// Canonical JSON encoding of the payload
+        pub payload: Vec<u8>,
+    }
+
+    /// Claims and the attester's signature over them
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct SignedAttestation {
+        pub claims: AttestationClaims,
+        pub signature: Signature,
+    }
+
+    impl SignedAttestation {
+        /// The attested payload, decoded
+        pub fn payload<T: serde::de::DeserializeOwned>(&self) -> ExternResult<T> {
+            serde_json::from_slice(&self.claims.payload).map_err(types::internal_error)
+        }
+
+        /// Whether the attestation is over exactly `payload`
+        pub fn attests_to<T: Serialize>(&self, payload: &T) -> ExternResult<bool> {
+            Ok(canonical_bytes(payload)? == self.claims.payload)
+        }
+    }
+
+    /// Canonical bytes of a payload: JSON without whitespace, object keys in
+    /// sorted order at every level
+    ///
+    /// serde_json keeps insertion order in this build, so maps are sorted
+    /// here rather than relied on to come out sorted.
+    pub fn canonical_bytes<T: Serialize>(payload: &T) -> ExternResult<Vec<u8>> {
+        let value = serde_json::to_value(payload).map_err(types::internal_error)?;
+        let mut out = Vec::new();
+        write_canonical(&value, &mut out)?;
+        Ok(out)
+    }
+
+    fn write_canonical(value: &serde_json::Value, out: &mut Vec<u8>) -> ExternResult<()> {
+        match value {
+            serde_json::Value::Array(items) => {
+                out.push(b'[');
+                for (index, item) in items.iter().enumerate() {
+                    if index > 0 {
+                        out.push(b',');
+                    }
+                    write_canonical(item, out)?;
+                }
+                out.push(b']');
+            }
+            serde_json::Value::Object(fields) => {
+                let mut fields: Vec<_> = fields.iter().collect();
+                fields.sort_by_key(|&(key, _)| key);
+                out.push(b'{');
+                for (index, (key, field)) in fields.into_iter().enumerate() {
+                    if index > 0 {
+                        out.push(b',');
+                    }
+                    serde_json::to_writer(&mut *out, key).map_err(types::internal_error)?;
+                    out.push(b':');
+                    write_canonical(field, out)?;
+                }
+                out.push(b'}');
+            }
+            scalar => serde_json::to_writer(&mut *out, scalar).map_err(types::internal_error)?,
+        }
+        Ok(())
+    }
+
+    /// Check an attestation kind: lower snake case, at most
+    /// `MAX_ATTESTATION_KIND_LEN` characters
+    pub fn validate_attestation_kind(kind: &str) -> ExternResult<()> {
+        let well_formed = !kind.is_empty()
+            && kind.len() <= MAX_ATTESTATION_KIND_LEN
+            && kind.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+        if !well_formed {
+            return Err(HealthError::ValidationError(format!(
+                "Attestation kinds are lower snake case and at most {} characters",
+                MAX_ATTESTATION_KIND_LEN
+            ))
+            .detail("kind", kind)
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Claims the calling agent would sign for `payload` now
+    pub fn attestation_claims<T: Serialize>(kind: &str, payload: &T) -> ExternResult<AttestationClaims> {
+        validate_attestation_kind(kind)?;
+        Ok(AttestationClaims {
+            version: ATTESTATION_VERSION,
+            kind: kind.to_string(),
+            attester: agent_info()?.agent_initial_pubkey,
+            attested_at: sys_time()?,
+            payload: canonical_bytes(payload)?,
+        })
+    }
+
+    /// Attest to `payload` as the calling agent
+    pub fn sign_attestation<T: Serialize>(kind: &str, payload: &T) -> ExternResult<SignedAttestation> {
+        let claims = attestation_claims(kind, payload)?;
+        let signature = sign(claims.attester.clone(), &claims)?;
+        Ok(SignedAttestation { claims, signature })
+    }
+
+    /// Whether `attestation` is a current-version `kind` attestation whose
+    /// attester signed exactly its claims
+    ///
+    /// Who the attester must be, and how old the attestation may be, is
+    /// left to the caller.
+    pub fn verify_attestation(attestation: &SignedAttestation, kind: &str) -> ExternResult<bool> {
+        if attestation.claims.version != ATTESTATION_VERSION || attestation.claims.kind != kind {
+            return Ok(false);
+        }
+        verify_signature(
+            attestation.claims.attester.clone(),
+            attestation.signature.clone(),
+            &attestation.claims,
+        )
+    }
+}
+
+/// Entry reference counting
+///
+/// A link into a tracked entry is mirrored by a back-reference from the entry
+/// to the link's base, so the entry can list who still reaches it. The
+/// back-reference tag names the forward link, and the two are deleted
+/// together. A reference from an action that still exists (a patient, a
+/// clinical record) owns the entry; one from an anchor only indexes it. An
+/// entry left with no owning reference is an orphan that no patient-scoped
+/// query reaches.
+///
+/// Tracked entries are also linked from a per-type registry anchor, so
+/// maintenance can walk them without knowing any owner. Entries written
+/// before tracking was added are not in the registry and are never pruned.
+pub mod references {
+    use super::*;
+
+    const REFERENCE_TAG_PREFIX: &[u8] = b"ref1";
+
+    /// Back-reference tag naming the forward link it mirrors
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub struct ReferenceTag {
+        /// Forward link type, e.g. "PatientToFhirMappings"
+        pub link_type: String,
+        /// Create-link action of the forward link
+        pub forward_link: ActionHash,
+    }
+
+    impl ReferenceTag {
+        pub fn to_tag(&self) -> LinkTag {
+            let mut bytes = REFERENCE_TAG_PREFIX.to_vec();
+            bytes.extend(serde_json::to_vec(self).unwrap_or_default());
+            LinkTag::new(bytes)
+        }
+
+        /// Decode a back-reference tag; tags written by anything else are ignored
+        pub fn from_tag(tag: &LinkTag) -> Option<Self> {
+            let body = tag.as_ref().strip_prefix(REFERENCE_TAG_PREFIX)?;
+            serde_json::from_slice(body).ok()
+        }
+    }
+
+    /// One live reference to a tracked entry
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct EntryReference {
+        pub referrer: AnyLinkableHash,
+        pub link_type: String,
+        pub forward_link: ActionHash,
+        /// Whether the referrer is an action that still exists
+        pub owning: bool,
+    }
+
+    /// Anchor listing every tracked entry of a type
+    pub fn reference_registry_anchor(entry_type: &str) -> ExternResult<EntryHash> {
+        anchor_hash(&format!("reference_registry:{}", entry_type))
+    }
+
+    /// Add a newly created entry to its type's registry
+    pub fn register_tracked_entry<R>(entry_type: &str, entry: ActionHash, registry_type: R) -> ExternResult<ActionHash>
+    where
+        ScopedLinkType: TryFrom<R, Error = WasmError>,
+    {
+        create_link(reference_registry_anchor(entry_type)?, entry, registry_type, ())
+    }
+
+    /// Create a link into a tracked entry along with its back-reference,
+    /// returning the forward link
+    pub fn create_tracked_link<T, R>(
+        base: impl Into<AnyLinkableHash>,
+        target: ActionHash,
+        link_type: T,
+        tag: impl Into<LinkTag>,
+        referrer_type: R,
+    ) -> ExternResult<ActionHash>
+    where
+        T: std::fmt::Debug,
+        ScopedLinkType: TryFrom<T, Error = WasmError> + TryFrom<R, Error = WasmError>,
+    {
+        let base = base.into();
+        let link_name = format!("{:?}", link_type);
+        let forward_link = create_link(base.clone(), target.clone(), link_type, tag)?;
+        let reference = ReferenceTag { link_type: link_name, forward_link: forward_link.clone() };
+        create_link(target, base, referrer_type, reference.to_tag())?;
+        Ok(forward_link)
+    }
+
+    /// Delete a tracked link and its back-reference
+    pub fn delete_tracked_link<R>(target: &ActionHash, forward_link: &ActionHash, referrer_type: R) -> ExternResult<()>
+    where
+        R: TryInto<LinkTypeFilter, Error = WasmError>,
+    {
+        delete_link(forward_link.clone(), GetOptions::default())?;
+        let links = get_links(LinkQuery::try_new(target.clone(), referrer_type)?, GetStrategy::default())?;
+        for link in links {
+            if ReferenceTag::from_tag(&link.tag).is_some_and(|r| &r.forward_link == forward_link) {
+                delete_link(link.create_link_hash, GetOptions::default())?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Live references to a tracked entry, with ownership re-checked
+    pub fn entry_references<R>(target: &ActionHash, referrer_type: R) -> ExternResult<Vec<EntryReference>>
+    where
+        R: TryInto<LinkTypeFilter, Error = WasmError>,
+    {
+        let links = get_links(LinkQuery::try_new(target.clone(), referrer_type)?, GetStrategy::default())?;
+        let mut references = Vec::new();
+        for link in links {
+            let Some(tag) = ReferenceTag::from_tag(&link.tag) else {
+                continue;
+            };
+            let owning = match link.target.clone().into_action_hash() {
+                Some(hash) => action_is_live(hash)?,
+                None => false,
+            };
+            references.push(EntryReference {
+                referrer: link.target,
+                link_type: tag.link_type,
+                forward_link: tag.forward_link,
+                owning,
+            });
+        }
+        Ok(references)
+    }
+
+    /// Whether an action exists and its entry has not been deleted
+    fn action_is_live(hash: ActionHash) -> ExternResult<bool> {
+        Ok(match get_details(hash, GetOptions::default())? {
+            Some(Details::Record(details)) => details.deletes.is_empty(),
+            _ => false,
+        })
+    }
+}
+
+/// Operator statistics
+///
+/// Zomes keep their figures in counters under `stats:` anchors, bumped as
+/// part of each write, so reporting never has to walk the records
+/// themselves. Time-windowed figures are bucketed by UTC day.
+pub mod statistics {
+    use super::*;
+
+    const MICROS_PER_DAY: i64 = 86_400_000_000;
+
+    /// Day bucket (days since the Unix epoch) a timestamp falls into
+    pub fn stats_day(at: Timestamp) -> i64 {
+        at.as_micros().div_euclid(MICROS_PER_DAY)
+    }
+
+    /// Trailing reporting windows, each ending with the current day
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum StatisticsWindow {
+        LastDay,
+        Last7Days,
+        Last30Days,
+    }
+
+    impl StatisticsWindow {
+        pub const ALL: [StatisticsWindow; 3] = [
+            StatisticsWindow::LastDay,
+            StatisticsWindow::Last7Days,
+            StatisticsWindow::Last30Days,
+        ];
+
+        pub fn days(&self) -> usize {
+            match self {
+                StatisticsWindow::LastDay => 1,
+                StatisticsWindow::Last7Days => 7,
+                StatisticsWindow::Last30Days => 30,
+            }
+        }
+    }
+
+    /// Ingest activity over some span of days
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct IngestTotals {
+        pub ingests: u64,
+        /// Ingests whose report carried at least one parse error
+        pub ingests_with_errors: u64,
+        pub resources_processed: u64,
+        pub parse_errors: u64,
+        /// Bytes written for reports and their designated payload fields
+        pub stored_bytes: u64,
+    }
+
+    impl IngestTotals {
+        pub fn add(&mut self, other: &IngestTotals) {
+            self.ingests += other.ingests;
+            self.ingests_with_errors += other.ingests_with_errors;
+            self.resources_processed += other.resources_processed;
+            self.parse_errors += other.parse_errors;
+            self.stored_bytes += other.stored_bytes;
+        }
+
+        /// Fraction of ingests that reported errors
+        pub fn error_rate(&self) -> f64 {
+            if self.ingests == 0 {
+                0.0
+            } else {
+                self.ingests_with_errors as f64 / self.ingests as f64
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct IngestWindowStatistics {
+        pub window: StatisticsWindow,
+        pub totals: IngestTotals,
+        pub error_rate: f64,
+    }
+
+    /// Roll per-day totals up into the standard windows
+    ///
+    /// `daily[0]` is today, `daily[1]` yesterday and so on.
+    pub fn ingest_windows(daily: &[IngestTotals]) -> Vec<IngestWindowStatistics> {
+        StatisticsWindow::ALL
+            .iter()
+            .map(|window| {
+                let mut totals = IngestTotals::default();
+                for day in daily.iter().take(window.days()) {
+                    totals.add(day);
+                }
+                let error_rate = totals.error_rate();
+                IngestWindowStatistics { window: *window, totals, error_rate }
+            })
+            .collect()
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct SourceIngestStatistics {
+        pub source_system: String,
+        pub windows: Vec<IngestWindowStatistics>,
+    }
+
+    /// Ingest activity across all source systems
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct IngestStatistics {
+        pub sources: Vec<SourceIngestStatistics>,
+        pub overall: Vec<IngestWindowStatistics>,
+        pub storage_growth: StorageGrowthEstimate,
+    }
+
+    /// Linear projection of storage growth from recent daily writes
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct StorageGrowthEstimate {
+        pub bytes_last_30_days: u64,
+        pub average_daily_bytes: u64,
+        pub projected_bytes_next_30_days: u64,
+    }
+
+    impl StorageGrowthEstimate {
+        pub fn from_daily(daily: &[IngestTotals]) -> Self {
+            let days = StatisticsWindow::Last30Days.days();
+            let bytes: u64 = daily.iter().take(days).map(|d| d.stored_bytes).sum();
+            let average = bytes / days as u64;
+            Self {
+                bytes_last_30_days: bytes,
+                average_daily_bytes: average,
+                projected_bytes_next_30_days: average * days as u64,
+            }
+        }
+    }
+
+    /// Approximate size of a signed link action (create or delete) with a
+    /// short tag; link storage is priced from action counts
+    pub const LINK_ACTION_BYTES: u64 = 400;
+
+    /// Links on one anchor, counting deleted links whose tombstones the DHT
+    /// still holds
+    #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq)]
+    pub struct AnchorLinkSample {
+        pub anchor: String,
+        pub live_links: u64,
+        pub deleted_links: u64,
+        /// Links created inside the report window, live or not
+        pub recent_links: u64,
+    }
+
+    impl AnchorLinkSample {
+        /// Share of the anchor's links that are tombstones
+        pub fn garbage_ratio(&self) -> f64 {
+            let total = self.live_links + self.deleted_links;
+            if total == 0 {
+                0.0
+            } else {
+                self.deleted_links as f64 / total as f64
+            }
+        }
+    }
+
+    /// Count the live and deleted links on an anchor
+    pub fn sample_anchor_links<T>(anchor: &str, link_type: T, since: Timestamp) -> ExternResult<AnchorLinkSample>
+    where
+        T: TryInto<LinkTypeFilter, Error = WasmError>,
+    {
+        let details = get_links_details(
+            LinkQuery::try_new(anchor_hash(anchor)?, link_type)?,
+            GetStrategy::default(),
+        )?;
+        let mut sample = AnchorLinkSample {
+            anchor: anchor.to_string(),
+            ..Default::default()
+        };
+        for (create, deletes) in details.into_inner() {
+            if deletes.is_empty() {
+                sample.live_links += 1;
+            } else {
+                sample.deleted_links += 1;
+            }
+            if create.action().timestamp() >= since {
+                sample.recent_links += 1;
+            }
+        }
+        Ok(sample)
+    }
+
+    /// App entries of one type written inside the report window
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct EntryTypeFootprint {
+        pub entry_type: String,
+        pub entries: u64,
+        pub bytes: u64,
+        pub average_bytes: u64,
+    }
+
+    /// Sizes of the calling agent's recent app entries, by the zome's entry types
+    ///
+    /// Only the local source chain is read, so this is a sample of one writer;
+    /// entries defined by other zomes are left out.
+    pub fn recent_entry_footprints<U>(since: Timestamp) -> ExternResult<Vec<EntryTypeFootprint>>
+    where
+        U: TryFrom<ScopedEntryDefIndex, Error = WasmError> + std::fmt::Debug,
+    {
+        let records = query(ChainQueryFilter::new().include_entries(true))?;
+        let mut sizes: Vec<(ScopedEntryDefIndex, u64, u64)> = Vec::new();
+        for record in records {
+            if record.action().timestamp() < since {
+                continue;
+            }
+            let (Some(EntryType::App(def)), Some(Entry::App(bytes))) =
+                (record.action().entry_type(), record.entry().as_option())
+            else {
+                continue;
+            };
+            let index = ScopedEntryDefIndex {
+                zome_index: def.zome_index(),
+                zome_type: def.entry_index(),
+            };
+            let size = bytes.bytes().len() as u64;
+            match sizes.iter_mut().find(|(i, _, _)| *i == index) {
+                Some((_, entries, total)) => {
+                    *entries += 1;
+                    *total += size;
+                }
+                None => sizes.push((index, 1, size)),
+            }
+        }
+
+        Ok(sizes
+            .into_iter()
+            .filter_map(|(index, entries, bytes)| {
+                let entry_type = U::try_from(index).ok()?;
+                Some(EntryTypeFootprint {
+                    entry_type: format!("{:?}", entry_type),
+                    entries,
+                    bytes,
+                    average_bytes: bytes / entries,
+                })
+            })
+            .collect())
+    }
+
+    /// Storage figures for one zome, for capacity planning
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct StorageReport {
+        pub zome: String,
+        pub generated_at: Timestamp,
+        /// Days of recent writes the rates are drawn from
+        pub window_days: u32,
+        pub anchors: Vec<AnchorLinkSample>,
+        pub entry_types: Vec<EntryTypeFootprint>,
+        pub live_links: u64,
+        pub deleted_links: u64,
+        /// Share of sampled links that are tombstones
+        pub garbage_ratio: f64,
+        /// Estimated bytes held for the sampled links, tombstones included
+        pub link_bytes: u64,
+        pub entry_bytes_per_day: u64,
+        pub link_bytes_per_day: u64,
+        pub projected_bytes_next_30_days: u64,
+    }
+
+    impl StorageReport {
+        pub fn new(
+            zome: &str,
+            generated_at: Timestamp,
+            window_days: u32,
+            anchors: Vec<AnchorLinkSample>,
+            entry_types: Vec<EntryTypeFootprint>,
+        ) -> Self {
+            let days = u64::from(window_days.max(1));
+            let live_links: u64 = anchors.iter().map(|a| a.live_links).sum();
+            let deleted_links: u64 = anchors.iter().map(|a| a.deleted_links).sum();
+            let recent_links: u64 = anchors.iter().map(|a| a.recent_links).sum();
+            let garbage = AnchorLinkSample {
+                live_links,
+                deleted_links,
+                ..Default::default()
+            };
+            let entry_bytes_per_day = entry_types.iter().map(|e| e.bytes).sum::<u64>() / days;
+            let link_bytes_per_day = recent_links * LINK_ACTION_BYTES / days;
+            Self {
+                zome: zome.to_string(),
+                generated_at,
+                window_days,
+                garbage_ratio: garbage.garbage_ratio(),
+                // A deleted link costs its create and its delete action
+                link_bytes: (live_links + 2 * deleted_links) * LINK_ACTION_BYTES,
+                live_links,
+                deleted_links,
+                anchors,
+                entry_types,
+                entry_bytes_per_day,
+                link_bytes_per_day,
+                projected_bytes_next_30_days: (entry_bytes_per_day + link_bytes_per_day)
+                    * StatisticsWindow::Last30Days.days() as u64,
+            }
+        }
+    }
+
+    /// Reject callers that do not hold the operator role in the consent zome
+    pub fn require_operator() -> ExternResult<()> {
+        let caller = agent_info()?.agent_initial_pubkey;
+        let response = call(
+            CallTargetCell::Local,
+            "consent",
+            "is_operator".into(),
+            None,
+            &caller,
+        )?;
+
+        let is_operator: bool = match response {
+            ZomeCallResponse::Ok(extern_io) => extern_io.decode().map_err(|e| {
+                wasm_error!(WasmErrorInner::Guest(format!("Failed to decode operator check: {:?}", e)))
+            })?,
+            other => {
+                return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                    "Operator check failed: {:?}",
+                    other
+                ))))
+            }
+        };
+
+        if !is_operator {
+            return Err(wasm_error!(WasmErrorInner::Guest(
+                "Network statistics require the operator role".to_string()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Provisional commits for optimistic UIs
+///
+/// A write is visible to its author as soon as it lands on the source chain,
+/// but other agents only see it once its ops have been published and
+/// validated. Writes can hand back a [`ProvisionalCommit`] straight away and
+/// the UI later upgrades it with [`confirm_entry_published`].
+pub mod provisional {
+    use super::*;
+
+    /// A write that is on the author's source chain but may not be on the DHT yet
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct ProvisionalCommit {
+        pub action_hash: ActionHash,
+        pub entry_hash: Option<EntryHash>,
+        pub committed_at: Timestamp,
+        pub record: Record,
+        pub provisional: bool,
+    }
+
+    impl ProvisionalCommit {
+        pub fn from_record(record: Record) -> Self {
+            Self {
+                action_hash: record.action_address().clone(),
+                entry_hash: record.action().entry_hash().cloned(),
+                committed_at: record.action().timestamp(),
+                record,
+                provisional: true,
+            }
+        }
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum PublicationStatus {
+        /// Committed locally; validators have not all answered yet
+        Provisional,
+        /// Every op has its full set of validation receipts
+        Published,
+        /// A validator rejected or abandoned one of the ops
+        Rejected,
+        /// Neither a local commit nor a DHT copy could be found
+        NotFound,
+    }
+
+    #[derive(Clone, Debug, Serialize, Deserialize)]
+    pub struct PublicationConfirmation {
+        pub action_hash: ActionHash,
+        pub status: PublicationStatus,
+        /// Validation receipts received so far (author's conductor only)
+        pub receipts: u32,
+        /// Whether the record can be fetched from the network
+        pub available: bool,
+    }
+
+    impl PublicationConfirmation {
+        pub fn provisional(&self) -> bool {
+            self.status == PublicationStatus::Provisional
+        }
+    }
+
+    /// Summarise validation receipts into a publication status
+    pub fn publication_status(receipt_sets: &[ValidationReceiptSet]) -> PublicationStatus {
+        let rejected = receipt_sets
+            .iter()
+            .flat_map(|set| &set.receipts)
+            .any(|receipt| receipt.validation_status != ValidationStatus::Valid);
+        if rejected {
+            PublicationStatus::Rejected
+        } else if !receipt_sets.is_empty() && receipt_sets.iter().all(|set| set.receipts_complete) {
+            PublicationStatus::Published
+        } else {
+            PublicationStatus::Provisional
+        }
+    }
+
+    /// Check whether a provisional write has reached the DHT
+    ///
+    /// Receipts are only held by the author's conductor, so for anyone else a
+    /// successful network fetch is taken as confirmation.
+    pub fn confirm_entry_published(action_hash: ActionHash) -> ExternResult<PublicationConfirmation> {
+        let record = get(action_hash.clone(), GetOptions::network())?;
+        let me = agent_info()?.agent_initial_pubkey;
+
+        let (status, receipts) = match &record {
+            None => (PublicationStatus::NotFound, 0),
+            Some(record) if record.action().author() == &me => {
+                let sets = get_validation_receipts(GetValidationReceiptsInput::new(action_hash.clone()))?;
+                let receipts = sets.iter().map(|set| set.receipts.len() as u32).sum();
+                (publication_status(&sets), receipts)
+            }
+            Some(_) => (PublicationStatus::Published, 0),
+        };
+
+        Ok(PublicationConfirmation {
+            action_hash,
+            status,
+            receipts,
+            available: record.is_some(),
+        })
+    }
+}
+
+/// Idempotency keys for retried creates
+///
+/// A client that retries a zome call it never heard back from would
+/// otherwise create the same entry twice. Creates that accept a key link a
+/// per-caller anchor, derived from a hash of the key, to the action they
+/// created, tagged with a fingerprint of the request; a retry with the same
+/// key and request finds the link and gets that record back instead, while
+/// reusing the key for a different request is a conflict. Keys are scoped
+/// to the caller and the extern, so two agents (or two externs) never
+/// share one.
+pub mod idempotency {
+    use super::*;
+
+    /// Longest idempotency key a caller may send
+    pub const MAX_IDEMPOTENCY_KEY_LEN: usize = 128;
+
+    /// Check a key: printable ASCII without spaces, at most
+    /// `MAX_IDEMPOTENCY_KEY_LEN` characters
+    pub fn validate_idempotency_key(key: &str) -> ExternResult<()> {
+        if key.is_empty() || key.len() > MAX_IDEMPOTENCY_KEY_LEN || !key.chars().all(|c| c.is_ascii_graphic()) {
+            return Err(HealthError::ValidationError(format!(
+                "Idempotency keys are 1 to {} printable characters without spaces",
+                MAX_IDEMPOTENCY_KEY_LEN
+            ))
+            .field("idempotency_key")
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Anchor text for a key; only a hash of the key appears in it
+    pub fn idempotency_anchor_text(scope: &str, caller: &AgentPubKey, key: &str) -> String {
+        format!(
+            "idempotency:{}:{}:{}",
+            scope,
+            caller,
+            encryption::base64_encode(&encryption::sha256_hash(key.as_bytes()))
+        )
+    }
+
+    /// Fingerprint of a request, kept with its key to tell a retry from a
+    /// different request reusing the key
+    pub fn idempotency_fingerprint<I: Serialize + std::fmt::Debug>(request: &I) -> ExternResult<Vec<u8>> {
+        let encoded = ExternIO::encode(request).map_err(|e| wasm_error!(WasmErrorInner::Serialize(e)))?;
+        Ok(encryption::sha256_hash(&encoded.0).to_vec())
+    }
+
+    /// The action a key already created for the calling agent, if any,
+    /// with the fingerprint of the request that created it
+    pub fn idempotent_lookup<T>(scope: &str, key: &str, link_type: T) -> ExternResult<Option<(ActionHash, Vec<u8>)>>
+    where
+        T: TryInto<LinkTypeFilter, Error = WasmError>,
+    {
+        validate_idempotency_key(key)?;
+        let me = agent_info()?.agent_initial_pubkey;
+        let anchor = anchor_hash(&idempotency_anchor_text(scope, &me, key))?;
+        let links = get_links(LinkQuery::try_new(anchor, link_type)?.author(me), GetStrategy::Local)?;
+        Ok(links
+            .into_iter()
+            .min_by_key(|link| link.timestamp)
+            .and_then(|link| Some((link.target.into_action_hash()?, link.tag.into_inner()))))
+    }
+
+    /// Run `create` unless `key` already created a record for the caller
+    ///
+    /// Returns the earlier record when `request` matches the one that
+    /// created it, and a conflict when the key was used for a different
+    /// request. Two calls racing with the same key can both create; the
+    /// earliest link wins later lookups.
+    pub fn idempotent_create<T, I>(
+        scope: &str,
+        key: &str,
+        request: &I,
+        link_type: T,
+        create: impl FnOnce() -> ExternResult<Record>,
+    ) -> ExternResult<Record>
+    where
+        T: Clone + TryInto<LinkTypeFilter, Error = WasmError>,
+        ScopedLinkType: TryFrom<T, Error = WasmError>,
+        I: Serialize + std::fmt::Debug,
+    {
+        let fingerprint = idempotency_fingerprint(request)?;
+        if let Some((existing, created_with)) = idempotent_lookup(scope, key, link_type.clone())? {
+            if created_with != fingerprint {
+                return Err(HealthError::Conflict(
+                    "Idempotency key was already used for a different request".to_string(),
+                )
+                .field("idempotency_key")
+                .into());
+            }
+            if let Some(record) = get(existing, GetOptions::local())? {
+                return Ok(record);
+            }
+        }
+        let record = create()?;
+        let me = agent_info()?.agent_initial_pubkey;
+        create_link(
+            anchor_hash(&idempotency_anchor_text(scope, &me, key))?,
+            record.action_address().clone(),
+            link_type,
+            LinkTag::new(fingerprint),
+        )?;
+        Ok(record)
+    }
+}
+
+/// Passphrase-encrypted backups of an agent's source chain
+///
+/// Keys are derived with PBKDF2-HMAC-SHA256 and the compressed chain is
+/// sealed with ChaCha20-Poly1305, the AEAD field encryption uses, with the
+/// header as associated data. Version 1 backups, sealed with HMAC-SHA256 in
+/// counter mode, can still be opened.
+pub mod backup {
+    use super::*;
+    use super::encryption::hmac_sha256;
+    use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+    use chacha20poly1305::{ChaCha20Poly1305, Nonce};
+    use std::collections::HashMap;
+
+    pub const BACKUP_FORMAT_VERSION: u8 = 2;
+
+    /// Backups sealed with the HMAC-SHA256 keystream; open only
+    pub const LEGACY_HMAC_BACKUP_VERSION: u8 = 1;
+
+    /// Nonce length for newly created backups
+    pub const BACKUP_NONCE_BYTES: usize = 12;
+
+    const BACKUP_TAG_BYTES: usize = 16;
+
+    /// PBKDF2 rounds for newly created backups
+    pub const BACKUP_KDF_ITERATIONS: u32 = 100_000;
+
+    /// Ciphertext bytes carried by each backup chunk
+    pub const BACKUP_CHUNK_BYTES: usize = 256 * 1024;
+
+    pub const BACKUP_MIN_PASSPHRASE_CHARS: usize = 12;
+
+    /// Upper bound on a decompressed backup, guarding against deflate bombs
+    pub const BACKUP_MAX_INFLATED_BYTES: usize = 64 * 1024 * 1024;
+
+    /// An app entry as it was committed on the original chain
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct BackedUpEntry {
+        pub original_action_hash: ActionHash,
+        pub original_timestamp: Timestamp,
+        pub entry_type: AppEntryDef,
+        /// Action the original updated, if it was an update
+        pub revision_of: Option<ActionHash>,
+        #[serde(with = "serde_bytes")]
+        pub entry_bytes: Vec<u8>,
+    }
+
+    /// A link as it was created on the original chain
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct BackedUpLink {
+        pub original_action_hash: ActionHash,
+        pub original_timestamp: Timestamp,
+        pub zome_index: ZomeIndex,
+        pub link_type: LinkType,
+        pub base: AnyLinkableHash,
+        pub target: AnyLinkableHash,
+        #[serde(with = "serde_bytes")]
+        pub tag: Vec<u8>,
+    }
+
+    /// Everything needed to re-create a chain on a new agent
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct ChainBackupPayload {
+        pub backup_id: String,
+        pub agent: AgentPubKey,
+        pub created_at: Timestamp,
+        /// In original chain order
+        pub entries: Vec<BackedUpEntry>,
+        pub links: Vec<BackedUpLink>,
+    }
+
+    /// One piece of an encrypted backup
     ///
-    /// # Returns
-    /// BatchGetResult with records, not_found, and errors
-    pub fn batch_get_records(
-        hashes: Vec<ActionHash>,
-        options: BatchGetOptions,
-    ) -> ExternResult<BatchGetResult> {
-        let total = hashes.len();
-        let mut result = BatchGetResult::new(total);
+    /// Every chunk repeats the header so any chunk identifies its backup and
+    /// the key derivation parameters.
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct BackupChunk {
+        pub backup_id: String,
+        pub version: u8,
+        pub index: u32,
+        pub total: u32,
+        pub kdf_iterations: u32,
+        #[serde(with = "serde_bytes")]
+        pub salt: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        pub nonce: Vec<u8>,
+        /// Authentication tag over the header and the whole ciphertext
+        #[serde(with = "serde_bytes")]
+        pub tag: Vec<u8>,
+        #[serde(with = "serde_bytes")]
+        pub data: Vec<u8>,
+    }
 
-        let limit = if options.limit == 0 { total } else { options.limit.min(total) };
+    /// PBKDF2-HMAC-SHA256 with a single 32-byte output block
+    pub fn derive_backup_key(passphrase: &str, salt: &[u8], iterations: u32) -> [u8; 32] {
+        let mut key = [0u8; 32];
+        super::encryption::pbkdf2_hmac_sha256(passphrase.as_bytes(), salt, iterations, &mut key);
+        key
+    }
 
-        for hash in hashes.into_iter().take(limit) {
-            match get(hash.clone(), GetOptions::default()) {
-                Ok(Some(record)) => {
-                    // Check if deleted
-                    if options.skip_deleted {
-                        if let Action::Delete(_) = record.action() {
-                            continue;
-                        }
-                    }
-                    result.records.push(record);
-                    result.success_count += 1;
-                }
-                Ok(None) => {
-                    result.not_found.push(hash);
-                }
-                Err(e) => {
-                    result.errors.push((hash, format!("{:?}", e)));
-                }
+    /// Keystream of version 1 backups
+    fn apply_keystream(key: &[u8; 32], nonce: &[u8], data: &mut [u8]) {
+        for (counter, block) in data.chunks_mut(32).enumerate() {
+            let stream = hmac_sha256(key, &[b"stream", nonce, &(counter as u64).to_be_bytes()]);
+            for (d, s) in block.iter_mut().zip(stream.iter()) {
+                *d ^= s;
             }
         }
+    }
 
-        Ok(result)
+    fn header_bytes(backup_id: &str, version: u8, kdf_iterations: u32, salt: &[u8]) -> Vec<u8> {
+        let mut header = Vec::new();
+        header.extend_from_slice(&(backup_id.len() as u32).to_be_bytes());
+        header.extend_from_slice(backup_id.as_bytes());
+        header.push(version);
+        header.extend_from_slice(&kdf_iterations.to_be_bytes());
+        header.extend_from_slice(salt);
+        header
     }
 
-    /// Convert links to records with pagination
-    ///
-    /// Takes a list of links and returns paginated records.
-    /// Use this after getting links from your zome's link type.
+    /// Serialize, compress, encrypt and chunk a chain backup
+    pub fn seal_chain_backup(
+        payload: &ChainBackupPayload,
+        passphrase: &str,
+        salt: &[u8],
+        nonce: &[u8],
+        kdf_iterations: u32,
+    ) -> Result<Vec<BackupChunk>, String> {
+        if passphrase.chars().count() < BACKUP_MIN_PASSPHRASE_CHARS {
+            return Err(format!(
+                "Backup passphrase must be at least {} characters",
+                BACKUP_MIN_PASSPHRASE_CHARS
+            ));
+        }
+        if nonce.len() != BACKUP_NONCE_BYTES {
+            return Err(format!("Backup nonce must be {} bytes", BACKUP_NONCE_BYTES));
+        }
+        let encoded = ExternIO::encode(payload).map_err(|e| e.to_string())?.0;
+        let compressed = miniz_oxide::deflate::compress_to_vec(&encoded, 6);
+
+        let key = derive_backup_key(passphrase, salt, kdf_iterations);
+        let header = header_bytes(&payload.backup_id, BACKUP_FORMAT_VERSION, kdf_iterations, salt);
+        let mut data = ChaCha20Poly1305::new((&key).into())
+            .encrypt(Nonce::from_slice(nonce), Payload { msg: &compressed, aad: &header })
+            .map_err(|_| "Failed to encrypt backup".to_string())?;
+        let tag = data.split_off(data.len() - BACKUP_TAG_BYTES);
+
+        let pieces: Vec<&[u8]> = if data.is_empty() {
+            vec![&[]]
+        } else {
+            data.chunks(BACKUP_CHUNK_BYTES).collect()
+        };
+        let total = pieces.len() as u32;
+        Ok(pieces
+            .into_iter()
+            .enumerate()
+            .map(|(index, piece)| BackupChunk {
+                backup_id: payload.backup_id.clone(),
+                version: BACKUP_FORMAT_VERSION,
+                index: index as u32,
+                total,
+                kdf_iterations,
+                salt: salt.to_vec(),
+                nonce: nonce.to_vec(),
+                tag: tag.clone(),
+                data: piece.to_vec(),
+            })
+            .collect())
+    }
+
+    /// Reassemble, authenticate and decrypt a chain backup
     ///
-    /// # Arguments
-    /// * `links` - Links to process
-    /// * `pagination` - Pagination parameters
+    /// A wrong passphrase and a tampered or incomplete backup are reported the
+    /// same way, before anything is decrypted.
+    pub fn open_chain_backup(chunks: &[BackupChunk], passphrase: &str) -> Result<ChainBackupPayload, String> {
+        let first = chunks.first().ok_or("Backup has no chunks")?;
+        if first.version != BACKUP_FORMAT_VERSION && first.version != LEGACY_HMAC_BACKUP_VERSION {
+            return Err(format!("Unsupported backup format version {}", first.version));
+        }
+        let same_backup = chunks.iter().all(|c| {
+            c.backup_id == first.backup_id
+                && c.version == first.version
+                && c.total == first.total
+                && c.kdf_iterations == first.kdf_iterations
+                && c.salt == first.salt
+                && c.nonce == first.nonce
+                && c.tag == first.tag
+        });
+        if !same_backup {
+            return Err("Chunks belong to different backups".to_string());
+        }
+        let mut ordered: Vec<&BackupChunk> = chunks.iter().collect();
+        ordered.sort_by_key(|c| c.index);
+        ordered.dedup_by_key(|c| c.index);
+        if ordered.len() as u32 != first.total
+            || ordered.iter().enumerate().any(|(i, c)| c.index != i as u32)
+        {
+            return Err(format!("Backup is incomplete: expected {} chunks", first.total));
+        }
+        let mut data: Vec<u8> = ordered.iter().flat_map(|c| c.data.iter().copied()).collect();
+
+        let key = derive_backup_key(passphrase, &first.salt, first.kdf_iterations);
+        let header = header_bytes(&first.backup_id, first.version, first.kdf_iterations, &first.salt);
+        let compressed = if first.version == LEGACY_HMAC_BACKUP_VERSION {
+            open_legacy_hmac(&key, &header, first, data)?
+        } else {
+            if first.nonce.len() != BACKUP_NONCE_BYTES || first.tag.len() != BACKUP_TAG_BYTES {
+                return Err("Wrong passphrase or corrupted backup".to_string());
+            }
+            data.extend_from_slice(&first.tag);
+            ChaCha20Poly1305::new((&key).into())
+                .decrypt(Nonce::from_slice(&first.nonce), Payload { msg: &data, aad: &header })
+                .map_err(|_| "Wrong passphrase or corrupted backup".to_string())?
+        };
+
+        let encoded = miniz_oxide::inflate::decompress_to_vec_with_limit(&compressed, BACKUP_MAX_INFLATED_BYTES)
+            .map_err(|e| format!("Failed to inflate backup: {:?}", e.status))?;
+        ExternIO(encoded).decode().map_err(|e| e.to_string())
+    }
+
+    /// Check the encrypt-then-MAC tag of a version 1 backup, then decrypt it
+    fn open_legacy_hmac(key: &[u8; 32], header: &[u8], first: &BackupChunk, mut data: Vec<u8>) -> Result<Vec<u8>, String> {
+        let enc_key = hmac_sha256(key, &[b"encrypt"]);
+        let mac_key = hmac_sha256(key, &[b"authenticate"]);
+        let expected = hmac_sha256(&mac_key, &[header, &first.nonce, &data]);
+        let mismatch = expected
+            .iter()
+            .zip(first.tag.iter())
+            .fold((expected.len() != first.tag.len()) as u8, |acc, (a, b)| acc | (a ^ b));
+        if mismatch != 0 {
+            return Err("Wrong passphrase or corrupted backup".to_string());
+        }
+        apply_keystream(&enc_key, &first.nonce, &mut data);
+        Ok(data)
+    }
+
+    /// Rewrite serialized hashes and agent keys in place
     ///
-    /// # Returns
-    /// PaginatedResult with the fetched records
-    pub fn links_to_records_paginated(
-        links: Vec<Link>,
-        pagination: &types::PaginationInput,
-    ) -> ExternResult<types::PaginatedResult<Record>> {
-        pagination.validate()?;
+    /// Holo hashes serialize as 39-byte msgpack binaries, so swapping one for
+    /// another of the same length leaves the surrounding encoding intact.
+    /// Returns how many hashes were replaced.
+    pub fn remap_raw_hashes(bytes: &mut [u8], replacements: &HashMap<Vec<u8>, Vec<u8>>) -> usize {
+        const BIN8: u8 = 0xc4;
+        const RAW_LEN: usize = 39;
+        let mut replaced = 0;
+        let mut i = 0;
+        while i + 2 + RAW_LEN <= bytes.len() {
+            if bytes[i] == BIN8 && bytes[i + 1] as usize == RAW_LEN {
+                let window = i + 2..i + 2 + RAW_LEN;
+                if let Some(new) = replacements.get(&bytes[window.clone()]) {
+                    if new.len() == RAW_LEN {
+                        bytes[window].copy_from_slice(new);
+                        replaced += 1;
+                        i += 2 + RAW_LEN;
+                        continue;
+                    }
+                }
+            }
+            i += 1;
+        }
+        replaced
+    }
+}
 
-        let total = links.len();
+/// Multi-step writes that undo their earlier steps when a later one fails
+///
+/// A failed zome call already discards everything it wrote, so compensation
+/// matters for flows that record an error and carry on, such as per-resource
+/// ingest, where a half-finished unit would otherwise be committed.
+pub mod saga {
+    use super::*;
+    use std::fmt::Display;
 
-        // Apply pagination
-        let paginated_links: Vec<_> = links
-            .into_iter()
-            .skip(pagination.offset)
-            .take(pagination.limit)
-            .collect();
+    /// A write made by a saga step and how to take it back
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum SagaCompensation {
+        DeleteEntry(ActionHash),
+        DeleteLink(ActionHash),
+    }
 
-        // Extract target hashes
-        let hashes: Vec<ActionHash> = paginated_links
-            .iter()
-            .filter_map(|link| link.target.clone().into_action_hash())
-            .collect();
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum SagaStepStatus {
+        Planned,
+        Completed,
+        Failed,
+        Compensated,
+        /// At least one of the step's writes could not be undone
+        CompensationFailed,
+    }
 
-        // Batch fetch records
-        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
+    #[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+    pub struct SagaStep {
+        pub name: String,
+        pub status: SagaStepStatus,
+        pub compensations: Vec<SagaCompensation>,
+        pub error: Option<String>,
+    }
 
-        Ok(types::PaginatedResult::new(
-            batch_result.records,
-            total,
-            pagination,
-        ))
+    #[derive(Clone, Copy, Debug, Serialize, Deserialize, PartialEq, Eq)]
+    pub enum SagaStatus {
+        Running,
+        Completed,
+        /// A step failed and every earlier write was undone
+        Compensated,
+        /// A step failed and some writes are still in place
+        CompensationFailed,
     }
 
-    /// Get records from links (non-paginated helper)
-    ///
-    /// Converts a list of links to their target records.
-    pub fn links_to_records(links: Vec<Link>) -> ExternResult<Vec<Record>> {
-        let hashes: Vec<ActionHash> = links
-            .into_iter()
-            .filter_map(|link| link.target.into_action_hash())
-            .collect();
+    /// The planned steps of a saga and what became of each
+    #[hdk_entry_helper]
+    #[derive(Clone, PartialEq)]
+    pub struct SagaLog {
+        pub saga_id: String,
+        pub name: String,
+        pub started_at: Timestamp,
+        pub finished_at: Option<Timestamp>,
+        pub status: SagaStatus,
+        pub steps: Vec<SagaStep>,
+    }
 
-        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
-        Ok(batch_result.records)
+    impl SagaLog {
+        /// Check that a finished log is internally consistent
+        pub fn validate(&self) -> Result<(), String> {
+            if self.saga_id.trim().is_empty() || self.name.trim().is_empty() {
+                return Err("Saga log needs an id and a name".to_string());
+            }
+            if self.steps.is_empty() || self.steps.iter().any(|step| step.name.trim().is_empty()) {
+                return Err("Saga log needs named steps".to_string());
+            }
+            match self.finished_at {
+                Some(finished_at) if finished_at >= self.started_at => {}
+                _ => return Err("Saga log must be finished after it started".to_string()),
+            }
+
+            let count = |status: SagaStepStatus| self.steps.iter().filter(|step| step.status == status).count();
+            let consistent = match self.status {
+                SagaStatus::Running => false,
+                SagaStatus::Completed => {
+                    count(SagaStepStatus::Failed) == 0
+                        && count(SagaStepStatus::Compensated) == 0
+                        && count(SagaStepStatus::CompensationFailed) == 0
+                }
+                SagaStatus::Compensated => {
+                    count(SagaStepStatus::Failed) == 1
+                        && count(SagaStepStatus::Completed) == 0
+                        && count(SagaStepStatus::CompensationFailed) == 0
+                }
+                SagaStatus::CompensationFailed => {
+                    count(SagaStepStatus::Failed) + count(SagaStepStatus::CompensationFailed) >= 1
+                        && count(SagaStepStatus::Completed) == 0
+                }
+            };
+            if !consistent {
+                return Err(format!("Saga log step statuses do not match {:?}", self.status));
+            }
+            Ok(())
+        }
     }
 
-    /// Get the most recent N records from links
+    /// A running saga
     ///
-    /// Useful for "recent activity" views.
-    /// Links are sorted by timestamp (newest first).
-    pub fn links_to_recent_records(
-        mut links: Vec<Link>,
-        count: usize,
-    ) -> ExternResult<Vec<Record>> {
-        // Sort by timestamp (newest first)
-        links.sort_by(|a, b| b.timestamp.cmp(&a.timestamp));
+    /// Steps run in the order they are called. Each step pushes a compensation
+    /// for every write it makes; when a step fails, its own writes and then
+    /// those of the completed steps are undone in reverse order and the step's
+    /// error is returned. A saga must not be stepped again after a failure.
+    #[derive(Clone, Debug)]
+    pub struct Saga {
+        log: SagaLog,
+    }
 
-        // Take only the requested count
-        let hashes: Vec<ActionHash> = links
-            .into_iter()
-            .take(count)
-            .filter_map(|link| link.target.into_action_hash())
-            .collect();
+    impl Saga {
+        pub fn new(name: &str, planned: &[&str], started_at: Timestamp) -> Self {
+            Self {
+                log: SagaLog {
+                    saga_id: format!("{}-{}", name, started_at.as_micros()),
+                    name: name.to_string(),
+                    started_at,
+                    finished_at: None,
+                    status: SagaStatus::Running,
+                    steps: planned
+                        .iter()
+                        .map(|step| SagaStep {
+                            name: step.to_string(),
+                            status: SagaStepStatus::Planned,
+                            compensations: Vec::new(),
+                            error: None,
+                        })
+                        .collect(),
+                },
+            }
+        }
 
-        let batch_result = batch_get_records(hashes, BatchGetOptions::default())?;
-        Ok(batch_result.records)
+        /// Start a saga with its planned steps
+        pub fn begin(name: &str, planned: &[&str]) -> ExternResult<Self> {
+            Ok(Self::new(name, planned, sys_time()?))
+        }
+
+        pub fn status(&self) -> SagaStatus {
+            self.log.status
+        }
+
+        /// Whether a failed step triggered compensation
+        pub fn failed(&self) -> bool {
+            !matches!(self.log.status, SagaStatus::Running | SagaStatus::Completed)
+        }
+
+        /// Run a step, undoing the saga's writes if it fails
+        pub fn step<T, E: Display>(
+            &mut self,
+            name: &str,
+            run: impl FnOnce(&mut Vec<SagaCompensation>) -> Result<T, E>,
+        ) -> Result<T, E> {
+            self.step_with(name, run, run_compensation)
+        }
+
+        pub(crate) fn step_with<T, E: Display>(
+            &mut self,
+            name: &str,
+            run: impl FnOnce(&mut Vec<SagaCompensation>) -> Result<T, E>,
+            mut compensate: impl FnMut(&SagaCompensation) -> ExternResult<()>,
+        ) -> Result<T, E> {
+            let index = self.planned_step(name);
+            let mut compensations = Vec::new();
+            let result = run(&mut compensations);
+            let failed_step = &mut self.log.steps[index];
+            failed_step.compensations = compensations;
+
+            let error = match result {
+                Ok(value) => {
+                    failed_step.status = SagaStepStatus::Completed;
+                    return Ok(value);
+                }
+                Err(e) => e,
+            };
+
+            failed_step.status = SagaStepStatus::Failed;
+            failed_step.error = Some(error.to_string());
+            let mut all_undone = true;
+            for (i, step) in self.log.steps.iter_mut().enumerate().rev() {
+                if i != index && step.status != SagaStepStatus::Completed {
+                    continue;
+                }
+                // Attempt every compensation even after one fails
+                let mut undone = true;
+                for compensation in step.compensations.iter().rev() {
+                    undone &= compensate(compensation).is_ok();
+                }
+                if i != index {
+                    step.status = if undone {
+                        SagaStepStatus::Compensated
+                    } else {
+                        SagaStepStatus::CompensationFailed
+                    };
+                }
+                all_undone &= undone;
+            }
+            self.log.status = if all_undone {
+                SagaStatus::Compensated
+            } else {
+                SagaStatus::CompensationFailed
+            };
+            Err(error)
+        }
+
+        /// The first still-planned step with this name, adding it if it was not planned
+        fn planned_step(&mut self, name: &str) -> usize {
+            if let Some(index) = self
+                .log
+                .steps
+                .iter()
+                .position(|step| step.name == name && step.status == SagaStepStatus::Planned)
+            {
+                return index;
+            }
+            self.log.steps.push(SagaStep {
+                name: name.to_string(),
+                status: SagaStepStatus::Planned,
+                compensations: Vec::new(),
+                error: None,
+            });
+            self.log.steps.len() - 1
+        }
+
+        /// Close the saga; planned steps that never ran stay planned
+        pub fn finish(mut self, finished_at: Timestamp) -> SagaLog {
+            if self.log.status == SagaStatus::Running {
+                self.log.status = SagaStatus::Completed;
+            }
+            self.log.finished_at = Some(finished_at);
+            self.log
+        }
+    }
+
+    fn run_compensation(compensation: &SagaCompensation) -> ExternResult<()> {
+        match compensation {
+            SagaCompensation::DeleteEntry(hash) => delete_entry(hash.clone()).map(|_| ()),
+            SagaCompensation::DeleteLink(hash) => delete_link(hash.clone(), GetOptions::default()).map(|_| ()),
+        }
     }
 }
 
@@ -1524,18 +9031,337 @@ mod tests {
     }
 
     #[test]
-    fn test_sharded_anchors() {
-        let shards = anchors::all_shard_anchors("patients");
-        assert_eq!(shards.len(), 27); // A-Z + _
-        assert!(shards.contains(&"patients_A".to_string()));
-        assert!(shards.contains(&"patients_Z".to_string()));
-        assert!(shards.contains(&"patients__".to_string()));
+    fn test_sharded_anchors() {
+        let shards = anchors::all_shard_anchors("patients");
+        assert_eq!(shards.len(), 27); // A-Z + _
+        assert!(shards.contains(&"patients_A".to_string()));
+        assert!(shards.contains(&"patients_Z".to_string()));
+        assert!(shards.contains(&"patients__".to_string()));
+    }
+
+    #[test]
+    fn test_period_anchors() {
+        // 2026-03-14T23:59:59Z and 2026-03-15T00:00:00Z
+        let late = Timestamp::from_micros(1_773_532_799_000_000);
+        let midnight = Timestamp::from_micros(1_773_532_800_000_000);
+        assert_eq!(period_anchor("access_logs", late, AnchorPeriod::Day), "access_logs:2026-03-14");
+        assert_eq!(period_anchor("access_logs", midnight, AnchorPeriod::Day), "access_logs:2026-03-15");
+        assert_eq!(period_anchor("access_logs", late, AnchorPeriod::Month), "access_logs:2026-03");
+
+        let days = period_anchors_between("logs", AnchorPeriod::Day, late, midnight).unwrap();
+        assert_eq!(days, vec!["logs:2026-03-14", "logs:2026-03-15"]);
+        assert!(period_anchors_between("logs", AnchorPeriod::Day, midnight, late).unwrap().is_empty());
+
+        // 2025-11-20 through 2026-02-01 crosses a year boundary
+        let november = Timestamp::from_micros(1_763_596_800_000_000);
+        let february = Timestamp::from_micros(1_769_904_000_000_000);
+        let months = period_anchors_between("logs", AnchorPeriod::Month, november, february).unwrap();
+        assert_eq!(months, vec!["logs:2025-11", "logs:2025-12", "logs:2026-01", "logs:2026-02"]);
+        assert_eq!(period_anchors_between("logs", AnchorPeriod::Day, november, february).unwrap().len(), 74);
+
+        let decade = Timestamp::from_micros(february.as_micros() + 3_650 * 86_400_000_000);
+        assert!(period_anchors_between("logs", AnchorPeriod::Day, february, decade).is_err());
+        assert_eq!(period_anchors_between("logs", AnchorPeriod::Month, february, decade).unwrap().len(), 120);
+    }
+
+    #[test]
+    fn test_retention_policies() {
+        let policy = |byte: u8, category: DataCategory, retention_days: u32| ApplicableRetention {
+            policy_hash: ActionHash::from_raw_36(vec![byte; 36]),
+            jurisdiction: "US-CA".to_string(),
+            category,
+            retention_days,
+            action: RetentionAction::Mark,
+        };
+        let policies = vec![policy(1, DataCategory::All, 3_650), policy(2, DataCategory::LabResults, 365)];
+        assert_eq!(retention_for(&policies, &DataCategory::LabResults).unwrap().retention_days, 365);
+        assert_eq!(retention_for(&policies, &DataCategory::VitalSigns).unwrap().retention_days, 3_650);
+        assert!(retention_for(&policies[1..], &DataCategory::VitalSigns).is_none());
+
+        let day = |d: i64| Timestamp::from_micros(d * 86_400_000_000);
+        let labs = &policies[1];
+        assert!(!labs.expired(day(100), day(465)));
+        assert!(labs.expired(day(100), day(466)));
+    }
+
+    #[test]
+    fn test_attestation_canonical_bytes() {
+        use std::collections::HashMap;
+
+        #[derive(Serialize)]
+        struct Manifest {
+            zeta: u32,
+            alpha: Vec<&'static str>,
+            counts: HashMap<String, u32>,
+        }
+        let counts: HashMap<String, u32> = [("b", 2), ("a", 1), ("c", 3)]
+            .into_iter()
+            .map(|(k, v)| (k.to_string(), v))
+            .collect();
+        let manifest = Manifest { zeta: 7, alpha: vec!["x", "y"], counts };
+        let bytes = canonical_bytes(&manifest).unwrap();
+        assert_eq!(
+            String::from_utf8(bytes.clone()).unwrap(),
+            r#"{"alpha":["x","y"],"counts":{"a":1,"b":2,"c":3},"zeta":7}"#
+        );
+
+        // Key order in the input does not change the encoding
+        let reordered = serde_json::json!({"zeta": 7, "counts": {"c": 3, "a": 1, "b": 2}, "alpha": ["x", "y"]});
+        assert_eq!(canonical_bytes(&reordered).unwrap(), bytes);
+
+        let attestation = SignedAttestation {
+            claims: AttestationClaims {
+                version: ATTESTATION_VERSION,
+                kind: "export_manifest".to_string(),
+                attester: AgentPubKey::from_raw_36(vec![1; 36]),
+                attested_at: Timestamp::from_micros(0),
+                payload: bytes,
+            },
+            signature: Signature::from([0u8; 64]),
+        };
+        assert!(attestation.attests_to(&reordered).unwrap());
+        assert!(!attestation.attests_to(&serde_json::json!({"zeta": 8})).unwrap());
+        let decoded: serde_json::Value = attestation.payload().unwrap();
+        assert_eq!(decoded["counts"]["b"], 2);
+
+        assert!(validate_attestation_kind("consent_receipt").is_ok());
+        assert!(validate_attestation_kind("").is_err());
+        assert!(validate_attestation_kind("Consent Receipt").is_err());
+        assert!(validate_attestation_kind(&"a".repeat(MAX_ATTESTATION_KIND_LEN + 1)).is_err());
+    }
+
+    #[test]
+    fn test_data_category_display() {
+        assert_eq!(format!("{}", DataCategory::Demographics), "Demographics");
+        assert_eq!(format!("{}", DataCategory::LabResults), "LabResults");
+    }
+
+    #[test]
+    fn test_identity_assurance_level() {
+        let none = IdentityAttestations::default();
+        assert_eq!(none.assurance_level(), IdentityAssuranceLevel::SelfAsserted);
+
+        let countersigned = IdentityAttestations { organization_countersigned: true, ..none.clone() };
+        assert_eq!(countersigned.assurance_level(), IdentityAssuranceLevel::OrganizationCountersigned);
+
+        // A license without a profile to attach it to proves nothing
+        let orphan_license = IdentityAttestations { license_verified: true, ..countersigned.clone() };
+        assert_eq!(orphan_license.assurance_level(), IdentityAssuranceLevel::OrganizationCountersigned);
+
+        let licensed = IdentityAttestations { has_profile: true, ..orphan_license };
+        assert_eq!(licensed.assurance_level(), IdentityAssuranceLevel::LicenseVerified);
+        assert!(IdentityAssuranceLevel::LicenseVerified > IdentityAssuranceLevel::OrganizationCountersigned);
+    }
+
+    #[test]
+    fn test_assignable_roles() {
+        use access_control::Role;
+        assert!(Role::Provider.is_assignable());
+        assert!(Role::Auditor.is_assignable());
+        assert!(Role::EmergencyAccess.is_assignable());
+        assert!(!Role::Admin.is_assignable());
+        assert!(!Role::Patient.is_assignable());
+    }
+
+    #[test]
+    fn test_needs_countersignature() {
+        assert!(needs_countersignature(&[DataCategory::Medications, DataCategory::MentalHealth]));
+        assert!(needs_countersignature(&[DataCategory::GeneticData]));
+        assert!(needs_countersignature(&[DataCategory::All]));
+        assert!(!needs_countersignature(&[DataCategory::Medications, DataCategory::LabResults]));
+        assert!(!needs_countersignature(&[]));
+    }
+
+    #[test]
+    fn test_combine_deny_overrides() {
+        let statement = |source: PolicySource, byte: u8, effect: PolicyEffect| PolicyStatement {
+            source,
+            policy_hash: ActionHash::from_raw_36(vec![byte; 36]),
+            effect,
+            explanation: format!("{:?}", effect),
+        };
+        let consent_permit = statement(PolicySource::Consent, 1, PolicyEffect::Permit);
+        let delegation_deny = statement(PolicySource::Delegation, 2, PolicyEffect::Deny);
+        let team_permit = statement(PolicySource::CareTeam, 3, PolicyEffect::Permit);
+
+        // A delegation's exclusion wins over both permits, whatever the order
+        let decision = combine_deny_overrides(vec![consent_permit.clone(), delegation_deny.clone(), team_permit.clone()]);
+        assert_eq!(decision.effect, PolicyEffect::Deny);
+        assert_eq!(decision.decisive, Some(delegation_deny.clone()));
+        assert!(decision.explanation.starts_with("Denied by Delegation"));
+        assert!(decision.explanation.ends_with("overrides 2 permits"));
+        assert_eq!(decision.statements.len(), 3);
+
+        let decision = combine_deny_overrides(vec![team_permit.clone(), consent_permit]);
+        assert!(decision.permitted());
+        assert_eq!(decision.decisive, Some(team_permit));
+
+        let shortfall = PolicyStatement {
+            explanation: "Consent needs LicenseVerified identity assurance".to_string(),
+            ..statement(PolicySource::Consent, 4, PolicyEffect::NotApplicable)
+        };
+        let decision = combine_deny_overrides(vec![shortfall]);
+        assert_eq!(decision.effect, PolicyEffect::NotApplicable);
+        assert!(decision.decisive.is_none());
+        assert_eq!(decision.explanation, "Consent needs LicenseVerified identity assurance");
+        assert_eq!(combine_deny_overrides(vec![]).explanation, "No applicable policy");
+    }
+
+    #[test]
+    fn test_authorization_cache() {
+        let patient = |byte: u8| ActionHash::from_raw_36(vec![byte; 36]);
+        let key = |patient_hash: ActionHash, permission: Permission| AuthorizationCacheKey {
+            requestor: AgentPubKey::from_raw_36(vec![1; 36]),
+            patient_hash,
+            category: DataCategory::LabResults,
+            permission,
+            access_context: None,
+        };
+        let granted = AuthorizationResult {
+            authorized: true,
+            consent_hash: Some(patient(9)),
+            reason: "Consent".to_string(),
+            permissions: vec![Permission::Read],
+            emergency_override: false,
+            assurance_level: IdentityAssuranceLevel::LicenseVerified,
+            decisive_policy: None,
+        };
+        let at = |seconds: i64| Timestamp::from_micros(seconds * 1_000_000);
+
+        let mut cache = AuthorizationCache::default();
+        cache.insert(key(patient(2), Permission::Read), granted.clone(), at(100));
+        assert_eq!(cache.lookup(&key(patient(2), Permission::Read), at(120), 30).unwrap().consent_hash, Some(patient(9)));
+        assert!(cache.lookup(&key(patient(2), Permission::Write), at(120), 30).is_none());
+        let research = AuthorizationCacheKey {
+            access_context: Some(AccessContext::new(PurposeOfUse::Research)),
+            ..key(patient(2), Permission::Read)
+        };
+        assert!(cache.lookup(&research, at(120), 30).is_none());
+        // Expired entries are dropped on lookup
+        assert!(cache.lookup(&key(patient(2), Permission::Read), at(130), 30).is_none());
+        assert!(cache.is_empty());
+
+        // Denials and emergency overrides are never cached
+        cache.insert(key(patient(2), Permission::Read), AuthorizationResult { authorized: false, ..granted.clone() }, at(200));
+        cache.insert(key(patient(2), Permission::Read), AuthorizationResult { emergency_override: true, ..granted.clone() }, at(200));
+        assert!(cache.is_empty());
+
+        cache.insert(key(patient(2), Permission::Read), granted.clone(), at(200));
+        cache.insert(key(patient(3), Permission::Read), granted.clone(), at(200));
+        cache.insert(key(patient(3), Permission::Read), granted, at(201));
+        assert_eq!(cache.len(), 2);
+        cache.invalidate_patient(&patient(3));
+        assert!(cache.lookup(&key(patient(3), Permission::Read), at(205), 30).is_none());
+        assert!(cache.lookup(&key(patient(2), Permission::Read), at(205), 30).is_some());
+    }
+
+    #[test]
+    fn test_access_context_validation() {
+        let mut context = AccessContext::new(PurposeOfUse::Treatment);
+        assert!(context.validate().is_valid());
+        assert_eq!(context.access_location(), DEFAULT_ACCESS_LOCATION);
+
+        context.facility = Some("St. Mary's ED".to_string());
+        context.device = Some("ws-0412".to_string());
+        context.requesting_application = Some("charting-app".to_string());
+        assert!(context.validate().is_valid());
+        assert_eq!(context.access_location(), "St. Mary's ED");
+
+        context.device = Some("  ".to_string());
+        context.requesting_application = Some("a".repeat(MAX_ACCESS_CONTEXT_FIELD_LEN + 1));
+        let result = context.validate();
+        let fields: Vec<&str> = result.errors.iter().map(|e| e.field.as_str()).collect();
+        assert_eq!(fields, vec!["device", "requesting_application"]);
+
+        let other = AccessContext::new(PurposeOfUse::Other("line\nbreak".to_string()));
+        assert_eq!(other.validate().errors[0].code, ValidationErrorCode::InvalidCharacters);
+    }
+
+    #[test]
+    fn test_cursor_pagination() {
+        let item = |micros: i64, hash: u8| (Timestamp::from_micros(micros), hash);
+        let cursor_of = |item: &(Timestamp, u8)| PaginationCursor::new(item.0, &[item.1]);
+        let items = vec![item(30, 1), item(10, 2), item(20, 9), item(20, 3)];
+
+        let first = cursor_page(items.clone(), &CursorInput { after: None, limit: 2 }, cursor_of).unwrap();
+        assert_eq!(first.items, vec![item(10, 2), item(20, 3)]);
+        assert!(first.has_more);
+        let token = first.next_cursor.unwrap();
+        assert_eq!(PaginationCursor::decode(&token).unwrap(), PaginationCursor::new(Timestamp::from_micros(20), &[3]));
+
+        // A newer item arriving between calls does not shift the next page
+        let mut grown = items;
+        grown.push(item(40, 0));
+        let second = cursor_page(grown, &CursorInput { after: Some(token), limit: 2 }, cursor_of).unwrap();
+        assert_eq!(second.items, vec![item(20, 9), item(30, 1)]);
+        assert!(second.has_more);
+
+        let last = cursor_page(vec![item(10, 2)], &CursorInput { after: None, limit: 2 }, cursor_of).unwrap();
+        assert!(!last.has_more);
+        assert!(last.next_cursor.is_none());
+        assert!(PaginationCursor::decode("rb1:7").is_err());
+    }
+
+    #[test]
+    fn test_record_status() {
+        let hash = |byte: u8| ActionHash::from_raw_36(vec![byte; 36]);
+        let fresh = RecordStatus::default();
+        assert!(!fresh.is_deleted());
+        assert!(!fresh.is_updated());
+
+        let status = RecordStatus { updates: vec![hash(1)], deletes: vec![hash(2)] };
+        assert!(status.is_updated());
+        assert!(status.is_deleted());
+        assert!(BatchGetOptions::default().entry_type.is_none());
+    }
+
+    #[test]
+    fn test_batch_chunk_plan() {
+        let hash = |byte: u8| ActionHash::from_raw_36(vec![byte; 36]);
+        let hashes = vec![hash(5), hash(1), hash(4), hash(2), hash(3), hash(1)];
+        let input = |continuation: Option<String>| BatchChunkInput { continuation, chunk_size: Some(2) };
+
+        let first = plan_chunk(hashes.clone(), &input(None)).unwrap();
+        assert_eq!(first.hashes, vec![hash(1), hash(2)]);
+        assert_eq!(first.total, 5);
+        let second = plan_chunk(hashes.clone(), &input(first.continuation.clone())).unwrap();
+        assert_eq!(second.hashes, vec![hash(3), hash(4)]);
+        let last = plan_chunk(hashes.clone(), &input(second.continuation)).unwrap();
+        assert_eq!(last.hashes, vec![hash(5)]);
+        assert!(last.continuation.is_none());
+
+        // A hash sorting before the resume point does not repeat earlier ones
+        let mut grown = hashes;
+        grown.push(hash(0));
+        let resumed = plan_chunk(grown, &input(first.continuation)).unwrap();
+        assert_eq!(resumed.hashes, vec![hash(3), hash(4)]);
+        assert_eq!(resumed.total, 6);
+
+        assert!(plan_chunk(vec![], &input(Some("rb1:2".to_string()))).is_err());
+        let oversized = BatchChunkInput { continuation: None, chunk_size: Some(MAX_BATCH_CHUNK_SIZE + 1) };
+        assert!(plan_chunk(vec![hash(1)], &oversized).is_err());
+        assert!(plan_chunk(vec![], &BatchChunkInput::default()).unwrap().hashes.is_empty());
     }
 
     #[test]
-    fn test_data_category_display() {
-        assert_eq!(format!("{}", DataCategory::Demographics), "Demographics");
-        assert_eq!(format!("{}", DataCategory::LabResults), "LabResults");
+    fn test_idempotency_keys() {
+        assert!(validate_idempotency_key("retry-7f3a").is_ok());
+        assert!(validate_idempotency_key("").is_err());
+        assert!(validate_idempotency_key("has space").is_err());
+        assert!(validate_idempotency_key(&"k".repeat(MAX_IDEMPOTENCY_KEY_LEN + 1)).is_err());
+
+        let alice = AgentPubKey::from_raw_36(vec![1; 36]);
+        let bob = AgentPubKey::from_raw_36(vec![2; 36]);
+        let anchor = idempotency_anchor_text("consent::create_consent", &alice, "retry-7f3a");
+        assert_eq!(anchor, idempotency_anchor_text("consent::create_consent", &alice, "retry-7f3a"));
+        assert!(!anchor.contains("retry-7f3a"));
+        assert_ne!(anchor, idempotency_anchor_text("consent::create_consent", &bob, "retry-7f3a"));
+        assert_ne!(anchor, idempotency_anchor_text("consent::create_access_notification", &alice, "retry-7f3a"));
+        assert_ne!(anchor, idempotency_anchor_text("consent::create_consent", &alice, "retry-7f3b"));
+
+        let request = idempotency_fingerprint(&("retry-7f3a", 1u32)).unwrap();
+        assert_eq!(request, idempotency_fingerprint(&("retry-7f3a", 1u32)).unwrap());
+        assert_ne!(request, idempotency_fingerprint(&("retry-7f3a", 2u32)).unwrap());
     }
 
     // ============== Validation Module Tests ==============
@@ -1575,6 +9401,147 @@ mod tests {
         assert!(result.errors.iter().any(|e| e.code == validation::ValidationErrorCode::TooLong));
     }
 
+    #[test]
+    fn test_validate_code() {
+        use validation::{validate_code, ClinicalCodeSystem::*};
+        let valid = [
+            (Loinc, "2345-7"), (Loinc, "4548-4"), (Loinc, "75622-1"), (Loinc, "8480-6"),
+            (SnomedCt, "80146002"), (SnomedCt, "44054006"), (SnomedCt, "38341003"), (SnomedCt, "73211009"), (SnomedCt, "22298006"),
+            (Icd10Cm, "E11.65"), (Icd10Cm, "F32.1"), (Icd10Cm, "S72.001A"), (Icd10Cm, "I10"), (Icd10Cm, "E1165"),
+            (RxNorm, "1819"), (RxNorm, "860975"),
+            (Cpt, "99213"), (Cpt, "0001F"), (Cpt, "0042T"),
+        ];
+        for (system, code) in valid {
+            assert!(validate_code(system, code).is_valid(), "{:?} {}", system, code);
+        }
+
+        let invalid = [
+            (Loinc, "2345"), (Loinc, "12345678-9"), (Loinc, "ABC-1"),
+            (SnomedCt, "12345"), (SnomedCt, "080146002"), (SnomedCt, "80146012"),
+            (Icd10Cm, "e11.65"), (Icd10Cm, "E1.65"), (Icd10Cm, "E11."), (Icd10Cm, "1E1"), (Icd10Cm, "E11.65432"),
+            (RxNorm, "01819"), (RxNorm, "RX1819"),
+            (Cpt, "9921"), (Cpt, "9921X"), (Cpt, "A9213"),
+        ];
+        for (system, code) in invalid {
+            assert!(!validate_code(system, code).is_valid(), "{:?} {}", system, code);
+        }
+
+        // Check digits are reported as such
+        let wrong_loinc = validate_code(Loinc, "2345-6");
+        assert_eq!(wrong_loinc.errors[0].code, validation::ValidationErrorCode::InvalidCheckDigit);
+        let wrong_sctid = validate_code(SnomedCt, "80146003");
+        assert!(wrong_sctid.errors.iter().any(|e| e.code == validation::ValidationErrorCode::InvalidCheckDigit));
+
+        assert_eq!(validation::ClinicalCodeSystem::from_system("http://loinc.org"), Some(Loinc));
+        assert_eq!(validation::ClinicalCodeSystem::from_system("ICD10-CM"), Some(Icd10Cm));
+        assert_eq!(validation::ClinicalCodeSystem::from_system("http://example.org/local"), None);
+    }
+
+    #[test]
+    fn test_ucum_validation() {
+        use validation::ucum;
+        for code in ["mg/dL", "mmol/L", "kg/m2", "mm[Hg]", "10*3/uL", "/min", "%", "[lb_av]", "Cel", "{cells}/uL", "mL/min/{1.73_m2}"] {
+            assert!(ucum::is_valid_ucum(code), "{}", code);
+        }
+        for code in ["", "mg/", "lbs", "mg dL", "[degF", "xyz", "kmin"] {
+            assert!(!ucum::is_valid_ucum(code), "{}", code);
+        }
+        assert_eq!(ucum::normalize_unit("lbs").as_deref(), Some("[lb_av]"));
+        assert_eq!(ucum::normalize_unit(" mg/dl ").as_deref(), Some("mg/dL"));
+        assert_eq!(ucum::normalize_unit("µmol/L").as_deref(), Some("umol/L"));
+        assert_eq!(ucum::normalize_unit("furlongs"), None);
+
+        assert!(ucum::validate_quantity("valueQuantity", 5.4, Some(ucum::UCUM_SYSTEM), Some("mmol/L")).is_valid());
+        assert!(ucum::validate_quantity("valueQuantity", 5.4, Some("http://example.org/units"), Some("whatever")).is_valid());
+        assert!(!ucum::validate_quantity("valueQuantity", 5.4, Some(ucum::UCUM_SYSTEM), Some("lbs")).is_valid());
+        assert!(!ucum::validate_quantity("valueQuantity", 5.4, Some(ucum::UCUM_SYSTEM), None).is_valid());
+        assert!(!ucum::validate_quantity("valueQuantity", f64::NAN, None, Some("kg")).is_valid());
+    }
+
+    #[test]
+    fn test_ucum_conversion() {
+        use validation::ucum;
+        let close = |a: f64, b: f64| (a - b).abs() < 1e-2;
+
+        assert!(close(ucum::convert(165.0, "lbs", "kg", None).unwrap(), 74.84));
+        assert!(close(ucum::convert(98.6, "[degF]", "Cel", None).unwrap(), 37.0));
+        assert!(close(ucum::convert(37.0, "Cel", "[degF]", None).unwrap(), 98.6));
+        assert!(close(ucum::convert(5.0, "[ft_i]", "cm", None).unwrap(), 152.4));
+
+        let glucose = ucum::molar_mass_for_loinc("2345-7");
+        assert!(close(ucum::convert(90.0, "mg/dL", "mmol/L", glucose).unwrap(), 5.0));
+        assert!(close(ucum::convert(5.0, "mmol/L", "mg/dL", glucose).unwrap(), 90.08));
+        let creatinine = ucum::molar_mass_for_loinc("2160-0");
+        assert!(close(ucum::convert(1.0, "mg/dL", "umol/L", creatinine).unwrap(), 88.40));
+
+        // Crossing mass and substance needs a molar mass; kinds must match
+        assert!(ucum::convert(90.0, "mg/dL", "mmol/L", None).is_err());
+        assert!(ucum::convert(1.0, "kg", "cm", None).is_err());
+        assert!(ucum::convert(-500.0, "Cel", "K", None).is_err());
+    }
+
+    #[test]
+    fn test_validate_jurisdiction() {
+        for code in ["DE", "EU", "US-CA", "GB-ENG", "FR-75"] {
+            assert!(validation::validate_jurisdiction(code).is_valid(), "{}", code);
+        }
+        for code in ["", "de", "DEU", "US-", "US-CALI", "US_CA"] {
+            assert!(!validation::validate_jurisdiction(code).is_valid(), "{}", code);
+        }
+
+        let residency = PatientResidency {
+            jurisdiction: Some("EU".to_string()),
+            prohibited_uses: vec![DataUse::Marketing, DataUse::DrugDevelopment],
+        };
+        assert!(!residency.allows(DataUse::Marketing));
+        assert!(residency.allows(DataUse::Export));
+        let uses = ["AcademicResearch".to_string(), "DrugDevelopment".to_string()];
+        assert_eq!(residency.permitted_contribution_uses(&uses), vec!["AcademicResearch".to_string()]);
+    }
+
+    #[test]
+    fn test_evaluate_site_rules() {
+        let rule = |field_path: &str, predicate, severity| SiteValidationRule {
+            field_path: field_path.to_string(),
+            predicate,
+            severity,
+            message: None,
+        };
+        let rules = vec![
+            rule("mrn", RulePredicate::Mask("MRN-######".to_string()), RuleSeverity::Error),
+            rule("contact.phone", RulePredicate::Required, RuleSeverity::Error),
+            rule("allergies.0", RulePredicate::Required, RuleSeverity::Warning),
+            rule("sex", RulePredicate::OneOf(vec!["F".to_string(), "M".to_string()]), RuleSeverity::Error),
+            rule("score", RulePredicate::NumericRange { min: Some(0.0), max: Some(1.0) }, RuleSeverity::Error),
+            rule("nickname", RulePredicate::MaxLength(3), RuleSeverity::Error),
+        ];
+
+        let entry = serde_json::json!({
+            "mrn": "MRN-004211",
+            "contact": { "phone": "555-0100" },
+            "allergies": [],
+            "sex": "F",
+            "score": 0.4,
+        });
+        let (errors, warnings) = evaluate_site_rules(&rules, &entry);
+        assert!(errors.is_empty(), "{:?}", errors);
+        assert_eq!(warnings.len(), 1);
+        assert_eq!(warnings[0].field_path, "allergies.0");
+
+        let entry = serde_json::json!({
+            "mrn": "MRN-42",
+            "contact": { "phone": "" },
+            "allergies": ["latex"],
+            "sex": "X",
+            "score": 1.5,
+            "nickname": "Bobby",
+        });
+        let (errors, warnings) = evaluate_site_rules(&rules, &entry);
+        let failed: Vec<&str> = errors.iter().map(|v| v.field_path.as_str()).collect();
+        assert_eq!(failed, vec!["mrn", "contact.phone", "sex", "score", "nickname"]);
+        assert!(warnings.is_empty());
+    }
+
     #[test]
     fn test_validate_did_valid() {
         let result = validation::validate_did("did:key:z6MkhaXgBZDvotDkL5257faiztiGiC2QtKLGpbnnEGta2doK");
@@ -1780,4 +9747,1284 @@ mod tests {
         let err = types::HealthError::ValidationError("Invalid MRN".to_string());
         assert_eq!(format!("{}", err), "Validation error: Invalid MRN");
     }
+
+    #[test]
+    fn test_health_error_payload() {
+        let payload = types::HealthError::Unauthorized("This operation requires the Admin role".to_string())
+            .detail("role", "Admin");
+        let guest = payload.to_guest_string();
+        assert_eq!(
+            guest,
+            r#"{"code":"UNAUTHORIZED","message":"This operation requires the Admin role","details":{"role":"Admin"}}"#
+        );
+        assert_eq!(types::HealthErrorPayload::from_guest_message(&guest), Some(payload.clone()));
+
+        // Relayed through a cross-zome call: wrapped and escaped
+        let relayed = format!("RibosomeError(WasmError {{ error: Guest({:?}) }})", guest);
+        assert_eq!(types::HealthErrorPayload::from_guest_message(&relayed), Some(payload));
+        assert_eq!(types::HealthErrorPayload::from_guest_message("Plain failure"), None);
+
+        let mut result = validation::ValidationResult::new();
+        result.add_error("mrn", "MRN is required", validation::ValidationErrorCode::Required);
+        result.add_error("did", "DID is required", validation::ValidationErrorCode::Required);
+        let error = result.into_result().unwrap_err();
+        let payload = types::HealthErrorPayload::from_wasm_error(&error).unwrap();
+        assert_eq!(payload.code, types::HealthErrorCode::ValidationError);
+        assert_eq!(payload.field.as_deref(), Some("mrn"));
+        assert!(payload.details["errors"].contains("DID is required"));
+    }
+
+    #[test]
+    fn test_call_failure_keeps_callee_error_code() {
+        let callee: WasmError = types::HealthError::NotFound("Consent not found".to_string()).into();
+        let failure = resilience::CallFailure::Rejected(format!("{:?}", callee));
+        let payload = failure.into_payload("checking authorization");
+        assert_eq!(payload.code, types::HealthErrorCode::NotFound);
+        assert_eq!(payload.details["during"], "checking authorization");
+
+        let timeout = resilience::CallFailure::Timeout("30s".to_string()).into_payload("reading consent");
+        assert_eq!(timeout.code, types::HealthErrorCode::Unavailable);
+    }
+
+    #[test]
+    fn test_api_manifest_type_tags() {
+        let externs = [
+            ExternSpec { name: "get_patient", input: "ActionHash", output: "Option<Record>" },
+            ExternSpec { name: "list_patients", input: "()", output: "Vec<Record>" },
+            ExternSpec { name: "update_patient", input: "Patient", output: "Option<Vec< PatientIdentityLink >>" },
+        ];
+        let manifest = build_api_manifest(
+            "patient",
+            "0.1.0",
+            &externs,
+            &[],
+            vec!["Patient".to_string(), "PatientIdentityLink".to_string()],
+            &[("Patient", 2)],
+        );
+
+        assert!(manifest.supports("get_patient"));
+        assert!(!manifest.supports("delete_patient"));
+        assert_eq!(manifest.externs[1].output_tag, "Vec<Record>");
+        assert_eq!(manifest.externs[2].input_tag, "Patient@v2");
+        assert_eq!(manifest.externs[2].output_tag, "Option<Vec<PatientIdentityLink@v1>>");
+        // Only whole names are versioned
+        assert_eq!(type_tag("PatientSummary", &manifest.entry_types), "PatientSummary");
+        assert_eq!(manifest.entry_types[0].version, 2);
+        assert_eq!(manifest.entry_types[1].version, INITIAL_ENTRY_VERSION);
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct CompressedHolder {
+        #[serde(with = "compressed")]
+        body: Option<String>,
+    }
+
+    #[derive(Debug, Serialize, Deserialize, PartialEq)]
+    struct PlainHolder {
+        body: Option<String>,
+    }
+
+    #[test]
+    fn test_compressed_field_round_trip() {
+        let large = CompressedHolder {
+            body: Some("{\"resourceType\":\"Observation\",\"status\":\"final\"}".repeat(40)),
+        };
+        let io = ExternIO::encode(&large).unwrap();
+        let plain_io = ExternIO::encode(&PlainHolder { body: large.body.clone() }).unwrap();
+        assert!(io.0.len() < plain_io.0.len() / 4);
+        assert_eq!(io.decode::<CompressedHolder>().unwrap(), large);
+
+        // Small values stay plain so the designated field remains readable
+        let small = CompressedHolder { body: Some("final".to_string()) };
+        let io = ExternIO::encode(&small).unwrap();
+        assert_eq!(io.decode::<PlainHolder>().unwrap().body, small.body);
+
+        // Entries written before the field was designated still decode
+        let legacy = ExternIO::encode(&PlainHolder { body: large.body.clone() }).unwrap();
+        assert_eq!(legacy.decode::<CompressedHolder>().unwrap(), large);
+
+        let mut stats = PayloadSizeStats::default();
+        stats.record(&large.body);
+        stats.record(&small.body);
+        assert_eq!(stats.fields, 2);
+        assert_eq!(stats.compressed_fields, 1);
+        assert!(stats.ratio() < 0.25);
+    }
+
+    #[test]
+    fn test_lab_explanations() {
+        let hba1c = explain_lab_result("4548-4", "5.9", "%", "High", ExplanationLanguage::English).unwrap();
+        assert_eq!(hba1c.position, RangePosition::SlightlyAbove);
+        assert_eq!(
+            hba1c.summary,
+            "Your HbA1c measures average blood sugar over about 3 months; yours is slightly above the typical range."
+        );
+
+        let spanish = ExplanationLanguage::from_preference("es-MX");
+        let glucose = explain_lab_result("2345-7", "85", "mg/dL", "Normal", spanish).unwrap();
+        assert_eq!(glucose.language, "es");
+        assert_eq!(glucose.position, RangePosition::Within);
+        assert!(glucose.summary.starts_with("Su glucosa mide"));
+
+        // Unit mismatch falls back to the lab's own flag
+        let mmol = explain_lab_result("2345-7", "9.1", "mmol/L", "High", ExplanationLanguage::English).unwrap();
+        assert_eq!(mmol.position, RangePosition::WellAbove);
+
+        assert_eq!(ExplanationLanguage::from_preference("Klingon"), ExplanationLanguage::English);
+        assert!(explain_lab_result("0000-0", "1", "", "Normal", ExplanationLanguage::English).is_none());
+    }
+
+    #[test]
+    fn test_local_date_labels() {
+        // 2024-02-29 23:30 UTC
+        let at = Timestamp::from_micros(1_709_249_400_000_000);
+
+        let utc = local_date_time(at, 0);
+        assert_eq!((utc.year, utc.month, utc.day, utc.hour, utc.minute), (2024, 2, 29, 23, 30));
+
+        // East of UTC the access already falls in March
+        let berlin = local_date_time(at, 60);
+        assert_eq!(month_key(&berlin), "2024-03");
+        assert_eq!(date_label(&berlin, ExplanationLanguage::English), "March 1, 2024");
+        assert_eq!(date_label(&berlin, ExplanationLanguage::Spanish), "1 de marzo de 2024");
+        assert_eq!(time_label(&berlin, ExplanationLanguage::English), "12:30 AM");
+
+        let bogota = local_date_time(at, -300);
+        assert_eq!(month_label(&bogota, ExplanationLanguage::Spanish), "febrero de 2024");
+        assert_eq!(time_label(&bogota, ExplanationLanguage::English), "6:30 PM");
+        assert_eq!(time_label(&bogota, ExplanationLanguage::Spanish), "18:30");
+
+        let before_epoch = local_date_time(Timestamp::from_micros(-1), 0);
+        assert_eq!((before_epoch.year, before_epoch.month, before_epoch.day), (1969, 12, 31));
+
+        assert!(is_valid_utc_offset(840));
+        assert!(!is_valid_utc_offset(-721));
+    }
+
+    #[test]
+    fn test_fhir_instant_round_trip() {
+        let at = Timestamp::from_micros(1_709_289_000_000_000);
+        assert_eq!(fhir_instant(at), "2024-03-01T10:30:00Z");
+        assert_eq!(parse_fhir_datetime(&fhir_instant(at)), Some(at));
+        assert_eq!(fhir_instant(Timestamp::from_micros(-1_000_000)), "1969-12-31T23:59:59Z");
+    }
+
+    #[test]
+    fn test_fhir_extension_round_trip() {
+        let patient = serde_json::json!({
+            "resourceType": "Patient",
+            "extension": [
+                {
+                    "url": US_CORE_RACE_URL,
+                    "extension": [
+                        { "url": "ombCategory", "valueCoding": { "system": "urn:oid:2.16.840.1.113883.6.238", "code": "2106-3", "display": "White" } },
+                        { "url": "detailed", "valueCoding": { "system": "urn:oid:2.16.840.1.113883.6.238", "code": "2108-9", "display": "European" } },
+                        { "url": "text", "valueString": "White" }
+                    ]
+                },
+                { "url": US_CORE_BIRTH_SEX_URL, "valueCode": "F" },
+                { "url": "http://example.org/payer-plan", "valueString": "Gold" },
+                { "valueString": "no url" }
+            ],
+            "modifierExtension": [{ "url": "http://example.org/confidential", "valueBoolean": true }]
+        });
+
+        let extensions = capture_fhir_extensions(&patient);
+        assert_eq!(extensions.len(), 4);
+        assert!(extensions[3].modifier);
+        assert!(extensions.iter().all(FhirExtension::is_well_formed));
+        let relabelled = FhirExtension { url: "http://example.org/other".to_string(), ..extensions[2].clone() };
+        assert!(!relabelled.is_well_formed());
+
+        let demographics = UsCoreDemographics::from_extensions(&extensions);
+        let race = demographics.race.unwrap();
+        assert_eq!(race.omb_categories[0].code, "2106-3");
+        assert_eq!(race.detailed[0].code, "2108-9");
+        assert_eq!(race.text.as_deref(), Some("White"));
+        assert_eq!(demographics.ethnicity, None);
+        assert_eq!(demographics.birth_sex.as_deref(), Some("F"));
+
+        let mut exported = serde_json::json!({ "resourceType": "Patient" });
+        emit_fhir_extensions(&mut exported, &extensions);
+        assert_eq!(exported["extension"].as_array().unwrap().len(), 3);
+        assert_eq!(exported["extension"][2], patient["extension"][2]);
+        assert_eq!(exported["modifierExtension"], patient["modifierExtension"]);
+    }
+
+    #[test]
+    fn test_entry_size_limits() {
+        #[derive(Debug, Serialize)]
+        struct Scope {
+            exclusions: Vec<String>,
+        }
+        #[derive(Debug, Serialize)]
+        struct Note {
+            title: String,
+            notes: Option<String>,
+            scope: Scope,
+            #[serde(with = "serde_bytes")]
+            signature: Vec<u8>,
+            readings: Vec<u8>,
+        }
+        const LIMITS: EntrySizeLimits = EntrySizeLimits {
+            max_string_chars: 10,
+            max_list_items: 3,
+            field_limits: &[("notes", FieldLimit::Chars(40)), ("exclusions", FieldLimit::Items(2))],
+            ..DEFAULT_ENTRY_SIZE_LIMITS
+        };
+        let note = |title: &str, notes: &str, exclusions: usize| Note {
+            title: title.to_string(),
+            notes: Some(notes.to_string()),
+            scope: Scope { exclusions: vec!["x".to_string(); exclusions] },
+            signature: vec![0; 64],
+            readings: vec![1; 50],
+        };
+        let check = |n: &Note| check_entry_bytes(&holochain_serialized_bytes::encode(n).unwrap(), &LIMITS);
+
+        // Byte fields and numeric lists only count towards the byte budget
+        assert!(check(&note("Visit", &"a".repeat(40), 2)).is_ok());
+
+        let long_title = check(&note("Follow-up visit", "", 0)).unwrap_err();
+        assert_eq!(long_title.code, ValidationErrorCode::TooLong);
+        assert_eq!(long_title.field, "title");
+
+        let long_notes = check(&note("Visit", &"a".repeat(41), 0)).unwrap_err();
+        assert_eq!(long_notes.field, "notes");
+
+        let many = check(&note("Visit", "", 3)).unwrap_err();
+        assert_eq!(many.code, ValidationErrorCode::TooManyItems);
+        assert_eq!(many.field, "scope.exclusions");
+
+        let tiny = EntrySizeLimits { max_entry_bytes: 16, ..LIMITS };
+        let encoded = holochain_serialized_bytes::encode(&note("Visit", "", 0)).unwrap();
+        assert_eq!(check_entry_bytes(&encoded, &tiny).unwrap_err().code, ValidationErrorCode::EntryTooLarge);
+    }
+
+    #[test]
+    fn test_field_encryption() {
+        use encryption::*;
+
+        let key = EncryptionKey::new([7u8; 32]);
+        let patient = ActionHash::from_raw_36(vec![3; 36]);
+        let binding = FieldBinding::new(patient.clone(), "Patient", "ssn");
+        let sealed = encrypt_field("123-45-6789", &key, SensitiveFieldType::Ssn, &binding).unwrap();
+        assert_eq!(sealed.version, ENCRYPTION_VERSION);
+        assert!(!sealed.ciphertext.contains("123-45-6789"));
+        assert_eq!(decrypt_field(&sealed, &key, &binding).unwrap(), "123-45-6789");
+
+        // Fresh nonce per encryption
+        let again = encrypt_field("123-45-6789", &key, SensitiveFieldType::Ssn, &binding).unwrap();
+        assert_ne!(sealed.nonce, again.nonce);
+
+        assert!(decrypt_field(&sealed, &EncryptionKey::new([8u8; 32]), &binding).is_err());
+        let mut tampered = sealed.clone();
+        let mut bytes = base64_decode(&tampered.ciphertext).unwrap();
+        bytes[0] ^= 1;
+        tampered.ciphertext = base64_encode(&bytes);
+        assert!(decrypt_field(&tampered, &key, &binding).is_err());
+        let relabeled = EncryptedField { field_type: SensitiveFieldType::GeneticData, ..sealed.clone() };
+        assert!(decrypt_field(&relabeled, &key, &binding).is_err());
+
+        // A ciphertext swapped into another patient, entry type or field is rejected
+        let elsewhere = [
+            FieldBinding::new(ActionHash::from_raw_36(vec![4; 36]), "Patient", "ssn"),
+            FieldBinding::new(patient.clone(), "InsurancePolicy", "ssn"),
+            FieldBinding::new(patient.clone(), "Patient", "emergency_contact_ssn"),
+            FieldBinding::new(patient.clone(), "Patientssn", ""),
+        ];
+        for binding in &elsewhere {
+            assert!(decrypt_field(&sealed, &key, binding).is_err());
+        }
+        assert!(encrypt_field("x", &key, SensitiveFieldType::Ssn, &elsewhere[3]).is_err());
+
+        // Field-type-bound fields are only read by the migration decryptor
+        use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+        let aad = [&[FIELD_TYPE_BOUND_VERSION][..], b"Ssn"].concat();
+        let nonce = [1u8; 12];
+        let unbound = EncryptedField {
+            ciphertext: base64_encode(
+                &chacha20poly1305::ChaCha20Poly1305::new(key.as_bytes().into())
+                    .encrypt((&nonce).into(), Payload { msg: b"123-45-6789", aad: &aad })
+                    .unwrap(),
+            ),
+            nonce: base64_encode(&nonce),
+            version: FIELD_TYPE_BOUND_VERSION,
+            ..sealed.clone()
+        };
+        assert!(needs_migration(&unbound));
+        assert!(decrypt_field(&unbound, &key, &binding).is_err());
+        let read_unbound = |field: &EncryptedField, key: &EncryptionKey| {
+            let plaintext = chacha20poly1305::ChaCha20Poly1305::new(key.as_bytes().into())
+                .decrypt(base64_decode(&field.nonce).unwrap().as_slice().into(), Payload {
+                    msg: &base64_decode(&field.ciphertext).unwrap(),
+                    aad: &aad,
+                })
+                .unwrap();
+            Ok(String::from_utf8(plaintext).unwrap())
+        };
+        let bound = migrate_legacy_field(&unbound, &key, &binding, read_unbound).unwrap();
+        assert_eq!(bound.version, ENCRYPTION_VERSION);
+        assert_eq!(decrypt_field(&bound, &key, &binding).unwrap(), "123-45-6789");
+        assert!(decrypt_field(&bound, &key, &elsewhere[0]).is_err());
+
+        let legacy = EncryptedField {
+            ciphertext: base64_encode(b"legacy"),
+            nonce: base64_encode(&[0u8; 12]),
+            field_type: SensitiveFieldType::Ssn,
+            version: LEGACY_XOR_VERSION,
+            key_derivation: LEGACY_KEY_DERIVATION_VERSION,
+            master_key_id: None,
+        };
+        assert!(needs_migration(&legacy));
+        assert!(decrypt_field(&legacy, &key, &binding).is_err());
+        let migrated = migrate_legacy_field(&legacy, &key, &binding, |_, _| Ok("123-45-6789".to_string())).unwrap();
+        assert!(!needs_migration(&migrated));
+        assert_eq!(decrypt_field(&migrated, &key, &binding).unwrap(), "123-45-6789");
+        let unchanged = migrate_legacy_field(&migrated, &key, &binding, |_, _| panic!("current fields are not re-read")).unwrap();
+        assert_eq!(unchanged.ciphertext, migrated.ciphertext);
+    }
+
+    #[test]
+    fn test_stored_sealed_field() {
+        use encryption::*;
+
+        #[derive(Serialize, Debug)]
+        struct Policy {
+            patient_hash: ActionHash,
+            member_id: String,
+            group: Option<String>,
+        }
+        let key = EncryptionKey::new([7u8; 32]);
+        let patient = ActionHash::from_raw_36(vec![3; 36]);
+        let binding = FieldBinding::new(patient.clone(), "InsurancePolicy", "member_id");
+        let sealed = encrypt_field("M-1", &key, SensitiveFieldType::FinancialData, &binding).unwrap();
+        let policy = Policy { patient_hash: patient, member_id: sealed.to_stored().unwrap(), group: None };
+        let bytes = SerializedBytes::from(UnsafeBytes::from(holochain_serialized_bytes::encode(&policy).unwrap()));
+        let entry = Entry::App(AppEntryBytes::try_from(bytes).unwrap());
+
+        assert_eq!(stored_sealed_field(&entry, "member_id").unwrap().ciphertext, sealed.ciphertext);
+        // Missing, non-text and plain text fields are not sealed values
+        for field_name in ["member_number", "patient_hash", "group"] {
+            assert!(stored_sealed_field(&entry, field_name).is_err());
+        }
+        let plain = Policy { member_id: "M-1".to_string(), ..policy };
+        let bytes = SerializedBytes::from(UnsafeBytes::from(holochain_serialized_bytes::encode(&plain).unwrap()));
+        assert!(stored_sealed_field(&Entry::App(AppEntryBytes::try_from(bytes).unwrap()), "member_id").is_err());
+    }
+
+    #[test]
+    fn test_blind_index() {
+        use encryption::*;
+        let index_key = [5u8; 32];
+        let ssn_key = key_management::blind_index_subkey(&index_key, &SensitiveFieldType::Ssn);
+        let index = |key: &[u8; 32], field_type: SensitiveFieldType, value: &str| {
+            compute_blind_index(key, "KEY-1", field_type, value).unwrap()
+        };
+
+        // Formatting does not change the token, the value does
+        let dashed = index(&ssn_key, SensitiveFieldType::Ssn, "123-45-6789");
+        assert_eq!(dashed, index(&ssn_key, SensitiveFieldType::Ssn, " 123 45 6789 "));
+        assert_ne!(dashed.token, index(&ssn_key, SensitiveFieldType::Ssn, "123-45-6780").token);
+        assert_eq!(base64_decode(&dashed.token).unwrap().len(), BLIND_INDEX_BYTES);
+
+        // Field types and index keys are separated
+        let mrn = SensitiveFieldType::Other("MRN".to_string());
+        let mrn_key = key_management::blind_index_subkey(&index_key, &mrn);
+        assert_ne!(ssn_key, mrn_key);
+        assert_eq!(
+            index(&mrn_key, mrn.clone(), "mrn-00042").token,
+            index(&mrn_key, mrn.clone(), "MRN-00042").token
+        );
+        let other_key = key_management::blind_index_subkey(&[6u8; 32], &SensitiveFieldType::Ssn);
+        assert_ne!(dashed.token, index(&other_key, SensitiveFieldType::Ssn, "123-45-6789").token);
+        assert_ne!(dashed.anchor(), index(&mrn_key, mrn, "123456789").anchor());
+
+        assert!(compute_blind_index(&ssn_key, "KEY-1", SensitiveFieldType::Ssn, "--").is_err());
+    }
+
+    #[test]
+    fn test_hmac_and_key_derivation() {
+        use encryption::*;
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+
+        // RFC 4231 test case 2
+        assert_eq!(
+            hex(&hmac_sha256(b"Jefe", &[b"what do ya want ", b"for nothing?"])),
+            "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+        );
+        // RFC 5869 test case 1, first output block
+        let salt: Vec<u8> = (0u8..=0x0c).collect();
+        let info: Vec<u8> = (0xf0u8..=0xf9).collect();
+        assert_eq!(
+            hex(&hkdf_sha256(&salt, &[0x0b; 22], &info)),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf"
+        );
+        let prk = hkdf_extract(&salt, &[0x0b; 22]);
+        assert_eq!(hex(&prk), "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5");
+        let mut okm = [0u8; 42];
+        hkdf_expand(&prk, &info, &mut okm).unwrap();
+        assert_eq!(
+            hex(&okm),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+        assert!(hkdf_expand(&prk, &info, &mut vec![0u8; HKDF_MAX_OUTPUT_BYTES + 1]).is_err());
+
+        let patient = ActionHash::from_raw_36(vec![3; 36]);
+        let master = [9u8; 32];
+        let key = EncryptionKey::derive(&patient, &master, &SensitiveFieldType::Ssn);
+        assert_eq!(key.derivation(), KEY_DERIVATION_VERSION);
+        let other_field = EncryptionKey::derive(&patient, &master, &SensitiveFieldType::GeneticData);
+        assert_ne!(key.as_bytes(), other_field.as_bytes());
+
+        let binding = FieldBinding::new(patient.clone(), "Patient", "ssn");
+        let sealed = encrypt_field("123-45-6789", &key, SensitiveFieldType::Ssn, &binding).unwrap();
+        assert_eq!(sealed.key_derivation, KEY_DERIVATION_VERSION);
+        let reader = EncryptionKey::derive_for_field(&patient, &master, &sealed).unwrap();
+        assert_eq!(decrypt_field(&sealed, &reader, &binding).unwrap(), "123-45-6789");
+
+        // Fields stored before the derivation was recorded use the legacy key
+        let mut json = serde_json::to_value(&sealed).unwrap();
+        json.as_object_mut().unwrap().remove("key_derivation");
+        let older: EncryptedField = serde_json::from_value(json).unwrap();
+        assert_eq!(older.key_derivation, LEGACY_KEY_DERIVATION_VERSION);
+        let legacy_key = EncryptionKey::derive_for_field(&patient, &master, &older).unwrap();
+        assert_eq!(legacy_key.derivation(), LEGACY_KEY_DERIVATION_VERSION);
+        assert_ne!(legacy_key.as_bytes(), key.as_bytes());
+
+        use key_management::*;
+        let field_label = SubkeyLabel::Field { patient_hash: patient.clone(), field_type: SensitiveFieldType::Ssn };
+        assert_eq!(&derive_subkey(&master, &field_label), key.as_bytes());
+        let purpose = |zome: &str, purpose: &str, patient_hash: Option<ActionHash>| SubkeyLabel::Purpose {
+            zome: zome.to_string(),
+            purpose: purpose.to_string(),
+            patient_hash,
+        };
+        // Length prefixes keep adjacent components from running together
+        assert_ne!(purpose("ab", "c", None).info(), purpose("a", "bc", None).info());
+        assert_ne!(
+            derive_subkey(&master, &purpose("consent", "export", None)),
+            derive_subkey(&master, &purpose("consent", "export", Some(patient.clone())))
+        );
+        assert_eq!(purpose("consent", "export", None).describe(), "purpose/consent/export");
+        assert!(field_label.describe().ends_with("/Ssn"));
+
+        let fingerprint = key_fingerprint(&master, KEY_FINGERPRINT_VERSION).unwrap();
+        assert_eq!(fingerprint.len(), 64);
+        assert_ne!(fingerprint, hex(&sha256_hash(&master)[..8]));
+        assert_eq!(key_fingerprint(&master, LEGACY_KEY_FINGERPRINT_VERSION).unwrap(), hex(&sha256_hash(&master)[..8]));
+        let metadata = KeyMetadata {
+            key_id: "KEY-1".to_string(),
+            created_at: Timestamp::from_micros(0),
+            expires_at: None,
+            is_active: true,
+            version: 1,
+            key_hash: hex(&sha256_hash(&master)[..8]),
+            fingerprint_version: LEGACY_KEY_FINGERPRINT_VERSION,
+            passphrase_kdf: None,
+        };
+        assert!(key_matches_metadata(&master, &metadata).unwrap());
+        assert!(!key_matches_metadata(&[1u8; 32], &metadata).unwrap());
+    }
+
+    #[test]
+    fn test_passphrase_key_wrapping() {
+        use encryption::*;
+        use key_management::*;
+        let hex = |bytes: &[u8]| bytes.iter().map(|b| format!("{:02x}", b)).collect::<String>();
+
+        // RFC 7914 section 12 vectors
+        let mut out = [0u8; 64];
+        scrypt(b"", b"", &ScryptCost { log_n: 4, r: 1, p: 1 }, &mut out).unwrap();
+        assert_eq!(
+            hex(&out),
+            "77d6576238657b203b19ca42c18a0497f16b4844e3074ae8dfdffa3fede21442\
+             fcd0069ded0948f8326a753a0fc81f17e8d3e0fb2e0d3628cf35e20c38d18906"
+        );
+        scrypt(b"password", b"NaCl", &ScryptCost { log_n: 10, r: 8, p: 16 }, &mut out).unwrap();
+        assert_eq!(
+            hex(&out),
+            "fdbabe1c9d3472007856e7190d01e9fe7c6ad7cbc8237830e77376634b373162\
+             2eaf30d92e22a3886ff109279d9830dac727afb94a83ee6d8360cbdfa2cc0640"
+        );
+        assert!(scrypt(b"", b"", &ScryptCost { log_n: 0, r: 1, p: 1 }, &mut out).is_err());
+
+        let key = [5u8; 32];
+        let metadata = KeyMetadata {
+            key_id: "KEY-1".to_string(),
+            created_at: Timestamp::from_micros(0),
+            expires_at: None,
+            is_active: true,
+            version: 1,
+            key_hash: key_fingerprint(&key, KEY_FINGERPRINT_VERSION).unwrap(),
+            fingerprint_version: KEY_FINGERPRINT_VERSION,
+            passphrase_kdf: None,
+        };
+        let passphrase = "correct horse battery staple";
+        let wrapped = wrap_key_with_passphrase(&key, metadata.clone(), passphrase, MIN_PASSPHRASE_COST).unwrap();
+        assert_eq!(wrapped.metadata.passphrase_kdf.as_ref().unwrap().cost, MIN_PASSPHRASE_COST);
+        assert_eq!(unwrap_key_with_passphrase(&wrapped, passphrase).unwrap(), key);
+        assert!(unwrap_key_with_passphrase(&wrapped, "incorrect horse battery").is_err());
+
+        // Cost and identity are bound to the wrap
+        let mut weakened = wrapped.clone();
+        weakened.metadata.passphrase_kdf.as_mut().unwrap().cost.log_n = 10;
+        assert!(unwrap_key_with_passphrase(&weakened, passphrase).is_err());
+        let mut renamed = wrapped.clone();
+        renamed.metadata.key_id = "KEY-2".to_string();
+        assert!(unwrap_key_with_passphrase(&renamed, passphrase).is_err());
+        let mut greedy = wrapped.clone();
+        greedy.metadata.passphrase_kdf.as_mut().unwrap().cost.log_n = 30;
+        assert!(unwrap_key_with_passphrase(&greedy, passphrase).is_err());
+
+        assert!(wrap_key_with_passphrase(&key, metadata.clone(), "short", MIN_PASSPHRASE_COST).is_err());
+        let cheap = ScryptCost { log_n: 10, ..MIN_PASSPHRASE_COST };
+        assert!(wrap_key_with_passphrase(&key, metadata.clone(), passphrase, cheap).is_err());
+        assert!(wrap_key_with_passphrase(&[6u8; 32], metadata, passphrase, MIN_PASSPHRASE_COST).is_err());
+    }
+
+    #[test]
+    fn test_key_ring_lazy_reseal() {
+        use encryption::*;
+        use key_management::*;
+
+        let master = |id: &str, byte: u8| MasterKey {
+            metadata: KeyMetadata {
+                key_id: id.to_string(),
+                created_at: Timestamp::from_micros(0),
+                expires_at: None,
+                is_active: true,
+                version: 1,
+                key_hash: key_fingerprint(&[byte; 32], KEY_FINGERPRINT_VERSION).unwrap(),
+                fingerprint_version: KEY_FINGERPRINT_VERSION,
+                passphrase_kdf: None,
+            },
+            key: [byte; 32],
+        };
+        let patient = encryption::FieldBinding::new(ActionHash::from_raw_36(vec![4; 36]), "Patient", "ssn");
+        let old_ring = MasterKeyRing { active: master("KEY-A", 1), retained: vec![] };
+        let sealed = seal_field("123-45-6789", &patient, SensitiveFieldType::Ssn, &old_ring).unwrap();
+        assert_eq!(sealed.master_key_id.as_deref(), Some("KEY-A"));
+        let current = open_field(&sealed, &patient, &old_ring).unwrap();
+        assert_eq!(current.plaintext, "123-45-6789");
+        assert!(current.resealed.is_none());
+
+        // After rotation the old key is retained and reads reseal under the new one
+        let rotated = MasterKeyRing { active: master("KEY-B", 2), retained: vec![master("KEY-A", 1)] };
+        let opened = open_field(&sealed, &patient, &rotated).unwrap();
+        assert_eq!(opened.plaintext, "123-45-6789");
+        let resealed = opened.resealed.unwrap();
+        assert_eq!(resealed.master_key_id.as_deref(), Some("KEY-B"));
+        assert!(open_field(&resealed, &patient, &rotated).unwrap().resealed.is_none());
+
+        // Once the old key is dropped, unmigrated fields cannot be read
+        let pruned = MasterKeyRing { active: master("KEY-B", 2), retained: vec![] };
+        assert!(open_field(&sealed, &patient, &pruned).is_err());
+        assert_eq!(open_field(&resealed, &patient, &pruned).unwrap().plaintext, "123-45-6789");
+
+        // Fields without a key id are found by trial and resealed
+        let unlabelled = EncryptedField { master_key_id: None, ..sealed };
+        let opened = open_field(&unlabelled, &patient, &rotated).unwrap();
+        assert_eq!(opened.resealed.unwrap().master_key_id.as_deref(), Some("KEY-B"));
+    }
+
+    #[test]
+    fn test_secret_sharing() {
+        use key_management::escrow::*;
+
+        let secret: Vec<u8> = (0u8..32).collect();
+        let mut counter = 0u8;
+        let shares = split_secret_with(&secret, 3, 5, |bytes| {
+            for b in bytes.iter_mut() {
+                counter = counter.wrapping_mul(31).wrapping_add(7);
+                *b = counter;
+            }
+        })
+        .unwrap();
+        assert_eq!(shares.len(), 5);
+        assert!(shares.iter().all(|share| share.value.len() == 32 && share.value != secret));
+
+        // Any three shares rebuild the secret
+        for picked in [[0, 1, 2], [0, 2, 4], [4, 3, 1]] {
+            let subset: Vec<SecretShare> = picked.iter().map(|&i| shares[i].clone()).collect();
+            assert_eq!(combine_shares(&subset, 3).unwrap(), secret);
+        }
+        // Two shares, or a repeated share, are not enough
+        assert!(combine_shares(&shares[..2], 3).is_err());
+        let repeated = vec![shares[0].clone(), shares[0].clone(), shares[1].clone()];
+        assert!(combine_shares(&repeated, 3).is_err());
+        // A wrong share yields a different secret rather than the real one
+        let mut corrupted = shares[..3].to_vec();
+        corrupted[1].value[0] ^= 1;
+        assert_ne!(combine_shares(&corrupted, 3).unwrap(), secret);
+
+        assert!(split_secret_with(&secret, 1, 3, |_| {}).is_err());
+        assert!(split_secret_with(&secret, 4, 3, |_| {}).is_err());
+        let random = split_secret(&secret, 2, 3).unwrap();
+        assert_eq!(combine_shares(&random[1..], 2).unwrap(), secret);
+    }
+
+    #[test]
+    fn test_budgeted_page() {
+        let source: Vec<String> = (0..10).map(|i| format!("{:0>100}", i)).collect();
+        let item_size = estimated_size(&source[0]).unwrap();
+        let fetch = |item: String| -> ExternResult<Option<String>> { Ok((item != source[5]).then_some(item)) };
+
+        let first = budgeted_page(
+            source.clone(),
+            &BudgetInput { continuation: None, max_bytes: Some(item_size * 3) },
+            fetch,
+        )
+        .unwrap();
+        assert_eq!(first.items, source[..3].to_vec());
+        assert!(first.truncated);
+        assert_eq!(first.continuation, Some(continuation_token(3)));
+
+        // Skipped elements don't count against the budget
+        let second = budgeted_page(
+            source.clone(),
+            &BudgetInput { continuation: first.continuation, max_bytes: Some(item_size * 3) },
+            fetch,
+        )
+        .unwrap();
+        assert_eq!(second.items, vec![source[3].clone(), source[4].clone(), source[6].clone()]);
+        assert_eq!(second.continuation, Some(continuation_token(7)));
+
+        let rest = budgeted_page(
+            source.clone(),
+            &BudgetInput { continuation: second.continuation, max_bytes: None },
+            fetch,
+        )
+        .unwrap();
+        assert_eq!(rest.items, source[7..].to_vec());
+        assert!(!rest.truncated && rest.continuation.is_none());
+
+        // An item larger than the budget still comes back on its own
+        let oversized = budgeted_page(source.clone(), &BudgetInput { continuation: None, max_bytes: Some(1) }, fetch).unwrap();
+        assert_eq!(oversized.items.len(), 1);
+        assert_eq!(oversized.continuation, Some(continuation_token(1)));
+
+        assert!(continuation_position(Some("12")).is_err());
+        assert!(ResponseBudget::new(Some(MAX_RESPONSE_BUDGET_BYTES + 1)).is_err());
+    }
+
+    #[test]
+    fn test_aggregate_timings() {
+        let timing = |zome: &str, function: &str, calls, errors, total_micros, max_micros, dht_gets| ExternTimings {
+            zome: zome.to_string(),
+            function: function.to_string(),
+            calls,
+            errors,
+            total_micros,
+            max_micros,
+            dht_gets,
+            link_queries: calls,
+        };
+        let samples = vec![
+            timing("records", "get_patient_lab_results", 3, 1, 30_000, 20_000, 12),
+            timing("consent", "check_authorization", 10, 0, 20_000, 4_000, 10),
+            timing("records", "get_patient_lab_results", 1, 0, 10_000, 10_000, 4),
+        ];
+        let metrics = aggregate_timings(&samples);
+        assert_eq!(metrics.len(), 2);
+
+        // Slowest overall first
+        let labs = &metrics[0];
+        assert_eq!((labs.zome.as_str(), labs.function.as_str()), ("records", "get_patient_lab_results"));
+        assert_eq!(labs.calls, 4);
+        assert_eq!(labs.errors, 1);
+        assert!((labs.error_rate - 0.25).abs() < 1e-9);
+        assert!((labs.mean_ms - 10.0).abs() < 1e-9);
+        assert!((labs.max_ms - 20.0).abs() < 1e-9);
+        assert!((labs.gets_per_call - 4.0).abs() < 1e-9);
+        assert!((labs.link_queries_per_call - 1.0).abs() < 1e-9);
+        assert!((metrics[1].mean_ms - 2.0).abs() < 1e-9);
+
+        assert!(aggregate_timings(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_infer_onset() {
+        const DAY: i64 = 86_400_000_000;
+        let candidate = |day: i64, basis| OnsetCandidate {
+            at: Timestamp::from_micros(day * DAY),
+            basis,
+            derived_from: format!("ref-{}", day),
+        };
+        assert!(infer_onset(&[]).is_none());
+
+        let lone = infer_onset(&[candidate(100, OnsetBasis::MonitoringResult)]).unwrap();
+        assert_eq!(lone.confidence, OnsetConfidence::Low);
+
+        // Earliest wins; a diagnostic result two weeks later corroborates it
+        let onset = infer_onset(&[
+            candidate(114, OnsetBasis::DiagnosticResult),
+            candidate(100, OnsetBasis::Encounter),
+            candidate(300, OnsetBasis::MonitoringResult),
+        ])
+        .unwrap();
+        assert_eq!(onset.at, Timestamp::from_micros(100 * DAY));
+        assert_eq!(onset.basis, OnsetBasis::Encounter);
+        assert_eq!(onset.derived_from, "ref-100");
+        assert_eq!(onset.confidence, OnsetConfidence::High);
+
+        // Monitoring results do not corroborate, nor do distant candidates
+        let onset = infer_onset(&[
+            candidate(100, OnsetBasis::DiagnosticResult),
+            candidate(105, OnsetBasis::MonitoringResult),
+            candidate(200, OnsetBasis::Encounter),
+        ])
+        .unwrap();
+        assert_eq!(onset.confidence, OnsetConfidence::Moderate);
+
+        let asserted = Timestamp::from_micros(50 * DAY);
+        let normalized = normalized_onset(Some(asserted), Some(&onset));
+        assert_eq!((normalized.at, normalized.inferred), (Some(asserted), false));
+        let normalized = normalized_onset(None, Some(&onset));
+        assert_eq!(normalized.at, Some(onset.at));
+        assert_eq!(normalized.confidence, Some(OnsetConfidence::Moderate));
+        assert_eq!(normalized_onset(None, None).at, None);
+    }
+
+    #[test]
+    fn test_sensitivity_matrix() {
+        let builtin = SensitivityMatrix::default();
+        assert!(builtin.requires_encryption(&DataCategory::GeneticData));
+        assert!(!builtin.requires_encryption(&DataCategory::LabResults));
+        assert!(builtin.allows_break_glass(&DataCategory::MentalHealth));
+        assert!(builtin.requires_encryption(&DataCategory::All));
+
+        let mut mental_health = CategorySensitivity::default_for(&DataCategory::MentalHealth);
+        mental_health.break_glass_allowed = false;
+        mental_health.research_allowed = false;
+        let mut labs = CategorySensitivity::default_for(&DataCategory::LabResults);
+        labs.notify_instantly = true;
+        let matrix = SensitivityMatrix { categories: vec![mental_health.clone(), labs] };
+        assert!(matrix.check().is_ok());
+        assert!(!matrix.allows_break_glass(&DataCategory::MentalHealth));
+        assert!(!matrix.allows_research(&DataCategory::MentalHealth));
+        assert!(matrix.notifies_instantly(&DataCategory::LabResults));
+        // Categories without a row keep the built-in handling
+        assert!(matrix.allows_break_glass(&DataCategory::Diagnoses));
+        // All is as strict as its strictest category
+        assert!(!matrix.allows_break_glass(&DataCategory::All));
+        assert!(matrix.notifies_instantly(&DataCategory::All));
+        assert_eq!(matrix.resolved().len(), SENSITIVITY_CATEGORIES.len());
+
+        let repeated = SensitivityMatrix { categories: vec![mental_health.clone(), mental_health] };
+        assert!(repeated.check().is_err());
+        let all_row = SensitivityMatrix { categories: vec![CategorySensitivity::default_for(&DataCategory::All)] };
+        assert!(all_row.check().is_err());
+    }
+
+    #[test]
+    fn test_supply_coverage() {
+        let day = |n: i64| Timestamp::from_micros(n * 86_400_000_000);
+        let period = AdherencePeriod { start: day(0), end: day(90) };
+        let fill = |n: i64, supply: u32| Dispense { dispensed_at: day(n), days_supply: supply };
+
+        // Three on-time 30-day fills cover the whole quarter
+        let on_time = supply_coverage(&[fill(60, 30), fill(0, 30), fill(30, 30)], &period);
+        assert_eq!(on_time.days_covered, 90);
+        assert_eq!(on_time.pdc, 1.0);
+        assert_eq!(on_time.refill_gaps, 0);
+
+        // An early refill carries forward instead of double counting
+        let early = supply_coverage(&[fill(0, 30), fill(20, 30)], &period);
+        assert_eq!(early.days_covered, 60);
+        assert_eq!(early.refill_gaps, 1);
+        assert_eq!(early.longest_gap_days, 30);
+
+        // A late refill leaves a gap; MPR ignores the earlier period's fill
+        let late = supply_coverage(&[fill(-10, 30), fill(40, 30)], &period);
+        assert_eq!(late.days_covered, 50);
+        assert_eq!(late.refill_gaps, 2);
+        assert_eq!(late.longest_gap_days, 20);
+        assert!((late.mpr - 30.0 / 90.0).abs() < 1e-9);
+        assert!(late.pdc < ADHERENCE_THRESHOLD);
+
+        assert_eq!(supply_coverage(&[], &AdherencePeriod { start: day(5), end: day(5) }), SupplyCoverage::default());
+    }
+
+    #[test]
+    fn test_counter_shards() {
+        let mut alice = CounterShard::default();
+        alice.apply(3);
+        alice.apply(-1);
+        assert_eq!(alice.value(), 2);
+        assert_eq!(CounterShard::from_tag(&alice.to_tag()), Some(alice));
+        assert_eq!(CounterShard::from_tag(&LinkTag::new(vec![1, 2, 3])), None);
+
+        // A stale copy of alice's shard must not be double counted
+        let mut stale = CounterShard::default();
+        stale.apply(3);
+        let mut bob = CounterShard::default();
+        bob.apply(5);
+        let total = sum_counter_shards(vec![("alice", alice), ("bob", bob), ("alice", stale)]);
+        assert_eq!(total, 7);
+
+        // Merge order does not matter
+        assert_eq!(alice.merge(stale), stale.merge(alice));
+    }
+
+    #[test]
+    fn test_link_metadata_tags() {
+        #[derive(Debug, PartialEq)]
+        enum Status {
+            Active,
+            Revoked,
+        }
+        impl TagStatus for Status {
+            fn tag_code(&self) -> u8 {
+                match self {
+                    Status::Active => 0,
+                    Status::Revoked => 1,
+                }
+            }
+            fn from_tag_code(code: u8) -> Option<Self> {
+                match code {
+                    0 => Some(Status::Active),
+                    1 => Some(Status::Revoked),
+                    _ => None,
+                }
+            }
+        }
+
+        let at = |micros: i64| Timestamp::from_micros(micros);
+        let open_ended = LinkMetadata::new(&Status::Active, at(1_000), None);
+        let decoded = LinkMetadata::from_tag(&open_ended.to_tag()).unwrap();
+        assert_eq!(decoded, open_ended);
+        assert_eq!(decoded.status::<Status>(), Some(Status::Active));
+        assert!(!decoded.is_expired(at(i64::MAX)));
+
+        let expiring = LinkMetadata::new(&Status::Revoked, at(1_000), Some(at(5_000)));
+        let decoded = LinkMetadata::from_tag(&expiring.to_tag()).unwrap();
+        assert!(decoded.has_status(&Status::Revoked));
+        assert_eq!(decoded.expires_at, Some(at(5_000)));
+        assert!(!decoded.is_expired(at(4_999)));
+        assert!(decoded.is_expired(at(5_000)));
+
+        // Untagged links and other codecs' tags carry no metadata
+        assert_eq!(LinkMetadata::from_tag(&LinkTag::new(vec![])), None);
+        assert_eq!(LinkMetadata::from_tag(&CounterShard::default().to_tag()), None);
+        let mut truncated = expiring.to_tag().0;
+        truncated.pop();
+        assert_eq!(LinkMetadata::from_tag(&LinkTag::new(truncated)), None);
+    }
+
+    #[test]
+    fn test_reference_tags() {
+        let reference = ReferenceTag {
+            link_type: "PatientToFhirMappings".to_string(),
+            forward_link: ActionHash::from_raw_36(vec![7; 36]),
+        };
+        assert_eq!(ReferenceTag::from_tag(&reference.to_tag()), Some(reference));
+        assert_eq!(ReferenceTag::from_tag(&CounterShard::default().to_tag()), None);
+        assert_eq!(ReferenceTag::from_tag(&LinkTag::new(b"ref1{".to_vec())), None);
+    }
+
+    #[test]
+    fn test_ingest_statistics_windows() {
+        let day = |ingests, with_errors, bytes| IngestTotals {
+            ingests,
+            ingests_with_errors: with_errors,
+            resources_processed: ingests * 10,
+            parse_errors: with_errors,
+            stored_bytes: bytes,
+        };
+        let mut daily = vec![day(4, 1, 300), day(2, 1, 300)];
+        daily.resize(40, day(1, 0, 30));
+
+        let windows = ingest_windows(&daily);
+        assert_eq!(windows[0].window, StatisticsWindow::LastDay);
+        assert_eq!(windows[0].totals.ingests, 4);
+        assert_eq!(windows[0].error_rate, 0.25);
+        assert_eq!(windows[1].totals.ingests, 11);
+        assert_eq!(windows[2].totals.ingests, 34);
+        assert_eq!(windows[2].totals.ingests_with_errors, 2);
+
+        let growth = StorageGrowthEstimate::from_daily(&daily);
+        assert_eq!(growth.bytes_last_30_days, 1440);
+        assert_eq!(growth.average_daily_bytes, 48);
+        assert_eq!(growth.projected_bytes_next_30_days, 1440);
+
+        assert_eq!(stats_day(Timestamp::from_micros(86_400_000_000 * 3 + 5)), 3);
+        assert_eq!(stats_day(Timestamp::from_micros(-1)), -1);
+    }
+
+    #[test]
+    fn test_storage_report() {
+        let anchor = |live, deleted, recent| AnchorLinkSample {
+            anchor: "active_consents".to_string(),
+            live_links: live,
+            deleted_links: deleted,
+            recent_links: recent,
+        };
+        assert_eq!(anchor(0, 0, 0).garbage_ratio(), 0.0);
+        assert_eq!(anchor(3, 1, 0).garbage_ratio(), 0.25);
+
+        let entries = vec![EntryTypeFootprint {
+            entry_type: "Consent".to_string(),
+            entries: 7,
+            bytes: 7_000,
+            average_bytes: 1_000,
+        }];
+        let report = StorageReport::new("consent", Timestamp::from_micros(0), 7, vec![anchor(6, 2, 7), anchor(2, 0, 0)], entries);
+        assert_eq!(report.live_links, 8);
+        assert_eq!(report.deleted_links, 2);
+        assert_eq!(report.garbage_ratio, 0.2);
+        assert_eq!(report.link_bytes, 12 * LINK_ACTION_BYTES);
+        assert_eq!(report.entry_bytes_per_day, 1_000);
+        assert_eq!(report.link_bytes_per_day, LINK_ACTION_BYTES);
+        assert_eq!(report.projected_bytes_next_30_days, (1_000 + LINK_ACTION_BYTES) * 30);
+
+        // An empty window does not divide by zero
+        let empty = StorageReport::new("consent", Timestamp::from_micros(0), 0, Vec::new(), Vec::new());
+        assert_eq!(empty.projected_bytes_next_30_days, 0);
+    }
+
+    #[test]
+    fn test_publication_status() {
+        let set = |complete, status| ValidationReceiptSet {
+            op_hash: DhtOpHash::from_raw_36(vec![0; 36]),
+            op_type: "StoreRecord".to_string(),
+            receipts_complete: complete,
+            receipts: vec![ValidationReceiptInfo {
+                validation_status: status,
+                validators: Vec::new(),
+            }],
+        };
+
+        assert_eq!(publication_status(&[]), PublicationStatus::Provisional);
+        assert_eq!(
+            publication_status(&[set(true, ValidationStatus::Valid), set(false, ValidationStatus::Valid)]),
+            PublicationStatus::Provisional
+        );
+        assert_eq!(
+            publication_status(&[set(true, ValidationStatus::Valid), set(true, ValidationStatus::Valid)]),
+            PublicationStatus::Published
+        );
+        assert_eq!(
+            publication_status(&[set(true, ValidationStatus::Valid), set(false, ValidationStatus::Rejected)]),
+            PublicationStatus::Rejected
+        );
+    }
+
+    #[test]
+    fn test_call_class_policies() {
+        let auth = CallClass::Authorization.policy();
+        assert_eq!(auth.attempts, vec![GetStrategy::Local, GetStrategy::Network]);
+        assert!(auth.retry_on_miss);
+
+        let consent = CallClass::ConsentStatus.policy();
+        assert!(consent.attempts.iter().all(|s| *s == GetStrategy::Network));
+        assert!(!consent.retry_on_miss);
+
+        assert_eq!(CallClass::default(), CallClass::Bulk);
+        assert_eq!(BatchGetOptions::default().class, CallClass::Bulk);
+    }
+
+    #[test]
+    fn test_chain_backup_sealing() {
+        // RFC 7914 section 11 PBKDF2-HMAC-SHA256 vector, first block
+        let key = derive_backup_key("passwd", b"salt", 1);
+        let hex: String = key.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(hex, "55ac046e56e3089fec1691c22544b605f94185216dde0465e68b9d57c20dacbc");
+
+        let original = ActionHash::from_raw_36(vec![1; 36]);
+        let payload = ChainBackupPayload {
+            backup_id: "BACKUP-1".to_string(),
+            agent: AgentPubKey::from_raw_36(vec![2; 36]),
+            created_at: Timestamp::from_micros(1_700_000_000_000_000),
+            entries: vec![BackedUpEntry {
+                original_action_hash: original.clone(),
+                original_timestamp: Timestamp::from_micros(1_700_000_000_000_000),
+                entry_type: AppEntryDef::new(0.into(), 0.into(), EntryVisibility::Public),
+                revision_of: None,
+                entry_bytes: vec![7; 4096],
+            }],
+            links: vec![],
+        };
+        let passphrase = "correct horse battery";
+        let chunks = seal_chain_backup(&payload, passphrase, &[3; 16], &[4; 12], 10).unwrap();
+        assert_eq!(chunks[0].version, BACKUP_FORMAT_VERSION);
+        assert_eq!(open_chain_backup(&chunks, passphrase).unwrap(), payload);
+
+        assert!(seal_chain_backup(&payload, "short", &[3; 16], &[4; 12], 10).is_err());
+        assert!(seal_chain_backup(&payload, passphrase, &[3; 16], &[4; 24], 10).is_err());
+        assert!(open_chain_backup(&chunks, "wrong horse battery").is_err());
+
+        let mut tampered = chunks.clone();
+        tampered[0].data[0] ^= 1;
+        assert!(open_chain_backup(&tampered, passphrase).is_err());
+
+        let mut relabelled = chunks.clone();
+        relabelled[0].backup_id = "BACKUP-2".to_string();
+        assert!(open_chain_backup(&relabelled, passphrase).is_err());
+        assert!(open_chain_backup(&[], passphrase).is_err());
+    }
+
+    #[test]
+    fn test_remap_raw_hashes() {
+        #[derive(Serialize, Deserialize, Debug, PartialEq)]
+        struct Referencing {
+            patient_hash: ActionHash,
+            other: ActionHash,
+            note: String,
+        }
+
+        let old = ActionHash::from_raw_36(vec![1; 36]);
+        let new = ActionHash::from_raw_36(vec![9; 36]);
+        let untouched = ActionHash::from_raw_36(vec![5; 36]);
+        let mut bytes = ExternIO::encode(&Referencing {
+            patient_hash: old.clone(),
+            other: untouched.clone(),
+            note: "unchanged".to_string(),
+        })
+        .unwrap()
+        .0;
+
+        let mut replacements = std::collections::HashMap::new();
+        replacements.insert(old.get_raw_39().to_vec(), new.get_raw_39().to_vec());
+        assert_eq!(remap_raw_hashes(&mut bytes, &replacements), 1);
+
+        let remapped: Referencing = ExternIO(bytes).decode().unwrap();
+        assert_eq!(remapped.patient_hash, new);
+        assert_eq!(remapped.other, untouched);
+        assert_eq!(remapped.note, "unchanged");
+    }
+
+    #[test]
+    fn test_saga_compensation() {
+        let started = Timestamp::from_micros(1_000);
+        let entry = |n: u8| ActionHash::from_raw_36(vec![n; 36]);
+        let mut undone = Vec::new();
+
+        let mut saga = Saga::new("ingest:Observation", &["create_mapping", "create_anchor", "link_anchor"], started);
+        let mapping: Result<ActionHash, String> = saga.step_with(
+            "create_mapping",
+            |undo| {
+                undo.push(SagaCompensation::DeleteEntry(entry(1)));
+                Ok(entry(1))
+            },
+            |c| {
+                undone.push(c.clone());
+                Ok(())
+            },
+        );
+        assert_eq!(mapping.unwrap(), entry(1));
+        assert!(undone.is_empty());
+
+        let failed: Result<(), String> = saga.step_with(
+            "create_anchor",
+            |undo| {
+                undo.push(SagaCompensation::DeleteEntry(entry(2)));
+                Err("link failed".to_string())
+            },
+            |c| {
+                undone.push(c.clone());
+                Ok(())
+            },
+        );
+        assert_eq!(failed.unwrap_err(), "link failed");
+        assert_eq!(
+            undone,
+            vec![SagaCompensation::DeleteEntry(entry(2)), SagaCompensation::DeleteEntry(entry(1))]
+        );
+        assert!(saga.failed());
+
+        let log = saga.finish(Timestamp::from_micros(2_000));
+        assert_eq!(log.status, SagaStatus::Compensated);
+        let statuses: Vec<_> = log.steps.iter().map(|s| s.status).collect();
+        assert_eq!(
+            statuses,
+            vec![SagaStepStatus::Compensated, SagaStepStatus::Failed, SagaStepStatus::Planned]
+        );
+        assert!(log.validate().is_ok());
+
+        let mut stuck = Saga::new("care_team", &["create", "link"], started);
+        let _: Result<(), String> = stuck.step_with("create", |undo| {
+            undo.push(SagaCompensation::DeleteEntry(entry(3)));
+            Ok(())
+        }, |_| Ok(()));
+        let _: Result<(), String> = stuck.step_with("link", |_| Err("boom".to_string()), |_| {
+            Err(wasm_error!(WasmErrorInner::Guest("gone".to_string())))
+        });
+        let log = stuck.finish(Timestamp::from_micros(2_000));
+        assert_eq!(log.status, SagaStatus::CompensationFailed);
+        assert!(log.validate().is_ok());
+
+        let completed = Saga::new("care_team", &["create"], started).finish(Timestamp::from_micros(500));
+        assert!(completed.validate().is_err());
+    }
+
+    #[test]
+    fn test_resilient_retry() {
+        let policy = RetryPolicy { max_attempts: 4, base_delay_ms: 20, max_delay_ms: 60, wait_budget_ms: 100 };
+        assert_eq!(policy.backoff_ms(0, 0), 10);
+        assert_eq!(policy.backoff_ms(0, u8::MAX), 20);
+        assert_eq!(policy.backoff_ms(5, u8::MAX), 60);
+        assert_eq!(policy.backoff_ms(u32::MAX, u8::MAX), 60);
+
+        // Transient failures are retried until one attempt succeeds
+        let mut attempts = 0;
+        let mut waits = Vec::new();
+        let result = retry_with(
+            policy,
+            || {
+                attempts += 1;
+                if attempts < 3 { Err(CallFailure::Network("unreachable".to_string())) } else { Ok(attempts) }
+            },
+            || u8::MAX,
+            |ms| waits.push(ms),
+        );
+        assert_eq!(result, Ok(3));
+        assert_eq!(waits, vec![20, 40]);
+
+        // The wait budget cuts retries short
+        let mut waits = Vec::new();
+        let result: Result<(), _> = retry_with(
+            policy,
+            || Err(CallFailure::Timeout("slow".to_string())),
+            || u8::MAX,
+            |ms| waits.push(ms),
+        );
+        assert_eq!(result, Err(CallFailure::Timeout("slow".to_string())));
+        assert_eq!(waits, vec![20, 40, 40]);
+
+        // Errors from the callee are returned without retrying
+        let mut attempts = 0;
+        let result: Result<(), _> = retry_with(
+            policy,
+            || {
+                attempts += 1;
+                Err(CallFailure::Rejected("invalid input".to_string()))
+            },
+            || 0,
+            |_| panic!("must not wait"),
+        );
+        assert!(result.is_err());
+        assert_eq!(attempts, 1);
+
+        let timeout = wasm_error!(WasmErrorInner::Host("call timed out".to_string()));
+        assert_eq!(CallFailure::from_wasm_error(&timeout), CallFailure::Timeout(timeout.to_string()));
+        assert!(!CallFailure::Unauthorized.is_transient());
+    }
+
+    #[test]
+    fn test_rate_limit_status() {
+        let second = |s: i64| Timestamp::from_micros(s * 1_000_000);
+        assert!(ThrottleTier::Patient.limits().requests < ThrottleTier::Provider.limits().requests);
+        assert!(ThrottleTier::Provider.limits().requests < ThrottleTier::Integration.limits().requests);
+
+        // Accesses older than the window no longer count
+        let times = [second(10), second(100), second(50)];
+        let status = RateLimitStatus::from_request_times(ThrottleTier::Patient, &times, second(105));
+        assert_eq!(status.used, 2);
+        assert_eq!(status.remaining, 118);
+        assert_eq!(status.resets_at, Some(second(110)));
+        assert!(!status.exceeded());
+
+        let empty = RateLimitStatus::from_request_times(ThrottleTier::Provider, &[], second(105));
+        assert_eq!((empty.used, empty.resets_at), (0, None));
+
+        let busy: Vec<Timestamp> = (0..120).map(|i| Timestamp::from_micros(100_000_000 + i)).collect();
+        let status = RateLimitStatus::from_request_times(ThrottleTier::Patient, &busy, second(101));
+        assert!(status.exceeded());
+        assert_eq!(status.remaining, 0);
+    }
+
+    #[test]
+    fn test_operation_rate_status() {
+        let second = |s: i64| Timestamp::from_micros(s * 1_000_000);
+        let limits = SensitiveOperation::EmergencyOverride.limits();
+
+        // Overrides older than the window no longer count
+        let times: Vec<Timestamp> = (0..limits.requests as i64).map(|i| second(1_000 + i)).collect();
+        let status = OperationRateStatus::from_times(SensitiveOperation::EmergencyOverride, &times, second(1_100));
+        assert!(status.exceeded());
+        assert_eq!(status.resets_at, Some(second(1_000 + limits.window_seconds as i64)));
+        let later = second(1_000 + limits.window_seconds as i64 + 4);
+        let status = OperationRateStatus::from_times(SensitiveOperation::EmergencyOverride, &times, later);
+        assert_eq!(status.used, limits.requests - 5);
+        assert!(!status.exceeded());
+
+        let error = rate_limited("Rate limited", Some(second(42)));
+        let payload = types::HealthErrorPayload::from_wasm_error(&error).unwrap();
+        assert_eq!(payload.code, types::HealthErrorCode::LimitExceeded);
+        assert_eq!(payload.details["retry_after"], "42000000");
+        let payload = types::HealthErrorPayload::from_wasm_error(&rate_limited("Rate limited", None)).unwrap();
+        assert!(!payload.details.contains_key("retry_after"));
+    }
+
+    #[test]
+    fn test_paced_batch_write() {
+        use std::cell::Cell;
+
+        let pacing = BatchWritePacing { max_items_per_call: 3, commit_spacing_ms: 50 };
+        assert!(pacing.validate().is_ok());
+        assert!(BatchWritePacing { max_items_per_call: 0, commit_spacing_ms: 0 }.validate().is_err());
+        assert!(BatchWritePacing { max_items_per_call: 1_000, commit_spacing_ms: 50 }.validate().is_err());
+
+        // Each commit takes 10ms of fake clock time; waits advance the clock too
+        let now = Cell::new(1_000_000i64);
+        let items = vec![1, 2, 3, 4, 5];
+        let progress = BatchWriteProgress::new("roster", items.len() as u32, Timestamp::from_micros(now.get()));
+        let write = |item: &i32| {
+            now.set(now.get() + 10_000);
+            if *item == 2 {
+                Err(wasm_error!(WasmErrorInner::Guest("duplicate".to_string())))
+            } else {
+                Ok(item * 10)
+            }
+        };
+
+        let mut waits = Vec::new();
+        let first = paced_write_with(&items, progress, pacing, write, || now.get(), |ms| {
+            waits.push(ms);
+            now.set(now.get() + ms as i64 * 1_000);
+        });
+        assert_eq!(waits, vec![40, 40]);
+        assert_eq!(first.succeeded, vec![(0, 10), (2, 30)]);
+        assert_eq!(first.failed.len(), 1);
+        assert_eq!(first.failed[0].0, 1);
+        assert_eq!(first.metrics.attempted, 3);
+        assert_eq!(first.metrics.committed, 2);
+        assert_eq!(first.metrics.spacing_wait_ms, 80);
+        assert_eq!(first.metrics.elapsed_ms, 110);
+        assert_eq!(first.metrics.mean_commit_ms, 10);
+        assert_eq!(first.metrics.commits_per_minute, 2 * 60_000 / 110);
+        assert!(!first.is_complete());
+        assert_eq!(first.progress.remaining(), 2);
+        assert!(first.progress.validate().is_ok());
+
+        // The next call picks up where the last one stopped
+        let second = paced_write_with(&items, first.progress, pacing, write, || now.get(), |ms| {
+            now.set(now.get() + ms as i64 * 1_000);
+        });
+        assert_eq!(second.succeeded, vec![(3, 40), (4, 50)]);
+        assert!(second.is_complete());
+        assert_eq!(second.progress.committed, 4);
+        assert_eq!(second.progress.failed, 1);
+        assert_eq!(second.progress.calls, 2);
+        assert!(second.progress.validate().is_ok());
+
+        let mut inconsistent = second.progress.clone();
+        inconsistent.committed += 1;
+        assert!(inconsistent.validate().is_err());
+    }
+
+    #[test]
+    fn test_domain_event_handlers() {
+        let payload = DomainEventPayload::ConsentRevoked {
+            consent_hash: ActionHash::from_raw_36(vec![7; 36]),
+        };
+        assert_eq!(payload.kind(), DomainEventKind::ConsentRevoked);
+
+        let handler = DomainEventHandler::new(
+            "on_change",
+            &[DomainEventKind::IngestCompleted, DomainEventKind::ConsentRevoked],
+        );
+        assert!(handler.handles(payload.kind()));
+        assert!(!handler.handles(DomainEventKind::CrisisAlert));
+    }
 }
@@ -736,3 +736,28 @@ fn timestamp_to_date(ts: Timestamp) -> String {
     let day = (day_of_year % 30) + 1;
     format!("{:04}-{:02}-{:02}", year, month.min(12), day.min(28))
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("schedule_telehealth_session", "ScheduleSessionInput", "Record"),
+        ("get_telehealth_session", "GetSessionInput", "Option<Record>"),
+        ("start_session", "ActionHash", "SessionDetails"),
+        ("end_session", "EndSessionInput", "Record"),
+        ("cancel_session", "CancelSessionInput", "Record"),
+        ("join_waiting_room", "ActionHash", "Record"),
+        ("call_patient", "ActionHash", "Record"),
+        ("create_session_documentation", "SessionDocumentation", "Record"),
+        ("get_session_documentation", "GetSessionInput", "Option<Record>"),
+        ("sign_documentation", "ActionHash", "Record"),
+        ("create_available_slot", "AvailableSlot", "Record"),
+        ("get_available_slots", "GetAvailableSlotsInput", "Vec<Record>"),
+        ("get_patient_sessions", "GetPatientSessionsInput", "Vec<Record>"),
+        ("get_provider_sessions", "GetProviderSessionsInput", "Vec<Record>"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: [],
+}
@@ -9,6 +9,9 @@
 //! HIPAA compliant for remote patient care.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 // ============================================================================
 // Telehealth Session Types
@@ -368,8 +371,24 @@ pub enum LinkTypes {
 // Validation Functions
 // ============================================================================
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("provider_notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("follow_up_notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+        ("assessment", FieldLimit::Chars(NOTE_CHARS)),
+        ("plan", FieldLimit::Chars(NOTE_CHARS)),
+        ("instructions", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
             OpEntry::CreateEntry { app_entry, .. } => validate_create_entry(app_entry),
@@ -13,6 +13,8 @@
 use hdk::prelude::*;
 use trials_integrity::*;
 use mycelix_health_shared::{require_authorization, log_data_access, DataCategory, Permission};
+use mycelix_health_shared::{patient_residency, DataUse};
+use mycelix_health_shared::{resilient_call, sensitivity_matrix, CallClass, RecordInventoryItem, SensitivityMatrix};
 
 // ==================== DATA DIVIDENDS INTEGRATION ====================
 
@@ -29,15 +31,42 @@ fn create_trial_contribution_internal(
     participant: &TrialParticipant,
     trial: &ClinicalTrial,
 ) -> ExternResult<()> {
-    // Determine data categories based on trial type
-    let data_categories = data_categories_for_trial(trial);
+    // Determine data categories based on trial type, leaving out those
+    // the sensitivity matrix keeps from research
+    let matrix = sensitivity_matrix();
+    let data_categories: Vec<String> = data_categories_for_trial(trial)
+        .into_iter()
+        .filter(|name| research_allowed(&matrix, name))
+        .collect();
 
     // Get NCT number or use trial_id as fallback
     let nct = trial.nct_number.clone().unwrap_or_else(|| trial.trial_id.clone());
 
+    // Drop uses the patient's jurisdiction prohibits, and skip the
+    // contribution entirely when none remain
+    let residency = patient_residency(&participant.patient_hash)?;
+    let permitted_uses = residency.permitted_contribution_uses(&trial_contribution_uses());
+    if permitted_uses.is_empty() {
+        return Ok(());
+    }
+    // Standard prohibited uses for trial data, plus the jurisdiction's own
+    let mut prohibited_uses = vec![
+        "Marketing".to_string(),
+        "InsuranceUnderwriting".to_string(),
+        "EmploymentDecisions".to_string(),
+    ];
+    for data_use in &residency.prohibited_uses {
+        let name = format!("{:?}", data_use);
+        if !prohibited_uses.contains(&name) {
+            prohibited_uses.push(name);
+        }
+    }
+
     // Create contribution input
+    let contribution_id = format!("TRIAL-{}-{}", nct, participant.participant_id);
     let contribution = TrialDataContributionInput {
-        contribution_id: format!("TRIAL-{}-{}", nct, participant.participant_id),
+        idempotency_key: Some(contribution_id.clone()),
+        contribution_id,
         patient_hash: participant.patient_hash.clone(),
         data_type: "TreatmentOutcomes".to_string(),
         data_categories,
@@ -47,18 +76,9 @@ fn create_trial_contribution_internal(
         trial_title: trial.title.clone(),
         trial_phase: format!("{:?}", trial.phase),
         contributed_at: sys_time()?.as_micros() as i64,
-        // Trial data is typically used for specific permitted uses
-        permitted_uses: vec![
-            "AcademicResearch".to_string(),
-            "DrugDevelopment".to_string(),
-            "ClinicalDecisionSupport".to_string(),
-        ],
-        // Standard prohibited uses for trial data
-        prohibited_uses: vec![
-            "Marketing".to_string(),
-            "InsuranceUnderwriting".to_string(),
-            "EmploymentDecisions".to_string(),
-        ],
+        permitted_uses,
+        prohibited_uses,
+        jurisdiction: residency.jurisdiction,
     };
 
     // Call dividends zome to create contribution
@@ -76,6 +96,21 @@ fn create_trial_contribution_internal(
     }
 }
 
+/// Uses a trial contribution is offered for, before jurisdiction filtering
+fn trial_contribution_uses() -> Vec<String> {
+    vec![
+        "AcademicResearch".to_string(),
+        "DrugDevelopment".to_string(),
+        "ClinicalDecisionSupport".to_string(),
+    ]
+}
+
+/// Whether a trial data category may go to research; categories collected
+/// during the trial have no record category and always may
+fn research_allowed(matrix: &SensitivityMatrix, name: &str) -> bool {
+    inventory_category(name).is_none_or(|category| matrix.allows_research(&category))
+}
+
 /// Determine which data categories a trial will collect
 fn data_categories_for_trial(trial: &ClinicalTrial) -> Vec<String> {
     let mut categories = vec![
@@ -166,6 +201,13 @@ pub struct TrialDataContributionInput {
     pub contributed_at: i64,
     pub permitted_uses: Vec<String>,
     pub prohibited_uses: Vec<String>,
+    /// Jurisdiction of the contributing patient, carried onto the contribution
+    #[serde(default)]
+    pub jurisdiction: Option<String>,
+    /// Idempotency key for the contribution, so a retried enrollment gets
+    /// the existing contribution back rather than a second one
+    #[serde(default)]
+    pub idempotency_key: Option<String>,
 }
 
 /// Input for tracking trial visit data usage
@@ -554,6 +596,23 @@ pub fn check_eligibility(input: EligibilityCheckInput) -> ExternResult<Eligibili
     let mut eligible = true;
     let mut reasons = Vec::new();
     
+    // Check the patient's jurisdiction allows cohort screening
+    let residency = patient_residency(&input.patient_hash)?;
+    if !residency.allows(DataUse::CohortDiscovery) {
+        eligible = false;
+        reasons.push(format!(
+            "Cohort discovery is prohibited for patients in {}",
+            residency.jurisdiction.as_deref().unwrap_or("this jurisdiction")
+        ));
+    }
+
+    // Screening reads demographics, which the sensitivity matrix may keep
+    // from research
+    if !sensitivity_matrix().allows_research(&DataCategory::Demographics) {
+        eligible = false;
+        reasons.push("Cohort discovery on demographics is disabled by the sensitivity policy".to_string());
+    }
+    
     // Check age
     if let Some(min_age) = trial.eligibility.min_age {
         if input.patient_age < min_age {
@@ -604,6 +663,597 @@ pub struct EligibilityResult {
     pub trial_phase: String,
 }
 
+// ==================== CONTRIBUTION PREVIEW ====================
+
+/// Zomes exposing `get_patient_record_inventory`
+const INVENTORY_ZOMES: &[&str] = &["records", "prescriptions"];
+
+/// Record titles shown per category in a preview
+const PREVIEW_EXAMPLES: usize = 3;
+
+/// Input for previewing what joining a project would contribute
+#[derive(Serialize, Deserialize, Debug)]
+pub struct PreviewContributionInput {
+    pub project_hash: ActionHash,
+    pub patient_hash: ActionHash,
+}
+
+/// What a contribution would hold for one requested category
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContributionCategoryPreview {
+    /// Category name as it would appear on the contribution
+    pub category: String,
+    pub record_count: u32,
+    pub earliest: Option<Timestamp>,
+    pub latest: Option<Timestamp>,
+    /// Titles of the most recent records in the category
+    pub examples: Vec<String>,
+    /// Set when the category holds sensitive data or has no existing records
+    /// to draw on
+    pub warning: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ContributionPreview {
+    pub project_hash: ActionHash,
+    pub trial_title: String,
+    pub data_type: String,
+    pub jurisdiction: Option<String>,
+    /// Uses the contribution would be offered for after jurisdiction filtering
+    pub permitted_uses: Vec<String>,
+    pub categories: Vec<ContributionCategoryPreview>,
+    pub total_records: u32,
+    /// Warnings that apply to the contribution as a whole
+    pub warnings: Vec<String>,
+    /// Zomes whose records could not be listed, so counts may be low
+    pub unavailable_sources: Vec<String>,
+}
+
+/// The record inventory category a trial data category is drawn from
+fn inventory_category(name: &str) -> Option<DataCategory> {
+    match name {
+        "Demographics" => Some(DataCategory::Demographics),
+        "Allergies" => Some(DataCategory::Allergies),
+        "Medications" => Some(DataCategory::Medications),
+        "Diagnoses" => Some(DataCategory::Diagnoses),
+        "Procedures" => Some(DataCategory::Procedures),
+        "LabResults" => Some(DataCategory::LabResults),
+        "ImagingStudies" => Some(DataCategory::ImagingStudies),
+        "VitalSigns" => Some(DataCategory::VitalSigns),
+        "Immunizations" => Some(DataCategory::Immunizations),
+        "MentalHealth" => Some(DataCategory::MentalHealth),
+        "SubstanceAbuse" => Some(DataCategory::SubstanceAbuse),
+        "SexualHealth" => Some(DataCategory::SexualHealth),
+        "GeneticData" => Some(DataCategory::GeneticData),
+        "FinancialData" => Some(DataCategory::FinancialData),
+        _ => None,
+    }
+}
+
+/// Summarize the records a patient would contribute to a project, without
+/// creating a contribution
+///
+/// Only the patient can list their record inventory, so only the patient
+/// can preview.
+#[hdk_extern]
+pub fn preview_contribution(input: PreviewContributionInput) -> ExternResult<ContributionPreview> {
+    let trial: ClinicalTrial = get(input.project_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Trial not found".to_string())))?
+        .entry()
+        .to_app_option()
+        .map_err(|e| wasm_error!(WasmErrorInner::Guest(e.to_string())))?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Invalid trial".to_string())))?;
+
+    let mut inventory: Vec<RecordInventoryItem> = Vec::new();
+    let mut unavailable_sources = Vec::new();
+    for zome in INVENTORY_ZOMES {
+        match resilient_call::<_, Vec<RecordInventoryItem>>(zome, "get_patient_record_inventory", &input.patient_hash, CallClass::Bulk) {
+            Ok(items) => inventory.extend(items),
+            Err(failure) if failure.is_transient() => unavailable_sources.push(zome.to_string()),
+            Err(failure) => return Err(failure.into_wasm_error("listing patient records")),
+        }
+    }
+    inventory.sort_by_key(|item| std::cmp::Reverse(item.recorded_at));
+
+    let matrix = sensitivity_matrix();
+    let mut categories = Vec::new();
+    for name in data_categories_for_trial(&trial) {
+        let category = inventory_category(&name);
+        let records: Vec<&RecordInventoryItem> = inventory
+            .iter()
+            .filter(|item| category.as_ref() == Some(&item.category))
+            .collect();
+        let warning = match &category {
+            _ if !research_allowed(&matrix, &name) => Some(format!(
+                "{} is not shared with research projects and would be left out",
+                name
+            )),
+            Some(category) if matrix.requires_encryption(category) => Some(format!(
+                "{} is a sensitive category; review these records before joining",
+                name
+            )),
+            Some(DataCategory::Demographics) => None,
+            Some(_) if records.is_empty() => Some(format!("No {} records are on file yet", name)),
+            Some(_) => None,
+            None => Some(format!("{} are collected during the trial, not taken from existing records", name)),
+        };
+        categories.push(ContributionCategoryPreview {
+            category: name,
+            record_count: records.len() as u32,
+            earliest: records.iter().map(|item| item.recorded_at).min(),
+            latest: records.iter().map(|item| item.recorded_at).max(),
+            examples: records.iter().take(PREVIEW_EXAMPLES).map(|item| item.title.clone()).collect(),
+            warning,
+        });
+    }
+
+    let residency = patient_residency(&input.patient_hash)?;
+    let permitted_uses = residency.permitted_contribution_uses(&trial_contribution_uses());
+    let mut warnings = Vec::new();
+    let jurisdiction = residency.jurisdiction.as_deref().unwrap_or("your jurisdiction");
+    if permitted_uses.is_empty() {
+        warnings.push(format!(
+            "No contribution would be created: {} prohibits every use this project requests",
+            jurisdiction
+        ));
+    } else if !residency.prohibited_uses.is_empty() {
+        warnings.push(format!(
+            "{} prohibits {}; those uses would be excluded",
+            jurisdiction,
+            residency.prohibited_uses.iter().map(|u| format!("{:?}", u)).collect::<Vec<_>>().join(", ")
+        ));
+    }
+    if !unavailable_sources.is_empty() {
+        warnings.push(format!(
+            "Records from {} could not be listed; counts may be low",
+            unavailable_sources.join(", ")
+        ));
+    }
+
+    Ok(ContributionPreview {
+        project_hash: input.project_hash,
+        trial_title: trial.title,
+        data_type: "TreatmentOutcomes".to_string(),
+        jurisdiction: residency.jurisdiction,
+        permitted_uses,
+        total_records: categories.iter().map(|c| c.record_count).sum(),
+        categories,
+        warnings,
+        unavailable_sources,
+    })
+}
+
+// ==================== RESEARCH RE-CONTACT ====================
+
+/// Access notification (mirrors consent_integrity::AccessNotification)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+struct RecontactNotification {
+    notification_id: String,
+    patient_hash: ActionHash,
+    accessor: AgentPubKey,
+    accessor_name: String,
+    data_categories: Vec<DataCategory>,
+    purpose: String,
+    accessed_at: Timestamp,
+    emergency_access: bool,
+    priority: RecontactNotificationPriority,
+    viewed: bool,
+    viewed_at: Option<Timestamp>,
+    summary: String,
+    access_log_hash: Option<ActionHash>,
+}
+
+/// Notification priority (mirrors consent_integrity::NotificationPriority)
+#[derive(Serialize, Deserialize, Debug, Clone)]
+enum RecontactNotificationPriority {
+    Daily,
+}
+
+/// Input for creating a project-scoped research pseudonym
+#[derive(Serialize, Deserialize, Debug)]
+pub struct CreatePseudonymInput {
+    pub project_hash: ActionHash,
+    pub patient_hash: ActionHash,
+}
+
+/// A pseudonym held in the caller's private vault
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ResearchPseudonym {
+    pub project_hash: ActionHash,
+    pub pseudonym: String,
+    pub created_at: Timestamp,
+}
+
+impl From<&PseudonymVaultEntry> for ResearchPseudonym {
+    fn from(entry: &PseudonymVaultEntry) -> Self {
+        Self {
+            project_hash: entry.project_hash.clone(),
+            pseudonym: entry.pseudonym.clone(),
+            created_at: entry.created_at,
+        }
+    }
+}
+
+/// Input for a researcher's re-contact message
+#[derive(Serialize, Deserialize, Debug)]
+pub struct SubmitRecontactInput {
+    pub project_hash: ActionHash,
+    pub pseudonym: String,
+    pub message: String,
+}
+
+/// A re-contact request addressed to one of the caller's pseudonyms
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct PendingRecontact {
+    pub request_hash: ActionHash,
+    pub request: RecontactRequest,
+    pub responded: bool,
+}
+
+/// Input for answering a re-contact request
+#[derive(Serialize, Deserialize, Debug)]
+pub struct RespondToRecontactInput {
+    pub request_hash: ActionHash,
+    pub decision: RecontactDecision,
+    /// Reply for the researcher, e.g. preferred contact details
+    pub message: Option<String>,
+}
+
+/// A researcher's view of one of their re-contact requests
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct RecontactOutcome {
+    pub request_hash: ActionHash,
+    pub request: RecontactRequest,
+    pub decision: Option<RecontactDecision>,
+    /// Patient reply, decrypted for the researcher
+    pub message: Option<String>,
+    pub responded_at: Option<Timestamp>,
+}
+
+fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn pseudonym_anchor(project_hash: &ActionHash, pseudonym: &str) -> ExternResult<EntryHash> {
+    anchor_hash(&format!("pseudonym:{}:{}", project_hash, pseudonym))
+}
+
+/// Latest vault entry per project on the caller's own chain
+fn my_pseudonym_vault() -> ExternResult<Vec<(ActionHash, PseudonymVaultEntry)>> {
+    let filter = ChainQueryFilter::new()
+        .entry_type(UnitEntryTypes::PseudonymVaultEntry.try_into()?)
+        .include_entries(true);
+    let mut vault: Vec<(ActionHash, PseudonymVaultEntry)> = Vec::new();
+    for record in query(filter)? {
+        let Some(entry) = record.entry().to_app_option::<PseudonymVaultEntry>().ok().flatten() else {
+            continue;
+        };
+        let hash = record.action_address().clone();
+        match vault.iter_mut().find(|(_, e)| e.project_hash == entry.project_hash) {
+            Some(slot) => *slot = (hash, entry),
+            None => vault.push((hash, entry)),
+        }
+    }
+    Ok(vault)
+}
+
+fn get_recontact_request(request_hash: &ActionHash) -> ExternResult<RecontactRequest> {
+    get(request_hash.clone(), GetOptions::default())?
+        .and_then(|record| record.entry().to_app_option::<RecontactRequest>().ok().flatten())
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Re-contact request not found".to_string())))
+}
+
+/// Tell the patient a researcher is trying to reach them
+///
+/// The notification names neither the project nor the request so the
+/// public notification cannot be tied back to the pseudonym.
+fn notify_recontact(vault: &PseudonymVaultEntry, me: &AgentPubKey, now: Timestamp) -> ExternResult<()> {
+    let patient_hash = ActionHash::try_from_raw_39(
+        ed_25519_x_salsa20_poly1305_decrypt(me.clone(), me.clone(), vault.sealed_patient_hash.clone())?
+            .as_ref()
+            .to_vec(),
+    )
+    .map_err(|e| wasm_error!(WasmErrorInner::Guest(format!("Corrupt pseudonym vault entry: {:?}", e))))?;
+
+    let notification = RecontactNotification {
+        notification_id: format!("RECONTACT-{}", hex_encode(&random_bytes(8)?)),
+        patient_hash,
+        accessor: me.clone(),
+        accessor_name: "Research re-contact".to_string(),
+        data_categories: vec![DataCategory::Demographics],
+        purpose: "Research re-contact request".to_string(),
+        accessed_at: now,
+        emergency_access: false,
+        priority: RecontactNotificationPriority::Daily,
+        viewed: false,
+        viewed_at: None,
+        summary: "A research team you shared data with would like to contact you. \
+            Your identity has not been shared; open your research messages to reply or decline."
+            .to_string(),
+        access_log_hash: None,
+    };
+
+    call(
+        CallTargetCell::Local,
+        ZomeName::from("consent"),
+        FunctionName::from("create_access_notification"),
+        None,
+        &notification,
+    )?;
+    Ok(())
+}
+
+/// Create (or return) the caller's pseudonym for a research project
+///
+/// The pseudonym is a salted commitment to the patient's agent key and the
+/// mapping back to the patient is kept only in a private vault entry.
+#[hdk_extern]
+pub fn create_research_pseudonym(input: CreatePseudonymInput) -> ExternResult<ResearchPseudonym> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let patient_record = get(input.patient_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Patient not found".to_string())))?;
+    if patient_record.action().author() != &me {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the patient can create their research pseudonyms".to_string()
+        )));
+    }
+    get(input.project_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Project not found".to_string())))?;
+
+    if let Some((_, existing)) = my_pseudonym_vault()?
+        .into_iter()
+        .find(|(_, e)| e.project_hash == input.project_hash)
+    {
+        return Ok(ResearchPseudonym::from(&existing));
+    }
+
+    let salt = random_bytes(32)?.to_vec();
+    let entry = PseudonymVaultEntry {
+        pseudonym: derive_pseudonym(&input.project_hash, &me, &salt)?,
+        project_hash: input.project_hash,
+        salt,
+        sealed_patient_hash: ed_25519_x_salsa20_poly1305_encrypt(
+            me.clone(),
+            me,
+            XSalsa20Poly1305Data::from(input.patient_hash.get_raw_39().to_vec()),
+        )?,
+        created_at: sys_time()?,
+        notified_requests: Vec::new(),
+    };
+    create_entry(&EntryTypes::PseudonymVaultEntry(entry.clone()))?;
+    Ok(ResearchPseudonym::from(&entry))
+}
+
+/// List the caller's research pseudonyms
+#[hdk_extern]
+pub fn get_my_pseudonyms(_: ()) -> ExternResult<Vec<ResearchPseudonym>> {
+    Ok(my_pseudonym_vault()?
+        .iter()
+        .map(|(_, entry)| ResearchPseudonym::from(entry))
+        .collect())
+}
+
+/// Send a message to the holder of a project pseudonym
+#[hdk_extern]
+pub fn submit_recontact_request(input: SubmitRecontactInput) -> ExternResult<Record> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let project_record = get(input.project_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Project not found".to_string())))?;
+    let principal_investigator = project_record
+        .entry()
+        .to_app_option::<ClinicalTrial>()
+        .ok()
+        .flatten()
+        .map(|trial| trial.principal_investigator);
+    if project_record.action().author() != &me && principal_investigator.as_ref() != Some(&me) {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "Only the project's creator or principal investigator can request re-contact".to_string()
+        )));
+    }
+
+    let now = sys_time()?;
+    let request = RecontactRequest {
+        request_id: format!("RECONTACT-{}", now.as_micros()),
+        project_hash: input.project_hash.clone(),
+        pseudonym: input.pseudonym.clone(),
+        researcher: me.clone(),
+        message: input.message,
+        sent_at: now,
+    };
+    let request_hash = create_entry(&EntryTypes::RecontactRequest(request))?;
+    let record = get(request_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find re-contact request".to_string())))?;
+
+    create_link(
+        pseudonym_anchor(&input.project_hash, &input.pseudonym)?,
+        request_hash.clone(),
+        LinkTypes::PseudonymToRecontactRequests,
+        (),
+    )?;
+    create_link(me, request_hash, LinkTypes::ResearcherToRecontactRequests, ())?;
+
+    Ok(record)
+}
+
+/// Collect re-contact requests for the caller's pseudonyms
+///
+/// Requests seen for the first time raise a notification to the patient;
+/// the vault remembers which requests have already been announced.
+#[hdk_extern]
+pub fn get_my_recontact_requests(_: ()) -> ExternResult<Vec<PendingRecontact>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let now = sys_time()?;
+    let mut pending = Vec::new();
+
+    for (vault_hash, mut vault) in my_pseudonym_vault()? {
+        let links = get_links(
+            LinkQuery::try_new(
+                pseudonym_anchor(&vault.project_hash, &vault.pseudonym)?,
+                LinkTypes::PseudonymToRecontactRequests,
+            )?,
+            GetStrategy::default(),
+        )?;
+
+        let mut announced = false;
+        for link in links {
+            let Some(request_hash) = link.target.into_action_hash() else {
+                continue;
+            };
+            let Some(request) = get(request_hash.clone(), GetOptions::default())?
+                .and_then(|record| record.entry().to_app_option::<RecontactRequest>().ok().flatten())
+            else {
+                continue;
+            };
+            if request.project_hash != vault.project_hash || request.pseudonym != vault.pseudonym {
+                continue;
+            }
+
+            if !vault.notified_requests.contains(&request_hash) {
+                notify_recontact(&vault, &me, now)?;
+                vault.notified_requests.push(request_hash.clone());
+                announced = true;
+            }
+
+            let responded = get_links(
+                LinkQuery::try_new(request_hash.clone(), LinkTypes::RecontactRequestToResponses)?,
+                GetStrategy::default(),
+            )?
+            .iter()
+            .any(|link| link.author == me);
+            pending.push(PendingRecontact { request_hash, request, responded });
+        }
+
+        if announced {
+            update_entry(vault_hash, &EntryTypes::PseudonymVaultEntry(vault))?;
+        }
+    }
+
+    Ok(pending)
+}
+
+/// Accept or decline a re-contact request
+///
+/// Responding reveals the pseudonym salt, which links the caller's agent to
+/// the pseudonym for the researcher; declining silently reveals nothing.
+#[hdk_extern]
+pub fn respond_to_recontact(input: RespondToRecontactInput) -> ExternResult<Record> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let request = get_recontact_request(&input.request_hash)?;
+    let vault = my_pseudonym_vault()?
+        .into_iter()
+        .map(|(_, entry)| entry)
+        .find(|e| e.project_hash == request.project_hash && e.pseudonym == request.pseudonym)
+        .ok_or(wasm_error!(WasmErrorInner::Guest(
+            "This re-contact request is not addressed to any of your pseudonyms".to_string()
+        )))?;
+
+    let already_responded = get_links(
+        LinkQuery::try_new(input.request_hash.clone(), LinkTypes::RecontactRequestToResponses)?,
+        GetStrategy::default(),
+    )?
+    .iter()
+    .any(|link| link.author == me);
+    if already_responded {
+        return Err(wasm_error!(WasmErrorInner::Guest(
+            "You have already responded to this re-contact request".to_string()
+        )));
+    }
+
+    let sealed_message = match input.message {
+        Some(message) if message.chars().count() > RECONTACT_MESSAGE_MAX_CHARS => {
+            return Err(wasm_error!(WasmErrorInner::Guest(format!(
+                "Reply must be at most {} characters",
+                RECONTACT_MESSAGE_MAX_CHARS
+            ))));
+        }
+        Some(message) => Some(ed_25519_x_salsa20_poly1305_encrypt(
+            me,
+            request.researcher,
+            XSalsa20Poly1305Data::from(message.into_bytes()),
+        )?),
+        None => None,
+    };
+
+    let response = RecontactResponse {
+        request_hash: input.request_hash.clone(),
+        pseudonym_salt: vault.salt,
+        decision: input.decision,
+        sealed_message,
+        responded_at: sys_time()?,
+    };
+    let response_hash = create_entry(&EntryTypes::RecontactResponse(response))?;
+    let record = get(response_hash.clone(), GetOptions::default())?
+        .ok_or(wasm_error!(WasmErrorInner::Guest("Could not find re-contact response".to_string())))?;
+
+    create_link(
+        input.request_hash,
+        response_hash,
+        LinkTypes::RecontactRequestToResponses,
+        (),
+    )?;
+
+    Ok(record)
+}
+
+/// List the caller's re-contact requests with any patient responses
+#[hdk_extern]
+pub fn get_recontact_responses(_: ()) -> ExternResult<Vec<RecontactOutcome>> {
+    let me = agent_info()?.agent_initial_pubkey;
+    let links = get_links(
+        LinkQuery::try_new(me.clone(), LinkTypes::ResearcherToRecontactRequests)?,
+        GetStrategy::default(),
+    )?;
+
+    let mut outcomes = Vec::new();
+    for link in links {
+        let Some(request_hash) = link.target.into_action_hash() else {
+            continue;
+        };
+        let request = get_recontact_request(&request_hash)?;
+        let mut outcome = RecontactOutcome {
+            request_hash: request_hash.clone(),
+            request,
+            decision: None,
+            message: None,
+            responded_at: None,
+        };
+
+        let response_links = get_links(
+            LinkQuery::try_new(request_hash, LinkTypes::RecontactRequestToResponses)?,
+            GetStrategy::default(),
+        )?;
+        for response_link in response_links {
+            let Some(response_hash) = response_link.target.into_action_hash() else {
+                continue;
+            };
+            let Some(record) = get(response_hash, GetOptions::default())? else {
+                continue;
+            };
+            let Some(response) = record.entry().to_app_option::<RecontactResponse>().ok().flatten() else {
+                continue;
+            };
+            outcome.message = match response.sealed_message {
+                Some(sealed) => {
+                    let plain = ed_25519_x_salsa20_poly1305_decrypt(
+                        me.clone(),
+                        record.action().author().clone(),
+                        sealed,
+                    )?;
+                    Some(String::from_utf8_lossy(plain.as_ref()).into_owned())
+                }
+                None => None,
+            };
+            outcome.decision = Some(response.decision);
+            outcome.responded_at = Some(response.responded_at);
+            break;
+        }
+
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
 /// Anchor entry for indexing
 #[hdk_entry_helper]
 #[derive(Clone, PartialEq)]
@@ -613,3 +1263,33 @@ fn anchor_hash(anchor_text: &str) -> ExternResult<EntryHash> {
     let anchor = Anchor(anchor_text.to_string());
     hash_entry(&anchor)
 }
+
+// ============================================================
+// API MANIFEST
+// ============================================================
+
+mycelix_health_shared::api_manifest! {
+    externs: [
+        ("create_trial", "ClinicalTrial", "Record"),
+        ("get_trial", "ActionHash", "Option<Record>"),
+        ("get_recruiting_trials", "()", "Vec<Record>"),
+        ("enroll_participant", "TrialParticipant", "Record"),
+        ("get_trial_participants", "ActionHash", "Vec<Record>"),
+        ("withdraw_participant", "WithdrawInput", "Record"),
+        ("record_visit", "TrialVisit", "Record"),
+        ("get_participant_visits", "ActionHash", "Vec<Record>"),
+        ("report_adverse_event", "AdverseEvent", "Record"),
+        ("get_trial_adverse_events", "ActionHash", "Vec<Record>"),
+        ("get_serious_adverse_events", "ActionHash", "Vec<Record>"),
+        ("check_eligibility", "EligibilityCheckInput", "EligibilityResult"),
+        ("preview_contribution", "PreviewContributionInput", "ContributionPreview"),
+        ("create_research_pseudonym", "CreatePseudonymInput", "ResearchPseudonym"),
+        ("get_my_pseudonyms", "()", "Vec<ResearchPseudonym>"),
+        ("submit_recontact_request", "SubmitRecontactInput", "Record"),
+        ("get_my_recontact_requests", "()", "Vec<PendingRecontact>"),
+        ("respond_to_recontact", "RespondToRecontactInput", "Record"),
+        ("get_recontact_responses", "()", "Vec<RecontactOutcome>"),
+        ("get_api_manifest", "()", "ApiManifest"),
+    ],
+    feature_flags: ["research_recontact", "jurisdiction_residency", "contribution_preview"],
+}
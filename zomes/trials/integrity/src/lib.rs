@@ -4,6 +4,9 @@
 //! data collection, and adverse event reporting with FDA 21 CFR Part 11 alignment.
 
 use hdi::prelude::*;
+use mycelix_health_shared::{
+    entry_size_violation, EntrySizeLimits, FieldLimit, DEFAULT_ENTRY_SIZE_LIMITS, NOTE_CHARS,
+};
 
 /// Clinical trial/study definition
 #[hdk_entry_helper]
@@ -331,6 +334,89 @@ pub enum ActionTaken {
     Hospitalized,
 }
 
+/// Longest re-contact message a researcher or patient may send
+pub const RECONTACT_MESSAGE_MAX_CHARS: usize = 2000;
+
+/// Project-scoped pseudonym held privately on the patient's own chain
+///
+/// The patient hash is sealed to the patient's own agent key so the link
+/// between pseudonym and patient never leaves the patient's device in clear.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PseudonymVaultEntry {
+    /// Trial or research project the pseudonym is scoped to
+    pub project_hash: ActionHash,
+    pub pseudonym: String,
+    /// Secret salt behind the pseudonym commitment
+    pub salt: Vec<u8>,
+    /// Patient hash boxed from the patient's agent key to itself
+    pub sealed_patient_hash: XSalsa20Poly1305EncryptedData,
+    pub created_at: Timestamp,
+    /// Re-contact requests the patient has already been notified about
+    #[serde(default)]
+    pub notified_requests: Vec<ActionHash>,
+}
+
+/// Commitment a pseudonym is derived from
+///
+/// Hashing the holder's agent key with a secret salt keeps the pseudonym
+/// unlinkable until the holder chooses to reveal the salt in a response.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct PseudonymCommitment {
+    pub project_hash: ActionHash,
+    pub holder: AgentPubKey,
+    pub salt: Vec<u8>,
+}
+
+/// Derive the project-scoped pseudonym for a holder and salt
+pub fn derive_pseudonym(
+    project_hash: &ActionHash,
+    holder: &AgentPubKey,
+    salt: &[u8],
+) -> ExternResult<String> {
+    let commitment = PseudonymCommitment {
+        project_hash: project_hash.clone(),
+        holder: holder.clone(),
+        salt: salt.to_vec(),
+    };
+    Ok(format!("PSN-{}", hash_entry(&commitment)?))
+}
+
+/// Researcher's message to the holder of a project pseudonym
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct RecontactRequest {
+    pub request_id: String,
+    pub project_hash: ActionHash,
+    pub pseudonym: String,
+    pub researcher: AgentPubKey,
+    pub message: String,
+    pub sent_at: Timestamp,
+}
+
+/// Patient's answer to a re-contact request
+///
+/// Responding is optional: revealing the salt proves the author holds the
+/// pseudonym, so a patient who stays silent never links their agent to it.
+#[hdk_entry_helper]
+#[derive(Clone, PartialEq)]
+pub struct RecontactResponse {
+    pub request_hash: ActionHash,
+    /// Salt opening the pseudonym commitment for the response author
+    pub pseudonym_salt: Vec<u8>,
+    pub decision: RecontactDecision,
+    /// Optional reply boxed to the requesting researcher
+    pub sealed_message: Option<XSalsa20Poly1305EncryptedData>,
+    pub responded_at: Timestamp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub enum RecontactDecision {
+    Accept,
+    Decline,
+}
+
 #[hdk_entry_types]
 #[unit_enum(UnitEntryTypes)]
 pub enum EntryTypes {
@@ -338,6 +424,10 @@ pub enum EntryTypes {
     TrialParticipant(TrialParticipant),
     TrialVisit(TrialVisit),
     AdverseEvent(AdverseEvent),
+    #[entry_type(visibility = "private")]
+    PseudonymVaultEntry(PseudonymVaultEntry),
+    RecontactRequest(RecontactRequest),
+    RecontactResponse(RecontactResponse),
 }
 
 #[hdk_link_types]
@@ -352,18 +442,41 @@ pub enum LinkTypes {
     RecruitingTrials,
     TrialsBySponsor,
     TrialsByPhase,
+    PseudonymToRecontactRequests,
+    ResearcherToRecontactRequests,
+    RecontactRequestToResponses,
 }
 
+/// Size guards checked before any entry-specific validation
+const SIZE_LIMITS: EntrySizeLimits = EntrySizeLimits {
+    field_limits: &[
+        ("notes", FieldLimit::Chars(NOTE_CHARS)),
+    ],
+    ..DEFAULT_ENTRY_SIZE_LIMITS
+};
+
 #[hdk_extern]
 pub fn validate(op: Op) -> ExternResult<ValidateCallbackResult> {
+    if let Some(invalid) = entry_size_violation(&op, &SIZE_LIMITS) {
+        return Ok(invalid);
+    }
     match op.flattened::<EntryTypes, LinkTypes>()? {
         FlatOp::StoreEntry(store_entry) => match store_entry {
-            OpEntry::CreateEntry { app_entry, .. } => match app_entry {
+            OpEntry::CreateEntry { app_entry, action } => match app_entry {
                 EntryTypes::ClinicalTrial(t) => validate_trial(&t),
                 EntryTypes::TrialParticipant(p) => validate_participant(&p),
                 EntryTypes::TrialVisit(v) => validate_visit(&v),
                 EntryTypes::AdverseEvent(a) => validate_adverse_event(&a),
+                EntryTypes::PseudonymVaultEntry(v) => validate_pseudonym_vault_entry(&v, &action.author),
+                EntryTypes::RecontactRequest(r) => validate_recontact_request(&r, &action.author),
+                EntryTypes::RecontactResponse(r) => validate_recontact_response(&r, &action.author),
             },
+            OpEntry::UpdateEntry {
+                app_entry: EntryTypes::RecontactRequest(_) | EntryTypes::RecontactResponse(_),
+                ..
+            } => Ok(ValidateCallbackResult::Invalid(
+                "Re-contact requests and responses cannot be updated".to_string(),
+            )),
             _ => Ok(ValidateCallbackResult::Valid),
         },
         _ => Ok(ValidateCallbackResult::Valid),
@@ -425,3 +538,73 @@ fn validate_adverse_event(event: &AdverseEvent) -> ExternResult<ValidateCallback
     }
     Ok(ValidateCallbackResult::Valid)
 }
+
+fn validate_pseudonym_vault_entry(
+    entry: &PseudonymVaultEntry,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if entry.pseudonym != derive_pseudonym(&entry.project_hash, author, &entry.salt)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Pseudonym does not match its commitment".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_recontact_request(
+    request: &RecontactRequest,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    if request.request_id.is_empty() || request.pseudonym.is_empty() {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Re-contact requests need a request ID and a pseudonym".to_string(),
+        ));
+    }
+    if request.message.trim().is_empty() || request.message.chars().count() > RECONTACT_MESSAGE_MAX_CHARS {
+        return Ok(ValidateCallbackResult::Invalid(format!(
+            "Re-contact message must be between 1 and {} characters",
+            RECONTACT_MESSAGE_MAX_CHARS
+        )));
+    }
+    if &request.researcher != author {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Re-contact researcher must match the action author".to_string(),
+        ));
+    }
+    let project_record = must_get_valid_record(request.project_hash.clone())?;
+    let principal_investigator = project_record
+        .entry()
+        .to_app_option::<ClinicalTrial>()
+        .ok()
+        .flatten()
+        .map(|trial| trial.principal_investigator);
+    if project_record.action().author() != author && principal_investigator.as_ref() != Some(author) {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the project's creator or principal investigator can request re-contact".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}
+
+fn validate_recontact_response(
+    response: &RecontactResponse,
+    author: &AgentPubKey,
+) -> ExternResult<ValidateCallbackResult> {
+    let request_record = must_get_valid_record(response.request_hash.clone())?;
+    let Some(request) = request_record
+        .entry()
+        .to_app_option::<RecontactRequest>()
+        .ok()
+        .flatten()
+    else {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Re-contact response must reference a re-contact request".to_string(),
+        ));
+    };
+    if request.pseudonym != derive_pseudonym(&request.project_hash, author, &response.pseudonym_salt)? {
+        return Ok(ValidateCallbackResult::Invalid(
+            "Only the pseudonym holder can respond to a re-contact request".to_string(),
+        ));
+    }
+    Ok(ValidateCallbackResult::Valid)
+}